@@ -0,0 +1,102 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::Error;
+use beefy_light_client_primitives::ClientState;
+use std::future::Future;
+
+/// What a light client has to be updated with for a signed commitment it received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NextCommitment {
+	/// The commitment itself, it's signed by the light client's current or next authority set.
+	Received,
+	/// Nothing, the commitment is signed by an authority set older than the light client's
+	/// current one.
+	Outdated,
+	/// The signed commitment of the mandatory block at which the authority set
+	/// `validator_set_id` took over. The relay chain stopped producing signed commitments long
+	/// enough for the light client to miss that change, so it can't verify the received
+	/// commitment before being updated with that one.
+	Mandatory {
+		/// Id of the authority set that took over at the mandatory block
+		validator_set_id: u64,
+	},
+}
+
+/// Returns what the light client in `client_state` has to be updated with for a signed
+/// commitment signed by the authority set `commitment_set_id`.
+pub fn next_commitment(client_state: &ClientState, commitment_set_id: u64) -> NextCommitment {
+	if commitment_set_id < client_state.current_authorities.id {
+		NextCommitment::Outdated
+	} else if commitment_set_id > client_state.next_authorities.id {
+		NextCommitment::Mandatory { validator_set_id: client_state.next_authorities.id }
+	} else {
+		NextCommitment::Received
+	}
+}
+
+/// Returns the first block after `from`, up to `to`, at which `set_id_at` reports a validator
+/// set id of at least `validator_set_id`, which is the mandatory block at which that validator
+/// set took over. `None` if it didn't take over in that range.
+pub async fn find_validator_set_change<F, Fut>(
+	from: u32,
+	to: u32,
+	validator_set_id: u64,
+	mut set_id_at: F,
+) -> Result<Option<u32>, Error>
+where
+	F: FnMut(u32) -> Fut,
+	Fut: Future<Output = Result<u64, Error>>,
+{
+	let (mut low, mut high) = (from + 1, to);
+	if low > high || set_id_at(high).await? < validator_set_id {
+		return Ok(None)
+	}
+	// validator set ids only ever increase
+	while low < high {
+		let mid = low + (high - low) / 2;
+		if set_id_at(mid).await? >= validator_set_id {
+			high = mid
+		} else {
+			low = mid + 1
+		}
+	}
+	Ok(Some(low))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use beefy_primitives::mmr::BeefyNextAuthoritySet;
+
+	#[test]
+	fn commitments_are_checked_against_the_client_authority_sets() {
+		let authorities = |id| BeefyNextAuthoritySet { id, len: 5, root: Default::default() };
+		let client_state = ClientState {
+			latest_beefy_height: 35,
+			mmr_root_hash: Default::default(),
+			current_authorities: authorities(3),
+			next_authorities: authorities(4),
+			beefy_activation_block: 0,
+		};
+		assert_eq!(next_commitment(&client_state, 2), NextCommitment::Outdated);
+		assert_eq!(next_commitment(&client_state, 3), NextCommitment::Received);
+		assert_eq!(next_commitment(&client_state, 4), NextCommitment::Received);
+		assert_eq!(
+			next_commitment(&client_state, 5),
+			NextCommitment::Mandatory { validator_set_id: 4 }
+		);
+	}
+}
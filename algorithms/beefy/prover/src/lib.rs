@@ -18,6 +18,8 @@
 #![allow(clippy::all)]
 #![deny(missing_docs)]
 
+/// Recovery from gaps in the relay chain's signed commitments
+pub mod commitment_gap;
 /// Errors that can be encountered by the prover
 pub mod error;
 /// Helper functions and types
@@ -43,7 +45,9 @@ use helpers::{
 };
 use hex_literal::hex;
 use pallet_mmr_primitives::BatchProof;
-use relay_chain_queries::{fetch_beefy_justification, fetch_mmr_batch_proof};
+use relay_chain_queries::{
+	fetch_beefy_justification, fetch_mandatory_commitment, fetch_mmr_batch_proof,
+};
 use sp_core::{hexdisplay::AsBytesRef, keccak_256, H256};
 use sp_io::crypto;
 use sp_runtime::traits::{BlakeTwo256, Header as HeaderT};
@@ -356,6 +360,29 @@ where
 		})
 	}
 
+	/// Returns the signed commitment of the mandatory block at which the authority set
+	/// `validator_set_id` took over, for a light client that last saw block
+	/// `latest_beefy_height` and missed that change. The block is searched for before
+	/// `commitment_block_number`, the block of the commitment the light client can't verify yet.
+	/// `None` if the mandatory block isn't signed yet.
+	pub async fn query_mandatory_commitment(
+		&self,
+		validator_set_id: u64,
+		latest_beefy_height: u32,
+		commitment_block_number: u32,
+	) -> Result<
+		Option<beefy_primitives::SignedCommitment<u32, beefy_primitives::crypto::Signature>>,
+		Error,
+	> {
+		fetch_mandatory_commitment(
+			&self.relay_client,
+			validator_set_id,
+			latest_beefy_height,
+			commitment_block_number,
+		)
+		.await
+	}
+
 	/// Construct a beefy client state to be submitted to the counterparty chain
 	pub async fn construct_beefy_client_state(
 		&self,
@@ -15,10 +15,11 @@
 
 use super::runtime;
 use crate::{
-	error::Error, runtime::api::runtime_types::polkadot_runtime_parachains::paras::ParaLifecycle,
+	commitment_gap::find_validator_set_change, error::Error,
+	runtime::api::runtime_types::polkadot_runtime_parachains::paras::ParaLifecycle,
 };
 use beefy_light_client_primitives::get_leaf_index_for_block_number;
-use beefy_primitives::{SignedCommitment, VersionedFinalityProof};
+use beefy_primitives::{SignedCommitment, VersionedFinalityProof, BEEFY_ENGINE_ID};
 use codec::{Decode, Encode};
 use pallet_mmr_rpc::{LeafBatchProof, LeafProof};
 use sp_core::{hexdisplay::AsBytesRef, storage::StorageKey, H256};
@@ -124,26 +125,79 @@ pub async fn fetch_beefy_justification<T: Config>(
 ) -> Result<(SignedCommitment<u32, beefy_primitives::crypto::Signature>, T::Hash), Error> {
 	let latest_beefy_finalized: <T as Config>::Hash =
 		client.rpc().request("beefy_getFinalizedHead", rpc_params!()).await?;
-	let block = client
-		.rpc()
-		.block(Some(latest_beefy_finalized))
-		.await
-		.ok()
-		.flatten()
-		.expect("Should find a valid block");
+	let signed_commitment = fetch_beefy_justification_at(client, latest_beefy_finalized)
+		.await?
+		.ok_or_else(|| {
+			Error::Custom(format!("No beefy justification in block {latest_beefy_finalized:?}"))
+		})?;
 
-	let justifications = block.justifications.expect("Block should have valid justifications");
+	Ok((signed_commitment, latest_beefy_finalized))
+}
 
-	let beefy_justification = justifications
-		.into_justification(beefy_primitives::BEEFY_ENGINE_ID)
-		.expect("Should have valid beefy justification");
+/// Get the beefy justification stored in the block with the given hash, if it has one
+pub async fn fetch_beefy_justification_at<T: Config>(
+	client: &OnlineClient<T>,
+	block_hash: T::Hash,
+) -> Result<Option<SignedCommitment<u32, beefy_primitives::crypto::Signature>>, Error> {
+	let block = client
+		.rpc()
+		.block(Some(block_hash))
+		.await?
+		.ok_or_else(|| Error::Custom(format!("Block not found: {block_hash:?}")))?;
+	let beefy_justification = match block
+		.justifications
+		.and_then(|justifications| justifications.into_justification(BEEFY_ENGINE_ID))
+	{
+		Some(justification) => justification,
+		None => return Ok(None),
+	};
 	let VersionedFinalityProof::V1(signed_commitment) = VersionedFinalityProof::<
 		u32,
 		beefy_primitives::crypto::Signature,
-	>::decode(&mut &*beefy_justification)
-	.expect("Beefy justification should decode correctly");
+	>::decode(&mut &*beefy_justification)?;
 
-	Ok((signed_commitment, latest_beefy_finalized))
+	Ok(Some(signed_commitment))
+}
+
+/// Get the signed commitment of the mandatory block at which the validator set
+/// `validator_set_id` took over, searching the blocks after `from` up to `to`. Returns `None`
+/// when the validator set didn't take over in that range or the block isn't signed yet.
+pub async fn fetch_mandatory_commitment<T: Config>(
+	client: &OnlineClient<T>,
+	validator_set_id: u64,
+	from: u32,
+	to: u32,
+) -> Result<Option<SignedCommitment<u32, beefy_primitives::crypto::Signature>>, Error> {
+	let block_hash = |block_number: u32| async move {
+		let subxt_block_number: subxt::rpc::BlockNumber = block_number.into();
+		client.rpc().block_hash(Some(subxt_block_number)).await?.ok_or_else(|| {
+			Error::Custom(format!("Block hash not found for number: {block_number}"))
+		})
+	};
+	let set_id_at = |block_number: u32| async move {
+		let key = runtime::api::storage().beefy().validator_set_id();
+		let block_hash = block_hash(block_number).await?;
+		Ok::<_, Error>(client.storage().fetch(&key, Some(block_hash)).await?.unwrap_or_default())
+	};
+
+	let block_number =
+		match find_validator_set_change(from, to, validator_set_id, set_id_at).await? {
+			Some(block_number) => block_number,
+			None => return Ok(None),
+		};
+	let signed_commitment =
+		match fetch_beefy_justification_at(client, block_hash(block_number).await?).await? {
+			Some(signed_commitment) => signed_commitment,
+			None => return Ok(None),
+		};
+	if signed_commitment.commitment.block_number != block_number {
+		Err(Error::Custom(format!(
+			"Justification of block {block_number} commits to block {}",
+			signed_commitment.commitment.block_number
+		)))?
+	}
+
+	Ok(Some(signed_commitment))
 }
 
 /// Query a batch leaf proof
@@ -14,19 +14,27 @@
 // limitations under the License.
 
 use beefy_light_client_primitives::{
-	error::BeefyClientError, MmrUpdateProof, ParachainsUpdateProof, SignatureWithAuthorityIndex,
+	error::BeefyClientError, ClientState, HostFunctions, MerkleHasher, MmrUpdateProof,
+	ParachainHeader, ParachainsUpdateProof, PartialMmrLeaf, SignatureWithAuthorityIndex,
 	SignedCommitment,
 };
 use beefy_primitives::{
 	known_payload_ids::MMR_ROOT_ID,
-	mmr::{BeefyNextAuthoritySet, MmrLeaf},
+	mmr::{BeefyNextAuthoritySet, MmrLeaf, MmrLeafVersion},
 	Payload,
 };
-use beefy_prover::{Crypto, Prover};
+use beefy_prover::{
+	commitment_gap::{next_commitment, NextCommitment},
+	Crypto, Prover,
+};
+use codec::{Compact, Encode};
 use futures::stream::StreamExt;
-use pallet_mmr_primitives::Proof;
+use mmr_lib::{util::MemStore, MMR};
+use pallet_mmr_primitives::{BatchProof, Proof};
 use serde_json::Value;
-use sp_core::bytes::to_hex;
+use sp_core::{bytes::to_hex, H256};
+use sp_runtime::{generic::Header, traits::BlakeTwo256};
+use sp_trie::{generate_trie_proof, LayoutV0, MemoryDB, TrieDBMut, TrieMut};
 use subxt::{
 	rpc::{rpc_params, Subscription},
 	PolkadotConfig,
@@ -293,3 +301,234 @@ async fn verify_parachain_headers() {
 		);
 	}
 }
+
+#[tokio::test]
+#[ignore]
+async fn verify_parachain_headers_across_a_gap_in_commitments() {
+	let relay = std::env::var("RELAY_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+	let para = std::env::var("PARA_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+
+	let relay_ws_url = format!("ws://{relay}:9944");
+	let para_ws_url = format!("ws://{para}:9188");
+
+	let client = subxt::client::OnlineClient::<PolkadotConfig>::from_url(relay_ws_url)
+		.await
+		.unwrap();
+	let para_client = subxt::client::OnlineClient::<PolkadotConfig>::from_url(para_ws_url)
+		.await
+		.unwrap();
+
+	let mut client_state = Prover::get_initial_client_state(Some(&client)).await;
+	let subscription: Subscription<String> = client
+		.rpc()
+		.subscribe(
+			"beefy_subscribeJustifications",
+			rpc_params![],
+			"beefy_unsubscribeJustifications",
+		)
+		.await
+		.unwrap();
+
+	let parachain_client =
+		Prover { relay_client: client, para_client, beefy_activation_block: 0, para_id: 2000 };
+
+	// Withhold commitments from the light client until the relay chain has gone through two
+	// authority set changes it didn't see, as if the relay chain hadn't signed any in between.
+	let mut subscription_stream = subscription.take(1000);
+	let signed_commitment = loop {
+		let commitment = subscription_stream.next().await.unwrap().unwrap();
+		let recv_commitment: sp_core::Bytes =
+			serde_json::from_value(Value::String(commitment)).unwrap();
+		let signed_commitment: beefy_primitives::SignedCommitment<
+			u32,
+			beefy_primitives::crypto::Signature,
+		> = codec::Decode::decode(&mut &*recv_commitment).unwrap();
+		if signed_commitment.commitment.validator_set_id > client_state.next_authorities.id {
+			break signed_commitment
+		}
+	};
+	let block_number = signed_commitment.commitment.block_number;
+	let latest_beefy_height = client_state.latest_beefy_height;
+	println!(
+		"Received commitment for block {block_number} signed by authority set {}, the light \
+		 client trusts authority sets {} and {}",
+		signed_commitment.commitment.validator_set_id,
+		client_state.current_authorities.id,
+		client_state.next_authorities.id
+	);
+
+	// Update the light client past the authority set changes with mandatory commitments alone.
+	loop {
+		match next_commitment(&client_state, signed_commitment.commitment.validator_set_id) {
+			NextCommitment::Received => break,
+			NextCommitment::Outdated => panic!("The received commitment can't be outdated"),
+			NextCommitment::Mandatory { validator_set_id } => {
+				let mandatory_commitment = parachain_client
+					.query_mandatory_commitment(
+						validator_set_id,
+						client_state.latest_beefy_height,
+						block_number,
+					)
+					.await
+					.unwrap()
+					.expect("The mandatory block before the received commitment is signed");
+				assert_eq!(mandatory_commitment.commitment.validator_set_id, validator_set_id);
+				println!(
+					"Updating the light client with the mandatory commitment for block {}",
+					mandatory_commitment.commitment.block_number
+				);
+				let mmr_update = parachain_client
+					.fetch_mmr_update_proof_for(mandatory_commitment)
+					.await
+					.unwrap();
+				client_state = crate::verify_mmr_root_with_proof::<Crypto>(client_state, mmr_update)
+					.expect("verify_mmr_root_with_proof should not panic!");
+				assert_eq!(client_state.current_authorities.id, validator_set_id);
+			},
+		}
+	}
+
+	// The parachain headers finalized in the skipped range are proven with the received
+	// commitment, along with the ones finalized since.
+	let headers = parachain_client
+		.query_finalized_parachain_headers_at(block_number, latest_beefy_height)
+		.await
+		.unwrap();
+	let (parachain_headers, batch_proof) = parachain_client
+		.query_finalized_parachain_headers_with_proof(
+			block_number,
+			latest_beefy_height,
+			headers.iter().map(|h| h.number).collect(),
+		)
+		.await
+		.unwrap();
+	assert_eq!(parachain_headers.len(), headers.len());
+
+	let mmr_update = parachain_client.fetch_mmr_update_proof_for(signed_commitment).await.unwrap();
+	client_state = crate::verify_mmr_root_with_proof::<Crypto>(client_state, mmr_update)
+		.expect("verify_mmr_root_with_proof should not panic!");
+	crate::verify_parachain_headers::<Crypto>(
+		client_state.clone(),
+		ParachainsUpdateProof { parachain_headers, mmr_proof: batch_proof },
+	)
+	.expect("verify_parachain_headers should not panic!");
+
+	println!(
+		"\nSuccessfully verified {} parachain headers finalized in relay chain blocks {}..={}\n",
+		headers.len(),
+		latest_beefy_height + 1,
+		client_state.latest_beefy_height,
+	);
+}
+
+const PARA_ID: u32 = 2000;
+
+/// Builds an mmr of `leaf_count` relay chain leaves, where the leaves at `proven` carry a header of
+/// our parachain. Returns the client state trusting the mmr root, together with the parachain
+/// headers and a single batch proof for all of them.
+fn batch_proof_fixture(leaf_count: u32, proven: &[u32]) -> (ClientState, ParachainsUpdateProof) {
+	let authority_set = BeefyNextAuthoritySet { id: 1, len: 1, root: H256::zero() };
+	let store = MemStore::default();
+	let mut mmr = MMR::<H256, MerkleHasher<Crypto>, _>::new(0, &store);
+	let mut positions = vec![];
+	let mut parachain_headers = vec![];
+
+	for leaf_index in 0..leaf_count {
+		// (para_id, encoded header) pairs committed to in the parachain heads root.
+		let mut heads = vec![(PARA_ID + 1, vec![0u8]).encode()];
+		let parachain_header = proven.contains(&leaf_index).then(|| {
+			let timestamp_extrinsic =
+				(1u8, 0u8, Compact(1_650_894_363_000u64 + leaf_index as u64)).encode();
+			let key = Compact(0u32).encode();
+			let mut db = MemoryDB::<BlakeTwo256>::default();
+			let extrinsics_root = {
+				let mut root = Default::default();
+				let mut trie = <TrieDBMut<LayoutV0<BlakeTwo256>>>::new(&mut db, &mut root);
+				trie.insert(&key, &timestamp_extrinsic).unwrap();
+				*trie.root()
+			};
+			let extrinsic_proof = generate_trie_proof::<LayoutV0<BlakeTwo256>, _, _, _>(
+				&db,
+				extrinsics_root,
+				vec![&key],
+			)
+			.unwrap();
+			let header = Header::<u32, BlakeTwo256> {
+				parent_hash: Default::default(),
+				number: leaf_index,
+				state_root: Default::default(),
+				extrinsics_root,
+				digest: Default::default(),
+			};
+			heads.insert(0, (PARA_ID, header.encode()).encode());
+			(header.encode(), extrinsic_proof, timestamp_extrinsic)
+		});
+
+		let leaves = heads.iter().map(|head| Crypto::keccak_256(head)).collect::<Vec<_>>();
+		let tree = rs_merkle::MerkleTree::<MerkleHasher<Crypto>>::from_leaves(&leaves);
+		let partial_mmr_leaf = PartialMmrLeaf {
+			version: MmrLeafVersion::new(0, 0),
+			parent_number_and_hash: (leaf_index, H256::repeat_byte(leaf_index as u8)),
+			beefy_next_authority_set: authority_set.clone(),
+		};
+		let mmr_leaf = MmrLeaf {
+			version: partial_mmr_leaf.version,
+			parent_number_and_hash: partial_mmr_leaf.parent_number_and_hash,
+			beefy_next_authority_set: partial_mmr_leaf.beefy_next_authority_set.clone(),
+			leaf_extra: H256::from(tree.root().unwrap()),
+		};
+		let position = mmr.push(H256::from(Crypto::keccak_256(&mmr_leaf.encode()))).unwrap();
+
+		if let Some((parachain_header, extrinsic_proof, timestamp_extrinsic)) = parachain_header {
+			positions.push(position);
+			parachain_headers.push(ParachainHeader {
+				parachain_header,
+				partial_mmr_leaf,
+				para_id: PARA_ID,
+				parachain_heads_proof: tree.proof(&[0]).proof_hashes().to_vec(),
+				heads_leaf_index: 0,
+				heads_total_count: heads.len() as u32,
+				extrinsic_proof,
+				timestamp_extrinsic,
+			});
+		}
+	}
+
+	let client_state = ClientState {
+		latest_beefy_height: leaf_count,
+		mmr_root_hash: mmr.get_root().unwrap(),
+		current_authorities: authority_set.clone(),
+		next_authorities: authority_set,
+		beefy_activation_block: 0,
+	};
+	let mmr_proof = BatchProof {
+		leaf_indices: proven.iter().map(|leaf_index| *leaf_index as u64).collect(),
+		leaf_count: leaf_count as u64,
+		items: mmr.gen_proof(positions).unwrap().proof_items().to_vec(),
+	};
+
+	(client_state, ParachainsUpdateProof { parachain_headers, mmr_proof })
+}
+
+#[test]
+fn should_verify_parachain_headers_with_batch_proof() {
+	let (client_state, parachain_update_proof) = batch_proof_fixture(5, &[1, 2, 3]);
+	assert_eq!(parachain_update_proof.parachain_headers.len(), 3);
+
+	crate::verify_parachain_headers::<Crypto>(client_state, parachain_update_proof)
+		.expect("verify_parachain_headers should not fail for a valid batch proof");
+}
+
+#[test]
+fn should_fail_batch_proof_with_tampered_leaf() {
+	let (client_state, mut parachain_update_proof) = batch_proof_fixture(5, &[1, 2, 3]);
+	// the second leaf no longer matches the one committed to in the mmr.
+	parachain_update_proof.parachain_headers[1].partial_mmr_leaf.parent_number_and_hash.1 =
+		H256::repeat_byte(0xff);
+
+	match crate::verify_parachain_headers::<Crypto>(client_state, parachain_update_proof) {
+		Err(BeefyClientError::InvalidMmrProof { .. }) => {},
+		Err(err) => panic!("Expected InvalidMmrProof found {:?}", err),
+		Ok(val) => panic!("Expected InvalidMmrProof found {:?}", val),
+	}
+}
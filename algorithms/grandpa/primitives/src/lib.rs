@@ -71,6 +71,64 @@ pub struct ClientState<H> {
 	pub latest_relay_hash: H,
 	/// para_id of associated parachain
 	pub para_id: u32,
+	/// Authority set change signalled in a finalized relay chain header, but not yet enacted.
+	pub pending_authority_change: Option<PendingAuthorityChange>,
+}
+
+/// A standard authority set change, signalled in a finalized relay chain header.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct PendingAuthorityChange {
+	/// Id of the authority set that takes over once the change is enacted.
+	pub next_set_id: u64,
+	/// Authorities that take over once the change is enacted.
+	pub next_authorities: AuthorityList,
+	/// Relay chain height of the block that enacts the change.
+	pub effective_height: u32,
+}
+
+impl<H> ClientState<H> {
+	/// Returns the id and the authorities of the set that signs justifications for the relay
+	/// chain block at `target_height`.
+	///
+	/// The next set only takes over from a change the current set has finalized, so the client
+	/// never trusts authorities announced in headers no known set has signed for.
+	pub fn authority_set_at(&self, target_height: u32) -> (u64, &AuthorityList) {
+		match &self.pending_authority_change {
+			Some(change) if target_height > change.effective_height =>
+				(change.next_set_id, &change.next_authorities),
+			_ => (self.current_set_id, &self.current_authorities),
+		}
+	}
+
+	/// Tracks the standard authority set changes signalled in `finalized`, the newly finalized
+	/// relay chain headers in ascending order, and rotates the current set once the block
+	/// enacting a change is final at `target_height`.
+	pub fn apply_authority_changes<T>(&mut self, finalized: &[&T], target_height: u32)
+	where
+		T: Header<Number = u32>,
+	{
+		for header in finalized {
+			self.enact_pending_change(*header.number());
+			if let Some(change) = justification::find_scheduled_change(*header) {
+				self.pending_authority_change = Some(PendingAuthorityChange {
+					next_set_id: self.current_set_id + 1,
+					next_authorities: change.next_authorities,
+					effective_height: header.number() + change.delay,
+				});
+			}
+		}
+		self.enact_pending_change(target_height);
+	}
+
+	fn enact_pending_change(&mut self, finalized_height: u32) {
+		match self.pending_authority_change.take() {
+			Some(change) if change.effective_height <= finalized_height => {
+				self.current_set_id = change.next_set_id;
+				self.current_authorities = change.next_authorities;
+			},
+			pending => self.pending_authority_change = pending,
+		}
+	}
 }
 
 /// Holds relavant parachain proofs for both header and timestamp extrinsic.
@@ -117,3 +175,84 @@ pub fn parachain_header_storage_key(para_id: u32) -> StorageKey {
 	storage_key.extend_from_slice(&encoded_para_id);
 	StorageKey(storage_key)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_finality_grandpa::{ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+	use sp_runtime::{
+		generic::{Digest, DigestItem, Header as GenericHeader},
+		traits::BlakeTwo256,
+	};
+
+	type RelayHeader = GenericHeader<u32, BlakeTwo256>;
+
+	fn authorities(seed: u8) -> AuthorityList {
+		vec![(ed25519::Public::from_raw([seed; 32]).into(), 1)]
+	}
+
+	fn header(number: u32, change: Option<(AuthorityList, u32)>) -> RelayHeader {
+		let mut digest = Digest::default();
+		if let Some((next_authorities, delay)) = change {
+			let log = ConsensusLog::ScheduledChange(ScheduledChange { next_authorities, delay });
+			digest.push(DigestItem::Consensus(GRANDPA_ENGINE_ID, log.encode()));
+		}
+		RelayHeader::new(number, Default::default(), Default::default(), Default::default(), digest)
+	}
+
+	fn client_state() -> ClientState<()> {
+		ClientState {
+			current_authorities: authorities(1),
+			current_set_id: 1,
+			latest_relay_height: 40,
+			latest_para_height: 0,
+			latest_relay_hash: (),
+			para_id: 2000,
+			pending_authority_change: None,
+		}
+	}
+
+	#[test]
+	fn test_delayed_authority_change_across_updates() {
+		let mut client_state = client_state();
+
+		// the change is signalled in an intermediate block and enacted two blocks later.
+		let headers = (41..=44)
+			.map(|n| header(n, (n == 43).then(|| (authorities(2), 2))))
+			.collect::<Vec<_>>();
+		client_state.apply_authority_changes(&headers.iter().collect::<Vec<_>>(), 44);
+
+		assert_eq!(client_state.current_set_id, 1);
+		assert_eq!(
+			client_state.pending_authority_change,
+			Some(PendingAuthorityChange {
+				next_set_id: 2,
+				next_authorities: authorities(2),
+				effective_height: 45,
+			})
+		);
+		// the old set keeps finalizing up to the enacting block.
+		assert_eq!(client_state.authority_set_at(45), (1, &authorities(1)));
+		assert_eq!(client_state.authority_set_at(47), (2, &authorities(2)));
+
+		let headers = (45..=47).map(|n| header(n, None)).collect::<Vec<_>>();
+		client_state.apply_authority_changes(&headers.iter().collect::<Vec<_>>(), 47);
+
+		assert_eq!(client_state.current_set_id, 2);
+		assert_eq!(client_state.current_authorities, authorities(2));
+		assert_eq!(client_state.pending_authority_change, None);
+		assert_eq!(client_state.authority_set_at(48), (2, &authorities(2)));
+	}
+
+	#[test]
+	fn test_immediate_authority_change_at_target() {
+		let mut client_state = client_state();
+
+		let headers = vec![header(41, None), header(42, Some((authorities(2), 0)))];
+		client_state.apply_authority_changes(&headers.iter().collect::<Vec<_>>(), 42);
+
+		assert_eq!(client_state.current_set_id, 2);
+		assert_eq!(client_state.current_authorities, authorities(2));
+		assert_eq!(client_state.pending_authority_change, None);
+	}
+}
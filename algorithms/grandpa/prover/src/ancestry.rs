@@ -0,0 +1,261 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Relay chain headers cached between consecutive finality proofs.
+
+use anyhow::anyhow;
+use sp_runtime::traits::Header;
+use std::{
+	collections::{BTreeMap, HashMap},
+	future::Future,
+	sync::Mutex,
+};
+
+/// Default number of relay chain headers kept by the [`HeaderCache`] of a prover.
+pub const DEFAULT_HEADER_CACHE_SIZE: usize = 4096;
+
+/// Default maximum number of headers in the ancestry of a finality proof.
+pub const DEFAULT_MAX_ANCESTRY_GAP: u32 = 4096;
+
+/// Least recently used cache of headers, keyed by hash. The ancestry of a finality proof starts
+/// at the relay chain block the light client last saw finalized, so consecutive proofs share
+/// most of their headers.
+pub struct HeaderCache<H: Header> {
+	capacity: usize,
+	/// Cached headers, with the tick they were last used at.
+	headers: HashMap<H::Hash, (H, u64)>,
+	/// Hashes of the cached headers by the tick they were last used at.
+	recency: BTreeMap<u64, H::Hash>,
+	tick: u64,
+}
+
+impl<H: Header> HeaderCache<H> {
+	/// Creates an empty cache holding at most `capacity` headers.
+	pub fn new(capacity: usize) -> Self {
+		Self { capacity, headers: HashMap::new(), recency: BTreeMap::new(), tick: 0 }
+	}
+
+	/// Number of cached headers.
+	pub fn len(&self) -> usize {
+		self.headers.len()
+	}
+
+	/// Returns the header with the given hash, marking it as the most recently used.
+	pub fn get(&mut self, hash: &H::Hash) -> Option<H> {
+		let tick = self.next_tick();
+		let (header, used_at) = self.headers.get_mut(hash)?;
+		self.recency.remove(used_at);
+		self.recency.insert(tick, *hash);
+		*used_at = tick;
+		Some(header.clone())
+	}
+
+	/// Caches `header`, evicting the least recently used header when the cache is full.
+	pub fn insert(&mut self, header: H) {
+		if self.capacity == 0 {
+			return
+		}
+		let hash = header.hash();
+		let tick = self.next_tick();
+		if let Some((_, used_at)) = self.headers.insert(hash, (header, tick)) {
+			self.recency.remove(&used_at);
+		} else if self.headers.len() > self.capacity {
+			if let Some((&used_at, &evicted)) = self.recency.iter().next() {
+				self.recency.remove(&used_at);
+				self.headers.remove(&evicted);
+			}
+		}
+		self.recency.insert(tick, hash);
+	}
+
+	fn next_tick(&mut self) -> u64 {
+		self.tick += 1;
+		self.tick
+	}
+}
+
+/// Returns the headers with the given hashes, in order. The headers missing from `cache` are
+/// fetched with a single call to `fetch`, which must return them in the order they are requested
+/// in, and are then cached.
+pub async fn fetch_ancestry<H, F, Fut>(
+	cache: &Mutex<HeaderCache<H>>,
+	hashes: &[H::Hash],
+	fetch: F,
+) -> Result<Vec<H>, anyhow::Error>
+where
+	H: Header,
+	F: FnOnce(Vec<H::Hash>) -> Fut,
+	Fut: Future<Output = Result<Vec<H>, anyhow::Error>>,
+{
+	// the lock isn't held while fetching
+	let cached = {
+		let mut cache = cache.lock().unwrap();
+		hashes.iter().map(|hash| cache.get(hash)).collect::<Vec<_>>()
+	};
+	let missing = hashes
+		.iter()
+		.zip(&cached)
+		.filter(|(_, header)| header.is_none())
+		.map(|(hash, _)| *hash)
+		.collect::<Vec<_>>();
+	if missing.is_empty() {
+		return Ok(cached.into_iter().flatten().collect())
+	}
+
+	let fetched = fetch(missing.clone()).await?;
+	if fetched.len() != missing.len() {
+		Err(anyhow!("Requested {} headers, received {}", missing.len(), fetched.len()))?
+	}
+	for (hash, header) in missing.iter().zip(&fetched) {
+		if header.hash() != *hash {
+			Err(anyhow!("Requested header {hash:?}, received {:?}", header.hash()))?
+		}
+	}
+
+	let mut fetched = fetched.into_iter();
+	let headers = cached
+		.into_iter()
+		.map(|header| header.or_else(|| fetched.next()))
+		.collect::<Option<Vec<_>>>()
+		.expect("There's a fetched header for every missing one; qed");
+	let mut cache = cache.lock().unwrap();
+	for header in &headers {
+		cache.insert(header.clone());
+	}
+	Ok(headers)
+}
+
+/// Fails when the ancestry from relay chain block `from` to block `to` holds more than `max`
+/// headers.
+pub fn check_ancestry_gap(from: u32, to: u32, max: u32) -> Result<(), anyhow::Error> {
+	let gap = to.saturating_sub(from) + 1;
+	if gap > max {
+		Err(anyhow!(
+			"The ancestry from relay chain block {from} to {to} holds {gap} headers, more than \
+			the maximum of {max}. The light client fell too far behind the relay chain, consider \
+			recovering it by substituting a new client for it instead."
+		))?
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+	use sp_runtime::{generic, traits::BlakeTwo256};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	type TestHeader = generic::Header<u32, BlakeTwo256>;
+
+	/// A relay chain rpc serving `chain_getHeader` batches, counting the headers it serves.
+	#[derive(Default)]
+	struct MockRpc {
+		headers: HashMap<H256, TestHeader>,
+		/// The canonical chain, by block number.
+		chain: Vec<H256>,
+		batches: AtomicUsize,
+		fetched: AtomicUsize,
+	}
+
+	impl MockRpc {
+		fn new(length: u32) -> Self {
+			let mut rpc = Self::default();
+			let mut parent_hash = Default::default();
+			for number in 0..length {
+				let header = TestHeader::new(
+					number,
+					Default::default(),
+					Default::default(),
+					parent_hash,
+					Default::default(),
+				);
+				parent_hash = header.hash();
+				rpc.chain.push(parent_hash);
+				rpc.headers.insert(parent_hash, header);
+			}
+			rpc
+		}
+
+		async fn get_headers(&self, hashes: Vec<H256>) -> Result<Vec<TestHeader>, anyhow::Error> {
+			self.batches.fetch_add(1, Ordering::SeqCst);
+			self.fetched.fetch_add(hashes.len(), Ordering::SeqCst);
+			hashes
+				.iter()
+				.map(|hash| self.headers.get(hash).cloned().ok_or_else(|| anyhow!("Unknown block")))
+				.collect()
+		}
+
+		/// Ancestry of a finality proof for block `to` to a client that last saw block `from`.
+		async fn prove(
+			&self,
+			cache: &Mutex<HeaderCache<TestHeader>>,
+			from: u32,
+			to: u32,
+		) -> Vec<TestHeader> {
+			check_ancestry_gap(from, to, DEFAULT_MAX_ANCESTRY_GAP).unwrap();
+			let hashes = &self.chain[from as usize..=to as usize];
+			let headers = fetch_ancestry(cache, hashes, |missing| self.get_headers(missing))
+				.await
+				.unwrap();
+			assert_eq!(headers.iter().map(|header| header.hash()).collect::<Vec<_>>(), hashes);
+			headers
+		}
+	}
+
+	#[tokio::test]
+	async fn consecutive_proofs_only_fetch_new_headers() {
+		let rpc = MockRpc::new(1000);
+		let cache = Mutex::new(HeaderCache::new(DEFAULT_HEADER_CACHE_SIZE));
+
+		rpc.prove(&cache, 100, 400).await;
+		assert_eq!(rpc.batches.load(Ordering::SeqCst), 1);
+		assert_eq!(rpc.fetched.load(Ordering::SeqCst), 301);
+
+		// the client didn't get updated, the next proof extends the same ancestry.
+		rpc.prove(&cache, 100, 600).await;
+		assert_eq!(rpc.batches.load(Ordering::SeqCst), 2);
+		assert_eq!(rpc.fetched.load(Ordering::SeqCst), 501);
+
+		// nothing to fetch for an ancestry that is fully cached.
+		rpc.prove(&cache, 400, 600).await;
+		assert_eq!(rpc.batches.load(Ordering::SeqCst), 2);
+	}
+
+	#[tokio::test]
+	async fn least_recently_used_headers_are_evicted() {
+		let rpc = MockRpc::new(100);
+		let cache = Mutex::new(HeaderCache::new(50));
+
+		rpc.prove(&cache, 0, 39).await;
+		rpc.prove(&cache, 30, 59).await;
+		assert_eq!(cache.lock().unwrap().len(), 50);
+		assert_eq!(rpc.fetched.load(Ordering::SeqCst), 60);
+
+		// blocks 0..10 were the least recently used ones.
+		rpc.prove(&cache, 10, 59).await;
+		assert_eq!(rpc.fetched.load(Ordering::SeqCst), 60);
+		rpc.prove(&cache, 0, 9).await;
+		assert_eq!(rpc.fetched.load(Ordering::SeqCst), 70);
+	}
+
+	#[test]
+	fn ancestry_gap_is_capped() {
+		assert!(check_ancestry_gap(10, 10, 1).is_ok());
+		assert!(check_ancestry_gap(10, 19, 10).is_ok());
+		let err = check_ancestry_gap(10, 20, 10).unwrap_err();
+		assert!(err.to_string().contains("holds 11 headers, more than the maximum of 10"));
+	}
+}
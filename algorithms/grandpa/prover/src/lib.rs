@@ -17,7 +17,13 @@
 
 //! GRANDPA prover utilities
 
-use crate::polkadot::api::runtime_types::polkadot_parachain::primitives::Id;
+use crate::{
+	ancestry::{
+		check_ancestry_gap, fetch_ancestry, HeaderCache, DEFAULT_HEADER_CACHE_SIZE,
+		DEFAULT_MAX_ANCESTRY_GAP,
+	},
+	polkadot::api::runtime_types::polkadot_parachain::primitives::Id,
+};
 use anyhow::anyhow;
 pub use beefy_prover;
 use beefy_prover::helpers::{
@@ -25,23 +31,27 @@ use beefy_prover::helpers::{
 };
 use codec::{Decode, Encode};
 use finality_grandpa_rpc::GrandpaApiClient;
-use jsonrpsee::{async_client::Client, ws_client::WsClientBuilder};
+use jsonrpsee::{
+	async_client::Client, core::client::ClientT, rpc_params, ws_client::WsClientBuilder,
+};
 use primitives::{
 	justification::GrandpaJustification, parachain_header_storage_key, ClientState, FinalityProof,
-	ParachainHeaderProofs, ParachainHeadersWithFinalityProof,
+	ParachainHeaderProofs, ParachainHeadersWithFinalityProof, PendingAuthorityChange,
 };
 use serde::{Deserialize, Serialize};
 use sp_core::H256;
 use sp_runtime::traits::{Header, Zero};
 use std::{
 	collections::{BTreeMap, BTreeSet},
-	sync::Arc,
+	sync::{Arc, Mutex},
 };
 use subxt::{
 	ext::{sp_core::hexdisplay::AsBytesRef, sp_runtime::traits::One},
 	Config, OnlineClient,
 };
 
+/// Relay chain header cache for finality proof ancestries
+pub mod ancestry;
 /// Host function implementation for the verifier
 pub mod host_functions;
 /// Subxt generated code for the parachain
@@ -61,6 +71,10 @@ pub struct GrandpaProver<T: Config> {
 	pub para_ws_client: Arc<Client>,
 	/// ParaId of the associated parachain
 	pub para_id: u32,
+	/// Relay chain headers fetched for previous finality proofs
+	pub header_cache: Arc<Mutex<HeaderCache<T::Header>>>,
+	/// Maximum number of relay chain headers in the ancestry of a finality proof
+	pub max_ancestry_gap: u32,
 }
 
 /// An encoded justification proving that the given header has been finalized
@@ -84,7 +98,15 @@ where
 		let para_ws_client = Arc::new(WsClientBuilder::default().build(para_ws_url).await?);
 		let para_client = OnlineClient::<T>::from_rpc_client(para_ws_client.clone()).await?;
 
-		Ok(Self { relay_ws_client, relay_client, para_ws_client, para_client, para_id })
+		Ok(Self {
+			relay_ws_client,
+			relay_client,
+			para_ws_client,
+			para_client,
+			para_id,
+			header_cache: Arc::new(Mutex::new(HeaderCache::new(DEFAULT_HEADER_CACHE_SIZE))),
+			max_ancestry_gap: DEFAULT_MAX_ANCESTRY_GAP,
+		})
 	}
 
 	/// Construct the inital client state.
@@ -115,6 +137,27 @@ where
 			AuthorityList::decode(&mut &bytes[..]).expect("Failed to scale decode authorities")
 		};
 
+		// A change signalled before the client's first block would never be seen by the client.
+		let pending_authority_change = {
+			let key = polkadot::api::storage().grandpa().pending_change();
+			self.relay_client
+				.storage()
+				.fetch(&key, None)
+				.await?
+				.filter(|change| change.forced.is_none())
+				.map(|change| -> Result<_, anyhow::Error> {
+					// the runtime authority list shares its encoding with `AuthorityList`
+					let next_authorities =
+						AuthorityList::decode(&mut &change.next_authorities.encode()[..])?;
+					Ok(PendingAuthorityChange {
+						next_set_id: current_set_id + 1,
+						next_authorities,
+						effective_height: change.scheduled_at + change.delay,
+					})
+				})
+				.transpose()?
+		};
+
 		// Ensure there are no duplicates in authority list
 		let mut set = BTreeSet::new();
 		for (id, ..) in &current_authorities {
@@ -143,6 +186,7 @@ where
 			para_id: self.para_id,
 			// we'll set this below
 			latest_para_height: u32::from(*finalized_para_header.number()),
+			pending_authority_change,
 		})
 	}
 
@@ -231,38 +275,28 @@ where
 		let latest_finalized_height = u32::from(justification.commit.target_number);
 		finality_proof.block = justification.commit.target_hash;
 
-		let start = self
-			.relay_client
-			.rpc()
-			.block_hash(Some(previous_finalized_height.into()))
-			.await?
-			.ok_or_else(|| anyhow!("Failed to fetch previous finalized hash + 1"))?;
-
-		let latest_finalized_hash = self
-			.relay_client
-			.rpc()
-			.block_hash(Some(latest_finalized_height.into()))
-			.await?
-			.ok_or_else(|| anyhow!("Failed to fetch previous finalized hash + 1"))?;
-
-		let mut unknown_headers = vec![];
-		for height in previous_finalized_height..=latest_finalized_height {
-			let hash = self
-				.relay_client
-				.rpc()
-				.block_hash(Some(height.into()))
-				.await?
-				.ok_or_else(|| anyhow!("Failed to fetch block has for height {height}"))?;
+		check_ancestry_gap(
+			previous_finalized_height,
+			latest_finalized_height,
+			self.max_ancestry_gap,
+		)?;
+		let hashes =
+			self.relay_block_hashes(previous_finalized_height, latest_finalized_height).await?;
+		let (start, latest_finalized_hash) = match (hashes.first(), hashes.last()) {
+			(Some(start), Some(latest)) => (*start, *latest),
+			_ => Err(anyhow!(
+				"No relay chain blocks from {previous_finalized_height} to \
+				{latest_finalized_height}"
+			))?,
+		};
 
-			let header = self
-				.relay_client
-				.rpc()
-				.header(Some(hash))
+		// only the headers missing from the cache are fetched, in a single batch.
+		let unknown_headers =
+			fetch_ancestry(&self.header_cache, &hashes, |hashes| self.relay_headers(hashes))
 				.await?
-				.ok_or_else(|| anyhow!("Header with hash: {hash:?} not found!"))?;
-
-			unknown_headers.push(H::decode(&mut &header.encode()[..])?);
-		}
+				.into_iter()
+				.map(|header| H::decode(&mut &header.encode()[..]))
+				.collect::<Result<Vec<_>, _>>()?;
 
 		// overwrite unknown headers
 		finality_proof.unknown_headers = unknown_headers;
@@ -331,6 +365,38 @@ where
 		})
 	}
 
+	// Queries the hashes of the relay chain blocks `from..=to` in a single `chain_getBlockHash`.
+	async fn relay_block_hashes(&self, from: u32, to: u32) -> Result<Vec<T::Hash>, anyhow::Error> {
+		let numbers = (from..=to).collect::<Vec<_>>();
+		let hashes = self
+			.relay_client
+			.rpc()
+			.request::<Vec<Option<T::Hash>>>("chain_getBlockHash", subxt::rpc_params!(&numbers))
+			.await?;
+		if hashes.len() != numbers.len() {
+			Err(anyhow!("Requested {} block hashes, received {}", numbers.len(), hashes.len()))?
+		}
+		(from..=to)
+			.zip(hashes)
+			.map(|(number, hash)| {
+				hash.ok_or_else(|| anyhow!("Failed to fetch block hash for height {number}"))
+			})
+			.collect()
+	}
+
+	// Queries the relay chain headers with the given hashes in a single batch request.
+	async fn relay_headers(&self, hashes: Vec<T::Hash>) -> Result<Vec<T::Header>, anyhow::Error> {
+		let batch = hashes.iter().map(|hash| ("chain_getHeader", rpc_params!(hash))).collect();
+		let headers = self.relay_ws_client.batch_request::<Option<T::Header>>(batch).await?;
+		hashes
+			.iter()
+			.zip(headers)
+			.map(|(hash, header)| {
+				header.ok_or_else(|| anyhow!("Header with hash: {hash:?} not found!"))
+			})
+			.collect()
+	}
+
 	// Queries the block at which the epoch for the given block belongs to ends.
 	async fn session_end_for_block(&self, block: u32) -> Result<u32, anyhow::Error> {
 		let epoch_addr = polkadot::api::storage().babe().epoch_start();
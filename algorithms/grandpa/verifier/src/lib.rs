@@ -21,7 +21,7 @@
 
 extern crate alloc;
 
-use alloc::vec;
+use alloc::{vec, vec::Vec};
 use anyhow::anyhow;
 use codec::{Decode, Encode};
 use finality_grandpa::Chain;
@@ -30,7 +30,7 @@ use light_client_common::state_machine;
 use primitive_types::H256;
 use primitives::{
 	error,
-	justification::{find_scheduled_change, AncestryChain, GrandpaJustification},
+	justification::{AncestryChain, GrandpaJustification},
 	parachain_header_storage_key, ClientState, HostFunctions, ParachainHeaderProofs,
 	ParachainHeadersWithFinalityProof,
 };
@@ -97,8 +97,10 @@ where
 	})?;
 	finalized.sort();
 
-	// 2. verify justification.
-	justification.verify::<Host>(client_state.current_set_id, &client_state.current_authorities)?;
+	// 2. verify justification, against the next authority set if a pending change was enacted
+	// below the target.
+	let (set_id, authorities) = client_state.authority_set_at(*target.number());
+	justification.verify::<Host>(set_id, authorities)?;
 
 	// 3. verify state proofs of parachain headers in finalized relay chain headers.
 	let mut para_heights = vec![];
@@ -138,15 +140,18 @@ where
 	}
 
 	// 4. set new client state, optionally rotating authorities
+	let mut finalized_headers = finalized
+		.iter()
+		.filter_map(|hash| headers.header(hash))
+		.filter(|header| *header.number() > client_state.latest_relay_height)
+		.collect::<Vec<_>>();
+	finalized_headers.sort_by_key(|header| *header.number());
+	client_state.apply_authority_changes(&finalized_headers, *target.number());
+
 	client_state.latest_relay_hash = target.hash();
 	client_state.latest_relay_height = (*target.number()).into();
 	if let Some(max_height) = para_heights.into_iter().max() {
 		client_state.latest_para_height = max_height;
 	}
-	if let Some(scheduled_change) = find_scheduled_change::<H>(&target) {
-		client_state.current_set_id += 1;
-		client_state.current_authorities = scheduled_change.next_authorities;
-	}
-
 	Ok(client_state)
 }
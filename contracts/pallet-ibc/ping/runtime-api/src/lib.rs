@@ -0,0 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use pallet_ibc_ping::PingStats;
+
+sp_api::decl_runtime_apis! {
+	/// Ping Runtime Apis
+	pub trait PingRuntimeApi {
+		/// Round-trip latency statistics, in blocks, of the pings acknowledged on the channel
+		fn ping_stats(channel_id: u64) -> Option<PingStats>;
+	}
+}
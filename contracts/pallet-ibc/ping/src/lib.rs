@@ -20,12 +20,13 @@ use ibc::{
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 		ics26_routing::context::{
 			Acknowledgement as GenericAcknowledgement, Module, ModuleCallbackContext,
-			ModuleOutputBuilder,
+			ModuleOutputBuilder, OnRecvPacketAck,
 		},
 	},
 	signer::Signer,
 };
 use ibc_primitives::{port_id_from_bytes, CallbackWeight, HandlerMessage, IbcHandler, Timeout};
+use sp_runtime::SaturatedConversion;
 use sp_std::{marker::PhantomData, prelude::*};
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
@@ -44,7 +45,10 @@ pub const VERSION: &str = "ping-1";
 	scale_info::TypeInfo,
 )]
 pub struct SendPingParams {
-	pub data: Vec<u8>,
+	/// Number of pings to send
+	pub count: u32,
+	/// Size in bytes of the data of every ping
+	pub payload_size: u32,
 	/// Timeout height offset relative to the client latest height
 	pub timeout_height_offset: u64,
 	/// Time out timestamp offset relative to client's latest height
@@ -53,6 +57,57 @@ pub struct SendPingParams {
 	pub channel_id: u64,
 }
 
+/// Round-trip latencies, in blocks, of the pings acknowledged on a channel
+#[derive(
+	Clone,
+	Default,
+	PartialEq,
+	Eq,
+	codec::Encode,
+	codec::Decode,
+	frame_support::RuntimeDebug,
+	scale_info::TypeInfo,
+)]
+pub struct PingLatencies {
+	pub acknowledged: u64,
+	pub min: u64,
+	pub max: u64,
+	/// Sum of all the latencies
+	pub total: u64,
+}
+
+impl PingLatencies {
+	fn record(&mut self, latency: u64) {
+		if self.acknowledged == 0 || latency < self.min {
+			self.min = latency;
+		}
+		self.max = self.max.max(latency);
+		self.total = self.total.saturating_add(latency);
+		self.acknowledged += 1;
+	}
+}
+
+/// Round-trip latency statistics of a channel, as returned by the runtime api
+#[derive(
+	Clone,
+	PartialEq,
+	Eq,
+	codec::Encode,
+	codec::Decode,
+	frame_support::RuntimeDebug,
+	scale_info::TypeInfo,
+)]
+pub struct PingStats {
+	/// Number of acknowledged pings
+	pub acknowledged: u64,
+	/// Lowest round-trip latency in blocks
+	pub min: u64,
+	/// Highest round-trip latency in blocks
+	pub max: u64,
+	/// Average round-trip latency in blocks
+	pub avg: u64,
+}
+
 // Definition of the pallet logic, to be aggregated at runtime definition through
 // `construct_runtime`.
 #[frame_support::pallet]
@@ -74,6 +129,10 @@ pub mod pallet {
 
 		/// ibc subsystem
 		type IbcHandler: ibc_primitives::IbcHandler<<Self as frame_system::Config>::AccountId>;
+
+		/// Largest data, in bytes, a ping may carry
+		#[pallet::constant]
+		type MaxPayloadSize: Get<u32>;
 	}
 
 	// Simple declaration of the `Pallet` type. It is placeholder we use to implement traits and
@@ -83,16 +142,36 @@ pub mod pallet {
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
+	#[pallet::storage]
+	/// channel_id, sequence => block number the ping was sent at
+	pub type PingSendBlocks<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, u64, Twox64Concat, u64, T::BlockNumber, OptionQuery>;
+
+	#[pallet::storage]
+	/// channel_id => round-trip latencies of the pings acknowledged on the channel
+	pub type Latencies<T: Config> = StorageMap<_, Twox64Concat, u64, PingLatencies, OptionQuery>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
+		/// Sends `params.count` pings of `params.payload_size` bytes, their round-trip latency is
+		/// recorded when they are acknowledged.
 		#[pallet::weight(0)]
+		#[frame_support::transactional]
 		pub fn send_ping(origin: OriginFor<T>, params: SendPingParams) -> DispatchResult {
 			ensure_root(origin)?;
-			Self::send_ping_impl(params).map_err(|e| {
-				log::trace!(target: "pallet_ibc_ping", "[send_ping] error: {:?}", e);
-				Error::<T>::PacketSendError
-			})?;
-			Self::deposit_event(Event::<T>::PacketSent);
+			if params.count == 0 {
+				return Err(Error::<T>::InvalidParams.into())
+			}
+			if params.payload_size > T::MaxPayloadSize::get() {
+				return Err(Error::<T>::PayloadTooLarge.into())
+			}
+			for _ in 0..params.count {
+				Self::send_ping_impl(&params).map_err(|e| {
+					log::trace!(target: "pallet_ibc_ping", "[send_ping] error: {:?}", e);
+					Error::<T>::PacketSendError
+				})?;
+				Self::deposit_event(Event::<T>::PacketSent);
+			}
 			Ok(())
 		}
 	}
@@ -114,21 +193,42 @@ pub mod pallet {
 		ChannelInitError,
 		/// Error registering packet
 		PacketSendError,
+		/// Ping payload is larger than `MaxPayloadSize`
+		PayloadTooLarge,
 	}
 }
 
 impl<T: Config> Pallet<T> {
-	pub fn send_ping_impl(params: SendPingParams) -> Result<(), ibc_primitives::Error> {
+	pub fn send_ping_impl(params: &SendPingParams) -> Result<(), ibc_primitives::Error> {
 		let channel_id = ChannelId::new(params.channel_id);
+		let port_id =
+			port_id_from_bytes(PORT_ID.as_bytes().to_vec()).expect("Valid port id expected");
+		let sequence = T::IbcHandler::next_sequence_send(&port_id, &channel_id)?;
 		T::IbcHandler::handle_message(HandlerMessage::SendPacket {
-			data: b"ping".to_vec(),
+			data: b"ping".iter().cycle().take(params.payload_size as usize).copied().collect(),
 			timeout: Timeout::Offset {
 				height: Some(params.timeout_height_offset),
 				timestamp: Some(params.timeout_timestamp_offset),
 			},
-			port_id: port_id_from_bytes(PORT_ID.as_bytes().to_vec())
-				.expect("Valid port id expected"),
+			port_id,
 			channel_id,
+		})?;
+		PingSendBlocks::<T>::insert(
+			params.channel_id,
+			sequence,
+			frame_system::Pallet::<T>::block_number(),
+		);
+		Ok(())
+	}
+
+	/// Round-trip latency statistics of the pings acknowledged on the channel
+	pub fn ping_stats(channel_id: u64) -> Option<PingStats> {
+		let latencies = Latencies::<T>::get(channel_id)?;
+		Some(PingStats {
+			acknowledged: latencies.acknowledged,
+			min: latencies.min,
+			max: latencies.max,
+			avg: latencies.total / latencies.acknowledged.max(1),
 		})
 	}
 }
@@ -267,14 +367,14 @@ impl<T: Config + Send + Sync> Module for IbcModule<T> {
 		_output: &mut ModuleOutputBuilder,
 		packet: &Packet,
 		_relayer: &Signer,
-	) -> Result<(), Ics04Error> {
+	) -> Result<OnRecvPacketAck, Ics04Error> {
 		let success = "ping-success".as_bytes().to_vec();
 		let data = String::from_utf8(packet.data.clone()).ok();
 		log::info!("Received Packet Sequence {:?}, Packet Data {:?}", packet.sequence, data);
 		let packet = packet.clone();
 		T::IbcHandler::write_acknowledgement(&packet, success)
 			.map_err(|e| Ics04Error::implementation_specific(format!("{:?}", e)))?;
-		Ok(())
+		Ok(OnRecvPacketAck::Async)
 	}
 
 	fn on_acknowledgement_packet(
@@ -286,6 +386,13 @@ impl<T: Config + Send + Sync> Module for IbcModule<T> {
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		log::info!("Acknowledged Packet {:?} {:?}", packet, acknowledgement);
+		let channel_id = packet.source_channel.sequence();
+		if let Some(sent) = PingSendBlocks::<T>::take(channel_id, u64::from(packet.sequence)) {
+			let latency = frame_system::Pallet::<T>::block_number() - sent;
+			Latencies::<T>::mutate(channel_id, |latencies| {
+				latencies.get_or_insert_with(Default::default).record(latency.saturated_into())
+			});
+		}
 		Ok(())
 	}
 
@@ -297,6 +404,7 @@ impl<T: Config + Send + Sync> Module for IbcModule<T> {
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		log::info!("Timeout Packet {:?}", packet);
+		PingSendBlocks::<T>::remove(packet.source_channel.sequence(), u64::from(packet.sequence));
 		Ok(())
 	}
 }
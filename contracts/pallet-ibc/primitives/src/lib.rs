@@ -229,6 +229,20 @@ pub struct QueryNextSequenceReceiveResponse {
 	pub trie_key: Vec<u8>,
 }
 
+#[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd)]
+pub struct QueryNextSequenceAckResponse {
+	pub sequence: u64,
+	pub height: u64,
+	pub trie_key: Vec<u8>,
+}
+
+#[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd)]
+pub struct QueryNextSequenceSendResponse {
+	pub sequence: u64,
+	pub height: u64,
+	pub trie_key: Vec<u8>,
+}
+
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd)]
 pub struct QueryPacketCommitmentResponse {
 	pub commitment: Vec<u8>,
@@ -362,9 +376,20 @@ pub trait IbcHandler<AccountId> {
 		port_id: &PortId,
 		channel_id: &ChannelId,
 	) -> Result<(Height, Timestamp), Error>;
+	/// Get the sequence the next packet sent on the channel and port combination will be given
+	fn next_sequence_send(port_id: &PortId, channel_id: &ChannelId) -> Result<u64, Error>;
 	/// Handle a message
 	fn handle_message(msg: HandlerMessage<AccountId>) -> Result<(), Error>;
 	fn write_acknowledgement(packet: &Packet, ack: Vec<u8>) -> Result<(), Error>;
+	/// Write the acknowledgement of a packet received on the channel and port combination, for
+	/// modules that returned `OnRecvPacketAck::Async` from `on_recv_packet`. Fails if the packet
+	/// was not received or is already acknowledged.
+	fn write_async_acknowledgement(
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: Vec<u8>,
+	) -> Result<(), Error>;
 	/// testing related methods
 	#[cfg(feature = "runtime-benchmarks")]
 	fn create_client() -> Result<ClientId, Error>;
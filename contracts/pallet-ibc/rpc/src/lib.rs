@@ -5,6 +5,7 @@
 use codec::Encode;
 use ibc::{
 	core::{
+		ics02_client::client_state::ClientStatus,
 		ics03_connection::connection::ConnectionEnd,
 		ics04_channel::channel::{ChannelEnd, IdentifiedChannelEnd, Order},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
@@ -105,6 +106,28 @@ pub struct Proof {
 	pub height: ibc_proto::ibc::core::client::v1::Height,
 }
 
+/// Next sequence to be acknowledged on an ordered channel
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryNextSequenceAckResponse {
+	/// Next sequence to be acknowledged
+	pub next_sequence_ack: u64,
+	/// Trie proof of the sequence
+	pub proof: Vec<u8>,
+	/// Height at which the proof was retrieved
+	pub proof_height: Option<Height>,
+}
+
+/// Next sequence to be sent on a channel
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueryNextSequenceSendResponse {
+	/// Next sequence to be sent
+	pub next_sequence_send: u64,
+	/// Trie proof of the sequence
+	pub proof: Vec<u8>,
+	/// Height at which the proof was retrieved
+	pub proof_height: Option<Height>,
+}
+
 /// Packet info
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct PacketInfo {
@@ -169,6 +192,11 @@ where
 	#[method(name = "ibc_queryProof")]
 	fn query_proof(&self, height: u32, keys: Vec<Vec<u8>>) -> Result<Proof>;
 
+	/// Generate proof for the values stored under the given ICS-024 paths, e.g.
+	/// `nextSequenceAck/ports/transfer/channels/channel-0`
+	#[method(name = "ibc_queryPathsProof")]
+	fn query_paths_proof(&self, height: u32, paths: Vec<String>) -> Result<Proof>;
+
 	/// Query latest height
 	#[method(name = "ibc_queryLatestHeight")]
 	fn query_latest_height(&self) -> Result<BlockNumber>;
@@ -186,6 +214,10 @@ where
 		src_client_id: String,
 	) -> Result<QueryClientStateResponse>;
 
+	/// Query whether a client is active, expired or frozen
+	#[method(name = "ibc_queryClientStatus")]
+	fn query_client_status(&self, height: u32, client_id: String) -> Result<ClientStatus>;
+
 	/// Query client consensus state
 	/// If the light client is a beefy light client, the revision height and revision number must be
 	/// specified And the `latest_consensus_state` field should be set to false, if not an error
@@ -222,6 +254,10 @@ where
 		connection_id: String,
 	) -> Result<QueryConnectionResponse>;
 
+	/// Query a connection end, as the JSON ibc-go's REST api renders it
+	#[method(name = "ibc_queryConnectionEnd")]
+	fn query_connection_end(&self, height: u32, connection_id: String) -> Result<ConnectionEnd>;
+
 	/// Query all connection states
 	#[method(name = "ibc_queryConnections")]
 	fn query_connections(&self) -> Result<QueryConnectionsResponse>;
@@ -252,6 +288,15 @@ where
 		port_id: String,
 	) -> Result<QueryChannelResponse>;
 
+	/// Query a channel end, as the JSON ibc-go's REST api renders it
+	#[method(name = "ibc_queryChannelEnd")]
+	fn query_channel_end(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<ChannelEnd>;
+
 	/// Query client state for channel and port id
 	#[method(name = "ibc_queryChannelClient")]
 	fn query_channel_client(
@@ -282,13 +327,41 @@ where
 		port_id: String,
 	) -> Result<QueryPacketCommitmentsResponse>;
 
-	/// Query packet acknowledgements
+	/// Query the number of outstanding packet commitments on a channel
+	#[method(name = "ibc_queryPacketCommitmentCount")]
+	fn query_packet_commitment_count(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<u64>;
+
+	/// Query the (sequence, timestamp) of the latest packets timed out on a channel
+	#[method(name = "ibc_queryTimedOutPackets")]
+	fn query_timed_out_packets(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<Vec<(u64, u64)>>;
+
+	/// Query the native tokens escrowed for transfers sent over a channel
+	#[method(name = "ibc_queryEscrowBalances")]
+	fn query_escrow_balances(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<Vec<Coin>>;
+
+	/// Query packet acknowledgements, only returning those for the sequences in `seqs` when given
 	#[method(name = "ibc_queryPacketAcknowledgements")]
 	fn query_packet_acknowledgements(
 		&self,
 		height: u32,
 		channel_id: String,
 		port_id: String,
+		seqs: Option<Vec<u64>>,
 	) -> Result<QueryPacketAcknowledgementsResponse>;
 
 	/// Given a list of counterparty packet commitments, the querier checks if the packet
@@ -340,6 +413,24 @@ where
 		port_id: String,
 	) -> Result<QueryNextSequenceReceiveResponse>;
 
+	/// Query next sequence to be acknowledged on channel
+	#[method(name = "ibc_queryNextSeqAck")]
+	fn query_next_seq_ack(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryNextSequenceAckResponse>;
+
+	/// Query next sequence to be sent on channel
+	#[method(name = "ibc_queryNextSeqSend")]
+	fn query_next_seq_send(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryNextSequenceSendResponse>;
+
 	/// Query packet commitment
 	#[method(name = "ibc_queryPacketCommitment")]
 	fn query_packet_commitment(
@@ -376,6 +467,11 @@ where
 	#[method(name = "ibc_queryDenomTrace")]
 	fn query_denom_trace(&self, asset_id: AssetId) -> Result<QueryDenomTraceResponse>;
 
+	/// Query the denom trace of an ibc voucher from its hash, as in ibc-go
+	/// The hash can be given either hex encoded or as an `ibc/{hash}` denom
+	#[method(name = "ibc_queryDenomTraceByHash")]
+	fn query_denom_trace_by_hash(&self, hash: String) -> Result<QueryDenomTraceResponse>;
+
 	/// Query the denom traces for ibc denoms
 	/// key is the asset id from which to start paginating results
 	/// The next_key value in the pagination field of the returned result is a scale encoded u128
@@ -621,6 +717,17 @@ where
 		})
 	}
 
+	fn query_paths_proof(&self, height: u32, paths: Vec<String>) -> Result<Proof> {
+		let api = self.client.runtime_api();
+		let at = BlockId::Number(height.into());
+		let keys = api
+			.commitment_keys(&at, paths.into_iter().map(String::into_bytes).collect())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Invalid ICS-024 path"))?;
+		self.query_proof(height, keys)
+	}
+
 	fn query_latest_height(&self) -> Result<<<Block as BlockT>::Header as HeaderT>::Number> {
 		if let Ok(Some(height)) = self.client.number(self.client.info().best_hash) {
 			Ok(height)
@@ -683,6 +790,16 @@ where
 		})
 	}
 
+	fn query_client_status(&self, height: u32, client_id: String) -> Result<ClientStatus> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		api.client_status(&at, client_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Error querying client status"))
+	}
+
 	fn query_client_consensus_state(
 		&self,
 		height: Option<u32>,
@@ -808,6 +925,19 @@ where
 		})
 	}
 
+	fn query_connection_end(&self, height: u32, connection_id: String) -> Result<ConnectionEnd> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		let result: ibc_primitives::QueryConnectionResponse = api
+			.connection(&at, connection_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch connection state"))?;
+		ConnectionEnd::decode_vec(&result.connection)
+			.map_err(|_| runtime_error_into_rpc_error("Failed to decode connection end"))
+	}
+
 	fn query_connections(&self) -> Result<QueryConnectionsResponse> {
 		let api = self.client.runtime_api();
 
@@ -973,6 +1103,24 @@ where
 		})
 	}
 
+	fn query_channel_end(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<ChannelEnd> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		let result: ibc_primitives::QueryChannelResponse = api
+			.channel(&at, channel_id.as_bytes().to_vec(), port_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch channel state"))?;
+		ChannelEnd::decode_vec(&result.channel)
+			.map_err(|_| runtime_error_into_rpc_error("Failed to decode channel state"))
+	}
+
 	fn query_channel_client(
 		&self,
 		height: u32,
@@ -1137,11 +1285,71 @@ where
 		})
 	}
 
+	fn query_packet_commitment_count(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<u64> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		api.packet_commitment_count(
+			&at,
+			channel_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+		)
+		.ok()
+		.flatten()
+		.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch commitment count"))
+	}
+
+	fn query_timed_out_packets(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<Vec<(u64, u64)>> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		api.timed_out_packets(&at, channel_id.as_bytes().to_vec(), port_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch timed out packets"))
+	}
+
+	fn query_escrow_balances(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<Vec<Coin>> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		let balances = api
+			.escrow_balances(&at, channel_id.as_bytes().to_vec(), port_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch escrow balances"))?;
+		balances
+			.into_iter()
+			.map(|(denom, amount)| {
+				let denom = String::from_utf8(denom).map_err(|_| {
+					runtime_error_into_rpc_error("Failed to decode escrowed denom")
+				})?;
+				Ok(Coin { denom, amount: amount.to_string() })
+			})
+			.collect()
+	}
+
 	fn query_packet_acknowledgements(
 		&self,
 		height: u32,
 		channel_id: String,
 		port_id: String,
+		seqs: Option<Vec<u64>>,
 	) -> Result<QueryPacketAcknowledgementsResponse> {
 		let api = self.client.runtime_api();
 
@@ -1154,6 +1362,7 @@ where
 				&at,
 				channel_id.as_bytes().to_vec(),
 				port_id.as_bytes().to_vec(),
+				seqs,
 			)
 			.ok()
 			.flatten()
@@ -1265,6 +1474,84 @@ where
 		})
 	}
 
+	fn query_next_seq_ack(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryNextSequenceAckResponse> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		let para_id = api
+			.para_id(&at)
+			.map_err(|_| runtime_error_into_rpc_error("Error getting para id"))?;
+		let result: ibc_primitives::QueryNextSequenceAckResponse = api
+			.next_seq_ack(&at, channel_id.as_bytes().to_vec(), port_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Error fetching next sequence ack"))?;
+		let mut keys = vec![result.trie_key];
+		let child_trie_key = api
+			.child_trie_key(&at)
+			.map_err(|_| runtime_error_into_rpc_error("Failed to get child trie key"))?;
+		let child_info = ChildInfo::new_default(&child_trie_key);
+		let proof = self
+			.client
+			.read_child_proof(&at, &child_info, &mut keys.iter_mut().map(|nodes| &nodes[..]))
+			.map_err(runtime_error_into_rpc_error)?
+			.iter_nodes()
+			.collect::<Vec<_>>()
+			.encode();
+		Ok(QueryNextSequenceAckResponse {
+			next_sequence_ack: result.sequence,
+			proof,
+			proof_height: Some(ibc_proto::ibc::core::client::v1::Height {
+				revision_number: para_id.into(),
+				revision_height: result.height,
+			}),
+		})
+	}
+
+	fn query_next_seq_send(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryNextSequenceSendResponse> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		let para_id = api
+			.para_id(&at)
+			.map_err(|_| runtime_error_into_rpc_error("Error getting para id"))?;
+		let result: ibc_primitives::QueryNextSequenceSendResponse = api
+			.next_seq_send(&at, channel_id.as_bytes().to_vec(), port_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Error fetching next sequence send"))?;
+		let mut keys = vec![result.trie_key];
+		let child_trie_key = api
+			.child_trie_key(&at)
+			.map_err(|_| runtime_error_into_rpc_error("Failed to get child trie key"))?;
+		let child_info = ChildInfo::new_default(&child_trie_key);
+		let proof = self
+			.client
+			.read_child_proof(&at, &child_info, &mut keys.iter_mut().map(|nodes| &nodes[..]))
+			.map_err(runtime_error_into_rpc_error)?
+			.iter_nodes()
+			.collect::<Vec<_>>()
+			.encode();
+		Ok(QueryNextSequenceSendResponse {
+			next_sequence_send: result.sequence,
+			proof,
+			proof_height: Some(ibc_proto::ibc::core::client::v1::Height {
+				revision_number: para_id.into(),
+				revision_height: result.height,
+			}),
+		})
+	}
+
 	fn query_packet_commitment(
 		&self,
 		height: u32,
@@ -1428,6 +1715,32 @@ where
 		Ok(QueryDenomTraceResponse { denom_trace: Some(denom_trace) })
 	}
 
+	fn query_denom_trace_by_hash(&self, hash: String) -> Result<QueryDenomTraceResponse> {
+		let api = self.client.runtime_api();
+		let block_hash = self.client.info().best_hash;
+
+		let at = BlockId::Hash(block_hash);
+
+		let denom_trace =
+			api.denom_trace_by_hash(&at, hash.as_bytes().to_vec()).ok().flatten().ok_or_else(
+				|| runtime_error_into_rpc_error("[ibc_rpc]: Could not find a denom trace for hash"),
+			)?;
+
+		let denom_str = String::from_utf8(denom_trace.denom).map_err(|_| {
+			runtime_error_into_rpc_error(
+				"[ibc_rpc]: Could not decode ibc denom into a valid string",
+			)
+		})?;
+		let denom_trace = ibc::applications::transfer::PrefixedDenom::from_str(&denom_str)
+			.map_err(|_| {
+				runtime_error_into_rpc_error(
+					"[ibc_rpc]: Could not derive a valid ibc denom from string",
+				)
+			})?;
+
+		Ok(QueryDenomTraceResponse { denom_trace: Some(denom_trace.into()) })
+	}
+
 	fn query_denom_traces(
 		&self,
 		key: Option<AssetId>,
@@ -28,6 +28,9 @@ sp_api::decl_runtime_apis! {
 		/// Returns client state at height
 		fn client_state(client_id: Vec<u8>) -> Option<QueryClientStateResponse>;
 
+		/// Returns whether the client is active, expired or frozen
+		fn client_status(client_id: Vec<u8>) -> Option<pallet_ibc::ClientStatus>;
+
 		/// Return the consensus state for the given client at a height
 		fn client_consensus_state(client_id: Vec<u8>, revision_number: u64, revision_height: u64, latest_cs: bool) -> Option<QueryConsensusStateResponse>;
 
@@ -58,7 +61,14 @@ sp_api::decl_runtime_apis! {
 
 		fn packet_commitments(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryPacketCommitmentsResponse>;
 
-		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryPacketAcknowledgementsResponse>;
+		/// Returns the number of outstanding packet commitments on a channel
+		fn packet_commitment_count(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<u64>;
+
+		/// Returns the (sequence, timestamp) of the latest packets timed out on a channel
+		fn timed_out_packets(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<Vec<(u64, u64)>>;
+
+		/// Returns the acknowledgements written on a channel, restricted to `seqs` when given
+		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>, seqs: Option<Vec<u64>>) -> Option<QueryPacketAcknowledgementsResponse>;
 
 		fn unreceived_packets(channel_id: Vec<u8>, port_id: Vec<u8>, seqs: Vec<u64>) -> Option<Vec<u64>>;
 
@@ -66,6 +76,15 @@ sp_api::decl_runtime_apis! {
 
 		fn next_seq_recv(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryNextSequenceReceiveResponse>;
 
+		/// Returns the next sequence to be acknowledged on an ordered channel
+		fn next_seq_ack(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryNextSequenceAckResponse>;
+
+		/// Returns the next sequence to be sent on a channel
+		fn next_seq_send(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryNextSequenceSendResponse>;
+
+		/// Returns the child trie keys of the given ICS-024 paths, `None` if one of them is invalid
+		fn commitment_keys(paths: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>>;
+
 		fn packet_commitment(channel_id: Vec<u8>, port_id: Vec<u8>, seq: u64) -> Option<QueryPacketCommitmentResponse>;
 
 		fn packet_acknowledgement(channel_id: Vec<u8>, port_id: Vec<u8>, seq: u64) -> Option<QueryPacketAcknowledgementResponse>;
@@ -74,9 +93,15 @@ sp_api::decl_runtime_apis! {
 
 		fn denom_trace(asset_id: AssetId) -> Option<QueryDenomTraceResponse>;
 
+		/// Returns the denom trace stored for a hash, given either as the hex encoded hash or as an `ibc/{hash}` denom
+		fn denom_trace_by_hash(hash: Vec<u8>) -> Option<QueryDenomTraceResponse>;
+
 		/// Key is the asset id from which to start looking up results
 		fn denom_traces(key: Option<AssetId>, offset: Option<u32>, limit: u64, count_total: bool) -> QueryDenomTracesResponse;
 
+		/// Returns the (denom, amount) of the native tokens escrowed for transfers sent over a channel
+		fn escrow_balances(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<Vec<(Vec<u8>, u128)>>;
+
 		fn block_events(extrinsic_index: Option<u32>) -> Vec<Result<pallet_ibc::events::IbcEvent, pallet_ibc::errors::IbcError>>;
 	}
 }
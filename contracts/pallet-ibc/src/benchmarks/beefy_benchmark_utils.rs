@@ -0,0 +1,219 @@
+use crate::light_clients::{AnyClientMessage, HostFunctionsManager};
+use alloc::format;
+use beefy_client_primitives::{
+	HostFunctions, MerkleHasher, MmrUpdateProof, PartialMmrLeaf, SignatureWithAuthorityIndex,
+	SignedCommitment,
+};
+use beefy_primitives::{
+	known_payload_ids::MMR_ROOT_ID,
+	mmr::{BeefyNextAuthoritySet, MmrLeaf, MmrLeafVersion},
+	Commitment, Payload, KEY_TYPE,
+};
+use codec::{Compact, Encode};
+use ibc::timestamp::Timestamp;
+use ics11_beefy::{
+	client_message::{BeefyHeader, ClientMessage, ParachainHeader, ParachainHeadersWithProof},
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+};
+use mmr_lib::{util::MemStore, MMR};
+use sp_core::{ecdsa, H256};
+use sp_runtime::{generic::Header, traits::BlakeTwo256, SaturatedConversion};
+use sp_std::prelude::*;
+use sp_trie::{generate_trie_proof, LayoutV0, MemoryDB, TrieDBMut, TrieMut};
+
+pub const BEEFY_UPDATE_TIMESTAMP: u64 = 1650894363;
+
+/// Generates `count` beefy authorities, returning their public keys along with the leaves of the
+/// authority set merkle tree, the keccak hashes of their ethereum addresses.
+fn generate_authorities(count: u32) -> (Vec<ecdsa::Public>, Vec<[u8; 32]>) {
+	(1..=count)
+		.map(|i| {
+			let public = sp_io::crypto::ecdsa_generate(
+				KEY_TYPE,
+				Some(format!("//{}", i).as_bytes().to_vec()),
+			);
+			// The uncompressed public key the address is derived from is only exposed by
+			// signature recovery.
+			let digest = [0u8; 32];
+			let signature =
+				sp_io::crypto::ecdsa_sign_prehashed(KEY_TYPE, &public, &digest).unwrap();
+			let uncompressed =
+				sp_io::crypto::secp256k1_ecdsa_recover(&signature.0, &digest).unwrap();
+			let address = &HostFunctionsManager::keccak_256(&uncompressed)[12..];
+			(public, HostFunctionsManager::keccak_256(address))
+		})
+		.unzip()
+}
+
+/// Builds a beefy client message that proves the requested number of parachain headers. With no
+/// authorities the headers are proven against the mmr root already trusted by the returned client
+/// state, otherwise the message also carries a commitment to a new mmr root signed by all of the
+/// requested number of authorities.
+pub fn generate_parachain_headers(
+	headers: u32,
+	authorities: u32,
+) -> (ClientState<HostFunctionsManager>, ConsensusState, AnyClientMessage) {
+	let para_id = 2000u32;
+	let latest_para_height = 1u32;
+	// The first leaves only exist to give the proven ones a non trivial position in the mmr.
+	let proven = (latest_para_height + 1)..=(latest_para_height + headers);
+	let leaf_count = latest_para_height + headers + 1;
+	let (public_keys, authority_leaves) = generate_authorities(authorities);
+	let authority_tree =
+		rs_merkle::MerkleTree::<MerkleHasher<HostFunctionsManager>>::from_leaves(&authority_leaves);
+	let authority_root = authority_tree.root().map(H256::from).unwrap_or_default();
+	let authority_set = BeefyNextAuthoritySet { id: 1, len: authorities, root: authority_root };
+	// The same authorities are kept for the next session.
+	let next_authority_set = BeefyNextAuthoritySet { id: 2, ..authority_set.clone() };
+
+	let store = MemStore::default();
+	let mut mmr = MMR::<H256, MerkleHasher<HostFunctionsManager>, _>::new(0, &store);
+	let mut positions = vec![];
+	let mut parachain_headers = vec![];
+	let mut latest_mmr_leaf = None;
+	for leaf_index in 0..leaf_count {
+		// (para_id, encoded header) pairs committed to in the parachain heads root.
+		let mut heads = vec![(para_id + 1, vec![0u8]).encode()];
+		let parachain_header = proven.contains(&leaf_index).then(|| {
+			let timestamp = BEEFY_UPDATE_TIMESTAMP.saturating_mul(1000) + leaf_index as u64;
+			let mut timestamp_extrinsic = (1u8, 0u8, Compact(timestamp)).encode();
+			timestamp_extrinsic.insert(0, 0);
+			timestamp_extrinsic.insert(0, 0);
+			let key = Compact(0u32).encode();
+			let mut db = MemoryDB::<BlakeTwo256>::default();
+			let extrinsics_root = {
+				let mut root = Default::default();
+				let mut trie = <TrieDBMut<LayoutV0<BlakeTwo256>>>::new(&mut db, &mut root);
+				trie.insert(&key, &timestamp_extrinsic).unwrap();
+				*trie.root()
+			};
+			let extrinsic_proof = generate_trie_proof::<LayoutV0<BlakeTwo256>, _, _, _>(
+				&db,
+				extrinsics_root,
+				vec![&key],
+			)
+			.unwrap();
+			let header = Header::<u32, BlakeTwo256> {
+				parent_hash: Default::default(),
+				number: leaf_index,
+				state_root: Default::default(),
+				extrinsics_root,
+				digest: Default::default(),
+			};
+			heads.insert(0, (para_id, header.encode()).encode());
+			(header, extrinsic_proof, timestamp_extrinsic)
+		});
+
+		let leaves =
+			heads.iter().map(|head| HostFunctionsManager::keccak_256(head)).collect::<Vec<_>>();
+		let tree =
+			rs_merkle::MerkleTree::<MerkleHasher<HostFunctionsManager>>::from_leaves(&leaves);
+		let partial_mmr_leaf = PartialMmrLeaf {
+			version: MmrLeafVersion::new(0, 0),
+			parent_number_and_hash: (leaf_index, H256::repeat_byte(leaf_index as u8)),
+			beefy_next_authority_set: next_authority_set.clone(),
+		};
+		let mmr_leaf = MmrLeaf {
+			version: partial_mmr_leaf.version,
+			parent_number_and_hash: partial_mmr_leaf.parent_number_and_hash,
+			beefy_next_authority_set: partial_mmr_leaf.beefy_next_authority_set.clone(),
+			leaf_extra: H256::from(tree.root().unwrap()),
+		};
+		let leaf_hash = HostFunctionsManager::keccak_256(&mmr_leaf.encode());
+		let position = mmr.push(H256::from(leaf_hash)).unwrap();
+		latest_mmr_leaf = Some((mmr_leaf, position));
+
+		if let Some((parachain_header, extrinsic_proof, timestamp_extrinsic)) = parachain_header {
+			positions.push(position);
+			parachain_headers.push(ParachainHeader {
+				parachain_header,
+				partial_mmr_leaf,
+				parachain_heads_proof: tree.proof(&[0]).proof_hashes().to_vec(),
+				heads_leaf_index: 0,
+				heads_total_count: heads.len() as u32,
+				extrinsic_proof,
+				timestamp_extrinsic,
+			});
+		}
+	}
+
+	let headers_with_proof = ParachainHeadersWithProof {
+		headers: parachain_headers,
+		mmr_proofs: mmr
+			.gen_proof(positions)
+			.unwrap()
+			.proof_items()
+			.iter()
+			.map(|item| item.encode())
+			.collect(),
+		mmr_size: mmr.mmr_size(),
+	};
+	let mmr_root = mmr.get_root().unwrap();
+
+	let mmr_update_proof = (authorities > 0).then(|| {
+		let commitment = Commitment {
+			payload: Payload::new(MMR_ROOT_ID, mmr_root.encode()),
+			block_number: leaf_count,
+			validator_set_id: authority_set.id,
+		};
+		let commitment_hash = HostFunctionsManager::keccak_256(&commitment.encode());
+		let signatures = public_keys
+			.iter()
+			.enumerate()
+			.map(|(index, public)| {
+				let signature =
+					sp_io::crypto::ecdsa_sign_prehashed(KEY_TYPE, public, &commitment_hash)
+						.unwrap();
+				SignatureWithAuthorityIndex { signature: signature.0, index: index as u32 }
+			})
+			.collect();
+		let indices = (0..authorities as usize).collect::<Vec<_>>();
+		let (latest_mmr_leaf, position) = latest_mmr_leaf.unwrap();
+		MmrUpdateProof {
+			signed_commitment: SignedCommitment { commitment, signatures },
+			latest_mmr_leaf,
+			mmr_proof: pallet_mmr_primitives::Proof {
+				leaf_index: (leaf_count - 1) as u64,
+				leaf_count: leaf_count as u64,
+				items: mmr.gen_proof(vec![position]).unwrap().proof_items().to_vec(),
+			},
+			authority_proof: authority_tree.proof(&indices).proof_hashes().to_vec(),
+		}
+	});
+	// A signed commitment has to be newer than the latest one the client trusts.
+	let (trusted_mmr_root, latest_beefy_height) = if mmr_update_proof.is_some() {
+		(H256::zero(), leaf_count - 1)
+	} else {
+		(mmr_root, leaf_count)
+	};
+
+	let beefy_header =
+		BeefyHeader { headers_with_proof: Some(headers_with_proof), mmr_update_proof };
+	let client_message = AnyClientMessage::Beefy(ClientMessage::Header(beefy_header));
+
+	let client_state = ClientState {
+		chain_id: Default::default(),
+		relay_chain: Default::default(),
+		mmr_root_hash: trusted_mmr_root,
+		latest_beefy_height,
+		frozen_height: None,
+		beefy_activation_block: 0,
+		latest_para_height,
+		para_id,
+		authority: authority_set,
+		next_authority_set,
+		_phantom: Default::default(),
+	};
+
+	let time = core::time::Duration::from_millis(BEEFY_UPDATE_TIMESTAMP.saturating_mul(1000));
+	let consensus_state = ConsensusState {
+		timestamp: Timestamp::from_nanoseconds(time.as_nanos().saturated_into::<u64>())
+			.unwrap()
+			.into_tm_time()
+			.unwrap(),
+		root: H256::zero().as_bytes().to_vec().into(),
+	};
+
+	(client_state, consensus_state, client_message)
+}
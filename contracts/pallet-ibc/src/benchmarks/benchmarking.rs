@@ -4,6 +4,7 @@
 use super::super::*;
 use crate::{
 	benchmarks::{
+		beefy_benchmark_utils::{generate_parachain_headers, BEEFY_UPDATE_TIMESTAMP},
 		grandpa_benchmark_utils::{generate_finality_proof, GRANDPA_UPDATE_TIMESTAMP},
 		tendermint_benchmark_utils::*,
 	},
@@ -15,7 +16,13 @@ use crate::{
 
 use core::str::FromStr;
 use frame_benchmarking::{benchmarks, whitelisted_caller};
-use frame_support::traits::fungibles::{Inspect, Mutate};
+use frame_support::{
+	pallet_prelude::Weight,
+	traits::{
+		fungibles::{Inspect, Mutate},
+		Get,
+	},
+};
 use frame_system::RawOrigin;
 use ibc_primitives::IbcHandler;
 use sp_runtime::traits::IdentifyAccount;
@@ -32,12 +39,14 @@ use ibc::{
 			height::Height,
 			msgs::{
 				create_client::{MsgCreateAnyClient, TYPE_URL},
+				recover_client::{MsgRecoverClient, TYPE_URL as RECOVER_CLIENT_TYPE_URL},
 				update_client::{MsgUpdateAnyClient, TYPE_URL as UPDATE_CLIENT_TYPE_URL},
 			},
 		},
 		ics03_connection::{
 			connection::{ConnectionEnd, Counterparty, State},
 			context::{ConnectionKeeper, ConnectionReader},
+			handler::HandshakeOrigin,
 			msgs::{
 				conn_open_ack::TYPE_URL as CONN_OPEN_ACK_TYPE_URL,
 				conn_open_confirm::TYPE_URL as CONN_OPEN_CONFIRM_TYPE_URL,
@@ -73,6 +82,7 @@ use ibc::{
 	timestamp::Timestamp,
 };
 use ibc_primitives::get_channel_escrow_address;
+use ics07_tendermint::client_state::ClientState as TendermintClientState;
 use scale_info::prelude::string::ToString;
 use sp_core::crypto::AccountId32;
 use sp_std::vec;
@@ -287,7 +297,7 @@ benchmarks! {
 		let value = MsgChannelOpenInit {
 			port_id: port_id.clone(),
 			channel: channel_end,
-			signer: Signer::from_str(MODULE_ID).unwrap()
+			signer: caller_signer()
 		}.encode_vec();
 
 		let caller: T::AccountId = whitelisted_caller();
@@ -390,7 +400,7 @@ benchmarks! {
 		let value = MsgChannelOpenInit {
 			port_id,
 			channel: channel_end,
-			signer: Signer::from_str(MODULE_ID).unwrap()
+			signer: caller_signer()
 		}.encode_vec();
 
 		let msg = ibc_proto::google::protobuf::Any  { type_url: CHAN_OPEN_TYPE_URL.to_string(), value };
@@ -643,6 +653,67 @@ benchmarks! {
 		}
 	}
 
+	// recv_packet batch
+	recv_packet_batch_tendermint {
+		let i in 1..100u32;
+		let data = vec![0u8;32];
+		let mut ctx = routing::Context::<T>::new();
+		let now: <T as pallet_timestamp::Config>::Moment = TIMESTAMP.saturating_mul(1000);
+		pallet_timestamp::Pallet::<T>::set_timestamp(now);
+		frame_system::Pallet::<T>::set_block_number(2u32.into());
+		let (mock_client_state, mock_cs_state) = create_mock_state();
+		let mock_client_state = AnyClientState::Tendermint(mock_client_state);
+		let mock_cs_state = AnyConsensusState::Tendermint(mock_cs_state);
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		let counterparty_client_id = ClientId::new("11-beefy", 1).unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		ctx.store_consensus_state(client_id.clone(), Height::new(0, 1), mock_cs_state).unwrap();
+		let time = core::time::Duration::from_millis(TIMESTAMP.saturating_mul(1000));
+		let time = Timestamp::from_nanoseconds(time.as_nanos() as u64).unwrap();
+		ctx.store_update_time(client_id.clone(), Height::new(0, 1), time).unwrap();
+		let connection_id = ConnectionId::new(0);
+		let commitment_prefix: CommitmentPrefix = <T as Config>::PALLET_PREFIX.to_vec().try_into().unwrap();
+		let delay_period = core::time::Duration::from_nanos(0);
+		let connection_counterparty = Counterparty::new(counterparty_client_id, Some(ConnectionId::new(1)), commitment_prefix);
+		let connection_end = ConnectionEnd::new(State::Open, client_id.clone(), connection_counterparty, vec![ConnVersion::default()], delay_period);
+
+		ctx.store_connection(connection_id.clone(), &connection_end).unwrap();
+		ctx.store_connection_to_client(connection_id, &client_id).unwrap();
+		let value = create_client_update::<T>().encode_vec();
+
+		let msg = ibc_proto::google::protobuf::Any  { type_url: UPDATE_CLIENT_TYPE_URL.to_string(), value };
+
+		ibc::core::ics26_routing::handler::deliver(&mut ctx, msg).unwrap();
+		let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+		let counterparty_channel = ibc::core::ics04_channel::channel::Counterparty::new(port_id.clone(), Some(ChannelId::new(0)));
+		let channel_end = ChannelEnd::new(
+			ibc::core::ics04_channel::channel::State::Open,
+			ibc::core::ics04_channel::channel::Order::Unordered,
+			counterparty_channel,
+			vec![ConnectionId::new(0)],
+			ibc::core::ics04_channel::Version::default()
+		);
+
+		ctx.store_channel((port_id.clone(), ChannelId::new(0)), &channel_end).unwrap();
+		ctx.store_connection_channels(ConnectionId::new(0), &(port_id.clone(), ChannelId::new(0))).unwrap();
+		ctx.store_next_sequence_recv((port_id.clone(), ChannelId::new(0)), 1u64.into()).unwrap();
+
+		let (cs_state, values) = create_recv_packets::<T>(data, i.into());
+		ctx.store_consensus_state(client_id, Height::new(0, 2), AnyConsensusState::Tendermint(cs_state)).unwrap();
+		let msgs = values.into_iter().map(|value| Any {
+			type_url: RECV_PACKET_TYPE_URL.as_bytes().to_vec(),
+			value: value.encode_vec()
+		}).collect::<Vec<_>>();
+		let caller: T::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), msgs)
+	verify {
+		for seq in 1..=u64::from(i) {
+			let receipt = ctx.get_packet_receipt(&(PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap(), ChannelId::new(0), seq.into())).unwrap();
+			assert!(matches!(receipt, Receipt::Ok));
+		}
+	}
+
 	// ack_packet
 	ack_packet_tendermint {
 		let i in 1..1000u32;
@@ -796,7 +867,7 @@ benchmarks! {
 			),
 			version: Some(ConnVersion::default()),
 			delay_period: core::time::Duration::from_secs(1000),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		};
 
 		let msg = Any {
@@ -817,7 +888,7 @@ benchmarks! {
 		let msg = MsgCreateAnyClient::<Context<T>>::new(
 			AnyClientState::Tendermint(mock_client_state),
 			AnyConsensusState::Tendermint(mock_cs_state),
-			Signer::from_str(MODULE_ID).unwrap(),
+			caller_signer(),
 		)
 		.unwrap()
 		.encode_vec();
@@ -862,6 +933,7 @@ benchmarks! {
 			to:  MultiAddress::Raw("bob".to_string().as_bytes().to_vec()),
 			source_channel: channel_id.sequence(),
 			timeout,
+			memo: None,
 		};
 
 		<Params<T>>::put(PalletParams {
@@ -1028,6 +1100,7 @@ benchmarks! {
 			token: coin,
 			sender: Signer::from_str("alice").unwrap(),
 			receiver: Signer::from_str(&hex_string).unwrap(),
+			memo: String::new(),
 		};
 
 		let data = serde_json::to_vec(&packet_data).unwrap();
@@ -1107,6 +1180,7 @@ benchmarks! {
 			token: coin,
 			sender: Signer::from_str(&hex_string).unwrap(),
 			receiver: Signer::from_str("alice").unwrap(),
+			memo: String::new(),
 		};
 
 		let data = serde_json::to_vec(&packet_data).unwrap();
@@ -1186,6 +1260,7 @@ benchmarks! {
 			token: coin,
 			sender: Signer::from_str(&hex_string).unwrap(),
 			receiver: Signer::from_str("alice").unwrap(),
+			memo: String::new(),
 		};
 
 		let data = serde_json::to_vec(&packet_data).unwrap();
@@ -1248,4 +1323,139 @@ benchmarks! {
 		let client_state = AnyClientState::decode_vec(&*client_state).unwrap();
 		assert_eq!(client_state.latest_height(), Height::new(2000, 2));
 	}
+
+	// update_beefy_client
+	update_beefy_client {
+		let i in 1..100u32;
+		let j in 0..100u32;
+		let mut ctx = routing::Context::<T>::new();
+		let now: <T as pallet_timestamp::Config>::Moment = BEEFY_UPDATE_TIMESTAMP.saturating_mul(1000);
+		pallet_timestamp::Pallet::<T>::set_timestamp(now);
+		let (mock_client_state, mock_cs_state, client_message) = generate_parachain_headers(i, j);
+		let mock_client_state = AnyClientState::Beefy(mock_client_state);
+		let mock_cs_state = AnyConsensusState::Beefy(mock_cs_state);
+		let client_id = ClientId::new(&mock_client_state.client_type(), 0).unwrap();
+		ctx.store_client_type(client_id.clone(), mock_client_state.client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), mock_client_state).unwrap();
+		ctx.store_consensus_state(client_id.clone(), Height::new(2000, 1), mock_cs_state).unwrap();
+		let time = core::time::Duration::from_millis(BEEFY_UPDATE_TIMESTAMP.saturating_mul(1000));
+		let time = Timestamp::from_nanoseconds(time.as_nanos() as u64).unwrap();
+		ctx.store_update_time(client_id.clone(), Height::new(2000, 1), time).unwrap();
+		let msg = MsgUpdateAnyClient::<routing::Context<T>> {
+			client_id: client_id.clone(),
+			client_message,
+			signer: Signer::from_str("relayer").unwrap()
+		};
+
+		let msg = Any { type_url: UPDATE_CLIENT_TYPE_URL.to_string().as_bytes().to_vec(), value: msg.encode_vec() };
+		let caller: T::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		let client_state = ClientStates::<T>::get(&client_id).unwrap();
+		let client_state = AnyClientState::decode_vec(&*client_state).unwrap();
+		assert_eq!(client_state.latest_height(), Height::new(2000, i as u64 + 1));
+	}
+
+	// reject_unknown_message
+	reject_unknown_message {
+		let msg = Any { type_url: b"/ibc.unknown.v1.MsgUnknown".to_vec(), value: vec![1u8; 1000] };
+		let caller: T::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		assert_eq!(ClientCounter::<T>::get(), 0);
+	}
+
+	// prune_consensus_state
+	prune_consensus_state {
+		let mut ctx = routing::Context::<T>::new();
+		let (_, mock_cs_state) = create_mock_state();
+		let client_id = ClientId::new("07-tendermint", 0).unwrap();
+		let retention = T::ConsensusStateRetention::get().max(1);
+		for height in 1..=retention as u64 + 1 {
+			let cs_state = AnyConsensusState::Tendermint(mock_cs_state.clone());
+			ctx.store_consensus_state(client_id.clone(), Height::new(0, height), cs_state).unwrap();
+		}
+	}: { Pallet::<T>::housekeeping(Weight::MAX) }
+	verify {
+		let heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
+		assert_eq!(heights.len() as u32, retention);
+	}
+
+	// cancel_stale_connection
+	cancel_stale_connection {
+		let mut ctx = routing::Context::<T>::new();
+		let commitment_prefix: CommitmentPrefix = <T as Config>::PALLET_PREFIX.to_vec().try_into().unwrap();
+		let connection_end = ConnectionEnd::new(
+			State::Init,
+			ClientId::new("07-tendermint", 0).unwrap(),
+			Counterparty::new(ClientId::new("11-beefy", 1).unwrap(), None, commitment_prefix),
+			vec![ConnVersion::default()],
+			core::time::Duration::from_secs(1000),
+		);
+		ctx.store_connection(ConnectionId::new(0), &connection_end).unwrap();
+		let origin = HandshakeOrigin {
+			signer: Signer::from_str("alice").unwrap(),
+			height: Height::new(0, 0),
+		};
+		ctx.store_connection_handshake_origin(ConnectionId::new(0), origin).unwrap();
+		let timeout = T::ConnectionHandshakeTimeout::get() as u32;
+		frame_system::Pallet::<T>::set_block_number(timeout.saturating_add(1).into());
+	}: { Pallet::<T>::housekeeping(Weight::MAX) }
+	verify {
+		assert!(ConnectionReader::connection_end(&ctx, &ConnectionId::new(0)).is_err());
+	}
+
+	// recover_client
+	recover_client {
+		let mut ctx = routing::Context::<T>::new();
+		let (mock_client_state, mock_cs_state) = create_mock_state();
+		let subject_client_id = ClientId::new("07-tendermint", 0).unwrap();
+		let substitute_client_id = ClientId::new("07-tendermint", 1).unwrap();
+		let subject_client_state = mock_client_state.clone().with_frozen_height(Height::new(0, 1)).unwrap();
+		let substitute_client_state = TendermintClientState { latest_height: Height::new(0, 2), ..mock_client_state };
+		for (client_id, client_state, height) in [
+			(subject_client_id.clone(), subject_client_state, Height::new(0, 1)),
+			(substitute_client_id.clone(), substitute_client_state, Height::new(0, 2)),
+		] {
+			let client_state = AnyClientState::Tendermint(client_state);
+			ctx.store_client_type(client_id.clone(), client_state.client_type()).unwrap();
+			ctx.store_client_state(client_id.clone(), client_state).unwrap();
+			ctx.store_consensus_state(client_id, height, AnyConsensusState::Tendermint(mock_cs_state.clone())).unwrap();
+		}
+
+		// Any signer the runtime can convert to an account will do as the recovery authority.
+		let signer = get_channel_escrow_address(&PortId::transfer(), ChannelId::new(0)).unwrap();
+		let authority = <T as Config>::AccountIdConversion::try_from(signer.clone()).map_err(|_| ()).unwrap();
+		ClientRecoveryAuthority::<T>::put(authority.into_account());
+		let msg = MsgRecoverClient::new(subject_client_id.clone(), substitute_client_id, signer);
+		let msg = Any { type_url: RECOVER_CLIENT_TYPE_URL.as_bytes().to_vec(), value: msg.encode_vec() };
+		let caller: T::AccountId = whitelisted_caller();
+	}: deliver(RawOrigin::Signed(caller), vec![msg])
+	verify {
+		let client_state = ClientStates::<T>::get(&subject_client_id).unwrap();
+		let client_state = AnyClientState::decode_vec(&*client_state).unwrap();
+		assert_eq!(client_state.latest_height(), Height::new(0, 2));
+	}
+
+	// write_async_acknowledgement
+	write_async_acknowledgement {
+		let mut ctx = routing::Context::<T>::new();
+		let port_id = PortId::transfer();
+		let channel_id = ChannelId::new(0);
+		let channel_end = ChannelEnd::new(
+			ChannelState::Open,
+			Order::Unordered,
+			channel::Counterparty::new(PortId::transfer(), Some(ChannelId::new(1))),
+			vec![ConnectionId::new(0)],
+			Version::new(VERSION.to_string()),
+		);
+		ctx.store_channel((port_id.clone(), channel_id), &channel_end).unwrap();
+		let key = (port_id.clone(), channel_id, 1u64.into());
+		ctx.store_packet_receipt(key.clone(), Receipt::Ok).unwrap();
+		let ack = vec![1u8; 1000];
+	}: _(RawOrigin::Root, port_id.as_bytes().to_vec(), channel_id.to_string().as_bytes().to_vec(), 1, ack.clone())
+	verify {
+		let ack_commitment = ibc::core::ics04_channel::commitment::compute_ack_commitment(&ack.into());
+		assert_eq!(ctx.get_packet_acknowledgement(&key).unwrap(), ack_commitment);
+	}
 }
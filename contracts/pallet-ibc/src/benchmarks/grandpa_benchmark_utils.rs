@@ -151,6 +151,7 @@ pub fn generate_finality_proof(
 		para_id,
 		current_set_id: set_id,
 		current_authorities: authorities.into_iter().map(|authority| (authority, 100)).collect(),
+		pending_authority_change: None,
 		_phantom: Default::default(),
 	};
 
@@ -161,6 +162,7 @@ pub fn generate_finality_proof(
 			.into_tm_time()
 			.unwrap(),
 		root: H256::zero().as_bytes().to_vec().into(),
+		block_hash: Default::default(),
 	};
 
 	(client_state, consensus_state, client_message)
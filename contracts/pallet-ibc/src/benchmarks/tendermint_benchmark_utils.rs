@@ -1,9 +1,10 @@
 use crate::{
 	light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager},
 	routing::Context,
-	Config, MODULE_ID,
+	Config,
 };
 use core::{str::FromStr, time::Duration};
+use frame_benchmarking::whitelisted_caller;
 use frame_support::traits::Get;
 use ibc::{
 	core::{
@@ -21,6 +22,7 @@ use ibc::{
 				ChannelEnd, Counterparty as ChannelCounterParty, Order as ChannelOrder,
 				State as ChannelState,
 			},
+			commitment::{compute_ack_commitment, compute_packet_commitment},
 			context::{ChannelKeeper, ChannelReader},
 			msgs::{
 				acknowledgement::MsgAcknowledgement, chan_close_confirm::MsgChannelCloseConfirm,
@@ -64,6 +66,12 @@ struct ConsensusProofwithHostConsensusStateProof {
 	host_proof: Vec<u8>,
 	connection_proof: Vec<u8>,
 }
+/// The signer of the benchmarked messages, `Pallet::deliver` requires it to be the caller.
+pub(crate) fn caller_signer() -> Signer {
+	let caller: AccountId32 = whitelisted_caller();
+	Signer::from_str(&format!("0x{}", hex::encode(caller))).unwrap()
+}
+
 /// Create a mock avl implementation that can be used to mock tendermint's iavl tree.
 fn create_avl() -> simple_iavl::avl::AvlTree<Vec<u8>, Vec<u8>> {
 	let mut avl_tree = simple_iavl::avl::AvlTree::new();
@@ -156,7 +164,7 @@ where
 		client_message: AnyClientMessage::Tendermint(
 			ics07_tendermint::client_message::ClientMessage::Header(create_tendermint_header()),
 		),
-		signer: Signer::from_str(MODULE_ID).unwrap(),
+		signer: caller_signer(),
 	}
 }
 // Proof generation process for all tendermint benchmarks
@@ -275,7 +283,7 @@ where
 			)
 			.unwrap(),
 			delay_period,
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -381,7 +389,7 @@ where
 			)
 			.unwrap(),
 			version: ConnVersion::default(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -467,7 +475,7 @@ pub(crate) fn create_conn_open_confirm<T: Config>() -> (ConsensusState, MsgConne
 				Height::new(0, 2),
 			)
 			.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -529,7 +537,7 @@ pub(crate) fn create_chan_open_try() -> (ConsensusState, MsgChannelOpenTry) {
 			counterparty_version: ChannelVersion::new(pallet_ibc_ping::VERSION.to_string()),
 			proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
 				.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -585,7 +593,7 @@ pub(crate) fn create_chan_open_ack() -> (ConsensusState, MsgChannelOpenAck) {
 			counterparty_version: ChannelVersion::new(pallet_ibc_ping::VERSION.to_string()),
 			proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
 				.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -639,7 +647,7 @@ pub(crate) fn create_chan_open_confirm() -> (ConsensusState, MsgChannelOpenConfi
 			channel_id: ChannelId::new(0),
 			proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
 				.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -649,7 +657,7 @@ pub(crate) fn create_chan_close_init() -> MsgChannelCloseInit {
 	MsgChannelCloseInit {
 		port_id,
 		channel_id: ChannelId::new(0),
-		signer: Signer::from_str(MODULE_ID).unwrap(),
+		signer: caller_signer(),
 	}
 }
 
@@ -702,7 +710,7 @@ pub(crate) fn create_chan_close_confirm() -> (ConsensusState, MsgChannelCloseCon
 			channel_id: Default::default(),
 			proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
 				.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -728,7 +736,7 @@ where
 	};
 	let ctx = Context::<T>::new();
 	let commitment =
-		ctx.packet_commitment(packet.data.clone(), packet.timeout_height, packet.timeout_timestamp);
+		compute_packet_commitment(&packet.data, &packet.timeout_height, &packet.timeout_timestamp);
 	let mut avl_tree = create_avl();
 	let path = format!(
 		"{}",
@@ -770,11 +778,104 @@ where
 			packet,
 			proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
 				.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
 
+/// Creates `count` packets with consecutive sequences starting at 1, all proven against the
+/// returned consensus state at the same height.
+pub(crate) fn create_recv_packets<T: Config + Send + Sync>(
+	data: Vec<u8>,
+	count: u64,
+) -> (ConsensusState, Vec<MsgRecvPacket>)
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<T::AccountId>,
+{
+	let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+	let packets = (1..=count)
+		.map(|sequence| Packet {
+			sequence: sequence.into(),
+			source_port: port_id.clone(),
+			source_channel: ChannelId::new(0),
+			destination_port: port_id.clone(),
+			destination_channel: ChannelId::new(0),
+			data: data.clone(),
+			timeout_height: Height::new(2087, 5),
+			timeout_timestamp: Timestamp::from_nanoseconds(
+				1690894363u64.saturating_mul(1000000000),
+			)
+			.unwrap(),
+		})
+		.collect::<Vec<_>>();
+	let ctx = Context::<T>::new();
+	let mut avl_tree = create_avl();
+	let paths = packets
+		.iter()
+		.map(|packet| {
+			let commitment = compute_packet_commitment(
+				&packet.data,
+				&packet.timeout_height,
+				&packet.timeout_timestamp,
+			);
+			let path = format!(
+				"{}",
+				CommitmentsPath {
+					port_id: port_id.clone(),
+					channel_id: ChannelId::new(0),
+					sequence: packet.sequence
+				}
+			)
+			.as_bytes()
+			.to_vec();
+			avl_tree.insert(path.clone(), commitment.into_vec());
+			path
+		})
+		.collect::<Vec<_>>();
+	let root = match *avl_tree.root_hash().unwrap() {
+		Hash::Sha256(root) => root.to_vec(),
+		Hash::None => panic!("Failed to generate root hash"),
+	};
+	let proofs = paths.iter().map(|path| avl_tree.get_proof(&**path).unwrap()).collect::<Vec<_>>();
+	avl_tree.insert("ibc/".as_bytes().to_vec(), root);
+	let root = match *avl_tree.root_hash().unwrap() {
+		Hash::Sha256(root) => root.to_vec(),
+		Hash::None => panic!("Failed to generate root hash"),
+	};
+	let proof_0 = avl_tree.get_proof("ibc/".as_bytes()).unwrap();
+	let mut buf = Vec::new();
+	prost::Message::encode(&proof_0, &mut buf).unwrap();
+	let proof_0: CommitmentProof = prost::Message::decode(buf.as_ref()).unwrap();
+	let header = create_tendermint_header();
+	let cs_state = ConsensusState {
+		timestamp: header.signed_header.header.time,
+		root: root.into(),
+		next_validators_hash: header.signed_header.header.next_validators_hash,
+	};
+
+	let msgs = packets
+		.into_iter()
+		.zip(proofs)
+		.map(|(packet, proof)| {
+			let mut buf = Vec::new();
+			prost::Message::encode(&proof, &mut buf).unwrap();
+			let proof: CommitmentProof = prost::Message::decode(buf.as_ref()).unwrap();
+			let merkle_proof = MerkleProof { proofs: vec![proof, proof_0.clone()] };
+			buf.clear();
+			prost::Message::encode(&merkle_proof, &mut buf).unwrap();
+			MsgRecvPacket {
+				packet,
+				proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
+					.unwrap(),
+				signer: caller_signer(),
+			}
+		})
+		.collect();
+
+	(cs_state, msgs)
+}
+
 pub(crate) fn create_ack_packet<T: Config + Send + Sync>(
 	data: Vec<u8>,
 	ack: Vec<u8>,
@@ -796,10 +897,11 @@ where
 			.unwrap(),
 	};
 	let mut ctx = Context::<T>::new();
-	let commitment = ctx.packet_commitment(data, packet.timeout_height, packet.timeout_timestamp);
+	let commitment =
+		compute_packet_commitment(&data, &packet.timeout_height, &packet.timeout_timestamp);
 	ctx.store_packet_commitment((port_id.clone(), ChannelId::new(0), 1.into()), commitment)
 		.unwrap();
-	let ack_commitment = ctx.ack_commitment(ack.clone().into());
+	let ack_commitment = compute_ack_commitment(&ack.clone().into());
 
 	let mut avl_tree = create_avl();
 	let path =
@@ -841,7 +943,7 @@ where
 			acknowledgement: ack.into(),
 			proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
 				.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
@@ -866,7 +968,8 @@ where
 			.unwrap(),
 	};
 	let mut ctx = Context::<T>::new();
-	let commitment = ctx.packet_commitment(data, packet.timeout_height, packet.timeout_timestamp);
+	let commitment =
+		compute_packet_commitment(&data, &packet.timeout_height, &packet.timeout_timestamp);
 	ctx.store_packet_commitment((port_id.clone(), ChannelId::new(0), 1.into()), commitment)
 		.unwrap();
 
@@ -909,7 +1012,7 @@ where
 			next_sequence_recv: Default::default(),
 			proofs: Proofs::new(buf.try_into().unwrap(), None, None, None, Height::new(0, 2))
 				.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: caller_signer(),
 		},
 	)
 }
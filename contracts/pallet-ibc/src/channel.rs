@@ -4,6 +4,7 @@ use frame_support::traits::Get;
 use ibc_primitives::PacketInfo;
 use scale_info::prelude::string::ToString;
 use sp_core::crypto::AccountId32;
+use sp_runtime::traits::IdentifyAccount;
 
 use crate::{
 	ics23::{
@@ -22,9 +23,11 @@ use ibc::{
 			context::{ChannelKeeper, ChannelReader},
 			error::Error as ICS04Error,
 			packet::{Receipt, Sequence},
+			upgrade::{UpgradeFields, UpgradeOrigin},
 		},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
+	signer::Signer,
 	timestamp::Timestamp,
 	Height,
 };
@@ -147,6 +150,16 @@ where
 		}
 	}
 
+	fn packet_commitment_count(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<u64, ICS04Error> {
+		Ok(PacketCommitmentCounter::<T>::get(
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		))
+	}
+
 	fn get_packet_receipt(
 		&self,
 		key: &(PortId, ChannelId, Sequence),
@@ -194,6 +207,113 @@ where
 		}
 	}
 
+	fn packet_acknowledgements(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+		start_sequence: Sequence,
+		limit: u64,
+	) -> Result<Vec<(Sequence, AcknowledgementCommitment)>, ICS04Error> {
+		// Trie keys are ordered as strings, so sequences have to be sorted numerically here.
+		let (port_id, channel_id) = port_channel_id.clone();
+		let mut acks = <Acknowledgements<T>>::iter_channel(port_id, channel_id)
+			.filter(|(seq, _)| *seq >= start_sequence)
+			.collect::<Vec<_>>();
+		acks.sort_by_key(|(seq, _)| *seq);
+		Ok(acks.into_iter().take(limit as usize).map(|(seq, ack)| (seq, ack.into())).collect())
+	}
+
+	fn get_pruning_sequence_start(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, ICS04Error> {
+		let seq = PruningSequenceStart::<T>::get(
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		)
+		.unwrap_or(1);
+		Ok(Sequence::from(seq))
+	}
+
+	fn timed_out_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<(Sequence, Timestamp)>, ICS04Error> {
+		TimedOutPackets::<T>::get(
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		)
+		.into_iter()
+		.map(|(seq, timestamp)| {
+			let timestamp = Timestamp::from_nanoseconds(timestamp).map_err(|e| {
+				ICS04Error::implementation_specific(format!(
+					"[timed_out_packets]: error decoding timestamp from nano seconds: {}",
+					e
+				))
+			})?;
+			Ok((Sequence::from(seq), timestamp))
+		})
+		.collect()
+	}
+
+	/// Channels on ports without a configured close authority may be closed by any signer.
+	/// The signer is resolved to an account, `Pallet::deliver` has already checked that it is
+	/// the account submitting the message.
+	fn is_close_authorized(
+		&self,
+		port_id: &PortId,
+		_channel_id: &ChannelId,
+		signer: &Signer,
+	) -> bool {
+		match ChannelCloseAuthority::<T>::get(port_id.as_bytes().to_vec()) {
+			Some(authority) => T::AccountIdConversion::try_from(signer.clone())
+				.map(|account| account.into_account() == authority)
+				.unwrap_or(false),
+			None => true,
+		}
+	}
+
+	/// Channels can only be upgraded on ports with a configured upgrade authority.
+	fn is_upgrade_authorized(
+		&self,
+		port_id: &PortId,
+		_channel_id: &ChannelId,
+		signer: &Signer,
+	) -> bool {
+		match ChannelUpgradeAuthority::<T>::get(port_id.as_bytes().to_vec()) {
+			Some(authority) => T::AccountIdConversion::try_from(signer.clone())
+				.map(|account| account.into_account() == authority)
+				.unwrap_or(false),
+			None => false,
+		}
+	}
+
+	fn channel_upgrade_origin(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<UpgradeOrigin, ICS04Error> {
+		let (previous, height) = ChannelUpgradeOrigin::<T>::get(
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		)
+		.ok_or_else(|| {
+			ICS04Error::missing_upgrade_origin(port_channel_id.0.clone(), port_channel_id.1)
+		})?;
+		let previous = UpgradeFields::decode_vec(&previous).map_err(|e| {
+			ICS04Error::implementation_specific(format!(
+				"[channel_upgrade_origin]: error decoding upgrade fields: {}",
+				e
+			))
+		})?;
+		let height = Height::decode_vec(&height).map_err(|e| {
+			ICS04Error::implementation_specific(format!(
+				"[channel_upgrade_origin]: error decoding height: {}",
+				e
+			))
+		})?;
+		log::trace!(target: "pallet_ibc", "in channel : [channel_upgrade_origin] >> previous = {:?}, height = {:?}", previous, height);
+		Ok(UpgradeOrigin { previous, height })
+	}
+
 	/// A hashing function for packet commitments
 	fn hash(&self, value: Vec<u8>) -> Vec<u8> {
 		sp_io::hashing::sha2_256(&value).to_vec()
@@ -274,6 +394,14 @@ where
 		commitment: PacketCommitmentType,
 	) -> Result<(), ICS04Error> {
 		log::trace!(target: "pallet_ibc", "in channel : [store_packet_commitment] >> packet_commitment = {:#?}", commitment);
+		// Overwriting a commitment must not count it twice.
+		if !<PacketCommitment<T>>::contains_key((key.0.clone(), key.1, key.2)) {
+			PacketCommitmentCounter::<T>::mutate(
+				key.0.as_bytes().to_vec(),
+				key.1.to_string().as_bytes().to_vec(),
+				|count| *count = count.saturating_add(1),
+			);
+		}
 		<PacketCommitment<T>>::insert((key.0.clone(), key.1, key.2), commitment);
 		if let Some(val) = PacketCounter::<T>::get().checked_add(1) {
 			PacketCounter::<T>::put(val);
@@ -326,6 +454,13 @@ where
 		&mut self,
 		key: (PortId, ChannelId, Sequence),
 	) -> Result<(), ICS04Error> {
+		if <PacketCommitment<T>>::contains_key((key.0.clone(), key.1, key.2)) {
+			PacketCommitmentCounter::<T>::mutate(
+				key.0.as_bytes().to_vec(),
+				key.1.to_string().as_bytes().to_vec(),
+				|count| *count = count.saturating_sub(1),
+			);
+		}
 		// delete packet commitment
 		<PacketCommitment<T>>::remove((key.0.clone(), key.1, key.2));
 
@@ -354,6 +489,23 @@ where
 		Ok(())
 	}
 
+	fn delete_packet_receipt(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+	) -> Result<(), ICS04Error> {
+		if !<PacketReceipt<T>>::contains_key((key.0.clone(), key.1, key.2)) {
+			return Ok(())
+		}
+
+		<PacketReceipt<T>>::remove((key.0.clone(), key.1, key.2));
+
+		if let Some(val) = <PacketReceiptCounter<T>>::get().checked_sub(1) {
+			<PacketReceiptCounter<T>>::put(val)
+		}
+
+		Ok(())
+	}
+
 	fn store_packet_acknowledgement(
 		&mut self,
 		key: (PortId, ChannelId, Sequence),
@@ -422,6 +574,31 @@ where
 		Ok(())
 	}
 
+	fn store_channel_upgrade_origin(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		origin: UpgradeOrigin,
+	) -> Result<(), ICS04Error> {
+		log::trace!(target: "pallet_ibc", "in channel : [store_channel_upgrade_origin] >> port_channel_id = {:?}, origin = {:?}", port_channel_id, origin);
+		ChannelUpgradeOrigin::<T>::insert(
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+			(origin.previous.encode_vec(), origin.height.encode_vec()),
+		);
+		Ok(())
+	}
+
+	fn delete_channel_upgrade_origin(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), ICS04Error> {
+		ChannelUpgradeOrigin::<T>::remove(
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+		);
+		Ok(())
+	}
+
 	fn store_next_sequence_send(
 		&mut self,
 		port_channel_id: (PortId, ChannelId),
@@ -458,6 +635,40 @@ where
 		Ok(())
 	}
 
+	fn store_pruning_sequence_start(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		seq: Sequence,
+	) -> Result<(), ICS04Error> {
+		PruningSequenceStart::<T>::insert(
+			port_channel_id.0.as_bytes().to_vec(),
+			port_channel_id.1.to_string().as_bytes().to_vec(),
+			u64::from(seq),
+		);
+
+		Ok(())
+	}
+
+	fn store_timed_out_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		timestamp: Timestamp,
+	) -> Result<(), ICS04Error> {
+		TimedOutPackets::<T>::mutate(
+			key.0.as_bytes().to_vec(),
+			key.1.to_string().as_bytes().to_vec(),
+			|records| {
+				records.push((u64::from(key.2), timestamp.nanoseconds()));
+				let max = T::MaxTimedOutPackets::get() as usize;
+				if records.len() > max {
+					records.drain(..records.len() - max);
+				}
+			},
+		);
+
+		Ok(())
+	}
+
 	/// Called upon channel identifier creation (Init or Try message processing).
 	/// Increases the counter which keeps track of how many channels have been created.
 	/// Should never fail.
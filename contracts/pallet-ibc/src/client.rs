@@ -3,7 +3,9 @@ use super::*;
 use crate::{
 	ics23::{client_states::ClientStates, clients::Clients, consensus_states::ConsensusStates},
 	impls::host_height,
-	light_clients::{AnyClient, AnyClientMessage, AnyClientState, AnyConsensusState},
+	light_clients::{
+		AnyClient, AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager,
+	},
 	routing::Context,
 };
 
@@ -19,10 +21,11 @@ use ibc::{
 		},
 		ics24_host::identifier::ClientId,
 	},
+	signer::Signer,
 	timestamp::Timestamp,
 	Height,
 };
-use sp_runtime::SaturatedConversion;
+use sp_runtime::{traits::IdentifyAccount, SaturatedConversion};
 use tendermint_proto::Protobuf;
 
 #[derive(Encode, Decode)]
@@ -32,6 +35,11 @@ pub struct HostConsensusProof {
 	pub extrinsic_proof: Vec<Vec<u8>>,
 }
 
+/// Neither beefy nor grandpa clients look up the consensus states adjacent to a height.
+fn uses_adjacent_consensus_states(client_id: &ClientId) -> bool {
+	!client_id.as_str().starts_with("10-grandpa") && !client_id.as_str().starts_with("11-beefy")
+}
+
 impl<T: Config + Send + Sync> ClientReader for Context<T>
 where
 	u32: From<<T as frame_system::Config>::BlockNumber>,
@@ -90,6 +98,10 @@ where
 		Ok(any_consensus_state)
 	}
 
+	fn supported_client_types(&self) -> Vec<ClientType> {
+		AnyClientState::supported_client_types()
+	}
+
 	fn host_client_type(&self) -> String {
 		// todo: https://github.com/cosmos/ibc/pull/839
 		if cfg!(any(test, feature = "runtime-benchmarks")) {
@@ -104,6 +116,9 @@ where
 		client_id: &ClientId,
 		height: Height,
 	) -> Result<Option<AnyConsensusState>, ICS02Error> {
+		if !uses_adjacent_consensus_states(client_id) {
+			return Ok(None)
+		}
 		let consensus_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
 		let cs_state = consensus_heights
 			.into_iter()
@@ -120,6 +135,9 @@ where
 		client_id: &ClientId,
 		height: Height,
 	) -> Result<Option<AnyConsensusState>, ICS02Error> {
+		if !uses_adjacent_consensus_states(client_id) {
+			return Ok(None)
+		}
 		let consensus_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
 		let cs_state = consensus_heights
 			.into_iter()
@@ -131,6 +149,23 @@ where
 		Ok(cs_state)
 	}
 
+	fn oldest_consensus_state_height(
+		&self,
+		client_id: &ClientId,
+	) -> Result<Option<Height>, ICS02Error> {
+		let consensus_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
+		Ok(consensus_heights.into_iter().next())
+	}
+
+	fn next_consensus_state_height(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Option<Height>, ICS02Error> {
+		let consensus_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
+		Ok(consensus_heights.into_iter().find(|next_height| next_height > &height))
+	}
+
 	fn host_height(&self) -> Height {
 		log::trace!(target: "pallet_ibc", "in client: [host_height]");
 		let current_height = host_height::<T>();
@@ -168,6 +203,7 @@ where
 				AnyConsensusState::Grandpa(ics10_grandpa::consensus_state::ConsensusState {
 					timestamp,
 					root: vec![].into(),
+					block_hash: Default::default(),
 				}),
 		};
 		Ok(consensus_state)
@@ -277,6 +313,7 @@ where
 				let cs_state = ics10_grandpa::consensus_state::ConsensusState {
 					timestamp,
 					root: header.state_root().as_ref().to_vec().into(),
+					block_hash: H256::from_slice(header_hash.as_ref()),
 				};
 				AnyConsensusState::Grandpa(cs_state)
 			},
@@ -290,6 +327,15 @@ where
 
 		Ok(count as u64)
 	}
+
+	fn is_client_recovery_authorized(&self, signer: &Signer) -> bool {
+		match ClientRecoveryAuthority::<T>::get() {
+			Some(authority) => T::AccountIdConversion::try_from(signer.clone())
+				.map(|account| account.into_account() == authority)
+				.unwrap_or(false),
+			None => false,
+		}
+	}
 }
 
 impl<T: Config> ClientTypes for Context<T> {
@@ -347,27 +393,59 @@ where
 			client_id, height, consensus_state);
 
 		let data = consensus_state.encode_to_vec();
-		// todo: pruning
 		ConsensusStates::<T>::insert(client_id.clone(), height, data);
-		// We do not need this hack for neither beefy nor grandpa clients
-		if !client_id.as_str().starts_with("10-grandpa") &&
-			!client_id.as_str().starts_with("11-beefy")
-		{
-			let mut stored_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
-			if let Err(val) = stored_heights.try_insert(height) {
-				let first = stored_heights
-					.iter()
-					.next()
-					.expect("Cannot fail as a value always exists")
-					.clone();
-				stored_heights.remove(&first);
-				stored_heights
-					.try_insert(val)
-					.expect("Cannot panic, since bounds cannot be exceeded at this point");
-			}
-			ConsensusHeights::<T>::insert(client_id.as_bytes().to_vec(), stored_heights);
+		// The heights of every client are tracked, pruning walks them from the oldest one.
+		let mut stored_heights = ConsensusHeights::<T>::get(client_id.as_bytes().to_vec());
+		if let Err(val) = stored_heights.try_insert(height) {
+			let first = stored_heights
+				.iter()
+				.next()
+				.expect("Cannot fail as a value always exists")
+				.clone();
+			stored_heights.remove(&first);
+			stored_heights
+				.try_insert(val)
+				.expect("Cannot panic, since bounds cannot be exceeded at this point");
 		}
+		ConsensusHeights::<T>::insert(client_id.as_bytes().to_vec(), stored_heights);
+
+		Ok(())
+	}
 
+	fn delete_consensus_state(
+		&mut self,
+		client_id: ClientId,
+		height: Height,
+	) -> Result<(), ICS02Error> {
+		log::trace!(target: "pallet_ibc", "in client : [delete_consensus_state] >> client_id: {:?}, height = {:?}",
+			client_id, height);
+
+		ConsensusStates::<T>::remove(client_id.clone(), height);
+		ConsensusHeights::<T>::mutate(client_id.as_bytes().to_vec(), |stored_heights| {
+			stored_heights.remove(&height);
+		});
+		Ok(())
+	}
+
+	fn delete_update_time(
+		&mut self,
+		client_id: ClientId,
+		height: Height,
+	) -> Result<(), ICS02Error> {
+		let height = height.encode_vec();
+		let client_id = client_id.as_bytes().to_vec();
+		ClientUpdateTime::<T>::remove(client_id, height);
+		Ok(())
+	}
+
+	fn delete_update_height(
+		&mut self,
+		client_id: ClientId,
+		height: Height,
+	) -> Result<(), ICS02Error> {
+		let height = height.encode_vec();
+		let client_id = client_id.as_bytes().to_vec();
+		ClientUpdateHeight::<T>::remove(client_id, height);
 		Ok(())
 	}
 
@@ -423,7 +501,13 @@ where
 
 				(client_state.relay_chain, client_state.para_id, client_state.latest_para_height)
 			},
-			client => Err(ICS02Error::unknown_client_type(format!("{}", client.client_type())))?,
+			client => Err(ICS02Error::unknown_client_type(
+				format!("{}", client.client_type()),
+				vec![
+					ics10_grandpa::client_state::ClientState::<HostFunctionsManager>::client_type(),
+					ics11_beefy::client_state::ClientState::<HostFunctionsManager>::client_type(),
+				],
+			))?,
 		};
 
 		if relay_chain != T::RelayChain::get() {
@@ -3,12 +3,14 @@ use core::time::Duration;
 
 use crate::{ics23::connections::Connections, routing::Context};
 use frame_support::traits::Get;
+use ibc_primitives::connection_id_from_bytes;
 use ibc::{
 	core::{
 		ics03_connection::{
 			connection::ConnectionEnd,
 			context::{ConnectionKeeper, ConnectionReader},
 			error::Error as ICS03Error,
+			handler::HandshakeOrigin,
 		},
 		ics23_commitment::commitment::CommitmentPrefix,
 		ics24_host::identifier::{ClientId, ConnectionId},
@@ -36,6 +38,21 @@ where
 		Ok(ret)
 	}
 
+	fn client_connections(&self, client_id: &ClientId) -> Result<Vec<ConnectionId>, ICS03Error> {
+		let connection_ids = ConnectionClient::<T>::get(client_id.as_bytes().to_vec())
+			.into_iter()
+			.map(|connection_id| {
+				connection_id_from_bytes(connection_id).map_err(|_| {
+					ICS03Error::implementation_specific(
+						"[client_connections]: error decoding connection id".to_string(),
+					)
+				})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+		log::trace!(target: "pallet_ibc", "in connection : [client_connections] >> client_id = {:?}, connection_ids = {:?}", client_id, connection_ids);
+		Ok(connection_ids)
+	}
+
 	fn host_oldest_height(&self) -> Height {
 		let mut temp = frame_system::BlockHash::<T>::iter().collect::<Vec<_>>();
 		temp.sort_by(|(a, ..), (b, ..)| a.cmp(b));
@@ -67,6 +84,34 @@ where
 
 		Ok(count as u64)
 	}
+
+	fn handshake_timeout(&self) -> u64 {
+		T::ConnectionHandshakeTimeout::get()
+	}
+
+	fn connection_handshake_origin(
+		&self,
+		conn_id: &ConnectionId,
+	) -> Result<HandshakeOrigin, ICS03Error> {
+		let (signer, height) = ConnectionHandshakeOrigin::<T>::get(conn_id.as_bytes().to_vec())
+			.ok_or_else(|| ICS03Error::connection_not_found(conn_id.clone()))?;
+		let signer = String::from_utf8(signer)
+			.map_err(|_| {
+				ICS03Error::implementation_specific(
+					"[connection_handshake_origin]: invalid signer".to_string(),
+				)
+			})?
+			.parse()
+			.map_err(ICS03Error::signer)?;
+		let height = Height::decode_vec(&height).map_err(|e| {
+			ICS03Error::implementation_specific(format!(
+				"[connection_handshake_origin]: error decoding height: {}",
+				e
+			))
+		})?;
+		log::trace!(target: "pallet_ibc", "in connection : [connection_handshake_origin] >> signer = {:?}, height = {:?}", signer, height);
+		Ok(HandshakeOrigin { signer, height })
+	}
 }
 
 impl<T: Config + Sync + Send> ConnectionKeeper for Context<T>
@@ -91,6 +136,46 @@ where
 		Ok(())
 	}
 
+	fn store_connection_handshake_origin(
+		&mut self,
+		connection_id: ConnectionId,
+		origin: HandshakeOrigin,
+	) -> Result<(), ICS03Error> {
+		log::trace!(target: "pallet_ibc",
+			"in connection : [store_connection_handshake_origin] >> connection_id: {:?}, origin: {:?}",
+			connection_id,
+			origin
+		);
+
+		ConnectionHandshakeOrigin::<T>::insert(
+			connection_id.as_bytes().to_vec(),
+			(origin.signer.as_ref().as_bytes().to_vec(), origin.height.encode_vec()),
+		);
+		Ok(())
+	}
+
+	fn delete_connection_handshake_origin(
+		&mut self,
+		connection_id: &ConnectionId,
+	) -> Result<(), ICS03Error> {
+		log::trace!(target: "pallet_ibc", "in connection : [delete_connection_handshake_origin] >> connection_id: {:?}", connection_id);
+
+		ConnectionHandshakeOrigin::<T>::remove(connection_id.as_bytes().to_vec());
+		Ok(())
+	}
+
+	fn delete_connection_end(&mut self, connection_id: &ConnectionId) -> Result<(), ICS03Error> {
+		log::trace!(target: "pallet_ibc", "in connection : [delete_connection_end] >> connection_id: {:?}", connection_id);
+
+		let connection_end = ConnectionReader::connection_end(self, connection_id)?;
+		<Connections<T>>::remove(connection_id);
+		ConnectionHandshakeOrigin::<T>::remove(connection_id.as_bytes().to_vec());
+		ConnectionClient::<T>::mutate(connection_end.client_id().as_bytes().to_vec(), |val| {
+			val.retain(|conn_id| conn_id.as_slice() != connection_id.as_bytes())
+		});
+		Ok(())
+	}
+
 	fn store_connection_to_client(
 		&mut self,
 		connection_id: ConnectionId,
@@ -1,5 +1,8 @@
 use super::*;
-use ibc::core::ics26_routing::error::{Error as RoutingError, ErrorDetail};
+use ibc::core::{
+	ics04_channel::error::ErrorDetail as ChannelErrorDetail,
+	ics26_routing::error::{Error as RoutingError, ErrorDetail},
+};
 
 #[derive(
 	PartialEq, Eq, Clone, frame_support::RuntimeDebug, scale_info::TypeInfo, Encode, Decode,
@@ -11,12 +14,16 @@ pub enum IbcError {
 	Ics03Connection { message: Vec<u8> },
 	/// ICS04 channel error
 	Ics04Channel { message: Vec<u8> },
+	/// A packet was submitted out of order on an ordered channel
+	InvalidPacketSequence { port_id: Vec<u8>, channel_id: Vec<u8>, expected: u64, received: u64 },
 	/// ICS20 fungible token transfer error
 	Ics20FungibleTokenTransfer { message: Vec<u8> },
 	/// Unknown message type URL
 	UnknownMessageTypeUrl { message: Vec<u8> },
 	/// The message is malformed and cannot be decoded
 	MalformedMessageBytes { message: Vec<u8> },
+	/// ICS05 port error
+	Ics05Port { message: Vec<u8> },
 }
 
 impl From<RoutingError> for IbcError {
@@ -26,8 +33,17 @@ impl From<RoutingError> for IbcError {
 				IbcError::Ics03Connection { message: format!("{:?}", e).as_bytes().to_vec() },
 			ErrorDetail::Ics02Client(e) =>
 				IbcError::Ics02Client { message: format!("{:?}", e).as_bytes().to_vec() },
-			ErrorDetail::Ics04Channel(e) =>
-				IbcError::Ics04Channel { message: format!("{:?}", e).as_bytes().to_vec() },
+			ErrorDetail::Ics04Channel(e) => match &e.source {
+				ChannelErrorDetail::InvalidPacketSequence(seq) => IbcError::InvalidPacketSequence {
+					port_id: seq.port_id.as_bytes().to_vec(),
+					channel_id: seq.channel_id.to_string().as_bytes().to_vec(),
+					expected: seq.expected.into(),
+					received: seq.received.into(),
+				},
+				_ => IbcError::Ics04Channel { message: format!("{:?}", e).as_bytes().to_vec() },
+			},
+			ErrorDetail::Ics05Port(e) =>
+				IbcError::Ics05Port { message: format!("{:?}", e).as_bytes().to_vec() },
 			ErrorDetail::Ics20FungibleTokenTransfer(e) => IbcError::Ics20FungibleTokenTransfer {
 				message: format!("{:?}", e).as_bytes().to_vec(),
 			},
@@ -39,6 +39,10 @@ pub enum IbcEvent {
 		revision_number: u64,
 		consensus_height: u64,
 		consensus_revision_number: u64,
+		/// Revision number and height of each consensus state written by the update
+		header_heights: Vec<(u64, u64)>,
+		/// Hex encoded, empty when unknown
+		consensus_root: Vec<u8>,
 	},
 	/// Client upgraded
 	UpgradeClient {
@@ -58,6 +62,16 @@ pub enum IbcEvent {
 		consensus_height: u64,
 		consensus_revision_number: u64,
 	},
+	/// Client recovered
+	ClientRecovered {
+		client_id: Vec<u8>,
+		client_type: Vec<u8>,
+		revision_height: u64,
+		revision_number: u64,
+		consensus_height: u64,
+		consensus_revision_number: u64,
+		substitute_client_id: Vec<u8>,
+	},
 	/// Connection open init
 	OpenInitConnection {
 		revision_height: u64,
@@ -76,6 +90,15 @@ pub enum IbcEvent {
 		counterparty_connection_id: Option<Vec<u8>>,
 		counterparty_client_id: Vec<u8>,
 	},
+	/// Connection handshake cancelled
+	ConnectionCancelled {
+		revision_height: u64,
+		revision_number: u64,
+		connection_id: Option<Vec<u8>>,
+		client_id: Vec<u8>,
+		counterparty_connection_id: Option<Vec<u8>>,
+		counterparty_client_id: Vec<u8>,
+	},
 	/// Connection try open
 	OpenTryConnection {
 		revision_height: u64,
@@ -153,6 +176,58 @@ pub enum IbcEvent {
 		connection_id: Vec<u8>,
 		counterparty_port_id: Vec<u8>,
 		counterparty_channel_id: Option<Vec<u8>>,
+		/// Set when the channel was closed without a counterparty proof, e.g. on a frozen client
+		reason: Option<Vec<u8>>,
+	},
+	/// Channel upgrade init
+	UpgradeInitChannel {
+		revision_height: u64,
+		revision_number: u64,
+		channel_id: Option<Vec<u8>>,
+		port_id: Vec<u8>,
+		connection_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+	},
+	/// Channel upgrade try
+	UpgradeTryChannel {
+		revision_height: u64,
+		revision_number: u64,
+		channel_id: Option<Vec<u8>>,
+		port_id: Vec<u8>,
+		connection_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+	},
+	/// Channel upgrade ack
+	UpgradeAckChannel {
+		revision_height: u64,
+		revision_number: u64,
+		channel_id: Option<Vec<u8>>,
+		port_id: Vec<u8>,
+		connection_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+	},
+	/// Channel upgrade confirm
+	UpgradeConfirmChannel {
+		revision_height: u64,
+		revision_number: u64,
+		channel_id: Option<Vec<u8>>,
+		port_id: Vec<u8>,
+		connection_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+	},
+	/// Channel upgrade cancelled
+	UpgradeCancelChannel {
+		revision_height: u64,
+		revision_number: u64,
+		channel_id: Option<Vec<u8>>,
+		port_id: Vec<u8>,
+		connection_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
 	},
 	/// Receive packet
 	ReceivePacket {
@@ -173,6 +248,11 @@ pub enum IbcEvent {
 		dest_port: Vec<u8>,
 		dest_channel: Vec<u8>,
 		sequence: u64,
+		timeout_revision_height: u64,
+		timeout_revision_number: u64,
+		timeout_timestamp: u64,
+		/// Empty when the ordering of the channel is unknown
+		channel_ordering: Vec<u8>,
 	},
 	/// Acknowledgement packet
 	AcknowledgePacket {
@@ -208,6 +288,14 @@ pub enum IbcEvent {
 		channel_id: Vec<u8>,
 		sequence: u64,
 	},
+	/// Acknowledgements pruned
+	AcknowledgementsPruned {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		total_pruned_sequences: u64,
+	},
 	/// Empty
 	Empty,
 	/// Chain Error
@@ -238,6 +326,15 @@ impl From<RawIbcEvent> for IbcEvent {
 				revision_number: ev.height().revision_number,
 				consensus_height: ev.consensus_height().revision_height,
 				consensus_revision_number: ev.consensus_height().revision_number,
+				header_heights: ev
+					.header_heights()
+					.iter()
+					.map(|height| (height.revision_number, height.revision_height))
+					.collect(),
+				consensus_root: ev
+					.consensus_root()
+					.map(|root| root.as_bytes().to_vec())
+					.unwrap_or_default(),
 			},
 			RawIbcEvent::UpgradeClient(ev) => IbcEvent::UpgradeClient {
 				client_id: ev.client_id().as_bytes().to_vec(),
@@ -255,6 +352,15 @@ impl From<RawIbcEvent> for IbcEvent {
 				consensus_height: ev.0.consensus_height.revision_height,
 				consensus_revision_number: ev.0.consensus_height.revision_number,
 			},
+			RawIbcEvent::ClientRecovered(ev) => IbcEvent::ClientRecovered {
+				client_id: ev.client_id().as_bytes().to_vec(),
+				client_type: ev.common.client_type.as_bytes().to_vec(),
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				consensus_height: ev.common.consensus_height.revision_height,
+				consensus_revision_number: ev.common.consensus_height.revision_number,
+				substitute_client_id: ev.substitute_client_id().as_bytes().to_vec(),
+			},
 			RawIbcEvent::OpenInitConnection(ev) => IbcEvent::OpenInitConnection {
 				revision_height: ev.height().revision_height,
 				revision_number: ev.height().revision_number,
@@ -303,6 +409,18 @@ impl From<RawIbcEvent> for IbcEvent {
 					.map(|val| val.as_bytes().to_vec()),
 				counterparty_client_id: ev.attributes().counterparty_client_id.as_bytes().to_vec(),
 			},
+			RawIbcEvent::ConnectionCancelled(ev) => IbcEvent::ConnectionCancelled {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				connection_id: ev.connection_id().map(|val| val.as_bytes().to_vec()),
+				client_id: ev.attributes().client_id.as_bytes().to_vec(),
+				counterparty_connection_id: ev
+					.attributes()
+					.counterparty_connection_id
+					.as_ref()
+					.map(|val| val.as_bytes().to_vec()),
+				counterparty_client_id: ev.attributes().counterparty_client_id.as_bytes().to_vec(),
+			},
 			RawIbcEvent::OpenInitChannel(ev) => IbcEvent::OpenInitChannel {
 				revision_height: ev.height().revision_height,
 				revision_number: ev.height().revision_number,
@@ -378,6 +496,72 @@ impl From<RawIbcEvent> for IbcEvent {
 				counterparty_channel_id: ev
 					.counterparty_channel_id
 					.map(|val| val.to_string().as_bytes().to_vec()),
+				reason: ev.reason.map(|reason| reason.into_bytes()),
+			},
+			RawIbcEvent::ChannelUpgradeInit(ev) => IbcEvent::UpgradeInitChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev
+					.channel_id()
+					.map(|channel_id| channel_id.to_string().as_bytes().to_vec()),
+				connection_id: ev.connection_id.as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+			},
+			RawIbcEvent::ChannelUpgradeTry(ev) => IbcEvent::UpgradeTryChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev
+					.channel_id()
+					.map(|channel_id| channel_id.to_string().as_bytes().to_vec()),
+				connection_id: ev.connection_id.as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+			},
+			RawIbcEvent::ChannelUpgradeAck(ev) => IbcEvent::UpgradeAckChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev
+					.channel_id()
+					.map(|channel_id| channel_id.to_string().as_bytes().to_vec()),
+				connection_id: ev.connection_id.as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+			},
+			RawIbcEvent::ChannelUpgradeConfirm(ev) => IbcEvent::UpgradeConfirmChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev
+					.channel_id()
+					.map(|channel_id| channel_id.to_string().as_bytes().to_vec()),
+				connection_id: ev.connection_id.as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+			},
+			RawIbcEvent::ChannelUpgradeCancel(ev) => IbcEvent::UpgradeCancelChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev
+					.channel_id()
+					.map(|channel_id| channel_id.to_string().as_bytes().to_vec()),
+				connection_id: ev.connection_id.as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
 			},
 			RawIbcEvent::SendPacket(ev) => IbcEvent::SendPacket {
 				revision_height: ev.height().revision_height,
@@ -387,6 +571,13 @@ impl From<RawIbcEvent> for IbcEvent {
 				dest_port: ev.dst_port_id().as_bytes().to_vec(),
 				dest_channel: ev.dst_channel_id().to_string().as_bytes().to_vec(),
 				sequence: ev.packet.sequence.into(),
+				timeout_revision_height: ev.packet.timeout_height.revision_height,
+				timeout_revision_number: ev.packet.timeout_height.revision_number,
+				timeout_timestamp: ev.packet.timeout_timestamp.nanoseconds(),
+				channel_ordering: ev
+					.channel_ordering
+					.map(|ordering| ordering.as_str().as_bytes().to_vec())
+					.unwrap_or_default(),
 			},
 			RawIbcEvent::ReceivePacket(ev) => IbcEvent::ReceivePacket {
 				revision_height: ev.height().revision_height,
@@ -427,6 +618,13 @@ impl From<RawIbcEvent> for IbcEvent {
 				port_id: ev.src_port_id().as_bytes().to_vec(),
 				sequence: ev.packet.sequence.into(),
 			},
+			RawIbcEvent::AcknowledgementsPruned(ev) => IbcEvent::AcknowledgementsPruned {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				total_pruned_sequences: ev.total_pruned_sequences,
+			},
 			RawIbcEvent::Empty(_) => IbcEvent::Empty,
 			RawIbcEvent::ChainError(_) => IbcEvent::ChainError,
 			RawIbcEvent::AppModule(ev) => IbcEvent::AppModule {
@@ -488,6 +686,8 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 				revision_number,
 				consensus_height,
 				consensus_revision_number,
+				header_heights,
+				consensus_root,
 			} => Ok(RawIbcEvent::UpdateClient(ClientEvents::UpdateClient {
 				common: ClientEvents::Attributes {
 					height: Height::new(revision_number, revision_height),
@@ -499,6 +699,17 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 					consensus_height: Height::new(consensus_revision_number, consensus_height),
 				},
 				header: None,
+				header_heights: header_heights
+					.into_iter()
+					.map(|(revision_number, revision_height)| {
+						Height::new(revision_number, revision_height)
+					})
+					.collect(),
+				consensus_root: if consensus_root.is_empty() {
+					None
+				} else {
+					Some(String::from_utf8(consensus_root).map_err(|_| ERROR_STR)?)
+				},
 			})),
 			IbcEvent::UpgradeClient {
 				client_id,
@@ -536,6 +747,29 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 					consensus_height: Height::new(consensus_revision_number, consensus_height),
 				},
 			))),
+			IbcEvent::ClientRecovered {
+				client_id,
+				client_type,
+				revision_height,
+				revision_number,
+				consensus_height,
+				consensus_revision_number,
+				substitute_client_id,
+			} => Ok(RawIbcEvent::ClientRecovered(ClientEvents::ClientRecovered {
+				common: ClientEvents::Attributes {
+					height: Height::new(revision_number, revision_height),
+					client_id: ClientId::from_str(
+						&String::from_utf8(client_id).map_err(|_| ERROR_STR)?,
+					)
+					.map_err(|_| ERROR_STR)?,
+					client_type: String::from_utf8(client_type).map_err(|_| ERROR_STR)?,
+					consensus_height: Height::new(consensus_revision_number, consensus_height),
+				},
+				substitute_client_id: ClientId::from_str(
+					&String::from_utf8(substitute_client_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+			})),
 			IbcEvent::OpenInitConnection {
 				revision_height,
 				revision_number,
@@ -602,6 +836,39 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 				}
 				.into(),
 			)),
+			IbcEvent::ConnectionCancelled {
+				revision_height,
+				revision_number,
+				connection_id,
+				client_id,
+				counterparty_connection_id,
+				counterparty_client_id,
+			} => Ok(RawIbcEvent::ConnectionCancelled(
+				ConnectionEvents::Attributes {
+					height: Height::new(revision_number, revision_height),
+					connection_id: connection_id.and_then(|connection_id| {
+						String::from_utf8(connection_id)
+							.ok()
+							.and_then(|connection_id| ConnectionId::from_str(&connection_id).ok())
+					}),
+					client_id: ClientId::from_str(
+						&String::from_utf8(client_id).map_err(|_| ERROR_STR)?,
+					)
+					.map_err(|_| ERROR_STR)?,
+					counterparty_connection_id: counterparty_connection_id.and_then(
+						|connection_id| {
+							String::from_utf8(connection_id).ok().and_then(|connection_id| {
+								ConnectionId::from_str(&connection_id).ok()
+							})
+						},
+					),
+					counterparty_client_id: ClientId::from_str(
+						&String::from_utf8(counterparty_client_id).map_err(|_| ERROR_STR)?,
+					)
+					.map_err(|_| ERROR_STR)?,
+				}
+				.into(),
+			)),
 			IbcEvent::OpenTryConnection {
 				revision_height,
 				revision_number,
@@ -830,6 +1097,7 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 				connection_id,
 				counterparty_port_id,
 				counterparty_channel_id,
+				reason,
 			} => Ok(RawIbcEvent::CloseConfirmChannel(ChannelEvents::CloseConfirm {
 				height: Height::new(revision_number, revision_height),
 				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
@@ -852,6 +1120,162 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 						.ok()
 						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
 				}),
+				reason: reason.and_then(|reason| String::from_utf8(reason).ok()),
+			})),
+			IbcEvent::UpgradeInitChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				connection_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+			} => Ok(RawIbcEvent::ChannelUpgradeInit(ChannelEvents::UpgradeInit {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				connection_id: ConnectionId::from_str(
+					&String::from_utf8(connection_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+			})),
+			IbcEvent::UpgradeTryChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				connection_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+			} => Ok(RawIbcEvent::ChannelUpgradeTry(ChannelEvents::UpgradeTry {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				connection_id: ConnectionId::from_str(
+					&String::from_utf8(connection_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+			})),
+			IbcEvent::UpgradeAckChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				connection_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+			} => Ok(RawIbcEvent::ChannelUpgradeAck(ChannelEvents::UpgradeAck {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				connection_id: ConnectionId::from_str(
+					&String::from_utf8(connection_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+			})),
+			IbcEvent::UpgradeConfirmChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				connection_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+			} => Ok(RawIbcEvent::ChannelUpgradeConfirm(ChannelEvents::UpgradeConfirm {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				connection_id: ConnectionId::from_str(
+					&String::from_utf8(connection_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+			})),
+			IbcEvent::UpgradeCancelChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				connection_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+			} => Ok(RawIbcEvent::ChannelUpgradeCancel(ChannelEvents::UpgradeCancel {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				connection_id: ConnectionId::from_str(
+					&String::from_utf8(connection_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
 			})),
 
 			IbcEvent::ReceivePacket {
@@ -895,6 +1319,10 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 				dest_port,
 				dest_channel,
 				sequence,
+				timeout_revision_height,
+				timeout_revision_number,
+				timeout_timestamp,
+				channel_ordering,
 			} => Ok(RawIbcEvent::SendPacket(ChannelEvents::SendPacket {
 				height: Height::new(revision_number, revision_height),
 				packet: Packet {
@@ -916,9 +1344,13 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 					)
 					.map_err(|_| ERROR_STR)?,
 					data: Default::default(),
-					timeout_height: Height::default(),
-					timeout_timestamp: Timestamp::default(),
+					timeout_height: Height::new(timeout_revision_number, timeout_revision_height),
+					timeout_timestamp: Timestamp::from_nanoseconds(timeout_timestamp)
+						.map_err(|_| ERROR_STR)?,
 				},
+				channel_ordering: String::from_utf8(channel_ordering)
+					.ok()
+					.and_then(|ordering| ordering.parse().ok()),
 			})),
 			IbcEvent::AcknowledgePacket {
 				revision_height,
@@ -1029,6 +1461,22 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 					timeout_timestamp: Timestamp::default(),
 				},
 			})),
+			IbcEvent::AcknowledgementsPruned {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				total_pruned_sequences,
+			} => Ok(RawIbcEvent::AcknowledgementsPruned(ChannelEvents::AcknowledgementsPruned {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				total_pruned_sequences,
+			})),
 			IbcEvent::Empty => Ok(RawIbcEvent::Empty("Empty".to_string())),
 			IbcEvent::ChainError => Ok(RawIbcEvent::ChainError("Chain Error".to_string())),
 			IbcEvent::AppModule { kind, module_id } => Ok(RawIbcEvent::AppModule(ModuleEvent {
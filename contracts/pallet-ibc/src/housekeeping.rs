@@ -0,0 +1,171 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Housekeeping run from `on_idle`, it prunes the state ibc no longer needs with the weight left
+//! at the end of the block. Each step only looks at what is still stored, so whatever doesn't fit
+//! in the weight of one block is picked up by the next ones.
+//!
+//! Packet commitments need no housekeeping, they are deleted as soon as their acknowledgement or
+//! timeout is processed. Receipts and acknowledgements are never pruned here: a local
+//! acknowledgement doesn't tell whether it was relayed back to the sender.
+
+use super::*;
+use crate::{impls::host_height, routing::Context};
+use frame_support::{pallet_prelude::Weight, traits::Get};
+use ibc::{
+	core::{
+		ics02_client::context::{ClientKeeper, ClientReader},
+		ics03_connection::{
+			connection::State as ConnectionState,
+			context::{ConnectionKeeper, ConnectionReader},
+			events::Attributes,
+		},
+		ics26_routing::error::Error as Ics26Error,
+	},
+	events::IbcEvent,
+	Height,
+};
+use ibc_primitives::{client_id_from_bytes, connection_id_from_bytes};
+use sp_core::crypto::AccountId32;
+use tendermint_proto::Protobuf;
+
+/// Weight the housekeeping of the current block may still use.
+struct Budget {
+	remaining: Weight,
+	used: Weight,
+}
+
+impl Budget {
+	/// Takes `weight` out of the budget, returns false and takes nothing if not enough is left.
+	fn try_consume(&mut self, weight: Weight) -> bool {
+		if self.remaining < weight {
+			return false
+		}
+		self.remaining -= weight;
+		self.used = self.used.saturating_add(weight);
+		true
+	}
+}
+
+impl<T: Config + Send + Sync> Pallet<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<T::AccountId>,
+{
+	/// Prunes expired consensus states, then cancels stale connection handshakes, within
+	/// `remaining_weight`. Returns the weight used.
+	pub(crate) fn housekeeping(remaining_weight: Weight) -> Weight {
+		let mut budget = Budget { remaining: remaining_weight, used: 0 };
+		let mut ctx = Context::<T>::new();
+
+		let consensus_states_pruned = Self::prune_consensus_states(&mut ctx, &mut budget);
+		let connections_cancelled = Self::cancel_stale_connections(&mut ctx, &mut budget);
+
+		if consensus_states_pruned > 0 || connections_cancelled > 0 {
+			Self::deposit_event(Event::<T>::HousekeepingPerformed {
+				consensus_states_pruned,
+				connections_cancelled,
+			});
+		}
+		budget.used
+	}
+
+	/// Deletes the oldest consensus states of every client that has more than
+	/// `ConsensusStateRetention` of them.
+	fn prune_consensus_states(ctx: &mut Context<T>, budget: &mut Budget) -> u32 {
+		// The latest consensus state is always kept, clients can't be updated without it.
+		let retention = T::ConsensusStateRetention::get().max(1) as usize;
+		let mut expired = vec![];
+		for (client_id, heights) in ConsensusHeights::<T>::iter() {
+			if !budget.try_consume(T::DbWeight::get().reads(1)) {
+				break
+			}
+			let excess = heights.len().saturating_sub(retention);
+			if excess > 0 {
+				expired.push((client_id, heights.iter().take(excess).cloned().collect::<Vec<_>>()));
+			}
+		}
+
+		let mut pruned = 0;
+		for (client_id, heights) in expired {
+			let client_id = match client_id_from_bytes(client_id) {
+				Ok(client_id) => client_id,
+				Err(_) => continue,
+			};
+			for height in heights {
+				if !budget.try_consume(<T as Config>::WeightInfo::prune_consensus_state()) {
+					return pruned
+				}
+				let _ = ctx.delete_consensus_state(client_id.clone(), height);
+				let _ = ctx.delete_update_time(client_id.clone(), height);
+				let _ = ctx.delete_update_height(client_id.clone(), height);
+				pruned += 1;
+			}
+		}
+		pruned
+	}
+
+	/// Cancels the connections still in `Init` after `ConnectionHandshakeTimeout` blocks, which
+	/// anyone could do through a `MsgConnectionCancel`. They are removed directly rather than
+	/// through a message, which would need a signer the pallet has no account for.
+	fn cancel_stale_connections(ctx: &mut Context<T>, budget: &mut Budget) -> u32 {
+		let now = host_height::<T>();
+		let timeout = T::ConnectionHandshakeTimeout::get();
+		let mut stale = vec![];
+		for (connection_id, (_, height)) in ConnectionHandshakeOrigin::<T>::iter() {
+			if !budget.try_consume(T::DbWeight::get().reads(1)) {
+				break
+			}
+			let started = match Height::decode_vec(&height) {
+				Ok(height) => height.revision_height,
+				Err(_) => continue,
+			};
+			if now.saturating_sub(started) >= timeout {
+				stale.push(connection_id);
+			}
+		}
+
+		let mut cancelled = 0;
+		for connection_id in stale {
+			let connection_id = match connection_id_from_bytes(connection_id) {
+				Ok(connection_id) => connection_id,
+				Err(_) => continue,
+			};
+			let connection_end = match ctx.connection_end(&connection_id) {
+				Ok(connection_end) if connection_end.state_matches(&ConnectionState::Init) =>
+					connection_end,
+				_ => continue,
+			};
+			if !budget.try_consume(<T as Config>::WeightInfo::cancel_stale_connection()) {
+				break
+			}
+			if ctx.delete_connection_end(&connection_id).is_err() {
+				continue
+			}
+			let event = IbcEvent::ConnectionCancelled(
+				Attributes {
+					height: ctx.host_height(),
+					connection_id: Some(connection_id),
+					client_id: connection_end.client_id().clone(),
+					counterparty_connection_id: connection_end.counterparty().connection_id.clone(),
+					counterparty_client_id: connection_end.counterparty().client_id().clone(),
+				}
+				.into(),
+			);
+			Self::deposit_event(vec![Ok::<_, Ics26Error>(event)].into());
+			cancelled += 1;
+		}
+		cancelled
+	}
+}
@@ -1,4 +1,4 @@
-use super::super::*;
+use super::{super::*, amount_to_balance};
 use crate::routing::Context;
 use frame_support::traits::{
 	fungibles::{Mutate, Transfer},
@@ -6,15 +6,24 @@ use frame_support::traits::{
 };
 use ibc::{
 	applications::transfer::{
-		context::{BankKeeper, Ics20Context, Ics20Keeper, Ics20Reader},
+		acknowledgement::Acknowledgement as Ics20Acknowledgement,
+		context::{
+			BankKeeper, DenomTraceStore, EscrowBalanceStore, Ics20Context, Ics20Keeper,
+			Ics20Reader, TransferRateLimiter,
+		},
 		error::Error as Ics20Error,
-		PORT_ID_STR,
+		forward::{ForwardKeeper, InFlightForward},
+		Amount, PrefixedDenom, PORT_ID_STR,
 	},
-	core::ics24_host::identifier::{ChannelId, PortId},
+	core::{
+		ics04_channel::packet::{Packet, Sequence},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	events::IbcEvent as RawIbcEvent,
 };
-use ibc_primitives::get_channel_escrow_address;
+use ibc_primitives::{get_channel_escrow_address, IbcHandler};
 use sp_core::crypto::AccountId32;
-use sp_runtime::traits::IdentifyAccount;
+use sp_runtime::traits::{CheckedAdd, IdentifyAccount, Zero};
 
 impl<T: Config + Send + Sync> Ics20Reader for Context<T>
 where
@@ -64,6 +73,207 @@ where
 	type AccountId = T::AccountIdConversion;
 }
 
+impl<T: Config + Send + Sync> DenomTraceStore for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<T::AccountId>,
+{
+	fn store_denom_trace(&mut self, hash: String, denom: PrefixedDenom) -> Result<(), Ics20Error> {
+		DenomTraces::<T>::insert(hash.as_bytes().to_vec(), denom.to_string().as_bytes().to_vec());
+		Ok(())
+	}
+
+	fn get_denom_trace(&self, hash: &str) -> Result<Option<PrefixedDenom>, Ics20Error> {
+		DenomTraces::<T>::get(hash.as_bytes().to_vec())
+			.map(|denom| {
+				let denom = String::from_utf8(denom).map_err(|e| {
+					Ics20Error::implementation_specific(format!("Invalid denom trace: {:?}", e))
+				})?;
+				PrefixedDenom::from_str(&denom)
+			})
+			.transpose()
+	}
+
+	fn iter_denom_traces(&self) -> Result<Vec<(String, PrefixedDenom)>, Ics20Error> {
+		DenomTraces::<T>::iter()
+			.map(|(hash, denom)| {
+				let hash = String::from_utf8(hash).map_err(|e| {
+					Ics20Error::implementation_specific(format!("Invalid denom hash: {:?}", e))
+				})?;
+				let denom = String::from_utf8(denom).map_err(|e| {
+					Ics20Error::implementation_specific(format!("Invalid denom trace: {:?}", e))
+				})?;
+				Ok((hash, PrefixedDenom::from_str(&denom)?))
+			})
+			.collect()
+	}
+}
+
+impl<T: Config + Send + Sync> TransferRateLimiter for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<T::AccountId>,
+{
+	fn check_send(
+		&mut self,
+		denom: &PrefixedDenom,
+		amount: Amount,
+		channel_id: &ChannelId,
+	) -> Result<(), Ics20Error> {
+		consume_transfer_rate_limit::<T>(denom, amount, channel_id, true)
+	}
+
+	fn check_recv(
+		&mut self,
+		denom: &PrefixedDenom,
+		amount: Amount,
+		channel_id: &ChannelId,
+	) -> Result<(), Ics20Error> {
+		consume_transfer_rate_limit::<T>(denom, amount, channel_id, false)
+	}
+}
+
+fn escrow_balance_key(port_id: &PortId, channel_id: &ChannelId) -> (Vec<u8>, Vec<u8>) {
+	(port_id.as_bytes().to_vec(), channel_id.to_string().as_bytes().to_vec())
+}
+
+impl<T: Config + Send + Sync> EscrowBalanceStore for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<T::AccountId>,
+{
+	fn escrow_balance(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		denom: &PrefixedDenom,
+	) -> Amount {
+		EscrowBalances::<T>::get(
+			escrow_balance_key(port_id, channel_id),
+			denom.to_string().as_bytes().to_vec(),
+		)
+		.into()
+	}
+
+	fn store_escrow_balance(
+		&mut self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		denom: &PrefixedDenom,
+		amount: Amount,
+	) -> Result<(), Ics20Error> {
+		let key = escrow_balance_key(port_id, channel_id);
+		let denom = denom.to_string().as_bytes().to_vec();
+		let amount = u128::try_from(amount)?;
+		if amount == 0 {
+			EscrowBalances::<T>::remove(key, denom);
+		} else {
+			EscrowBalances::<T>::insert(key, denom, amount);
+		}
+		Ok(())
+	}
+}
+
+/// Adds `amount` to what was sent or received of the asset over the channel in the current
+/// period, failing without recording anything if that exceeds the asset's rate limit.
+fn consume_transfer_rate_limit<T: Config>(
+	denom: &PrefixedDenom,
+	amount: Amount,
+	channel_id: &ChannelId,
+	is_send: bool,
+) -> Result<(), Ics20Error>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+{
+	// An asset that is not registered yet can't have a limit.
+	let asset_id = match T::IbcDenomToAssetIdConversion::lookup(&denom.to_string()) {
+		Some(asset_id) => asset_id,
+		None => return Ok(()),
+	};
+	let limit = match TransferRateLimits::<T>::get(asset_id) {
+		Some(limit) => limit,
+		None => return Ok(()),
+	};
+	let exceeded = || Ics20Error::rate_limit_exceeded(denom.to_string(), *channel_id);
+
+	let block_number: u32 = <frame_system::Pallet<T>>::block_number().into();
+	let period = block_number / T::TransferRateLimitPeriod::get().max(1);
+	let channel = channel_id.to_string().as_bytes().to_vec();
+	let (mut sent, mut received) = match TransferRateUsage::<T>::get(&channel, asset_id) {
+		Some((usage_period, sent, received)) if usage_period == period => (sent, received),
+		_ => (T::Balance::zero(), T::Balance::zero()),
+	};
+
+	let amount = amount_to_balance::<T>(amount)?;
+	let total = if is_send { &mut sent } else { &mut received };
+	*total = total.checked_add(&amount).filter(|total| *total <= limit).ok_or_else(exceeded)?;
+
+	TransferRateUsage::<T>::insert(channel, asset_id, (period, sent, received));
+	Ok(())
+}
+
+fn in_flight_forward_key(
+	(port_id, channel_id, sequence): &(PortId, ChannelId, Sequence),
+) -> (Vec<u8>, Vec<u8>, u64) {
+	(port_id.as_bytes().to_vec(), channel_id.to_string().as_bytes().to_vec(), (*sequence).into())
+}
+
+impl<T: Config + Send + Sync> ForwardKeeper for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<T::AccountId>,
+{
+	fn store_in_flight_forward(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		forward: InFlightForward,
+	) -> Result<(), Ics20Error> {
+		let forward = serde_json::to_vec(&forward).map_err(|e| {
+			Ics20Error::implementation_specific(format!("Invalid in flight forward: {:?}", e))
+		})?;
+		InFlightForwards::<T>::insert(in_flight_forward_key(&key), forward);
+		Ok(())
+	}
+
+	fn get_in_flight_forward(
+		&self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<Option<InFlightForward>, Ics20Error> {
+		InFlightForwards::<T>::get(in_flight_forward_key(key))
+			.map(|forward| {
+				serde_json::from_slice(&forward).map_err(|e| {
+					Ics20Error::implementation_specific(format!(
+						"Invalid in flight forward: {:?}",
+						e
+					))
+				})
+			})
+			.transpose()
+	}
+
+	fn delete_in_flight_forward(
+		&mut self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<(), Ics20Error> {
+		InFlightForwards::<T>::remove(in_flight_forward_key(key));
+		Ok(())
+	}
+
+	fn write_acknowledgement(
+		&mut self,
+		packet: &Packet,
+		ack: &Ics20Acknowledgement,
+	) -> Result<(), Ics20Error> {
+		Pallet::<T>::write_acknowledgement(packet, ack.as_ref().to_vec()).map_err(|e| {
+			Ics20Error::implementation_specific(format!("Failed to write acknowledgement {:?}", e))
+		})
+	}
+
+	fn deposit_ibc_events(&mut self, events: Vec<RawIbcEvent>) {
+		Pallet::<T>::deposit_event(events.into())
+	}
+}
+
 impl<T> BankKeeper for Context<T>
 where
 	T: Config + Send + Sync,
@@ -78,11 +288,11 @@ where
 		to: &Self::AccountId,
 		amt: &ibc::applications::transfer::PrefixedCoin,
 	) -> Result<(), Ics20Error> {
-		let amount: T::Balance = amt.amount.as_u256().low_u128().into();
+		let amount = amount_to_balance::<T>(amt.amount)?;
 		let denom = amt.denom.to_string();
 		// Token should be registered already if sending an ibc asset
-		let asset_id = T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom)
-			.map_err(|_| Ics20Error::invalid_token())?;
+		let asset_id = T::IbcDenomToAssetIdConversion::lookup(&denom)
+			.ok_or_else(Ics20Error::invalid_token)?;
 		if asset_id == T::NativeAssetId::get() {
 			<T::NativeCurrency as Currency<T::AccountId>>::transfer(
 				&from.clone().into_account(),
@@ -116,7 +326,7 @@ where
 		account: &Self::AccountId,
 		amt: &ibc::applications::transfer::PrefixedCoin,
 	) -> Result<(), Ics20Error> {
-		let amount: T::Balance = amt.amount.as_u256().low_u128().into();
+		let amount = amount_to_balance::<T>(amt.amount)?;
 		let denom = amt.denom.to_string();
 		// Find existing asset or create a new one
 		let asset_id =
@@ -143,11 +353,11 @@ where
 		account: &Self::AccountId,
 		amt: &ibc::applications::transfer::PrefixedCoin,
 	) -> Result<(), Ics20Error> {
-		let amount: T::Balance = amt.amount.as_u256().low_u128().into();
+		let amount = amount_to_balance::<T>(amt.amount)?;
 		let denom = amt.denom.to_string();
 		// Token should be registered already if burning a voucher
-		let asset_id = T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom)
-			.map_err(|_| Ics20Error::invalid_token())?;
+		let asset_id = T::IbcDenomToAssetIdConversion::lookup(&denom)
+			.ok_or_else(Ics20Error::invalid_token)?;
 		<<T as Config>::Fungibles as Mutate<T::AccountId>>::burn_from(
 			asset_id.into(),
 			&account.clone().into_account(),
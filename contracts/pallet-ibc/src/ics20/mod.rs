@@ -11,16 +11,21 @@ use ibc::{
 	applications::transfer::{
 		acknowledgement::{Acknowledgement as Ics20Acknowledgement, ACK_ERR_STR, ACK_SUCCESS_B64},
 		context::{
-			on_chan_close_confirm, on_chan_close_init, on_chan_open_ack, on_chan_open_confirm,
-			on_chan_open_init, on_chan_open_try,
+			decode_packet_data, on_chan_close_confirm, on_chan_close_init, on_chan_open_ack,
+			on_chan_open_confirm, on_chan_open_init, on_chan_open_try, on_chan_upgrade_init,
+			on_chan_upgrade_try,
+		},
+		forward::{
+			forward_metadata, forward_recv_packet, on_forward_acknowledgement, on_forward_timeout,
 		},
 		is_receiver_chain_source, is_sender_chain_source,
-		packet::PacketData,
+		packet::MultiTokenPacketData,
 		relay::{
-			on_ack_packet::process_ack_packet, on_recv_packet::process_recv_packet,
-			on_timeout_packet::process_timeout_packet,
+			on_ack_packet::process_ack_packet_tokens, on_recv_packet::process_recv_packet_tokens,
+			on_timeout_packet::process_timeout_packet_tokens,
 		},
-		PrefixedCoin, TracePrefix,
+		error::Error as Ics20Error,
+		Amount, PrefixedCoin, TracePrefix,
 	},
 	core::{
 		ics04_channel::{
@@ -28,10 +33,13 @@ use ibc::{
 			error::Error as Ics04Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::Packet,
+			upgrade::UpgradeFields,
 			Version,
 		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
-		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+		ics26_routing::context::{
+			Module, ModuleCallbackContext, ModuleOutputBuilder, OnRecvPacketAck,
+		},
 	},
 	signer::Signer,
 };
@@ -196,26 +204,65 @@ where
 			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
 	}
 
+	fn on_chan_upgrade_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_upgrade: &UpgradeFields,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_init(&mut ctx, output, port_id, channel_id, proposed_upgrade)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_upgrade: &UpgradeFields,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_try(&mut ctx, output, port_id, channel_id, counterparty_upgrade)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
 	fn on_recv_packet(
 		&self,
 		_ctx: &dyn ModuleCallbackContext,
 		output: &mut ModuleOutputBuilder,
 		packet: &Packet,
 		_relayer: &Signer,
-	) -> Result<(), Ics04Error> {
+	) -> Result<OnRecvPacketAck, Ics04Error> {
 		let mut ctx = Context::<T>::default();
-		let result = serde_json::from_slice(packet.data.as_slice())
-			.map_err(|e| {
-				Ics04Error::implementation_specific(format!("Failed to decode packet data {:?}", e))
-			})
-			.and_then(|packet_data: PacketData| {
-				process_recv_packet(&mut ctx, output, packet, packet_data.clone())
-					.map(|_| packet_data)
-					.map_err(|e| {
-						log::trace!(target: "pallet_ibc", "[on_recv_packet]: {:?}", e);
-						Ics04Error::implementation_specific(e.to_string())
-					})
-			});
+		if let Some((packet_data, metadata)) = forward_metadata(packet) {
+			// the acknowledgement is written once the forwarded packet settles
+			return forward_recv_packet(&mut ctx, output, packet, packet_data, metadata)
+				.map(|_| OnRecvPacketAck::Async)
+				.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+		}
+		let result = decode_packet_data(
+			&ctx,
+			&packet.destination_port,
+			&packet.destination_channel,
+			&packet.data,
+		)
+		.map_err(|e| {
+			Ics04Error::implementation_specific(format!("Failed to decode packet data {:?}", e))
+		})
+		.and_then(|packet_data: MultiTokenPacketData| {
+			process_recv_packet_tokens(&mut ctx, output, packet, packet_data.clone())
+				.map(|_| packet_data)
+				.map_err(|e| {
+					log::trace!(target: "pallet_ibc", "[on_recv_packet]: {:?}", e);
+					Ics04Error::implementation_specific(e.to_string())
+				})
+		});
 		match result {
 			Err(err) => {
 				Pallet::<T>::write_acknowledgement(
@@ -227,22 +274,30 @@ where
 				})?;
 			},
 			Ok(packet_data) => {
-				let denom = full_ibc_denom(packet, packet_data.token.clone());
-				Pallet::<T>::deposit_event(Event::<T>::TokenReceived {
-					from: packet_data.sender.to_string().as_bytes().to_vec(),
-					to: packet_data.receiver.to_string().as_bytes().to_vec(),
-					ibc_denom: denom.as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom)
-						.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_receiver_source: is_receiver_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel.clone(),
-						&packet_data.token.denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				});
+				for packet_data in packet_data.token_packets() {
+					let denom = full_ibc_denom(packet, packet_data.token.clone());
+					Pallet::<T>::deposit_event(Event::<T>::TokenReceived {
+						from: packet_data.sender.to_string().as_bytes().to_vec(),
+						to: packet_data.receiver.to_string().as_bytes().to_vec(),
+						ibc_denom: denom.as_bytes().to_vec(),
+						local_asset_id:
+							T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom).ok(),
+						amount: amount_to_balance::<T>(packet_data.token.amount)
+							.unwrap_or_default(),
+						is_receiver_source: is_receiver_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel.clone(),
+							&packet_data.token.denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+						memo: packet_data.memo.into_bytes(),
+					});
+				}
 				let packet = packet.clone();
 				Pallet::<T>::write_acknowledgement(
 					&packet,
@@ -253,7 +308,8 @@ where
 				})?;
 			},
 		}
-		Ok(())
+		// The acknowledgement was written above, along with its off chain copy.
+		Ok(OnRecvPacketAck::Async)
 	}
 
 	fn on_acknowledgement_packet(
@@ -265,10 +321,14 @@ where
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		let mut ctx = Context::<T>::default();
-		let packet_data: PacketData =
-			serde_json::from_slice(packet.data.as_slice()).map_err(|e| {
-				Ics04Error::implementation_specific(format!("Failed to decode packet data {:?}", e))
-			})?;
+		let packet_data =
+			decode_packet_data(&ctx, &packet.source_port, &packet.source_channel, &packet.data)
+				.map_err(|e| {
+					Ics04Error::implementation_specific(format!(
+						"Failed to decode packet data {:?}",
+						e
+					))
+				})?;
 		let ack = String::from_utf8(acknowledgement.as_ref().to_vec())
 			.map(|val| {
 				if val.as_bytes() == ACK_SUCCESS_B64 {
@@ -283,45 +343,59 @@ where
 					e
 				))
 			})?;
-		process_ack_packet(&mut ctx, packet, &packet_data, &ack)
+		process_ack_packet_tokens(&mut ctx, packet, &packet_data, &ack)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))?;
+		on_forward_acknowledgement(&mut ctx, packet, &ack)
 			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))?;
-		match ack {
-			Ics20Acknowledgement::Success(_) =>
-				Pallet::<T>::deposit_event(Event::<T>::TokenTransferCompleted {
-					from: packet_data.sender.to_string().as_bytes().to_vec(),
-					to: packet_data.receiver.to_string().as_bytes().to_vec(),
-					ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-						&packet_data.token.denom.to_string(),
-					)
-					.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_sender_source: is_sender_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel.clone(),
-						&packet_data.token.denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				}),
-			Ics20Acknowledgement::Error(_) =>
-				Pallet::<T>::deposit_event(Event::<T>::TokenTransferFailed {
-					from: packet_data.sender.to_string().as_bytes().to_vec(),
-					to: packet_data.receiver.to_string().as_bytes().to_vec(),
-					ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-						&packet_data.token.denom.to_string(),
-					)
-					.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_sender_source: is_sender_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel.clone(),
-						&packet_data.token.denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				}),
+		for packet_data in packet_data.token_packets() {
+			match &ack {
+				Ics20Acknowledgement::Success(_) =>
+					Pallet::<T>::deposit_event(Event::<T>::TokenTransferCompleted {
+						from: packet_data.sender.to_string().as_bytes().to_vec(),
+						to: packet_data.receiver.to_string().as_bytes().to_vec(),
+						ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
+						local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+							&packet_data.token.denom.to_string(),
+						)
+						.ok(),
+						amount: amount_to_balance::<T>(packet_data.token.amount)
+							.unwrap_or_default(),
+						is_sender_source: is_sender_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel.clone(),
+							&packet_data.token.denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					}),
+				Ics20Acknowledgement::Error(_) =>
+					Pallet::<T>::deposit_event(Event::<T>::TokenTransferFailed {
+						from: packet_data.sender.to_string().as_bytes().to_vec(),
+						to: packet_data.receiver.to_string().as_bytes().to_vec(),
+						ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
+						local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+							&packet_data.token.denom.to_string(),
+						)
+						.ok(),
+						amount: amount_to_balance::<T>(packet_data.token.amount)
+							.unwrap_or_default(),
+						is_sender_source: is_sender_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel.clone(),
+							&packet_data.token.denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					}),
+			}
 		}
 
 		Ok(())
@@ -335,10 +409,14 @@ where
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		let mut ctx = Context::<T>::default();
-		let packet_data: PacketData = serde_json::from_slice(packet.data.as_slice())
-			.map_err(|e| Ics04Error::app_module(format!("Failed to decode packet data {:?}", e)))?;
-		process_timeout_packet(&mut ctx, packet, &packet_data)
+		let packet_data =
+			decode_packet_data(&ctx, &packet.source_port, &packet.source_channel, &packet.data)
+				.map_err(|e| {
+					Ics04Error::app_module(format!("Failed to decode packet data {:?}", e))
+				})?;
+		process_timeout_packet_tokens(&mut ctx, packet, &packet_data)
 			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
+		on_forward_timeout(&mut ctx, packet).map_err(|e| Ics04Error::app_module(e.to_string()))?;
 
 		Ok(())
 	}
@@ -407,3 +485,9 @@ pub fn full_ibc_denom(packet: &Packet, mut token: PrefixedCoin) -> String {
 		token.denom.to_string()
 	}
 }
+
+/// Converts an ics20 amount to the runtime balance type, failing with
+/// [`Ics20Error::amount_overflow`] instead of truncating amounts that exceed `u128::MAX`.
+pub fn amount_to_balance<T: Config>(amount: Amount) -> Result<T::Balance, Ics20Error> {
+	Ok(u128::try_from(amount)?.into())
+}
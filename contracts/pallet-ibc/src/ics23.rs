@@ -15,3 +15,15 @@ pub mod next_seq_recv;
 pub mod next_seq_send;
 pub mod packet_commitments;
 pub mod receipts;
+
+use crate::format;
+use alloc::string::String;
+use ibc::core::ics24_host::Path;
+use sp_std::{prelude::*, str::FromStr};
+
+/// Rebuilds the [`Path`] a key yielded by a `ChildTriePrefixIterator` over `prefix` was stored
+/// under, skipping keys that do not parse.
+fn parse_path(prefix: &str, remaining_key: Vec<u8>) -> Option<Path> {
+	let path = format!("{prefix}{}", String::from_utf8(remaining_key).ok()?);
+	Path::from_str(&path).ok()
+}
@@ -1,5 +1,5 @@
 use crate::{format, Config};
-use alloc::string::{String, ToString};
+use alloc::string::ToString;
 use frame_support::storage::{child, child::ChildInfo, ChildTriePrefixIterator};
 use ibc::core::{
 	ics04_channel::channel::ChannelEnd,
@@ -10,7 +10,7 @@ use ibc::core::{
 	},
 };
 use ibc_primitives::apply_prefix;
-use sp_std::{marker::PhantomData, prelude::*, str::FromStr};
+use sp_std::{marker::PhantomData, prelude::*};
 use tendermint_proto::Protobuf;
 
 // todo: pruning
@@ -41,9 +41,8 @@ impl<T: Config> Channels<T> {
 		let key = apply_prefix(T::PALLET_PREFIX, vec![prefix.clone()]);
 		ChildTriePrefixIterator::with_prefix(&ChildInfo::new_default(T::PALLET_PREFIX), &key)
 			.filter_map(move |(key, value)| {
-				let path = format!("{prefix}{}", String::from_utf8(key).ok()?);
 				if let Path::ChannelEnds(ChannelEndsPath(port_id, channel_id)) =
-					Path::from_str(&path).ok()?
+					super::parse_path(&prefix, key)?
 				{
 					return Some((
 						port_id.as_bytes().to_vec(),
@@ -1,9 +1,9 @@
 use crate::{format, Config};
-use alloc::string::{String, ToString};
+use alloc::string::ToString;
 use frame_support::storage::{child, child::ChildInfo, ChildTriePrefixIterator};
 use ibc::core::ics24_host::{identifier::ClientId, path::ClientStatePath, Path};
 use ibc_primitives::apply_prefix;
-use sp_std::{marker::PhantomData, prelude::*, str::FromStr};
+use sp_std::{marker::PhantomData, prelude::*};
 
 /// client_id => client_states
 /// trie key path: "clients/{client_id}/clientState"
@@ -35,8 +35,9 @@ impl<T: Config> ClientStates<T> {
 		let key = apply_prefix(T::PALLET_PREFIX, vec![prefix_path.clone()]);
 		ChildTriePrefixIterator::with_prefix(&ChildInfo::new_default(T::PALLET_PREFIX), &key)
 			.filter_map(move |(remaining_key, value)| {
-				let path = format!("{prefix_path}{}", String::from_utf8(remaining_key).ok()?);
-				if let Path::ClientState(ClientStatePath(client_id)) = Path::from_str(&path).ok()? {
+				if let Path::ClientState(ClientStatePath(client_id)) =
+					super::parse_path(&prefix_path, remaining_key)?
+				{
 					return Some((client_id, value))
 				}
 				None
@@ -3,7 +3,7 @@ use alloc::string::ToString;
 use frame_support::storage::{child, child::ChildInfo, ChildTriePrefixIterator};
 use ibc::core::{
 	ics03_connection::connection::ConnectionEnd,
-	ics24_host::{identifier::ConnectionId, path::ConnectionsPath},
+	ics24_host::{identifier::ConnectionId, path::ConnectionsPath, Path},
 };
 use ibc_primitives::apply_prefix;
 use sp_std::{marker::PhantomData, prelude::*};
@@ -31,10 +31,24 @@ impl<T: Config> Connections<T> {
 		);
 	}
 
+	pub fn remove(connection_id: &ConnectionId) {
+		let connection_path = format!("{}", ConnectionsPath(connection_id.clone()));
+		let connection_key = apply_prefix(T::PALLET_PREFIX, vec![connection_path]);
+		child::kill(&ChildInfo::new_default(T::PALLET_PREFIX), &connection_key);
+	}
+
 	// WARNING: too expensive to be called from an on-chain context, only here for rpc layer.
-	pub fn iter() -> ChildTriePrefixIterator<(Vec<u8>, Vec<u8>)> {
+	pub fn iter() -> impl Iterator<Item = (Vec<u8>, Vec<u8>)> {
 		let prefix_path = "connections/".to_string();
-		let key = apply_prefix(T::PALLET_PREFIX, vec![prefix_path]);
+		let key = apply_prefix(T::PALLET_PREFIX, vec![prefix_path.clone()]);
 		ChildTriePrefixIterator::with_prefix(&ChildInfo::new_default(T::PALLET_PREFIX), &key)
+			.filter_map(move |(remaining_key, value)| {
+				if let Path::Connections(ConnectionsPath(connection_id)) =
+					super::parse_path(&prefix_path, remaining_key)?
+				{
+					return Some((connection_id.as_bytes().to_vec(), value))
+				}
+				None
+			})
 	}
 }
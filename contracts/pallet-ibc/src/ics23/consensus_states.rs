@@ -9,7 +9,7 @@ use sp_std::{marker::PhantomData, prelude::*};
 
 /// client_id, height => consensus_state
 /// trie key path: "clients/{client_id}/consensusStates/{height}"
-/// todo: only store up to 250 (height => consensus_state) per client_id
+/// Consensus states older than the client's trusting period are pruned on client updates.
 pub struct ConsensusStates<T>(PhantomData<T>);
 
 impl<T: Config> ConsensusStates<T> {
@@ -34,4 +34,15 @@ impl<T: Config> ConsensusStates<T> {
 		let key = apply_prefix(T::PALLET_PREFIX, vec![path]);
 		child::put(&ChildInfo::new_default(T::PALLET_PREFIX), &key, &consensus_state)
 	}
+
+	pub fn remove(client_id: ClientId, height: Height) {
+		let consensus_path = ClientConsensusStatePath {
+			client_id,
+			epoch: height.revision_number,
+			height: height.revision_height,
+		};
+		let path = format!("{}", consensus_path);
+		let key = apply_prefix(T::PALLET_PREFIX, vec![path]);
+		child::kill(&ChildInfo::new_default(T::PALLET_PREFIX), &key)
+	}
 }
@@ -1,5 +1,5 @@
 use crate::{format, Config};
-use alloc::string::{String, ToString};
+use alloc::string::ToString;
 use frame_support::storage::{child, child::ChildInfo, ChildTriePrefixIterator};
 use ibc::core::{
 	ics04_channel::{commitment::PacketCommitment as PacketCommitmentType, packet::Sequence},
@@ -10,7 +10,7 @@ use ibc::core::{
 	},
 };
 use ibc_primitives::apply_prefix;
-use sp_std::{marker::PhantomData, prelude::*, str::FromStr};
+use sp_std::{marker::PhantomData, prelude::*};
 
 /// (port_id, channel_id, sequence) => hash
 /// trie key path: "commitments/ports/{port_id}/channels/{channel_id}/sequences/{sequence}"
@@ -58,9 +58,8 @@ impl<T: Config> PacketCommitment<T> {
 		let prefix_key = apply_prefix(T::PALLET_PREFIX, vec![prefix.clone()]);
 		ChildTriePrefixIterator::with_prefix(&ChildInfo::new_default(T::PALLET_PREFIX), &prefix_key)
 			.filter_map(move |(remaining_key, value)| {
-				let path = format!("{prefix}{}", String::from_utf8(remaining_key).ok()?);
 				if let Path::Commitments(CommitmentsPath { port_id, channel_id, sequence }) =
-					Path::from_str(&path).ok()?
+					super::parse_path(&prefix, remaining_key)?
 				{
 					return Some(((port_id, channel_id, sequence), value))
 				}
@@ -10,7 +10,6 @@ use ibc::core::{
 use ibc_primitives::apply_prefix;
 use sp_std::{marker::PhantomData, prelude::*};
 
-// todo: pruning
 /// (port_id, channel_id, sequence) => hash
 /// trie key path: "receipts/ports/{port_id}/channels/{channel_id}/sequences/{sequence}"
 pub struct PacketReceipt<T>(PhantomData<T>);
@@ -33,12 +32,12 @@ impl<T: Config> PacketReceipt<T> {
 		child::get(&ChildInfo::new_default(T::PALLET_PREFIX), &receipt_key)
 	}
 
-	// pub fn remove((port_id, channel_id, sequence): (PortId, ChannelId, Sequence)) {
-	// 	let receipt_path = ReceiptsPath { port_id, channel_id, sequence };
-	// 	let receipt_path = format!("{}", receipt_path);
-	// 	let receipt_key = apply_prefix_and_encode(T::PALLET_PREFIX, vec![receipt_path]);
-	// 	child::kill(&ChildInfo::new_default(T::PALLET_PREFIX), &receipt_key)
-	// }
+	pub fn remove((port_id, channel_id, sequence): (PortId, ChannelId, Sequence)) {
+		let receipt_path = ReceiptsPath { port_id, channel_id, sequence };
+		let receipt_path = format!("{}", receipt_path);
+		let receipt_key = apply_prefix(T::PALLET_PREFIX, vec![receipt_path]);
+		child::kill(&ChildInfo::new_default(T::PALLET_PREFIX), &receipt_key)
+	}
 
 	pub fn contains_key((port_id, channel_id, sequence): (PortId, ChannelId, Sequence)) -> bool {
 		let receipt_path = ReceiptsPath { port_id, channel_id, sequence };
@@ -3,11 +3,13 @@ use core::time::Duration;
 
 use super::*;
 use crate::{
+	errors::IbcError,
 	events::IbcEvent,
 	ics23::{
 		acknowledgements::Acknowledgements, channels::Channels, client_states::ClientStates,
 		connections::Connections, consensus_states::ConsensusStates,
-		next_seq_recv::NextSequenceRecv, packet_commitments::PacketCommitment,
+		next_seq_ack::NextSequenceAck, next_seq_recv::NextSequenceRecv,
+		next_seq_send::NextSequenceSend, packet_commitments::PacketCommitment,
 		receipts::PacketReceipt,
 	},
 	light_clients::AnyClientState,
@@ -17,7 +19,8 @@ use codec::{Decode, Encode};
 use frame_support::traits::Currency;
 use ibc::{
 	applications::transfer::{
-		msgs::transfer::MsgTransfer, relay::send_transfer::send_transfer, PrefixedCoin,
+		context::parse_hashed_ibc_denom, msgs::transfer::MsgTransfer,
+		relay::send_transfer::send_transfer, PrefixedCoin,
 	},
 	core::{
 		ics02_client::{
@@ -25,22 +28,40 @@ use ibc::{
 		},
 		ics03_connection::context::ConnectionReader,
 		ics04_channel::{
-			channel::ChannelEnd,
+			channel::{ChannelEnd, Order, State},
+			commitment::{compute_ack_commitment, compute_packet_commitment},
 			context::{ChannelKeeper, ChannelReader},
 			error::Error as Ics04Error,
-			msgs::{chan_close_init::MsgChannelCloseInit, chan_open_init::MsgChannelOpenInit},
+			handler::{
+				verify::{verify_next_sequence_recv, verify_packet_receipt_absence},
+				write_acknowledgement,
+			},
+			msgs::{
+				chan_close_init::MsgChannelCloseInit,
+				chan_open_init::MsgChannelOpenInit,
+				recv_packet::{self, MsgRecvPacket},
+			},
 			packet::{Packet, Sequence},
 		},
+		ics05_port::context::PortReader,
+		ics23_commitment::commitment::CommitmentProofBytes,
 		ics24_host::{
 			identifier::*,
 			path::{
 				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
-				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+				CommitmentsPath, ConnectionsPath, Path, ReceiptsPath, SeqAcksPath, SeqRecvsPath,
+				SeqSendsPath,
 			},
 		},
-		ics26_routing::handler::MsgReceipt,
+		ics26_routing::{
+			context::{Ics26Context, ModuleOutputBuilder, Router},
+			error::Error as Ics26Error,
+			handler::{DispatchOutcome, MsgReceipt},
+		},
 	},
-	handler::HandlerOutputBuilder,
+	events::IbcEvent as RawIbcEvent,
+	handler::{HandlerOutput, HandlerOutputBuilder},
+	proofs::Proofs,
 	signer::Signer,
 	timestamp::Timestamp,
 	tx_msg::Msg,
@@ -52,7 +73,8 @@ use ibc_primitives::{
 	Error as IbcHandlerError, HandlerMessage, IbcHandler, IdentifiedChannel, IdentifiedClientState,
 	IdentifiedConnection, PacketInfo, PacketState, QueryChannelResponse, QueryChannelsResponse,
 	QueryClientStateResponse, QueryConnectionResponse, QueryConnectionsResponse,
-	QueryConsensusStateResponse, QueryNextSequenceReceiveResponse,
+	QueryConsensusStateResponse, QueryNextSequenceAckResponse, QueryNextSequenceReceiveResponse,
+	QueryNextSequenceSendResponse,
 	QueryPacketAcknowledgementResponse, QueryPacketAcknowledgementsResponse,
 	QueryPacketCommitmentResponse, QueryPacketCommitmentsResponse, QueryPacketReceiptResponse,
 };
@@ -68,36 +90,225 @@ pub const OFFCHAIN_SEND_PACKET_SEQS: &[u8] = b"pallet_ibc:pending_send_packet_se
 pub const OFFCHAIN_RECV_PACKET_SEQS: &[u8] = b"pallet_ibc:pending_recv_packet_sequences";
 const PACKET_CLEANUP_PER_CYCLE: u64 = 1001;
 
+/// What became of a message passed to `execute_ibc_messages`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum MessageOutcome {
+	/// The message was executed and its writes were kept.
+	Executed,
+	/// The message was valid but redundant, e.g. a packet already relayed, nothing was written.
+	NoOp,
+	/// The message was rejected, none of its writes were kept.
+	Failed,
+}
+
 impl<T: Config> Pallet<T>
 where
 	T: Send + Sync,
 	u32: From<<T as frame_system::Config>::BlockNumber>,
 	AccountId32: From<T::AccountId>,
 {
+	/// Whether every signer of `message` is `account`. Handlers use the signers to authorize
+	/// messages, e.g. channel closes and connection cancellations, so they can't be taken from the
+	/// message as is. Messages that can't be decoded are left for the handlers to reject.
+	pub(crate) fn is_signed_by(
+		message: &ibc_proto::google::protobuf::Any,
+		account: &T::AccountId,
+	) -> bool {
+		let envelope =
+			match ibc::core::ics26_routing::handler::decode::<Context<T>>(message.clone()) {
+				Ok(envelope) => envelope,
+				Err(_) => return true,
+			};
+		envelope.signers().into_iter().all(|signer| {
+			T::AccountIdConversion::try_from(signer.clone())
+				.map(|signer| signer.into_account() == *account)
+				.unwrap_or(false)
+		})
+	}
+
+	/// Executes `messages` in order and returns what became of each of them, in the same order.
 	pub(crate) fn execute_ibc_messages(
 		ctx: &mut Context<T>,
 		messages: Vec<ibc_proto::google::protobuf::Any>,
-	) {
-		let (events, logs) =
-			messages.into_iter().fold((vec![], vec![]), |(mut events, mut logs), msg| {
-				match ibc::core::ics26_routing::handler::deliver(ctx, msg) {
-					Ok(MsgReceipt { events: temp_events, log: temp_logs }) => {
-						events.extend(temp_events.into_iter().map(Ok));
+	) -> Vec<MessageOutcome> {
+		// Consecutive packets received on the same channel are handled as a batch, sharing the
+		// reads needed to verify their proofs.
+		let mut receipts = vec![];
+		let mut batch: Vec<MsgRecvPacket> = vec![];
+		for msg in messages {
+			let recv_msg = (msg.type_url == recv_packet::TYPE_URL)
+				.then(|| MsgRecvPacket::decode_vec(&msg.value).ok())
+				.flatten();
+			let same_channel = match (batch.last(), &recv_msg) {
+				(Some(last), Some(recv_msg)) =>
+					last.packet.destination_port == recv_msg.packet.destination_port &&
+						last.packet.destination_channel == recv_msg.packet.destination_channel,
+				_ => false,
+			};
+			if !same_channel {
+				receipts.extend(Self::deliver_recv_packets(ctx, core::mem::take(&mut batch)));
+			}
+			// Each message runs in its own storage transaction, so a failed one leaves no writes
+			// behind and the messages after it are still executed.
+			match recv_msg {
+				Some(recv_msg) => batch.push(recv_msg),
+				None => receipts.push(ctx.with_transaction(|ctx| {
+					ibc::core::ics26_routing::handler::deliver(ctx, msg)
+				})),
+			}
+		}
+		receipts.extend(Self::deliver_recv_packets(ctx, batch));
+
+		let mut failures = vec![];
+		let mut outcomes = Vec::with_capacity(receipts.len());
+		let (events, logs) = receipts.into_iter().enumerate().fold(
+			(vec![], vec![]),
+			|(mut events, mut logs), (index, receipt)| {
+				match receipt {
+					Ok(MsgReceipt { events: temp_events, log: temp_logs, outcome }) => {
+						outcomes.push(match outcome {
+							DispatchOutcome::Applied => MessageOutcome::Executed,
+							DispatchOutcome::NoOp => MessageOutcome::NoOp,
+						});
+						events.extend(temp_events.into_iter().map(|ev| Ok(ev.into())));
 						logs.extend(temp_logs);
 					},
 					Err(e) => {
 						log::trace!(target: "pallet_ibc", "execution error: {}", e);
-						events.push(Err(e));
+						let error = IbcError::from(e);
+						outcomes.push(MessageOutcome::Failed);
+						failures.push((index as u32, error.clone()));
+						events.push(Err(error));
 					},
 				}
 				(events, logs)
-			});
+			},
+		);
 
-		log::trace!(target: "pallet_ibc", "logs: {:#?}", logs);
+		for entry in logs.iter() {
+			log::trace!(target: "pallet_ibc", "log: {}", entry);
+		}
 		// todo: consolidate into one.
 		if !events.is_empty() {
-			Self::deposit_event(events.into())
+			Self::deposit_event(Event::<T>::Events { events })
 		};
+		for (index, error) in failures {
+			Self::deposit_event(Event::<T>::MessageFailed { index, error })
+		}
+		outcomes
+	}
+
+	fn deliver_recv_packets(
+		ctx: &mut Context<T>,
+		msgs: Vec<MsgRecvPacket>,
+	) -> Vec<Result<MsgReceipt, Ics26Error>> {
+		if msgs.is_empty() {
+			return vec![]
+		}
+		ibc::core::ics26_routing::handler::dispatch_recv_packets(ctx, msgs)
+			.into_iter()
+			.map(|output| {
+				let HandlerOutput { events, log, result: outcome } = output?;
+				Ok(MsgReceipt { events, log, outcome })
+			})
+			.collect()
+	}
+
+	/// Times out a transfer sent over a closed channel on behalf of its sender, see
+	/// [`Pallet::claim_refund`]. Unlike packets delivered through [`Pallet::deliver`], a failure
+	/// fails the whole call.
+	pub(crate) fn refund_closed_channel_packet(params: RefundParams) -> Result<(), Error<T>> {
+		let mut ctx = Context::<T>::new();
+		let port_id = PortId::transfer();
+		let channel_id = ChannelId::new(params.source_channel);
+		let channel_end = ctx
+			.channel_end(&(port_id.clone(), channel_id))
+			.map_err(|_| Error::<T>::ChannelNotFound)?;
+		if !channel_end.state_matches(&State::Closed) {
+			return Err(Error::<T>::ChannelNotClosed)
+		}
+		// The commitment is deleted once the packet is refunded, so it can only be claimed once.
+		let sequence = Sequence::from(params.sequence);
+		let commitment = ctx
+			.get_packet_commitment(&(port_id.clone(), channel_id, sequence))
+			.map_err(|_| Error::<T>::PacketCommitmentNotFound)?;
+
+		let counterparty = channel_end.counterparty();
+		let packet = Packet {
+			sequence,
+			source_port: port_id.clone(),
+			source_channel: channel_id,
+			destination_port: counterparty.port_id.clone(),
+			destination_channel: counterparty.channel_id.ok_or(Error::<T>::ChannelNotFound)?,
+			data: params.data,
+			timeout_height: Height::new(params.timeout_height.0, params.timeout_height.1),
+			timeout_timestamp: Timestamp::from_nanoseconds(params.timeout_timestamp)
+				.map_err(|_| Error::<T>::InvalidTimestamp)?,
+		};
+		let expected_commitment = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+		if commitment != expected_commitment {
+			return Err(Error::<T>::RefundFailed)
+		}
+
+		// The local channel end being closed stands in for the proof of the counterparty's
+		// closure, only the counterparty may have received the packet before the channel closed.
+		let proof_unreceived = CommitmentProofBytes::try_from(params.proof_unreceived)
+			.map_err(|_| Error::<T>::InvalidRefundProof)?;
+		let proofs = Proofs::new(
+			proof_unreceived,
+			None,
+			None,
+			None,
+			Height::new(params.proof_height.0, params.proof_height.1),
+		)
+		.map_err(|_| Error::<T>::InvalidRefundProof)?;
+		let connection_end = ctx
+			.connection_end(&channel_end.connection_hops()[0])
+			.map_err(|_| Error::<T>::ConnectionNotFound)?;
+		let unreceived = if channel_end.order_matches(&Order::Unordered) {
+			verify_packet_receipt_absence(
+				&ctx,
+				proofs.height(),
+				&connection_end,
+				packet.clone(),
+				&proofs,
+			)
+		} else {
+			verify_next_sequence_recv(
+				&ctx,
+				proofs.height(),
+				&connection_end,
+				packet.clone(),
+				sequence,
+				&proofs,
+			)
+		};
+		unreceived.map_err(|e| {
+			log::trace!(target: "pallet_ibc", "[claim_refund]: error: {}", e);
+			Error::<T>::InvalidRefundProof
+		})?;
+
+		ctx.delete_packet_commitment((port_id.clone(), channel_id, sequence))
+			.map_err(|_| Error::<T>::RefundFailed)?;
+		let module_id = ctx.lookup_module_by_port(&port_id).map_err(|_| Error::<T>::RefundFailed)?;
+		let signer = Signer::from_str(MODULE_ID).map_err(|_| Error::<T>::Other)?;
+		let mut module_output = ModuleOutputBuilder::new();
+		let ctx_clone = ctx.clone();
+		ctx.router_mut()
+			.get_route_mut(&module_id)
+			.ok_or(Error::<T>::RefundFailed)?
+			.on_timeout_packet(&ctx_clone, &mut module_output, &packet, &signer)
+			.map_err(|e| {
+				log::trace!(target: "pallet_ibc", "[claim_refund]: error: {}", e);
+				Error::<T>::RefundFailed
+			})?;
+		let events = module_output.with_result(()).events;
+		Self::deposit_event(events.into_iter().map(RawIbcEvent::from).collect::<Vec<_>>().into());
+		Ok(())
 	}
 }
 
@@ -313,29 +524,80 @@ where
 		Ok(QueryPacketCommitmentsResponse { commitments, height: host_height::<T>() })
 	}
 
+	pub fn packet_commitment_count(
+		channel_id_bytes: Vec<u8>,
+		port_id_bytes: Vec<u8>,
+	) -> Result<u64, Error<T>> {
+		let channel_id =
+			channel_id_from_bytes(channel_id_bytes).map_err(|_| Error::<T>::DecodingError)?;
+		let port_id = port_id_from_bytes(port_id_bytes).map_err(|_| Error::<T>::DecodingError)?;
+		Ok(PacketCommitmentCounter::<T>::get(
+			port_id.as_bytes().to_vec(),
+			channel_id.to_string().as_bytes().to_vec(),
+		))
+	}
+
+	/// Returns the (denom, amount) of the native tokens escrowed for transfers sent over a channel
+	pub fn escrow_balances(
+		channel_id_bytes: Vec<u8>,
+		port_id_bytes: Vec<u8>,
+	) -> Result<Vec<(Vec<u8>, u128)>, Error<T>> {
+		let channel_id =
+			channel_id_from_bytes(channel_id_bytes).map_err(|_| Error::<T>::DecodingError)?;
+		let port_id = port_id_from_bytes(port_id_bytes).map_err(|_| Error::<T>::DecodingError)?;
+		Ok(EscrowBalances::<T>::iter_prefix((
+			port_id.as_bytes().to_vec(),
+			channel_id.to_string().as_bytes().to_vec(),
+		))
+		.collect())
+	}
+
+	pub fn timed_out_packets(
+		channel_id_bytes: Vec<u8>,
+		port_id_bytes: Vec<u8>,
+	) -> Result<Vec<(u64, u64)>, Error<T>> {
+		let channel_id =
+			channel_id_from_bytes(channel_id_bytes).map_err(|_| Error::<T>::DecodingError)?;
+		let port_id = port_id_from_bytes(port_id_bytes).map_err(|_| Error::<T>::DecodingError)?;
+		Ok(TimedOutPackets::<T>::get(
+			port_id.as_bytes().to_vec(),
+			channel_id.to_string().as_bytes().to_vec(),
+		))
+	}
+
+	/// Returns the acknowledgements written on the channel, restricted to the sequences in `seqs`
+	/// when given.
 	pub fn packet_acknowledgements(
 		channel_id_bytes: Vec<u8>,
 		port_id_bytes: Vec<u8>,
+		seqs: Option<Vec<u64>>,
 	) -> Result<QueryPacketAcknowledgementsResponse, Error<T>> {
 		let channel_id = channel_id_from_bytes(channel_id_bytes.clone())
 			.map_err(|_| Error::<T>::DecodingError)?;
 		let port_id =
 			port_id_from_bytes(port_id_bytes.clone()).map_err(|_| Error::<T>::DecodingError)?;
-		let acks = Acknowledgements::<T>::iter()
-			.filter_map(|((p, c, s), ack)| {
-				if p == port_id && c == channel_id {
-					let packet_state = PacketState {
-						port_id: port_id_bytes.clone(),
-						channel_id: channel_id_bytes.clone(),
-						sequence: s.into(),
-						data: ack,
-					};
-					Some(packet_state)
-				} else {
-					None
-				}
+		let mut acks = match seqs {
+			Some(seqs) => seqs
+				.into_iter()
+				.filter_map(|seq| {
+					Acknowledgements::<T>::get((port_id.clone(), channel_id, seq.into()))
+						.map(|ack| (seq, ack))
+				})
+				.collect::<Vec<_>>(),
+			None => Acknowledgements::<T>::iter_channel(port_id, channel_id)
+				.map(|(seq, ack)| (seq.into(), ack))
+				.collect::<Vec<_>>(),
+		};
+		acks.sort_by_key(|(seq, _)| *seq);
+		let acks = acks
+			.into_iter()
+			.map(|(sequence, data)| PacketState {
+				port_id: port_id_bytes.clone(),
+				channel_id: channel_id_bytes.clone(),
+				sequence,
+				data,
 			})
-			.collect::<Vec<_>>();
+			.collect();
 		Ok(QueryPacketAcknowledgementsResponse { acks, height: host_height::<T>() })
 	}
 
@@ -386,6 +648,49 @@ where
 		Ok(QueryNextSequenceReceiveResponse { sequence, trie_key: key, height: host_height::<T>() })
 	}
 
+	pub fn next_seq_ack(
+		channel_id: Vec<u8>,
+		port_id: Vec<u8>,
+	) -> Result<QueryNextSequenceAckResponse, Error<T>> {
+		let port_id = port_id_from_bytes(port_id).map_err(|_| Error::<T>::DecodingError)?;
+		let channel_id =
+			channel_id_from_bytes(channel_id).map_err(|_| Error::<T>::DecodingError)?;
+		let sequence = NextSequenceAck::<T>::get(port_id.clone(), channel_id)
+			.ok_or(Error::<T>::SendPacketError)?;
+		let next_seq_ack_path = format!("{}", SeqAcksPath(port_id, channel_id));
+		let key = apply_prefix(T::PALLET_PREFIX, vec![next_seq_ack_path]);
+
+		Ok(QueryNextSequenceAckResponse { sequence, trie_key: key, height: host_height::<T>() })
+	}
+
+	pub fn next_seq_send(
+		channel_id: Vec<u8>,
+		port_id: Vec<u8>,
+	) -> Result<QueryNextSequenceSendResponse, Error<T>> {
+		let port_id = port_id_from_bytes(port_id).map_err(|_| Error::<T>::DecodingError)?;
+		let channel_id =
+			channel_id_from_bytes(channel_id).map_err(|_| Error::<T>::DecodingError)?;
+		let sequence = NextSequenceSend::<T>::get(port_id.clone(), channel_id)
+			.ok_or(Error::<T>::SendPacketError)?;
+		let next_seq_send_path = format!("{}", SeqSendsPath(port_id, channel_id));
+		let key = apply_prefix(T::PALLET_PREFIX, vec![next_seq_send_path]);
+
+		Ok(QueryNextSequenceSendResponse { sequence, trie_key: key, height: host_height::<T>() })
+	}
+
+	/// Child trie keys of the given ICS-024 paths, for building a proof of any of the values the
+	/// pallet commits to. Fails if one of the paths is not a valid ICS-024 path.
+	pub fn commitment_keys(paths: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, Error<T>> {
+		paths
+			.into_iter()
+			.map(|path| {
+				let path = String::from_utf8(path).map_err(|_| Error::<T>::Utf8Error)?;
+				let path = Path::from_str(&path).map_err(|_| Error::<T>::DecodingError)?;
+				Ok(apply_prefix(T::PALLET_PREFIX, vec![path.to_string()]))
+			})
+			.collect()
+	}
+
 	pub fn packet_commitment(
 		channel_id: Vec<u8>,
 		port_id: Vec<u8>,
@@ -714,6 +1019,14 @@ where
 			.nanoseconds();
 		Ok((update_height.revision_height, update_time))
 	}
+
+	/// Returns whether the client is active, expired or frozen
+	pub fn client_status(client_id: Vec<u8>) -> Result<ClientStatus, Error<T>> {
+		let ctx = Context::<T>::default();
+		let client_id = client_id_from_bytes(client_id).map_err(|_| Error::<T>::DecodingError)?;
+		ibc::core::ics02_client::handler::client_status(&ctx, &client_id)
+			.map_err(|_| Error::<T>::ClientStateNotFound)
+	}
 }
 
 impl<T: Config> Pallet<T> {
@@ -764,6 +1077,21 @@ impl<T: Config> Pallet<T> {
 		})
 	}
 
+	/// Returns the denom trace stored for a hash, which can be given either as the hex encoded
+	/// hash or as the `ibc/{hash}` voucher denomination
+	pub fn get_denom_trace_by_hash(
+		hash: Vec<u8>,
+	) -> Option<ibc_primitives::QueryDenomTraceResponse> {
+		let hash = String::from_utf8(hash).ok()?;
+		let hash = if hash.contains('/') {
+			parse_hashed_ibc_denom(&hash).ok()?
+		} else {
+			hash.to_uppercase()
+		};
+		DenomTraces::<T>::get(hash.as_bytes().to_vec())
+			.map(|denom| ibc_primitives::QueryDenomTraceResponse { denom })
+	}
+
 	pub fn get_denom_traces(
 		key: Option<Either<T::AssetId, u32>>,
 		limit: u64,
@@ -791,6 +1119,19 @@ where
 		Pallet::<T>::latest_height_and_timestamp(port_id, channel_id)
 	}
 
+	fn next_sequence_send(
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<u64, IbcHandlerError> {
+		let ctx = Context::<T>::new();
+		let sequence =
+			ctx.get_next_sequence_send(&(port_id.clone(), *channel_id))
+				.map_err(|_| IbcHandlerError::SendPacketError {
+					msg: Some(format!("Failed to get next_sequence_send for {}", channel_id)),
+				})?;
+		Ok(sequence.into())
+	}
+
 	fn handle_message(msg: HandlerMessage<T::AccountId>) -> Result<(), IbcHandlerError> {
 		match msg {
 			HandlerMessage::OpenChannel { port_id, channel_end } =>
@@ -815,7 +1156,7 @@ where
 		.map_err(|e| IbcHandlerError::AcknowledgementError {
 			msg: Some(format!("Failed to store acknowledgement off chain {:?}", e)),
 		})?;
-		let ack = ctx.ack_commitment(ack.into());
+		let ack = compute_ack_commitment(&ack.into());
 		ctx.store_packet_acknowledgement(
 			(packet.destination_port.clone(), packet.destination_channel, packet.sequence),
 			ack,
@@ -835,6 +1176,36 @@ where
 		Ok(())
 	}
 
+	fn write_async_acknowledgement(
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: Vec<u8>,
+	) -> Result<(), IbcHandlerError> {
+		let ctx = Context::<T>::default();
+		let channel_end = ctx
+			.channel_end(&(port_id.clone(), *channel_id))
+			.map_err(|e| IbcHandlerError::ChannelOrPortError { msg: Some(e.to_string()) })?;
+		// The identifiers are all the core checks and the emitted event need from the packet.
+		let counterparty = channel_end.counterparty();
+		let source_channel = counterparty.channel_id.ok_or_else(|| {
+			IbcHandlerError::ChannelOrPortError {
+				msg: Some("Counterparty channel id is missing".to_string()),
+			}
+		})?;
+		let packet = Packet {
+			sequence,
+			source_port: counterparty.port_id.clone(),
+			source_channel,
+			destination_port: port_id.clone(),
+			destination_channel: *channel_id,
+			..Default::default()
+		};
+		write_acknowledgement::process(&ctx, packet.clone(), ack.clone())
+			.map_err(|e| IbcHandlerError::WriteAcknowledgementError { msg: Some(e.to_string()) })?;
+		<Self as IbcHandler<T::AccountId>>::write_acknowledgement(&packet, ack)
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	fn create_client() -> Result<ClientId, IbcHandlerError> {
 		use crate::{
@@ -1067,12 +1438,14 @@ where
 			source_port: PortId::transfer(),
 			source_channel: channel_id,
 			token: coin,
+			tokens: vec![],
 			sender: Signer::from_str(&from).map_err(|_| IbcHandlerError::SendTransferError {
 				msg: Some("Failed to deriver signer from String".to_string()),
 			})?,
 			receiver: to,
 			timeout_height,
 			timeout_timestamp,
+			memo: String::new(),
 		};
 		Ok(msg)
 	}
@@ -55,7 +55,9 @@ mod ics23;
 pub mod light_clients;
 mod port;
 pub mod routing;
+mod wasm;
 pub use client::HostConsensusProof;
+pub use ibc::core::ics02_client::client_state::ClientStatus;
 pub use ibc_primitives::Timeout;
 pub use light_client_common;
 
@@ -119,6 +121,30 @@ pub struct TransferParams<AccountId> {
 	pub source_channel: u64,
 	/// Timeout for this packet
 	pub timeout: Timeout,
+	/// Optional utf8 memo carried in the packet data
+	pub memo: Option<Vec<u8>>,
+}
+
+/// A transfer sent over a channel whose closing handshake has completed.
+#[derive(
+	frame_support::RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo, Encode, Decode, Clone,
+)]
+pub struct RefundParams {
+	/// Source channel identifier on host chain
+	pub source_channel: u64,
+	/// Sequence of the packet to refund
+	pub sequence: u64,
+	/// Json encoded packet data the transfer was sent with
+	pub data: Vec<u8>,
+	/// Timeout height the transfer was sent with, as (revision_number, revision_height)
+	pub timeout_height: (u64, u64),
+	/// Timeout timestamp the transfer was sent with, in nanoseconds
+	pub timeout_timestamp: u64,
+	/// Proof that the counterparty never received the packet: the absence of its receipt on
+	/// unordered channels, the counterparty's next receive sequence on ordered ones
+	pub proof_unreceived: Vec<u8>,
+	/// Counterparty height the proof was generated at, as (revision_number, revision_height)
+	pub proof_height: (u64, u64),
 }
 
 pub enum LightClientProtocol {
@@ -134,6 +160,7 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
+mod housekeeping;
 mod impls;
 pub mod weight;
 
@@ -168,11 +195,15 @@ pub mod pallet {
 			ics02_client::context::{ClientKeeper, ClientReader},
 			ics04_channel::context::ChannelReader,
 			ics24_host::identifier::{ChannelId, PortId},
+			ics26_routing::context::ModuleId,
 		},
 		timestamp::Timestamp,
 		Height,
 	};
-	use ibc_primitives::{client_id_from_bytes, get_channel_escrow_address, IbcHandler};
+	use ibc_primitives::{
+		channel_id_from_bytes, client_id_from_bytes, get_channel_escrow_address,
+		port_id_from_bytes, IbcHandler,
+	};
 	use light_clients::AnyClientState;
 	use sp_runtime::{
 		traits::{IdentifyAccount, Saturating},
@@ -220,6 +251,23 @@ pub mod pallet {
 		/// accepted. Ensure that this is non-zero in production as it's a critical vulnerability.
 		#[pallet::constant]
 		type MinimumConnectionDelay: Get<u64>;
+		/// Number of blocks after which anyone may cancel a connection handshake that has not
+		/// progressed past `TryOpen`.
+		#[pallet::constant]
+		type ConnectionHandshakeTimeout: Get<u64>;
+		/// Number of the most recent consensus states of each client kept when the older ones are
+		/// pruned at the end of the block
+		#[pallet::constant]
+		type ConsensusStateRetention: Get<u32>;
+		/// Whether processed packet timeouts are recorded in storage for later inspection
+		#[pallet::constant]
+		type PersistTimeouts: Get<bool>;
+		/// Maximum number of timeout records kept per channel, the oldest are pruned first
+		#[pallet::constant]
+		type MaxTimedOutPackets: Get<u32>;
+		/// Number of blocks over which the per asset transfer rate limits apply
+		#[pallet::constant]
+		type TransferRateLimitPeriod: Get<u32>;
 		/// ParaId of the runtime
 		type ParaId: Get<ParaId>;
 		/// Relay chain this runtime is attached to
@@ -265,6 +313,25 @@ pub mod pallet {
 	#[allow(clippy::disallowed_types)]
 	pub type PacketCounter<T: Config> = StorageValue<_, u32, ValueQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id, channel_id => number of outstanding packet commitments
+	pub type PacketCommitmentCounter<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, Vec<u8>, u64, ValueQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id, channel_id => (sequence, timestamp) of the latest timed out packets, oldest first
+	pub type TimedOutPackets<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		Vec<(u64, u64)>,
+		ValueQuery,
+	>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	/// connection_identifier => Vec<(port_id, channel_id)>
@@ -297,6 +364,54 @@ pub mod pallet {
 	pub type ConnectionClient<T: Config> =
 		StorageMap<_, Blake2_128Concat, Vec<u8>, Vec<Vec<u8>>, ValueQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// connection_id => (signer, host height) of the account that started the handshake
+	pub type ConnectionHandshakeOrigin<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, (Vec<u8>, Vec<u8>), OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id, channel_id => lowest sequence whose receipt and acknowledgement are not pruned
+	pub type PruningSequenceStart<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, Vec<u8>, u64, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id => account allowed to initiate the closing handshake of channels on that port
+	pub type ChannelCloseAuthority<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, T::AccountId, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id => account allowed to propose and cancel upgrades of channels on that port
+	pub type ChannelUpgradeAuthority<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, T::AccountId, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// account allowed to substitute frozen or expired clients
+	pub type ClientRecoveryAuthority<T: Config> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id, channel_id => (replaced upgrade fields, host height) of the upgrade in progress
+	pub type ChannelUpgradeOrigin<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		(Vec<u8>, Vec<u8>),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// port_id => module_id the channels and packets of the port are routed to
+	pub type PortBindings<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, Vec<u8>, OptionQuery>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	/// Pallet Params used to disable sending or receipt of ibc tokens
@@ -314,6 +429,54 @@ pub mod pallet {
 	pub type IbcDenoms<T: Config> =
 		CountedStorageMap<_, Twox64Concat, Vec<u8>, T::AssetId, OptionQuery>;
 
+	#[pallet::storage]
+	/// asset_id => maximum amount that can be sent or received over a channel in each
+	/// `TransferRateLimitPeriod`
+	pub type TransferRateLimits<T: Config> =
+		StorageMap<_, Twox64Concat, T::AssetId, T::Balance, OptionQuery>;
+
+	#[pallet::storage]
+	/// (channel_id, asset_id) => (period, amount sent, amount received) for rate limited assets
+	pub type TransferRateUsage<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Twox64Concat,
+		T::AssetId,
+		(u32, T::Balance, T::Balance),
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	/// (port_id, channel_id) => denom => amount of the native tokens held in the channel's escrow
+	/// account for transfers sent over it
+	pub type EscrowBalances<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(Vec<u8>, Vec<u8>),
+		Blake2_128Concat,
+		Vec<u8>,
+		u128,
+		ValueQuery,
+	>;
+
+	#[pallet::storage]
+	/// Map of denom trace hashes to the full ibc denom path of the vouchers received by the
+	/// transfer module, (Vec<u8>, Vec<u8>), both represented as utf8 string bytes
+	pub type DenomTraces<T: Config> =
+		CountedStorageMap<_, Blake2_128Concat, Vec<u8>, Vec<u8>, OptionQuery>;
+
+	#[pallet::storage]
+	/// (port_id, channel_id, sequence) of a packet sent while forwarding a transfer => json encoded
+	/// in flight forward, removed once the forwarded packet is acknowledged or times out
+	pub type InFlightForwards<T: Config> =
+		StorageMap<_, Blake2_128Concat, (Vec<u8>, Vec<u8>, u64), Vec<u8>, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// sha256 checksum => wasm code of a light client contract governance allowed clients to run
+	pub type WasmCodes<T: Config> = StorageMap<_, Identity, Vec<u8>, Vec<u8>, OptionQuery>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	/// ChannelIds open from this module
@@ -374,6 +537,8 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// Events emitted by the ibc subsystem
 		Events { events: Vec<Result<events::IbcEvent, errors::IbcError>> },
+		/// The message at `index` of a `deliver` call failed, none of its writes were applied
+		MessageFailed { index: u32, error: errors::IbcError },
 		/// An Ibc token transfer has been started
 		TokenTransferInitiated {
 			from: Vec<u8>,
@@ -384,11 +549,24 @@ pub mod pallet {
 			is_sender_source: bool,
 			source_channel: Vec<u8>,
 			destination_channel: Vec<u8>,
+			memo: Vec<u8>,
 		},
 		/// A channel has been opened
 		ChannelOpened { channel_id: Vec<u8>, port_id: Vec<u8> },
 		/// Pallet params updated
 		ParamsUpdated { send_enabled: bool, receive_enabled: bool },
+		/// The account allowed to close channels on a port has been updated
+		ChannelCloseAuthoritySet { port_id: Vec<u8>, authority: Option<T::AccountId> },
+		/// The account allowed to upgrade channels on a port has been updated
+		ChannelUpgradeAuthoritySet { port_id: Vec<u8>, authority: Option<T::AccountId> },
+		/// The account allowed to recover clients has been updated
+		ClientRecoveryAuthoritySet { authority: Option<T::AccountId> },
+		/// A port has been bound to a module
+		PortBound { port_id: Vec<u8>, module_id: Vec<u8> },
+		/// The transfer rate limit of an asset has been updated
+		TransferRateLimitSet { asset_id: T::AssetId, limit: Option<T::Balance> },
+		/// The tokens of a transfer sent over a closed channel have been refunded
+		RefundClaimed { source_channel: Vec<u8>, sequence: u64 },
 		/// An outgoing Ibc token transfer has been completed and burnt
 		TokenTransferCompleted {
 			from: Vec<u8>,
@@ -410,6 +588,7 @@ pub mod pallet {
 			is_receiver_source: bool,
 			source_channel: Vec<u8>,
 			destination_channel: Vec<u8>,
+			memo: Vec<u8>,
 		},
 		/// Ibc transfer failed, received an acknowledgement error, tokens have been refunded
 		TokenTransferFailed {
@@ -428,8 +607,16 @@ pub mod pallet {
 		ClientUpgradeSet,
 		/// Client has been frozen
 		ClientFrozen { client_id: Vec<u8>, height: u64, revision_number: u64 },
+		/// Client has been unfrozen
+		ClientUnfrozen { client_id: Vec<u8> },
 		/// Asset Admin Account Updated
 		AssetAdminUpdated { admin_account: T::AccountId },
+		/// Wasm light client code has been stored under its checksum
+		WasmCodeStored { code_hash: Vec<u8> },
+		/// The symbol and decimals of an ibc asset have been updated
+		AssetMetadataUpdated { asset_id: T::AssetId, symbol: Vec<u8>, decimals: u8 },
+		/// Expired state has been pruned with the weight left at the end of the block
+		HousekeepingPerformed { consensus_states_pruned: u32, connections_cancelled: u32 },
 	}
 
 	/// Errors inform users that something went wrong.
@@ -497,6 +684,24 @@ pub mod pallet {
 		ClientUpdateNotFound,
 		/// Error Freezing client
 		ClientFreezeFailed,
+		/// Channel has not been closed
+		ChannelNotClosed,
+		/// Proof of the refunded packet's non-receipt is missing or invalid
+		InvalidRefundProof,
+		/// The refund of a packet was not successfully processed
+		RefundFailed,
+		/// Wasm code is not a valid wasm module or is too large
+		InvalidWasmCode,
+		/// Wasm code with the same checksum is already stored
+		WasmCodeAlreadyStored,
+		/// Client is not frozen
+		ClientNotFrozen,
+		/// Failed to update the metadata of an ibc asset
+		AssetMetadataUpdateFailed,
+		/// Port is already bound or the module has no route
+		PortBindingFailed,
+		/// A message is signed by another account than the one submitting it
+		SignerMismatch,
 	}
 
 	#[pallet::hooks]
@@ -506,6 +711,10 @@ pub mod pallet {
 		T: Send + Sync,
 		AccountId32: From<T::AccountId>,
 	{
+		fn on_idle(_n: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+			Pallet::<T>::housekeeping(remaining_weight)
+		}
+
 		fn offchain_worker(_n: BlockNumberFor<T>) {
 			let _ = Pallet::<T>::packet_cleanup();
 		}
@@ -523,19 +732,23 @@ pub mod pallet {
 	{
 		#[pallet::weight(crate::weight::deliver::< T > (messages))]
 		#[frame_support::transactional]
-		pub fn deliver(origin: OriginFor<T>, messages: Vec<Any>) -> DispatchResult {
+		pub fn deliver(origin: OriginFor<T>, messages: Vec<Any>) -> DispatchResultWithPostInfo {
 			use ibc::core::{
 				ics02_client::msgs::create_client, ics03_connection::msgs::conn_open_init,
 			};
 			let sender = ensure_signed(origin)?;
+			let message_count = messages.len();
 
 			// reserve a fixed deposit for every client and connection created
 			// so people don't spam our chain with useless clients.
 			let mut ctx = routing::Context::<T>::new();
 			let mut reserve_count = 0u128;
-			let messages = messages
+			let (messages, weights): (Vec<_>, Vec<_>) = messages
 				.into_iter()
 				.filter_map(|message| {
+					// Weighed before any message is executed, as executing them changes the
+					// storage the weights are read from.
+					let weight = crate::weight::message_weight::<T>(&message);
 					let type_url = String::from_utf8(message.type_url.clone()).ok()?;
 					if matches!(
 						type_url.as_str(),
@@ -544,9 +757,16 @@ pub mod pallet {
 						reserve_count += 1;
 					}
 
-					Some(Ok(ibc_proto::google::protobuf::Any { type_url, value: message.value }))
+					let message =
+						ibc_proto::google::protobuf::Any { type_url, value: message.value };
+					if !Self::is_signed_by(&message, &sender) {
+						return Some(Err(Error::<T>::SignerMismatch))
+					}
+					Some(Ok((message, weight)))
 				})
-				.collect::<Result<Vec<ibc_proto::google::protobuf::Any>, Error<T>>>()?;
+				.collect::<Result<Vec<_>, Error<T>>>()?
+				.into_iter()
+				.unzip();
 			let reserve_amt = T::SpamProtectionDeposit::get().saturating_mul(reserve_count.into());
 
 			if reserve_amt >= T::SpamProtectionDeposit::get() {
@@ -555,9 +775,20 @@ pub mod pallet {
 					reserve_amt.into(),
 				)?;
 			}
-			Self::execute_ibc_messages(&mut ctx, messages);
+			let outcomes = Self::execute_ibc_messages(&mut ctx, messages);
+
+			// The call is charged up front as if every message it can classify is executed, and
+			// the worst case for the others. Refund what wasn't used: messages that failed or
+			// changed nothing, like those dropped for their type url, only weigh their rejection.
+			let dropped = message_count - weights.len();
+			let rejected = <T as Config>::WeightInfo::reject_unknown_message()
+				.saturating_mul(dropped as Weight);
+			let consumed =
+				weights.into_iter().zip(outcomes).fold(rejected, |acc, (weight, outcome)| {
+					acc.saturating_add(crate::weight::consumed_message_weight::<T>(weight, outcome))
+				});
 
-			Ok(())
+			Ok(Some(consumed).into())
 		}
 
 		#[frame_support::transactional]
@@ -595,6 +826,12 @@ pub mod pallet {
 			let ibc_amount = Amount::from_str(&format!("{:?}", amount))
 				.map_err(|_| Error::<T>::InvalidAmount)?;
 			let coin = PrefixedCoin { denom, amount: ibc_amount };
+			let memo = params
+				.memo
+				.map(String::from_utf8)
+				.transpose()
+				.map_err(|_| Error::<T>::Utf8Error)?
+				.unwrap_or_default();
 			let source_channel = ChannelId::new(params.source_channel);
 			let source_port = PortId::transfer();
 			let (latest_height, latest_timestamp) =
@@ -630,10 +867,12 @@ pub mod pallet {
 				source_port,
 				source_channel: source_channel.clone(),
 				token: coin.clone(),
+				tokens: vec![],
 				sender: Signer::from_str(&from).map_err(|_| Error::<T>::Utf8Error)?,
 				receiver: Signer::from_str(&to).map_err(|_| Error::<T>::Utf8Error)?,
 				timeout_height,
 				timeout_timestamp,
+				memo: memo.clone(),
 			};
 			let is_sender_source = is_sender_chain_source(
 				msg.source_port.clone(),
@@ -672,10 +911,7 @@ pub mod pallet {
 				from: from.as_bytes().to_vec(),
 				to: to.as_bytes().to_vec(),
 				amount,
-				local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-					&coin.denom.to_string(),
-				)
-				.ok(),
+				local_asset_id: T::IbcDenomToAssetIdConversion::lookup(&coin.denom.to_string()),
 				ibc_denom: coin.denom.to_string().as_bytes().to_vec(),
 				is_sender_source,
 				source_channel: source_channel.to_string().as_bytes().to_vec(),
@@ -686,6 +922,26 @@ pub mod pallet {
 					.to_string()
 					.as_bytes()
 					.to_vec(),
+				memo: memo.into_bytes(),
+			});
+			Ok(())
+		}
+
+		/// Refund a transfer left pending when its channel was closed. The local channel end is
+		/// closed already, so no proof of the counterparty's closure is needed, but the
+		/// counterparty may have received the packet before closing, so a proof of its
+		/// non-receipt is required.
+		#[frame_support::transactional]
+		#[pallet::weight(<T as Config>::WeightInfo::on_timeout_packet())]
+		pub fn claim_refund(origin: OriginFor<T>, params: RefundParams) -> DispatchResult {
+			ensure_signed(origin)?;
+			let source_channel = ChannelId::new(params.source_channel);
+			let sequence = params.sequence;
+			Pallet::<T>::refund_closed_channel_packet(params)?;
+
+			Self::deposit_event(Event::<T>::RefundClaimed {
+				source_channel: source_channel.to_string().as_bytes().to_vec(),
+				sequence,
 			});
 			Ok(())
 		}
@@ -701,6 +957,131 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Restrict initiating the closing handshake of channels on `port_id` to `authority`.
+		/// Passing `None` allows any signer to close them again.
+		#[pallet::weight(0)]
+		pub fn set_channel_close_authority(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			authority: Option<T::AccountId>,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let port = String::from_utf8(port_id.clone()).map_err(|_| Error::<T>::Utf8Error)?;
+			PortId::from_str(&port).map_err(|_| Error::<T>::InvalidPortId)?;
+			match &authority {
+				Some(account) =>
+					ChannelCloseAuthority::<T>::insert(port_id.clone(), account.clone()),
+				None => ChannelCloseAuthority::<T>::remove(port_id.clone()),
+			}
+			Self::deposit_event(Event::<T>::ChannelCloseAuthoritySet { port_id, authority });
+			Ok(())
+		}
+
+		/// Allow `authority` to propose and cancel upgrades of channels on `port_id`. Passing
+		/// `None` stops channels on the port from being upgraded.
+		#[pallet::weight(0)]
+		pub fn set_channel_upgrade_authority(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			authority: Option<T::AccountId>,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let port = String::from_utf8(port_id.clone()).map_err(|_| Error::<T>::Utf8Error)?;
+			PortId::from_str(&port).map_err(|_| Error::<T>::InvalidPortId)?;
+			match &authority {
+				Some(account) =>
+					ChannelUpgradeAuthority::<T>::insert(port_id.clone(), account.clone()),
+				None => ChannelUpgradeAuthority::<T>::remove(port_id.clone()),
+			}
+			Self::deposit_event(Event::<T>::ChannelUpgradeAuthoritySet { port_id, authority });
+			Ok(())
+		}
+
+		/// Allow `authority` to substitute frozen or expired clients with `MsgRecoverClient`.
+		/// Passing `None` disables client recovery.
+		#[pallet::weight(0)]
+		pub fn set_client_recovery_authority(
+			origin: OriginFor<T>,
+			authority: Option<T::AccountId>,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			match &authority {
+				Some(account) => ClientRecoveryAuthority::<T>::put(account.clone()),
+				None => ClientRecoveryAuthority::<T>::kill(),
+			}
+			Self::deposit_event(Event::<T>::ClientRecoveryAuthoritySet { authority });
+			Ok(())
+		}
+
+		/// Bind `port_id` to the module registered in the router under `module_id`, so that
+		/// channels can be opened on the port without a runtime upgrade.
+		#[pallet::weight(0)]
+		pub fn bind_port(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			module_id: Vec<u8>,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let port = String::from_utf8(port_id.clone()).map_err(|_| Error::<T>::Utf8Error)?;
+			let port = PortId::from_str(&port).map_err(|_| Error::<T>::InvalidPortId)?;
+			let module =
+				String::from_utf8(module_id.clone()).map_err(|_| Error::<T>::Utf8Error)?;
+			let module = ModuleId::from_str(&module).map_err(|_| Error::<T>::InvalidRoute)?;
+			let mut ctx = routing::Context::<T>::default();
+			ibc::core::ics26_routing::handler::bind_port(&mut ctx, port, module).map_err(|e| {
+				log::trace!(target: "pallet_ibc", "[bind_port]: error: {:?}", e);
+				Error::<T>::PortBindingFailed
+			})?;
+			Self::deposit_event(Event::<T>::PortBound { port_id, module_id });
+			Ok(())
+		}
+
+		/// Limit the amount of `asset_id` that can be sent or received over each channel in a
+		/// `TransferRateLimitPeriod`. Passing `None` removes the limit.
+		#[pallet::weight(0)]
+		pub fn set_transfer_rate_limit(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			limit: Option<T::Balance>,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			match limit {
+				Some(limit) => TransferRateLimits::<T>::insert(asset_id, limit),
+				None => TransferRateLimits::<T>::remove(asset_id),
+			}
+			Self::deposit_event(Event::<T>::TransferRateLimitSet { asset_id, limit });
+			Ok(())
+		}
+
+		/// Write the acknowledgement of a packet received on `channel_id` on behalf of a module
+		/// that deferred it. Modules write their own through
+		/// `IbcHandler::write_async_acknowledgement`, this is the fallback for those that cannot.
+		/// Fails if the packet was not received or is already acknowledged.
+		#[pallet::weight(<T as Config>::WeightInfo::write_async_acknowledgement())]
+		pub fn write_async_acknowledgement(
+			origin: OriginFor<T>,
+			port_id: Vec<u8>,
+			channel_id: Vec<u8>,
+			sequence: u64,
+			ack: Vec<u8>,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let port_id = port_id_from_bytes(port_id).map_err(|_| Error::<T>::InvalidPortId)?;
+			let channel_id =
+				channel_id_from_bytes(channel_id).map_err(|_| Error::<T>::InvalidChannelId)?;
+			<Pallet<T> as IbcHandler<T::AccountId>>::write_async_acknowledgement(
+				&port_id,
+				&channel_id,
+				sequence.into(),
+				ack,
+			)
+			.map_err(|e| {
+				log::trace!(target: "pallet_ibc", "[write_async_acknowledgement]: error: {:?}", e);
+				Error::<T>::WriteAckError
+			})?;
+			Ok(())
+		}
+
 		/// We write the consensus & client state under these predefined paths so that
 		/// we can produce state proofs of the values to connected chains
 		/// in order to execute client upgrades.
@@ -718,6 +1099,31 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Allow wasm light clients to run `code`, which is stored under its sha256 checksum.
+		#[pallet::weight(0)]
+		pub fn store_wasm_code(origin: OriginFor<T>, code: Vec<u8>) -> DispatchResult {
+			use ics08_wasm::{
+				context::WasmCodeKeeper, error::ErrorDetail, handler::store_code,
+				msg::MsgStoreCode,
+			};
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let mut ctx = routing::Context::<T>::default();
+			let msg = MsgStoreCode {
+				code,
+				signer: Signer::from_str(MODULE_ID).map_err(|_| Error::<T>::Utf8Error)?,
+			};
+			let result = store_code(&ctx, msg).map_err(|e| match e.detail() {
+				ErrorDetail::CodeAlreadyStored(_) => Error::<T>::WasmCodeAlreadyStored,
+				_ => Error::<T>::InvalidWasmCode,
+			})?;
+			let code_hash = result.code_hash.clone();
+			ctx.store_wasm_code(result.code_hash, result.code)
+				.map_err(|_| Error::<T>::InvalidWasmCode)?;
+
+			Self::deposit_event(Event::<T>::WasmCodeStored { code_hash });
+			Ok(())
+		}
+
 		/// Freeze a client at a specific height
 		#[pallet::weight(0)]
 		pub fn freeze_client(
@@ -756,6 +1162,14 @@ pub mod pallet {
 							.map_err(|_| Error::<T>::ClientFreezeFailed)?,
 					)
 				},
+				AnyClientState::Ethereum(ethereum) => {
+					let latest_height = ethereum.latest_height();
+					AnyClientState::wrap(
+						&ethereum
+							.with_frozen_height(Height::new(latest_height.revision_number, height))
+							.map_err(|_| Error::<T>::ClientFreezeFailed)?,
+					)
+				},
 				#[cfg(test)]
 				AnyClientState::Mock(mut ms) => {
 					ms.frozen_height =
@@ -776,6 +1190,60 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Set the symbol and decimals wallets show for an asset registered for an ibc denom.
+		#[pallet::weight(0)]
+		pub fn set_asset_metadata(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			symbol: Vec<u8>,
+			decimals: u8,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			if !IbcAssetIds::<T>::contains_key(asset_id) {
+				return Err(Error::<T>::InvalidAssetId.into())
+			}
+			T::IbcDenomToAssetIdConversion::set_asset_metadata(asset_id, symbol.clone(), decimals)
+				.map_err(|_| Error::<T>::AssetMetadataUpdateFailed)?;
+			Self::deposit_event(Event::<T>::AssetMetadataUpdated { asset_id, symbol, decimals });
+			Ok(())
+		}
+
+		/// Unfreeze a client that was frozen by [`Pallet::freeze_client`] or misbehaviour, once
+		/// its counterparty has recovered.
+		#[pallet::weight(0)]
+		pub fn unfreeze_client(origin: OriginFor<T>, client_id: Vec<u8>) -> DispatchResult {
+			use ibc::core::ics02_client::client_state::ClientState;
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let client_id =
+				client_id_from_bytes(client_id).map_err(|_| Error::<T>::DecodingError)?;
+			let mut ctx = routing::Context::<T>::default();
+			let client_state =
+				ctx.client_state(&client_id).map_err(|_| Error::<T>::ClientStateNotFound)?;
+			if !client_state.is_frozen() {
+				return Err(Error::<T>::ClientNotFrozen.into())
+			}
+			let unfrozen_state = match client_state {
+				AnyClientState::Grandpa(grandpa) => AnyClientState::wrap(&grandpa.unfreeze()),
+				AnyClientState::Beefy(beefy) => AnyClientState::wrap(&beefy.unfreeze()),
+				AnyClientState::Tendermint(tm) => AnyClientState::wrap(&tm.unfreeze()),
+				AnyClientState::Ethereum(ethereum) => AnyClientState::wrap(&ethereum.unfreeze()),
+				#[cfg(test)]
+				AnyClientState::Mock(mut ms) => {
+					ms.frozen_height = None;
+					AnyClientState::wrap(&ms)
+				},
+			}
+			.ok_or_else(|| Error::<T>::ClientFreezeFailed)?;
+			ctx.store_client_state(client_id.clone(), unfrozen_state)
+				.map_err(|_| Error::<T>::ClientFreezeFailed)?;
+
+			Self::deposit_event(Event::<T>::ClientUnfrozen {
+				client_id: client_id.as_bytes().to_vec(),
+			});
+
+			Ok(())
+		}
 	}
 }
 
@@ -795,12 +1263,21 @@ pub trait DenomToAssetId<T: Config> {
 	/// Get the equivalent asset id for this ibc denom
 	/// **Note**
 	/// This function should create and register an asset with a valid metadata
-	/// if an asset does not exist for this denom
+	/// if an asset does not exist for this denom, recording the pair in [`IbcDenoms`] and
+	/// [`IbcAssetIds`]
 	fn from_denom_to_asset_id(denom: &String) -> Result<T::AssetId, Self::Error>;
 
+	/// Get the asset id registered for this ibc denom, without registering one
+	fn lookup(denom: &String) -> Option<T::AssetId> {
+		IbcDenoms::<T>::get(denom.as_bytes())
+	}
+
 	/// Return full denom for given asset id
 	fn from_asset_id_to_denom(id: T::AssetId) -> Option<String>;
 
+	/// Set the symbol and decimals shown for a registered ibc asset, its name stays the denom
+	fn set_asset_metadata(id: T::AssetId, symbol: Vec<u8>, decimals: u8) -> Result<(), Self::Error>;
+
 	/// Returns `IbcAssets` containing a list of assets bound by `limit`.
 	/// `start_key` is either an `AssetId` or an offset to start from.
 	fn ibc_assets(start_key: Option<Either<T::AssetId, u32>>, limit: u64) -> IbcAssets<T::AssetId>;
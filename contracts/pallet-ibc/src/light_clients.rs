@@ -5,7 +5,10 @@ use frame_support::{
 };
 use ibc::core::{
 	ics02_client,
-	ics02_client::{client_consensus::ConsensusState, client_state::ClientState},
+	ics02_client::{
+		client_consensus::ConsensusState,
+		client_state::{ClientState, ClientType},
+	},
 };
 use ibc_derive::{ClientDef, ClientMessage, ClientState, ConsensusState, Protobuf};
 use ibc_primitives::runtime_interface;
@@ -19,6 +22,10 @@ use ics11_beefy::{
 	client_message::BEEFY_CLIENT_MESSAGE_TYPE_URL, client_state::BEEFY_CLIENT_STATE_TYPE_URL,
 	consensus_state::BEEFY_CONSENSUS_STATE_TYPE_URL,
 };
+use icsxx_ethereum::{
+	client_message::ETHEREUM_CLIENT_MESSAGE_TYPE_URL, client_state::ETHEREUM_CLIENT_STATE_TYPE_URL,
+	consensus_state::ETHEREUM_CONSENSUS_STATE_TYPE_URL,
+};
 use sp_core::{ed25519, H256};
 use sp_runtime::{
 	app_crypto::RuntimePublic,
@@ -172,11 +179,30 @@ impl beefy_client_primitives::HostFunctions for HostFunctionsManager {
 	}
 }
 
+impl icsxx_ethereum::HostFunctions for HostFunctionsManager {
+	fn sha256(input: &[u8]) -> [u8; 32] {
+		sp_io::hashing::sha2_256(input)
+	}
+
+	fn keccak_256(input: &[u8]) -> [u8; 32] {
+		sp_io::hashing::keccak_256(input)
+	}
+
+	fn bls_fast_aggregate_verify(
+		public_keys: &[[u8; 48]],
+		message: &[u8],
+		signature: &[u8; 96],
+	) -> bool {
+		runtime_interface::bls_fast_aggregate_verify(public_keys, message, signature)
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, ClientDef)]
 pub enum AnyClient {
 	Grandpa(ics10_grandpa::client_def::GrandpaClient<HostFunctionsManager>),
 	Beefy(ics11_beefy::client_def::BeefyClient<HostFunctionsManager>),
 	Tendermint(ics07_tendermint::client_def::TendermintClient<HostFunctionsManager>),
+	Ethereum(icsxx_ethereum::client_def::EthereumClient<HostFunctionsManager>),
 	#[cfg(test)]
 	Mock(ibc::mock::client_def::MockClient),
 }
@@ -186,6 +212,7 @@ pub enum AnyUpgradeOptions {
 	Grandpa(ics10_grandpa::client_state::UpgradeOptions),
 	Beefy(ics11_beefy::client_state::UpgradeOptions),
 	Tendermint(ics07_tendermint::client_state::UpgradeOptions),
+	Ethereum(icsxx_ethereum::client_state::UpgradeOptions),
 	#[cfg(test)]
 	Mock(()),
 }
@@ -198,11 +225,29 @@ pub enum AnyClientState {
 	Beefy(ics11_beefy::client_state::ClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "TENDERMINT_CLIENT_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::client_state::ClientState<HostFunctionsManager>),
+	#[ibc(proto_url = "ETHEREUM_CLIENT_STATE_TYPE_URL")]
+	Ethereum(icsxx_ethereum::client_state::ClientState<HostFunctionsManager>),
 	#[cfg(test)]
 	#[ibc(proto_url = "MOCK_CLIENT_STATE_TYPE_URL")]
 	Mock(ibc::mock::client_state::MockClientState),
 }
 
+impl AnyClientState {
+	/// The client types of the variants above.
+	pub fn supported_client_types() -> Vec<ClientType> {
+		#[allow(unused_mut)]
+		let mut client_types = vec![
+			ics10_grandpa::client_state::ClientState::<HostFunctionsManager>::client_type(),
+			ics11_beefy::client_state::ClientState::<HostFunctionsManager>::client_type(),
+			ics07_tendermint::client_state::ClientState::<HostFunctionsManager>::client_type(),
+			icsxx_ethereum::client_state::ClientState::<HostFunctionsManager>::client_type(),
+		];
+		#[cfg(test)]
+		client_types.push(ibc::mock::client_state::MockClientState::client_type());
+		client_types
+	}
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, ConsensusState, Protobuf)]
 pub enum AnyConsensusState {
 	#[ibc(proto_url = "GRANDPA_CONSENSUS_STATE_TYPE_URL")]
@@ -211,6 +256,8 @@ pub enum AnyConsensusState {
 	Beefy(ics11_beefy::consensus_state::ConsensusState),
 	#[ibc(proto_url = "TENDERMINT_CONSENSUS_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::consensus_state::ConsensusState),
+	#[ibc(proto_url = "ETHEREUM_CONSENSUS_STATE_TYPE_URL")]
+	Ethereum(icsxx_ethereum::consensus_state::ConsensusState),
 	#[cfg(test)]
 	#[ibc(proto_url = "MOCK_CONSENSUS_STATE_TYPE_URL")]
 	Mock(ibc::mock::client_state::MockConsensusState),
@@ -225,6 +272,8 @@ pub enum AnyClientMessage {
 	Beefy(ics11_beefy::client_message::ClientMessage),
 	#[ibc(proto_url = "TENDERMINT_CLIENT_MESSAGE_TYPE_URL")]
 	Tendermint(ics07_tendermint::client_message::ClientMessage),
+	#[ibc(proto_url = "ETHEREUM_CLIENT_MESSAGE_TYPE_URL")]
+	Ethereum(icsxx_ethereum::client_message::ClientMessage),
 	#[cfg(test)]
 	#[ibc(proto_url = "MOCK_CLIENT_MESSAGE_TYPE_URL")]
 	Mock(ibc::mock::header::MockClientMessage),
@@ -249,6 +298,10 @@ impl TryFrom<Any> for AnyClientMessage {
 				ics07_tendermint::client_message::ClientMessage::decode_vec(&value.value)
 					.map_err(ics02_client::error::Error::decode_raw_header)?,
 			)),
+			ETHEREUM_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Ethereum(
+				icsxx_ethereum::client_message::ClientMessage::decode_vec(&value.value)
+					.map_err(ics02_client::error::Error::decode_raw_header)?,
+			)),
 			_ => Err(ics02_client::error::Error::unknown_consensus_state_type(value.type_url)),
 		}
 	}
@@ -267,6 +320,10 @@ impl From<AnyClientMessage> for Any {
 				type_url: TENDERMINT_CLIENT_MESSAGE_TYPE_URL.to_string(),
 				value: msg.encode_vec(),
 			},
+			AnyClientMessage::Ethereum(msg) => Any {
+				type_url: ETHEREUM_CLIENT_MESSAGE_TYPE_URL.to_string(),
+				value: msg.encode_vec(),
+			},
 			#[cfg(test)]
 			AnyClientMessage::Mock(_msg) => panic!("MockHeader can't be serialized"),
 		}
@@ -1,7 +1,7 @@
 use crate::{self as pallet_ibc, routing::ModuleRouter};
 use cumulus_primitives_core::ParaId;
 use frame_support::{
-	pallet_prelude::ConstU32,
+	pallet_prelude::{ConstU32, Weight},
 	parameter_types,
 	traits::{
 		fungibles::{metadata::Mutate, Create, InspectMetadata},
@@ -109,12 +109,19 @@ impl pallet_ibc_ping::Config for Test {
 	type Event = Event;
 
 	type IbcHandler = Ibc;
+
+	type MaxPayloadSize = ConstU32<16>;
 }
 
 parameter_types! {
 	pub const NativeAssetId: u128 = 1;
 	pub const StringLimit: u32 = 32;
 	pub const MinimumConnectionDelay: u64 = 1;
+	pub const ConnectionHandshakeTimeout: u64 = 100;
+	pub const ConsensusStateRetention: u32 = 2;
+	pub const PersistTimeouts: bool = true;
+	pub const MaxTimedOutPackets: u32 = 2;
+	pub const TransferRateLimitPeriod: u32 = 10;
 }
 
 pub type Balances = orml_tokens::CurrencyAdapter<Test, NativeAssetId>;
@@ -181,14 +188,157 @@ impl Config for Test {
 	type ExpectedBlockTime = ExpectedBlockTime;
 	type Router = Router;
 	type MinimumConnectionDelay = MinimumConnectionDelay;
+	type ConnectionHandshakeTimeout = ConnectionHandshakeTimeout;
+	type ConsensusStateRetention = ConsensusStateRetention;
+	type PersistTimeouts = PersistTimeouts;
+	type MaxTimedOutPackets = MaxTimedOutPackets;
+	type TransferRateLimitPeriod = TransferRateLimitPeriod;
 	type ParaId = ParachainId;
 	type RelayChain = RelayChainId;
-	type WeightInfo = ();
+	type WeightInfo = MockWeightInfo;
 	type AdminOrigin = EnsureRoot<AccountId>;
 	type SentryOrigin = EnsureRoot<AccountId>;
 	type SpamProtectionDeposit = SpamProtectionDeposit;
 }
 
+/// Weights that tell the light client verification branches apart and bound the housekeeping,
+/// all other calls are free.
+pub struct MockWeightInfo;
+
+impl pallet_ibc::WeightInfo for MockWeightInfo {
+	fn create_client() -> Weight {
+		0
+	}
+
+	fn conn_open_init() -> Weight {
+		0
+	}
+
+	fn update_tendermint_client() -> Weight {
+		1_000
+	}
+
+	fn conn_try_open_tendermint() -> Weight {
+		0
+	}
+
+	fn conn_open_ack_tendermint() -> Weight {
+		0
+	}
+
+	fn conn_open_confirm_tendermint() -> Weight {
+		0
+	}
+
+	fn channel_open_init() -> Weight {
+		0
+	}
+
+	fn channel_open_try_tendermint() -> Weight {
+		0
+	}
+
+	fn channel_open_ack_tendermint() -> Weight {
+		0
+	}
+
+	fn channel_open_confirm_tendermint() -> Weight {
+		0
+	}
+
+	fn channel_close_init() -> Weight {
+		0
+	}
+
+	fn channel_close_confirm_tendermint() -> Weight {
+		0
+	}
+
+	fn recv_packet_tendermint(_i: u32) -> Weight {
+		0
+	}
+
+	fn ack_packet_tendermint(_i: u32, _j: u32) -> Weight {
+		0
+	}
+
+	fn timeout_packet_tendermint(_i: u32) -> Weight {
+		0
+	}
+
+	fn set_params() -> Weight {
+		0
+	}
+
+	fn transfer() -> Weight {
+		0
+	}
+
+	fn on_chan_open_init() -> Weight {
+		0
+	}
+
+	fn on_chan_open_try() -> Weight {
+		0
+	}
+
+	fn on_recv_packet() -> Weight {
+		0
+	}
+
+	fn on_chan_open_ack() -> Weight {
+		0
+	}
+
+	fn on_chan_open_confirm() -> Weight {
+		0
+	}
+
+	fn on_chan_close_init() -> Weight {
+		0
+	}
+
+	fn on_chan_close_confirm() -> Weight {
+		0
+	}
+
+	fn on_acknowledgement_packet() -> Weight {
+		0
+	}
+
+	fn on_timeout_packet() -> Weight {
+		0
+	}
+
+	fn update_grandpa_client(i: u32) -> Weight {
+		2_000 + 10 * i as Weight
+	}
+
+	fn update_beefy_client(i: u32, j: u32) -> Weight {
+		3_000 + 10 * i as Weight + 20 * j as Weight
+	}
+
+	fn reject_unknown_message() -> Weight {
+		10
+	}
+
+	fn prune_consensus_state() -> Weight {
+		100
+	}
+
+	fn cancel_stale_connection() -> Weight {
+		100
+	}
+
+	fn recover_client() -> Weight {
+		100
+	}
+
+	fn write_async_acknowledgement() -> Weight {
+		100
+	}
+}
+
 impl pallet_timestamp::Config for Test {
 	type Moment = u64;
 	type OnTimestampSet = ();
@@ -211,12 +361,40 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	ext
 }
 
+/// Asset id of the first denom registered on receive, `PICA` is always asset 2.
+const FIRST_REGISTERED_ASSET_ID: u128 = 100;
+
 impl<T: Config> DenomToAssetId<T> for ()
 where
-	T::AssetId: From<u128>,
+	T::AssetId: From<u128> + Into<u128>,
 {
 	type Error = ();
-	fn from_denom_to_asset_id(_denom: &String) -> Result<T::AssetId, Self::Error> {
+	fn from_denom_to_asset_id(denom: &String) -> Result<T::AssetId, Self::Error> {
+		if let Some(id) = IbcDenoms::<T>::get(denom.as_bytes()) {
+			return Ok(id)
+		}
+		if denom != "PICA" {
+			let id = FIRST_REGISTERED_ASSET_ID + IbcAssetIds::<T>::count() as u128;
+			let symbol = denom.rsplit('/').next().unwrap_or_default().as_bytes().to_vec();
+			<<Test as Config>::Fungibles as Create<AccountId>>::create(
+				id,
+				AccountId::new([0; 32]),
+				true,
+				1,
+			)
+			.map_err(|_| ())?;
+			<<Test as Config>::Fungibles as Mutate<AccountId>>::set(
+				id,
+				&AccountId::new([0; 32]),
+				denom.as_bytes().to_vec(),
+				symbol,
+				12,
+			)
+			.map_err(|_| ())?;
+			IbcDenoms::<T>::insert(denom.as_bytes().to_vec(), T::AssetId::from(id));
+			IbcAssetIds::<T>::insert(T::AssetId::from(id), denom.as_bytes().to_vec());
+			return Ok(id.into())
+		}
 		if <<Test as Config>::Fungibles as InspectMetadata<AccountId>>::decimals(&2u128) == 0 {
 			<<Test as Config>::Fungibles as Create<AccountId>>::create(
 				2u128.into(),
@@ -238,8 +416,31 @@ where
 		Ok(2u128.into())
 	}
 
-	fn from_asset_id_to_denom(_id: T::AssetId) -> Option<String> {
-		Some("PICA".to_string())
+	fn lookup(denom: &String) -> Option<T::AssetId> {
+		IbcDenoms::<T>::get(denom.as_bytes()).or_else(|| (denom == "PICA").then(|| 2u128.into()))
+	}
+
+	fn from_asset_id_to_denom(id: T::AssetId) -> Option<String> {
+		match IbcAssetIds::<T>::get(id) {
+			Some(denom) => String::from_utf8(denom).ok(),
+			None => Some("PICA".to_string()),
+		}
+	}
+
+	fn set_asset_metadata(
+		id: T::AssetId,
+		symbol: Vec<u8>,
+		decimals: u8,
+	) -> Result<(), Self::Error> {
+		let name = IbcAssetIds::<T>::get(id).ok_or(())?;
+		<<Test as Config>::Fungibles as Mutate<AccountId>>::set(
+			id.into(),
+			&AccountId::new([0; 32]),
+			name,
+			symbol,
+			decimals,
+		)
+		.map_err(|_| ())
 	}
 
 	fn ibc_assets(
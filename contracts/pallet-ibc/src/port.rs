@@ -6,7 +6,10 @@ use ibc::{
 		MODULE_ID_STR as TRANSFER_MODULE_ID, PORT_ID_STR as TRANSFER_PORT_ID,
 	},
 	core::{
-		ics05_port::{context::PortReader, error::Error as ICS05Error},
+		ics05_port::{
+			context::{PortKeeper, PortReader},
+			error::Error as ICS05Error,
+		},
 		ics24_host::identifier::PortId,
 		ics26_routing::context::ModuleId,
 	},
@@ -14,6 +17,14 @@ use ibc::{
 
 impl<T: Config + Sync + Send> PortReader for Context<T> {
 	fn lookup_module_by_port(&self, port_id: &PortId) -> Result<ModuleId, ICS05Error> {
+		// ports bound at runtime take precedence over the static routes
+		if let Some(module_id) = PortBindings::<T>::get(port_id.as_bytes().to_vec()) {
+			return core::str::from_utf8(&module_id)
+				.ok()
+				.and_then(|module_id| ModuleId::from_str(module_id).ok())
+				.ok_or_else(|| ICS05Error::module_not_found(port_id.clone()))
+		}
+
 		// check if the user has defined any custom modules
 		if let Some(module_id) = T::Router::lookup_module_by_port(port_id) {
 			return Ok(module_id)
@@ -26,3 +37,15 @@ impl<T: Config + Sync + Send> PortReader for Context<T> {
 		}
 	}
 }
+
+impl<T: Config + Sync + Send> PortKeeper for Context<T> {
+	fn store_port_binding(
+		&mut self,
+		port_id: PortId,
+		module_id: ModuleId,
+	) -> Result<(), ICS05Error> {
+		let module_id = module_id.as_ref().as_bytes().to_vec();
+		PortBindings::<T>::insert(port_id.as_bytes().to_vec(), module_id);
+		Ok(())
+	}
+}
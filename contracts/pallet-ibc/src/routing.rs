@@ -1,16 +1,19 @@
 use super::*;
 use core::fmt::Debug;
+use frame_support::{storage::TransactionOutcome, traits::Get};
 use ibc::{
 	applications::transfer::MODULE_ID_STR as IBC_TRANSFER_MODULE_ID,
 	core::{
 		ics24_host::identifier::PortId,
-		ics26_routing::context::{
-			Ics26Context, Module, ModuleCallbackContext, ModuleId, ReaderContext, Router,
+		ics26_routing::{
+			context::{Ics26Context, Module, ModuleCallbackContext, ModuleId, ReaderContext, Router},
+			error::Error as Ics26Error,
 		},
 	},
 };
 use scale_info::prelude::string::ToString;
 use sp_core::crypto::AccountId32;
+use sp_runtime::DispatchError;
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub(crate) struct Context<T: Config> {
@@ -77,6 +80,11 @@ where
 
 		matches!(module_id.to_string().as_str(), IBC_TRANSFER_MODULE_ID,)
 	}
+
+	fn add_route(&mut self, module_id: ModuleId, _module: Box<dyn Module>) -> Result<(), String> {
+		// Modules are compiled into the runtime, new ones come with an upgrade of `Config::Router`
+		Err(format!("Module {} can't be registered outside of Config::Router", module_id))
+	}
 }
 
 impl<T: Config + Send + Sync> Ics26Context for Context<T>
@@ -93,6 +101,29 @@ where
 	fn router_mut(&mut self) -> &mut Self::Router {
 		&mut self.router
 	}
+
+	fn with_transaction<R>(
+		&mut self,
+		f: impl FnOnce(&mut Self) -> Result<R, Ics26Error>,
+	) -> Result<R, Ics26Error> {
+		let mut f = Some(f);
+		let mut result = None;
+		let _ = frame_support::storage::with_transaction(|| {
+			let res = (f.take().expect("the transaction runs once; qed"))(self);
+			let commit = res.is_ok();
+			result = Some(res);
+			if commit {
+				TransactionOutcome::Commit(Ok::<_, DispatchError>(()))
+			} else {
+				TransactionOutcome::Rollback(Ok(()))
+			}
+		});
+		match result {
+			Some(result) => result,
+			// No transaction is started once the nesting limit is reached, run `f` as is then.
+			None => (f.take().expect("f only runs in a transaction that was started; qed"))(self),
+		}
+	}
 }
 
 impl<T: Config + Send + Sync> ReaderContext for Context<T>
@@ -100,6 +131,9 @@ where
 	u32: From<<T as frame_system::Config>::BlockNumber>,
 	AccountId32: From<T::AccountId>,
 {
+	fn persist_timeouts(&self) -> bool {
+		T::PersistTimeouts::get()
+	}
 }
 
 impl<T: Config + Send + Sync> ModuleCallbackContext for Context<T>
@@ -1,61 +1,110 @@
 use crate::{
 	impls::{OFFCHAIN_RECV_PACKET_SEQS, OFFCHAIN_SEND_PACKET_SEQS},
-	light_clients::{AnyClientState, AnyConsensusState},
+	light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState},
 	mock::*,
 	routing::Context,
-	Any, Config, ConsensusHeights, DenomToAssetId, MultiAddress, Pallet, PalletParams, Timeout,
-	TransferParams, MODULE_ID,
+	Any, Config, ConsensusHeights, DenomToAssetId, MultiAddress, Pallet, PalletParams, RefundParams,
+	Timeout, TransferParams, WeightInfo,
+};
+use codec::{Decode, Encode};
+use beefy_client_primitives::{
+	MmrUpdateProof, PartialMmrLeaf, SignatureWithAuthorityIndex, SignedCommitment,
+};
+use beefy_primitives::{
+	known_payload_ids::MMR_ROOT_ID,
+	mmr::{MmrLeaf, MmrLeafVersion},
+	Commitment, Payload,
 };
 use core::time::Duration;
 use frame_support::{
-	assert_ok,
+	assert_noop, assert_ok,
+	pallet_prelude::Weight,
+	storage::{child, child::ChildInfo},
 	traits::{
-		fungibles::{Inspect, Mutate},
-		Len,
+		fungibles::{Inspect, InspectMetadata, Mutate},
+		Hooks, Len,
 	},
 };
 use ibc::{
-	applications::transfer::{packet::PacketData, Coin, PrefixedDenom, VERSION},
+	applications::transfer::{
+		acknowledgement::ACK_SUCCESS_B64, packet::PacketData, Coin, PrefixedDenom, VERSION,
+	},
 	core::{
 		ics02_client::{
 			client_state::ClientState,
 			context::{ClientKeeper, ClientReader},
+			events::{Attributes, UpdateClient},
 			height::Height,
-			msgs::create_client::{MsgCreateAnyClient, TYPE_URL},
+			msgs::{
+				create_client::{MsgCreateAnyClient, TYPE_URL},
+				recover_client::MsgRecoverClient,
+				update_client::{MsgUpdateAnyClient, TYPE_URL as UPDATE_CLIENT_TYPE_URL},
+			},
 		},
 		ics03_connection::{
 			connection::{ConnectionEnd, Counterparty, State as ConnState},
 			context::{ConnectionKeeper, ConnectionReader},
+			handler::HandshakeOrigin,
 			msgs::conn_open_init,
 			version::Version as ConnVersion,
 		},
 		ics04_channel::{
 			channel::{ChannelEnd, Counterparty as ChanCounterParty, Order, State},
-			context::ChannelKeeper,
-			msgs::recv_packet::MsgRecvPacket,
-			packet::Packet,
+			commitment::{compute_ack_commitment, compute_packet_commitment},
+			context::{ChannelKeeper, ChannelReader},
+			events::SendPacket,
+			msgs::{
+				acknowledgement::{Acknowledgement, MsgAcknowledgement},
+				chan_close_init::MsgChannelCloseInit,
+				chan_upgrade_init::MsgChannelUpgradeInit,
+				recv_packet::MsgRecvPacket,
+			},
+			packet::{Packet, Receipt, Sequence},
+			upgrade::UpgradeFields,
 			Version as ChanVersion,
 		},
-		ics23_commitment::commitment::CommitmentPrefix,
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{SeqAcksPath, SeqSendsPath},
+		},
 	},
+	events::IbcEvent as RawIbcEvent,
 	mock::{
 		client_state::{MockClientState, MockConsensusState},
 		header::{MockClientMessage, MockHeader},
 	},
 	proofs::Proofs,
 	signer::Signer,
+	timestamp::Timestamp,
 	tx_msg::Msg,
 };
-use ibc_primitives::{get_channel_escrow_address, IbcHandler};
+use grandpa_client_primitives::justification::GrandpaJustification;
+use ibc_primitives::{get_channel_escrow_address, Error as IbcHandlerError, IbcHandler};
+use ics10_grandpa::client_message::{
+	ClientMessage as GrandpaClientMessage, Header as GrandpaHeader, RelayChainHeader,
+};
+use ics11_beefy::client_message::{
+	BeefyHeader, ClientMessage as BeefyClientMessage, ParachainHeader as BeefyParachainHeader,
+	ParachainHeadersWithProof,
+};
 use sp_core::Pair;
-use sp_runtime::{offchain::storage::StorageValueRef, traits::IdentifyAccount, AccountId32};
+use sp_runtime::{
+	offchain::storage::StorageValueRef,
+	traits::{BlakeTwo256, IdentifyAccount},
+	AccountId32,
+};
 use std::{
 	collections::{BTreeMap, BTreeSet},
 	str::FromStr,
 };
 use tendermint_proto::Protobuf;
 
+/// The hex signer `Pallet::deliver` accepts in messages submitted by `account`.
+fn signer_of(account: &AccountId32) -> Signer {
+	Signer::from_str(&format!("0x{}", hex::encode(account))).unwrap()
+}
+
 fn setup_client_and_consensus_state(port_id: PortId) {
 	// Set up client state and consensus states
 	let mock_client_state =
@@ -66,7 +115,7 @@ fn setup_client_and_consensus_state(port_id: PortId) {
 	let msg = MsgCreateAnyClient::<Context<Test>>::new(
 		AnyClientState::Mock(mock_client_state),
 		AnyConsensusState::Mock(mock_cs_state),
-		Signer::from_str(MODULE_ID).unwrap(),
+		signer_of(&AccountId32::new([0; 32])),
 	)
 	.unwrap()
 	.encode_vec();
@@ -116,7 +165,7 @@ fn initialize_connection() {
 		let msg = MsgCreateAnyClient::<Context<Test>>::new(
 			AnyClientState::Mock(mock_client_state),
 			AnyConsensusState::Mock(mock_cs_state),
-			Signer::from_str(MODULE_ID).unwrap(),
+			signer_of(&AccountId32::new([0; 32])),
 		)
 		.unwrap()
 		.encode_vec();
@@ -137,7 +186,7 @@ fn initialize_connection() {
 			),
 			version: Some(ConnVersion::default()),
 			delay_period: Duration::from_nanos(1000),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
 		};
 
 		let msg = Any {
@@ -163,7 +212,7 @@ fn initialize_connection_with_low_delay() {
 		let msg = MsgCreateAnyClient::<Context<Test>>::new(
 			AnyClientState::Mock(mock_client_state),
 			AnyConsensusState::Mock(mock_cs_state),
-			Signer::from_str(MODULE_ID).unwrap(),
+			signer_of(&AccountId32::new([0; 32])),
 		)
 		.unwrap()
 		.encode_vec();
@@ -184,7 +233,7 @@ fn initialize_connection_with_low_delay() {
 			),
 			version: Some(ConnVersion::default()),
 			delay_period: Duration::from_nanos(900),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
 		};
 
 		let msg = Any {
@@ -230,6 +279,7 @@ fn send_transfer() {
 				to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
 				source_channel: 0,
 				timeout,
+				memo: None,
 			},
 			asset_id,
 			balance,
@@ -306,6 +356,7 @@ fn on_deliver_ics20_recv_packet() {
 			token: coin,
 			sender: Signer::from_str("alice").unwrap(),
 			receiver: Signer::from_str(&ss58_address).unwrap(),
+			memo: String::new(),
 		};
 
 		let data = serde_json::to_vec(&packet_data).unwrap();
@@ -333,7 +384,7 @@ fn on_deliver_ics20_recv_packet() {
 				Height::new(0, 1),
 			)
 			.unwrap(),
-			signer: Signer::from_str(MODULE_ID).unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
 		};
 
 		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
@@ -621,3 +672,1878 @@ fn test_next_and_previous_consensus_state_for_other_client_types() {
 		assert!(ctx.next_consensus_state(&client_id, Height::new(0, 400)).unwrap().is_some());
 	})
 }
+
+#[test]
+fn packet_commitment_count_tracks_store_and_delete() {
+	new_test_ext().execute_with(|| {
+		let mut ctx = Context::<Test>::new();
+		let port_id = PortId::transfer();
+		let channel_0 = (port_id.clone(), ChannelId::new(0));
+		let channel_1 = (port_id.clone(), ChannelId::new(1));
+
+		for seq in 1..=3u64 {
+			ctx.store_packet_commitment((port_id.clone(), channel_0.1, seq.into()), vec![1].into())
+				.unwrap();
+		}
+		// Overwriting an existing commitment does not change the count.
+		ctx.store_packet_commitment((port_id.clone(), channel_0.1, 3.into()), vec![2].into())
+			.unwrap();
+		ctx.store_packet_commitment((port_id.clone(), channel_1.1, 1.into()), vec![1].into())
+			.unwrap();
+		assert_eq!(ctx.packet_commitment_count(&channel_0).unwrap(), 3);
+		assert_eq!(ctx.packet_commitment_count(&channel_1).unwrap(), 1);
+
+		ctx.delete_packet_commitment((port_id.clone(), channel_0.1, 2.into())).unwrap();
+		// Deleting a missing commitment does not change the count.
+		ctx.delete_packet_commitment((port_id.clone(), channel_0.1, 2.into())).unwrap();
+		assert_eq!(ctx.packet_commitment_count(&channel_0).unwrap(), 2);
+
+		ctx.delete_packet_commitment((port_id, channel_1.1, 1.into())).unwrap();
+		assert_eq!(ctx.packet_commitment_count(&channel_1).unwrap(), 0);
+		assert_eq!(
+			Pallet::<Test>::packet_commitment_count(
+				channel_0.1.to_string().as_bytes().to_vec(),
+				channel_0.0.as_bytes().to_vec(),
+			)
+			.unwrap(),
+			2
+		);
+	})
+}
+
+#[test]
+fn packet_acknowledgements_are_paginated_in_sequence_order() {
+	new_test_ext().execute_with(|| {
+		let mut ctx = Context::<Test>::new();
+		let port_id = PortId::transfer();
+		let channel_0 = (port_id.clone(), ChannelId::new(0));
+		let channel_1 = (port_id.clone(), ChannelId::new(1));
+
+		// Sequences past 9 sort before 2 as trie keys.
+		for seq in 1..=12u64 {
+			ctx.store_packet_acknowledgement(
+				(port_id.clone(), channel_0.1, seq.into()),
+				vec![seq as u8].into(),
+			)
+			.unwrap();
+		}
+		ctx.store_packet_acknowledgement((port_id, channel_1.1, 1.into()), vec![1].into())
+			.unwrap();
+
+		let mut start = Sequence::from(1);
+		let mut pages = vec![];
+		loop {
+			let page = ctx.packet_acknowledgements(&channel_0, start, 5).unwrap();
+			if page.is_empty() {
+				break
+			}
+			start = page.last().unwrap().0.increment();
+			pages.push(page.into_iter().map(|(seq, _)| u64::from(seq)).collect::<Vec<_>>());
+		}
+		assert_eq!(pages, vec![(1..=5).collect::<Vec<_>>(), (6..=10).collect(), vec![11, 12]]);
+
+		let acks = Pallet::<Test>::packet_acknowledgements(
+			channel_0.1.to_string().as_bytes().to_vec(),
+			channel_0.0.as_bytes().to_vec(),
+			Some(vec![12, 3, 20]),
+		)
+		.unwrap()
+		.acks;
+		assert_eq!(acks.iter().map(|ack| ack.sequence).collect::<Vec<_>>(), vec![3, 12]);
+		assert_eq!(acks[1].data, vec![12]);
+
+		let acks = Pallet::<Test>::packet_acknowledgements(
+			channel_1.1.to_string().as_bytes().to_vec(),
+			channel_1.0.as_bytes().to_vec(),
+			None,
+		)
+		.unwrap()
+		.acks;
+		assert_eq!(acks.len(), 1);
+	})
+}
+
+#[test]
+fn next_sequence_ack_proof_verifies_against_state_root() {
+	let mut ext = new_test_ext();
+	let port_id = PortId::transfer();
+	let channel_id = ChannelId::new(0);
+
+	ext.execute_with(|| {
+		let mut ctx = Context::<Test>::new();
+		ctx.store_next_sequence_ack((port_id.clone(), channel_id), 5.into()).unwrap();
+		assert_eq!(ctx.get_next_sequence_ack(&(port_id.clone(), channel_id)).unwrap(), 5.into());
+	});
+	ext.commit_all().unwrap();
+	let root = *ext.backend.root();
+
+	let (res, proof) = ext.execute_and_prove(|| {
+		Pallet::<Test>::next_seq_ack(
+			channel_id.to_string().as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+		)
+		.unwrap()
+	});
+	assert_eq!(res.sequence, 5);
+
+	let prefix = CommitmentPrefix::try_from(<Test as Config>::PALLET_PREFIX.to_vec()).unwrap();
+	let proof =
+		CommitmentProofBytes::try_from(proof.into_iter_nodes().collect::<Vec<_>>().encode())
+			.unwrap();
+	let root = CommitmentRoot::from_bytes(root.as_bytes());
+	let path = SeqAcksPath(port_id, channel_id);
+	light_client_common::verify_membership::<BlakeTwo256, _>(
+		&prefix,
+		&proof,
+		&root,
+		path.clone(),
+		5u64.encode(),
+	)
+	.unwrap();
+	assert!(light_client_common::verify_membership::<BlakeTwo256, _>(
+		&prefix,
+		&proof,
+		&root,
+		path,
+		6u64.encode(),
+	)
+	.is_err());
+}
+
+#[test]
+fn commitment_keys_proof_verifies_against_state_root() {
+	let mut ext = new_test_ext();
+	let port_id = PortId::transfer();
+	let channel_id = ChannelId::new(0);
+	let send_path = SeqSendsPath(port_id.clone(), channel_id);
+	let ack_path = SeqAcksPath(port_id.clone(), channel_id);
+
+	ext.execute_with(|| {
+		let mut ctx = Context::<Test>::new();
+		ctx.store_next_sequence_send((port_id.clone(), channel_id), 3.into()).unwrap();
+		ctx.store_next_sequence_ack((port_id.clone(), channel_id), 5.into()).unwrap();
+	});
+	ext.commit_all().unwrap();
+	let root = *ext.backend.root();
+
+	let paths = vec![send_path.to_string().into_bytes(), ack_path.to_string().into_bytes()];
+	let (values, proof) = ext.execute_and_prove(|| {
+		let child_info = ChildInfo::new_default(<Test as Config>::PALLET_PREFIX);
+		Pallet::<Test>::commitment_keys(paths)
+			.unwrap()
+			.iter()
+			.map(|key| child::get::<u64>(&child_info, key))
+			.collect::<Vec<_>>()
+	});
+	assert_eq!(values, vec![Some(3), Some(5)]);
+
+	let prefix = CommitmentPrefix::try_from(<Test as Config>::PALLET_PREFIX.to_vec()).unwrap();
+	let proof =
+		CommitmentProofBytes::try_from(proof.into_iter_nodes().collect::<Vec<_>>().encode())
+			.unwrap();
+	let root = CommitmentRoot::from_bytes(root.as_bytes());
+	for (path, value) in [(send_path, 3u64), (ack_path, 5u64)] {
+		light_client_common::verify_membership::<BlakeTwo256, _>(
+			&prefix,
+			&proof,
+			&root,
+			path,
+			value.encode(),
+		)
+		.unwrap();
+	}
+
+	ext.execute_with(|| {
+		assert!(Pallet::<Test>::commitment_keys(vec![b"not/an/ibc/path".to_vec()]).is_err());
+		assert_eq!(
+			Pallet::<Test>::next_seq_send(
+				channel_id.to_string().as_bytes().to_vec(),
+				port_id.as_bytes().to_vec(),
+			)
+			.unwrap()
+			.sequence,
+			3
+		);
+	});
+}
+
+#[test]
+fn duplicate_ack_in_batch_is_a_noop() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let mut ctx = Context::<Test>::default();
+
+		let packet_data = PacketData {
+			token: Coin {
+				denom: PrefixedDenom::from_str("PICA").unwrap(),
+				amount: ibc::applications::transfer::Amount::from_str("1000").unwrap(),
+			},
+			sender: Signer::from_str("alice").unwrap(),
+			receiver: Signer::from_str("bob").unwrap(),
+			memo: String::new(),
+		};
+		let packet = Packet {
+			sequence: 1u64.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(1),
+			data: serde_json::to_vec(&packet_data).unwrap(),
+			timeout_height: Height::new(2000, 5),
+			timeout_timestamp: Default::default(),
+		};
+		let commitment = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+		ctx.store_packet_commitment(
+			(packet.source_port.clone(), packet.source_channel, packet.sequence),
+			commitment,
+		)
+		.unwrap();
+
+		let msg = MsgAcknowledgement {
+			packet: packet.clone(),
+			acknowledgement: Acknowledgement::from(ACK_SUCCESS_B64.to_vec()),
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		// A competing relayer delivered the same acknowledgement in the same batch.
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg.clone(), msg]).unwrap();
+
+		assert!(ctx
+			.get_packet_commitment(&(packet.source_port, packet.source_channel, packet.sequence))
+			.is_err());
+		let events = frame_system::Pallet::<Test>::events();
+		assert!(!events.iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::Events { events }) if events.iter().any(|e| e.is_err())
+		)));
+		// The module callback only ran for the first delivery.
+		let completed = events
+			.iter()
+			.filter(|record| {
+				matches!(record.event, Event::Ibc(crate::Event::TokenTransferCompleted { .. }))
+			})
+			.count();
+		assert_eq!(completed, 1);
+	})
+}
+
+#[test]
+fn ping_round_trip_latency_is_recorded_on_acknowledgement() {
+	use pallet_ibc_ping::{PingSendBlocks, PingStats, SendPingParams};
+
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let port_id = PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap();
+		let channel_id = ChannelId::new(0);
+		setup_client_and_consensus_state(port_id.clone());
+
+		let params = |payload_size| SendPingParams {
+			count: 2,
+			payload_size,
+			timeout_height_offset: 10,
+			timeout_timestamp_offset: 1_000,
+			channel_id: 0,
+		};
+		assert_noop!(
+			IbcPing::send_ping(Origin::root(), params(17)),
+			pallet_ibc_ping::Error::<Test>::PayloadTooLarge
+		);
+		assert_ok!(IbcPing::send_ping(Origin::root(), params(8)));
+		assert_eq!(PingSendBlocks::<Test>::get(0, 1), Some(1));
+		assert_eq!(PingSendBlocks::<Test>::get(0, 2), Some(1));
+
+		let (latest_height, latest_timestamp) =
+			Pallet::<Test>::latest_height_and_timestamp(&port_id, &channel_id).unwrap();
+		let packet = Packet {
+			sequence: 1u64.into(),
+			source_port: port_id.clone(),
+			source_channel: channel_id,
+			destination_port: port_id,
+			destination_channel: ChannelId::new(1),
+			data: b"pingping".to_vec(),
+			timeout_height: latest_height.add(10),
+			timeout_timestamp: (latest_timestamp + Duration::from_nanos(1_000)).unwrap(),
+		};
+		let msg = MsgAcknowledgement {
+			packet,
+			acknowledgement: Acknowledgement::from(b"ping-success".to_vec()),
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		frame_system::Pallet::<Test>::set_block_number(4u32);
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		assert_eq!(PingSendBlocks::<Test>::get(0, 1), None);
+		assert_eq!(PingSendBlocks::<Test>::get(0, 2), Some(1));
+		assert_eq!(
+			IbcPing::ping_stats(0),
+			Some(PingStats { acknowledged: 1, min: 3, max: 3, avg: 3 })
+		);
+	})
+}
+
+#[test]
+fn channel_close_init_requires_close_authority() {
+	new_test_ext().execute_with(|| {
+		setup_client_and_consensus_state(PortId::transfer());
+		let ctx = Context::<Test>::default();
+		let port_channel_id = (PortId::transfer(), ChannelId::new(0));
+
+		let alice = AccountId32::new([1; 32]);
+		let bob = AccountId32::new([2; 32]);
+		Ibc::set_channel_close_authority(
+			Origin::root(),
+			PortId::transfer().as_bytes().to_vec(),
+			Some(alice.clone()),
+		)
+		.unwrap();
+
+		let close_init = |signer: Signer| {
+			let msg = MsgChannelCloseInit {
+				port_id: port_channel_id.0.clone(),
+				channel_id: port_channel_id.1,
+				signer,
+			};
+			Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() }
+		};
+
+		Ibc::deliver(Origin::signed(bob.clone()), vec![close_init(signer_of(&bob))]).unwrap();
+		assert_eq!(ctx.channel_end(&port_channel_id).unwrap().state, State::Open);
+
+		// bob can't pass as the close authority by signing the message with alice's address
+		assert_noop!(
+			Ibc::deliver(Origin::signed(bob), vec![close_init(signer_of(&alice))]),
+			crate::Error::<Test>::SignerMismatch
+		);
+		assert_eq!(ctx.channel_end(&port_channel_id).unwrap().state, State::Open);
+
+		Ibc::deliver(Origin::signed(alice.clone()), vec![close_init(signer_of(&alice))]).unwrap();
+		assert_eq!(ctx.channel_end(&port_channel_id).unwrap().state, State::Closed);
+	})
+}
+
+#[test]
+fn channel_upgrade_init_requires_upgrade_authority() {
+	new_test_ext().execute_with(|| {
+		setup_client_and_consensus_state(PortId::transfer());
+		let ctx = Context::<Test>::default();
+		let port_channel_id = (PortId::transfer(), ChannelId::new(0));
+
+		let alice = AccountId32::new([1; 32]);
+		let upgrade_init = |signer: Signer| {
+			let msg = MsgChannelUpgradeInit::new(
+				port_channel_id.0.clone(),
+				port_channel_id.1,
+				UpgradeFields::new(Order::Unordered, ChanVersion::ics20_multi_token()),
+				signer,
+			);
+			Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() }
+		};
+
+		// without an upgrade authority the channel can't be upgraded at all
+		Ibc::deliver(Origin::signed(alice.clone()), vec![upgrade_init(signer_of(&alice))])
+			.unwrap();
+		assert_eq!(ctx.channel_end(&port_channel_id).unwrap().state, State::Open);
+
+		Ibc::set_channel_upgrade_authority(
+			Origin::root(),
+			PortId::transfer().as_bytes().to_vec(),
+			Some(alice.clone()),
+		)
+		.unwrap();
+		Ibc::deliver(Origin::signed(alice.clone()), vec![upgrade_init(signer_of(&alice))])
+			.unwrap();
+		assert_eq!(ctx.channel_end(&port_channel_id).unwrap().state, State::InitUpgrade);
+		let origin = ctx.channel_upgrade_origin(&port_channel_id).unwrap();
+		assert_eq!(origin.previous.version, ChanVersion::ics20());
+	})
+}
+
+#[test]
+fn client_recovery_requires_recovery_authority() {
+	new_test_ext().execute_with(|| {
+		let alice = AccountId32::new([1; 32]);
+		let create_client = |height: u64| {
+			let msg = MsgCreateAnyClient::<Context<Test>>::new(
+				AnyClientState::Mock(MockClientState::new(MockClientMessage::from(
+					MockHeader::new(Height::new(0, height)),
+				))),
+				AnyConsensusState::Mock(MockConsensusState::new(MockHeader::new(Height::new(
+					0, height,
+				)))),
+				signer_of(&alice),
+			)
+			.unwrap();
+			Any { type_url: TYPE_URL.as_bytes().to_vec(), value: msg.encode_vec() }
+		};
+		assert_ok!(Ibc::deliver(
+			Origin::signed(alice.clone()),
+			vec![create_client(1), create_client(5)]
+		));
+
+		let mut ctx = Context::<Test>::default();
+		let subject_client_id = ClientId::new(&MockClientState::client_type(), 0).unwrap();
+		let substitute_client_id = ClientId::new(&MockClientState::client_type(), 1).unwrap();
+		let frozen = MockClientState {
+			header: MockHeader::new(Height::new(0, 1)),
+			frozen_height: Some(Height::new(0, 1)),
+		};
+		ctx.store_client_state(subject_client_id.clone(), AnyClientState::Mock(frozen)).unwrap();
+
+		let recover_client = || {
+			let msg = MsgRecoverClient::new(
+				subject_client_id.clone(),
+				substitute_client_id.clone(),
+				signer_of(&alice),
+			);
+			Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() }
+		};
+
+		// without a recovery authority the frozen client stays frozen
+		assert_ok!(Ibc::deliver(Origin::signed(alice.clone()), vec![recover_client()]));
+		assert!(ctx.client_state(&subject_client_id).unwrap().frozen_height().is_some());
+
+		assert_ok!(Ibc::set_client_recovery_authority(Origin::root(), Some(alice.clone())));
+		assert_ok!(Ibc::deliver(Origin::signed(alice.clone()), vec![recover_client()]));
+		let recovered = ctx.client_state(&subject_client_id).unwrap();
+		assert!(recovered.frozen_height().is_none());
+		assert_eq!(recovered.latest_height(), Height::new(0, 5));
+	})
+}
+
+#[test]
+fn failed_message_does_not_abort_the_batch() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let create_client = || {
+			let mock_client_state =
+				MockClientState::new(MockClientMessage::from(MockHeader::new(Height::new(0, 1))));
+			let mock_cs_state = MockConsensusState::new(MockHeader::new(Height::new(0, 1)));
+			let msg = MsgCreateAnyClient::<Context<Test>>::new(
+				AnyClientState::Mock(mock_client_state),
+				AnyConsensusState::Mock(mock_cs_state),
+				signer_of(&AccountId32::new([0; 32])),
+			)
+			.unwrap()
+			.encode_vec();
+			Any { type_url: TYPE_URL.to_string().as_bytes().to_vec(), value: msg }
+		};
+		let bogus = Any { type_url: b"/bogus.Msg".to_vec(), value: vec![] };
+
+		assert_ok!(Ibc::deliver(
+			Origin::signed(AccountId32::new([0; 32])),
+			vec![create_client(), bogus, create_client()]
+		));
+
+		assert_eq!(crate::ClientCounter::<Test>::get(), 2);
+		let failed = frame_system::Pallet::<Test>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				Event::Ibc(crate::Event::MessageFailed { index, .. }) => Some(index),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(failed, vec![1]);
+	})
+}
+
+#[test]
+fn channels_open_on_ports_bound_at_runtime() {
+	use ibc::core::{
+		ics04_channel::msgs::chan_open_init::MsgChannelOpenInit,
+		ics05_port::{context::PortReader, error::ErrorDetail as PortErrorDetail},
+	};
+
+	new_test_ext().execute_with(|| {
+		setup_client_and_consensus_state(PortId::transfer());
+		let ctx = Context::<Test>::default();
+		let port_id = PortId::from_str("pong").unwrap();
+		let ping_module = pallet_ibc_ping::MODULE_ID.as_bytes().to_vec();
+
+		assert!(matches!(
+			ctx.lookup_module_by_port(&port_id).unwrap_err().detail(),
+			PortErrorDetail::ModuleNotFound(_)
+		));
+		assert_noop!(
+			Ibc::bind_port(Origin::root(), b"pong".to_vec(), b"unknown".to_vec()),
+			crate::Error::<Test>::PortBindingFailed
+		);
+		assert_ok!(Ibc::bind_port(Origin::root(), b"pong".to_vec(), ping_module.clone()));
+		assert_eq!(
+			ctx.lookup_module_by_port(&port_id).unwrap().as_ref(),
+			pallet_ibc_ping::MODULE_ID
+		);
+		// Neither runtime nor static bindings can be overwritten.
+		assert_noop!(
+			Ibc::bind_port(Origin::root(), b"pong".to_vec(), ping_module.clone()),
+			crate::Error::<Test>::PortBindingFailed
+		);
+		assert_noop!(
+			Ibc::bind_port(Origin::root(), b"transfer".to_vec(), ping_module),
+			crate::Error::<Test>::PortBindingFailed
+		);
+
+		let msg = MsgChannelOpenInit {
+			port_id: port_id.clone(),
+			channel: ChannelEnd::new(
+				State::Init,
+				Order::Ordered,
+				ChanCounterParty::new(port_id, None),
+				vec![ConnectionId::new(0)],
+				ChanVersion::new(pallet_ibc_ping::VERSION.to_string()),
+			),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		let events = frame_system::Pallet::<Test>::events();
+		assert!(events.iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::Events { events }) if matches!(
+				events.as_slice(),
+				[Ok(crate::events::IbcEvent::OpenInitChannel { port_id, .. })] if port_id == b"pong"
+			)
+		)));
+	})
+}
+
+#[test]
+fn timed_out_packets_are_pruned_by_count() {
+	new_test_ext().execute_with(|| {
+		let mut ctx = Context::<Test>::new();
+		let port_channel_id = (PortId::transfer(), ChannelId::new(0));
+		let timestamp = ibc::timestamp::Timestamp::from_nanoseconds(1_000).unwrap();
+
+		for seq in 1..=3u64 {
+			ctx.store_timed_out_packet(
+				(port_channel_id.0.clone(), port_channel_id.1, seq.into()),
+				timestamp,
+			)
+			.unwrap();
+		}
+
+		// `MaxTimedOutPackets` is 2 in the mock runtime, so the oldest record is dropped.
+		assert_eq!(
+			ctx.timed_out_packets(&port_channel_id).unwrap(),
+			vec![(2.into(), timestamp), (3.into(), timestamp)]
+		);
+		assert_eq!(
+			Pallet::<Test>::timed_out_packets(
+				port_channel_id.1.to_string().as_bytes().to_vec(),
+				port_channel_id.0.as_bytes().to_vec(),
+			)
+			.unwrap(),
+			vec![(2, 1_000), (3, 1_000)]
+		);
+	})
+}
+
+#[test]
+fn consecutive_recv_packets_are_delivered_as_a_batch() {
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address_bytes =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(ss58_address_bytes).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				&"PICA".to_string(),
+			)
+			.unwrap();
+		setup_client_and_consensus_state(PortId::transfer());
+		let ctx = Context::<Test>::default();
+
+		let channel_id = ChannelId::new(0);
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::mint_into(asset_id, &channel_escrow_address.into_account(), 100000 * MILLIS)
+		.unwrap();
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+
+		let amt = 1000 * MILLIS;
+		let packet_data = PacketData {
+			token: Coin {
+				denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+				amount: ibc::applications::transfer::Amount::from_str(&format!("{:?}", amt))
+					.unwrap(),
+			},
+			sender: Signer::from_str("alice").unwrap(),
+			receiver: Signer::from_str(&ss58_address).unwrap(),
+			memo: String::new(),
+		};
+		let recv_msg = |sequence: u64| {
+			let msg = MsgRecvPacket {
+				packet: Packet {
+					sequence: sequence.into(),
+					source_port: PortId::transfer(),
+					source_channel: ChannelId::new(1),
+					destination_port: PortId::transfer(),
+					destination_channel: channel_id,
+					data: serde_json::to_vec(&packet_data).unwrap(),
+					timeout_height: Height::new(2000, 5),
+					timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+						1690894363u64.saturating_mul(1000000000),
+					)
+					.unwrap(),
+				},
+				proofs: Proofs::new(
+					vec![0u8; 32].try_into().unwrap(),
+					None,
+					None,
+					None,
+					Height::new(0, 1),
+				)
+				.unwrap(),
+				signer: signer_of(&AccountId32::new([0; 32])),
+			};
+			Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() }
+		};
+
+		// The duplicate of the second packet is a no-op, as it would be in a later delivery.
+		Ibc::deliver(
+			Origin::signed(AccountId32::new([0; 32])),
+			vec![recv_msg(1), recv_msg(2), recv_msg(2)],
+		)
+		.unwrap();
+
+		for seq in 1..=2u64 {
+			assert!(ctx.get_packet_receipt(&(PortId::transfer(), channel_id, seq.into())).is_ok());
+		}
+		assert!(!frame_system::Pallet::<Test>::events().iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::Events { events }) if events.iter().any(|e| e.is_err())
+		)));
+		let balance =
+			<Assets as Inspect<AccountId>>::balance(2, &AccountId32::new(pair.public().0));
+		assert_eq!(balance, (2 * amt).into())
+	})
+}
+
+#[test]
+fn recv_packet_with_memo_from_ibc_go() {
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address_bytes =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(ss58_address_bytes).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				&"PICA".to_string(),
+			)
+			.unwrap();
+		setup_client_and_consensus_state(PortId::transfer());
+
+		let channel_id = ChannelId::new(0);
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::mint_into(asset_id, &channel_escrow_address.into_account(), 100000 * MILLIS)
+		.unwrap();
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+
+		// Packet data as encoded by an ibc-go v7 sender, with its keys sorted.
+		let memo = r#"{"forward":{"receiver":"bob","port":"transfer","channel":"channel-1"}}"#;
+		let data = format!(
+			r#"{{"amount":"1000","denom":"transfer/channel-1/PICA","memo":{},"receiver":"{}","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}}"#,
+			serde_json::to_string(memo).unwrap(),
+			ss58_address
+		);
+		let msg = MsgRecvPacket {
+			packet: Packet {
+				sequence: 1u64.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: channel_id,
+				data: data.into_bytes(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+					1690894363u64.saturating_mul(1000000000),
+				)
+				.unwrap(),
+			},
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		let balance =
+			<Assets as Inspect<AccountId>>::balance(2, &AccountId32::new(pair.public().0));
+		assert_eq!(balance, 1000);
+		assert!(frame_system::Pallet::<Test>::events().iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::TokenReceived { memo: received, .. })
+				if received == memo.as_bytes()
+		)));
+	})
+}
+
+#[test]
+fn recv_packet_registers_denom_trace_by_hash() {
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address_bytes =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(ss58_address_bytes).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+
+		// The sender chain is the source of `uDOT`, so vouchers for
+		// `transfer/channel-0/uDOT` are minted on this chain.
+		let data = format!(
+			r#"{{"amount":"1000","denom":"uDOT","receiver":"{}","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}}"#,
+			ss58_address
+		);
+		let msg = MsgRecvPacket {
+			packet: Packet {
+				sequence: 1u64.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: ChannelId::new(0),
+				data: data.into_bytes(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+					1690894363u64.saturating_mul(1000000000),
+				)
+				.unwrap(),
+			},
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		let asset_id = crate::IbcDenoms::<Test>::get(b"transfer/channel-0/uDOT".to_vec()).unwrap();
+		let balance =
+			<Assets as Inspect<AccountId>>::balance(asset_id, &AccountId32::new(pair.public().0));
+		assert_eq!(balance, 1000);
+
+		// Hash of `transfer/channel-0/uDOT` as computed by ibc-go
+		let hash = "D55F653266AD268FB95BD4CFB1C555B73F29A1E7AA56841C960807A50C4F0E70";
+		let expected = b"transfer/channel-0/uDOT".to_vec();
+		assert_eq!(Pallet::<Test>::get_denom_trace_by_hash(hash.into()).unwrap().denom, expected);
+		assert_eq!(
+			Pallet::<Test>::get_denom_trace_by_hash(format!("ibc/{}", hash.to_lowercase()).into())
+				.unwrap()
+				.denom,
+			expected
+		);
+		assert!(Pallet::<Test>::get_denom_trace_by_hash(b"ibc/D55F6532".to_vec()).is_none());
+	})
+}
+
+#[test]
+fn rate_limited_recv_packet_gets_error_ack() {
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address_bytes =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(ss58_address_bytes).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				&"PICA".to_string(),
+			)
+			.unwrap();
+		setup_client_and_consensus_state(PortId::transfer());
+
+		let channel_id = ChannelId::new(0);
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::mint_into(asset_id, &channel_escrow_address.clone().into_account(), 100000 * MILLIS)
+		.unwrap();
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+		Ibc::set_transfer_rate_limit(Origin::root(), asset_id, Some(500)).unwrap();
+
+		let data = format!(
+			r#"{{"amount":"1000","denom":"transfer/channel-1/PICA","receiver":"{}","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}}"#,
+			ss58_address
+		);
+		let msg = MsgRecvPacket {
+			packet: Packet {
+				sequence: 1u64.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: channel_id,
+				data: data.into_bytes(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+					1690894363u64.saturating_mul(1000000000),
+				)
+				.unwrap(),
+			},
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		// The packet was acknowledged with an error and nothing left the escrow
+		let ctx = Context::<Test>::default();
+		let ack = ctx
+			.get_packet_acknowledgement(&(PortId::transfer(), channel_id, 1u64.into()))
+			.unwrap();
+		assert_ne!(ack, compute_ack_commitment(&Acknowledgement::from(ACK_SUCCESS_B64.to_vec())));
+		let balance =
+			<Assets as Inspect<AccountId>>::balance(2, &AccountId32::new(pair.public().0));
+		assert_eq!(balance, 0);
+		assert_eq!(
+			<Assets as Inspect<AccountId>>::balance(2, &channel_escrow_address.into_account()),
+			100000 * MILLIS
+		);
+		assert!(!frame_system::Pallet::<Test>::events()
+			.iter()
+			.any(|record| matches!(record.event, Event::Ibc(crate::Event::TokenReceived { .. }))));
+	})
+}
+
+#[test]
+fn error_ack_refunds_rate_limited_transfer() {
+	let mut ext = new_test_ext();
+	let balance = 100000 * MILLIS;
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let raw_user = ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(raw_user).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				&"PICA".to_string(),
+			)
+			.unwrap();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::mint_into(asset_id, &AccountId32::new([0; 32]), balance).unwrap();
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+		Ibc::set_transfer_rate_limit(Origin::root(), asset_id, Some(1500 * MILLIS)).unwrap();
+
+		let params = TransferParams {
+			to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+			source_channel: 0,
+			timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+			memo: None,
+		};
+		Ibc::transfer(
+			Origin::signed(AccountId32::new([0; 32])),
+			params.clone(),
+			asset_id,
+			1000 * MILLIS,
+		)
+		.unwrap();
+
+		// The next transfer would exceed the limit of the current period
+		assert!(Ibc::transfer(
+			Origin::signed(AccountId32::new([0; 32])),
+			params,
+			asset_id,
+			1000 * MILLIS,
+		)
+		.is_err());
+		assert_eq!(
+			<Assets as Inspect<AccountId>>::balance(asset_id, &AccountId32::new([0; 32])),
+			balance - 1000 * MILLIS
+		);
+	});
+
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		let packet_info = Pallet::<Test>::get_send_packet_info(
+			ChannelId::new(0).to_string().as_bytes().to_vec(),
+			PortId::transfer().as_bytes().to_vec(),
+			vec![1],
+		)
+		.unwrap()
+		.get(0)
+		.unwrap()
+		.clone();
+
+		// The receiver rejected the transfer, e.g. because of its own rate limit
+		let msg = MsgAcknowledgement {
+			packet: packet_info.into(),
+			acknowledgement: Acknowledgement::from(
+				br#"{"error":"transfer exceeds the rate limit"}"#.to_vec(),
+			),
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		assert_eq!(
+			<Assets as Inspect<AccountId>>::balance(2, &AccountId32::new([0; 32])),
+			balance
+		);
+		assert!(frame_system::Pallet::<Test>::events().iter().any(|record| matches!(
+			record.event,
+			Event::Ibc(crate::Event::TokenTransferFailed { .. })
+		)));
+	})
+}
+
+#[test]
+fn recv_packet_with_amount_above_u128_gets_error_ack() {
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address_bytes =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(ss58_address_bytes).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+
+		// Valid on Cosmos SDK chains, but larger than any runtime balance
+		let amount = "1000000000000000000000000000000000000000000";
+		let data = format!(
+			r#"{{"amount":"{}","denom":"uatom","receiver":"{}","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}}"#,
+			amount, ss58_address
+		);
+		let channel_id = ChannelId::new(0);
+		let msg = MsgRecvPacket {
+			packet: Packet {
+				sequence: 1u64.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: channel_id,
+				data: data.into_bytes(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+					1690894363u64.saturating_mul(1000000000),
+				)
+				.unwrap(),
+			},
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		// The packet was received and acknowledged with an error instead of failing the handler
+		let ctx = Context::<Test>::default();
+		let ack = ctx
+			.get_packet_acknowledgement(&(PortId::transfer(), channel_id, 1u64.into()))
+			.unwrap();
+		assert_ne!(ack, compute_ack_commitment(&Acknowledgement::from(ACK_SUCCESS_B64.to_vec())));
+		let events = frame_system::Pallet::<Test>::events();
+		assert!(!events.iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::Events { events }) if events.iter().any(|e| e.is_err())
+		)));
+		assert!(!events
+			.iter()
+			.any(|record| matches!(record.event, Event::Ibc(crate::Event::TokenReceived { .. }))));
+	})
+}
+
+#[test]
+fn recv_packet_with_unroutable_forward_gets_error_ack() {
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address_bytes =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(ss58_address_bytes).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+
+		// There is no channel-5 to forward the vouchers through
+		let memo = r#"{\"forward\":{\"receiver\":\"osmo1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2m6r5m7p\",\"port\":\"transfer\",\"channel\":\"channel-5\"}}"#;
+		let data = format!(
+			r#"{{"amount":"1000","denom":"uatom","memo":"{}","receiver":"{}","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}}"#,
+			memo, ss58_address
+		);
+		let channel_id = ChannelId::new(0);
+		let msg = MsgRecvPacket {
+			packet: Packet {
+				sequence: 1u64.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: channel_id,
+				data: data.into_bytes(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+					1690894363u64.saturating_mul(1000000000),
+				)
+				.unwrap(),
+			},
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		// The vouchers minted for the forward are burnt again and the sender is refunded on A
+		let ctx = Context::<Test>::default();
+		let ack = ctx
+			.get_packet_acknowledgement(&(PortId::transfer(), channel_id, 1u64.into()))
+			.unwrap();
+		assert_ne!(ack, compute_ack_commitment(&Acknowledgement::from(ACK_SUCCESS_B64.to_vec())));
+		let balance =
+			<Assets as Inspect<AccountId>>::balance(2, &AccountId32::new(pair.public().0));
+		assert_eq!(balance, 0);
+		assert_eq!(crate::InFlightForwards::<Test>::iter().count(), 0);
+	})
+}
+
+#[test]
+fn claim_refund_of_transfer_pending_on_closed_channel() {
+	let mut ext = new_test_ext();
+	let balance = 100000 * MILLIS;
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let raw_user = ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(raw_user).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				&"PICA".to_string(),
+			)
+			.unwrap();
+		<<Test as Config>::Fungibles as Mutate<
+			<Test as frame_system::Config>::AccountId,
+		>>::mint_into(asset_id, &AccountId32::new([0; 32]), balance).unwrap();
+
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+
+		Ibc::transfer(
+			Origin::signed(AccountId32::new([0; 32])),
+			TransferParams {
+				to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+				source_channel: 0,
+				timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				memo: None,
+			},
+			asset_id,
+			1000 * MILLIS,
+		)
+		.unwrap();
+	});
+
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		let packet_info = Pallet::<Test>::get_send_packet_info(
+			ChannelId::new(0).to_string().as_bytes().to_vec(),
+			PortId::transfer().as_bytes().to_vec(),
+			vec![1],
+		)
+		.unwrap()
+		.get(0)
+		.unwrap()
+		.clone();
+		let params = RefundParams {
+			source_channel: 0,
+			sequence: 1,
+			data: packet_info.data,
+			timeout_height: packet_info.timeout_height,
+			timeout_timestamp: packet_info.timeout_timestamp,
+			proof_unreceived: vec![0u8; 32],
+			proof_height: (0, 1),
+		};
+
+		assert_noop!(
+			Ibc::claim_refund(Origin::signed(AccountId32::new([0; 32])), params.clone()),
+			crate::Error::<Test>::ChannelNotClosed
+		);
+
+		let mut ctx = Context::<Test>::default();
+		let key = (PortId::transfer(), ChannelId::new(0));
+		let mut channel_end = ctx.channel_end(&key).unwrap();
+		channel_end.set_state(State::Closed);
+		ctx.store_channel(key, &channel_end).unwrap();
+
+		// The packet must be the one that was committed
+		assert_noop!(
+			Ibc::claim_refund(
+				Origin::signed(AccountId32::new([0; 32])),
+				RefundParams { timeout_timestamp: params.timeout_timestamp + 1, ..params.clone() }
+			),
+			crate::Error::<Test>::RefundFailed
+		);
+
+		// The counterparty may have received the packet before the channel was closed
+		assert_noop!(
+			Ibc::claim_refund(
+				Origin::signed(AccountId32::new([0; 32])),
+				RefundParams { proof_unreceived: vec![], ..params.clone() }
+			),
+			crate::Error::<Test>::InvalidRefundProof
+		);
+
+		// No proof of the counterparty's closure is needed, the local channel end is closed
+		assert_ok!(Ibc::claim_refund(Origin::signed(AccountId32::new([0; 32])), params.clone()));
+		assert_eq!(
+			<Assets as Inspect<AccountId>>::balance(2, &AccountId32::new([0; 32])),
+			balance
+		);
+
+		assert_noop!(
+			Ibc::claim_refund(Origin::signed(AccountId32::new([0; 32])), params),
+			crate::Error::<Test>::PacketCommitmentNotFound
+		);
+	})
+}
+
+#[test]
+fn async_acknowledgement_can_only_be_written_once() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let port_id = PortId::transfer();
+		let channel_id = ChannelId::new(0);
+		setup_client_and_consensus_state(port_id.clone());
+		// The module writes the acknowledgement through the handler once it has one.
+		let write = |sequence: u64| {
+			<Ibc as IbcHandler<AccountId>>::write_async_acknowledgement(
+				&port_id,
+				&channel_id,
+				sequence.into(),
+				b"oracle-result".to_vec(),
+			)
+		};
+
+		// Only received packets can be acknowledged.
+		assert!(matches!(write(1), Err(IbcHandlerError::WriteAcknowledgementError { .. })));
+
+		// The module deferred the acknowledgement, only the receipt was stored on receive.
+		let key = (port_id.clone(), channel_id, Sequence::from(1));
+		let mut ctx = Context::<Test>::default();
+		ctx.store_packet_receipt(key.clone(), Receipt::Ok).unwrap();
+		assert!(ctx.get_packet_acknowledgement(&key).is_err());
+
+		assert_ok!(write(1));
+		assert_eq!(
+			ctx.get_packet_acknowledgement(&key).unwrap(),
+			compute_ack_commitment(&b"oracle-result".to_vec().into())
+		);
+
+		assert!(matches!(write(1), Err(IbcHandlerError::WriteAcknowledgementError { .. })));
+	})
+}
+
+#[test]
+fn admin_can_write_async_acknowledgement_on_behalf_of_module() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let port_id = PortId::transfer();
+		let channel_id = ChannelId::new(0);
+		setup_client_and_consensus_state(port_id.clone());
+		let key = (port_id.clone(), channel_id, Sequence::from(2));
+		let mut ctx = Context::<Test>::default();
+		ctx.store_packet_receipt(key.clone(), Receipt::Ok).unwrap();
+		let write = |origin: Origin| {
+			Ibc::write_async_acknowledgement(
+				origin,
+				port_id.as_bytes().to_vec(),
+				channel_id.to_string().as_bytes().to_vec(),
+				2,
+				b"oracle-result".to_vec(),
+			)
+		};
+
+		assert_noop!(
+			write(Origin::signed(AccountId32::new([0; 32]))),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(write(Origin::root()));
+		assert_eq!(
+			ctx.get_packet_acknowledgement(&key).unwrap(),
+			compute_ack_commitment(&b"oracle-result".to_vec().into())
+		);
+		assert_noop!(write(Origin::root()), crate::Error::<Test>::WriteAckError);
+	})
+}
+
+#[test]
+fn send_packet_event_round_trips_timeouts_and_channel_ordering() {
+	let send_packet = SendPacket {
+		height: Height::new(0, 5),
+		packet: Packet {
+			sequence: 7u64.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(1),
+			// Packet data is not part of the pallet event.
+			data: vec![],
+			timeout_height: Height::new(1, 100),
+			timeout_timestamp: Timestamp::from_nanoseconds(1_000_000).unwrap(),
+		},
+		channel_ordering: Some(Order::Ordered),
+	};
+	let round_trip = |send_packet: SendPacket| {
+		let event = crate::events::IbcEvent::from(RawIbcEvent::SendPacket(send_packet));
+		let decoded = crate::events::IbcEvent::decode(&mut &event.encode()[..]).unwrap();
+		RawIbcEvent::try_from(decoded).unwrap()
+	};
+
+	match round_trip(send_packet.clone()) {
+		RawIbcEvent::SendPacket(ev) => assert_eq!(ev, send_packet),
+		ev => panic!("Expected SendPacket event, instead got {:?}", ev),
+	}
+
+	let unordered = SendPacket { channel_ordering: None, ..send_packet };
+	match round_trip(unordered.clone()) {
+		RawIbcEvent::SendPacket(ev) => assert_eq!(ev, unordered),
+		ev => panic!("Expected SendPacket event, instead got {:?}", ev),
+	}
+}
+
+#[test]
+fn update_client_event_round_trips_header_heights_and_consensus_root() {
+	let update_client = UpdateClient {
+		common: Attributes {
+			height: Height::new(0, 5),
+			client_id: ClientId::new(&MockClientState::client_type(), 0).unwrap(),
+			client_type: MockClientState::client_type(),
+			consensus_height: Height::new(1, 10),
+		},
+		// The header is not part of the pallet event.
+		header: None,
+		header_heights: vec![Height::new(1, 11), Height::new(1, 12)],
+		consensus_root: Some("0a0b".to_string()),
+	};
+	let round_trip = |update_client: UpdateClient| {
+		let event = crate::events::IbcEvent::from(RawIbcEvent::UpdateClient(update_client));
+		let decoded = crate::events::IbcEvent::decode(&mut &event.encode()[..]).unwrap();
+		RawIbcEvent::try_from(decoded).unwrap()
+	};
+
+	match round_trip(update_client.clone()) {
+		RawIbcEvent::UpdateClient(ev) => assert_eq!(ev, update_client),
+		ev => panic!("Expected UpdateClient event, instead got {:?}", ev),
+	}
+
+	let without_root = UpdateClient { consensus_root: None, ..update_client };
+	match round_trip(without_root.clone()) {
+		RawIbcEvent::UpdateClient(ev) => assert_eq!(ev, without_root),
+		ev => panic!("Expected UpdateClient event, instead got {:?}", ev),
+	}
+}
+
+#[test]
+fn store_wasm_code_is_restricted_to_governance() {
+	new_test_ext().execute_with(|| {
+		// The smallest valid wasm module.
+		let code = b"\0asm\x01\0\0\0".to_vec();
+		let code_hash = ics08_wasm::code::code_hash(&code);
+
+		assert_noop!(
+			Ibc::store_wasm_code(Origin::signed(AccountId32::new([0; 32])), code.clone()),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Ibc::store_wasm_code(Origin::root(), b"not wasm".to_vec()),
+			crate::Error::<Test>::InvalidWasmCode
+		);
+
+		assert_ok!(Ibc::store_wasm_code(Origin::root(), code.clone()));
+		assert_eq!(crate::WasmCodes::<Test>::get(&code_hash), Some(code.clone()));
+
+		assert_noop!(
+			Ibc::store_wasm_code(Origin::root(), code),
+			crate::Error::<Test>::WasmCodeAlreadyStored
+		);
+	})
+}
+
+/// Weight charged by `deliver` for a single update of a client with `client_message`.
+fn update_client_weight(client_message: AnyClientMessage) -> Weight {
+	let msg = MsgUpdateAnyClient::<Context<Test>> {
+		// A tendermint client id, the weight depends on the decoded message alone.
+		client_id: ClientId::new("07-tendermint", 0).unwrap(),
+		client_message,
+		signer: Signer::from_str("relayer").unwrap(),
+	};
+	let msg = Any { type_url: UPDATE_CLIENT_TYPE_URL.as_bytes().to_vec(), value: msg.encode_vec() };
+	crate::weight::deliver::<Test>(&[msg])
+}
+
+#[test]
+fn update_client_weight_is_selected_by_client_message_type() {
+	new_test_ext().execute_with(|| {
+		let commit = grandpa_client_primitives::Commit::<RelayChainHeader> {
+			target_hash: Default::default(),
+			target_number: 1,
+			precommits: vec![],
+		};
+		let justification =
+			GrandpaJustification::<RelayChainHeader> { round: 1, commit, votes_ancestries: vec![] };
+		let grandpa_header = |justification: Vec<u8>| {
+			let finality_proof = grandpa_client_primitives::FinalityProof {
+				block: Default::default(),
+				justification,
+				unknown_headers: vec![],
+			};
+			AnyClientMessage::Grandpa(GrandpaClientMessage::Header(GrandpaHeader {
+				finality_proof,
+				parachain_headers: Default::default(),
+			}))
+		};
+		assert_eq!(
+			update_client_weight(grandpa_header(justification.encode())),
+			<Test as Config>::WeightInfo::update_grandpa_client(0)
+		);
+		assert_eq!(update_client_weight(grandpa_header(vec![1, 2, 3])), Weight::MAX);
+
+		let parachain_header = BeefyParachainHeader {
+			parachain_header: sp_runtime::generic::Header::<u32, BlakeTwo256> {
+				parent_hash: Default::default(),
+				number: 1,
+				state_root: Default::default(),
+				extrinsics_root: Default::default(),
+				digest: Default::default(),
+			},
+			partial_mmr_leaf: PartialMmrLeaf {
+				version: MmrLeafVersion::new(0, 0),
+				parent_number_and_hash: (1, Default::default()),
+				beefy_next_authority_set: Default::default(),
+			},
+			parachain_heads_proof: vec![],
+			heads_leaf_index: 0,
+			heads_total_count: 1,
+			extrinsic_proof: vec![],
+			timestamp_extrinsic: vec![],
+		};
+		let headers_with_proof = ParachainHeadersWithProof {
+			headers: vec![parachain_header; 3],
+			mmr_proofs: vec![],
+			mmr_size: 1,
+		};
+		let beefy_header = |mmr_update_proof| {
+			AnyClientMessage::Beefy(BeefyClientMessage::Header(BeefyHeader {
+				headers_with_proof: Some(headers_with_proof.clone()),
+				mmr_update_proof,
+			}))
+		};
+		assert_eq!(
+			update_client_weight(beefy_header(None)),
+			<Test as Config>::WeightInfo::update_beefy_client(3, 0)
+		);
+		let commitment = Commitment {
+			payload: Payload::new(MMR_ROOT_ID, vec![0u8; 32]),
+			block_number: 2,
+			validator_set_id: 1,
+		};
+		let mmr_update_proof = MmrUpdateProof {
+			signed_commitment: SignedCommitment {
+				commitment,
+				signatures: vec![SignatureWithAuthorityIndex { signature: [0u8; 65], index: 0 }; 2],
+			},
+			latest_mmr_leaf: MmrLeaf {
+				version: MmrLeafVersion::new(0, 0),
+				parent_number_and_hash: (1, Default::default()),
+				beefy_next_authority_set: Default::default(),
+				leaf_extra: Default::default(),
+			},
+			mmr_proof: pallet_mmr_primitives::Proof { leaf_index: 1, leaf_count: 2, items: vec![] },
+			authority_proof: vec![],
+		};
+		assert_eq!(
+			update_client_weight(beefy_header(Some(mmr_update_proof))),
+			<Test as Config>::WeightInfo::update_beefy_client(3, 2)
+		);
+
+		let mock_header = MockClientMessage::Header(MockHeader::new(Height::new(0, 1)));
+		assert_eq!(update_client_weight(AnyClientMessage::Mock(mock_header)), 0);
+	})
+}
+
+#[test]
+fn unclassified_messages_are_charged_worst_case_and_refunded() {
+	new_test_ext().execute_with(|| {
+		let unknown = Any { type_url: b"/ibc.unknown.v1.MsgUnknown".to_vec(), value: vec![] };
+		let undecodable =
+			Any { type_url: UPDATE_CLIENT_TYPE_URL.as_bytes().to_vec(), value: vec![1, 2, 3] };
+		let worst_case = crate::weight::worst_case_message_weight::<Test>();
+		assert!(worst_case >= <Test as Config>::WeightInfo::update_beefy_client(100, 100));
+		assert_eq!(crate::weight::deliver::<Test>(&[unknown.clone()]), worst_case);
+		assert_eq!(crate::weight::deliver::<Test>(&[undecodable.clone()]), worst_case);
+
+		let messages = vec![unknown, undecodable];
+		let charged = crate::weight::deliver::<Test>(&messages);
+		let post_info = Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), messages).unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(2 * <Test as Config>::WeightInfo::reject_unknown_message())
+		);
+		assert!(post_info.actual_weight.unwrap() < charged);
+	})
+}
+
+#[test]
+fn failed_messages_are_refunded_down_to_their_rejection() {
+	new_test_ext().execute_with(|| {
+		let signer = signer_of(&AccountId32::new([0; 32]));
+		let create_client = MsgCreateAnyClient::<Context<Test>>::new(
+			AnyClientState::Mock(MockClientState::new(MockClientMessage::from(MockHeader::new(
+				Height::new(0, 1),
+			)))),
+			AnyConsensusState::Mock(MockConsensusState::new(MockHeader::new(Height::new(0, 1)))),
+			signer.clone(),
+		)
+		.unwrap();
+		let create_client =
+			Any { type_url: TYPE_URL.as_bytes().to_vec(), value: create_client.encode_vec() };
+
+		// Classified as a grandpa update, but there is no grandpa client to update.
+		let commit = grandpa_client_primitives::Commit::<RelayChainHeader> {
+			target_hash: Default::default(),
+			target_number: 1,
+			precommits: vec![],
+		};
+		let justification =
+			GrandpaJustification::<RelayChainHeader> { round: 1, commit, votes_ancestries: vec![] };
+		let update_client = MsgUpdateAnyClient::<Context<Test>> {
+			client_id: ClientId::new("10-grandpa", 0).unwrap(),
+			client_message: AnyClientMessage::Grandpa(GrandpaClientMessage::Header(GrandpaHeader {
+				finality_proof: grandpa_client_primitives::FinalityProof {
+					block: Default::default(),
+					justification: justification.encode(),
+					unknown_headers: vec![],
+				},
+				parachain_headers: Default::default(),
+			})),
+			signer,
+		};
+		let update_client = Any {
+			type_url: UPDATE_CLIENT_TYPE_URL.as_bytes().to_vec(),
+			value: update_client.encode_vec(),
+		};
+
+		let create_weight = <Test as Config>::WeightInfo::create_client();
+		let messages = vec![create_client, update_client];
+		let charged = crate::weight::deliver::<Test>(&messages);
+		assert_eq!(
+			charged,
+			create_weight + <Test as Config>::WeightInfo::update_grandpa_client(0)
+		);
+
+		let post_info = Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), messages).unwrap();
+		assert_eq!(
+			post_info.actual_weight,
+			Some(create_weight + <Test as Config>::WeightInfo::reject_unknown_message())
+		);
+		assert!(post_info.actual_weight.unwrap() < charged);
+	})
+}
+
+#[test]
+fn frozen_client_rejects_packets_until_unfrozen() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+		let ctx = Context::<Test>::default();
+		let client_id = ClientId::new(&MockClientState::client_type(), 0).unwrap();
+		let channel_id = ChannelId::new(0);
+		let has_event = |event: crate::Event<Test>| {
+			frame_system::Pallet::<Test>::events()
+				.iter()
+				.any(|record| record.event == Event::Ibc(event.clone()))
+		};
+
+		assert_noop!(
+			Ibc::freeze_client(Origin::root(), b"9999-mock-7".to_vec(), 1),
+			crate::Error::<Test>::ClientStateNotFound
+		);
+		assert_noop!(
+			Ibc::unfreeze_client(Origin::root(), client_id.as_bytes().to_vec()),
+			crate::Error::<Test>::ClientNotFrozen
+		);
+		assert_noop!(
+			Ibc::unfreeze_client(
+				Origin::signed(AccountId32::new([0; 32])),
+				client_id.as_bytes().to_vec()
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		assert_ok!(Ibc::freeze_client(Origin::root(), client_id.as_bytes().to_vec(), 1));
+		assert!(ctx.client_state(&client_id).unwrap().is_frozen());
+		assert!(has_event(crate::Event::ClientFrozen {
+			client_id: client_id.as_bytes().to_vec(),
+			height: 1,
+			revision_number: 0,
+		}));
+
+		let packet_data = PacketData {
+			token: Coin {
+				denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+				amount: ibc::applications::transfer::Amount::from_str("1000").unwrap(),
+			},
+			sender: Signer::from_str("alice").unwrap(),
+			receiver: Signer::from_str("bob").unwrap(),
+			memo: String::new(),
+		};
+		let msg = MsgRecvPacket {
+			packet: Packet {
+				sequence: 1u64.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: channel_id,
+				data: serde_json::to_vec(&packet_data).unwrap(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: Timestamp::none(),
+			},
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+		let receipt_key = (PortId::transfer(), channel_id, 1u64.into());
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg.clone()]).unwrap();
+		assert!(ctx.get_packet_receipt(&receipt_key).is_err());
+		assert!(frame_system::Pallet::<Test>::events().iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::Events { events }) if events.iter().any(|e| e.is_err())
+		)));
+
+		assert_ok!(Ibc::unfreeze_client(Origin::root(), client_id.as_bytes().to_vec()));
+		assert!(!ctx.client_state(&client_id).unwrap().is_frozen());
+		let client_id_bytes = client_id.as_bytes().to_vec();
+		assert!(has_event(crate::Event::ClientUnfrozen { client_id: client_id_bytes }));
+
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+		assert!(ctx.get_packet_receipt(&receipt_key).is_ok());
+	})
+}
+
+#[test]
+fn first_receive_registers_asset_that_round_trips() {
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address_bytes =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let ss58_address = String::from_utf8(ss58_address_bytes).unwrap();
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		Ibc::set_params(Origin::root(), PalletParams { send_enabled: true, receive_enabled: true })
+			.unwrap();
+
+		let voucher = b"transfer/channel-0/uATOM".to_vec();
+		assert_eq!(crate::IbcDenoms::<Test>::get(&voucher), None);
+
+		let data = format!(
+			r#"{{"amount":"1000","denom":"uATOM","receiver":"{}","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}}"#,
+			ss58_address
+		);
+		let msg = MsgRecvPacket {
+			packet: Packet {
+				sequence: 1u64.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: ChannelId::new(0),
+				data: data.into_bytes(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: Timestamp::none(),
+			},
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: signer_of(&AccountId32::new([0; 32])),
+		};
+		let msg = Any { type_url: msg.type_url().as_bytes().to_vec(), value: msg.encode_vec() };
+		Ibc::deliver(Origin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		// The voucher is registered under a new asset id, mapped both ways.
+		let asset_id = crate::IbcDenoms::<Test>::get(&voucher).unwrap();
+		assert_ne!(asset_id, 2);
+		assert_eq!(crate::IbcAssetIds::<Test>::get(asset_id), Some(voucher.clone()));
+		let account = AccountId32::new(pair.public().0);
+		assert_eq!(<Assets as Inspect<AccountId>>::balance(asset_id, &account), 1000);
+		assert_eq!(<Assets as InspectMetadata<AccountId>>::symbol(&asset_id), b"uATOM".to_vec());
+
+		assert_noop!(
+			Ibc::set_asset_metadata(
+				Origin::signed(AccountId32::new([0; 32])),
+				asset_id,
+				b"ATOM".to_vec(),
+				6
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			Ibc::set_asset_metadata(Origin::root(), 7, b"ATOM".to_vec(), 6),
+			crate::Error::<Test>::InvalidAssetId
+		);
+		assert_ok!(Ibc::set_asset_metadata(Origin::root(), asset_id, b"ATOM".to_vec(), 6));
+		assert_eq!(<Assets as InspectMetadata<AccountId>>::symbol(&asset_id), b"ATOM".to_vec());
+		assert_eq!(<Assets as InspectMetadata<AccountId>>::decimals(&asset_id), 6);
+		assert_eq!(<Assets as InspectMetadata<AccountId>>::name(&asset_id), voucher);
+
+		// Sending the voucher back resolves to the original denom trace and burns it.
+		Ibc::transfer(
+			Origin::signed(account.clone()),
+			TransferParams {
+				to: MultiAddress::Raw(b"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng".to_vec()),
+				source_channel: 0,
+				timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				memo: None,
+			},
+			asset_id,
+			1000,
+		)
+		.unwrap();
+		assert_eq!(<Assets as Inspect<AccountId>>::balance(asset_id, &account), 0);
+		assert!(frame_system::Pallet::<Test>::events().iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::TokenTransferInitiated { ibc_denom, local_asset_id, .. })
+				if *ibc_denom == voucher && *local_asset_id == Some(asset_id)
+		)));
+	})
+}
+
+#[test]
+fn housekeeping_prunes_consensus_states_within_the_idle_weight() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let client_id = ClientId::from_str("07-tendermint-0").unwrap();
+		let mut ctx = Context::<Test>::default();
+		let mock_cs_state = MockConsensusState::new(MockHeader::default());
+		for i in 1..=6u64 {
+			ctx.store_consensus_state(
+				client_id.clone(),
+				Height::new(0, i),
+				AnyConsensusState::Mock(mock_cs_state.clone()),
+			)
+			.unwrap();
+		}
+
+		// `ConsensusStateRetention` is 2 in the mock runtime, four states have expired but the
+		// weight left only covers two of them.
+		let prune_weight = <Test as Config>::WeightInfo::prune_consensus_state();
+		assert_eq!(Ibc::on_idle(1, 2 * prune_weight + prune_weight / 2), 2 * prune_weight);
+		let stored_heights = ConsensusHeights::<Test>::get(client_id.as_bytes().to_vec());
+		assert_eq!(stored_heights.len(), 4);
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 2)).is_err());
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 3)).is_ok());
+		assert!(frame_system::Pallet::<Test>::events().iter().any(|record| matches!(
+			record.event,
+			Event::Ibc(crate::Event::HousekeepingPerformed { consensus_states_pruned: 2, .. })
+		)));
+
+		// The next block picks up the remaining ones.
+		frame_system::Pallet::<Test>::set_block_number(2u32);
+		assert_eq!(Ibc::on_idle(2, 10 * prune_weight), 2 * prune_weight);
+		let stored_heights = ConsensusHeights::<Test>::get(client_id.as_bytes().to_vec());
+		assert_eq!(
+			stored_heights.into_iter().collect::<Vec<_>>(),
+			vec![Height::new(0, 5), Height::new(0, 6)]
+		);
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 4)).is_err());
+
+		assert_eq!(Ibc::on_idle(3, 10 * prune_weight), 0);
+	})
+}
+
+#[test]
+fn housekeeping_cancels_stale_connections_and_keeps_receipts() {
+	new_test_ext().execute_with(|| {
+		let mut ctx = Context::<Test>::default();
+		let commitment_prefix: CommitmentPrefix =
+			<Test as Config>::PALLET_PREFIX.to_vec().try_into().unwrap();
+		let connection_end = ConnectionEnd::new(
+			ConnState::Init,
+			ClientId::from_str("07-tendermint-0").unwrap(),
+			Counterparty::new(
+				ClientId::from_str("07-tendermint-1").unwrap(),
+				None,
+				commitment_prefix,
+			),
+			vec![ConnVersion::default()],
+			Duration::from_nanos(0),
+		);
+		ctx.store_connection(ConnectionId::new(0), &connection_end).unwrap();
+		ctx.store_connection_handshake_origin(
+			ConnectionId::new(0),
+			HandshakeOrigin {
+				signer: Signer::from_str("alice").unwrap(),
+				height: Height::new(0, 1),
+			},
+		)
+		.unwrap();
+
+		let port_id = PortId::transfer();
+		let channel_id = ChannelId::new(0);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			ChanCounterParty::new(port_id.clone(), Some(channel_id)),
+			vec![ConnectionId::new(1)],
+			ChanVersion::new(VERSION.to_string()),
+		);
+		ctx.store_channel((port_id.clone(), channel_id), &channel_end).unwrap();
+		for seq in 1..=4u64 {
+			ctx.store_packet_receipt((port_id.clone(), channel_id, seq.into()), Receipt::Ok)
+				.unwrap();
+			ctx.store_packet_acknowledgement(
+				(port_id.clone(), channel_id, seq.into()),
+				vec![1u8].into(),
+			)
+			.unwrap();
+		}
+		let has_receipt = |seq: u64| {
+			Context::<Test>::default()
+				.get_packet_receipt(&(port_id.clone(), channel_id, seq.into()))
+				.is_ok()
+		};
+
+		// The handshake hasn't timed out yet.
+		frame_system::Pallet::<Test>::set_block_number(100u32);
+		let cancel_weight = <Test as Config>::WeightInfo::cancel_stale_connection();
+		Ibc::on_idle(100, 10 * cancel_weight);
+		assert!(ctx.connection_end(&ConnectionId::new(0)).is_ok());
+
+		// `ConnectionHandshakeTimeout` is 100 in the mock runtime.
+		frame_system::Pallet::<Test>::set_block_number(101u32);
+		assert!(Ibc::on_idle(101, 10 * cancel_weight) >= cancel_weight);
+		assert!(ctx.connection_end(&ConnectionId::new(0)).is_err());
+		assert!(ctx.connection_handshake_origin(&ConnectionId::new(0)).is_err());
+		let events = frame_system::Pallet::<Test>::events();
+		assert!(events.iter().any(|record| matches!(
+			record.event,
+			Event::Ibc(crate::Event::HousekeepingPerformed {
+				consensus_states_pruned: 0,
+				connections_cancelled: 1,
+			})
+		)));
+		assert!(events.iter().any(|record| matches!(
+			&record.event,
+			Event::Ibc(crate::Event::Events { events })
+				if matches!(events[..], [Ok(crate::events::IbcEvent::ConnectionCancelled { .. })])
+		)));
+
+		// Acknowledged receipts of open channels are left alone, the acknowledgements may not
+		// have been relayed yet.
+		assert!((1..=4).all(has_receipt));
+	})
+}
@@ -0,0 +1,20 @@
+use super::*;
+
+use crate::routing::Context;
+use ics08_wasm::{
+	context::{WasmCodeKeeper, WasmCodeReader},
+	error::Error as ICS08Error,
+};
+
+impl<T: Config + Sync + Send> WasmCodeReader for Context<T> {
+	fn wasm_code(&self, code_hash: &[u8]) -> Option<Vec<u8>> {
+		<WasmCodes<T>>::get(code_hash)
+	}
+}
+
+impl<T: Config + Sync + Send> WasmCodeKeeper for Context<T> {
+	fn store_wasm_code(&mut self, code_hash: Vec<u8>, code: Vec<u8>) -> Result<(), ICS08Error> {
+		<WasmCodes<T>>::insert(code_hash, code);
+		Ok(())
+	}
+}
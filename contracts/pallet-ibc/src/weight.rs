@@ -1,5 +1,5 @@
 use super::*;
-use crate::{light_clients::AnyClientMessage, routing::Context};
+use crate::{impls::MessageOutcome, light_clients::AnyClientMessage, routing::Context};
 use core::marker::PhantomData;
 use frame_support::pallet_prelude::Weight;
 use grandpa_client_primitives::justification::GrandpaJustification;
@@ -12,6 +12,7 @@ use ibc::core::{
 };
 use ibc_primitives::{client_id_from_bytes, CallbackWeight};
 use ics10_grandpa::client_message::{ClientMessage, RelayChainHeader};
+use ics11_beefy::client_message::ClientMessage as BeefyClientMessage;
 use scale_info::prelude::string::ToString;
 
 pub trait WeightInfo {
@@ -42,6 +43,12 @@ pub trait WeightInfo {
 	fn on_acknowledgement_packet() -> Weight;
 	fn on_timeout_packet() -> Weight;
 	fn update_grandpa_client(i: u32) -> Weight;
+	fn update_beefy_client(i: u32, j: u32) -> Weight;
+	fn reject_unknown_message() -> Weight;
+	fn prune_consensus_state() -> Weight;
+	fn cancel_stale_connection() -> Weight;
+	fn recover_client() -> Weight;
+	fn write_async_acknowledgement() -> Weight;
 }
 
 impl WeightInfo for () {
@@ -152,6 +159,30 @@ impl WeightInfo for () {
 	fn update_grandpa_client(_i: u32) -> Weight {
 		0
 	}
+
+	fn update_beefy_client(_i: u32, _j: u32) -> Weight {
+		0
+	}
+
+	fn reject_unknown_message() -> Weight {
+		0
+	}
+
+	fn prune_consensus_state() -> Weight {
+		0
+	}
+
+	fn cancel_stale_connection() -> Weight {
+		0
+	}
+
+	fn recover_client() -> Weight {
+		0
+	}
+
+	fn write_async_acknowledgement() -> Weight {
+		0
+	}
 }
 
 pub struct WeightRouter<T: Config>(PhantomData<T>);
@@ -180,364 +211,459 @@ pub fn channel_client<T: Config>(channel_id: &[u8], port_id: &[u8]) -> Result<Cl
 	Err(Error::<T>::Other)
 }
 
-pub(crate) fn deliver<T: Config + Send + Sync>(msgs: &[Any]) -> Weight
+/// Benchmarked weight of updating a client with a single client message, selected by the client
+/// type of the decoded message.
+pub(crate) fn update_client_weight<T: Config>(client_message: &AnyClientMessage) -> Weight {
+	match client_message {
+		AnyClientMessage::Tendermint(_) => <T as Config>::WeightInfo::update_tendermint_client(),
+		AnyClientMessage::Grandpa(ClientMessage::Header(header)) =>
+			GrandpaJustification::<RelayChainHeader>::decode(
+				&mut &*header.finality_proof.justification,
+			)
+			.map(|justification| {
+				<T as Config>::WeightInfo::update_grandpa_client(
+					justification.commit.precommits.len() as u32,
+				)
+			})
+			// The update is rejected on an undecodable justification anyway, charge the most.
+			.unwrap_or(Weight::MAX),
+		AnyClientMessage::Beefy(BeefyClientMessage::Header(header)) => {
+			let parachain_headers = header
+				.headers_with_proof
+				.as_ref()
+				.map(|headers_with_proof| headers_with_proof.headers.len() as u32)
+				.unwrap_or_default();
+			let signatures = header
+				.mmr_update_proof
+				.as_ref()
+				.map(|mmr_update| mmr_update.signed_commitment.signatures.len() as u32)
+				.unwrap_or_default();
+			<T as Config>::WeightInfo::update_beefy_client(parachain_headers, signatures)
+		},
+		// Not benchmarked yet, charged like a tendermint update which also verifies the
+		// signatures of a validator committee.
+		AnyClientMessage::Ethereum(_) => <T as Config>::WeightInfo::update_tendermint_client(),
+		AnyClientMessage::Grandpa(ClientMessage::Misbehaviour(_)) |
+		AnyClientMessage::Beefy(BeefyClientMessage::Misbehaviour(_)) => Weight::default(),
+		#[cfg(test)]
+		AnyClientMessage::Mock(_) => Weight::default(),
+	}
+}
+
+/// Benchmarked weight of a message along with its module callback, `None` if the message can't
+/// be decoded into one the pallet handles.
+pub(crate) fn message_weight<T: Config + Send + Sync>(msg: &Any) -> Option<Weight>
 where
 	u32: From<<T as frame_system::Config>::BlockNumber>,
 {
-	msgs.iter()
-		.filter_map(|msg| {
-			let type_url = String::from_utf8(msg.type_url.clone()).unwrap_or_default();
-			let msg = ibc_proto::google::protobuf::Any { type_url, value: msg.value.clone() };
-			let msg: Option<Ics26Envelope<Context<T>>> = msg.try_into().ok();
-			msg
-		})
-		.fold(Weight::default(), |acc, msg| {
-			// Add benchmarked weight for that message type
-			// Add benchmarked weight for module callback
-			let temp = match msg {
-				Ics26Envelope::Ics2Msg(msgs) => match msgs {
-					ClientMsg::CreateClient(_) => <T as Config>::WeightInfo::create_client(),
-					ClientMsg::UpdateClient(msg) => {
-						let client_type = msg
-							.client_id
+	let type_url = String::from_utf8(msg.type_url.clone()).ok()?;
+	let msg = ibc_proto::google::protobuf::Any { type_url, value: msg.value.clone() };
+	let msg: Ics26Envelope<Context<T>> = msg.try_into().ok()?;
+	let weight = match msg {
+		Ics26Envelope::Ics2Msg(msgs) => match msgs {
+			ClientMsg::CreateClient(_) => <T as Config>::WeightInfo::create_client(),
+			ClientMsg::UpdateClient(msg) => update_client_weight::<T>(&msg.client_message),
+			ClientMsg::UpdateClientBatch(msg) =>
+				msg.client_messages.iter().fold(Weight::default(), |acc, client_message| {
+					acc.saturating_add(update_client_weight::<T>(client_message))
+				}),
+			ClientMsg::UpgradeClient(_) => Weight::default(),
+			ClientMsg::RecoverClient(_) => <T as Config>::WeightInfo::recover_client(),
+			ClientMsg::Misbehaviour(_) => Weight::default(),
+		},
+		Ics26Envelope::Ics3Msg(msgs) => match msgs {
+			ConnectionMsg::ConnectionOpenInit(_) =>
+				<T as Config>::WeightInfo::conn_open_init(),
+			ConnectionMsg::ConnectionOpenTry(msg) => {
+				let client_type = msg
+					.client_id
+					.as_str()
+					.rsplit_once('-')
+					.map(|(client_type_str, ..)| client_type_str);
+				match client_type {
+					Some(ty) if ty.contains("tendermint") =>
+						<T as Config>::WeightInfo::conn_try_open_tendermint(),
+					_ => Weight::default(),
+				}
+			},
+			ConnectionMsg::ConnectionOpenAck(msg) => {
+				let connection_id = msg.connection_id;
+				let ctx = routing::Context::<T>::new();
+				let connection_end = ctx.connection_end(&connection_id).unwrap_or_default();
+				let client_type = connection_end
+					.client_id()
+					.as_str()
+					.rsplit_once('-')
+					.map(|(client_type_str, ..)| client_type_str);
+				match client_type {
+					Some(ty) if ty.contains("tendermint") =>
+						<T as Config>::WeightInfo::conn_open_ack_tendermint(),
+					_ => Weight::default(),
+				}
+			},
+			ConnectionMsg::ConnectionOpenConfirm(msg) => {
+				let connection_id = msg.connection_id;
+				let ctx = routing::Context::<T>::new();
+				let connection_end = ctx.connection_end(&connection_id).unwrap_or_default();
+				let client_type = connection_end
+					.client_id()
+					.as_str()
+					.rsplit_once('-')
+					.map(|(client_type_str, ..)| client_type_str);
+				match client_type {
+					Some(ty) if ty.contains("tendermint") =>
+						<T as Config>::WeightInfo::conn_open_confirm_tendermint(),
+					_ => Weight::default(),
+				}
+			},
+			ConnectionMsg::ConnectionCancel(_) => Weight::default(),
+		},
+		Ics26Envelope::Ics4ChannelMsg(msgs) => match msgs {
+			ChannelMsg::ChannelOpenInit(channel_msg) => {
+				let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+					.unwrap_or_else(|| Box::new(()));
+				let cb_weight = cb.on_chan_open_init();
+				let lc_verification_weight = match channel_msg.channel.connection_hops.get(0) {
+					Some(connection_id) => {
+						let ctx = routing::Context::<T>::new();
+						let connection_end = ctx.connection_end(connection_id).unwrap_or_default();
+						let client_type = connection_end
+							.client_id()
 							.as_str()
 							.rsplit_once('-')
 							.map(|(client_type_str, ..)| client_type_str);
 						match client_type {
 							Some(ty) if ty.contains("tendermint") =>
-								<T as Config>::WeightInfo::update_tendermint_client(),
-							Some(ty) if ty.contains("grandpa") => match msg.client_message {
-								AnyClientMessage::Grandpa(client_message) => match client_message {
-									ClientMessage::Header(header) => {
-										let justification =
-											GrandpaJustification::<RelayChainHeader>::decode(
-												&mut &*header.finality_proof.justification,
-											)
-											.expect("Justification should be valid");
-										<T as Config>::WeightInfo::update_grandpa_client(
-											justification.commit.precommits.len() as u32,
-										)
-									},
-									ClientMessage::Misbehaviour(_) => Weight::default(),
-								},
-								_ => return Weight::MAX,
-							},
+								<T as Config>::WeightInfo::channel_open_init(),
 							_ => Weight::default(),
 						}
 					},
-					ClientMsg::UpgradeClient(_) => Weight::default(),
-				},
-				Ics26Envelope::Ics3Msg(msgs) => match msgs {
-					ConnectionMsg::ConnectionOpenInit(_) =>
-						<T as Config>::WeightInfo::conn_open_init(),
-					ConnectionMsg::ConnectionOpenTry(msg) => {
-						let client_type = msg
-							.client_id
+					None => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			ChannelMsg::ChannelOpenTry(channel_msg) => {
+				let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+					.unwrap_or_else(|| Box::new(()));
+				let cb_weight = cb.on_chan_open_try();
+				let lc_verification_weight = match channel_msg.channel.connection_hops.get(0) {
+					Some(connection_id) => {
+						let ctx = routing::Context::<T>::new();
+						let connection_end = ctx.connection_end(connection_id).unwrap_or_default();
+						let client_type = connection_end
+							.client_id()
 							.as_str()
 							.rsplit_once('-')
 							.map(|(client_type_str, ..)| client_type_str);
 						match client_type {
 							Some(ty) if ty.contains("tendermint") =>
-								<T as Config>::WeightInfo::conn_try_open_tendermint(),
+								<T as Config>::WeightInfo::channel_open_try_tendermint(),
 							_ => Weight::default(),
 						}
 					},
-					ConnectionMsg::ConnectionOpenAck(msg) => {
-						let connection_id = msg.connection_id;
-						let ctx = routing::Context::<T>::new();
-						let connection_end = ctx.connection_end(&connection_id).unwrap_or_default();
-						let client_type = connection_end
-							.client_id()
+					None => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			ChannelMsg::ChannelOpenAck(channel_msg) => {
+				let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+					.unwrap_or_else(|| Box::new(()));
+				let cb_weight = cb.on_chan_open_ack(&channel_msg.port_id, &channel_msg.channel_id);
+				let lc_verification_weight = match channel_client::<T>(
+					channel_msg.port_id.as_bytes(),
+					channel_msg.channel_id.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
 							.as_str()
 							.rsplit_once('-')
 							.map(|(client_type_str, ..)| client_type_str);
 						match client_type {
 							Some(ty) if ty.contains("tendermint") =>
-								<T as Config>::WeightInfo::conn_open_ack_tendermint(),
+								<T as Config>::WeightInfo::channel_open_ack_tendermint(),
 							_ => Weight::default(),
 						}
 					},
-					ConnectionMsg::ConnectionOpenConfirm(msg) => {
-						let connection_id = msg.connection_id;
-						let ctx = routing::Context::<T>::new();
-						let connection_end = ctx.connection_end(&connection_id).unwrap_or_default();
-						let client_type = connection_end
-							.client_id()
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			ChannelMsg::ChannelOpenConfirm(channel_msg) => {
+				let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+					.unwrap_or_else(|| Box::new(()));
+				let cb_weight =
+					cb.on_chan_open_confirm(&channel_msg.port_id, &channel_msg.channel_id);
+				let lc_verification_weight = match channel_client::<T>(
+					channel_msg.port_id.as_bytes(),
+					channel_msg.channel_id.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
 							.as_str()
 							.rsplit_once('-')
 							.map(|(client_type_str, ..)| client_type_str);
 						match client_type {
 							Some(ty) if ty.contains("tendermint") =>
-								<T as Config>::WeightInfo::conn_open_confirm_tendermint(),
+								<T as Config>::WeightInfo::channel_open_confirm_tendermint(),
 							_ => Weight::default(),
 						}
 					},
-				},
-				Ics26Envelope::Ics4ChannelMsg(msgs) => match msgs {
-					ChannelMsg::ChannelOpenInit(channel_msg) => {
-						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
-							.unwrap_or_else(|| Box::new(()));
-						let cb_weight = cb.on_chan_open_init();
-						let lc_verification_weight =
-							match channel_msg.channel.connection_hops.get(0) {
-								Some(connection_id) => {
-									let ctx = routing::Context::<T>::new();
-									let connection_end =
-										ctx.connection_end(connection_id).unwrap_or_default();
-									let client_type = connection_end
-										.client_id()
-										.as_str()
-										.rsplit_once('-')
-										.map(|(client_type_str, ..)| client_type_str);
-									match client_type {
-										Some(ty) if ty.contains("tendermint") =>
-											<T as Config>::WeightInfo::channel_open_init(),
-										_ => Weight::default(),
-									}
-								},
-								None => Weight::default(),
-							};
-						cb_weight.saturating_add(lc_verification_weight)
-					},
-					ChannelMsg::ChannelOpenTry(channel_msg) => {
-						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
-							.unwrap_or_else(|| Box::new(()));
-						let cb_weight = cb.on_chan_open_try();
-						let lc_verification_weight =
-							match channel_msg.channel.connection_hops.get(0) {
-								Some(connection_id) => {
-									let ctx = routing::Context::<T>::new();
-									let connection_end =
-										ctx.connection_end(connection_id).unwrap_or_default();
-									let client_type = connection_end
-										.client_id()
-										.as_str()
-										.rsplit_once('-')
-										.map(|(client_type_str, ..)| client_type_str);
-									match client_type {
-										Some(ty) if ty.contains("tendermint") =>
-											<T as Config>::WeightInfo::channel_open_try_tendermint(),
-										_ => Weight::default(),
-									}
-								},
-								None => Weight::default(),
-							};
-						cb_weight.saturating_add(lc_verification_weight)
-					},
-					ChannelMsg::ChannelOpenAck(channel_msg) => {
-						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
-							.unwrap_or_else(|| Box::new(()));
-						let cb_weight =
-							cb.on_chan_open_ack(&channel_msg.port_id, &channel_msg.channel_id);
-						let lc_verification_weight = match channel_client::<T>(
-							channel_msg.port_id.as_bytes(),
-							channel_msg.channel_id.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_open_ack_tendermint(),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
-					},
-					ChannelMsg::ChannelOpenConfirm(channel_msg) => {
-						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
-							.unwrap_or_else(|| Box::new(()));
-						let cb_weight =
-							cb.on_chan_open_confirm(&channel_msg.port_id, &channel_msg.channel_id);
-						let lc_verification_weight = match channel_client::<T>(
-							channel_msg.port_id.as_bytes(),
-							channel_msg.channel_id.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_open_confirm_tendermint(),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
-					},
-					ChannelMsg::ChannelCloseInit(channel_msg) => {
-						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
-							.unwrap_or_else(|| Box::new(()));
-						let cb_weight =
-							cb.on_chan_close_init(&channel_msg.port_id, &channel_msg.channel_id);
-						let lc_verification_weight = match channel_client::<T>(
-							channel_msg.port_id.as_bytes(),
-							channel_msg.channel_id.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_close_init(),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			ChannelMsg::ChannelCloseInit(channel_msg) => {
+				let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+					.unwrap_or_else(|| Box::new(()));
+				let cb_weight =
+					cb.on_chan_close_init(&channel_msg.port_id, &channel_msg.channel_id);
+				let lc_verification_weight = match channel_client::<T>(
+					channel_msg.port_id.as_bytes(),
+					channel_msg.channel_id.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
+							.as_str()
+							.rsplit_once('-')
+							.map(|(client_type_str, ..)| client_type_str);
+						match client_type {
+							Some(ty) if ty.contains("tendermint") =>
+								<T as Config>::WeightInfo::channel_close_init(),
+							_ => Weight::default(),
+						}
 					},
-					ChannelMsg::ChannelCloseConfirm(channel_msg) => {
-						let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
-							.unwrap_or_else(|| Box::new(()));
-						let cb_weight =
-							cb.on_chan_close_confirm(&channel_msg.port_id, &channel_msg.channel_id);
-						let lc_verification_weight = match channel_client::<T>(
-							channel_msg.port_id.as_bytes(),
-							channel_msg.channel_id.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::channel_close_confirm_tendermint(
-										),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			ChannelMsg::ChannelCloseConfirm(channel_msg) => {
+				let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+					.unwrap_or_else(|| Box::new(()));
+				let cb_weight =
+					cb.on_chan_close_confirm(&channel_msg.port_id, &channel_msg.channel_id);
+				let lc_verification_weight = match channel_client::<T>(
+					channel_msg.port_id.as_bytes(),
+					channel_msg.channel_id.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
+							.as_str()
+							.rsplit_once('-')
+							.map(|(client_type_str, ..)| client_type_str);
+						match client_type {
+							Some(ty) if ty.contains("tendermint") =>
+								<T as Config>::WeightInfo::channel_close_confirm_tendermint(),
+							_ => Weight::default(),
+						}
 					},
-				},
-				Ics26Envelope::Ics4PacketMsg(msgs) => match msgs {
-					PacketMsg::RecvPacket(packet_msg) => {
-						let cb = WeightRouter::<T>::get_weight(
-							packet_msg.packet.destination_port.as_str(),
-						)
-						.unwrap_or_else(|| Box::new(()));
-						let cb_weight = cb.on_recv_packet(&packet_msg.packet);
-						let lc_verification_weight = match channel_client::<T>(
-							packet_msg.packet.destination_port.as_bytes(),
-							packet_msg.packet.destination_channel.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::recv_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			// Closing on a frozen client verifies no proof, only the callback is charged.
+			ChannelMsg::ChannelCloseFrozen(channel_msg) => {
+				let cb = WeightRouter::<T>::get_weight(channel_msg.port_id.as_str())
+					.unwrap_or_else(|| Box::new(()));
+				cb.on_chan_close_confirm(&channel_msg.port_id, &channel_msg.channel_id)
+			},
+			ChannelMsg::ChannelUpgradeInit(_) |
+			ChannelMsg::ChannelUpgradeTry(_) |
+			ChannelMsg::ChannelUpgradeAck(_) |
+			ChannelMsg::ChannelUpgradeConfirm(_) |
+			ChannelMsg::ChannelUpgradeCancel(_) => Weight::default(),
+		},
+		Ics26Envelope::Ics4PacketMsg(msgs) => match msgs {
+			PacketMsg::RecvPacket(packet_msg) => {
+				let cb = WeightRouter::<T>::get_weight(
+					packet_msg.packet.destination_port.as_str(),
+				)
+				.unwrap_or_else(|| Box::new(()));
+				let cb_weight = cb.on_recv_packet(&packet_msg.packet);
+				let lc_verification_weight = match channel_client::<T>(
+					packet_msg.packet.destination_port.as_bytes(),
+					packet_msg.packet.destination_channel.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
+							.as_str()
+							.rsplit_once('-')
+							.map(|(client_type_str, ..)| client_type_str);
+						match client_type {
+							Some(ty) if ty.contains("tendermint") =>
+								<T as Config>::WeightInfo::recv_packet_tendermint(
+									packet_msg.packet.data.len() as u32,
+								),
+							_ => Weight::default(),
+						}
 					},
-					PacketMsg::AckPacket(packet_msg) => {
-						let cb = WeightRouter::<T>::get_weight(
-							packet_msg.packet.destination_port.as_str(),
-						)
-						.unwrap_or_else(|| Box::new(()));
-						let cb_weight = cb.on_acknowledgement_packet(
-							&packet_msg.packet,
-							&packet_msg.acknowledgement,
-						);
-						let lc_verification_weight = match channel_client::<T>(
-							packet_msg.packet.destination_port.as_bytes(),
-							packet_msg.packet.destination_channel.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::ack_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-											packet_msg.acknowledgement.into_bytes().len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			PacketMsg::AckPacket(packet_msg) => {
+				let cb = WeightRouter::<T>::get_weight(
+					packet_msg.packet.destination_port.as_str(),
+				)
+				.unwrap_or_else(|| Box::new(()));
+				let cb_weight = cb.on_acknowledgement_packet(
+					&packet_msg.packet,
+					&packet_msg.acknowledgement,
+				);
+				let lc_verification_weight = match channel_client::<T>(
+					packet_msg.packet.destination_port.as_bytes(),
+					packet_msg.packet.destination_channel.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
+							.as_str()
+							.rsplit_once('-')
+							.map(|(client_type_str, ..)| client_type_str);
+						match client_type {
+							Some(ty) if ty.contains("tendermint") =>
+								<T as Config>::WeightInfo::ack_packet_tendermint(
+									packet_msg.packet.data.len() as u32,
+									packet_msg.acknowledgement.into_bytes().len() as u32,
+								),
+							_ => Weight::default(),
+						}
 					},
-					PacketMsg::ToPacket(packet_msg) => {
-						let cb = WeightRouter::<T>::get_weight(
-							packet_msg.packet.destination_port.as_str(),
-						)
-						.unwrap_or_else(|| Box::new(()));
-						let cb_weight = cb.on_timeout_packet(&packet_msg.packet);
-						let lc_verification_weight = match channel_client::<T>(
-							packet_msg.packet.destination_port.as_bytes(),
-							packet_msg.packet.destination_channel.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::timeout_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			PacketMsg::ToPacket(packet_msg) => {
+				let cb = WeightRouter::<T>::get_weight(
+					packet_msg.packet.destination_port.as_str(),
+				)
+				.unwrap_or_else(|| Box::new(()));
+				let cb_weight = cb.on_timeout_packet(&packet_msg.packet);
+				let lc_verification_weight = match channel_client::<T>(
+					packet_msg.packet.destination_port.as_bytes(),
+					packet_msg.packet.destination_channel.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
+							.as_str()
+							.rsplit_once('-')
+							.map(|(client_type_str, ..)| client_type_str);
+						match client_type {
+							Some(ty) if ty.contains("tendermint") =>
+								<T as Config>::WeightInfo::timeout_packet_tendermint(
+									packet_msg.packet.data.len() as u32,
+								),
+							_ => Weight::default(),
+						}
 					},
-					PacketMsg::ToClosePacket(packet_msg) => {
-						let cb = WeightRouter::<T>::get_weight(
-							packet_msg.packet.destination_port.as_str(),
-						)
-						.unwrap_or_else(|| Box::new(()));
-						let cb_weight = cb.on_timeout_packet(&packet_msg.packet);
-						let lc_verification_weight = match channel_client::<T>(
-							packet_msg.packet.destination_port.as_bytes(),
-							packet_msg.packet.destination_channel.to_string().as_bytes(),
-						) {
-							Ok(client_id) => {
-								let client_type = client_id
-									.as_str()
-									.rsplit_once('-')
-									.map(|(client_type_str, ..)| client_type_str);
-								match client_type {
-									Some(ty) if ty.contains("tendermint") =>
-										<T as Config>::WeightInfo::timeout_packet_tendermint(
-											packet_msg.packet.data.len() as u32,
-										),
-									_ => Weight::default(),
-								}
-							},
-							Err(_) => Weight::default(),
-						};
-						cb_weight.saturating_add(lc_verification_weight)
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			PacketMsg::ToClosePacket(packet_msg) => {
+				let cb = WeightRouter::<T>::get_weight(
+					packet_msg.packet.destination_port.as_str(),
+				)
+				.unwrap_or_else(|| Box::new(()));
+				let cb_weight = cb.on_timeout_packet(&packet_msg.packet);
+				let lc_verification_weight = match channel_client::<T>(
+					packet_msg.packet.destination_port.as_bytes(),
+					packet_msg.packet.destination_channel.to_string().as_bytes(),
+				) {
+					Ok(client_id) => {
+						let client_type = client_id
+							.as_str()
+							.rsplit_once('-')
+							.map(|(client_type_str, ..)| client_type_str);
+						match client_type {
+							Some(ty) if ty.contains("tendermint") =>
+								<T as Config>::WeightInfo::timeout_packet_tendermint(
+									packet_msg.packet.data.len() as u32,
+								),
+							_ => Weight::default(),
+						}
 					},
-				},
-				_ => Weight::default(),
-			};
-			acc.saturating_add(temp)
-		})
+					Err(_) => Weight::default(),
+				};
+				cb_weight.saturating_add(lc_verification_weight)
+			},
+			PacketMsg::PruneAcknowledgements(_) => Weight::default(),
+		},
+		_ => Weight::default(),
+	};
+	Some(weight)
+}
+
+/// Largest value of the components of the benchmarked weights, the upper bounds of their
+/// benchmark ranges.
+const MAX_PACKET_DATA_LEN: u32 = 1000;
+const MAX_ACK_LEN: u32 = 1000;
+const MAX_PRECOMMITS: u32 = 100;
+const MAX_PARACHAIN_HEADERS: u32 = 100;
+const MAX_BEEFY_SIGNATURES: u32 = 100;
+
+/// Weight charged for a message that can't be classified, the most any single message is
+/// benchmarked to weigh.
+pub(crate) fn worst_case_message_weight<T: Config>() -> Weight {
+	let callback = [
+		<T as Config>::WeightInfo::on_chan_open_init(),
+		<T as Config>::WeightInfo::on_chan_open_try(),
+		<T as Config>::WeightInfo::on_chan_open_ack(),
+		<T as Config>::WeightInfo::on_chan_open_confirm(),
+		<T as Config>::WeightInfo::on_chan_close_init(),
+		<T as Config>::WeightInfo::on_chan_close_confirm(),
+		<T as Config>::WeightInfo::on_recv_packet(),
+		<T as Config>::WeightInfo::on_acknowledgement_packet(),
+		<T as Config>::WeightInfo::on_timeout_packet(),
+	]
+	.into_iter()
+	.max()
+	.unwrap_or_default();
+	let handler = [
+		<T as Config>::WeightInfo::create_client(),
+		<T as Config>::WeightInfo::update_tendermint_client(),
+		<T as Config>::WeightInfo::update_grandpa_client(MAX_PRECOMMITS),
+		<T as Config>::WeightInfo::update_beefy_client(MAX_PARACHAIN_HEADERS, MAX_BEEFY_SIGNATURES),
+		<T as Config>::WeightInfo::conn_open_init(),
+		<T as Config>::WeightInfo::conn_try_open_tendermint(),
+		<T as Config>::WeightInfo::conn_open_ack_tendermint(),
+		<T as Config>::WeightInfo::conn_open_confirm_tendermint(),
+		<T as Config>::WeightInfo::channel_open_init(),
+		<T as Config>::WeightInfo::channel_open_try_tendermint(),
+		<T as Config>::WeightInfo::channel_open_ack_tendermint(),
+		<T as Config>::WeightInfo::channel_open_confirm_tendermint(),
+		<T as Config>::WeightInfo::channel_close_init(),
+		<T as Config>::WeightInfo::channel_close_confirm_tendermint(),
+		<T as Config>::WeightInfo::recv_packet_tendermint(MAX_PACKET_DATA_LEN),
+		<T as Config>::WeightInfo::ack_packet_tendermint(MAX_PACKET_DATA_LEN, MAX_ACK_LEN),
+		<T as Config>::WeightInfo::timeout_packet_tendermint(MAX_PACKET_DATA_LEN),
+	]
+	.into_iter()
+	.max()
+	.unwrap_or_default();
+	handler.saturating_add(callback)
+}
+
+/// Weight charged up front for delivering `msgs`, the sum of the benchmarked weight of each
+/// message. Messages that can't be classified are charged the worst case.
+pub(crate) fn deliver<T: Config + Send + Sync>(msgs: &[Any]) -> Weight
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+{
+	msgs.iter().fold(Weight::default(), |acc, msg| {
+		let weight = message_weight::<T>(msg).unwrap_or_else(worst_case_message_weight::<T>);
+		acc.saturating_add(weight)
+	})
+}
+
+/// Weight actually consumed by a message passed to `deliver`, given its weight as returned by
+/// [`message_weight`] before it was executed and what became of it. Messages that failed or
+/// changed nothing are only charged their decoding and rejection.
+pub(crate) fn consumed_message_weight<T: Config>(
+	weight: Option<Weight>,
+	outcome: MessageOutcome,
+) -> Weight {
+	match outcome {
+		MessageOutcome::Executed => weight.unwrap_or_else(worst_case_message_weight::<T>),
+		MessageOutcome::NoOp | MessageOutcome::Failed =>
+			<T as Config>::WeightInfo::reject_unknown_message(),
+	}
 }
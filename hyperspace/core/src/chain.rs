@@ -15,14 +15,16 @@
 #![allow(unreachable_patterns)]
 
 use async_trait::async_trait;
+#[cfg(feature = "cosmos")]
+use cosmos::CosmosClient;
 use derive_more::From;
 use futures::Stream;
 #[cfg(any(test, feature = "testing"))]
 use ibc::applications::transfer::msgs::transfer::MsgTransfer;
 use ibc::{
-	applications::transfer::PrefixedCoin,
+	applications::{fee::PacketFee, transfer::PrefixedCoin},
 	core::{
-		ics02_client::client_state::ClientType,
+		ics02_client::client_state::{ClientStatus, ClientType},
 		ics23_commitment::commitment::CommitmentPrefix,
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
@@ -44,16 +46,25 @@ use ibc_proto::{
 		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
 	},
 };
+use ibc_rpc::QueryNextSequenceAckResponse;
+use metrics::health::DEFAULT_HEALTH_STALENESS;
 use pallet_ibc::light_clients::AnyClientMessage;
 #[cfg(any(test, feature = "testing"))]
 use pallet_ibc::Timeout;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::{
+	filter::PacketFilter,
+	keep_alive::{KeepAliveConfig, DEFAULT_KEEP_ALIVE_INTERVAL, DEFAULT_KEEP_ALIVE_THRESHOLD},
+	reload::DEFAULT_RELOAD_INTERVAL,
+};
 use ibc::core::ics02_client::events::UpdateClient;
 use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState};
 use parachain::{config, ParachainClient};
-use primitives::{Chain, IbcProvider, KeyProvider, MisbehaviourHandler, UpdateType};
+use primitives::{
+	Chain, IbcProvider, KeyProvider, MisbehaviourHandler, SubmitErrorKind, UpdateType,
+};
 use sp_runtime::generic::Era;
 use std::{pin::Pin, time::Duration};
 #[cfg(feature = "dali")]
@@ -106,38 +117,92 @@ pub struct Config {
 	pub chain_a: AnyConfig,
 	pub chain_b: AnyConfig,
 	pub core: CoreConfig,
+	/// Restricts the packets relayed, every packet is relayed when unset.
+	#[serde(default)]
+	pub filter: PacketFilter,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum AnyConfig {
 	Parachain(parachain::ParachainClientConfig),
+	#[cfg(feature = "cosmos")]
+	Cosmos(cosmos::CosmosClientConfig),
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct CoreConfig {
 	pub prometheus_endpoint: Option<String>,
+	/// Path of the database keeping the relayer's progress across restarts, defaults to
+	/// [`DEFAULT_STORE_PATH`].
+	pub store_path: Option<String>,
+	/// Seconds between two checks of the clients' expiry, defaults to
+	/// [`DEFAULT_KEEP_ALIVE_INTERVAL`].
+	pub keep_alive_interval: Option<u64>,
+	/// Fraction of a client's trusting period left below which it gets updated even if no packets
+	/// are relayed, defaults to [`DEFAULT_KEEP_ALIVE_THRESHOLD`].
+	pub keep_alive_threshold: Option<f64>,
+	/// Seconds without a processed finality event after which `/health` reports the relayer as
+	/// unhealthy, defaults to [`DEFAULT_HEALTH_STALENESS`].
+	pub health_staleness: Option<u64>,
+	/// Seconds between two checks of the config file for changed channel whitelists and packet
+	/// filters, defaults to [`DEFAULT_RELOAD_INTERVAL`].
+	pub config_reload_interval: Option<u64>,
 }
 
+impl CoreConfig {
+	/// Keep-alive settings for the clients on both chains.
+	pub fn keep_alive(&self) -> KeepAliveConfig {
+		KeepAliveConfig {
+			interval: Duration::from_secs(
+				self.keep_alive_interval.unwrap_or(DEFAULT_KEEP_ALIVE_INTERVAL),
+			),
+			threshold: self.keep_alive_threshold.unwrap_or(DEFAULT_KEEP_ALIVE_THRESHOLD),
+		}
+	}
+
+	/// Time between two checks of the config file for changes to apply while running.
+	pub fn config_reload_interval(&self) -> Duration {
+		Duration::from_secs(self.config_reload_interval.unwrap_or(DEFAULT_RELOAD_INTERVAL))
+	}
+
+	/// Window within which a finality event has to be processed for the relayer to be healthy.
+	pub fn health_staleness(&self) -> Duration {
+		Duration::from_secs(self.health_staleness.unwrap_or(DEFAULT_HEALTH_STALENESS))
+	}
+}
+
+/// Default location of the relayer's database.
+pub const DEFAULT_STORE_PATH: &str = "hyperspace-store";
+
 #[derive(Clone)]
 pub enum AnyChain {
 	Parachain(ParachainClient<DefaultConfig>),
+	#[cfg(feature = "cosmos")]
+	Cosmos(CosmosClient),
 }
 
 #[derive(From)]
 pub enum AnyFinalityEvent {
 	Parachain(parachain::finality_protocol::FinalityEvent),
+	#[cfg(feature = "cosmos")]
+	Cosmos(cosmos::chain::FinalityEvent),
 }
 
-#[derive(From)]
+#[derive(From, Debug)]
 pub enum AnyTransactionId {
 	Parachain(parachain::provider::TransactionId<sp_core::H256>),
+	#[cfg(feature = "cosmos")]
+	Cosmos(cosmos::tx::TransactionId),
 }
 
 #[derive(Error, Debug)]
 pub enum AnyError {
 	#[error("{0}")]
 	Parachain(#[from] parachain::error::Error),
+	#[cfg(feature = "cosmos")]
+	#[error("{0}")]
+	Cosmos(#[from] cosmos::error::Error),
 	#[error("{0}")]
 	Other(String),
 }
@@ -170,6 +235,14 @@ impl IbcProvider for AnyChain {
 					chain.query_latest_ibc_events(finality_event, counterparty).await?;
 				Ok((client_msg, events, update_type))
 			},
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => {
+				let finality_event = ibc::downcast!(finality_event => AnyFinalityEvent::Cosmos)
+					.ok_or_else(|| AnyError::Other("Invalid finality event type".to_owned()))?;
+				let (client_msg, events, update_type) =
+					chain.query_latest_ibc_events(finality_event, counterparty).await?;
+				Ok((client_msg, events, update_type))
+			},
 			_ => unreachable!(),
 		}
 	}
@@ -177,6 +250,8 @@ impl IbcProvider for AnyChain {
 	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
 		match self {
 			Self::Parachain(chain) => chain.ibc_events().await,
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.ibc_events().await,
 			_ => unreachable!(),
 		}
 	}
@@ -192,6 +267,11 @@ impl IbcProvider for AnyChain {
 				.query_client_consensus(at, client_id, consensus_height)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain
+				.query_client_consensus(at, client_id, consensus_height)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -204,6 +284,24 @@ impl IbcProvider for AnyChain {
 		match self {
 			AnyChain::Parachain(chain) =>
 				chain.query_client_state(at, client_id).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) =>
+				chain.query_client_state(at, client_id).await.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_client_status(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<ClientStatus, Self::Error> {
+		match self {
+			AnyChain::Parachain(chain) =>
+				chain.query_client_status(at, client_id).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) =>
+				chain.query_client_status(at, client_id).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -216,6 +314,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			AnyChain::Parachain(chain) =>
 				chain.query_connection_end(at, connection_id).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) =>
+				chain.query_connection_end(at, connection_id).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -229,6 +330,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			AnyChain::Parachain(chain) =>
 				chain.query_channel_end(at, channel_id, port_id).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) =>
+				chain.query_channel_end(at, channel_id, port_id).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -236,6 +340,21 @@ impl IbcProvider for AnyChain {
 	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
 		match self {
 			AnyChain::Parachain(chain) => chain.query_proof(at, keys).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain.query_proof(at, keys).await.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_proofs(
+		&self,
+		at: Height,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Vec<u8>>, Self::Error> {
+		match self {
+			AnyChain::Parachain(chain) => chain.query_proofs(at, keys).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain.query_proofs(at, keys).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -252,6 +371,11 @@ impl IbcProvider for AnyChain {
 				.query_packet_commitment(at, port_id, channel_id, seq)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain
+				.query_packet_commitment(at, port_id, channel_id, seq)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -268,6 +392,11 @@ impl IbcProvider for AnyChain {
 				.query_packet_acknowledgement(at, port_id, channel_id, seq)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain
+				.query_packet_acknowledgement(at, port_id, channel_id, seq)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -283,6 +412,31 @@ impl IbcProvider for AnyChain {
 				.query_next_sequence_recv(at, port_id, channel_id)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain
+				.query_next_sequence_recv(at, port_id, channel_id)
+				.await
+				.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_next_sequence_ack(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceAckResponse, Self::Error> {
+		match self {
+			AnyChain::Parachain(chain) => chain
+				.query_next_sequence_ack(at, port_id, channel_id)
+				.await
+				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain
+				.query_next_sequence_ack(at, port_id, channel_id)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -299,6 +453,11 @@ impl IbcProvider for AnyChain {
 				.query_packet_receipt(at, port_id, channel_id, seq)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain
+				.query_packet_receipt(at, port_id, channel_id, seq)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -307,6 +466,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			AnyChain::Parachain(chain) =>
 				chain.latest_height_and_timestamp().await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) =>
+				chain.latest_height_and_timestamp().await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -322,6 +484,11 @@ impl IbcProvider for AnyChain {
 				.query_packet_commitments(at, channel_id, port_id)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.query_packet_commitments(at, channel_id, port_id)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -331,10 +498,16 @@ impl IbcProvider for AnyChain {
 		at: Height,
 		channel_id: ChannelId,
 		port_id: PortId,
+		seqs: Option<Vec<u64>>,
 	) -> Result<Vec<u64>, Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain
-				.query_packet_acknowledgements(at, channel_id, port_id)
+				.query_packet_acknowledgements(at, channel_id, port_id, seqs)
+				.await
+				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.query_packet_acknowledgements(at, channel_id, port_id, seqs)
 				.await
 				.map_err(Into::into),
 			_ => unreachable!(),
@@ -353,6 +526,11 @@ impl IbcProvider for AnyChain {
 				.query_unreceived_packets(at, channel_id, port_id, seqs)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.query_unreceived_packets(at, channel_id, port_id, seqs)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -369,6 +547,11 @@ impl IbcProvider for AnyChain {
 				.query_unreceived_acknowledgements(at, channel_id, port_id, seqs)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.query_unreceived_acknowledgements(at, channel_id, port_id, seqs)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -376,6 +559,8 @@ impl IbcProvider for AnyChain {
 	fn channel_whitelist(&self) -> Vec<(ChannelId, PortId)> {
 		match self {
 			Self::Parachain(chain) => chain.channel_whitelist(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.channel_whitelist(),
 			_ => unreachable!(),
 		}
 	}
@@ -388,6 +573,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			Self::Parachain(chain) =>
 				chain.query_connection_channels(at, connection_id).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.query_connection_channels(at, connection_id).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -401,6 +589,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			Self::Parachain(chain) =>
 				chain.query_send_packets(channel_id, port_id, seqs).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.query_send_packets(channel_id, port_id, seqs).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -414,6 +605,26 @@ impl IbcProvider for AnyChain {
 		match self {
 			Self::Parachain(chain) =>
 				chain.query_recv_packets(channel_id, port_id, seqs).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.query_recv_packets(channel_id, port_id, seqs).await.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_packet_fees(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seq: u64,
+	) -> Result<Vec<PacketFee>, Self::Error> {
+		match self {
+			Self::Parachain(chain) =>
+				chain.query_packet_fees(at, channel_id, port_id, seq).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.query_packet_fees(at, channel_id, port_id, seq).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -421,6 +632,8 @@ impl IbcProvider for AnyChain {
 	fn expected_block_time(&self) -> Duration {
 		match self {
 			Self::Parachain(chain) => chain.expected_block_time(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.expected_block_time(),
 			_ => unreachable!(),
 		}
 	}
@@ -435,6 +648,11 @@ impl IbcProvider for AnyChain {
 				.query_client_update_time_and_height(client_id, client_height)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.query_client_update_time_and_height(client_id, client_height)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -446,6 +664,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			AnyChain::Parachain(chain) =>
 				chain.query_host_consensus_state_proof(height).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) =>
+				chain.query_host_consensus_state_proof(height).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -453,6 +674,8 @@ impl IbcProvider for AnyChain {
 	async fn query_ibc_balance(&self) -> Result<Vec<PrefixedCoin>, Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain.query_ibc_balance().await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.query_ibc_balance().await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -460,6 +683,8 @@ impl IbcProvider for AnyChain {
 	fn connection_prefix(&self) -> CommitmentPrefix {
 		match self {
 			AnyChain::Parachain(chain) => chain.connection_prefix(),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain.connection_prefix(),
 			_ => unreachable!(),
 		}
 	}
@@ -467,6 +692,8 @@ impl IbcProvider for AnyChain {
 	fn client_id(&self) -> ClientId {
 		match self {
 			AnyChain::Parachain(chain) => chain.client_id(),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain.client_id(),
 			_ => unreachable!(),
 		}
 	}
@@ -474,6 +701,8 @@ impl IbcProvider for AnyChain {
 	fn connection_id(&self) -> ConnectionId {
 		match self {
 			AnyChain::Parachain(chain) => chain.connection_id(),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain.connection_id(),
 			_ => unreachable!(),
 		}
 	}
@@ -481,6 +710,8 @@ impl IbcProvider for AnyChain {
 	fn client_type(&self) -> ClientType {
 		match self {
 			AnyChain::Parachain(chain) => chain.client_type(),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(chain) => chain.client_type(),
 			_ => unreachable!(),
 		}
 	}
@@ -489,6 +720,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			Self::Parachain(chain) =>
 				chain.query_timestamp_at(block_number).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.query_timestamp_at(block_number).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -496,6 +730,17 @@ impl IbcProvider for AnyChain {
 	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain.query_clients().await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.query_clients().await.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_connections(&self) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		match self {
+			Self::Parachain(chain) => chain.query_connections().await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.query_connections().await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -503,6 +748,8 @@ impl IbcProvider for AnyChain {
 	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain.query_channels().await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.query_channels().await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -515,6 +762,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			Self::Parachain(chain) =>
 				chain.query_connection_using_client(height, client_id).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.query_connection_using_client(height, client_id).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -527,6 +777,9 @@ impl IbcProvider for AnyChain {
 		match self {
 			Self::Parachain(chain) =>
 				chain.is_update_required(latest_height, latest_client_height_on_counterparty),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.is_update_required(latest_height, latest_client_height_on_counterparty),
 			_ => unreachable!(),
 		}
 	}
@@ -535,6 +788,8 @@ impl IbcProvider for AnyChain {
 	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain.initialize_client_state().await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.initialize_client_state().await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -551,6 +806,38 @@ impl IbcProvider for AnyChain {
 				)
 				.await
 				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.query_client_id_from_tx_hash(
+					downcast!(tx_id => AnyTransactionId::Cosmos)
+						.expect("Should be cosmos transaction id"),
+				)
+				.await
+				.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_tx_block_events(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<Vec<IbcEvent>, Self::Error> {
+		match self {
+			Self::Parachain(chain) => chain
+				.query_tx_block_events(
+					downcast!(tx_id => AnyTransactionId::Parachain)
+						.expect("Should be parachain transaction id"),
+				)
+				.await
+				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.query_tx_block_events(
+					downcast!(tx_id => AnyTransactionId::Cosmos)
+						.expect("Should be cosmos transaction id"),
+				)
+				.await
+				.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -566,6 +853,9 @@ impl MisbehaviourHandler for AnyChain {
 		match self {
 			AnyChain::Parachain(parachain) =>
 				parachain.check_for_misbehaviour(counterparty, client_message).await,
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(cosmos) =>
+				cosmos.check_for_misbehaviour(counterparty, client_message).await,
 			_ => unreachable!(),
 		}
 	}
@@ -575,6 +865,8 @@ impl KeyProvider for AnyChain {
 	fn account_id(&self) -> Signer {
 		match self {
 			AnyChain::Parachain(parachain) => parachain.account_id(),
+			#[cfg(feature = "cosmos")]
+			AnyChain::Cosmos(cosmos) => cosmos.account_id(),
 			_ => unreachable!(),
 		}
 	}
@@ -585,6 +877,8 @@ impl Chain for AnyChain {
 	fn name(&self) -> &str {
 		match self {
 			Self::Parachain(chain) => chain.name(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.name(),
 			_ => unreachable!(),
 		}
 	}
@@ -592,6 +886,8 @@ impl Chain for AnyChain {
 	fn block_max_weight(&self) -> u64 {
 		match self {
 			Self::Parachain(chain) => chain.block_max_weight(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.block_max_weight(),
 			_ => unreachable!(),
 		}
 	}
@@ -599,6 +895,8 @@ impl Chain for AnyChain {
 	async fn estimate_weight(&self, msg: Vec<Any>) -> Result<u64, Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain.estimate_weight(msg).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.estimate_weight(msg).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -611,6 +909,20 @@ impl Chain for AnyChain {
 				use futures::StreamExt;
 				Box::pin(chain.finality_notifications().await.map(|x| x.into()))
 			},
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => {
+				use futures::StreamExt;
+				Box::pin(chain.finality_notifications().await.map(|x| x.into()))
+			},
+			_ => unreachable!(),
+		}
+	}
+
+	fn subscription_reconnects(&self) -> u64 {
+		match self {
+			Self::Parachain(chain) => chain.subscription_reconnects(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.subscription_reconnects(),
 			_ => unreachable!(),
 		}
 	}
@@ -622,16 +934,80 @@ impl Chain for AnyChain {
 				.await
 				.map_err(Into::into)
 				.map(|id| AnyTransactionId::Parachain(id)),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.submit(messages)
+				.await
+				.map_err(Into::into)
+				.map(|id| AnyTransactionId::Cosmos(id)),
 			_ => unreachable!(),
 		}
 	}
 
+	fn submit_error_kind(&self, error: &Self::Error) -> SubmitErrorKind {
+		match (self, error) {
+			(Self::Parachain(chain), AnyError::Parachain(error)) => chain.submit_error_kind(error),
+			#[cfg(feature = "cosmos")]
+			(Self::Cosmos(chain), AnyError::Cosmos(error)) => chain.submit_error_kind(error),
+			_ => SubmitErrorKind::Fatal,
+		}
+	}
+
 	async fn query_client_message(
 		&self,
 		update: UpdateClient,
 	) -> Result<AnyClientMessage, Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain.query_client_message(update).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.query_client_message(update).await.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	fn has_secondary_rpc(&self) -> bool {
+		match self {
+			Self::Parachain(chain) => chain.has_secondary_rpc(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.has_secondary_rpc(),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_conflicting_finality(
+		&self,
+		finality_event: Self::FinalityEvent,
+	) -> Result<Option<AnyClientMessage>, anyhow::Error> {
+		match self {
+			Self::Parachain(chain) => {
+				let finality_event = ibc::downcast!(finality_event => AnyFinalityEvent::Parachain)
+					.ok_or_else(|| anyhow::anyhow!("Invalid finality event type"))?;
+				chain.query_conflicting_finality(finality_event).await
+			},
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => {
+				let finality_event = ibc::downcast!(finality_event => AnyFinalityEvent::Cosmos)
+					.ok_or_else(|| anyhow::anyhow!("Invalid finality event type"))?;
+				chain.query_conflicting_finality(finality_event).await
+			},
+			_ => unreachable!(),
+		}
+	}
+
+	fn encode_client_message(&self, client_message: AnyClientMessage) -> Any {
+		match self {
+			Self::Parachain(chain) => chain.encode_client_message(client_message),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.encode_client_message(client_message),
+			_ => unreachable!(),
+		}
+	}
+
+	fn supports_client_update_batches(&self) -> bool {
+		match self {
+			Self::Parachain(chain) => chain.supports_client_update_batches(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.supports_client_update_batches(),
 			_ => unreachable!(),
 		}
 	}
@@ -643,6 +1019,8 @@ impl primitives::TestProvider for AnyChain {
 	async fn send_transfer(&self, params: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
 		match self {
 			Self::Parachain(chain) => chain.send_transfer(params).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.send_transfer(params).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -655,6 +1033,42 @@ impl primitives::TestProvider for AnyChain {
 		match self {
 			Self::Parachain(chain) =>
 				chain.send_ordered_packet(channel_id, timeout).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) =>
+				chain.send_ordered_packet(channel_id, timeout).await.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn send_pings(
+		&self,
+		channel_id: ChannelId,
+		count: u32,
+		payload_size: u32,
+		timeout: Timeout,
+	) -> Result<(), Self::Error> {
+		match self {
+			Self::Parachain(chain) => chain
+				.send_pings(channel_id, count, payload_size, timeout)
+				.await
+				.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain
+				.send_pings(channel_id, count, payload_size, timeout)
+				.await
+				.map_err(Into::into),
+			_ => unreachable!(),
+		}
+	}
+
+	async fn query_ping_stats(
+		&self,
+		channel_id: ChannelId,
+	) -> Result<Option<pallet_ibc_ping::PingStats>, Self::Error> {
+		match self {
+			Self::Parachain(chain) => chain.query_ping_stats(channel_id).await.map_err(Into::into),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.query_ping_stats(channel_id).await.map_err(Into::into),
 			_ => unreachable!(),
 		}
 	}
@@ -662,6 +1076,8 @@ impl primitives::TestProvider for AnyChain {
 	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
 		match self {
 			Self::Parachain(chain) => chain.subscribe_blocks().await,
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.subscribe_blocks().await,
 			_ => unreachable!(),
 		}
 	}
@@ -669,6 +1085,8 @@ impl primitives::TestProvider for AnyChain {
 	fn set_channel_whitelist(&mut self, channel_whitelist: Vec<(ChannelId, PortId)>) {
 		match self {
 			Self::Parachain(chain) => chain.set_channel_whitelist(channel_whitelist),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.set_channel_whitelist(channel_whitelist),
 			_ => unreachable!(),
 		}
 	}
@@ -679,6 +1097,8 @@ impl AnyConfig {
 		Ok(match self {
 			AnyConfig::Parachain(config) =>
 				AnyChain::Parachain(ParachainClient::new(config).await?),
+			#[cfg(feature = "cosmos")]
+			AnyConfig::Cosmos(config) => AnyChain::Cosmos(CosmosClient::new(config).await?),
 		})
 	}
 
@@ -687,6 +1107,10 @@ impl AnyConfig {
 			Self::Parachain(chain) => {
 				chain.client_id.replace(client_id);
 			},
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => {
+				chain.client_id.replace(client_id);
+			},
 		}
 	}
 
@@ -695,6 +1119,18 @@ impl AnyConfig {
 			Self::Parachain(chain) => {
 				chain.connection_id.replace(connection_id);
 			},
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => {
+				chain.connection_id.replace(connection_id);
+			},
+		}
+	}
+
+	pub fn channel_whitelist(&self) -> Vec<(ChannelId, PortId)> {
+		match self {
+			Self::Parachain(chain) => chain.channel_whitelist.clone(),
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => chain.channel_whitelist.clone(),
 		}
 	}
 
@@ -703,6 +1139,10 @@ impl AnyConfig {
 			Self::Parachain(chain) => {
 				chain.channel_whitelist.push((channel_id, port_id));
 			},
+			#[cfg(feature = "cosmos")]
+			Self::Cosmos(chain) => {
+				chain.channel_whitelist.push((channel_id, port_id));
+			},
 		}
 	}
 }
@@ -12,19 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::Parser;
 use primitives::Chain;
 use prometheus::Registry;
-use std::{path::PathBuf, str::FromStr, time::Duration};
-
-use crate::{chain::Config, fish, relay, Mode};
-use ibc::core::{ics04_channel::channel::Order, ics24_host::identifier::PortId};
-use metrics::{data::Metrics, handler::MetricsHandler, init_prometheus};
-use primitives::{
-	utils::{create_channel, create_clients, create_connection},
-	IbcProvider,
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use crate::{
+	chain::{AnyChain, Config, DEFAULT_STORE_PATH},
+	fish,
+	handshake::{create_channel, create_connection},
+	packets::clear::{relay_packet, PacketMessageKind},
+	query::{query_channels, query_clients, query_connections, query_pending_packets, render},
+	relay,
+	reload::watch_config,
+	store::{MemoryStore, SledStore},
+	Mode,
+};
+use ibc::core::{
+	ics04_channel::channel::Order,
+	ics24_host::identifier::{ChannelId, PortId},
 };
+use metrics::{
+	data::Metrics,
+	handler::MetricsHandler,
+	health::{HealthCheck, Liveness},
+	init_prometheus,
+};
+use primitives::{utils::create_clients, IbcProvider};
 
 #[derive(Debug, Parser)]
 pub struct Cli {
@@ -48,6 +63,54 @@ pub enum Subcommand {
 	CreateConnection(Cmd),
 	#[clap(name = "create-channel", about = "Creates a channel on the specified port")]
 	CreateChannel(Cmd),
+	#[clap(name = "relay-packet", about = "Relays a single packet message and exits")]
+	RelayPacket(RelayPacketCmd),
+	#[clap(name = "query", about = "Queries the IBC state of one of the chains")]
+	Query(QueryCmd),
+}
+
+#[derive(Debug, Parser)]
+pub struct QueryCmd {
+	#[clap(subcommand)]
+	pub subcommand: QuerySubcommand,
+}
+
+/// Possible subcommands of the query command.
+#[derive(Debug, Parser)]
+pub enum QuerySubcommand {
+	#[clap(name = "clients", about = "Lists the light clients on the chain")]
+	Clients(QueryArgs),
+	#[clap(name = "connections", about = "Lists the connections on the chain")]
+	Connections(QueryArgs),
+	#[clap(name = "channels", about = "Lists the channels on the chain")]
+	Channels(QueryArgs),
+	#[clap(name = "packets", about = "Lists the packets and acknowledgements pending on a channel")]
+	Packets(QueryPacketsArgs),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct QueryArgs {
+	/// Relayer config path.
+	#[clap(long)]
+	pub config: String,
+	/// Chain to query, one of 'a' or 'b'
+	#[clap(long)]
+	chain: String,
+	/// Print json instead of a table
+	#[clap(long)]
+	json: bool,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct QueryPacketsArgs {
+	#[clap(flatten)]
+	query: QueryArgs,
+	/// Channel id on the queried chain
+	#[clap(long)]
+	channel: String,
+	/// Port id on the queried chain
+	#[clap(long)]
+	port: String,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -59,8 +122,7 @@ pub struct Cmd {
 	#[clap(long)]
 	port_id: Option<String>,
 	/// Connection delay period in seconds
-	#[clap(long)]
-	#[clap(long)]
+	#[clap(long = "connection-delay", alias = "delay-period")]
 	delay_period: Option<u32>,
 	/// Channel order
 	#[clap(long)]
@@ -73,13 +135,37 @@ pub struct Cmd {
 	pub new_config: Option<String>,
 }
 
+#[derive(Debug, Clone, Parser)]
+pub struct RelayPacketCmd {
+	/// Relayer config path.
+	#[clap(long)]
+	pub config: String,
+	/// Channel id on the chain relayed from
+	#[clap(long)]
+	channel: String,
+	/// Port id on the chain relayed from
+	#[clap(long)]
+	port: String,
+	/// Packet sequence
+	#[clap(long)]
+	sequence: u64,
+	/// Relay direction, one of 'a-to-b' or 'b-to-a'
+	#[clap(long)]
+	direction: String,
+	/// Message to relay, one of 'recv', 'ack' or 'timeout'. Timeouts are submitted to the chain
+	/// relayed from
+	#[clap(long, default_value = "recv")]
+	kind: String,
+}
+
 impl Cmd {
 	// todo: IntoClient, since clients are generic, users must configure clients themselves.
 	/// Run the command
 	pub async fn run(&self) -> Result<()> {
 		let path: PathBuf = self.config.parse()?;
-		let file_content = tokio::fs::read_to_string(path).await?;
+		let file_content = tokio::fs::read_to_string(&path).await?;
 		let config: Config = toml::from_str(&file_content)?;
+		let runtime_config = watch_config(path, &config)?;
 		let any_chain_a = config.chain_a.into_client().await?;
 		let any_chain_b = config.chain_b.into_client().await?;
 
@@ -87,16 +173,33 @@ impl Cmd {
 			Registry::new_custom(None, None).expect("this can only fail if the prefix is empty");
 		let metrics_a = Metrics::register(any_chain_a.name(), &registry)?;
 		let metrics_b = Metrics::register(any_chain_b.name(), &registry)?;
-		let mut metrics_handler_a = MetricsHandler::new(registry.clone(), metrics_a);
-		let mut metrics_handler_b = MetricsHandler::new(registry.clone(), metrics_b);
+		let liveness = Liveness::default();
+		let mut metrics_handler_a =
+			MetricsHandler::new(registry.clone(), metrics_a).with_liveness(liveness.clone());
+		let mut metrics_handler_b =
+			MetricsHandler::new(registry.clone(), metrics_b).with_liveness(liveness.clone());
 		metrics_handler_a.link_with_counterparty(&mut metrics_handler_b);
 
+		let keep_alive = config.core.keep_alive();
+		let health = HealthCheck { liveness, staleness: config.core.health_staleness() };
 		if let Some(addr) = config.core.prometheus_endpoint.map(|s| s.parse().ok()).flatten() {
-			tokio::spawn(init_prometheus(addr, registry.clone()));
+			tokio::spawn(init_prometheus(addr, registry.clone(), Some(health)));
 		}
 
-		relay(any_chain_a, any_chain_b, Some(metrics_handler_a), Some(metrics_handler_b), None)
-			.await
+		let store_path = config.core.store_path.as_deref().unwrap_or(DEFAULT_STORE_PATH);
+		let store = Arc::new(SledStore::open(store_path)?);
+
+		relay(
+			any_chain_a,
+			any_chain_b,
+			Some(metrics_handler_a),
+			Some(metrics_handler_b),
+			None,
+			store,
+			Some(keep_alive),
+			runtime_config,
+		)
+		.await
 	}
 
 	/// Run fisherman
@@ -141,24 +244,34 @@ impl Cmd {
 	pub async fn create_connection(&self) -> Result<Config> {
 		let delay = self
 			.delay_period
-			.expect("delay_period should be provided when creating a connection");
+			.expect("connection_delay should be provided when creating a connection");
 		let delay = Duration::from_secs(delay.into());
 		let path: PathBuf = self.config.parse()?;
 		let file_content = tokio::fs::read_to_string(path).await?;
 		let mut config: Config = toml::from_str(&file_content)?;
-		let any_chain_a = config.chain_a.clone().into_client().await?;
-		let any_chain_b = config.chain_b.clone().into_client().await?;
+		let mut any_chain_a = config.chain_a.clone().into_client().await?;
+		let mut any_chain_b = config.chain_b.clone().into_client().await?;
 
 		let any_chain_a_clone = any_chain_a.clone();
 		let any_chain_b_clone = any_chain_b.clone();
 		let handle = tokio::task::spawn(async move {
-			relay(any_chain_a_clone, any_chain_b_clone, None, None, Some(Mode::Light))
-				.await
-				.unwrap();
+			let store = Arc::new(MemoryStore::default());
+			relay(
+				any_chain_a_clone,
+				any_chain_b_clone,
+				None,
+				None,
+				Some(Mode::Light),
+				store,
+				None,
+				Default::default(),
+			)
+			.await
+			.unwrap();
 		});
 
 		let (connection_id_a, connection_id_b) =
-			create_connection(&any_chain_a, &any_chain_b, delay).await?;
+			create_connection(&mut any_chain_a, &mut any_chain_b, delay).await?;
 		log::info!("ConnectionId on Chain {}: {}", any_chain_a.name(), connection_id_a);
 		log::info!("ConnectionId on Chain {}: {}", any_chain_b.name(), connection_id_b);
 		handle.abort();
@@ -186,22 +299,33 @@ impl Cmd {
 		let path: PathBuf = self.config.parse()?;
 		let file_content = tokio::fs::read_to_string(path).await?;
 		let mut config: Config = toml::from_str(&file_content)?;
-		let any_chain_a = config.chain_a.clone().into_client().await?;
-		let any_chain_b = config.chain_b.clone().into_client().await?;
+		let mut any_chain_a = config.chain_a.clone().into_client().await?;
+		let mut any_chain_b = config.chain_b.clone().into_client().await?;
 
 		let any_chain_a_clone = any_chain_a.clone();
 		let any_chain_b_clone = any_chain_b.clone();
 		let handle = tokio::task::spawn(async move {
-			relay(any_chain_a_clone, any_chain_b_clone, None, None, Some(Mode::Light))
-				.await
-				.unwrap();
+			let store = Arc::new(MemoryStore::default());
+			relay(
+				any_chain_a_clone,
+				any_chain_b_clone,
+				None,
+				None,
+				Some(Mode::Light),
+				store,
+				None,
+				Default::default(),
+			)
+			.await
+			.unwrap();
 		});
 
 		let order = Order::from_str(order).expect("Expected one of 'ordered' or 'unordered'");
+		let connection_id = any_chain_a.connection_id();
 		let (channel_id_a, channel_id_b) = create_channel(
-			&any_chain_a,
-			&any_chain_b,
-			any_chain_a.connection_id(),
+			&mut any_chain_a,
+			&mut any_chain_b,
+			connection_id,
 			port_id.clone(),
 			version,
 			order,
@@ -217,3 +341,78 @@ impl Cmd {
 		Ok(config)
 	}
 }
+
+impl RelayPacketCmd {
+	/// Relay the packet message
+	pub async fn run(&self) -> Result<()> {
+		let channel_id = ChannelId::from_str(&self.channel)
+			.map_err(|e| anyhow!("Invalid channel id {}: {e}", self.channel))?;
+		let port_id = PortId::from_str(&self.port)
+			.map_err(|e| anyhow!("Invalid port id {}: {e}", self.port))?;
+		let kind = PacketMessageKind::from_str(&self.kind)?;
+		let path: PathBuf = self.config.parse()?;
+		let file_content = tokio::fs::read_to_string(path).await?;
+		let config: Config = toml::from_str(&file_content)?;
+		let mut any_chain_a = config.chain_a.into_client().await?;
+		let mut any_chain_b = config.chain_b.into_client().await?;
+
+		let (source, sink) = match self.direction.as_str() {
+			"a-to-b" => (&mut any_chain_a, &mut any_chain_b),
+			"b-to-a" => (&mut any_chain_b, &mut any_chain_a),
+			direction =>
+				return Err(anyhow!("Expected one of 'a-to-b' or 'b-to-a', found '{direction}'")),
+		};
+		let events = relay_packet(source, sink, channel_id, port_id, self.sequence, kind).await?;
+		for event in events {
+			log::info!("{:?}", event);
+		}
+		Ok(())
+	}
+}
+
+impl QueryArgs {
+	/// Returns the queried chain followed by its counterparty.
+	async fn chains(&self) -> Result<(AnyChain, AnyChain)> {
+		let path: PathBuf = self.config.parse()?;
+		let file_content = tokio::fs::read_to_string(path).await?;
+		let config: Config = toml::from_str(&file_content)?;
+		let (chain, counterparty) = match self.chain.as_str() {
+			"a" => (config.chain_a, config.chain_b),
+			"b" => (config.chain_b, config.chain_a),
+			chain => return Err(anyhow!("Expected one of 'a' or 'b', found '{chain}'")),
+		};
+		Ok((chain.into_client().await?, counterparty.into_client().await?))
+	}
+}
+
+impl QueryCmd {
+	/// Run the query and print its result
+	pub async fn run(&self) -> Result<()> {
+		let output = match &self.subcommand {
+			QuerySubcommand::Clients(args) => {
+				let (chain, _) = args.chains().await?;
+				render(&query_clients(&chain).await?, args.json)?
+			},
+			QuerySubcommand::Connections(args) => {
+				let (chain, _) = args.chains().await?;
+				render(&query_connections(&chain).await?, args.json)?
+			},
+			QuerySubcommand::Channels(args) => {
+				let (chain, _) = args.chains().await?;
+				render(&query_channels(&chain).await?, args.json)?
+			},
+			QuerySubcommand::Packets(args) => {
+				let channel_id = ChannelId::from_str(&args.channel)
+					.map_err(|e| anyhow!("Invalid channel id {}: {e}", args.channel))?;
+				let port_id = PortId::from_str(&args.port)
+					.map_err(|e| anyhow!("Invalid port id {}: {e}", args.port))?;
+				let (chain, counterparty) = args.query.chains().await?;
+				query_pending_packets(&chain, &counterparty, channel_id, port_id)
+					.await?
+					.render(args.query.json)?
+			},
+		};
+		println!("{output}");
+		Ok(())
+	}
+}
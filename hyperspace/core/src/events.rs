@@ -14,7 +14,6 @@
 
 #[cfg(feature = "testing")]
 use crate::send_packet_relay::packet_relay_status;
-use crate::{packets::query_ready_and_timed_out_packets, Mode};
 use codec::Encode;
 use ibc::{
 	core::{
@@ -55,14 +54,13 @@ pub struct ConnectionProof {
 }
 
 /// This parses events coming from a source chain
-/// Returns a tuple of messages, with the first item being packets that are ready to be sent to the
-/// sink chain. And the second item being packet timeouts that should be sent to the source.
+/// Returns the messages for these events that should be sent to the sink chain. Packets that can't
+/// be sent straight away are relayed by [`crate::pipeline::packet_task`].
 pub async fn parse_events(
 	source: &mut impl Chain,
 	sink: &mut impl Chain,
 	events: Vec<IbcEvent>,
-	mode: Option<Mode>,
-) -> Result<(Vec<Any>, Vec<Any>), anyhow::Error> {
+) -> Result<Vec<Any>, anyhow::Error> {
 	let mut messages = vec![];
 	// 1. translate events to messages
 	for event in events {
@@ -536,17 +534,7 @@ pub async fn parse_events(
 		}
 	}
 
-	// In light mode do not try to query channel state
-	if let Some(Mode::Light) = mode {
-		return Ok((messages, vec![]))
-	}
-
-	// 2. query packets that can now be sent, at this sink height because of connection delay.
-	let (ready_packets, timed_out_packets) =
-		query_ready_and_timed_out_packets(source, sink).await?;
-	messages.extend(ready_packets);
-
-	Ok((messages, timed_out_packets))
+	Ok(messages)
 }
 
 /// Fetch the connection proof for the sink chain.
@@ -0,0 +1,227 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Packet filtering, restricting which packets the relayer delivers.
+//!
+//! Rules are set per chain and apply to the packets sent on that chain's channels: a packet that's
+//! filtered out is neither received on the counterparty nor timed out, and neither is its
+//! acknowledgement relayed back. With a minimum fee, only packets whose ICS29 fees escrowed on the
+//! sending chain add up to at least that amount are relayed.
+
+use ibc::{
+	applications::{fee::PacketFee, transfer::Amount},
+	core::{
+		ics04_channel::packet::Packet,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	Height,
+};
+use primitives::Chain;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterAction {
+	Allow,
+	Deny,
+}
+
+impl Default for FilterAction {
+	fn default() -> Self {
+		Self::Allow
+	}
+}
+
+/// Action taken for the packets sent on a channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChannelRule {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub action: FilterAction,
+}
+
+/// Minimum amount of a denomination a packet's fees must add up to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MinFee {
+	pub denom: String,
+	pub amount: Amount,
+}
+
+/// Filter for the packets sent on one chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainFilter {
+	/// Action for channels without a rule.
+	#[serde(default)]
+	pub default: FilterAction,
+	/// Fee required for a packet to be relayed, none if unset.
+	pub min_fee: Option<MinFee>,
+	#[serde(default)]
+	pub channels: Vec<ChannelRule>,
+}
+
+impl ChainFilter {
+	/// Returns true if packets sent on the channel may be relayed, regardless of their fees.
+	pub fn allows_channel(&self, channel_id: ChannelId, port_id: &PortId) -> bool {
+		let action = self
+			.channels
+			.iter()
+			.find(|rule| rule.channel_id == channel_id && &rule.port_id == port_id)
+			.map_or(self.default, |rule| rule.action);
+		action == FilterAction::Allow
+	}
+
+	/// Returns true if a packet with the given escrowed fees pays at least the minimum fee.
+	pub fn allows_fees(&self, fees: &[PacketFee]) -> bool {
+		let min_fee = match self.min_fee.as_ref() {
+			Some(min_fee) => min_fee,
+			None => return true,
+		};
+		let mut total = Amount::from(0u64);
+		for coin in fees.iter().flat_map(|packet_fee| packet_fee.fee.total()) {
+			if coin.denom.to_string() != min_fee.denom {
+				continue
+			}
+			total = match total.checked_add(coin.amount) {
+				Some(total) => total,
+				// more than any minimum could ask for
+				None => return true,
+			};
+		}
+		total >= min_fee.amount
+	}
+
+	/// Returns true if `packet`, sent on `chain`, may be relayed. Its fees are only queried, at
+	/// `chain` height `at`, when a minimum fee is set.
+	pub async fn allows_packet(
+		&self,
+		chain: &impl Chain,
+		at: Height,
+		packet: &Packet,
+	) -> Result<bool, anyhow::Error> {
+		if !self.allows_channel(packet.source_channel, &packet.source_port) {
+			return Ok(false)
+		}
+		if self.min_fee.is_none() {
+			return Ok(true)
+		}
+		let fees = chain
+			.query_packet_fees(
+				at,
+				packet.source_channel,
+				packet.source_port.clone(),
+				packet.sequence.into(),
+			)
+			.await?;
+		Ok(self.allows_fees(&fees))
+	}
+}
+
+/// Filters for the packets sent on either chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PacketFilter {
+	#[serde(default)]
+	pub chain_a: ChainFilter,
+	#[serde(default)]
+	pub chain_b: ChainFilter,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::{
+		applications::{
+			fee::Fee,
+			transfer::{PrefixedCoin, PrefixedDenom},
+		},
+		signer::Signer,
+	};
+	use std::str::FromStr;
+
+	fn packet_fee(denom: &str, recv: u64, ack: u64) -> PacketFee {
+		let coin = |amount: u64| PrefixedCoin {
+			denom: PrefixedDenom::from_str(denom).unwrap(),
+			amount: amount.into(),
+		};
+		PacketFee {
+			fee: Fee { recv_fee: vec![coin(recv)], ack_fee: vec![coin(ack)], timeout_fee: vec![] },
+			refund_address: Signer::from_str("refund").unwrap(),
+			relayers: vec![],
+		}
+	}
+
+	fn rule(channel: u64, action: FilterAction) -> ChannelRule {
+		ChannelRule { port_id: PortId::transfer(), channel_id: ChannelId::new(channel), action }
+	}
+
+	#[test]
+	fn channel_rules_override_default() {
+		let filter = ChainFilter {
+			default: FilterAction::Deny,
+			min_fee: None,
+			channels: vec![rule(0, FilterAction::Allow), rule(1, FilterAction::Deny)],
+		};
+		assert!(filter.allows_channel(ChannelId::new(0), &PortId::transfer()));
+		assert!(!filter.allows_channel(ChannelId::new(1), &PortId::transfer()));
+		assert!(!filter.allows_channel(ChannelId::new(2), &PortId::transfer()));
+		// rules only match their own port
+		assert!(!filter.allows_channel(ChannelId::new(0), &PortId::from_str("other").unwrap()));
+
+		let filter =
+			ChainFilter { channels: vec![rule(1, FilterAction::Deny)], ..Default::default() };
+		assert!(filter.allows_channel(ChannelId::new(0), &PortId::transfer()));
+		assert!(!filter.allows_channel(ChannelId::new(1), &PortId::transfer()));
+	}
+
+	#[test]
+	fn min_fee_sums_matching_denom() {
+		let filter = ChainFilter {
+			min_fee: Some(MinFee { denom: "PICA".to_string(), amount: 100u64.into() }),
+			..Default::default()
+		};
+		assert!(!filter.allows_fees(&[]));
+		assert!(!filter.allows_fees(&[packet_fee("PICA", 40, 20)]));
+		assert!(filter.allows_fees(&[packet_fee("PICA", 40, 20), packet_fee("PICA", 40, 0)]));
+		assert!(!filter.allows_fees(&[packet_fee("PICA", 40, 20), packet_fee("KSM", 100, 100)]));
+
+		// without a minimum fee, packets without fees are relayed
+		assert!(ChainFilter::default().allows_fees(&[]));
+	}
+
+	#[test]
+	fn deserializes_from_toml() {
+		let filter: PacketFilter = toml::from_str(
+			r#"
+			[chain_a]
+			default = "deny"
+			min_fee = { denom = "PICA", amount = "1000" }
+
+			[[chain_a.channels]]
+			port_id = "transfer"
+			channel_id = "channel-0"
+			action = "allow"
+			"#,
+		)
+		.unwrap();
+		assert_eq!(
+			filter.chain_a,
+			ChainFilter {
+				default: FilterAction::Deny,
+				min_fee: Some(MinFee { denom: "PICA".to_string(), amount: 1000u64.into() }),
+				channels: vec![rule(0, FilterAction::Allow)],
+			}
+		);
+		assert_eq!(filter.chain_b, ChainFilter::default());
+	}
+}
@@ -0,0 +1,408 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Connection and channel handshakes that pick up where an interrupted handshake left off, instead
+//! of starting another one.
+//!
+//! The relayer only relays the handshake events emitted after the latest height of the
+//! counterparty's light client, see [`crate::events::parse_events`]. A step taken at or below
+//! that height was lost with the interrupted process, so it is relayed here. Later steps are left
+//! to the relayer, which must be running.
+
+use crate::events::parse_events;
+use anyhow::anyhow;
+use ibc::{
+	core::{
+		ics02_client::client_state::ClientState as ClientStateT,
+		ics03_connection::{
+			connection::{ConnectionEnd, IdentifiedConnectionEnd, State as ConnectionState},
+			events::Attributes,
+		},
+		ics04_channel::{
+			channel::{ChannelEnd, IdentifiedChannelEnd, Order, State as ChannelState},
+			events as channel_events, Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+	},
+	events::IbcEvent,
+	Height,
+};
+use pallet_ibc::light_clients::AnyClientState;
+use primitives::{utils, Chain};
+use std::time::Duration;
+
+/// How long to wait for a resumed handshake to complete.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// A connection handshake started on chain A that hasn't completed.
+#[derive(Debug, Clone)]
+pub struct PendingConnection {
+	pub connection_a: IdentifiedConnectionEnd,
+	/// The connection end on chain B, once the handshake got past OpenTry.
+	pub connection_b: Option<IdentifiedConnectionEnd>,
+}
+
+/// A channel handshake started on chain A that hasn't completed.
+#[derive(Debug, Clone)]
+pub struct PendingChannel {
+	pub channel_a: IdentifiedChannelEnd,
+	/// The channel end on chain B, once the handshake got past OpenTry.
+	pub channel_b: Option<IdentifiedChannelEnd>,
+}
+
+/// Creates a connection between the clients of the chains, resuming a matching handshake left
+/// pending on them rather than sending another OpenInit.
+pub async fn create_connection(
+	chain_a: &mut impl Chain,
+	chain_b: &mut impl Chain,
+	delay_period: Duration,
+) -> Result<(ConnectionId, ConnectionId), anyhow::Error> {
+	let pending = match find_pending_connection(chain_a, chain_b, delay_period).await? {
+		Some(pending) => pending,
+		None => return utils::create_connection(chain_a, chain_b, delay_period).await,
+	};
+	let connection_id = pending.connection_a.connection_id.clone();
+	log::info!(
+		"Resuming the handshake of connection {connection_id} on {}, {:?} on {}",
+		chain_a.name(),
+		pending.connection_b.as_ref().map(|c| c.connection_end.state),
+		chain_b.name()
+	);
+
+	match (pending.connection_a.connection_end.state, pending.connection_b) {
+		(ConnectionState::Init, None) =>
+			relay_connection_step(chain_a, chain_b, &pending.connection_a, |attributes| {
+				IbcEvent::OpenInitConnection(attributes.into())
+			})
+			.await?,
+		(ConnectionState::Init, Some(connection_b)) =>
+			relay_connection_step(chain_b, chain_a, &connection_b, |attributes| {
+				IbcEvent::OpenTryConnection(attributes.into())
+			})
+			.await?,
+		(_, Some(_)) =>
+			relay_connection_step(chain_a, chain_b, &pending.connection_a, |attributes| {
+				IbcEvent::OpenAckConnection(attributes.into())
+			})
+			.await?,
+		(state, None) => return Err(anyhow!("Unexpected pending connection state {state:?}")),
+	}
+
+	let wait = async {
+		loop {
+			tokio::time::sleep(chain_b.expected_block_time()).await;
+			let (height_a, ..) = chain_a.latest_height_and_timestamp().await?;
+			let connection_a = query_connection(chain_a, height_a, &connection_id).await?;
+			let connection_b =
+				find_counterparty_connection(chain_a, chain_b, &connection_id).await?;
+			match connection_b {
+				Some(connection_b)
+					if connection_a.map_or(false, |c| c.is_open()) &&
+						connection_b.connection_end.is_open() =>
+					return Ok::<_, anyhow::Error>(connection_b.connection_id),
+				_ => continue,
+			}
+		}
+	};
+	let connection_id_b = tokio::time::timeout(HANDSHAKE_TIMEOUT, wait).await.map_err(|_| {
+		anyhow!("Connection {connection_id} wasn't opened within {HANDSHAKE_TIMEOUT:?}")
+	})??;
+
+	Ok((connection_id, connection_id_b))
+}
+
+/// Creates a channel on the connection, resuming a matching handshake left pending on the chains
+/// rather than sending another OpenInit.
+pub async fn create_channel(
+	chain_a: &mut impl Chain,
+	chain_b: &mut impl Chain,
+	connection_id: ConnectionId,
+	port_id: PortId,
+	version: String,
+	order: Order,
+) -> Result<(ChannelId, ChannelId), anyhow::Error> {
+	let pending =
+		find_pending_channel(chain_a, chain_b, &connection_id, &port_id, &version, order).await?;
+	let pending = match pending {
+		Some(pending) => pending,
+		None =>
+			return utils::create_channel(chain_a, chain_b, connection_id, port_id, version, order)
+				.await,
+	};
+	let channel_id = pending.channel_a.channel_id;
+	log::info!(
+		"Resuming the handshake of channel {channel_id} on {}, {:?} on {}",
+		chain_a.name(),
+		pending.channel_b.as_ref().map(|c| c.channel_end.state),
+		chain_b.name()
+	);
+
+	match (pending.channel_a.channel_end.state, pending.channel_b) {
+		(ChannelState::Init, None) =>
+			relay_channel_step(chain_a, chain_b, &pending.channel_a, |height, channel| {
+				IbcEvent::OpenInitChannel(channel_events::OpenInit {
+					height,
+					port_id: channel.port_id.clone(),
+					channel_id: Some(channel.channel_id),
+					connection_id: channel.channel_end.connection_hops[0].clone(),
+					counterparty_port_id: channel.channel_end.counterparty().port_id.clone(),
+					counterparty_channel_id: channel.channel_end.counterparty().channel_id,
+				})
+			})
+			.await?,
+		(ChannelState::Init, Some(channel_b)) =>
+			relay_channel_step(chain_b, chain_a, &channel_b, |height, channel| {
+				IbcEvent::OpenTryChannel(channel_events::OpenTry {
+					height,
+					port_id: channel.port_id.clone(),
+					channel_id: Some(channel.channel_id),
+					connection_id: channel.channel_end.connection_hops[0].clone(),
+					counterparty_port_id: channel.channel_end.counterparty().port_id.clone(),
+					counterparty_channel_id: channel.channel_end.counterparty().channel_id,
+				})
+			})
+			.await?,
+		(_, Some(_)) =>
+			relay_channel_step(chain_a, chain_b, &pending.channel_a, |height, channel| {
+				IbcEvent::OpenAckChannel(channel_events::OpenAck {
+					height,
+					port_id: channel.port_id.clone(),
+					channel_id: Some(channel.channel_id),
+					counterparty_channel_id: channel.channel_end.counterparty().channel_id,
+					connection_id: channel.channel_end.connection_hops[0].clone(),
+					counterparty_port_id: channel.channel_end.counterparty().port_id.clone(),
+				})
+			})
+			.await?,
+		(state, None) => return Err(anyhow!("Unexpected pending channel state {state:?}")),
+	}
+
+	let wait = async {
+		loop {
+			tokio::time::sleep(chain_b.expected_block_time()).await;
+			let (height_a, ..) = chain_a.latest_height_and_timestamp().await?;
+			let counterparty = match query_channel(chain_a, height_a, &port_id, channel_id).await? {
+				Some(channel_a) if channel_a.is_open() => channel_a.counterparty().clone(),
+				_ => continue,
+			};
+			let channel_id_b = counterparty.channel_id.ok_or_else(|| {
+				anyhow!("Channel {channel_id} is open without a counterparty channel")
+			})?;
+			let (height_b, ..) = chain_b.latest_height_and_timestamp().await?;
+			let channel_b =
+				query_channel(chain_b, height_b, &counterparty.port_id, channel_id_b).await?;
+			if channel_b.map_or(false, |channel_b| channel_b.is_open()) {
+				return Ok::<_, anyhow::Error>(channel_id_b)
+			}
+		}
+	};
+	let channel_id_b = tokio::time::timeout(HANDSHAKE_TIMEOUT, wait).await.map_err(|_| {
+		anyhow!("Channel {channel_id} wasn't opened within {HANDSHAKE_TIMEOUT:?}")
+	})??;
+
+	Ok((channel_id, channel_id_b))
+}
+
+/// Returns a connection handshake between the clients of the chains with the given delay period,
+/// that was started on chain A and hasn't completed.
+pub async fn find_pending_connection(
+	chain_a: &impl Chain,
+	chain_b: &impl Chain,
+	delay_period: Duration,
+) -> Result<Option<PendingConnection>, anyhow::Error> {
+	let (height_a, ..) = chain_a.latest_height_and_timestamp().await?;
+	let connections = chain_a
+		.query_connection_using_client(
+			height_a.revision_height as u32,
+			chain_a.client_id().to_string(),
+		)
+		.await?;
+	for connection in connections {
+		let connection_a = IdentifiedConnectionEnd::try_from(connection)?;
+		let end = &connection_a.connection_end;
+		if end.counterparty().client_id() != &chain_b.client_id() ||
+			end.delay_period() != delay_period
+		{
+			continue
+		}
+		let connection_b =
+			find_counterparty_connection(chain_a, chain_b, &connection_a.connection_id).await?;
+		let state_b = connection_b.as_ref().map(|c| c.connection_end.state);
+		match (end.state, state_b) {
+			(ConnectionState::Init, None | Some(ConnectionState::TryOpen)) |
+			(ConnectionState::Open, Some(ConnectionState::TryOpen)) =>
+				return Ok(Some(PendingConnection { connection_a, connection_b })),
+			_ => continue,
+		}
+	}
+	Ok(None)
+}
+
+/// Returns a channel handshake on the connection with the given port, version and ordering, that
+/// was started on chain A and hasn't completed.
+pub async fn find_pending_channel(
+	chain_a: &impl Chain,
+	chain_b: &impl Chain,
+	connection_id: &ConnectionId,
+	port_id: &PortId,
+	version: &str,
+	order: Order,
+) -> Result<Option<PendingChannel>, anyhow::Error> {
+	let (height_a, ..) = chain_a.latest_height_and_timestamp().await?;
+	let connection_b = query_connection(chain_a, height_a, connection_id)
+		.await?
+		.and_then(|connection| connection.counterparty().connection_id().cloned());
+	let connection_b = match connection_b {
+		Some(connection_b) => connection_b,
+		None => return Ok(None),
+	};
+	let (height_b, ..) = chain_b.latest_height_and_timestamp().await?;
+	let channels_b = chain_b
+		.query_connection_channels(height_b, &connection_b)
+		.await?
+		.channels
+		.into_iter()
+		.map(IdentifiedChannelEnd::try_from)
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let channels_a = chain_a.query_connection_channels(height_a, connection_id).await?.channels;
+	for channel in channels_a {
+		let channel_a = IdentifiedChannelEnd::try_from(channel)?;
+		let end = &channel_a.channel_end;
+		if &channel_a.port_id != port_id ||
+			end.ordering != order ||
+			end.version != Version::new(version.to_string())
+		{
+			continue
+		}
+		let channel_b = channels_b
+			.iter()
+			.find(|channel_b| {
+				channel_b.channel_end.counterparty().port_id == channel_a.port_id &&
+					channel_b.channel_end.counterparty().channel_id == Some(channel_a.channel_id)
+			})
+			.cloned();
+		let state_b = channel_b.as_ref().map(|c| c.channel_end.state);
+		match (end.state, state_b) {
+			(ChannelState::Init, None | Some(ChannelState::TryOpen)) |
+			(ChannelState::Open, Some(ChannelState::TryOpen)) =>
+				return Ok(Some(PendingChannel { channel_a, channel_b })),
+			_ => continue,
+		}
+	}
+	Ok(None)
+}
+
+/// Returns the connection end on chain B whose counterparty is `connection_id` on chain A.
+async fn find_counterparty_connection(
+	chain_a: &impl Chain,
+	chain_b: &impl Chain,
+	connection_id: &ConnectionId,
+) -> Result<Option<IdentifiedConnectionEnd>, anyhow::Error> {
+	let (height_b, ..) = chain_b.latest_height_and_timestamp().await?;
+	let connections = chain_b
+		.query_connection_using_client(
+			height_b.revision_height as u32,
+			chain_b.client_id().to_string(),
+		)
+		.await?;
+	for connection in connections {
+		let connection = IdentifiedConnectionEnd::try_from(connection)?;
+		let counterparty = connection.connection_end.counterparty();
+		if counterparty.connection_id() == Some(connection_id) &&
+			counterparty.client_id() == &chain_a.client_id()
+		{
+			return Ok(Some(connection))
+		}
+	}
+	Ok(None)
+}
+
+/// Latest height of `source` known to its light client on `sink`.
+async fn client_height_on_counterparty(
+	source: &impl Chain,
+	sink: &impl Chain,
+) -> Result<Height, anyhow::Error> {
+	let (sink_height, ..) = sink.latest_height_and_timestamp().await?;
+	let response = sink.query_client_state(sink_height, source.client_id()).await?;
+	let client_state = response.client_state.ok_or_else(|| {
+		anyhow!("Client state for {} should exist on {}", source.name(), sink.name())
+	})?;
+	Ok(AnyClientState::try_from(client_state)?.latest_height())
+}
+
+async fn query_connection(
+	chain: &impl Chain,
+	at: Height,
+	connection_id: &ConnectionId,
+) -> Result<Option<ConnectionEnd>, anyhow::Error> {
+	let response = chain.query_connection_end(at, connection_id.clone()).await?;
+	Ok(response.connection.map(ConnectionEnd::try_from).transpose()?)
+}
+
+async fn query_channel(
+	chain: &impl Chain,
+	at: Height,
+	port_id: &PortId,
+	channel_id: ChannelId,
+) -> Result<Option<ChannelEnd>, anyhow::Error> {
+	let response = chain.query_channel_end(at, channel_id, port_id.clone()).await?;
+	Ok(response.channel.map(ChannelEnd::try_from).transpose()?)
+}
+
+/// Relays the handshake step that took `connection` on `source` to its current state, if the
+/// step was taken at or below the latest height of the client of `source` on `sink`.
+async fn relay_connection_step(
+	source: &mut impl Chain,
+	sink: &mut impl Chain,
+	connection: &IdentifiedConnectionEnd,
+	event: impl FnOnce(Attributes) -> IbcEvent,
+) -> Result<(), anyhow::Error> {
+	let height = client_height_on_counterparty(source, sink).await?;
+	let end = query_connection(source, height, &connection.connection_id).await?;
+	let end = match end {
+		Some(end) if end.state_matches(connection.connection_end.state()) => end,
+		// the step is above the client's height, the relayer relays it with the next update.
+		_ => return Ok(()),
+	};
+	let event = event(Attributes {
+		height,
+		connection_id: Some(connection.connection_id.clone()),
+		client_id: end.client_id().clone(),
+		counterparty_connection_id: end.counterparty().connection_id().cloned(),
+		counterparty_client_id: end.counterparty().client_id().clone(),
+	});
+	let messages = parse_events(source, sink, vec![event]).await?;
+	sink.submit(messages).await?;
+	Ok(())
+}
+
+/// Relays the handshake step that took `channel` on `source` to its current state, if the step
+/// was taken at or below the latest height of the client of `source` on `sink`.
+async fn relay_channel_step(
+	source: &mut impl Chain,
+	sink: &mut impl Chain,
+	channel: &IdentifiedChannelEnd,
+	event: impl FnOnce(Height, &IdentifiedChannelEnd) -> IbcEvent,
+) -> Result<(), anyhow::Error> {
+	let height = client_height_on_counterparty(source, sink).await?;
+	let end = query_channel(source, height, &channel.port_id, channel.channel_id).await?;
+	if !end.map_or(false, |end| end.state_matches(&channel.channel_end.state)) {
+		// the step is above the client's height, the relayer relays it with the next update.
+		return Ok(())
+	}
+	let messages = parse_events(source, sink, vec![event(height, channel)]).await?;
+	sink.submit(messages).await?;
+	Ok(())
+}
@@ -0,0 +1,141 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Keeps the light clients on both chains from expiring while no packets are relayed.
+//!
+//! Client updates are optional unless they carry packet events, so a quiet channel can leave the
+//! counterparty's client untouched until its trusting period runs out, after which it can only be
+//! recovered through governance. The keep-alive task periodically checks how much of the trusting
+//! period is left and, once it drops below the configured fraction, makes the client update task
+//! send the next client update.
+
+use crate::pipeline::query_sink_client_state;
+use ibc::core::ics02_client::{
+	client_consensus::ConsensusState as ConsensusStateT, client_state::ClientState as ClientStateT,
+};
+use metrics::handler::MetricsHandler;
+use pallet_ibc::light_clients::AnyConsensusState;
+use primitives::{error::Error, Chain};
+use std::{
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+use tokio::sync::Mutex;
+
+/// Default time between two expiry checks, in seconds.
+pub const DEFAULT_KEEP_ALIVE_INTERVAL: u64 = 10 * 60;
+
+/// Default fraction of the trusting period left at which a client update is forced.
+pub const DEFAULT_KEEP_ALIVE_THRESHOLD: f64 = 1.0 / 3.0;
+
+#[derive(Debug, Clone, Copy)]
+pub struct KeepAliveConfig {
+	/// Time between two expiry checks.
+	pub interval: Duration,
+	/// Fraction of the trusting period left below which a client update is forced.
+	pub threshold: f64,
+}
+
+impl Default for KeepAliveConfig {
+	fn default() -> Self {
+		Self {
+			interval: Duration::from_secs(DEFAULT_KEEP_ALIVE_INTERVAL),
+			threshold: DEFAULT_KEEP_ALIVE_THRESHOLD,
+		}
+	}
+}
+
+/// Periodically checks the time left until the source's client on the sink expires, and sets
+/// `force_update` once it's below `config.threshold` of the trusting period. The client update task
+/// then sends the next client update even if it's optional, and clears the flag.
+pub async fn keep_alive_task<A, B>(
+	source: A,
+	sink: B,
+	config: KeepAliveConfig,
+	metrics: Option<Arc<Mutex<MetricsHandler>>>,
+	force_update: Arc<AtomicBool>,
+) -> Result<(), anyhow::Error>
+where
+	A: Chain,
+	B: Chain,
+{
+	let mut interval = tokio::time::interval(config.interval);
+	loop {
+		interval.tick().await;
+		let (time_to_expiry, trusting_period) = match query_time_to_expiry(&source, &sink).await {
+			Ok(times) => times,
+			Err(e) => {
+				log::error!(
+					"Failed to query the expiry of the client for {} on {} {:?}",
+					source.name(),
+					sink.name(),
+					e
+				);
+				continue
+			},
+		};
+		if let Some(metrics) = metrics.as_ref() {
+			let seconds_to_expiry = time_to_expiry.as_secs();
+			if let Err(e) =
+				metrics.lock().await.handle_client_expiry(&source.client_id(), seconds_to_expiry)
+			{
+				log::error!("Failed to handle metrics for {} {:?}", source.name(), e);
+			}
+		}
+		if time_to_expiry.as_secs_f64() < trusting_period.as_secs_f64() * config.threshold {
+			log::info!(
+				"Client for {} on {} expires in {}s, forcing the next client update",
+				source.name(),
+				sink.name(),
+				time_to_expiry.as_secs()
+			);
+			force_update.store(true, Ordering::SeqCst);
+		}
+	}
+}
+
+/// Returns the time left until the source's client on the sink expires, along with its trusting
+/// period. The time left is measured from the timestamp of the client's latest consensus state to
+/// the sink's latest block timestamp.
+pub async fn query_time_to_expiry(
+	source: &impl Chain,
+	sink: &impl Chain,
+) -> Result<(Duration, Duration), anyhow::Error> {
+	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+	let client_state = query_sink_client_state(source, sink, sink_height).await?;
+	let response = sink
+		.query_client_consensus(sink_height, source.client_id(), client_state.latest_height())
+		.await?;
+	let consensus_state = AnyConsensusState::try_from(response.consensus_state.ok_or_else(|| {
+		Error::Custom(format!(
+			"Consensus state for {} should exist on {}",
+			source.name(),
+			sink.name()
+		))
+	})?)
+	.map_err(|_| {
+		Error::Custom(format!(
+			"Invalid consensus state for {} found on {}",
+			source.name(),
+			sink.name()
+		))
+	})?;
+	let elapsed = sink_timestamp.duration_since(&consensus_state.timestamp()).unwrap_or_default();
+	let trusting_period = client_state.trusting_period();
+	Ok((trusting_period.saturating_sub(elapsed), trusting_period))
+}
@@ -16,18 +16,30 @@
 
 use futures::{future::ready, StreamExt};
 use primitives::Chain;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
 pub mod chain;
 pub mod command;
 pub mod events;
+pub mod filter;
+pub mod handshake;
+pub mod keep_alive;
 pub mod logging;
-mod macros;
 pub mod packets;
+pub mod pipeline;
+pub mod query;
 pub mod queue;
+pub mod reload;
+pub mod retry;
+pub mod store;
+pub mod watchtower;
 
-use events::{has_packet_events, parse_events};
 use ibc::events::IbcEvent;
+use keep_alive::KeepAliveConfig;
 use metrics::handler::MetricsHandler;
+use reload::SharedRuntimeConfig;
+use store::RelayerStore;
 
 #[derive(Copy, Debug, Clone)]
 pub enum Mode {
@@ -37,34 +49,56 @@ pub enum Mode {
 
 /// Core relayer loop, waits for new finality events and forwards any new [`ibc::IbcEvents`]
 /// to the counter party chain.
+///
+/// Each direction runs its client updates and its packet relaying in separate tasks, see
+/// [`pipeline`]. The relayer stops as soon as any of the tasks does. The progress kept in `store`
+/// lets a restarted relayer catch up on packets sent while it was down. With `keep_alive`, both
+/// clients are updated before they expire even when no packets are relayed, see [`keep_alive`].
+/// Only the packets on the channels whitelisted in `runtime_config` and let through by its filters
+/// are relayed, see [`filter`]. Both can change while relaying, see [`reload`].
+#[allow(clippy::too_many_arguments)]
 pub async fn relay<A, B>(
-	mut chain_a: A,
-	mut chain_b: B,
-	mut chain_a_metrics: Option<MetricsHandler>,
-	mut chain_b_metrics: Option<MetricsHandler>,
+	chain_a: A,
+	chain_b: B,
+	chain_a_metrics: Option<MetricsHandler>,
+	chain_b_metrics: Option<MetricsHandler>,
 	mode: Option<Mode>,
+	store: Arc<dyn RelayerStore>,
+	keep_alive: Option<KeepAliveConfig>,
+	runtime_config: SharedRuntimeConfig,
 ) -> Result<(), anyhow::Error>
 where
-	A: Chain,
-	B: Chain,
+	A: Chain + Clone + 'static,
+	A::FinalityEvent: Send + Sync,
+	B: Chain + Clone + 'static,
+	B::FinalityEvent: Send + Sync,
 {
-	let (mut chain_a_finality, mut chain_b_finality) =
-		(chain_a.finality_notifications().await, chain_b.finality_notifications().await);
-	// loop forever
-	loop {
-		tokio::select! {
-			// new finality event from chain A
-			result = chain_a_finality.next() => {
-				process_finality_event!(chain_a, chain_b, chain_a_metrics, mode, result)
-			}
-			// new finality event from chain B
-			result = chain_b_finality.next() => {
-				process_finality_event!(chain_b, chain_a, chain_b_metrics, mode, result)
-			}
-		}
-	}
+	let chain_a_metrics = chain_a_metrics.map(|metrics| Arc::new(Mutex::new(metrics)));
+	let chain_b_metrics = chain_b_metrics.map(|metrics| Arc::new(Mutex::new(metrics)));
+	let mut tasks = pipeline::spawn(
+		chain_a.clone(),
+		chain_b.clone(),
+		chain_a_metrics,
+		store.clone(),
+		mode,
+		keep_alive,
+		runtime_config.clone(),
+	);
+	tasks.extend(pipeline::spawn(
+		chain_b,
+		chain_a,
+		chain_b_metrics,
+		store,
+		mode,
+		keep_alive,
+		runtime_config.reversed(),
+	));
 
-	Ok(())
+	let (result, _, remaining) = futures::future::select_all(tasks).await;
+	for task in remaining {
+		task.abort();
+	}
+	result?
 }
 
 pub async fn fish<A, B>(chain_a: A, chain_b: B) -> Result<(), anyhow::Error>
@@ -122,7 +156,9 @@ where
 #[cfg(feature = "testing")]
 pub mod send_packet_relay {
 	use std::sync::atomic::{AtomicBool, Ordering};
+	use std::{sync::Mutex, time::Duration};
 	static RELAY_PACKETS: AtomicBool = AtomicBool::new(true);
+	static SEND_PACKETS_QUERY_DELAY: Mutex<Option<(String, Duration)>> = Mutex::new(None);
 
 	/// Returns status of send packet relay
 	pub fn packet_relay_status() -> bool {
@@ -133,4 +169,17 @@ pub mod send_packet_relay {
 	pub fn set_relay_status(status: bool) {
 		RELAY_PACKETS.store(status, Ordering::SeqCst);
 	}
+
+	/// Returns how long send packet queries on the chain with the given name are held back
+	pub fn send_packets_query_delay(chain_name: &str) -> Option<Duration> {
+		match &*SEND_PACKETS_QUERY_DELAY.lock().unwrap() {
+			Some((name, delay)) if name == chain_name => Some(*delay),
+			_ => None,
+		}
+	}
+
+	/// Holds back send packet queries on the chain with the given name, `None` removes the delay
+	pub fn set_send_packets_query_delay(delay: Option<(String, Duration)>) {
+		*SEND_PACKETS_QUERY_DELAY.lock().unwrap() = delay;
+	}
 }
@@ -13,17 +13,21 @@
 // limitations under the License.
 
 #[cfg(feature = "testing")]
-use crate::send_packet_relay::packet_relay_status;
+use crate::send_packet_relay::{packet_relay_status, send_packets_query_delay};
 
-use crate::packets::utils::{
-	construct_ack_message, construct_recv_message, construct_timeout_message,
-	get_timeout_proof_height, verify_delay_passed, VerifyDelayOn,
+use crate::{
+	filter::ChainFilter,
+	packets::utils::{
+		construct_proven_messages, construct_timeout_message, get_timeout_proof_height,
+		verify_delay_passed, UnprovenMessage, VerifyDelayOn,
+	},
 };
 use ibc::{
 	core::{
-		ics02_client::client_state::ClientState as ClientStateT,
+		ics02_client::client_state::{ClientState as ClientStateT, ClientStatus},
 		ics03_connection::connection::ConnectionEnd,
 		ics04_channel::channel::{ChannelEnd, State},
+		ics24_host::identifier::{ChannelId, PortId},
 	},
 	Height,
 };
@@ -34,22 +38,59 @@ use primitives::{
 	query_undelivered_acks, query_undelivered_sequences, Chain,
 };
 
+pub mod clear;
 pub mod connection_delay;
 pub mod utils;
 
-/// Returns a tuple of messages, with the first item being packets that are ready to be sent to the
-/// sink chain. And the second item being packet timeouts that should be sent to the source.
+/// Messages queried for a set of source channels by [`query_ready_and_timed_out_packets`].
+pub struct ReadyPackets {
+	/// Packets and acknowledgements that are ready to be sent to the sink chain.
+	pub messages: Vec<Any>,
+	/// Packet timeouts that should be sent to the source.
+	pub timeouts: Vec<Any>,
+	/// Number of packets and acknowledgements skipped because of `source_filter`, for packets
+	/// sent on the source, or `sink_filter`, for acknowledgements of packets sent on the sink.
+	pub filtered: u64,
+	/// Number of packets sent on each channel that have not been received on the sink yet.
+	pub undelivered: Vec<((ChannelId, PortId), u64)>,
+}
+
+/// Returns the messages for the given source channels, see [`ReadyPackets`].
 pub async fn query_ready_and_timed_out_packets(
 	source: &impl Chain,
 	sink: &impl Chain,
-) -> Result<(Vec<Any>, Vec<Any>), anyhow::Error> {
-	let mut messages = vec![];
+	channels: Vec<(ChannelId, PortId)>,
+	source_filter: &ChainFilter,
+	sink_filter: &ChainFilter,
+) -> Result<ReadyPackets, anyhow::Error> {
+	let mut unproven = vec![];
 	let mut timeout_messages = vec![];
+	let mut filtered = 0;
+	let mut undelivered = vec![];
 	let (source_height, source_timestamp) = source.latest_height_and_timestamp().await?;
 	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
-	let channel_whitelist = source.channel_whitelist();
 
-	for (channel_id, port_id) in channel_whitelist {
+	// A frozen client, e.g. one frozen by governance after its chain halted, can't verify any of
+	// the proofs, so the channels it backs are not relayed until it is unfrozen.
+	let source_client_status = sink.query_client_status(sink_height, source.client_id()).await?;
+	let sink_client_status = source.query_client_status(source_height, sink.client_id()).await?;
+	if source_client_status == ClientStatus::Frozen || sink_client_status == ClientStatus::Frozen {
+		log::warn!(
+			"Client of {} on {} or of {} on {} is frozen, halting packet relay",
+			source.name(),
+			sink.name(),
+			sink.name(),
+			source.name()
+		);
+		return Ok(ReadyPackets {
+			messages: vec![],
+			timeouts: vec![],
+			filtered: 0,
+			undelivered: vec![],
+		})
+	}
+
+	for (channel_id, port_id) in channels {
 		let source_channel_response =
 			source.query_channel_end(source_height, channel_id, port_id.clone()).await?;
 		let source_channel_end =
@@ -144,6 +185,26 @@ pub async fn query_ready_and_timed_out_packets(
 		let latest_sink_height_on_source = sink_client_state_on_source.latest_height();
 		let latest_source_height_on_sink = source_client_state_on_sink.latest_height();
 
+		// Packets can't be received on a channel that's closed on the sink, so they're timed out
+		// on close instead, proving the closed channel end at the latest height of the sink's
+		// client on the source.
+		let sink_channel_closed_on_source = if sink_channel_end.state == State::Closed {
+			let channel = sink
+				.query_channel_end(
+					latest_sink_height_on_source,
+					sink_channel_id,
+					sink_port_id.clone(),
+				)
+				.await?
+				.channel;
+			match channel {
+				Some(channel) => ChannelEnd::try_from(channel)?.state == State::Closed,
+				None => false,
+			}
+		} else {
+			false
+		};
+
 		// query packets that are waiting for connection delay.
 		let seqs = query_undelivered_sequences(
 			source_height,
@@ -154,10 +215,19 @@ pub async fn query_ready_and_timed_out_packets(
 			sink,
 		)
 		.await?;
+		undelivered.push(((channel_id, port_id.clone()), seqs.len() as u64));
 
+		#[cfg(feature = "testing")]
+		if let Some(delay) = send_packets_query_delay(source.name()) {
+			tokio::time::sleep(delay).await;
+		}
 		let send_packets = source.query_send_packets(channel_id, port_id.clone(), seqs).await?;
 		for send_packet in send_packets {
 			let packet = packet_info_to_packet(&send_packet);
+			if !source_filter.allows_packet(source, source_height, &packet).await? {
+				filtered += 1;
+				continue
+			}
 			// Check if packet has timed out
 			if packet.timed_out(&sink_timestamp, sink_height) {
 				// so we know this packet has timed out on the sink, we need to find the maximum
@@ -211,10 +281,37 @@ pub async fn query_ready_and_timed_out_packets(
 				continue
 			}
 
-			// If packet has not timed out but channel is closed on sink we skip
-			// Since we have no reference point for when this channel was closed so we can't
-			// calculate connection delays yet
 			if sink_channel_end.state == State::Closed {
+				// the sink's client on the source isn't aware of the channel closing yet.
+				if !sink_channel_closed_on_source {
+					continue
+				}
+				let proof_height = latest_sink_height_on_source;
+				if !verify_delay_passed(
+					source,
+					sink,
+					source_timestamp,
+					source_height,
+					sink_timestamp,
+					sink_height,
+					source_connection_end.delay_period(),
+					proof_height,
+					VerifyDelayOn::Source,
+				)
+				.await?
+				{
+					continue
+				}
+				let msg = construct_timeout_message(
+					source,
+					sink,
+					&sink_channel_end,
+					packet,
+					next_sequence_recv.next_sequence_receive,
+					proof_height,
+				)
+				.await?;
+				timeout_messages.push(msg);
 				continue
 			}
 
@@ -264,8 +361,7 @@ pub async fn query_ready_and_timed_out_packets(
 				continue
 			}
 
-			let msg = construct_recv_message(source, sink, packet, proof_height).await?;
-			messages.push(msg)
+			unproven.push((UnprovenMessage::Recv(packet), proof_height))
 		}
 
 		// query acknowledgements that are waiting for connection delay.
@@ -285,6 +381,11 @@ pub async fn query_ready_and_timed_out_packets(
 		let acknowledgements = source.query_recv_packets(channel_id, port_id, acks).await?;
 		for acknowledgement in acknowledgements {
 			let packet = packet_info_to_packet(&acknowledgement);
+			// the packet was sent on the sink, which escrows its fees.
+			if !sink_filter.allows_packet(sink, sink_height, &packet).await? {
+				filtered += 1;
+				continue
+			}
 			let ack = if let Some(ack) = acknowledgement.ack {
 				ack
 			} else {
@@ -332,11 +433,12 @@ pub async fn query_ready_and_timed_out_packets(
 				continue
 			}
 
-			let msg = construct_ack_message(source, sink, packet, ack, proof_height).await?;
-
-			messages.push(msg)
+			unproven.push((UnprovenMessage::Ack(packet, ack), proof_height))
 		}
 	}
 
-	Ok((messages, timeout_messages))
+	// the proofs of messages proven at the same height are queried together.
+	let messages = construct_proven_messages(source, sink, unproven).await?;
+
+	Ok(ReadyPackets { messages, timeouts: timeout_messages, filtered, undelivered })
 }
@@ -0,0 +1,357 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! One-shot relaying of a single packet, for clearing packets the relayer left behind without
+//! running the relayer loop.
+
+use crate::{
+	packets::utils::{
+		construct_ack_message, construct_recv_message, construct_timeout_message,
+		get_timeout_proof_height, verify_delay_passed, VerifyDelayOn,
+	},
+	pipeline::query_proven_height,
+};
+use anyhow::{anyhow, bail};
+use futures::StreamExt;
+use ibc::{
+	core::{
+		ics03_connection::connection::ConnectionEnd,
+		ics04_channel::{channel::ChannelEnd, packet::Packet},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	events::IbcEvent,
+	Height,
+};
+use ibc_proto::google::protobuf::Any;
+use primitives::{find_suitable_proof_height_for_client, packet_info_to_packet, Chain};
+use std::{str::FromStr, time::Duration};
+
+/// Number of times the client state is checked for a submitted client update before waiting for
+/// the next finality notification.
+const CLIENT_UPDATE_CHECKS: u32 = 10;
+
+/// Message to relay for a packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketMessageKind {
+	/// `MsgRecvPacket` for a packet sent on the source, submitted to the sink.
+	Recv,
+	/// `MsgAcknowledgement` for a packet sent on the sink and acknowledged on the source,
+	/// submitted to the sink.
+	Ack,
+	/// `MsgTimeout` for a packet sent on the source that timed out on the sink, submitted to the
+	/// source.
+	Timeout,
+}
+
+impl FromStr for PacketMessageKind {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"recv" => Ok(Self::Recv),
+			"ack" => Ok(Self::Ack),
+			"timeout" => Ok(Self::Timeout),
+			_ => Err(anyhow!("Expected one of 'recv', 'ack' or 'timeout', found '{s}'")),
+		}
+	}
+}
+
+/// Relays the message of `kind` for the packet with `sequence` on the given channel of `source`,
+/// updating the counterparty's client first if it's behind the packet. Packets and
+/// acknowledgements are proven at the lowest consensus height of the client that covers them, see
+/// [`packet_proof_height`]. Returns the events about the packet emitted in the block that included
+/// the message.
+pub async fn relay_packet(
+	source: &mut impl Chain,
+	sink: &mut impl Chain,
+	channel_id: ChannelId,
+	port_id: PortId,
+	sequence: u64,
+	kind: PacketMessageKind,
+) -> Result<Vec<IbcEvent>, anyhow::Error> {
+	let (source_height, _) = source.latest_height_and_timestamp().await?;
+	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+	let channel_response =
+		source.query_channel_end(source_height, channel_id, port_id.clone()).await?;
+	let channel_end = ChannelEnd::try_from(channel_response.channel.ok_or_else(|| {
+		anyhow!("Channel {port_id}/{channel_id} not found on {}", source.name())
+	})?)?;
+	let sink_channel_id = channel_end.counterparty().channel_id.ok_or_else(|| {
+		anyhow!("Channel {port_id}/{channel_id} has no counterparty channel id")
+	})?;
+	let sink_port_id = channel_end.counterparty().port_id.clone();
+	let connection_id = channel_end
+		.connection_hops
+		.get(0)
+		.ok_or_else(|| anyhow!("Channel {port_id}/{channel_id} has no connection"))?
+		.clone();
+	let connection_response = source.query_connection_end(source_height, connection_id).await?;
+	let connection_delay = ConnectionEnd::try_from(
+		connection_response
+			.connection
+			.ok_or_else(|| anyhow!("Connection of {port_id}/{channel_id} not found"))?,
+	)?
+	.delay_period();
+
+	if matches!(kind, PacketMessageKind::Recv | PacketMessageKind::Timeout) {
+		let unreceived = sink
+			.query_unreceived_packets(
+				sink_height,
+				sink_channel_id,
+				sink_port_id.clone(),
+				vec![sequence],
+			)
+			.await?;
+		if unreceived.is_empty() {
+			bail!(
+				"Packet {sequence} on {port_id}/{channel_id} was already received on {}",
+				sink.name()
+			)
+		}
+		// packets sent on the source keep their commitment until they're acknowledged or time out.
+		let committed = source
+			.query_unreceived_acknowledgements(
+				source_height,
+				channel_id,
+				port_id.clone(),
+				vec![sequence],
+			)
+			.await?;
+		if committed.is_empty() {
+			bail!(
+				"Packet {sequence} on {port_id}/{channel_id} has no commitment on {}, it was \
+				 already acknowledged or timed out",
+				source.name()
+			)
+		}
+	}
+
+	match kind {
+		PacketMessageKind::Recv => {
+			let packet_info = source
+				.query_send_packets(channel_id, port_id.clone(), vec![sequence])
+				.await?
+				.pop()
+				.ok_or_else(|| anyhow!("Packet {sequence} on {port_id}/{channel_id} not found"))?;
+			let packet = packet_info_to_packet(&packet_info);
+			if packet.timed_out(&sink_timestamp, sink_height) {
+				bail!(
+					"Packet {sequence} on {port_id}/{channel_id} timed out on {}, relay its \
+					 timeout instead",
+					sink.name()
+				)
+			}
+			let latest_height = update_client(source, sink, packet_info.height).await?;
+			let proof_height =
+				packet_proof_height(source, sink, packet_info.height, latest_height).await?;
+			check_delay(source, sink, connection_delay, proof_height, VerifyDelayOn::Sink).await?;
+			let msg = construct_recv_message(source, sink, packet.clone(), proof_height).await?;
+			submit(sink, msg, &packet).await
+		},
+		PacketMessageKind::Ack => {
+			let unacknowledged = sink
+				.query_unreceived_acknowledgements(
+					sink_height,
+					sink_channel_id,
+					sink_port_id,
+					vec![sequence],
+				)
+				.await?;
+			if unacknowledged.is_empty() {
+				bail!(
+					"Acknowledgement of packet {sequence} on {port_id}/{channel_id} was already \
+					 received on {}",
+					sink.name()
+				)
+			}
+			let packet_info = source
+				.query_recv_packets(channel_id, port_id.clone(), vec![sequence])
+				.await?
+				.pop()
+				.ok_or_else(|| {
+					anyhow!("Packet {sequence} on {port_id}/{channel_id} wasn't received")
+				})?;
+			let ack = packet_info.ack.clone().ok_or_else(|| {
+				anyhow!("Packet {sequence} on {port_id}/{channel_id} has no acknowledgement yet")
+			})?;
+			let packet = packet_info_to_packet(&packet_info);
+			let latest_height = update_client(source, sink, packet_info.height).await?;
+			let proof_height =
+				packet_proof_height(source, sink, packet_info.height, latest_height).await?;
+			check_delay(source, sink, connection_delay, proof_height, VerifyDelayOn::Sink).await?;
+			let msg =
+				construct_ack_message(source, sink, packet.clone(), ack, proof_height).await?;
+			submit(sink, msg, &packet).await
+		},
+		PacketMessageKind::Timeout => {
+			let packet_info = source
+				.query_send_packets(channel_id, port_id.clone(), vec![sequence])
+				.await?
+				.pop()
+				.ok_or_else(|| anyhow!("Packet {sequence} on {port_id}/{channel_id} not found"))?;
+			let packet = packet_info_to_packet(&packet_info);
+			if !packet.timed_out(&sink_timestamp, sink_height) {
+				bail!(
+					"Packet {sequence} on {port_id}/{channel_id} hasn't timed out on {} yet",
+					sink.name()
+				)
+			}
+			let latest_sink_height_on_source =
+				update_client(sink, source, sink_height.revision_height).await?;
+			let (source_height, _) = source.latest_height_and_timestamp().await?;
+			let proof_height = get_timeout_proof_height(
+				source,
+				sink,
+				source_height,
+				sink_height,
+				sink_timestamp,
+				latest_sink_height_on_source,
+				&packet,
+				packet_info.height,
+			)
+			.await
+			.ok_or_else(|| anyhow!("No height to prove the timeout of packet {sequence} at"))?;
+			check_delay(source, sink, connection_delay, proof_height, VerifyDelayOn::Source)
+				.await?;
+			let sink_channel_response = sink
+				.query_channel_end(sink_height, sink_channel_id, packet.destination_port.clone())
+				.await?;
+			let sink_channel_end = ChannelEnd::try_from(
+				sink_channel_response
+					.channel
+					.ok_or_else(|| anyhow!("Counterparty channel not found on {}", sink.name()))?,
+			)?;
+			let next_sequence_recv = sink
+				.query_next_sequence_recv(sink_height, &packet.destination_port, &sink_channel_id)
+				.await?;
+			let msg = construct_timeout_message(
+				source,
+				sink,
+				&sink_channel_end,
+				packet.clone(),
+				next_sequence_recv.next_sequence_receive,
+				proof_height,
+			)
+			.await?;
+			submit(source, msg, &packet).await
+		},
+	}
+}
+
+/// Updates the source's client on the sink until its latest height is at least `height`, returns
+/// the client's latest height.
+async fn update_client(
+	source: &mut impl Chain,
+	sink: &impl Chain,
+	height: u64,
+) -> Result<Height, anyhow::Error> {
+	let mut latest_height = query_proven_height(source, sink).await?;
+	if latest_height.revision_height >= height {
+		return Ok(latest_height)
+	}
+	let mut finality_notifications = source.finality_notifications().await;
+	while let Some(finality_event) = finality_notifications.next().await {
+		let (msg_update_client, ..) = source.query_latest_ibc_events(finality_event, sink).await?;
+		log::info!("Updating the client of {} on {}", source.name(), sink.name());
+		sink.submit(vec![msg_update_client]).await?;
+		for _ in 0..CLIENT_UPDATE_CHECKS {
+			tokio::time::sleep(sink.expected_block_time()).await;
+			let proven_height = query_proven_height(source, sink).await?;
+			if proven_height > latest_height {
+				latest_height = proven_height;
+				break
+			}
+		}
+		if latest_height.revision_height >= height {
+			return Ok(latest_height)
+		}
+	}
+	Err(anyhow!("Finality notifications of {} ended before its client was updated", source.name()))
+}
+
+/// Lowest height from `height` up to the client's `latest_height` at which the source's client on
+/// the sink has a consensus state, so that the connection delay runs from the earliest client
+/// update covering the packet rather than the latest one. Falls back to
+/// `latest_height` when no consensus state is found, e.g. because they were pruned.
+async fn packet_proof_height(
+	source: &impl Chain,
+	sink: &impl Chain,
+	height: u64,
+	latest_height: Height,
+) -> Result<Height, anyhow::Error> {
+	let (sink_height, _) = sink.latest_height_and_timestamp().await?;
+	let proof_height = find_suitable_proof_height_for_client(
+		sink,
+		sink_height,
+		source.client_id(),
+		Height::new(latest_height.revision_number, height),
+		None,
+		latest_height,
+	)
+	.await;
+	Ok(proof_height.unwrap_or(latest_height))
+}
+
+/// Fails if the connection delay since the client update at `proof_height` hasn't passed yet.
+async fn check_delay(
+	source: &impl Chain,
+	sink: &impl Chain,
+	connection_delay: Duration,
+	proof_height: Height,
+	verify_delay_on: VerifyDelayOn,
+) -> Result<(), anyhow::Error> {
+	let (source_height, source_timestamp) = source.latest_height_and_timestamp().await?;
+	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+	let passed = verify_delay_passed(
+		source,
+		sink,
+		source_timestamp,
+		source_height,
+		sink_timestamp,
+		sink_height,
+		connection_delay,
+		proof_height,
+		verify_delay_on,
+	)
+	.await?;
+	if !passed {
+		bail!("The connection delay of {connection_delay:?} hasn't passed yet, try again later")
+	}
+	Ok(())
+}
+
+/// Submits `msg` to `chain`, returns the events about `packet` emitted in the same block.
+async fn submit(
+	chain: &impl Chain,
+	msg: Any,
+	packet: &Packet,
+) -> Result<Vec<IbcEvent>, anyhow::Error> {
+	let tx_id = chain
+		.submit(vec![msg])
+		.await
+		.map_err(|e| anyhow!("Failed to submit packet message to {}: {e}", chain.name()))?;
+	log::info!("Submitted packet message to {} in {tx_id:?}", chain.name());
+	let events = chain.query_tx_block_events(tx_id).await?;
+	Ok(events
+		.into_iter()
+		.filter(|event| {
+			event.packet().map_or(false, |p| {
+				p.sequence == packet.sequence &&
+					p.source_channel == packet.source_channel &&
+					p.source_port == packet.source_port
+			})
+		})
+		.collect())
+}
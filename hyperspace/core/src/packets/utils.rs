@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::packets::connection_delay::has_delay_elapsed;
+use anyhow::anyhow;
 use ibc::{
 	core::{
 		ics02_client::client_state::ClientState as ClientStateT,
@@ -38,7 +39,7 @@ use ibc::{
 use ibc_proto::google::protobuf::Any;
 use pallet_ibc::light_clients::AnyClientState;
 use primitives::{apply_prefix, find_suitable_proof_height_for_client, Chain};
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 use tendermint_proto::Protobuf;
 
 pub async fn get_timeout_proof_height(
@@ -225,6 +226,7 @@ pub async fn construct_timeout_message(
 			next_sequence_recv: next_sequence_recv.into(),
 			proofs: Proofs::new(proof_unreceived, None, None, Some(proof_closed), proof_height)?,
 			signer: source.account_id(),
+			proof_close_connection_id: None,
 		};
 		let value = msg.encode_vec();
 		Any { value, type_url: msg.type_url() }
@@ -248,19 +250,9 @@ pub async fn construct_recv_message(
 	packet: Packet,
 	proof_height: Height,
 ) -> Result<Any, anyhow::Error> {
-	let path = get_key_path(KeyPathType::CommitmentPath, &packet);
-
-	let key = apply_prefix(source.connection_prefix().into_vec(), path);
-	let proof = source.query_proof(proof_height, vec![key]).await?;
-	let commitment_proof = CommitmentProofBytes::try_from(proof)?;
-	let msg = MsgRecvPacket {
-		packet,
-		proofs: Proofs::new(commitment_proof, None, None, None, proof_height)?,
-		signer: sink.account_id(),
-	};
-	let value = msg.encode_vec();
-	let msg = Any { value, type_url: msg.type_url() };
-	Ok(msg)
+	let msg = UnprovenMessage::Recv(packet);
+	let proof = source.query_proof(proof_height, vec![msg.proof_key(source)]).await?;
+	msg.into_message(sink, proof, proof_height)
 }
 
 pub async fn construct_ack_message(
@@ -270,20 +262,103 @@ pub async fn construct_ack_message(
 	ack: Vec<u8>,
 	proof_height: Height,
 ) -> Result<Any, anyhow::Error> {
-	let path = get_key_path(KeyPathType::AcksPath, &packet);
+	let msg = UnprovenMessage::Ack(packet, ack);
+	let proof = source.query_proof(proof_height, vec![msg.proof_key(source)]).await?;
+	msg.into_message(sink, proof, proof_height)
+}
 
-	let key = apply_prefix(source.connection_prefix().into_vec(), path);
-	let proof = source.query_proof(proof_height, vec![key]).await?;
-	let commitment_proof = CommitmentProofBytes::try_from(proof)?;
-	let msg = MsgAcknowledgement {
-		packet,
-		proofs: Proofs::new(commitment_proof, None, None, None, proof_height)?,
-		acknowledgement: ack.into(),
-		signer: sink.account_id(),
-	};
-	let value = msg.encode_vec();
-	let msg = Any { value, type_url: msg.type_url() };
-	Ok(msg)
+/// Above this many messages proven at the same height, their proofs are fetched with a single
+/// `query_proofs` call instead of one query per message.
+pub const PROOF_BATCH_THRESHOLD: usize = 4;
+
+/// A recv or acknowledgement message that is ready to be sent, but still needs to be proven.
+pub enum UnprovenMessage {
+	Recv(Packet),
+	/// The packet and its acknowledgement.
+	Ack(Packet, Vec<u8>),
+}
+
+impl UnprovenMessage {
+	/// Key of the source chain's state proven by the message.
+	fn proof_key(&self, source: &impl Chain) -> Vec<u8> {
+		let path = match self {
+			Self::Recv(packet) => get_key_path(KeyPathType::CommitmentPath, packet),
+			Self::Ack(packet, _) => get_key_path(KeyPathType::AcksPath, packet),
+		};
+		apply_prefix(source.connection_prefix().into_vec(), path)
+	}
+
+	fn into_message(
+		self,
+		sink: &impl Chain,
+		proof: Vec<u8>,
+		proof_height: Height,
+	) -> Result<Any, anyhow::Error> {
+		let commitment_proof = CommitmentProofBytes::try_from(proof)?;
+		let proofs = Proofs::new(commitment_proof, None, None, None, proof_height)?;
+		let msg = match self {
+			Self::Recv(packet) => {
+				let msg = MsgRecvPacket { packet, proofs, signer: sink.account_id() };
+				Any { value: msg.encode_vec(), type_url: msg.type_url() }
+			},
+			Self::Ack(packet, ack) => {
+				let msg = MsgAcknowledgement {
+					packet,
+					proofs,
+					acknowledgement: ack.into(),
+					signer: sink.account_id(),
+				};
+				Any { value: msg.encode_vec(), type_url: msg.type_url() }
+			},
+		};
+		Ok(msg)
+	}
+}
+
+/// Proves the messages with proofs queried from the source, in a single query for the messages
+/// proven at the same height when there are more than [`PROOF_BATCH_THRESHOLD`] of them.
+pub async fn construct_proven_messages(
+	source: &impl Chain,
+	sink: &impl Chain,
+	unproven: Vec<(UnprovenMessage, Height)>,
+) -> Result<Vec<Any>, anyhow::Error> {
+	let mut by_height = BTreeMap::<Height, Vec<usize>>::new();
+	for (index, (_, proof_height)) in unproven.iter().enumerate() {
+		by_height.entry(*proof_height).or_default().push(index);
+	}
+
+	let mut proofs = vec![vec![]; unproven.len()];
+	for (proof_height, indices) in by_height {
+		let keys =
+			indices.iter().map(|index| unproven[*index].0.proof_key(source)).collect::<Vec<_>>();
+		let batch = if keys.len() > PROOF_BATCH_THRESHOLD {
+			let expected = keys.len();
+			let batch = source.query_proofs(proof_height, keys).await?;
+			if batch.len() != expected {
+				return Err(anyhow!(
+					"Expected {expected} proofs from {}, got {}",
+					source.name(),
+					batch.len()
+				))
+			}
+			batch
+		} else {
+			let mut batch = vec![];
+			for key in keys {
+				batch.push(source.query_proof(proof_height, vec![key]).await?);
+			}
+			batch
+		};
+		for (index, proof) in indices.into_iter().zip(batch) {
+			proofs[index] = proof;
+		}
+	}
+
+	unproven
+		.into_iter()
+		.zip(proofs)
+		.map(|((msg, proof_height), proof)| msg.into_message(sink, proof, proof_height))
+		.collect()
 }
 
 pub enum KeyPathType {
@@ -0,0 +1,596 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The relayer pipelines for a single direction, from a source chain to a sink chain.
+//!
+//! Each direction runs two tasks: the client update task follows the source's finality
+//! notifications, keeps the source's client on the sink up to date and relays handshake messages,
+//! while the packet task relays packets, acknowledgements and timeouts. The client update task
+//! hands packet events over through a bounded queue and publishes the source height proven on the
+//! sink through a watch channel, so slow packet queries never hold back client updates.
+//!
+//! The packet task records how far it got with each channel in a [`RelayerStore`]. On startup,
+//! the client update task looks for packets written after the recorded heights, while the
+//! relayer was down, and makes sure the next client update gets sent so they can be relayed.
+//! A keep-alive task can likewise force the next client update before the client expires, see
+//! [`crate::keep_alive`].
+
+use crate::{
+	events::{has_packet_events, parse_events},
+	keep_alive::{keep_alive_task, KeepAliveConfig},
+	packets::{query_ready_and_timed_out_packets, ReadyPackets},
+	queue,
+	reload::SharedRuntimeConfig,
+	store::RelayerStore,
+	watchtower::watchtower_task,
+	Mode,
+};
+use futures::StreamExt;
+use ibc::{
+	core::{
+		ics02_client::client_state::ClientState as ClientStateT,
+		ics04_channel::packet::Sequence,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	events::IbcEvent,
+	Height,
+};
+use metrics::handler::MetricsHandler;
+use pallet_ibc::light_clients::AnyClientState;
+use ibc_rpc::PacketInfo;
+use primitives::{
+	error::Error, find_suitable_proof_height_for_client, query_undelivered_acks,
+	query_undelivered_sequences, Chain,
+};
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+use tokio::{
+	sync::{
+		mpsc::{self, error::TrySendError},
+		watch, Mutex,
+	},
+	task::JoinHandle,
+};
+
+/// Maximum number of packet events waiting for the packet task, before new ones are dropped.
+pub const PACKET_QUEUE_SIZE: usize = 1024;
+
+/// A packet event seen on the source chain, to be relayed by the packet task.
+#[derive(Debug, Clone)]
+pub struct PacketWork {
+	/// Channel of the packet on the source chain.
+	pub channel_id: ChannelId,
+	/// Port of the packet on the source chain.
+	pub port_id: PortId,
+	pub sequence: Sequence,
+	/// Source height of the event, the source's client on the sink has to reach it before the
+	/// packet can be proven.
+	pub height: Height,
+}
+
+impl PacketWork {
+	/// Work item for a packet event, if it's one the packet task relays.
+	pub fn from_event(event: &IbcEvent) -> Option<Self> {
+		match event {
+			IbcEvent::SendPacket(send_packet) => Some(Self {
+				channel_id: send_packet.packet.source_channel,
+				port_id: send_packet.packet.source_port.clone(),
+				sequence: send_packet.packet.sequence,
+				height: send_packet.height,
+			}),
+			// acknowledgements are written on the receiving end of the packet.
+			IbcEvent::WriteAcknowledgement(write_ack) => Some(Self {
+				channel_id: write_ack.packet.destination_channel,
+				port_id: write_ack.packet.destination_port.clone(),
+				sequence: write_ack.packet.sequence,
+				height: write_ack.height,
+			}),
+			_ => None,
+		}
+	}
+}
+
+/// Spawns the tasks relaying from `source` to `sink`, along with the keep-alive task of the
+/// source's client on the sink when `keep_alive` is given, and the watchtower task when the source
+/// has a secondary rpc endpoint. In [`Mode::Light`] packets are only relayed as their events are
+/// seen, so no packet task is spawned.
+///
+/// Both tasks only relay the packets on the channels whitelisted in `runtime_config`. The packet
+/// task relays the packets sent on the source that pass the source's filter, and the
+/// acknowledgements of the packets sent on the sink that pass the sink's.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn<A, B>(
+	source: A,
+	sink: B,
+	metrics: Option<Arc<Mutex<MetricsHandler>>>,
+	store: Arc<dyn RelayerStore>,
+	mode: Option<Mode>,
+	keep_alive: Option<KeepAliveConfig>,
+	runtime_config: SharedRuntimeConfig,
+) -> Vec<JoinHandle<Result<(), anyhow::Error>>>
+where
+	A: Chain + Clone + 'static,
+	A::FinalityEvent: Send + Sync,
+	B: Chain + Clone + 'static,
+{
+	let force_update = Arc::new(AtomicBool::new(false));
+	let mut tasks = vec![];
+	if let Some(config) = keep_alive {
+		tasks.push(tokio::spawn(keep_alive_task(
+			source.clone(),
+			sink.clone(),
+			config,
+			metrics.clone(),
+			force_update.clone(),
+		)));
+	}
+	if source.has_secondary_rpc() {
+		tasks.push(tokio::spawn(watchtower_task(source.clone(), sink.clone(), metrics.clone())));
+	}
+
+	if let Some(Mode::Light) = mode {
+		let proven_height = watch::channel(None).0;
+		let update_task = client_update_task(
+			source,
+			sink,
+			metrics,
+			None,
+			None,
+			proven_height,
+			force_update,
+			runtime_config,
+		);
+		tasks.push(tokio::spawn(update_task));
+		return tasks
+	}
+
+	let (packets, packet_queue) = mpsc::channel(PACKET_QUEUE_SIZE);
+	let (proven_height, proven_height_rx) = watch::channel(None);
+	let update_task = client_update_task(
+		source.clone(),
+		sink.clone(),
+		metrics.clone(),
+		Some(store.clone()),
+		Some(packets),
+		proven_height,
+		force_update,
+		runtime_config.clone(),
+	);
+	let packet_task =
+		packet_task(source, sink, metrics, store, runtime_config, packet_queue, proven_height_rx);
+	tasks.extend([tokio::spawn(update_task), tokio::spawn(packet_task)]);
+	tasks
+}
+
+/// Waits for new finality events on the source and submits the client update for each of them,
+/// along with messages for any non-packet events, to the sink.
+///
+/// Packet events are queued for the packet task when `packets` is given, otherwise they are relayed
+/// here. After every finality event the source height proven on the sink is published on
+/// `proven_height`, which also wakes up the packet task.
+///
+/// With a `store`, the first client update is sent even if it's optional when packets were
+/// written on the source while the relayer was down, see [`query_missed_packets`]. The same goes
+/// for the client update following `force_update` getting set by the keep-alive task. Packet
+/// events don't make an optional update mandatory when the client on the sink can already prove
+/// them, see [`query_height_proving_packets`]. Only the packets on the channels whitelisted in
+/// `runtime_config` are queued.
+#[allow(clippy::too_many_arguments)]
+pub async fn client_update_task<A, B>(
+	mut source: A,
+	mut sink: B,
+	metrics: Option<Arc<Mutex<MetricsHandler>>>,
+	store: Option<Arc<dyn RelayerStore>>,
+	packets: Option<mpsc::Sender<PacketWork>>,
+	proven_height: watch::Sender<Option<Height>>,
+	force_update: Arc<AtomicBool>,
+	runtime_config: SharedRuntimeConfig,
+) -> Result<(), anyhow::Error>
+where
+	A: Chain,
+	A::FinalityEvent: Send + Sync,
+	B: Chain,
+{
+	let mut missed_packets = match store {
+		Some(store) => {
+			let whitelist = runtime_config.current().0.channel_whitelist(&source);
+			let missed = query_missed_packets(&source, &sink, &*store, whitelist).await?;
+			if !missed.is_empty() {
+				let seqs = missed.iter().map(|packet| packet.sequence).collect::<Vec<_>>();
+				log::info!(
+					"Found packets written on {} while the relayer was down: {seqs:?}",
+					source.name()
+				);
+			}
+			!missed.is_empty()
+		},
+		None => false,
+	};
+	let mut finality_notifications = source.finality_notifications().await;
+	let mut latest_proven_height = None;
+	while let Some(finality_event) = finality_notifications.next().await {
+		log::info!("=======================================================");
+		log::info!("Received finality notification from {}", source.name());
+		let (msg_update_client, events, update_type) =
+			match source.query_latest_ibc_events(finality_event, &sink).await {
+				Ok(resp) => resp,
+				Err(err) => {
+					log::error!(
+						"Failed to fetch IBC events for finality event for {} {:?}",
+						source.name(),
+						err
+					);
+					continue
+				},
+			};
+		if let Some(metrics) = metrics.as_ref() {
+			let mut metrics = metrics.lock().await;
+			metrics.handle_finality_event();
+			metrics.handle_subscription_reconnects(source.subscription_reconnects());
+			match source.latest_height_and_timestamp().await {
+				Ok((height, _)) => metrics.handle_finalized_height(height),
+				Err(e) => log::error!("Failed to query latest height of {} {:?}", source.name(), e),
+			}
+			if let Err(e) = metrics.handle_events(events.as_slice()).await {
+				log::error!("Failed to handle metrics for {} {:?}", source.name(), e);
+			}
+		}
+		let event_types = events.iter().map(|ev| ev.event_type()).collect::<Vec<_>>();
+		let (packet_work, events) = match packets {
+			Some(_) => {
+				let whitelist = runtime_config.current().0.channel_whitelist(&source);
+				let (packet_events, events): (Vec<_>, Vec<_>) =
+					events.into_iter().partition(|ev| PacketWork::from_event(ev).is_some());
+				let packet_work = packet_events
+					.iter()
+					.filter_map(PacketWork::from_event)
+					.filter(|work| whitelist.contains(&(work.channel_id, work.port_id.clone())))
+					.collect::<Vec<_>>();
+				(packet_work, events)
+			},
+			None => (vec![], events),
+		};
+		let mut messages = parse_events(&mut source, &mut sink, events).await?;
+		let packets_proven = match packets {
+			Some(_) if has_packet_events(&event_types) =>
+				query_height_proving_packets(&source, &sink, &packet_work).await,
+			_ => None,
+		};
+		if let Some(height) = packets_proven {
+			log::info!(
+				"Client of {} on {} at {height} already proves the packet events",
+				source.name(),
+				sink.name()
+			);
+			latest_proven_height = Some(height);
+		}
+
+		// We want to send client update if packet events exist or packets were missed while the
+		// relayer was down, so their packets can be proven on the sink, or if the client is about
+		// to expire, even if client update message is optional
+		let forced = force_update.load(Ordering::SeqCst);
+		let is_optional = update_type.is_optional() && !missed_packets && !forced;
+		let packet_events = has_packet_events(&event_types) && packets_proven.is_none();
+		match (is_optional, packet_events, messages.is_empty()) {
+			(true, false, true) => {
+				// skip sending ibc messages if no new events
+				log::info!("Skipping finality notification for {}, No new events", source.name());
+				queue_packet_work(packets.as_ref(), packet_work, source.name())?;
+				// packets waiting on a connection delay or a timeout might be ready by now.
+				proven_height.send_replace(latest_proven_height);
+				continue
+			},
+			(false, _, true) =>
+				log::info!("Sending mandatory client update message for {}", source.name()),
+			_ => log::info!("Received finalized events from: {} {event_types:#?}", source.name()),
+		};
+		// insert client update at first position.
+		messages.insert(0, msg_update_client);
+		if let Some(metrics) = metrics.as_ref() {
+			metrics.lock().await.handle_messages(messages.as_slice()).await;
+		}
+		let type_urls = messages.iter().map(|msg| msg.type_url.as_str()).collect::<Vec<_>>();
+		log::info!("Submitting messages to {}: {type_urls:#?}", sink.name());
+		queue::flush_message_batch(messages, metrics.as_deref(), &sink, &source).await?;
+		missed_packets = false;
+		if forced {
+			force_update.store(false, Ordering::SeqCst);
+		}
+
+		match query_proven_height(&source, &sink).await {
+			Ok(height) => {
+				if let Some(metrics) = metrics.as_ref() {
+					metrics.lock().await.handle_counterparty_client_height(height);
+				}
+				latest_proven_height = Some(height)
+			},
+			Err(e) => log::error!(
+				"Failed to query the client state of {} on {} {:?}",
+				source.name(),
+				sink.name(),
+				e
+			),
+		}
+		proven_height.send_replace(latest_proven_height);
+		queue_packet_work(packets.as_ref(), packet_work, source.name())?;
+	}
+
+	Ok(())
+}
+
+/// Hands the packet events over to the packet task, if there is one.
+fn queue_packet_work(
+	packets: Option<&mpsc::Sender<PacketWork>>,
+	packet_work: Vec<PacketWork>,
+	source_name: &str,
+) -> Result<(), anyhow::Error> {
+	let packets = match packets {
+		Some(packets) => packets,
+		None => return Ok(()),
+	};
+	for work in packet_work {
+		match packets.try_send(work) {
+			Ok(()) => {},
+			// the packet task sweeps every channel on the next finality event anyway.
+			Err(TrySendError::Full(work)) => log::warn!(
+				"Packet queue for {source_name} is full, deferring {work:?} to the next sweep"
+			),
+			Err(TrySendError::Closed(_)) =>
+				Err(Error::Custom(format!("Packet task for {source_name} stopped")))?,
+		}
+	}
+	Ok(())
+}
+
+/// Latest height of the source's client on the sink, if the client already has a consensus state
+/// at or above the height of every event in `packet_work`, so that the packet task can prove them
+/// without another client update.
+///
+/// The consensus states at the event heights may have been pruned, in which case the next one up
+/// is used, like the packet task does. Packets behind a connection delay wait for it to pass from
+/// the update of that consensus state. Returns `None` for an event of another revision or when a
+/// query fails, so the client update is sent as before.
+pub async fn query_height_proving_packets(
+	source: &impl Chain,
+	sink: &impl Chain,
+	packet_work: &[PacketWork],
+) -> Option<Height> {
+	if packet_work.is_empty() {
+		return None
+	}
+	let (sink_height, ..) = sink.latest_height_and_timestamp().await.ok()?;
+	let client_state = query_sink_client_state(source, sink, sink_height).await.ok()?;
+	let client_height = client_state.latest_height();
+	for work in packet_work {
+		if work.height.revision_number != client_height.revision_number ||
+			work.height > client_height
+		{
+			return None
+		}
+		find_suitable_proof_height_for_client(
+			sink,
+			sink_height,
+			source.client_id(),
+			work.height,
+			None,
+			client_height,
+		)
+		.await?;
+	}
+	Some(client_height)
+}
+
+/// Relays the packets, acknowledgements and timeouts of the channels whitelisted in
+/// `runtime_config`.
+///
+/// Channels with queued packet events are processed as soon as `proven_height` shows the source's
+/// client on the sink can prove the events. Every channel is swept whenever the client update task
+/// marks `proven_height` as changed, picking up packets whose connection delay has passed, timed
+/// out packets and events that didn't fit in the queue, and whenever `runtime_config` changes.
+/// Packets and acknowledgements are filtered with the source's and the sink's filter
+/// respectively.
+pub async fn packet_task<A, B>(
+	source: A,
+	sink: B,
+	metrics: Option<Arc<Mutex<MetricsHandler>>>,
+	store: Arc<dyn RelayerStore>,
+	mut runtime_config: SharedRuntimeConfig,
+	mut packet_queue: mpsc::Receiver<PacketWork>,
+	mut proven_height: watch::Receiver<Option<Height>>,
+) -> Result<(), anyhow::Error>
+where
+	A: Chain,
+	B: Chain,
+{
+	let mut pending = vec![];
+	let mut reloadable = true;
+	loop {
+		let sweep = tokio::select! {
+			work = packet_queue.recv() => match work {
+				Some(work) => {
+					pending.push(work);
+					false
+				},
+				// client update task stopped
+				None => break,
+			},
+			changed = proven_height.changed() => match changed {
+				Ok(()) => true,
+				Err(_) => break,
+			},
+			changed = runtime_config.changed(), if reloadable => {
+				reloadable = changed;
+				changed
+			},
+		};
+		while let Ok(work) = packet_queue.try_recv() {
+			pending.push(work)
+		}
+
+		let (source_config, sink_config) = runtime_config.current();
+		let whitelist = source_config.channel_whitelist(&source);
+		let channels = if sweep {
+			pending.clear();
+			whitelist
+		} else {
+			let proven_height = *proven_height.borrow();
+			let is_proven = |work: &PacketWork| {
+				proven_height
+					.map_or(false, |height| height.revision_height >= work.height.revision_height)
+			};
+			let mut channels = vec![];
+			for work in pending.iter().filter(|work| is_proven(work)) {
+				let channel = (work.channel_id, work.port_id.clone());
+				// channels removed from the whitelist since the work was queued are skipped
+				if whitelist.contains(&channel) && !channels.contains(&channel) {
+					channels.push(channel)
+				}
+			}
+			pending.retain(|work| !is_proven(work));
+			channels
+		};
+		if channels.is_empty() {
+			continue
+		}
+
+		let (source_height, ..) = source.latest_height_and_timestamp().await?;
+		let ReadyPackets { messages, timeouts, filtered, undelivered } =
+			query_ready_and_timed_out_packets(
+				&source,
+				&sink,
+				channels.clone(),
+				&source_config.filter,
+				&sink_config.filter,
+			)
+			.await?;
+		if let Some(metrics) = metrics.as_ref() {
+			let metrics = metrics.lock().await;
+			for ((channel_id, port_id), count) in undelivered {
+				metrics.handle_undelivered_packets(&channel_id, &port_id, count);
+			}
+		}
+		if filtered > 0 {
+			log::debug!("Skipped {filtered} packets from {} by filter", source.name());
+			if let Some(metrics) = metrics.as_ref() {
+				metrics.lock().await.handle_filtered_packets(filtered);
+			}
+		}
+		if !timeouts.is_empty() {
+			if let Some(metrics) = metrics.as_ref() {
+				metrics.lock().await.handle_timeouts(timeouts.as_slice()).await;
+			}
+			let type_urls = timeouts.iter().map(|msg| msg.type_url.as_str()).collect::<Vec<_>>();
+			log::info!("Submitting timeout messages to {}: {type_urls:#?}", source.name());
+			queue::flush_message_batch(timeouts, metrics.as_deref(), &source, &sink).await?;
+		}
+		if !messages.is_empty() {
+			if let Some(metrics) = metrics.as_ref() {
+				metrics.lock().await.handle_messages(messages.as_slice()).await;
+			}
+			let type_urls = messages.iter().map(|msg| msg.type_url.as_str()).collect::<Vec<_>>();
+			log::info!("Submitting packet messages to {}: {type_urls:#?}", sink.name());
+			queue::flush_message_batch(messages, metrics.as_deref(), &sink, &source).await?;
+		}
+		// everything written on these channels up to `source_height` has been looked at, whatever
+		// is left will be found again by the next sweep.
+		for (channel_id, port_id) in channels {
+			store.set_processed_height(
+				source.name(),
+				channel_id,
+				&port_id,
+				source_height.revision_height,
+			)?;
+		}
+	}
+
+	Ok(())
+}
+
+/// Returns the undelivered packets and acknowledgements written on the source's `channels` after
+/// the height recorded for them in `store`.
+pub async fn query_missed_packets(
+	source: &impl Chain,
+	sink: &impl Chain,
+	store: &dyn RelayerStore,
+	channels: Vec<(ChannelId, PortId)>,
+) -> Result<Vec<PacketInfo>, anyhow::Error> {
+	let (source_height, ..) = source.latest_height_and_timestamp().await?;
+	let (sink_height, ..) = sink.latest_height_and_timestamp().await?;
+	let mut missed = vec![];
+	for (channel_id, port_id) in channels {
+		let processed_height = match store.processed_height(source.name(), channel_id, &port_id)? {
+			Some(height) if height < source_height.revision_height => height,
+			_ => continue,
+		};
+		let seqs = query_undelivered_sequences(
+			source_height,
+			sink_height,
+			channel_id,
+			port_id.clone(),
+			source,
+			sink,
+		)
+		.await?;
+		let send_packets = source.query_send_packets(channel_id, port_id.clone(), seqs).await?;
+		let acks = query_undelivered_acks(
+			source_height,
+			sink_height,
+			channel_id,
+			port_id.clone(),
+			source,
+			sink,
+		)
+		.await?;
+		let recv_packets = source.query_recv_packets(channel_id, port_id, acks).await?;
+		missed.extend(
+			send_packets
+				.into_iter()
+				.chain(recv_packets)
+				.filter(|packet| packet.height > processed_height),
+		);
+	}
+	Ok(missed)
+}
+
+/// Latest height of the source's client on the sink.
+pub(crate) async fn query_proven_height(
+	source: &impl Chain,
+	sink: &impl Chain,
+) -> Result<Height, anyhow::Error> {
+	let (sink_height, ..) = sink.latest_height_and_timestamp().await?;
+	let client_state = query_sink_client_state(source, sink, sink_height).await?;
+	Ok(client_state.latest_height())
+}
+
+/// Client state of the source's client on the sink, at sink height `at`.
+pub(crate) async fn query_sink_client_state(
+	source: &impl Chain,
+	sink: &impl Chain,
+	at: Height,
+) -> Result<AnyClientState, anyhow::Error> {
+	let response = sink.query_client_state(at, source.client_id()).await?;
+	let client_state = AnyClientState::try_from(response.client_state.ok_or_else(|| {
+		Error::Custom(format!("Client state for {} should exist on {}", source.name(), sink.name()))
+	})?)
+	.map_err(|_| {
+		Error::Custom(format!(
+			"Invalid Client state for {} found on {}",
+			source.name(),
+			sink.name()
+		))
+	})?;
+	Ok(client_state)
+}
@@ -0,0 +1,469 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Read-only queries behind the `query` command, rendered as tables or json.
+
+use ibc::{
+	core::{
+		ics02_client::client_state::ClientState as ClientStateT,
+		ics03_connection::connection::IdentifiedConnectionEnd,
+		ics04_channel::{channel::ChannelEnd, packet::Packet},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	timestamp::Timestamp,
+	Height,
+};
+use pallet_ibc::light_clients::AnyClientState;
+use primitives::{
+	error::Error, packet_info_to_packet, query_undelivered_acks, query_undelivered_sequences,
+	Chain,
+};
+use serde::Serialize;
+use std::time::Duration;
+
+/// Packets timing out on the counterparty within this duration are reported as near timeout.
+pub const NEAR_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// A query result that can be rendered as a table row.
+pub trait TableRow {
+	/// Column titles.
+	const HEADER: &'static [&'static str];
+
+	/// Cells of this row, in the order of [`Self::HEADER`].
+	fn cells(&self) -> Vec<String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClientRow {
+	pub client_id: String,
+	pub client_type: String,
+	pub latest_height: String,
+}
+
+impl TableRow for ClientRow {
+	const HEADER: &'static [&'static str] = &["CLIENT", "TYPE", "LATEST HEIGHT"];
+
+	fn cells(&self) -> Vec<String> {
+		vec![self.client_id.clone(), self.client_type.clone(), self.latest_height.clone()]
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConnectionRow {
+	pub connection_id: String,
+	pub client_id: String,
+	pub state: String,
+	pub counterparty_client_id: String,
+	pub counterparty_connection_id: Option<String>,
+	pub delay_period: u64,
+}
+
+impl TableRow for ConnectionRow {
+	const HEADER: &'static [&'static str] = &[
+		"CONNECTION",
+		"CLIENT",
+		"STATE",
+		"COUNTERPARTY CLIENT",
+		"COUNTERPARTY CONNECTION",
+		"DELAY (S)",
+	];
+
+	fn cells(&self) -> Vec<String> {
+		vec![
+			self.connection_id.clone(),
+			self.client_id.clone(),
+			self.state.clone(),
+			self.counterparty_client_id.clone(),
+			self.counterparty_connection_id.clone().unwrap_or_else(|| "-".to_string()),
+			self.delay_period.to_string(),
+		]
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChannelRow {
+	pub port_id: String,
+	pub channel_id: String,
+	pub state: String,
+	pub ordering: String,
+	pub connection_id: String,
+	pub counterparty_port_id: String,
+	pub counterparty_channel_id: Option<String>,
+}
+
+impl TableRow for ChannelRow {
+	const HEADER: &'static [&'static str] = &[
+		"PORT",
+		"CHANNEL",
+		"STATE",
+		"ORDER",
+		"CONNECTION",
+		"COUNTERPARTY PORT",
+		"COUNTERPARTY CHANNEL",
+	];
+
+	fn cells(&self) -> Vec<String> {
+		vec![
+			self.port_id.clone(),
+			self.channel_id.clone(),
+			self.state.clone(),
+			self.ordering.clone(),
+			self.connection_id.clone(),
+			self.counterparty_port_id.clone(),
+			self.counterparty_channel_id.clone().unwrap_or_else(|| "-".to_string()),
+		]
+	}
+}
+
+/// How close a packet is to timing out on the counterparty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeoutStatus {
+	TimedOut,
+	NearTimeout,
+	Pending,
+}
+
+impl TimeoutStatus {
+	/// Status of `packet` given the counterparty's latest height and timestamp, with the time
+	/// left until a height timeout estimated from the counterparty's `block_time`.
+	pub fn of(
+		packet: &Packet,
+		counterparty_height: Height,
+		counterparty_timestamp: Timestamp,
+		block_time: Duration,
+	) -> Self {
+		if packet.timed_out(&counterparty_timestamp, counterparty_height) {
+			return Self::TimedOut
+		}
+		let by_height = (!packet.timeout_height.is_zero()).then(|| {
+			let blocks = packet
+				.timeout_height
+				.revision_height
+				.saturating_sub(counterparty_height.revision_height);
+			block_time.saturating_mul(blocks.try_into().unwrap_or(u32::MAX))
+		});
+		let by_timestamp = (packet.timeout_timestamp != Timestamp::none()).then(|| {
+			Duration::from_nanos(
+				packet
+					.timeout_timestamp
+					.nanoseconds()
+					.saturating_sub(counterparty_timestamp.nanoseconds()),
+			)
+		});
+		match by_height.into_iter().chain(by_timestamp).min() {
+			Some(left) if left < NEAR_TIMEOUT => Self::NearTimeout,
+			_ => Self::Pending,
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PacketRow {
+	pub sequence: u64,
+	pub timeout_height: String,
+	pub timeout_timestamp: String,
+	pub status: TimeoutStatus,
+}
+
+impl TableRow for PacketRow {
+	const HEADER: &'static [&'static str] =
+		&["SEQUENCE", "TIMEOUT HEIGHT", "TIMEOUT TIMESTAMP", "STATUS"];
+
+	fn cells(&self) -> Vec<String> {
+		let status = match self.status {
+			TimeoutStatus::TimedOut => "timed out",
+			TimeoutStatus::NearTimeout => "near timeout",
+			TimeoutStatus::Pending => "pending",
+		};
+		vec![
+			self.sequence.to_string(),
+			self.timeout_height.clone(),
+			self.timeout_timestamp.clone(),
+			status.to_string(),
+		]
+	}
+}
+
+/// Packets of a channel still waiting on the relayer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PendingPackets {
+	/// Packets sent on the channel that the counterparty hasn't received.
+	pub undelivered: Vec<PacketRow>,
+	/// Sequences of packets received on the channel whose acknowledgements the counterparty
+	/// hasn't received.
+	pub unreceived_acks: Vec<u64>,
+}
+
+impl PendingPackets {
+	pub fn render(&self, json: bool) -> Result<String, anyhow::Error> {
+		if json {
+			return Ok(serde_json::to_string_pretty(self)?)
+		}
+		let acks = self.unreceived_acks.iter().map(|seq| seq.to_string()).collect::<Vec<_>>();
+		Ok(format!(
+			"Undelivered packets:\n{}\nUnreceived acknowledgements: {}",
+			format_table(PacketRow::HEADER, self.undelivered.iter().map(TableRow::cells)),
+			if acks.is_empty() { "-".to_string() } else { acks.join(", ") }
+		))
+	}
+}
+
+/// Renders `rows` as json or as a table.
+pub fn render<T: TableRow + Serialize>(rows: &[T], json: bool) -> Result<String, anyhow::Error> {
+	if json {
+		return Ok(serde_json::to_string_pretty(rows)?)
+	}
+	Ok(format_table(T::HEADER, rows.iter().map(TableRow::cells)))
+}
+
+/// Lays out `rows` under `header`, padding every column to its widest cell.
+pub fn format_table(header: &[&str], rows: impl IntoIterator<Item = Vec<String>>) -> String {
+	let header = header.iter().map(|title| title.to_string()).collect::<Vec<_>>();
+	let rows = [header].into_iter().chain(rows).collect::<Vec<_>>();
+	let mut widths = vec![0; rows[0].len()];
+	for row in &rows {
+		for (width, cell) in widths.iter_mut().zip(row) {
+			*width = (*width).max(cell.chars().count());
+		}
+	}
+	let lines = rows
+		.iter()
+		.map(|row| {
+			let cells = row
+				.iter()
+				.zip(&widths)
+				.map(|(cell, width)| format!("{cell:<width$}"))
+				.collect::<Vec<_>>();
+			cells.join("  ").trim_end().to_string()
+		})
+		.collect::<Vec<_>>();
+	lines.join("\n")
+}
+
+pub async fn query_clients(chain: &impl Chain) -> Result<Vec<ClientRow>, anyhow::Error> {
+	let (height, _) = chain.latest_height_and_timestamp().await?;
+	let mut rows = vec![];
+	for client_id in chain.query_clients().await? {
+		let response = chain.query_client_state(height, client_id.clone()).await?;
+		let client_state =
+			response.client_state.map(AnyClientState::try_from).transpose().map_err(|_| {
+				Error::Custom(format!("Invalid client state for {client_id} on {}", chain.name()))
+			})?;
+		let (client_type, latest_height) = match client_state {
+			Some(client_state) =>
+				(client_state.client_type(), client_state.latest_height().to_string()),
+			None => ("-".to_string(), "-".to_string()),
+		};
+		rows.push(ClientRow { client_id: client_id.to_string(), client_type, latest_height });
+	}
+	Ok(rows)
+}
+
+pub async fn query_connections(chain: &impl Chain) -> Result<Vec<ConnectionRow>, anyhow::Error> {
+	chain
+		.query_connections()
+		.await?
+		.into_iter()
+		.map(|connection| {
+			let connection = IdentifiedConnectionEnd::try_from(connection)?;
+			let end = connection.end();
+			Ok(ConnectionRow {
+				connection_id: connection.id().to_string(),
+				client_id: end.client_id().to_string(),
+				state: end.state().to_string(),
+				counterparty_client_id: end.counterparty().client_id().to_string(),
+				counterparty_connection_id: end
+					.counterparty()
+					.connection_id()
+					.map(ToString::to_string),
+				delay_period: end.delay_period().as_secs(),
+			})
+		})
+		.collect()
+}
+
+pub async fn query_channels(chain: &impl Chain) -> Result<Vec<ChannelRow>, anyhow::Error> {
+	let (height, _) = chain.latest_height_and_timestamp().await?;
+	let mut rows = vec![];
+	for (channel_id, port_id) in chain.query_channels().await? {
+		let response = chain.query_channel_end(height, channel_id, port_id.clone()).await?;
+		let channel_end = ChannelEnd::try_from(response.channel.ok_or_else(|| {
+			Error::Custom(format!("ChannelEnd not found for {port_id}/{channel_id}"))
+		})?)?;
+		rows.push(ChannelRow {
+			port_id: port_id.to_string(),
+			channel_id: channel_id.to_string(),
+			state: channel_end.state.to_string(),
+			ordering: channel_end.ordering.to_string(),
+			connection_id: channel_end
+				.connection_hops
+				.iter()
+				.map(ToString::to_string)
+				.collect::<Vec<_>>()
+				.join(","),
+			counterparty_port_id: channel_end.counterparty().port_id().to_string(),
+			counterparty_channel_id: channel_end
+				.counterparty()
+				.channel_id()
+				.map(ToString::to_string),
+		});
+	}
+	Ok(rows)
+}
+
+/// Queries the packets sent on the given channel of `chain` that `counterparty` hasn't received,
+/// and the acknowledgements written on it that `counterparty` hasn't received.
+pub async fn query_pending_packets(
+	chain: &impl Chain,
+	counterparty: &impl Chain,
+	channel_id: ChannelId,
+	port_id: PortId,
+) -> Result<PendingPackets, anyhow::Error> {
+	let (height, _) = chain.latest_height_and_timestamp().await?;
+	let (counterparty_height, counterparty_timestamp) =
+		counterparty.latest_height_and_timestamp().await?;
+	let seqs = query_undelivered_sequences(
+		height,
+		counterparty_height,
+		channel_id,
+		port_id.clone(),
+		chain,
+		counterparty,
+	)
+	.await?;
+	let unreceived_acks = query_undelivered_acks(
+		height,
+		counterparty_height,
+		channel_id,
+		port_id.clone(),
+		chain,
+		counterparty,
+	)
+	.await?;
+
+	let mut undelivered = chain
+		.query_send_packets(channel_id, port_id, seqs)
+		.await?
+		.iter()
+		.map(|packet_info| {
+			let packet = packet_info_to_packet(packet_info);
+			let status = TimeoutStatus::of(
+				&packet,
+				counterparty_height,
+				counterparty_timestamp,
+				counterparty.expected_block_time(),
+			);
+			PacketRow {
+				sequence: packet_info.sequence,
+				timeout_height: packet.timeout_height.to_string(),
+				timeout_timestamp: packet.timeout_timestamp.to_string(),
+				status,
+			}
+		})
+		.collect::<Vec<_>>();
+	undelivered.sort_by_key(|packet| packet.sequence);
+	Ok(PendingPackets { undelivered, unreceived_acks })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn packet(timeout_height: u64, timeout_timestamp: u64) -> Packet {
+		Packet {
+			sequence: 1.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(0),
+			data: vec![],
+			timeout_height: Height::new(0, timeout_height),
+			timeout_timestamp: Timestamp::from_nanoseconds(timeout_timestamp).unwrap(),
+		}
+	}
+
+	#[test]
+	fn table_columns_are_padded_to_widest_cell() {
+		let rows = vec![
+			ClientRow {
+				client_id: "10-grandpa-0".to_string(),
+				client_type: "10-grandpa".to_string(),
+				latest_height: "2000-120".to_string(),
+			},
+			ClientRow {
+				client_id: "11-beefy-12".to_string(),
+				client_type: "11-beefy".to_string(),
+				latest_height: "0-7".to_string(),
+			},
+		];
+		let table = render(&rows, false).unwrap();
+		let expected = [
+			"CLIENT        TYPE        LATEST HEIGHT",
+			"10-grandpa-0  10-grandpa  2000-120",
+			"11-beefy-12   11-beefy    0-7",
+		]
+		.join("\n");
+		assert_eq!(table, expected);
+	}
+
+	#[test]
+	fn empty_table_renders_header() {
+		assert_eq!(render::<ChannelRow>(&[], false).unwrap(), ChannelRow::HEADER.join("  "));
+	}
+
+	#[test]
+	fn renders_json() {
+		let pending = PendingPackets {
+			undelivered: vec![PacketRow {
+				sequence: 4,
+				timeout_height: "0-100".to_string(),
+				timeout_timestamp: "no timestamp".to_string(),
+				status: TimeoutStatus::NearTimeout,
+			}],
+			unreceived_acks: vec![2, 3],
+		};
+		let json: serde_json::Value =
+			serde_json::from_str(&pending.render(true).unwrap()).unwrap();
+		assert_eq!(json["undelivered"][0]["sequence"], 4);
+		assert_eq!(json["undelivered"][0]["status"], "near_timeout");
+		assert_eq!(json["unreceived_acks"], serde_json::json!([2, 3]));
+
+		let table = pending.render(false).unwrap();
+		assert!(table.contains("4         0-100           no timestamp       near timeout"));
+		assert!(table.ends_with("Unreceived acknowledgements: 2, 3"));
+	}
+
+	#[test]
+	fn timeout_status_compares_against_counterparty() {
+		let block_time = Duration::from_secs(6);
+		let now = Timestamp::from_nanoseconds(1_000_000_000_000).unwrap();
+		let status = |packet: &Packet, height| {
+			TimeoutStatus::of(packet, Height::new(0, height), now, block_time)
+		};
+
+		assert_eq!(status(&packet(100, 0), 100), TimeoutStatus::TimedOut);
+		// 50 blocks of 6 seconds are left
+		assert_eq!(status(&packet(150, 0), 100), TimeoutStatus::NearTimeout);
+		assert_eq!(status(&packet(1000, 0), 100), TimeoutStatus::Pending);
+
+		let in_a_minute = now.nanoseconds() + 60 * 1_000_000_000;
+		assert_eq!(status(&packet(0, in_a_minute), 100), TimeoutStatus::NearTimeout);
+		assert_eq!(status(&packet(0, now.nanoseconds()), 100), TimeoutStatus::TimedOut);
+		// the closest of both timeouts counts
+		assert_eq!(status(&packet(1000, in_a_minute), 100), TimeoutStatus::NearTimeout);
+		assert_eq!(status(&packet(0, 0), 100), TimeoutStatus::Pending);
+	}
+}
@@ -12,47 +12,118 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ibc_proto::google::protobuf::Any;
+use crate::retry::{submit_with_retry, ChainSubmitter, RetryPolicy};
+use ibc::core::ics02_client::msgs::{update_client, update_client_batch};
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::client::v1::{
+		MsgUpdateClient as RawMsgUpdateClient, MsgUpdateClientBatch as RawMsgUpdateClientBatch,
+	},
+};
 use metrics::handler::MetricsHandler;
 use primitives::Chain;
+use prost::Message;
+use tokio::sync::Mutex;
 
-/// This sends messages to the sink chain in a gas-aware manner.
+/// This sends messages to the sink chain in a gas-aware manner, resubmitting the batches that
+/// failed retriably with proofs refreshed from the source chain.
 pub async fn flush_message_batch(
 	msgs: Vec<Any>,
-	metrics: Option<&MetricsHandler>,
+	metrics: Option<&Mutex<MetricsHandler>>,
 	sink: &impl Chain,
+	source: &impl Chain,
 ) -> Result<(), anyhow::Error> {
+	let msgs = match sink.supports_client_update_batches() {
+		true => batch_client_updates(msgs),
+		false => msgs,
+	};
 	let block_max_weight = sink.block_max_weight();
 	let batch_weight = sink.estimate_weight(msgs.clone()).await?;
 
 	if let Some(metrics) = metrics {
-		metrics.handle_transaction_costs(batch_weight, &msgs).await;
+		metrics.lock().await.handle_transaction_costs(batch_weight, &msgs).await;
 	}
 
+	let total = msgs.len() as u64;
 	let ratio = (batch_weight / block_max_weight) as usize;
-	if ratio == 0 {
-		sink.submit(msgs).await?;
-		return Ok(())
-	}
-
-	// whelp our batch exceeds the block max weight.
-	let chunk = if ratio == 1 {
-		// split the batch into ratio * 2
-		ratio * 2
+	let batches = if ratio == 0 {
+		vec![msgs]
 	} else {
-		// split the batch into ratio + 2
-		ratio + 2
+		// whelp our batch exceeds the block max weight.
+		let chunk = if ratio == 1 {
+			// split the batch into ratio * 2
+			ratio * 2
+		} else {
+			// split the batch into ratio + 2
+			ratio + 2
+		};
+
+		log::info!(
+			"Outgoing messages weight: {} exceeds the block max weight: {}. Chunking {} messages into {} chunks",
+			batch_weight, block_max_weight, msgs.len(), chunk,
+		);
+		msgs.chunks(chunk).map(|batch| batch.to_vec()).collect()
 	};
 
-	log::info!(
-		"Outgoing messages weight: {} exceeds the block max weight: {}. Chunking {} messages into {} chunks",
-        batch_weight, block_max_weight, msgs.len(), chunk,
-	);
-	// TODO: return number of failed messages and record it to metrics
-	for batch in msgs.chunks(chunk) {
-		// send out batches.
-		sink.submit(batch.to_vec()).await?;
+	let submitter = ChainSubmitter { sink, source };
+	let dropped = submit_with_retry(&submitter, batches, &RetryPolicy::default()).await?;
+	if let Some(metrics) = metrics {
+		let metrics = metrics.lock().await;
+		if dropped > 0 {
+			metrics.handle_dropped_messages(dropped);
+		}
+		if dropped < total {
+			metrics.handle_successful_submission();
+		}
 	}
 
 	Ok(())
 }
+
+/// Merges consecutive `MsgUpdateClient`s for the same client into a single
+/// `MsgUpdateClientBatch`, so that catching up on several pending headers costs one message.
+pub fn batch_client_updates(msgs: Vec<Any>) -> Vec<Any> {
+	let mut batched = Vec::with_capacity(msgs.len());
+	let mut pending: Option<RawMsgUpdateClientBatch> = None;
+	for msg in msgs {
+		let update = if msg.type_url == update_client::TYPE_URL {
+			RawMsgUpdateClient::decode(msg.value.as_slice()).ok()
+		} else {
+			None
+		};
+		match update {
+			Some(RawMsgUpdateClient { client_id, client_message: Some(client_message), signer }) =>
+				match pending.as_mut() {
+					Some(batch) if batch.client_id == client_id && batch.signer == signer =>
+						batch.client_messages.push(client_message),
+					_ => {
+						batched.extend(pending.take().map(encode_client_updates));
+						pending = Some(RawMsgUpdateClientBatch {
+							client_id,
+							client_messages: vec![client_message],
+							signer,
+						});
+					},
+				},
+			_ => {
+				batched.extend(pending.take().map(encode_client_updates));
+				batched.push(msg);
+			},
+		}
+	}
+	batched.extend(pending.take().map(encode_client_updates));
+	batched
+}
+
+fn encode_client_updates(mut batch: RawMsgUpdateClientBatch) -> Any {
+	// A lone header is sent as a plain `MsgUpdateClient`.
+	if batch.client_messages.len() == 1 {
+		let msg = RawMsgUpdateClient {
+			client_id: batch.client_id,
+			client_message: batch.client_messages.pop(),
+			signer: batch.signer,
+		};
+		return Any { type_url: update_client::TYPE_URL.to_string(), value: msg.encode_to_vec() }
+	}
+	Any { type_url: update_client_batch::TYPE_URL.to_string(), value: batch.encode_to_vec() }
+}
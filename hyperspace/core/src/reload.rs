@@ -0,0 +1,452 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applies changes to the config file while the relayer runs.
+//!
+//! The config watcher task checks the config file for changes and re-reads the settings that can
+//! change without a restart, the channel whitelists and packet filters of both chains, see
+//! [`RuntimeConfig`]. They're validated as a whole and handed to the pipelines through a watch
+//! channel, so a pipeline always sees either the old settings or the new ones. Packet tasks sweep
+//! their channels when the settings change, so newly whitelisted channels are relayed right away.
+//!
+//! Everything else, the chains' endpoints, keys, client and connection ids as well as the `core`
+//! section, is only read on startup. Changes to those are logged and otherwise ignored.
+
+use crate::{
+	chain::{AnyConfig, Config},
+	filter::{ChainFilter, PacketFilter},
+};
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use primitives::Chain;
+use std::{
+	path::{Path, PathBuf},
+	time::{Duration, SystemTime},
+};
+use tokio::sync::watch;
+
+/// Default time between two checks of the config file, in seconds.
+pub const DEFAULT_RELOAD_INTERVAL: u64 = 10;
+
+/// Settings of one chain that can change while the relayer runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainRuntimeConfig {
+	/// Channels cleared for packet relay, the chain client's own whitelist when unset.
+	pub channel_whitelist: Option<Vec<(ChannelId, PortId)>>,
+	/// Filter for the packets sent on the chain.
+	pub filter: ChainFilter,
+}
+
+impl ChainRuntimeConfig {
+	/// Channels of `chain` whose packets are relayed.
+	pub fn channel_whitelist(&self, chain: &impl Chain) -> Vec<(ChannelId, PortId)> {
+		match self.channel_whitelist.as_ref() {
+			Some(whitelist) => whitelist.clone(),
+			None => chain.channel_whitelist(),
+		}
+	}
+
+	fn validate(&self, chain: &str) -> Result<(), anyhow::Error> {
+		let whitelist = self.channel_whitelist.as_deref().unwrap_or_default();
+		for (i, channel) in whitelist.iter().enumerate() {
+			if whitelist[..i].contains(channel) {
+				anyhow::bail!("{}/{} is whitelisted twice on {chain}", channel.1, channel.0)
+			}
+		}
+		let rules = &self.filter.channels;
+		for (i, rule) in rules.iter().enumerate() {
+			let duplicate = rules[..i]
+				.iter()
+				.any(|other| other.channel_id == rule.channel_id && other.port_id == rule.port_id);
+			if duplicate {
+				anyhow::bail!(
+					"{}/{} has more than one filter rule on {chain}",
+					rule.port_id,
+					rule.channel_id
+				)
+			}
+		}
+		if let Some(min_fee) = self.filter.min_fee.as_ref() {
+			if min_fee.denom.is_empty() {
+				anyhow::bail!("The minimum fee on {chain} has no denomination")
+			}
+		}
+		Ok(())
+	}
+
+	fn changes(&self, new: &Self, chain: &str, changes: &mut Vec<String>) {
+		let old_whitelist = self.channel_whitelist.as_deref().unwrap_or_default();
+		let new_whitelist = new.channel_whitelist.as_deref().unwrap_or_default();
+		for (channel_id, port_id) in new_whitelist.iter().filter(|c| !old_whitelist.contains(c)) {
+			changes.push(format!("whitelisted {port_id}/{channel_id} on {chain}"))
+		}
+		for (channel_id, port_id) in old_whitelist.iter().filter(|c| !new_whitelist.contains(c)) {
+			changes.push(format!("removed {port_id}/{channel_id} from the whitelist on {chain}"))
+		}
+		if self.filter != new.filter {
+			changes.push(format!("updated the packet filter on {chain}"))
+		}
+	}
+}
+
+/// Settings of both chains that can change while the relayer runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuntimeConfig {
+	pub chain_a: ChainRuntimeConfig,
+	pub chain_b: ChainRuntimeConfig,
+}
+
+impl RuntimeConfig {
+	/// Dynamic settings of `config`.
+	pub fn from_config(config: &Config) -> Self {
+		let chain = |chain: &AnyConfig, filter: &ChainFilter| ChainRuntimeConfig {
+			channel_whitelist: Some(chain.channel_whitelist()),
+			filter: filter.clone(),
+		};
+		Self {
+			chain_a: chain(&config.chain_a, &config.filter.chain_a),
+			chain_b: chain(&config.chain_b, &config.filter.chain_b),
+		}
+	}
+
+	/// Rejects whitelists naming a channel twice, filters with several rules for a channel and
+	/// minimum fees without a denomination.
+	pub fn validate(&self) -> Result<(), anyhow::Error> {
+		self.chain_a.validate("chain_a")?;
+		self.chain_b.validate("chain_b")
+	}
+
+	/// Describes the differences between `self` and `new`, for logging.
+	pub fn changes(&self, new: &Self) -> Vec<String> {
+		let mut changes = vec![];
+		self.chain_a.changes(&new.chain_a, "chain_a", &mut changes);
+		self.chain_b.changes(&new.chain_b, "chain_b", &mut changes);
+		changes
+	}
+}
+
+impl From<PacketFilter> for RuntimeConfig {
+	fn from(filter: PacketFilter) -> Self {
+		Self {
+			chain_a: ChainRuntimeConfig { channel_whitelist: None, filter: filter.chain_a },
+			chain_b: ChainRuntimeConfig { channel_whitelist: None, filter: filter.chain_b },
+		}
+	}
+}
+
+/// [`RuntimeConfig`] shared with the pipelines, from the point of view of the pipelines relaying
+/// from chain A, or from chain B once [`SharedRuntimeConfig::reversed`].
+#[derive(Debug, Clone)]
+pub struct SharedRuntimeConfig {
+	config: watch::Receiver<RuntimeConfig>,
+	reversed: bool,
+}
+
+impl SharedRuntimeConfig {
+	/// Shares `config`, which can then be replaced through the returned sender.
+	pub fn new(config: RuntimeConfig) -> (watch::Sender<RuntimeConfig>, Self) {
+		let (sender, config) = watch::channel(config);
+		(sender, Self { config, reversed: false })
+	}
+
+	/// The same config, with the source and sink swapped.
+	pub fn reversed(&self) -> Self {
+		Self { config: self.config.clone(), reversed: !self.reversed }
+	}
+
+	/// Current settings of the source and of the sink.
+	pub fn current(&self) -> (ChainRuntimeConfig, ChainRuntimeConfig) {
+		let config = self.config.borrow();
+		let (a, b) = (config.chain_a.clone(), config.chain_b.clone());
+		if self.reversed {
+			(b, a)
+		} else {
+			(a, b)
+		}
+	}
+
+	/// Waits for the settings to change, returns false once they can't change anymore.
+	pub async fn changed(&mut self) -> bool {
+		self.config.changed().await.is_ok()
+	}
+}
+
+impl Default for SharedRuntimeConfig {
+	fn default() -> Self {
+		Self::new(RuntimeConfig::default()).1
+	}
+}
+
+impl From<PacketFilter> for SharedRuntimeConfig {
+	fn from(filter: PacketFilter) -> Self {
+		Self::new(filter.into()).1
+	}
+}
+
+/// Parts of `config` that are only read on startup, as a toml value.
+pub fn immutable_sections(config: &Config) -> Result<toml::Value, anyhow::Error> {
+	let mut value = toml::Value::try_from(config)?;
+	if let Some(table) = value.as_table_mut() {
+		table.remove("filter");
+		for chain in ["chain_a", "chain_b"] {
+			if let Some(chain) = table.get_mut(chain).and_then(toml::Value::as_table_mut) {
+				chain.remove("channel_whitelist");
+			}
+		}
+	}
+	Ok(value)
+}
+
+/// Dotted paths of the fields that differ between the toml values `old` and `new`.
+pub fn changed_fields(old: &toml::Value, new: &toml::Value) -> Vec<String> {
+	fn diff(old: &toml::Value, new: &toml::Value, path: String, changed: &mut Vec<String>) {
+		let (old, new) = match (old.as_table(), new.as_table()) {
+			(Some(old), Some(new)) => (old, new),
+			_ => {
+				if old != new {
+					changed.push(path)
+				}
+				return
+			},
+		};
+		for key in old.keys().chain(new.keys().filter(|key| !old.contains_key(*key))) {
+			let path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+			match (old.get(key), new.get(key)) {
+				(Some(old), Some(new)) => diff(old, new, path, changed),
+				_ => changed.push(path),
+			}
+		}
+	}
+	let mut changed = vec![];
+	diff(old, new, String::new(), &mut changed);
+	changed
+}
+
+/// Starts the config watcher task for the config file at `path`, which `config` was read from,
+/// and returns the settings it keeps up to date.
+pub fn watch_config(path: PathBuf, config: &Config) -> Result<SharedRuntimeConfig, anyhow::Error> {
+	let immutable = immutable_sections(config)?;
+	let runtime_config = RuntimeConfig::from_config(config);
+	runtime_config.validate()?;
+	let interval = config.core.config_reload_interval();
+	let modified = modified_time(&path);
+	let (sender, shared) = SharedRuntimeConfig::new(runtime_config);
+	tokio::spawn(config_watcher_task(path, immutable, sender, modified, interval));
+	Ok(shared)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Re-reads the config file every `interval` once it was modified after `modified`, and replaces
+/// the runtime config in `sender` with the file's if it's valid. `immutable` holds the sections
+/// read on startup, see [`immutable_sections`], changes to them are only logged.
+async fn config_watcher_task(
+	path: PathBuf,
+	immutable: toml::Value,
+	sender: watch::Sender<RuntimeConfig>,
+	mut modified: Option<SystemTime>,
+	interval: Duration,
+) {
+	let mut interval = tokio::time::interval(interval);
+	let mut ignored = vec![];
+	loop {
+		interval.tick().await;
+		let latest = modified_time(&path);
+		if latest == modified {
+			continue
+		}
+		modified = latest;
+
+		let config = match read_config(&path).await {
+			Ok(config) => config,
+			Err(e) => {
+				log::error!("Failed to reload the config at {}: {e:?}", path.display());
+				continue
+			},
+		};
+		match immutable_sections(&config) {
+			Ok(sections) => {
+				let changed = changed_fields(&immutable, &sections);
+				for field in changed.iter().filter(|field| !ignored.contains(*field)) {
+					log::warn!("Ignoring the change to {field}, restart the relayer to apply it");
+				}
+				ignored = changed;
+			},
+			Err(e) => log::error!("Failed to compare the config at {}: {e:?}", path.display()),
+		}
+
+		let runtime_config = RuntimeConfig::from_config(&config);
+		if let Err(e) = runtime_config.validate() {
+			log::error!("Rejected the config at {}: {e:?}", path.display());
+			continue
+		}
+		let changes = sender.borrow().changes(&runtime_config);
+		if changes.is_empty() {
+			continue
+		}
+		log::info!("Reloaded the config at {}: {}", path.display(), changes.join(", "));
+		sender.send_replace(runtime_config);
+	}
+}
+
+async fn read_config(path: &Path) -> Result<Config, anyhow::Error> {
+	let file_content = tokio::fs::read_to_string(path).await?;
+	Ok(toml::from_str(&file_content)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::filter::{ChannelRule, FilterAction, MinFee};
+
+	const CONFIG: &str = r#"
+		[chain_a]
+		type = "parachain"
+		name = "picasso"
+		para_id = 2001
+		parachain_rpc_url = "ws://127.0.0.1:9988"
+		relay_chain_rpc_url = "ws://127.0.0.1:9944"
+		client_id = "10-grandpa-0"
+		connection_id = "connection-0"
+		channel_whitelist = [["channel-0", "transfer"]]
+		commitment_prefix = "0x6962632f"
+		private_key = "//Alice"
+		ss58_version = 49
+		key_type = "sr25519"
+		finality_protocol = "Grandpa"
+
+		[chain_b]
+		type = "parachain"
+		name = "dali"
+		para_id = 2000
+		parachain_rpc_url = "ws://127.0.0.1:9188"
+		relay_chain_rpc_url = "ws://127.0.0.1:9944"
+		client_id = "10-grandpa-0"
+		connection_id = "connection-0"
+		channel_whitelist = [["channel-0", "transfer"]]
+		commitment_prefix = "0x6962632f"
+		private_key = "//Alice"
+		ss58_version = 49
+		key_type = "sr25519"
+		finality_protocol = "Grandpa"
+
+		[core]
+		store_path = "hyperspace-store"
+		"#;
+
+	fn config(edit: impl FnOnce(&mut toml::Value)) -> Config {
+		let mut value: toml::Value = toml::from_str(CONFIG).unwrap();
+		edit(&mut value);
+		value.try_into().unwrap()
+	}
+
+	fn channel(channel: u64) -> (ChannelId, PortId) {
+		(ChannelId::new(channel), PortId::transfer())
+	}
+
+	#[test]
+	fn dynamic_changes_keep_immutable_sections() {
+		let old = config(|_| {});
+		let new = config(|value| {
+			value["chain_a"]["channel_whitelist"] =
+				toml::Value::try_from(vec![channel(0), channel(1)]).unwrap();
+			value.as_table_mut().unwrap().insert(
+				"filter".to_string(),
+				toml::from_str("[chain_b]\ndefault = \"deny\"").unwrap(),
+			);
+		});
+		let (old_sections, new_sections) =
+			(immutable_sections(&old).unwrap(), immutable_sections(&new).unwrap());
+		assert!(changed_fields(&old_sections, &new_sections).is_empty());
+
+		let old = RuntimeConfig::from_config(&old);
+		let new = RuntimeConfig::from_config(&new);
+		assert_eq!(new.chain_a.channel_whitelist, Some(vec![channel(0), channel(1)]));
+		assert_eq!(new.chain_b.filter.default, FilterAction::Deny);
+		assert_eq!(
+			old.changes(&new),
+			vec![
+				"whitelisted transfer/channel-1 on chain_a".to_string(),
+				"updated the packet filter on chain_b".to_string(),
+			]
+		);
+		let removed = "removed transfer/channel-1 from the whitelist on chain_a";
+		assert_eq!(new.changes(&old)[0], removed);
+		assert!(new.changes(&new).is_empty());
+	}
+
+	#[test]
+	fn immutable_changes_are_reported() {
+		let old = immutable_sections(&config(|_| {})).unwrap();
+		let new = immutable_sections(&config(|value| {
+			value["chain_a"]["parachain_rpc_url"] = "ws://127.0.0.1:9989".into();
+			value["chain_b"]["client_id"] = "10-grandpa-1".into();
+			let core = value["core"].as_table_mut().unwrap();
+			core.remove("store_path");
+			core.insert("keep_alive_interval".to_string(), toml::Value::Integer(60));
+		}))
+		.unwrap();
+		let mut changed = changed_fields(&old, &new);
+		changed.sort();
+		assert_eq!(
+			changed,
+			vec![
+				"chain_a.parachain_rpc_url",
+				"chain_b.client_id",
+				"core.keep_alive_interval",
+				"core.store_path",
+			]
+		);
+	}
+
+	#[test]
+	fn validation_rejects_ambiguous_settings() {
+		let valid = RuntimeConfig::from_config(&config(|_| {}));
+		assert!(valid.validate().is_ok());
+
+		let mut config = valid.clone();
+		config.chain_b.channel_whitelist = Some(vec![channel(0), channel(1), channel(0)]);
+		assert!(config.validate().is_err());
+
+		let rule = |action| ChannelRule {
+			port_id: PortId::transfer(),
+			channel_id: ChannelId::new(0),
+			action,
+		};
+		let mut config = valid.clone();
+		config.chain_a.filter.channels = vec![rule(FilterAction::Allow), rule(FilterAction::Deny)];
+		assert!(config.validate().is_err());
+
+		let mut config = valid;
+		config.chain_a.filter.min_fee =
+			Some(MinFee { denom: String::new(), amount: 100u64.into() });
+		assert!(config.validate().is_err());
+	}
+
+	#[test]
+	fn reversed_config_swaps_chains() {
+		let mut config = RuntimeConfig::default();
+		config.chain_a.channel_whitelist = Some(vec![channel(0)]);
+		let (sender, shared) = SharedRuntimeConfig::new(config);
+		assert_eq!(shared.current().0.channel_whitelist, Some(vec![channel(0)]));
+		assert_eq!(shared.reversed().current().1.channel_whitelist, Some(vec![channel(0)]));
+
+		let mut config = RuntimeConfig::default();
+		config.chain_b.channel_whitelist = Some(vec![channel(1)]);
+		sender.send_replace(config);
+		assert_eq!(shared.current().1.channel_whitelist, Some(vec![channel(1)]));
+		assert_eq!(shared.reversed().current().0.channel_whitelist, Some(vec![channel(1)]));
+	}
+}
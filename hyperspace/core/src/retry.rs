@@ -0,0 +1,342 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Resubmission of message batches whose submission failed.
+//!
+//! Failures are classified by the chain submitted to, see [`Chain::submit_error_kind`]. A batch
+//! that failed retriably is submitted again with an exponential backoff, before any of the batches
+//! queued after it, until it runs out of attempts and is dropped. Fatal failures are returned right
+//! away. Before a batch is resubmitted, the packet messages whose proofs can no longer be verified
+//! on the sink are rebuilt with fresh proofs.
+
+use crate::{
+	packets::utils::{construct_ack_message, construct_recv_message},
+	pipeline::query_sink_client_state,
+};
+use async_trait::async_trait;
+use ibc::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as ConsensusStateT,
+			client_state::ClientState as ClientStateT,
+		},
+		ics04_channel::msgs::{
+			acknowledgement::{self, MsgAcknowledgement},
+			recv_packet::{self, MsgRecvPacket},
+			timeout::{self, MsgTimeout},
+			timeout_on_close::{self, MsgTimeoutOnClose},
+		},
+	},
+	timestamp::Timestamp,
+	Height,
+};
+use ibc_proto::google::protobuf::Any;
+use pallet_ibc::light_clients::AnyConsensusState;
+use primitives::{Chain, SubmitErrorKind};
+use std::{collections::VecDeque, time::Duration};
+use tendermint_proto::Protobuf;
+
+/// Number of attempts at submitting a batch of messages, and the wait between them.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+	/// Attempts, including the first one, after which a batch is dropped.
+	pub max_attempts: u32,
+	/// Wait before the first retry, doubled for every further one.
+	pub initial_backoff: Duration,
+	/// Upper bound of the wait between two attempts.
+	pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_attempts: 5,
+			initial_backoff: Duration::from_secs(6),
+			max_backoff: Duration::from_secs(2 * 60),
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Wait before the attempt following `attempts` failed ones.
+	pub fn backoff(&self, attempts: u32) -> Duration {
+		let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+		self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+	}
+}
+
+/// Destination of the batches submitted by [`submit_with_retry`].
+#[async_trait]
+pub trait Submitter: Send + Sync {
+	/// Name of the chain submitted to, used in logs.
+	fn name(&self) -> &str;
+
+	/// Submits `messages`, classifying the error if they weren't accepted.
+	async fn submit(&self, messages: Vec<Any>) -> Result<(), (SubmitErrorKind, anyhow::Error)>;
+
+	/// Called before `messages` are resubmitted, refreshes the proofs that can no longer be
+	/// verified.
+	async fn refresh_proofs(&self, messages: Vec<Any>) -> Result<Vec<Any>, anyhow::Error>;
+}
+
+/// [`Submitter`] of messages to `sink`, carrying proofs of `source`.
+pub struct ChainSubmitter<'a, S, C> {
+	pub sink: &'a S,
+	pub source: &'a C,
+}
+
+#[async_trait]
+impl<S: Chain, C: Chain> Submitter for ChainSubmitter<'_, S, C> {
+	fn name(&self) -> &str {
+		self.sink.name()
+	}
+
+	async fn submit(&self, messages: Vec<Any>) -> Result<(), (SubmitErrorKind, anyhow::Error)> {
+		match self.sink.submit(messages).await {
+			Ok(_) => Ok(()),
+			Err(e) => Err((self.sink.submit_error_kind(&e), e.into())),
+		}
+	}
+
+	async fn refresh_proofs(&self, messages: Vec<Any>) -> Result<Vec<Any>, anyhow::Error> {
+		refresh_stale_proofs(self.source, self.sink, messages).await
+	}
+}
+
+/// Submits `batches` in order, resubmitting a batch that failed retriably according to `policy`
+/// before moving on to the next one. Returns the number of messages dropped after running out of
+/// attempts.
+pub async fn submit_with_retry(
+	submitter: &impl Submitter,
+	batches: Vec<Vec<Any>>,
+	policy: &RetryPolicy,
+) -> Result<u64, anyhow::Error> {
+	let mut queue = batches.into_iter().collect::<VecDeque<_>>();
+	let mut attempts = 0;
+	let mut dropped = 0;
+	while let Some(messages) = queue.pop_front() {
+		let messages =
+			if attempts > 0 { submitter.refresh_proofs(messages).await? } else { messages };
+		if messages.is_empty() {
+			attempts = 0;
+			continue
+		}
+		let error = match submitter.submit(messages.clone()).await {
+			Ok(()) => {
+				attempts = 0;
+				continue
+			},
+			Err((SubmitErrorKind::Fatal, error)) => return Err(error),
+			Err((SubmitErrorKind::Retriable, error)) => error,
+		};
+		attempts += 1;
+		if attempts >= policy.max_attempts {
+			log::error!(
+				"Dropping {} messages to {} after {attempts} failed submissions: {error:?}",
+				messages.len(),
+				submitter.name()
+			);
+			dropped += messages.len() as u64;
+			attempts = 0;
+			continue
+		}
+		let backoff = policy.backoff(attempts);
+		log::warn!(
+			"Failed to submit {} messages to {}, retrying in {backoff:?}: {error:?}",
+			messages.len(),
+			submitter.name()
+		);
+		tokio::time::sleep(backoff).await;
+		queue.push_front(messages);
+	}
+	Ok(dropped)
+}
+
+/// Rebuilds the packet and acknowledgement messages whose proof height is no longer within the
+/// trusting period of the source's client on the sink, with proofs at the client's latest height.
+/// Stale timeouts are left out, the packet task rebuilds them on its next sweep.
+pub async fn refresh_stale_proofs(
+	source: &impl Chain,
+	sink: &impl Chain,
+	messages: Vec<Any>,
+) -> Result<Vec<Any>, anyhow::Error> {
+	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+	let client_state = query_sink_client_state(source, sink, sink_height).await?;
+	let latest_height = client_state.latest_height();
+	let trusting_period = client_state.trusting_period();
+	let sink_at = (sink_height, sink_timestamp);
+	let is_trusted =
+		|proof_height| is_trusted_height(source, sink, sink_at, trusting_period, proof_height);
+
+	let mut refreshed = Vec::with_capacity(messages.len());
+	for msg in messages {
+		let msg = match msg.type_url.as_str() {
+			recv_packet::TYPE_URL => {
+				let recv = MsgRecvPacket::decode_vec(&msg.value)?;
+				if is_trusted(recv.proofs.height()).await {
+					msg
+				} else {
+					construct_recv_message(source, sink, recv.packet, latest_height).await?
+				}
+			},
+			acknowledgement::TYPE_URL => {
+				let ack = MsgAcknowledgement::decode_vec(&msg.value)?;
+				if is_trusted(ack.proofs.height()).await {
+					msg
+				} else {
+					let acknowledgement = ack.acknowledgement.into_bytes();
+					construct_ack_message(source, sink, ack.packet, acknowledgement, latest_height)
+						.await?
+				}
+			},
+			timeout::TYPE_URL | timeout_on_close::TYPE_URL => {
+				let proof_height = if msg.type_url == timeout::TYPE_URL {
+					MsgTimeout::decode_vec(&msg.value)?.proofs.height()
+				} else {
+					MsgTimeoutOnClose::decode_vec(&msg.value)?.proofs.height()
+				};
+				if !is_trusted(proof_height).await {
+					log::info!("Leaving out timeout with stale proof for {}", sink.name());
+					continue
+				}
+				msg
+			},
+			_ => msg,
+		};
+		refreshed.push(msg);
+	}
+	Ok(refreshed)
+}
+
+/// Returns true if the consensus state of the source's client on the sink at `proof_height` is
+/// still within the client's trusting period.
+async fn is_trusted_height(
+	source: &impl Chain,
+	sink: &impl Chain,
+	(sink_height, sink_timestamp): (Height, Timestamp),
+	trusting_period: Duration,
+	proof_height: Height,
+) -> bool {
+	let consensus_state = match sink
+		.query_client_consensus(sink_height, source.client_id(), proof_height)
+		.await
+	{
+		Ok(response) => response.consensus_state,
+		Err(_) => None,
+	};
+	match consensus_state.map(AnyConsensusState::try_from) {
+		Some(Ok(consensus_state)) => sink_timestamp
+			.duration_since(&consensus_state.timestamp())
+			.map_or(true, |elapsed| elapsed < trusting_period),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::Mutex;
+
+	/// Fails its submissions with the given errors, in order, before accepting any.
+	#[derive(Default)]
+	struct FlakySubmitter {
+		failures: Mutex<VecDeque<SubmitErrorKind>>,
+		submitted: Mutex<Vec<Vec<Any>>>,
+		refreshed: Mutex<u32>,
+	}
+
+	impl FlakySubmitter {
+		fn new(failures: &[SubmitErrorKind]) -> Self {
+			Self { failures: Mutex::new(failures.iter().copied().collect()), ..Default::default() }
+		}
+	}
+
+	#[async_trait]
+	impl Submitter for FlakySubmitter {
+		fn name(&self) -> &str {
+			"flaky"
+		}
+
+		async fn submit(
+			&self,
+			messages: Vec<Any>,
+		) -> Result<(), (SubmitErrorKind, anyhow::Error)> {
+			if let Some(kind) = self.failures.lock().unwrap().pop_front() {
+				return Err((kind, anyhow::anyhow!("submission failed")))
+			}
+			self.submitted.lock().unwrap().push(messages);
+			Ok(())
+		}
+
+		async fn refresh_proofs(&self, messages: Vec<Any>) -> Result<Vec<Any>, anyhow::Error> {
+			*self.refreshed.lock().unwrap() += 1;
+			Ok(messages)
+		}
+	}
+
+	fn msg(type_url: &str) -> Any {
+		Any { type_url: type_url.to_string(), value: vec![] }
+	}
+
+	fn policy(max_attempts: u32) -> RetryPolicy {
+		RetryPolicy {
+			max_attempts,
+			initial_backoff: Duration::from_millis(1),
+			max_backoff: Duration::from_millis(4),
+		}
+	}
+
+	#[tokio::test]
+	async fn resubmits_after_retriable_failures() {
+		let submitter =
+			FlakySubmitter::new(&[SubmitErrorKind::Retriable, SubmitErrorKind::Retriable]);
+		let batches = vec![vec![msg("a")], vec![msg("b")]];
+
+		let dropped = submit_with_retry(&submitter, batches.clone(), &policy(5)).await.unwrap();
+
+		assert_eq!(dropped, 0);
+		// the first batch goes through on its third attempt, before the second one is submitted.
+		assert_eq!(*submitter.submitted.lock().unwrap(), batches);
+		assert_eq!(*submitter.refreshed.lock().unwrap(), 2);
+	}
+
+	#[tokio::test]
+	async fn drops_batch_after_max_attempts() {
+		let submitter = FlakySubmitter::new(&[SubmitErrorKind::Retriable; 3]);
+		let batches = vec![vec![msg("a"), msg("b")], vec![msg("c")]];
+
+		let dropped = submit_with_retry(&submitter, batches, &policy(3)).await.unwrap();
+
+		assert_eq!(dropped, 2);
+		assert_eq!(*submitter.submitted.lock().unwrap(), vec![vec![msg("c")]]);
+	}
+
+	#[tokio::test]
+	async fn returns_fatal_failures() {
+		let submitter = FlakySubmitter::new(&[SubmitErrorKind::Fatal]);
+		let batches = vec![vec![msg("a")], vec![msg("b")]];
+
+		assert!(submit_with_retry(&submitter, batches, &policy(5)).await.is_err());
+		assert!(submitter.submitted.lock().unwrap().is_empty());
+	}
+
+	#[test]
+	fn backoff_doubles_up_to_max() {
+		let policy = policy(10);
+		let backoffs = (1..=5).map(|attempts| policy.backoff(attempts)).collect::<Vec<_>>();
+		let millis = [1, 2, 4, 4, 4].map(Duration::from_millis);
+		assert_eq!(backoffs, millis);
+	}
+}
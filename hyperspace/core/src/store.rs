@@ -0,0 +1,117 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Relayer state that has to survive restarts.
+
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use std::{collections::BTreeMap, path::Path, sync::Mutex};
+
+/// Records, per channel, the source chain height up to which the relayer has processed the
+/// channel's packets and acknowledgements, so that it can catch up on the ones written while it
+/// was down.
+pub trait RelayerStore: Send + Sync {
+	/// Height up to which the packets of the channel on `chain` were processed, `None` if the
+	/// channel was never relayed.
+	fn processed_height(
+		&self,
+		chain: &str,
+		channel_id: ChannelId,
+		port_id: &PortId,
+	) -> Result<Option<u64>, anyhow::Error>;
+
+	/// Records that the packets of the channel on `chain` were processed up to `height`.
+	fn set_processed_height(
+		&self,
+		chain: &str,
+		channel_id: ChannelId,
+		port_id: &PortId,
+		height: u64,
+	) -> Result<(), anyhow::Error>;
+}
+
+fn channel_key(chain: &str, channel_id: ChannelId, port_id: &PortId) -> String {
+	format!("{chain}/{port_id}/{channel_id}")
+}
+
+/// [`RelayerStore`] backed by a sled database on disk.
+pub struct SledStore {
+	db: sled::Db,
+}
+
+impl SledStore {
+	/// Opens the database at `path`, creating it if it doesn't exist.
+	pub fn open(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+		Ok(Self { db: sled::open(path)? })
+	}
+}
+
+impl RelayerStore for SledStore {
+	fn processed_height(
+		&self,
+		chain: &str,
+		channel_id: ChannelId,
+		port_id: &PortId,
+	) -> Result<Option<u64>, anyhow::Error> {
+		let value = match self.db.get(channel_key(chain, channel_id, port_id))? {
+			Some(value) => value,
+			None => return Ok(None),
+		};
+		let bytes = value
+			.as_ref()
+			.try_into()
+			.map_err(|_| anyhow::anyhow!("Invalid processed height stored for {channel_id}"))?;
+		Ok(Some(u64::from_be_bytes(bytes)))
+	}
+
+	fn set_processed_height(
+		&self,
+		chain: &str,
+		channel_id: ChannelId,
+		port_id: &PortId,
+		height: u64,
+	) -> Result<(), anyhow::Error> {
+		self.db.insert(channel_key(chain, channel_id, port_id), height.to_be_bytes().to_vec())?;
+		self.db.flush()?;
+		Ok(())
+	}
+}
+
+/// In-memory [`RelayerStore`], for tests.
+#[derive(Default)]
+pub struct MemoryStore {
+	heights: Mutex<BTreeMap<String, u64>>,
+}
+
+impl RelayerStore for MemoryStore {
+	fn processed_height(
+		&self,
+		chain: &str,
+		channel_id: ChannelId,
+		port_id: &PortId,
+	) -> Result<Option<u64>, anyhow::Error> {
+		let heights = self.heights.lock().unwrap();
+		Ok(heights.get(&channel_key(chain, channel_id, port_id)).copied())
+	}
+
+	fn set_processed_height(
+		&self,
+		chain: &str,
+		channel_id: ChannelId,
+		port_id: &PortId,
+		height: u64,
+	) -> Result<(), anyhow::Error> {
+		self.heights.lock().unwrap().insert(channel_key(chain, channel_id, port_id), height);
+		Ok(())
+	}
+}
@@ -0,0 +1,83 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Watches a chain's finality for conflicts between its primary and secondary rpc endpoints.
+//!
+//! A full node feeding the relayer finality proofs for blocks the rest of the network didn't
+//! finalize would get the chain's light client on the counterparty to accept a fork. When the
+//! chain has a secondary rpc endpoint, the watchtower task checks every block finalized according
+//! to the primary endpoint against it, and on conflict submits the misbehaviour to the
+//! counterparty, freezing the client.
+
+use futures::StreamExt;
+use metrics::handler::MetricsHandler;
+use primitives::{submit_misbehaviour_msg, Chain};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Cross-checks each of the source's finality events against its secondary rpc endpoint,
+/// submitting the misbehaviour to the sink whenever they conflict.
+pub async fn watchtower_task<A, B>(
+	source: A,
+	sink: B,
+	metrics: Option<Arc<Mutex<MetricsHandler>>>,
+) -> Result<(), anyhow::Error>
+where
+	A: Chain,
+	A::FinalityEvent: Send + Sync,
+	B: Chain,
+{
+	let mut finality_notifications = source.finality_notifications().await;
+	while let Some(finality_event) = finality_notifications.next().await {
+		let checked = check_finality_event(&source, &sink, finality_event, metrics.as_ref()).await;
+		if let Err(e) = checked {
+			log::error!("Failed to cross-check finality of {}: {:?}", source.name(), e);
+		}
+	}
+	Ok(())
+}
+
+/// Submits the misbehaviour to the sink if the block finalized by `finality_event` conflicts
+/// with the source's secondary rpc endpoint, returning whether it did.
+pub async fn check_finality_event<A, B>(
+	source: &A,
+	sink: &B,
+	finality_event: A::FinalityEvent,
+	metrics: Option<&Arc<Mutex<MetricsHandler>>>,
+) -> Result<bool, anyhow::Error>
+where
+	A: Chain,
+	A::FinalityEvent: Send + Sync,
+	B: Chain,
+{
+	let misbehaviour = match source.query_conflicting_finality(finality_event).await? {
+		Some(misbehaviour) => misbehaviour,
+		None => return Ok(false),
+	};
+	log::error!(
+		"🚨🚨 The primary and secondary rpc endpoints of {} finalized conflicting blocks, \
+		 submitting misbehaviour to freeze client {} on {}",
+		source.name(),
+		source.client_id(),
+		sink.name()
+	);
+	if let Some(metrics) = metrics {
+		metrics.lock().await.handle_conflicting_finality();
+	}
+	let msg = submit_misbehaviour_msg(sink, source.client_id(), misbehaviour);
+	sink.submit(vec![msg])
+		.await
+		.map_err(|e| anyhow::anyhow!("Failed to submit misbehaviour report: {:?}", e))?;
+	Ok(true)
+}
@@ -0,0 +1,191 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{pin::Pin, sync::atomic::Ordering, time::Duration};
+
+use anyhow::anyhow;
+use futures::{Stream, StreamExt, TryFutureExt};
+use ibc::core::ics02_client::events::UpdateClient;
+use ibc_proto::google::protobuf::Any;
+use ics07_tendermint::client_message::{ClientMessage, Misbehaviour};
+use pallet_ibc::light_clients::AnyClientMessage;
+use primitives::{
+	submit_misbehaviour_msg, Chain, IbcProvider, MisbehaviourHandler, SubmitErrorKind,
+};
+use prost::Message;
+use tendermint_rpc::Client;
+use tokio::{sync::mpsc, time::sleep};
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::{error::Error, tx::TransactionId, CosmosClient};
+
+/// Interval between two queries of the chain's latest block.
+const BLOCK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A block committed by the chain. Tendermint blocks are final once committed, so every block
+/// can be proven to the counterparty.
+#[derive(Debug, Clone)]
+pub struct FinalityEvent {
+	/// Height of the committed block
+	pub height: u64,
+}
+
+impl CosmosClient {
+	/// Yields the height of every block committed from now on, in order.
+	pub fn committed_blocks(&self) -> ReceiverStream<u64> {
+		let (sender, receiver) = mpsc::channel(32);
+		let client = self.clone();
+		tokio::spawn(async move {
+			let mut next_height = None;
+			loop {
+				let latest_height = match client.rpc_client.status().await {
+					Ok(status) => status.sync_info.latest_block_height.value(),
+					Err(e) => {
+						log::error!("Failed to query the latest block of {}: {:?}", client.name, e);
+						sleep(BLOCK_POLL_INTERVAL).await;
+						continue
+					},
+				};
+				for height in next_height.unwrap_or(latest_height)..=latest_height {
+					// the receiver was dropped along with the stream.
+					if sender.send(height).await.is_err() {
+						return
+					}
+				}
+				next_height = Some(latest_height + 1);
+				sleep(BLOCK_POLL_INTERVAL).await;
+			}
+		});
+		ReceiverStream::new(receiver)
+	}
+}
+
+#[async_trait::async_trait]
+impl Chain for CosmosClient {
+	fn name(&self) -> &str {
+		&*self.name
+	}
+
+	fn block_max_weight(&self) -> u64 {
+		// transactions are limited by the gas they're given, not by the size of the block.
+		u64::MAX
+	}
+
+	async fn estimate_weight(&self, _messages: Vec<Any>) -> Result<u64, Self::Error> {
+		// batches are simulated and split to fit in `max_gas` once submitted.
+		Ok(self.max_gas)
+	}
+
+	async fn finality_notifications(
+		&self,
+	) -> Pin<Box<dyn Stream<Item = <Self as IbcProvider>::FinalityEvent> + Send + Sync>> {
+		Box::pin(self.committed_blocks().map(|height| FinalityEvent { height }))
+	}
+
+	fn subscription_reconnects(&self) -> u64 {
+		self.subscription_reconnects.load(Ordering::SeqCst)
+	}
+
+	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Error> {
+		self.submit_tx(messages).await
+	}
+
+	fn submit_error_kind(&self, error: &Error) -> SubmitErrorKind {
+		error.submit_error_kind()
+	}
+
+	async fn query_client_message(&self, update: UpdateClient) -> Result<AnyClientMessage, Error> {
+		let header = update
+			.header
+			.ok_or_else(|| Error::Custom("Update client event has no header".to_string()))?;
+		let any = Any::decode(header.as_slice())?;
+		// ibc-go clients are updated with the messages of the light client, not wrapped in an
+		// `AnyClientMessage`.
+		match ClientMessage::try_from(any.clone()) {
+			Ok(message) => Ok(AnyClientMessage::Tendermint(message)),
+			Err(_) => Ok(AnyClientMessage::try_from(any)?),
+		}
+	}
+
+	fn has_secondary_rpc(&self) -> bool {
+		false
+	}
+
+	async fn query_conflicting_finality(
+		&self,
+		_finality_event: FinalityEvent,
+	) -> Result<Option<AnyClientMessage>, anyhow::Error> {
+		Ok(None)
+	}
+
+	fn encode_client_message(&self, client_message: AnyClientMessage) -> Any {
+		// ibc-go routes client messages to its light clients by the type url of their own
+		// messages.
+		match client_message {
+			AnyClientMessage::Tendermint(message) => message.into(),
+			client_message => client_message.into(),
+		}
+	}
+
+	fn supports_client_update_batches(&self) -> bool {
+		false
+	}
+}
+
+#[async_trait::async_trait]
+impl MisbehaviourHandler for CosmosClient {
+	async fn check_for_misbehaviour<C: Chain>(
+		&self,
+		counterparty: &C,
+		client_message: AnyClientMessage,
+	) -> Result<(), anyhow::Error> {
+		let header = match client_message {
+			AnyClientMessage::Tendermint(ClientMessage::Header(header)) => header,
+			_ => return Ok(()),
+		};
+		let height = header.signed_header.header.height;
+		let commit = self.rpc_client.commit(height).await?;
+		let header_hash = header.signed_header.header.hash();
+		let committed_hash = commit.signed_header.header.hash();
+		if header_hash == committed_hash {
+			return Ok(())
+		}
+		log::warn!(
+			"Found misbehaviour on client {}: {:?} != {:?}",
+			self.client_id
+				.as_ref()
+				.map(|x| x.as_str().to_owned())
+				.unwrap_or_else(|| "{unknown}".to_owned()),
+			header_hash,
+			committed_hash
+		);
+
+		let committed_header = self.query_header(height.value(), header.trusted_height).await?;
+		let misbehaviour = ClientMessage::Misbehaviour(Misbehaviour {
+			client_id: self.client_id(),
+			header1: header,
+			header2: committed_header,
+		});
+		let msg = submit_misbehaviour_msg(
+			counterparty,
+			self.client_id(),
+			AnyClientMessage::Tendermint(misbehaviour),
+		);
+		counterparty
+			.submit(vec![msg])
+			.map_err(|e| anyhow!("Failed to submit misbehaviour report: {:?}", e))
+			.await?;
+		Ok(())
+	}
+}
@@ -0,0 +1,100 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc::{core::ics02_client, timestamp::ParseTimestampError};
+use primitives::SubmitErrorKind;
+use thiserror::Error;
+
+/// Error definition for the cosmos client
+#[derive(Error, Debug)]
+pub enum Error {
+	/// An error from the tendermint rpc interface
+	#[error("Tendermint rpc error: {0}")]
+	Rpc(#[from] tendermint_rpc::Error),
+	/// An error returned by a gRPC endpoint of the chain
+	#[error("gRPC error: {0}")]
+	Grpc(#[from] tonic::Status),
+	/// Protobuf decoding error
+	#[error("Protobuf decoding error: {0}")]
+	Decode(#[from] prost::DecodeError),
+	/// Tendermint types error
+	#[error("Tendermint error: {0}")]
+	Tendermint(#[from] tendermint::Error),
+	/// An error communicating with the remote signer
+	#[error("Remote signer io error: {0}")]
+	Io(#[from] std::io::Error),
+	/// The transaction was rejected by the chain or failed once included in a block
+	#[error("Transaction {hash} failed with code {code} of {codespace}: {log}")]
+	TxFailed { hash: String, codespace: String, code: u32, log: String },
+	/// Executing the messages of a transaction needs more gas than it may be given
+	#[error("Transaction needs {gas_used} gas, more than the max gas of {max_gas}")]
+	GasLimitExceeded { gas_used: u64, max_gas: u64 },
+	/// The transaction wasn't included in a block in time
+	#[error("Transaction {0} wasn't included in a block in time")]
+	TxTimeout(String),
+	/// Custom error
+	#[error("{0}")]
+	Custom(String),
+	/// Errors associated with ics-02 client
+	#[error("Ibc client error: {0}")]
+	IbcClient(#[from] ics02_client::error::Error),
+	/// Ics-20 errors
+	#[error("Ics-20 error: {0}")]
+	Ics20Error(#[from] ibc::applications::transfer::error::Error),
+	/// Error occured parsing timestamp
+	#[error("Timestamp error: {0}")]
+	ParseTimestamp(#[from] ParseTimestampError),
+}
+
+/// Codespace of the errors of the cosmos sdk itself, rather than of its modules.
+const SDK_CODESPACE: &str = "sdk";
+
+/// Codes of the cosmos sdk errors returned for transactions that may succeed when retried: an
+/// account sequence mismatch, a transaction already in the mempool and a full mempool.
+const RETRIABLE_TX_CODES: &[u32] = &[32, 19, 20];
+
+/// Code of the cosmos sdk error returned for transactions that ran out of gas.
+pub const OUT_OF_GAS_CODE: u32 = 11;
+
+impl Error {
+	/// Classifies an error returned while submitting a transaction.
+	pub fn submit_error_kind(&self) -> SubmitErrorKind {
+		match self {
+			Error::TxFailed { codespace, code, .. }
+				if codespace == SDK_CODESPACE && RETRIABLE_TX_CODES.contains(code) =>
+				SubmitErrorKind::Retriable,
+			// the node or the remote signer couldn't be reached, or the transaction wasn't
+			// included in time.
+			Error::Rpc(_) | Error::Grpc(_) | Error::Io(_) | Error::TxTimeout(_) =>
+				SubmitErrorKind::Retriable,
+			_ => SubmitErrorKind::Fatal,
+		}
+	}
+
+	/// Whether the messages of a transaction needed more gas than it was given.
+	pub fn is_out_of_gas(&self) -> bool {
+		match self {
+			Error::TxFailed { codespace, code, .. } =>
+				codespace == SDK_CODESPACE && *code == OUT_OF_GAS_CODE,
+			Error::GasLimitExceeded { .. } => true,
+			_ => false,
+		}
+	}
+}
+
+impl From<String> for Error {
+	fn from(error: String) -> Self {
+		Self::Custom(error)
+	}
+}
@@ -0,0 +1,149 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion of the abci events emitted by ibc-go into [`IbcEvent`]s.
+
+use ibc::{
+	core::{
+		ics02_client::events::{
+			Attributes, ClientMisbehaviour, CreateClient, UpdateClient, UpgradeClient,
+		},
+		ics03_connection, ics04_channel,
+	},
+	events::IbcEvent,
+	Height,
+};
+use tendermint::abci::Event as AbciEvent;
+
+/// ibc-go names the event of a received packet differently from ibc-rs.
+const RECV_PACKET_EVENT: &str = "recv_packet";
+const RECEIVE_PACKET_EVENT: &str = "receive_packet";
+
+const CREATE_CLIENT_EVENT: &str = "create_client";
+const UPDATE_CLIENT_EVENT: &str = "update_client";
+const CLIENT_MISBEHAVIOUR_EVENT: &str = "client_misbehaviour";
+const UPGRADE_CLIENT_EVENT: &str = "upgrade_client";
+
+/// Converts an event emitted in the block at `height` into an [`IbcEvent`], returns `None` for
+/// events of other modules and ibc events that fail to parse.
+pub fn ibc_event_from_abci(event: &AbciEvent, height: Height) -> Option<IbcEvent> {
+	let mut ibc_event = match event.kind.as_str() {
+		CREATE_CLIENT_EVENT => IbcEvent::CreateClient(CreateClient(client_attributes(event)?)),
+		UPDATE_CLIENT_EVENT => {
+			let attributes = client_attributes(event)?;
+			let header = attribute(event, "header").and_then(|header| hex::decode(header).ok());
+			IbcEvent::UpdateClient(UpdateClient {
+				header_heights: vec![attributes.consensus_height],
+				header,
+				..UpdateClient::from(attributes)
+			})
+		},
+		CLIENT_MISBEHAVIOUR_EVENT =>
+			IbcEvent::ClientMisbehaviour(ClientMisbehaviour(client_attributes(event)?)),
+		UPGRADE_CLIENT_EVENT => IbcEvent::UpgradeClient(UpgradeClient(client_attributes(event)?)),
+		RECV_PACKET_EVENT => {
+			let event = AbciEvent { kind: RECEIVE_PACKET_EVENT.to_string(), ..event.clone() };
+			ics04_channel::events::try_from_tx(&event)?
+		},
+		_ => ics03_connection::events::try_from_tx(event)
+			.or_else(|| ics04_channel::events::try_from_tx(event))?,
+	};
+	ibc_event.set_height(height);
+	Some(ibc_event)
+}
+
+fn attribute<'a>(event: &'a AbciEvent, key: &str) -> Option<&'a str> {
+	event
+		.attributes
+		.iter()
+		.find(|attribute| attribute.key.as_str() == key)
+		.map(|attribute| attribute.value.as_str())
+}
+
+fn client_attributes(event: &AbciEvent) -> Option<Attributes> {
+	Some(Attributes {
+		height: Default::default(),
+		client_id: attribute(event, "client_id")?.parse().ok()?,
+		client_type: attribute(event, "client_type")?.to_string(),
+		consensus_height: attribute(event, "consensus_height")?.parse().ok()?,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tendermint::abci::EventAttribute;
+
+	fn abci_event(kind: &str, attributes: &[(&str, &str)]) -> AbciEvent {
+		AbciEvent {
+			kind: kind.to_string(),
+			attributes: attributes
+				.iter()
+				.map(|(key, value)| EventAttribute {
+					key: key.to_string(),
+					value: value.to_string(),
+					index: true,
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn parses_the_events_of_ibc_go() {
+		let height = Height::new(1, 20);
+		let update = abci_event(
+			UPDATE_CLIENT_EVENT,
+			&[
+				("client_id", "07-tendermint-0"),
+				("client_type", "07-tendermint"),
+				("consensus_height", "1-15"),
+				("header", "0a02"),
+			],
+		);
+		match ibc_event_from_abci(&update, height) {
+			Some(IbcEvent::UpdateClient(update)) => {
+				assert_eq!(update.client_id().as_str(), "07-tendermint-0");
+				assert_eq!(update.height(), height);
+				assert_eq!(update.header_heights(), &[Height::new(1, 15)]);
+				assert_eq!(update.header, Some(vec![0x0a, 0x02]));
+			},
+			event => panic!("Expected an update client event, found {event:?}"),
+		}
+
+		let recv = abci_event(
+			RECV_PACKET_EVENT,
+			&[
+				("packet_data", "{}"),
+				("packet_timeout_height", "0-100"),
+				("packet_timeout_timestamp", "0"),
+				("packet_sequence", "3"),
+				("packet_src_port", "transfer"),
+				("packet_src_channel", "channel-0"),
+				("packet_dst_port", "transfer"),
+				("packet_dst_channel", "channel-1"),
+				("packet_channel_ordering", "ORDER_UNORDERED"),
+			],
+		);
+		match ibc_event_from_abci(&recv, height) {
+			Some(IbcEvent::ReceivePacket(recv)) => {
+				assert_eq!(recv.height, height);
+				assert_eq!(u64::from(recv.packet.sequence), 3);
+				assert_eq!(recv.packet.destination_channel.to_string(), "channel-1");
+			},
+			event => panic!("Expected a receive packet event, found {event:?}"),
+		}
+
+		assert!(ibc_event_from_abci(&abci_event("transfer", &[]), height).is_none());
+	}
+}
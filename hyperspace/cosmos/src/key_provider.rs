@@ -0,0 +1,173 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::str::FromStr;
+
+use bip39::{Language, Mnemonic, Seed};
+use hmac::{Hmac, Mac, NewMac};
+use primitives::KeyProvider;
+use secp256k1::{Message, PublicKey, Scalar, SecretKey, SECP256K1};
+use sha2::{Digest, Sha256, Sha512};
+use sha3::Keccak256;
+
+use super::{
+	error::Error,
+	signer::{KeyType, TxSigner},
+	CosmosClient,
+};
+
+/// Marks an index of a derivation path as hardened.
+const HARDENED: u32 = 0x8000_0000;
+
+/// The standard derivation path of cosmos accounts, `m/44'/118'/0'/0/0`.
+const COSMOS_HD_PATH: [u32; 5] = [44 | HARDENED, 118 | HARDENED, HARDENED, 0, 0];
+
+/// The derivation path of the ethereum compatible accounts of ethermint chains,
+/// `m/44'/60'/0'/0/0`.
+const ETHERMINT_HD_PATH: [u32; 5] = [44 | HARDENED, 60 | HARDENED, HARDENED, 0, 0];
+
+/// A secp256k1 key derived from a BIP-39 mnemonic, held in memory.
+#[derive(Clone)]
+pub struct KeyEntry {
+	/// Compressed public key
+	pub public_key: PublicKey,
+	/// Private key
+	pub private_key: SecretKey,
+	/// Scheme of the key
+	pub key_type: KeyType,
+}
+
+impl KeyEntry {
+	/// Derives the key of the first account of `mnemonic` for `key_type`.
+	pub fn from_mnemonic(mnemonic: &str, key_type: KeyType) -> Result<Self, Error> {
+		let mnemonic = Mnemonic::from_phrase(mnemonic, Language::English)
+			.map_err(|e| Error::Custom(format!("Invalid mnemonic: {e}")))?;
+		let seed = Seed::new(&mnemonic, "");
+		let path = match key_type {
+			KeyType::Secp256k1 => COSMOS_HD_PATH,
+			KeyType::EthSecp256k1 => ETHERMINT_HD_PATH,
+		};
+		let private_key = derive_private_key(seed.as_bytes(), &path)?;
+		let public_key = PublicKey::from_secret_key(SECP256K1, &private_key);
+		Ok(Self { public_key, private_key, key_type })
+	}
+}
+
+#[async_trait::async_trait]
+impl TxSigner for KeyEntry {
+	async fn sign(&self, sign_doc: &[u8]) -> Result<Vec<u8>, Error> {
+		let message = Message::from_slice(&self.key_type.digest(sign_doc))
+			.map_err(|e| Error::Custom(format!("Invalid message digest: {e}")))?;
+		let signature = SECP256K1.sign_ecdsa(&message, &self.private_key);
+		Ok(signature.serialize_compact().to_vec())
+	}
+
+	fn public_key(&self) -> PublicKey {
+		self.public_key
+	}
+
+	fn key_type(&self) -> KeyType {
+		self.key_type
+	}
+}
+
+/// Bech32 encoded address of the account of `public_key`: `ripemd160(sha256(public_key))` for
+/// cosmos accounts, the last 20 bytes of the keccak256 digest of the uncompressed key for
+/// ethermint accounts.
+pub fn account_address(public_key: &PublicKey, key_type: KeyType, account_prefix: &str) -> String {
+	let address = match key_type {
+		KeyType::Secp256k1 => {
+			let sha256 = Sha256::digest(&public_key.serialize());
+			<ripemd::Ripemd160 as ripemd::Digest>::digest(&sha256).to_vec()
+		},
+		KeyType::EthSecp256k1 => {
+			// the uncompressed key without its `0x04` prefix.
+			let keccak256 =
+				<Keccak256 as sha3::Digest>::digest(&public_key.serialize_uncompressed()[1..]);
+			keccak256[12..].to_vec()
+		},
+	};
+	subtle_encoding::bech32::encode(account_prefix, address)
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> Result<([u8; 32], [u8; 32]), Error> {
+	let mut mac = Hmac::<Sha512>::new_from_slice(key)
+		.map_err(|e| Error::Custom(format!("Invalid hmac key: {e}")))?;
+	mac.update(data);
+	let output = mac.finalize().into_bytes();
+	let (mut left, mut right) = ([0u8; 32], [0u8; 32]);
+	left.copy_from_slice(&output[..32]);
+	right.copy_from_slice(&output[32..]);
+	Ok((left, right))
+}
+
+/// BIP-32 derivation of the private key at `path` from the master key of `seed`.
+fn derive_private_key(seed: &[u8], path: &[u32]) -> Result<SecretKey, Error> {
+	let invalid_key = |e| Error::Custom(format!("Invalid derived key: {e}"));
+	let (key, mut chain_code) = hmac_sha512(b"Bitcoin seed", seed)?;
+	let mut private_key = SecretKey::from_slice(&key).map_err(invalid_key)?;
+	for index in path {
+		let mut data = Vec::with_capacity(37);
+		if index & HARDENED != 0 {
+			data.push(0);
+			data.extend_from_slice(&private_key.secret_bytes());
+		} else {
+			let public_key = PublicKey::from_secret_key(SECP256K1, &private_key);
+			data.extend_from_slice(&public_key.serialize());
+		}
+		data.extend_from_slice(&index.to_be_bytes());
+		let (tweak, next_chain_code) = hmac_sha512(&chain_code, &data)?;
+		let tweak = Scalar::from_be_bytes(tweak)
+			.map_err(|_| Error::Custom("Derived key is out of range".to_string()))?;
+		private_key = private_key.add_tweak(&tweak).map_err(invalid_key)?;
+		chain_code = next_chain_code;
+	}
+	Ok(private_key)
+}
+
+impl KeyProvider for CosmosClient {
+	fn account_id(&self) -> ibc::signer::Signer {
+		ibc::signer::Signer::from_str(&self.account).expect("Account Id should be valid")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn derives_the_cosmos_account_of_a_mnemonic() {
+		// the first account of this mnemonic, as derived by `simd keys add --recover`.
+		let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+			abandon abandon about";
+		let key = KeyEntry::from_mnemonic(mnemonic, KeyType::Secp256k1).unwrap();
+		assert_eq!(
+			account_address(&key.public_key, key.key_type, "cosmos"),
+			"cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4"
+		);
+	}
+
+	#[test]
+	fn derives_the_ethermint_account_of_a_mnemonic() {
+		// the first ethereum account of this mnemonic,
+		// 0x9858EfFD232B4033E47d90003D41EC34EcaEda94.
+		let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+			abandon abandon about";
+		let key = KeyEntry::from_mnemonic(mnemonic, KeyType::EthSecp256k1).unwrap();
+		let account = account_address(&key.public_key, key.key_type, "evmos");
+		let (prefix, address) = subtle_encoding::bech32::decode(&account).unwrap();
+		assert_eq!(prefix, "evmos");
+		assert_eq!(hex::encode(address), "9858effd232b4033e47d90003d41ec34ecaeda94");
+	}
+}
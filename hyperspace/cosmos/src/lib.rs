@@ -11,3 +11,312 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+#![allow(clippy::all)]
+
+use std::{
+	sync::{atomic::AtomicU64, Arc},
+	time::Duration,
+};
+
+pub mod chain;
+pub mod error;
+pub mod events;
+pub mod key_provider;
+pub mod provider;
+pub mod signer;
+pub mod subscription;
+pub mod tx;
+
+#[cfg(any(test, feature = "testing"))]
+pub mod test_provider;
+
+use error::Error;
+use serde::{Deserialize, Serialize};
+
+use ibc::{
+	core::{
+		ics02_client::trust_threshold::TrustThreshold,
+		ics23_commitment::specs::ProofSpecs,
+		ics24_host::{
+			identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+			path::Path,
+		},
+	},
+	Height,
+};
+use ibc_proto::{
+	cosmos::staking::v1beta1::{
+		query_client::QueryClient as StakingQueryClient, QueryParamsRequest,
+	},
+	ibc::core::commitment::v1::MerkleProof,
+	ics23::CommitmentProof,
+};
+use ics07_tendermint::{
+	client_message::Header, client_state::ClientState as TendermintClientState,
+	consensus_state::ConsensusState as TendermintConsensusState,
+};
+use key_provider::account_address;
+use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
+use prost::Message;
+use signer::{SignerConfig, TxSigner};
+use tendermint::{block::Height as TmHeight, validator::Set as ValidatorSet};
+use tendermint_rpc::{Client, HttpClient, Paging};
+use tonic::transport::{Channel, Endpoint};
+
+/// Metadata key of the gRPC requests, selecting the height at which the chain is queried.
+const BLOCK_HEIGHT_METADATA_KEY: &str = "x-cosmos-block-height";
+
+/// Implements the [`crate::Chain`] trait for cosmos sdk chains running ibc-go.
+/// This is responsible for:
+/// 1. Tracking a tendermint light client on a counter-party chain, advancing this light
+/// client state as new blocks are committed.
+/// 2. Submiting new IBC messages to this chain in transactions.
+#[derive(Clone)]
+pub struct CosmosClient {
+	/// Chain name
+	pub name: String,
+	/// Tendermint rpc client
+	pub rpc_client: HttpClient,
+	/// Channel to the chain's gRPC endpoint
+	pub grpc_channel: Channel,
+	/// Tendermint rpc url
+	pub rpc_url: String,
+	/// Tendermint websocket url, subscriptions reconnect to it when their connection drops
+	pub websocket_url: String,
+	/// gRPC url
+	pub grpc_url: String,
+	/// Chain Id
+	pub chain_id: ChainId,
+	/// Light client id on counterparty chain
+	pub client_id: Option<ClientId>,
+	/// Connection Id
+	pub connection_id: Option<ConnectionId>,
+	/// ICS-23 provable store commitment prefix
+	pub commitment_prefix: Vec<u8>,
+	/// Bech32 prefix of the chain's account addresses
+	pub account_prefix: String,
+	/// Signer of the relayer's transactions
+	pub signer: Arc<dyn TxSigner>,
+	/// Bech32 encoded address of the relayer's account
+	pub account: String,
+	/// Denomination of the transaction fees
+	pub fee_denom: String,
+	/// Amount of `fee_denom` paid per unit of gas
+	pub gas_price: f64,
+	/// Factor applied to the gas used by the simulation of a transaction, for its gas limit
+	pub gas_multiplier: f64,
+	/// Most gas given to a transaction
+	pub max_gas: u64,
+	/// Channels cleared for packet relay
+	pub channel_whitelist: Vec<(ChannelId, PortId)>,
+	/// Number of times the event subscription was re-established
+	pub subscription_reconnects: Arc<AtomicU64>,
+}
+
+/// config options for [`CosmosClient`]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CosmosClientConfig {
+	/// Chain name
+	pub name: String,
+	/// Tendermint rpc url
+	pub rpc_url: String,
+	/// Tendermint websocket url, subscriptions reconnect to it when their connection drops
+	pub websocket_url: String,
+	/// gRPC url
+	pub grpc_url: String,
+	/// Chain Id
+	pub chain_id: String,
+	/// Light client id on counterparty chain
+	pub client_id: Option<ClientId>,
+	/// Connection Id
+	pub connection_id: Option<ConnectionId>,
+	/// Bech32 prefix of the chain's account addresses
+	pub account_prefix: String,
+	/// Name of the store holding the ibc state, used as the commitment prefix
+	#[serde(default = "default_store_prefix")]
+	pub store_prefix: String,
+	/// Key signing the relayer's transactions, either derived from a mnemonic or held by a
+	/// remote signer, e.g. `signer = { type = "kms", endpoint = "unix:///run/signer.sock" }`
+	pub signer: SignerConfig,
+	/// Denomination of the transaction fees
+	pub fee_denom: String,
+	/// Amount of `fee_denom` paid per unit of gas
+	pub gas_price: f64,
+	/// Factor applied to the gas used by the simulation of a transaction, for its gas limit
+	#[serde(default = "default_gas_multiplier")]
+	pub gas_multiplier: f64,
+	/// Most gas given to a transaction, batches of messages needing more are split
+	#[serde(default = "default_max_gas")]
+	pub max_gas: u64,
+	/// Channels cleared for packet relay
+	pub channel_whitelist: Vec<(ChannelId, PortId)>,
+}
+
+fn default_store_prefix() -> String {
+	"ibc".to_string()
+}
+
+fn default_gas_multiplier() -> f64 {
+	1.1
+}
+
+fn default_max_gas() -> u64 {
+	4_000_000
+}
+
+impl CosmosClient {
+	/// Initializes a [`CosmosClient`] given a [`CosmosClientConfig`]
+	pub async fn new(config: CosmosClientConfig) -> Result<Self, Error> {
+		let rpc_client = HttpClient::new(config.rpc_url.as_str())?;
+		let grpc_channel = Endpoint::from_shared(config.grpc_url.clone())
+			.map_err(|e| Error::Custom(format!("Invalid gRPC url: {e}")))?
+			.connect_lazy();
+		let signer = signer::connect(config.signer, &config.chain_id).await?;
+		let account =
+			account_address(&signer.public_key(), signer.key_type(), &config.account_prefix);
+
+		Ok(Self {
+			name: config.name,
+			rpc_client,
+			grpc_channel,
+			rpc_url: config.rpc_url,
+			websocket_url: config.websocket_url,
+			grpc_url: config.grpc_url,
+			chain_id: ChainId::from_string(&config.chain_id),
+			client_id: config.client_id,
+			connection_id: config.connection_id,
+			commitment_prefix: config.store_prefix.into_bytes(),
+			account_prefix: config.account_prefix,
+			signer,
+			account,
+			fee_denom: config.fee_denom,
+			gas_price: config.gas_price,
+			gas_multiplier: config.gas_multiplier,
+			max_gas: config.max_gas,
+			channel_whitelist: config.channel_whitelist,
+			subscription_reconnects: Arc::new(AtomicU64::new(0)),
+		})
+	}
+
+	pub fn client_id(&self) -> ClientId {
+		self.client_id.as_ref().expect("Client Id should be defined").clone()
+	}
+
+	/// Height of this chain's block `height`, in its current revision.
+	pub fn height(&self, height: u64) -> Height {
+		Height::new(self.chain_id.version(), height)
+	}
+
+	/// Wraps a gRPC query of the chain state at `at`.
+	pub fn request<T>(&self, at: Height, message: T) -> tonic::Request<T> {
+		let mut request = tonic::Request::new(message);
+		let height = at.revision_height.to_string().parse().expect("Height is valid metadata");
+		request.metadata_mut().insert(BLOCK_HEIGHT_METADATA_KEY, height);
+		request
+	}
+
+	/// Queries the value stored at `path` in the ibc store, along with its proof. The proof
+	/// verifies against the app hash of the block at `at`, which commits to the state before it
+	/// was executed. The value is empty if nothing is stored at `path`.
+	pub async fn query_path(
+		&self,
+		at: Height,
+		path: impl Into<Path>,
+	) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		self.query_key(at, path.into().to_string().into_bytes()).await
+	}
+
+	/// Queries the value stored under `key` in the ibc store, along with its proof, see
+	/// [`CosmosClient::query_path`].
+	pub async fn query_key(&self, at: Height, key: Vec<u8>) -> Result<(Vec<u8>, Vec<u8>), Error> {
+		let store = String::from_utf8(self.commitment_prefix.clone())
+			.map_err(|_| Error::Custom("Commitment prefix isn't a store name".to_string()))?;
+		let height = TmHeight::try_from(at.revision_height.saturating_sub(1))?;
+		let response = self
+			.rpc_client
+			.abci_query(
+				Some(format!("store/{store}/key").parse().expect("Path is valid")),
+				key,
+				Some(height),
+				true,
+			)
+			.await?;
+		if response.code.is_err() {
+			return Err(Error::Custom(format!("Abci query failed: {}", response.log)))
+		}
+		let proof_ops = response
+			.proof
+			.ok_or_else(|| Error::Custom("Abci query returned no proof".to_string()))?;
+		let proofs = proof_ops
+			.ops
+			.into_iter()
+			.map(|op| CommitmentProof::decode(op.data.as_slice()))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok((response.value, MerkleProof { proofs }.encode_to_vec()))
+	}
+
+	/// Validators that signed the block at `height`, along with the block's proposer.
+	pub async fn query_validator_set(&self, height: u64) -> Result<ValidatorSet, Error> {
+		let height = TmHeight::try_from(height)?;
+		let validators = self.rpc_client.validators(height, Paging::All).await?.validators;
+		let proposer = self.rpc_client.commit(height).await?.signed_header.header.proposer_address;
+		Ok(ValidatorSet::with_proposer(validators, proposer)?)
+	}
+
+	/// Header of the block at `height`, for a light client that trusts the block at
+	/// `trusted_height`.
+	pub async fn query_header(&self, height: u64, trusted_height: Height) -> Result<Header, Error> {
+		let signed_header =
+			self.rpc_client.commit(TmHeight::try_from(height)?).await?.signed_header;
+		Ok(Header {
+			signed_header,
+			validator_set: self.query_validator_set(height).await?,
+			trusted_height,
+			// the light client checks the header against the next validators of the trusted block.
+			trusted_validator_set: self
+				.query_validator_set(trusted_height.revision_height + 1)
+				.await?,
+		})
+	}
+
+	/// Client and consensus state of a tendermint light client of this chain, trusting its
+	/// latest block.
+	pub async fn construct_tendermint_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Error> {
+		let mut staking = StakingQueryClient::new(self.grpc_channel.clone());
+		let unbonding_time = staking
+			.params(QueryParamsRequest {})
+			.await?
+			.into_inner()
+			.params
+			.and_then(|params| params.unbonding_time)
+			.ok_or_else(|| Error::Custom("Staking params have no unbonding time".to_string()))?;
+		let unbonding_period =
+			Duration::new(unbonding_time.seconds as u64, unbonding_time.nanos as u32);
+		// trust headers for two thirds of the unbonding period, as other relayers do.
+		let trusting_period = unbonding_period * 2 / 3;
+
+		let status = self.rpc_client.status().await?;
+		let latest_height = status.sync_info.latest_block_height;
+		let header = self.rpc_client.commit(latest_height).await?.signed_header.header;
+		let client_state = TendermintClientState::<HostFunctionsManager>::new(
+			self.chain_id.clone(),
+			TrustThreshold::ONE_THIRD,
+			trusting_period,
+			unbonding_period,
+			Duration::from_secs(10),
+			self.height(latest_height.value()),
+			ProofSpecs::cosmos(),
+			vec!["upgrade".to_string(), "upgradedIBCState".to_string()],
+		)
+		.map_err(|e| Error::Custom(format!("Invalid client state: {e:?}")))?;
+		let consensus_state = TendermintConsensusState::from(header);
+
+		Ok((
+			AnyClientState::Tendermint(client_state),
+			AnyConsensusState::Tendermint(consensus_state),
+		))
+	}
+}
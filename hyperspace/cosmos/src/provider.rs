@@ -0,0 +1,853 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{error::Error, CosmosClient};
+use crate::{chain::FinalityEvent, events::ibc_event_from_abci, tx::TransactionId};
+use anyhow::anyhow;
+use futures::Stream;
+use ibc::{
+	applications::{
+		fee::PacketFee,
+		transfer::{Amount, PrefixedCoin, PrefixedDenom},
+	},
+	core::{
+		ics02_client::client_state::{ClientStatus, ClientType},
+		ics04_channel::{self, packet::Packet},
+		ics23_commitment::commitment::CommitmentPrefix,
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
+				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqAcksPath, SeqRecvsPath,
+			},
+		},
+	},
+	events::IbcEvent,
+	timestamp::Timestamp,
+	Height,
+};
+use ibc_proto::{
+	cosmos::{
+		bank::v1beta1::{query_client::QueryClient as BankQueryClient, QueryAllBalancesRequest},
+		base::query::v1beta1::PageRequest,
+	},
+	google::protobuf::Any,
+	ibc::{
+		applications::{
+			fee::v1::{query_client::QueryClient as FeeQueryClient, QueryIncentivizedPacketRequest},
+			transfer::v1::{
+				query_client::QueryClient as TransferQueryClient, QueryDenomTraceRequest,
+			},
+		},
+		core::{
+			channel::v1::{
+				query_client::QueryClient as ChannelQueryClient, Channel, PacketId,
+				QueryChannelResponse, QueryChannelsRequest, QueryChannelsResponse,
+				QueryConnectionChannelsRequest, QueryNextSequenceReceiveResponse,
+				QueryPacketAcknowledgementResponse, QueryPacketAcknowledgementsRequest,
+				QueryPacketCommitmentResponse, QueryPacketCommitmentsRequest,
+				QueryPacketReceiptResponse, QueryUnreceivedAcksRequest,
+				QueryUnreceivedPacketsRequest,
+			},
+			client::v1::{
+				query_client::QueryClient as ClientQueryClient, QueryClientStateResponse,
+				QueryClientStatesRequest, QueryClientStatusRequest, QueryConsensusStateResponse,
+			},
+			connection::v1::{
+				query_client::QueryClient as ConnectionQueryClient, ConnectionEnd,
+				IdentifiedConnection, QueryClientConnectionsRequest, QueryConnectionRequest,
+				QueryConnectionResponse, QueryConnectionsRequest,
+			},
+		},
+	},
+};
+use ibc_rpc::{PacketInfo, QueryNextSequenceAckResponse};
+use ics07_tendermint::{
+	client_message::ClientMessage, client_state::ClientState as TendermintClientState,
+};
+use pallet_ibc::light_clients::{
+	AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager,
+};
+use primitives::{
+	query_maximum_height_for_timeout_proofs, update_client_msg, Chain, IbcProvider, UpdateType,
+};
+use prost::Message;
+use std::{pin::Pin, str::FromStr, time::Duration};
+use tendermint::{abci::Event as AbciEvent, block::Height as TmHeight};
+use tendermint_rpc::{query::Query, Client, Order};
+
+/// Requests every item of a paginated gRPC query at once.
+fn all_pages() -> Option<PageRequest> {
+	Some(PageRequest { limit: u64::MAX, ..Default::default() })
+}
+
+/// Decodes a sequence number stored in the ibc store, which is absent until it's first set.
+fn decode_sequence(value: &[u8]) -> Result<u64, Error> {
+	let bytes = <[u8; 8]>::try_from(value)
+		.map_err(|_| Error::Custom(format!("Invalid sequence: {}", hex::encode(value))))?;
+	Ok(u64::from_be_bytes(bytes))
+}
+
+fn has_attribute(event: &AbciEvent, key: &str, value: &str) -> bool {
+	event.attributes.iter().any(|attribute| attribute.key == key && attribute.value == value)
+}
+
+fn packet_info(
+	height: u64,
+	packet: Packet,
+	channel_order: String,
+	ack: Option<Vec<u8>>,
+) -> PacketInfo {
+	PacketInfo {
+		height,
+		sequence: packet.sequence.into(),
+		source_port: packet.source_port.to_string(),
+		source_channel: packet.source_channel.to_string(),
+		destination_port: packet.destination_port.to_string(),
+		destination_channel: packet.destination_channel.to_string(),
+		channel_order,
+		data: packet.data,
+		timeout_height: packet.timeout_height.into(),
+		timeout_timestamp: packet.timeout_timestamp.nanoseconds(),
+		ack,
+	}
+}
+
+impl CosmosClient {
+	/// The ibc events emitted in the block at `height`, by its transactions that succeeded and
+	/// while beginning and ending it.
+	pub async fn query_block_events(&self, height: u64) -> Result<Vec<IbcEvent>, Error> {
+		let response = self.rpc_client.block_results(TmHeight::try_from(height)?).await?;
+		let tx_events = response
+			.txs_results
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|tx| tx.code.is_ok())
+			.flat_map(|tx| tx.events);
+		let events = response
+			.begin_block_events
+			.unwrap_or_default()
+			.into_iter()
+			.chain(tx_events)
+			.chain(response.end_block_events.unwrap_or_default());
+		let height = self.height(height);
+		Ok(events.filter_map(|event| ibc_event_from_abci(&event, height)).collect())
+	}
+
+	/// Finds the `kind` event of the packet with `sequence` on a channel end of this chain, which
+	/// is the packet's source or destination depending on `side`, along with the height of the
+	/// block that emitted it.
+	async fn query_packet_event(
+		&self,
+		kind: &str,
+		side: &str,
+		channel_id: ChannelId,
+		port_id: &PortId,
+		sequence: u64,
+	) -> Result<Option<(u64, AbciEvent)>, Error> {
+		let channel_key = format!("packet_{side}_channel");
+		let port_key = format!("packet_{side}_port");
+		let (channel_id, port_id, sequence) =
+			(channel_id.to_string(), port_id.to_string(), sequence.to_string());
+		let query = Query::eq(format!("{kind}.{channel_key}"), channel_id.clone())
+			.and_eq(format!("{kind}.{port_key}"), port_id.clone())
+			.and_eq(format!("{kind}.packet_sequence"), sequence.clone());
+		let response = self.rpc_client.tx_search(query, false, 1, 1, Order::Ascending).await?;
+		Ok(response.txs.into_iter().find_map(|tx| {
+			let height = tx.height.value();
+			tx.tx_result
+				.events
+				.into_iter()
+				.find(|event| {
+					event.kind == kind &&
+						has_attribute(event, &channel_key, &channel_id) &&
+						has_attribute(event, &port_key, &port_id) &&
+						has_attribute(event, "packet_sequence", &sequence)
+				})
+				.map(|event| (height, event))
+		}))
+	}
+
+	/// Height of the block that included the first transaction emitting an event of `kind` with
+	/// all the given attributes.
+	async fn query_event_height(
+		&self,
+		kind: &str,
+		attributes: &[(&str, String)],
+	) -> Result<Option<u64>, Error> {
+		let mut attributes = attributes.iter();
+		let (key, value) = attributes.next().expect("At least one attribute is given");
+		let query = attributes
+			.fold(Query::eq(format!("{kind}.{key}"), value.clone()), |query, (key, value)| {
+				query.and_eq(format!("{kind}.{key}"), value.clone())
+			});
+		let response = self.rpc_client.tx_search(query, false, 1, 1, Order::Ascending).await?;
+		Ok(response.txs.first().map(|tx| tx.height.value()))
+	}
+}
+
+#[async_trait::async_trait]
+impl IbcProvider for CosmosClient {
+	type FinalityEvent = FinalityEvent;
+	type TransactionId = TransactionId;
+	type Error = Error;
+
+	async fn query_latest_ibc_events<C>(
+		&mut self,
+		finality_event: Self::FinalityEvent,
+		counterparty: &C,
+	) -> Result<(Any, Vec<IbcEvent>, UpdateType), anyhow::Error>
+	where
+		C: Chain,
+	{
+		let client_id = self.client_id();
+		let latest_height = counterparty.latest_height_and_timestamp().await?.0;
+		let response = counterparty.query_client_state(latest_height, client_id.clone()).await?;
+		let client_state = response.client_state.ok_or_else(|| {
+			Error::Custom("Received an empty client state from counterparty".to_string())
+		})?;
+		let client_state = AnyClientState::try_from(client_state)
+			.map_err(|_| Error::Custom("Failed to decode client state".to_string()))?;
+		let client_state = match client_state {
+			AnyClientState::Tendermint(client_state) => client_state,
+			c => Err(Error::Custom(format!("Expected AnyClientState::Tendermint found: {c:?}")))?,
+		};
+
+		let latest_client_height = client_state.latest_height.revision_height;
+		let target_height = finality_event.height;
+		if target_height <= latest_client_height {
+			Err(anyhow!(
+				"skipping outdated block: {}, with latest client height: {}",
+				target_height,
+				latest_client_height
+			))?
+		}
+
+		// the events of a block are proven by the app hash of the next block, so the header at
+		// `target_height` proves the events of the blocks before it.
+		let heights = latest_client_height..target_height;
+		log::info!("Fetching events from {} for blocks {:?}", self.name, heights);
+		let mut events = vec![];
+		for height in heights {
+			events.extend(self.query_block_events(height).await?);
+		}
+
+		let max_height_for_timeouts =
+			query_maximum_height_for_timeout_proofs(counterparty, self).await;
+		let timeout_update_required = max_height_for_timeouts
+			.map(|height| height > latest_client_height && height <= target_height)
+			.unwrap_or(false);
+		let is_update_required = self.is_update_required(target_height, latest_client_height);
+
+		let header = self.query_header(target_height, client_state.latest_height).await?;
+		// the light client has to see the blocks where the validator set changes.
+		let validators_changed = header.signed_header.header.validators_hash !=
+			header.signed_header.header.next_validators_hash;
+		let update_type =
+			match validators_changed || timeout_update_required || is_update_required {
+				true => UpdateType::Mandatory,
+				false => UpdateType::Optional,
+			};
+
+		let client_message = AnyClientMessage::Tendermint(ClientMessage::Header(header));
+		let update_header = update_client_msg(counterparty, client_id, client_message);
+
+		Ok((update_header, events, update_type))
+	}
+
+	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
+		Box::pin(self.subscribe_ibc_events(true))
+	}
+
+	async fn query_client_consensus(
+		&self,
+		at: Height,
+		client_id: ClientId,
+		consensus_height: Height,
+	) -> Result<QueryConsensusStateResponse, Self::Error> {
+		let path = ClientConsensusStatePath {
+			client_id: client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let (value, proof) = self.query_path(at, path).await?;
+		if value.is_empty() {
+			return Err(Error::Custom(format!(
+				"Consensus state of {client_id} at {consensus_height} not found"
+			)))
+		}
+		Ok(QueryConsensusStateResponse {
+			consensus_state: Some(Any::decode(value.as_slice())?),
+			proof,
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_client_state(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<QueryClientStateResponse, Self::Error> {
+		let (value, proof) = self.query_path(at, ClientStatePath(client_id.clone())).await?;
+		if value.is_empty() {
+			return Err(Error::Custom(format!("Client state of {client_id} not found")))
+		}
+		Ok(QueryClientStateResponse {
+			client_state: Some(Any::decode(value.as_slice())?),
+			proof,
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_client_status(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<ClientStatus, Self::Error> {
+		let mut client = ClientQueryClient::new(self.grpc_channel.clone());
+		let request = QueryClientStatusRequest { client_id: client_id.to_string() };
+		let status = client.client_status(self.request(at, request)).await?.into_inner().status;
+		match status.as_str() {
+			"Active" => Ok(ClientStatus::Active),
+			"Expired" => Ok(ClientStatus::Expired),
+			"Frozen" => Ok(ClientStatus::Frozen),
+			status => Err(Error::Custom(format!("Unknown status {status} of client {client_id}"))),
+		}
+	}
+
+	async fn query_connection_end(
+		&self,
+		at: Height,
+		connection_id: ConnectionId,
+	) -> Result<QueryConnectionResponse, Self::Error> {
+		let (value, proof) = self.query_path(at, ConnectionsPath(connection_id)).await?;
+		let connection =
+			(!value.is_empty()).then(|| ConnectionEnd::decode(value.as_slice())).transpose()?;
+		Ok(QueryConnectionResponse { connection, proof, proof_height: Some(at.into()) })
+	}
+
+	async fn query_channel_end(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryChannelResponse, Self::Error> {
+		let (value, proof) = self.query_path(at, ChannelEndsPath(port_id, channel_id)).await?;
+		let channel = (!value.is_empty()).then(|| Channel::decode(value.as_slice())).transpose()?;
+		Ok(QueryChannelResponse { channel, proof, proof_height: Some(at.into()) })
+	}
+
+	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
+		let key = keys
+			.into_iter()
+			.next()
+			.ok_or_else(|| Error::Custom("No key to query a proof for".to_string()))?;
+		// keys are prefixed with the name of the store they're queried from.
+		let key = key
+			.strip_prefix(self.commitment_prefix.as_slice())
+			.ok_or_else(|| Error::Custom("Key isn't in the ibc store".to_string()))?
+			.to_vec();
+		let (_, proof) = self.query_key(at, key).await?;
+		Ok(proof)
+	}
+
+	async fn query_proofs(
+		&self,
+		at: Height,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Vec<u8>>, Self::Error> {
+		// abci queries prove a single key.
+		let mut proofs = Vec::with_capacity(keys.len());
+		for key in keys {
+			proofs.push(self.query_proof(at, vec![key]).await?);
+		}
+		Ok(proofs)
+	}
+
+	async fn query_packet_commitment(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
+		let path = CommitmentsPath {
+			port_id: port_id.clone(),
+			channel_id: *channel_id,
+			sequence: seq.into(),
+		};
+		let (commitment, proof) = self.query_path(at, path).await?;
+		Ok(QueryPacketCommitmentResponse { commitment, proof, proof_height: Some(at.into()) })
+	}
+
+	async fn query_packet_acknowledgement(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
+		let path =
+			AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence: seq.into() };
+		let (acknowledgement, proof) = self.query_path(at, path).await?;
+		Ok(QueryPacketAcknowledgementResponse {
+			acknowledgement,
+			proof,
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_next_sequence_recv(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
+		let path = SeqRecvsPath(port_id.clone(), *channel_id);
+		let (value, proof) = self.query_path(at, path).await?;
+		Ok(QueryNextSequenceReceiveResponse {
+			next_sequence_receive: decode_sequence(&value)?,
+			proof,
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_next_sequence_ack(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceAckResponse, Self::Error> {
+		let path = SeqAcksPath(port_id.clone(), *channel_id);
+		let (value, proof) = self.query_path(at, path).await?;
+		Ok(QueryNextSequenceAckResponse {
+			next_sequence_ack: decode_sequence(&value)?,
+			proof,
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn query_packet_receipt(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketReceiptResponse, Self::Error> {
+		let path = ReceiptsPath {
+			port_id: port_id.clone(),
+			channel_id: *channel_id,
+			sequence: seq.into(),
+		};
+		let (receipt, proof) = self.query_path(at, path).await?;
+		Ok(QueryPacketReceiptResponse {
+			received: !receipt.is_empty(),
+			proof,
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		let status = self.rpc_client.status().await?;
+		let height = self.height(status.sync_info.latest_block_height.value());
+		Ok((height, status.sync_info.latest_block_time.into()))
+	}
+
+	async fn query_packet_commitments(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		let mut client = ChannelQueryClient::new(self.grpc_channel.clone());
+		let request = QueryPacketCommitmentsRequest {
+			port_id: port_id.to_string(),
+			channel_id: channel_id.to_string(),
+			pagination: all_pages(),
+		};
+		let response = client.packet_commitments(self.request(at, request)).await?.into_inner();
+		Ok(response.commitments.into_iter().map(|packet_state| packet_state.sequence).collect())
+	}
+
+	async fn query_packet_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Option<Vec<u64>>,
+	) -> Result<Vec<u64>, Self::Error> {
+		let mut client = ChannelQueryClient::new(self.grpc_channel.clone());
+		let request = QueryPacketAcknowledgementsRequest {
+			port_id: port_id.to_string(),
+			channel_id: channel_id.to_string(),
+			pagination: all_pages(),
+			packet_commitment_sequences: seqs.unwrap_or_default(),
+		};
+		let response =
+			client.packet_acknowledgements(self.request(at, request)).await?.into_inner();
+		Ok(response
+			.acknowledgements
+			.into_iter()
+			.map(|packet_state| packet_state.sequence)
+			.collect())
+	}
+
+	async fn query_unreceived_packets(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		let mut client = ChannelQueryClient::new(self.grpc_channel.clone());
+		let request = QueryUnreceivedPacketsRequest {
+			port_id: port_id.to_string(),
+			channel_id: channel_id.to_string(),
+			packet_commitment_sequences: seqs,
+		};
+		let response = client.unreceived_packets(self.request(at, request)).await?.into_inner();
+		Ok(response.sequences)
+	}
+
+	async fn query_unreceived_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		let mut client = ChannelQueryClient::new(self.grpc_channel.clone());
+		let request = QueryUnreceivedAcksRequest {
+			port_id: port_id.to_string(),
+			channel_id: channel_id.to_string(),
+			packet_ack_sequences: seqs,
+		};
+		let response = client.unreceived_acks(self.request(at, request)).await?.into_inner();
+		Ok(response.sequences)
+	}
+
+	fn channel_whitelist(&self) -> Vec<(ChannelId, PortId)> {
+		self.channel_whitelist.clone()
+	}
+
+	async fn query_connection_channels(
+		&self,
+		at: Height,
+		connection_id: &ConnectionId,
+	) -> Result<QueryChannelsResponse, Self::Error> {
+		let mut client = ChannelQueryClient::new(self.grpc_channel.clone());
+		let request = QueryConnectionChannelsRequest {
+			connection: connection_id.to_string(),
+			pagination: all_pages(),
+		};
+		let response = client.connection_channels(self.request(at, request)).await?.into_inner();
+		Ok(QueryChannelsResponse {
+			channels: response.channels,
+			pagination: response.pagination,
+			height: response.height,
+		})
+	}
+
+	async fn query_send_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		let mut packets = Vec::with_capacity(seqs.len());
+		for seq in seqs {
+			let (height, event) = match self
+				.query_packet_event("send_packet", "src", channel_id, &port_id, seq)
+				.await?
+			{
+				Some(found) => found,
+				None => continue,
+			};
+			let channel_order = event
+				.attributes
+				.iter()
+				.find(|attribute| attribute.key == "packet_channel_ordering")
+				.map(|attribute| attribute.value.clone())
+				.unwrap_or_default();
+			if let Some(IbcEvent::SendPacket(send_packet)) =
+				ics04_channel::events::try_from_tx(&event)
+			{
+				packets.push(packet_info(height, send_packet.packet, channel_order, None));
+			}
+		}
+		Ok(packets)
+	}
+
+	async fn query_recv_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		let mut packets = Vec::with_capacity(seqs.len());
+		for seq in seqs {
+			let (height, event) = match self
+				.query_packet_event("write_acknowledgement", "dst", channel_id, &port_id, seq)
+				.await?
+			{
+				Some(found) => found,
+				None => continue,
+			};
+			if let Some(IbcEvent::WriteAcknowledgement(write_ack)) =
+				ics04_channel::events::try_from_tx(&event)
+			{
+				let ack = Some(write_ack.ack);
+				packets.push(packet_info(height, write_ack.packet, String::new(), ack));
+			}
+		}
+		Ok(packets)
+	}
+
+	async fn query_packet_fees(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seq: u64,
+	) -> Result<Vec<PacketFee>, Self::Error> {
+		let mut client = FeeQueryClient::new(self.grpc_channel.clone());
+		let request = QueryIncentivizedPacketRequest {
+			packet_id: Some(PacketId {
+				port_id: port_id.to_string(),
+				channel_id: channel_id.to_string(),
+				sequence: seq,
+			}),
+			query_height: at.revision_height,
+		};
+		let response = match client.incentivized_packet(request).await {
+			Ok(response) => response.into_inner(),
+			// the chain doesn't run the fee middleware, or no fees were escrowed for the packet.
+			Err(status)
+				if matches!(status.code(), tonic::Code::Unimplemented | tonic::Code::NotFound) =>
+				return Ok(vec![]),
+			Err(status) => return Err(status.into()),
+		};
+		response
+			.incentivized_packet
+			.map(|packet| packet.packet_fees)
+			.unwrap_or_default()
+			.into_iter()
+			.map(|fee| {
+				PacketFee::try_from(fee)
+					.map_err(|e| Error::Custom(format!("Invalid packet fee: {e:?}")))
+			})
+			.collect()
+	}
+
+	fn expected_block_time(&self) -> Duration {
+		// Cosmos sdk chains have an expected block time of around 6 seconds
+		Duration::from_secs(6)
+	}
+
+	async fn query_client_update_time_and_height(
+		&self,
+		client_id: ClientId,
+		client_height: Height,
+	) -> Result<(Height, Timestamp), Self::Error> {
+		let attributes =
+			[("client_id", client_id.to_string()), ("consensus_height", client_height.to_string())];
+		let height = match self.query_event_height("update_client", &attributes).await? {
+			Some(height) => height,
+			// the first consensus state of a client is written when it's created.
+			None => self.query_event_height("create_client", &attributes).await?.ok_or_else(|| {
+				Error::Custom(format!("No update of {client_id} to {client_height} found"))
+			})?,
+		};
+		let commit = self.rpc_client.commit(TmHeight::try_from(height)?).await?;
+		Ok((self.height(height), commit.signed_header.header.time.into()))
+	}
+
+	async fn query_host_consensus_state_proof(
+		&self,
+		_height: Height,
+	) -> Result<Option<Vec<u8>>, Self::Error> {
+		// tendermint light clients check the consensus state of a cosmos chain by its height.
+		Ok(None)
+	}
+
+	async fn query_ibc_balance(&self) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		let mut bank = BankQueryClient::new(self.grpc_channel.clone());
+		let request = QueryAllBalancesRequest {
+			address: self.account.clone(),
+			pagination: all_pages(),
+		};
+		let balances = bank.all_balances(request).await?.into_inner().balances;
+		let mut transfer = TransferQueryClient::new(self.grpc_channel.clone());
+		let mut coins = Vec::with_capacity(balances.len());
+		for coin in balances {
+			// vouchers of tokens received over ibc are denominated by the hash of their trace.
+			let denom = match coin.denom.strip_prefix("ibc/") {
+				Some(hash) => {
+					let request = QueryDenomTraceRequest { hash: hash.to_string() };
+					let trace =
+						transfer.denom_trace(request).await?.into_inner().denom_trace.ok_or_else(
+							|| Error::Custom(format!("Denom trace of {} not found", coin.denom)),
+						)?;
+					PrefixedDenom::try_from(trace)?
+				},
+				None => PrefixedDenom::from_str(&coin.denom)?,
+			};
+			coins.push(PrefixedCoin { denom, amount: Amount::from_str(&coin.amount)? });
+		}
+		Ok(coins)
+	}
+
+	fn connection_prefix(&self) -> CommitmentPrefix {
+		CommitmentPrefix::try_from(self.commitment_prefix.clone()).expect("Should not fail")
+	}
+
+	fn client_id(&self) -> ClientId {
+		self.client_id()
+	}
+
+	fn connection_id(&self) -> ConnectionId {
+		self.connection_id.as_ref().expect("Connection id should be defined").clone()
+	}
+
+	fn client_type(&self) -> ClientType {
+		TendermintClientState::<HostFunctionsManager>::client_type()
+	}
+
+	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		let header =
+			self.rpc_client.commit(TmHeight::try_from(block_number)?).await?.signed_header.header;
+		Ok(Timestamp::from(header.time).nanoseconds())
+	}
+
+	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
+		let mut client = ClientQueryClient::new(self.grpc_channel.clone());
+		let request = QueryClientStatesRequest { pagination: all_pages() };
+		let response = client.client_states(request).await?.into_inner();
+		response
+			.client_states
+			.into_iter()
+			.map(|client| {
+				ClientId::from_str(&client.client_id)
+					.map_err(|_| Error::Custom("Invalid client id ".to_string()))
+			})
+			.collect()
+	}
+
+	async fn query_connections(&self) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		let mut client = ConnectionQueryClient::new(self.grpc_channel.clone());
+		let request = QueryConnectionsRequest { pagination: all_pages() };
+		Ok(client.connections(request).await?.into_inner().connections)
+	}
+
+	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
+		let mut client = ChannelQueryClient::new(self.grpc_channel.clone());
+		let request = QueryChannelsRequest { pagination: all_pages() };
+		let response = client.channels(request).await?.into_inner();
+		response
+			.channels
+			.into_iter()
+			.map(|identified_chan| {
+				Ok((
+					ChannelId::from_str(&identified_chan.channel_id)
+						.expect("Failed to convert invalid string to channel id"),
+					PortId::from_str(&identified_chan.port_id)
+						.expect("Failed to convert invalid string to port id"),
+				))
+			})
+			.collect::<Result<Vec<_>, _>>()
+	}
+
+	async fn query_connection_using_client(
+		&self,
+		height: u32,
+		client_id: String,
+	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		let at = self.height(height.into());
+		let mut client = ConnectionQueryClient::new(self.grpc_channel.clone());
+		let request = QueryClientConnectionsRequest { client_id };
+		let response = client.client_connections(self.request(at, request)).await?;
+		let connection_ids = response.into_inner().connection_paths;
+		let mut connections = Vec::with_capacity(connection_ids.len());
+		for connection_id in connection_ids {
+			let request = QueryConnectionRequest { connection_id: connection_id.clone() };
+			let connection = client.connection(self.request(at, request)).await?.into_inner();
+			if let Some(connection) = connection.connection {
+				connections.push(IdentifiedConnection {
+					id: connection_id,
+					client_id: connection.client_id,
+					versions: connection.versions,
+					state: connection.state,
+					counterparty: connection.counterparty,
+					delay_period: connection.delay_period,
+				});
+			}
+		}
+		Ok(connections)
+	}
+
+	fn is_update_required(
+		&self,
+		latest_height: u64,
+		latest_client_height_on_counterparty: u64,
+	) -> bool {
+		let refresh_period: u64 = if cfg!(feature = "testing") { 15 } else { 50 };
+		latest_height - latest_client_height_on_counterparty >= refresh_period
+	}
+
+	async fn initialize_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		self.construct_tendermint_client_state().await
+	}
+
+	async fn query_client_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ClientId, Self::Error> {
+		let response = self.rpc_client.tx(tx_id.hash, false).await?;
+		let client_id = response
+			.tx_result
+			.events
+			.iter()
+			.filter(|event| event.kind == "create_client")
+			.flat_map(|event| event.attributes.iter())
+			.find(|attribute| attribute.key == "client_id")
+			.ok_or_else(|| Error::Custom(format!("No client created by {}", tx_id.hash)))?;
+		ClientId::from_str(&client_id.value)
+			.map_err(|_| Error::Custom("Invalid client id ".to_string()))
+	}
+
+	async fn query_tx_block_events(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<Vec<IbcEvent>, Self::Error> {
+		self.query_block_events(tx_id.height).await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decodes_the_sequences_stored_by_ibc_go() {
+		// ibc-go stores the next sequences as big endian u64s.
+		assert_eq!(decode_sequence(&7u64.to_be_bytes()).unwrap(), 7);
+		assert!(decode_sequence(&[]).is_err());
+		assert!(decode_sequence(&[0, 1]).is_err());
+
+		let path = SeqAcksPath(PortId::transfer(), ChannelId::new(3));
+		assert_eq!(path.to_string(), "nextSequenceAck/ports/transfer/channels/channel-3");
+	}
+}
@@ -0,0 +1,391 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Signers of the relayer's transactions, holding its key in memory or asking a remote signer.
+
+use std::{
+	path::{Path, PathBuf},
+	sync::Arc,
+};
+
+use prost::Message;
+use secp256k1::PublicKey;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use tokio::{
+	io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+	net::UnixStream,
+};
+
+use super::{error::Error, key_provider::KeyEntry};
+
+/// Type url of the public keys of secp256k1 accounts.
+const SECP256K1_PUB_KEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+/// Type url of the public keys of the ethereum compatible accounts of ethermint chains.
+const ETH_SECP256K1_PUB_KEY_TYPE_URL: &str = "/ethermint.crypto.v1.ethsecp256k1.PubKey";
+
+/// Prefix of the endpoints of remote signers listening on a unix socket.
+const UNIX_ENDPOINT_PREFIX: &str = "unix://";
+
+/// Most bytes of the varint prefixing the length of a message of the remote signer.
+const MAX_LENGTH_DELIMITER_LEN: usize = 10;
+
+/// Scheme of the key signing the relayer's transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyType {
+	/// Key of cosmos sdk accounts, signing the sha256 digest of transactions
+	Secp256k1,
+	/// Key of the ethereum compatible accounts of ethermint chains, signing the keccak256 digest
+	/// of transactions
+	EthSecp256k1,
+}
+
+impl Default for KeyType {
+	fn default() -> Self {
+		KeyType::Secp256k1
+	}
+}
+
+impl KeyType {
+	/// The digest of `message` signed by keys of this type.
+	pub fn digest(&self, message: &[u8]) -> [u8; 32] {
+		let mut digest = [0; 32];
+		match self {
+			KeyType::Secp256k1 => digest.copy_from_slice(&Sha256::digest(message)),
+			KeyType::EthSecp256k1 =>
+				digest.copy_from_slice(&<Keccak256 as sha3::Digest>::digest(message)),
+		}
+		digest
+	}
+
+	/// Type url of the public keys of this type in transactions.
+	pub fn pub_key_type_url(&self) -> &'static str {
+		match self {
+			KeyType::Secp256k1 => SECP256K1_PUB_KEY_TYPE_URL,
+			KeyType::EthSecp256k1 => ETH_SECP256K1_PUB_KEY_TYPE_URL,
+		}
+	}
+}
+
+/// Signs the transactions of the relayer's account.
+#[async_trait::async_trait]
+pub trait TxSigner: Send + Sync {
+	/// Signs `sign_doc`, the encoded `SignDoc` of a transaction, returning the 64 byte compact
+	/// signature of its digest.
+	async fn sign(&self, sign_doc: &[u8]) -> Result<Vec<u8>, Error>;
+
+	/// Public key of the signing key.
+	fn public_key(&self) -> PublicKey;
+
+	/// Scheme of the signing key.
+	fn key_type(&self) -> KeyType;
+}
+
+/// Key signing the relayer's transactions.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerConfig {
+	/// Key derived from a BIP-39 mnemonic, held in memory
+	Mnemonic {
+		/// BIP-39 mnemonic of the key
+		mnemonic: String,
+		/// Scheme of the key
+		#[serde(default)]
+		key_type: KeyType,
+	},
+	/// Key held by a remote signer listening on a unix socket
+	Kms {
+		/// Socket of the remote signer, `unix:///path/to/socket`
+		endpoint: String,
+		/// Scheme of the key
+		#[serde(default)]
+		key_type: KeyType,
+	},
+}
+
+/// Initializes the signer given by `config`, signing transactions of the chain `chain_id`.
+pub async fn connect(config: SignerConfig, chain_id: &str) -> Result<Arc<dyn TxSigner>, Error> {
+	match config {
+		SignerConfig::Mnemonic { mnemonic, key_type } =>
+			Ok(Arc::new(KeyEntry::from_mnemonic(&mnemonic, key_type)?)),
+		SignerConfig::Kms { endpoint, key_type } =>
+			Ok(Arc::new(RemoteSigner::connect(&endpoint, chain_id, key_type).await?)),
+	}
+}
+
+/// A message exchanged with a remote signer, prefixed by its length as in the privval protocol
+/// of tendermint's remote signers.
+#[derive(Clone, PartialEq, Message)]
+pub struct SignerMessage {
+	#[prost(oneof = "SignerMessageSum", tags = "1, 2, 3, 4")]
+	pub sum: Option<SignerMessageSum>,
+}
+
+#[derive(Clone, PartialEq, prost::Oneof)]
+pub enum SignerMessageSum {
+	#[prost(message, tag = "1")]
+	PubKeyRequest(PubKeyRequest),
+	#[prost(message, tag = "2")]
+	PubKeyResponse(PubKeyResponse),
+	#[prost(message, tag = "3")]
+	SignRequest(SignRequest),
+	#[prost(message, tag = "4")]
+	SignResponse(SignResponse),
+}
+
+/// Requests the public key of the signer's key for a chain.
+#[derive(Clone, PartialEq, Message)]
+pub struct PubKeyRequest {
+	#[prost(string, tag = "1")]
+	pub chain_id: String,
+}
+
+/// The public key of the signer's key, compressed, or the error that prevented getting it.
+#[derive(Clone, PartialEq, Message)]
+pub struct PubKeyResponse {
+	#[prost(bytes = "vec", tag = "1")]
+	pub pub_key: Vec<u8>,
+	#[prost(message, optional, tag = "2")]
+	pub error: Option<RemoteSignerError>,
+}
+
+/// Requests the signature of the encoded `SignDoc` of a transaction to a chain.
+#[derive(Clone, PartialEq, Message)]
+pub struct SignRequest {
+	#[prost(bytes = "vec", tag = "1")]
+	pub sign_doc: Vec<u8>,
+	#[prost(string, tag = "2")]
+	pub chain_id: String,
+}
+
+/// The 64 byte compact signature of a `SignDoc`, or the error that prevented signing it.
+#[derive(Clone, PartialEq, Message)]
+pub struct SignResponse {
+	#[prost(bytes = "vec", tag = "1")]
+	pub signature: Vec<u8>,
+	#[prost(message, optional, tag = "2")]
+	pub error: Option<RemoteSignerError>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct RemoteSignerError {
+	#[prost(int32, tag = "1")]
+	pub code: i32,
+	#[prost(string, tag = "2")]
+	pub description: String,
+}
+
+impl From<RemoteSignerError> for Error {
+	fn from(error: RemoteSignerError) -> Self {
+		Error::Custom(format!(
+			"Remote signer failed with code {}: {}",
+			error.code, error.description
+		))
+	}
+}
+
+/// Writes `message` to `stream`, prefixed by its length.
+pub async fn write_message(
+	stream: &mut (impl AsyncWrite + Unpin),
+	message: SignerMessageSum,
+) -> Result<(), Error> {
+	let message = SignerMessage { sum: Some(message) };
+	stream.write_all(&message.encode_length_delimited_to_vec()).await?;
+	Ok(())
+}
+
+/// Reads a message prefixed by its length from `stream`.
+pub async fn read_message(
+	stream: &mut (impl AsyncRead + Unpin),
+) -> Result<SignerMessageSum, Error> {
+	let mut delimiter = Vec::with_capacity(MAX_LENGTH_DELIMITER_LEN);
+	loop {
+		let byte = stream.read_u8().await?;
+		delimiter.push(byte);
+		// the last byte of a varint has its most significant bit unset.
+		if byte & 0x80 == 0 || delimiter.len() == MAX_LENGTH_DELIMITER_LEN {
+			break
+		}
+	}
+	let mut message = vec![0; prost::decode_length_delimiter(delimiter.as_slice())?];
+	stream.read_exact(&mut message).await?;
+	SignerMessage::decode(message.as_slice())?
+		.sum
+		.ok_or_else(|| Error::Custom("Empty remote signer message".to_string()))
+}
+
+/// A key held by a remote signer, e.g. a KMS, listening on a unix socket. Every request is made
+/// on a new connection.
+pub struct RemoteSigner {
+	/// Path of the signer's socket
+	socket_path: PathBuf,
+	/// Chain whose transactions are signed
+	chain_id: String,
+	/// Scheme of the key
+	key_type: KeyType,
+	/// Public key of the signer's key
+	public_key: PublicKey,
+}
+
+impl RemoteSigner {
+	/// Connects to the signer listening on `endpoint`, `unix:///path/to/socket`, and queries the
+	/// public key it signs the transactions of `chain_id` with.
+	pub async fn connect(endpoint: &str, chain_id: &str, key_type: KeyType) -> Result<Self, Error> {
+		let socket_path = endpoint.strip_prefix(UNIX_ENDPOINT_PREFIX).ok_or_else(|| {
+			Error::Custom(format!("Remote signer endpoint {endpoint} isn't a unix socket"))
+		})?;
+		let socket_path = PathBuf::from(socket_path);
+		let chain_id = chain_id.to_string();
+		let request = SignerMessageSum::PubKeyRequest(PubKeyRequest { chain_id: chain_id.clone() });
+		let public_key = match request_signer(&socket_path, request).await? {
+			SignerMessageSum::PubKeyResponse(PubKeyResponse { error: Some(error), .. }) =>
+				return Err(error.into()),
+			SignerMessageSum::PubKeyResponse(PubKeyResponse { pub_key, .. }) =>
+				PublicKey::from_slice(&pub_key)
+					.map_err(|e| Error::Custom(format!("Invalid remote signer key: {e}")))?,
+			response => return Err(unexpected_response(response)),
+		};
+		Ok(Self { socket_path, chain_id, key_type, public_key })
+	}
+}
+
+/// Sends `request` on a new connection to the signer listening on `socket_path`, returning its
+/// response.
+async fn request_signer(
+	socket_path: &Path,
+	request: SignerMessageSum,
+) -> Result<SignerMessageSum, Error> {
+	let mut stream = UnixStream::connect(socket_path).await?;
+	write_message(&mut stream, request).await?;
+	read_message(&mut stream).await
+}
+
+fn unexpected_response(response: SignerMessageSum) -> Error {
+	Error::Custom(format!("Unexpected remote signer response: {response:?}"))
+}
+
+#[async_trait::async_trait]
+impl TxSigner for RemoteSigner {
+	async fn sign(&self, sign_doc: &[u8]) -> Result<Vec<u8>, Error> {
+		let request = SignerMessageSum::SignRequest(SignRequest {
+			sign_doc: sign_doc.to_vec(),
+			chain_id: self.chain_id.clone(),
+		});
+		match request_signer(&self.socket_path, request).await? {
+			SignerMessageSum::SignResponse(SignResponse { error: Some(error), .. }) =>
+				Err(error.into()),
+			SignerMessageSum::SignResponse(SignResponse { signature, .. })
+				if signature.len() == 64 =>
+				Ok(signature),
+			SignerMessageSum::SignResponse(SignResponse { signature, .. }) => Err(Error::Custom(
+				format!("Remote signer returned a signature of {} bytes", signature.len()),
+			)),
+			response => Err(unexpected_response(response)),
+		}
+	}
+
+	fn public_key(&self) -> PublicKey {
+		self.public_key
+	}
+
+	fn key_type(&self) -> KeyType {
+		self.key_type
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tx::sign_tx;
+	use ibc_proto::{
+		cosmos::{
+			auth::v1beta1::BaseAccount,
+			tx::v1beta1::{AuthInfo, Fee, SignDoc},
+		},
+		google::protobuf::Any,
+	};
+	use secp256k1::{ecdsa::Signature, SECP256K1};
+	use std::sync::Mutex;
+	use tokio::net::UnixListener;
+
+	const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+		abandon abandon abandon about";
+
+	/// Serves the requests of remote signers on `socket_path` with `key`, returning the sign docs
+	/// it's asked to sign.
+	fn mock_remote_signer(socket_path: &Path, key: KeyEntry) -> Arc<Mutex<Vec<Vec<u8>>>> {
+		let _ = std::fs::remove_file(socket_path);
+		let listener = UnixListener::bind(socket_path).unwrap();
+		let sign_docs = Arc::new(Mutex::new(vec![]));
+		let received = sign_docs.clone();
+		tokio::spawn(async move {
+			loop {
+				let (mut stream, _) = listener.accept().await.unwrap();
+				let response = match read_message(&mut stream).await.unwrap() {
+					SignerMessageSum::PubKeyRequest(_) =>
+						SignerMessageSum::PubKeyResponse(PubKeyResponse {
+							pub_key: key.public_key.serialize().to_vec(),
+							error: None,
+						}),
+					SignerMessageSum::SignRequest(SignRequest { sign_doc, .. }) => {
+						let signature = key.sign(&sign_doc).await.unwrap();
+						received.lock().unwrap().push(sign_doc);
+						SignerMessageSum::SignResponse(SignResponse { signature, error: None })
+					},
+					request => panic!("Unexpected request: {request:?}"),
+				};
+				write_message(&mut stream, response).await.unwrap();
+			}
+		});
+		sign_docs
+	}
+
+	#[tokio::test]
+	async fn sends_the_sign_doc_to_the_remote_signer() {
+		for key_type in [KeyType::Secp256k1, KeyType::EthSecp256k1] {
+			let socket_path = std::env::temp_dir()
+				.join(format!("hyperspace-signer-{}-{key_type:?}.sock", std::process::id()));
+			let key = KeyEntry::from_mnemonic(MNEMONIC, key_type).unwrap();
+			let sign_docs = mock_remote_signer(&socket_path, key.clone());
+			let endpoint = format!("unix://{}", socket_path.display());
+			let signer = RemoteSigner::connect(&endpoint, "mock-0", key_type).await.unwrap();
+			assert_eq!(signer.public_key(), key.public_key);
+
+			let account = BaseAccount { account_number: 7, sequence: 3, ..Default::default() };
+			let messages = vec![Any { type_url: "/mock.Msg".to_string(), value: vec![1, 2] }];
+			let fee = Fee { gas_limit: 100_000, ..Default::default() };
+			let tx = sign_tx(&signer, "mock-0", &account, messages, fee).await.unwrap();
+
+			let sign_doc = SignDoc {
+				body_bytes: tx.body_bytes.clone(),
+				auth_info_bytes: tx.auth_info_bytes.clone(),
+				chain_id: "mock-0".to_string(),
+				account_number: 7,
+			}
+			.encode_to_vec();
+			assert_eq!(*sign_docs.lock().unwrap(), vec![sign_doc.clone()]);
+
+			let auth_info = AuthInfo::decode(tx.auth_info_bytes.as_slice()).unwrap();
+			let public_key = auth_info.signer_infos[0].public_key.clone().unwrap();
+			assert_eq!(public_key.type_url, key_type.pub_key_type_url());
+			let digest = secp256k1::Message::from_slice(&key_type.digest(&sign_doc)).unwrap();
+			let signature = Signature::from_compact(&tx.signatures[0]).unwrap();
+			assert!(SECP256K1.verify_ecdsa(&digest, &signature, &key.public_key).is_ok());
+			let _ = std::fs::remove_file(&socket_path);
+		}
+	}
+}
@@ -0,0 +1,334 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Ibc events received through the websocket subscription of a tendermint node, caught up with
+//! the results of the blocks committed while the subscription reconnects.
+
+use std::{collections::BTreeSet, sync::atomic::Ordering, time::Duration};
+
+use futures::{stream::BoxStream, StreamExt};
+use ibc::{events::IbcEvent, Height};
+use sha2::{Digest, Sha256};
+use tendermint::{abci::Event as AbciEvent, block::Height as TmHeight};
+use tendermint_rpc::{
+	event::{Event, EventData},
+	query::EventType,
+	Client, SubscriptionClient, WebSocketClient,
+};
+use tokio::{sync::mpsc, time::sleep};
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::{error::Error, events::ibc_event_from_abci, CosmosClient};
+
+/// Events buffered between the subscription and its consumer.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Wait before the first attempt at resubscribing, doubled for every further one.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Upper bound of the wait between two attempts at resubscribing.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Number of blocks below the latest one whose events are remembered, so that events received
+/// both from the subscription and from catching up are forwarded once.
+const DEDUP_BLOCKS: u64 = 100;
+
+/// Identifies an abci event by the height of its block, the hash of the transaction that emitted
+/// it, `None` for the events of beginning and ending the block, and its index among the events of
+/// the transaction or block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EventId {
+	/// Height of the block that emitted the event
+	pub height: u64,
+	/// Hash of the transaction that emitted the event
+	pub tx_hash: Option<[u8; 32]>,
+	/// Index of the event among the events of its transaction or block
+	pub index: usize,
+}
+
+/// Identifies `events`, all emitted by the transaction with `tx_hash` or while beginning and
+/// ending the block at `height`.
+pub fn identify(
+	height: u64,
+	tx_hash: Option<[u8; 32]>,
+	events: impl IntoIterator<Item = AbciEvent>,
+) -> impl Iterator<Item = (EventId, AbciEvent)> {
+	events
+		.into_iter()
+		.enumerate()
+		.map(move |(index, event)| (EventId { height, tx_hash, index }, event))
+}
+
+/// Hash of the encoded transaction `tx`.
+pub fn tx_hash(tx: &[u8]) -> [u8; 32] {
+	let mut hash = [0; 32];
+	hash.copy_from_slice(&Sha256::digest(tx));
+	hash
+}
+
+/// The height of the block and the events carried by `event`, a transaction or a new block
+/// delivered by the subscription.
+fn subscription_events(event: Event) -> Option<(u64, Vec<(EventId, AbciEvent)>)> {
+	match event.data {
+		EventData::Tx { tx_result } => {
+			let height = tx_result.height as u64;
+			let tx_hash = Some(tx_hash(&tx_result.tx));
+			Some((height, identify(height, tx_hash, tx_result.result.events).collect()))
+		},
+		EventData::NewBlock { block, result_begin_block, result_end_block } => {
+			let height = block?.header.height.value();
+			let events = result_begin_block
+				.map(|begin_block| begin_block.events)
+				.unwrap_or_default()
+				.into_iter()
+				.chain(result_end_block.map(|end_block| end_block.events).unwrap_or_default());
+			Some((height, identify(height, None, events).collect()))
+		},
+		_ => None,
+	}
+}
+
+/// Opens a websocket connection to `url`, subscribed to the node's transactions and new blocks.
+/// The connection is closed once the returned client is dropped.
+async fn subscribe(
+	url: &str,
+) -> Result<(WebSocketClient, BoxStream<'static, Result<Event, tendermint_rpc::Error>>), Error> {
+	let (client, driver) = WebSocketClient::new(url).await?;
+	tokio::spawn(async move {
+		if let Err(e) = driver.run().await {
+			log::warn!("Websocket connection closed: {e:?}");
+		}
+	});
+	let txs = client.subscribe(EventType::Tx.into()).await?;
+	let blocks = client.subscribe(EventType::NewBlock.into()).await?;
+	Ok((client, futures::stream::select(txs, blocks).boxed()))
+}
+
+/// Wait before the attempt at resubscribing following `attempts` failed ones.
+fn backoff(attempts: u32) -> Duration {
+	INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempts)).min(MAX_BACKOFF)
+}
+
+/// Forwards the ibc events among the abci events it's given, once each.
+pub struct EventForwarder {
+	sender: mpsc::Sender<IbcEvent>,
+	/// Revision of the chain's heights
+	revision_number: u64,
+	/// Events already forwarded
+	seen: BTreeSet<EventId>,
+	/// Height of the latest block whose events were forwarded
+	last_height: Option<u64>,
+}
+
+impl EventForwarder {
+	pub fn new(sender: mpsc::Sender<IbcEvent>, revision_number: u64) -> Self {
+		Self { sender, revision_number, seen: BTreeSet::new(), last_height: None }
+	}
+
+	/// Forwards the ibc events among `events`, emitted in the block at `height`, that weren't
+	/// forwarded before. Fails once the receiver is dropped.
+	pub async fn forward(
+		&mut self,
+		height: u64,
+		events: Vec<(EventId, AbciEvent)>,
+	) -> Result<(), Error> {
+		for (id, event) in events {
+			if !self.seen.insert(id) {
+				continue
+			}
+			let event_height = Height::new(self.revision_number, id.height);
+			if let Some(ibc_event) = ibc_event_from_abci(&event, event_height) {
+				self.sender
+					.send(ibc_event)
+					.await
+					.map_err(|_| Error::Custom("Ibc event receiver was dropped".to_string()))?;
+			}
+		}
+		self.last_height = self.last_height.max(Some(height));
+		let oldest = EventId {
+			height: self.last_height.unwrap_or_default().saturating_sub(DEDUP_BLOCKS),
+			tx_hash: None,
+			index: 0,
+		};
+		self.seen = self.seen.split_off(&oldest);
+		Ok(())
+	}
+}
+
+impl CosmosClient {
+	/// The events emitted in the block at `height` by its transactions that succeeded and while
+	/// beginning and ending it, identified by the transactions that emitted them.
+	pub async fn query_identified_block_events(
+		&self,
+		height: u64,
+	) -> Result<Vec<(EventId, AbciEvent)>, Error> {
+		let tm_height = TmHeight::try_from(height)?;
+		let block = self.rpc_client.block(tm_height).await?.block;
+		let response = self.rpc_client.block_results(tm_height).await?;
+		let tx_events = block
+			.data
+			.iter()
+			.zip(response.txs_results.unwrap_or_default())
+			.filter(|(_, result)| result.code.is_ok())
+			.flat_map(|(tx, result)| identify(height, Some(tx_hash(tx.as_ref())), result.events));
+		let block_events = response
+			.begin_block_events
+			.unwrap_or_default()
+			.into_iter()
+			.chain(response.end_block_events.unwrap_or_default());
+		Ok(tx_events.chain(identify(height, None, block_events)).collect())
+	}
+
+	/// Forwards the events of the blocks from `from_height` to the latest one.
+	async fn catch_up(
+		&self,
+		from_height: u64,
+		forwarder: &mut EventForwarder,
+	) -> Result<(), Error> {
+		let latest_height = self.rpc_client.status().await?.sync_info.latest_block_height.value();
+		let heights = from_height..=latest_height;
+		log::info!("Catching up on the events of {} for blocks {:?}", self.name, heights);
+		for height in heights {
+			let events = self.query_identified_block_events(height).await?;
+			forwarder.forward(height, events).await?;
+		}
+		Ok(())
+	}
+
+	/// Forwards the events of a new subscription to `websocket_url` until its connection drops.
+	/// With `catch_up`, the events of the blocks committed since the last block forwarded are
+	/// queried first, the last block included as its events may have been cut short.
+	async fn forward_subscription(
+		&self,
+		forwarder: &mut EventForwarder,
+		catch_up: bool,
+	) -> Result<(), Error> {
+		let (_ws_client, mut events) = subscribe(&self.websocket_url).await?;
+		if let Some(last_height) = forwarder.last_height.filter(|_| catch_up) {
+			self.catch_up(last_height, forwarder).await?;
+		}
+		while let Some(event) = events.next().await {
+			if let Some((height, events)) = subscription_events(event?) {
+				forwarder.forward(height, events).await?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Yields the ibc events of the blocks committed from now on, received through a websocket
+	/// subscription. The subscription is re-established whenever its connection drops: with
+	/// `catch_up`, the events of the blocks committed in the meantime are then queried from the
+	/// node, otherwise they're missed.
+	pub fn subscribe_ibc_events(&self, catch_up: bool) -> ReceiverStream<IbcEvent> {
+		let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+		let client = self.clone();
+		tokio::spawn(async move {
+			let mut forwarder = EventForwarder::new(sender, client.chain_id.version());
+			let mut attempts = 0;
+			loop {
+				let last_height = forwarder.last_height;
+				if let Err(e) = client.forward_subscription(&mut forwarder, catch_up).await {
+					log::warn!("Error in the event subscription of {}: {:?}", client.name, e);
+				}
+				// the receiver was dropped along with the stream.
+				if forwarder.sender.is_closed() {
+					return
+				}
+				// the subscription forwarded events before its connection dropped.
+				if forwarder.last_height != last_height {
+					attempts = 0;
+				}
+				let backoff = backoff(attempts);
+				log::warn!("Resubscribing to the events of {} in {:?}", client.name, backoff);
+				sleep(backoff).await;
+				attempts += 1;
+				client.subscription_reconnects.fetch_add(1, Ordering::SeqCst);
+			}
+		});
+		ReceiverStream::new(receiver)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use tendermint::abci::EventAttribute;
+
+	fn send_packet(sequence: &str) -> AbciEvent {
+		let attributes = [
+			("packet_data", "{}"),
+			("packet_timeout_height", "0-100"),
+			("packet_timeout_timestamp", "0"),
+			("packet_sequence", sequence),
+			("packet_src_port", "transfer"),
+			("packet_src_channel", "channel-0"),
+			("packet_dst_port", "transfer"),
+			("packet_dst_channel", "channel-1"),
+			("packet_channel_ordering", "ORDER_UNORDERED"),
+		];
+		AbciEvent {
+			kind: "send_packet".to_string(),
+			attributes: attributes
+				.iter()
+				.map(|(key, value)| EventAttribute {
+					key: key.to_string(),
+					value: value.to_string(),
+					index: true,
+				})
+				.collect(),
+		}
+	}
+
+	fn sequence(event: IbcEvent) -> u64 {
+		match event {
+			IbcEvent::SendPacket(send) => u64::from(send.packet.sequence),
+			event => panic!("Expected a send packet event, found {event:?}"),
+		}
+	}
+
+	#[tokio::test]
+	async fn forwards_the_events_of_the_subscription_and_catching_up_once() {
+		let (sender, mut receiver) = mpsc::channel(16);
+		let mut forwarder = EventForwarder::new(sender, 1);
+		let tx = tx_hash(b"transfer");
+		let transfer = || identify(10, Some(tx), vec![send_packet("1"), send_packet("2")]);
+
+		// received from the subscription, then again while catching up after a reconnect.
+		forwarder.forward(10, transfer().collect()).await.unwrap();
+		forwarder.forward(10, transfer().collect()).await.unwrap();
+		// the same event emitted by another transaction of the block is told apart by its hash.
+		let other_tx = identify(10, Some(tx_hash(b"other")), vec![send_packet("1")]);
+		forwarder.forward(10, other_tx.collect()).await.unwrap();
+		drop(forwarder);
+
+		let mut sequences = vec![];
+		while let Some(event) = receiver.recv().await {
+			assert_eq!(event.height(), Height::new(1, 10));
+			sequences.push(sequence(event));
+		}
+		assert_eq!(sequences, vec![1, 2, 1]);
+	}
+
+	#[tokio::test]
+	async fn forgets_the_events_of_old_blocks() {
+		let (sender, _receiver) = mpsc::channel(16);
+		let mut forwarder = EventForwarder::new(sender, 1);
+		forwarder.forward(10, identify(10, None, vec![send_packet("1")]).collect()).await.unwrap();
+		forwarder.forward(10 + DEDUP_BLOCKS, vec![]).await.unwrap();
+		assert_eq!(forwarder.seen.len(), 1);
+		forwarder.forward(11 + DEDUP_BLOCKS, vec![]).await.unwrap();
+		assert!(forwarder.seen.is_empty());
+		assert_eq!(forwarder.last_height, Some(11 + DEDUP_BLOCKS));
+	}
+}
@@ -0,0 +1,96 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::pin::Pin;
+
+use futures::Stream;
+use ibc::{
+	applications::transfer::{msgs::transfer::MsgTransfer, PrefixedCoin},
+	core::ics24_host::identifier::{ChannelId, ClientId, PortId},
+};
+use ibc_proto::{cosmos::base::v1beta1::Coin, google::protobuf::Any};
+use pallet_ibc::Timeout;
+use pallet_ibc_ping::PingStats;
+use primitives::{KeyProvider, TestProvider};
+use sha2::{Digest, Sha256};
+
+use super::{error::Error, CosmosClient};
+
+/// The bank denomination of `coin`, tokens received over ibc are denominated by the hash of
+/// their trace.
+fn bank_coin(coin: PrefixedCoin) -> Coin {
+	let denom = match coin.denom.trace_path().is_empty() {
+		true => coin.denom.base_denom().to_string(),
+		false => format!("ibc/{}", hex::encode_upper(Sha256::digest(coin.denom.to_string()))),
+	};
+	Coin { denom, amount: coin.amount.to_string() }
+}
+
+impl CosmosClient {
+	pub fn set_client_id(&mut self, client_id: ClientId) {
+		self.client_id = Some(client_id)
+	}
+}
+
+#[async_trait::async_trait]
+impl TestProvider for CosmosClient {
+	async fn send_transfer(&self, transfer: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
+		let msg = MsgTransfer {
+			source_port: transfer.source_port,
+			source_channel: transfer.source_channel,
+			token: bank_coin(transfer.token),
+			tokens: transfer.tokens.into_iter().map(bank_coin).collect(),
+			sender: self.account_id(),
+			receiver: transfer.receiver,
+			timeout_height: transfer.timeout_height,
+			timeout_timestamp: transfer.timeout_timestamp,
+			memo: transfer.memo,
+		};
+		self.submit_tx(vec![Any::from(msg)]).await?;
+		Ok(())
+	}
+
+	async fn send_ordered_packet(
+		&self,
+		channel_id: ChannelId,
+		timeout: Timeout,
+	) -> Result<(), Self::Error> {
+		self.send_pings(channel_id, 1, "ping".len() as u32, timeout).await
+	}
+
+	async fn send_pings(
+		&self,
+		_channel_id: ChannelId,
+		_count: u32,
+		_payload_size: u32,
+		_timeout: Timeout,
+	) -> Result<(), Self::Error> {
+		Err(Error::Custom(format!("{} doesn't run the ping module", self.name)))
+	}
+
+	async fn query_ping_stats(
+		&self,
+		_channel_id: ChannelId,
+	) -> Result<Option<PingStats>, Self::Error> {
+		Ok(None)
+	}
+
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		Box::pin(self.committed_blocks())
+	}
+
+	fn set_channel_whitelist(&mut self, channel_whitelist: Vec<(ChannelId, PortId)>) {
+		self.channel_whitelist = channel_whitelist;
+	}
+}
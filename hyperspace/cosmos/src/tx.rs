@@ -0,0 +1,520 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Construction, signing and broadcasting of cosmos sdk transactions.
+
+use std::{str::FromStr, time::Duration};
+
+use ibc_proto::{
+	cosmos::{
+		auth::v1beta1::{
+			query_client::QueryClient as AuthQueryClient, BaseAccount, QueryAccountRequest,
+		},
+		base::v1beta1::Coin,
+		tx::{
+			signing::v1beta1::SignMode,
+			v1beta1::{
+				mode_info::{Single, Sum},
+				service_client::ServiceClient,
+				AuthInfo, BroadcastMode, BroadcastTxRequest, Fee, GetTxRequest, ModeInfo, SignDoc,
+				SignerInfo, SimulateRequest, TxBody, TxRaw,
+			},
+		},
+	},
+	google::protobuf::Any,
+};
+use prost::Message;
+use tendermint_rpc::abci::transaction::Hash;
+use tokio::time::sleep;
+
+use super::{error::Error, signer::TxSigner, CosmosClient};
+
+/// Interval between two lookups of a broadcast transaction.
+const TX_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Number of lookups of a broadcast transaction before it's considered not included.
+const TX_POLL_ATTEMPTS: u32 = 60;
+
+/// Factor applied to the gas multiplier to retry a transaction that ran out of gas.
+const OUT_OF_GAS_RETRY_FACTOR: f64 = 1.5;
+
+/// Protobuf encoding of a secp256k1 or ethsecp256k1 public key.
+#[derive(Clone, PartialEq, Message)]
+struct PubKey {
+	#[prost(bytes = "vec", tag = "1")]
+	key: Vec<u8>,
+}
+
+/// A transaction included in a block.
+#[derive(Debug, Clone)]
+pub struct TransactionId {
+	/// Transaction hash
+	pub hash: Hash,
+	/// Height of the block that included the transaction
+	pub height: u64,
+}
+
+/// Encodes `messages` into a transaction signed by `signer` with the account's current sequence.
+pub async fn sign_tx(
+	signer: &dyn TxSigner,
+	chain_id: &str,
+	account: &BaseAccount,
+	messages: Vec<Any>,
+	fee: Fee,
+) -> Result<TxRaw, Error> {
+	let body = TxBody {
+		messages,
+		memo: String::new(),
+		timeout_height: 0,
+		extension_options: vec![],
+		non_critical_extension_options: vec![],
+	};
+	let public_key = PubKey { key: signer.public_key().serialize().to_vec() };
+	let signer_info = SignerInfo {
+		public_key: Some(Any {
+			type_url: signer.key_type().pub_key_type_url().to_string(),
+			value: public_key.encode_to_vec(),
+		}),
+		mode_info: Some(ModeInfo {
+			sum: Some(Sum::Single(Single { mode: SignMode::Direct as i32 })),
+		}),
+		sequence: account.sequence,
+	};
+	let auth_info = AuthInfo { signer_infos: vec![signer_info], fee: Some(fee) };
+
+	let body_bytes = body.encode_to_vec();
+	let auth_info_bytes = auth_info.encode_to_vec();
+	let sign_doc = SignDoc {
+		body_bytes: body_bytes.clone(),
+		auth_info_bytes: auth_info_bytes.clone(),
+		chain_id: chain_id.to_string(),
+		account_number: account.account_number,
+	};
+	let signature = signer.sign(&sign_doc.encode_to_vec()).await?;
+	Ok(TxRaw { body_bytes, auth_info_bytes, signatures: vec![signature] })
+}
+
+impl CosmosClient {
+	/// Queries the relayer's account, for its number and sequence.
+	pub async fn query_account(&self) -> Result<BaseAccount, Error> {
+		let mut client = AuthQueryClient::new(self.grpc_channel.clone());
+		let request = QueryAccountRequest { address: self.account.clone() };
+		let account = client
+			.account(request)
+			.await?
+			.into_inner()
+			.account
+			.ok_or_else(|| Error::Custom(format!("Account {} not found", self.account)))?;
+		Ok(BaseAccount::decode(account.value.as_slice())?)
+	}
+
+	/// The fee paid for a transaction, at most `gas_limit` gas is used.
+	pub fn fee(&self, gas_limit: u64) -> Fee {
+		let amount = (gas_limit as f64 * self.gas_price).ceil() as u128;
+		Fee {
+			amount: vec![Coin { denom: self.fee_denom.clone(), amount: amount.to_string() }],
+			gas_limit,
+			payer: String::new(),
+			granter: String::new(),
+		}
+	}
+
+	/// Gas limit of a transaction whose simulation used `gas_used`, capped at `max_gas`.
+	pub fn gas_limit(&self, gas_used: u64, gas_multiplier: f64) -> u64 {
+		((gas_used as f64 * gas_multiplier).ceil() as u64).min(self.max_gas)
+	}
+
+	/// Gas used by the messages of a transaction signed with the sequence of `account`, as
+	/// simulated by the chain.
+	pub async fn simulate_gas(
+		&self,
+		account: &BaseAccount,
+		messages: Vec<Any>,
+	) -> Result<u64, Error> {
+		let fee = self.fee(0);
+		let tx = sign_tx(&*self.signer, self.chain_id.as_str(), account, messages, fee).await?;
+		let mut client = ServiceClient::new(self.grpc_channel.clone());
+		#[allow(deprecated)]
+		let request = SimulateRequest { tx: None, tx_bytes: tx.encode_to_vec() };
+		let gas_info = client
+			.simulate(request)
+			.await?
+			.into_inner()
+			.gas_info
+			.ok_or_else(|| Error::Custom("Simulation returned no gas info".to_string()))?;
+		Ok(gas_info.gas_used)
+	}
+
+	/// Signs transactions carrying `messages`, broadcasts them and waits for their inclusion in
+	/// a block. Returns the last transaction, see [`CosmosClient::submit_messages`].
+	pub async fn submit_tx(&self, messages: Vec<Any>) -> Result<TransactionId, Error> {
+		let account = self.query_account().await?;
+		self.submit_messages(account, messages).await
+	}
+
+	/// Submits `messages` in transactions signed from the sequence of `account`, given the gas
+	/// used by their simulation times `gas_multiplier`. Batches whose execution needs more than
+	/// `max_gas` are split in halves until they fit, and submitted in order. Returns the last
+	/// transaction.
+	pub async fn submit_messages(
+		&self,
+		mut account: BaseAccount,
+		messages: Vec<Any>,
+	) -> Result<TransactionId, Error> {
+		let mut tx_id = None;
+		// batches left to submit, the next one last.
+		let mut batches = vec![messages];
+		while let Some(mut batch) = batches.pop() {
+			let gas_used = self.simulate_gas(&account, batch.clone()).await?;
+			let result = match gas_used <= self.max_gas {
+				true => self.submit_batch(&mut account, batch.clone(), gas_used).await,
+				false => Err(Error::GasLimitExceeded { gas_used, max_gas: self.max_gas }),
+			};
+			match result {
+				Ok(id) => tx_id = Some(id),
+				Err(e) if e.is_out_of_gas() && batch.len() > 1 => {
+					log::info!(
+						"Splitting a batch of {} messages to {} in halves: {}",
+						batch.len(),
+						self.name,
+						e
+					);
+					let second_half = batch.split_off(batch.len() / 2);
+					batches.push(second_half);
+					batches.push(batch);
+				},
+				Err(e) => return Err(e),
+			}
+		}
+		tx_id.ok_or_else(|| Error::Custom("No messages to submit".to_string()))
+	}
+
+	/// Submits `messages`, whose simulation used `gas_used`, in a transaction signed with the
+	/// sequence of `account`. A transaction that runs out of gas is retried once with a larger
+	/// gas multiplier.
+	async fn submit_batch(
+		&self,
+		account: &mut BaseAccount,
+		messages: Vec<Any>,
+		gas_used: u64,
+	) -> Result<TransactionId, Error> {
+		let gas_limit = self.gas_limit(gas_used, self.gas_multiplier);
+		match self.sign_and_submit(account, messages.clone(), gas_limit).await {
+			Err(e) if e.is_out_of_gas() && gas_limit < self.max_gas => {
+				let retry_gas_limit =
+					self.gas_limit(gas_used, self.gas_multiplier * OUT_OF_GAS_RETRY_FACTOR);
+				log::warn!(
+					"Transaction to {} ran out of {} gas, retrying with {}",
+					self.name,
+					gas_limit,
+					retry_gas_limit
+				);
+				self.sign_and_submit(account, messages, retry_gas_limit).await
+			},
+			result => result,
+		}
+	}
+
+	async fn sign_and_submit(
+		&self,
+		account: &mut BaseAccount,
+		messages: Vec<Any>,
+		gas_limit: u64,
+	) -> Result<TransactionId, Error> {
+		let fee = self.fee(gas_limit);
+		let tx = sign_tx(&*self.signer, self.chain_id.as_str(), account, messages, fee).await?;
+		let hash = self.broadcast_tx(tx).await?;
+		// the sequence is used once the transaction is in the mempool, even if it fails.
+		account.sequence += 1;
+		self.wait_for_tx(hash).await
+	}
+
+	/// Broadcasts `tx`, returning its hash once it was accepted in the mempool.
+	pub async fn broadcast_tx(&self, tx: TxRaw) -> Result<Hash, Error> {
+		let mut client = ServiceClient::new(self.grpc_channel.clone());
+		let request =
+			BroadcastTxRequest { tx_bytes: tx.encode_to_vec(), mode: BroadcastMode::Sync as i32 };
+		let response = client
+			.broadcast_tx(request)
+			.await?
+			.into_inner()
+			.tx_response
+			.ok_or_else(|| Error::Custom("Broadcast returned no response".to_string()))?;
+		if response.code != 0 {
+			return Err(Error::TxFailed {
+				hash: response.txhash,
+				codespace: response.codespace,
+				code: response.code,
+				log: response.raw_log,
+			})
+		}
+		Hash::from_str(&response.txhash)
+			.map_err(|e| Error::Custom(format!("Invalid transaction hash: {e}")))
+	}
+
+	/// Waits for the transaction with `hash` to be included in a block, failing if its execution
+	/// failed.
+	pub async fn wait_for_tx(&self, hash: Hash) -> Result<TransactionId, Error> {
+		let mut client = ServiceClient::new(self.grpc_channel.clone());
+		for _ in 0..TX_POLL_ATTEMPTS {
+			let request = GetTxRequest { hash: hash.to_string() };
+			let response = match client.get_tx(request).await {
+				Ok(response) => response.into_inner().tx_response,
+				// the transaction isn't indexed until it's included.
+				Err(_) => None,
+			};
+			let response = match response {
+				Some(response) => response,
+				None => {
+					sleep(TX_POLL_INTERVAL).await;
+					continue
+				},
+			};
+			if response.code != 0 {
+				return Err(Error::TxFailed {
+					hash: hash.to_string(),
+					codespace: response.codespace,
+					code: response.code,
+					log: response.raw_log,
+				})
+			}
+			return Ok(TransactionId { hash, height: response.height as u64 })
+		}
+		Err(Error::TxTimeout(hash.to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		error::OUT_OF_GAS_CODE,
+		signer::{KeyType, SignerConfig},
+		CosmosClientConfig,
+	};
+	use ibc_proto::cosmos::{
+		base::abci::v1beta1::{GasInfo, TxResponse},
+		tx::v1beta1::{
+			service_server::{Service, ServiceServer},
+			BroadcastTxResponse, GetBlockWithTxsRequest, GetBlockWithTxsResponse, GetTxResponse,
+			GetTxsEventRequest, GetTxsEventResponse, SimulateResponse,
+		},
+	};
+	use sha2::{Digest, Sha256};
+	use std::{
+		collections::HashMap,
+		sync::{Arc, Mutex},
+	};
+	use tokio::net::TcpListener;
+	use tokio_stream::wrappers::TcpListenerStream;
+	use tonic::{transport::Server, Request, Response, Status};
+
+	/// Gas used by the simulation of each message.
+	const GAS_PER_MESSAGE: u64 = 100_000;
+
+	/// A transaction broadcast to the mock tx service.
+	#[derive(Debug, Clone, PartialEq)]
+	struct Broadcast {
+		/// Type urls of the messages of the transaction
+		messages: Vec<String>,
+		/// Gas limit of the transaction
+		gas_limit: u64,
+		/// Sequence the transaction was signed with
+		sequence: u64,
+	}
+
+	/// A tx service simulating every message to use [`GAS_PER_MESSAGE`], and executing
+	/// transactions with `execution_overhead` times the gas of their simulation.
+	#[derive(Default)]
+	struct MockTxService {
+		execution_overhead: f64,
+		broadcasts: Arc<Mutex<Vec<Broadcast>>>,
+		responses: Mutex<HashMap<String, TxResponse>>,
+	}
+
+	fn decode_tx(tx_bytes: &[u8]) -> (Vec<Any>, AuthInfo) {
+		let tx = TxRaw::decode(tx_bytes).unwrap();
+		let body = TxBody::decode(tx.body_bytes.as_slice()).unwrap();
+		(body.messages, AuthInfo::decode(tx.auth_info_bytes.as_slice()).unwrap())
+	}
+
+	#[tonic::async_trait]
+	impl Service for MockTxService {
+		async fn simulate(
+			&self,
+			request: Request<SimulateRequest>,
+		) -> Result<Response<SimulateResponse>, Status> {
+			let (messages, _) = decode_tx(&request.into_inner().tx_bytes);
+			let gas_used = GAS_PER_MESSAGE * messages.len() as u64;
+			let gas_info = GasInfo { gas_wanted: 0, gas_used };
+			Ok(Response::new(SimulateResponse { gas_info: Some(gas_info), result: None }))
+		}
+
+		async fn get_tx(
+			&self,
+			request: Request<GetTxRequest>,
+		) -> Result<Response<GetTxResponse>, Status> {
+			let hash = request.into_inner().hash.to_uppercase();
+			let tx_response = self.responses.lock().unwrap().get(&hash).cloned();
+			match tx_response {
+				Some(tx_response) =>
+					Ok(Response::new(GetTxResponse { tx: None, tx_response: Some(tx_response) })),
+				None => Err(Status::not_found(format!("Transaction {hash} not found"))),
+			}
+		}
+
+		async fn broadcast_tx(
+			&self,
+			request: Request<BroadcastTxRequest>,
+		) -> Result<Response<BroadcastTxResponse>, Status> {
+			let tx_bytes = request.into_inner().tx_bytes;
+			let (messages, auth_info) = decode_tx(&tx_bytes);
+			let gas_limit = auth_info.fee.unwrap().gas_limit;
+			let gas_used = GAS_PER_MESSAGE as f64 * messages.len() as f64 * self.execution_overhead;
+			self.broadcasts.lock().unwrap().push(Broadcast {
+				messages: messages.into_iter().map(|message| message.type_url).collect(),
+				gas_limit,
+				sequence: auth_info.signer_infos[0].sequence,
+			});
+
+			let txhash = hex::encode_upper(Sha256::digest(&tx_bytes));
+			let delivered = match gas_limit as f64 >= gas_used {
+				true => TxResponse { height: 10, txhash: txhash.clone(), ..Default::default() },
+				false => TxResponse {
+					height: 10,
+					txhash: txhash.clone(),
+					codespace: "sdk".to_string(),
+					code: OUT_OF_GAS_CODE,
+					raw_log: "out of gas".to_string(),
+					..Default::default()
+				},
+			};
+			self.responses.lock().unwrap().insert(txhash.clone(), delivered);
+			let tx_response = TxResponse { txhash, ..Default::default() };
+			Ok(Response::new(BroadcastTxResponse { tx_response: Some(tx_response) }))
+		}
+
+		async fn get_txs_event(
+			&self,
+			_request: Request<GetTxsEventRequest>,
+		) -> Result<Response<GetTxsEventResponse>, Status> {
+			Err(Status::unimplemented("get_txs_event"))
+		}
+
+		async fn get_block_with_txs(
+			&self,
+			_request: Request<GetBlockWithTxsRequest>,
+		) -> Result<Response<GetBlockWithTxsResponse>, Status> {
+			Err(Status::unimplemented("get_block_with_txs"))
+		}
+	}
+
+	/// Serves `service` and connects a client to it, giving at most `max_gas` to transactions.
+	async fn mock_client(service: MockTxService, max_gas: u64) -> CosmosClient {
+		let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+		let address = listener.local_addr().unwrap();
+		let server = Server::builder()
+			.add_service(ServiceServer::new(service))
+			.serve_with_incoming(TcpListenerStream::new(listener));
+		tokio::spawn(server);
+
+		let config = CosmosClientConfig {
+			name: "mock".to_string(),
+			rpc_url: "http://127.0.0.1:26657".to_string(),
+			websocket_url: "ws://127.0.0.1:26657/websocket".to_string(),
+			grpc_url: format!("http://{address}"),
+			chain_id: "mock-0".to_string(),
+			client_id: None,
+			connection_id: None,
+			account_prefix: "cosmos".to_string(),
+			store_prefix: "ibc".to_string(),
+			signer: SignerConfig::Mnemonic {
+				mnemonic: "abandon abandon abandon abandon abandon abandon abandon abandon \
+					abandon abandon abandon about"
+					.to_string(),
+				key_type: KeyType::Secp256k1,
+			},
+			fee_denom: "stake".to_string(),
+			gas_price: 0.025,
+			gas_multiplier: 1.1,
+			max_gas,
+			channel_whitelist: vec![],
+		};
+		CosmosClient::new(config).await.unwrap()
+	}
+
+	fn messages(count: usize) -> Vec<Any> {
+		(0..count).map(|i| Any { type_url: format!("/mock.Msg{i}"), value: vec![] }).collect()
+	}
+
+	fn broadcast(messages: &[usize], gas_limit: u64, sequence: u64) -> Broadcast {
+		let messages = messages.iter().map(|i| format!("/mock.Msg{i}")).collect();
+		Broadcast { messages, gas_limit, sequence }
+	}
+
+	#[tokio::test]
+	async fn splits_batches_exceeding_the_max_gas() {
+		let broadcasts = Arc::new(Mutex::new(vec![]));
+		let service = MockTxService {
+			execution_overhead: 1.0,
+			broadcasts: broadcasts.clone(),
+			..Default::default()
+		};
+		let client = mock_client(service, 250_000).await;
+		let account = BaseAccount { sequence: 4, ..Default::default() };
+
+		// needs 500k gas, split into halves of 200k and 300k, the second split again.
+		client.submit_messages(account.clone(), messages(5)).await.unwrap();
+		let one_message = client.gas_limit(GAS_PER_MESSAGE, client.gas_multiplier);
+		let two_messages = client.gas_limit(2 * GAS_PER_MESSAGE, client.gas_multiplier);
+		assert_eq!(
+			*broadcasts.lock().unwrap(),
+			vec![
+				broadcast(&[0, 1], two_messages, 4),
+				broadcast(&[2], one_message, 5),
+				broadcast(&[3, 4], two_messages, 6),
+			]
+		);
+
+		// a single message needing more than the max gas can't be split.
+		let client = CosmosClient { max_gas: GAS_PER_MESSAGE - 1, ..client };
+		match client.submit_messages(account, messages(1)).await {
+			Err(Error::GasLimitExceeded { gas_used: GAS_PER_MESSAGE, .. }) => {},
+			result => panic!("Expected the gas limit to be exceeded, found {result:?}"),
+		}
+	}
+
+	#[tokio::test]
+	async fn retries_transactions_that_ran_out_of_gas() {
+		let broadcasts = Arc::new(Mutex::new(vec![]));
+		let service = MockTxService {
+			execution_overhead: 1.2,
+			broadcasts: broadcasts.clone(),
+			..Default::default()
+		};
+		let client = mock_client(service, 4_000_000).await;
+		let account = BaseAccount { sequence: 0, ..Default::default() };
+
+		client.submit_messages(account, messages(2)).await.unwrap();
+		let gas_limit = client.gas_limit(2 * GAS_PER_MESSAGE, client.gas_multiplier);
+		let retry_gas_limit =
+			client.gas_limit(2 * GAS_PER_MESSAGE, client.gas_multiplier * OUT_OF_GAS_RETRY_FACTOR);
+		assert_eq!(
+			*broadcasts.lock().unwrap(),
+			vec![broadcast(&[0, 1], gas_limit, 0), broadcast(&[0, 1], retry_gas_limit, 1)]
+		);
+		// fees are rounded up to a whole amount.
+		assert_eq!(client.fee(100_001).amount[0].amount, "2501");
+	}
+}
@@ -110,11 +110,27 @@ pub struct Metrics {
 	pub number_of_sent_acknowledgments: Counter<U64>,
 	/// Total number of timed out packets.
 	pub number_of_sent_timeout_packets: Counter<U64>,
+	/// Total number of packets, acknowledgements and timeouts skipped by the packet filter.
+	pub number_of_filtered_packets: Counter<U64>,
+	/// Total number of messages dropped after running out of submission attempts.
+	pub number_of_dropped_messages: Counter<U64>,
 
 	/// Number of undelivered packets over time.
 	pub number_of_undelivered_packets: Gauge<U64>,
 	/// Number of undelivered acknowledgements over time.
 	pub number_of_undelivered_acknowledgements: Gauge<U64>,
+	/// Number of packets sent on each channel that the counterparty hasn't received.
+	pub number_of_undelivered_packets_per_channel: GaugeVec<U64>,
+	/// Latest finalized height seen.
+	pub latest_finalized_height: Gauge<U64>,
+	/// Latest height of this chain's light client on the counterparty.
+	pub latest_client_height_on_counterparty: Gauge<U64>,
+	/// Unix timestamp, in seconds, of the last batch of messages submitted successfully.
+	pub last_successful_submission_timestamp: Gauge<U64>,
+	/// Number of times the chain's subscriptions were re-established.
+	pub number_of_subscription_reconnects: Gauge<U64>,
+	/// Total number of blocks finalized differently by the primary and secondary rpc endpoints.
+	pub number_of_conflicting_finality_events: Counter<U64>,
 	/// Gas cost for every sent tx bundle.
 	pub gas_cost_for_sent_tx_bundle: Histogram,
 	/// Transaction length (in bytes) for every sent tx bundle.
@@ -122,6 +138,8 @@ pub struct Metrics {
 
 	/// Light client height.
 	pub light_client_height: HashMap<ClientId, LightClientMetrics>,
+	/// Seconds left until the light client's trusting period runs out.
+	pub light_client_seconds_to_expiry: HashMap<ClientId, Gauge<U64>>,
 
 	/// Average time between "send packet" events.
 	pub send_packet_event_time: Histogram,
@@ -196,6 +214,20 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			number_of_filtered_packets: register(
+				Counter::new(
+					&format!("hyperspace_{}_number_of_filtered_packets", prefix),
+					"Total number of packets, acknowledgements and timeouts skipped by the filter",
+				)?,
+				registry,
+			)?,
+			number_of_dropped_messages: register(
+				Counter::new(
+					&format!("hyperspace_{}_number_of_dropped_messages", prefix),
+					"Total number of messages dropped after running out of submission attempts",
+				)?,
+				registry,
+			)?,
 			number_of_undelivered_packets: register(
 				Gauge::new(
 					&format!("hyperspace_{}_number_of_undelivered_packets", prefix),
@@ -210,6 +242,51 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			number_of_undelivered_packets_per_channel: register(
+				GaugeVec::new(
+					Opts::new(
+						&format!("hyperspace_{}_number_of_undelivered_packets_per_channel", prefix),
+						"Number of packets sent on the channel that the counterparty hasn't received",
+					),
+					&["port_id", "channel_id"],
+				)?,
+				registry,
+			)?,
+			latest_finalized_height: register(
+				Gauge::new(
+					&format!("hyperspace_{}_latest_finalized_height", prefix),
+					"Latest finalized height seen",
+				)?,
+				registry,
+			)?,
+			latest_client_height_on_counterparty: register(
+				Gauge::new(
+					&format!("hyperspace_{}_latest_client_height_on_counterparty", prefix),
+					"Latest height of the light client on the counterparty",
+				)?,
+				registry,
+			)?,
+			last_successful_submission_timestamp: register(
+				Gauge::new(
+					&format!("hyperspace_{}_last_successful_submission_timestamp", prefix),
+					"Unix timestamp of the last batch of messages submitted successfully",
+				)?,
+				registry,
+			)?,
+			number_of_subscription_reconnects: register(
+				Gauge::new(
+					&format!("hyperspace_{}_number_of_subscription_reconnects", prefix),
+					"Number of times the chain's subscriptions were re-established",
+				)?,
+				registry,
+			)?,
+			number_of_conflicting_finality_events: register(
+				Counter::new(
+					&format!("hyperspace_{}_number_of_conflicting_finality_events", prefix),
+					"Total number of blocks finalized differently by the primary and secondary rpc",
+				)?,
+				registry,
+			)?,
 			gas_cost_for_sent_tx_bundle: register(
 				Histogram::with_opts(
 					HistogramOpts::new(
@@ -231,6 +308,7 @@ impl Metrics {
 				registry,
 			)?,
 			light_client_height: HashMap::new(),
+			light_client_seconds_to_expiry: HashMap::new(),
 			send_packet_event_time: register(
 				Histogram::with_opts(
 					HistogramOpts::new(
@@ -350,4 +428,27 @@ impl Metrics {
 			},
 		}
 	}
+
+	pub fn update_light_client_seconds_to_expiry(
+		&mut self,
+		client_id: &ClientId,
+		seconds: u64,
+		registry: &Registry,
+	) -> anyhow::Result<()> {
+		if !self.light_client_seconds_to_expiry.contains_key(client_id) {
+			let gauge = register(
+				Gauge::with_opts(
+					Opts::new(
+						format!("hyperspace_{}_light_client_seconds_to_expiry", self.prefix),
+						"Seconds left until the trusting period of the light client runs out",
+					)
+					.const_label("client_id", client_id.to_string()),
+				)?,
+				registry,
+			)?;
+			self.light_client_seconds_to_expiry.insert(client_id.clone(), gauge);
+		}
+		self.light_client_seconds_to_expiry[client_id].set(seconds);
+		Ok(())
+	}
 }
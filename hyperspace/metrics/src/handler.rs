@@ -12,16 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::data::Metrics;
+use crate::{data::Metrics, health::Liveness};
 use ibc::{
 	core::{
 		ics04_channel::{
 			events::{TimeoutOnClosePacket, TimeoutPacket},
 			packet::{Packet, Sequence},
 		},
-		ics24_host::identifier::{ChannelId, PortId},
+		ics24_host::identifier::{ChannelId, ClientId, PortId},
 	},
 	events::IbcEvent,
+	Height,
 };
 use ibc_proto::google::protobuf::Any;
 use prometheus::{Histogram, Registry};
@@ -29,7 +30,7 @@ use std::{
 	collections::HashMap,
 	ops::DerefMut,
 	sync::{Arc, Mutex},
-	time::Instant,
+	time::{Instant, SystemTime, UNIX_EPOCH},
 };
 
 #[derive(Eq, PartialEq, Hash)]
@@ -63,6 +64,8 @@ pub struct MetricsHandler {
 	counterparty_last_sent_packet_time: Option<PacketMap>,
 	counterparty_last_sent_acknowledgment_time: Option<PacketMap>,
 	counterparty_last_sent_timeout_packet_time: Option<PacketMap>,
+
+	liveness: Option<Liveness>,
 }
 
 impl MetricsHandler {
@@ -77,9 +80,51 @@ impl MetricsHandler {
 			counterparty_last_sent_packet_time: None,
 			counterparty_last_sent_acknowledgment_time: None,
 			counterparty_last_sent_timeout_packet_time: None,
+			liveness: None,
 		}
 	}
 
+	/// Reports the finality events handled for this chain to `liveness`.
+	pub fn with_liveness(mut self, liveness: Liveness) -> Self {
+		liveness.register(&self.metrics.prefix);
+		self.liveness = Some(liveness);
+		self
+	}
+
+	pub fn handle_finality_event(&self) {
+		if let Some(liveness) = self.liveness.as_ref() {
+			liveness.record_finality_event(&self.metrics.prefix);
+		}
+	}
+
+	pub fn handle_finalized_height(&self, height: Height) {
+		self.metrics.latest_finalized_height.set(height.revision_height);
+	}
+
+	pub fn handle_counterparty_client_height(&self, height: Height) {
+		self.metrics.latest_client_height_on_counterparty.set(height.revision_height);
+	}
+
+	pub fn handle_undelivered_packets(&self, channel_id: &ChannelId, port_id: &PortId, count: u64) {
+		self.metrics
+			.number_of_undelivered_packets_per_channel
+			.with_label_values(&[port_id.as_str(), &channel_id.to_string()])
+			.set(count);
+	}
+
+	pub fn handle_subscription_reconnects(&self, count: u64) {
+		self.metrics.number_of_subscription_reconnects.set(count);
+	}
+
+	pub fn handle_conflicting_finality(&self) {
+		self.metrics.number_of_conflicting_finality_events.inc();
+	}
+
+	pub fn handle_successful_submission(&self) {
+		let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+		self.metrics.last_successful_submission_timestamp.set(now.as_secs());
+	}
+
 	pub async fn handle_events(&mut self, events: &[IbcEvent]) -> anyhow::Result<()> {
 		for event in events {
 			match event {
@@ -174,6 +219,18 @@ impl MetricsHandler {
 			Some(self.last_sent_timeout_packet_time.clone());
 	}
 
+	pub fn handle_client_expiry(
+		&mut self,
+		client_id: &ClientId,
+		seconds_to_expiry: u64,
+	) -> anyhow::Result<()> {
+		self.metrics.update_light_client_seconds_to_expiry(
+			client_id,
+			seconds_to_expiry,
+			&self.registry,
+		)
+	}
+
 	pub async fn handle_timeouts(&self, timeouts: &[Any]) {
 		for message in timeouts {
 			match message.type_url.as_str() {
@@ -185,6 +242,14 @@ impl MetricsHandler {
 		}
 	}
 
+	pub fn handle_filtered_packets(&self, count: u64) {
+		self.metrics.number_of_filtered_packets.inc_by(count);
+	}
+
+	pub fn handle_dropped_messages(&self, count: u64) {
+		self.metrics.number_of_dropped_messages.inc_by(count);
+	}
+
 	pub async fn handle_transaction_costs(&self, batch_weight: u64, messages: &[Any]) {
 		let batch_size = messages.iter().map(|x| x.value.len()).sum::<usize>();
 		self.metrics.gas_cost_for_sent_tx_bundle.observe(batch_weight as f64);
@@ -221,3 +286,50 @@ fn observe_delta_time(maybe_time: &mut Option<Instant>, time_metrics: &Histogram
 		*maybe_time = Some(now);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+
+	fn handler() -> MetricsHandler {
+		let registry = Registry::new();
+		let metrics = Metrics::register("test", &registry).unwrap();
+		MetricsHandler::new(registry, metrics)
+	}
+
+	#[test]
+	fn liveness_metrics_are_updated() {
+		let handler = handler();
+		handler.handle_finalized_height(Height::new(1, 42));
+		handler.handle_counterparty_client_height(Height::new(1, 40));
+		handler.handle_undelivered_packets(&ChannelId::new(3), &PortId::transfer(), 2);
+		handler.handle_successful_submission();
+		handler.handle_subscription_reconnects(3);
+		handler.handle_conflicting_finality();
+
+		let metrics = &handler.metrics;
+		assert_eq!(metrics.latest_finalized_height.get(), 42);
+		assert_eq!(metrics.latest_client_height_on_counterparty.get(), 40);
+		let undelivered = metrics
+			.number_of_undelivered_packets_per_channel
+			.with_label_values(&["transfer", "channel-3"])
+			.get();
+		assert_eq!(undelivered, 2);
+		assert!(metrics.last_successful_submission_timestamp.get() > 0);
+		assert_eq!(metrics.number_of_subscription_reconnects.get(), 3);
+		assert_eq!(metrics.number_of_conflicting_finality_events.get(), 1);
+	}
+
+	#[test]
+	fn finality_events_are_reported_to_liveness() {
+		let liveness = Liveness::default();
+		let handler = handler().with_liveness(liveness.clone());
+		let staleness = Duration::from_secs(60);
+		let later = Instant::now() + Duration::from_secs(120);
+		assert_eq!(liveness.stale_chains(later, staleness), ["test"]);
+
+		handler.handle_finality_event();
+		assert!(liveness.stale_chains(Instant::now(), staleness).is_empty());
+	}
+}
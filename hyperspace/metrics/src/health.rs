@@ -0,0 +1,134 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Liveness of the relayer, as reported by the `/health` endpoint.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+/// Default time without a processed finality event after which a chain is reported as stale, in
+/// seconds.
+pub const DEFAULT_HEALTH_STALENESS: u64 = 5 * 60;
+
+/// Time of the last finality event processed for each chain, shared between the relayer's tasks
+/// and the `/health` endpoint.
+#[derive(Clone, Default)]
+pub struct Liveness {
+	last_finality_events: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl Liveness {
+	/// Starts tracking `chain`, which is stale if no finality event is processed for it within the
+	/// staleness window from now.
+	pub fn register(&self, chain: &str) {
+		let mut last_finality_events = self.last_finality_events.lock().unwrap();
+		last_finality_events.entry(chain.to_string()).or_insert_with(Instant::now);
+	}
+
+	/// Records that a finality event of `chain` was processed.
+	pub fn record_finality_event(&self, chain: &str) {
+		self.record_finality_event_at(chain, Instant::now())
+	}
+
+	fn record_finality_event_at(&self, chain: &str, at: Instant) {
+		self.last_finality_events.lock().unwrap().insert(chain.to_string(), at);
+	}
+
+	/// Returns the chains, in alphabetical order, whose last finality event was processed more than
+	/// `staleness` before `now`.
+	pub fn stale_chains(&self, now: Instant, staleness: Duration) -> Vec<String> {
+		let last_finality_events = self.last_finality_events.lock().unwrap();
+		let mut stale = last_finality_events
+			.iter()
+			.filter(|(_, at)| now.saturating_duration_since(**at) > staleness)
+			.map(|(chain, _)| chain.clone())
+			.collect::<Vec<_>>();
+		stale.sort();
+		stale
+	}
+}
+
+/// Configuration of the `/health` endpoint.
+#[derive(Clone)]
+pub struct HealthCheck {
+	pub liveness: Liveness,
+	/// Time without a processed finality event after which a chain is reported as stale.
+	pub staleness: Duration,
+}
+
+impl HealthCheck {
+	/// Returns an error describing the stale chains, if any.
+	pub fn check(&self, now: Instant) -> Result<(), String> {
+		let stale = self.liveness.stale_chains(now, self.staleness);
+		if stale.is_empty() {
+			return Ok(())
+		}
+		Err(format!(
+			"No finality event processed within {:?} for: {}",
+			self.staleness,
+			stale.join(", ")
+		))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn chains_are_stale_after_the_window() {
+		let liveness = Liveness::default();
+		let start = Instant::now();
+		liveness.record_finality_event_at("chain_a", start);
+		liveness.record_finality_event_at("chain_b", start + Duration::from_secs(30));
+		let staleness = Duration::from_secs(60);
+
+		assert!(liveness.stale_chains(start + Duration::from_secs(60), staleness).is_empty());
+		assert_eq!(liveness.stale_chains(start + Duration::from_secs(61), staleness), ["chain_a"]);
+		assert_eq!(
+			liveness.stale_chains(start + Duration::from_secs(100), staleness),
+			["chain_a", "chain_b"]
+		);
+
+		liveness.record_finality_event_at("chain_a", start + Duration::from_secs(90));
+		assert_eq!(liveness.stale_chains(start + Duration::from_secs(100), staleness), ["chain_b"]);
+	}
+
+	#[test]
+	fn registering_keeps_processed_events() {
+		let liveness = Liveness::default();
+		let start = Instant::now();
+		liveness.record_finality_event_at("chain_a", start + Duration::from_secs(100));
+		liveness.register("chain_a");
+
+		let staleness = Duration::from_secs(60);
+		assert!(liveness.stale_chains(start + Duration::from_secs(120), staleness).is_empty());
+	}
+
+	#[test]
+	fn health_check_reports_stale_chains() {
+		let health =
+			HealthCheck { liveness: Liveness::default(), staleness: Duration::from_secs(5) };
+		let start = Instant::now();
+		health.liveness.record_finality_event_at("chain_a", start);
+
+		assert_eq!(health.check(start), Ok(()));
+		let error = health.check(start + Duration::from_secs(6)).unwrap_err();
+		assert_eq!(error, "No finality event processed within 5s for: chain_a");
+	}
+}
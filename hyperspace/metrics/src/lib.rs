@@ -14,7 +14,9 @@
 
 pub mod data;
 pub mod handler;
+pub mod health;
 
+use health::HealthCheck;
 use hyper::{
 	http::StatusCode,
 	server::Server,
@@ -31,7 +33,7 @@ pub use prometheus::{
 	Registry,
 };
 use prometheus::{core::Collector, Encoder, TextEncoder};
-use std::net::SocketAddr;
+use std::{net::SocketAddr, time::Instant};
 
 pub fn register<T: Clone + Collector + 'static>(
 	metric: T,
@@ -59,49 +61,67 @@ pub enum Error {
 	PortInUse(SocketAddr),
 }
 
-async fn request_metrics(req: Request<Body>, registry: Registry) -> Result<Response<Body>, Error> {
-	if req.uri().path() == "/metrics" {
-		let metric_families = registry.gather();
-		let mut buffer = vec![];
-		let encoder = TextEncoder::new();
-		encoder.encode(&metric_families, &mut buffer).unwrap();
-
-		Response::builder()
-			.status(StatusCode::OK)
-			.header("Content-Type", encoder.format_type())
-			.body(Body::from(buffer))
-			.map_err(Error::Http)
-	} else {
-		Response::builder()
+async fn request_metrics(
+	req: Request<Body>,
+	registry: Registry,
+	health: Option<HealthCheck>,
+) -> Result<Response<Body>, Error> {
+	match (req.uri().path(), health) {
+		("/metrics", _) => {
+			let metric_families = registry.gather();
+			let mut buffer = vec![];
+			let encoder = TextEncoder::new();
+			encoder.encode(&metric_families, &mut buffer).unwrap();
+
+			Response::builder()
+				.status(StatusCode::OK)
+				.header("Content-Type", encoder.format_type())
+				.body(Body::from(buffer))
+				.map_err(Error::Http)
+		},
+		("/health", Some(health)) => {
+			let (status, body) = match health.check(Instant::now()) {
+				Ok(()) => (StatusCode::OK, "OK".to_string()),
+				Err(reason) => (StatusCode::SERVICE_UNAVAILABLE, reason),
+			};
+			Response::builder().status(status).body(Body::from(body)).map_err(Error::Http)
+		},
+		_ => Response::builder()
 			.status(StatusCode::NOT_FOUND)
 			.body(Body::from("Not found."))
-			.map_err(Error::Http)
+			.map_err(Error::Http),
 	}
 }
 
 /// Initializes the metrics context, and starts an HTTP server
-/// to serve metrics.
-pub async fn init_prometheus(prometheus_addr: SocketAddr, registry: Registry) -> Result<(), Error> {
+/// to serve metrics, and the relayer's liveness on `/health` when `health` is given.
+pub async fn init_prometheus(
+	prometheus_addr: SocketAddr,
+	registry: Registry,
+	health: Option<HealthCheck>,
+) -> Result<(), Error> {
 	let listener = tokio::net::TcpListener::bind(&prometheus_addr)
 		.await
 		.map_err(|_| Error::PortInUse(prometheus_addr))?;
 
-	init_prometheus_with_listener(listener, registry).await
+	init_prometheus_with_listener(listener, registry, health).await
 }
 
 /// Init prometheus using the given listener.
 async fn init_prometheus_with_listener(
 	listener: tokio::net::TcpListener,
 	registry: Registry,
+	health: Option<HealthCheck>,
 ) -> Result<(), Error> {
 	let listener = hyper::server::conn::AddrIncoming::from_listener(listener)?;
 
 	let service = make_service_fn(move |_| {
 		let registry = registry.clone();
+		let health = health.clone();
 
 		async move {
 			Ok::<_, hyper::Error>(service_fn(move |req: Request<Body>| {
-				request_metrics(req, registry.clone())
+				request_metrics(req, registry.clone(), health.clone())
 			}))
 		}
 	});
@@ -14,11 +14,17 @@
 
 use anyhow::anyhow;
 use codec::{Decode, Encode};
-use std::{collections::BTreeMap, fmt::Display, pin::Pin, time::Duration};
+use std::{
+	collections::BTreeMap,
+	fmt::{Debug, Display},
+	pin::Pin,
+	sync::atomic::Ordering,
+	time::Duration,
+};
 
 use beefy_gadget_rpc::BeefyApiClient;
 use finality_grandpa::BlockNumberOps;
-use futures::{Stream, StreamExt, TryFutureExt};
+use futures::{stream::BoxStream, Stream, StreamExt, TryFutureExt};
 use grandpa_light_client_primitives::{FinalityProof, ParachainHeaderProofs};
 use ibc_proto::google::protobuf::Any;
 use sp_runtime::{
@@ -40,49 +46,100 @@ use subxt::tx::{
 use transaction_payment_rpc::TransactionPaymentApiClient;
 use transaction_payment_runtime_api::RuntimeDispatchInfo;
 
-use primitives::{Chain, IbcProvider, MisbehaviourHandler};
+use primitives::{
+	submit_misbehaviour_msg, Chain, IbcProvider, MisbehaviourHandler, SubmitErrorKind,
+};
 
 use super::{error::Error, signer::ExtrinsicSigner, ParachainClient};
 use crate::{
 	config,
-	parachain::{
-		api,
-		api::runtime_types::{frame_system::Phase, pallet_ibc::Any as RawAny},
-		UncheckedExtrinsic,
-	},
+	events::{parse_ibc_event, IbcEvents},
+	finality_protocol::FinalityEvent,
+	parachain::{api, api::runtime_types::pallet_ibc::Any as RawAny, UncheckedExtrinsic},
 	provider::TransactionId,
-	utils::MetadataIbcEventWrapper,
-	FinalityProtocol,
+	reconnect::{self, ReconnectPolicy},
+	runtime_upgrade, watchtower, FinalityProtocol,
 };
 use finality_grandpa_rpc::GrandpaApiClient;
 use ibc::{
 	core::{
-		ics02_client::{
-			events::UpdateClient,
-			msgs::{update_client::MsgUpdateAnyClient, ClientMsg},
-		},
+		ics02_client::{events::UpdateClient, msgs::ClientMsg},
 		ics26_routing::msgs::Ics26Envelope,
 	},
 	events::IbcEvent,
-	tx_msg::Msg,
 };
 use ics10_grandpa::client_message::{ClientMessage, Misbehaviour, RelayChainHeader};
 use pallet_ibc::light_clients::AnyClientMessage;
 use primitives::mock::LocalClientTypes;
-use sp_core::{twox_128, H256};
+use sp_core::H256;
 use tokio::time::sleep;
 
 type GrandpaJustification = grandpa_light_client_primitives::justification::GrandpaJustification<
 	polkadot_core_primitives::Header,
 >;
 
-type BeefyJustification =
-	beefy_primitives::SignedCommitment<u32, beefy_primitives::crypto::Signature>;
-
 /// An encoded justification proving that the given header has been finalized
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 struct JustificationNotification(sp_core::Bytes);
 
+/// Subscribes to the relay chain's justifications for `protocol` on a new connection to `url`.
+async fn subscribe_justifications(
+	url: String,
+	protocol: FinalityProtocol,
+) -> Result<BoxStream<'static, Result<FinalityEvent, Error>>, Error> {
+	let client = reconnect::connect(&url).await?;
+	match protocol {
+		FinalityProtocol::Grandpa => {
+			let subscription =
+				GrandpaApiClient::<JustificationNotification, H256, u32>::subscribe_justifications(
+					&client,
+				)
+				.await
+				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+			let stream = subscription.filter_map(|notification| {
+				let justification = decode_justification(notification, "Grandpa Justification");
+				futures::future::ready(justification.map(|j| j.map(FinalityEvent::Grandpa)))
+			});
+			Ok(reconnect::keep_alive(client, stream).boxed())
+		},
+		FinalityProtocol::Beefy => {
+			let subscription =
+				BeefyApiClient::<JustificationNotification, H256>::subscribe_justifications(&client)
+					.await
+					.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+			let stream = subscription.filter_map(|notification| {
+				let commitment = decode_justification(notification, "SignedCommitment");
+				futures::future::ready(commitment.map(|c| {
+					c.map(|signed_commitment| FinalityEvent::Beefy {
+						signed_commitment,
+						skipped: None,
+					})
+				}))
+			});
+			Ok(reconnect::keep_alive(client, stream).boxed())
+		},
+	}
+}
+
+/// Decodes the justification carried by a notification. Notifications that fail to decode are
+/// logged and skipped, rpc errors are returned so that the subscription is re-established.
+fn decode_justification<J: Decode, E: Debug>(
+	notification: Result<JustificationNotification, E>,
+	name: &str,
+) -> Option<Result<J, Error>> {
+	let encoded = match notification {
+		Ok(JustificationNotification(sp_core::Bytes(encoded))) => encoded,
+		Err(e) => return Some(Err(Error::from(format!("Rpc Error {:?}", e)))),
+	};
+	match J::decode(&mut &*encoded) {
+		Ok(justification) => Some(Ok(justification)),
+		Err(err) => {
+			log::error!("{} scale decode error: {}", name, err);
+			None
+		},
+	}
+}
+
 #[async_trait::async_trait]
 impl<T: config::Config + Send + Sync> Chain for ParachainClient<T>
 where
@@ -128,7 +185,7 @@ where
 			let tx_params = ParachainExtrinsicsParamsBuilder::new()
 				.tip(Tip::new(100_000))
 				.era(Era::Immortal, self.para_client.genesis_hash());
-			let call = api::tx().ibc().deliver(messages);
+			let call = runtime_upgrade::deliver(messages);
 			self.para_client.tx().create_signed(&call, &signer, tx_params.into()).await?
 		};
 		let dispatch_info =
@@ -145,96 +202,71 @@ where
 	async fn finality_notifications(
 		&self,
 	) -> Pin<Box<dyn Stream<Item = <Self as IbcProvider>::FinalityEvent> + Send + Sync>> {
+		let url = self.relay_chain_rpc_url.clone();
+		let protocol = self.finality_protocol.clone();
+		let stream = reconnect::reconnecting_stream(
+			format!("{} finality notifications", self.name),
+			ReconnectPolicy::default(),
+			self.subscription_reconnects.clone(),
+			move || subscribe_justifications(url.clone(), protocol.clone()),
+			FinalityEvent::height,
+		);
 		match self.finality_protocol {
-			FinalityProtocol::Grandpa => {
-				let subscription =
-					GrandpaApiClient::<JustificationNotification, sp_core::H256, u32>::subscribe_justifications(
-						&*self.relay_ws_client,
-					)
-						.await
-						.expect("Failed to subscribe to grandpa justifications")
-						.chunks(6)
-						.map(|mut notifs| notifs.remove(notifs.len() - 1)); // skip every 4 finality notifications
-
-				let stream = subscription.filter_map(|justification_notif| {
-					let encoded_justification = match justification_notif {
-						Ok(JustificationNotification(sp_core::Bytes(justification))) =>
-							justification,
-						Err(err) => {
-							log::error!("Failed to fetch Justification: {}", err);
-							return futures::future::ready(None)
-						},
-					};
-
-					let justification =
-						match GrandpaJustification::decode(&mut &*encoded_justification) {
-							Ok(j) => j,
-							Err(err) => {
-								log::error!("Grandpa Justification scale decode error: {}", err);
-								return futures::future::ready(None)
-							},
-						};
-					futures::future::ready(Some(Self::FinalityEvent::Grandpa(justification)))
-				});
-
-				Box::pin(Box::new(stream))
-			},
+			// skip every 4 finality notifications
+			FinalityProtocol::Grandpa =>
+				Box::pin(stream.chunks(6).map(|mut notifs| notifs.remove(notifs.len() - 1))),
+			// deliver commitments that were skipped for a mandatory commitment before new ones
 			FinalityProtocol::Beefy => {
-				let subscription =
-					BeefyApiClient::<JustificationNotification, sp_core::H256>::subscribe_justifications(
-						&*self.relay_ws_client,
-					)
-						.await
-						.expect("Failed to subscribe to beefy justifications");
-
-				let stream = subscription.filter_map(|commitment_notification| {
-					let encoded_commitment = match commitment_notification {
-						Ok(JustificationNotification(sp_core::Bytes(commitment))) => commitment,
-						Err(err) => {
-							log::error!("Failed to fetch Commitment: {}", err);
-							return futures::future::ready(None)
-						},
-					};
-
-					let signed_commitment =
-						match BeefyJustification::decode(&mut &*encoded_commitment) {
-							Ok(c) => c,
-							Err(err) => {
-								log::error!("SignedCommitment scale decode error: {}", err);
-								return futures::future::ready(None)
+				let skipped = self.skipped_beefy_commitment.clone();
+				Box::pin(futures::stream::unfold(
+					(stream, skipped),
+					|(mut stream, skipped)| async move {
+						let skipped_commitment = skipped.lock().unwrap().take();
+						let event = match skipped_commitment {
+							Some(event) => {
+								log::info!(
+									"Proving skipped beefy commitment for block {}",
+									event.height()
+								);
+								event
 							},
+							None => stream.next().await?,
 						};
-					futures::future::ready(Some(Self::FinalityEvent::Beefy(signed_commitment)))
-				});
-
-				Box::pin(Box::new(stream))
+						Some((event, (stream, skipped)))
+					},
+				))
 			},
 		}
 	}
 
+	fn subscription_reconnects(&self) -> u64 {
+		self.subscription_reconnects.load(Ordering::SeqCst)
+	}
+
 	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Error> {
 		let messages = messages
 			.into_iter()
 			.map(|msg| RawAny { type_url: msg.type_url.as_bytes().to_vec(), value: msg.value })
 			.collect::<Vec<_>>();
 
-		let call = api::tx().ibc().deliver(messages);
+		let call = runtime_upgrade::deliver(messages);
 		let (ext_hash, block_hash) = self.submit_call(call).await?;
 
 		Ok(TransactionId { ext_hash, block_hash })
 	}
 
+	fn submit_error_kind(&self, error: &Error) -> SubmitErrorKind {
+		error.submit_error_kind()
+	}
+
 	async fn query_client_message(&self, update: UpdateClient) -> Result<AnyClientMessage, Error> {
-		use api::runtime_types::{
-			frame_system::EventRecord,
-			pallet_ibc::pallet::{Call as IbcCall, Event as PalletEvent},
-		};
+		use api::runtime_types::pallet_ibc::pallet::Call as IbcCall;
+		use subxt::events::Phase;
 
 		#[cfg(feature = "dali")]
-		use api::runtime_types::dali_runtime::{Call as RuntimeCall, Event};
+		use api::runtime_types::dali_runtime::Call as RuntimeCall;
 		#[cfg(not(feature = "dali"))]
-		use api::runtime_types::parachain_runtime::{Call as RuntimeCall, Event};
-		use pallet_ibc::events::IbcEvent as RawIbcEvent;
+		use api::runtime_types::parachain_runtime::Call as RuntimeCall;
 
 		let host_height = update.height();
 
@@ -256,45 +288,26 @@ where
 			}
 		};
 
-		let mut storage_key = twox_128(b"System").to_vec();
-		storage_key.extend(twox_128(b"Events").to_vec());
-
-		let event_bytes = self
-			.para_client
-			.rpc()
-			.storage(&*storage_key, Some(block_hash))
-			.await?
-			.map(|e| e.0)
-			.ok_or_else(|| Error::from("No events found".to_owned()))?;
-		let events: Vec<EventRecord<Event, H256>> = Decode::decode(&mut &*event_bytes)
-			.map_err(|e| Error::from(format!("Failed to decode events: {:?}", e)))?;
+		let events = self.para_client.events().at(Some(block_hash)).await?;
 		let (transaction_index, event_index) = events
-			.into_iter()
-			.find_map(|pallet_event| {
-				let tx_index = match pallet_event.phase {
+			.iter()
+			.find_map(|details| {
+				let details = details.ok()?;
+				let ibc_events = details.as_event::<IbcEvents>().ok()??;
+				let tx_index = match details.phase() {
 					Phase::ApplyExtrinsic(i) => i as usize,
 					other => {
 						log::error!("Unexpected event phase: {:?}", other);
 						return None
 					},
 				};
-				if let Event::Ibc(PalletEvent::Events { events }) = pallet_event.event {
-					events.into_iter().enumerate().find_map(|(i, event)| match event {
-						Ok(ibc_event) => IbcEvent::try_from(RawIbcEvent::from(
-							MetadataIbcEventWrapper(ibc_event),
-						))
-						.map(|event| match event {
-							IbcEvent::UpdateClient(ev_update) if ev_update == update =>
-								Some((tx_index, i)),
-							_ => None,
-						})
-						.ok()
-						.flatten(),
+				ibc_events.events.into_iter().enumerate().find_map(|(i, event)| {
+					match parse_ibc_event(event.ok()?).ok()? {
+						IbcEvent::UpdateClient(ev_update) if ev_update == update =>
+							Some((tx_index, i)),
 						_ => None,
-					})
-				} else {
-					None
-				}
+					}
+				})
 			})
 			.ok_or_else(|| Error::from("No update client event found".to_owned()))?;
 
@@ -324,6 +337,11 @@ where
 				match envelope {
 					Ok(Ics26Envelope::Ics2Msg(ClientMsg::UpdateClient(update_msg))) =>
 						return Ok(update_msg.client_message),
+					// The latest header of a batch is the one the update event refers to.
+					Ok(Ics26Envelope::Ics2Msg(ClientMsg::UpdateClientBatch(batch_msg))) =>
+						if let Some(client_message) = batch_msg.client_messages.into_iter().last() {
+							return Ok(client_message)
+						},
 					_ => (),
 				}
 			},
@@ -332,6 +350,46 @@ where
 
 		Err(Error::from("No client message found".to_owned()))
 	}
+
+	fn has_secondary_rpc(&self) -> bool {
+		self.secondary_relay_client.is_some()
+	}
+
+	async fn query_conflicting_finality(
+		&self,
+		finality_event: FinalityEvent,
+	) -> Result<Option<AnyClientMessage>, anyhow::Error> {
+		let (secondary_client, secondary_ws_client) =
+			match (&self.secondary_relay_client, &self.secondary_relay_ws_client) {
+				(Some(client), Some(ws_client)) => (client, ws_client),
+				_ => return Ok(None),
+			};
+		// beefy clients have no misbehaviour to report finality conflicts with.
+		let justification = match finality_event {
+			FinalityEvent::Grandpa(justification) => justification,
+			FinalityEvent::Beefy { .. } => return Ok(None),
+		};
+		let number = justification.commit.target_number;
+		let hash = justification.commit.target_hash;
+		let secondary_hash =
+			match watchtower::conflicting_block_hash(secondary_client, number, hash).await? {
+				Some(secondary_hash) => secondary_hash,
+				None => return Ok(None),
+			};
+		log::warn!(
+			"Block {} finalized by {} is {:?} on the primary rpc and {:?} on the secondary rpc",
+			number,
+			self.name,
+			hash,
+			secondary_hash
+		);
+
+		let misbehaviour = ClientMessage::Misbehaviour(Misbehaviour {
+			first_finality_proof: watchtower::prove_finality(&self.relay_ws_client, number).await?,
+			second_finality_proof: watchtower::prove_finality(secondary_ws_client, number).await?,
+		});
+		Ok(Some(AnyClientMessage::Grandpa(misbehaviour)))
+	}
 }
 
 #[async_trait::async_trait]
@@ -445,13 +503,13 @@ where
 						second_finality_proof: trusted_finality_proof,
 					});
 
+					let msg = submit_misbehaviour_msg(
+						counterparty,
+						self.client_id(),
+						AnyClientMessage::Grandpa(misbehaviour.clone()),
+					);
 					counterparty
-						.submit(vec![MsgUpdateAnyClient::<LocalClientTypes>::new(
-							self.client_id(),
-							AnyClientMessage::Grandpa(misbehaviour.clone()),
-							counterparty.account_id(),
-						)
-						.to_any()])
+						.submit(vec![msg])
 						.map_err(|e| anyhow!("Failed to submit misbehaviour report: {:?}", e))
 						.await?;
 				}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use ibc::{core::ics02_client, timestamp::ParseTimestampError};
+use primitives::SubmitErrorKind;
 use sp_runtime::traits::BlakeTwo256;
 use sp_trie::TrieError;
 use std::num::ParseIntError;
@@ -82,6 +83,40 @@ pub enum Error {
 	JosnrpseeError(#[from] jsonrpsee::core::Error),
 }
 
+/// Fragments of the errors the node and the transaction pool return for submissions that may
+/// succeed when retried: stale or future nonces, transactions replaced or dropped by a full pool
+/// and broken connections.
+const RETRIABLE_SUBMIT_ERRORS: &[&str] = &[
+	"Priority is too low",
+	"Transaction is outdated",
+	"Transaction will be valid in the future",
+	"Dropped",
+	"Usurped",
+	"Connection reset",
+	"Networking or low-level protocol error",
+	"RestartNeeded",
+	"Request timeout",
+];
+
+impl Error {
+	/// Classifies an error returned while submitting an extrinsic.
+	pub fn submit_error_kind(&self) -> SubmitErrorKind {
+		match self {
+			// the extrinsic was included but failed, e.g. a message didn't decode.
+			Error::Subxt(subxt::Error::Runtime(_)) | Error::Codec(_) => SubmitErrorKind::Fatal,
+			Error::Subxt(_) | Error::SubxtRRpc(_) | Error::JosnrpseeError(_) => {
+				let error = format!("{self:?}");
+				if RETRIABLE_SUBMIT_ERRORS.iter().any(|fragment| error.contains(fragment)) {
+					SubmitErrorKind::Retriable
+				} else {
+					SubmitErrorKind::Fatal
+				}
+			},
+			_ => SubmitErrorKind::Fatal,
+		}
+	}
+}
+
 impl From<String> for Error {
 	fn from(error: String) -> Self {
 		Self::Custom(error)
@@ -0,0 +1,292 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parsing of the events emitted by pallet-ibc into [`IbcEvent`]s.
+//!
+//! pallet-ibc deposits the ibc events of a block as one `Events` event, a list holding the SCALE
+//! encoded [`PalletIbcEvent`] of every ibc event, or an [`IbcError`] for the ones that failed.
+//! These are decoded with pallet-ibc's own types rather than the ones generated from the runtime
+//! metadata, so that every event the pallet emits is understood, and converted back into the
+//! [`IbcEvent`]s pallet-ibc emitted them for.
+//!
+//! For every event variant, parsing gives back the [`IbcEvent`] pallet-ibc converted, up to the
+//! fields pallet-ibc doesn't emit, such as packet data. An event whose variant pallet-ibc doesn't
+//! know fails to decode rather than being parsed as another event.
+
+use crate::Error;
+use codec::{Decode, DecodeAll, Encode};
+use ibc::events::IbcEvent;
+use pallet_ibc::{errors::IbcError, events::IbcEvent as PalletIbcEvent};
+use subxt::events::{Events, StaticEvent};
+
+/// The `Events` event of pallet-ibc.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct IbcEvents {
+	pub events: Vec<Result<PalletIbcEvent, IbcError>>,
+}
+
+impl StaticEvent for IbcEvents {
+	const PALLET: &'static str = "Ibc";
+	const EVENT: &'static str = "Events";
+}
+
+impl IbcEvents {
+	/// Decodes the SCALE encoded fields of an `Events` event, which must be all of `bytes`.
+	pub fn decode_fields(mut bytes: &[u8]) -> Result<Self, Error> {
+		Ok(Self::decode_all(&mut bytes)?)
+	}
+
+	/// Parses the events, skipping the errors of the failed ones.
+	pub fn parse(self) -> Result<Vec<IbcEvent>, Error> {
+		self.events.into_iter().flatten().map(parse_ibc_event).collect()
+	}
+}
+
+/// Converts an event emitted by pallet-ibc back into the [`IbcEvent`] it was emitted for.
+pub fn parse_ibc_event(event: PalletIbcEvent) -> Result<IbcEvent, Error> {
+	IbcEvent::try_from(event).map_err(|e| Error::Custom(format!("Failed to parse ibc event: {e}")))
+}
+
+/// Parses the ibc events found in `events`, the events of a block, see [`IbcEvents::parse`].
+pub fn parse_ibc_events<T: subxt::Config>(events: &Events<T>) -> Result<Vec<IbcEvent>, Error> {
+	let mut parsed = vec![];
+	for ibc_events in events.find::<IbcEvents>() {
+		parsed.extend(ibc_events?.parse()?);
+	}
+	Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ibc::core::ics04_channel::channel::Order;
+
+	/// Position of the event's variant in [`PalletIbcEvent`], stops compiling when pallet-ibc gains
+	/// an event so that it gets added to [`events`].
+	fn variant_index(event: &PalletIbcEvent) -> usize {
+		match event {
+			PalletIbcEvent::NewBlock { .. } => 0,
+			PalletIbcEvent::CreateClient { .. } => 1,
+			PalletIbcEvent::UpdateClient { .. } => 2,
+			PalletIbcEvent::UpgradeClient { .. } => 3,
+			PalletIbcEvent::ClientMisbehaviour { .. } => 4,
+			PalletIbcEvent::ClientRecovered { .. } => 5,
+			PalletIbcEvent::OpenInitConnection { .. } => 6,
+			PalletIbcEvent::OpenConfirmConnection { .. } => 7,
+			PalletIbcEvent::ConnectionCancelled { .. } => 8,
+			PalletIbcEvent::OpenTryConnection { .. } => 9,
+			PalletIbcEvent::OpenAckConnection { .. } => 10,
+			PalletIbcEvent::OpenInitChannel { .. } => 11,
+			PalletIbcEvent::OpenConfirmChannel { .. } => 12,
+			PalletIbcEvent::OpenTryChannel { .. } => 13,
+			PalletIbcEvent::OpenAckChannel { .. } => 14,
+			PalletIbcEvent::CloseInitChannel { .. } => 15,
+			PalletIbcEvent::CloseConfirmChannel { .. } => 16,
+			PalletIbcEvent::UpgradeInitChannel { .. } => 17,
+			PalletIbcEvent::UpgradeTryChannel { .. } => 18,
+			PalletIbcEvent::UpgradeAckChannel { .. } => 19,
+			PalletIbcEvent::UpgradeConfirmChannel { .. } => 20,
+			PalletIbcEvent::UpgradeCancelChannel { .. } => 21,
+			PalletIbcEvent::ReceivePacket { .. } => 22,
+			PalletIbcEvent::SendPacket { .. } => 23,
+			PalletIbcEvent::AcknowledgePacket { .. } => 24,
+			PalletIbcEvent::WriteAcknowledgement { .. } => 25,
+			PalletIbcEvent::TimeoutPacket { .. } => 26,
+			PalletIbcEvent::TimeoutOnClosePacket { .. } => 27,
+			PalletIbcEvent::AcknowledgementsPruned { .. } => 28,
+			PalletIbcEvent::Empty => 29,
+			PalletIbcEvent::ChainError => 30,
+			PalletIbcEvent::AppModule { .. } => 31,
+		}
+	}
+
+	/// One event of every variant.
+	fn events() -> Vec<PalletIbcEvent> {
+		let bytes = |s: &str| s.as_bytes().to_vec();
+		let (revision_height, revision_number) = (42, 1);
+		let (client_id, client_type) = (bytes("07-tendermint-0"), bytes("07-tendermint"));
+		let (connection_id, port_id) = (bytes("connection-0"), bytes("transfer"));
+		let (channel_id, counterparty_channel_id) = (bytes("channel-0"), bytes("channel-1"));
+		let seq = 7;
+
+		macro_rules! client_event {
+			($variant:ident $(, $field:ident: $value:expr)*) => {
+				PalletIbcEvent::$variant {
+					client_id: client_id.clone(),
+					client_type: client_type.clone(),
+					revision_height,
+					revision_number,
+					consensus_height: 40,
+					consensus_revision_number: 1,
+					$($field: $value,)*
+				}
+			};
+		}
+		macro_rules! connection_event {
+			($variant:ident) => {
+				PalletIbcEvent::$variant {
+					revision_height,
+					revision_number,
+					connection_id: Some(connection_id.clone()),
+					client_id: client_id.clone(),
+					counterparty_connection_id: Some(bytes("connection-1")),
+					counterparty_client_id: bytes("10-grandpa-0"),
+				}
+			};
+		}
+		macro_rules! channel_event {
+			($variant:ident $(, $field:ident: $value:expr)*) => {
+				PalletIbcEvent::$variant {
+					revision_height,
+					revision_number,
+					port_id: port_id.clone(),
+					channel_id: Some(channel_id.clone()),
+					connection_id: connection_id.clone(),
+					counterparty_port_id: port_id.clone(),
+					counterparty_channel_id: Some(counterparty_channel_id.clone()),
+					$($field: $value,)*
+				}
+			};
+		}
+		macro_rules! packet_event {
+			($variant:ident $(, $field:ident: $value:expr)*) => {
+				PalletIbcEvent::$variant {
+					revision_height,
+					revision_number,
+					port_id: port_id.clone(),
+					channel_id: channel_id.clone(),
+					$($field: $value,)*
+				}
+			};
+		}
+
+		vec![
+			PalletIbcEvent::NewBlock { revision_height, revision_number },
+			client_event!(CreateClient),
+			client_event!(
+				UpdateClient,
+				header_heights: vec![(1, 40), (1, 41)],
+				consensus_root: bytes("0a0b")
+			),
+			client_event!(UpgradeClient),
+			client_event!(ClientMisbehaviour),
+			client_event!(ClientRecovered, substitute_client_id: bytes("07-tendermint-1")),
+			connection_event!(OpenInitConnection),
+			connection_event!(OpenConfirmConnection),
+			connection_event!(ConnectionCancelled),
+			connection_event!(OpenTryConnection),
+			connection_event!(OpenAckConnection),
+			channel_event!(OpenInitChannel),
+			channel_event!(OpenConfirmChannel),
+			channel_event!(OpenTryChannel),
+			channel_event!(OpenAckChannel),
+			PalletIbcEvent::CloseInitChannel {
+				revision_height,
+				revision_number,
+				port_id: port_id.clone(),
+				channel_id: channel_id.clone(),
+				connection_id: connection_id.clone(),
+				counterparty_port_id: port_id.clone(),
+				counterparty_channel_id: Some(counterparty_channel_id.clone()),
+			},
+			channel_event!(CloseConfirmChannel, reason: Some(bytes("counterparty frozen"))),
+			channel_event!(UpgradeInitChannel),
+			channel_event!(UpgradeTryChannel),
+			channel_event!(UpgradeAckChannel),
+			channel_event!(UpgradeConfirmChannel),
+			channel_event!(UpgradeCancelChannel),
+			packet_event!(
+				ReceivePacket,
+				dest_port: port_id.clone(),
+				dest_channel: counterparty_channel_id.clone(),
+				sequence: seq
+			),
+			packet_event!(
+				SendPacket,
+				dest_port: port_id.clone(),
+				dest_channel: counterparty_channel_id.clone(),
+				sequence: seq,
+				timeout_revision_height: 100,
+				timeout_revision_number: 1,
+				timeout_timestamp: 1_000_000,
+				channel_ordering: bytes(Order::Ordered.as_str())
+			),
+			packet_event!(AcknowledgePacket, sequence: seq),
+			packet_event!(
+				WriteAcknowledgement,
+				dest_port: port_id.clone(),
+				dest_channel: counterparty_channel_id.clone(),
+				sequence: seq
+			),
+			packet_event!(TimeoutPacket, sequence: seq),
+			packet_event!(TimeoutOnClosePacket, sequence: seq),
+			packet_event!(AcknowledgementsPruned, total_pruned_sequences: 3),
+			PalletIbcEvent::Empty,
+			PalletIbcEvent::ChainError,
+			PalletIbcEvent::AppModule { kind: bytes("ping"), module_id: bytes("ibcping") },
+		]
+	}
+
+	#[test]
+	fn every_event_round_trips() {
+		let events = events();
+		let indices = events.iter().map(variant_index).collect::<Vec<_>>();
+		assert_eq!(indices, (0..events.len()).collect::<Vec<_>>());
+		assert_eq!(variant_index(events.last().unwrap()), 30);
+
+		for event in events {
+			let encoded = IbcEvents { events: vec![Ok(event.clone())] }.encode();
+			let parsed = IbcEvents::decode_fields(&encoded).unwrap().parse().unwrap();
+			assert_eq!(parsed.len(), 1);
+			assert_eq!(PalletIbcEvent::from(parsed[0].clone()), event);
+			assert_eq!(parsed[0], IbcEvent::try_from(event).unwrap());
+		}
+	}
+
+	#[test]
+	fn failed_messages_are_skipped() {
+		let events = IbcEvents {
+			events: vec![
+				Err(IbcError::Ics02Client { message: b"client frozen".to_vec() }),
+				Ok(PalletIbcEvent::NewBlock { revision_height: 42, revision_number: 1 }),
+			],
+		};
+		let parsed = IbcEvents::decode_fields(&events.encode()).unwrap().parse().unwrap();
+		assert_eq!(parsed.len(), 1);
+		assert!(matches!(parsed[0], IbcEvent::NewBlock(_)));
+	}
+
+	#[test]
+	fn unknown_variants_are_rejected() {
+		let mut encoded = IbcEvents { events: vec![Ok(PalletIbcEvent::Empty)] }.encode();
+		// one event, `Ok`, followed by the event's variant index
+		assert_eq!(encoded, vec![4, 0, 28]);
+		encoded[2] = u8::MAX;
+		assert!(IbcEvents::decode_fields(&encoded).is_err());
+
+		let mut encoded = IbcEvents { events: vec![Ok(PalletIbcEvent::Empty)] }.encode();
+		encoded.push(0);
+		assert!(IbcEvents::decode_fields(&encoded).is_err());
+
+		let invalid_id = PalletIbcEvent::TimeoutPacket {
+			revision_height: 42,
+			revision_number: 1,
+			port_id: b"transfer".to_vec(),
+			channel_id: b"not a channel".to_vec(),
+			sequence: 7,
+		};
+		let encoded = IbcEvents { events: vec![Ok(invalid_id)] }.encode();
+		assert!(IbcEvents::decode_fields(&encoded).unwrap().parse().is_err());
+	}
+}
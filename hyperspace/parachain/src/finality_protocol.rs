@@ -14,20 +14,19 @@
 
 //! Light client protocols for parachains.
 
-use crate::{config, error::Error, ParachainClient};
+use crate::{
+	config, error::Error, verification::verify_finalized_parachain_headers, ParachainClient,
+};
 use anyhow::anyhow;
 use beefy_light_client_primitives::{ClientState as BeefyPrimitivesClientState, NodesUtils};
+use beefy_prover::commitment_gap::{next_commitment, NextCommitment};
 use codec::{Decode, Encode};
 use finality_grandpa::BlockNumberOps;
 use grandpa_light_client_primitives::{
 	justification::find_scheduled_change, FinalityProof, ParachainHeaderProofs,
 	ParachainHeadersWithFinalityProof,
 };
-use ibc::{
-	core::ics02_client::{client_state::ClientState as _, msgs::update_client::MsgUpdateAnyClient},
-	events::IbcEvent,
-	tx_msg::Msg,
-};
+use ibc::{core::ics02_client::client_state::ClientState as _, events::IbcEvent};
 use ibc_proto::google::protobuf::Any;
 use ibc_rpc::{BlockNumberOrHash, IbcApiClient};
 use ics10_grandpa::client_message::{ClientMessage, Header as GrandpaHeader};
@@ -36,8 +35,8 @@ use ics11_beefy::client_message::{
 };
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState};
 use primitives::{
-	mock::LocalClientTypes, query_maximum_height_for_timeout_proofs, Chain, IbcProvider,
-	KeyProvider, UpdateType,
+	query_maximum_height_for_timeout_proofs, update_client_msg, Chain, IbcProvider, KeyProvider,
+	UpdateType,
 };
 use serde::{Deserialize, Serialize};
 use sp_core::H256;
@@ -52,7 +51,6 @@ use std::{
 #[cfg(feature = "dali")]
 use subxt::tx::AssetTip as Tip;
 use subxt::tx::{BaseExtrinsicParamsBuilder, ExtrinsicParams};
-use tendermint_proto::Protobuf;
 
 #[cfg(not(feature = "dali"))]
 use subxt::tx::PlainTip as Tip;
@@ -71,7 +69,34 @@ pub enum FinalityEvent {
 			polkadot_core_primitives::Header,
 		>,
 	),
-	Beefy(beefy_primitives::SignedCommitment<u32, beefy_primitives::crypto::Signature>),
+	Beefy {
+		signed_commitment:
+			beefy_primitives::SignedCommitment<u32, beefy_primitives::crypto::Signature>,
+		/// Relay chain blocks before the commitment that the light client was updated past with a
+		/// mandatory commitment alone, see [`SkippedRange`].
+		skipped: Option<SkippedRange>,
+	},
+}
+
+/// Relay chain blocks `from..=to` the relay chain produced no signed commitment the light client
+/// could verify for. The light client is updated past them with the mandatory commitment at `to`
+/// without any parachain headers, the parachain blocks they finalized are proven and queried for
+/// events with the commitment that follows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Decode, Encode)]
+pub struct SkippedRange {
+	pub from: u32,
+	pub to: u32,
+}
+
+impl FinalityEvent {
+	/// Relay chain height finalized by this event.
+	pub fn height(&self) -> u64 {
+		match self {
+			FinalityEvent::Grandpa(justification) => justification.commit.target_number.into(),
+			FinalityEvent::Beefy { signed_commitment, .. } =>
+				signed_commitment.commitment.block_number.into(),
+		}
+	}
 }
 
 impl FinalityProtocol {
@@ -135,8 +160,8 @@ where
 	T::Hash: From<sp_core::H256>,
 	sp_core::H256: From<T::Hash>,
 {
-	let signed_commitment = match finality_event {
-		FinalityEvent::Beefy(signed_commitment) => signed_commitment,
+	let (signed_commitment, skipped) = match finality_event {
+		FinalityEvent::Beefy { signed_commitment, skipped } => (signed_commitment, skipped),
 		_ => panic!("Expected beefy signed commitment"),
 	};
 	let client_id = source.client_id();
@@ -161,26 +186,96 @@ where
 		)))?,
 	};
 
-	if signed_commitment.commitment.validator_set_id < beefy_client_state.current_authorities.id {
-		log::info!(
-			"Commitment: {:#?}\nClientState: {:#?}",
-			signed_commitment.commitment,
-			beefy_client_state
-		);
-		// If validator set id of signed commitment is less than current validator set
-		// id we have Then commitment is outdated and we skip it.
-		log::warn!(
+	if let Some(skipped) = skipped {
+		if beefy_client_state.latest_beefy_height < skipped.to {
+			let block_number = signed_commitment.commitment.block_number;
+			*source.skipped_beefy_commitment.lock().unwrap() =
+				Some(FinalityEvent::Beefy { signed_commitment, skipped: Some(skipped) });
+			return Err(Error::HeaderConstruction(format!(
+				"The light client isn't updated with the mandatory commitment at block {} yet, \
+				 proving the commitment at block {block_number} later",
+				skipped.to
+			))
+			.into())
+		}
+	}
+
+	match next_commitment(&beefy_client_state, signed_commitment.commitment.validator_set_id) {
+		NextCommitment::Received => {},
+		NextCommitment::Outdated => {
+			log::info!(
+				"Commitment: {:#?}\nClientState: {:#?}",
+				signed_commitment.commitment,
+				beefy_client_state
+			);
+			// If validator set id of signed commitment is less than current validator set
+			// id we have Then commitment is outdated and we skip it.
+			log::warn!(
 				"Skipping outdated commitment \n Received signed commitmment with validator_set_id: {:?}\n Current authority set id: {:?}\n Next authority set id: {:?}\n",
 				signed_commitment.commitment.validator_set_id, beefy_client_state.current_authorities.id, beefy_client_state.next_authorities.id
 			);
-		Err(Error::HeaderConstruction("Received an outdated beefy commitment".to_string()))?
+			Err(Error::HeaderConstruction("Received an outdated beefy commitment".to_string()))?
+		},
+		// The relay chain didn't sign any commitment for a whole session, e.g. because beefy
+		// stalled, so the light client missed an authority set change. Update it past the block
+		// at which its next authority set took over first, the received commitment comes next
+		// with the skipped range and proves the parachain blocks finalized in it.
+		NextCommitment::Mandatory { validator_set_id } => {
+			let received_block = signed_commitment.commitment.block_number;
+			let mandatory_commitment = source
+				.query_beefy_mandatory_commitment(
+					validator_set_id,
+					received_block,
+					&beefy_client_state,
+				)
+				.await?
+				.ok_or_else(|| {
+					Error::HeaderConstruction(format!(
+						"Waiting for the mandatory commitment of authority set {validator_set_id}"
+					))
+				})?;
+			let skipped = SkippedRange {
+				from: skipped
+					.map(|skipped| skipped.from)
+					.unwrap_or(beefy_client_state.latest_beefy_height + 1),
+				to: mandatory_commitment.commitment.block_number,
+			};
+			log::warn!(
+				"Missing beefy justifications for relay chain blocks {}..{}, updating the light \
+				 client with the mandatory commitment at block {} first",
+				skipped.from,
+				received_block,
+				skipped.to
+			);
+			*source.skipped_beefy_commitment.lock().unwrap() =
+				Some(FinalityEvent::Beefy { signed_commitment, skipped: Some(skipped) });
+
+			let mmr_update = source
+				.query_beefy_mmr_update_proof(mandatory_commitment, &beefy_client_state)
+				.await?;
+			let client_message = AnyClientMessage::Beefy(BeefyClientMessage::Header(BeefyHeader {
+				headers_with_proof: None,
+				mmr_update_proof: Some(mmr_update),
+			}));
+			let msg = update_client_msg(counterparty, source.client_id(), client_message);
+			return Ok((msg, vec![], UpdateType::Mandatory))
+		},
 	}
 
+	// Parachain blocks finalized in a skipped range weren't proven by the light client update
+	// past it, so they are proven along with the ones finalized since.
+	let headers_client_state = BeefyPrimitivesClientState {
+		latest_beefy_height: skipped
+			.map(|skipped| skipped.from - 1)
+			.unwrap_or(beefy_client_state.latest_beefy_height),
+		..beefy_client_state.clone()
+	};
+
 	// fetch the new parachain headers that have been finalized
 	let headers = source
 		.query_beefy_finalized_parachain_headers_between(
 			signed_commitment.commitment.block_number,
-			&beefy_client_state,
+			&headers_client_state,
 		)
 		.await?;
 
@@ -277,7 +372,7 @@ where
 		let (headers, batch_proof) = source
 			.query_beefy_finalized_parachain_headers_with_proof(
 				signed_commitment.commitment.block_number,
-				&beefy_client_state,
+				&headers_client_state,
 				headers_with_events.into_iter().collect(),
 			)
 			.await?;
@@ -296,18 +391,11 @@ where
 		.query_beefy_mmr_update_proof(signed_commitment, &beefy_client_state)
 		.await?;
 
-	let update_header = {
-		let msg = MsgUpdateAnyClient::<LocalClientTypes> {
-			client_id: source.client_id(),
-			client_message: AnyClientMessage::Beefy(BeefyClientMessage::Header(BeefyHeader {
-				headers_with_proof,
-				mmr_update_proof: Some(mmr_update),
-			})),
-			signer: counterparty.account_id(),
-		};
-		let value = msg.encode_vec();
-		Any { value, type_url: msg.type_url() }
-	};
+	let client_message = AnyClientMessage::Beefy(BeefyClientMessage::Header(BeefyHeader {
+		headers_with_proof,
+		mmr_update_proof: Some(mmr_update),
+	}));
+	let update_header = update_client_msg(counterparty, source.client_id(), client_message);
 
 	Ok((update_header, events, update_type))
 }
@@ -451,6 +539,7 @@ where
 		latest_relay_height: client_state.latest_relay_height,
 		latest_para_height: client_state.latest_para_height,
 		para_id: client_state.para_id,
+		pending_authority_change: client_state.pending_authority_change.clone(),
 	};
 	let ParachainHeadersWithFinalityProof { finality_proof, parachain_headers } = prover
 		.query_finalized_parachain_headers_with_proof(
@@ -460,6 +549,25 @@ where
 		)
 		.await?;
 
+	if !source.trusted_rpc {
+		// the client state on the counterparty is the trusted starting point of the relay chain.
+		let client_state = grandpa_light_client_primitives::ClientState::<H256> {
+			current_authorities: client_state.current_authorities.clone(),
+			current_set_id: client_state.current_set_id,
+			latest_relay_hash: client_state.latest_relay_hash,
+			latest_relay_height: client_state.latest_relay_height,
+			latest_para_height: client_state.latest_para_height,
+			para_id: client_state.para_id,
+			pending_authority_change: client_state.pending_authority_change.clone(),
+		};
+		let proof = ParachainHeadersWithFinalityProof {
+			finality_proof: finality_proof.clone().into(),
+			parachain_headers: parachain_headers.clone().into(),
+		};
+		let headers = verify_finalized_parachain_headers(client_state, proof)?;
+		source.verified_state_roots.insert(&headers);
+	}
+
 	let target =
 		source
 			.relay_client
@@ -485,15 +593,8 @@ where
 		parachain_headers: parachain_headers.into(),
 	};
 
-	let update_header = {
-		let msg = MsgUpdateAnyClient::<LocalClientTypes> {
-			client_id: source.client_id(),
-			client_message: AnyClientMessage::Grandpa(ClientMessage::Header(grandpa_header)),
-			signer: counterparty.account_id(),
-		};
-		let value = msg.encode_vec();
-		Any { value, type_url: msg.type_url() }
-	};
+	let client_message = AnyClientMessage::Grandpa(ClientMessage::Header(grandpa_header));
+	let update_header = update_client_msg(counterparty, source.client_id(), client_message);
 
 	Ok((update_header, events, update_type))
 }
@@ -14,17 +14,28 @@
 
 #![allow(clippy::all)]
 
-use std::{collections::BTreeMap, str::FromStr, sync::Arc, time::Duration};
+use std::{
+	collections::BTreeMap,
+	str::FromStr,
+	sync::{atomic::AtomicU64, Arc, Mutex},
+	time::Duration,
+};
 
 pub mod chain;
 pub mod config;
 pub mod error;
+pub mod events;
 pub mod key_provider;
 pub mod parachain;
 pub mod polkadot;
+pub mod proofs;
 pub mod provider;
+pub mod reconnect;
+pub mod runtime_upgrade;
 pub mod signer;
 pub mod utils;
+pub mod verification;
+pub mod watchtower;
 
 pub mod finality_protocol;
 #[cfg(any(test, feature = "testing"))]
@@ -36,7 +47,18 @@ use serde::Deserialize;
 
 use beefy_light_client_primitives::{ClientState, MmrUpdateProof};
 use beefy_prover::Prover;
-use ibc::core::ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId};
+use ibc::{
+	applications::transfer::{Amount, PrefixedCoin, PrefixedDenom},
+	core::{
+		ics23_commitment::commitment::CommitmentPrefix,
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::Path,
+		},
+	},
+	Height,
+};
+use ibc_rpc::IbcApiClient;
 use ics11_beefy::client_message::ParachainHeader;
 use pallet_mmr_primitives::BatchProof;
 use sp_core::{ecdsa, ed25519, sr25519, Bytes, Pair, H256};
@@ -50,19 +72,30 @@ use subxt::ext::sp_runtime::{traits::Header as HeaderT, MultiSigner};
 
 use crate::{
 	parachain::api,
+	runtime_upgrade,
 	utils::{fetch_max_extrinsic_weight, unsafe_cast_to_jsonrpsee_client},
+	verification::{verify_storage_proof, VerifiedStateRoots},
 };
 use codec::Decode;
-use ics10_grandpa::consensus_state::ConsensusState as GrandpaConsensusState;
+use ics10_grandpa::{
+	client_message::RelayChainHeader, consensus_state::ConsensusState as GrandpaConsensusState,
+};
 use ics11_beefy::{
 	client_state::ClientState as BeefyClientState,
 	consensus_state::ConsensusState as BeefyConsensusState,
 };
 use primitives::KeyProvider;
 
-use crate::{finality_protocol::FinalityProtocol, signer::ExtrinsicSigner};
+use crate::{
+	finality_protocol::{FinalityEvent, FinalityProtocol},
+	signer::ExtrinsicSigner,
+};
 use grandpa_light_client_primitives::{FinalityProof, ParachainHeaderProofs};
-use grandpa_prover::GrandpaProver;
+pub use grandpa_prover::ancestry::DEFAULT_MAX_ANCESTRY_GAP;
+use grandpa_prover::{
+	ancestry::{HeaderCache, DEFAULT_HEADER_CACHE_SIZE},
+	GrandpaProver,
+};
 use ibc::timestamp::Timestamp;
 use ics10_grandpa::client_state::ClientState as GrandpaClientState;
 use jsonrpsee_ws_client::WsClientBuilder;
@@ -112,6 +145,28 @@ pub struct ParachainClient<T: config::Config> {
 	pub channel_whitelist: Vec<(ChannelId, PortId)>,
 	/// Finality protocol to use, eg Beefy, Grandpa
 	pub finality_protocol: FinalityProtocol,
+	/// Whether storage values queried from the parachain rpc are used without being verified
+	pub trusted_rpc: bool,
+	/// State roots of the parachain blocks verified to be final, used when the rpc isn't trusted
+	pub verified_state_roots: VerifiedStateRoots,
+	/// Parachain rpc url, subscriptions reconnect to it when their connection drops
+	pub parachain_rpc_url: String,
+	/// Relay chain rpc url, subscriptions reconnect to it when their connection drops
+	pub relay_chain_rpc_url: String,
+	/// Number of times subscriptions were re-established
+	pub subscription_reconnects: Arc<AtomicU64>,
+	/// Secondary relay chain rpc client, to cross-check finality with
+	pub secondary_relay_client: Option<subxt::OnlineClient<T>>,
+	/// Secondary relay chain ws client
+	pub secondary_relay_ws_client: Option<Arc<jsonrpsee_ws_client::WsClient>>,
+	/// Relay chain headers fetched for previous GRANDPA finality proofs
+	pub relay_header_cache: Arc<Mutex<HeaderCache<T::Header>>>,
+	/// Maximum number of relay chain headers in the ancestry of a GRANDPA finality proof
+	pub max_ancestry_gap: u32,
+	/// Beefy finality event that couldn't be proven yet because the light client first had to be
+	/// updated with a mandatory commitment, it's delivered again by the finality notifications
+	/// along with the range of relay chain blocks skipped by that update.
+	pub skipped_beefy_commitment: Arc<Mutex<Option<FinalityEvent>>>,
 }
 
 enum KeyType {
@@ -172,6 +227,26 @@ pub struct ParachainClientConfig {
 	pub finality_protocol: FinalityProtocol,
 	/// Digital signature scheme
 	pub key_type: String,
+	/// Whether storage values queried from the parachain rpc are used as is. When `false`, they
+	/// are verified against the state roots of parachain blocks proven final by the relay chain's
+	/// GRANDPA finality proofs, which requires the Grandpa finality protocol.
+	#[serde(default = "default_trusted_rpc")]
+	pub trusted_rpc: bool,
+	/// rpc url of a second relay chain node, whose finalized blocks are checked against the ones
+	/// finalized according to `relay_chain_rpc_url`
+	pub secondary_rpc: Option<String>,
+	/// Maximum number of relay chain headers between the relay chain block last seen by the
+	/// counterparty's light client and the one finalized by a GRANDPA finality proof
+	#[serde(default = "default_max_ancestry_gap")]
+	pub max_ancestry_gap: u32,
+}
+
+fn default_trusted_rpc() -> bool {
+	true
+}
+
+fn default_max_ancestry_gap() -> u32 {
+	DEFAULT_MAX_ANCESTRY_GAP
 }
 
 impl<T> ParachainClient<T>
@@ -180,6 +255,11 @@ where
 {
 	/// Initializes a [`ParachainClient`] given a [`ParachainConfig`]
 	pub async fn new(config: ParachainClientConfig) -> Result<Self, Error> {
+		if !config.trusted_rpc && !matches!(config.finality_protocol, FinalityProtocol::Grandpa) {
+			Err(Error::Custom(
+				"Verifying rpc queries requires the Grandpa finality protocol".to_string(),
+			))?
+		}
 		let relay_ws_client = Arc::new(
 			WsClientBuilder::default()
 				.build(&config.relay_chain_rpc_url)
@@ -203,8 +283,33 @@ where
 		})
 		.await?;
 
+		let (secondary_relay_client, secondary_relay_ws_client) = match &config.secondary_rpc {
+			Some(url) => {
+				let ws_client = Arc::new(
+					WsClientBuilder::default()
+						.build(url)
+						.await
+						.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
+				);
+				let client = subxt::OnlineClient::from_rpc_client(unsafe {
+					unsafe_cast_to_jsonrpsee_client(&ws_client)
+				})
+				.await?;
+				(Some(client), Some(ws_client))
+			},
+			None => (None, None),
+		};
+
 		let max_extrinsic_weight = fetch_max_extrinsic_weight(&para_client).await?;
 
+		let watched_client = para_client.clone();
+		let name = config.name.clone();
+		tokio::spawn(async move {
+			if let Err(e) = runtime_upgrade::watch_runtime_upgrades(watched_client).await {
+				log::error!("Stopped watching {} for runtime upgrades: {:?}", name, e);
+			}
+		});
+
 		let key_store: SyncCryptoStorePtr = Arc::new(KeyStore::new());
 		let key_type = KeyType::from_str(&config.key_type)?;
 		let key_type_id = key_type.to_key_type_id();
@@ -253,10 +358,51 @@ where
 			ss58_version: Ss58AddressFormat::from(config.ss58_version),
 			channel_whitelist: config.channel_whitelist,
 			finality_protocol: config.finality_protocol,
+			trusted_rpc: config.trusted_rpc,
+			verified_state_roots: VerifiedStateRoots::default(),
+			parachain_rpc_url: config.parachain_rpc_url,
+			relay_chain_rpc_url: config.relay_chain_rpc_url,
+			subscription_reconnects: Arc::new(AtomicU64::new(0)),
+			secondary_relay_client,
+			secondary_relay_ws_client,
+			relay_header_cache: Arc::new(Mutex::new(HeaderCache::new(DEFAULT_HEADER_CACHE_SIZE))),
+			max_ancestry_gap: config.max_ancestry_gap,
+			skipped_beefy_commitment: Arc::new(Mutex::new(None)),
 		})
 	}
 }
 
+impl<T: config::Config> ParachainClient<T>
+where
+	T::Hash: From<H256>,
+	RelayChainHeader: From<T::Header>,
+{
+	/// Checks a storage value queried at `at` against its proof and a verified state root, unless
+	/// the rpc is trusted.
+	pub async fn verify_query(
+		&self,
+		at: Height,
+		proof: &[u8],
+		path: impl Into<Path>,
+		value: Option<Vec<u8>>,
+	) -> Result<(), Error> {
+		if self.trusted_rpc {
+			return Ok(())
+		}
+		let fetch_header = |hash: H256| async move {
+			let header = self.para_client.rpc().header(Some(hash.into())).await?;
+			Ok::<_, Error>(header.map(RelayChainHeader::from))
+		};
+		let state_root = self
+			.verified_state_roots
+			.state_root_at(at.revision_height as u32, fetch_header)
+			.await?;
+		let prefix = CommitmentPrefix::try_from(self.commitment_prefix.clone())
+			.map_err(|e| Error::Custom(format!("Invalid commitment prefix: {e:?}")))?;
+		verify_storage_proof(&prefix, state_root, proof.to_vec(), path, value)
+	}
+}
+
 impl<T: config::Config + Send + Sync> ParachainClient<T>
 where
 	u32: From<<<T as subxt::Config>::Header as HeaderT>::Number>,
@@ -278,6 +424,8 @@ where
 			para_client: self.para_client.clone(),
 			para_ws_client,
 			para_id: self.para_id,
+			header_cache: self.relay_header_cache.clone(),
+			max_ancestry_gap: self.max_ancestry_gap,
 		}
 	}
 
@@ -382,38 +530,82 @@ where
 		Ok(mmr_update)
 	}
 
+	/// Queries the signed commitment of the mandatory block at which the authority set
+	/// `validator_set_id` took over, before the relay chain block `commitment_block_number`.
+	pub async fn query_beefy_mandatory_commitment(
+		&self,
+		validator_set_id: u64,
+		commitment_block_number: u32,
+		client_state: &ClientState,
+	) -> Result<
+		Option<beefy_primitives::SignedCommitment<u32, beefy_primitives::crypto::Signature>>,
+		Error,
+	> {
+		let prover = Prover {
+			relay_client: self.relay_client.clone(),
+			para_client: self.para_client.clone(),
+			beefy_activation_block: client_state.beefy_activation_block,
+			para_id: self.para_id,
+		};
+
+		let commitment = prover
+			.query_mandatory_commitment(
+				validator_set_id,
+				client_state.latest_beefy_height,
+				commitment_block_number,
+			)
+			.await
+			.map_err(|e| {
+				Error::from(format!("[query_mandatory_commitment] Failed due to {:?}", e))
+			})?;
+		Ok(commitment)
+	}
+
 	/// Submits the given transaction to the parachain node, waits for it to be included in a block
 	/// and asserts that it was successfully dispatched on-chain.
 	///
 	/// We retry sending the transaction up to 5 times in the case where the transaction pool might
-	/// reject the transaction because of conflicting nonces.
+	/// reject the transaction because of conflicting nonces. If the transaction fails because the
+	/// runtime was upgraded while it was in flight, it's submitted once more with the new metadata.
 	pub async fn submit_call<C: TxPayload>(&self, call: C) -> Result<(T::Hash, T::Hash), Error> {
+		let spec_version = self.para_client.runtime_version().spec_version;
+		let error = match self.try_submit_call(&call).await {
+			Ok(hashes) => return Ok(hashes),
+			Err(e) => e,
+		};
+		runtime_upgrade::refresh_if_upgraded(&self.para_client).await?;
+		if self.para_client.runtime_version().spec_version == spec_version {
+			return Err(error)
+		}
+		log::warn!("Runtime upgraded while submitting extrinsic: {:?}. Retrying...", error);
+		self.try_submit_call(&call).await
+	}
+
+	async fn try_submit_call<C: TxPayload>(&self, call: &C) -> Result<(T::Hash, T::Hash), Error> {
 		let signer = ExtrinsicSigner::<T, Self>::new(
 			self.key_store.clone(),
 			self.key_type_id.clone(),
 			self.public_key.clone(),
 		);
 
-		// Try extrinsic submission five times in case of failures
+		// Try extrinsic submission five times in case of failures, returning the last error so
+		// that it can be classified, see [`Error::submit_error_kind`].
 		let mut count = 0;
 		let progress = loop {
-			if count == 5 {
-				Err(Error::Custom("Failed to submit extrinsic after 5 tries".to_string()))?
-			}
-
 			let other_params = T::custom_extrinsic_params(&self.para_client).await?;
 
 			let res = self
 				.para_client
 				.tx()
-				.sign_and_submit_then_watch(&call, &signer, other_params)
+				.sign_and_submit_then_watch(call, &signer, other_params)
 				.await;
 			match res {
 				Ok(progress) => break progress,
-				Err(e) => {
+				Err(e) if count < 4 => {
 					log::warn!("Failed to submit extrinsic: {:?}. Retrying...", e);
 					count += 1;
 				},
+				Err(e) => return Err(e.into()),
 			}
 		};
 
@@ -425,6 +617,33 @@ where
 	pub fn client_id(&self) -> ClientId {
 		self.client_id.as_ref().expect("Client Id should be defined").clone()
 	}
+
+	/// Queries the native tokens held in escrow for the transfers sent over a channel.
+	pub async fn query_escrow_balances(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<PrefixedCoin>, Error> {
+		let balances =
+			IbcApiClient::<u32, H256, <T as config::Config>::AssetId>::query_escrow_balances(
+				&*self.para_ws_client,
+				at.revision_height as u32,
+				channel_id.to_string(),
+				port_id.to_string(),
+			)
+			.await
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		balances
+			.into_iter()
+			.map(|coin| {
+				Ok(PrefixedCoin {
+					denom: PrefixedDenom::from_str(&coin.denom)?,
+					amount: Amount::from_str(&coin.amount)?,
+				})
+			})
+			.collect()
+	}
 }
 
 impl<T: config::Config + Send + Sync> ParachainClient<T>
@@ -551,6 +770,8 @@ where
 			para_client: self.para_client.clone(),
 			para_ws_client,
 			para_id: self.para_id,
+			header_cache: self.relay_header_cache.clone(),
+			max_ancestry_gap: self.max_ancestry_gap,
 		};
 		let api = self.relay_client.storage();
 		let para_client_api = self.para_client.storage();
@@ -587,6 +808,7 @@ where
 			client_state.relay_chain = Default::default();
 			client_state.current_authorities = light_client_state.current_authorities;
 			client_state.current_set_id = light_client_state.current_set_id;
+			client_state.pending_authority_change = light_client_state.pending_authority_change;
 			client_state.latest_relay_hash = light_client_state.latest_relay_hash.into();
 			client_state.frozen_height = None;
 			client_state.latest_para_height = block_number;
@@ -609,6 +831,7 @@ where
 					.into_tm_time()
 					.unwrap(),
 				root: decoded_para_head.state_root.as_bytes().to_vec().into(),
+				block_hash: sp_runtime::traits::Header::hash(&decoded_para_head),
 			});
 
 			return Ok((AnyClientState::Grandpa(client_state), consensus_state))
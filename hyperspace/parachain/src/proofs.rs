@@ -0,0 +1,155 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Child trie proofs of many keys fetched in a single query.
+
+use crate::error::Error;
+use codec::{Decode, Encode};
+use sp_core::{storage::ChildInfo, H256};
+use sp_runtime::traits::BlakeTwo256;
+use sp_trie::StorageProof;
+use std::future::Future;
+
+/// Fetches a single read proof covering every key in `keys` with one call to `query_proof`, and
+/// splits it into a proof per key, in the order of `keys`.
+///
+/// `query_proof` must return the encoded trie nodes proving the keys in the child trie
+/// `child_trie_key` of the state whose root is `state_root`, like the `ibc_queryProof` rpc does.
+pub async fn query_proofs_batch<F, Fut>(
+	query_proof: F,
+	state_root: H256,
+	child_trie_key: &[u8],
+	keys: Vec<Vec<u8>>,
+) -> Result<Vec<Vec<u8>>, Error>
+where
+	F: FnOnce(Vec<Vec<u8>>) -> Fut,
+	Fut: Future<Output = Result<Vec<u8>, Error>>,
+{
+	let proof = query_proof(keys.clone()).await?;
+	split_child_read_proof(state_root, child_trie_key, &proof, &keys)
+}
+
+/// Splits a read proof of several keys of a child trie into the smallest proof of each key, so
+/// that every message only carries the trie nodes its own key needs.
+pub fn split_child_read_proof(
+	state_root: H256,
+	child_trie_key: &[u8],
+	proof: &[u8],
+	keys: &[Vec<u8>],
+) -> Result<Vec<Vec<u8>>, Error> {
+	let nodes: Vec<Vec<u8>> = Decode::decode(&mut &*proof)?;
+	let backend = state_machine::create_proof_check_backend::<BlakeTwo256>(
+		state_root,
+		StorageProof::new(nodes),
+	)
+	.map_err(|e| Error::Custom(format!("Invalid read proof: {e:?}")))?;
+	let child_info = ChildInfo::new_default(child_trie_key);
+	keys.iter()
+		.map(|key| {
+			let proof =
+				state_machine::prove_child_read_on_trie_backend(&backend, &child_info, [key])
+					.map_err(|e| {
+						Error::Custom(format!(
+							"Read proof doesn't cover key {}: {e:?}",
+							hex::encode(key)
+						))
+					})?;
+			Ok(proof.into_nodes().into_iter().collect::<Vec<_>>().encode())
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::storage::StateVersion;
+	use state_machine::{prove_child_read_on_trie_backend, read_child_proof_check, InMemoryBackend};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	const CHILD_TRIE_KEY: &[u8] = b"ibc/";
+
+	#[test]
+	fn batch_is_fetched_with_a_single_rpc_call() {
+		let child_info = ChildInfo::new_default(CHILD_TRIE_KEY);
+		let keys = (1..=200)
+			.map(|seq| {
+				format!("ibc/commitments/ports/transfer/channels/channel-0/sequences/{seq}")
+					.into_bytes()
+			})
+			.collect::<Vec<_>>();
+		let storage = keys.iter().map(|key| (key.clone(), Some(vec![1u8; 32]))).collect();
+		let backend = InMemoryBackend::<BlakeTwo256>::from((
+			vec![(Some(child_info.clone()), storage)],
+			StateVersion::V0,
+		));
+		let state_root = *backend.root();
+
+		let calls = AtomicUsize::new(0);
+		let combined_len = AtomicUsize::new(0);
+		let query_proof = |keys: Vec<Vec<u8>>| {
+			calls.fetch_add(1, Ordering::SeqCst);
+			let proof = prove_child_read_on_trie_backend(&backend, &child_info, &keys)
+				.unwrap()
+				.into_nodes()
+				.into_iter()
+				.collect::<Vec<_>>()
+				.encode();
+			combined_len.store(proof.len(), Ordering::SeqCst);
+			async move { Ok(proof) }
+		};
+		let proofs = futures::executor::block_on(query_proofs_batch(
+			query_proof,
+			state_root,
+			CHILD_TRIE_KEY,
+			keys.clone(),
+		))
+		.unwrap();
+
+		assert_eq!(calls.load(Ordering::SeqCst), 1);
+		assert_eq!(proofs.len(), keys.len());
+		for (key, proof) in keys.into_iter().zip(proofs) {
+			assert!(proof.len() < combined_len.load(Ordering::SeqCst));
+			let nodes: Vec<Vec<u8>> = Decode::decode(&mut &*proof).unwrap();
+			let values = read_child_proof_check::<BlakeTwo256, _>(
+				state_root,
+				StorageProof::new(nodes),
+				child_info.clone(),
+				[&key],
+			)
+			.unwrap();
+			assert_eq!(values.get(&key), Some(&Some(vec![1u8; 32])));
+		}
+	}
+
+	#[test]
+	fn split_fails_for_keys_missing_from_the_proof() {
+		let child_info = ChildInfo::new_default(CHILD_TRIE_KEY);
+		let keys = vec![b"ibc/acks/1".to_vec(), b"ibc/acks/2".to_vec()];
+		let storage = keys.iter().map(|key| (key.clone(), Some(vec![1u8]))).collect();
+		let backend = InMemoryBackend::<BlakeTwo256>::from((
+			vec![(Some(child_info.clone()), storage)],
+			StateVersion::V0,
+		));
+		let proof = prove_child_read_on_trie_backend(&backend, &child_info, &keys[..1])
+			.unwrap()
+			.into_nodes()
+			.into_iter()
+			.collect::<Vec<_>>()
+			.encode();
+
+		let state_root = *backend.root();
+		assert!(split_child_read_proof(state_root, CHILD_TRIE_KEY, &proof, &keys[..1]).is_ok());
+		assert!(split_child_read_proof(state_root, CHILD_TRIE_KEY, &proof, &keys).is_err());
+	}
+}
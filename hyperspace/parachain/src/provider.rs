@@ -14,20 +14,33 @@
 
 use super::{error::Error, ParachainClient};
 use crate::{
-	config, finality_protocol::FinalityEvent, parachain, utils::MetadataIbcEventWrapper,
+	config,
+	events::{parse_ibc_event, IbcEvents},
+	finality_protocol::FinalityEvent,
+	parachain,
+	proofs::query_proofs_batch,
+	reconnect::{self, ReconnectPolicy},
+	utils::unsafe_cast_to_jsonrpsee_client,
 	FinalityProtocol, GrandpaClientState,
 };
 use beefy_prover::helpers::fetch_timestamp_extrinsic_with_proof;
 use codec::Encode;
 use finality_grandpa::BlockNumberOps;
-use futures::Stream;
+use futures::{stream::BoxStream, Stream, StreamExt};
 use grandpa_light_client_primitives::{FinalityProof, ParachainHeaderProofs};
 use ibc::{
-	applications::transfer::{Amount, PrefixedCoin, PrefixedDenom},
+	applications::{
+		fee::PacketFee,
+		transfer::{Amount, PrefixedCoin, PrefixedDenom},
+	},
 	core::{
-		ics02_client::client_state::ClientType,
+		ics02_client::client_state::{ClientStatus, ClientType},
+		ics04_channel::channel::ChannelEnd,
 		ics23_commitment::commitment::CommitmentPrefix,
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{AcksPath, ChannelEndsPath, CommitmentsPath},
+		},
 	},
 	events::IbcEvent,
 	timestamp::Timestamp,
@@ -47,7 +60,8 @@ use ibc_proto::{
 		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
 	},
 };
-use ibc_rpc::{IbcApiClient, PacketInfo};
+use ibc_rpc::{BlockNumberOrHash, IbcApiClient, PacketInfo, QueryNextSequenceAckResponse};
+use jsonrpsee::core::client::SubscriptionClientT;
 use ics10_grandpa::client_message::RelayChainHeader;
 use ics11_beefy::client_state::ClientState as BeefyClientState;
 use pallet_ibc::{
@@ -60,19 +74,68 @@ use sp_runtime::{
 	traits::{Header as HeaderT, IdentifyAccount, One, Verify},
 	MultiSignature, MultiSigner,
 };
-use std::{collections::BTreeMap, fmt::Display, pin::Pin, str::FromStr, time::Duration};
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt::Display,
+	pin::Pin,
+	str::FromStr,
+	sync::Arc,
+	time::Duration,
+};
 #[cfg(feature = "dali")]
 use subxt::tx::AssetTip as Tip;
+use subxt::events::Events;
 use subxt::tx::{BaseExtrinsicParamsBuilder, ExtrinsicParams};
+use tendermint_proto::Protobuf;
 
 #[cfg(not(feature = "dali"))]
 use subxt::tx::PlainTip as Tip;
 
+#[derive(Debug)]
 pub struct TransactionId<Hash> {
 	pub ext_hash: Hash,
 	pub block_hash: Hash,
 }
 
+/// Subscribes, on a new connection to `url`, to the events of the parachain's best blocks,
+/// along with the block numbers.
+async fn subscribe_events<T: config::Config>(
+	url: String,
+) -> Result<BoxStream<'static, Result<(u64, Events<T>), Error>>, Error> {
+	let ws_client = Arc::new(reconnect::connect(&url).await?);
+	let rpc_client = unsafe { unsafe_cast_to_jsonrpsee_client(&ws_client) };
+	let client = subxt::OnlineClient::<T>::from_rpc_client(rpc_client.clone()).await?;
+	let headers = rpc_client
+		.subscribe::<T::Header>("chain_subscribeNewHeads", None, "chain_unsubscribeNewHeads")
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+	let stream = headers.then(move |header| {
+		let client = client.clone();
+		async move {
+			let header = header.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+			let events = client.events().at(Some(header.hash())).await?;
+			Ok(((*header.number()).into(), events))
+		}
+	});
+	Ok(stream.boxed())
+}
+
+/// Decodes the ibc events emitted in a block, events that fail to decode are logged and skipped.
+fn decode_ibc_events<T: config::Config>(events: Events<T>) -> Vec<IbcEvent> {
+	events
+		.find::<IbcEvents>()
+		.filter_map(|result| {
+			result.map_err(|err| log::error!("Error in IbcEvent stream: {err:?}")).ok()
+		})
+		.flat_map(|ibc_events| ibc_events.events)
+		.filter_map(|ev| {
+			parse_ibc_event(ev.ok()?)
+				.map_err(|err| log::error!("Failed to decode event: {err:?}"))
+				.ok()
+		})
+		.collect()
+}
+
 #[async_trait::async_trait]
 impl<T: config::Config + Send + Sync> IbcProvider for ParachainClient<T>
 where
@@ -113,50 +176,20 @@ where
 	}
 
 	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
-		use futures::{stream, StreamExt};
-		use pallet_ibc::events::IbcEvent as RawIbcEvent;
-
-		let stream = self
-			.para_client
-			.events()
-			.subscribe()
-			.await
-			.expect("Failed to subscribe to events")
-			.filter_events::<(parachain::api::ibc::events::Events,)>()
-			.filter_map(|result| {
-				let events = match result {
-					Ok(ev) => ev,
-					Err(err) => {
-						log::error!("Error in IbcEvent stream: {err:?}");
-						return futures::future::ready(None)
-					},
-				};
-				let result = events
-					.event
-					.events
-					.into_iter()
-					.filter_map(|ev| {
-						Some(
-							IbcEvent::try_from(RawIbcEvent::from(MetadataIbcEventWrapper(
-								ev.ok()?,
-							)))
-							.map_err(|e| subxt::Error::Other(e.to_string())),
-						)
-					})
-					.collect::<Result<Vec<_>, _>>();
-
-				let events = match result {
-					Ok(ev) => ev,
-					Err(err) => {
-						log::error!("Failed to decode event: {err:?}");
-						return futures::future::ready(None)
-					},
-				};
-				futures::future::ready(Some(stream::iter(events)))
-			})
-			.flatten();
+		use futures::stream;
+
+		let url = self.parachain_rpc_url.clone();
+		let stream = reconnect::reconnecting_stream(
+			format!("{} ibc events", self.name),
+			ReconnectPolicy::default(),
+			self.subscription_reconnects.clone(),
+			move || subscribe_events::<T>(url.clone()),
+			|(number, _)| *number,
+		)
+		.flat_map(|(_, events)| stream::iter(decode_ibc_events(events)));
 		Box::pin(stream)
 	}
+
 	async fn query_client_consensus(
 		&self,
 		at: Height,
@@ -192,6 +225,22 @@ where
 		Ok(response)
 	}
 
+	async fn query_client_status(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<ClientStatus, Self::Error> {
+		let status =
+			IbcApiClient::<u32, H256, <T as config::Config>::AssetId>::query_client_status(
+				&*self.para_ws_client,
+				at.revision_height as u32,
+				client_id.to_string(),
+			)
+			.await
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		Ok(status)
+	}
+
 	async fn query_connection_end(
 		&self,
 		at: Height,
@@ -221,6 +270,14 @@ where
 		)
 		.await
 		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		let channel_end = response.channel.clone().map(ChannelEnd::try_from).transpose()?;
+		self.verify_query(
+			at,
+			&response.proof,
+			ChannelEndsPath(port_id, channel_id),
+			channel_end.map(|channel_end| channel_end.encode_vec()),
+		)
+		.await?;
 		Ok(response)
 	}
 
@@ -236,6 +293,32 @@ where
 		Ok(proof.proof)
 	}
 
+	async fn query_proofs(
+		&self,
+		at: Height,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Vec<u8>>, Self::Error> {
+		let block_hash = self
+			.para_client
+			.rpc()
+			.block_hash(Some(at.revision_height.into()))
+			.await?
+			.ok_or_else(|| Error::from(format!("Block hash not found for height {at}")))?;
+		let header = self
+			.para_client
+			.rpc()
+			.header(Some(block_hash))
+			.await?
+			.ok_or_else(|| Error::from(format!("Header not found for height {at}")))?;
+		query_proofs_batch(
+			|keys| self.query_proof(at, keys),
+			H256::from(*header.state_root()),
+			&self.commitment_prefix,
+			keys,
+		)
+		.await
+	}
+
 	async fn query_packet_commitment(
 		&self,
 		at: Height,
@@ -253,6 +336,13 @@ where
 			)
 			.await
 			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		let path = CommitmentsPath {
+			port_id: port_id.clone(),
+			channel_id: *channel_id,
+			sequence: seq.into(),
+		};
+		let commitment = (!res.commitment.is_empty()).then(|| res.commitment.clone());
+		self.verify_query(at, &res.proof, path, commitment).await?;
 		Ok(res)
 	}
 
@@ -272,6 +362,11 @@ where
 		)
 		.await
 		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		let path =
+			AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence: seq.into() };
+		let acknowledgement =
+			(!res.acknowledgement.is_empty()).then(|| res.acknowledgement.clone());
+		self.verify_query(at, &res.proof, path, acknowledgement).await?;
 		Ok(res)
 	}
 
@@ -292,6 +387,23 @@ where
 		Ok(res)
 	}
 
+	async fn query_next_sequence_ack(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceAckResponse, Self::Error> {
+		let res = IbcApiClient::<u32, H256, <T as config::Config>::AssetId>::query_next_seq_ack(
+			&*self.para_ws_client,
+			at.revision_height as u32,
+			channel_id.to_string(),
+			port_id.to_string(),
+		)
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		Ok(res)
+	}
+
 	async fn query_packet_receipt(
 		&self,
 		at: Height,
@@ -358,12 +470,14 @@ where
 		at: Height,
 		channel_id: ChannelId,
 		port_id: PortId,
+		seqs: Option<Vec<u64>>,
 	) -> Result<Vec<u64>, Self::Error> {
 		let res = IbcApiClient::<u32, H256, <T as config::Config>::AssetId>::query_packet_acknowledgements(
 			&*self.para_ws_client,
 			at.revision_height as u32,
 			channel_id.to_string(),
 			port_id.to_string(),
+			seqs,
 		)
 		.await
 		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
@@ -469,6 +583,17 @@ where
 		Ok(response)
 	}
 
+	async fn query_packet_fees(
+		&self,
+		_at: Height,
+		_channel_id: ChannelId,
+		_port_id: PortId,
+		_seq: u64,
+	) -> Result<Vec<PacketFee>, Self::Error> {
+		// pallet-ibc doesn't run the fee middleware, so no fees are ever escrowed for packets.
+		Ok(vec![])
+	}
+
 	fn expected_block_time(&self) -> Duration {
 		// Parachains have an expected block time of 12 seconds
 		Duration::from_secs(12)
@@ -585,6 +710,16 @@ where
 			.collect()
 	}
 
+	async fn query_connections(&self) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		let response =
+			IbcApiClient::<u32, H256, <T as config::Config>::AssetId>::query_connections(
+				&*self.para_ws_client,
+			)
+			.await
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		Ok(response.connections)
+	}
+
 	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
 		let response = IbcApiClient::<u32, H256, <T as config::Config>::AssetId>::query_channels(
 			&*self.para_ws_client,
@@ -664,4 +799,19 @@ where
 			.expect("Should have a valid client id");
 		Ok(client_id)
 	}
+
+	async fn query_tx_block_events(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<Vec<IbcEvent>, Self::Error> {
+		let TransactionId { block_hash, .. } = tx_id;
+		let events: HashMap<String, Vec<IbcEvent>> =
+			IbcApiClient::<u32, H256, <T as config::Config>::AssetId>::query_events(
+				&*self.para_ws_client,
+				vec![BlockNumberOrHash::Hash(block_hash.into())],
+			)
+			.await
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		Ok(events.into_values().flatten().collect())
+	}
 }
@@ -0,0 +1,198 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Subscriptions that survive the node dropping its websocket connection, e.g. when it restarts.
+
+use crate::error::Error;
+use futures::{Stream, StreamExt};
+use jsonrpsee_ws_client::{WsClient, WsClientBuilder};
+use std::{
+	future::Future,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Items buffered between the subscription and its consumer.
+const CHANNEL_CAPACITY: usize = 64;
+
+/// Wait between two attempts at resubscribing.
+#[derive(Debug, Clone, Copy)]
+pub struct ReconnectPolicy {
+	/// Wait before the first attempt, doubled for every further one.
+	pub initial_backoff: Duration,
+	/// Upper bound of the wait between two attempts.
+	pub max_backoff: Duration,
+}
+
+impl Default for ReconnectPolicy {
+	fn default() -> Self {
+		Self { initial_backoff: Duration::from_secs(1), max_backoff: Duration::from_secs(60) }
+	}
+}
+
+impl ReconnectPolicy {
+	/// Wait before the attempt following `attempts` failed ones.
+	pub fn backoff(&self, attempts: u32) -> Duration {
+		self.initial_backoff.saturating_mul(2u32.saturating_pow(attempts)).min(self.max_backoff)
+	}
+}
+
+/// Opens a new websocket connection to `url`.
+pub async fn connect(url: &str) -> Result<WsClient, Error> {
+	WsClientBuilder::default()
+		.build(url)
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))
+}
+
+/// Keeps `client` open for as long as `stream`, a subscription made through it, is in use.
+pub fn keep_alive<C, S: Stream>(client: C, stream: S) -> impl Stream<Item = S::Item> {
+	futures::stream::unfold((client, Box::pin(stream)), |(client, mut stream)| async move {
+		let item = stream.next().await?;
+		Some((item, (client, stream)))
+	})
+}
+
+/// Forwards the items of the subscription opened by `subscribe` to the returned stream.
+///
+/// Whenever the subscription ends or yields an error, `subscribe` is called again after a backoff,
+/// so it should establish a new connection, and `reconnects` is incremented. Items at or below the
+/// height, given by `height`, of the last forwarded item are skipped, so that blocks seen before
+/// the connection dropped aren't processed twice. Subscribing stops once the returned stream is
+/// dropped.
+pub fn reconnecting_stream<T, S, F, Fut>(
+	name: String,
+	policy: ReconnectPolicy,
+	reconnects: Arc<AtomicU64>,
+	subscribe: F,
+	height: fn(&T) -> u64,
+) -> ReceiverStream<T>
+where
+	T: Send + 'static,
+	S: Stream<Item = Result<T, Error>> + Send + 'static,
+	F: Fn() -> Fut + Send + 'static,
+	Fut: Future<Output = Result<S, Error>> + Send + 'static,
+{
+	let (sender, receiver) = mpsc::channel(CHANNEL_CAPACITY);
+	tokio::spawn(async move {
+		let mut last_height = None;
+		let mut attempts = 0;
+		loop {
+			match subscribe().await {
+				Ok(subscription) => {
+					attempts = 0;
+					let mut subscription = Box::pin(subscription);
+					while let Some(item) = subscription.next().await {
+						let item = match item {
+							Ok(item) => item,
+							Err(e) => {
+								log::warn!("Error in {name} subscription: {e:?}");
+								break
+							},
+						};
+						let item_height = height(&item);
+						if last_height.map_or(false, |last| item_height <= last) {
+							continue
+						}
+						last_height = Some(item_height);
+						if sender.send(item).await.is_err() {
+							return
+						}
+					}
+				},
+				Err(e) => log::warn!("Failed to subscribe to {name}: {e:?}"),
+			}
+			if sender.is_closed() {
+				return
+			}
+			let backoff = policy.backoff(attempts);
+			log::warn!("Resubscribing to {name} in {backoff:?}");
+			tokio::time::sleep(backoff).await;
+			attempts += 1;
+			reconnects.fetch_add(1, Ordering::SeqCst);
+		}
+	});
+	ReceiverStream::new(receiver)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::stream;
+	use std::{collections::VecDeque, sync::Mutex};
+
+	/// A transport serving a scripted sequence of connections, each either refused or yielding
+	/// some heights before it's severed. The last connection is never severed.
+	fn scripted_transport(
+		connections: Vec<Result<Vec<Result<u64, Error>>, Error>>,
+	) -> impl Fn() -> futures::future::Ready<
+		Result<stream::BoxStream<'static, Result<u64, Error>>, Error>,
+	> {
+		let connections = Mutex::new(connections.into_iter().collect::<VecDeque<_>>());
+		move || {
+			let mut connections = connections.lock().unwrap();
+			let stream = match connections.pop_front().expect("No connections left") {
+				Ok(items) if connections.is_empty() =>
+					Ok(stream::iter(items).chain(stream::pending()).boxed()),
+				Ok(items) => Ok(stream::iter(items).boxed()),
+				Err(e) => Err(e),
+			};
+			futures::future::ready(stream)
+		}
+	}
+
+	#[tokio::test]
+	async fn resumes_after_the_connection_drops() {
+		let transport = scripted_transport(vec![
+			Err(Error::Custom("connection refused".to_string())),
+			Ok(vec![Ok(1), Ok(2), Ok(3)]),
+			Ok(vec![Ok(2), Ok(3), Ok(4), Err(Error::Custom("connection reset".to_string()))]),
+			Ok(vec![Ok(4), Ok(5)]),
+		]);
+		let reconnects = Arc::new(AtomicU64::new(0));
+		let policy =
+			ReconnectPolicy { initial_backoff: Duration::ZERO, max_backoff: Duration::ZERO };
+
+		let heights = reconnecting_stream(
+			"test".to_string(),
+			policy,
+			reconnects.clone(),
+			transport,
+			|height| *height,
+		)
+		.take(5)
+		.collect::<Vec<_>>()
+		.await;
+
+		assert_eq!(heights, vec![1, 2, 3, 4, 5]);
+		assert_eq!(reconnects.load(Ordering::SeqCst), 3);
+	}
+
+	#[test]
+	fn backoff_doubles_up_to_max() {
+		let policy = ReconnectPolicy {
+			initial_backoff: Duration::from_secs(1),
+			max_backoff: Duration::from_secs(10),
+		};
+		assert_eq!(policy.backoff(0), Duration::from_secs(1));
+		assert_eq!(policy.backoff(1), Duration::from_secs(2));
+		assert_eq!(policy.backoff(3), Duration::from_secs(8));
+		assert_eq!(policy.backoff(4), Duration::from_secs(10));
+	}
+}
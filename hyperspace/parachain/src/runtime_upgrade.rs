@@ -0,0 +1,166 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Keeps the parachain client usable across runtime upgrades.
+//!
+//! The statically generated api fixes the pallet and call indices, and a hash of the call types,
+//! at build time, so its calls are rejected once an upgrade changes them. Calls built with
+//! [`NamedCall`] instead look their indices up by name in the client's metadata, which
+//! [`watch_runtime_upgrades`] refreshes whenever the runtime's spec version changes.
+
+use crate::{error::Error, parachain::api};
+use codec::Encode;
+use futures::StreamExt;
+use subxt::{tx::TxPayload, Config, Metadata, OnlineClient};
+
+/// A call whose pallet and call indices are looked up by name in the metadata it's encoded with.
+pub struct NamedCall<A> {
+	pallet: &'static str,
+	call: &'static str,
+	args: A,
+}
+
+impl<A: Encode> NamedCall<A> {
+	pub fn new(pallet: &'static str, call: &'static str, args: A) -> Self {
+		Self { pallet, call, args }
+	}
+}
+
+impl<A: Encode> TxPayload for NamedCall<A> {
+	fn encode_call_data(&self, metadata: &Metadata, out: &mut Vec<u8>) -> Result<(), subxt::Error> {
+		out.extend(call_indices(metadata, self.pallet, self.call)?);
+		self.args.encode_to(out);
+		Ok(())
+	}
+}
+
+/// Pallet and call indices of `pallet::call` in `metadata`.
+pub fn call_indices(
+	metadata: &Metadata,
+	pallet: &str,
+	call: &str,
+) -> Result<[u8; 2], subxt::Error> {
+	let pallet = metadata.pallet(pallet)?;
+	Ok([pallet.index(), pallet.call_index(call)?])
+}
+
+/// `ibc::deliver` call, to submit `messages`.
+pub fn deliver(
+	messages: Vec<api::runtime_types::pallet_ibc::Any>,
+) -> NamedCall<api::ibc::calls::Deliver> {
+	NamedCall::new("Ibc", "deliver", api::ibc::calls::Deliver { messages })
+}
+
+/// Fetches the node's metadata into `client` if the node's runtime spec version differs from the
+/// client's, returning whether it did.
+pub async fn refresh_if_upgraded<T: Config>(client: &OnlineClient<T>) -> Result<bool, Error> {
+	let runtime_version = client.rpc().runtime_version(None).await?;
+	if runtime_version.spec_version == client.runtime_version().spec_version {
+		return Ok(false)
+	}
+	let metadata = client.rpc().metadata().await?;
+	log::info!(
+		"Runtime upgraded from spec version {} to {}, refreshed metadata",
+		client.runtime_version().spec_version,
+		runtime_version.spec_version
+	);
+	client.set_metadata(metadata);
+	client.set_runtime_version(runtime_version);
+	Ok(true)
+}
+
+/// Refreshes the metadata of `client` whenever the runtime's spec version changes, using the
+/// `state_subscribeRuntimeVersion` subscription, until the subscription ends.
+pub async fn watch_runtime_upgrades<T: Config>(client: OnlineClient<T>) -> Result<(), Error> {
+	let mut runtime_versions = client.rpc().subscribe_runtime_version().await?;
+	while let Some(runtime_version) = runtime_versions.next().await {
+		if runtime_version?.spec_version != client.runtime_version().spec_version {
+			refresh_if_upgraded(&client).await?;
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, TypeInfo};
+	use subxt::ext::frame_metadata::{
+		v14::{ExtrinsicMetadata, PalletCallMetadata, PalletMetadata, RuntimeMetadataV14},
+		RuntimeMetadataPrefixed,
+	};
+
+	#[allow(non_camel_case_types, dead_code)]
+	#[derive(TypeInfo)]
+	enum CallsBeforeUpgrade {
+		#[codec(index = 0)]
+		deliver { messages: Vec<u8> },
+		#[codec(index = 1)]
+		transfer { amount: u128 },
+	}
+
+	#[allow(non_camel_case_types, dead_code)]
+	#[derive(TypeInfo)]
+	enum CallsAfterUpgrade {
+		#[codec(index = 0)]
+		set_params { params: Vec<u8> },
+		#[codec(index = 1)]
+		transfer { amount: u128 },
+		#[codec(index = 2)]
+		deliver { messages: Vec<u8> },
+	}
+
+	/// Metadata of a runtime with an `Ibc` pallet at `index` dispatching `Calls`.
+	fn metadata<Calls: TypeInfo + 'static>(index: u8) -> Metadata {
+		let pallet = PalletMetadata {
+			name: "Ibc",
+			storage: None,
+			calls: Some(PalletCallMetadata { ty: meta_type::<Calls>() }),
+			event: None,
+			constants: vec![],
+			error: None,
+			index,
+		};
+		let extrinsic =
+			ExtrinsicMetadata { ty: meta_type::<()>(), version: 4, signed_extensions: vec![] };
+		let metadata = RuntimeMetadataV14::new(vec![pallet], extrinsic, meta_type::<()>());
+		Metadata::try_from(RuntimeMetadataPrefixed::from(metadata)).unwrap()
+	}
+
+	fn encode(call: &impl TxPayload, metadata: &Metadata) -> Vec<u8> {
+		let mut out = vec![];
+		call.encode_call_data(metadata, &mut out).unwrap();
+		out
+	}
+
+	#[test]
+	fn call_indices_follow_the_metadata() {
+		let before = metadata::<CallsBeforeUpgrade>(60);
+		let after = metadata::<CallsAfterUpgrade>(61);
+		let call = NamedCall::new("Ibc", "deliver", vec![1u8, 2, 3]);
+
+		let args = vec![1u8, 2, 3].encode();
+		assert_eq!(encode(&call, &before), [&[60, 0][..], &args].concat());
+		assert_eq!(encode(&call, &after), [&[61, 2][..], &args].concat());
+	}
+
+	#[test]
+	fn unknown_calls_fail_to_encode() {
+		let before = metadata::<CallsBeforeUpgrade>(60);
+		let call = NamedCall::new("Ibc", "set_params", vec![1u8]);
+		assert!(call.encode_call_data(&before, &mut vec![]).is_err());
+		let call = NamedCall::new("Transfer", "transfer", 1u128);
+		assert!(call.encode_call_data(&before, &mut vec![]).is_err());
+	}
+}
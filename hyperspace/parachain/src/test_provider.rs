@@ -13,8 +13,13 @@
 // limitations under the License.
 
 use crate::{
-	config, parachain::api, signer::ExtrinsicSigner, utils::unsafe_cast_to_jsonrpsee_client, Error,
-	ParachainClient,
+	config,
+	parachain::api,
+	reconnect::{self, ReconnectPolicy},
+	runtime_upgrade::{self, NamedCall},
+	signer::ExtrinsicSigner,
+	utils::unsafe_cast_to_jsonrpsee_client,
+	Error, ParachainClient,
 };
 #[cfg(feature = "dali")]
 use api::runtime_types::dali_runtime::Call;
@@ -27,20 +32,25 @@ use ibc::{
 	applications::transfer::{msgs::transfer::MsgTransfer, PrefixedCoin},
 	core::ics24_host::identifier::{ChannelId, ClientId, PortId},
 };
+use codec::{Decode, Encode};
 use ibc_rpc::IbcApiClient;
 use ics10_grandpa::client_message::RelayChainHeader;
-use jsonrpsee::core::client::SubscriptionClientT;
+use jsonrpsee::{
+	core::client::{ClientT, SubscriptionClientT},
+	rpc_params,
+};
 use pallet_ibc::{MultiAddress, Timeout, TransferParams};
+use pallet_ibc_ping::PingStats;
 use primitives::{KeyProvider, TestProvider};
 use sp_core::{
 	crypto::{AccountId32, Ss58Codec},
-	H256,
+	Bytes, H256,
 };
 use sp_runtime::{
 	traits::{Header as HeaderT, IdentifyAccount, One, Verify},
 	MultiSignature, MultiSigner,
 };
-use std::{collections::BTreeMap, fmt::Display, pin::Pin, str::FromStr};
+use std::{collections::BTreeMap, fmt::Display, pin::Pin, str::FromStr, sync::Arc};
 #[cfg(feature = "dali")]
 use subxt::tx::AssetTip as Tip;
 #[cfg(not(feature = "dali"))]
@@ -72,7 +82,7 @@ where
 	}
 
 	pub async fn submit_create_client_msg(&self, msg: pallet_ibc::Any) -> Result<ClientId, Error> {
-		let call = api::tx().ibc().deliver(vec![api::runtime_types::pallet_ibc::Any {
+		let call = runtime_upgrade::deliver(vec![api::runtime_types::pallet_ibc::Any {
 			type_url: msg.type_url,
 			value: msg.value,
 		}]);
@@ -121,7 +131,11 @@ where
 		let asset_id = api::runtime_types::primitives::currency::CurrencyId(asset_id);
 
 		// Submit extrinsic to parachain node
-		let call = api::tx().ibc().transfer(params, asset_id, amount.into());
+		let call = NamedCall::new(
+			"Ibc",
+			"transfer",
+			api::ibc::calls::Transfer { params, asset_id, amount: amount.into() },
+		);
 
 		self.submit_call(call).await?;
 
@@ -198,6 +212,7 @@ where
 				timestamp: Some(transfer.timeout_timestamp.nanoseconds()),
 				height: Some(transfer.timeout_height.revision_height),
 			},
+			memo: (!transfer.memo.is_empty()).then(|| transfer.memo.into_bytes()),
 		};
 		let amount = str::parse::<u128>(&transfer.token.amount.to_string()).expect("Infallible!");
 		dbg!(&amount);
@@ -210,6 +225,16 @@ where
 		&self,
 		channel_id: ChannelId,
 		timeout: Timeout,
+	) -> Result<(), Self::Error> {
+		self.send_pings(channel_id, 1, "ping".len() as u32, timeout).await
+	}
+
+	async fn send_pings(
+		&self,
+		channel_id: ChannelId,
+		count: u32,
+		payload_size: u32,
+		timeout: Timeout,
 	) -> Result<(), Self::Error> {
 		let (timeout_height, timestamp) = match timeout {
 			Timeout::Offset { timestamp, height } => (height.unwrap(), timestamp.unwrap()),
@@ -217,7 +242,8 @@ where
 		};
 
 		let params = api::runtime_types::pallet_ibc_ping::SendPingParams {
-			data: "ping".as_bytes().to_vec(),
+			count,
+			payload_size,
 			timeout_height_offset: timeout_height,
 			timeout_timestamp_offset: timestamp,
 			channel_id: channel_id.sequence(),
@@ -228,19 +254,52 @@ where
 		self.submit_call(call).await.map(|_| ())
 	}
 
-	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
-		let para_client = unsafe { unsafe_cast_to_jsonrpsee_client(&self.para_ws_client) };
-		let stream = para_client
-			.subscribe::<T::Header>("chain_subscribeNewHeads", None, "chain_unsubscribeNewHeads")
+	async fn query_ping_stats(
+		&self,
+		channel_id: ChannelId,
+	) -> Result<Option<PingStats>, Self::Error> {
+		let client = unsafe { unsafe_cast_to_jsonrpsee_client(&self.para_ws_client) };
+		let params =
+			rpc_params!("PingRuntimeApi_ping_stats", Bytes(channel_id.sequence().encode()));
+		let stats = client
+			.request::<Bytes>("state_call", params)
 			.await
-			.unwrap()
-			.map(|header| {
-				let header = header.unwrap();
-				let block_number: u64 = (*header.number()).into();
-				block_number
-			});
-
-		Box::pin(Box::new(stream))
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		Ok(Decode::decode(&mut &stats[..])?)
+	}
+
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		let url = self.parachain_rpc_url.clone();
+		let subscribe = move || {
+			let url = url.clone();
+			async move {
+				let ws_client = Arc::new(reconnect::connect(&url).await?);
+				let client = unsafe { unsafe_cast_to_jsonrpsee_client(&ws_client) };
+				let subscription = client
+					.subscribe::<T::Header>(
+						"chain_subscribeNewHeads",
+						None,
+						"chain_unsubscribeNewHeads",
+					)
+					.await
+					.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?
+					.map(|header| {
+						let header = header.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+						let block_number: u64 = (*header.number()).into();
+						Ok(block_number)
+					});
+				Ok(reconnect::keep_alive(client, subscription))
+			}
+		};
+		let stream = reconnect::reconnecting_stream(
+			format!("{} blocks", self.name),
+			ReconnectPolicy::default(),
+			self.subscription_reconnects.clone(),
+			subscribe,
+			|block_number| *block_number,
+		);
+
+		Box::pin(stream)
 	}
 
 	fn set_channel_whitelist(&mut self, channel_whitelist: Vec<(ChannelId, PortId)>) {
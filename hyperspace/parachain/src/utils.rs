@@ -16,344 +16,13 @@ use crate::{config, Error};
 use beefy_light_client_primitives::{ClientState, MmrUpdateProof};
 use std::sync::Arc;
 
-use crate::parachain::api::runtime_types::pallet_ibc::events::IbcEvent as MetadataIbcEvent;
 use beefy_primitives::known_payload_ids::MMR_ROOT_ID;
 use beefy_prover::helpers::unsafe_arc_cast;
 use codec::Decode;
 use frame_support::weights::DispatchClass;
 use frame_system::limits::BlockWeights;
-use pallet_ibc::events::IbcEvent as RawIbcEvent;
 use sp_core::H256;
 
-/// Allows to implement traits for the subxt generated code
-pub struct MetadataIbcEventWrapper(pub MetadataIbcEvent);
-
-impl From<MetadataIbcEventWrapper> for RawIbcEvent {
-	fn from(event: MetadataIbcEventWrapper) -> Self {
-		let event = event.0;
-		match event {
-			MetadataIbcEvent::NewBlock { revision_height, revision_number } =>
-				RawIbcEvent::NewBlock { revision_height, revision_number },
-			MetadataIbcEvent::OpenInitConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			} => RawIbcEvent::OpenInitConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			},
-			MetadataIbcEvent::OpenTryConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			} => RawIbcEvent::OpenTryConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			},
-			MetadataIbcEvent::OpenAckConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			} => RawIbcEvent::OpenAckConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			},
-			MetadataIbcEvent::OpenConfirmConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			} => RawIbcEvent::OpenConfirmConnection {
-				revision_height,
-				revision_number,
-				connection_id,
-				counterparty_connection_id,
-				client_id,
-				counterparty_client_id,
-			},
-			MetadataIbcEvent::OpenInitChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			} => RawIbcEvent::OpenInitChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			},
-			MetadataIbcEvent::OpenTryChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			} => RawIbcEvent::OpenTryChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			},
-			MetadataIbcEvent::OpenAckChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			} => RawIbcEvent::OpenAckChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			},
-			MetadataIbcEvent::OpenConfirmChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			} => RawIbcEvent::OpenConfirmChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			},
-			MetadataIbcEvent::CloseInitChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			} => RawIbcEvent::CloseInitChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			},
-			MetadataIbcEvent::CloseConfirmChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			} => RawIbcEvent::CloseConfirmChannel {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				connection_id,
-				counterparty_port_id,
-				counterparty_channel_id,
-			},
-			MetadataIbcEvent::SendPacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				dest_port,
-				dest_channel,
-				sequence,
-			} => RawIbcEvent::SendPacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				dest_port,
-				dest_channel,
-				sequence,
-			},
-			MetadataIbcEvent::WriteAcknowledgement {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				dest_port,
-				dest_channel,
-				sequence,
-			} => RawIbcEvent::WriteAcknowledgement {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				dest_port,
-				dest_channel,
-				sequence,
-			},
-			MetadataIbcEvent::TimeoutPacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				sequence,
-			} => RawIbcEvent::TimeoutPacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				sequence,
-			},
-			MetadataIbcEvent::TimeoutOnClosePacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				sequence,
-			} => RawIbcEvent::TimeoutOnClosePacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				sequence,
-			},
-			MetadataIbcEvent::CreateClient {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			} => RawIbcEvent::CreateClient {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			},
-			MetadataIbcEvent::UpdateClient {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			} => RawIbcEvent::UpdateClient {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			},
-			MetadataIbcEvent::UpgradeClient {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			} => RawIbcEvent::UpgradeClient {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			},
-			MetadataIbcEvent::ClientMisbehaviour {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			} => RawIbcEvent::ClientMisbehaviour {
-				client_id,
-				client_type,
-				revision_height,
-				revision_number,
-				consensus_height,
-				consensus_revision_number,
-			},
-			MetadataIbcEvent::ReceivePacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				dest_port,
-				dest_channel,
-				sequence,
-			} => RawIbcEvent::ReceivePacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				dest_port,
-				dest_channel,
-				sequence,
-			},
-			MetadataIbcEvent::AcknowledgePacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				sequence,
-			} => RawIbcEvent::AcknowledgePacket {
-				revision_height,
-				revision_number,
-				port_id,
-				channel_id,
-				sequence,
-			},
-			MetadataIbcEvent::AppModule { kind, module_id } =>
-				RawIbcEvent::AppModule { kind, module_id },
-			MetadataIbcEvent::Empty => RawIbcEvent::Empty,
-			MetadataIbcEvent::ChainError => RawIbcEvent::ChainError,
-		}
-	}
-}
-
 pub fn get_updated_client_state(
 	mut client_state: ClientState,
 	mmr_update: &MmrUpdateProof,
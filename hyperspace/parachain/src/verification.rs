@@ -0,0 +1,299 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local verification of the parachain rpc's storage queries, for operators that don't trust the
+//! rpc node. Query results are checked against the state roots of parachain headers proven final
+//! by the relay chain's GRANDPA finality proofs, which are verified against the client state
+//! tracked on the counterparty.
+
+use crate::error::Error;
+use codec::Decode;
+use finality_grandpa::Chain as _;
+use grandpa_light_client_primitives::{
+	justification::AncestryChain, parachain_header_storage_key, ClientState,
+	ParachainHeadersWithFinalityProof,
+};
+use grandpa_light_client_verifier::verify_parachain_headers_with_grandpa_finality_proof;
+use ibc::core::{
+	ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+	ics24_host::path::Path,
+};
+use ics10_grandpa::client_message::RelayChainHeader;
+use light_client_common::{verify_membership, verify_non_membership};
+use pallet_ibc::light_clients::HostFunctionsManager;
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, Header as HeaderT};
+use sp_trie::StorageProof;
+use std::{
+	collections::HashMap,
+	future::Future,
+	sync::{Arc, Mutex},
+};
+
+/// Parachains use the same header type as the relay chain.
+pub type ParachainHeader = RelayChainHeader;
+
+/// A parachain block verified to be final.
+#[derive(Clone, Copy, Debug)]
+struct VerifiedBlock {
+	number: u32,
+	parent_hash: H256,
+	state_root: H256,
+}
+
+impl From<&ParachainHeader> for VerifiedBlock {
+	fn from(header: &ParachainHeader) -> Self {
+		Self {
+			number: header.number,
+			parent_hash: header.parent_hash,
+			state_root: header.state_root,
+		}
+	}
+}
+
+/// State roots of the parachain blocks verified to be final, by block hash.
+#[derive(Clone, Default)]
+pub struct VerifiedStateRoots {
+	blocks: Arc<Mutex<HashMap<H256, VerifiedBlock>>>,
+}
+
+impl VerifiedStateRoots {
+	/// Records the headers as final.
+	pub fn insert(&self, headers: &[ParachainHeader]) {
+		let mut blocks = self.blocks.lock().unwrap();
+		for header in headers {
+			blocks.insert(header.hash(), VerifiedBlock::from(header));
+		}
+	}
+
+	/// Returns the state root of the final block at `number`. Blocks below the latest verified
+	/// one are verified by walking back the parent hashes from the closest verified block above
+	/// them, with the headers returned by `fetch_header`.
+	pub async fn state_root_at<F, Fut>(&self, number: u32, fetch_header: F) -> Result<H256, Error>
+	where
+		F: Fn(H256) -> Fut,
+		Fut: Future<Output = Result<Option<ParachainHeader>, Error>>,
+	{
+		let mut block = self
+			.blocks
+			.lock()
+			.unwrap()
+			.values()
+			.filter(|block| block.number >= number)
+			.min_by_key(|block| block.number)
+			.copied()
+			.ok_or_else(|| {
+				Error::Custom(format!(
+					"Block {number} hasn't been finalized by a verified relay chain proof yet"
+				))
+			})?;
+		while block.number > number {
+			let hash = block.parent_hash;
+			let header = fetch_header(hash)
+				.await?
+				.ok_or_else(|| Error::Custom(format!("Header {hash:?} not found")))?;
+			if header.hash() != hash {
+				return Err(Error::Custom(format!(
+					"Rpc returned header {:?} when queried for {hash:?}",
+					header.hash()
+				)))
+			}
+			block = VerifiedBlock::from(&header);
+			self.blocks.lock().unwrap().insert(hash, block);
+		}
+		Ok(block.state_root)
+	}
+}
+
+/// Verifies the GRANDPA finality proof of parachain headers against `client_state`, and returns
+/// the parachain headers it proves to be final.
+pub fn verify_finalized_parachain_headers(
+	client_state: ClientState<H256>,
+	proof: ParachainHeadersWithFinalityProof<RelayChainHeader>,
+) -> Result<Vec<ParachainHeader>, Error> {
+	let latest_relay_hash = client_state.latest_relay_hash;
+	let key = parachain_header_storage_key(client_state.para_id);
+	verify_parachain_headers_with_grandpa_finality_proof::<RelayChainHeader, HostFunctionsManager>(
+		client_state,
+		proof.clone(),
+	)
+	.map_err(|e| Error::Custom(format!("Finality proof failed local verification: {e:?}")))?;
+
+	let ParachainHeadersWithFinalityProof { finality_proof, parachain_headers } = proof;
+	let headers = AncestryChain::<RelayChainHeader>::new(&finality_proof.unknown_headers);
+	let finalized = headers
+		.ancestry(latest_relay_hash, finality_proof.block)
+		.map_err(|e| Error::Custom(format!("Invalid relay chain ancestry: {e:?}")))?;
+	parachain_headers
+		.into_iter()
+		// the verifier ignores the headers of relay chain blocks that aren't final.
+		.filter(|(hash, _)| finalized.contains(hash))
+		.map(|(hash, proofs)| {
+			let relay_header = headers
+				.header(&hash)
+				.ok_or_else(|| Error::Custom(format!("Relay chain header {hash:?} not found")))?;
+			let header = state_machine::read_proof_check::<BlakeTwo256, _>(
+				relay_header.state_root,
+				StorageProof::new(proofs.state_proof),
+				[key.as_ref()],
+			)
+			.map_err(|e| Error::Custom(format!("Invalid parachain header proof: {e:?}")))?
+			.remove(key.as_ref())
+			.flatten()
+			.ok_or_else(|| Error::Custom(format!("Parachain header not found in {hash:?}")))?;
+			Ok(ParachainHeader::decode(&mut &header[..])?)
+		})
+		.collect()
+}
+
+/// Verifies that `proof` proves `value` to be stored at `path` of the ibc child trie in the
+/// state whose root is `state_root`, or that nothing is stored there if `value` is `None`.
+pub fn verify_storage_proof(
+	prefix: &CommitmentPrefix,
+	state_root: H256,
+	proof: Vec<u8>,
+	path: impl Into<Path>,
+	value: Option<Vec<u8>>,
+) -> Result<(), Error> {
+	let proof = CommitmentProofBytes::try_from(proof)
+		.map_err(|e| Error::Custom(format!("Rpc returned an invalid proof: {e:?}")))?;
+	let root = CommitmentRoot::from_bytes(state_root.as_bytes());
+	let result = match value {
+		Some(value) => verify_membership::<BlakeTwo256, _>(prefix, &proof, &root, path, value),
+		None => verify_non_membership::<BlakeTwo256, _>(prefix, &proof, &root, path),
+	};
+	result.map_err(|e| {
+		Error::Custom(format!("Rpc response doesn't match its proof, refusing to use it: {e:?}"))
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use ibc::core::{
+		ics04_channel::packet::Sequence,
+		ics24_host::{
+			identifier::{ChannelId, PortId},
+			path::CommitmentsPath,
+		},
+	};
+	use sp_core::storage::{ChildInfo, StateVersion};
+	use state_machine::{prove_child_read_on_trie_backend, InMemoryBackend};
+
+	const PREFIX: &[u8] = b"ibc/";
+
+	fn commitment_path(sequence: u64) -> CommitmentsPath {
+		CommitmentsPath {
+			port_id: PortId::transfer(),
+			channel_id: ChannelId::new(0),
+			sequence: Sequence::from(sequence),
+		}
+	}
+
+	/// A state holding a commitment for sequence 1, with a proof of it.
+	fn state_with_commitment() -> (H256, Vec<u8>) {
+		let mut key = PREFIX.to_vec();
+		key.extend(commitment_path(1).to_string().as_bytes());
+		let child_info = ChildInfo::new_default(PREFIX);
+		let backend = InMemoryBackend::<BlakeTwo256>::from((
+			vec![(Some(child_info.clone()), vec![(key.clone(), Some(vec![1u8; 32]))])],
+			StateVersion::V0,
+		));
+		let proof = prove_child_read_on_trie_backend(&backend, &child_info, [&key])
+			.unwrap()
+			.into_nodes()
+			.into_iter()
+			.collect::<Vec<_>>()
+			.encode();
+		(*backend.root(), proof)
+	}
+
+	fn header(number: u32, parent_hash: H256) -> ParachainHeader {
+		ParachainHeader::new(
+			number,
+			Default::default(),
+			H256::repeat_byte(number as u8),
+			parent_hash,
+			Default::default(),
+		)
+	}
+
+	#[test]
+	fn storage_values_matching_the_proof_are_accepted() {
+		let (state_root, proof) = state_with_commitment();
+		let prefix = CommitmentPrefix::try_from(PREFIX.to_vec()).unwrap();
+
+		verify_storage_proof(&prefix, state_root, proof, commitment_path(1), Some(vec![1u8; 32]))
+			.unwrap();
+	}
+
+	#[test]
+	fn storage_values_inconsistent_with_the_proof_are_rejected() {
+		let (state_root, proof) = state_with_commitment();
+		let prefix = CommitmentPrefix::try_from(PREFIX.to_vec()).unwrap();
+
+		let forged_value = Some(vec![2u8; 32]);
+		assert!(verify_storage_proof(
+			&prefix,
+			state_root,
+			proof.clone(),
+			commitment_path(1),
+			forged_value
+		)
+		.is_err());
+		// the rpc claims the commitment doesn't exist.
+		assert!(
+			verify_storage_proof(&prefix, state_root, proof.clone(), commitment_path(1), None)
+				.is_err()
+		);
+		// the proof is for another state.
+		assert!(verify_storage_proof(
+			&prefix,
+			H256::repeat_byte(1),
+			proof,
+			commitment_path(1),
+			Some(vec![1u8; 32])
+		)
+		.is_err());
+	}
+
+	#[test]
+	fn state_roots_below_verified_blocks_are_verified_through_parent_hashes() {
+		let first = header(1, H256::zero());
+		let second = header(2, first.hash());
+		let third = header(3, second.hash());
+		let roots = VerifiedStateRoots::default();
+		roots.insert(&[third.clone()]);
+
+		let chain = vec![first.clone(), second.clone()];
+		let fetch = |hash: H256| {
+			let header = chain.iter().find(|header| header.hash() == hash).cloned();
+			async move { Ok(header) }
+		};
+		let state_root = futures::executor::block_on(roots.state_root_at(1, fetch)).unwrap();
+		assert_eq!(state_root, first.state_root);
+		assert!(futures::executor::block_on(roots.state_root_at(4, fetch)).is_err());
+
+		// a header that isn't the parent of the verified block is rejected.
+		let roots = VerifiedStateRoots::default();
+		roots.insert(&[third]);
+		let forged = header(2, H256::repeat_byte(9));
+		let fetch = |_: H256| {
+			let header = forged.clone();
+			async move { Ok(Some(header)) }
+		};
+		assert!(futures::executor::block_on(roots.state_root_at(2, fetch)).is_err());
+	}
+}
@@ -0,0 +1,113 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cross-checks the relay chain finality reported by the primary rpc endpoint against a secondary
+//! endpoint, so that a node feeding the relayer a conflicting finality proof gets caught.
+
+use crate::error::Error;
+use codec::Decode;
+use finality_grandpa_rpc::GrandpaApiClient;
+use grandpa_light_client_primitives::FinalityProof;
+use ics10_grandpa::client_message::RelayChainHeader;
+use sp_core::H256;
+use sp_runtime::traits::Header as HeaderT;
+use subxt::OnlineClient;
+
+/// Blocks finalized by a node.
+#[async_trait::async_trait]
+pub trait FinalizedBlocks {
+	/// Hash of the block finalized at `number`, `None` if the node hasn't finalized it yet.
+	async fn finalized_block_hash(&self, number: u32) -> Result<Option<H256>, Error>;
+}
+
+#[async_trait::async_trait]
+impl<T: subxt::Config + Send + Sync> FinalizedBlocks for OnlineClient<T>
+where
+	u32: From<<<T as subxt::Config>::Header as HeaderT>::Number>,
+	H256: From<T::Hash>,
+{
+	async fn finalized_block_hash(&self, number: u32) -> Result<Option<H256>, Error> {
+		let finalized_hash = self.rpc().finalized_head().await?;
+		let finalized_header = self
+			.rpc()
+			.header(Some(finalized_hash))
+			.await?
+			.ok_or_else(|| Error::from(format!("No header found for hash: {:?}", finalized_hash)))?;
+		if u32::from(*finalized_header.number()) < number {
+			return Ok(None)
+		}
+		Ok(self.rpc().block_hash(Some(number.into())).await?.map(H256::from))
+	}
+}
+
+/// Hash of the block `secondary` finalized at `number`, if it differs from `hash`, the block the
+/// primary endpoint reported as finalized at that height.
+pub async fn conflicting_block_hash(
+	secondary: &impl FinalizedBlocks,
+	number: u32,
+	hash: H256,
+) -> Result<Option<H256>, Error> {
+	let secondary_hash = secondary.finalized_block_hash(number).await?;
+	Ok(secondary_hash.filter(|secondary_hash| *secondary_hash != hash))
+}
+
+/// Queries the node's proof of the finality of block `number`.
+pub async fn prove_finality(
+	client: &jsonrpsee_ws_client::WsClient,
+	number: u32,
+) -> Result<FinalityProof<RelayChainHeader>, Error> {
+	let encoded = GrandpaApiClient::<sp_core::Bytes, H256, u32>::prove_finality(client, number)
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?
+		.ok_or_else(|| Error::from(format!("No justification found for block: {}", number)))?
+		.0;
+	Ok(FinalityProof::<RelayChainHeader>::decode(&mut &encoded[..])?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::collections::HashMap;
+
+	/// A node that finalized the given blocks.
+	struct MockNode(HashMap<u32, H256>);
+
+	#[async_trait::async_trait]
+	impl FinalizedBlocks for MockNode {
+		async fn finalized_block_hash(&self, number: u32) -> Result<Option<H256>, Error> {
+			Ok(self.0.get(&number).copied())
+		}
+	}
+
+	#[test]
+	fn conflicting_finality_is_detected() {
+		let primary = MockNode(HashMap::from([(10, H256::repeat_byte(1))]));
+		let secondary = MockNode(HashMap::from([(10, H256::repeat_byte(2))]));
+		futures::executor::block_on(async {
+			let hash = primary.finalized_block_hash(10).await.unwrap().unwrap();
+			let conflict = conflicting_block_hash(&secondary, 10, hash).await.unwrap();
+			assert_eq!(conflict, Some(H256::repeat_byte(2)));
+			assert_eq!(conflicting_block_hash(&primary, 10, hash).await.unwrap(), None);
+		});
+	}
+
+	#[test]
+	fn blocks_not_final_on_the_secondary_are_skipped() {
+		let secondary = MockNode(HashMap::from([(10, H256::repeat_byte(2))]));
+		let conflict =
+			futures::executor::block_on(conflicting_block_hash(&secondary, 11, H256::zero()))
+				.unwrap();
+		assert_eq!(conflict, None);
+	}
+}
@@ -14,7 +14,7 @@
 
 #![allow(clippy::all)]
 
-use std::{pin::Pin, str::FromStr, time::Duration};
+use std::{collections::BTreeSet, pin::Pin, str::FromStr, time::Duration};
 
 use futures::Stream;
 use ibc_proto::{
@@ -25,21 +25,27 @@ use ibc_proto::{
 			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
 			QueryPacketReceiptResponse,
 		},
-		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
+		client::v1::{
+			MsgSubmitMisbehaviour as RawMsgSubmitMisbehaviour,
+			MsgUpdateClient as RawMsgUpdateClient, QueryClientStateResponse,
+			QueryConsensusStateResponse,
+		},
 		connection::v1::QueryConnectionResponse,
 	},
 };
+use prost::Message;
 
 use crate::error::Error;
 #[cfg(feature = "testing")]
 use ibc::applications::transfer::msgs::transfer::MsgTransfer;
 use ibc::{
-	applications::transfer::PrefixedCoin,
+	applications::{fee::PacketFee, transfer::PrefixedCoin},
 	core::{
 		ics02_client::{
 			client_consensus::ConsensusState as ConsensusStateT,
-			client_state::{ClientState as ClientStateT, ClientType},
+			client_state::{ClientState as ClientStateT, ClientStatus, ClientType},
 			events::UpdateClient,
+			msgs::{misbehaviour, update_client},
 		},
 		ics04_channel::{
 			channel::{ChannelEnd, Order},
@@ -57,13 +63,24 @@ use ibc::{
 use ibc_proto::ibc::core::{
 	channel::v1::QueryChannelsResponse, connection::v1::IdentifiedConnection,
 };
-use ibc_rpc::PacketInfo;
+use ibc_rpc::{PacketInfo, QueryNextSequenceAckResponse};
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
 
 pub mod error;
 pub mod mock;
 pub mod utils;
 
+/// How a failed submission of messages should be handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitErrorKind {
+	/// The submission may succeed if retried, e.g. after a nonce mismatch, a full transaction
+	/// pool or a dropped connection.
+	Retriable,
+	/// The messages won't be accepted as they are, e.g. a proof failed to verify or a message
+	/// couldn't be decoded.
+	Fatal,
+}
+
 pub enum UpdateMessage {
 	Single(Any),
 	Batch(Vec<Any>),
@@ -99,7 +116,7 @@ pub trait IbcProvider {
 	type FinalityEvent;
 
 	/// A representation of the transaction id for the chain
-	type TransactionId;
+	type TransactionId: std::fmt::Debug;
 
 	/// Error type, just needs to implement standard error trait.
 	type Error: std::error::Error + From<String> + Send + Sync + 'static;
@@ -134,6 +151,13 @@ pub trait IbcProvider {
 		client_id: ClientId,
 	) -> Result<QueryClientStateResponse, Self::Error>;
 
+	/// Query whether a client is active, expired or frozen
+	async fn query_client_status(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<ClientStatus, Self::Error>;
+
 	/// Query connection end with proof
 	async fn query_connection_end(
 		&self,
@@ -152,6 +176,14 @@ pub trait IbcProvider {
 	/// Query proof for provided key path
 	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error>;
 
+	/// Query a proof for each of the provided key paths, in the same order, fetching all of them
+	/// at once if the chain supports it.
+	async fn query_proofs(
+		&self,
+		at: Height,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Vec<u8>>, Self::Error>;
+
 	/// Query packet commitment with proof
 	async fn query_packet_commitment(
 		&self,
@@ -178,6 +210,14 @@ pub trait IbcProvider {
 		channel_id: &ChannelId,
 	) -> Result<QueryNextSequenceReceiveResponse, Self::Error>;
 
+	/// Query next sequence to be acknowledged on an ordered channel
+	async fn query_next_sequence_ack(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceAckResponse, Self::Error>;
+
 	/// Query packet receipt
 	async fn query_packet_receipt(
 		&self,
@@ -197,11 +237,14 @@ pub trait IbcProvider {
 		port_id: PortId,
 	) -> Result<Vec<u64>, Self::Error>;
 
+	/// Returns the sequences of the acknowledgements written on a channel. When `seqs` is given
+	/// only those sequences are looked up.
 	async fn query_packet_acknowledgements(
 		&self,
 		at: Height,
 		channel_id: ChannelId,
 		port_id: PortId,
+		seqs: Option<Vec<u64>>,
 	) -> Result<Vec<u64>, Self::Error>;
 
 	/// Given a list of counterparty packet commitments, the querier checks if the packet
@@ -261,6 +304,16 @@ pub trait IbcProvider {
 		seqs: Vec<u64>,
 	) -> Result<Vec<PacketInfo>, Self::Error>;
 
+	/// Query the ICS29 fees escrowed on this chain for the packet sent with sequence `seq` on the
+	/// given channel.
+	async fn query_packet_fees(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seq: u64,
+	) -> Result<Vec<PacketFee>, Self::Error>;
+
 	/// Return the expected block time for this chain
 	fn expected_block_time(&self) -> Duration;
 
@@ -300,6 +353,9 @@ pub trait IbcProvider {
 	/// Should return a list of all clients on the chain
 	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error>;
 
+	/// Should return a list of all connections on the chain
+	async fn query_connections(&self) -> Result<Vec<IdentifiedConnection>, Self::Error>;
+
 	/// Should return a list of all clients on the chain
 	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error>;
 
@@ -328,6 +384,12 @@ pub trait IbcProvider {
 		&self,
 		tx_id: Self::TransactionId,
 	) -> Result<ClientId, Self::Error>;
+
+	/// Should return the ibc events emitted in the block that included this transaction
+	async fn query_tx_block_events(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<Vec<IbcEvent>, Self::Error>;
 }
 
 /// Provides an interface that allows us run the hyperspace-testsuite
@@ -345,6 +407,21 @@ pub trait TestProvider: Chain + Clone + 'static {
 		timeout: pallet_ibc::Timeout,
 	) -> Result<(), Self::Error>;
 
+	/// Send `count` pings carrying `payload_size` bytes each on an ordered channel
+	async fn send_pings(
+		&self,
+		channel_id: ChannelId,
+		count: u32,
+		payload_size: u32,
+		timeout: pallet_ibc::Timeout,
+	) -> Result<(), Self::Error>;
+
+	/// Query the round-trip latencies of the pings acknowledged on the channel
+	async fn query_ping_stats(
+		&self,
+		channel_id: ChannelId,
+	) -> Result<Option<pallet_ibc_ping::PingStats>, Self::Error>;
+
 	/// Returns a stream that yields chain Block number
 	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>>;
 
@@ -388,16 +465,78 @@ pub trait Chain: IbcProvider + MisbehaviourHandler + KeyProvider + Send + Sync {
 		&self,
 	) -> Pin<Box<dyn Stream<Item = Self::FinalityEvent> + Send + Sync>>;
 
+	/// Number of times the chain's subscriptions were re-established after their connection
+	/// dropped.
+	fn subscription_reconnects(&self) -> u64;
+
 	/// This should be used to submit new messages [`Vec<Any>`] from a counterparty chain to this
 	/// chain.
 	/// Should return the transaction id
 	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Self::Error>;
 
+	/// Classifies an error returned by [`Chain::submit`], telling whether the same messages may
+	/// be accepted if they're submitted again.
+	fn submit_error_kind(&self, error: &Self::Error) -> SubmitErrorKind;
+
 	/// Returns an [`AnyClientMessage`] for an [`UpdateClient`] event
 	async fn query_client_message(
 		&self,
 		update: UpdateClient,
 	) -> Result<AnyClientMessage, Self::Error>;
+
+	/// Whether the chain has a secondary rpc endpoint to cross-check its finality with, see
+	/// [`Chain::query_conflicting_finality`].
+	fn has_secondary_rpc(&self) -> bool;
+
+	/// Checks the block finalized by `finality_event` against the chain's secondary rpc endpoint.
+	/// If the two endpoints finalized different blocks at the same height, returns the
+	/// misbehaviour proving it, to freeze the chain's light client on the counterparty with.
+	async fn query_conflicting_finality(
+		&self,
+		finality_event: Self::FinalityEvent,
+	) -> Result<Option<AnyClientMessage>, anyhow::Error>;
+
+	/// Encodes a message for the chain's light client of its counterparty, as the chain's ibc
+	/// host decodes it in the messages updating the client or submitting its misbehaviour.
+	fn encode_client_message(&self, client_message: AnyClientMessage) -> Any {
+		client_message.into()
+	}
+
+	/// Whether the chain accepts `MsgUpdateClientBatch`es, carrying several updates of the same
+	/// client in one message.
+	fn supports_client_update_batches(&self) -> bool {
+		true
+	}
+}
+
+/// Message updating the client `client_id` on `sink` with `client_message`, signed by the
+/// relayer's account on `sink`.
+pub fn update_client_msg(
+	sink: &impl Chain,
+	client_id: ClientId,
+	client_message: AnyClientMessage,
+) -> Any {
+	let msg = RawMsgUpdateClient {
+		client_id: client_id.to_string(),
+		client_message: Some(sink.encode_client_message(client_message)),
+		signer: sink.account_id().to_string(),
+	};
+	Any { type_url: update_client::TYPE_URL.to_string(), value: msg.encode_to_vec() }
+}
+
+/// Message freezing the client `client_id` on `sink` with the misbehaviour in `client_message`,
+/// signed by the relayer's account on `sink`.
+pub fn submit_misbehaviour_msg(
+	sink: &impl Chain,
+	client_id: ClientId,
+	client_message: AnyClientMessage,
+) -> Any {
+	let msg = RawMsgSubmitMisbehaviour {
+		client_id: client_id.to_string(),
+		misbehaviour: Some(sink.encode_client_message(client_message)),
+		signer: sink.account_id().to_string(),
+	};
+	Any { type_url: misbehaviour::TYPE_URL.to_string(), value: msg.encode_to_vec() }
 }
 
 /// Returns undelivered packet sequences that have been sent out from
@@ -442,14 +581,21 @@ pub async fn query_undelivered_sequences(
 			.query_next_sequence_recv(sink_height, &counterparty_port_id, &counterparty_channel_id)
 			.await?
 			.next_sequence_receive;
-		seqs.into_iter().filter(|seq| *seq > next_seq_recv).collect()
+		undelivered_ordered_sequences(seqs, next_seq_recv)
 	};
 
 	Ok(undelivered_sequences)
 }
 
+/// Sequences of the packets still committed on the source of an ordered channel that the sink
+/// hasn't received, given the next sequence the sink expects to receive. That next sequence itself
+/// is still undelivered.
+pub fn undelivered_ordered_sequences(seqs: Vec<u64>, next_seq_recv: u64) -> Vec<u64> {
+	seqs.into_iter().filter(|seq| *seq >= next_seq_recv).collect()
+}
+
 /// Queries the `source` chain for packet acknowledgements that have not been seen by the `sink`
-/// chain.
+/// chain, see [`undelivered_acks`].
 pub async fn query_undelivered_acks(
 	source_height: Height,
 	sink_height: Height,
@@ -466,26 +612,48 @@ pub async fn query_undelivered_acks(
 			.ok_or_else(|| Error::Custom("ChannelEnd not could not be decoded".to_string()))?,
 	)
 	.map_err(|e| Error::Custom(e.to_string()))?;
-	// First we fetch all packet acknowledgements from source
-	let seqs = source
-		.query_packet_acknowledgements(source_height, channel_id, port_id.clone())
-		.await?;
 	let counterparty_channel_id = channel_end
 		.counterparty()
 		.channel_id
 		.ok_or_else(|| Error::Custom("Expected counterparty channel id".to_string()))?;
 	let counterparty_port_id = channel_end.counterparty().port_id.clone();
 
-	let undelivered_acks = sink
-		.query_unreceived_acknowledgements(
-			sink_height,
-			counterparty_channel_id,
-			counterparty_port_id.clone(),
-			seqs,
+	// The sink keeps the commitment of every packet it sent until it sees the acknowledgement,
+	// so only those sequences can have an undelivered acknowledgement on source.
+	let pending_seqs = sink
+		.query_packet_commitments(sink_height, counterparty_channel_id, counterparty_port_id)
+		.await?;
+	if pending_seqs.is_empty() {
+		return Ok(vec![])
+	}
+
+	let written_acks = source
+		.query_packet_acknowledgements(
+			source_height,
+			channel_id,
+			port_id,
+			Some(pending_seqs.clone()),
 		)
 		.await?;
 
-	Ok(undelivered_acks)
+	Ok(undelivered_acks(channel_end.ordering, &pending_seqs, &written_acks))
+}
+
+/// Sequences of the acknowledgements the sink can be sent, in increasing order, given the
+/// sequences of the packets it sent that are still committed on it and the sequences of the
+/// acknowledgements written for them on the source.
+///
+/// An acknowledgement is undelivered as long as the commitment of its packet exists. Channels
+/// that aren't unordered process acknowledgements in sequence order, so there only the ones before
+/// the first committed packet without an acknowledgement can be delivered.
+pub fn undelivered_acks(ordering: Order, commitments: &[u64], written_acks: &[u64]) -> Vec<u64> {
+	let written_acks = written_acks.iter().copied().collect::<BTreeSet<_>>();
+	let commitments = commitments.iter().copied().collect::<BTreeSet<_>>().into_iter();
+	match ordering {
+		Order::Unordered => commitments.filter(|seq| written_acks.contains(seq)).collect(),
+		Order::Ordered | Order::OrderedAllowTimeout =>
+			commitments.take_while(|seq| written_acks.contains(seq)).collect(),
+	}
 }
 
 pub fn packet_info_to_packet(packet_info: &PacketInfo) -> Packet {
@@ -505,6 +673,23 @@ pub fn packet_info_to_packet(packet_info: &PacketInfo) -> Packet {
 	}
 }
 
+/// Returns the host height of the `UpdateClient` event among `events` that wrote the consensus
+/// state of `client_id` at `client_height`, sparing a query to the chain. Events emitted before
+/// header heights were recorded never match.
+pub fn client_update_height_from_events(
+	events: &[IbcEvent],
+	client_id: &ClientId,
+	client_height: Height,
+) -> Option<Height> {
+	events.iter().find_map(|event| match event {
+		IbcEvent::UpdateClient(update)
+			if update.client_id() == client_id &&
+				update.header_heights().contains(&client_height) =>
+			Some(update.height()),
+		_ => None,
+	})
+}
+
 /// Should return the first client consensus height with a consensus state timestamp that
 /// is equal to or greater than the values provided
 pub async fn find_suitable_proof_height_for_client(
@@ -625,3 +810,62 @@ pub async fn query_maximum_height_for_timeout_proofs(
 	}
 	min_timeout_height
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use proptest::{collection::vec, prelude::*};
+
+	/// Brute-force [`undelivered_acks`]: a committed packet's acknowledgement can be delivered if
+	/// it's written and, on ordered channels, so are those of all the committed packets before it.
+	fn reference_undelivered_acks(
+		ordering: Order,
+		commitments: &[u64],
+		written_acks: &[u64],
+	) -> Vec<u64> {
+		let max = commitments.iter().copied().max().unwrap_or_default();
+		(0..=max)
+			.filter(|seq| commitments.contains(seq) && written_acks.contains(seq))
+			.filter(|seq| {
+				ordering == Order::Unordered ||
+					commitments
+						.iter()
+						.filter(|committed| *committed < seq)
+						.all(|committed| written_acks.contains(committed))
+			})
+			.collect()
+	}
+
+	proptest! {
+		#[test]
+		fn undelivered_acks_matches_reference(
+			commitments in vec(0u64..64, 0..32),
+			written_acks in vec(0u64..64, 0..32),
+			ordering in prop_oneof![
+				Just(Order::Unordered),
+				Just(Order::Ordered),
+				Just(Order::OrderedAllowTimeout),
+			],
+		) {
+			prop_assert_eq!(
+				undelivered_acks(ordering, &commitments, &written_acks),
+				reference_undelivered_acks(ordering, &commitments, &written_acks)
+			);
+		}
+	}
+
+	#[test]
+	fn next_sequence_recv_of_ordered_channel_is_undelivered() {
+		assert_eq!(undelivered_ordered_sequences(vec![3, 4, 5, 6], 5), vec![5, 6]);
+		assert_eq!(undelivered_ordered_sequences(vec![3, 4], 5), Vec::<u64>::new());
+		assert_eq!(undelivered_ordered_sequences(vec![1, 2], 1), vec![1, 2]);
+	}
+
+	#[test]
+	fn ordered_acks_stop_at_the_first_gap() {
+		let commitments = [6, 3, 4, 5];
+		let written_acks = [3, 4, 6, 7];
+		assert_eq!(undelivered_acks(Order::Ordered, &commitments, &written_acks), vec![3, 4]);
+		assert_eq!(undelivered_acks(Order::Unordered, &commitments, &written_acks), vec![3, 4, 6]);
+	}
+}
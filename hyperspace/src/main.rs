@@ -50,5 +50,7 @@ async fn main() -> Result<()> {
 				.map_err(|e| anyhow!(e))
 		},
 		Subcommand::Fish(cmd) => cmd.fish().await,
+		Subcommand::RelayPacket(cmd) => cmd.run().await,
+		Subcommand::Query(cmd) => cmd.run().await,
 	}
 }
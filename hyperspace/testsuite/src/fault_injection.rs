@@ -0,0 +1,777 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Chain`] wrapper that injects faults in the submissions made to the wrapped chain and in the
+//! proofs served by it, to exercise the relayer's handling of late, lost, duplicated and
+//! invalid messages.
+
+use crate::{
+	assert_send_transfer, send_transfer, setup_connection_and_channel, utils::assert_timeout_packet,
+};
+use async_trait::async_trait;
+use futures::{future, Stream, StreamExt};
+use hyperspace_core::store::MemoryStore;
+use hyperspace_primitives::{
+	utils::timeout_future, Chain, IbcProvider, KeyProvider, MisbehaviourHandler,
+	SubmitErrorKind, TestProvider, UpdateType,
+};
+use ibc::{
+	applications::{
+		fee::PacketFee,
+		transfer::{msgs::transfer::MsgTransfer, PrefixedCoin},
+	},
+	core::{
+		ics02_client::{
+			client_state::{ClientStatus, ClientType},
+			events::UpdateClient,
+			msgs::update_client,
+		},
+		ics23_commitment::commitment::CommitmentPrefix,
+		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+	},
+	events::IbcEvent,
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::{
+		channel::v1::{
+			QueryChannelResponse, QueryChannelsResponse, QueryNextSequenceReceiveResponse,
+			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
+			QueryPacketReceiptResponse,
+		},
+		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
+		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
+	},
+};
+use ibc_rpc::{PacketInfo, QueryNextSequenceAckResponse};
+use pallet_ibc::{
+	light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState},
+	Timeout,
+};
+use std::{
+	pin::Pin,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc, Mutex,
+	},
+	time::Duration,
+};
+use tokio::task::JoinHandle;
+
+/// The faults injected by a [`FaultyChain`]. The default injects none.
+#[derive(Debug, Clone, Default)]
+pub struct FaultConfig {
+	/// Number of blocks to wait for on the wrapped chain before making a submission.
+	pub submission_delay: u64,
+	/// Drop every k-th message submitted to the wrapped chain.
+	pub drop_every: Option<u64>,
+	/// Submit every message twice in the same batch. Client updates are never duplicated, as
+	/// the second update to the same height would be rejected along with the whole batch.
+	pub duplicate: bool,
+	/// Serve the proofs queried at a height from that many blocks below it, so that they don't
+	/// verify against the root of the height they're claimed for.
+	pub stale_proof_offset: u64,
+}
+
+#[derive(Default)]
+struct FaultState {
+	config: Mutex<FaultConfig>,
+	submitted_messages: AtomicU64,
+	dropped_messages: AtomicU64,
+	duplicated_messages: AtomicU64,
+	stale_proofs: AtomicU64,
+}
+
+/// Wraps a [`Chain`] and injects the faults of its [`FaultConfig`], delegating everything else to
+/// the wrapped chain. Clones share the same faults and counters, so the faults of a chain handed
+/// to the relayer can still be changed by the test.
+#[derive(Clone)]
+pub struct FaultyChain<C> {
+	inner: C,
+	state: Arc<FaultState>,
+}
+
+impl<C> FaultyChain<C> {
+	/// Wraps `inner`, injecting no faults until [`FaultyChain::set_faults`] is called.
+	pub fn new(inner: C) -> Self {
+		Self { inner, state: Default::default() }
+	}
+
+	/// Replaces the injected faults.
+	pub fn set_faults(&self, config: FaultConfig) {
+		*self.state.config.lock().unwrap() = config;
+	}
+
+	/// The currently injected faults.
+	pub fn faults(&self) -> FaultConfig {
+		self.state.config.lock().unwrap().clone()
+	}
+
+	/// Number of messages that were dropped instead of being submitted.
+	pub fn dropped_messages(&self) -> u64 {
+		self.state.dropped_messages.load(Ordering::SeqCst)
+	}
+
+	/// Number of messages that were submitted a second time.
+	pub fn duplicated_messages(&self) -> u64 {
+		self.state.duplicated_messages.load(Ordering::SeqCst)
+	}
+
+	/// Number of proofs that were served at a stale height.
+	pub fn stale_proofs(&self) -> u64 {
+		self.state.stale_proofs.load(Ordering::SeqCst)
+	}
+
+	/// The wrapped chain.
+	pub fn inner(&self) -> &C {
+		&self.inner
+	}
+
+	/// The height to actually query a proof requested at `at` from.
+	fn proof_height(&self, at: Height) -> Height {
+		let offset = self.faults().stale_proof_offset;
+		if offset == 0 {
+			return at
+		}
+		self.state.stale_proofs.fetch_add(1, Ordering::SeqCst);
+		Height::new(at.revision_number, at.revision_height.saturating_sub(offset).max(1))
+	}
+
+	/// Applies the dropping and duplication faults to a batch of messages.
+	fn tamper(&self, messages: Vec<Any>) -> Vec<Any> {
+		let config = self.faults();
+		let mut tampered = Vec::with_capacity(messages.len());
+		for message in messages {
+			let count = self.state.submitted_messages.fetch_add(1, Ordering::SeqCst) + 1;
+			if matches!(config.drop_every, Some(k) if k > 0 && count % k == 0) {
+				self.state.dropped_messages.fetch_add(1, Ordering::SeqCst);
+				continue
+			}
+			if config.duplicate && message.type_url != update_client::TYPE_URL {
+				self.state.duplicated_messages.fetch_add(1, Ordering::SeqCst);
+				tampered.push(message.clone());
+			}
+			tampered.push(message);
+		}
+		tampered
+	}
+}
+
+impl<C: IbcProvider> FaultyChain<C> {
+	/// Waits for the wrapped chain to produce `blocks` blocks.
+	async fn wait_for_blocks(&self, blocks: u64) -> Result<(), C::Error> {
+		let (start, ..) = self.inner.latest_height_and_timestamp().await?;
+		loop {
+			tokio::time::sleep(self.inner.expected_block_time()).await;
+			let (height, ..) = self.inner.latest_height_and_timestamp().await?;
+			if height.revision_height >= start.revision_height + blocks {
+				return Ok(())
+			}
+		}
+	}
+}
+
+#[async_trait]
+impl<C> IbcProvider for FaultyChain<C>
+where
+	C: Chain,
+	C::FinalityEvent: Send + Sync,
+	C::TransactionId: Send + Sync,
+{
+	type FinalityEvent = C::FinalityEvent;
+	type TransactionId = C::TransactionId;
+	type Error = C::Error;
+
+	async fn query_latest_ibc_events<T>(
+		&mut self,
+		finality_event: Self::FinalityEvent,
+		counterparty: &T,
+	) -> Result<(Any, Vec<IbcEvent>, UpdateType), anyhow::Error>
+	where
+		T: Chain,
+	{
+		self.inner.query_latest_ibc_events(finality_event, counterparty).await
+	}
+
+	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
+		self.inner.ibc_events().await
+	}
+
+	async fn query_client_consensus(
+		&self,
+		at: Height,
+		client_id: ClientId,
+		consensus_height: Height,
+	) -> Result<QueryConsensusStateResponse, Self::Error> {
+		self.inner.query_client_consensus(at, client_id, consensus_height).await
+	}
+
+	async fn query_client_state(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<QueryClientStateResponse, Self::Error> {
+		self.inner.query_client_state(at, client_id).await
+	}
+
+	async fn query_client_status(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<ClientStatus, Self::Error> {
+		self.inner.query_client_status(at, client_id).await
+	}
+
+	async fn query_connection_end(
+		&self,
+		at: Height,
+		connection_id: ConnectionId,
+	) -> Result<QueryConnectionResponse, Self::Error> {
+		self.inner.query_connection_end(at, connection_id).await
+	}
+
+	async fn query_channel_end(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryChannelResponse, Self::Error> {
+		self.inner.query_channel_end(at, channel_id, port_id).await
+	}
+
+	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
+		self.inner.query_proof(self.proof_height(at), keys).await
+	}
+
+	async fn query_proofs(
+		&self,
+		at: Height,
+		keys: Vec<Vec<u8>>,
+	) -> Result<Vec<Vec<u8>>, Self::Error> {
+		self.inner.query_proofs(self.proof_height(at), keys).await
+	}
+
+	async fn query_packet_commitment(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
+		self.inner.query_packet_commitment(at, port_id, channel_id, seq).await
+	}
+
+	async fn query_packet_acknowledgement(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
+		self.inner.query_packet_acknowledgement(at, port_id, channel_id, seq).await
+	}
+
+	async fn query_next_sequence_recv(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
+		self.inner.query_next_sequence_recv(at, port_id, channel_id).await
+	}
+
+	async fn query_next_sequence_ack(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceAckResponse, Self::Error> {
+		self.inner.query_next_sequence_ack(at, port_id, channel_id).await
+	}
+
+	async fn query_packet_receipt(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketReceiptResponse, Self::Error> {
+		self.inner.query_packet_receipt(at, port_id, channel_id, seq).await
+	}
+
+	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		self.inner.latest_height_and_timestamp().await
+	}
+
+	async fn query_packet_commitments(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner.query_packet_commitments(at, channel_id, port_id).await
+	}
+
+	async fn query_packet_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Option<Vec<u64>>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner.query_packet_acknowledgements(at, channel_id, port_id, seqs).await
+	}
+
+	async fn query_unreceived_packets(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner.query_unreceived_packets(at, channel_id, port_id, seqs).await
+	}
+
+	async fn query_unreceived_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner.query_unreceived_acknowledgements(at, channel_id, port_id, seqs).await
+	}
+
+	fn channel_whitelist(&self) -> Vec<(ChannelId, PortId)> {
+		self.inner.channel_whitelist()
+	}
+
+	async fn query_connection_channels(
+		&self,
+		at: Height,
+		connection_id: &ConnectionId,
+	) -> Result<QueryChannelsResponse, Self::Error> {
+		self.inner.query_connection_channels(at, connection_id).await
+	}
+
+	async fn query_send_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.inner.query_send_packets(channel_id, port_id, seqs).await
+	}
+
+	async fn query_recv_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.inner.query_recv_packets(channel_id, port_id, seqs).await
+	}
+
+	async fn query_packet_fees(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seq: u64,
+	) -> Result<Vec<PacketFee>, Self::Error> {
+		self.inner.query_packet_fees(at, channel_id, port_id, seq).await
+	}
+
+	fn expected_block_time(&self) -> Duration {
+		self.inner.expected_block_time()
+	}
+
+	async fn query_client_update_time_and_height(
+		&self,
+		client_id: ClientId,
+		client_height: Height,
+	) -> Result<(Height, Timestamp), Self::Error> {
+		self.inner.query_client_update_time_and_height(client_id, client_height).await
+	}
+
+	async fn query_host_consensus_state_proof(
+		&self,
+		height: Height,
+	) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.inner.query_host_consensus_state_proof(height).await
+	}
+
+	async fn query_ibc_balance(&self) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		self.inner.query_ibc_balance().await
+	}
+
+	fn connection_prefix(&self) -> CommitmentPrefix {
+		self.inner.connection_prefix()
+	}
+
+	fn client_id(&self) -> ClientId {
+		self.inner.client_id()
+	}
+
+	fn connection_id(&self) -> ConnectionId {
+		self.inner.connection_id()
+	}
+
+	fn client_type(&self) -> ClientType {
+		self.inner.client_type()
+	}
+
+	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		self.inner.query_timestamp_at(block_number).await
+	}
+
+	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
+		self.inner.query_clients().await
+	}
+
+	async fn query_connections(&self) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		self.inner.query_connections().await
+	}
+
+	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
+		self.inner.query_channels().await
+	}
+
+	async fn query_connection_using_client(
+		&self,
+		height: u32,
+		client_id: String,
+	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		self.inner.query_connection_using_client(height, client_id).await
+	}
+
+	fn is_update_required(
+		&self,
+		latest_height: u64,
+		latest_client_height_on_counterparty: u64,
+	) -> bool {
+		self.inner.is_update_required(latest_height, latest_client_height_on_counterparty)
+	}
+
+	async fn initialize_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		self.inner.initialize_client_state().await
+	}
+
+	async fn query_client_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ClientId, Self::Error> {
+		self.inner.query_client_id_from_tx_hash(tx_id).await
+	}
+
+	async fn query_tx_block_events(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<Vec<IbcEvent>, Self::Error> {
+		self.inner.query_tx_block_events(tx_id).await
+	}
+}
+
+#[async_trait]
+impl<C: Chain> MisbehaviourHandler for FaultyChain<C> {
+	async fn check_for_misbehaviour<T: Chain>(
+		&self,
+		counterparty: &T,
+		client_message: AnyClientMessage,
+	) -> Result<(), anyhow::Error> {
+		self.inner.check_for_misbehaviour(counterparty, client_message).await
+	}
+}
+
+impl<C: KeyProvider> KeyProvider for FaultyChain<C> {
+	fn account_id(&self) -> Signer {
+		self.inner.account_id()
+	}
+}
+
+#[async_trait]
+impl<C> Chain for FaultyChain<C>
+where
+	C: Chain,
+	C::FinalityEvent: Send + Sync,
+	C::TransactionId: Send + Sync,
+{
+	fn name(&self) -> &str {
+		self.inner.name()
+	}
+
+	fn block_max_weight(&self) -> u64 {
+		self.inner.block_max_weight()
+	}
+
+	async fn estimate_weight(&self, msg: Vec<Any>) -> Result<u64, Self::Error> {
+		self.inner.estimate_weight(msg).await
+	}
+
+	async fn finality_notifications(
+		&self,
+	) -> Pin<Box<dyn Stream<Item = Self::FinalityEvent> + Send + Sync>> {
+		self.inner.finality_notifications().await
+	}
+
+	fn subscription_reconnects(&self) -> u64 {
+		self.inner.subscription_reconnects()
+	}
+
+	/// Submits `messages` to the wrapped chain with the configured faults. Fails like a lost
+	/// transaction would if every message was dropped.
+	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Self::Error> {
+		let delay = self.faults().submission_delay;
+		if delay > 0 {
+			log::info!(
+				target: "hyperspace",
+				"Delaying submission to {} by {delay} blocks",
+				self.name()
+			);
+			self.wait_for_blocks(delay).await?;
+		}
+		let messages = self.tamper(messages);
+		if messages.is_empty() {
+			return Err(format!("Every message submitted to {} was dropped", self.name()).into())
+		}
+		self.inner.submit(messages).await
+	}
+
+	fn submit_error_kind(&self, error: &Self::Error) -> SubmitErrorKind {
+		self.inner.submit_error_kind(error)
+	}
+
+	async fn query_client_message(
+		&self,
+		update: UpdateClient,
+	) -> Result<AnyClientMessage, Self::Error> {
+		self.inner.query_client_message(update).await
+	}
+
+	fn has_secondary_rpc(&self) -> bool {
+		self.inner.has_secondary_rpc()
+	}
+
+	async fn query_conflicting_finality(
+		&self,
+		finality_event: Self::FinalityEvent,
+	) -> Result<Option<AnyClientMessage>, anyhow::Error> {
+		self.inner.query_conflicting_finality(finality_event).await
+	}
+
+	fn encode_client_message(&self, client_message: AnyClientMessage) -> Any {
+		self.inner.encode_client_message(client_message)
+	}
+
+	fn supports_client_update_batches(&self) -> bool {
+		self.inner.supports_client_update_batches()
+	}
+}
+
+#[async_trait]
+impl<C> TestProvider for FaultyChain<C>
+where
+	C: TestProvider,
+	C::FinalityEvent: Send + Sync,
+	C::TransactionId: Send + Sync,
+{
+	async fn send_transfer(&self, params: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
+		self.inner.send_transfer(params).await
+	}
+
+	async fn send_ordered_packet(
+		&self,
+		channel_id: ChannelId,
+		timeout: Timeout,
+	) -> Result<(), Self::Error> {
+		self.inner.send_ordered_packet(channel_id, timeout).await
+	}
+
+	async fn send_pings(
+		&self,
+		channel_id: ChannelId,
+		count: u32,
+		payload_size: u32,
+		timeout: Timeout,
+	) -> Result<(), Self::Error> {
+		self.inner.send_pings(channel_id, count, payload_size, timeout).await
+	}
+
+	async fn query_ping_stats(
+		&self,
+		channel_id: ChannelId,
+	) -> Result<Option<pallet_ibc_ping::PingStats>, Self::Error> {
+		self.inner.query_ping_stats(channel_id).await
+	}
+
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		self.inner.subscribe_blocks().await
+	}
+
+	fn set_channel_whitelist(&mut self, channel_whitelist: Vec<(ChannelId, PortId)>) {
+		self.inner.set_channel_whitelist(channel_whitelist)
+	}
+}
+
+/// Sets up a transfer channel between the chains, without faults, and restarts the relayer with
+/// only that channel whitelisted. Returns the relayer task and the channel ids on either chain.
+async fn setup_channel<A, B>(
+	chain_a: &mut FaultyChain<A>,
+	chain_b: &mut FaultyChain<B>,
+) -> (JoinHandle<()>, ChannelId, ChannelId)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::TransactionId: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::TransactionId: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	chain_a.set_faults(FaultConfig::default());
+	chain_b.set_faults(FaultConfig::default());
+	let (handle, channel_id, channel_b, _connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+	chain_a.set_channel_whitelist(vec![(channel_id, PortId::transfer())]);
+	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
+	let client_a_clone = chain_a.clone();
+	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
+	let handle = tokio::task::spawn(async move {
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
+	});
+	(handle, channel_id, channel_b)
+}
+
+/// Delay the deliveries to chain B past the timeout height of a packet and assert the packet
+/// times out on chain A.
+pub async fn ibc_messaging_packet_timeout_with_delayed_delivery<A, B>(
+	chain_a: &mut FaultyChain<A>,
+	chain_b: &mut FaultyChain<B>,
+) where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::TransactionId: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::TransactionId: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, _channel_b) = setup_channel(chain_a, chain_b).await;
+	chain_b.set_faults(FaultConfig { submission_delay: 30, ..Default::default() });
+
+	send_transfer(
+		chain_a,
+		chain_b,
+		channel_id,
+		Some(Timeout::Offset { timestamp: Some(60 * 60), height: Some(10) }),
+	)
+	.await;
+
+	assert_timeout_packet(chain_a).await;
+	log::info!(target: "hyperspace", "🚀🚀 Timeout packet successfully processed for delayed delivery");
+	chain_b.set_faults(FaultConfig::default());
+	handle.abort()
+}
+
+/// Submit every packet message to chain B twice and assert the duplicate receive is a no-op: the
+/// packet is acknowledged once and only once.
+pub async fn ibc_messaging_duplicate_recv_is_noop<A, B>(
+	chain_a: &mut FaultyChain<A>,
+	chain_b: &mut FaultyChain<B>,
+) where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::TransactionId: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::TransactionId: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b) = setup_channel(chain_a, chain_b).await;
+	chain_b.set_faults(FaultConfig { duplicate: true, ..Default::default() });
+
+	let write_acks = chain_b.ibc_events().await.filter(move |ev| {
+		future::ready(matches!(
+			ev,
+			IbcEvent::WriteAcknowledgement(write_ack)
+				if write_ack.packet.destination_channel == channel_b
+		))
+	});
+	let (previous_balance, ..) = send_transfer(chain_a, chain_b, channel_id, None).await;
+	assert_send_transfer(chain_a, previous_balance, 20 * 60).await;
+	assert!(chain_b.duplicated_messages() > 0, "No message was duplicated");
+
+	// give a second acknowledgement the time to show up
+	let deadline = tokio::time::sleep(chain_b.expected_block_time() * 10);
+	let write_acks = write_acks.take_until(deadline).collect::<Vec<_>>().await;
+	assert_eq!(write_acks.len(), 1, "Packet was acknowledged {} times", write_acks.len());
+	log::info!(target: "hyperspace", "🚀🚀 Duplicate receive packet was a no-op");
+	chain_b.set_faults(FaultConfig::default());
+	handle.abort()
+}
+
+/// Serve stale proofs from chain A so that the first deliveries of a packet fail to verify on
+/// chain B, then assert the packet is delivered once the proofs are served at the right height
+/// again.
+pub async fn ibc_messaging_stale_proof_is_retried<A, B>(
+	chain_a: &mut FaultyChain<A>,
+	chain_b: &mut FaultyChain<B>,
+) where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::TransactionId: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::TransactionId: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, _channel_b) = setup_channel(chain_a, chain_b).await;
+	chain_a.set_faults(FaultConfig { stale_proof_offset: 5, ..Default::default() });
+
+	let (previous_balance, ..) = send_transfer(chain_a, chain_b, channel_id, None).await;
+	let served = async {
+		while chain_a.stale_proofs() == 0 {
+			tokio::time::sleep(Duration::from_secs(5)).await;
+		}
+	};
+	timeout_future(served, 10 * 60, format!("No stale proof was served by {}", chain_a.name()))
+		.await;
+
+	chain_a.set_faults(FaultConfig::default());
+	assert_send_transfer(chain_a, previous_balance, 20 * 60).await;
+	log::info!(target: "hyperspace", "🚀🚀 Packet delivered after its stale proof was refreshed");
+	handle.abort()
+}
@@ -15,30 +15,47 @@
 #![allow(clippy::all)]
 
 use crate::utils::{assert_timeout_packet, parse_amount};
-use futures::{future, StreamExt};
-use hyperspace_core::send_packet_relay::set_relay_status;
+use futures::{future, Stream, StreamExt};
+use hyperspace_core::{
+	filter::{ChainFilter, ChannelRule, FilterAction, PacketFilter},
+	handshake,
+	keep_alive::KeepAliveConfig,
+	packets::clear::{relay_packet, PacketMessageKind},
+	pipeline::{query_height_proving_packets, PacketWork},
+	query::{
+		query_channels, query_clients, query_connections, query_pending_packets, TimeoutStatus,
+	},
+	reload::{RuntimeConfig, SharedRuntimeConfig},
+	send_packet_relay::{set_relay_status, set_send_packets_query_delay},
+	store::{MemoryStore, RelayerStore},
+};
 use hyperspace_primitives::{
+	query_undelivered_sequences,
 	utils::{create_channel, create_connection, timeout_future},
 	TestProvider,
 };
 use ibc::{
 	applications::transfer::{msgs::transfer::MsgTransfer, Amount, PrefixedCoin, VERSION},
 	core::{
+		ics02_client::client_state::ClientState as ClientStateT,
 		ics04_channel::{
 			channel::{ChannelEnd, Order, State},
 			msgs::chan_close_init::MsgChannelCloseInit,
+			packet::Sequence,
 		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 	},
 	events::IbcEvent,
 	tx_msg::Msg,
+	Height,
 };
 use ibc_proto::google::protobuf::Any;
-use pallet_ibc::Timeout;
-use std::{str::FromStr, time::Duration};
+use pallet_ibc::{light_clients::AnyClientState, Timeout};
+use std::{str::FromStr, sync::Arc, time::Duration};
 use tendermint_proto::Protobuf;
 use tokio::task::JoinHandle;
 
+pub mod fault_injection;
 pub mod misbehaviour;
 pub mod ordered_channels;
 mod utils;
@@ -61,10 +78,20 @@ where
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	// Start relayer loop
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	// check if an open transfer channel exists
 	let (latest_height, ..) = chain_a.latest_height_and_timestamp().await.unwrap();
@@ -194,10 +221,12 @@ where
 		source_port: PortId::transfer(),
 		source_channel: channel_id,
 		token: coin,
+		tokens: vec![],
 		sender: chain_a.account_id(),
 		receiver: chain_b.account_id(),
 		timeout_height,
 		timeout_timestamp,
+		memo: String::new(),
 	};
 	chain_a.send_transfer(msg.clone()).await.expect("Failed to send transfer: ");
 	(amount, msg)
@@ -475,10 +504,20 @@ pub async fn ibc_messaging_packet_height_timeout_with_connection_delay<A, B>(
 	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	send_packet_and_assert_height_timeout(chain_a, chain_b, channel_id).await;
 	handle.abort()
@@ -504,10 +543,20 @@ pub async fn ibc_messaging_packet_timestamp_timeout_with_connection_delay<A, B>(
 	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	send_packet_and_assert_timestamp_timeout(chain_a, chain_b, channel_id).await;
 	handle.abort()
@@ -532,12 +581,698 @@ where
 	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
+	});
+	send_packet_with_connection_delay(chain_a, chain_b, channel_id).await;
+	handle.abort()
+}
+
+/// Stop the relayer between sending a packet and its delivery, and assert the restarted relayer
+/// still delivers it.
+pub async fn ibc_messaging_across_relayer_restart<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, _connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+	// Set channel whitelist and restart relayer loop, keeping its progress across restarts
+	chain_a.set_channel_whitelist(vec![(channel_id, PortId::transfer())]);
+	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
+	let store: Arc<dyn RelayerStore> = Arc::new(MemoryStore::default());
+	let start_relayer = || {
+		let client_a_clone = chain_a.clone();
+		let client_b_clone = chain_b.clone();
+		let store = store.clone();
+		tokio::task::spawn(async move {
+			hyperspace_core::relay(
+				client_a_clone,
+				client_b_clone,
+				None,
+				None,
+				None,
+				store,
+				None,
+				Default::default(),
+			)
 			.await
 			.unwrap()
+		})
+	};
+	let handle = start_relayer();
+
+	// wait for the relayer to record its progress on the channel before stopping it.
+	let chain_name = chain_a.name().to_string();
+	let recorded = async {
+		let port_id = PortId::transfer();
+		while store.processed_height(&chain_name, channel_id, &port_id).unwrap().is_none() {
+			tokio::time::sleep(Duration::from_secs(5)).await;
+		}
+	};
+	timeout_future(recorded, 5 * 60, format!("Relayer didn't record progress on {chain_name}"))
+		.await;
+	handle.abort();
+
+	let (previous_balance, ..) = send_transfer(chain_a, chain_b, channel_id, None).await;
+	// let the packet get finalized while the relayer is down
+	tokio::time::sleep(Duration::from_secs(60)).await;
+
+	let handle = start_relayer();
+	assert_send_transfer(chain_a, previous_balance, 20 * 60).await;
+	handle.abort()
+}
+
+/// Deny a second channel on chain A in the relayer's packet filter and assert packets sent on the
+/// allowed channel are relayed while the ones sent on the denied channel aren't.
+pub async fn ibc_messaging_with_packet_filter<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	let (denied_channel_id, denied_channel_b) = create_channel(
+		chain_a,
+		chain_b,
+		connection_id,
+		PortId::transfer(),
+		VERSION.to_string(),
+		Order::Unordered,
+	)
+	.await
+	.unwrap();
+	handle.abort();
+	// Whitelist both channels, deny the second one and restart relayer loop
+	chain_a.set_channel_whitelist(vec![
+		(channel_id, PortId::transfer()),
+		(denied_channel_id, PortId::transfer()),
+	]);
+	chain_b.set_channel_whitelist(vec![
+		(channel_b, PortId::transfer()),
+		(denied_channel_b, PortId::transfer()),
+	]);
+	let filter = PacketFilter {
+		chain_a: ChainFilter {
+			channels: vec![ChannelRule {
+				port_id: PortId::transfer(),
+				channel_id: denied_channel_id,
+				action: FilterAction::Deny,
+			}],
+			..Default::default()
+		},
+		chain_b: Default::default(),
+	};
+	let client_a_clone = chain_a.clone();
+	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
+	let handle = tokio::task::spawn(async move {
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			filter.into(),
+		)
+		.await
+		.unwrap()
 	});
-	send_packet_with_connection_delay(chain_a, chain_b, channel_id).await;
+
+	send_transfer(chain_a, chain_b, denied_channel_id, None).await;
+	let (previous_balance, ..) = send_transfer(chain_a, chain_b, channel_id, None).await;
+	assert_send_transfer(chain_a, previous_balance, 20 * 60).await;
+
+	// by now the packet on the denied channel would have been received as well
+	let (height_a, ..) = chain_a.latest_height_and_timestamp().await.unwrap();
+	let (height_b, ..) = chain_b.latest_height_and_timestamp().await.unwrap();
+	let undelivered = query_undelivered_sequences(
+		height_a,
+		height_b,
+		denied_channel_id,
+		PortId::transfer(),
+		&*chain_a,
+		&*chain_b,
+	)
+	.await
+	.unwrap();
+	assert_eq!(undelivered.len(), 1, "Packet on denied channel {denied_channel_id} was relayed");
+	handle.abort()
+}
+
+/// Sequences of the transfer packets sent on `channel_id` on chain A that chain B hasn't received.
+async fn undelivered_transfers<A, B>(chain_a: &A, chain_b: &B, channel_id: ChannelId) -> Vec<u64>
+where
+	A: TestProvider,
+	B: TestProvider,
+{
+	let (height_a, ..) = chain_a.latest_height_and_timestamp().await.unwrap();
+	let (height_b, ..) = chain_b.latest_height_and_timestamp().await.unwrap();
+	let port_id = PortId::transfer();
+	query_undelivered_sequences(height_a, height_b, channel_id, port_id, chain_a, chain_b)
+		.await
+		.unwrap()
+}
+
+/// Start the relayer with only one of two channels whitelisted, then whitelist the second one
+/// through its runtime config and assert the packet sent on it gets relayed without a restart.
+pub async fn ibc_messaging_with_reloaded_channel_whitelist<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	let (new_channel_id, new_channel_b) = create_channel(
+		chain_a,
+		chain_b,
+		connection_id,
+		PortId::transfer(),
+		VERSION.to_string(),
+		Order::Unordered,
+	)
+	.await
+	.unwrap();
+	handle.abort();
+	let mut runtime_config = RuntimeConfig::default();
+	runtime_config.chain_a.channel_whitelist = Some(vec![(channel_id, PortId::transfer())]);
+	runtime_config.chain_b.channel_whitelist = Some(vec![(channel_b, PortId::transfer())]);
+	let (sender, shared) = SharedRuntimeConfig::new(runtime_config.clone());
+	let client_a_clone = chain_a.clone();
+	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
+	let handle = tokio::task::spawn(async move {
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			shared,
+		)
+		.await
+		.unwrap()
+	});
+
+	send_transfer(chain_a, chain_b, new_channel_id, None).await;
+	let (previous_balance, ..) = send_transfer(chain_a, chain_b, channel_id, None).await;
+	assert_send_transfer(chain_a, previous_balance, 20 * 60).await;
+	let undelivered = undelivered_transfers(&*chain_a, &*chain_b, new_channel_id).await;
+	assert_eq!(undelivered.len(), 1, "Packet on {new_channel_id} was relayed too early");
+
+	// whitelist the second channel on both chains while the relayer keeps running
+	for (whitelist, channel) in [
+		(&mut runtime_config.chain_a.channel_whitelist, new_channel_id),
+		(&mut runtime_config.chain_b.channel_whitelist, new_channel_b),
+	] {
+		whitelist.as_mut().unwrap().push((channel, PortId::transfer()));
+	}
+	sender.send_replace(runtime_config);
+	let delivered = async {
+		while !undelivered_transfers(&*chain_a, &*chain_b, new_channel_id).await.is_empty() {
+			tokio::time::sleep(Duration::from_secs(5)).await;
+		}
+	};
+	timeout_future(delivered, 20 * 60, format!("Packet on {new_channel_id} wasn't relayed")).await;
+	handle.abort()
+}
+
+/// Run the relayer with packet relaying disabled and a keep-alive threshold covering the whole
+/// trusting period, and assert chain A's client on chain B still gets updated.
+pub async fn client_update_near_expiry_without_packet_relay<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, ..) = setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+	let client_height = || async {
+		let (height, ..) = chain_b.latest_height_and_timestamp().await.unwrap();
+		let response = chain_b.query_client_state(height, chain_a.client_id()).await.unwrap();
+		AnyClientState::try_from(response.client_state.unwrap()).unwrap().latest_height()
+	};
+	set_relay_status(false);
+	let initial_height = client_height().await;
+
+	let client_a_clone = chain_a.clone();
+	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
+	let keep_alive = KeepAliveConfig { interval: Duration::from_secs(10), threshold: 1.0 };
+	let handle = tokio::task::spawn(async move {
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			Some(keep_alive),
+			Default::default(),
+		)
+		.await
+		.unwrap()
+	});
+
+	let updated = async {
+		while client_height().await <= initial_height {
+			tokio::time::sleep(Duration::from_secs(5)).await;
+		}
+	};
+	timeout_future(
+		updated,
+		5 * 60,
+		format!("Client for {} wasn't updated on {}", chain_a.name(), chain_b.name()),
+	)
+	.await;
+	set_relay_status(true);
+	handle.abort()
+}
+
+/// Assert the `query` command's queries list the clients, connection and channel set up between
+/// both chains, and an unrelayed packet sent with the relayer down. Also checks the next
+/// acknowledgement sequence of the channel is queried with its proof.
+pub async fn query_ibc_state<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+
+	let clients = query_clients(&*chain_a).await.unwrap();
+	assert!(clients.iter().any(|client| client.client_id == chain_a.client_id().to_string()));
+	let connections = query_connections(&*chain_a).await.unwrap();
+	let connection = connections
+		.iter()
+		.find(|connection| connection.connection_id == connection_id.to_string())
+		.expect("Connection not listed");
+	assert_eq!(connection.state, "OPEN");
+	let channels = query_channels(&*chain_a).await.unwrap();
+	let channel = channels
+		.iter()
+		.find(|channel| channel.channel_id == channel_id.to_string())
+		.expect("Channel not listed");
+	assert_eq!(channel.counterparty_channel_id, Some(channel_b.to_string()));
+	let (latest_height, ..) = chain_a.latest_height_and_timestamp().await.unwrap();
+	let next_sequence_ack = chain_a
+		.query_next_sequence_ack(latest_height, &PortId::transfer(), &channel_id)
+		.await
+		.unwrap();
+	// acknowledgements on unordered channels don't advance the sequence.
+	assert_eq!(next_sequence_ack.next_sequence_ack, 1);
+	assert!(!next_sequence_ack.proof.is_empty());
+
+	send_transfer(chain_a, chain_b, channel_id, None).await;
+	let listed = async {
+		loop {
+			let pending =
+				query_pending_packets(&*chain_a, &*chain_b, channel_id, PortId::transfer())
+					.await
+					.unwrap();
+			if !pending.undelivered.is_empty() {
+				break pending
+			}
+			tokio::time::sleep(Duration::from_secs(5)).await;
+		}
+	};
+	let pending =
+		timeout_future(listed, 5 * 60, format!("Packet wasn't listed on {}", chain_a.name())).await;
+	assert!(pending.undelivered.iter().all(|packet| packet.status == TimeoutStatus::Pending));
+}
+
+/// Send a transfer and assert its `SendPacket` event is received from `events`, a stream of the
+/// ibc events of chain A opened beforehand.
+pub async fn send_packet_event_is_received<A, B>(
+	chain_a: &mut A,
+	chain_b: &mut B,
+	events: impl Stream<Item = IbcEvent> + Send,
+) where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, _channel_b, _connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+
+	send_transfer(chain_a, chain_b, channel_id, None).await;
+	let send_packet = events
+		.filter(|event| {
+			future::ready(matches!(
+				event,
+				IbcEvent::SendPacket(send) if send.packet.source_channel == channel_id
+			))
+		})
+		.take(1)
+		.collect::<Vec<_>>();
+	let reason = format!("SendPacket wasn't received from {}", chain_a.name());
+	timeout_future(send_packet, 5 * 60, reason).await;
+}
+
+/// Interrupt a connection handshake once chain B has sent OpenTry and assert running it again
+/// completes the same connection, rather than starting another one.
+pub async fn connection_handshake_resumes_after_interruption<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	// a delay period no other test uses, so no other connection matches the handshake.
+	let delay_period = Duration::from_secs(60 * 3);
+	let start_relayer = || {
+		let client_a_clone = chain_a.clone();
+		let client_b_clone = chain_b.clone();
+		tokio::task::spawn(async move {
+			hyperspace_core::relay(
+				client_a_clone,
+				client_b_clone,
+				None,
+				None,
+				None,
+				Arc::new(MemoryStore::default()),
+				None,
+				Default::default(),
+			)
+			.await
+			.unwrap()
+		})
+	};
+
+	let open_try = chain_b
+		.ibc_events()
+		.await
+		.skip_while(|ev| future::ready(!matches!(ev, IbcEvent::OpenTryConnection(_))))
+		.take(1)
+		.collect::<Vec<_>>();
+	let relayer = start_relayer();
+	let handshake = {
+		let (mut chain_a, mut chain_b) = (chain_a.clone(), chain_b.clone());
+		tokio::task::spawn(async move {
+			handshake::create_connection(&mut chain_a, &mut chain_b, delay_period).await.unwrap()
+		})
+	};
+	let mut events =
+		timeout_future(open_try, 15 * 60, format!("Didn't see OpenTry on {}", chain_b.name()))
+			.await;
+	// kill the handshake before the relayer gets to relay OpenTry back to chain A
+	handshake.abort();
+	relayer.abort();
+	let connection_id = match events.pop() {
+		Some(IbcEvent::OpenTryConnection(open_try)) =>
+			open_try.attributes().counterparty_connection_id.clone().unwrap(),
+		got => panic!("Last event should be OpenTryConnection: {got:?}"),
+	};
+	log::info!(target: "hyperspace", "Interrupted the handshake of connection {connection_id}");
+
+	let relayer = start_relayer();
+	let (connection_id_a, _connection_id_b) =
+		handshake::create_connection(chain_a, chain_b, delay_period).await.unwrap();
+	assert_eq!(connection_id_a, connection_id);
+	log::info!(target: "hyperspace", "🚀🚀 Connection handshake resumed after interruption");
+	relayer.abort()
+}
+
+/// Send a packet with the relayer down and assert it gets cleared by relaying its receipt and then
+/// its acknowledgement on their own, as the `relay-packet` command does.
+pub async fn ibc_messaging_relay_stuck_packet<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, ..) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+	send_transfer(chain_a, chain_b, channel_id, None).await;
+
+	let stuck = async {
+		loop {
+			let (height_a, ..) = chain_a.latest_height_and_timestamp().await.unwrap();
+			let (height_b, ..) = chain_b.latest_height_and_timestamp().await.unwrap();
+			let undelivered = query_undelivered_sequences(
+				height_a,
+				height_b,
+				channel_id,
+				PortId::transfer(),
+				&*chain_a,
+				&*chain_b,
+			)
+			.await
+			.unwrap();
+			if let Some(sequence) = undelivered.into_iter().max() {
+				break sequence
+			}
+			tokio::time::sleep(Duration::from_secs(5)).await;
+		}
+	};
+	let sequence =
+		timeout_future(stuck, 5 * 60, format!("Packet wasn't sent on {}", chain_a.name())).await;
+
+	let events = relay_packet(
+		chain_a,
+		chain_b,
+		channel_id,
+		PortId::transfer(),
+		sequence,
+		PacketMessageKind::Recv,
+	)
+	.await
+	.unwrap();
+	assert!(events.iter().any(|event| matches!(event, IbcEvent::ReceivePacket(_))));
+	let relayed_again = relay_packet(
+		chain_a,
+		chain_b,
+		channel_id,
+		PortId::transfer(),
+		sequence,
+		PacketMessageKind::Recv,
+	)
+	.await;
+	assert!(relayed_again.is_err(), "Packet {sequence} was received twice");
+
+	// the acknowledgement can only be relayed once the receipt is finalized on chain B.
+	let acknowledged = async {
+		loop {
+			match relay_packet(
+				chain_b,
+				chain_a,
+				channel_b,
+				PortId::transfer(),
+				sequence,
+				PacketMessageKind::Ack,
+			)
+			.await
+			{
+				Ok(events) => break events,
+				Err(e) => log::info!("Acknowledgement not relayed yet: {e:?}"),
+			}
+			tokio::time::sleep(Duration::from_secs(10)).await;
+		}
+	};
+	let events = timeout_future(
+		acknowledged,
+		5 * 60,
+		format!("Acknowledgement wasn't relayed to {}", chain_a.name()),
+	)
+	.await;
+	assert!(events.iter().any(|event| matches!(event, IbcEvent::AcknowledgePacket(_))));
+}
+
+/// Send two packets with the relayer down, relay the first one and assert the second one is
+/// relayed at a consensus height of chain A's client on chain B that already proves it, without
+/// another client update.
+pub async fn ibc_messaging_relay_packet_on_updated_client<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, ..) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+	send_transfer(chain_a, chain_b, channel_id, None).await;
+	send_transfer(chain_a, chain_b, channel_id, None).await;
+
+	let sent = async {
+		loop {
+			let (height_a, ..) = chain_a.latest_height_and_timestamp().await.unwrap();
+			let (height_b, ..) = chain_b.latest_height_and_timestamp().await.unwrap();
+			let mut undelivered = query_undelivered_sequences(
+				height_a,
+				height_b,
+				channel_id,
+				PortId::transfer(),
+				&*chain_a,
+				&*chain_b,
+			)
+			.await
+			.unwrap();
+			if undelivered.len() >= 2 {
+				undelivered.sort();
+				break (undelivered[0], undelivered[1])
+			}
+			tokio::time::sleep(Duration::from_secs(5)).await;
+		}
+	};
+	let (first, second) =
+		timeout_future(sent, 5 * 60, format!("Packets weren't sent on {}", chain_a.name())).await;
+
+	// the client update needed for the first packet also covers the second one.
+	let events = relay_packet(
+		chain_a,
+		chain_b,
+		channel_id,
+		PortId::transfer(),
+		first,
+		PacketMessageKind::Recv,
+	)
+	.await
+	.unwrap();
+	assert!(events.iter().any(|event| matches!(event, IbcEvent::ReceivePacket(_))));
+	// relaying needs the chains mutably, so the client state is queried through clones.
+	let (client_a, client_b) = (chain_a.clone(), chain_b.clone());
+	let client_height = || async {
+		let (height, ..) = client_b.latest_height_and_timestamp().await.unwrap();
+		let response = client_b.query_client_state(height, client_a.client_id()).await.unwrap();
+		AnyClientState::try_from(response.client_state.unwrap()).unwrap().latest_height()
+	};
+	let updated_height = client_height().await;
+
+	let packet_info = chain_a
+		.query_send_packets(channel_id, PortId::transfer(), vec![second])
+		.await
+		.unwrap()
+		.pop()
+		.unwrap();
+	let work = PacketWork {
+		channel_id,
+		port_id: PortId::transfer(),
+		sequence: Sequence::from(second),
+		height: Height::new(updated_height.revision_number, packet_info.height),
+	};
+	let proving_height = query_height_proving_packets(&*chain_a, &*chain_b, &[work]).await;
+	assert_eq!(proving_height, Some(updated_height), "Packet {second} needs a client update");
+
+	let events = relay_packet(
+		chain_a,
+		chain_b,
+		channel_id,
+		PortId::transfer(),
+		second,
+		PacketMessageKind::Recv,
+	)
+	.await
+	.unwrap();
+	assert!(events.iter().any(|event| matches!(event, IbcEvent::ReceivePacket(_))));
+	assert_eq!(
+		client_height().await,
+		updated_height,
+		"Client of {} was updated on {} to relay packet {second}",
+		chain_a.name(),
+		chain_b.name()
+	);
+}
+
+/// Hold back send packet queries on chain A and assert packets from chain B are still received on
+/// chain A, so a slow packet query doesn't hold back relaying in the other direction.
+pub async fn ibc_messaging_with_delayed_packet_query<A, B>(chain_a: &mut A, chain_b: &mut B)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, _connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::from_secs(0)).await;
+	handle.abort();
+	// Set channel whitelist, delay chain A's packet queries and restart relayer loop
+	chain_a.set_channel_whitelist(vec![(channel_id, PortId::transfer())]);
+	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
+	set_send_packets_query_delay(Some((chain_a.name().to_string(), Duration::from_secs(60 * 10))));
+	let client_a_clone = chain_a.clone();
+	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
+	let handle = tokio::task::spawn(async move {
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
+	});
+
+	let future = chain_a
+		.ibc_events()
+		.await
+		.skip_while(|ev| future::ready(!matches!(ev, IbcEvent::ReceivePacket(_))))
+		.take(1)
+		.collect::<Vec<_>>();
+	let (previous_balance, ..) = send_transfer(chain_b, chain_a, channel_b, None).await;
+	// well before chain A's packet query returns
+	timeout_future(future, 5 * 60, format!("Didn't see ReceivePacket on {}", chain_a.name()))
+		.await;
+
+	// the acknowledgement is relayed by chain A's packet task, once its query returns.
+	set_send_packets_query_delay(None);
+	assert_send_transfer(chain_b, previous_balance, 20 * 60).await;
 	handle.abort()
 }
 
@@ -559,10 +1294,20 @@ where
 	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	send_channel_close_init_and_assert_channel_close_confirm(chain_a, chain_b, channel_id).await;
 	handle.abort()
@@ -586,10 +1331,20 @@ where
 	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())]);
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	send_packet_and_assert_timeout_on_channel_close(chain_a, chain_b, channel_id).await;
 	handle.abort()
@@ -14,20 +14,26 @@
 
 use crate::{assert_timeout_packet, timeout_future, StreamExt};
 use futures::future;
-use hyperspace_core::send_packet_relay::set_relay_status;
+use hyperspace_core::{send_packet_relay::set_relay_status, store::MemoryStore};
 use hyperspace_primitives::{
 	utils::{create_channel, create_connection},
 	TestProvider,
 };
 use ibc::{
 	core::{
-		ics04_channel::channel::{ChannelEnd, Order, State},
+		ics04_channel::{
+			channel::{ChannelEnd, Order, State},
+			msgs::chan_close_init::MsgChannelCloseInit,
+		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 	},
 	events::IbcEvent,
+	tx_msg::Msg,
 };
+use ibc_proto::google::protobuf::Any;
 use pallet_ibc::Timeout;
-use std::{str::FromStr, time::Duration};
+use std::{str::FromStr, sync::Arc, time::Duration};
+use tendermint_proto::Protobuf;
 use tokio::task::JoinHandle;
 
 /// This will set up a connection and an ordered channel in-between the two chains with the provided
@@ -50,10 +56,20 @@ where
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
 	// Start relayer loop
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	// check if an open ping channel exists
 	let (latest_height, ..) = chain_a.latest_height_and_timestamp().await.unwrap();
@@ -223,6 +239,56 @@ async fn send_ordered_packet_and_assert_timeout<A, B>(
 	log::info!(target: "hyperspace", "🚀🚀 Timeout packet successfully processed for ordered channel");
 }
 
+/// Send a packet on an ordered channel, close the channel on the counterparty before the packet
+/// is delivered and assert the packet is timed out on close
+async fn send_ordered_packet_and_assert_timeout_on_close<A, B>(
+	chain_a: &A,
+	chain_b: &B,
+	channel_id: ChannelId,
+	channel_b: ChannelId,
+	port_id: PortId,
+) where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	log::info!(target: "hyperspace", "Suspending send packet relay");
+	set_relay_status(false);
+
+	chain_a
+		.send_ordered_packet(
+			channel_id,
+			Timeout::Offset { height: Some(400), timestamp: Some(60 * 60) },
+		)
+		.await
+		.unwrap();
+
+	let future = chain_a
+		.ibc_events()
+		.await
+		.skip_while(|ev| future::ready(!matches!(ev, IbcEvent::TimeoutOnClosePacket(_))))
+		.take(1)
+		.collect::<Vec<_>>();
+
+	let msg = MsgChannelCloseInit { port_id, channel_id: channel_b, signer: chain_b.account_id() };
+	let msg = Any { type_url: msg.type_url(), value: msg.encode_vec() };
+	chain_b.submit(vec![msg]).await.unwrap();
+
+	log::info!(target: "hyperspace", "Waiting for packet to be timed out on close");
+	timeout_future(
+		future,
+		20 * 60,
+		format!("Didn't see TimeoutOnClose packet on {}", chain_a.name()),
+	)
+	.await;
+
+	set_relay_status(true);
+	log::info!(target: "hyperspace", "🚀🚀 Timeout on close packet successfully processed for ordered channel");
+}
+
 ///
 pub async fn ibc_messaging_ordered_packet_with_connection_delay<A, B>(
 	chain_a: &mut A,
@@ -251,10 +317,20 @@ pub async fn ibc_messaging_ordered_packet_with_connection_delay<A, B>(
 	chain_b.set_channel_whitelist(vec![(channel_b, port_id)]);
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	send_ordered_packet_and_assert_acknowledgement(chain_a, chain_b, channel_id).await;
 	handle.abort()
@@ -288,11 +364,166 @@ pub async fn ibc_messaging_ordered_packet_timeout<A, B>(
 	chain_b.set_channel_whitelist(vec![(channel_b, port_id)]);
 	let client_a_clone = chain_a.clone();
 	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
 	let handle = tokio::task::spawn(async move {
-		hyperspace_core::relay(client_a_clone, client_b_clone, None, None, None)
-			.await
-			.unwrap()
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
 	});
 	send_ordered_packet_and_assert_timeout(chain_a, chain_b, channel_id).await;
 	handle.abort()
 }
+
+///
+pub async fn ibc_messaging_ordered_packet_timeout_on_close<A, B>(
+	chain_a: &mut A,
+	chain_b: &mut B,
+	port_id: PortId,
+	version: String,
+) where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, _connection_id) = setup_connection_and_channel(
+		chain_a,
+		chain_b,
+		Duration::from_secs(60 * 2),
+		port_id.clone(),
+		version,
+	)
+	.await;
+	// Set channel whitelist and restart relayer loop
+	handle.abort();
+	chain_a.set_channel_whitelist(vec![(channel_id, port_id.clone())]);
+	chain_b.set_channel_whitelist(vec![(channel_b, port_id.clone())]);
+	let client_a_clone = chain_a.clone();
+	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
+	let handle = tokio::task::spawn(async move {
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
+	});
+	send_ordered_packet_and_assert_timeout_on_close(
+		chain_a,
+		chain_b,
+		channel_id,
+		channel_b,
+		port_id,
+	)
+	.await;
+	handle.abort()
+}
+
+/// Send a batch of pings on an ordered channel, wait for all of them to be acknowledged and report
+/// the round-trip latencies recorded by the ping pallet
+async fn send_pings_and_assert_latency<A, B>(chain_a: &A, channel_id: ChannelId)
+where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let count = 10;
+	let payload_size = 256;
+	let future = chain_a
+		.ibc_events()
+		.await
+		.filter(|ev| future::ready(matches!(ev, IbcEvent::AcknowledgePacket(_))))
+		.take(count as usize)
+		.collect::<Vec<_>>();
+
+	chain_a
+		.send_pings(
+			channel_id,
+			count,
+			payload_size,
+			Timeout::Offset { height: Some(200), timestamp: Some(60 * 60) },
+		)
+		.await
+		.unwrap();
+
+	timeout_future(
+		future,
+		20 * 60,
+		format!("Didn't see {count} Acknowledgement packets on {}", chain_a.name()),
+	)
+	.await;
+
+	let stats = chain_a.query_ping_stats(channel_id).await.unwrap().unwrap();
+	assert_eq!(stats.acknowledged, count as u64);
+	assert!(stats.min <= stats.avg && stats.avg <= stats.max);
+	log::info!(
+		target: "hyperspace",
+		"🚀🚀 {count} pings of {payload_size} bytes acknowledged, round trip latency in blocks: min {}, max {}, avg {}",
+		stats.min,
+		stats.max,
+		stats.avg
+	);
+}
+
+/// Measure the round-trip latency of a batch of pings over an ordered channel
+pub async fn ibc_messaging_ping_throughput<A, B>(
+	chain_a: &mut A,
+	chain_b: &mut B,
+	port_id: PortId,
+	version: String,
+) where
+	A: TestProvider,
+	A::FinalityEvent: Send + Sync,
+	A::Error: From<B::Error>,
+	B: TestProvider,
+	B::FinalityEvent: Send + Sync,
+	B::Error: From<A::Error>,
+{
+	let (handle, channel_id, channel_b, _connection_id) =
+		setup_connection_and_channel(chain_a, chain_b, Duration::ZERO, port_id.clone(), version)
+			.await;
+	// Set channel whitelist and restart relayer loop
+	handle.abort();
+	chain_a.set_channel_whitelist(vec![(channel_id, port_id.clone())]);
+	chain_b.set_channel_whitelist(vec![(channel_b, port_id)]);
+	let client_a_clone = chain_a.clone();
+	let client_b_clone = chain_b.clone();
+	let store = Arc::new(MemoryStore::default());
+	let handle = tokio::task::spawn(async move {
+		hyperspace_core::relay(
+			client_a_clone,
+			client_b_clone,
+			None,
+			None,
+			None,
+			store,
+			None,
+			Default::default(),
+		)
+		.await
+		.unwrap()
+	});
+	send_pings_and_assert_latency::<A, B>(chain_a, channel_id).await;
+	handle.abort()
+}
@@ -0,0 +1,126 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use futures::StreamExt;
+use hyperspace_core::logging;
+use hyperspace_cosmos::{signer::SignerConfig, CosmosClient, CosmosClientConfig};
+use hyperspace_primitives::{utils::create_clients, IbcProvider, TestProvider};
+use hyperspace_testsuite::{
+	ibc_messaging_with_connection_delay, query_ibc_state, send_packet_event_is_received,
+};
+
+/// Addresses of a chain's tendermint rpc and gRPC endpoints.
+#[derive(Debug, Clone)]
+pub struct Endpoints {
+	pub rpc: String,
+	pub grpc: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Args {
+	pub chain_a: Endpoints,
+	pub chain_b: Endpoints,
+	pub chain_id_a: String,
+	pub chain_id_b: String,
+	pub mnemonic: String,
+}
+
+impl Default for Args {
+	fn default() -> Self {
+		let host = std::env::var("COSMOS_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+
+		Args {
+			chain_a: Endpoints { rpc: format!("{host}:26657"), grpc: format!("{host}:9090") },
+			chain_b: Endpoints { rpc: format!("{host}:26557"), grpc: format!("{host}:9091") },
+			chain_id_a: "ibc-0".to_string(),
+			chain_id_b: "ibc-1".to_string(),
+			mnemonic: std::env::var("COSMOS_MNEMONIC")
+				.expect("COSMOS_MNEMONIC should hold a mnemonic funded on both chains"),
+		}
+	}
+}
+
+fn config(
+	name: &str,
+	endpoints: Endpoints,
+	chain_id: String,
+	mnemonic: String,
+) -> CosmosClientConfig {
+	CosmosClientConfig {
+		name: name.to_string(),
+		rpc_url: format!("http://{}", endpoints.rpc),
+		websocket_url: format!("ws://{}/websocket", endpoints.rpc),
+		grpc_url: format!("http://{}", endpoints.grpc),
+		chain_id,
+		client_id: None,
+		connection_id: None,
+		account_prefix: "cosmos".to_string(),
+		store_prefix: "ibc".to_string(),
+		signer: SignerConfig::Mnemonic { mnemonic, key_type: Default::default() },
+		fee_denom: "stake".to_string(),
+		gas_price: 0.025,
+		gas_multiplier: 1.3,
+		max_gas: 4_000_000,
+		channel_whitelist: vec![],
+	}
+}
+
+async fn setup_clients() -> (CosmosClient, CosmosClient) {
+	log::info!(target: "hyperspace", "=========================== Starting Test ===========================");
+	let args = Args::default();
+
+	let config_a = config("ibc-0", args.chain_a, args.chain_id_a, args.mnemonic.clone());
+	let config_b = config("ibc-1", args.chain_b, args.chain_id_b, args.mnemonic);
+	let mut chain_a = CosmosClient::new(config_a).await.unwrap();
+	let mut chain_b = CosmosClient::new(config_b).await.unwrap();
+
+	// Wait until both chains produce blocks
+	log::info!(target: "hyperspace", "Waiting for block production from the simd chains");
+	let _ = futures::join!(
+		chain_a.subscribe_blocks().await.take(2).collect::<Vec<_>>(),
+		chain_b.subscribe_blocks().await.take(2).collect::<Vec<_>>()
+	);
+	log::info!(target: "hyperspace", "Simd chains have started block production");
+
+	let clients_on_a = chain_a.query_clients().await.unwrap();
+	let clients_on_b = chain_b.query_clients().await.unwrap();
+
+	if !clients_on_a.is_empty() && !clients_on_b.is_empty() {
+		chain_a.set_client_id(clients_on_b[0].clone());
+		chain_b.set_client_id(clients_on_a[0].clone());
+		return (chain_a, chain_b)
+	}
+
+	let (client_a, client_b) = create_clients(&chain_a, &chain_b).await.unwrap();
+	chain_a.set_client_id(client_a);
+	chain_b.set_client_id(client_b);
+	(chain_a, chain_b)
+}
+
+#[tokio::test]
+#[ignore = "needs two simd chains, see the testsuite README"]
+async fn cosmos_to_cosmos_ibc_messaging_full_integration_test() {
+	logging::setup_logging();
+	let (mut chain_a, mut chain_b) = setup_clients().await;
+
+	// no timeouts + connection delay
+	ibc_messaging_with_connection_delay(&mut chain_a, &mut chain_b).await;
+
+	// state queries
+	query_ibc_state(&mut chain_a, &mut chain_b).await;
+
+	// events through the websocket subscription alone, without catching up by polling blocks
+	let events = chain_a.subscribe_ibc_events(false);
+	send_packet_event_is_received(&mut chain_a, &mut chain_b, events).await;
+}
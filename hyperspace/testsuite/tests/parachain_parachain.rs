@@ -17,16 +17,29 @@ use futures::StreamExt;
 use hyperspace_core::logging;
 use hyperspace_parachain::{
 	config, config::CustomExtrinsicParams, finality_protocol::FinalityProtocol, ParachainClient,
-	ParachainClientConfig,
+	ParachainClientConfig, DEFAULT_MAX_ANCESTRY_GAP,
 };
 use hyperspace_primitives::{utils::create_clients, IbcProvider};
 use hyperspace_testsuite::{
-	ibc_channel_close, ibc_messaging_packet_height_timeout_with_connection_delay,
+	client_update_near_expiry_without_packet_relay, connection_handshake_resumes_after_interruption,
+	fault_injection::{
+		ibc_messaging_duplicate_recv_is_noop, ibc_messaging_packet_timeout_with_delayed_delivery,
+		ibc_messaging_stale_proof_is_retried, FaultyChain,
+	},
+	ibc_channel_close,
+	ibc_messaging_packet_height_timeout_with_connection_delay,
 	ibc_messaging_packet_timeout_on_channel_close,
 	ibc_messaging_packet_timestamp_timeout_with_connection_delay,
-	ibc_messaging_with_connection_delay, misbehaviour::ibc_messaging_submit_misbehaviour,
+	ibc_messaging_across_relayer_restart, ibc_messaging_with_connection_delay,
+	ibc_messaging_with_delayed_packet_query, ibc_messaging_with_packet_filter,
+	ibc_messaging_relay_packet_on_updated_client, ibc_messaging_relay_stuck_packet,
+	ibc_messaging_with_reloaded_channel_whitelist,
+	misbehaviour::ibc_messaging_submit_misbehaviour,
+	ordered_channels::ibc_messaging_ping_throughput, query_ibc_state,
 };
+use ibc::core::ics24_host::identifier::PortId;
 use sp_runtime::generic::Era;
+use std::str::FromStr;
 use subxt::{
 	tx::{PolkadotExtrinsicParams, PolkadotExtrinsicParamsBuilder},
 	Error, OnlineClient,
@@ -107,6 +120,9 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		finality_protocol: FinalityProtocol::Grandpa,
 		private_key: "//Alice".to_string(),
 		key_type: "sr25519".to_string(),
+		trusted_rpc: true,
+		secondary_rpc: None,
+		max_ancestry_gap: DEFAULT_MAX_ANCESTRY_GAP,
 	};
 	let config_b = ParachainClientConfig {
 		name: format!("9188"),
@@ -122,6 +138,9 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		channel_whitelist: vec![],
 		finality_protocol: FinalityProtocol::Grandpa,
 		key_type: "sr25519".to_string(),
+		trusted_rpc: true,
+		secondary_rpc: None,
+		max_ancestry_gap: DEFAULT_MAX_ANCESTRY_GAP,
 	};
 
 	let mut chain_a = ParachainClient::<DefaultConfig>::new(config_a).await.unwrap();
@@ -174,6 +193,42 @@ async fn parachain_to_parachain_ibc_messaging_full_integration_test() {
 	// no timeouts + connection delay
 	ibc_messaging_with_connection_delay(&mut chain_a, &mut chain_b).await;
 
+	// packets keep flowing while the other direction's packet query is slow
+	ibc_messaging_with_delayed_packet_query(&mut chain_a, &mut chain_b).await;
+
+	// packets sent while the relayer is down get delivered once it's back
+	ibc_messaging_across_relayer_restart(&mut chain_a, &mut chain_b).await;
+
+	// packets on channels denied by the filter aren't relayed
+	ibc_messaging_with_packet_filter(&mut chain_a, &mut chain_b).await;
+
+	// a channel whitelisted while the relayer runs gets relayed without a restart
+	ibc_messaging_with_reloaded_channel_whitelist(&mut chain_a, &mut chain_b).await;
+
+	// clients are kept from expiring even when no packets are relayed
+	client_update_near_expiry_without_packet_relay(&mut chain_a, &mut chain_b).await;
+
+	// an interrupted connection handshake is resumed
+	connection_handshake_resumes_after_interruption(&mut chain_a, &mut chain_b).await;
+
+	// the ibc state of either chain can be inspected
+	query_ibc_state(&mut chain_a, &mut chain_b).await;
+
+	// stuck packets can be cleared one at a time
+	ibc_messaging_relay_stuck_packet(&mut chain_a, &mut chain_b).await;
+
+	// packets already covered by the client are relayed without updating it
+	ibc_messaging_relay_packet_on_updated_client(&mut chain_a, &mut chain_b).await;
+
+	// round-trip latency of a batch of pings over an ordered channel
+	ibc_messaging_ping_throughput(
+		&mut chain_a,
+		&mut chain_b,
+		PortId::from_str(pallet_ibc_ping::PORT_ID).unwrap(),
+		pallet_ibc_ping::VERSION.to_string(),
+	)
+	.await;
+
 	// timeouts + connection delay
 	ibc_messaging_packet_height_timeout_with_connection_delay(&mut chain_a, &mut chain_b).await;
 	ibc_messaging_packet_timestamp_timeout_with_connection_delay(&mut chain_a, &mut chain_b).await;
@@ -182,6 +237,13 @@ async fn parachain_to_parachain_ibc_messaging_full_integration_test() {
 	ibc_messaging_packet_timeout_on_channel_close(&mut chain_a, &mut chain_b).await;
 	ibc_channel_close(&mut chain_a, &mut chain_b).await;
 
+	// faults injected in deliveries and proofs
+	let mut faulty_a = FaultyChain::new(chain_a.clone());
+	let mut faulty_b = FaultyChain::new(chain_b.clone());
+	ibc_messaging_packet_timeout_with_delayed_delivery(&mut faulty_a, &mut faulty_b).await;
+	ibc_messaging_duplicate_recv_is_noop(&mut faulty_a, &mut faulty_b).await;
+	ibc_messaging_stale_proof_is_retried(&mut faulty_a, &mut faulty_b).await;
+
 	// misbehaviour
 	ibc_messaging_submit_misbehaviour(&mut chain_a, &mut chain_b).await;
 }
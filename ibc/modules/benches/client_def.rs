@@ -0,0 +1,116 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Native benchmarks of the light client paths exercised by `MsgUpdateClient` and by the proof
+//! carrying channel and packet messages, run them via
+//! ```bash
+//! cargo bench -p ibc --features=mocks
+//! ```
+//! The mock client measures the overhead of the handlers themselves, the verification cost of
+//! the grandpa, beefy and tendermint clients is measured by the pallet-ibc runtime benchmarks.
+
+use core::str::FromStr;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use ibc::{
+	core::{
+		ics02_client::{
+			client_def::ClientDef,
+			context::ClientKeeper,
+			handler::dispatch,
+			msgs::{update_client::MsgUpdateAnyClient, ClientMsg},
+		},
+		ics03_connection::connection::ConnectionEnd,
+		ics23_commitment::commitment::{CommitmentProofBytes, CommitmentRoot},
+		ics24_host::identifier::{ChannelId, ClientId, PortId},
+	},
+	mock::{
+		client_def::MockClient,
+		client_state::MockClientState,
+		context::{MockClientTypes, MockContext},
+		header::{MockClientMessage, MockHeader},
+	},
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
+
+const LATEST_HEIGHT: Height = Height { revision_number: 0, revision_height: 42 };
+
+fn mock_context(client_id: &ClientId) -> MockContext<MockClientTypes> {
+	let mut ctx = MockContext::<MockClientTypes>::default().with_client(client_id, LATEST_HEIGHT);
+	ctx.store_update_time(client_id.clone(), LATEST_HEIGHT, Timestamp::now()).unwrap();
+	ctx
+}
+
+fn update_client(c: &mut Criterion) {
+	let mut group = c.benchmark_group("update_client");
+
+	let client_id = ClientId::from_str("mockclient").unwrap();
+	let ctx = mock_context(&client_id);
+	group.bench_function("mock", |b| {
+		b.iter_batched(
+			|| {
+				let header =
+					MockHeader::new(LATEST_HEIGHT.increment()).with_timestamp(Timestamp::now());
+				ClientMsg::UpdateClient(MsgUpdateAnyClient {
+					client_id: client_id.clone(),
+					client_message: header.into(),
+					signer: Signer::from_str("relayer").unwrap(),
+				})
+			},
+			|msg| dispatch(&ctx, black_box(msg)).unwrap(),
+			BatchSize::SmallInput,
+		)
+	});
+
+	group.finish();
+}
+
+fn verify_membership(c: &mut Criterion) {
+	let mut group = c.benchmark_group("verify_membership");
+
+	let client_id = ClientId::from_str("mockclient").unwrap();
+	let ctx = mock_context(&client_id);
+	let client_state =
+		MockClientState::new(MockClientMessage::from(MockHeader::new(LATEST_HEIGHT)));
+	let connection_end = ConnectionEnd::default();
+	let proof = CommitmentProofBytes::try_from(vec![1]).unwrap();
+	let root = CommitmentRoot::from(vec![0]);
+	let (port_id, channel_id) = (PortId::default(), ChannelId::default());
+	group.bench_function("mock", |b| {
+		b.iter(|| {
+			MockClient::default()
+				.verify_packet_data(
+					&ctx,
+					&client_id,
+					&client_state,
+					LATEST_HEIGHT,
+					&connection_end,
+					&proof,
+					&root,
+					&port_id,
+					&channel_id,
+					1.into(),
+					black_box(vec![0; 32].into()),
+				)
+				.unwrap()
+		})
+	});
+
+	group.finish();
+}
+
+criterion_group!(benches, update_client, verify_membership);
+criterion_main!(benches);
@@ -0,0 +1,55 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc_proto::ibc::applications::fee::v1::IncentivizedAcknowledgement as RawIncentivizedAcknowledgement;
+use tendermint_proto::Protobuf;
+
+use super::error::Error;
+use crate::prelude::*;
+
+/// The acknowledgement of a packet received over a fee enabled channel, protobuf encoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncentivizedAcknowledgement {
+	/// Acknowledgement written by the underlying application
+	pub app_acknowledgement: Vec<u8>,
+	/// Address the receive fee is paid to on the chain that sent the packet, empty if the
+	/// relayer delivering the packet did not register one
+	pub forward_relayer_address: String,
+	/// Whether the underlying application handled the packet successfully
+	pub underlying_app_success: bool,
+}
+
+impl Protobuf<RawIncentivizedAcknowledgement> for IncentivizedAcknowledgement {}
+
+impl TryFrom<RawIncentivizedAcknowledgement> for IncentivizedAcknowledgement {
+	type Error = Error;
+
+	fn try_from(raw: RawIncentivizedAcknowledgement) -> Result<Self, Self::Error> {
+		Ok(IncentivizedAcknowledgement {
+			app_acknowledgement: raw.app_acknowledgement,
+			forward_relayer_address: raw.forward_relayer_address,
+			underlying_app_success: raw.underlying_app_success,
+		})
+	}
+}
+
+impl From<IncentivizedAcknowledgement> for RawIncentivizedAcknowledgement {
+	fn from(ack: IncentivizedAcknowledgement) -> Self {
+		RawIncentivizedAcknowledgement {
+			app_acknowledgement: ack.app_acknowledgement,
+			forward_relayer_address: ack.forward_relayer_address,
+			underlying_app_success: ack.underlying_app_success,
+		}
+	}
+}
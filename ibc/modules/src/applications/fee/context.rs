@@ -0,0 +1,67 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::{error::Error, PacketFee};
+use crate::{
+	applications::transfer::context::BankKeeper,
+	core::{
+		ics04_channel::{context::ChannelReader, packet::Sequence},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+/// Captures all the dependencies of the ICS29 fee middleware: the bank moving fees in and out of
+/// escrow, and the storage of fee enabled channels, escrowed fees and registered payees.
+pub trait FeeKeeper:
+	BankKeeper<AccountId = <Self as FeeKeeper>::AccountId> + ChannelReader
+{
+	type AccountId: TryFrom<Signer>;
+
+	/// Account holding the fees escrowed for packets until they are paid out or refunded.
+	fn fee_escrow_address(&self) -> Result<<Self as FeeKeeper>::AccountId, Error>;
+
+	/// Records that the version of a channel negotiated the fee middleware.
+	fn store_fee_enabled(&mut self, port_id: &PortId, channel_id: &ChannelId)
+		-> Result<(), Error>;
+
+	fn is_fee_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool;
+
+	fn store_packet_fees(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		fees: Vec<PacketFee>,
+	) -> Result<(), Error>;
+
+	/// Returns the fees escrowed for a packet, empty if there are none.
+	fn get_packet_fees(&self, key: &(PortId, ChannelId, Sequence))
+		-> Result<Vec<PacketFee>, Error>;
+
+	/// Prunes the fees of a packet once they have been paid out and refunded.
+	fn delete_packet_fees(&mut self, key: &(PortId, ChannelId, Sequence)) -> Result<(), Error>;
+
+	fn store_counterparty_payee(
+		&mut self,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+		counterparty_payee: &Signer,
+	) -> Result<(), Error>;
+
+	fn get_counterparty_payee(
+		&self,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<Option<Signer>, Error>;
+}
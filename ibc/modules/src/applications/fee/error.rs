@@ -0,0 +1,103 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use flex_error::{define_error, TraceError};
+use tendermint_proto::Error as TendermintProtoError;
+
+use crate::{
+	applications::transfer::error::Error as Ics20Error,
+	core::{
+		ics04_channel::{error as channel_error, packet::Sequence, Version},
+		ics24_host::{
+			error::ValidationError,
+			identifier::{ChannelId, PortId},
+		},
+	},
+	prelude::*,
+	signer::SignerError,
+};
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		Ics04Channel
+			[ channel_error::Error ]
+			|_ | { "Ics04 channel error" },
+
+		Ics20
+			[ Ics20Error ]
+			|_ | { "Ics20 transfer error" },
+
+		InvalidPortId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid port identifier" },
+
+		InvalidChannelId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid channel identifier" },
+
+		Signer
+			[ SignerError ]
+			| _ | { "failed to parse signer" },
+
+		MissingFee
+			| _ | { "missing fee" },
+
+		MissingPacketId
+			| _ | { "missing packet id" },
+
+		MissingPacketFee
+			| _ | { "missing packet fee" },
+
+		RelayersNotSupported
+			| _ | { "restricting the relayers paid for a packet is not supported" },
+
+		FeeNotEnabled
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("fees are not enabled on port {0} and channel {1}", e.port_id, e.channel_id) },
+
+		PacketNotFound
+			{ sequence: Sequence }
+			| e | { format_args!("no packet commitment found for sequence {0}", e.sequence) },
+
+		InvalidVersion
+			{ version: Version }
+			| e | { format_args!("expected fee version '{0}', got '{1}'", super::VERSION, e.version) },
+
+		InvalidCounterpartyVersion
+			{ version: Version }
+			| e | { format_args!("expected counterparty fee version '{0}', got '{1}'", super::VERSION, e.version) },
+
+		AckDeserialization
+			[ TraceError<TendermintProtoError> ]
+			| _ | { "failed to deserialize incentivized acknowledgement" },
+
+		ParseAccountFailure
+			| _ | { "failed to parse as AccountId" },
+
+		DecodeRawMsg
+			[ TraceError<TendermintProtoError> ]
+			| _ | { "error decoding raw msg" },
+
+		UnknownMsgType
+			{ msg_type: String }
+			| e | { format_args!("unknown msg type: {0}", e.msg_type) },
+
+		ImplementationSpecific
+			{ reason: String }
+			| e | { format_args!("implementation specific error: {}", e.reason) },
+	}
+}
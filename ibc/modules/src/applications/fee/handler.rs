@@ -0,0 +1,169 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Escrow of the fees paid for packets and their payout to relayers.
+
+use super::{
+	context::FeeKeeper,
+	error::Error,
+	msgs::{
+		pay_packet_fee::MsgPayPacketFee, pay_packet_fee_async::MsgPayPacketFeeAsync,
+		register_counterparty_payee::MsgRegisterCounterpartyPayee,
+	},
+	IncentivizedAcknowledgement, PacketFee,
+};
+use crate::{
+	applications::transfer::PrefixedCoin,
+	core::{
+		ics04_channel::packet::Sequence,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+fn ensure_fee_enabled(
+	ctx: &impl FeeKeeper,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+) -> Result<(), Error> {
+	if !ctx.is_fee_enabled(port_id, channel_id) {
+		return Err(Error::fee_not_enabled(port_id.clone(), *channel_id))
+	}
+	Ok(())
+}
+
+fn escrow_packet_fee<Ctx: FeeKeeper>(
+	ctx: &mut Ctx,
+	key: (PortId, ChannelId, Sequence),
+	packet_fee: PacketFee,
+) -> Result<(), Error> {
+	let payer: <Ctx as FeeKeeper>::AccountId = packet_fee
+		.refund_address
+		.clone()
+		.try_into()
+		.map_err(|_| Error::parse_account_failure())?;
+	let escrow_address = ctx.fee_escrow_address()?;
+	for coin in packet_fee.fee.total() {
+		ctx.send_coins(&payer, &escrow_address, coin).map_err(Error::ics20)?;
+	}
+
+	let mut fees = ctx.get_packet_fees(&key)?;
+	fees.push(packet_fee);
+	ctx.store_packet_fees(key, fees)
+}
+
+/// Escrows a fee for the next packet sent over the message's channel.
+pub fn pay_packet_fee(ctx: &mut impl FeeKeeper, msg: MsgPayPacketFee) -> Result<(), Error> {
+	ensure_fee_enabled(ctx, &msg.source_port_id, &msg.source_channel_id)?;
+	let sequence = ctx
+		.get_next_sequence_send(&(msg.source_port_id.clone(), msg.source_channel_id))
+		.map_err(Error::ics04_channel)?;
+
+	let packet_fee = PacketFee { fee: msg.fee, refund_address: msg.signer, relayers: vec![] };
+	escrow_packet_fee(ctx, (msg.source_port_id, msg.source_channel_id, sequence), packet_fee)
+}
+
+/// Escrows a fee for a packet that has been sent but not acknowledged or timed out yet.
+pub fn pay_packet_fee_async(
+	ctx: &mut impl FeeKeeper,
+	msg: MsgPayPacketFeeAsync,
+) -> Result<(), Error> {
+	ensure_fee_enabled(ctx, &msg.port_id, &msg.channel_id)?;
+	let key = (msg.port_id, msg.channel_id, msg.sequence);
+	ctx.get_packet_commitment(&key).map_err(|_| Error::packet_not_found(msg.sequence))?;
+
+	escrow_packet_fee(ctx, key, msg.packet_fee)
+}
+
+/// Registers the address the relayer is paid the receive fees of the packets it delivers to.
+pub fn register_counterparty_payee(
+	ctx: &mut impl FeeKeeper,
+	msg: MsgRegisterCounterpartyPayee,
+) -> Result<(), Error> {
+	ctx.channel_end(&(msg.port_id.clone(), msg.channel_id)).map_err(Error::ics04_channel)?;
+	ensure_fee_enabled(ctx, &msg.port_id, &msg.channel_id)?;
+
+	ctx.store_counterparty_payee(&msg.channel_id, &msg.relayer, &msg.counterparty_payee)
+}
+
+/// Wraps the acknowledgement the underlying application wrote for a packet received over a fee
+/// enabled channel, recording where the relayer delivering it is paid the receive fee.
+pub fn incentivized_acknowledgement(
+	ctx: &impl FeeKeeper,
+	channel_id: &ChannelId,
+	relayer: &Signer,
+	app_acknowledgement: Vec<u8>,
+	underlying_app_success: bool,
+) -> Result<IncentivizedAcknowledgement, Error> {
+	let forward_relayer_address = ctx
+		.get_counterparty_payee(channel_id, relayer)?
+		.map(|payee| payee.to_string())
+		.unwrap_or_default();
+	Ok(IncentivizedAcknowledgement {
+		app_acknowledgement,
+		forward_relayer_address,
+		underlying_app_success,
+	})
+}
+
+fn distribute<Ctx: FeeKeeper>(
+	ctx: &mut Ctx,
+	coins: &[PrefixedCoin],
+	receiver: &Signer,
+) -> Result<(), Error> {
+	let receiver: <Ctx as FeeKeeper>::AccountId =
+		receiver.clone().try_into().map_err(|_| Error::parse_account_failure())?;
+	let escrow_address = ctx.fee_escrow_address()?;
+	for coin in coins {
+		ctx.send_coins(&escrow_address, &receiver, coin).map_err(Error::ics20)?;
+	}
+	Ok(())
+}
+
+/// Pays the receive fees of an acknowledged packet to `forward_relayer`, or refunds them if no
+/// payee was registered, pays the acknowledgement fees to `relayer` and refunds the timeout fees.
+pub fn distribute_acknowledgement_fees(
+	ctx: &mut impl FeeKeeper,
+	key: &(PortId, ChannelId, Sequence),
+	forward_relayer: Option<Signer>,
+	relayer: &Signer,
+) -> Result<(), Error> {
+	let fees = ctx.get_packet_fees(key)?;
+	ctx.delete_packet_fees(key)?;
+	for packet_fee in fees {
+		let recv_fee_payee = forward_relayer.as_ref().unwrap_or(&packet_fee.refund_address);
+		distribute(ctx, &packet_fee.fee.recv_fee, recv_fee_payee)?;
+		distribute(ctx, &packet_fee.fee.ack_fee, relayer)?;
+		distribute(ctx, &packet_fee.fee.timeout_fee, &packet_fee.refund_address)?;
+	}
+	Ok(())
+}
+
+/// Pays the timeout fees of a timed out packet to `relayer` and refunds the receive and
+/// acknowledgement fees.
+pub fn distribute_timeout_fees(
+	ctx: &mut impl FeeKeeper,
+	key: &(PortId, ChannelId, Sequence),
+	relayer: &Signer,
+) -> Result<(), Error> {
+	let fees = ctx.get_packet_fees(key)?;
+	ctx.delete_packet_fees(key)?;
+	for packet_fee in fees {
+		distribute(ctx, &packet_fee.fee.recv_fee, &packet_fee.refund_address)?;
+		distribute(ctx, &packet_fee.fee.ack_fee, &packet_fee.refund_address)?;
+		distribute(ctx, &packet_fee.fee.timeout_fee, relayer)?;
+	}
+	Ok(())
+}
@@ -0,0 +1,545 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The [`FeeMiddleware`] wraps the module of an application, such as ICS20, and pays out the
+//! fees escrowed for the packets the application sends once they are acknowledged or time out.
+//!
+//! The middleware does not wrap the acknowledgements written by the application it sits on top
+//! of, as modules write them in `on_recv_packet`: hosts writing the acknowledgement of a packet
+//! received over a fee enabled channel should wrap it with
+//! [`incentivized_acknowledgement`](super::handler::incentivized_acknowledgement).
+
+use tendermint_proto::Protobuf;
+
+use super::{
+	context::FeeKeeper,
+	error::Error as FeeError,
+	handler::{distribute_acknowledgement_fees, distribute_timeout_fees},
+	version::Metadata,
+	IncentivizedAcknowledgement, VERSION,
+};
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+			packet::Packet,
+			upgrade::UpgradeFields,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{
+			Module, ModuleCallbackContext, ModuleOutputBuilder, OnRecvPacketAck,
+		},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+/// A module paying relayers the fees escrowed for the packets sent by the module it wraps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeMiddleware<Ctx, M> {
+	ctx: Ctx,
+	app: M,
+}
+
+impl<Ctx, M> FeeMiddleware<Ctx, M> {
+	pub fn new(ctx: Ctx, app: M) -> Self {
+		Self { ctx, app }
+	}
+}
+
+fn fee_error(e: FeeError) -> Ics04Error {
+	Ics04Error::app_module(e.to_string())
+}
+
+/// Returns the fee metadata of `version`, failing if it names another fee version.
+fn fee_metadata(
+	version: &Version,
+	err: fn(Version) -> FeeError,
+) -> Result<Option<Metadata>, Ics04Error> {
+	match Metadata::from_version(version) {
+		Some(metadata) if metadata.fee_version != VERSION => Err(fee_error(err(version.clone()))),
+		metadata => Ok(metadata),
+	}
+}
+
+impl<Ctx, M> Module for FeeMiddleware<Ctx, M>
+where
+	Ctx: 'static + FeeKeeper + Send + Sync,
+	M: Module,
+{
+	fn on_chan_open_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let metadata = match fee_metadata(version, FeeError::invalid_version)? {
+			Some(metadata) => metadata,
+			None =>
+				return self.app.on_chan_open_init(
+					ctx,
+					output,
+					order,
+					connection_hops,
+					port_id,
+					channel_id,
+					counterparty,
+					version,
+					relayer,
+				),
+		};
+		self.app.on_chan_open_init(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			&metadata.app_version(),
+			relayer,
+		)?;
+		self.ctx.store_fee_enabled(port_id, channel_id).map_err(fee_error)
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		let metadata =
+			match fee_metadata(counterparty_version, FeeError::invalid_counterparty_version)? {
+				Some(metadata) => metadata,
+				None =>
+					return self.app.on_chan_open_try(
+						ctx,
+						output,
+						order,
+						connection_hops,
+						port_id,
+						channel_id,
+						counterparty,
+						version,
+						counterparty_version,
+						relayer,
+					),
+			};
+		let app_version = Metadata::from_version(version)
+			.map(|metadata| metadata.app_version())
+			.unwrap_or_else(|| version.clone());
+		let app_version = self.app.on_chan_open_try(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			&app_version,
+			&metadata.app_version(),
+			relayer,
+		)?;
+		self.ctx.store_fee_enabled(port_id, channel_id).map_err(fee_error)?;
+		Ok(Metadata::new(&app_version).into())
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		if !self.ctx.is_fee_enabled(port_id, channel_id) {
+			return self.app.on_chan_open_ack(
+				ctx,
+				output,
+				port_id,
+				channel_id,
+				counterparty_version,
+				relayer,
+			)
+		}
+		// The counterparty must have accepted the fee version proposed in `ChanOpenInit`.
+		let metadata = fee_metadata(counterparty_version, FeeError::invalid_counterparty_version)?
+			.ok_or_else(|| {
+				fee_error(FeeError::invalid_counterparty_version(counterparty_version.clone()))
+			})?;
+		self.app.on_chan_open_ack(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			&metadata.app_version(),
+			relayer,
+		)
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.app.on_chan_open_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.app.on_chan_close_init(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.app.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_upgrade: &UpgradeFields,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.app.on_chan_upgrade_init(ctx, output, port_id, channel_id, proposed_upgrade, relayer)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_upgrade: &UpgradeFields,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.app.on_chan_upgrade_try(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			counterparty_upgrade,
+			relayer,
+		)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.app.on_chan_upgrade_open(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_upgrade_cancel(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.app.on_chan_upgrade_cancel(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_recv_packet(
+		&self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &Packet,
+		relayer: &Signer,
+	) -> Result<OnRecvPacketAck, Ics04Error> {
+		self.app.on_recv_packet(ctx, output, packet, relayer)
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &Packet,
+		acknowledgement: &GenericAcknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		if !self.ctx.is_fee_enabled(&packet.source_port, &packet.source_channel) {
+			return self.app.on_acknowledgement_packet(
+				ctx,
+				output,
+				packet,
+				acknowledgement,
+				relayer,
+			)
+		}
+
+		// Acknowledgements the counterparty did not wrap carry no payee for the receive fee.
+		let (app_acknowledgement, forward_relayer) =
+			match IncentivizedAcknowledgement::decode_vec(acknowledgement.as_ref()) {
+				Ok(ack) => {
+					let forward_relayer = ack.forward_relayer_address.parse::<Signer>().ok();
+					(GenericAcknowledgement::from(ack.app_acknowledgement), forward_relayer)
+				},
+				Err(_) => (acknowledgement.clone(), None),
+			};
+		let key = (packet.source_port.clone(), packet.source_channel, packet.sequence);
+		distribute_acknowledgement_fees(&mut self.ctx, &key, forward_relayer, relayer)
+			.map_err(fee_error)?;
+
+		self.app.on_acknowledgement_packet(ctx, output, packet, &app_acknowledgement, relayer)
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		if self.ctx.is_fee_enabled(&packet.source_port, &packet.source_channel) {
+			let key = (packet.source_port.clone(), packet.source_channel, packet.sequence);
+			distribute_timeout_fees(&mut self.ctx, &key, relayer).map_err(fee_error)?;
+		}
+
+		self.app.on_timeout_packet(ctx, output, packet, relayer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+	use tendermint_proto::Protobuf;
+
+	use crate::{
+		applications::{
+			fee::{
+				context::FeeKeeper,
+				handler::{
+					incentivized_acknowledgement, pay_packet_fee, register_counterparty_payee,
+				},
+				middleware::FeeMiddleware,
+				msgs::{
+					pay_packet_fee::MsgPayPacketFee,
+					register_counterparty_payee::MsgRegisterCounterpartyPayee,
+				},
+				version::Metadata,
+				Fee,
+			},
+			transfer::{context::BankKeeper, Amount, PrefixedCoin, PrefixedDenom},
+		},
+		core::{
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+				packet::{Packet, Sequence},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+			ics26_routing::context::{Module, ModuleOutputBuilder},
+		},
+		mock::context::{MockClientTypes, MockContext},
+		signer::Signer,
+		test_utils::DummyTransferModule,
+	};
+
+	fn signer(s: &str) -> Signer {
+		s.parse().unwrap()
+	}
+
+	fn denom() -> PrefixedDenom {
+		"stake".parse().unwrap()
+	}
+
+	fn coins(amount: u64) -> Vec<PrefixedCoin> {
+		vec![PrefixedCoin { denom: denom(), amount: Amount::from(amount) }]
+	}
+
+	fn balance(module: &DummyTransferModule<MockClientTypes>, account: &str) -> Amount {
+		module.balance(&signer(account), &denom())
+	}
+
+	/// A fee enabled transfer channel on channel-0, with `payer` holding 1000stake and having
+	/// escrowed a fee of 10, 20 and 30stake for the receive, ack and timeout of sequence 1.
+	fn fee_paid_packet() -> (
+		MockContext<MockClientTypes>,
+		FeeMiddleware<DummyTransferModule<MockClientTypes>, DummyTransferModule<MockClientTypes>>,
+		DummyTransferModule<MockClientTypes>,
+		Packet,
+	) {
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(PortId::transfer(), Some(ChannelId::new(1))),
+			vec![ConnectionId::default()],
+			Metadata::new(&Version::ics20()).into(),
+		);
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_channel(PortId::transfer(), ChannelId::new(0), channel_end)
+			.with_send_sequence(PortId::transfer(), ChannelId::new(0), Sequence::from(1));
+		let mut module = DummyTransferModule::new(ctx.ibc_store_share());
+		module.store_fee_enabled(&PortId::transfer(), &ChannelId::new(0)).unwrap();
+		module.mint_coins(&signer("payer"), &coins(1000)[0]).unwrap();
+
+		let msg = MsgPayPacketFee {
+			fee: Fee { recv_fee: coins(10), ack_fee: coins(20), timeout_fee: coins(30) },
+			source_port_id: PortId::transfer(),
+			source_channel_id: ChannelId::new(0),
+			signer: signer("payer"),
+		};
+		pay_packet_fee(&mut module, msg).unwrap();
+		assert_eq!(balance(&module, "payer"), Amount::from(940u64));
+		assert_eq!(balance(&module, "feeibc"), Amount::from(60u64));
+
+		let packet = Packet {
+			sequence: 1.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(1),
+			..Default::default()
+		};
+		let middleware = FeeMiddleware::new(module.clone(), module.clone());
+		(ctx, middleware, module, packet)
+	}
+
+	#[test]
+	fn acknowledged_packet_pays_relayers_and_refunds_timeout_fee() {
+		let (ctx, mut middleware, mut module, packet) = fee_paid_packet();
+
+		// The relayer delivering the packet is paid its receive fee on this chain
+		let msg = MsgRegisterCounterpartyPayee {
+			port_id: PortId::transfer(),
+			channel_id: ChannelId::new(0),
+			relayer: signer("recv_relayer"),
+			counterparty_payee: signer("recv_payee"),
+		};
+		register_counterparty_payee(&mut module, msg).unwrap();
+		let ack = incentivized_acknowledgement(
+			&module,
+			&ChannelId::new(0),
+			&signer("recv_relayer"),
+			br#"{"result":"AQ=="}"#.to_vec(),
+			true,
+		)
+		.unwrap();
+		let ack = GenericAcknowledgement::from(ack.encode_vec());
+
+		middleware
+			.on_acknowledgement_packet(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&packet,
+				&ack,
+				&signer("ack_relayer"),
+			)
+			.unwrap();
+
+		assert_eq!(balance(&module, "recv_payee"), Amount::from(10u64));
+		assert_eq!(balance(&module, "ack_relayer"), Amount::from(20u64));
+		assert_eq!(balance(&module, "payer"), Amount::from(970u64));
+		assert_eq!(balance(&module, "feeibc"), Amount::from(0u64));
+		let key = (PortId::transfer(), ChannelId::new(0), packet.sequence);
+		assert!(module.get_packet_fees(&key).unwrap().is_empty());
+	}
+
+	#[test]
+	fn timed_out_packet_pays_timeout_fee_and_refunds_the_rest() {
+		let (ctx, mut middleware, module, packet) = fee_paid_packet();
+
+		middleware
+			.on_timeout_packet(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&packet,
+				&signer("timeout_relayer"),
+			)
+			.unwrap();
+
+		assert_eq!(balance(&module, "timeout_relayer"), Amount::from(30u64));
+		assert_eq!(balance(&module, "payer"), Amount::from(970u64));
+		assert_eq!(balance(&module, "feeibc"), Amount::from(0u64));
+	}
+
+	#[test]
+	fn fee_version_is_negotiated_in_the_handshake() {
+		let ctx = MockContext::<MockClientTypes>::default();
+		let module = DummyTransferModule::new(ctx.ibc_store_share());
+		let mut middleware = FeeMiddleware::new(module.clone(), module.clone());
+		let counterparty = Counterparty::new(PortId::transfer(), Some(ChannelId::new(1)));
+		let mut open_try = |channel_id: ChannelId, counterparty_version: Version| {
+			middleware.on_chan_open_try(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Unordered,
+				&[ConnectionId::default()],
+				&PortId::transfer(),
+				&channel_id,
+				&counterparty,
+				&Version::empty(),
+				&counterparty_version,
+				&signer("relayer"),
+			)
+		};
+
+		let version = open_try(ChannelId::new(0), Metadata::new(&Version::ics20()).into()).unwrap();
+		assert_eq!(Metadata::from_version(&version), Some(Metadata::new(&Version::ics20())));
+
+		let version = open_try(ChannelId::new(1), Version::ics20()).unwrap();
+		assert_eq!(version, Version::ics20());
+
+		let unsupported = r#"{"fee_version":"ics29-2","app_version":"ics20-1"}"#;
+		assert!(open_try(ChannelId::new(2), Version::new(unsupported.to_string())).is_err());
+
+		assert!(module.is_fee_enabled(&PortId::transfer(), &ChannelId::new(0)));
+		assert!(!module.is_fee_enabled(&PortId::transfer(), &ChannelId::new(1)));
+		assert!(!module.is_fee_enabled(&PortId::transfer(), &ChannelId::new(2)));
+	}
+}
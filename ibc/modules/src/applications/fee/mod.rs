@@ -0,0 +1,35 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ICS 29: Fee Middleware incentivizes relayers by paying them, out of fees escrowed by the
+//! senders of packets, for delivering packets and relaying their acknowledgements and timeouts.
+//!
+//! Fees are only paid on channels whose version negotiated the fee middleware, see
+//! [`version::Metadata`]. On such channels the acknowledgement of a received packet is wrapped in
+//! an [`IncentivizedAcknowledgement`] carrying the address the receive fee is paid to on the
+//! sending chain.
+pub mod acknowledgement;
+pub mod context;
+pub mod error;
+pub mod handler;
+pub mod middleware;
+pub mod msgs;
+pub mod packet_fee;
+pub mod version;
+
+pub use acknowledgement::IncentivizedAcknowledgement;
+pub use packet_fee::*;
+
+/// ICS29 fee middleware current version.
+pub const VERSION: &str = "ics29-1";
@@ -0,0 +1,100 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::prelude::*;
+
+use ibc_proto::{
+	google::protobuf::Any, ibc::applications::fee::v1::MsgPayPacketFee as RawMsgPayPacketFee,
+};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::fee::{error::Error, Fee},
+	core::ics24_host::identifier::{ChannelId, PortId},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgPayPacketFee";
+
+/// Message escrowing a fee for the next packet sent over a channel, submitted in the same
+/// transaction as the message sending the packet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgPayPacketFee {
+	pub fee: Fee,
+	pub source_port_id: PortId,
+	pub source_channel_id: ChannelId,
+	/// Account paying the fee, refunded the part of it that is not paid out
+	pub signer: Signer,
+}
+
+impl Msg for MsgPayPacketFee {
+	type ValidationError = Error;
+	type Raw = RawMsgPayPacketFee;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl TryFrom<RawMsgPayPacketFee> for MsgPayPacketFee {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgPayPacketFee) -> Result<Self, Self::Error> {
+		if !raw_msg.relayers.is_empty() {
+			return Err(Error::relayers_not_supported())
+		}
+		Ok(MsgPayPacketFee {
+			fee: raw_msg.fee.ok_or_else(Error::missing_fee)?.try_into()?,
+			source_port_id: raw_msg
+				.source_port_id
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw_msg.source_port_id.clone(), e))?,
+			source_channel_id: raw_msg
+				.source_channel_id
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw_msg.source_channel_id.clone(), e))?,
+			signer: raw_msg.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgPayPacketFee> for RawMsgPayPacketFee {
+	fn from(domain_msg: MsgPayPacketFee) -> Self {
+		RawMsgPayPacketFee {
+			fee: Some(domain_msg.fee.into()),
+			source_port_id: domain_msg.source_port_id.to_string(),
+			source_channel_id: domain_msg.source_channel_id.to_string(),
+			signer: domain_msg.signer.to_string(),
+			relayers: vec![],
+		}
+	}
+}
+
+impl Protobuf<RawMsgPayPacketFee> for MsgPayPacketFee {}
+
+impl TryFrom<Any> for MsgPayPacketFee {
+	type Error = Error;
+
+	fn try_from(raw: Any) -> Result<Self, Self::Error> {
+		match raw.type_url.as_str() {
+			TYPE_URL => MsgPayPacketFee::decode_vec(&raw.value).map_err(Error::decode_raw_msg),
+			_ => Err(Error::unknown_msg_type(raw.type_url)),
+		}
+	}
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::prelude::*;
+
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::applications::fee::v1::MsgRegisterCounterpartyPayee as RawMsgRegisterCounterpartyPayee,
+};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::fee::error::Error,
+	core::ics24_host::identifier::{ChannelId, PortId},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgRegisterCounterpartyPayee";
+
+/// Message registering the address a relayer is paid receive fees to on the counterparty chain,
+/// for the packets it delivers over a channel of this chain.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgRegisterCounterpartyPayee {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	/// The relayer, signer of the message
+	pub relayer: Signer,
+	/// Address of the relayer on the counterparty chain
+	pub counterparty_payee: Signer,
+}
+
+impl Msg for MsgRegisterCounterpartyPayee {
+	type ValidationError = Error;
+	type Raw = RawMsgRegisterCounterpartyPayee;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl TryFrom<RawMsgRegisterCounterpartyPayee> for MsgRegisterCounterpartyPayee {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgRegisterCounterpartyPayee) -> Result<Self, Self::Error> {
+		Ok(MsgRegisterCounterpartyPayee {
+			port_id: raw_msg
+				.port_id
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw_msg.port_id.clone(), e))?,
+			channel_id: raw_msg
+				.channel_id
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw_msg.channel_id.clone(), e))?,
+			relayer: raw_msg.relayer.parse().map_err(Error::signer)?,
+			counterparty_payee: raw_msg.counterparty_payee.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgRegisterCounterpartyPayee> for RawMsgRegisterCounterpartyPayee {
+	fn from(domain_msg: MsgRegisterCounterpartyPayee) -> Self {
+		RawMsgRegisterCounterpartyPayee {
+			port_id: domain_msg.port_id.to_string(),
+			channel_id: domain_msg.channel_id.to_string(),
+			relayer: domain_msg.relayer.to_string(),
+			counterparty_payee: domain_msg.counterparty_payee.to_string(),
+		}
+	}
+}
+
+impl Protobuf<RawMsgRegisterCounterpartyPayee> for MsgRegisterCounterpartyPayee {}
+
+impl TryFrom<Any> for MsgRegisterCounterpartyPayee {
+	type Error = Error;
+
+	fn try_from(raw: Any) -> Result<Self, Self::Error> {
+		match raw.type_url.as_str() {
+			TYPE_URL =>
+				MsgRegisterCounterpartyPayee::decode_vec(&raw.value).map_err(Error::decode_raw_msg),
+			_ => Err(Error::unknown_msg_type(raw.type_url)),
+		}
+	}
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc_proto::ibc::applications::fee::v1::{Fee as RawFee, PacketFee as RawPacketFee};
+
+use super::error::Error;
+use crate::{applications::transfer::PrefixedCoin, prelude::*, signer::Signer};
+
+/// The fees paid to the relayers of a packet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fee {
+	/// Paid to the relayer delivering the packet to the counterparty
+	pub recv_fee: Vec<PrefixedCoin>,
+	/// Paid to the relayer delivering the acknowledgement of the packet
+	pub ack_fee: Vec<PrefixedCoin>,
+	/// Paid to the relayer delivering the timeout of the packet
+	pub timeout_fee: Vec<PrefixedCoin>,
+}
+
+impl Fee {
+	/// All the coins escrowed for the fee.
+	pub fn total(&self) -> impl Iterator<Item = &PrefixedCoin> {
+		self.recv_fee.iter().chain(self.ack_fee.iter()).chain(self.timeout_fee.iter())
+	}
+}
+
+fn coins_from_raw(
+	coins: Vec<ibc_proto::cosmos::base::v1beta1::Coin>,
+) -> Result<Vec<PrefixedCoin>, Error> {
+	coins.into_iter().map(|coin| coin.try_into().map_err(Error::ics20)).collect()
+}
+
+impl TryFrom<RawFee> for Fee {
+	type Error = Error;
+
+	fn try_from(raw: RawFee) -> Result<Self, Self::Error> {
+		Ok(Fee {
+			recv_fee: coins_from_raw(raw.recv_fee)?,
+			ack_fee: coins_from_raw(raw.ack_fee)?,
+			timeout_fee: coins_from_raw(raw.timeout_fee)?,
+		})
+	}
+}
+
+impl From<Fee> for RawFee {
+	fn from(fee: Fee) -> Self {
+		RawFee {
+			recv_fee: fee.recv_fee.into_iter().map(Into::into).collect(),
+			ack_fee: fee.ack_fee.into_iter().map(Into::into).collect(),
+			timeout_fee: fee.timeout_fee.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// A fee escrowed for a packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PacketFee {
+	pub fee: Fee,
+	/// Account the fee was escrowed from, refunded whatever is not paid out to relayers
+	pub refund_address: Signer,
+	/// Relayers permitted to receive the fee, any relayer if empty
+	pub relayers: Vec<Signer>,
+}
+
+impl TryFrom<RawPacketFee> for PacketFee {
+	type Error = Error;
+
+	fn try_from(raw: RawPacketFee) -> Result<Self, Self::Error> {
+		if !raw.relayers.is_empty() {
+			return Err(Error::relayers_not_supported())
+		}
+		Ok(PacketFee {
+			fee: raw.fee.ok_or_else(Error::missing_fee)?.try_into()?,
+			refund_address: raw.refund_address.parse().map_err(Error::signer)?,
+			relayers: vec![],
+		})
+	}
+}
+
+impl From<PacketFee> for RawPacketFee {
+	fn from(packet_fee: PacketFee) -> Self {
+		RawPacketFee {
+			fee: Some(packet_fee.fee.into()),
+			refund_address: packet_fee.refund_address.to_string(),
+			relayers: packet_fee.relayers.into_iter().map(|relayer| relayer.to_string()).collect(),
+		}
+	}
+}
@@ -0,0 +1,55 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{core::ics04_channel::Version, prelude::*};
+
+/// The version of a fee enabled channel, wrapping the version of the application the fee
+/// middleware sits on top of:
+///
+/// ```json
+/// {"fee_version":"ics29-1","app_version":"ics20-1"}
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+	/// Version of the fee middleware
+	pub fee_version: String,
+	/// Version of the underlying application
+	pub app_version: String,
+}
+
+impl Metadata {
+	pub fn new(app_version: &Version) -> Self {
+		Self { fee_version: super::VERSION.to_string(), app_version: app_version.to_string() }
+	}
+
+	/// Returns the fee metadata of a channel version, or `None` if the version is not a fee
+	/// version, in which case it is the version of the underlying application.
+	pub fn from_version(version: &Version) -> Option<Self> {
+		serde_json::from_str(&version.to_string()).ok()
+	}
+
+	pub fn app_version(&self) -> Version {
+		Version::new(self.app_version.clone())
+	}
+}
+
+impl From<Metadata> for Version {
+	fn from(metadata: Metadata) -> Self {
+		let version =
+			serde_json::to_string(&metadata).expect("Metadata's infallible Serialize impl failed");
+		Version::new(version)
+	}
+}
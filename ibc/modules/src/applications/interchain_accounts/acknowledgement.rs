@@ -0,0 +1,51 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+use super::error::Error;
+use crate::{
+	applications::transfer::acknowledgement::ACK_ERR_STR,
+	core::ics04_channel::msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+	prelude::*,
+};
+
+/// The acknowledgement of an interchain account packet, encoded as the JSON of the ICS4
+/// acknowledgement envelope, i.e. `{"result":"<base64>"}` or `{"error":"<reason>"}`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Acknowledgement {
+	/// The result returned by the host for the executed transaction
+	Result(#[serde(with = "tendermint_proto::serializers::bytes::base64string")] Vec<u8>),
+	/// Error Acknowledgement
+	Error(String),
+}
+
+impl Acknowledgement {
+	pub fn from_error(err: Error) -> Self {
+		Self::Error(format!("{}: {}", ACK_ERR_STR, err))
+	}
+
+	pub fn is_successful(&self) -> bool {
+		matches!(self, Self::Result(_))
+	}
+}
+
+impl From<Acknowledgement> for GenericAcknowledgement {
+	fn from(ack: Acknowledgement) -> Self {
+		serde_json::to_vec(&ack)
+			.expect("Acknowledgement's infallible Serialize impl failed")
+			.into()
+	}
+}
@@ -0,0 +1,706 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc_proto::google::protobuf::Any;
+use subtle_encoding::hex;
+
+use super::{error::Error, VERSION};
+use crate::{
+	core::{
+		ics03_connection::context::ConnectionReader,
+		ics04_channel::{
+			channel::State,
+			context::{ChannelKeeper, ChannelReader},
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::ReaderContext,
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+/// Storage of the controller chain, keyed by the connection of the interchain account and the
+/// controller port, i.e. `icacontroller-{owner}`, of its owner.
+pub trait IcaControllerKeeper {
+	/// Records the channel an interchain account is currently reachable through.
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Error>;
+
+	fn get_active_channel(&self, connection_id: &ConnectionId, port_id: &PortId)
+		-> Option<ChannelId>;
+
+	/// Records the address the host registered for an interchain account.
+	fn store_interchain_account_address(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		address: Signer,
+	) -> Result<(), Error>;
+
+	fn get_interchain_account_address(
+		&self,
+		connection_id: &ConnectionId,
+		port_id: &PortId,
+	) -> Option<Signer>;
+}
+
+/// Captures all the dependencies which the ICS27 controller module requires to be able to
+/// register interchain accounts and send them transactions.
+pub trait IcaControllerContext: IcaControllerKeeper + ChannelKeeper + ReaderContext {}
+
+/// Storage of the host chain, keyed by the connection of the interchain account and the
+/// controller port of its owner.
+pub trait IcaHostKeeper {
+	/// Records the channel an interchain account is currently reachable through.
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Error>;
+
+	fn get_active_channel(&self, connection_id: &ConnectionId, port_id: &PortId)
+		-> Option<ChannelId>;
+
+	/// This function should create the account at `address`, so that it can hold funds and sign
+	/// the messages sent by its owner.
+	fn register_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		address: Signer,
+	) -> Result<(), Error>;
+
+	fn get_interchain_account(&self, connection_id: &ConnectionId, port_id: &PortId)
+		-> Option<Signer>;
+}
+
+/// Executes the transactions of the interchain accounts on the host chain.
+pub trait IcaHostExecutor {
+	/// This function should execute `messages` in order with `account` as their signer, rejecting
+	/// messages signed by any other account. Either all of the messages are executed or, when an
+	/// error is returned, none of them. The returned bytes are acknowledged to the controller.
+	fn execute(&mut self, account: &Signer, messages: Vec<Any>) -> Result<Vec<u8>, Error>;
+}
+
+/// Captures all the dependencies which the ICS27 host module requires to be able to register
+/// interchain accounts and execute their transactions.
+pub trait IcaHostContext: IcaHostKeeper + IcaHostExecutor + ReaderContext {
+	/// Returns the address of the interchain account of the owner of `port_id` on `connection_id`.
+	/// Defaults to the hex encoded hash of the account's connection and port, computed like ICS20
+	/// escrow addresses.
+	fn generate_address(
+		&self,
+		connection_id: &ConnectionId,
+		port_id: &PortId,
+	) -> Result<Signer, Error> {
+		let contents = format!("{}/{}", connection_id, port_id);
+		let mut data = VERSION.as_bytes().to_vec();
+		data.extend_from_slice(&[0]);
+		data.extend_from_slice(contents.as_bytes());
+
+		let hash = self.hash(data);
+		String::from_utf8(hex::encode_upper(hash))
+			.expect("hex encoded bytes are not valid UTF8")
+			.parse()
+			.map_err(Error::signer)
+	}
+}
+
+/// Returns the id of the counterparty of a connection.
+pub(crate) fn counterparty_connection_id(
+	ctx: &impl ConnectionReader,
+	connection_id: &ConnectionId,
+) -> Result<ConnectionId, Error> {
+	let connection_end = ctx.connection_end(connection_id).map_err(Error::ics03_connection)?;
+	connection_end
+		.counterparty()
+		.connection_id()
+		.cloned()
+		.ok_or_else(|| Error::counterparty_connection_not_found(connection_id.clone()))
+}
+
+/// Fails if the channel currently bound to the interchain account of the owner of `port_id` on
+/// `connection_id`, opened on `local_port_id`, is not closed. Ordered channels close on timeout,
+/// after which the account can be reached through a new channel.
+pub(crate) fn ensure_no_active_channel(
+	ctx: &impl ChannelReader,
+	connection_id: &ConnectionId,
+	port_id: &PortId,
+	local_port_id: &PortId,
+	active_channel: Option<ChannelId>,
+) -> Result<(), Error> {
+	let channel_id = match active_channel {
+		Some(channel_id) => channel_id,
+		None => return Ok(()),
+	};
+	let channel_end =
+		ctx.channel_end(&(local_port_id.clone(), channel_id)).map_err(Error::ics04_channel)?;
+	if channel_end.state_matches(&State::Closed) {
+		Ok(())
+	} else {
+		Err(Error::active_channel_exists(connection_id.clone(), port_id.clone(), channel_id))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::{Arc, Mutex};
+
+	use ibc_proto::google::protobuf::Any;
+
+	use crate::{
+		applications::interchain_accounts::{
+			acknowledgement::Acknowledgement,
+			context::{IcaControllerKeeper, IcaHostKeeper},
+			controller::{self, registration_version, send_tx},
+			controller_port_id,
+			error::ErrorDetail,
+			host,
+			metadata::Metadata,
+			packet::InterchainAccountPacketData,
+			CONTROLLER_MODULE_ID_STR, HOST_MODULE_ID_STR,
+		},
+		core::{
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				context::ChannelKeeper,
+				error::Error as Ics04Error,
+				packet::{Packet, Sequence},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			ics26_routing::context::{
+				Module, ModuleCallbackContext, ModuleId, ModuleOutputBuilder, OnRecvPacketAck,
+				Router,
+			},
+		},
+		events::IbcEvent,
+		handler::HandlerOutputBuilder,
+		mock::context::{MockClientTypes, MockContext, MockRouterBuilder},
+		prelude::*,
+		signer::Signer,
+		test_utils::{get_dummy_bech32_account, DummyTransferModule, DUMMY_ICA_MSG_TYPE_URL},
+		timestamp::{Timestamp, ZERO_DURATION},
+		Height,
+	};
+
+	type Ctx = DummyTransferModule<MockClientTypes>;
+
+	fn to_ics04(e: impl ToString) -> Ics04Error {
+		Ics04Error::implementation_specific(e.to_string())
+	}
+
+	/// Routes the channel callbacks of the controller ports to the ICS27 controller.
+	struct ControllerModule(Ctx);
+
+	impl Module for ControllerModule {
+		fn on_chan_open_init(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			order: Order,
+			connection_hops: &[ConnectionId],
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			counterparty: &Counterparty,
+			version: &Version,
+			_relayer: &Signer,
+		) -> Result<(), Ics04Error> {
+			controller::on_chan_open_init(
+				&mut self.0,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+			)
+			.map_err(to_ics04)
+		}
+
+		fn on_chan_open_try(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			order: Order,
+			connection_hops: &[ConnectionId],
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			counterparty: &Counterparty,
+			version: &Version,
+			counterparty_version: &Version,
+			_relayer: &Signer,
+		) -> Result<Version, Ics04Error> {
+			controller::on_chan_open_try(
+				&mut self.0,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+				counterparty_version,
+			)
+			.map_err(to_ics04)
+		}
+
+		fn on_chan_open_ack(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			counterparty_version: &Version,
+			_relayer: &Signer,
+		) -> Result<(), Ics04Error> {
+			controller::on_chan_open_ack(
+				&mut self.0,
+				output,
+				port_id,
+				channel_id,
+				counterparty_version,
+			)
+			.map_err(to_ics04)
+		}
+
+		fn on_chan_open_confirm(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			_relayer: &Signer,
+		) -> Result<(), Ics04Error> {
+			controller::on_chan_open_confirm(&mut self.0, output, port_id, channel_id)
+				.map_err(to_ics04)
+		}
+	}
+
+	/// Routes the channel callbacks of the host port to the ICS27 host, keeping the
+	/// acknowledgements of the packets it receives.
+	struct HostModule {
+		ctx: Ctx,
+		acks: Arc<Mutex<Vec<Acknowledgement>>>,
+	}
+
+	impl Module for HostModule {
+		fn on_chan_open_init(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			order: Order,
+			connection_hops: &[ConnectionId],
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			counterparty: &Counterparty,
+			version: &Version,
+			_relayer: &Signer,
+		) -> Result<(), Ics04Error> {
+			host::on_chan_open_init(
+				&mut self.ctx,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+			)
+			.map_err(to_ics04)
+		}
+
+		fn on_chan_open_try(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			order: Order,
+			connection_hops: &[ConnectionId],
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			counterparty: &Counterparty,
+			version: &Version,
+			counterparty_version: &Version,
+			_relayer: &Signer,
+		) -> Result<Version, Ics04Error> {
+			host::on_chan_open_try(
+				&mut self.ctx,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+				counterparty_version,
+			)
+			.map_err(to_ics04)
+		}
+
+		fn on_chan_open_ack(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			counterparty_version: &Version,
+			_relayer: &Signer,
+		) -> Result<(), Ics04Error> {
+			host::on_chan_open_ack(&mut self.ctx, output, port_id, channel_id, counterparty_version)
+				.map_err(to_ics04)
+		}
+
+		fn on_chan_open_confirm(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			port_id: &PortId,
+			channel_id: &ChannelId,
+			_relayer: &Signer,
+		) -> Result<(), Ics04Error> {
+			host::on_chan_open_confirm(&mut self.ctx, output, port_id, channel_id)
+				.map_err(to_ics04)
+		}
+
+		fn on_recv_packet(
+			&self,
+			_ctx: &dyn ModuleCallbackContext,
+			output: &mut ModuleOutputBuilder,
+			packet: &Packet,
+			_relayer: &Signer,
+		) -> Result<OnRecvPacketAck, Ics04Error> {
+			let ack = host::on_recv_packet(&mut self.ctx.clone(), output, packet);
+			self.acks.lock().unwrap().push(ack);
+			Ok(OnRecvPacketAck::Async)
+		}
+	}
+
+	struct Chain {
+		ctx: MockContext<MockClientTypes>,
+		module: Ctx,
+		module_id: ModuleId,
+	}
+
+	impl Chain {
+		fn route(&mut self) -> &mut dyn Module {
+			self.ctx.router.get_route_mut(&self.module_id).unwrap()
+		}
+	}
+
+	/// A context with an open `connection_id` to `counterparty_connection_id`, and a module
+	/// sharing its store.
+	fn connected_context(
+		connection_id: u64,
+		counterparty_connection_id: u64,
+	) -> (MockContext<MockClientTypes>, Ctx) {
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::new(counterparty_connection_id)),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), Height::new(0, 10))
+			.with_connection(ConnectionId::new(connection_id), connection_end);
+		let module = DummyTransferModule::new(ctx.ibc_store_share());
+		(ctx, module)
+	}
+
+	/// A controller chain on connection-0, to the host's connection-1.
+	fn controller_chain() -> Chain {
+		let (ctx, module) = connected_context(0, 1);
+		let module_id: ModuleId = CONTROLLER_MODULE_ID_STR.parse().unwrap();
+		let router = MockRouterBuilder::default()
+			.add_route(module_id.clone(), ControllerModule(module.clone()))
+			.unwrap()
+			.build();
+		Chain { ctx: ctx.with_router(router), module, module_id }
+	}
+
+	/// A host chain on connection-1, to the controller's connection-0, and the acknowledgements
+	/// of the packets it receives.
+	fn host_chain() -> (Chain, Arc<Mutex<Vec<Acknowledgement>>>) {
+		let (ctx, module) = connected_context(1, 0);
+		let module_id: ModuleId = HOST_MODULE_ID_STR.parse().unwrap();
+		let acks = Arc::new(Mutex::new(Vec::new()));
+		let host_module = HostModule { ctx: module.clone(), acks: acks.clone() };
+		let router =
+			MockRouterBuilder::default().add_route(module_id.clone(), host_module).unwrap().build();
+		(Chain { ctx: ctx.with_router(router), module, module_id }, acks)
+	}
+
+	fn relayer() -> Signer {
+		get_dummy_bech32_account().parse().unwrap()
+	}
+
+	/// Runs the handshake of a channel registering the interchain account of `owner`, over
+	/// `channel_id` on both chains, storing the channel ends like the ICS4 handlers do.
+	fn register(
+		controller: &mut Chain,
+		host: &mut Chain,
+		owner: &str,
+		channel_id: ChannelId,
+	) -> Result<(), Ics04Error> {
+		// The callbacks only use the contexts of their modules.
+		let cb_ctx = MockContext::<MockClientTypes>::default();
+		let port_id = controller_port_id(owner).unwrap();
+		let controller_connection = vec![ConnectionId::new(0)];
+		let host_connection = vec![ConnectionId::new(1)];
+		let version = registration_version(&controller.module, &controller_connection[0]).unwrap();
+
+		let counterparty = Counterparty::new(PortId::ica_host(), None);
+		controller.route().on_chan_open_init(
+			&cb_ctx,
+			&mut ModuleOutputBuilder::new(),
+			Order::Ordered,
+			&controller_connection,
+			&port_id,
+			&channel_id,
+			&counterparty,
+			&version,
+			&relayer(),
+		)?;
+		let channel_end = ChannelEnd::new(
+			State::Init,
+			Order::Ordered,
+			counterparty,
+			controller_connection.clone(),
+			version.clone(),
+		);
+		controller.ctx.store_channel((port_id.clone(), channel_id), &channel_end)?;
+
+		let counterparty = Counterparty::new(port_id.clone(), Some(channel_id));
+		let host_version = host.route().on_chan_open_try(
+			&cb_ctx,
+			&mut ModuleOutputBuilder::new(),
+			Order::Ordered,
+			&host_connection,
+			&PortId::ica_host(),
+			&channel_id,
+			&counterparty,
+			&Version::empty(),
+			&version,
+			&relayer(),
+		)?;
+		let mut host_channel_end = ChannelEnd::new(
+			State::TryOpen,
+			Order::Ordered,
+			counterparty,
+			host_connection,
+			host_version.clone(),
+		);
+		host.ctx.store_channel((PortId::ica_host(), channel_id), &host_channel_end)?;
+
+		controller.route().on_chan_open_ack(
+			&cb_ctx,
+			&mut ModuleOutputBuilder::new(),
+			&port_id,
+			&channel_id,
+			&host_version,
+			&relayer(),
+		)?;
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			Counterparty::new(PortId::ica_host(), Some(channel_id)),
+			controller_connection,
+			host_version,
+		);
+		controller.ctx.store_channel((port_id.clone(), channel_id), &channel_end)?;
+		controller.ctx.store_next_sequence_send((port_id, channel_id), Sequence::from(1))?;
+
+		host.route().on_chan_open_confirm(
+			&cb_ctx,
+			&mut ModuleOutputBuilder::new(),
+			&PortId::ica_host(),
+			&channel_id,
+			&relayer(),
+		)?;
+		host_channel_end.set_state(State::Open);
+		host.ctx.store_channel((PortId::ica_host(), channel_id), &host_channel_end)
+	}
+
+	/// Sends the messages to the interchain account of `owner` and delivers them to the host.
+	fn execute(controller: &mut Chain, host: &mut Chain, owner: &str, messages: Vec<Any>) {
+		let mut output = HandlerOutputBuilder::new();
+		let data = InterchainAccountPacketData::execute_tx(messages, String::new());
+		send_tx(
+			&mut controller.module,
+			&mut output,
+			&ConnectionId::new(0),
+			&controller_port_id(owner).unwrap(),
+			data,
+			Height::new(0, 100),
+			Timestamp::none(),
+		)
+		.unwrap();
+		let packet = output
+			.with_result(())
+			.events
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::SendPacket(e) => Some(e.packet),
+				_ => None,
+			})
+			.expect("no packet was sent");
+
+		host.route()
+			.on_recv_packet(
+				&MockContext::<MockClientTypes>::default(),
+				&mut ModuleOutputBuilder::new(),
+				&packet,
+				&relayer(),
+			)
+			.unwrap();
+	}
+
+	fn dummy_message(value: Vec<u8>) -> Any {
+		Any { type_url: DUMMY_ICA_MSG_TYPE_URL.to_string(), value }
+	}
+
+	#[test]
+	fn test_register_account_and_execute_tx() {
+		let mut controller = controller_chain();
+		let (mut host, acks) = host_chain();
+		register(&mut controller, &mut host, "alice", ChannelId::new(0)).unwrap();
+		register(&mut controller, &mut host, "bob", ChannelId::new(1)).unwrap();
+
+		let alice = controller_port_id("alice").unwrap();
+		let bob = controller_port_id("bob").unwrap();
+		let controller_conn = ConnectionId::new(0);
+		let host_conn = ConnectionId::new(1);
+		assert_eq!(
+			IcaControllerKeeper::get_active_channel(&controller.module, &controller_conn, &bob),
+			Some(ChannelId::new(1))
+		);
+		assert_eq!(
+			IcaHostKeeper::get_active_channel(&host.module, &host_conn, &bob),
+			Some(ChannelId::new(1))
+		);
+		let account = host.module.get_interchain_account(&host_conn, &alice).unwrap();
+		assert_eq!(
+			controller.module.get_interchain_account_address(&controller_conn, &alice),
+			Some(account.clone())
+		);
+		let bob_account = host.module.get_interchain_account(&host_conn, &bob).unwrap();
+		assert_ne!(bob_account, account);
+
+		let messages = vec![dummy_message(vec![7]), dummy_message(vec![8])];
+		execute(&mut controller, &mut host, "alice", messages.clone());
+
+		let acks = acks.lock().unwrap();
+		assert_eq!(acks.as_slice(), &[Acknowledgement::Result(vec![7, 8])]);
+		assert_eq!(serde_json::to_string(&acks[0]).unwrap(), r#"{"result":"Bwg="}"#);
+		assert_eq!(
+			host.module.ica_executed(),
+			messages.into_iter().map(|m| (account.clone(), m)).collect::<Vec<_>>()
+		);
+	}
+
+	#[test]
+	fn test_failed_execution_is_acknowledged_as_error() {
+		let mut controller = controller_chain();
+		let (mut host, acks) = host_chain();
+		register(&mut controller, &mut host, "alice", ChannelId::new(0)).unwrap();
+
+		let unsupported =
+			Any { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: vec![] };
+		execute(&mut controller, &mut host, "alice", vec![dummy_message(vec![7]), unsupported]);
+
+		let acks = acks.lock().unwrap();
+		assert_eq!(acks.len(), 1);
+		assert!(!acks[0].is_successful());
+		assert!(host.module.ica_executed().is_empty());
+	}
+
+	#[test]
+	fn test_handshake_validates_metadata_and_active_channel() {
+		let mut controller = controller_chain();
+		let (mut host, _) = host_chain();
+		let cb_ctx = MockContext::<MockClientTypes>::default();
+		let port_id = controller_port_id("alice").unwrap();
+		let init = |controller: &mut Chain, order: Order, version: &Version| {
+			controller.route().on_chan_open_init(
+				&cb_ctx,
+				&mut ModuleOutputBuilder::new(),
+				order,
+				&[ConnectionId::new(0)],
+				&port_id,
+				&ChannelId::new(2),
+				&Counterparty::new(PortId::ica_host(), None),
+				version,
+				&relayer(),
+			)
+		};
+
+		let version = registration_version(&controller.module, &ConnectionId::new(0)).unwrap();
+		assert!(init(&mut controller, Order::Unordered, &version).is_err());
+		let wrong_host = Metadata::new(ConnectionId::new(0), ConnectionId::new(0));
+		assert!(init(&mut controller, Order::Ordered, &wrong_host.into()).is_err());
+		let json_encoded = Metadata {
+			encoding: "json".to_string(),
+			..Metadata::new(ConnectionId::new(0), ConnectionId::new(1))
+		};
+		assert!(init(&mut controller, Order::Ordered, &json_encoded.into()).is_err());
+
+		// The host does not start handshakes.
+		assert!(host
+			.route()
+			.on_chan_open_init(
+				&cb_ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Ordered,
+				&[ConnectionId::new(1)],
+				&PortId::ica_host(),
+				&ChannelId::new(2),
+				&Counterparty::new(port_id.clone(), None),
+				&version,
+				&relayer(),
+			)
+			.is_err());
+
+		// A second channel cannot be opened while the account is reachable through the first.
+		register(&mut controller, &mut host, "alice", ChannelId::new(0)).unwrap();
+		assert!(init(&mut controller, Order::Ordered, &version).is_err());
+		let err = controller::on_chan_open_init(
+			&mut controller.module,
+			&mut ModuleOutputBuilder::new(),
+			Order::Ordered,
+			&[ConnectionId::new(0)],
+			&port_id,
+			&ChannelId::new(2),
+			&Counterparty::new(PortId::ica_host(), None),
+			&version,
+		)
+		.unwrap_err();
+		assert!(matches!(err.detail(), ErrorDetail::ActiveChannelExists(_)));
+	}
+}
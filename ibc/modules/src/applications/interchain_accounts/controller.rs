@@ -0,0 +1,241 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The controller end of the interchain accounts, registering accounts for their owners and
+//! sending them transactions to execute on the host chain.
+
+use crate::{
+	applications::interchain_accounts::{
+		context::{counterparty_connection_id, ensure_no_active_channel, IcaControllerContext},
+		error::Error,
+		is_controller_port,
+		metadata::Metadata,
+		packet::{InterchainAccountPacketData, Type},
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			handler::send_packet::send_packet,
+			packet::{Packet, Sequence},
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::ModuleOutputBuilder,
+	},
+	handler::{HandlerOutput, HandlerOutputBuilder},
+	prelude::*,
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
+
+/// Returns the version a channel registering an interchain account on `connection_id` should be
+/// opened with, by a channel open init from the controller port of the account's owner to the
+/// host port.
+pub fn registration_version(
+	ctx: &impl IcaControllerContext,
+	connection_id: &ConnectionId,
+) -> Result<Version, Error> {
+	let host_connection_id = counterparty_connection_id(ctx, connection_id)?;
+	Ok(Metadata::new(connection_id.clone(), host_connection_id).into())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_init(
+	ctx: &mut impl IcaControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	_channel_id: &ChannelId,
+	counterparty: &Counterparty,
+	version: &Version,
+) -> Result<(), Error> {
+	if order != Order::Ordered {
+		return Err(Error::channel_not_ordered(order))
+	}
+
+	if !is_controller_port(port_id) {
+		return Err(Error::invalid_controller_port(port_id.clone()))
+	}
+
+	if counterparty.port_id() != &PortId::ica_host() {
+		return Err(Error::invalid_host_port(counterparty.port_id().clone()))
+	}
+
+	let connection_id = &connection_hops[0];
+	let metadata = Metadata::from_version(version)?;
+	metadata.validate(connection_id, &counterparty_connection_id(ctx, connection_id)?)?;
+
+	let active_channel = ctx.get_active_channel(connection_id, port_id);
+	ensure_no_active_channel(ctx, connection_id, port_id, port_id, active_channel)?;
+
+	// A new channel to an existing account must not propose another address.
+	if !metadata.address.is_empty() {
+		if let Some(address) = ctx.get_interchain_account_address(connection_id, port_id) {
+			if address.as_ref() != metadata.address {
+				return Err(Error::address_mismatch(address.to_string(), metadata.address))
+			}
+		}
+	}
+
+	Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_try(
+	_ctx: &mut impl IcaControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	_order: Order,
+	_connection_hops: &[ConnectionId],
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+	_counterparty: &Counterparty,
+	_version: &Version,
+	_counterparty_version: &Version,
+) -> Result<Version, Error> {
+	Err(Error::handshake_step_not_allowed("channel open try".to_string()))
+}
+
+/// Records the channel and the address of the interchain account registered by the host.
+pub fn on_chan_open_ack(
+	ctx: &mut impl IcaControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	counterparty_version: &Version,
+) -> Result<(), Error> {
+	let metadata = Metadata::from_version(counterparty_version)?;
+	if metadata.address.is_empty() {
+		return Err(Error::empty_address())
+	}
+	let address: Signer = metadata.address.parse().map_err(Error::signer)?;
+
+	let channel_end =
+		ctx.channel_end(&(port_id.clone(), *channel_id)).map_err(Error::ics04_channel)?;
+	let connection_id = &channel_end.connection_hops()[0];
+	metadata.validate(connection_id, &counterparty_connection_id(ctx, connection_id)?)?;
+
+	let active_channel = ctx.get_active_channel(connection_id, port_id);
+	ensure_no_active_channel(ctx, connection_id, port_id, port_id, active_channel)?;
+
+	if let Some(registered) = ctx.get_interchain_account_address(connection_id, port_id) {
+		if registered != address {
+			return Err(Error::address_mismatch(registered.to_string(), address.to_string()))
+		}
+	}
+
+	ctx.store_active_channel(connection_id.clone(), port_id.clone(), *channel_id)?;
+	ctx.store_interchain_account_address(connection_id.clone(), port_id.clone(), address)
+}
+
+pub fn on_chan_open_confirm(
+	_ctx: &mut impl IcaControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+) -> Result<(), Error> {
+	Err(Error::handshake_step_not_allowed("channel open confirm".to_string()))
+}
+
+pub fn on_chan_close_init(
+	_ctx: &mut impl IcaControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+) -> Result<(), Error> {
+	Err(Error::cant_close_channel())
+}
+
+pub fn on_recv_packet(
+	_ctx: &impl IcaControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	_packet: &Packet,
+) -> Result<(), Error> {
+	Err(Error::receive_not_supported())
+}
+
+/// Sends the transaction of `packet_data` to the interchain account of the owner of `port_id` on
+/// `connection_id`, returning the sequence of the packet carrying it.
+/// If this method returns an error, the runtime is expected to rollback all state modifications to
+/// the `Ctx` caused by all messages from the transaction that this call is a part of.
+pub fn send_tx<Ctx>(
+	ctx: &mut Ctx,
+	output: &mut HandlerOutputBuilder<()>,
+	connection_id: &ConnectionId,
+	port_id: &PortId,
+	packet_data: InterchainAccountPacketData,
+	timeout_height: Height,
+	timeout_timestamp: Timestamp,
+) -> Result<Sequence, Error>
+where
+	Ctx: IcaControllerContext,
+{
+	if timeout_height.is_zero() && timeout_timestamp == Timestamp::none() {
+		return Err(Error::invalid_timeout())
+	}
+
+	if packet_data.packet_type != Type::ExecuteTx {
+		return Err(Error::unsupported_packet_type(packet_data.packet_type as i32))
+	}
+	if packet_data.messages()?.is_empty() {
+		return Err(Error::empty_messages())
+	}
+
+	let source_channel = ctx
+		.get_active_channel(connection_id, port_id)
+		.ok_or_else(|| Error::no_active_channel(connection_id.clone(), port_id.clone()))?;
+
+	let source_channel_end =
+		ctx.channel_end(&(port_id.clone(), source_channel)).map_err(Error::ics04_channel)?;
+
+	let destination_port = source_channel_end.counterparty().port_id().clone();
+	let destination_channel = *source_channel_end
+		.counterparty()
+		.channel_id()
+		.ok_or_else(|| Error::destination_channel_not_found(port_id.clone(), source_channel))?;
+
+	// get the next sequence
+	let sequence = ctx
+		.get_next_sequence_send(&(port_id.clone(), source_channel))
+		.map_err(Error::ics04_channel)?;
+
+	let data = serde_json::to_vec(&packet_data)
+		.expect("InterchainAccountPacketData's infallible Serialize impl failed");
+
+	let packet = Packet {
+		sequence,
+		source_port: port_id.clone(),
+		source_channel,
+		destination_port,
+		destination_channel,
+		data,
+		timeout_height,
+		timeout_timestamp,
+	};
+
+	let HandlerOutput { result, log, events } =
+		send_packet(ctx, packet).map_err(Error::ics04_channel)?;
+
+	ctx.store_packet_result(result).map_err(Error::ics04_channel)?;
+
+	output.merge_output(HandlerOutput::builder().with_log(log).with_events(events).with_result(()));
+
+	output.log(format!(
+		"IBC interchain account transaction sent over {}/{} with sequence {}",
+		port_id, source_channel, sequence
+	));
+
+	Ok(sequence)
+}
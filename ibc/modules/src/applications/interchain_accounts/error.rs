@@ -0,0 +1,146 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use flex_error::define_error;
+
+use crate::{
+	core::{
+		ics03_connection::error as connection_error,
+		ics04_channel::{channel::Order, error as channel_error},
+		ics24_host::{
+			error::ValidationError,
+			identifier::{ChannelId, ConnectionId, PortId},
+		},
+	},
+	prelude::*,
+	signer::SignerError,
+};
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		Ics03Connection
+			[ connection_error::Error ]
+			|_ | { "Ics03 connection error" },
+
+		Ics04Channel
+			[ channel_error::Error ]
+			|_ | { "Ics04 channel error" },
+
+		EmptyOwner
+			|_| { "owner of an interchain account is empty" },
+
+		InvalidPortId
+			{ owner: String }
+			[ ValidationError ]
+			| e | { format_args!("invalid controller port identifier for owner {0}", e.owner) },
+
+		InvalidControllerPort
+			{ port_id: PortId }
+			| e | { format_args!("invalid controller port: '{0}', expected prefix '{1}'", e.port_id, super::CONTROLLER_PORT_PREFIX) },
+
+		InvalidHostPort
+			{ port_id: PortId }
+			| e | { format_args!("invalid host port: '{0}', expected '{1}'", e.port_id, super::HOST_PORT_ID_STR) },
+
+		ChannelNotOrdered
+			{ order: Order }
+			| e | { format_args!("expected '{0}' channel, got '{1}'", Order::Ordered, e.order) },
+
+		HandshakeStepNotAllowed
+			{ step: String }
+			| e | { format_args!("{0} is not allowed on this end of an interchain account channel", e.step) },
+
+		CantCloseChannel
+			| _ | { "interchain account channels cannot be closed by the application" },
+
+		MetadataDeserialization
+			{ version: String }
+			| e | { format_args!("failed to deserialize interchain account metadata from version '{0}'", e.version) },
+
+		InvalidVersion
+			{ version: String }
+			| e | { format_args!("expected version '{0}', got '{1}'", super::VERSION, e.version) },
+
+		UnsupportedEncoding
+			{ encoding: String }
+			| e | { format_args!("unsupported encoding '{0}', expected '{1}'", e.encoding, super::ENCODING_PROTO3) },
+
+		UnsupportedTxType
+			{ tx_type: String }
+			| e | { format_args!("unsupported transaction type '{0}', expected '{1}'", e.tx_type, super::TX_TYPE_SDK_MULTI_MSG) },
+
+		ConnectionMismatch
+			{ expected: ConnectionId, actual: ConnectionId }
+			| e | { format_args!("metadata connection '{1}' does not match channel connection '{0}'", e.expected, e.actual) },
+
+		CounterpartyConnectionNotFound
+			{ connection_id: ConnectionId }
+			| e | { format_args!("counterparty of connection {0} has no connection id", e.connection_id) },
+
+		EmptyAddress
+			| _ | { "interchain account address is empty" },
+
+		AddressMismatch
+			{ expected: String, actual: String }
+			| e | { format_args!("metadata address '{1}' does not match registered interchain account '{0}'", e.expected, e.actual) },
+
+		ActiveChannelExists
+			{ connection_id: ConnectionId, port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("interchain account of port {1} on connection {0} already has active channel {2}", e.connection_id, e.port_id, e.channel_id) },
+
+		NoActiveChannel
+			{ connection_id: ConnectionId, port_id: PortId }
+			| e | { format_args!("no active channel for port {1} on connection {0}", e.connection_id, e.port_id) },
+
+		DestinationChannelNotFound
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("destination channel not found in the counterparty of port_id {0} and channel_id {1} ", e.port_id, e.channel_id) },
+
+		AccountNotFound
+			{ connection_id: ConnectionId, port_id: PortId }
+			| e | { format_args!("no interchain account registered for port {1} on connection {0}", e.connection_id, e.port_id) },
+
+		InvalidTimeout
+			| _ | { "packet timeout height and timestamp cannot both be zero" },
+
+		PacketDataDeserialization
+			| _ | { "failed to deserialize packet data" },
+
+		UnsupportedPacketType
+			{ packet_type: i32 }
+			| e | { format_args!("unsupported interchain account packet type {0}", e.packet_type) },
+
+		CosmosTxDeserialization
+			| _ | { "failed to deserialize the transaction of an interchain account packet" },
+
+		EmptyMessages
+			| _ | { "interchain account transaction carries no messages" },
+
+		ReceiveNotSupported
+			| _ | { "controller chains do not receive interchain account packets" },
+
+		Signer
+			[ SignerError ]
+			| _ | { "failed to parse signer" },
+
+		ExecutionFailed
+			{ reason: String }
+			| e | { format_args!("failed to execute interchain account transaction: {}", e.reason) },
+
+		ImplementationSpecific
+			{ reason: String }
+			| e | { format_args!("implementation specific error: {}", e.reason) },
+	}
+}
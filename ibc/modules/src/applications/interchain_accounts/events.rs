@@ -0,0 +1,44 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::interchain_accounts::HOST_MODULE_ID_STR,
+	core::ics24_host::identifier::{ChannelId, PortId},
+	events::ModuleEvent,
+	prelude::*,
+};
+
+const EVENT_TYPE_PACKET: &str = "ics27_packet";
+
+/// Emitted by the host for every interchain account packet it receives.
+pub struct RecvEvent {
+	pub controller_port_id: PortId,
+	pub host_channel_id: ChannelId,
+	pub success: bool,
+}
+
+impl From<RecvEvent> for ModuleEvent {
+	fn from(ev: RecvEvent) -> Self {
+		let RecvEvent { controller_port_id, host_channel_id, success } = ev;
+		Self {
+			kind: EVENT_TYPE_PACKET.to_string(),
+			module_name: HOST_MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![
+				("controller_port_id", controller_port_id).into(),
+				("host_channel_id", host_channel_id).into(),
+				("success", success).into(),
+			],
+		}
+	}
+}
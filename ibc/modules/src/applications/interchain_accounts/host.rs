@@ -0,0 +1,186 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The host end of the interchain accounts, registering the accounts requested by controller
+//! chains and executing their transactions.
+
+use crate::{
+	applications::interchain_accounts::{
+		acknowledgement::Acknowledgement,
+		context::{counterparty_connection_id, ensure_no_active_channel, IcaHostContext},
+		error::Error,
+		events::RecvEvent,
+		is_controller_port,
+		metadata::Metadata,
+		packet::{InterchainAccountPacketData, Type},
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::ModuleOutputBuilder,
+	},
+	prelude::*,
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_init(
+	_ctx: &mut impl IcaHostContext,
+	_output: &mut ModuleOutputBuilder,
+	_order: Order,
+	_connection_hops: &[ConnectionId],
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+	_counterparty: &Counterparty,
+	_version: &Version,
+) -> Result<(), Error> {
+	Err(Error::handshake_step_not_allowed("channel open init".to_string()))
+}
+
+/// Registers the interchain account requested by the controller, unless the owner already has
+/// one on this connection, and returns the version of the channel carrying its address.
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_try(
+	ctx: &mut impl IcaHostContext,
+	_output: &mut ModuleOutputBuilder,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	_channel_id: &ChannelId,
+	counterparty: &Counterparty,
+	_version: &Version,
+	counterparty_version: &Version,
+) -> Result<Version, Error> {
+	if order != Order::Ordered {
+		return Err(Error::channel_not_ordered(order))
+	}
+
+	if port_id != &PortId::ica_host() {
+		return Err(Error::invalid_host_port(port_id.clone()))
+	}
+
+	let controller_port_id = counterparty.port_id();
+	if !is_controller_port(controller_port_id) {
+		return Err(Error::invalid_controller_port(controller_port_id.clone()))
+	}
+
+	let connection_id = &connection_hops[0];
+	let mut metadata = Metadata::from_version(counterparty_version)?;
+	metadata.validate(&counterparty_connection_id(ctx, connection_id)?, connection_id)?;
+
+	let active_channel = ctx.get_active_channel(connection_id, controller_port_id);
+	ensure_no_active_channel(ctx, connection_id, controller_port_id, port_id, active_channel)?;
+
+	let address = match ctx.get_interchain_account(connection_id, controller_port_id) {
+		Some(address) => address,
+		None => {
+			let address = ctx.generate_address(connection_id, controller_port_id)?;
+			ctx.register_interchain_account(
+				connection_id.clone(),
+				controller_port_id.clone(),
+				address.clone(),
+			)?;
+			address
+		},
+	};
+
+	if !metadata.address.is_empty() && metadata.address != address.as_ref() {
+		return Err(Error::address_mismatch(address.to_string(), metadata.address))
+	}
+	metadata.address = address.to_string();
+
+	Ok(metadata.into())
+}
+
+pub fn on_chan_open_ack(
+	_ctx: &mut impl IcaHostContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+	_counterparty_version: &Version,
+) -> Result<(), Error> {
+	Err(Error::handshake_step_not_allowed("channel open ack".to_string()))
+}
+
+/// Binds the opened channel to the interchain account of its controller port.
+pub fn on_chan_open_confirm(
+	ctx: &mut impl IcaHostContext,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+) -> Result<(), Error> {
+	let channel_end =
+		ctx.channel_end(&(port_id.clone(), *channel_id)).map_err(Error::ics04_channel)?;
+	let connection_id = channel_end.connection_hops()[0].clone();
+	let controller_port_id = channel_end.counterparty().port_id().clone();
+	ctx.store_active_channel(connection_id, controller_port_id, *channel_id)
+}
+
+pub fn on_chan_close_init(
+	_ctx: &mut impl IcaHostContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+) -> Result<(), Error> {
+	Err(Error::cant_close_channel())
+}
+
+/// Executes the transaction of the packet with the interchain account bound to its channel. The
+/// failure to execute it is acknowledged to the controller as an error acknowledgement.
+pub fn on_recv_packet(
+	ctx: &mut impl IcaHostContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+) -> Acknowledgement {
+	let ack = match execute_packet(ctx, packet) {
+		Ok(result) => Acknowledgement::Result(result),
+		Err(e) => Acknowledgement::from_error(e),
+	};
+
+	let recv_event = RecvEvent {
+		controller_port_id: packet.source_port.clone(),
+		host_channel_id: packet.destination_channel,
+		success: ack.is_successful(),
+	};
+	output.emit(recv_event.into());
+
+	ack
+}
+
+fn execute_packet(ctx: &mut impl IcaHostContext, packet: &Packet) -> Result<Vec<u8>, Error> {
+	let data = serde_json::from_slice::<InterchainAccountPacketData>(&packet.data)
+		.map_err(|_| Error::packet_data_deserialization())?;
+	if data.packet_type != Type::ExecuteTx {
+		return Err(Error::unsupported_packet_type(data.packet_type as i32))
+	}
+
+	let channel_end = ctx
+		.channel_end(&(packet.destination_port.clone(), packet.destination_channel))
+		.map_err(Error::ics04_channel)?;
+	let connection_id = &channel_end.connection_hops()[0];
+	let account =
+		ctx.get_interchain_account(connection_id, &packet.source_port).ok_or_else(|| {
+			Error::account_not_found(connection_id.clone(), packet.source_port.clone())
+		})?;
+
+	let messages = data.messages()?;
+	if messages.is_empty() {
+		return Err(Error::empty_messages())
+	}
+
+	ctx.execute(&account, messages)
+}
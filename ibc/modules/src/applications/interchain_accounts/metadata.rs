@@ -0,0 +1,122 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+use super::{error::Error, ENCODING_PROTO3, TX_TYPE_SDK_MULTI_MSG, VERSION};
+use crate::{
+	core::{ics04_channel::Version, ics24_host::identifier::ConnectionId},
+	prelude::*,
+};
+
+/// The version of an interchain account channel, negotiated during its handshake:
+///
+/// ```json
+/// {"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-1","address":"","encoding":"proto3","tx_type":"sdk_multi_msg"}
+/// ```
+///
+/// The controller proposes it with an empty address, which the host fills in with the address of
+/// the interchain account it registered.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+	/// Version of the interchain accounts application
+	pub version: String,
+	/// Connection the channel is built on, on the controller chain
+	pub controller_connection_id: ConnectionId,
+	/// Connection the channel is built on, on the host chain
+	pub host_connection_id: ConnectionId,
+	/// Address of the interchain account on the host chain, empty until the host registered it
+	#[serde(default)]
+	pub address: String,
+	/// Encoding of the messages executed by the interchain account
+	pub encoding: String,
+	/// Type of the transactions executed by the interchain account
+	pub tx_type: String,
+}
+
+impl Metadata {
+	pub fn new(controller_connection_id: ConnectionId, host_connection_id: ConnectionId) -> Self {
+		Self {
+			version: VERSION.to_string(),
+			controller_connection_id,
+			host_connection_id,
+			address: String::new(),
+			encoding: ENCODING_PROTO3.to_string(),
+			tx_type: TX_TYPE_SDK_MULTI_MSG.to_string(),
+		}
+	}
+
+	pub fn from_version(version: &Version) -> Result<Self, Error> {
+		serde_json::from_str(&version.to_string())
+			.map_err(|_| Error::metadata_deserialization(version.to_string()))
+	}
+
+	/// Checks that the metadata describes a supported interchain account built on the given
+	/// connections.
+	pub fn validate(
+		&self,
+		controller_connection_id: &ConnectionId,
+		host_connection_id: &ConnectionId,
+	) -> Result<(), Error> {
+		if self.version != VERSION {
+			return Err(Error::invalid_version(self.version.clone()))
+		}
+		if self.encoding != ENCODING_PROTO3 {
+			return Err(Error::unsupported_encoding(self.encoding.clone()))
+		}
+		if self.tx_type != TX_TYPE_SDK_MULTI_MSG {
+			return Err(Error::unsupported_tx_type(self.tx_type.clone()))
+		}
+		if &self.controller_connection_id != controller_connection_id {
+			return Err(Error::connection_mismatch(
+				controller_connection_id.clone(),
+				self.controller_connection_id.clone(),
+			))
+		}
+		if &self.host_connection_id != host_connection_id {
+			return Err(Error::connection_mismatch(
+				host_connection_id.clone(),
+				self.host_connection_id.clone(),
+			))
+		}
+		Ok(())
+	}
+}
+
+impl From<Metadata> for Version {
+	fn from(metadata: Metadata) -> Self {
+		let version =
+			serde_json::to_string(&metadata).expect("Metadata's infallible Serialize impl failed");
+		Version::new(version)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn metadata_matches_ibc_go_encoding() {
+		let json = r#"{"version":"ics27-1","controller_connection_id":"connection-0","host_connection_id":"connection-1","address":"","encoding":"proto3","tx_type":"sdk_multi_msg"}"#;
+		let metadata = Metadata::new(ConnectionId::new(0), ConnectionId::new(1));
+		assert_eq!(Version::from(metadata.clone()).to_string(), json);
+		assert_eq!(Metadata::from_version(&Version::new(json.to_string())).unwrap(), metadata);
+
+		assert!(metadata.validate(&ConnectionId::new(0), &ConnectionId::new(1)).is_ok());
+		assert!(metadata.validate(&ConnectionId::new(1), &ConnectionId::new(0)).is_err());
+		let json_encoded = Metadata { encoding: "json".to_string(), ..metadata };
+		assert!(json_encoded.validate(&ConnectionId::new(0), &ConnectionId::new(1)).is_err());
+		assert!(Metadata::from_version(&Version::new("ics27-1".to_string())).is_err());
+	}
+}
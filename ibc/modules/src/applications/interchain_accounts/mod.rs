@@ -0,0 +1,65 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ICS 27: Interchain Accounts lets a controller chain register accounts on a host chain and
+//! execute transactions with them. Every account is bound to an ordered channel, negotiated by a
+//! channel handshake whose version is the JSON encoded [`metadata::Metadata`] of the account.
+pub mod acknowledgement;
+pub mod context;
+pub mod controller;
+pub mod error;
+pub mod events;
+pub mod host;
+pub mod metadata;
+pub mod packet;
+
+use crate::{core::ics24_host::identifier::PortId, prelude::*};
+use error::Error;
+
+/// Module identifier for the ICS27 controller application.
+pub const CONTROLLER_MODULE_ID_STR: &str = "icacontroller";
+
+/// Module identifier for the ICS27 host application.
+pub const HOST_MODULE_ID_STR: &str = "icahost";
+
+/// The port identifier that the ICS27 host application binds with.
+pub const HOST_PORT_ID_STR: &str = "icahost";
+
+/// The prefix of the controller ports, each owner of interchain accounts binds with
+/// `icacontroller-{owner}`.
+pub const CONTROLLER_PORT_PREFIX: &str = "icacontroller-";
+
+/// ICS27 application current version.
+pub const VERSION: &str = "ics27-1";
+
+/// The only supported encoding of the transactions.
+pub const ENCODING_PROTO3: &str = "proto3";
+
+/// The only supported transaction type, a list of messages executed atomically.
+pub const TX_TYPE_SDK_MULTI_MSG: &str = "sdk_multi_msg";
+
+/// Returns the controller port of an owner.
+pub fn controller_port_id(owner: &str) -> Result<PortId, Error> {
+	if owner.trim().is_empty() {
+		return Err(Error::empty_owner())
+	}
+	format!("{}{}", CONTROLLER_PORT_PREFIX, owner)
+		.parse()
+		.map_err(|e| Error::invalid_port_id(owner.to_string(), e))
+}
+
+/// Returns true iff `port_id` is a controller port.
+pub fn is_controller_port(port_id: &PortId) -> bool {
+	port_id.as_str().starts_with(CONTROLLER_PORT_PREFIX)
+}
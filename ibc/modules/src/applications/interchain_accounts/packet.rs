@@ -0,0 +1,130 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::applications::interchain_accounts::v1::{
+		CosmosTx as RawCosmosTx, InterchainAccountPacketData as RawPacketData,
+	},
+};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+pub use ibc_proto::ibc::applications::interchain_accounts::v1::Type;
+
+use super::error::Error;
+use crate::prelude::*;
+
+/// The ICS27 packet payload, sent by the controller for the host to execute with the interchain
+/// account bound to the channel. It is encoded as the proto3 JSON of ibc-go:
+///
+/// ```json
+/// {"type":"TYPE_EXECUTE_TX","data":"<base64 encoded CosmosTx>","memo":""}
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "JsonPacketData", into = "JsonPacketData")]
+pub struct InterchainAccountPacketData {
+	pub packet_type: Type,
+	/// The protobuf encoded `CosmosTx` carrying the messages to execute.
+	pub data: Vec<u8>,
+	pub memo: String,
+}
+
+impl InterchainAccountPacketData {
+	/// Returns the packet executing `messages`, all or none of them, with the interchain account.
+	pub fn execute_tx(messages: Vec<Any>, memo: String) -> Self {
+		Self {
+			packet_type: Type::ExecuteTx,
+			data: RawCosmosTx { messages }.encode_to_vec(),
+			memo,
+		}
+	}
+
+	/// Returns the messages of the `CosmosTx` carried by the packet.
+	pub fn messages(&self) -> Result<Vec<Any>, Error> {
+		let tx = RawCosmosTx::decode(self.data.as_slice())
+			.map_err(|_| Error::cosmos_tx_deserialization())?;
+		Ok(tx.messages)
+	}
+}
+
+impl TryFrom<RawPacketData> for InterchainAccountPacketData {
+	type Error = Error;
+
+	fn try_from(raw_pkt_data: RawPacketData) -> Result<Self, Self::Error> {
+		Ok(Self {
+			packet_type: Type::from_i32(raw_pkt_data.r#type)
+				.ok_or_else(|| Error::unsupported_packet_type(raw_pkt_data.r#type))?,
+			data: raw_pkt_data.data,
+			memo: raw_pkt_data.memo,
+		})
+	}
+}
+
+impl From<InterchainAccountPacketData> for RawPacketData {
+	fn from(pkt_data: InterchainAccountPacketData) -> Self {
+		Self { r#type: pkt_data.packet_type as i32, data: pkt_data.data, memo: pkt_data.memo }
+	}
+}
+
+/// The JSON form of [`InterchainAccountPacketData`], with the fields in proto order.
+#[derive(Serialize, Deserialize)]
+struct JsonPacketData {
+	r#type: String,
+	#[serde(with = "tendermint_proto::serializers::bytes::base64string")]
+	data: Vec<u8>,
+	#[serde(default)]
+	memo: String,
+}
+
+impl TryFrom<JsonPacketData> for InterchainAccountPacketData {
+	type Error = Error;
+
+	fn try_from(json: JsonPacketData) -> Result<Self, Self::Error> {
+		let packet_type = match json.r#type.as_str() {
+			"TYPE_UNSPECIFIED" => Type::Unspecified,
+			"TYPE_EXECUTE_TX" => Type::ExecuteTx,
+			_ => return Err(Error::packet_data_deserialization()),
+		};
+		Ok(Self { packet_type, data: json.data, memo: json.memo })
+	}
+}
+
+impl From<InterchainAccountPacketData> for JsonPacketData {
+	fn from(pkt_data: InterchainAccountPacketData) -> Self {
+		Self {
+			r#type: pkt_data.packet_type.as_str_name().to_string(),
+			data: pkt_data.data,
+			memo: pkt_data.memo,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn packet_data_matches_ibc_go_json() {
+		let message = Any { type_url: "/ibc.mock.MsgDummy".to_string(), value: vec![1, 2, 3] };
+		let data = InterchainAccountPacketData::execute_tx(vec![message.clone()], String::new());
+		let json = r#"{"type":"TYPE_EXECUTE_TX","data":"ChkKEi9pYmMubW9jay5Nc2dEdW1teRIDAQID","memo":""}"#;
+		assert_eq!(serde_json::to_string(&data).unwrap(), json);
+		assert_eq!(serde_json::from_str::<InterchainAccountPacketData>(json).unwrap(), data);
+		assert_eq!(data.messages().unwrap(), vec![message]);
+
+		let unknown = json.replace("TYPE_EXECUTE_TX", "TYPE_QUERY");
+		assert!(serde_json::from_str::<InterchainAccountPacketData>(&unknown).is_err());
+	}
+}
@@ -0,0 +1,210 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::{fmt, str::FromStr};
+
+use derive_more::Display;
+use ibc_proto::ibc::applications::nft_transfer::v1::ClassTrace as RawClassTrace;
+use serde::{Deserialize, Serialize};
+
+use super::error::Error;
+use crate::{
+	applications::transfer::{TracePath, TracePrefix},
+	core::ics24_host::identifier::{ChannelId, PortId},
+	prelude::*,
+	serializers::serde_string,
+};
+
+/// Base class id type
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize, Display)]
+#[serde(transparent)]
+pub struct BaseClassId(String);
+
+impl BaseClassId {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl FromStr for BaseClassId {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.trim().is_empty() {
+			Err(Error::empty_base_class_id())
+		} else {
+			Ok(BaseClassId(s.to_owned()))
+		}
+	}
+}
+
+/// A type that contains the base class id for ICS721 and the source tracing information path,
+/// prefixed exactly like an ICS20 [`PrefixedDenom`](crate::applications::transfer::PrefixedDenom).
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PrefixedClassId {
+	/// A series of `{port-id}/{channel-id}`s for tracing the source of the class.
+	#[serde(with = "serde_string")]
+	trace_path: TracePath,
+	/// Base class id of the relayed non fungible tokens.
+	base_class_id: BaseClassId,
+}
+
+impl PrefixedClassId {
+	/// Removes the specified prefix from the trace path if there is a match, otherwise does
+	/// nothing.
+	pub fn remove_trace_prefix(&mut self, prefix: &TracePrefix) {
+		self.trace_path.remove_prefix(prefix)
+	}
+
+	/// Adds the specified prefix to the trace path.
+	pub fn add_trace_prefix(&mut self, prefix: TracePrefix) {
+		self.trace_path.add_prefix(prefix)
+	}
+
+	pub fn trace_path(&self) -> &TracePath {
+		&self.trace_path
+	}
+
+	pub fn base_class_id(&self) -> &BaseClassId {
+		&self.base_class_id
+	}
+}
+
+/// Returns true if the class originally came from the sender chain and false otherwise, see
+/// [`is_sender_chain_source`](crate::applications::transfer::is_sender_chain_source).
+pub fn is_sender_chain_source(
+	source_port: PortId,
+	source_channel: ChannelId,
+	class_id: &PrefixedClassId,
+) -> bool {
+	!is_receiver_chain_source(source_port, source_channel, class_id)
+}
+
+/// Returns true if the class originally came from the receiving chain and false otherwise.
+pub fn is_receiver_chain_source(
+	source_port: PortId,
+	source_channel: ChannelId,
+	class_id: &PrefixedClassId,
+) -> bool {
+	let prefix = TracePrefix::new(source_port, source_channel);
+	class_id.trace_path.starts_with(&prefix)
+}
+
+impl FromStr for PrefixedClassId {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts: Vec<&str> = s.split('/').collect();
+		let last_part = parts.pop().expect("split() returned an empty iterator");
+
+		let (base_class_id, trace_path) = {
+			if last_part == s {
+				(BaseClassId::from_str(s)?, TracePath::default())
+			} else {
+				let base_class_id = BaseClassId::from_str(last_part)?;
+				let trace_path = TracePath::try_from(parts).map_err(Error::invalid_class_trace)?;
+				(base_class_id, trace_path)
+			}
+		};
+
+		Ok(Self { trace_path, base_class_id })
+	}
+}
+
+impl TryFrom<RawClassTrace> for PrefixedClassId {
+	type Error = Error;
+
+	fn try_from(value: RawClassTrace) -> Result<Self, Self::Error> {
+		let base_class_id = BaseClassId::from_str(&value.base_class_id)?;
+		let trace_path = TracePath::from_str(&value.path).map_err(Error::invalid_class_trace)?;
+		Ok(Self { trace_path, base_class_id })
+	}
+}
+
+impl From<PrefixedClassId> for RawClassTrace {
+	fn from(value: PrefixedClassId) -> Self {
+		Self { path: value.trace_path.to_string(), base_class_id: value.base_class_id.to_string() }
+	}
+}
+
+impl From<BaseClassId> for PrefixedClassId {
+	fn from(class_id: BaseClassId) -> Self {
+		Self { trace_path: Default::default(), base_class_id: class_id }
+	}
+}
+
+impl fmt::Display for PrefixedClassId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.trace_path.is_empty() {
+			write!(f, "{}", self.base_class_id)
+		} else {
+			write!(f, "{}/{}", self.trace_path, self.base_class_id)
+		}
+	}
+}
+
+/// Identifier of a non fungible token within its class.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize, Display)]
+#[serde(transparent)]
+pub struct TokenId(String);
+
+impl TokenId {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl FromStr for TokenId {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.trim().is_empty() {
+			Err(Error::empty_token_id())
+		} else {
+			Ok(TokenId(s.to_owned()))
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_class_id_trace() -> Result<(), Error> {
+		assert!(PrefixedClassId::from_str("").is_err(), "empty class id");
+		assert!(PrefixedClassId::from_str("nft-transfer/channel-0/").is_err(), "empty base");
+		assert!(PrefixedClassId::from_str("nft-transfer/kitty").is_err(), "single trace");
+
+		let class_id = PrefixedClassId::from_str("nft-transfer/channel-0/kitty")?;
+		assert_eq!(class_id.base_class_id().as_str(), "kitty");
+		assert_eq!(class_id.to_string(), "nft-transfer/channel-0/kitty");
+
+		let prefix = TracePrefix::new(PortId::nft_transfer(), ChannelId::new(0));
+		assert!(is_receiver_chain_source(PortId::nft_transfer(), ChannelId::new(0), &class_id));
+		assert!(is_sender_chain_source(PortId::nft_transfer(), ChannelId::new(1), &class_id));
+
+		let mut class_id = class_id;
+		class_id.remove_trace_prefix(&prefix);
+		assert_eq!(class_id, PrefixedClassId::from_str("kitty")?);
+		class_id.add_trace_prefix(TracePrefix::new(PortId::nft_transfer(), ChannelId::new(1)));
+		assert_eq!(class_id.to_string(), "nft-transfer/channel-1/kitty");
+
+		let raw = RawClassTrace::from(class_id.clone());
+		assert_eq!(raw.path, "nft-transfer/channel-1");
+		assert_eq!(PrefixedClassId::try_from(raw)?, class_id);
+
+		Ok(())
+	}
+}
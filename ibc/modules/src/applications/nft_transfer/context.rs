@@ -0,0 +1,628 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use subtle_encoding::hex;
+
+use super::error::Error as NftTransferError;
+use crate::{
+	applications::nft_transfer::{
+		acknowledgement::Acknowledgement,
+		events::{AckEvent, AckStatusEvent, RecvEvent, TimeoutEvent},
+		packet::PacketData,
+		relay::{
+			on_ack_packet::process_ack_packet, on_recv_packet::process_recv_packet,
+			on_timeout_packet::process_timeout_packet,
+		},
+		PrefixedClassId, TokenId, VERSION,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			context::{ChannelKeeper, ChannelReader},
+			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{ModuleOutputBuilder, ReaderContext},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+pub trait NftTransferKeeper:
+	ChannelKeeper + NftKeeper<AccountId = <Self as NftTransferKeeper>::AccountId>
+{
+	type AccountId;
+}
+
+pub trait NftTransferReader: ChannelReader
+where
+	Self: Sized,
+{
+	type AccountId: TryFrom<Signer>;
+
+	/// get_port returns the portID for the nft transfer module.
+	fn get_port(&self) -> Result<PortId, NftTransferError>;
+
+	/// Returns the escrow account id for a port and channel combination
+	fn get_channel_escrow_address(
+		&self,
+		port_id: &PortId,
+		channel_id: ChannelId,
+	) -> Result<<Self as NftTransferReader>::AccountId, NftTransferError> {
+		let hash = cosmos_adr028_escrow_address(self, port_id, channel_id);
+
+		String::from_utf8(hex::encode_upper(hash))
+			.expect("hex encoded bytes are not valid UTF8")
+			.parse::<Signer>()
+			.map_err(NftTransferError::signer)?
+			.try_into()
+			.map_err(|_| NftTransferError::parse_account_failure())
+	}
+
+	/// Returns true iff send is enabled.
+	fn is_send_enabled(&self) -> bool;
+
+	/// Returns true iff receive is enabled.
+	fn is_receive_enabled(&self) -> bool;
+
+	/// Returns a hash of the prefixed class id.
+	/// Defaults to the hex encoded SHA-256 hash of the full class path.
+	fn class_hash_string(&self, class_id: &PrefixedClassId) -> Option<String> {
+		Some(class_trace_hash(self, &class_id.to_string()))
+	}
+}
+
+/// Returns the upper case hex encoded SHA-256 hash of a full class path
+/// (e.g. `nft-transfer/channel-0/kitty`), computed like ICS20 denomination trace hashes.
+pub fn class_trace_hash(ctx: &dyn ChannelReader, full_class_path: &str) -> String {
+	let hash = ctx.hash(full_class_path.as_bytes().to_vec());
+	String::from_utf8(hex::encode_upper(hash)).expect("hex encoded bytes are not valid UTF8")
+}
+
+// https://github.com/cosmos/cosmos-sdk/blob/master/docs/architecture/adr-028-public-key-addresses.md
+fn cosmos_adr028_escrow_address(
+	ctx: &dyn ChannelReader,
+	port_id: &PortId,
+	channel_id: ChannelId,
+) -> Vec<u8> {
+	let contents = format!("{}/{}", port_id, channel_id);
+	let mut data = VERSION.as_bytes().to_vec();
+	data.extend_from_slice(&[0]);
+	data.extend_from_slice(contents.as_bytes());
+
+	let mut hash = ctx.hash(data);
+	hash.truncate(20);
+	hash
+}
+
+/// Registry of the class traces of the vouchers minted by the nft transfer module, keyed by
+/// their [`class_trace_hash`].
+pub trait ClassTraceStore {
+	/// Stores the trace of a class under its hash.
+	fn store_class_trace(
+		&mut self,
+		hash: String,
+		class_id: PrefixedClassId,
+	) -> Result<(), NftTransferError>;
+
+	/// Returns the trace stored under the given hash, if any.
+	fn get_class_trace(&self, hash: &str) -> Result<Option<PrefixedClassId>, NftTransferError>;
+}
+
+pub trait NftKeeper {
+	type AccountId;
+
+	/// Returns the uri of a class, `None` if the class does not exist.
+	fn get_class_uri(&self, class_id: &PrefixedClassId) -> Option<String>;
+
+	/// Returns the uri of a token, `None` if the token does not exist.
+	fn get_token_uri(&self, class_id: &PrefixedClassId, token_id: &TokenId) -> Option<String>;
+
+	/// This function should create a class, so that its tokens can be minted.
+	fn create_class(
+		&mut self,
+		class_id: &PrefixedClassId,
+		class_uri: &str,
+	) -> Result<(), NftTransferError>;
+
+	/// This function should move a token from one account to another, failing if `from` does not
+	/// own it.
+	fn transfer_token(
+		&mut self,
+		from: &Self::AccountId,
+		to: &Self::AccountId,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+	) -> Result<(), NftTransferError>;
+
+	/// This function should mint a token of an existing class to an account.
+	fn mint_token(
+		&mut self,
+		account: &Self::AccountId,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+		token_uri: &str,
+	) -> Result<(), NftTransferError>;
+
+	/// This function should burn a token, failing if `account` does not own it.
+	fn burn_token(
+		&mut self,
+		account: &Self::AccountId,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+	) -> Result<(), NftTransferError>;
+}
+
+/// Captures all the dependencies which the ICS721 module requires to be able to dispatch and
+/// process IBC messages.
+pub trait NftTransferContext:
+	NftTransferKeeper<AccountId = <Self as NftTransferContext>::AccountId>
+	+ NftTransferReader<AccountId = <Self as NftTransferContext>::AccountId>
+	+ ClassTraceStore
+	+ ReaderContext
+{
+	type AccountId: TryFrom<Signer>;
+}
+
+fn validate_transfer_channel_params(
+	ctx: &mut impl NftTransferContext,
+	order: Order,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	version: &Version,
+) -> Result<(), NftTransferError> {
+	if channel_id.sequence() > (u32::MAX as u64) {
+		return Err(NftTransferError::chan_seq_exceeds_limit(channel_id.sequence()))
+	}
+
+	if order != Order::Unordered {
+		return Err(NftTransferError::channel_not_unordered(order))
+	}
+
+	let bound_port = ctx.get_port()?;
+	if port_id != &bound_port {
+		return Err(NftTransferError::invalid_port(port_id.clone(), bound_port))
+	}
+
+	if version != &Version::ics721() {
+		return Err(NftTransferError::invalid_version(version.clone()))
+	}
+
+	Ok(())
+}
+
+fn validate_counterparty_version(counterparty_version: &Version) -> Result<(), NftTransferError> {
+	if counterparty_version == &Version::ics721() {
+		Ok(())
+	} else {
+		Err(NftTransferError::invalid_counterparty_version(counterparty_version.clone()))
+	}
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_init(
+	ctx: &mut impl NftTransferContext,
+	_output: &mut ModuleOutputBuilder,
+	order: Order,
+	_connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	_counterparty: &Counterparty,
+	version: &Version,
+) -> Result<(), NftTransferError> {
+	validate_transfer_channel_params(ctx, order, port_id, channel_id, version)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_try(
+	ctx: &mut impl NftTransferContext,
+	_output: &mut ModuleOutputBuilder,
+	order: Order,
+	_connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	_counterparty: &Counterparty,
+	version: &Version,
+	counterparty_version: &Version,
+) -> Result<Version, NftTransferError> {
+	validate_transfer_channel_params(ctx, order, port_id, channel_id, version)?;
+	validate_counterparty_version(counterparty_version)?;
+	Ok(Version::ics721())
+}
+
+pub fn on_chan_open_ack(
+	_ctx: &mut impl NftTransferContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+	counterparty_version: &Version,
+) -> Result<(), NftTransferError> {
+	validate_counterparty_version(counterparty_version)?;
+	Ok(())
+}
+
+pub fn on_chan_open_confirm(
+	_ctx: &mut impl NftTransferContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+) -> Result<(), NftTransferError> {
+	Ok(())
+}
+
+pub fn on_chan_close_init(
+	_ctx: &mut impl NftTransferContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+) -> Result<(), NftTransferError> {
+	Ok(())
+}
+
+pub fn on_chan_close_confirm(
+	_ctx: &mut impl NftTransferContext,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+) -> Result<(), NftTransferError> {
+	Ok(())
+}
+
+pub fn on_recv_packet<Ctx: 'static + NftTransferContext>(
+	ctx: &mut Ctx,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	_relayer: &Signer,
+) -> Acknowledgement {
+	let data = match serde_json::from_slice::<PacketData>(&packet.data) {
+		Ok(data) => data,
+		Err(_) =>
+			return Acknowledgement::from_error(NftTransferError::packet_data_deserialization()),
+	};
+
+	let ack = match process_recv_packet(ctx, output, packet, data.clone()) {
+		Ok(_) => Acknowledgement::success(),
+		Err(e) => Acknowledgement::from_error(e),
+	};
+
+	let recv_event = RecvEvent {
+		receiver: data.receiver,
+		class_id: data.class_id,
+		token_ids: data.token_ids,
+		memo: data.memo,
+		success: ack.is_successful(),
+	};
+	output.emit(recv_event.into());
+
+	ack
+}
+
+pub fn on_acknowledgement_packet(
+	ctx: &mut impl NftTransferContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	acknowledgement: &GenericAcknowledgement,
+	_relayer: &Signer,
+) -> Result<(), NftTransferError> {
+	let data = serde_json::from_slice::<PacketData>(&packet.data)
+		.map_err(|_| NftTransferError::packet_data_deserialization())?;
+
+	let acknowledgement = serde_json::from_slice::<Acknowledgement>(acknowledgement.as_ref())
+		.map_err(|_| NftTransferError::ack_deserialization())?;
+
+	process_ack_packet(ctx, packet, &data, &acknowledgement)?;
+
+	let ack_event = AckEvent {
+		receiver: data.receiver,
+		class_id: data.class_id,
+		token_ids: data.token_ids,
+		acknowledgement: acknowledgement.clone(),
+	};
+	output.emit(ack_event.into());
+	output.emit(AckStatusEvent { acknowledgement }.into());
+
+	Ok(())
+}
+
+pub fn on_timeout_packet(
+	ctx: &mut impl NftTransferContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	_relayer: &Signer,
+) -> Result<(), NftTransferError> {
+	let data = serde_json::from_slice::<PacketData>(&packet.data)
+		.map_err(|_| NftTransferError::packet_data_deserialization())?;
+
+	process_timeout_packet(ctx, packet, &data)?;
+
+	let timeout_event = TimeoutEvent {
+		refund_receiver: data.sender,
+		refund_class_id: data.class_id,
+		refund_token_ids: data.token_ids,
+	};
+	output.emit(timeout_event.into());
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod test {
+	use std::sync::{Arc, Mutex};
+
+	use crate::{
+		applications::nft_transfer::{
+			acknowledgement::Acknowledgement,
+			context::{
+				class_trace_hash, on_acknowledgement_packet, on_chan_open_ack,
+				on_chan_open_confirm, on_chan_open_init, on_chan_open_try, on_recv_packet,
+				on_timeout_packet, ClassTraceStore, NftKeeper, NftTransferReader,
+			},
+			error::{Error as NftTransferError, ErrorDetail},
+			msgs::transfer::MsgTransfer,
+			packet::PacketData,
+			relay::send_transfer::send_transfer,
+			PrefixedClassId, TokenId,
+		},
+		core::{
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				packet::{Packet, Sequence},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			ics26_routing::context::ModuleOutputBuilder,
+		},
+		events::IbcEvent,
+		handler::HandlerOutputBuilder,
+		mock::context::{MockClientTypes, MockContext, MockIbcStore},
+		prelude::*,
+		signer::Signer,
+		test_utils::{get_dummy_bech32_account, DummyTransferModule},
+		timestamp::{Timestamp, ZERO_DURATION},
+		Height,
+	};
+
+	type Ctx = DummyTransferModule<MockClientTypes>;
+
+	/// A module with an open nft-transfer channel-0 to the counterparty's channel-1.
+	fn nft_transfer_module_with_channel() -> Ctx {
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(PortId::nft_transfer(), Some(ChannelId::new(1))),
+			vec![ConnectionId::default()],
+			Version::ics721(),
+		);
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), Height::new(0, 10))
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(PortId::nft_transfer(), ChannelId::new(0), channel_end)
+			.with_send_sequence(PortId::nft_transfer(), ChannelId::new(0), Sequence::from(1));
+		DummyTransferModule::new(ctx.ibc_store_share())
+	}
+
+	fn account() -> Signer {
+		get_dummy_bech32_account().parse().unwrap()
+	}
+
+	fn token_ids(ids: &[&str]) -> Vec<TokenId> {
+		ids.iter().map(|id| id.parse().unwrap()).collect()
+	}
+
+	fn escrow(ctx: &Ctx) -> Signer {
+		ctx.get_channel_escrow_address(&PortId::nft_transfer(), ChannelId::new(0)).unwrap()
+	}
+
+	fn send(ctx: &mut Ctx, class_id: &PrefixedClassId, ids: &[&str]) -> Packet {
+		let msg = MsgTransfer {
+			source_port: PortId::nft_transfer(),
+			source_channel: ChannelId::new(0),
+			class_id: class_id.clone(),
+			token_ids: token_ids(ids),
+			sender: account(),
+			receiver: account(),
+			timeout_height: Height::new(0, 100),
+			timeout_timestamp: Timestamp::none(),
+			memo: String::new(),
+		};
+		let mut output = HandlerOutputBuilder::new();
+		send_transfer(ctx, &mut output, msg).unwrap();
+		output
+			.with_result(())
+			.events
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::SendPacket(e) => Some(e.packet),
+				_ => None,
+			})
+			.expect("no packet was sent")
+	}
+
+	/// A packet sent by the counterparty over its channel-1.
+	fn incoming(class_id: &str, ids: &[&str]) -> Packet {
+		let data = PacketData {
+			class_id: class_id.parse().unwrap(),
+			class_uri: "ipfs://kitties".to_string(),
+			token_ids: token_ids(ids),
+			token_uris: ids.iter().map(|id| format!("ipfs://kitty/{}", id)).collect(),
+			sender: account(),
+			receiver: account(),
+			memo: String::new(),
+		};
+		Packet {
+			source_port: PortId::nft_transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::nft_transfer(),
+			destination_channel: ChannelId::new(0),
+			data: serde_json::to_vec(&data).unwrap(),
+			..Default::default()
+		}
+	}
+
+	fn receive(ctx: &mut Ctx, packet: &Packet) -> Acknowledgement {
+		on_recv_packet(ctx, &mut ModuleOutputBuilder::new(), packet, &"relayer".parse().unwrap())
+	}
+
+	fn acknowledge(ctx: &mut Ctx, packet: &Packet, ack: Acknowledgement) {
+		on_acknowledgement_packet(
+			ctx,
+			&mut ModuleOutputBuilder::new(),
+			packet,
+			&serde_json::to_vec(&ack.to_string()).unwrap().into(),
+			&"relayer".parse().unwrap(),
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn test_handshake_negotiates_nft_version() {
+		let mut ctx = DummyTransferModule::new(Arc::new(Mutex::new(
+			MockIbcStore::<MockClientTypes>::default(),
+		)));
+		let port_id = PortId::nft_transfer();
+		let channel_id = ChannelId::new(0);
+		let counterparty = Counterparty::new(PortId::nft_transfer(), None);
+		let init = |ctx: &mut Ctx, order: Order, port_id: &PortId, version: &Version| {
+			on_chan_open_init(
+				ctx,
+				&mut ModuleOutputBuilder::new(),
+				order,
+				&[ConnectionId::default()],
+				port_id,
+				&channel_id,
+				&counterparty,
+				version,
+			)
+		};
+
+		assert!(init(&mut ctx, Order::Unordered, &port_id, &Version::ics721()).is_ok());
+		assert!(init(&mut ctx, Order::Ordered, &port_id, &Version::ics721()).is_err());
+		assert!(init(&mut ctx, Order::Unordered, &PortId::transfer(), &Version::ics721()).is_err());
+		match init(&mut ctx, Order::Unordered, &port_id, &Version::ics20()) {
+			Err(e) => match e.detail() {
+				ErrorDetail::InvalidVersion(e) => assert_eq!(e.version, Version::ics20()),
+				_ => panic!("Expected InvalidVersion error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected InvalidVersion error"),
+		}
+
+		let version = on_chan_open_try(
+			&mut ctx,
+			&mut ModuleOutputBuilder::new(),
+			Order::Unordered,
+			&[ConnectionId::default()],
+			&port_id,
+			&channel_id,
+			&counterparty,
+			&Version::ics721(),
+			&Version::ics721(),
+		)
+		.unwrap();
+		assert_eq!(version, Version::ics721());
+
+		let mut output = ModuleOutputBuilder::new();
+		assert!(on_chan_open_ack(&mut ctx, &mut output, &port_id, &channel_id, &version).is_ok());
+		assert!(on_chan_open_ack(&mut ctx, &mut output, &port_id, &channel_id, &Version::ics20())
+			.is_err());
+		assert!(on_chan_open_confirm(&mut ctx, &mut output, &port_id, &channel_id).is_ok());
+	}
+
+	#[test]
+	fn test_send_escrows_and_timeout_refunds_native_tokens() {
+		let mut ctx = nft_transfer_module_with_channel();
+		let kitty: PrefixedClassId = "kitty".parse().unwrap();
+		ctx.create_class(&kitty, "ipfs://kitties").unwrap();
+		for id in token_ids(&["1", "2"]) {
+			ctx.mint_token(&account(), &kitty, &id, "").unwrap();
+		}
+
+		let packet = send(&mut ctx, &kitty, &["1", "2"]);
+		let data: PacketData = serde_json::from_slice(&packet.data).unwrap();
+		assert_eq!(data.class_uri, "ipfs://kitties");
+		assert!(data.token_uris.is_empty());
+		for id in token_ids(&["1", "2"]) {
+			assert_eq!(ctx.nft_owner(&kitty, &id), Some(escrow(&ctx)));
+		}
+
+		on_timeout_packet(&mut ctx, &mut ModuleOutputBuilder::new(), &packet, &account()).unwrap();
+		for id in token_ids(&["1", "2"]) {
+			assert_eq!(ctx.nft_owner(&kitty, &id), Some(account()));
+		}
+
+		// once delivered, the tokens only come back with a packet from the counterparty
+		let packet = send(&mut ctx, &kitty, &["1"]);
+		acknowledge(&mut ctx, &packet, Acknowledgement::success());
+		assert_eq!(ctx.nft_owner(&kitty, &token_ids(&["1"])[0]), Some(escrow(&ctx)));
+		let packet = incoming("nft-transfer/channel-1/kitty", &["1"]);
+		assert!(receive(&mut ctx, &packet).is_successful());
+		assert_eq!(ctx.nft_owner(&kitty, &token_ids(&["1"])[0]), Some(account()));
+	}
+
+	#[test]
+	fn test_vouchers_are_minted_traced_and_refunded() {
+		let mut ctx = nft_transfer_module_with_channel();
+		assert!(receive(&mut ctx, &incoming("kitty", &["7"])).is_successful());
+
+		let voucher: PrefixedClassId = "nft-transfer/channel-0/kitty".parse().unwrap();
+		let id = &token_ids(&["7"])[0];
+		assert_eq!(ctx.nft_owner(&voucher, id), Some(account()));
+		assert_eq!(ctx.get_class_uri(&voucher), Some("ipfs://kitties".to_string()));
+		assert_eq!(ctx.get_token_uri(&voucher, id), Some("ipfs://kitty/7".to_string()));
+		let hash = class_trace_hash(&ctx, "nft-transfer/channel-0/kitty");
+		assert_eq!(ctx.get_class_trace(&hash).unwrap(), Some(voucher.clone()));
+
+		// vouchers are burned on the way back and minted again when the receive fails
+		let packet = send(&mut ctx, &voucher, &["7"]);
+		assert_eq!(ctx.nft_owner(&voucher, id), None);
+		let data: PacketData = serde_json::from_slice(&packet.data).unwrap();
+		assert_eq!(data.token_uris, vec!["ipfs://kitty/7".to_string()]);
+		let error = NftTransferError::receive_disabled();
+		acknowledge(&mut ctx, &packet, Acknowledgement::from_error(error));
+		assert_eq!(ctx.nft_owner(&voucher, id), Some(account()));
+		assert_eq!(ctx.get_token_uri(&voucher, id), Some("ipfs://kitty/7".to_string()));
+	}
+
+	#[test]
+	fn test_recv_is_atomic() {
+		let mut ctx = nft_transfer_module_with_channel();
+		let kitty: PrefixedClassId = "kitty".parse().unwrap();
+		ctx.create_class(&kitty, "").unwrap();
+		ctx.mint_token(&escrow(&ctx), &kitty, &token_ids(&["1"])[0], "").unwrap();
+
+		// the second token was never escrowed on this chain
+		let ack = receive(&mut ctx, &incoming("nft-transfer/channel-1/kitty", &["1", "2"]));
+		assert!(!ack.is_successful());
+		assert_eq!(ctx.nft_owner(&kitty, &token_ids(&["1"])[0]), Some(escrow(&ctx)));
+		assert_eq!(ctx.nft_owner(&kitty, &token_ids(&["2"])[0]), None);
+	}
+}
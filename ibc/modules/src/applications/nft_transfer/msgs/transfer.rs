@@ -0,0 +1,198 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This is the definition of an nft transfer message that an application submits to a chain.
+
+use crate::prelude::*;
+
+use ibc_proto::{
+	google::protobuf::Any, ibc::applications::nft_transfer::v1::MsgTransfer as RawMsgTransfer,
+};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::nft_transfer::{error::Error, PrefixedClassId, TokenId},
+	core::{
+		ics02_client::height::Height,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	signer::Signer,
+	timestamp::Timestamp,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.applications.nft_transfer.v1.MsgTransfer";
+
+/// Message used to build an ICS721 nft transfer packet, moving tokens of one class.
+///
+/// Like the ICS20 [`MsgTransfer`](crate::applications::transfer::msgs::transfer::MsgTransfer),
+/// it leaves the sequence and the destination port/channel for the library to fill in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgTransfer {
+	/// the port on which the packet will be sent
+	pub source_port: PortId,
+	/// the channel by which the packet will be sent
+	pub source_channel: ChannelId,
+	/// the class of the tokens to be transferred
+	pub class_id: PrefixedClassId,
+	/// the tokens to be transferred, never empty
+	pub token_ids: Vec<TokenId>,
+	/// the sender address
+	pub sender: Signer,
+	/// the recipient address on the destination chain
+	pub receiver: Signer,
+	/// Timeout height relative to the current block height.
+	/// The timeout is disabled when set to 0.
+	pub timeout_height: Height,
+	/// Timeout timestamp relative to the current block timestamp.
+	/// The timeout is disabled when set to 0.
+	pub timeout_timestamp: Timestamp,
+	/// Optional memo, carried in the packet data
+	pub memo: String,
+}
+
+impl Msg for MsgTransfer {
+	type ValidationError = Error;
+	type Raw = RawMsgTransfer;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl TryFrom<RawMsgTransfer> for MsgTransfer {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgTransfer) -> Result<Self, Self::Error> {
+		let timeout_timestamp = Timestamp::from_nanoseconds(raw_msg.timeout_timestamp)
+			.map_err(|_| Error::invalid_packet_timeout_timestamp(raw_msg.timeout_timestamp))?;
+
+		let timeout_height = match raw_msg.timeout_height.clone() {
+			None => Height::zero(),
+			Some(raw_height) => raw_height.try_into().map_err(|e| {
+				Error::invalid_packet_timeout_height(format!("invalid timeout height {}", e))
+			})?,
+		};
+
+		if raw_msg.token_ids.is_empty() {
+			return Err(Error::empty_token_ids())
+		}
+
+		Ok(MsgTransfer {
+			source_port: raw_msg
+				.source_port
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw_msg.source_port.clone(), e))?,
+			source_channel: raw_msg
+				.source_channel
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw_msg.source_channel.clone(), e))?,
+			class_id: raw_msg.class_id.parse()?,
+			token_ids: raw_msg.token_ids.iter().map(|id| id.parse()).collect::<Result<_, _>>()?,
+			sender: raw_msg.sender.parse().map_err(Error::signer)?,
+			receiver: raw_msg.receiver.parse().map_err(Error::signer)?,
+			timeout_height,
+			timeout_timestamp,
+			memo: raw_msg.memo,
+		})
+	}
+}
+
+impl From<MsgTransfer> for RawMsgTransfer {
+	fn from(domain_msg: MsgTransfer) -> Self {
+		RawMsgTransfer {
+			source_port: domain_msg.source_port.to_string(),
+			source_channel: domain_msg.source_channel.to_string(),
+			class_id: domain_msg.class_id.to_string(),
+			token_ids: domain_msg.token_ids.iter().map(ToString::to_string).collect(),
+			sender: domain_msg.sender.to_string(),
+			receiver: domain_msg.receiver.to_string(),
+			timeout_height: Some(domain_msg.timeout_height.into()),
+			timeout_timestamp: domain_msg.timeout_timestamp.nanoseconds(),
+			memo: domain_msg.memo,
+		}
+	}
+}
+
+impl Protobuf<RawMsgTransfer> for MsgTransfer {}
+
+impl TryFrom<Any> for MsgTransfer {
+	type Error = Error;
+
+	fn try_from(raw: Any) -> Result<Self, Self::Error> {
+		match raw.type_url.as_str() {
+			TYPE_URL => MsgTransfer::decode_vec(&raw.value).map_err(Error::decode_raw_msg),
+			_ => Err(Error::unknown_msg_type(raw.type_url)),
+		}
+	}
+}
+
+impl From<MsgTransfer> for Any {
+	fn from(msg: MsgTransfer) -> Self {
+		Self { type_url: TYPE_URL.to_string(), value: msg.encode_vec() }
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use core::{ops::Add, time::Duration};
+
+	use super::MsgTransfer;
+	use crate::{
+		core::ics24_host::identifier::{ChannelId, PortId},
+		prelude::*,
+		signer::Signer,
+		test_utils::get_dummy_bech32_account,
+		timestamp::Timestamp,
+		Height,
+	};
+
+	// Returns a dummy ICS721 `MsgTransfer`, for testing only!
+	pub fn get_dummy_msg_transfer(height: u64) -> MsgTransfer {
+		let address: Signer = get_dummy_bech32_account().as_str().parse().unwrap();
+		MsgTransfer {
+			source_port: PortId::nft_transfer(),
+			source_channel: ChannelId::default(),
+			class_id: "kitty".parse().unwrap(),
+			token_ids: vec!["1".parse().unwrap()],
+			sender: address.clone(),
+			receiver: address,
+			timeout_timestamp: Timestamp::now().add(Duration::from_secs(10)).unwrap(),
+			timeout_height: Height { revision_number: 0, revision_height: height },
+			memo: String::new(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use ibc_proto::ibc::applications::nft_transfer::v1::MsgTransfer as RawMsgTransfer;
+
+	use super::{test_util::get_dummy_msg_transfer, MsgTransfer};
+	use crate::prelude::*;
+
+	#[test]
+	fn msg_transfer_round_trips() {
+		let msg = get_dummy_msg_transfer(10);
+		let raw = RawMsgTransfer::from(msg.clone());
+		assert_eq!(MsgTransfer::try_from(raw.clone()).unwrap(), msg);
+
+		let no_tokens = RawMsgTransfer { token_ids: vec![], ..raw };
+		assert!(MsgTransfer::try_from(no_tokens).is_err());
+	}
+}
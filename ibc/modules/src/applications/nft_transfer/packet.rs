@@ -0,0 +1,175 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::{
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::{convert::TryFrom, str::FromStr};
+
+use ibc_proto::ibc::applications::nft_transfer::v1::NonFungibleTokenPacketData as RawPacketData;
+use serde::{Deserialize, Serialize};
+
+use super::{error::Error, PrefixedClassId, TokenId};
+use crate::signer::Signer;
+
+/// The ICS721 packet payload, moving one or more tokens of a class from one sender to one
+/// receiver. It is encoded as the camel cased JSON of the ICS721 spec, with the optional fields
+/// omitted when empty.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "JsonPacketData", into = "JsonPacketData")]
+pub struct PacketData {
+	pub class_id: PrefixedClassId,
+	/// Off-chain metadata of the class, empty if the class has none.
+	pub class_uri: String,
+	/// The transferred tokens, never empty.
+	pub token_ids: Vec<TokenId>,
+	/// Off-chain metadata of each of the tokens, either empty or one per token id.
+	pub token_uris: Vec<String>,
+	pub sender: Signer,
+	pub receiver: Signer,
+	/// Arbitrary data for the receiving chain, empty if the sender did not set one.
+	pub memo: String,
+}
+
+impl PacketData {
+	/// Returns the uri of the token at position `index`, empty if the packet carries no uris.
+	pub fn token_uri(&self, index: usize) -> &str {
+		self.token_uris.get(index).map(String::as_str).unwrap_or_default()
+	}
+}
+
+impl TryFrom<RawPacketData> for PacketData {
+	type Error = Error;
+
+	fn try_from(raw_pkt_data: RawPacketData) -> Result<Self, Self::Error> {
+		if raw_pkt_data.token_ids.is_empty() {
+			return Err(Error::empty_token_ids())
+		}
+		if !raw_pkt_data.token_uris.is_empty() &&
+			raw_pkt_data.token_uris.len() != raw_pkt_data.token_ids.len()
+		{
+			return Err(Error::token_uris_mismatch(
+				raw_pkt_data.token_ids.len(),
+				raw_pkt_data.token_uris.len(),
+			))
+		}
+		Ok(Self {
+			class_id: PrefixedClassId::from_str(&raw_pkt_data.class_id)?,
+			class_uri: raw_pkt_data.class_uri,
+			token_ids: raw_pkt_data
+				.token_ids
+				.iter()
+				.map(|id| TokenId::from_str(id))
+				.collect::<Result<_, _>>()?,
+			token_uris: raw_pkt_data.token_uris,
+			sender: raw_pkt_data.sender.parse().map_err(Error::signer)?,
+			receiver: raw_pkt_data.receiver.parse().map_err(Error::signer)?,
+			memo: raw_pkt_data.memo,
+		})
+	}
+}
+
+impl From<PacketData> for RawPacketData {
+	fn from(pkt_data: PacketData) -> Self {
+		Self {
+			class_id: pkt_data.class_id.to_string(),
+			class_uri: pkt_data.class_uri,
+			token_ids: pkt_data.token_ids.iter().map(ToString::to_string).collect(),
+			token_uris: pkt_data.token_uris,
+			sender: pkt_data.sender.to_string(),
+			receiver: pkt_data.receiver.to_string(),
+			memo: pkt_data.memo,
+		}
+	}
+}
+
+/// The JSON form of [`PacketData`], with fields in alphabetical order.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonPacketData {
+	class_id: String,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	class_uri: String,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	memo: String,
+	receiver: String,
+	sender: String,
+	token_ids: Vec<String>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	token_uris: Vec<String>,
+}
+
+impl TryFrom<JsonPacketData> for PacketData {
+	type Error = Error;
+
+	fn try_from(json: JsonPacketData) -> Result<Self, Self::Error> {
+		RawPacketData {
+			class_id: json.class_id,
+			class_uri: json.class_uri,
+			token_ids: json.token_ids,
+			token_uris: json.token_uris,
+			sender: json.sender,
+			receiver: json.receiver,
+			memo: json.memo,
+		}
+		.try_into()
+	}
+}
+
+impl From<PacketData> for JsonPacketData {
+	fn from(pkt_data: PacketData) -> Self {
+		let raw = RawPacketData::from(pkt_data);
+		Self {
+			class_id: raw.class_id,
+			class_uri: raw.class_uri,
+			memo: raw.memo,
+			receiver: raw.receiver,
+			sender: raw.sender,
+			token_ids: raw.token_ids,
+			token_uris: raw.token_uris,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{prelude::*, test_utils::get_dummy_bech32_account};
+
+	#[test]
+	fn packet_data_matches_spec_json() {
+		let account: Signer = get_dummy_bech32_account().parse().unwrap();
+		let data = PacketData {
+			class_id: "nft-transfer/channel-0/kitty".parse().unwrap(),
+			class_uri: String::new(),
+			token_ids: vec!["1".parse().unwrap(), "2".parse().unwrap()],
+			token_uris: vec![],
+			sender: account.clone(),
+			receiver: account,
+			memo: String::new(),
+		};
+		let json = format!(
+			r#"{{"classId":"nft-transfer/channel-0/kitty","receiver":"{0}","sender":"{0}","tokenIds":["1","2"]}}"#,
+			get_dummy_bech32_account()
+		);
+		assert_eq!(String::from_utf8(serde_json::to_vec(&data).unwrap()).unwrap(), json);
+		assert_eq!(serde_json::from_str::<PacketData>(&json).unwrap(), data);
+
+		let mismatched = json.replace(r#"["1","2"]"#, r#"["1","2"],"tokenUris":["a"]"#);
+		assert!(serde_json::from_str::<PacketData>(&mismatched).is_err());
+		let empty = json.replace(r#"["1","2"]"#, "[]");
+		assert!(serde_json::from_str::<PacketData>(&empty).is_err());
+	}
+}
@@ -0,0 +1,84 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! This module implements the processing logic for ICS721 (nft transfer) messages.
+use crate::{
+	applications::nft_transfer::{
+		context::NftTransferContext, error::Error as NftTransferError, is_sender_chain_source,
+		packet::PacketData, TokenId,
+	},
+	core::ics04_channel::packet::Packet,
+	prelude::*,
+};
+
+pub mod on_ack_packet;
+pub mod on_recv_packet;
+pub mod on_timeout_packet;
+pub mod send_transfer;
+
+/// Applies `apply` to the token at each position of `token_ids`, in order. If one of them fails,
+/// `revert` undoes the tokens already applied, so that either all the tokens of a packet move or
+/// none of them do.
+pub(crate) fn for_each_token<Ctx>(
+	ctx: &mut Ctx,
+	token_ids: &[TokenId],
+	mut apply: impl FnMut(&mut Ctx, usize) -> Result<(), NftTransferError>,
+	mut revert: impl FnMut(&mut Ctx, usize) -> Result<(), NftTransferError>,
+) -> Result<(), NftTransferError> {
+	for i in 0..token_ids.len() {
+		if let Err(e) = apply(ctx, i) {
+			for applied in (0..i).rev() {
+				revert(ctx, applied)?;
+			}
+			return Err(e)
+		}
+	}
+	Ok(())
+}
+
+/// Gives the tokens of a packet back to its sender, releasing them from escrow or minting the
+/// burned vouchers again.
+fn refund_packet_tokens(
+	ctx: &mut impl NftTransferContext,
+	packet: &Packet,
+	data: &PacketData,
+) -> Result<(), NftTransferError> {
+	let sender = data
+		.sender
+		.clone()
+		.try_into()
+		.map_err(|_| NftTransferError::parse_account_failure())?;
+	let class_id = &data.class_id;
+
+	if is_sender_chain_source(packet.source_port.clone(), packet.source_channel, class_id) {
+		// unescrow tokens back to sender
+		let escrow_address =
+			ctx.get_channel_escrow_address(&packet.source_port, packet.source_channel)?;
+		for_each_token(
+			ctx,
+			&data.token_ids,
+			|ctx, i| ctx.transfer_token(&escrow_address, &sender, class_id, &data.token_ids[i]),
+			|ctx, i| ctx.transfer_token(&sender, &escrow_address, class_id, &data.token_ids[i]),
+		)
+	}
+	// mint vouchers back to sender
+	else {
+		for_each_token(
+			ctx,
+			&data.token_ids,
+			|ctx, i| ctx.mint_token(&sender, class_id, &data.token_ids[i], data.token_uri(i)),
+			|ctx, i| ctx.burn_token(&sender, class_id, &data.token_ids[i]),
+		)
+	}
+}
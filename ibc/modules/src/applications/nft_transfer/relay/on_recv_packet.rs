@@ -0,0 +1,107 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::{
+		nft_transfer::{
+			context::{class_trace_hash, NftTransferContext},
+			error::Error as NftTransferError,
+			events::ClassTraceEvent,
+			is_receiver_chain_source,
+			packet::PacketData,
+			relay::for_each_token,
+		},
+		transfer::TracePrefix,
+	},
+	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
+	prelude::*,
+};
+
+/// Credits the receiver of the packet with all of its tokens, or with none of them if one of the
+/// tokens cannot be received.
+pub fn process_recv_packet<Ctx: 'static + NftTransferContext>(
+	ctx: &mut Ctx,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	data: PacketData,
+) -> Result<(), NftTransferError> {
+	if !ctx.is_receive_enabled() {
+		return Err(NftTransferError::receive_disabled())
+	}
+
+	let receiver_account = data
+		.receiver
+		.clone()
+		.try_into()
+		.map_err(|_| NftTransferError::parse_account_failure())?;
+
+	if is_receiver_chain_source(packet.source_port.clone(), packet.source_channel, &data.class_id)
+	{
+		// sender chain is not the source, unescrow tokens
+		let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
+		let class_id = {
+			let mut c = data.class_id.clone();
+			c.remove_trace_prefix(&prefix);
+			c
+		};
+		let escrow_address =
+			ctx.get_channel_escrow_address(&packet.destination_port, packet.destination_channel)?;
+
+		for_each_token(
+			ctx,
+			&data.token_ids,
+			|ctx, i| {
+				let token_id = &data.token_ids[i];
+				ctx.transfer_token(&escrow_address, &receiver_account, &class_id, token_id)
+			},
+			|ctx, i| {
+				let token_id = &data.token_ids[i];
+				ctx.transfer_token(&receiver_account, &escrow_address, &class_id, token_id)
+			},
+		)
+	} else {
+		// sender chain is the source, mint vouchers
+		let prefix = TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
+		let class_id = {
+			let mut c = data.class_id.clone();
+			c.add_trace_prefix(prefix);
+			c
+		};
+
+		if ctx.get_class_uri(&class_id).is_none() {
+			ctx.create_class(&class_id, &data.class_uri)?;
+		}
+
+		for_each_token(
+			ctx,
+			&data.token_ids,
+			|ctx, i| {
+				ctx.mint_token(&receiver_account, &class_id, &data.token_ids[i], data.token_uri(i))
+			},
+			|ctx, i| ctx.burn_token(&receiver_account, &class_id, &data.token_ids[i]),
+		)?;
+
+		// Only record the trace once the vouchers exist, so failed receives leave no trace behind
+		let hash = class_trace_hash(&*ctx, &class_id.to_string());
+		if ctx.get_class_trace(&hash)?.is_none() {
+			ctx.store_class_trace(hash, class_id.clone())?;
+		}
+
+		let class_trace_event =
+			ClassTraceEvent { trace_hash: ctx.class_hash_string(&class_id), class_id };
+		output.emit(class_trace_event.into());
+
+		Ok(())
+	}
+}
@@ -0,0 +1,29 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::nft_transfer::{
+		context::NftTransferContext, error::Error as NftTransferError, packet::PacketData,
+		relay::refund_packet_tokens,
+	},
+	core::ics04_channel::packet::Packet,
+};
+
+pub fn process_timeout_packet(
+	ctx: &mut impl NftTransferContext,
+	packet: &Packet,
+	data: &PacketData,
+) -> Result<(), NftTransferError> {
+	refund_packet_tokens(ctx, packet, data)
+}
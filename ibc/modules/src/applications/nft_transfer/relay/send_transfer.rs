@@ -0,0 +1,137 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::nft_transfer::{
+		context::NftTransferContext, error::Error, events::TransferEvent, is_sender_chain_source,
+		msgs::transfer::MsgTransfer, packet::PacketData, relay::for_each_token,
+	},
+	core::ics04_channel::{handler::send_packet::send_packet, packet::Packet},
+	events::ModuleEvent,
+	handler::{HandlerOutput, HandlerOutputBuilder},
+	prelude::*,
+};
+
+/// This function handles the nft transfer sending logic.
+/// If this method returns an error, the runtime is expected to rollback all state modifications to
+/// the `Ctx` caused by all messages from the transaction that this `msg` is a part of.
+pub fn send_transfer<Ctx>(
+	ctx: &mut Ctx,
+	output: &mut HandlerOutputBuilder<()>,
+	msg: MsgTransfer,
+) -> Result<(), Error>
+where
+	Ctx: NftTransferContext,
+{
+	if !ctx.is_send_enabled() {
+		return Err(Error::send_disabled())
+	}
+
+	if msg.token_ids.is_empty() {
+		return Err(Error::empty_token_ids())
+	}
+
+	let source_channel_end = ctx
+		.channel_end(&(msg.source_port.clone(), msg.source_channel))
+		.map_err(Error::ics04_channel)?;
+
+	let destination_port = source_channel_end.counterparty().port_id().clone();
+	let destination_channel = *source_channel_end.counterparty().channel_id().ok_or_else(|| {
+		Error::destination_channel_not_found(msg.source_port.clone(), msg.source_channel)
+	})?;
+
+	// get the next sequence
+	let sequence = ctx
+		.get_next_sequence_send(&(msg.source_port.clone(), msg.source_channel))
+		.map_err(Error::ics04_channel)?;
+
+	let class_id = &msg.class_id;
+	let class_uri = ctx
+		.get_class_uri(class_id)
+		.ok_or_else(|| Error::class_not_found(class_id.to_string()))?;
+	let token_uris = msg
+		.token_ids
+		.iter()
+		.map(|token_id| {
+			ctx.get_token_uri(class_id, token_id)
+				.ok_or_else(|| Error::token_not_found(class_id.to_string(), token_id.to_string()))
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let sender = msg.sender.clone().try_into().map_err(|_| Error::parse_account_failure())?;
+
+	if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, class_id) {
+		let escrow_address =
+			ctx.get_channel_escrow_address(&msg.source_port, msg.source_channel)?;
+		for_each_token(
+			ctx,
+			&msg.token_ids,
+			|ctx, i| ctx.transfer_token(&sender, &escrow_address, class_id, &msg.token_ids[i]),
+			|ctx, i| ctx.transfer_token(&escrow_address, &sender, class_id, &msg.token_ids[i]),
+		)?;
+	} else {
+		for_each_token(
+			ctx,
+			&msg.token_ids,
+			|ctx, i| ctx.burn_token(&sender, class_id, &msg.token_ids[i]),
+			|ctx, i| ctx.mint_token(&sender, class_id, &msg.token_ids[i], &token_uris[i]),
+		)?;
+	}
+
+	// Only carry the uris when there is something to tell the receiving chain.
+	let token_uris =
+		if token_uris.iter().all(String::is_empty) { Vec::new() } else { token_uris };
+	let data = PacketData {
+		class_id: msg.class_id.clone(),
+		class_uri,
+		token_ids: msg.token_ids.clone(),
+		token_uris,
+		sender: msg.sender.clone(),
+		receiver: msg.receiver.clone(),
+		memo: msg.memo.clone(),
+	};
+	let data = serde_json::to_vec(&data).expect("PacketData's infallible Serialize impl failed");
+
+	let packet = Packet {
+		sequence,
+		source_port: msg.source_port,
+		source_channel: msg.source_channel,
+		destination_port,
+		destination_channel,
+		data,
+		timeout_height: msg.timeout_height,
+		timeout_timestamp: msg.timeout_timestamp,
+	};
+
+	let HandlerOutput { result, log, events } =
+		send_packet(ctx, packet).map_err(Error::ics04_channel)?;
+
+	ctx.store_packet_result(result).map_err(Error::ics04_channel)?;
+
+	output.merge_output(HandlerOutput::builder().with_log(log).with_events(events).with_result(()));
+
+	output.log(format!(
+		"IBC nft transfer: {} --({} {})--> {}",
+		msg.sender,
+		msg.class_id,
+		msg.token_ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(","),
+		msg.receiver
+	));
+
+	let transfer_event =
+		TransferEvent { sender: msg.sender, receiver: msg.receiver, memo: msg.memo };
+	output.emit(ModuleEvent::from(transfer_event).into());
+
+	Ok(())
+}
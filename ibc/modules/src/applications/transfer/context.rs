@@ -16,15 +16,19 @@ use subtle_encoding::hex;
 
 use super::error::Error as Ics20Error;
 use crate::{
-	applications::transfer::{
-		acknowledgement::Acknowledgement,
-		events::{AckEvent, AckStatusEvent, RecvEvent, TimeoutEvent},
-		packet::PacketData,
-		relay::{
-			on_ack_packet::process_ack_packet, on_recv_packet::process_recv_packet,
-			on_timeout_packet::process_timeout_packet,
+	applications::{
+		fee::version::Metadata as FeeMetadata,
+		transfer::{
+			acknowledgement::Acknowledgement,
+			events::{AckEvent, AckStatusEvent, RecvEvent, TimeoutEvent},
+			packet::{MultiTokenPacketData, PacketData},
+			relay::{
+				on_ack_packet::process_ack_packet_tokens,
+				on_recv_packet::process_recv_packet_tokens,
+				on_timeout_packet::process_timeout_packet_tokens,
+			},
+			Amount, PrefixedCoin, PrefixedDenom, DENOM_HASH_PREFIX, VERSION,
 		},
-		PrefixedCoin, PrefixedDenom, VERSION,
 	},
 	core::{
 		ics04_channel::{
@@ -32,6 +36,7 @@ use crate::{
 			context::{ChannelKeeper, ChannelReader},
 			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
 			packet::Packet,
+			upgrade::UpgradeFields,
 			Version,
 		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
@@ -79,12 +84,41 @@ where
 	fn is_receive_enabled(&self) -> bool;
 
 	/// Returns a hash of the prefixed denom.
-	/// Implement only if the host chain supports hashed denominations.
-	fn denom_hash_string(&self, _denom: &PrefixedDenom) -> Option<String> {
-		None
+	/// Defaults to the hex encoded SHA-256 hash of the full denomination path used by ibc-go.
+	fn denom_hash_string(&self, denom: &PrefixedDenom) -> Option<String> {
+		Some(denom_trace_hash(self, &denom.to_string()))
 	}
 }
 
+/// Returns the upper case hex encoded SHA-256 hash of a full denomination path
+/// (e.g. `transfer/channel-0/uatom`), matching ibc-go's `DenomTrace::Hash`.
+pub fn denom_trace_hash(ctx: &dyn ChannelReader, full_denom_path: &str) -> String {
+	let hash = ctx.hash(full_denom_path.as_bytes().to_vec());
+	String::from_utf8(hex::encode_upper(hash)).expect("hex encoded bytes are not valid UTF8")
+}
+
+/// Returns the `ibc/{hash}` voucher denomination for a full denomination path, matching ibc-go's
+/// `DenomTrace::IBCDenom`. Denominations without a trace are returned unchanged.
+pub fn hashed_ibc_denom(ctx: &dyn ChannelReader, full_denom_path: &str) -> String {
+	if full_denom_path.contains('/') {
+		format!("{}/{}", DENOM_HASH_PREFIX, denom_trace_hash(ctx, full_denom_path))
+	} else {
+		full_denom_path.to_string()
+	}
+}
+
+/// Extracts the hash from an `ibc/{hash}` voucher denomination.
+pub fn parse_hashed_ibc_denom(denom: &str) -> Result<String, Ics20Error> {
+	let hash = denom
+		.strip_prefix(DENOM_HASH_PREFIX)
+		.and_then(|rest| rest.strip_prefix('/'))
+		.ok_or_else(Ics20Error::missing_denom_ibc_prefix)?;
+	if hash.len() != 64 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+		return Err(Ics20Error::malformed_hash_denom())
+	}
+	Ok(hash.to_uppercase())
+}
+
 // https://github.com/cosmos/cosmos-sdk/blob/master/docs/architecture/adr-028-public-key-addresses.md
 fn cosmos_adr028_escrow_address(
 	ctx: &dyn ChannelReader,
@@ -101,6 +135,67 @@ fn cosmos_adr028_escrow_address(
 	hash
 }
 
+/// Registry of the denomination traces of the vouchers minted by the transfer module, keyed by
+/// their [`denom_trace_hash`].
+pub trait DenomTraceStore {
+	/// Stores the trace of a denomination under its hash.
+	fn store_denom_trace(&mut self, hash: String, denom: PrefixedDenom) -> Result<(), Ics20Error>;
+
+	/// Returns the trace stored under the given hash, if any.
+	fn get_denom_trace(&self, hash: &str) -> Result<Option<PrefixedDenom>, Ics20Error>;
+
+	/// Returns all the stored traces together with their hashes.
+	fn iter_denom_traces(&self) -> Result<Vec<(String, PrefixedDenom)>, Ics20Error>;
+}
+
+/// Lets the host cap the value of the tokens transferred over a channel, e.g. per epoch.
+/// Every transfer is accepted by default.
+pub trait TransferRateLimiter {
+	/// Called by [`send_transfer`](super::relay::send_transfer::send_transfer) before the tokens
+	/// sent over the local `channel_id` are escrowed or burned.
+	fn check_send(
+		&mut self,
+		_denom: &PrefixedDenom,
+		_amount: Amount,
+		_channel_id: &ChannelId,
+	) -> Result<(), Ics20Error> {
+		Ok(())
+	}
+
+	/// Called by [`on_recv_packet`] before the tokens received over the local `channel_id` are
+	/// unescrowed or minted. A rejection results in an error acknowledgement, so that the sender
+	/// gets refunded.
+	fn check_recv(
+		&mut self,
+		_denom: &PrefixedDenom,
+		_amount: Amount,
+		_channel_id: &ChannelId,
+	) -> Result<(), Ics20Error> {
+		Ok(())
+	}
+}
+
+/// Per channel accounting of the native tokens held in the channel escrow accounts, so that the
+/// host can tell how much of each denomination is backing the vouchers minted by a counterparty.
+pub trait EscrowBalanceStore {
+	/// Returns the amount of `denom` escrowed for transfers sent over the given channel.
+	fn escrow_balance(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		denom: &PrefixedDenom,
+	) -> Amount;
+
+	/// Sets the amount of `denom` escrowed for transfers sent over the given channel.
+	fn store_escrow_balance(
+		&mut self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		denom: &PrefixedDenom,
+		amount: Amount,
+	) -> Result<(), Ics20Error>;
+}
+
 pub trait BankKeeper {
 	type AccountId;
 
@@ -132,11 +227,49 @@ pub trait BankKeeper {
 pub trait Ics20Context:
 	Ics20Keeper<AccountId = <Self as Ics20Context>::AccountId>
 	+ Ics20Reader<AccountId = <Self as Ics20Context>::AccountId>
+	+ DenomTraceStore
+	+ TransferRateLimiter
+	+ EscrowBalanceStore
 	+ ReaderContext
 {
 	type AccountId: TryFrom<Signer>;
 }
 
+fn is_supported_version(version: &Version) -> bool {
+	version == &Version::ics20() || version == &Version::ics20_multi_token()
+}
+
+/// Returns true if the channel negotiated the [`MULTI_TOKEN_VERSION`](super::MULTI_TOKEN_VERSION),
+/// also when the version is wrapped by the fee middleware.
+pub fn is_multi_token_channel(
+	ctx: &dyn ChannelReader,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+) -> Result<bool, Ics20Error> {
+	let channel_end =
+		ctx.channel_end(&(port_id.clone(), *channel_id)).map_err(Ics20Error::ics04_channel)?;
+	let version = FeeMetadata::from_version(channel_end.version())
+		.map(|metadata| metadata.app_version())
+		.unwrap_or_else(|| channel_end.version().clone());
+	Ok(version == Version::ics20_multi_token())
+}
+
+/// Decodes the data of a packet sent or received over the given channel, as a
+/// [`MultiTokenPacketData`] on multi token channels and as a [`PacketData`] otherwise.
+pub fn decode_packet_data(
+	ctx: &dyn ChannelReader,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	data: &[u8],
+) -> Result<MultiTokenPacketData, Ics20Error> {
+	let data = if is_multi_token_channel(ctx, port_id, channel_id)? {
+		serde_json::from_slice::<MultiTokenPacketData>(data)
+	} else {
+		serde_json::from_slice::<PacketData>(data).map(Into::into)
+	};
+	data.map_err(|_| Ics20Error::packet_data_deserialization())
+}
+
 fn validate_transfer_channel_params(
 	ctx: &mut impl Ics20Context,
 	order: Order,
@@ -157,7 +290,7 @@ fn validate_transfer_channel_params(
 		return Err(Ics20Error::invalid_port(port_id.clone(), bound_port))
 	}
 
-	if version != &Version::ics20() {
+	if !is_supported_version(version) {
 		return Err(Ics20Error::invalid_version(version.clone()))
 	}
 
@@ -165,7 +298,7 @@ fn validate_transfer_channel_params(
 }
 
 fn validate_counterparty_version(counterparty_version: &Version) -> Result<(), Ics20Error> {
-	if counterparty_version == &Version::ics20() {
+	if is_supported_version(counterparty_version) {
 		Ok(())
 	} else {
 		Err(Ics20Error::invalid_counterparty_version(counterparty_version.clone()))
@@ -198,9 +331,12 @@ pub fn on_chan_open_try(
 	version: &Version,
 	counterparty_version: &Version,
 ) -> Result<Version, Ics20Error> {
+	// Like ibc-go, an empty proposed version leaves the choice of the version to the module
+	let version = if version == &Version::empty() { counterparty_version } else { version };
 	validate_transfer_channel_params(ctx, order, port_id, channel_id, version)?;
 	validate_counterparty_version(counterparty_version)?;
-	Ok(Version::ics20())
+	// Go with the version the counterparty proposed, so it can opt into multi token packets
+	Ok(counterparty_version.clone())
 }
 
 pub fn on_chan_open_ack(
@@ -241,30 +377,71 @@ pub fn on_chan_close_confirm(
 	Ok(())
 }
 
+/// The upgraded channel must still be usable for transfers, so the proposed fields are held to
+/// the same rules as a newly opened channel.
+pub fn on_chan_upgrade_init(
+	ctx: &mut impl Ics20Context,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	proposed_upgrade: &UpgradeFields,
+) -> Result<(), Ics20Error> {
+	validate_transfer_channel_params(
+		ctx,
+		proposed_upgrade.ordering,
+		port_id,
+		channel_id,
+		&proposed_upgrade.version,
+	)
+}
+
+pub fn on_chan_upgrade_try(
+	ctx: &mut impl Ics20Context,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	counterparty_upgrade: &UpgradeFields,
+) -> Result<(), Ics20Error> {
+	validate_transfer_channel_params(
+		ctx,
+		counterparty_upgrade.ordering,
+		port_id,
+		channel_id,
+		&counterparty_upgrade.version,
+	)
+}
+
 pub fn on_recv_packet<Ctx: 'static + Ics20Context>(
 	ctx: &mut Ctx,
 	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
 	_relayer: &Signer,
 ) -> Acknowledgement {
-	let data = match serde_json::from_slice::<PacketData>(&packet.data) {
+	let data = match decode_packet_data(
+		&*ctx,
+		&packet.destination_port,
+		&packet.destination_channel,
+		&packet.data,
+	) {
 		Ok(data) => data,
-		Err(_) =>
-			return Acknowledgement::Error(Ics20Error::packet_data_deserialization().to_string()),
+		Err(e) => return Acknowledgement::Error(e.to_string()),
 	};
 
-	let ack = match process_recv_packet(ctx, output, packet, data.clone()) {
+	let ack = match process_recv_packet_tokens(ctx, output, packet, data.clone()) {
 		Ok(_) => Acknowledgement::success(),
 		Err(e) => Acknowledgement::from_error(e),
 	};
 
-	let recv_event = RecvEvent {
-		receiver: data.receiver,
-		denom: data.token.denom,
-		amount: data.token.amount,
-		success: ack.is_successful(),
-	};
-	output.emit(recv_event.into());
+	for data in data.token_packets() {
+		let recv_event = RecvEvent {
+			receiver: data.receiver,
+			denom: data.token.denom,
+			amount: data.token.amount,
+			memo: data.memo,
+			success: ack.is_successful(),
+		};
+		output.emit(recv_event.into());
+	}
 
 	ack
 }
@@ -276,21 +453,23 @@ pub fn on_acknowledgement_packet(
 	acknowledgement: &GenericAcknowledgement,
 	_relayer: &Signer,
 ) -> Result<(), Ics20Error> {
-	let data = serde_json::from_slice::<PacketData>(&packet.data)
-		.map_err(|_| Ics20Error::packet_data_deserialization())?;
+	let data =
+		decode_packet_data(&*ctx, &packet.source_port, &packet.source_channel, &packet.data)?;
 
 	let acknowledgement = serde_json::from_slice::<Acknowledgement>(acknowledgement.as_ref())
 		.map_err(|_| Ics20Error::ack_deserialization())?;
 
-	process_ack_packet(ctx, packet, &data, &acknowledgement)?;
+	process_ack_packet_tokens(ctx, packet, &data, &acknowledgement)?;
 
-	let ack_event = AckEvent {
-		receiver: data.receiver,
-		denom: data.token.denom,
-		amount: data.token.amount,
-		acknowledgement: acknowledgement.clone(),
-	};
-	output.emit(ack_event.into());
+	for data in data.token_packets() {
+		let ack_event = AckEvent {
+			receiver: data.receiver,
+			denom: data.token.denom,
+			amount: data.token.amount,
+			acknowledgement: acknowledgement.clone(),
+		};
+		output.emit(ack_event.into());
+	}
 	output.emit(AckStatusEvent { acknowledgement }.into());
 
 	Ok(())
@@ -302,17 +481,19 @@ pub fn on_timeout_packet(
 	packet: &Packet,
 	_relayer: &Signer,
 ) -> Result<(), Ics20Error> {
-	let data = serde_json::from_slice::<PacketData>(&packet.data)
-		.map_err(|_| Ics20Error::packet_data_deserialization())?;
-
-	process_timeout_packet(ctx, packet, &data)?;
-
-	let timeout_event = TimeoutEvent {
-		refund_receiver: data.sender,
-		refund_denom: data.token.denom,
-		refund_amount: data.token.amount,
-	};
-	output.emit(timeout_event.into());
+	let data =
+		decode_packet_data(&*ctx, &packet.source_port, &packet.source_channel, &packet.data)?;
+
+	process_timeout_packet_tokens(ctx, packet, &data)?;
+
+	for data in data.token_packets() {
+		let timeout_event = TimeoutEvent {
+			refund_receiver: data.sender,
+			refund_denom: data.token.denom,
+			refund_amount: data.token.amount,
+		};
+		output.emit(timeout_event.into());
+	}
 
 	Ok(())
 }
@@ -326,14 +507,42 @@ pub(crate) mod test {
 
 	use crate::{
 		applications::transfer::{
-			context::cosmos_adr028_escrow_address, error::Error as Ics20Error,
-			msgs::transfer::MsgTransfer, relay::send_transfer::send_transfer, PrefixedCoin,
+			acknowledgement::Acknowledgement,
+			context::{
+				cosmos_adr028_escrow_address, hashed_ibc_denom, on_acknowledgement_packet,
+				on_chan_open_try, on_recv_packet, parse_hashed_ibc_denom, BankKeeper,
+				DenomTraceStore, EscrowBalanceStore, Ics20Reader,
+			},
+			error::{Error as Ics20Error, ErrorDetail as Ics20ErrorDetail},
+			msgs::transfer::MsgTransfer,
+			packet::{MultiTokenPacketData, PacketData},
+			relay::send_transfer::send_transfer,
+			Amount, PrefixedCoin, PrefixedDenom,
+		},
+		core::{
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				error::Error,
+				packet::{Packet, Sequence},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			ics26_routing::context::ModuleOutputBuilder,
 		},
-		core::ics04_channel::error::Error,
+		events::IbcEvent,
 		handler::HandlerOutputBuilder,
-		mock::context::{HostBlockType, MockClientTypes, MockIbcStore},
+		mock::context::{HostBlockType, MockClientTypes, MockContext, MockIbcStore},
 		prelude::*,
-		test_utils::DummyTransferModule,
+		signer::Signer,
+		test_utils::{get_dummy_bech32_account, DummyTransferModule},
+		timestamp::{Timestamp, ZERO_DURATION},
+		Height,
 	};
 
 	pub(crate) fn deliver<C: HostBlockType>(
@@ -344,6 +553,317 @@ pub(crate) mod test {
 		send_transfer(ctx, output, msg).map_err(|e: Ics20Error| Error::app_module(e.to_string()))
 	}
 
+	fn dummy_transfer_module() -> DummyTransferModule<MockClientTypes> {
+		let ibc_store = MockIbcStore::<MockClientTypes>::default();
+		DummyTransferModule::new(Arc::new(Mutex::new(ibc_store)))
+	}
+
+	#[test]
+	fn test_ibc_go_denom_hashes() {
+		let ctx = dummy_transfer_module();
+
+		// hashes obtained using `gaiad query ibc-transfer denom-hash [trace]`
+		assert_eq!(
+			hashed_ibc_denom(&ctx, "transfer/channel-0/uatom"),
+			"ibc/27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+		);
+		assert_eq!(
+			hashed_ibc_denom(&ctx, "transfer/channel-141/uosmo"),
+			"ibc/14F9BC3E44B8A9C1BE1FB08980FAB87034C9905EF17CF2F5008FC085218811CC"
+		);
+		assert_eq!(hashed_ibc_denom(&ctx, "uatom"), "uatom");
+
+		assert_eq!(
+			parse_hashed_ibc_denom(
+				"ibc/27394fb092d2eccd56123c74f36e4c1f926001ceada9ca97ea622b25f41e5eb2"
+			)
+			.unwrap(),
+			"27394FB092D2ECCD56123C74F36E4C1F926001CEADA9CA97EA622B25F41E5EB2"
+		);
+		assert!(parse_hashed_ibc_denom("uatom").is_err());
+		assert!(parse_hashed_ibc_denom("ibc/27394FB092D2").is_err());
+	}
+
+	#[test]
+	fn test_recv_packet_stores_denom_trace() {
+		let mut ctx = transfer_module_with_channel(Version::ics20());
+		let packet = Packet {
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(0),
+			data: br#"{"amount":"100","denom":"uDOT","receiver":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}"#.to_vec(),
+			..Default::default()
+		};
+
+		for _ in 0..2 {
+			let ack = on_recv_packet(
+				&mut ctx,
+				&mut ModuleOutputBuilder::new(),
+				&packet,
+				&"relayer".parse().unwrap(),
+			);
+			assert!(ack.is_successful());
+		}
+
+		let hash = "D55F653266AD268FB95BD4CFB1C555B73F29A1E7AA56841C960807A50C4F0E70";
+		let expected: PrefixedDenom = "transfer/channel-0/uDOT".parse().unwrap();
+		assert_eq!(ctx.get_denom_trace(hash).unwrap(), Some(expected.clone()));
+		assert_eq!(ctx.iter_denom_traces().unwrap(), vec![(hash.to_string(), expected)]);
+	}
+
+	/// A transfer module with an open channel-0 to the counterparty's channel-1.
+	fn transfer_module_with_channel(version: Version) -> DummyTransferModule<MockClientTypes> {
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(PortId::transfer(), Some(ChannelId::new(1))),
+			vec![ConnectionId::default()],
+			version,
+		);
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), Height::new(0, 10))
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(PortId::transfer(), ChannelId::new(0), channel_end)
+			.with_send_sequence(PortId::transfer(), ChannelId::new(0), Sequence::from(1));
+		DummyTransferModule::new(ctx.ibc_store_share())
+	}
+
+	fn send(ctx: &mut DummyTransferModule<MockClientTypes>, amount: u64) -> Packet {
+		let token = PrefixedCoin { denom: "uatom".parse().unwrap(), amount: amount.into() };
+		send_tokens(ctx, token, vec![]).unwrap()
+	}
+
+	fn send_tokens(
+		ctx: &mut DummyTransferModule<MockClientTypes>,
+		token: PrefixedCoin,
+		tokens: Vec<PrefixedCoin>,
+	) -> Result<Packet, Ics20Error> {
+		let account: Signer = get_dummy_bech32_account().parse().unwrap();
+		let msg = MsgTransfer {
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(0),
+			token,
+			tokens,
+			sender: account.clone(),
+			receiver: account,
+			timeout_height: Height::new(0, 100),
+			timeout_timestamp: Timestamp::none(),
+			memo: String::new(),
+		};
+		let mut output = HandlerOutputBuilder::new();
+		send_transfer(ctx, &mut output, msg)?;
+		Ok(output
+			.with_result(())
+			.events
+			.into_iter()
+			.find_map(|event| match event {
+				IbcEvent::SendPacket(e) => Some(e.packet),
+				_ => None,
+			})
+			.expect("no packet was sent"))
+	}
+
+	fn acknowledge(
+		ctx: &mut DummyTransferModule<MockClientTypes>,
+		packet: &Packet,
+		ack: Acknowledgement,
+	) {
+		on_acknowledgement_packet(
+			ctx,
+			&mut ModuleOutputBuilder::new(),
+			packet,
+			&serde_json::to_vec(&ack.to_string()).unwrap().into(),
+			&"relayer".parse().unwrap(),
+		)
+		.unwrap();
+	}
+
+	#[test]
+	fn test_escrow_balance_follows_transfer_cycle() {
+		let mut ctx = transfer_module_with_channel(Version::ics20());
+		let account: Signer = get_dummy_bech32_account().parse().unwrap();
+		let uatom: PrefixedDenom = "uatom".parse().unwrap();
+		let escrow_balance = |ctx: &DummyTransferModule<MockClientTypes>| -> Amount {
+			ctx.escrow_balance(&PortId::transfer(), &ChannelId::new(0), &uatom)
+		};
+		let coin = PrefixedCoin { denom: uatom.clone(), amount: Amount::from(1000u64) };
+		ctx.mint_coins(&account, &coin).unwrap();
+
+		// escrowed on send and kept once the counterparty acknowledges the receive
+		let packet = send(&mut ctx, 100);
+		assert_eq!(escrow_balance(&ctx), Amount::from(100u64));
+		acknowledge(&mut ctx, &packet, Acknowledgement::success());
+		assert_eq!(escrow_balance(&ctx), Amount::from(100u64));
+
+		// refunded on an error acknowledgement
+		let packet = send(&mut ctx, 40);
+		assert_eq!(escrow_balance(&ctx), Amount::from(140u64));
+		acknowledge(&mut ctx, &packet, Acknowledgement::from_error(Ics20Error::invalid_token()));
+		assert_eq!(escrow_balance(&ctx), Amount::from(100u64));
+
+		// released when the vouchers come back
+		let data = PacketData {
+			token: PrefixedCoin {
+				denom: "transfer/channel-1/uatom".parse().unwrap(),
+				amount: Amount::from(60u64),
+			},
+			sender: account.clone(),
+			receiver: account.clone(),
+			memo: String::new(),
+		};
+		let packet = Packet {
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(0),
+			data: serde_json::to_vec(&data).unwrap(),
+			..Default::default()
+		};
+		let ack = on_recv_packet(
+			&mut ctx,
+			&mut ModuleOutputBuilder::new(),
+			&packet,
+			&"relayer".parse().unwrap(),
+		);
+		assert!(ack.is_successful());
+		assert_eq!(escrow_balance(&ctx), Amount::from(40u64));
+
+		let escrow =
+			ctx.get_channel_escrow_address(&PortId::transfer(), ChannelId::new(0)).unwrap();
+		assert_eq!(ctx.balance(&escrow, &uatom), escrow_balance(&ctx));
+		assert_eq!(ctx.balance(&account, &uatom), Amount::from(960u64));
+	}
+
+	#[test]
+	fn test_multi_token_transfer_refunds_every_coin() {
+		let mut ctx = transfer_module_with_channel(Version::ics20_multi_token());
+		let account: Signer = get_dummy_bech32_account().parse().unwrap();
+		let native = PrefixedCoin { denom: "uatom".parse().unwrap(), amount: Amount::from(100u64) };
+		let voucher = PrefixedCoin {
+			denom: "transfer/channel-0/uosmo".parse().unwrap(),
+			amount: Amount::from(50u64),
+		};
+		ctx.mint_coins(&account, &native).unwrap();
+		ctx.mint_coins(&account, &voucher).unwrap();
+
+		let packet = send_tokens(&mut ctx, native.clone(), vec![voucher.clone()]).unwrap();
+		let data: MultiTokenPacketData = serde_json::from_slice(&packet.data).unwrap();
+		assert_eq!(data.tokens, vec![native.clone(), voucher.clone()]);
+
+		// the native coin is escrowed while the voucher is burned
+		let escrow =
+			ctx.get_channel_escrow_address(&PortId::transfer(), ChannelId::new(0)).unwrap();
+		assert_eq!(ctx.balance(&account, &native.denom), Amount::from(0u64));
+		assert_eq!(ctx.balance(&account, &voucher.denom), Amount::from(0u64));
+		assert_eq!(ctx.balance(&escrow, &native.denom), native.amount);
+
+		acknowledge(&mut ctx, &packet, Acknowledgement::from_error(Ics20Error::invalid_token()));
+		assert_eq!(ctx.balance(&account, &native.denom), native.amount);
+		assert_eq!(ctx.balance(&account, &voucher.denom), voucher.amount);
+		assert_eq!(ctx.balance(&escrow, &native.denom), Amount::from(0u64));
+	}
+
+	#[test]
+	fn test_multi_token_transfer_requires_negotiated_channel() {
+		let mut ctx = transfer_module_with_channel(Version::ics20());
+		let account: Signer = get_dummy_bech32_account().parse().unwrap();
+		let coin = PrefixedCoin { denom: "uatom".parse().unwrap(), amount: Amount::from(10u64) };
+		ctx.mint_coins(&account, &coin).unwrap();
+		ctx.mint_coins(&account, &coin).unwrap();
+
+		match send_tokens(&mut ctx, coin.clone(), vec![coin.clone()]) {
+			Err(e) => match e.detail() {
+				Ics20ErrorDetail::MultiTokenNotSupported(e) => {
+					assert_eq!(e.channel_id, ChannelId::new(0));
+					assert_eq!(e.version, Version::ics20());
+				},
+				_ => panic!("Expected MultiTokenNotSupported error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected MultiTokenNotSupported error"),
+		}
+		assert_eq!(ctx.balance(&account, &coin.denom), Amount::from(20u64));
+	}
+
+	#[test]
+	fn test_chan_open_try_version_negotiation() {
+		fn open_try(
+			version: Version,
+			counterparty_version: Version,
+		) -> Result<Version, Ics20Error> {
+			on_chan_open_try(
+				&mut dummy_transfer_module(),
+				&mut ModuleOutputBuilder::new(),
+				Order::Unordered,
+				&[ConnectionId::default()],
+				&PortId::transfer(),
+				&ChannelId::new(0),
+				&Counterparty::new(PortId::transfer(), Some(ChannelId::new(1))),
+				&version,
+				&counterparty_version,
+			)
+		}
+
+		// An empty proposal leaves the version to the module, which goes with the counterparty's.
+		assert_eq!(open_try(Version::empty(), Version::ics20()).unwrap(), Version::ics20());
+		// The version stored on the channel end can differ from the proposed one.
+		assert_eq!(
+			open_try(Version::ics20(), Version::ics20_multi_token()).unwrap(),
+			Version::ics20_multi_token()
+		);
+		assert!(open_try(Version::empty(), Version::empty()).is_err());
+		assert!(open_try(Version::new("ics20-3".to_string()), Version::ics20()).is_err());
+	}
+
+	#[test]
+	fn test_multi_token_recv_is_atomic() {
+		let mut ctx = transfer_module_with_channel(Version::ics20_multi_token());
+		let account: Signer = get_dummy_bech32_account().parse().unwrap();
+		// the second coin claims tokens that were never escrowed on this chain
+		let data = MultiTokenPacketData {
+			tokens: vec![
+				PrefixedCoin { denom: "uosmo".parse().unwrap(), amount: Amount::from(30u64) },
+				PrefixedCoin {
+					denom: "transfer/channel-1/uatom".parse().unwrap(),
+					amount: Amount::from(60u64),
+				},
+			],
+			sender: account.clone(),
+			receiver: account.clone(),
+			memo: String::new(),
+		};
+		let packet = Packet {
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(0),
+			data: serde_json::to_vec(&data).unwrap(),
+			..Default::default()
+		};
+		let ack = on_recv_packet(
+			&mut ctx,
+			&mut ModuleOutputBuilder::new(),
+			&packet,
+			&"relayer".parse().unwrap(),
+		);
+		assert!(!ack.is_successful());
+
+		let voucher: PrefixedDenom = "transfer/channel-0/uosmo".parse().unwrap();
+		assert_eq!(ctx.balance(&account, &voucher), Amount::from(0u64));
+		assert_eq!(ctx.balance(&account, &"uatom".parse().unwrap()), Amount::from(0u64));
+	}
+
 	#[test]
 	fn test_cosmos_escrow_address() {
 		fn assert_eq_escrow_address(port_id: &str, channel_id: &str, address: &str) {
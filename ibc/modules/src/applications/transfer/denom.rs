@@ -292,9 +292,13 @@ impl fmt::Display for PrefixedDenom {
 }
 
 /// A type for representing token transfer amounts.
+///
+/// Amounts are 256 bit unsigned integers, like the `sdk.Int` amounts of Cosmos SDK chains, and are
+/// (de)serialized as decimal strings.
 #[derive(
 	Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize, Display, From, Into,
 )]
+#[serde(try_from = "String", into = "String")]
 pub struct Amount(U256);
 
 impl Amount {
@@ -320,12 +324,43 @@ impl FromStr for Amount {
 	}
 }
 
+impl TryFrom<String> for Amount {
+	type Error = Error;
+
+	fn try_from(s: String) -> Result<Self, Self::Error> {
+		s.parse()
+	}
+}
+
+impl From<Amount> for String {
+	fn from(amount: Amount) -> Self {
+		amount.to_string()
+	}
+}
+
 impl From<u64> for Amount {
 	fn from(v: u64) -> Self {
 		Self(v.into())
 	}
 }
 
+impl From<u128> for Amount {
+	fn from(v: u128) -> Self {
+		Self(v.into())
+	}
+}
+
+impl TryFrom<Amount> for u128 {
+	type Error = Error;
+
+	fn try_from(amount: Amount) -> Result<Self, Self::Error> {
+		if amount.0 > U256::from(u128::MAX) {
+			return Err(Error::amount_overflow(amount))
+		}
+		Ok(amount.0.as_u128())
+	}
+}
+
 /// Coin defines a token with a denomination and an amount.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Coin<D> {
@@ -433,6 +468,32 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn test_amount_serde() -> Result<(), Error> {
+		let amount_str = "1000000000000000000000000000000000000000";
+		let amount = Amount::from_str(amount_str)?;
+		assert_eq!(amount.to_string(), amount_str);
+		assert_eq!(serde_json::to_string(&amount).unwrap(), format!("\"{}\"", amount_str));
+		assert_eq!(serde_json::from_str::<Amount>(&format!("\"{}\"", amount_str)).unwrap(), amount);
+		assert!(u128::try_from(amount).is_err(), "amount exceeds u128::MAX");
+
+		let amount = Amount::from(u128::MAX);
+		assert_eq!(amount.to_string(), u128::MAX.to_string());
+		assert_eq!(u128::try_from(amount)?, u128::MAX);
+
+		let coin: PrefixedCoin = serde_json::from_str(&format!(
+			r#"{{"denom":"transfer/channel-0/uatom","amount":"{}"}}"#,
+			amount_str
+		))
+		.unwrap();
+		assert_eq!(coin.amount.to_string(), amount_str);
+
+		assert!(Amount::from_str(&"9".repeat(78)).is_err(), "amount exceeds U256::MAX");
+		assert!(serde_json::from_str::<Amount>("\"0x10\"").is_err(), "hex amounts are rejected");
+
+		Ok(())
+	}
+
 	#[test]
 	fn test_trace_path() -> Result<(), Error> {
 		assert!(TracePath::from_str("").is_ok(), "empty trace path");
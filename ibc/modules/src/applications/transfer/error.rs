@@ -20,6 +20,7 @@ use tendermint_proto::Error as TendermintProtoError;
 use uint::FromStrRadixErr;
 
 use crate::{
+	applications::transfer::Amount,
 	core::{
 		ics04_channel::{channel::Order, error as channel_error, Version},
 		ics24_host::{
@@ -92,6 +93,10 @@ define_error! {
 		InvalidToken
 			| _ | { "invalid token" },
 
+		AmountOverflow
+			{ amount: Amount }
+			| e | { format_args!("amount {0} overflows the target integer type", e.amount) },
+
 		Signer
 			[ SignerError ]
 			| _ | { "failed to parse signer" },
@@ -116,11 +121,18 @@ define_error! {
 
 		InvalidVersion
 			{ version: Version }
-			| e | { format_args!("expected version '{0}', got '{1}'", Version::ics20(), e.version) },
+			| e | { format_args!("expected version '{0}' or '{1}', got '{2}'", Version::ics20(), Version::ics20_multi_token(), e.version) },
 
 		InvalidCounterpartyVersion
 			{ version: Version }
-			| e | { format_args!("expected counterparty version '{0}', got '{1}'", Version::ics20(), e.version) },
+			| e | { format_args!("expected counterparty version '{0}' or '{1}', got '{2}'", Version::ics20(), Version::ics20_multi_token(), e.version) },
+
+		MultiTokenNotSupported
+			{ channel_id: ChannelId, version: Version }
+			| e | { format_args!("channel {0} with version '{1}' does not support sending multiple tokens in one packet, it requires version '{2}'", e.channel_id, e.version, Version::ics20_multi_token()) },
+
+		EmptyTokens
+			| _ | { "packet data carries no tokens" },
 
 		CantCloseChannel
 			| _ | { "channel cannot be closed" },
@@ -147,6 +159,14 @@ define_error! {
 		TraceNotFound
 			| _ | { "no trace associated with specified hash" },
 
+		RateLimitExceeded
+			{ denom: String, channel_id: ChannelId }
+			| e | { format_args!("transfer of {0} over channel {1} exceeds the rate limit", e.denom, e.channel_id) },
+
+		ForwardFailed
+			{ channel_id: ChannelId, reason: String }
+			| e | { format_args!("forwarding the transfer over channel {0} failed: {1}", e.channel_id, e.reason) },
+
 		DecodeRawMsg
 			[ TraceError<TendermintProtoError> ]
 			| _ | { "error decoding raw msg" },
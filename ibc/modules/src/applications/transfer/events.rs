@@ -39,12 +39,13 @@ pub struct RecvEvent {
 	pub receiver: Signer,
 	pub denom: PrefixedDenom,
 	pub amount: Amount,
+	pub memo: String,
 	pub success: bool,
 }
 
 impl From<RecvEvent> for ModuleEvent {
 	fn from(ev: RecvEvent) -> Self {
-		let RecvEvent { receiver, denom, amount, success } = ev;
+		let RecvEvent { receiver, denom, amount, memo, success } = ev;
 		Self {
 			kind: EVENT_TYPE_PACKET.to_string(),
 			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
@@ -52,6 +53,7 @@ impl From<RecvEvent> for ModuleEvent {
 				("receiver", receiver).into(),
 				("denom", denom).into(),
 				("amount", amount).into(),
+				("memo", memo).into(),
 				("success", success).into(),
 			],
 		}
@@ -146,15 +148,20 @@ impl From<DenomTraceEvent> for ModuleEvent {
 pub struct TransferEvent {
 	pub sender: Signer,
 	pub receiver: Signer,
+	pub memo: String,
 }
 
 impl From<TransferEvent> for ModuleEvent {
 	fn from(ev: TransferEvent) -> Self {
-		let TransferEvent { sender, receiver } = ev;
+		let TransferEvent { sender, receiver, memo } = ev;
 		Self {
 			kind: EVENT_TYPE_TRANSFER.to_string(),
 			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
-			attributes: vec![("sender", sender).into(), ("receiver", receiver).into()],
+			attributes: vec![
+				("sender", sender).into(),
+				("receiver", receiver).into(),
+				("memo", memo).into(),
+			],
 		}
 	}
 }
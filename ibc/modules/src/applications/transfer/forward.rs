@@ -0,0 +1,816 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Packet forwarding for ICS20, in the style of the ibc-go packet-forward-middleware.
+//!
+//! A transfer whose memo carries a `forward` object is received into the packet's receiver on
+//! this chain, which then sends the tokens on to the next hop:
+//!
+//! ```json
+//! {"forward":{"receiver":"osmo1..","port":"transfer","channel":"channel-1","retries":2}}
+//! ```
+//!
+//! The acknowledgement of the incoming packet is held back until the forwarded packet is
+//! acknowledged or times out, so the original sender is only refunded once the route has failed.
+//! Memos without a well formed `forward` object are received like any other transfer.
+
+use core::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	applications::transfer::{
+		acknowledgement::Acknowledgement,
+		context::{
+			on_acknowledgement_packet as ics20_on_acknowledgement_packet, on_chan_close_confirm,
+			on_chan_close_init, on_chan_open_ack, on_chan_open_confirm, on_chan_open_init,
+			on_chan_open_try, on_chan_upgrade_init, on_chan_upgrade_try,
+			on_recv_packet as ics20_on_recv_packet,
+			on_timeout_packet as ics20_on_timeout_packet, Ics20Context,
+		},
+		error::Error as Ics20Error,
+		events::RecvEvent,
+		msgs::transfer::MsgTransfer,
+		packet::PacketData,
+		relay::{
+			on_recv_packet::process_recv_packet, received_coin, revert_recv_packet_token,
+			send_transfer::send_transfer,
+		},
+		PrefixedCoin,
+	},
+	core::{
+		ics02_client::context::ClientReader,
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			context::ChannelReader,
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+			packet::{Packet, Sequence},
+			upgrade::UpgradeFields,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{
+			Module, ModuleCallbackContext, ModuleOutputBuilder, OnRecvPacketAck,
+		},
+	},
+	events::IbcEvent,
+	handler::HandlerOutputBuilder,
+	prelude::*,
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
+
+/// Timeout of a forwarded packet when the memo does not set one, 10 minutes in nanoseconds.
+pub const DEFAULT_FORWARD_TIMEOUT: u64 = 600_000_000_000;
+
+fn default_forward_timeout() -> u64 {
+	DEFAULT_FORWARD_TIMEOUT
+}
+
+/// The `forward` object of an ICS20 memo.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ForwardMetadata {
+	/// Receiver of the tokens on the next chain
+	pub receiver: Signer,
+	/// Port the tokens are forwarded through
+	pub port: PortId,
+	/// Channel the tokens are forwarded through
+	pub channel: ChannelId,
+	/// Timeout of the forwarded packet relative to the host timestamp, in nanoseconds
+	#[serde(default = "default_forward_timeout")]
+	pub timeout: u64,
+	/// Number of times the forwarded packet is sent again after timing out
+	#[serde(default)]
+	pub retries: u8,
+	/// Memo of the forwarded packet, usually holding the `forward` object of the next hop
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub next: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct ForwardMemo {
+	forward: ForwardMetadata,
+}
+
+impl ForwardMetadata {
+	/// Returns the forward metadata of a memo, or `None` if the memo has no well formed
+	/// `forward` object.
+	pub fn from_memo(memo: &str) -> Option<Self> {
+		let metadata = serde_json::from_str::<ForwardMemo>(memo).ok()?.forward;
+		if metadata.receiver.as_ref().trim().is_empty() {
+			return None
+		}
+		Some(metadata)
+	}
+}
+
+/// A forwarded packet awaiting its acknowledgement or timeout.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InFlightForward {
+	/// The received packet whose acknowledgement is held back
+	pub original_packet: Packet,
+	/// Number of times the forwarded packet may still be sent again after timing out
+	pub retries: u8,
+	/// Timeout of each forward attempt relative to the host timestamp, in nanoseconds
+	pub timeout: u64,
+}
+
+/// Storage of the packets forwarded by this chain, keyed by the port, channel and sequence of the
+/// forwarded packet.
+pub trait ForwardKeeper: Ics20Context {
+	fn store_in_flight_forward(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		forward: InFlightForward,
+	) -> Result<(), Ics20Error>;
+
+	fn get_in_flight_forward(
+		&self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<Option<InFlightForward>, Ics20Error>;
+
+	/// Prunes a forward once it has been settled.
+	fn delete_in_flight_forward(
+		&mut self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<(), Ics20Error>;
+
+	/// Writes the (held back) acknowledgement of a received packet.
+	fn write_acknowledgement(
+		&mut self,
+		packet: &Packet,
+		ack: &Acknowledgement,
+	) -> Result<(), Ics20Error>;
+
+	/// Surfaces the events of sending a forwarded packet, the same way the host does for
+	/// transfers sent by its users.
+	fn deposit_ibc_events(&mut self, events: Vec<IbcEvent>);
+}
+
+fn forward_timeout(
+	ctx: &impl Ics20Context,
+	channel_id: ChannelId,
+	timeout: u64,
+) -> Result<Timestamp, Ics20Error> {
+	(ctx.host_timestamp() + Duration::from_nanos(timeout))
+		.map_err(|e| Ics20Error::forward_failed(channel_id, e.to_string()))
+}
+
+/// Undoes the receive of a packet whose forward failed, so that refunding the original sender
+/// does not create tokens out of thin air.
+fn revert_recv_packet(ctx: &mut impl Ics20Context, packet: &Packet) -> Result<(), Ics20Error> {
+	let data = serde_json::from_slice::<PacketData>(&packet.data)
+		.map_err(|_| Ics20Error::packet_data_deserialization())?;
+	revert_recv_packet_token(ctx, packet, &data)
+}
+
+fn fail_forward(
+	ctx: &mut impl ForwardKeeper,
+	packet: &Packet,
+	err: Ics20Error,
+) -> Result<(), Ics20Error> {
+	revert_recv_packet(ctx, packet)?;
+	ctx.write_acknowledgement(packet, &Acknowledgement::from_error(err))
+}
+
+fn send_forward(
+	ctx: &mut impl ForwardKeeper,
+	forward: InFlightForward,
+	msg: MsgTransfer<PrefixedCoin>,
+) -> Result<(), Ics20Error> {
+	let port_channel_id = (msg.source_port.clone(), msg.source_channel);
+	let sequence = ctx.get_next_sequence_send(&port_channel_id).map_err(Ics20Error::ics04_channel)?;
+
+	let mut output = HandlerOutputBuilder::new();
+	send_transfer(ctx, &mut output, msg)?;
+
+	ctx.store_in_flight_forward((port_channel_id.0, port_channel_id.1, sequence), forward)?;
+	ctx.deposit_ibc_events(output.with_result(()).events);
+	Ok(())
+}
+
+/// Returns the packet data and forward metadata of a packet that should be forwarded.
+pub fn forward_metadata(packet: &Packet) -> Option<(PacketData, ForwardMetadata)> {
+	let data = serde_json::from_slice::<PacketData>(&packet.data).ok()?;
+	let metadata = ForwardMetadata::from_memo(&data.memo)?;
+	Some((data, metadata))
+}
+
+/// Receives a packet carrying forward metadata and sends the tokens on to the next hop.
+///
+/// The acknowledgement of `packet` is only written here if the packet cannot be received or
+/// forwarded, otherwise it is written once the forwarded packet settles.
+pub fn forward_recv_packet<Ctx: 'static + ForwardKeeper>(
+	ctx: &mut Ctx,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	data: PacketData,
+	metadata: ForwardMetadata,
+) -> Result<(), Ics20Error> {
+	if let Err(e) = process_recv_packet(ctx, output, packet, data.clone()) {
+		return ctx.write_acknowledgement(packet, &Acknowledgement::from_error(e))
+	}
+
+	let recv_event = RecvEvent {
+		receiver: data.receiver.clone(),
+		denom: data.token.denom.clone(),
+		amount: data.token.amount,
+		memo: data.memo.clone(),
+		success: true,
+	};
+	output.emit(recv_event.into());
+
+	let timeout_timestamp = match forward_timeout(&*ctx, metadata.channel, metadata.timeout) {
+		Ok(timestamp) => timestamp,
+		Err(e) => return fail_forward(ctx, packet, e),
+	};
+	let msg = MsgTransfer {
+		source_port: metadata.port,
+		source_channel: metadata.channel,
+		token: received_coin(packet, &data),
+		tokens: vec![],
+		sender: data.receiver,
+		receiver: metadata.receiver,
+		timeout_height: Height::zero(),
+		timeout_timestamp,
+		memo: metadata.next.map(|next| next.to_string()).unwrap_or_default(),
+	};
+	let forward = InFlightForward {
+		original_packet: packet.clone(),
+		retries: metadata.retries,
+		timeout: metadata.timeout,
+	};
+
+	match send_forward(ctx, forward, msg) {
+		Ok(()) => Ok(()),
+		Err(e) => fail_forward(ctx, packet, e),
+	}
+}
+
+/// Settles the forward of an acknowledged packet, if it was one, once the regular ICS20
+/// acknowledgement handling has refunded the intermediate account on error.
+pub fn on_forward_acknowledgement(
+	ctx: &mut impl ForwardKeeper,
+	packet: &Packet,
+	ack: &Acknowledgement,
+) -> Result<(), Ics20Error> {
+	let key = (packet.source_port.clone(), packet.source_channel, packet.sequence);
+	let forward = match ctx.get_in_flight_forward(&key)? {
+		Some(forward) => forward,
+		None => return Ok(()),
+	};
+	ctx.delete_in_flight_forward(&key)?;
+
+	match ack {
+		Acknowledgement::Success(_) =>
+			ctx.write_acknowledgement(&forward.original_packet, &Acknowledgement::success()),
+		Acknowledgement::Error(err) => fail_forward(
+			ctx,
+			&forward.original_packet,
+			Ics20Error::forward_failed(packet.source_channel, err.clone()),
+		),
+	}
+}
+
+/// Settles or retries the forward of a timed out packet, if it was one, once the regular ICS20
+/// timeout handling has refunded the intermediate account.
+pub fn on_forward_timeout(ctx: &mut impl ForwardKeeper, packet: &Packet) -> Result<(), Ics20Error> {
+	let key = (packet.source_port.clone(), packet.source_channel, packet.sequence);
+	let mut forward = match ctx.get_in_flight_forward(&key)? {
+		Some(forward) => forward,
+		None => return Ok(()),
+	};
+	ctx.delete_in_flight_forward(&key)?;
+
+	if forward.retries == 0 {
+		let err = Ics20Error::forward_failed(packet.source_channel, "packet timed out".to_string());
+		return fail_forward(ctx, &forward.original_packet, err)
+	}
+	forward.retries -= 1;
+
+	let data = serde_json::from_slice::<PacketData>(&packet.data)
+		.map_err(|_| Ics20Error::packet_data_deserialization())?;
+	let original_packet = forward.original_packet.clone();
+	let retry = forward_timeout(&*ctx, packet.source_channel, forward.timeout).and_then(
+		|timeout_timestamp| {
+			let msg = MsgTransfer {
+				source_port: packet.source_port.clone(),
+				source_channel: packet.source_channel,
+				token: data.token,
+				tokens: vec![],
+				sender: data.sender,
+				receiver: data.receiver,
+				timeout_height: Height::zero(),
+				timeout_timestamp,
+				memo: data.memo,
+			};
+			send_forward(ctx, forward, msg)
+		},
+	);
+
+	match retry {
+		Ok(()) => Ok(()),
+		Err(e) => fail_forward(ctx, &original_packet, e),
+	}
+}
+
+/// An ICS20 module that forwards transfers carrying forward metadata, see the
+/// [module documentation](self).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForwardingMiddleware<Ctx> {
+	ctx: Ctx,
+}
+
+impl<Ctx> ForwardingMiddleware<Ctx> {
+	pub fn new(ctx: Ctx) -> Self {
+		Self { ctx }
+	}
+}
+
+impl<Ctx> Module for ForwardingMiddleware<Ctx>
+where
+	Ctx: 'static + ForwardKeeper + Clone + Send + Sync,
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		on_chan_open_init(
+			&mut self.ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+		)
+		.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		on_chan_open_try(
+			&mut self.ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			counterparty_version,
+		)
+		.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		on_chan_open_ack(&mut self.ctx, output, port_id, channel_id, counterparty_version)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		on_chan_open_confirm(&mut self.ctx, output, port_id, channel_id)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		on_chan_close_init(&mut self.ctx, output, port_id, channel_id)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		on_chan_close_confirm(&mut self.ctx, output, port_id, channel_id)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_chan_upgrade_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		proposed_upgrade: &UpgradeFields,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		on_chan_upgrade_init(&mut self.ctx, output, port_id, channel_id, proposed_upgrade)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_upgrade: &UpgradeFields,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		on_chan_upgrade_try(&mut self.ctx, output, port_id, channel_id, counterparty_upgrade)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &Packet,
+		relayer: &Signer,
+	) -> Result<OnRecvPacketAck, Ics04Error> {
+		let mut ctx = self.ctx.clone();
+		let result = match forward_metadata(packet) {
+			Some((data, metadata)) => forward_recv_packet(&mut ctx, output, packet, data, metadata),
+			None => {
+				let ack = ics20_on_recv_packet(&mut ctx, output, packet, relayer);
+				ctx.write_acknowledgement(packet, &ack)
+			},
+		};
+		// The acknowledgement goes through the host, now or once the forward settles.
+		result
+			.map(|_| OnRecvPacketAck::Async)
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &Packet,
+		acknowledgement: &GenericAcknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		ics20_on_acknowledgement_packet(&mut self.ctx, output, packet, acknowledgement, relayer)
+			.and_then(|_| {
+				let ack = serde_json::from_slice::<Acknowledgement>(acknowledgement.as_ref())
+					.map_err(|_| Ics20Error::ack_deserialization())?;
+				on_forward_acknowledgement(&mut self.ctx, packet, &ack)
+			})
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		ics20_on_timeout_packet(&mut self.ctx, output, packet, relayer)
+			.and_then(|_| on_forward_timeout(&mut self.ctx, packet))
+			.map_err(|e| Ics04Error::app_module(e.to_string()))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+
+	use crate::{
+		applications::transfer::{
+			acknowledgement::Acknowledgement,
+			context::Ics20Reader,
+			forward::{ForwardKeeper, ForwardMetadata, ForwardingMiddleware},
+			packet::PacketData,
+			Amount, PrefixedCoin, PrefixedDenom,
+		},
+		core::{
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				commitment::{compute_ack_commitment, AcknowledgementCommitment},
+				context::ChannelReader,
+				msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+				packet::{Packet, Sequence},
+				upgrade::UpgradeFields,
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			ics26_routing::context::{Module, ModuleOutputBuilder},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		signer::Signer,
+		test_utils::{get_dummy_account_id, get_dummy_bech32_account, DummyTransferModule},
+		timestamp::ZERO_DURATION,
+		Height,
+	};
+
+	const FINAL_RECEIVER: &str = "osmo1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2m6r5m7p";
+
+	/// Chain B of an A -> B -> C route, connected to A over channel-0 and to C over channel-1.
+	fn chain_b() -> (MockContext<MockClientTypes>, DummyTransferModule<MockClientTypes>) {
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let channel_end = |counterparty_channel| {
+			ChannelEnd::new(
+				State::Open,
+				Order::Unordered,
+				Counterparty::new(PortId::transfer(), Some(ChannelId::new(counterparty_channel))),
+				vec![ConnectionId::default()],
+				Version::ics20(),
+			)
+		};
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), Height::new(0, 10))
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(PortId::transfer(), ChannelId::new(0), channel_end(1))
+			.with_channel(PortId::transfer(), ChannelId::new(1), channel_end(0))
+			.with_send_sequence(PortId::transfer(), ChannelId::new(1), Sequence::from(1));
+		let module = DummyTransferModule::new(ctx.ibc_store_share());
+		(ctx, module)
+	}
+
+	/// The account receiving the tokens on B, which forwards them to C.
+	fn intermediate() -> Signer {
+		get_dummy_bech32_account().parse().unwrap()
+	}
+
+	fn voucher() -> PrefixedDenom {
+		"transfer/channel-0/uatom".parse().unwrap()
+	}
+
+	/// A transfer of 100uatom, native to A, sent from A to B.
+	fn packet_from_a(memo: String) -> Packet {
+		let data = PacketData {
+			token: PrefixedCoin { denom: "uatom".parse().unwrap(), amount: Amount::from(100u64) },
+			sender: get_dummy_bech32_account().parse().unwrap(),
+			receiver: intermediate(),
+			memo,
+		};
+		Packet {
+			sequence: 1.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: ChannelId::new(0),
+			data: serde_json::to_vec(&data).unwrap(),
+			..Default::default()
+		}
+	}
+
+	fn forward_memo(retries: u8, next: serde_json::Value) -> String {
+		serde_json::json!({
+			"forward": {
+				"receiver": FINAL_RECEIVER,
+				"port": "transfer",
+				"channel": "channel-1",
+				"retries": retries,
+				"next": next,
+			}
+		})
+		.to_string()
+	}
+
+	fn original_ack(
+		module: &DummyTransferModule<MockClientTypes>,
+	) -> Option<AcknowledgementCommitment> {
+		module.get_packet_acknowledgement(&(PortId::transfer(), ChannelId::new(0), 1.into())).ok()
+	}
+
+	fn success_ack(module: &DummyTransferModule<MockClientTypes>) -> AcknowledgementCommitment {
+		compute_ack_commitment(&Acknowledgement::success().as_ref().to_vec().into())
+	}
+
+	fn last_sent_packet(module: &DummyTransferModule<MockClientTypes>) -> Packet {
+		module
+			.ibc_events()
+			.into_iter()
+			.rev()
+			.find_map(|event| match event {
+				IbcEvent::SendPacket(e) => Some(e.packet),
+				_ => None,
+			})
+			.expect("no packet was forwarded")
+	}
+
+	#[test]
+	fn forward_is_acknowledged_once_the_next_hop_acknowledges() {
+		let (ctx, module) = chain_b();
+		let mut middleware = ForwardingMiddleware::new(module.clone());
+		let relayer = get_dummy_account_id();
+		let next = serde_json::json!({ "forward": { "receiver": "juno1", "channel": "channel-7" }});
+		let packet = packet_from_a(forward_memo(0, next.clone()));
+
+		middleware
+			.on_recv_packet(&ctx, &mut ModuleOutputBuilder::new(), &packet, &relayer)
+			.unwrap();
+		assert_eq!(original_ack(&module), None, "the ack is held back until C acknowledges");
+
+		let escrow =
+			module.get_channel_escrow_address(&PortId::transfer(), ChannelId::new(1)).unwrap();
+		assert_eq!(module.balance(&intermediate(), &voucher()), Amount::from(0u64));
+		assert_eq!(module.balance(&escrow, &voucher()), Amount::from(100u64));
+
+		let forwarded = last_sent_packet(&module);
+		let data: PacketData = serde_json::from_slice(&forwarded.data).unwrap();
+		assert_eq!(forwarded.source_channel, ChannelId::new(1));
+		assert_eq!(forwarded.destination_channel, ChannelId::new(0));
+		assert_eq!(data.receiver.as_ref(), FINAL_RECEIVER);
+		assert_eq!(data.token.denom, voucher());
+		assert_eq!(data.memo, next.to_string());
+
+		let key = (PortId::transfer(), ChannelId::new(1), forwarded.sequence);
+		assert!(module.get_in_flight_forward(&key).unwrap().is_some());
+
+		let ack = GenericAcknowledgement::from(br#""AQ==""#.to_vec());
+		middleware
+			.on_acknowledgement_packet(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&forwarded,
+				&ack,
+				&relayer,
+			)
+			.unwrap();
+		assert_eq!(original_ack(&module), Some(success_ack(&module)));
+		assert_eq!(module.get_in_flight_forward(&key).unwrap(), None);
+		assert_eq!(module.balance(&escrow, &voucher()), Amount::from(100u64));
+	}
+
+	#[test]
+	fn forward_timeout_is_retried_then_refunded() {
+		let (ctx, module) = chain_b();
+		let mut middleware = ForwardingMiddleware::new(module.clone());
+		let relayer = get_dummy_account_id();
+		let packet = packet_from_a(forward_memo(1, serde_json::Value::Null));
+
+		middleware
+			.on_recv_packet(&ctx, &mut ModuleOutputBuilder::new(), &packet, &relayer)
+			.unwrap();
+		let first = last_sent_packet(&module);
+
+		middleware
+			.on_timeout_packet(&ctx, &mut ModuleOutputBuilder::new(), &first, &relayer)
+			.unwrap();
+		assert_eq!(original_ack(&module), None, "the timed out forward is retried");
+		let retry = last_sent_packet(&module);
+		assert_eq!(retry.sequence, first.sequence.increment());
+		assert_eq!(retry.data, first.data);
+		assert_eq!(
+			module
+				.get_in_flight_forward(&(PortId::transfer(), ChannelId::new(1), first.sequence))
+				.unwrap(),
+			None
+		);
+
+		middleware
+			.on_timeout_packet(&ctx, &mut ModuleOutputBuilder::new(), &retry, &relayer)
+			.unwrap();
+		let ack = original_ack(&module).expect("the original packet is acknowledged");
+		assert_ne!(ack, success_ack(&module), "A refunds the sender on the error ack");
+
+		// the vouchers refunded by the timeout are burnt again, they are redeemed on A
+		let escrow =
+			module.get_channel_escrow_address(&PortId::transfer(), ChannelId::new(1)).unwrap();
+		assert_eq!(module.balance(&intermediate(), &voucher()), Amount::from(0u64));
+		assert_eq!(module.balance(&escrow, &voucher()), Amount::from(0u64));
+		assert_eq!(
+			module
+				.get_in_flight_forward(&(PortId::transfer(), ChannelId::new(1), retry.sequence))
+				.unwrap(),
+			None
+		);
+	}
+
+	#[test]
+	fn malformed_forward_memo_is_received_normally() {
+		let memos = [
+			"not json".to_string(),
+			r#"{"forward":{"receiver":"osmo1","port":"transfer"}}"#.to_string(),
+			r#"{"forward":{"receiver":" ","port":"transfer","channel":"channel-1"}}"#.to_string(),
+			r#"{"forward":{"receiver":"osmo1","port":"transfer","channel":"chan"}}"#.to_string(),
+		];
+
+		for memo in memos {
+			assert_eq!(ForwardMetadata::from_memo(&memo), None, "{}", memo);
+
+			let (ctx, module) = chain_b();
+			let middleware = ForwardingMiddleware::new(module.clone());
+			let packet = packet_from_a(memo);
+
+			middleware
+				.on_recv_packet(
+					&ctx,
+					&mut ModuleOutputBuilder::new(),
+					&packet,
+					&get_dummy_account_id(),
+				)
+				.unwrap();
+			assert_eq!(original_ack(&module), Some(success_ack(&module)));
+			assert_eq!(module.balance(&intermediate(), &voucher()), Amount::from(100u64));
+			assert!(module.ibc_events().is_empty(), "nothing is forwarded");
+		}
+	}
+
+	#[test]
+	fn upgrade_must_keep_the_channel_usable_for_transfers() {
+		let (ctx, module) = chain_b();
+		let mut middleware = ForwardingMiddleware::new(module);
+		let relayer = get_dummy_account_id();
+		let upgrade = |ordering, version: &str| {
+			UpgradeFields::new(ordering, Version::new(version.to_string()))
+		};
+
+		let mut upgrade_init = |fields: &UpgradeFields| {
+			middleware.on_chan_upgrade_init(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&PortId::transfer(),
+				&ChannelId::new(0),
+				fields,
+				&relayer,
+			)
+		};
+		assert!(upgrade_init(&upgrade(Order::Unordered, "ics20-2")).is_ok());
+		assert!(upgrade_init(&upgrade(Order::Ordered, "ics20-2")).is_err());
+		assert!(upgrade_init(&upgrade(Order::Unordered, "ics721-1")).is_err());
+
+		assert!(middleware
+			.on_chan_upgrade_try(
+				&ctx,
+				&mut ModuleOutputBuilder::new(),
+				&PortId::transfer(),
+				&ChannelId::new(0),
+				&upgrade(Order::Ordered, "ics20-1"),
+				&relayer,
+			)
+			.is_err());
+	}
+}
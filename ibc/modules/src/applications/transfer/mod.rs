@@ -20,6 +20,7 @@ pub mod context;
 pub mod denom;
 pub mod error;
 pub mod events;
+pub mod forward;
 pub mod msgs;
 pub mod packet;
 pub mod relay;
@@ -35,3 +36,10 @@ pub const PORT_ID_STR: &str = "transfer";
 
 /// ICS20 application current version.
 pub const VERSION: &str = "ics20-1";
+
+/// Opt-in ICS20 version whose packets carry several coins, see
+/// [`MultiTokenPacketData`](packet::MultiTokenPacketData).
+pub const MULTI_TOKEN_VERSION: &str = "ics20-2";
+
+/// The prefix of the hashed voucher denominations, e.g. `ibc/{hash}`.
+pub const DENOM_HASH_PREFIX: &str = "ibc";
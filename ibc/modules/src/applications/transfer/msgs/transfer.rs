@@ -50,6 +50,9 @@ pub struct MsgTransfer<C = Coin> {
 	pub source_channel: ChannelId,
 	/// the tokens to be transferred
 	pub token: C,
+	/// further tokens to be transferred in the same packet, only accepted by channels that
+	/// negotiated the multi token version
+	pub tokens: Vec<C>,
 	/// the sender address
 	pub sender: Signer,
 	/// the recipient address on the destination chain
@@ -60,6 +63,8 @@ pub struct MsgTransfer<C = Coin> {
 	/// Timeout timestamp relative to the current block timestamp.
 	/// The timeout is disabled when set to 0.
 	pub timeout_timestamp: Timestamp,
+	/// Optional memo, carried in the packet data
+	pub memo: String,
 }
 
 impl Msg for MsgTransfer {
@@ -89,6 +94,10 @@ impl TryFrom<RawMsgTransfer> for MsgTransfer {
 			})?,
 		};
 
+		// The first of the tokens stands in for the token when only the tokens are set
+		let mut tokens = raw_msg.tokens.into_iter();
+		let token = raw_msg.token.or_else(|| tokens.next()).ok_or_else(Error::invalid_token)?;
+
 		Ok(MsgTransfer {
 			source_port: raw_msg
 				.source_port
@@ -98,11 +107,13 @@ impl TryFrom<RawMsgTransfer> for MsgTransfer {
 				.source_channel
 				.parse()
 				.map_err(|e| Error::invalid_channel_id(raw_msg.source_channel.clone(), e))?,
-			token: raw_msg.token.ok_or_else(Error::invalid_token)?,
+			token,
+			tokens: tokens.collect(),
 			sender: raw_msg.sender.parse().map_err(Error::signer)?,
 			receiver: raw_msg.receiver.parse().map_err(Error::signer)?,
 			timeout_height,
 			timeout_timestamp,
+			memo: raw_msg.memo,
 		})
 	}
 }
@@ -113,10 +124,12 @@ impl From<MsgTransfer> for RawMsgTransfer {
 			source_port: domain_msg.source_port.to_string(),
 			source_channel: domain_msg.source_channel.to_string(),
 			token: Some(domain_msg.token),
+			tokens: domain_msg.tokens,
 			sender: domain_msg.sender.to_string(),
 			receiver: domain_msg.receiver.to_string(),
 			timeout_height: Some(domain_msg.timeout_height.into()),
 			timeout_timestamp: domain_msg.timeout_timestamp.nanoseconds(),
+			memo: domain_msg.memo,
 		}
 	}
 }
@@ -163,10 +176,12 @@ pub mod test_util {
 			source_channel: ChannelId::default(),
 			token: BaseCoin { denom: "uatom".parse().unwrap(), amount: U256::from(10).into() }
 				.into(),
+			tokens: vec![],
 			sender: address.clone(),
 			receiver: address,
 			timeout_timestamp: Timestamp::now().add(Duration::from_secs(10)).unwrap(),
 			timeout_height: Height { revision_number: 0, revision_height: height },
+			memo: String::new(),
 		}
 	}
 }
@@ -12,7 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloc::string::ToString;
+use alloc::{
+	string::{String, ToString},
+	vec,
+	vec::Vec,
+};
 use core::{convert::TryFrom, str::FromStr};
 
 use ibc_proto::ibc::applications::transfer::v2::FungibleTokenPacketData as RawPacketData;
@@ -21,11 +25,17 @@ use serde::{Deserialize, Serialize};
 use super::{error::Error, Amount, PrefixedCoin, PrefixedDenom};
 use crate::signer::Signer;
 
+/// The ICS20 packet payload. It is encoded as the JSON used by ibc-go, with the denom and amount
+/// as plain strings and the memo omitted when empty, so packets to and from chains that predate
+/// the memo field keep their encoding.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "JsonPacketData", into = "JsonPacketData")]
 pub struct PacketData {
 	pub token: PrefixedCoin,
 	pub sender: Signer,
 	pub receiver: Signer,
+	/// Arbitrary data for the receiving chain, empty if the sender did not set one.
+	pub memo: String,
 }
 
 impl TryFrom<RawPacketData> for PacketData {
@@ -39,6 +49,7 @@ impl TryFrom<RawPacketData> for PacketData {
 			token: PrefixedCoin { denom, amount },
 			sender: raw_pkt_data.sender.parse().map_err(Error::signer)?,
 			receiver: raw_pkt_data.receiver.parse().map_err(Error::signer)?,
+			memo: raw_pkt_data.memo,
 		})
 	}
 }
@@ -50,6 +61,236 @@ impl From<PacketData> for RawPacketData {
 			amount: pkt_data.token.amount.to_string(),
 			sender: pkt_data.sender.to_string(),
 			receiver: pkt_data.receiver.to_string(),
+			memo: pkt_data.memo,
 		}
 	}
 }
+
+/// The JSON form of [`PacketData`]. Fields are declared in alphabetical order, matching the
+/// sorted JSON ibc-go commits to.
+#[derive(Serialize, Deserialize)]
+struct JsonPacketData {
+	amount: String,
+	denom: String,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	memo: String,
+	receiver: String,
+	sender: String,
+}
+
+impl TryFrom<JsonPacketData> for PacketData {
+	type Error = Error;
+
+	fn try_from(json: JsonPacketData) -> Result<Self, Self::Error> {
+		RawPacketData {
+			denom: json.denom,
+			amount: json.amount,
+			sender: json.sender,
+			receiver: json.receiver,
+			memo: json.memo,
+		}
+		.try_into()
+	}
+}
+
+impl From<PacketData> for JsonPacketData {
+	fn from(pkt_data: PacketData) -> Self {
+		let raw = RawPacketData::from(pkt_data);
+		Self {
+			amount: raw.amount,
+			denom: raw.denom,
+			memo: raw.memo,
+			receiver: raw.receiver,
+			sender: raw.sender,
+		}
+	}
+}
+
+/// The packet payload of channels that negotiated the
+/// [`MULTI_TOKEN_VERSION`](super::MULTI_TOKEN_VERSION), moving several coins from one sender to
+/// one receiver. It is encoded as JSON like [`PacketData`], with the coins under `tokens`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "JsonMultiTokenPacketData", into = "JsonMultiTokenPacketData")]
+pub struct MultiTokenPacketData {
+	/// The coins transferred, never empty.
+	pub tokens: Vec<PrefixedCoin>,
+	pub sender: Signer,
+	pub receiver: Signer,
+	/// Arbitrary data for the receiving chain, empty if the sender did not set one.
+	pub memo: String,
+}
+
+impl MultiTokenPacketData {
+	/// Returns the single coin packet data of each of the transferred coins, in order.
+	pub fn token_packets(&self) -> Vec<PacketData> {
+		self.tokens
+			.iter()
+			.map(|token| PacketData {
+				token: token.clone(),
+				sender: self.sender.clone(),
+				receiver: self.receiver.clone(),
+				memo: self.memo.clone(),
+			})
+			.collect()
+	}
+}
+
+impl From<PacketData> for MultiTokenPacketData {
+	fn from(data: PacketData) -> Self {
+		Self {
+			tokens: vec![data.token],
+			sender: data.sender,
+			receiver: data.receiver,
+			memo: data.memo,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonToken {
+	amount: String,
+	denom: String,
+}
+
+/// The JSON form of [`MultiTokenPacketData`], with fields in alphabetical order.
+#[derive(Serialize, Deserialize)]
+struct JsonMultiTokenPacketData {
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	memo: String,
+	receiver: String,
+	sender: String,
+	tokens: Vec<JsonToken>,
+}
+
+impl TryFrom<JsonMultiTokenPacketData> for MultiTokenPacketData {
+	type Error = Error;
+
+	fn try_from(json: JsonMultiTokenPacketData) -> Result<Self, Self::Error> {
+		if json.tokens.is_empty() {
+			return Err(Error::empty_tokens())
+		}
+		let tokens = json
+			.tokens
+			.into_iter()
+			.map(|token| {
+				Ok(PrefixedCoin {
+					denom: PrefixedDenom::from_str(&token.denom)?,
+					amount: Amount::from_str(&token.amount)?,
+				})
+			})
+			.collect::<Result<_, Error>>()?;
+		Ok(Self {
+			tokens,
+			sender: json.sender.parse().map_err(Error::signer)?,
+			receiver: json.receiver.parse().map_err(Error::signer)?,
+			memo: json.memo,
+		})
+	}
+}
+
+impl From<MultiTokenPacketData> for JsonMultiTokenPacketData {
+	fn from(data: MultiTokenPacketData) -> Self {
+		Self {
+			memo: data.memo,
+			receiver: data.receiver.to_string(),
+			sender: data.sender.to_string(),
+			tokens: data
+				.tokens
+				.into_iter()
+				.map(|token| JsonToken {
+					amount: token.amount.to_string(),
+					denom: token.denom.to_string(),
+				})
+				.collect(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use test_log::test;
+
+	/// A packet sent by an ibc-go v7 chain.
+	const IBC_GO_MEMO_PACKET: &str = r#"{"amount":"100","denom":"transfer/channel-0/uatom","memo":"{\"forward\":{\"receiver\":\"bob\",\"port\":\"transfer\",\"channel\":\"channel-1\"}}","receiver":"5yNZjX24n2eg7W6EVamaTXNQbWCwchhThEaSWB7V3GRjtHeL","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng"}"#;
+
+	fn packet_data(memo: &str) -> PacketData {
+		PacketData {
+			token: PrefixedCoin {
+				denom: "transfer/channel-0/uatom".parse().unwrap(),
+				amount: 100u64.into(),
+			},
+			sender: "cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng".parse().unwrap(),
+			receiver: "5yNZjX24n2eg7W6EVamaTXNQbWCwchhThEaSWB7V3GRjtHeL".parse().unwrap(),
+			memo: memo.to_string(),
+		}
+	}
+
+	#[test]
+	fn packet_data_without_memo_omits_it() {
+		let data = packet_data("");
+		let json = serde_json::to_string(&data).unwrap();
+		assert!(!json.contains("memo"));
+		assert_eq!(serde_json::from_str::<PacketData>(&json).unwrap(), data);
+
+		let raw = RawPacketData::from(data.clone());
+		assert_eq!(PacketData::try_from(raw).unwrap(), data);
+	}
+
+	#[test]
+	fn packet_data_with_memo_round_trips() {
+		let data = packet_data(r#"{"forward":{"port":"transfer"}}"#);
+		let json = serde_json::to_string(&data).unwrap();
+		assert_eq!(serde_json::from_str::<PacketData>(&json).unwrap(), data);
+
+		let raw = RawPacketData::from(data.clone());
+		assert_eq!(raw.memo, data.memo);
+		assert_eq!(PacketData::try_from(raw).unwrap(), data);
+	}
+
+	#[test]
+	fn decode_ibc_go_packet_with_memo() {
+		let data = serde_json::from_str::<PacketData>(IBC_GO_MEMO_PACKET).unwrap();
+		assert_eq!(
+			data,
+			packet_data(
+				r#"{"forward":{"receiver":"bob","port":"transfer","channel":"channel-1"}}"#
+			)
+		);
+		// Encoding gives back the bytes the sender committed to.
+		assert_eq!(serde_json::to_string(&data).unwrap(), IBC_GO_MEMO_PACKET);
+	}
+
+	#[test]
+	fn multi_token_packet_data_round_trips() {
+		let json = r#"{"receiver":"5yNZjX24n2eg7W6EVamaTXNQbWCwchhThEaSWB7V3GRjtHeL","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng","tokens":[{"amount":"100","denom":"transfer/channel-0/uatom"},{"amount":"5","denom":"uosmo"}]}"#;
+		let data = serde_json::from_str::<MultiTokenPacketData>(json).unwrap();
+		let packets = data.token_packets();
+		assert_eq!(packets.len(), 2);
+		assert_eq!(packets[0], packet_data(""));
+		assert_eq!(packets[1].token.denom.to_string(), "uosmo");
+		assert_eq!(serde_json::to_string(&data).unwrap(), json);
+
+		let empty = r#"{"receiver":"5yNZjX24n2eg7W6EVamaTXNQbWCwchhThEaSWB7V3GRjtHeL","sender":"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng","tokens":[]}"#;
+		assert!(serde_json::from_str::<MultiTokenPacketData>(empty).is_err());
+	}
+
+	#[test]
+	fn packet_data_with_amount_above_u128_keeps_full_precision() {
+		let amount = "1234567890123456789012345678901234567890";
+		let json = IBC_GO_MEMO_PACKET.replacen(
+			r#""amount":"100""#,
+			&format!(r#""amount":"{}""#, amount),
+			1,
+		);
+		let data = serde_json::from_str::<PacketData>(&json).unwrap();
+		assert_eq!(data.token.amount.to_string(), amount);
+		assert!(u128::try_from(data.token.amount).is_err());
+		assert_eq!(serde_json::to_string(&data).unwrap(), json);
+
+		let raw = RawPacketData::from(data.clone());
+		assert_eq!(raw.amount, amount);
+		assert_eq!(PacketData::try_from(raw).unwrap(), data);
+	}
+}
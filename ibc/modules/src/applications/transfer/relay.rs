@@ -15,10 +15,16 @@
 //! This module implements the processing logic for ICS20 (token transfer) message.
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, is_sender_chain_source,
-		packet::PacketData,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		is_receiver_chain_source, is_sender_chain_source,
+		packet::{MultiTokenPacketData, PacketData},
+		Amount, PrefixedCoin, TracePrefix,
+	},
+	core::{
+		ics04_channel::packet::Packet,
+		ics24_host::identifier::{ChannelId, PortId},
 	},
-	core::ics04_channel::packet::Packet,
 	prelude::*,
 };
 
@@ -27,6 +33,45 @@ pub mod on_recv_packet;
 pub mod on_timeout_packet;
 pub mod send_transfer;
 
+/// Moves `coin` from `from` into the escrow account of the channel and records it in the
+/// channel's escrow balance.
+pub(crate) fn escrow_coin<Ctx: Ics20Context>(
+	ctx: &mut Ctx,
+	port_id: &PortId,
+	channel_id: ChannelId,
+	from: &<Ctx as Ics20Context>::AccountId,
+	coin: &PrefixedCoin,
+) -> Result<(), Ics20Error> {
+	let escrow_address = ctx.get_channel_escrow_address(port_id, channel_id)?;
+	ctx.send_coins(from, &escrow_address, coin)?;
+
+	let balance = ctx
+		.escrow_balance(port_id, &channel_id, &coin.denom)
+		.checked_add(coin.amount)
+		.ok_or_else(|| Ics20Error::amount_overflow(coin.amount))?;
+	ctx.store_escrow_balance(port_id, &channel_id, &coin.denom, balance)
+}
+
+/// Releases `coin` from the escrow account of the channel to `to` and deducts it from the
+/// channel's escrow balance.
+pub(crate) fn unescrow_coin<Ctx: Ics20Context>(
+	ctx: &mut Ctx,
+	port_id: &PortId,
+	channel_id: ChannelId,
+	to: &<Ctx as Ics20Context>::AccountId,
+	coin: &PrefixedCoin,
+) -> Result<(), Ics20Error> {
+	let escrow_address = ctx.get_channel_escrow_address(port_id, channel_id)?;
+	ctx.send_coins(&escrow_address, to, coin)?;
+
+	// Tokens escrowed before the host started keeping track can still be released.
+	let balance = ctx
+		.escrow_balance(port_id, &channel_id, &coin.denom)
+		.checked_sub(coin.amount)
+		.unwrap_or_else(|| Amount::from(0u64));
+	ctx.store_escrow_balance(port_id, &channel_id, &coin.denom, balance)
+}
+
 fn refund_packet_token(
 	ctx: &mut impl Ics20Context,
 	packet: &Packet,
@@ -41,13 +86,88 @@ fn refund_packet_token(
 	if is_sender_chain_source(packet.source_port.clone(), packet.source_channel, &data.token.denom)
 	{
 		// unescrow tokens back to sender
-		let escrow_address =
-			ctx.get_channel_escrow_address(&packet.source_port, packet.source_channel)?;
-
-		ctx.send_coins(&escrow_address, &sender, &data.token)
+		unescrow_coin(ctx, &packet.source_port, packet.source_channel, &sender, &data.token)
 	}
 	// mint vouchers back to sender
 	else {
 		ctx.mint_coins(&sender, &data.token)
 	}
 }
+
+/// Takes back the refund of [`refund_packet_token`].
+fn revert_refund_packet_token(
+	ctx: &mut impl Ics20Context,
+	packet: &Packet,
+	data: &PacketData,
+) -> Result<(), Ics20Error> {
+	let sender = data
+		.sender
+		.clone()
+		.try_into()
+		.map_err(|_| Ics20Error::parse_account_failure())?;
+
+	if is_sender_chain_source(packet.source_port.clone(), packet.source_channel, &data.token.denom)
+	{
+		escrow_coin(ctx, &packet.source_port, packet.source_channel, &sender, &data.token)
+	} else {
+		ctx.burn_coins(&sender, &data.token)
+	}
+}
+
+/// Refunds all the coins of a packet, or none of them if one of the refunds fails.
+fn refund_packet_tokens(
+	ctx: &mut impl Ics20Context,
+	packet: &Packet,
+	data: &MultiTokenPacketData,
+) -> Result<(), Ics20Error> {
+	let tokens = data.token_packets();
+	for (i, token) in tokens.iter().enumerate() {
+		if let Err(e) = refund_packet_token(ctx, packet, token) {
+			for refunded in tokens[..i].iter().rev() {
+				revert_refund_packet_token(ctx, packet, refunded)?;
+			}
+			return Err(e)
+		}
+	}
+	Ok(())
+}
+
+/// The coin credited to the receiver of `packet` on this chain.
+pub(crate) fn received_coin(packet: &Packet, data: &PacketData) -> PrefixedCoin {
+	let mut coin = data.token.clone();
+	if is_receiver_chain_source(packet.source_port.clone(), packet.source_channel, &coin.denom) {
+		let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
+		coin.denom.remove_trace_prefix(&prefix);
+	} else {
+		let prefix = TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
+		coin.denom.add_trace_prefix(prefix);
+	}
+	coin
+}
+
+/// Undoes the receive of one coin of `packet`, so that refunding the original sender does not
+/// create tokens out of thin air.
+pub(crate) fn revert_recv_packet_token(
+	ctx: &mut impl Ics20Context,
+	packet: &Packet,
+	data: &PacketData,
+) -> Result<(), Ics20Error> {
+	let receiver = data
+		.receiver
+		.clone()
+		.try_into()
+		.map_err(|_| Ics20Error::parse_account_failure())?;
+	let coin = received_coin(packet, data);
+
+	if is_receiver_chain_source(
+		packet.source_port.clone(),
+		packet.source_channel,
+		&data.token.denom,
+	) {
+		// the tokens were unescrowed, put them back into escrow
+		escrow_coin(ctx, &packet.destination_port, packet.destination_channel, &receiver, &coin)
+	} else {
+		// the vouchers were minted, burn them
+		ctx.burn_coins(&receiver, &coin)
+	}
+}
@@ -14,8 +14,11 @@
 
 use crate::{
 	applications::transfer::{
-		acknowledgement::Acknowledgement, context::Ics20Context, error::Error as Ics20Error,
-		packet::PacketData, relay::refund_packet_token,
+		acknowledgement::Acknowledgement,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		packet::{MultiTokenPacketData, PacketData},
+		relay::{refund_packet_token, refund_packet_tokens},
 	},
 	core::ics04_channel::packet::Packet,
 };
@@ -32,3 +35,18 @@ pub fn process_ack_packet(
 
 	Ok(())
 }
+
+/// Like [`process_ack_packet`], refunding all the coins of the packet on an error
+/// acknowledgement, or none of them if one of the refunds fails.
+pub fn process_ack_packet_tokens(
+	ctx: &mut impl Ics20Context,
+	packet: &Packet,
+	data: &MultiTokenPacketData,
+	ack: &Acknowledgement,
+) -> Result<(), Ics20Error> {
+	if matches!(ack, Acknowledgement::Error(_)) {
+		refund_packet_tokens(ctx, packet, data)?;
+	}
+
+	Ok(())
+}
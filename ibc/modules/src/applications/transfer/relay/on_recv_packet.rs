@@ -14,8 +14,13 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, events::DenomTraceEvent,
-		is_receiver_chain_source, packet::PacketData, TracePrefix,
+		context::{denom_trace_hash, Ics20Context},
+		error::Error as Ics20Error,
+		events::DenomTraceEvent,
+		is_receiver_chain_source,
+		packet::{MultiTokenPacketData, PacketData},
+		relay::{revert_recv_packet_token, unescrow_coin},
+		TracePrefix,
 	},
 	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
 	prelude::*,
@@ -49,11 +54,15 @@ pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 			c.denom.remove_trace_prefix(&prefix);
 			c
 		};
+		ctx.check_recv(&coin.denom, coin.amount, &packet.destination_channel)?;
 
-		let escrow_address =
-			ctx.get_channel_escrow_address(&packet.destination_port, packet.destination_channel)?;
-
-		ctx.send_coins(&escrow_address, &receiver_account, &coin)
+		unescrow_coin(
+			ctx,
+			&packet.destination_port,
+			packet.destination_channel,
+			&receiver_account,
+			&coin,
+		)
 	} else {
 		// sender chain is the source, mint vouchers
 		let prefix = TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
@@ -62,6 +71,15 @@ pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 			c.denom.add_trace_prefix(prefix);
 			c
 		};
+		ctx.check_recv(&coin.denom, coin.amount, &packet.destination_channel)?;
+
+		ctx.mint_coins(&receiver_account, &coin)?;
+
+		// Only record the trace once the vouchers exist, so failed receives leave no trace behind
+		let hash = denom_trace_hash(&*ctx, &coin.denom.to_string());
+		if ctx.get_denom_trace(&hash)?.is_none() {
+			ctx.store_denom_trace(hash, coin.denom.clone())?;
+		}
 
 		let denom_trace_event = DenomTraceEvent {
 			trace_hash: ctx.denom_hash_string(&coin.denom),
@@ -69,6 +87,26 @@ pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 		};
 		output.emit(denom_trace_event.into());
 
-		ctx.mint_coins(&receiver_account, &coin)
+		Ok(())
+	}
+}
+
+/// Like [`process_recv_packet`], crediting the receiver with every coin of the packet, or with
+/// none of them if one of the coins cannot be received.
+pub fn process_recv_packet_tokens<Ctx: 'static + Ics20Context>(
+	ctx: &mut Ctx,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	data: MultiTokenPacketData,
+) -> Result<(), Ics20Error> {
+	let tokens = data.token_packets();
+	for (i, token) in tokens.iter().enumerate() {
+		if let Err(e) = process_recv_packet(ctx, output, packet, token.clone()) {
+			for received in tokens[..i].iter().rev() {
+				revert_recv_packet_token(ctx, packet, received)?;
+			}
+			return Err(e)
+		}
 	}
+	Ok(())
 }
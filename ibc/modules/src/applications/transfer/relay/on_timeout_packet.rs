@@ -14,8 +14,10 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, packet::PacketData,
-		relay::refund_packet_token,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		packet::{MultiTokenPacketData, PacketData},
+		relay::{refund_packet_token, refund_packet_tokens},
 	},
 	core::ics04_channel::packet::Packet,
 };
@@ -27,3 +29,13 @@ pub fn process_timeout_packet(
 ) -> Result<(), Ics20Error> {
 	refund_packet_token(ctx, packet, data)
 }
+
+/// Like [`process_timeout_packet`], refunding all the coins of the packet, or none of them if
+/// one of the refunds fails.
+pub fn process_timeout_packet_tokens(
+	ctx: &mut impl Ics20Context,
+	packet: &Packet,
+	data: &MultiTokenPacketData,
+) -> Result<(), Ics20Error> {
+	refund_packet_tokens(ctx, packet, data)
+}
@@ -14,8 +14,14 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error, events::TransferEvent, is_sender_chain_source,
-		msgs::transfer::MsgTransfer, packet::PacketData, Coin, PrefixedCoin,
+		context::{is_multi_token_channel, Ics20Context},
+		error::Error,
+		events::TransferEvent,
+		is_sender_chain_source,
+		msgs::transfer::MsgTransfer,
+		packet::{MultiTokenPacketData, PacketData},
+		relay::escrow_coin,
+		PrefixedCoin,
 	},
 	core::ics04_channel::{handler::send_packet::send_packet, packet::Packet},
 	events::ModuleEvent,
@@ -53,23 +59,47 @@ where
 		.get_next_sequence_send(&(msg.source_port.clone(), msg.source_channel))
 		.map_err(Error::ics04_channel)?;
 
-	let token = msg.token.try_into().map_err(|_| Error::invalid_token())?;
-	let denom = token.denom.clone();
-	let coin = Coin { denom: denom.clone(), amount: token.amount };
+	let tokens = core::iter::once(msg.token)
+		.chain(msg.tokens)
+		.map(|token| token.try_into().map_err(|_| Error::invalid_token()))
+		.collect::<Result<Vec<PrefixedCoin>, _>>()?;
+
+	let multi_token = is_multi_token_channel(&*ctx, &msg.source_port, &msg.source_channel)?;
+	if tokens.len() > 1 && !multi_token {
+		return Err(Error::multi_token_not_supported(
+			msg.source_channel,
+			source_channel_end.version().clone(),
+		))
+	}
 
 	let sender = msg.sender.clone().try_into().map_err(|_| Error::parse_account_failure())?;
 
-	if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &denom) {
-		let escrow_address =
-			ctx.get_channel_escrow_address(&msg.source_port, msg.source_channel)?;
-		ctx.send_coins(&sender, &escrow_address, &coin)?;
-	} else {
-		ctx.burn_coins(&sender, &coin)?;
+	for coin in &tokens {
+		ctx.check_send(&coin.denom, coin.amount, &msg.source_channel)?;
+
+		if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &coin.denom) {
+			escrow_coin(ctx, &msg.source_port, msg.source_channel, &sender, coin)?;
+		} else {
+			ctx.burn_coins(&sender, coin)?;
+		}
 	}
 
-	let data = {
-		let data =
-			PacketData { token: coin, sender: msg.sender.clone(), receiver: msg.receiver.clone() };
+	let token_list = tokens.iter().map(ToString::to_string).collect::<Vec<_>>().join(",");
+	let data = if multi_token {
+		let data = MultiTokenPacketData {
+			tokens,
+			sender: msg.sender.clone(),
+			receiver: msg.receiver.clone(),
+			memo: msg.memo.clone(),
+		};
+		serde_json::to_vec(&data).expect("MultiTokenPacketData's infallible Serialize impl failed")
+	} else {
+		let data = PacketData {
+			token: tokens[0].clone(),
+			sender: msg.sender.clone(),
+			receiver: msg.receiver.clone(),
+			memo: msg.memo.clone(),
+		};
 		serde_json::to_vec(&data).expect("PacketData's infallible Serialize impl failed")
 	};
 
@@ -93,10 +123,11 @@ where
 
 	output.log(format!(
 		"IBC fungible token transfer: {} --({})--> {}",
-		msg.sender, token, msg.receiver
+		msg.sender, token_list, msg.receiver
 	));
 
-	let transfer_event = TransferEvent { sender: msg.sender, receiver: msg.receiver };
+	let transfer_event =
+		TransferEvent { sender: msg.sender, receiver: msg.receiver, memo: msg.memo };
 	output.emit(ModuleEvent::from(transfer_event).into());
 
 	Ok(())
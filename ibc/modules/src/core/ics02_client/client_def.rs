@@ -86,6 +86,33 @@ pub trait ClientDef: Clone {
 		client_msg: Self::ClientMessage,
 	) -> Result<bool, Error>;
 
+	/// Verifies the misbehaviour evidence in `client_msg` and returns the frozen client state
+	/// that should be stored for the client. Fails if the evidence does not prove misbehaviour.
+	fn check_misbehaviour_and_update_state<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: ClientId,
+		client_state: Self::ClientState,
+		client_msg: Self::ClientMessage,
+	) -> Result<Self::ClientState, Error> {
+		self.verify_client_message(
+			ctx,
+			client_id.clone(),
+			client_state.clone(),
+			client_msg.clone(),
+		)?;
+		let found_misbehaviour = self.check_for_misbehaviour(
+			ctx,
+			client_id.clone(),
+			client_state.clone(),
+			client_msg.clone(),
+		)?;
+		if !found_misbehaviour {
+			return Err(Error::misbehaviour_not_found(client_id))
+		}
+		self.update_state_on_misbehaviour(client_state, client_msg)
+	}
+
 	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
 		&self,
 		ctx: &Ctx,
@@ -97,6 +124,18 @@ pub trait ClientDef: Clone {
 		proof_upgrade_consensus_state: Vec<u8>,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error>;
 
+	/// Checks that the substitute client state can replace the subject client state, i.e. that
+	/// every parameter other than the latest and frozen heights matches. Returns the client state
+	/// that should be stored for the subject client.
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		subject_client_id: ClientId,
+		substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<Self::ClientState, Error>;
+
 	/// Verification functions as specified in:
 	/// <https://github.com/cosmos/ibc/tree/master/spec/core/ics-002-client-semantics>
 	///
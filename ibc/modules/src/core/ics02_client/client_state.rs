@@ -13,16 +13,20 @@
 // limitations under the License.
 
 use crate::{
-	core::{ics02_client::client_def::ClientDef, ics24_host::identifier::ChainId},
+	core::{
+		ics02_client::client_def::ClientDef, ics23_commitment::specs::ProofSpecs,
+		ics24_host::identifier::ChainId,
+	},
 	prelude::*,
 	Height,
 };
 use alloc::string::String;
 use core::{
-	fmt::Debug,
+	fmt::{Debug, Display, Error as FmtError, Formatter},
 	marker::{Send, Sync},
 	time::Duration,
 };
+use serde_derive::{Deserialize, Serialize};
 
 pub trait ClientState: Clone + Debug + Send + Sync {
 	/// Client-specific options for upgrading the client
@@ -62,9 +66,20 @@ pub trait ClientState: Clone + Debug + Send + Sync {
 		chain_id: ChainId,
 	) -> Self;
 
-	/// Helper function to verify the upgrade client procedure.
+	/// Whether the client is expired, i.e. `elapsed` since its latest consensus state was
+	/// processed exceeds the trusting period. Unlike a frozen client, an expired client can be
+	/// recovered.
 	fn expired(&self, elapsed: Duration) -> bool;
 
+	/// Period within which the latest consensus state of the client can be trusted.
+	fn trusting_period(&self) -> Duration;
+
+	/// Proof specs of the counterparty's store, used to verify ics23 membership and
+	/// non-membership proofs. Defaults to the Cosmos-SDK (IAVL + Tendermint) specs.
+	fn proof_specs(&self) -> ProofSpecs {
+		ProofSpecs::cosmos()
+	}
+
 	/// Performs downcast of the client state from an "AnyClientState" type to T, otherwise
 	/// panics. Downcast from `T` to `T` is always successful.
 	fn downcast<T: Clone + 'static>(self) -> Option<T>
@@ -86,3 +101,42 @@ pub trait ClientState: Clone + Debug + Send + Sync {
 
 /// Type of the client, depending on the specific consensus algorithm.
 pub type ClientType = String;
+
+/// Whether a client can still be used to verify proofs.
+#[derive(
+	Copy,
+	Clone,
+	Debug,
+	PartialEq,
+	Eq,
+	Serialize,
+	Deserialize,
+	codec::Encode,
+	codec::Decode,
+	scale_info::TypeInfo,
+)]
+pub enum ClientStatus {
+	/// The client is neither frozen nor expired.
+	Active,
+	/// The trusting period of the latest consensus state has elapsed, or that consensus state is
+	/// missing. An expired client can be recovered.
+	Expired,
+	/// The client was frozen after misbehaviour was submitted.
+	Frozen,
+}
+
+impl ClientStatus {
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Self::Active => "Active",
+			Self::Expired => "Expired",
+			Self::Frozen => "Frozen",
+		}
+	}
+}
+
+impl Display for ClientStatus {
+	fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+		write!(f, "{}", self.as_str())
+	}
+}
@@ -24,10 +24,11 @@ use crate::{
 			client_message::ClientMessage,
 			client_state::{ClientState, ClientType},
 			error::{Error, ErrorDetail},
-			handler::ClientResult::{self, Create, Update, Upgrade},
+			handler::ClientResult::{self, Create, Misbehaviour, Recover, Update, Upgrade},
 		},
 		ics24_host::identifier::ClientId,
 	},
+	signer::Signer,
 	timestamp::Timestamp,
 	Height,
 };
@@ -52,6 +53,10 @@ pub trait ClientReader: ClientKeeper {
 	/// This should return the host type.
 	fn host_client_type(&self) -> String;
 
+	/// The client types that may be created on, and decoded by, the host. Client states and
+	/// messages of any other type are rejected with an `UnknownClientType` error listing these.
+	fn supported_client_types(&self) -> Vec<ClientType>;
+
 	/// Similar to `consensus_state`, attempt to retrieve the consensus state,
 	/// but return `None` if no state exists at the given height.
 	fn maybe_consensus_state(
@@ -82,6 +87,22 @@ pub trait ClientReader: ClientKeeper {
 		height: Height,
 	) -> Result<Option<Self::AnyConsensusState>, Error>;
 
+	/// Returns the height of the oldest consensus state stored for the client, if any.
+	fn oldest_consensus_state_height(&self, client_id: &ClientId) -> Result<Option<Height>, Error>;
+
+	/// Returns the height of the lowest consensus state stored for the client above `height`.
+	fn next_consensus_state_height(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Option<Height>, Error>;
+
+	/// Maximum number of consensus states that fell out of the trusting period to prune with
+	/// every client update.
+	fn consensus_prune_limit(&self) -> usize {
+		2
+	}
+
 	/// Returns the current height of the local chain.
 	fn host_height(&self) -> Height;
 
@@ -101,6 +122,13 @@ pub trait ClientReader: ClientKeeper {
 	/// The value of this counter should increase only via method
 	/// `ClientKeeper::increase_client_counter`.
 	fn client_counter(&self) -> Result<u64, Error>;
+
+	/// Returns whether `signer` may substitute a frozen or expired client. Recovery replaces a
+	/// client without any light client verification, so hosts should restrict it to governance;
+	/// the default allows no signer.
+	fn is_client_recovery_authorized(&self, _signer: &Signer) -> bool {
+		false
+	}
 }
 
 pub trait ClientTypes: 'static {
@@ -148,6 +176,11 @@ where
 			},
 			Update(res) => {
 				self.store_client_state(res.client_id.clone(), res.client_state.clone())?;
+				for height in res.pruned_heights {
+					self.delete_consensus_state(res.client_id.clone(), height)?;
+					self.delete_update_time(res.client_id.clone(), height)?;
+					self.delete_update_height(res.client_id.clone(), height)?;
+				}
 				match res.consensus_state {
 					None => {},
 					Some(cs_state_update) => match cs_state_update {
@@ -203,6 +236,16 @@ where
 								res.client_state.latest_height(),
 								cs_state,
 							)?;
+							self.store_update_time(
+								res.client_id.clone(),
+								res.client_state.latest_height(),
+								res.processed_time,
+							)?;
+							self.store_update_height(
+								res.client_id,
+								res.client_state.latest_height(),
+								res.processed_height,
+							)?;
 						},
 						ConsensusUpdateResult::Batch(cs_states) => {
 							for (height, cs_state) in cs_states {
@@ -211,12 +254,34 @@ where
 									height,
 									cs_state,
 								)?;
+								self.store_update_time(
+									res.client_id.clone(),
+									height,
+									res.processed_time,
+								)?;
+								self.store_update_height(
+									res.client_id.clone(),
+									height,
+									res.processed_height,
+								)?;
 							}
 						},
 					},
 				}
 				Ok(())
 			},
+			Recover(res) => {
+				let height = res.client_state.latest_height();
+				self.store_client_state(res.client_id.clone(), res.client_state)?;
+				self.store_consensus_state(res.client_id.clone(), height, res.consensus_state)?;
+				self.store_update_time(res.client_id.clone(), height, res.processed_time)?;
+				self.store_update_height(res.client_id, height, res.processed_height)?;
+				Ok(())
+			},
+			Misbehaviour(res) => {
+				self.store_client_state(res.client_id, res.client_state)?;
+				Ok(())
+			},
 		}
 	}
 
@@ -267,6 +332,18 @@ where
 		host_height: Height,
 	) -> Result<(), Error>;
 
+	/// Called upon successful client update, for each consensus state that is pruned.
+	fn delete_consensus_state(&mut self, client_id: ClientId, height: Height)
+		-> Result<(), Error>;
+
+	/// Called along with `delete_consensus_state` to remove the time recorded by
+	/// `store_update_time` for the pruned consensus state.
+	fn delete_update_time(&mut self, client_id: ClientId, height: Height) -> Result<(), Error>;
+
+	/// Called along with `delete_consensus_state` to remove the height recorded by
+	/// `store_update_height` for the pruned consensus state.
+	fn delete_update_height(&mut self, client_id: ClientId, height: Height) -> Result<(), Error>;
+
 	/// validates the client parameters for a client of the running chain
 	/// This function is only used to validate the client state the counterparty stores for this
 	/// chain
@@ -14,6 +14,7 @@
 
 use crate::prelude::*;
 
+use core::time::Duration;
 use flex_error::{define_error, TraceError};
 use tendermint::Error as TendermintError;
 use tendermint_proto::Error as TendermintProtoError;
@@ -37,8 +38,11 @@ define_error! {
 			|e| { format_args!("client '{}' error: {}", e.client_type, e.inner) },
 
 		UnknownClientType
-			{ client_type: String }
-			| e | { format_args!("unknown client type: {0}", e.client_type) },
+			{ client_type: String, supported: Vec<ClientType> }
+			| e | {
+				format_args!("unknown client type: {0}, supported client types are: {1}",
+					e.client_type, e.supported.join(", "))
+			},
 
 		ClientIdentifierConstructor
 			{ client_type: String, counter: u64 }
@@ -295,5 +299,77 @@ define_error! {
 		Signer
 			[ SignerError ]
 			| _ | { "failed to parse signer" },
+
+		ClientExpired
+			{
+				client_id: ClientId,
+				elapsed: Duration,
+				trusting_period: Duration,
+			}
+			| e | {
+				format_args!("client {0} is expired: {1:?} elapsed since its latest update, trusting period is {2:?}",
+					e.client_id, e.elapsed, e.trusting_period)
+			},
+
+		ConsensusStatePruned
+			{ client_id: ClientId, height: Height }
+			| e | {
+				format_args!("consensus state of client {0} at height {1} was pruned after falling out of the trusting period",
+					e.client_id, e.height)
+			},
+
+		BatchUpdateFailed
+			{
+				client_id: ClientId,
+				applied: usize,
+				reason: String,
+			}
+			| e | {
+				format_args!("batch update of client {0} failed after {1} headers were applied: {2}",
+					e.client_id, e.applied, e.reason)
+			},
+
+		BatchHeightNotAscending
+			{ previous: Height, height: Height }
+			| e | {
+				format_args!("header for height {0} does not follow height {1}, batched headers must be in strictly ascending height order",
+					e.height, e.previous)
+			},
+
+		MisbehaviourNotFound
+			{ client_id: ClientId }
+			| e | { format_args!("the submitted evidence does not prove misbehaviour of client {0}", e.client_id) },
+
+		UnauthorizedClientRecovery
+			{ client_id: ClientId }
+			| e | { format_args!("signer is not authorized to recover client {0}", e.client_id) },
+
+		ClientNotRecoverable
+			{ client_id: ClientId }
+			| e | { format_args!("client {0} is neither frozen nor expired and cannot be recovered", e.client_id) },
+
+		ClientRecoveryTypeMismatch
+			{
+				subject_client_type: ClientType,
+				substitute_client_type: ClientType,
+			}
+			| e | {
+				format_args!("subject client type {} does not match substitute client type {}",
+					e.subject_client_type, e.substitute_client_type)
+			},
+
+		LowSubstituteHeight
+			{
+				substitute_height: Height,
+				subject_height: Height,
+			}
+			| e | {
+				format_args!("substitute client height {} must be greater than subject client height {}",
+					e.substitute_height, e.subject_height)
+			},
+
+		SubstituteClientStateMismatch
+			{ reason: String }
+			| e | { format_args!("substitute client state does not match the subject: {}", e.reason) },
 	}
 }
@@ -152,6 +152,13 @@ impl core::fmt::Display for CreateClient {
 pub struct UpdateClient {
 	pub common: Attributes,
 	pub header: Option<Vec<u8>>,
+	/// Heights of the consensus states the update wrote, more than one for batched headers.
+	/// Empty when decoded from an event emitted before they were recorded.
+	#[serde(default)]
+	pub header_heights: Vec<Height>,
+	/// Hex encoded root of the consensus state at the latest of `header_heights`.
+	#[serde(default)]
+	pub consensus_root: Option<String>,
 }
 
 impl UpdateClient {
@@ -174,11 +181,19 @@ impl UpdateClient {
 	pub fn consensus_height(&self) -> Height {
 		self.common.consensus_height
 	}
+
+	pub fn header_heights(&self) -> &[Height] {
+		&self.header_heights
+	}
+
+	pub fn consensus_root(&self) -> Option<&str> {
+		self.consensus_root.as_deref()
+	}
 }
 
 impl From<Attributes> for UpdateClient {
 	fn from(attrs: Attributes) -> Self {
-		UpdateClient { common: attrs, header: None }
+		UpdateClient { common: attrs, header: None, header_heights: vec![], consensus_root: None }
 	}
 }
 
@@ -250,3 +265,65 @@ impl From<Attributes> for UpgradeClient {
 		UpgradeClient(attrs)
 	}
 }
+
+/// Signals the recovery of a frozen or expired on-chain client (IBC Client) using the state of a
+/// substitute client.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct ClientRecovered {
+	pub common: Attributes,
+	pub substitute_client_id: ClientId,
+}
+
+impl ClientRecovered {
+	pub fn client_id(&self) -> &ClientId {
+		&self.common.client_id
+	}
+	pub fn substitute_client_id(&self) -> &ClientId {
+		&self.substitute_client_id
+	}
+	pub fn height(&self) -> Height {
+		self.common.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.common.height = height;
+	}
+}
+
+impl From<ClientRecovered> for IbcEvent {
+	fn from(v: ClientRecovered) -> Self {
+		IbcEvent::ClientRecovered(v)
+	}
+}
+
+impl core::fmt::Display for ClientRecovered {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+		write!(f, "{} substitute_client_id: {}", self.common, self.substitute_client_id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn update_client_event_without_header_heights() {
+		let update_client = UpdateClient {
+			common: Attributes {
+				height: Height::new(0, 5),
+				client_id: ClientId::default(),
+				client_type: "9999-mock".to_string(),
+				consensus_height: Height::new(0, 10),
+			},
+			header: None,
+			header_heights: vec![],
+			consensus_root: None,
+		};
+
+		// Events serialized before the header heights and root were recorded still deserialize.
+		let mut json = serde_json::to_value(&update_client).unwrap();
+		let object = json.as_object_mut().unwrap();
+		object.remove("header_heights");
+		object.remove("consensus_root");
+		assert_eq!(serde_json::from_value::<UpdateClient>(json).unwrap(), update_client);
+	}
+}
@@ -16,14 +16,25 @@
 
 use crate::{
 	core::{
-		ics02_client::{context::ClientTypes, error::Error, msgs::ClientMsg},
+		ics02_client::{
+			client_state::{ClientState, ClientStatus},
+			context::{ClientReader, ClientTypes},
+			error::{Error, ErrorDetail},
+			msgs::ClientMsg,
+		},
+		ics24_host::identifier::ClientId,
 		ics26_routing::context::ReaderContext,
 	},
 	handler::HandlerOutput,
+	prelude::*,
+	Height,
 };
 use core::fmt::Debug;
+use ibc_proto::google::protobuf::Any;
 
 pub mod create_client;
+pub mod misbehaviour;
+pub mod recover_client;
 pub mod update_client;
 pub mod upgrade_client;
 
@@ -32,6 +43,8 @@ pub enum ClientResult<C: ClientTypes> {
 	Create(create_client::Result<C>),
 	Update(update_client::Result<C>),
 	Upgrade(upgrade_client::Result<C>),
+	Recover(recover_client::Result<C>),
+	Misbehaviour(misbehaviour::Result<C>),
 }
 
 /// General entry point for processing any message related to ICS2 (client functions) protocols.
@@ -45,6 +58,183 @@ where
 	match msg {
 		ClientMsg::CreateClient(msg) => create_client::process::<_>(ctx, msg),
 		ClientMsg::UpdateClient(msg) => update_client::process::<_>(ctx, msg),
+		ClientMsg::UpdateClientBatch(msg) => update_client::process_batch::<_>(ctx, msg),
 		ClientMsg::UpgradeClient(msg) => upgrade_client::process::<_>(ctx, msg),
+		ClientMsg::RecoverClient(msg) => recover_client::process::<_>(ctx, msg),
+		ClientMsg::Misbehaviour(msg) => misbehaviour::process::<_>(ctx, msg),
+	}
+}
+
+/// Checks that `client_type` is one of the host's [`ClientReader::supported_client_types`].
+pub fn ensure_client_type_supported<Ctx: ClientReader>(
+	ctx: &Ctx,
+	client_type: &str,
+) -> Result<(), Error> {
+	let supported = ctx.supported_client_types();
+	if !supported.iter().any(|supported| supported == client_type) {
+		return Err(Error::unknown_client_type(client_type.to_string(), supported))
+	}
+	Ok(())
+}
+
+/// Checks that the latest consensus state of the client is still within its trusting period.
+/// A client whose latest update time was not recorded is not considered expired.
+pub fn ensure_client_not_expired<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	client_state: &Ctx::AnyClientState,
+) -> Result<(), Error> {
+	let last_update_timestamp =
+		match ctx.client_update_time(client_id, client_state.latest_height()) {
+			Ok(timestamp) => timestamp,
+			Err(_) => return Ok(()),
+		};
+	let elapsed = ctx.host_timestamp().duration_since(&last_update_timestamp).unwrap_or_default();
+	if client_state.expired(elapsed) {
+		let trusting_period = client_state.trusting_period();
+		return Err(Error::client_expired(client_id.clone(), elapsed, trusting_period))
+	}
+	Ok(())
+}
+
+/// Returns the status of a client: frozen clients are reported as such first, then a client
+/// whose latest consensus state is missing or outside the trusting period is expired.
+pub fn client_status<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+) -> Result<ClientStatus, Error> {
+	let client_state = ctx.client_state(client_id)?;
+	if client_state.is_frozen() {
+		return Ok(ClientStatus::Frozen)
+	}
+	if ctx.consensus_state(client_id, client_state.latest_height()).is_err() {
+		return Ok(ClientStatus::Expired)
+	}
+	match ensure_client_not_expired(ctx, client_id, &client_state) {
+		Ok(()) => Ok(ClientStatus::Active),
+		Err(e) => match e.detail() {
+			ErrorDetail::ClientExpired(_) => Ok(ClientStatus::Expired),
+			_ => Err(e),
+		},
+	}
+}
+
+/// Reads the consensus state that proofs at `height` are verified against, telling a state that
+/// was pruned for falling out of the trusting period apart from one that is missing.
+pub fn proof_consensus_state<Ctx: ClientReader>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	height: Height,
+) -> Result<Ctx::AnyConsensusState, Error> {
+	ctx.consensus_state(client_id, height).map_err(|e| {
+		match ctx.oldest_consensus_state_height(client_id) {
+			Ok(Some(oldest)) if height < oldest =>
+				Error::consensus_state_pruned(client_id.clone(), height),
+			_ => e,
+		}
+	})
+}
+
+/// Decodes a client state, reporting a type url that none of the host's client types decodes
+/// from as an unknown client type rather than as a malformed message.
+pub fn decode_client_state<Ctx>(ctx: &Ctx, any: Any) -> Result<Ctx::AnyClientState, Error>
+where
+	Ctx: ClientReader,
+	Ctx::AnyClientState: TryFrom<Any, Error = Error>,
+{
+	let type_url = any.type_url.clone();
+	Ctx::AnyClientState::try_from(any).map_err(|e| with_supported_client_types(ctx, type_url, e))
+}
+
+/// Decodes a client message, see [`decode_client_state`].
+pub fn decode_client_message<Ctx>(ctx: &Ctx, any: Any) -> Result<Ctx::AnyClientMessage, Error>
+where
+	Ctx: ClientReader,
+	Ctx::AnyClientMessage: TryFrom<Any, Error = Error>,
+{
+	let type_url = any.type_url.clone();
+	Ctx::AnyClientMessage::try_from(any).map_err(|e| with_supported_client_types(ctx, type_url, e))
+}
+
+fn with_supported_client_types<Ctx: ClientReader>(ctx: &Ctx, type_url: String, e: Error) -> Error {
+	// The derived `TryFrom<Any>` implementations report a type url that matches none of their
+	// variants with these errors.
+	match e.detail() {
+		ErrorDetail::UnknownConsensusStateType(_) | ErrorDetail::EmptyConsensusStateResponse(_) =>
+			Error::unknown_client_type(type_url, ctx.supported_client_types()),
+		_ => e,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use core::time::Duration;
+	use test_log::test;
+
+	use crate::{
+		core::{
+			ics02_client::{
+				client_state::ClientStatus,
+				context::{ClientKeeper, ClientReader},
+				handler::client_status,
+			},
+			ics24_host::identifier::ClientId,
+		},
+		mock::{
+			client_state::{MockClientState, MOCK_TRUSTING_PERIOD},
+			context::{MockClientTypes, MockContext},
+			header::MockHeader,
+		},
+		prelude::*,
+		Height,
+	};
+
+	#[test]
+	fn client_status_active() {
+		let client_id = ClientId::default();
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		ctx.store_update_time(client_id.clone(), Height::new(0, 42), ctx.host_timestamp())
+			.unwrap();
+
+		assert_eq!(client_status(&ctx, &client_id).unwrap(), ClientStatus::Active);
+	}
+
+	#[test]
+	fn client_status_expired() {
+		let client_id = ClientId::default();
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		ctx.store_update_time(client_id.clone(), Height::new(0, 42), ctx.host_timestamp())
+			.unwrap();
+		ctx.block_time = MOCK_TRUSTING_PERIOD + Duration::from_secs(1);
+		ctx.advance_host_chain_height();
+
+		assert_eq!(client_status(&ctx, &client_id).unwrap(), ClientStatus::Expired);
+
+		// A client without a consensus state at its latest height is expired as well.
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		ctx.delete_consensus_state(client_id.clone(), Height::new(0, 42)).unwrap();
+
+		assert_eq!(client_status(&ctx, &client_id).unwrap(), ClientStatus::Expired);
+	}
+
+	#[test]
+	fn client_status_frozen() {
+		let client_id = ClientId::default();
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		let frozen = MockClientState {
+			header: MockHeader::new(Height::new(0, 42)),
+			frozen_height: Some(Height::new(0, 1)),
+		};
+		ctx.store_client_state(client_id.clone(), frozen.into()).unwrap();
+		// Freezing takes precedence over expiry.
+		ctx.block_time = MOCK_TRUSTING_PERIOD + Duration::from_secs(1);
+		ctx.advance_host_chain_height();
+
+		assert_eq!(client_status(&ctx, &client_id).unwrap(), ClientStatus::Frozen);
+		assert!(client_status(&ctx, &ClientId::new("07-tendermint", 1).unwrap()).is_err());
 	}
 }
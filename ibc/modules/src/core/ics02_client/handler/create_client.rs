@@ -22,8 +22,12 @@ use crate::core::ics02_client::client_state::{ClientState, ClientType};
 use crate::{
 	core::{
 		ics02_client::{
-			context::ClientTypes, error::Error, events::Attributes, handler::ClientResult,
-			height::Height, msgs::create_client::MsgCreateAnyClient,
+			context::ClientTypes,
+			error::Error,
+			events::Attributes,
+			handler::{ensure_client_type_supported, ClientResult},
+			height::Height,
+			msgs::create_client::MsgCreateAnyClient,
 		},
 		ics24_host::identifier::ClientId,
 	},
@@ -53,9 +57,11 @@ where
 {
 	let mut output = HandlerOutput::builder();
 
+	let client_type = msg.client_state.client_type();
+	ensure_client_type_supported(ctx, &client_type)?;
+
 	// Construct this client's identifier
 	let id_counter = ctx.client_counter()?;
-	let client_type = msg.client_state.client_type();
 	let client_id = ClientId::new(&client_type, id_counter)
 		.map_err(|e| Error::client_identifier_constructor(client_type.to_owned(), id_counter, e))?;
 
@@ -0,0 +1,210 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to processing ICS2 messages of type `MsgSubmitMisbehaviour`.
+
+use crate::{
+	core::{
+		ics02_client::{
+			client_def::ClientDef,
+			client_state::ClientState,
+			context::ClientTypes,
+			error::Error,
+			events::Attributes,
+			handler::{ensure_client_type_supported, ClientResult},
+			msgs::misbehaviour::MsgSubmitMisbehaviour,
+		},
+		ics24_host::identifier::ClientId,
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+/// The result following the successful processing of a `MsgSubmitMisbehaviour` message.
+/// This data type should be used with a qualified name `misbehaviour::Result` to avoid ambiguity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Result<C: ClientTypes> {
+	pub client_id: ClientId,
+	/// The frozen client state.
+	pub client_state: C::AnyClientState,
+}
+
+pub fn process<Ctx>(
+	ctx: &Ctx,
+	msg: MsgSubmitMisbehaviour<Ctx>,
+) -> HandlerResult<ClientResult<Ctx>, Error>
+where
+	Ctx: ReaderContext,
+{
+	let mut output = HandlerOutput::builder();
+
+	let MsgSubmitMisbehaviour { client_id, misbehaviour, signer: _ } = msg;
+
+	let client_state = ctx.client_state(&client_id)?;
+	if client_state.is_frozen() {
+		return Err(Error::client_frozen(client_id))
+	}
+
+	let client_type = ctx.client_type(&client_id)?;
+	ensure_client_type_supported(ctx, &client_type)?;
+
+	let consensus_height = client_state.latest_height();
+	let client_def = client_state.client_def();
+	let client_state = client_def.check_misbehaviour_and_update_state::<Ctx>(
+		ctx,
+		client_id.clone(),
+		client_state,
+		misbehaviour,
+	)?;
+
+	output.log("success: client frozen on misbehaviour");
+
+	let event_attributes = Attributes {
+		client_id: client_id.clone(),
+		height: ctx.host_height(),
+		client_type,
+		consensus_height,
+	};
+
+	let result = ClientResult::<Ctx>::Misbehaviour(Result { client_id, client_state });
+
+	output.emit(IbcEvent::ClientMisbehaviour(event_attributes.into()));
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use core::str::FromStr;
+	use test_log::test;
+
+	use crate::{
+		core::{
+			ics02_client::{
+				client_state::ClientState,
+				context::{ClientKeeper, ClientReader},
+				error::{Error, ErrorDetail},
+				handler::{dispatch, ClientResult::Misbehaviour},
+				msgs::{misbehaviour::MsgSubmitMisbehaviour, ClientMsg},
+			},
+			ics03_connection::{
+				error::ErrorDetail as Ics03ErrorDetail,
+				handler::dispatch as connection_dispatch,
+				msgs::{
+					conn_open_init::{
+						test_util::get_dummy_raw_msg_conn_open_init, MsgConnectionOpenInit,
+					},
+					ConnectionMsg,
+				},
+			},
+			ics24_host::identifier::ClientId,
+		},
+		events::IbcEvent,
+		handler::HandlerOutput,
+		mock::{
+			context::{MockClientTypes, MockContext},
+			header::MockHeader,
+			misbehaviour::MockMisbehaviour,
+		},
+		prelude::*,
+		test_utils::get_dummy_account_id,
+		timestamp::Timestamp,
+		Height,
+	};
+
+	fn misbehaviour_msg(
+		client_id: &ClientId,
+		header1: MockHeader,
+		header2: MockHeader,
+	) -> MsgSubmitMisbehaviour<MockContext<MockClientTypes>> {
+		let misbehaviour = MockMisbehaviour { client_id: client_id.clone(), header1, header2 };
+		MsgSubmitMisbehaviour::new(client_id.clone(), misbehaviour.into(), get_dummy_account_id())
+	}
+
+	fn conflicting_headers(height: Height) -> (MockHeader, MockHeader) {
+		(
+			MockHeader::new(height).with_timestamp(Timestamp::from_nanoseconds(1).unwrap()),
+			MockHeader::new(height).with_timestamp(Timestamp::from_nanoseconds(2).unwrap()),
+		)
+	}
+
+	#[test]
+	fn test_misbehaviour_freezes_client() {
+		let client_id = ClientId::from_str("mockclient1").unwrap();
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+
+		let (header1, header2) = conflicting_headers(Height::new(0, 46));
+		let msg = misbehaviour_msg(&client_id, header1, header2);
+
+		let output = dispatch(&ctx, ClientMsg::Misbehaviour(msg.clone()));
+		match output {
+			Ok(HandlerOutput { result, mut events, .. }) => {
+				assert_eq!(events.len(), 1);
+				let event = events.pop().unwrap();
+				assert!(
+					matches!(event, IbcEvent::ClientMisbehaviour(ref e) if e.client_id() == &client_id)
+				);
+				assert_eq!(event.height(), ctx.host_height());
+				match result {
+					Misbehaviour(res) => {
+						assert_eq!(res.client_id, client_id);
+						assert_eq!(res.client_state.frozen_height(), Some(Height::new(0, 46)));
+						ctx.store_client_result(Misbehaviour(res)).unwrap();
+					},
+					_ => panic!("misbehaviour handler result has incorrect type"),
+				}
+			},
+			Err(err) => panic!("unexpected error: {}", err),
+		}
+		assert!(ctx.client_state(&client_id).unwrap().is_frozen());
+
+		// A frozen client can be neither frozen again nor used for new connections.
+		match dispatch(&ctx, ClientMsg::Misbehaviour(msg)) {
+			Err(Error(ErrorDetail::ClientFrozen(e), _)) => assert_eq!(e.client_id, client_id),
+			other => panic!("Expected ClientFrozen error, instead got {:?}", other.err()),
+		}
+
+		let conn_open_init = MsgConnectionOpenInit {
+			client_id: client_id.clone(),
+			..MsgConnectionOpenInit::try_from(get_dummy_raw_msg_conn_open_init()).unwrap()
+		};
+		match connection_dispatch(&ctx, ConnectionMsg::ConnectionOpenInit(conn_open_init)) {
+			Err(e) => match e.detail() {
+				Ics03ErrorDetail::FrozenClient(e) => assert_eq!(e.client_id, client_id),
+				_ => panic!("Expected FrozenClient error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected FrozenClient error"),
+		}
+	}
+
+	#[test]
+	fn test_misbehaviour_without_evidence() {
+		let client_id = ClientId::from_str("mockclient1").unwrap();
+		let ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+
+		let (header, _) = conflicting_headers(Height::new(0, 46));
+		let msg = misbehaviour_msg(&client_id, header, header);
+
+		match dispatch(&ctx, ClientMsg::Misbehaviour(msg)) {
+			Err(Error(ErrorDetail::MisbehaviourNotFound(e), _)) =>
+				assert_eq!(e.client_id, client_id),
+			other => panic!("Expected MisbehaviourNotFound error, instead got {:?}", other.err()),
+		}
+		assert!(!ctx.client_state(&client_id).unwrap().is_frozen());
+	}
+}
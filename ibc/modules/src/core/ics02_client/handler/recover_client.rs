@@ -0,0 +1,318 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to processing ICS2 messages of type `MsgRecoverClient`.
+
+use crate::{
+	core::{
+		ics02_client::{
+			client_def::ClientDef,
+			client_state::ClientState,
+			context::ClientTypes,
+			error::Error,
+			events::{Attributes, ClientRecovered},
+			handler::ClientResult,
+			height::Height,
+			msgs::recover_client::MsgRecoverClient,
+		},
+		ics24_host::identifier::ClientId,
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+	timestamp::Timestamp,
+};
+
+/// The result following the successful processing of a `MsgRecoverClient` message.
+/// This data type should be used with a qualified name `recover_client::Result` to avoid ambiguity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Result<C: ClientTypes> {
+	pub client_id: ClientId,
+	pub client_state: C::AnyClientState,
+	pub consensus_state: C::AnyConsensusState,
+	pub processed_time: Timestamp,
+	pub processed_height: Height,
+}
+
+pub fn process<Ctx>(ctx: &Ctx, msg: MsgRecoverClient) -> HandlerResult<ClientResult<Ctx>, Error>
+where
+	Ctx: ReaderContext,
+{
+	let mut output = HandlerOutput::builder();
+
+	let MsgRecoverClient { subject_client_id, substitute_client_id, signer } = msg;
+
+	if !ctx.is_client_recovery_authorized(&signer) {
+		return Err(Error::unauthorized_client_recovery(subject_client_id))
+	}
+
+	let subject_client_state = ctx.client_state(&subject_client_id)?;
+
+	// Only a client that can no longer be updated through the regular path may be recovered.
+	if !subject_client_state.is_frozen() {
+		let now = ctx.host_timestamp();
+		let last_update_timestamp = ctx
+			.client_update_time(&subject_client_id, subject_client_state.latest_height())
+			.map_err(|_| {
+				Error::implementation_specific("Could not find update time for client".to_string())
+			})?;
+		let expired = now
+			.duration_since(&last_update_timestamp)
+			.map(|duration| subject_client_state.expired(duration))
+			.unwrap_or(false);
+		if !expired {
+			return Err(Error::client_not_recoverable(subject_client_id))
+		}
+	}
+
+	let subject_client_type = ctx.client_type(&subject_client_id)?;
+	let substitute_client_type = ctx.client_type(&substitute_client_id)?;
+	if subject_client_type != substitute_client_type {
+		return Err(Error::client_recovery_type_mismatch(
+			subject_client_type,
+			substitute_client_type,
+		))
+	}
+
+	let substitute_client_state = ctx.client_state(&substitute_client_id)?;
+	if substitute_client_state.is_frozen() {
+		return Err(Error::client_frozen(substitute_client_id))
+	}
+
+	let substitute_height = substitute_client_state.latest_height();
+	if substitute_height <= subject_client_state.latest_height() {
+		return Err(Error::low_substitute_height(
+			substitute_height,
+			subject_client_state.latest_height(),
+		))
+	}
+
+	let client_def = subject_client_state.client_def();
+	let new_client_state = client_def.check_substitute_and_update_state::<Ctx>(
+		ctx,
+		subject_client_id.clone(),
+		substitute_client_id.clone(),
+		subject_client_state,
+		substitute_client_state,
+	)?;
+
+	let consensus_state =
+		ctx.consensus_state(&substitute_client_id, substitute_height).map_err(|_| {
+			Error::consensus_state_not_found(substitute_client_id.clone(), substitute_height)
+		})?;
+
+	let event_attributes = Attributes {
+		client_id: subject_client_id.clone(),
+		height: ctx.host_height(),
+		client_type: subject_client_type,
+		consensus_height: new_client_state.latest_height(),
+	};
+
+	let result = ClientResult::<Ctx>::Recover(Result {
+		client_id: subject_client_id,
+		client_state: new_client_state,
+		consensus_state,
+		processed_time: ctx.host_timestamp(),
+		processed_height: ctx.host_height(),
+	});
+
+	output.emit(IbcEvent::ClientRecovered(ClientRecovered {
+		common: event_attributes,
+		substitute_client_id,
+	}));
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use core::str::FromStr;
+	use test_log::test;
+
+	use crate::{
+		core::{
+			ics02_client::{
+				context::ClientKeeper,
+				error::{Error, ErrorDetail},
+				handler::{dispatch, ClientResult::Recover},
+				msgs::{recover_client::MsgRecoverClient, ClientMsg},
+			},
+			ics24_host::identifier::ClientId,
+		},
+		events::IbcEvent,
+		handler::HandlerOutput,
+		mock::{
+			client_state::{AnyClientState, MockClientState},
+			context::{MockClientTypes, MockContext},
+			header::MockHeader,
+		},
+		prelude::*,
+		test_utils::get_dummy_account_id,
+		timestamp::Timestamp,
+		Height,
+	};
+
+	fn frozen_mock_client_state(height: Height) -> AnyClientState {
+		MockClientState { header: MockHeader::new(height), frozen_height: Some(Height::new(0, 1)) }
+			.into()
+	}
+
+	#[test]
+	fn test_recover_client_ok() {
+		let subject_client_id = ClientId::from_str("mockclient1").unwrap();
+		let substitute_client_id = ClientId::from_str("mockclient2").unwrap();
+
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&subject_client_id, Height::new(0, 42))
+			.with_client(&substitute_client_id, Height::new(0, 50))
+			.with_client_recovery_authority(get_dummy_account_id());
+		ctx.store_client_state(
+			subject_client_id.clone(),
+			frozen_mock_client_state(Height::new(0, 42)),
+		)
+		.unwrap();
+
+		let msg = MsgRecoverClient::new(
+			subject_client_id.clone(),
+			substitute_client_id.clone(),
+			get_dummy_account_id(),
+		);
+
+		let output = dispatch(&ctx, ClientMsg::RecoverClient(msg));
+
+		match output {
+			Ok(HandlerOutput { result, mut events, log }) => {
+				assert_eq!(events.len(), 1);
+				let event = events.pop().unwrap();
+				assert!(matches!(
+					event,
+					IbcEvent::ClientRecovered(ref e)
+						if e.client_id() == &subject_client_id &&
+							e.substitute_client_id() == &substitute_client_id
+				));
+				assert_eq!(event.height(), ctx.host_height());
+				assert!(log.is_empty());
+				match result {
+					Recover(res) => {
+						assert_eq!(res.client_id, subject_client_id);
+						assert_eq!(
+							res.client_state,
+							MockClientState::new(MockHeader::new(Height::new(0, 50)).into()).into()
+						);
+					},
+					_ => panic!("recover handler result has incorrect type"),
+				}
+			},
+			Err(err) => {
+				panic!("unexpected error: {}", err);
+			},
+		}
+	}
+
+	#[test]
+	fn test_recover_client_type_mismatch() {
+		let subject_client_id = ClientId::from_str("mockclient1").unwrap();
+		let substitute_client_id = ClientId::from_str("mockclient2").unwrap();
+
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&subject_client_id, Height::new(0, 42))
+			.with_client(&substitute_client_id, Height::new(0, 50))
+			.with_client_recovery_authority(get_dummy_account_id());
+		ctx.store_client_state(
+			subject_client_id.clone(),
+			frozen_mock_client_state(Height::new(0, 42)),
+		)
+		.unwrap();
+		ctx.store_client_type(substitute_client_id.clone(), "07-tendermint".to_string())
+			.unwrap();
+
+		let msg =
+			MsgRecoverClient::new(subject_client_id, substitute_client_id, get_dummy_account_id());
+
+		let output = dispatch(&ctx, ClientMsg::RecoverClient(msg));
+
+		match output {
+			Err(Error(ErrorDetail::ClientRecoveryTypeMismatch(e), _)) => {
+				assert_eq!(e.subject_client_type, MockClientState::client_type());
+				assert_eq!(e.substitute_client_type, "07-tendermint".to_string());
+			},
+			_ => {
+				panic!("expected ClientRecoveryTypeMismatch error, instead got {:?}", output)
+			},
+		}
+	}
+
+	#[test]
+	fn test_recover_active_client() {
+		let subject_client_id = ClientId::from_str("mockclient1").unwrap();
+		let substitute_client_id = ClientId::from_str("mockclient2").unwrap();
+
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&subject_client_id, Height::new(0, 42))
+			.with_client(&substitute_client_id, Height::new(0, 50))
+			.with_client_recovery_authority(get_dummy_account_id());
+		ctx.store_update_time(subject_client_id.clone(), Height::new(0, 42), Timestamp::now())
+			.unwrap();
+
+		let msg = MsgRecoverClient::new(
+			subject_client_id.clone(),
+			substitute_client_id,
+			get_dummy_account_id(),
+		);
+
+		let output = dispatch(&ctx, ClientMsg::RecoverClient(msg));
+
+		match output {
+			Err(Error(ErrorDetail::ClientNotRecoverable(e), _)) => {
+				assert_eq!(e.client_id, subject_client_id);
+			},
+			_ => {
+				panic!("expected ClientNotRecoverable error, instead got {:?}", output)
+			},
+		}
+	}
+
+	#[test]
+	fn test_recover_client_unauthorized() {
+		let subject_client_id = ClientId::from_str("mockclient1").unwrap();
+		let substitute_client_id = ClientId::from_str("mockclient2").unwrap();
+
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&subject_client_id, Height::new(0, 42))
+			.with_client(&substitute_client_id, Height::new(0, 50));
+		ctx.store_client_state(
+			subject_client_id.clone(),
+			frozen_mock_client_state(Height::new(0, 42)),
+		)
+		.unwrap();
+
+		let msg = MsgRecoverClient::new(
+			subject_client_id.clone(),
+			substitute_client_id,
+			get_dummy_account_id(),
+		);
+
+		let output = dispatch(&ctx, ClientMsg::RecoverClient(msg));
+
+		match output {
+			Err(Error(ErrorDetail::UnauthorizedClientRecovery(e), _)) => {
+				assert_eq!(e.client_id, subject_client_id);
+			},
+			_ => {
+				panic!("expected UnauthorizedClientRecovery error, instead got {:?}", output)
+			},
+		}
+	}
+}
@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Protocol logic specific to processing ICS2 messages of type `MsgUpdateAnyClient`.
+//! Protocol logic specific to processing ICS2 messages of type `MsgUpdateAnyClient` and
+//! `MsgUpdateAnyClientBatch`.
 use core::fmt::Debug;
 
 use crate::{
@@ -20,13 +21,15 @@ use crate::{
 		ics02_client::{
 			client_consensus::ConsensusState,
 			client_def::{ClientDef, ConsensusUpdateResult},
-			client_state::ClientState,
+			client_state::{ClientState, ClientType},
 			context::ClientTypes,
 			error::Error,
-			events::Attributes,
-			handler::ClientResult,
+			events::{Attributes, UpdateClient},
+			handler::{ensure_client_type_supported, ClientResult},
 			height::Height,
-			msgs::update_client::MsgUpdateAnyClient,
+			msgs::{
+				update_client::MsgUpdateAnyClient, update_client_batch::MsgUpdateAnyClientBatch,
+			},
 		},
 		ics24_host::identifier::ClientId,
 		ics26_routing::context::ReaderContext,
@@ -36,6 +39,10 @@ use crate::{
 	prelude::*,
 	timestamp::Timestamp,
 };
+use batch_context::BatchContext;
+use subtle_encoding::hex;
+
+mod batch_context;
 
 /// The result following the successful processing of a `MsgUpdateAnyClient` message. Preferably
 /// this data type should be used with a qualified name `update_client::Result` to avoid ambiguity.
@@ -46,6 +53,9 @@ pub struct Result<C: ClientTypes> {
 	pub consensus_state: Option<ConsensusUpdateResult<C>>,
 	pub processed_time: Timestamp,
 	pub processed_height: Height,
+	/// Heights of the consensus states that fell out of the trusting period and are deleted
+	/// along with this update.
+	pub pruned_heights: Vec<Height>,
 }
 
 pub fn process<Ctx>(
@@ -59,21 +69,155 @@ where
 
 	let MsgUpdateAnyClient { client_id, client_message, signer: _ } = msg;
 
+	let (client_type, client_state) = updatable_client_state(ctx, &client_id)?;
+	let pruned_heights = expired_consensus_heights(ctx, &client_id, &client_state)?;
+
+	let event_attributes = Attributes {
+		client_id: client_id.clone(),
+		height: ctx.host_height(),
+		client_type,
+		consensus_height: client_state.latest_height(),
+	};
+
+	let (new_client_state, new_consensus_state) =
+		match apply_client_message(ctx, &client_id, client_state, client_message)? {
+			Update::Misbehaviour(client_state) => {
+				let result = ClientResult::Update(Result {
+					client_id,
+					client_state,
+					consensus_state: None,
+					processed_time: ctx.host_timestamp(),
+					processed_height: ctx.host_height(),
+					pruned_heights: vec![],
+				});
+				output.emit(IbcEvent::ClientMisbehaviour(event_attributes.into()));
+				return Ok(output.with_result(result))
+			},
+			Update::Header(client_state, consensus_state) => (client_state, consensus_state),
+		};
+
+	let event = update_client_event(event_attributes, &new_client_state, &new_consensus_state);
+
+	let result = ClientResult::<Ctx>::Update(Result {
+		client_id,
+		client_state: new_client_state,
+		consensus_state: Some(new_consensus_state),
+		processed_time: ctx.host_timestamp(),
+		processed_height: ctx.host_height(),
+		pruned_heights,
+	});
+
+	output.emit(IbcEvent::UpdateClient(event));
+
+	Ok(output.with_result(result))
+}
+
+/// Applies the headers of a `MsgUpdateAnyClientBatch` in order, and stores the consensus state of
+/// every header. Each header is verified against the client state and consensus states produced
+/// by the headers before it, so it may trust the previous header of the batch. Headers must be in
+/// strictly ascending height order. Nothing is stored unless all of them apply; the error reports
+/// how many did before the failing one.
+pub fn process_batch<Ctx>(
+	ctx: &Ctx,
+	msg: MsgUpdateAnyClientBatch<Ctx>,
+) -> HandlerResult<ClientResult<Ctx>, Error>
+where
+	Ctx: ReaderContext,
+{
+	let mut output = HandlerOutput::builder();
+
+	let MsgUpdateAnyClientBatch { client_id, client_messages, signer: _ } = msg;
+
+	let (client_type, client_state) = updatable_client_state(ctx, &client_id)?;
+	let pruned_heights = expired_consensus_heights(ctx, &client_id, &client_state)?;
+
+	let event_attributes = Attributes {
+		client_id: client_id.clone(),
+		height: ctx.host_height(),
+		client_type,
+		consensus_height: client_state.latest_height(),
+	};
+
+	let mut batch = BatchContext::new(ctx.clone(), client_id.clone(), client_state);
+	for (applied, client_message) in client_messages.into_iter().enumerate() {
+		let batch_update_failed =
+			|e: Error| Error::batch_update_failed(client_id.clone(), applied, e.to_string());
+		let update =
+			apply_client_message(&batch, &client_id, batch.client_state(), client_message)
+				.map_err(batch_update_failed)?;
+		let (new_client_state, consensus_states) = match update {
+			// Evidence of misbehaviour freezes the client, the rest of the batch is moot.
+			Update::Misbehaviour(client_state) => {
+				let result = ClientResult::Update(Result {
+					client_id,
+					client_state,
+					consensus_state: None,
+					processed_time: ctx.host_timestamp(),
+					processed_height: ctx.host_height(),
+					pruned_heights: vec![],
+				});
+				output.emit(IbcEvent::ClientMisbehaviour(event_attributes.into()));
+				return Ok(output.with_result(result))
+			},
+			Update::Header(new_client_state, ConsensusUpdateResult::Single(consensus_state)) => {
+				let height = new_client_state.latest_height();
+				(new_client_state, vec![(height, consensus_state)])
+			},
+			Update::Header(new_client_state, ConsensusUpdateResult::Batch(states)) =>
+				(new_client_state, states),
+		};
+		batch.apply(new_client_state, consensus_states).map_err(batch_update_failed)?;
+	}
+
+	let (client_state, consensus_states) = batch.into_updates();
+	let new_consensus_state = ConsensusUpdateResult::Batch(consensus_states);
+	let event = update_client_event(event_attributes, &client_state, &new_consensus_state);
+
+	let result = ClientResult::<Ctx>::Update(Result {
+		client_id,
+		client_state,
+		consensus_state: Some(new_consensus_state),
+		processed_time: ctx.host_timestamp(),
+		processed_height: ctx.host_height(),
+		pruned_heights,
+	});
+
+	output.emit(IbcEvent::UpdateClient(event));
+
+	Ok(output.with_result(result))
+}
+
+/// Outcome of applying a single client message to a client state.
+enum Update<C: ClientTypes> {
+	/// The message proved misbehaviour, this is the frozen client state.
+	Misbehaviour(C::AnyClientState),
+	/// The message was a valid header, these are the updated client and consensus states.
+	Header(C::AnyClientState, ConsensusUpdateResult<C>),
+}
+
+/// Reads the type and state of a client that may be updated, i.e. one that exists, is of a
+/// supported type, and is neither frozen nor expired.
+fn updatable_client_state<Ctx>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+) -> core::result::Result<(ClientType, Ctx::AnyClientState), Error>
+where
+	Ctx: ReaderContext,
+{
 	// Read client type from the host chain store. The client should already exist.
-	let client_type = ctx.client_type(&client_id)?;
+	let client_type = ctx.client_type(client_id)?;
+	ensure_client_type_supported(ctx, &client_type)?;
 
 	// Read client state from the host chain store.
-	let client_state = ctx.client_state(&client_id)?;
-
-	let client_def = client_state.client_def();
+	let client_state = ctx.client_state(client_id)?;
 
 	if client_state.is_frozen() {
-		return Err(Error::client_frozen(client_id))
+		return Err(Error::client_frozen(client_id.clone()))
 	}
 
 	// Read consensus state from the host chain store.
 	let latest_consensus_state =
-		ctx.consensus_state(&client_id, client_state.latest_height()).map_err(|_| {
+		ctx.consensus_state(client_id, client_state.latest_height()).map_err(|_| {
 			Error::consensus_state_not_found(client_id.clone(), client_state.latest_height())
 		})?;
 
@@ -81,7 +225,7 @@ where
 
 	let now = ctx.host_timestamp();
 	let last_update_timestamp =
-		ctx.client_update_time(&client_id, client_state.latest_height()).map_err(|_| {
+		ctx.client_update_time(client_id, client_state.latest_height()).map_err(|_| {
 			Error::implementation_specific("Could not find update time for client".to_string())
 		})?;
 	let duration = now.duration_since(&last_update_timestamp).ok_or_else(|| {
@@ -89,8 +233,59 @@ where
 	})?;
 
 	if client_state.expired(duration) {
-		return Err(Error::header_not_within_trust_period(last_update_timestamp, now))
+		let trusting_period = client_state.trusting_period();
+		return Err(Error::client_expired(client_id.clone(), duration, trusting_period))
+	}
+
+	Ok((client_type, client_state))
+}
+
+/// Heights of the oldest consensus states of the client that were processed longer than its
+/// trusting period ago, at most `ClientReader::consensus_prune_limit` of them. The latest
+/// consensus state of the client, and any state whose processed time is unknown, is kept.
+fn expired_consensus_heights<Ctx>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	client_state: &Ctx::AnyClientState,
+) -> core::result::Result<Vec<Height>, Error>
+where
+	Ctx: ReaderContext,
+{
+	let now = ctx.host_timestamp();
+	let limit = ctx.consensus_prune_limit();
+	let mut heights = Vec::new();
+	let mut next = ctx.oldest_consensus_state_height(client_id)?;
+	while let Some(height) = next {
+		if heights.len() >= limit || height >= client_state.latest_height() {
+			break
+		}
+		let expired = match ctx.client_update_time(client_id, height) {
+			Ok(processed_time) => now
+				.duration_since(&processed_time)
+				.map_or(false, |elapsed| client_state.expired(elapsed)),
+			Err(_) => false,
+		};
+		if !expired {
+			break
+		}
+		heights.push(height);
+		next = ctx.next_consensus_state_height(client_id, height)?;
 	}
+	Ok(heights)
+}
+
+/// Verifies `client_message` against `client_state`, then either freezes the client on
+/// misbehaviour or updates it with the header.
+fn apply_client_message<Ctx>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	client_state: Ctx::AnyClientState,
+	client_message: Ctx::AnyClientMessage,
+) -> core::result::Result<Update<Ctx>, Error>
+where
+	Ctx: ReaderContext,
+{
+	let client_def = client_state.client_def();
 
 	client_def
 		.verify_client_message::<Ctx>(
@@ -110,25 +305,11 @@ where
 		)
 		.map_err(|e| Error::header_verification_failure(e.to_string()))?;
 
-	let event_attributes = Attributes {
-		client_id: client_id.clone(),
-		height: ctx.host_height(),
-		client_type: client_type.to_owned(),
-		consensus_height: client_state.latest_height(),
-	};
-
 	if found_misbehaviour {
 		let client_state = client_def.update_state_on_misbehaviour(client_state, client_message)?;
-		let result = ClientResult::Update(Result {
-			client_id,
-			client_state,
-			consensus_state: None,
-			processed_time: ctx.host_timestamp(),
-			processed_height: ctx.host_height(),
-		});
-		output.emit(IbcEvent::ClientMisbehaviour(event_attributes.into()));
-		return Ok(output.with_result(result))
+		return Ok(Update::Misbehaviour(client_state))
 	}
+
 	// Use client_state to validate the new header against the latest consensus_state.
 	// This function will return the new client_state (its latest_height changed) and a
 	// consensus_state obtained from header. These will be later persisted by the keeper.
@@ -136,38 +317,63 @@ where
 		.update_state(ctx, client_id.clone(), client_state, client_message)
 		.map_err(|e| Error::header_verification_failure(e.to_string()))?;
 
-	let result = ClientResult::<Ctx>::Update(Result {
-		client_id,
-		client_state: new_client_state,
-		consensus_state: Some(new_consensus_state),
-		processed_time: ctx.host_timestamp(),
-		processed_height: ctx.host_height(),
-	});
-
-	output.emit(IbcEvent::UpdateClient(event_attributes.into()));
+	Ok(Update::Header(new_client_state, new_consensus_state))
+}
 
-	Ok(output.with_result(result))
+fn update_client_event<C: ClientTypes>(
+	event_attributes: Attributes,
+	new_client_state: &C::AnyClientState,
+	new_consensus_state: &ConsensusUpdateResult<C>,
+) -> UpdateClient {
+	// A single consensus state is stored at the new latest height of the client.
+	let (header_heights, consensus_root) = match new_consensus_state {
+		ConsensusUpdateResult::Single(consensus_state) =>
+			(vec![new_client_state.latest_height()], Some(consensus_state.root())),
+		ConsensusUpdateResult::Batch(consensus_states) => (
+			consensus_states.iter().map(|(height, _)| *height).collect(),
+			consensus_states
+				.iter()
+				.max_by_key(|(height, _)| *height)
+				.map(|(_, consensus_state)| consensus_state.root()),
+		),
+	};
+	UpdateClient {
+		common: event_attributes,
+		header: None,
+		header_heights,
+		consensus_root: consensus_root.map(|root| {
+			String::from_utf8(hex::encode(root.as_bytes()))
+				.expect("hex encoded bytes are not valid UTF8")
+		}),
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use core::str::FromStr;
+	use core::{str::FromStr, time::Duration};
 	use test_log::test;
 
 	use crate::{
 		core::{
 			ics02_client::{
+				client_state::ClientState,
 				context::{ClientKeeper, ClientReader},
 				error::{Error, ErrorDetail},
 				handler::{dispatch, ClientResult::Update},
-				msgs::{update_client::MsgUpdateAnyClient, ClientMsg},
+				msgs::{
+					update_client::MsgUpdateAnyClient,
+					update_client_batch::MsgUpdateAnyClientBatch, ClientMsg,
+				},
 			},
+			ics04_channel::context::ChannelReader,
 			ics24_host::identifier::ClientId,
 		},
 		events::IbcEvent,
 		handler::HandlerOutput,
 		mock::{
-			client_state::{AnyClientState, MockClientState},
+			client_state::{
+				AnyClientState, MockClientState, MockConsensusState, MOCK_TRUSTING_PERIOD,
+			},
 			context::{MockClientTypes, MockContext},
 			header::{AnyClientMessage, MockHeader},
 		},
@@ -204,6 +410,13 @@ mod tests {
 					matches!(event, IbcEvent::UpdateClient(ref e) if e.client_id() == &msg.client_id)
 				);
 				assert_eq!(event.height(), ctx.host_height());
+				match &event {
+					IbcEvent::UpdateClient(e) => {
+						assert_eq!(e.header_heights(), &[Height::new(0, 46)]);
+						assert!(e.consensus_root().is_some());
+					},
+					_ => unreachable!(),
+				}
 				assert!(log.is_empty());
 				// Check the result
 				match result {
@@ -257,6 +470,39 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn test_update_expired_client() {
+		let client_id = ClientId::default();
+		let signer = get_dummy_account_id();
+
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		ctx.store_update_time(client_id.clone(), Height::new(0, 42), ctx.host_timestamp())
+			.unwrap();
+		// Move the host clock past the trusting period of the mock client.
+		ctx.block_time = MOCK_TRUSTING_PERIOD + Duration::from_secs(1);
+		ctx.advance_host_chain_height();
+
+		let msg = MsgUpdateAnyClient {
+			client_id: client_id.clone(),
+			client_message: MockHeader::new(Height::new(0, 46)).into(),
+			signer,
+		};
+
+		let output = dispatch(&ctx, ClientMsg::UpdateClient(msg));
+
+		match output {
+			Err(Error(ErrorDetail::ClientExpired(e), _)) => {
+				assert_eq!(e.client_id, client_id);
+				assert!(e.elapsed > MOCK_TRUSTING_PERIOD);
+				assert_eq!(e.trusting_period, MOCK_TRUSTING_PERIOD);
+			},
+			_ => {
+				panic!("expected ClientExpired error, instead got {:?}", output)
+			},
+		}
+	}
+
 	#[test]
 	fn test_update_client_ok_multiple() {
 		let client_ids = vec![
@@ -300,4 +546,139 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn test_update_client_batch_rejects_repeated_height() {
+		let client_id = ClientId::default();
+		let signer = get_dummy_account_id();
+
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		ctx.store_update_time(client_id.clone(), Height::new(0, 42), Timestamp::now())
+			.unwrap();
+
+		let msg = MsgUpdateAnyClientBatch::new(
+			client_id.clone(),
+			vec![
+				MockHeader::new(Height::new(0, 43)).into(),
+				MockHeader::new(Height::new(0, 43)).into(),
+			],
+			signer,
+		);
+
+		match dispatch(&ctx, ClientMsg::UpdateClientBatch(msg)) {
+			Err(Error(ErrorDetail::BatchUpdateFailed(e), _)) => {
+				assert_eq!(e.client_id, client_id);
+				assert_eq!(e.applied, 1);
+			},
+			output => panic!("expected BatchUpdateFailed error, instead got {:?}", output),
+		}
+	}
+
+	#[test]
+	fn test_update_client_batch_fails_atomically() {
+		let client_id = ClientId::default();
+		let signer = get_dummy_account_id();
+
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 42));
+		ctx.store_update_time(client_id.clone(), Height::new(0, 42), Timestamp::now())
+			.unwrap();
+
+		// The middle header does not advance the client past the first one.
+		let msg = MsgUpdateAnyClientBatch::new(
+			client_id.clone(),
+			vec![
+				MockHeader::new(Height::new(0, 44)).into(),
+				MockHeader::new(Height::new(0, 43)).into(),
+				MockHeader::new(Height::new(0, 45)).into(),
+			],
+			signer,
+		);
+
+		let output = dispatch(&ctx, ClientMsg::UpdateClientBatch(msg));
+
+		match output {
+			Err(Error(ErrorDetail::BatchUpdateFailed(e), _)) => {
+				assert_eq!(e.client_id, client_id);
+				assert_eq!(e.applied, 1);
+			},
+			_ => {
+				panic!("expected BatchUpdateFailed error, instead got {:?}", output)
+			},
+		}
+
+		// Not even the first header was stored.
+		assert_eq!(ctx.client_state(&client_id).unwrap().latest_height(), Height::new(0, 42));
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 44)).is_err());
+	}
+
+	/// Stores a consensus state for `client_id` at `height` that was processed `age` ago.
+	fn store_consensus_state_processed_ago(
+		ctx: &mut MockContext<MockClientTypes>,
+		client_id: &ClientId,
+		height: Height,
+		age: Duration,
+	) {
+		let processed_time = (ctx.host_timestamp() - age).unwrap();
+		ctx.store_consensus_state(
+			client_id.clone(),
+			height,
+			MockConsensusState::new(MockHeader::new(height)).into(),
+		)
+		.unwrap();
+		ctx.store_update_time(client_id.clone(), height, processed_time).unwrap();
+	}
+
+	fn update_and_store(ctx: &mut MockContext<MockClientTypes>, client_id: &ClientId, height: u64) {
+		let msg = MsgUpdateAnyClient {
+			client_id: client_id.clone(),
+			client_message: MockHeader::new(Height::new(0, height)).into(),
+			signer: get_dummy_account_id(),
+		};
+		let output = dispatch(&*ctx, ClientMsg::UpdateClient(msg)).unwrap();
+		ctx.store_client_result(output.result).unwrap();
+	}
+
+	#[test]
+	fn test_update_client_prunes_expired_consensus_states() {
+		let client_id = ClientId::default();
+		let expired = MOCK_TRUSTING_PERIOD + Duration::from_secs(1);
+		let trusted = Duration::from_secs(60 * 60);
+
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_client(&client_id, Height::new(0, 10));
+		store_consensus_state_processed_ago(&mut ctx, &client_id, Height::new(0, 10), trusted);
+		for height in 1..=3 {
+			let height = Height::new(0, height);
+			store_consensus_state_processed_ago(&mut ctx, &client_id, height, expired);
+		}
+		store_consensus_state_processed_ago(&mut ctx, &client_id, Height::new(0, 4), trusted);
+
+		let msg = MsgUpdateAnyClient {
+			client_id: client_id.clone(),
+			client_message: MockHeader::new(Height::new(0, 11)).into(),
+			signer: get_dummy_account_id(),
+		};
+		match dispatch(&ctx, ClientMsg::UpdateClient(msg)).unwrap().result {
+			Update(res) =>
+				assert_eq!(res.pruned_heights, vec![Height::new(0, 1), Height::new(0, 2)]),
+			_ => panic!("update handler result has incorrect type"),
+		}
+
+		update_and_store(&mut ctx, &client_id, 11);
+		for height in 1..=2 {
+			assert!(ctx.consensus_state(&client_id, Height::new(0, height)).is_err());
+			assert!(ctx.client_update_time(&client_id, Height::new(0, height)).is_err());
+		}
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 3)).is_ok());
+
+		// Only one expired consensus state is left, the next one is still trusted.
+		update_and_store(&mut ctx, &client_id, 12);
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 3)).is_err());
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 4)).is_ok());
+
+		update_and_store(&mut ctx, &client_id, 13);
+		assert_eq!(ctx.oldest_consensus_state_height(&client_id).unwrap(), Some(Height::new(0, 4)));
+	}
 }
@@ -0,0 +1,543 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A read-only view of the host chain in which the client states and consensus states produced by
+//! the headers of a `MsgUpdateAnyClientBatch` already applied are visible, so that each header is
+//! verified on top of the previous one before any of them is stored.
+
+use alloc::collections::BTreeMap;
+use core::time::Duration;
+
+use crate::{
+	core::{
+		ics02_client::{
+			client_state::{ClientState, ClientType},
+			context::{ClientKeeper, ClientReader, ClientTypes},
+			error::Error,
+		},
+		ics03_connection::{
+			connection::ConnectionEnd,
+			context::ConnectionReader,
+			error::Error as ConnectionError,
+			handler::HandshakeOrigin,
+			version::Version,
+		},
+		ics04_channel::{
+			channel::ChannelEnd,
+			commitment::{AcknowledgementCommitment, PacketCommitment},
+			context::ChannelReader,
+			error::Error as ChannelError,
+			packet::{Receipt, Sequence},
+			upgrade::UpgradeOrigin,
+		},
+		ics23_commitment::commitment::CommitmentPrefix,
+		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics26_routing::context::ReaderContext,
+	},
+	prelude::*,
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
+
+/// The host chain `ctx` with the pending updates of the client `client_id` laid over it. All
+/// pending consensus states are above the heights stored for the client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct BatchContext<Ctx: ReaderContext> {
+	ctx: Ctx,
+	client_id: ClientId,
+	client_state: Ctx::AnyClientState,
+	latest_height: Height,
+	consensus_states: BTreeMap<Height, Ctx::AnyConsensusState>,
+}
+
+impl<Ctx: ReaderContext> BatchContext<Ctx> {
+	pub(super) fn new(ctx: Ctx, client_id: ClientId, client_state: Ctx::AnyClientState) -> Self {
+		let latest_height = client_state.latest_height();
+		Self { ctx, client_id, client_state, latest_height, consensus_states: BTreeMap::new() }
+	}
+
+	/// The client state produced by the last header applied.
+	pub(super) fn client_state(&self) -> Ctx::AnyClientState {
+		self.client_state.clone()
+	}
+
+	/// Records the states produced by a header. Its consensus states must all be above those of
+	/// the headers applied before it, and above the latest height of the stored client.
+	pub(super) fn apply(
+		&mut self,
+		client_state: Ctx::AnyClientState,
+		consensus_states: Vec<(Height, Ctx::AnyConsensusState)>,
+	) -> Result<(), Error> {
+		for (height, consensus_state) in consensus_states {
+			if height <= self.latest_height {
+				return Err(Error::batch_height_not_ascending(self.latest_height, height))
+			}
+			self.latest_height = height;
+			self.consensus_states.insert(height, consensus_state);
+		}
+		self.client_state = client_state;
+		Ok(())
+	}
+
+	/// The final client state and every consensus state of the batch, in ascending height order.
+	pub(super) fn into_updates(
+		self,
+	) -> (Ctx::AnyClientState, Vec<(Height, Ctx::AnyConsensusState)>) {
+		(self.client_state, self.consensus_states.into_iter().collect())
+	}
+
+	fn pending(&self, client_id: &ClientId, height: Height) -> Option<&Ctx::AnyConsensusState> {
+		if client_id == &self.client_id {
+			self.consensus_states.get(&height)
+		} else {
+			None
+		}
+	}
+
+	fn is_pending(&self, client_id: &ClientId, height: Height) -> bool {
+		self.pending(client_id, height).is_some()
+	}
+
+	/// Pending consensus states of `client_id`, which only exist for the batched client.
+	fn pending_states(
+		&self,
+		client_id: &ClientId,
+	) -> impl DoubleEndedIterator<Item = (&Height, &Ctx::AnyConsensusState)> {
+		let is_batched = client_id == &self.client_id;
+		self.consensus_states.iter().filter(move |_| is_batched)
+	}
+}
+
+impl<Ctx: ReaderContext> ClientTypes for BatchContext<Ctx> {
+	type AnyClientMessage = Ctx::AnyClientMessage;
+	type AnyClientState = Ctx::AnyClientState;
+	type AnyConsensusState = Ctx::AnyConsensusState;
+	type ClientDef = Ctx::ClientDef;
+}
+
+impl<Ctx: ReaderContext> ReaderContext for BatchContext<Ctx> {
+	fn require_proofs_in_handshake(&self) -> bool {
+		self.ctx.require_proofs_in_handshake()
+	}
+
+	fn persist_timeouts(&self) -> bool {
+		self.ctx.persist_timeouts()
+	}
+
+	fn block_delay(&self, delay_period: Duration) -> u64 {
+		self.ctx.block_delay(delay_period)
+	}
+}
+
+impl<Ctx: ReaderContext> ClientReader for BatchContext<Ctx> {
+	fn client_type(&self, client_id: &ClientId) -> Result<ClientType, Error> {
+		self.ctx.client_type(client_id)
+	}
+
+	fn client_state(&self, client_id: &ClientId) -> Result<Self::AnyClientState, Error> {
+		if client_id == &self.client_id {
+			return Ok(self.client_state.clone())
+		}
+		self.ctx.client_state(client_id)
+	}
+
+	fn consensus_state(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Self::AnyConsensusState, Error> {
+		match self.pending(client_id, height) {
+			Some(consensus_state) => Ok(consensus_state.clone()),
+			None => self.ctx.consensus_state(client_id, height),
+		}
+	}
+
+	fn host_client_type(&self) -> String {
+		self.ctx.host_client_type()
+	}
+
+	fn supported_client_types(&self) -> Vec<ClientType> {
+		self.ctx.supported_client_types()
+	}
+
+	fn maybe_consensus_state(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Option<Self::AnyConsensusState>, Error> {
+		match self.pending(client_id, height) {
+			Some(consensus_state) => Ok(Some(consensus_state.clone())),
+			None => self.ctx.maybe_consensus_state(client_id, height),
+		}
+	}
+
+	fn next_consensus_state(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Option<Self::AnyConsensusState>, Error> {
+		// stored consensus states are all below the pending ones.
+		match self.ctx.next_consensus_state(client_id, height)? {
+			Some(consensus_state) => Ok(Some(consensus_state)),
+			None => Ok(self
+				.pending_states(client_id)
+				.find(|(pending_height, _)| **pending_height > height)
+				.map(|(_, consensus_state)| consensus_state.clone())),
+		}
+	}
+
+	fn prev_consensus_state(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Option<Self::AnyConsensusState>, Error> {
+		let pending = self.pending_states(client_id).rev().find(|(prev, _)| **prev < height);
+		match pending {
+			Some((_, consensus_state)) => Ok(Some(consensus_state.clone())),
+			None => self.ctx.prev_consensus_state(client_id, height),
+		}
+	}
+
+	fn oldest_consensus_state_height(&self, client_id: &ClientId) -> Result<Option<Height>, Error> {
+		match self.ctx.oldest_consensus_state_height(client_id)? {
+			Some(height) => Ok(Some(height)),
+			None => Ok(self.pending_states(client_id).next().map(|(height, _)| *height)),
+		}
+	}
+
+	fn next_consensus_state_height(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Option<Height>, Error> {
+		match self.ctx.next_consensus_state_height(client_id, height)? {
+			Some(next) => Ok(Some(next)),
+			None => Ok(self
+				.pending_states(client_id)
+				.map(|(pending_height, _)| *pending_height)
+				.find(|pending_height| *pending_height > height)),
+		}
+	}
+
+	fn consensus_prune_limit(&self) -> usize {
+		self.ctx.consensus_prune_limit()
+	}
+
+	fn host_height(&self) -> Height {
+		self.ctx.host_height()
+	}
+
+	fn host_timestamp(&self) -> Timestamp {
+		self.ctx.host_timestamp()
+	}
+
+	fn host_consensus_state(
+		&self,
+		height: Height,
+		proof: Option<Vec<u8>>,
+	) -> Result<Self::AnyConsensusState, Error> {
+		self.ctx.host_consensus_state(height, proof)
+	}
+
+	fn client_counter(&self) -> Result<u64, Error> {
+		self.ctx.client_counter()
+	}
+
+	fn is_client_recovery_authorized(&self, signer: &Signer) -> bool {
+		self.ctx.is_client_recovery_authorized(signer)
+	}
+}
+
+/// Nothing is written while a batch is verified, the handler returns every state to store.
+impl<Ctx: ReaderContext> ClientKeeper for BatchContext<Ctx> {
+	fn store_client_type(
+		&mut self,
+		_client_id: ClientId,
+		_client_type: ClientType,
+	) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn store_client_state(
+		&mut self,
+		_client_id: ClientId,
+		_client_state: Self::AnyClientState,
+	) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn store_consensus_state(
+		&mut self,
+		_client_id: ClientId,
+		_height: Height,
+		_consensus_state: Self::AnyConsensusState,
+	) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn increase_client_counter(&mut self) {}
+
+	fn store_update_time(
+		&mut self,
+		_client_id: ClientId,
+		_height: Height,
+		_timestamp: Timestamp,
+	) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn store_update_height(
+		&mut self,
+		_client_id: ClientId,
+		_height: Height,
+		_host_height: Height,
+	) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn delete_consensus_state(
+		&mut self,
+		_client_id: ClientId,
+		_height: Height,
+	) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn delete_update_time(&mut self, _client_id: ClientId, _height: Height) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn delete_update_height(&mut self, _client_id: ClientId, _height: Height) -> Result<(), Error> {
+		Err(read_only())
+	}
+
+	fn validate_self_client(&self, client_state: &Self::AnyClientState) -> Result<(), Error> {
+		self.ctx.validate_self_client(client_state)
+	}
+}
+
+fn read_only() -> Error {
+	Error::implementation_specific(
+		"batched client updates are not stored until the batch applies".to_string(),
+	)
+}
+
+impl<Ctx: ReaderContext> ConnectionReader for BatchContext<Ctx> {
+	fn minimum_delay_period(&self) -> Duration {
+		self.ctx.minimum_delay_period()
+	}
+
+	fn connection_end(&self, conn_id: &ConnectionId) -> Result<ConnectionEnd, ConnectionError> {
+		self.ctx.connection_end(conn_id)
+	}
+
+	fn client_connections(
+		&self,
+		client_id: &ClientId,
+	) -> Result<Vec<ConnectionId>, ConnectionError> {
+		self.ctx.client_connections(client_id)
+	}
+
+	fn host_oldest_height(&self) -> Height {
+		self.ctx.host_oldest_height()
+	}
+
+	fn commitment_prefix(&self) -> CommitmentPrefix {
+		self.ctx.commitment_prefix()
+	}
+
+	fn expected_counterparty_prefix(&self, client_id: &ClientId) -> Option<CommitmentPrefix> {
+		self.ctx.expected_counterparty_prefix(client_id)
+	}
+
+	fn get_compatible_versions(&self) -> Vec<Version> {
+		self.ctx.get_compatible_versions()
+	}
+
+	fn supported_versions(&self) -> Vec<Version> {
+		self.ctx.supported_versions()
+	}
+
+	fn pick_version(
+		&self,
+		supported_versions: Vec<Version>,
+		counterparty_candidate_versions: Vec<Version>,
+	) -> Result<Version, ConnectionError> {
+		self.ctx.pick_version(supported_versions, counterparty_candidate_versions)
+	}
+
+	fn connection_counter(&self) -> Result<u64, ConnectionError> {
+		self.ctx.connection_counter()
+	}
+
+	fn handshake_timeout(&self) -> u64 {
+		self.ctx.handshake_timeout()
+	}
+
+	fn connection_handshake_origin(
+		&self,
+		conn_id: &ConnectionId,
+	) -> Result<HandshakeOrigin, ConnectionError> {
+		self.ctx.connection_handshake_origin(conn_id)
+	}
+}
+
+impl<Ctx: ReaderContext> ChannelReader for BatchContext<Ctx> {
+	fn channel_end(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<ChannelEnd, ChannelError> {
+		self.ctx.channel_end(port_channel_id)
+	}
+
+	fn connection_channels(
+		&self,
+		cid: &ConnectionId,
+	) -> Result<Vec<(PortId, ChannelId)>, ChannelError> {
+		self.ctx.connection_channels(cid)
+	}
+
+	fn get_next_sequence_send(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, ChannelError> {
+		self.ctx.get_next_sequence_send(port_channel_id)
+	}
+
+	fn get_next_sequence_recv(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, ChannelError> {
+		self.ctx.get_next_sequence_recv(port_channel_id)
+	}
+
+	fn get_next_sequence_ack(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, ChannelError> {
+		self.ctx.get_next_sequence_ack(port_channel_id)
+	}
+
+	fn get_packet_commitment(
+		&self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<PacketCommitment, ChannelError> {
+		self.ctx.get_packet_commitment(key)
+	}
+
+	fn packet_commitment_count(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<u64, ChannelError> {
+		self.ctx.packet_commitment_count(port_channel_id)
+	}
+
+	fn get_packet_receipt(
+		&self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<Receipt, ChannelError> {
+		self.ctx.get_packet_receipt(key)
+	}
+
+	fn get_packet_acknowledgement(
+		&self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<AcknowledgementCommitment, ChannelError> {
+		self.ctx.get_packet_acknowledgement(key)
+	}
+
+	fn packet_acknowledgements(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+		start_sequence: Sequence,
+		limit: u64,
+	) -> Result<Vec<(Sequence, AcknowledgementCommitment)>, ChannelError> {
+		self.ctx.packet_acknowledgements(port_channel_id, start_sequence, limit)
+	}
+
+	fn get_pruning_sequence_start(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, ChannelError> {
+		self.ctx.get_pruning_sequence_start(port_channel_id)
+	}
+
+	fn timed_out_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<(Sequence, Timestamp)>, ChannelError> {
+		self.ctx.timed_out_packets(port_channel_id)
+	}
+
+	fn is_close_authorized(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		signer: &Signer,
+	) -> bool {
+		self.ctx.is_close_authorized(port_id, channel_id, signer)
+	}
+
+	fn is_upgrade_authorized(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		signer: &Signer,
+	) -> bool {
+		self.ctx.is_upgrade_authorized(port_id, channel_id, signer)
+	}
+
+	fn channel_upgrade_origin(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<UpgradeOrigin, ChannelError> {
+		self.ctx.channel_upgrade_origin(port_channel_id)
+	}
+
+	fn hash(&self, value: Vec<u8>) -> Vec<u8> {
+		self.ctx.hash(value)
+	}
+
+	/// Pending consensus states are processed now, when the batch is stored.
+	fn client_update_time(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Timestamp, ChannelError> {
+		if self.is_pending(client_id, height) {
+			return Ok(self.ctx.host_timestamp())
+		}
+		self.ctx.client_update_time(client_id, height)
+	}
+
+	fn client_update_height(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Height, ChannelError> {
+		if self.is_pending(client_id, height) {
+			return Ok(self.ctx.host_height())
+		}
+		self.ctx.client_update_height(client_id, height)
+	}
+
+	fn channel_counter(&self) -> Result<u64, ChannelError> {
+		self.ctx.channel_counter()
+	}
+
+	fn max_expected_time_per_block(&self) -> Duration {
+		self.ctx.max_expected_time_per_block()
+	}
+}
@@ -22,7 +22,7 @@ use crate::{
 			context::ClientTypes,
 			error::Error,
 			events::Attributes,
-			handler::ClientResult,
+			handler::{ensure_client_type_supported, ClientResult},
 			msgs::upgrade_client::MsgUpgradeAnyClient,
 		},
 		ics24_host::identifier::ClientId,
@@ -31,6 +31,8 @@ use crate::{
 	events::IbcEvent,
 	handler::{HandlerOutput, HandlerResult},
 	prelude::*,
+	timestamp::Timestamp,
+	Height,
 };
 use core::fmt::Debug;
 
@@ -41,6 +43,8 @@ pub struct Result<C: ClientTypes> {
 	pub client_id: ClientId,
 	pub client_state: C::AnyClientState,
 	pub consensus_state: Option<ConsensusUpdateResult<C>>,
+	pub processed_time: Timestamp,
+	pub processed_height: Height,
 }
 
 pub fn process<Ctx>(
@@ -61,6 +65,7 @@ where
 	}
 
 	let upgrade_client_state = msg.client_state.clone();
+	ensure_client_type_supported(ctx, &upgrade_client_state.client_type())?;
 
 	if client_state.latest_height() >= upgrade_client_state.latest_height() {
 		return Err(Error::low_upgrade_height(
@@ -95,6 +100,8 @@ where
 		client_id,
 		client_state: new_client_state,
 		consensus_state: Some(new_consensus_state),
+		processed_time: ctx.host_timestamp(),
+		processed_height: ctx.host_height(),
 	});
 
 	output.emit(IbcEvent::UpgradeClient(event_attributes.into()));
@@ -21,13 +21,17 @@
 use crate::core::ics02_client::{
 	context::ClientTypes,
 	msgs::{
-		create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient,
-		upgrade_client::MsgUpgradeAnyClient,
+		create_client::MsgCreateAnyClient, misbehaviour::MsgSubmitMisbehaviour,
+		recover_client::MsgRecoverClient, update_client::MsgUpdateAnyClient,
+		update_client_batch::MsgUpdateAnyClientBatch, upgrade_client::MsgUpgradeAnyClient,
 	},
 };
 
 pub mod create_client;
+pub mod misbehaviour;
+pub mod recover_client;
 pub mod update_client;
+pub mod update_client_batch;
 pub mod upgrade_client;
 
 #[allow(clippy::large_enum_variant)]
@@ -35,5 +39,8 @@ pub mod upgrade_client;
 pub enum ClientMsg<C: ClientTypes> {
 	CreateClient(MsgCreateAnyClient<C>),
 	UpdateClient(MsgUpdateAnyClient<C>),
+	UpdateClientBatch(MsgUpdateAnyClientBatch<C>),
 	UpgradeClient(MsgUpgradeAnyClient<C>),
+	RecoverClient(MsgRecoverClient),
+	Misbehaviour(MsgSubmitMisbehaviour<C>),
 }
@@ -0,0 +1,113 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Definition of domain type message `MsgSubmitMisbehaviour`.
+
+use crate::prelude::*;
+use core::fmt::Display;
+
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::client::v1::MsgSubmitMisbehaviour as RawMsgSubmitMisbehaviour;
+
+use crate::{
+	core::{
+		ics02_client::{context::ClientTypes, error::Error},
+		ics24_host::{error::ValidationError, identifier::ClientId},
+	},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.client.v1.MsgSubmitMisbehaviour";
+
+/// A type of message that submits evidence of misbehaviour of the chain tracked by an on-chain
+/// (IBC) client, freezing the client.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgSubmitMisbehaviour<C: ClientTypes> {
+	pub client_id: ClientId,
+	pub misbehaviour: C::AnyClientMessage,
+	pub signer: Signer,
+}
+
+impl<C> MsgSubmitMisbehaviour<C>
+where
+	C: ClientTypes,
+{
+	pub fn new(client_id: ClientId, misbehaviour: C::AnyClientMessage, signer: Signer) -> Self {
+		MsgSubmitMisbehaviour { client_id, misbehaviour, signer }
+	}
+}
+
+impl<C> Msg for MsgSubmitMisbehaviour<C>
+where
+	C: ClientTypes + Clone,
+	C::AnyClientMessage: Clone,
+	Any: From<C::AnyClientMessage>,
+{
+	type ValidationError = ValidationError;
+	type Raw = RawMsgSubmitMisbehaviour;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl<C> Protobuf<RawMsgSubmitMisbehaviour> for MsgSubmitMisbehaviour<C>
+where
+	C: ClientTypes + Clone,
+	C::AnyClientMessage: Clone,
+	Any: From<C::AnyClientMessage>,
+	MsgSubmitMisbehaviour<C>: TryFrom<RawMsgSubmitMisbehaviour>,
+	<MsgSubmitMisbehaviour<C> as TryFrom<RawMsgSubmitMisbehaviour>>::Error: Display,
+{
+}
+
+impl<C> TryFrom<RawMsgSubmitMisbehaviour> for MsgSubmitMisbehaviour<C>
+where
+	C: ClientTypes,
+	C::AnyClientMessage: TryFrom<Any>,
+	Error: From<<C::AnyClientMessage as TryFrom<Any>>::Error>,
+{
+	type Error = Error;
+
+	fn try_from(raw: RawMsgSubmitMisbehaviour) -> Result<Self, Self::Error> {
+		let raw_misbehaviour = raw.misbehaviour.ok_or_else(Error::missing_raw_misbehaviour)?;
+
+		Ok(MsgSubmitMisbehaviour {
+			client_id: raw.client_id.parse().map_err(Error::invalid_raw_misbehaviour)?,
+			misbehaviour: C::AnyClientMessage::try_from(raw_misbehaviour)?,
+			signer: raw.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl<C> From<MsgSubmitMisbehaviour<C>> for RawMsgSubmitMisbehaviour
+where
+	C: ClientTypes,
+	Any: From<C::AnyClientMessage>,
+{
+	fn from(ics_msg: MsgSubmitMisbehaviour<C>) -> Self {
+		RawMsgSubmitMisbehaviour {
+			client_id: ics_msg.client_id.to_string(),
+			misbehaviour: Some(ics_msg.misbehaviour.into()),
+			signer: ics_msg.signer.to_string(),
+		}
+	}
+}
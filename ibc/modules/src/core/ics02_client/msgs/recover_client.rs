@@ -0,0 +1,119 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Definition of domain type message `MsgRecoverClient`.
+
+use crate::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::client::v1::MsgRecoverClient as RawMsgRecoverClient;
+
+use crate::{
+	core::{ics02_client::error::Error, ics24_host::identifier::ClientId},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.client.v1.MsgRecoverClient";
+
+/// A type of message that replaces a frozen or expired client (the subject) with the state of
+/// an active client (the substitute) tracking the same chain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgRecoverClient {
+	pub subject_client_id: ClientId,
+	pub substitute_client_id: ClientId,
+	pub signer: Signer,
+}
+
+impl MsgRecoverClient {
+	pub fn new(
+		subject_client_id: ClientId,
+		substitute_client_id: ClientId,
+		signer: Signer,
+	) -> Self {
+		MsgRecoverClient { subject_client_id, substitute_client_id, signer }
+	}
+}
+
+impl Msg for MsgRecoverClient {
+	type ValidationError = Error;
+	type Raw = RawMsgRecoverClient;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgRecoverClient> for MsgRecoverClient {}
+
+impl TryFrom<RawMsgRecoverClient> for MsgRecoverClient {
+	type Error = Error;
+
+	fn try_from(raw: RawMsgRecoverClient) -> Result<Self, Self::Error> {
+		Ok(MsgRecoverClient {
+			subject_client_id: raw
+				.subject_client_id
+				.parse()
+				.map_err(Error::invalid_client_identifier)?,
+			substitute_client_id: raw
+				.substitute_client_id
+				.parse()
+				.map_err(Error::invalid_client_identifier)?,
+			signer: raw.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgRecoverClient> for RawMsgRecoverClient {
+	fn from(ics_msg: MsgRecoverClient) -> Self {
+		RawMsgRecoverClient {
+			subject_client_id: ics_msg.subject_client_id.to_string(),
+			substitute_client_id: ics_msg.substitute_client_id.to_string(),
+			signer: ics_msg.signer.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+
+	use ibc_proto::ibc::core::client::v1::MsgRecoverClient as RawMsgRecoverClient;
+
+	use crate::{
+		core::ics02_client::msgs::recover_client::MsgRecoverClient,
+		test_utils::get_dummy_bech32_account,
+	};
+
+	#[test]
+	fn to_and_from() {
+		let raw = RawMsgRecoverClient {
+			subject_client_id: "07-tendermint-0".to_string(),
+			substitute_client_id: "07-tendermint-1".to_string(),
+			signer: get_dummy_bech32_account(),
+		};
+		let msg = MsgRecoverClient::try_from(raw.clone()).unwrap();
+		let raw_back = RawMsgRecoverClient::from(msg.clone());
+		let msg_back = MsgRecoverClient::try_from(raw_back.clone()).unwrap();
+		assert_eq!(raw, raw_back);
+		assert_eq!(msg, msg_back);
+	}
+}
@@ -0,0 +1,123 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Definition of domain type message `MsgUpdateAnyClientBatch`.
+
+use crate::prelude::*;
+use core::fmt::Display;
+
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::client::v1::MsgUpdateClientBatch as RawMsgUpdateClientBatch;
+
+use crate::{
+	core::{
+		ics02_client::{context::ClientTypes, error::Error},
+		ics24_host::{error::ValidationError, identifier::ClientId},
+	},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.client.v1.MsgUpdateClientBatch";
+
+/// A type of message that updates an on-chain (IBC) client with several headers at once, which
+/// are applied in order, each on top of the client state produced by the previous one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgUpdateAnyClientBatch<C: ClientTypes> {
+	pub client_id: ClientId,
+	pub client_messages: Vec<C::AnyClientMessage>,
+	pub signer: Signer,
+}
+
+impl<C> MsgUpdateAnyClientBatch<C>
+where
+	C: ClientTypes,
+{
+	pub fn new(
+		client_id: ClientId,
+		client_messages: Vec<C::AnyClientMessage>,
+		signer: Signer,
+	) -> Self {
+		MsgUpdateAnyClientBatch { client_id, client_messages, signer }
+	}
+}
+
+impl<C> Msg for MsgUpdateAnyClientBatch<C>
+where
+	C: ClientTypes + Clone,
+	C::AnyClientMessage: Clone,
+	Any: From<C::AnyClientMessage>,
+{
+	type ValidationError = ValidationError;
+	type Raw = RawMsgUpdateClientBatch;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl<C> Protobuf<RawMsgUpdateClientBatch> for MsgUpdateAnyClientBatch<C>
+where
+	C: ClientTypes + Clone,
+	C::AnyClientMessage: Clone,
+	Any: From<C::AnyClientMessage>,
+	MsgUpdateAnyClientBatch<C>: TryFrom<RawMsgUpdateClientBatch>,
+	<MsgUpdateAnyClientBatch<C> as TryFrom<RawMsgUpdateClientBatch>>::Error: Display,
+{
+}
+
+impl<C> TryFrom<RawMsgUpdateClientBatch> for MsgUpdateAnyClientBatch<C>
+where
+	C: ClientTypes,
+	C::AnyClientMessage: TryFrom<Any>,
+	Error: From<<C::AnyClientMessage as TryFrom<Any>>::Error>,
+{
+	type Error = Error;
+
+	fn try_from(raw: RawMsgUpdateClientBatch) -> Result<Self, Self::Error> {
+		if raw.client_messages.is_empty() {
+			return Err(Error::missing_raw_client_message())
+		}
+
+		Ok(MsgUpdateAnyClientBatch {
+			client_id: raw.client_id.parse().map_err(Error::invalid_msg_update_client_id)?,
+			client_messages: raw
+				.client_messages
+				.into_iter()
+				.map(C::AnyClientMessage::try_from)
+				.collect::<Result<_, _>>()?,
+			signer: raw.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl<C> From<MsgUpdateAnyClientBatch<C>> for RawMsgUpdateClientBatch
+where
+	C: ClientTypes,
+	Any: From<C::AnyClientMessage>,
+{
+	fn from(ics_msg: MsgUpdateAnyClientBatch<C>) -> Self {
+		RawMsgUpdateClientBatch {
+			client_id: ics_msg.client_id.to_string(),
+			client_messages: ics_msg.client_messages.into_iter().map(Into::into).collect(),
+			signer: ics_msg.signer.to_string(),
+		}
+	}
+}
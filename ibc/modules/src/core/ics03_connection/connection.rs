@@ -16,12 +16,15 @@ use crate::prelude::*;
 
 use core::{fmt, str::FromStr, time::Duration, u64};
 
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use tendermint_proto::Protobuf;
 
-use ibc_proto::ibc::core::connection::v1::{
-	ConnectionEnd as RawConnectionEnd, Counterparty as RawCounterparty,
-	IdentifiedConnection as RawIdentifiedConnection,
+use ibc_proto::ibc::core::{
+	commitment::v1::MerklePrefix as RawMerklePrefix,
+	connection::v1::{
+		ConnectionEnd as RawConnectionEnd, Counterparty as RawCounterparty,
+		IdentifiedConnection as RawIdentifiedConnection,
+	},
 };
 
 use crate::{
@@ -34,6 +37,7 @@ use crate::{
 			identifier::{ClientId, ConnectionId},
 		},
 	},
+	serializers::serde_string,
 	timestamp::ZERO_DURATION,
 };
 
@@ -91,7 +95,10 @@ impl From<IdentifiedConnectionEnd> for RawIdentifiedConnection {
 	}
 }
 
+/// A connection end. It is encoded as the JSON ibc-go's gRPC gateway renders a connection end,
+/// with the delay period in nanoseconds as a string.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "JsonConnectionEnd", into = "JsonConnectionEnd")]
 pub struct ConnectionEnd {
 	pub state: State,
 	client_id: ClientId,
@@ -151,6 +158,44 @@ impl From<ConnectionEnd> for RawConnectionEnd {
 	}
 }
 
+/// The JSON form of [`ConnectionEnd`].
+#[derive(Serialize, Deserialize)]
+struct JsonConnectionEnd {
+	client_id: String,
+	versions: Vec<Version>,
+	state: State,
+	counterparty: JsonCounterparty,
+	#[serde(with = "serde_string")]
+	delay_period: u64,
+}
+
+impl TryFrom<JsonConnectionEnd> for ConnectionEnd {
+	type Error = Error;
+
+	fn try_from(json: JsonConnectionEnd) -> Result<Self, Self::Error> {
+		RawConnectionEnd {
+			client_id: json.client_id,
+			versions: json.versions.into_iter().map(Into::into).collect(),
+			state: json.state as i32,
+			counterparty: Some(json.counterparty.into()),
+			delay_period: json.delay_period,
+		}
+		.try_into()
+	}
+}
+
+impl From<ConnectionEnd> for JsonConnectionEnd {
+	fn from(value: ConnectionEnd) -> Self {
+		Self {
+			client_id: value.client_id.to_string(),
+			versions: value.versions,
+			state: value.state,
+			counterparty: value.counterparty.into(),
+			delay_period: value.delay_period.as_nanos() as u64,
+		}
+	}
+}
+
 impl ConnectionEnd {
 	pub fn new(
 		state: State,
@@ -234,7 +279,10 @@ impl ConnectionEnd {
 	}
 }
 
+/// The counterparty of a connection end. It is encoded as JSON like ibc-go does, with an empty
+/// connection id while it is unknown and the commitment prefix in base64.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "JsonCounterparty", into = "JsonCounterparty")]
 pub struct Counterparty {
 	client_id: ClientId,
 	pub connection_id: Option<ConnectionId>,
@@ -274,9 +322,52 @@ impl From<Counterparty> for RawCounterparty {
 			connection_id: value
 				.connection_id
 				.map_or_else(|| "".to_string(), |v| v.as_str().to_string()),
-			prefix: Some(ibc_proto::ibc::core::commitment::v1::MerklePrefix {
-				key_prefix: value.prefix.into_vec(),
-			}),
+			prefix: Some(RawMerklePrefix { key_prefix: value.prefix.into_vec() }),
+		}
+	}
+}
+
+/// The JSON form of [`Counterparty`].
+#[derive(Serialize, Deserialize)]
+struct JsonCounterparty {
+	client_id: String,
+	connection_id: String,
+	prefix: JsonMerklePrefix,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonMerklePrefix {
+	#[serde(with = "tendermint_proto::serializers::bytes::base64string")]
+	key_prefix: Vec<u8>,
+}
+
+impl From<JsonCounterparty> for RawCounterparty {
+	fn from(json: JsonCounterparty) -> Self {
+		RawCounterparty {
+			client_id: json.client_id,
+			connection_id: json.connection_id,
+			prefix: Some(RawMerklePrefix { key_prefix: json.prefix.key_prefix }),
+		}
+	}
+}
+
+impl TryFrom<JsonCounterparty> for Counterparty {
+	type Error = Error;
+
+	fn try_from(json: JsonCounterparty) -> Result<Self, Self::Error> {
+		RawCounterparty::from(json).try_into()
+	}
+}
+
+impl From<Counterparty> for JsonCounterparty {
+	fn from(value: Counterparty) -> Self {
+		let raw = RawCounterparty::from(value);
+		Self {
+			client_id: raw.client_id,
+			connection_id: raw.connection_id,
+			prefix: JsonMerklePrefix {
+				key_prefix: raw.prefix.map(|prefix| prefix.key_prefix).unwrap_or_default(),
+			},
 		}
 	}
 }
@@ -309,7 +400,9 @@ impl Counterparty {
 	}
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// The state of a connection end. It is encoded in JSON as the name of the protobuf enum value,
+/// e.g. `"STATE_OPEN"`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum State {
 	Uninitialized = 0,
 	Init = 1,
@@ -328,6 +421,16 @@ impl State {
 		}
 	}
 
+	/// Yields the State as the name of its protobuf enum value.
+	pub fn as_proto_str(&self) -> &'static str {
+		match self {
+			Self::Uninitialized => "STATE_UNINITIALIZED_UNSPECIFIED",
+			Self::Init => "STATE_INIT",
+			Self::TryOpen => "STATE_TRYOPEN",
+			Self::Open => "STATE_OPEN",
+		}
+	}
+
 	/// Parses the State out from a i32.
 	pub fn from_i32(s: i32) -> Result<Self, Error> {
 		match s {
@@ -364,6 +467,22 @@ impl fmt::Display for State {
 	}
 }
 
+impl Serialize for State {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_proto_str())
+	}
+}
+
+impl<'de> Deserialize<'de> for State {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let name = String::deserialize(deserializer)?;
+		[Self::Uninitialized, Self::Init, Self::TryOpen, Self::Open]
+			.into_iter()
+			.find(|state| state.as_proto_str() == name)
+			.ok_or_else(|| de::Error::custom(format!("unknown connection state {}", name)))
+	}
+}
+
 impl TryFrom<i32> for State {
 	type Error = Error;
 	fn try_from(value: i32) -> Result<Self, Self::Error> {
@@ -382,3 +501,63 @@ impl From<State> for i32 {
 		value.into()
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use core::time::Duration;
+	use test_log::test;
+
+	use crate::core::{
+		ics03_connection::{
+			connection::{ConnectionEnd, Counterparty, State},
+			version::get_compatible_versions,
+		},
+		ics24_host::identifier::ConnectionId,
+	};
+
+	/// Checks that `connection_end` serializes to the connection of an ibc-go
+	/// `/ibc/core/connection/v1/connections/{connection_id}` response, and parses back from it.
+	fn assert_matches_ibc_go(response: &str, connection_end: ConnectionEnd) {
+		let response: serde_json::Value = serde_json::from_str(response).unwrap();
+		let json = &response["connection"];
+		assert_eq!(&serde_json::to_value(&connection_end).unwrap(), json);
+		assert_eq!(serde_json::from_value::<ConnectionEnd>(json.clone()).unwrap(), connection_end);
+	}
+
+	#[test]
+	fn connection_end_json_matches_ibc_go() {
+		let open = ConnectionEnd::new(
+			State::Open,
+			"07-tendermint-0".parse().unwrap(),
+			Counterparty::new(
+				"07-tendermint-12".parse().unwrap(),
+				Some(ConnectionId::new(7)),
+				b"ibc".to_vec().try_into().unwrap(),
+			),
+			get_compatible_versions(),
+			Duration::ZERO,
+		);
+		assert_matches_ibc_go(include_str!("serialization/query_connection.json"), open);
+
+		let init = ConnectionEnd::new(
+			State::Init,
+			"07-tendermint-3".parse().unwrap(),
+			Counterparty::new(
+				"10-grandpa-0".parse().unwrap(),
+				None,
+				b"ibc".to_vec().try_into().unwrap(),
+			),
+			get_compatible_versions(),
+			Duration::from_secs(30),
+		);
+		assert_matches_ibc_go(include_str!("serialization/query_connection_init.json"), init);
+	}
+
+	#[test]
+	fn unknown_connection_state_is_rejected() {
+		assert!(serde_json::from_str::<State>("\"STATE_CLOSED\"").is_err());
+		assert_eq!(serde_json::from_str::<State>("\"STATE_TRYOPEN\"").unwrap(), State::TryOpen);
+	}
+}
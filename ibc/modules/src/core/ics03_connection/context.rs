@@ -19,9 +19,9 @@
 use crate::{
 	core::{
 		ics03_connection::{
-			connection::ConnectionEnd,
+			connection::{ConnectionEnd, State},
 			error::Error,
-			handler::{ConnectionIdState, ConnectionResult},
+			handler::{ConnectionIdState, ConnectionResult, HandshakeOrigin},
 			version::{get_compatible_versions, pick_version, Version},
 		},
 		ics23_commitment::commitment::CommitmentPrefix,
@@ -41,18 +41,34 @@ pub trait ConnectionReader {
 	/// Returns the ConnectionEnd for the given identifier `conn_id`.
 	fn connection_end(&self, conn_id: &ConnectionId) -> Result<ConnectionEnd, Error>;
 
+	/// Returns the identifiers of all connections built on top of the client `client_id`, in the
+	/// order they were created.
+	fn client_connections(&self, client_id: &ClientId) -> Result<Vec<ConnectionId>, Error>;
+
 	/// Returns the oldest height available on the local chain.
 	fn host_oldest_height(&self) -> Height;
 
 	/// Returns the prefix that the local chain uses in the KV store.
 	fn commitment_prefix(&self) -> CommitmentPrefix;
 
+	/// Returns the commitment prefix the counterparty tracked by `client_id` is expected to use, if
+	/// the host pins one. Connection handshakes announcing a different prefix are rejected.
+	fn expected_counterparty_prefix(&self, _client_id: &ClientId) -> Option<CommitmentPrefix> {
+		None
+	}
+
 	/// Function required by ICS 03. Returns the list of all possible versions that the connection
 	/// handshake protocol supports.
 	fn get_compatible_versions(&self) -> Vec<Version> {
 		get_compatible_versions()
 	}
 
+	/// Returns the list of versions this host is willing to negotiate during the connection
+	/// handshake. Hosts may override this to restrict or extend the features they support.
+	fn supported_versions(&self) -> Vec<Version> {
+		self.get_compatible_versions()
+	}
+
 	/// Function required by ICS 03. Returns one version out of the supplied list of versions, which
 	/// the connection handshake protocol prefers.
 	fn pick_version(
@@ -67,12 +83,23 @@ pub trait ConnectionReader {
 	/// The value of this counter should increase only via method
 	/// `ConnectionKeeper::increase_connection_counter`.
 	fn connection_counter(&self) -> Result<u64, Error>;
+
+	/// Number of host blocks after which anyone, not only the account that started it, may
+	/// cancel a connection handshake that has not progressed past `TryOpen`.
+	fn handshake_timeout(&self) -> u64;
+
+	/// Returns who started the handshake for the given connection and when.
+	fn connection_handshake_origin(&self, conn_id: &ConnectionId) -> Result<HandshakeOrigin, Error>;
 }
 
 /// A context supplying all the necessary write-only dependencies (i.e., storage writing facility)
 /// for processing any `ConnectionMsg`.
 pub trait ConnectionKeeper {
 	fn store_connection_result(&mut self, result: ConnectionResult) -> Result<(), Error> {
+		if matches!(result.connection_id_state, ConnectionIdState::Removed) {
+			return self.delete_connection_end(&result.connection_id)
+		}
+
 		self.store_connection(result.connection_id.clone(), &result.connection_end)?;
 
 		// If we generated an identifier, increase the counter & associate this new identifier
@@ -87,6 +114,15 @@ pub trait ConnectionKeeper {
 			)?;
 		}
 
+		if let Some(origin) = result.handshake_origin {
+			self.store_connection_handshake_origin(result.connection_id.clone(), origin)?;
+		}
+
+		// Open connections can't be cancelled anymore, so who started the handshake is moot.
+		if result.connection_end.state_matches(&State::Open) {
+			self.delete_connection_handshake_origin(&result.connection_id)?;
+		}
+
 		Ok(())
 	}
 
@@ -97,7 +133,24 @@ pub trait ConnectionKeeper {
 		connection_end: &ConnectionEnd,
 	) -> Result<(), Error>;
 
-	/// Stores the given connection_id at a path associated with the client_id.
+	/// Stores who started the handshake for the given connection and when.
+	fn store_connection_handshake_origin(
+		&mut self,
+		connection_id: ConnectionId,
+		origin: HandshakeOrigin,
+	) -> Result<(), Error>;
+
+	/// Removes the handshake origin stored for the given connection_id, if any.
+	fn delete_connection_handshake_origin(
+		&mut self,
+		connection_id: &ConnectionId,
+	) -> Result<(), Error>;
+
+	/// Removes the connection end stored for the given connection_id, along with its handshake
+	/// origin and its association to the client.
+	fn delete_connection_end(&mut self, connection_id: &ConnectionId) -> Result<(), Error>;
+
+	/// Adds the given connection_id to the list of connections associated with the client_id.
 	fn store_connection_to_client(
 		&mut self,
 		connection_id: ConnectionId,
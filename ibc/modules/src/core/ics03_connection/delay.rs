@@ -0,0 +1,67 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	core::{
+		ics03_connection::{connection::ConnectionEnd, error::Error as ConnectionError},
+		ics04_channel::error::Error,
+		ics26_routing::context::ReaderContext,
+	},
+	Height,
+};
+
+/// Checks that the delay period of the connection has passed, both in time and in blocks, since
+/// the consensus state at `packet_proof_height` was stored on the host chain.
+pub fn verify_conn_delay_passed<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	packet_proof_height: Height,
+	connection_end: &ConnectionEnd,
+) -> Result<(), Error> {
+	// Fetch the current host chain time and height.
+	let current_host_time = ctx.host_timestamp();
+	let current_host_height = ctx.host_height();
+
+	// Fetch the time and height at which the client stored the consensus state.
+	let client_id = connection_end.client_id();
+	let last_client_update_time = ctx.client_update_time(client_id, packet_proof_height)?;
+	let last_client_update_height = ctx.client_update_height(client_id, packet_proof_height)?;
+
+	// Fetch the connection delay time and height periods.
+	let conn_delay_time_period = connection_end.delay_period();
+	let conn_delay_height_period = ctx.block_delay(conn_delay_time_period);
+
+	// Verify that the delay period has elapsed on the host chain clock.
+	let earliest_valid_time = (last_client_update_time + conn_delay_time_period)
+		.map_err(|e| Error::ics03_connection(ConnectionError::timestamp_overflow(e)))?;
+	if !(current_host_time == earliest_valid_time ||
+		current_host_time.after(&earliest_valid_time))
+	{
+		return Err(Error::ics03_connection(ConnectionError::not_enough_time_elapsed(
+			current_host_time,
+			earliest_valid_time,
+		)))
+	}
+
+	// Verify that the equivalent number of host chain blocks has been produced as well.
+	let earliest_valid_height = last_client_update_height.add(conn_delay_height_period);
+	if current_host_height < earliest_valid_height {
+		return Err(Error::ics03_connection(ConnectionError::not_enough_blocks_elapsed(
+			current_host_height,
+			earliest_valid_height,
+		)))
+	}
+
+	Ok(())
+}
@@ -15,7 +15,8 @@
 use crate::{
 	core::{
 		ics02_client::error as client_error,
-		ics03_connection::version::Version,
+		ics03_connection::{connection::State, version::Version},
+		ics23_commitment::{commitment::CommitmentPrefix, error as commitment_error},
 		ics24_host::{
 			error::ValidationError,
 			identifier::{ClientId, ConnectionId},
@@ -24,6 +25,7 @@ use crate::{
 	prelude::*,
 	proofs::ProofError,
 	signer::SignerError,
+	timestamp::{Timestamp, TimestampOverflowError},
 	Height,
 };
 
@@ -144,6 +146,23 @@ define_error! {
 		MissingCounterpartyPrefix
 			| _ | { "missing counterparty prefix" },
 
+		CounterpartyPrefixMismatch
+			{
+				expected: CommitmentPrefix,
+				actual: CommitmentPrefix,
+			}
+			| e | {
+				format_args!("counterparty commitment prefix {0:?} does not match the expected prefix {1:?}",
+					e.actual, e.expected)
+			},
+
+		InvalidCounterpartyPrefix
+			{ prefix: CommitmentPrefix }
+			[ commitment_error::Error ]
+			| e | {
+				format_args!("invalid counterparty commitment prefix {0:?}", e.prefix)
+			},
+
 		NullClientProof
 			| _ | { "client proof must be present" },
 
@@ -165,6 +184,13 @@ define_error! {
 					e.height)
 			},
 
+		InvalidSelfConsensusState
+			{ height: Height }
+			| e | {
+				format_args!("the consensus state proven for this chain at height {0} does not match the local history",
+					e.height)
+			},
+
 		// TODO: use more specific error source
 		ClientStateVerificationFailure
 			{
@@ -176,6 +202,51 @@ define_error! {
 					e.client_id)
 			},
 
+		ConnectionNotCancellable
+			{
+				connection_id: ConnectionId,
+				state: State,
+			}
+			| e | {
+				format_args!("connection {0} cannot be cancelled in state {1}",
+					e.connection_id, e.state)
+			},
+
+		HandshakeNotTimedOut
+			{
+				connection_id: ConnectionId,
+				elapsed: u64,
+				timeout: u64,
+			}
+			| e | {
+				format_args!("handshake for connection {0} can only be cancelled by its initiator before the timeout ({1} of {2} blocks elapsed)",
+					e.connection_id, e.elapsed, e.timeout)
+			},
+
+		NotEnoughTimeElapsed
+			{
+				current_host_time: Timestamp,
+				earliest_time: Timestamp,
+			}
+			| e | {
+				format_args!("not enough time elapsed since the consensus state was stored, current host time: {0}, earliest time: {1}",
+					e.current_host_time, e.earliest_time)
+			},
+
+		NotEnoughBlocksElapsed
+			{
+				current_host_height: Height,
+				earliest_height: Height,
+			}
+			| e | {
+				format_args!("not enough blocks elapsed since the consensus state was stored, current host height: {0}, earliest height: {1}",
+					e.current_host_height, e.earliest_height)
+			},
+
+		TimestampOverflow
+			[ TimestampOverflowError ]
+			| _ | { "timestamp overflowed while computing the end of the connection delay" },
+
 		ImplementationSpecific
 			{ reason: String }
 			| e | { format_args!("implementation specific error: {}", e.reason) },
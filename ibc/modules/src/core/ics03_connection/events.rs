@@ -52,6 +52,10 @@ pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
 			.map(OpenConfirm::from)
 			.map(IbcEvent::OpenConfirmConnection)
 			.ok(),
+		Ok(IbcEventType::ConnectionCancelled) => extract_attributes_from_tx(event)
+			.map(Cancelled::from)
+			.map(IbcEvent::ConnectionCancelled)
+			.ok(),
 		_ => None,
 	}
 }
@@ -286,6 +290,43 @@ impl From<OpenConfirm> for AbciEvent {
 	}
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Cancelled(Attributes);
+
+impl Cancelled {
+	pub fn attributes(&self) -> &Attributes {
+		&self.0
+	}
+	pub fn connection_id(&self) -> Option<&ConnectionId> {
+		self.0.connection_id.as_ref()
+	}
+	pub fn height(&self) -> Height {
+		self.0.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.0.height = height;
+	}
+}
+
+impl From<Attributes> for Cancelled {
+	fn from(attrs: Attributes) -> Self {
+		Cancelled(attrs)
+	}
+}
+
+impl From<Cancelled> for IbcEvent {
+	fn from(v: Cancelled) -> Self {
+		IbcEvent::ConnectionCancelled(v)
+	}
+}
+
+impl From<Cancelled> for AbciEvent {
+	fn from(v: Cancelled) -> Self {
+		let attributes = Vec::<EventAttribute>::from(v.0);
+		AbciEvent { kind: IbcEventType::ConnectionCancelled.as_str().to_string(), attributes }
+	}
+}
+
 #[cfg(test)]
 mod test {
 	use super::*;
@@ -307,8 +348,10 @@ mod test {
 		abci_events.push(AbciEvent::from(open_try.clone()));
 		let open_ack = OpenAck::from(attributes.clone());
 		abci_events.push(AbciEvent::from(open_ack.clone()));
-		let open_confirm = OpenConfirm::from(attributes);
+		let open_confirm = OpenConfirm::from(attributes.clone());
 		abci_events.push(AbciEvent::from(open_confirm.clone()));
+		let cancelled = Cancelled::from(attributes);
+		abci_events.push(AbciEvent::from(cancelled.clone()));
 
 		for event in abci_events {
 			match try_from_tx(&event) {
@@ -317,6 +360,7 @@ mod test {
 					IbcEvent::OpenTryConnection(e) => assert_eq!(e.0, open_try.0),
 					IbcEvent::OpenAckConnection(e) => assert_eq!(e.0, open_ack.0),
 					IbcEvent::OpenConfirmConnection(e) => assert_eq!(e.0, open_confirm.0),
+					IbcEvent::ConnectionCancelled(e) => assert_eq!(e.0, cancelled.0),
 					_ => panic!("unexpected event type"),
 				},
 				None => panic!("converted event was wrong"),
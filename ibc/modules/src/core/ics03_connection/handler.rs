@@ -21,9 +21,12 @@ use crate::{
 		ics26_routing::context::ReaderContext,
 	},
 	handler::HandlerOutput,
+	signer::Signer,
+	Height,
 };
 use core::fmt::Debug;
 
+pub mod conn_cancel;
 pub mod conn_open_ack;
 pub mod conn_open_confirm;
 pub mod conn_open_init;
@@ -40,6 +43,18 @@ pub enum ConnectionIdState {
 
 	/// Specifies that the handler reused a previously-allocated connection identifier.
 	Reused,
+
+	/// Specifies that the handler discarded the connection identifier. This happens when a
+	/// handshake is aborted through `MsgConnectionCancel`.
+	Removed,
+}
+
+/// The account that started a connection handshake on the local chain, together with the host
+/// height at which it did so.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HandshakeOrigin {
+	pub signer: Signer,
+	pub height: Height,
 }
 
 #[derive(Clone, Debug)]
@@ -54,6 +69,10 @@ pub struct ConnectionResult {
 
 	/// The connection end, which the handler produced as a result of processing the message.
 	pub connection_end: ConnectionEnd,
+
+	/// Who started the handshake and when. Only set when the handler allocated a new connection
+	/// identifier.
+	pub handshake_origin: Option<HandshakeOrigin>,
 }
 
 /// General entry point for processing any type of message related to the ICS3 connection open
@@ -67,5 +86,6 @@ pub fn dispatch<Ctx: ReaderContext>(
 		ConnectionMsg::ConnectionOpenTry(msg) => conn_open_try::process::<_>(ctx, *msg),
 		ConnectionMsg::ConnectionOpenAck(msg) => conn_open_ack::process::<_>(ctx, *msg),
 		ConnectionMsg::ConnectionOpenConfirm(msg) => conn_open_confirm::process::<_>(ctx, msg),
+		ConnectionMsg::ConnectionCancel(msg) => conn_cancel::process::<_>(ctx, msg),
 	}
 }
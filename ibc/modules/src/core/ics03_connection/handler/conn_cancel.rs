@@ -0,0 +1,188 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to processing ICS3 messages of type `MsgConnectionCancel`.
+
+use crate::{
+	core::{
+		ics03_connection::{
+			connection::State,
+			error::Error,
+			events::Attributes,
+			handler::{ConnectionIdState, ConnectionResult},
+			msgs::conn_cancel::MsgConnectionCancel,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: MsgConnectionCancel,
+) -> HandlerResult<ConnectionResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let conn_end = ctx.connection_end(&msg.connection_id)?;
+	// Only a handshake that has not progressed past TryOpen can be aborted.
+	if !(conn_end.state_matches(&State::Init) || conn_end.state_matches(&State::TryOpen)) {
+		return Err(Error::connection_not_cancellable(msg.connection_id, *conn_end.state()))
+	}
+
+	// Until the handshake times out, only the account that started it may cancel it. Hosts bind
+	// message signers to the account submitting them, see `Ics26Envelope::signers`.
+	let origin = ctx.connection_handshake_origin(&msg.connection_id)?;
+	if origin.signer != msg.signer {
+		let elapsed =
+			ctx.host_height().revision_height.saturating_sub(origin.height.revision_height);
+		let timeout = ctx.handshake_timeout();
+		if elapsed < timeout {
+			return Err(Error::handshake_not_timed_out(msg.connection_id, elapsed, timeout))
+		}
+	}
+
+	output.log("success: connection handshake cancelled");
+
+	let event_attributes = Attributes {
+		connection_id: Some(msg.connection_id.clone()),
+		height: ctx.host_height(),
+		client_id: conn_end.client_id().clone(),
+		counterparty_connection_id: conn_end.counterparty().connection_id.clone(),
+		counterparty_client_id: conn_end.counterparty().client_id().clone(),
+	};
+
+	let result = ConnectionResult {
+		connection_id: msg.connection_id,
+		connection_id_state: ConnectionIdState::Removed,
+		connection_end: conn_end,
+		handshake_origin: None,
+	};
+
+	output.emit(IbcEvent::ConnectionCancelled(event_attributes.into()));
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use core::str::FromStr;
+	use test_log::test;
+
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics03_connection::{
+				connection::{ConnectionEnd, Counterparty, State},
+				context::{ConnectionKeeper, ConnectionReader},
+				error,
+				handler::{dispatch, ConnectionIdState, HandshakeOrigin},
+				msgs::{
+					conn_cancel::{test_util::get_dummy_raw_msg_conn_cancel, MsgConnectionCancel},
+					ConnectionMsg,
+				},
+				version::get_compatible_versions,
+			},
+			ics23_commitment::commitment::CommitmentPrefix,
+			ics24_host::identifier::ClientId,
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		timestamp::ZERO_DURATION,
+	};
+
+	fn context_with_connection(
+		msg: &MsgConnectionCancel,
+		state: State,
+		initiator: &str,
+	) -> MockContext<MockClientTypes> {
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+		let conn_end = ConnectionEnd::new(
+			state,
+			client_id.clone(),
+			Counterparty::new(
+				client_id,
+				None,
+				CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_connection(msg.connection_id.clone(), conn_end)
+			.with_handshake_timeout(2);
+		let origin =
+			HandshakeOrigin { signer: initiator.parse().unwrap(), height: ctx.host_height() };
+		ctx.store_connection_handshake_origin(msg.connection_id.clone(), origin).unwrap();
+		ctx
+	}
+
+	#[test]
+	fn conn_cancel_by_initiator_before_timeout() {
+		let msg = MsgConnectionCancel::try_from(get_dummy_raw_msg_conn_cancel()).unwrap();
+		let mut ctx = context_with_connection(&msg, State::Init, msg.signer.as_ref());
+
+		let output = dispatch(&ctx, ConnectionMsg::ConnectionCancel(msg.clone())).unwrap();
+		assert!(matches!(output.result.connection_id_state, ConnectionIdState::Removed));
+		assert!(matches!(output.events[0], IbcEvent::ConnectionCancelled(_)));
+
+		ctx.store_connection_result(output.result).unwrap();
+		assert!(ctx.connection_end(&msg.connection_id).is_err());
+	}
+
+	#[test]
+	fn conn_cancel_by_other_account_respects_timeout() {
+		let msg = MsgConnectionCancel::try_from(get_dummy_raw_msg_conn_cancel()).unwrap();
+		let mut ctx = context_with_connection(&msg, State::TryOpen, "someoneelse");
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionCancel(msg.clone()));
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::HandshakeNotTimedOut(e) => {
+					assert_eq!(e.connection_id, msg.connection_id);
+					assert_eq!(e.timeout, 2);
+				},
+				_ => panic!("Expected HandshakeNotTimedOut error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected HandshakeNotTimedOut error"),
+		}
+
+		ctx.advance_host_chain_height();
+		ctx.advance_host_chain_height();
+
+		let output = dispatch(&ctx, ConnectionMsg::ConnectionCancel(msg)).unwrap();
+		assert!(matches!(output.result.connection_id_state, ConnectionIdState::Removed));
+	}
+
+	#[test]
+	fn conn_cancel_rejects_open_connection() {
+		let msg = MsgConnectionCancel::try_from(get_dummy_raw_msg_conn_cancel()).unwrap();
+		let ctx = context_with_connection(&msg, State::Open, msg.signer.as_ref());
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionCancel(msg.clone()));
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::ConnectionNotCancellable(e) => {
+					assert_eq!(e.connection_id, msg.connection_id);
+					assert_eq!(e.state, State::Open);
+				},
+				_ => panic!("Expected ConnectionNotCancellable error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ConnectionNotCancellable error"),
+		}
+	}
+}
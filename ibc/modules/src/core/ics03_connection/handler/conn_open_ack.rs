@@ -21,10 +21,7 @@ use crate::{
 			error::Error,
 			events::Attributes,
 			handler::{
-				verify::{
-					check_client_consensus_height, verify_client_proof, verify_connection_proof,
-					verify_consensus_proof,
-				},
+				verify::{check_client_consensus_height, verify_handshake_proofs},
 				ConnectionIdState, ConnectionResult,
 			},
 			msgs::conn_open_ack::MsgConnectionOpenAck,
@@ -60,6 +57,12 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		return Err(Error::connection_mismatch(msg.connection_id))
 	}
 
+	// The version chosen by the counterparty must be one this host supports.
+	let version = ctx.pick_version(ctx.supported_versions(), vec![msg.version.clone()])?;
+	if version != msg.version {
+		return Err(Error::version_not_supported(msg.version))
+	}
+
 	// Set the connection ID of the counterparty
 	let prev_counterparty = conn_end.counterparty();
 	let counterparty = Counterparty::new(
@@ -90,41 +93,43 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		)
 	};
 
-	let client_state = msg.client_state.ok_or_else(|| {
-		Error::implementation_specific("client state is required in connOpenTry".into())
-	})?;
+	// Hosts that trust self-client validation alone may accept the handshake without client and
+	// consensus state proofs.
+	let (client_proof, consensus_proof) = if ctx.require_proofs_in_handshake() {
+		let client_state = msg.client_state.ok_or_else(|| {
+			Error::implementation_specific("client state is required in connOpenTry".into())
+		})?;
 
-	let client_proof = msg.proofs.client_proof().as_ref().ok_or_else(|| {
-		Error::implementation_specific("client proof is required in connOpenTry".into())
-	})?;
+		let client_proof = msg.proofs.client_proof().as_ref().ok_or_else(|| {
+			Error::implementation_specific("client proof is required in connOpenTry".into())
+		})?;
 
-	let consensus_proof = msg.proofs.consensus_proof().ok_or_else(|| {
-		Error::implementation_specific("consensus proof is required in connOpenTry".into())
-	})?;
+		let consensus_proof = msg.proofs.consensus_proof().ok_or_else(|| {
+			Error::implementation_specific("consensus proof is required in connOpenTry".into())
+		})?;
 
-	ctx.validate_self_client(&client_state).map_err(Error::ics02_client)?;
+		ctx.validate_self_client(&client_state).map_err(Error::ics02_client)?;
 
-	verify_connection_proof::<Ctx>(
-		ctx,
-		msg.proofs.height(),
-		&conn_end,
-		&expected_conn,
-		msg.proofs.height(),
-		msg.proofs.object_proof(),
-	)?;
+		(Some((client_state, client_proof)), Some(consensus_proof))
+	} else {
+		if let Some(client_state) = &msg.client_state {
+			ctx.validate_self_client(client_state).map_err(Error::ics02_client)?;
+		}
+		(None, None)
+	};
 
-	verify_client_proof::<Ctx>(
+	verify_handshake_proofs::<Ctx>(
 		ctx,
-		msg.proofs.height(),
 		&conn_end,
-		client_state,
-		msg.proofs.height(),
+		&expected_conn,
+		&msg.proofs,
 		client_proof,
+		consensus_proof.as_ref(),
 	)?;
 
-	verify_consensus_proof::<Ctx>(ctx, msg.proofs.height(), &conn_end, &consensus_proof)?;
-
 	output.log("success: connection verification passed");
+	output.log_kv("connection_id", &msg.connection_id);
+	output.log_kv("counterparty_connection_id", &msg.counterparty_connection_id);
 
 	let event_attributes = Attributes {
 		connection_id: Some(msg.connection_id.clone()),
@@ -138,6 +143,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		connection_id: msg.connection_id,
 		connection_id_state: ConnectionIdState::Reused,
 		connection_end: conn_end,
+		handshake_origin: None,
 	};
 
 	output.emit(IbcEvent::OpenAckConnection(event_attributes.into()));
@@ -149,16 +155,23 @@ pub(crate) fn process<Ctx: ReaderContext>(
 mod tests {
 	use crate::prelude::*;
 
-	use core::str::FromStr;
+	use core::{str::FromStr, time::Duration};
 	use test_log::test;
 
 	use crate::{
 		core::{
-			ics02_client::context::ClientReader,
+			ics02_client::{
+				context::{ClientKeeper, ClientReader},
+				error as ics02_error,
+			},
 			ics03_connection::{
-				connection::{ConnectionEnd, Counterparty, State},
+				connection::{Counterparty, State},
+				context::{ConnectionKeeper, ConnectionReader},
 				error,
-				handler::{dispatch, ConnectionResult},
+				handler::{
+					dispatch, verify::ConsensusProofwithHostConsensusStateProof, ConnectionResult,
+					HandshakeOrigin,
+				},
 				msgs::{
 					conn_open_ack::{
 						test_util::get_dummy_raw_msg_conn_open_ack, MsgConnectionOpenAck,
@@ -166,16 +179,62 @@ mod tests {
 					ConnectionMsg,
 				},
 			},
-			ics23_commitment::commitment::CommitmentPrefix,
+			ics03_connection::version::Version,
+			ics04_channel::channel::Order,
+			ics23_commitment::commitment::{CommitmentProofBytes, CommitmentRoot},
 			ics24_host::identifier::{ChainId, ClientId},
 		},
 		events::IbcEvent,
 		mock::{
+			builder::MockContextBuilder,
+			client_state::{AnyConsensusState, MockConsensusState, MOCK_TRUSTING_PERIOD},
 			context::{MockClientTypes, MockContext},
-			host::MockHostType,
+			header::MockHeader,
+			host::{HostBlock, MockHostType},
 		},
-		timestamp::ZERO_DURATION,
+		proofs::{ConsensusProof, Proofs},
+		test_utils::get_dummy_proof,
 	};
+	use ibc_proto::ibc::core::connection::v1::Version as RawVersion;
+
+	/// A context holding, as `ConnOpenInit` left it, the connection that `msg` acknowledges, over
+	/// the client `client_id` tracking the counterparty at the proof height of `msg`. The message
+	/// is made to carry the identifier of the mirror connection on the counterparty.
+	fn init_connection_context(
+		client_id: &ClientId,
+		msg: &mut MsgConnectionOpenAck<MockContext<MockClientTypes>>,
+	) -> MockContext<MockClientTypes> {
+		let proof_height = msg.proofs.height();
+		let latest_height = proof_height.increment();
+		let ctx = MockContext::new(
+			ChainId::new("mockgaia".to_string(), latest_height.revision_number),
+			MockHostType::Mock,
+			5,
+			latest_height,
+		);
+		let counterparty = MockContext::new(
+			ChainId::new("mockcounterparty".to_string(), proof_height.revision_number),
+			MockHostType::Mock,
+			5,
+			proof_height,
+		);
+		let ctx = MockContextBuilder::new(ctx)
+			.with_counterparty(counterparty)
+			.with_open_connection(client_id.clone(), msg.connection_id.clone())
+			.build();
+
+		let mut conn_end = ctx.connection_end(&msg.connection_id).unwrap();
+		let counterparty = conn_end.counterparty().clone();
+		msg.counterparty_connection_id = counterparty.connection_id.clone().unwrap();
+		// The counterparty connection is not known before the Ack.
+		conn_end.set_state(State::Init);
+		conn_end.set_counterparty(Counterparty::new(
+			counterparty.client_id().clone(),
+			None,
+			counterparty.prefix().clone(),
+		));
+		ctx.with_connection(msg.connection_id.clone(), conn_end)
+	}
 
 	#[test]
 	#[ignore]
@@ -188,50 +247,32 @@ mod tests {
 			match_error: Box<dyn FnOnce(error::Error)>,
 		}
 
-		let msg_ack =
+		let mut msg_ack =
 			MsgConnectionOpenAck::try_from(get_dummy_raw_msg_conn_open_ack(10, 10)).unwrap();
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+
+		// A context that will exercise the successful path.
+		let context = init_connection_context(&client_id, &mut msg_ack);
 		let conn_id = msg_ack.connection_id.clone();
 		let counterparty_conn_id = msg_ack.counterparty_connection_id.clone();
 
-		// Client parameters -- identifier and correct height (matching the proof height)
-		let client_id = ClientId::from_str("mock_clientid").unwrap();
-		let proof_height = msg_ack.proofs.height();
-
-		// Parametrize the host chain to have a height at least as recent as the
-		// the height of the proofs in the Ack msg.
-		let latest_height = proof_height.increment();
-		let max_history_size = 5;
-		let default_context = MockContext::new(
+		// The same context, without the connection.
+		let latest_height = context.latest_height();
+		let empty_context = MockContext::new(
 			ChainId::new("mockgaia".to_string(), latest_height.revision_number),
 			MockHostType::Mock,
-			max_history_size,
+			5,
 			latest_height,
 		);
 
-		// A connection end that will exercise the successful path.
-		let default_conn_end = ConnectionEnd::new(
-			State::Init,
-			client_id.clone(),
-			Counterparty::new(
-				client_id.clone(),
-				Some(msg_ack.counterparty_connection_id.clone()),
-				CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
-			),
-			vec![msg_ack.version.clone()],
-			ZERO_DURATION,
-		);
-
 		// A connection end with incorrect state `Open`; will be part of the context.
-		let mut conn_end_open = default_conn_end.clone();
+		let mut conn_end_open = context.connection_end(&conn_id).unwrap();
 		conn_end_open.set_state(State::Open); // incorrect field
 
 		let tests: Vec<Test> = vec![
 			Test {
 				name: "Successful processing of an Ack message".to_string(),
-				ctx: default_context
-					.clone()
-					.with_client(&client_id, proof_height)
-					.with_connection(conn_id.clone(), default_conn_end),
+				ctx: context.clone(),
 				msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone())),
 				want_pass: true,
 				match_error: Box::new(|_| panic!("should not have error")),
@@ -239,7 +280,7 @@ mod tests {
 			Test {
 				name: "Processing fails because the connection does not exist in the context"
 					.to_string(),
-				ctx: default_context.clone(),
+				ctx: empty_context,
 				msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone())),
 				want_pass: false,
 				match_error: {
@@ -257,9 +298,7 @@ mod tests {
 			Test {
 				name: "Processing fails due to connections mismatch (incorrect 'open' state)"
 					.to_string(),
-				ctx: default_context
-					.with_client(&client_id, proof_height)
-					.with_connection(conn_id.clone(), conn_end_open),
+				ctx: context.with_connection(conn_id.clone(), conn_end_open),
 				msg: ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack)),
 				want_pass: false,
 				match_error: {
@@ -333,4 +372,171 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn conn_open_ack_rejects_version_not_supported_by_host() {
+		let mut msg_ack =
+			MsgConnectionOpenAck::try_from(get_dummy_raw_msg_conn_open_ack(10, 10)).unwrap();
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+
+		// The counterparty picked the default version, which is in the connection end, but this
+		// host only supports unordered channels.
+		let unordered_only = Version::try_from(RawVersion {
+			identifier: "1".to_string(),
+			features: vec![Order::Unordered.as_str().to_owned()],
+		})
+		.unwrap();
+		let ctx = init_connection_context(&client_id, &mut msg_ack)
+			.with_supported_versions(vec![unordered_only]);
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone())));
+
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::VersionNotSupported(e) => {
+					assert_eq!(e.version, msg_ack.version)
+				},
+				_ => panic!("Expected VersionNotSupported error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected VersionNotSupported error"),
+		}
+	}
+
+	#[test]
+	fn conn_open_ack_without_client_and_consensus_proofs() {
+		let mut msg_ack =
+			MsgConnectionOpenAck::try_from(get_dummy_raw_msg_conn_open_ack(10, 10)).unwrap();
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+		let proof_height = msg_ack.proofs.height();
+
+		// Only the connection proof is provided.
+		msg_ack.client_state = None;
+		msg_ack.proofs =
+			Proofs::new(msg_ack.proofs.object_proof().clone(), None, None, None, proof_height)
+				.unwrap();
+
+		let ctx = init_connection_context(&client_id, &mut msg_ack)
+			.with_handshake_proofs_required(false);
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack)));
+
+		match res {
+			Ok(output) => assert_eq!(output.result.connection_end.state(), &State::Open),
+			Err(e) => panic!("unexpected error: {}", e),
+		}
+	}
+
+	#[test]
+	fn conn_open_ack_forgets_handshake_origin() {
+		let mut msg_ack =
+			MsgConnectionOpenAck::try_from(get_dummy_raw_msg_conn_open_ack(10, 10)).unwrap();
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+		let mut ctx = init_connection_context(&client_id, &mut msg_ack);
+		let origin = HandshakeOrigin { signer: msg_ack.signer.clone(), height: ctx.host_height() };
+		ctx.store_connection_handshake_origin(msg_ack.connection_id.clone(), origin).unwrap();
+
+		let output =
+			dispatch(&ctx, ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone()))).unwrap();
+		ctx.store_connection_result(output.result).unwrap();
+
+		assert_eq!(ctx.connection_end(&msg_ack.connection_id).unwrap().state(), &State::Open);
+		assert!(ctx.connection_handshake_origin(&msg_ack.connection_id).is_err());
+	}
+
+	#[test]
+	fn conn_open_ack_rejects_expired_client() {
+		let mut msg_ack =
+			MsgConnectionOpenAck::try_from(get_dummy_raw_msg_conn_open_ack(10, 10)).unwrap();
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+		let proof_height = msg_ack.proofs.height();
+
+		msg_ack.client_state = None;
+		msg_ack.proofs =
+			Proofs::new(msg_ack.proofs.object_proof().clone(), None, None, None, proof_height)
+				.unwrap();
+
+		let mut ctx = init_connection_context(&client_id, &mut msg_ack)
+			.with_handshake_proofs_required(false);
+		ctx.store_update_time(client_id.clone(), proof_height, ctx.host_timestamp()).unwrap();
+		// Move the host clock past the trusting period of the mock client.
+		ctx.block_time = MOCK_TRUSTING_PERIOD + Duration::from_secs(1);
+		ctx.advance_host_chain_height();
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack)));
+
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::Ics02Client(e) => match &e.source {
+					ics02_error::ErrorDetail::ClientExpired(e) => {
+						assert_eq!(e.client_id, client_id);
+						assert_eq!(e.trusting_period, MOCK_TRUSTING_PERIOD);
+					},
+					_ => panic!("Expected ClientExpired error, instead got {:?}", e),
+				},
+				_ => panic!("Expected ClientExpired error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ClientExpired error"),
+		}
+	}
+
+	#[test]
+	fn conn_open_ack_rejects_mismatched_self_consensus_state() {
+		let mut msg_ack =
+			MsgConnectionOpenAck::try_from(get_dummy_raw_msg_conn_open_ack(10, 10)).unwrap();
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+		let proof_height = msg_ack.proofs.height();
+		let consensus_height = msg_ack.proofs.consensus_proof().unwrap().height();
+
+		// Non-tendermint hosts receive the consensus proof bundled with a host consensus state
+		// proof.
+		let consensus_proof = codec::Encode::encode(&ConsensusProofwithHostConsensusStateProof {
+			host_consensus_state_proof: get_dummy_proof(),
+			consensus_proof: get_dummy_proof(),
+		});
+		msg_ack.proofs = Proofs::new(
+			msg_ack.proofs.object_proof().clone(),
+			msg_ack.proofs.client_proof().clone(),
+			Some(
+				ConsensusProof::new(
+					CommitmentProofBytes::try_from(consensus_proof).unwrap(),
+					consensus_height,
+				)
+				.unwrap(),
+			),
+			None,
+			proof_height,
+		)
+		.unwrap();
+
+		let ctx = init_connection_context(&client_id, &mut msg_ack);
+
+		// The proven consensus state matches the history of the host.
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack.clone())));
+		assert!(res.is_ok(), "unexpected error: {:?}", res.err());
+
+		// The counterparty holds a consensus state for this chain with a different root.
+		let forged = MockConsensusState {
+			header: MockHeader {
+				height: consensus_height,
+				timestamp: ctx.host_block(consensus_height).unwrap().timestamp(),
+			},
+			root: CommitmentRoot::from(vec![1]),
+		};
+		let ctx = ctx.with_claimed_host_consensus_state(
+			consensus_height,
+			AnyConsensusState::Mock(forged),
+		);
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenAck(Box::new(msg_ack)));
+
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::InvalidSelfConsensusState(e) => {
+					assert_eq!(e.height, consensus_height)
+				},
+				_ => panic!("Expected InvalidSelfConsensusState error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected InvalidSelfConsensusState error"),
+		}
+	}
 }
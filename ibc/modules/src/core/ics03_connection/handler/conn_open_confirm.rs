@@ -85,6 +85,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		connection_id: msg.connection_id,
 		connection_id_state: ConnectionIdState::Reused,
 		connection_end: conn_end,
+		handshake_origin: None,
 	};
 
 	output.emit(IbcEvent::OpenConfirmConnection(event_attributes.into()));
@@ -104,8 +105,8 @@ mod tests {
 			ics02_client::context::ClientReader,
 			ics03_connection::{
 				connection::{ConnectionEnd, Counterparty, State},
-				context::ConnectionReader,
-				handler::{dispatch, ConnectionResult},
+				context::{ConnectionKeeper, ConnectionReader},
+				handler::{dispatch, ConnectionResult, HandshakeOrigin},
 				msgs::{
 					conn_open_confirm::{
 						test_util::get_dummy_raw_msg_conn_open_confirm, MsgConnectionOpenConfirm,
@@ -218,4 +219,37 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn conn_open_confirm_forgets_handshake_origin() {
+		let client_id = ClientId::from_str("mock_clientid").unwrap();
+		let msg_confirm =
+			MsgConnectionOpenConfirm::try_from(get_dummy_raw_msg_conn_open_confirm()).unwrap();
+		let counterparty = Counterparty::new(
+			client_id.clone(),
+			Some(msg_confirm.connection_id.clone()),
+			CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap(),
+		);
+		let context = MockContext::default();
+		let conn_end = ConnectionEnd::new(
+			State::TryOpen,
+			client_id.clone(),
+			counterparty,
+			context.get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let mut ctx = context
+			.with_client(&client_id, Height::new(0, 10))
+			.with_connection(msg_confirm.connection_id.clone(), conn_end);
+		let origin =
+			HandshakeOrigin { signer: msg_confirm.signer.clone(), height: ctx.host_height() };
+		ctx.store_connection_handshake_origin(msg_confirm.connection_id.clone(), origin).unwrap();
+
+		let output =
+			dispatch(&ctx, ConnectionMsg::ConnectionOpenConfirm(msg_confirm.clone())).unwrap();
+		ctx.store_connection_result(output.result).unwrap();
+
+		assert_eq!(ctx.connection_end(&msg_confirm.connection_id).unwrap().state(), &State::Open);
+		assert!(ctx.connection_handshake_origin(&msg_confirm.connection_id).is_err());
+	}
 }
@@ -16,11 +16,12 @@
 
 use crate::{
 	core::{
+		ics02_client::client_state::ClientState,
 		ics03_connection::{
 			connection::{ConnectionEnd, State},
 			error::Error,
 			events::Attributes,
-			handler::{ConnectionIdState, ConnectionResult},
+			handler::{ConnectionIdState, ConnectionResult, HandshakeOrigin},
 			msgs::conn_open_init::MsgConnectionOpenInit,
 		},
 		ics24_host::identifier::ConnectionId,
@@ -46,17 +47,20 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		)))?
 	}
 
-	// An IBC client running on the local (host) chain should exist.
-	ctx.client_state(&msg.client_id).map_err(Error::ics02_client)?;
+	// An IBC client running on the local (host) chain should exist and not be frozen.
+	let client_state = ctx.client_state(&msg.client_id).map_err(Error::ics02_client)?;
+	if client_state.is_frozen() {
+		return Err(Error::frozen_client(msg.client_id.clone()))
+	}
 
 	let versions = match msg.version {
 		Some(version) =>
-			if ctx.get_compatible_versions().contains(&version) {
+			if ctx.supported_versions().contains(&version) {
 				Ok(vec![version])
 			} else {
 				Err(Error::version_not_supported(version))
 			},
-		None => Ok(ctx.get_compatible_versions()),
+		None => Ok(ctx.supported_versions()),
 	}?;
 
 	let new_connection_end = ConnectionEnd::new(
@@ -85,6 +89,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		connection_id: conn_id,
 		connection_id_state: ConnectionIdState::Generated,
 		connection_end: new_connection_end,
+		handshake_origin: Some(HandshakeOrigin { signer: msg.signer, height: ctx.host_height() }),
 	};
 
 	output.emit(IbcEvent::OpenInitConnection(event_attributes.into()));
@@ -101,7 +106,7 @@ mod tests {
 			ics02_client::context::ClientReader,
 			ics03_connection::{
 				connection::State,
-				context::ConnectionReader,
+				context::{ConnectionKeeper, ConnectionReader},
 				handler::{dispatch, ConnectionResult},
 				msgs::{
 					conn_open_init::{
@@ -167,7 +172,7 @@ mod tests {
 				name: "No version in MsgConnectionOpenInit msg".to_string(),
 				ctx: good_context.clone(),
 				msg: ConnectionMsg::ConnectionOpenInit(msg_conn_init_no_version),
-				expected_versions: good_context.get_compatible_versions(),
+				expected_versions: good_context.supported_versions(),
 				want_pass: true,
 			},
 			Test {
@@ -221,4 +226,21 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn conn_open_init_indexes_connections_by_client() {
+		let msg = MsgConnectionOpenInit::try_from(get_dummy_raw_msg_conn_open_init()).unwrap();
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&msg.client_id, Height::new(0, 10));
+
+		let mut expected = vec![];
+		for _ in 0..2 {
+			let output = dispatch(&ctx, ConnectionMsg::ConnectionOpenInit(msg.clone())).unwrap();
+			expected.push(output.result.connection_id.clone());
+			ctx.store_connection_result(output.result).unwrap();
+		}
+
+		assert_ne!(expected[0], expected[1]);
+		assert_eq!(ctx.client_connections(&msg.client_id).unwrap(), expected);
+	}
 }
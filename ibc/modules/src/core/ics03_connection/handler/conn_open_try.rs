@@ -21,11 +21,8 @@ use crate::{
 			error::Error,
 			events::Attributes,
 			handler::{
-				verify::{
-					check_client_consensus_height, verify_client_proof, verify_connection_proof,
-					verify_consensus_proof,
-				},
-				ConnectionIdState, ConnectionResult,
+				verify::{check_client_consensus_height, verify_handshake_proofs},
+				ConnectionIdState, ConnectionResult, HandshakeOrigin,
 			},
 			msgs::conn_open_try::MsgConnectionOpenTry,
 		},
@@ -51,6 +48,17 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		)))?
 	}
 
+	// The counterparty prefix is used to verify every subsequent proof, so it must be usable.
+	let counterparty_prefix = msg.counterparty.prefix();
+	if counterparty_prefix.as_bytes().is_empty() {
+		return Err(Error::missing_counterparty_prefix())
+	}
+	if let Some(expected) = ctx.expected_counterparty_prefix(&msg.client_id) {
+		if &expected != counterparty_prefix {
+			return Err(Error::counterparty_prefix_mismatch(expected, counterparty_prefix.clone()))
+		}
+	}
+
 	// Check that consensus height if provided (for client proof) in message is not too advanced nor
 	// too old.
 	if msg.proofs.consensus_proof().is_some() {
@@ -74,6 +82,9 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		(conn_end, conn_id)
 	};
 
+	// Pick a version out of the counterparty's candidates among the versions this host supports.
+	let version = ctx.pick_version(ctx.supported_versions(), msg.counterparty_versions.clone())?;
+
 	// Proof verification in two steps:
 	// 1. Setup: build the ConnectionEnd as we expect to find it on the other party.
 	let expected_conn = ConnectionEnd::new(
@@ -84,47 +95,45 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		msg.delay_period,
 	);
 
-	let client_state = msg.client_state.ok_or_else(|| {
-		Error::implementation_specific("client state is required in connOpenTry".into())
-	})?;
+	// Hosts that trust self-client validation alone may accept the handshake without client and
+	// consensus state proofs.
+	let (client_proof, consensus_proof) = if ctx.require_proofs_in_handshake() {
+		let client_state = msg.client_state.ok_or_else(|| {
+			Error::implementation_specific("client state is required in connOpenTry".into())
+		})?;
 
-	let client_proof = msg.proofs.client_proof().as_ref().ok_or_else(|| {
-		Error::implementation_specific("client proof is required in connOpenTry".into())
-	})?;
+		let client_proof = msg.proofs.client_proof().as_ref().ok_or_else(|| {
+			Error::implementation_specific("client proof is required in connOpenTry".into())
+		})?;
 
-	let consensus_proof = msg.proofs.consensus_proof().ok_or_else(|| {
-		Error::implementation_specific("consensus proof is required in connOpenTry".into())
-	})?;
+		let consensus_proof = msg.proofs.consensus_proof().ok_or_else(|| {
+			Error::implementation_specific("consensus proof is required in connOpenTry".into())
+		})?;
 
-	ctx.validate_self_client(&client_state).map_err(Error::ics02_client)?;
+		ctx.validate_self_client(&client_state).map_err(Error::ics02_client)?;
 
-	verify_connection_proof::<_>(
-		ctx,
-		msg.proofs.height(),
-		&new_connection_end,
-		&expected_conn,
-		msg.proofs.height(),
-		msg.proofs.object_proof(),
-	)?;
+		(Some((client_state, client_proof)), Some(consensus_proof))
+	} else {
+		if let Some(client_state) = &msg.client_state {
+			ctx.validate_self_client(client_state).map_err(Error::ics02_client)?;
+		}
+		(None, None)
+	};
 
-	verify_client_proof::<_>(
+	verify_handshake_proofs::<_>(
 		ctx,
-		msg.proofs.height(),
 		&new_connection_end,
-		client_state,
-		msg.proofs.height(),
+		&expected_conn,
+		&msg.proofs,
 		client_proof,
+		consensus_proof.as_ref(),
 	)?;
 
-	verify_consensus_proof::<_>(ctx, msg.proofs.height(), &new_connection_end, &consensus_proof)?;
-
-	// Transition the connection end to the new state & pick a version.
+	// Transition the connection end to the new state.
 	new_connection_end.set_state(State::TryOpen);
 
-	// Pick the version.
-	new_connection_end.set_version(
-		ctx.pick_version(ctx.get_compatible_versions(), msg.counterparty_versions.clone())?,
-	);
+	// Set the version picked above.
+	new_connection_end.set_version(version);
 
 	assert_eq!(new_connection_end.versions().len(), 1);
 
@@ -142,6 +151,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		connection_id: conn_id,
 		connection_id_state: ConnectionIdState::Generated,
 		connection_end: new_connection_end,
+		handshake_origin: Some(HandshakeOrigin { signer: msg.signer, height: ctx.host_height() }),
 	};
 
 	output.emit(IbcEvent::OpenTryConnection(event_attributes.into()));
@@ -159,7 +169,8 @@ mod tests {
 		core::{
 			ics02_client::context::ClientReader,
 			ics03_connection::{
-				connection::State,
+				connection::{Counterparty, State},
+				error,
 				handler::{dispatch, ConnectionResult},
 				msgs::{
 					conn_open_try::{
@@ -167,7 +178,10 @@ mod tests {
 					},
 					ConnectionMsg,
 				},
+				version::{get_compatible_versions, Version},
 			},
+			ics04_channel::channel::Order,
+			ics23_commitment::commitment::CommitmentPrefix,
 			ics24_host::identifier::ChainId,
 		},
 		events::IbcEvent,
@@ -177,6 +191,7 @@ mod tests {
 		},
 		Height,
 	};
+	use ibc_proto::ibc::core::connection::v1::Version as RawVersion;
 
 	#[test]
 	fn conn_open_try_msg_processing() {
@@ -293,4 +308,135 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn conn_open_try_picks_host_supported_version() {
+		let unordered_only = Version::try_from(RawVersion {
+			identifier: "1".to_string(),
+			features: vec![Order::Unordered.as_str().to_owned()],
+		})
+		.unwrap();
+		let host_chain_height = Height::new(0, 35);
+		let client_consensus_state_height = 10;
+		let mut msg_conn_try = MsgConnectionOpenTry::try_from(get_dummy_raw_msg_conn_open_try(
+			client_consensus_state_height,
+			host_chain_height.revision_height,
+		))
+		.unwrap();
+		msg_conn_try.counterparty_versions =
+			get_compatible_versions().into_iter().chain([unordered_only.clone()]).collect();
+		let ctx = MockContext::<MockClientTypes>::new(
+			ChainId::new("mockgaia".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			host_chain_height,
+		)
+		.with_client(&msg_conn_try.client_id, Height::new(0, client_consensus_state_height))
+		.with_supported_versions(vec![unordered_only.clone()]);
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenTry(Box::new(msg_conn_try)))
+			.expect("conn_open_try should agree on the host's version");
+		let res: ConnectionResult = res.result;
+		assert_eq!(res.connection_end.versions(), &[unordered_only]);
+	}
+
+	#[test]
+	fn conn_open_try_fails_without_common_version() {
+		let host_chain_height = Height::new(0, 35);
+		let client_consensus_state_height = 10;
+		let unknown_version = Version::try_from(RawVersion {
+			identifier: "2".to_string(),
+			features: vec![Order::Unordered.as_str().to_owned()],
+		})
+		.unwrap();
+		let msg_conn_try = MsgConnectionOpenTry::try_from(get_dummy_raw_msg_conn_open_try(
+			client_consensus_state_height,
+			host_chain_height.revision_height,
+		))
+		.unwrap();
+		let ctx = MockContext::<MockClientTypes>::new(
+			ChainId::new("mockgaia".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			host_chain_height,
+		)
+		.with_client(&msg_conn_try.client_id, Height::new(0, client_consensus_state_height))
+		.with_supported_versions(vec![unknown_version]);
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenTry(Box::new(msg_conn_try)));
+
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::NoCommonVersion(_) => {},
+				_ => panic!("Expected NoCommonVersion error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected NoCommonVersion error"),
+		}
+	}
+
+	#[test]
+	fn conn_open_try_rejects_unexpected_counterparty_prefix() {
+		let host_chain_height = Height::new(0, 35);
+		let client_consensus_state_height = 10;
+		let msg_conn_try = MsgConnectionOpenTry::try_from(get_dummy_raw_msg_conn_open_try(
+			client_consensus_state_height,
+			host_chain_height.revision_height,
+		))
+		.unwrap();
+		let expected_prefix = CommitmentPrefix::try_from(b"expected".to_vec()).unwrap();
+		let ctx = MockContext::<MockClientTypes>::new(
+			ChainId::new("mockgaia".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			host_chain_height,
+		)
+		.with_client(&msg_conn_try.client_id, Height::new(0, client_consensus_state_height))
+		.with_expected_counterparty_prefix(msg_conn_try.client_id.clone(), expected_prefix.clone());
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenTry(Box::new(msg_conn_try.clone())));
+
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::CounterpartyPrefixMismatch(e) => {
+					assert_eq!(e.expected, expected_prefix);
+					assert_eq!(&e.actual, msg_conn_try.counterparty.prefix());
+				},
+				_ => panic!("Expected CounterpartyPrefixMismatch error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected CounterpartyPrefixMismatch error"),
+		}
+	}
+
+	#[test]
+	fn conn_open_try_rejects_empty_counterparty_prefix() {
+		let host_chain_height = Height::new(0, 35);
+		let client_consensus_state_height = 10;
+		let mut msg_conn_try = MsgConnectionOpenTry::try_from(get_dummy_raw_msg_conn_open_try(
+			client_consensus_state_height,
+			host_chain_height.revision_height,
+		))
+		.unwrap();
+		msg_conn_try.counterparty = Counterparty::new(
+			msg_conn_try.counterparty.client_id().clone(),
+			msg_conn_try.counterparty.connection_id().cloned(),
+			CommitmentPrefix::default(),
+		);
+		let ctx = MockContext::<MockClientTypes>::new(
+			ChainId::new("mockgaia".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			host_chain_height,
+		)
+		.with_client(&msg_conn_try.client_id, Height::new(0, client_consensus_state_height));
+
+		let res = dispatch(&ctx, ConnectionMsg::ConnectionOpenTry(Box::new(msg_conn_try)));
+
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::MissingCounterpartyPrefix(_) => {},
+				_ => panic!("Expected MissingCounterpartyPrefix error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected MissingCounterpartyPrefix error"),
+		}
+	}
 }
@@ -16,15 +16,16 @@
 
 use crate::core::ics02_client::{
 	client_consensus::ConsensusState, client_def::ClientDef, client_state::ClientState,
+	handler::{ensure_client_not_expired, proof_consensus_state},
 };
 
 use crate::{
 	core::{
 		ics03_connection::{connection::ConnectionEnd, error::Error},
-		ics23_commitment::commitment::CommitmentProofBytes,
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes},
 		ics26_routing::context::ReaderContext,
 	},
-	proofs::ConsensusProof,
+	proofs::{ConsensusProof, Proofs},
 	Height,
 };
 use alloc::{format, vec::Vec};
@@ -45,19 +46,152 @@ pub fn verify_connection_proof<Ctx: ReaderContext>(
 	proof_height: Height,
 	proof: &CommitmentProofBytes,
 ) -> Result<(), Error> {
+	let (client_state, consensus_state) =
+		client_and_consensus_state(ctx, connection_end, proof_height)?;
+	verify_connection_membership(
+		ctx,
+		&client_state,
+		&consensus_state,
+		height,
+		connection_end,
+		expected_conn,
+		proof,
+	)
+}
+
+/// Verifies the client `proof` from a connection handshake message, typically from a
+/// `MsgConnectionOpenTry` or a `MsgConnectionOpenAck`. The `expected_client_state` argument is a
+/// representation for a client of the current chain (the chain handling the current message), which
+/// is running on the counterparty chain (the chain which sent this message). This method does a
+/// complete verification: that the client state the counterparty stores is valid (i.e., not frozen,
+/// at the same revision as the current chain, with matching chain identifiers, etc) and that the
+/// `proof` is correct.
+pub fn verify_client_proof<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	height: Height,
+	connection_end: &ConnectionEnd,
+	expected_client_state: Ctx::AnyClientState,
+	proof_height: Height,
+	proof: &CommitmentProofBytes,
+) -> Result<(), Error> {
+	let (client_state, consensus_state) =
+		client_and_consensus_state(ctx, connection_end, proof_height)?;
+	verify_client_membership(
+		ctx,
+		&client_state,
+		&consensus_state,
+		height,
+		connection_end,
+		&expected_client_state,
+		proof,
+	)
+}
+
+pub fn verify_consensus_proof<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	height: Height,
+	connection_end: &ConnectionEnd,
+	proof: &ConsensusProof,
+) -> Result<(), Error> {
+	let (client_state, consensus_state) = client_and_consensus_state(ctx, connection_end, height)?;
+	verify_consensus_membership(ctx, &client_state, &consensus_state, height, connection_end, proof)
+}
+
+/// Verifies the connection proof of a handshake message together with its optional client and
+/// consensus proofs. Unlike calling `verify_connection_proof`, `verify_client_proof` and
+/// `verify_consensus_proof` in turn, the local client state and consensus state at the proof height
+/// are only read once and shared across all membership checks. Failures surface the same errors as
+/// the individual functions.
+pub fn verify_handshake_proofs<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	connection_end: &ConnectionEnd,
+	expected_conn: &ConnectionEnd,
+	proofs: &Proofs,
+	client_proof: Option<(Ctx::AnyClientState, &CommitmentProofBytes)>,
+	consensus_proof: Option<&ConsensusProof>,
+) -> Result<(), Error> {
+	let height = proofs.height();
+	let (client_state, consensus_state) = client_and_consensus_state(ctx, connection_end, height)?;
+
+	verify_connection_membership(
+		ctx,
+		&client_state,
+		&consensus_state,
+		height,
+		connection_end,
+		expected_conn,
+		proofs.object_proof(),
+	)?;
+
+	if let Some((expected_client_state, proof)) = client_proof {
+		verify_client_membership(
+			ctx,
+			&client_state,
+			&consensus_state,
+			height,
+			connection_end,
+			&expected_client_state,
+			proof,
+		)?;
+	}
+
+	if let Some(proof) = consensus_proof {
+		verify_consensus_membership(
+			ctx,
+			&client_state,
+			&consensus_state,
+			height,
+			connection_end,
+			proof,
+		)?;
+	}
+
+	Ok(())
+}
+
+/// Fetches the local client state (which must be neither frozen nor expired) and its consensus
+/// state at `proof_height`.
+fn client_and_consensus_state<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	connection_end: &ConnectionEnd,
+	proof_height: Height,
+) -> Result<(Ctx::AnyClientState, Ctx::AnyConsensusState), Error> {
 	// Fetch the client state (IBC client on the local/host chain).
 	let client_state = ctx.client_state(connection_end.client_id()).map_err(Error::ics02_client)?;
 
-	// The client must not be frozen.
+	// The client must be neither frozen nor expired.
 	if client_state.is_frozen() {
 		return Err(Error::frozen_client(connection_end.client_id().clone()))
 	}
+	ensure_client_not_expired(ctx, connection_end.client_id(), &client_state)
+		.map_err(Error::ics02_client)?;
 
 	// The client must have the consensus state for the height where this proof was created.
-	let consensus_state = ctx
-		.consensus_state(connection_end.client_id(), proof_height)
+	let consensus_state = proof_consensus_state(ctx, connection_end.client_id(), proof_height)
 		.map_err(|e| Error::consensus_state_verification_failure(proof_height, e))?;
 
+	Ok((client_state, consensus_state))
+}
+
+/// The prefix of the counterparty's store, checked before it is used to build a proof path so
+/// that a bad prefix is reported as such rather than as a failed proof.
+fn counterparty_prefix(connection_end: &ConnectionEnd) -> Result<&CommitmentPrefix, Error> {
+	let prefix = connection_end.counterparty().prefix();
+	prefix
+		.validate()
+		.map_err(|e| Error::invalid_counterparty_prefix(prefix.clone(), e))?;
+	Ok(prefix)
+}
+
+fn verify_connection_membership<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_state: &Ctx::AnyClientState,
+	consensus_state: &Ctx::AnyConsensusState,
+	height: Height,
+	connection_end: &ConnectionEnd,
+	expected_conn: &ConnectionEnd,
+	proof: &CommitmentProofBytes,
+) -> Result<(), Error> {
 	// A counterparty connection id of None causes `unwrap()` below and indicates an internal
 	// error as this is the connection id on the counterparty chain that must always be present.
 	let connection_id = connection_end
@@ -72,9 +206,9 @@ pub fn verify_connection_proof<Ctx: ReaderContext>(
 		.verify_connection_state(
 			ctx,
 			connection_end.client_id(),
-			&client_state,
+			client_state,
 			height,
-			connection_end.counterparty().prefix(),
+			counterparty_prefix(connection_end)?,
 			proof,
 			consensus_state.root(),
 			connection_id,
@@ -83,67 +217,41 @@ pub fn verify_connection_proof<Ctx: ReaderContext>(
 		.map_err(Error::verify_connection_state)
 }
 
-/// Verifies the client `proof` from a connection handshake message, typically from a
-/// `MsgConnectionOpenTry` or a `MsgConnectionOpenAck`. The `expected_client_state` argument is a
-/// representation for a client of the current chain (the chain handling the current message), which
-/// is running on the counterparty chain (the chain which sent this message). This method does a
-/// complete verification: that the client state the counterparty stores is valid (i.e., not frozen,
-/// at the same revision as the current chain, with matching chain identifiers, etc) and that the
-/// `proof` is correct.
-pub fn verify_client_proof<Ctx: ReaderContext>(
+fn verify_client_membership<Ctx: ReaderContext>(
 	ctx: &Ctx,
+	client_state: &Ctx::AnyClientState,
+	consensus_state: &Ctx::AnyConsensusState,
 	height: Height,
 	connection_end: &ConnectionEnd,
-	expected_client_state: Ctx::AnyClientState,
-	proof_height: Height,
+	expected_client_state: &Ctx::AnyClientState,
 	proof: &CommitmentProofBytes,
 ) -> Result<(), Error> {
-	// Fetch the local client state (IBC client running on the host chain).
-	let client_state = ctx.client_state(connection_end.client_id()).map_err(Error::ics02_client)?;
-
-	if client_state.is_frozen() {
-		return Err(Error::frozen_client(connection_end.client_id().clone()))
-	}
-
-	let consensus_state = ctx
-		.consensus_state(connection_end.client_id(), proof_height)
-		.map_err(|e| Error::consensus_state_verification_failure(proof_height, e))?;
-
 	let client_def = client_state.client_def();
 
 	client_def
 		.verify_client_full_state(
 			ctx,
-			&client_state,
+			client_state,
 			height,
-			connection_end.counterparty().prefix(),
+			counterparty_prefix(connection_end)?,
 			proof,
 			consensus_state.root(),
 			connection_end.counterparty().client_id(),
-			&expected_client_state,
+			expected_client_state,
 		)
 		.map_err(|e| {
 			Error::client_state_verification_failure(connection_end.client_id().clone(), e)
 		})
 }
 
-pub fn verify_consensus_proof<Ctx: ReaderContext>(
+fn verify_consensus_membership<Ctx: ReaderContext>(
 	ctx: &Ctx,
+	client_state: &Ctx::AnyClientState,
+	consensus_state: &Ctx::AnyConsensusState,
 	height: Height,
 	connection_end: &ConnectionEnd,
 	proof: &ConsensusProof,
 ) -> Result<(), Error> {
-	// Fetch the client state (IBC client on the local chain).
-	let client_state = ctx.client_state(connection_end.client_id()).map_err(Error::ics02_client)?;
-
-	if client_state.is_frozen() {
-		return Err(Error::frozen_client(connection_end.client_id().clone()))
-	}
-
-	let consensus_state = ctx
-		.consensus_state(connection_end.client_id(), height)
-		.map_err(|e| Error::consensus_state_verification_failure(height, e))?;
-
 	let client = client_state.client_def();
 
 	// todo: we can remove this hack, once this is merged https://github.com/cosmos/ibc/pull/839
@@ -161,6 +269,15 @@ pub fn verify_consensus_proof<Ctx: ReaderContext>(
 					Some(connection_proof.host_consensus_state_proof),
 				)
 				.map_err(|e| Error::consensus_state_verification_failure(proof.height(), e))?;
+			// Hosts that can read their own history without an auxiliary proof cross-check the
+			// consensus state the counterparty is claimed to hold against it.
+			if let Ok(local_consensus) = ctx.host_consensus_state(proof.height(), None) {
+				if local_consensus.root() != expected_consensus.root() ||
+					local_consensus.timestamp() != expected_consensus.timestamp()
+				{
+					return Err(Error::invalid_self_consensus_state(proof.height()))
+				}
+			}
 			(
 				CommitmentProofBytes::try_from(connection_proof.consensus_proof).map_err(|e| {
 					Error::implementation_specific(format!("empty proof bytes: {:?}", e))
@@ -178,9 +295,9 @@ pub fn verify_consensus_proof<Ctx: ReaderContext>(
 	client
 		.verify_client_consensus_state(
 			ctx,
-			&client_state,
+			client_state,
 			height,
-			connection_end.counterparty().prefix(),
+			counterparty_prefix(connection_end)?,
 			&consensus_proof,
 			consensus_state.root(),
 			connection_end.counterparty().client_id(),
@@ -29,13 +29,15 @@
 use crate::core::{
 	ics02_client::context::ClientTypes,
 	ics03_connection::msgs::{
-		conn_open_ack::MsgConnectionOpenAck, conn_open_confirm::MsgConnectionOpenConfirm,
-		conn_open_init::MsgConnectionOpenInit, conn_open_try::MsgConnectionOpenTry,
+		conn_cancel::MsgConnectionCancel, conn_open_ack::MsgConnectionOpenAck,
+		conn_open_confirm::MsgConnectionOpenConfirm, conn_open_init::MsgConnectionOpenInit,
+		conn_open_try::MsgConnectionOpenTry,
 	},
 };
 use alloc::boxed::Box;
 use core::fmt::Debug;
 
+pub mod conn_cancel;
 pub mod conn_open_ack;
 pub mod conn_open_confirm;
 pub mod conn_open_init;
@@ -51,6 +53,7 @@ where
 	ConnectionOpenTry(Box<MsgConnectionOpenTry<C>>),
 	ConnectionOpenAck(Box<MsgConnectionOpenAck<C>>),
 	ConnectionOpenConfirm(MsgConnectionOpenConfirm),
+	ConnectionCancel(MsgConnectionCancel),
 }
 
 #[cfg(test)]
@@ -0,0 +1,118 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::connection::v1::MsgConnectionCancel as RawMsgConnectionCancel;
+
+use crate::{
+	core::{ics03_connection::error::Error, ics24_host::identifier::ConnectionId},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.connection.v1.MsgConnectionCancel";
+
+///
+/// Message definition for `MsgConnectionCancel`, which aborts a connection handshake that has not
+/// progressed past `TryOpen`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgConnectionCancel {
+	pub connection_id: ConnectionId,
+	pub signer: Signer,
+}
+
+impl Msg for MsgConnectionCancel {
+	type ValidationError = Error;
+	type Raw = RawMsgConnectionCancel;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgConnectionCancel> for MsgConnectionCancel {}
+
+impl TryFrom<RawMsgConnectionCancel> for MsgConnectionCancel {
+	type Error = Error;
+
+	fn try_from(msg: RawMsgConnectionCancel) -> Result<Self, Self::Error> {
+		Ok(Self {
+			connection_id: msg.connection_id.parse().map_err(Error::invalid_identifier)?,
+			signer: msg.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgConnectionCancel> for RawMsgConnectionCancel {
+	fn from(ics_msg: MsgConnectionCancel) -> Self {
+		RawMsgConnectionCancel {
+			connection_id: ics_msg.connection_id.as_str().to_string(),
+			signer: ics_msg.signer.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::prelude::*;
+	use ibc_proto::ibc::core::connection::v1::MsgConnectionCancel as RawMsgConnectionCancel;
+
+	use crate::test_utils::get_dummy_bech32_account;
+
+	pub fn get_dummy_raw_msg_conn_cancel() -> RawMsgConnectionCancel {
+		RawMsgConnectionCancel {
+			connection_id: "srcconnection".to_string(),
+			signer: get_dummy_bech32_account(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+
+	use ibc_proto::ibc::core::connection::v1::MsgConnectionCancel as RawMsgConnectionCancel;
+
+	use crate::core::ics03_connection::msgs::conn_cancel::{
+		test_util::get_dummy_raw_msg_conn_cancel, MsgConnectionCancel,
+	};
+
+	#[test]
+	fn parse_connection_cancel_msg() {
+		let default_msg = get_dummy_raw_msg_conn_cancel();
+		assert!(MsgConnectionCancel::try_from(default_msg.clone()).is_ok());
+
+		let bad_id = RawMsgConnectionCancel { connection_id: "con007".to_string(), ..default_msg };
+		assert!(MsgConnectionCancel::try_from(bad_id).is_err());
+	}
+
+	#[test]
+	fn to_and_from() {
+		let raw = get_dummy_raw_msg_conn_cancel();
+		let msg = MsgConnectionCancel::try_from(raw.clone()).unwrap();
+		let raw_back = RawMsgConnectionCancel::from(msg.clone());
+		let msg_back = MsgConnectionCancel::try_from(raw_back.clone()).unwrap();
+		assert_eq!(raw, raw_back);
+		assert_eq!(msg, msg_back);
+	}
+}
@@ -111,8 +111,8 @@ where
 
 		let proof_height = msg.proof_height.ok_or_else(Error::missing_proof_height)?.into();
 
-		let client_proof =
-			CommitmentProofBytes::try_from(msg.proof_client).map_err(Error::invalid_proof)?;
+		// The client proof may be omitted by hosts that do not require it during the handshake.
+		let client_proof = CommitmentProofBytes::try_from(msg.proof_client).ok();
 
 		Ok(Self {
 			connection_id: msg.connection_id.parse().map_err(Error::invalid_identifier)?,
@@ -128,7 +128,7 @@ where
 			version: msg.version.ok_or_else(Error::empty_versions)?.try_into()?,
 			proofs: Proofs::new(
 				msg.proof_try.try_into().map_err(Error::invalid_proof)?,
-				Some(client_proof),
+				client_proof,
 				consensus_proof_obj,
 				None,
 				proof_height,
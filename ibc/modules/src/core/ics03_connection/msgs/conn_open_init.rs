@@ -61,7 +61,7 @@ impl TryFrom<RawMsgConnectionOpenInit> for MsgConnectionOpenInit {
 
 	fn try_from(msg: RawMsgConnectionOpenInit) -> Result<Self, Self::Error> {
 		Ok(Self {
-			client_id: msg.client_id.parse().map_err(Error::invalid_identifier)?,
+			client_id: ClientId::new_strict(&msg.client_id).map_err(Error::invalid_identifier)?,
 			counterparty: msg.counterparty.ok_or_else(Error::missing_counterparty)?.try_into()?,
 			version: msg.version.map(|version| version.try_into()).transpose()?,
 			delay_period: Duration::from_nanos(msg.delay_period),
@@ -162,6 +162,14 @@ mod tests {
 				},
 				want_pass: false,
 			},
+			Test {
+				name: "Bad client id, non ascii character".to_string(),
+				raw: RawMsgConnectionOpenInit {
+					client_id: "07-tendermínt-0".to_string(),
+					..default_init_msg.clone()
+				},
+				want_pass: false,
+			},
 			Test {
 				name: "Bad destination connection id, name too long".to_string(),
 				raw: RawMsgConnectionOpenInit {
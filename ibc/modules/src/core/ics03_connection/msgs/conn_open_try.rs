@@ -118,8 +118,8 @@ where
 
 		let proof_height = msg.proof_height.ok_or_else(Error::missing_proof_height)?.into();
 
-		let client_proof =
-			CommitmentProofBytes::try_from(msg.proof_client).map_err(Error::invalid_proof)?;
+		// The client proof may be omitted by hosts that do not require it during the handshake.
+		let client_proof = CommitmentProofBytes::try_from(msg.proof_client).ok();
 
 		let counterparty_versions = msg
 			.counterparty_versions
@@ -132,7 +132,7 @@ where
 		}
 
 		Ok(Self {
-			client_id: msg.client_id.parse().map_err(Error::invalid_identifier)?,
+			client_id: ClientId::new_strict(&msg.client_id).map_err(Error::invalid_identifier)?,
 			client_state: msg
 				.client_state
 				.map(C::AnyClientState::try_from)
@@ -142,7 +142,7 @@ where
 			counterparty_versions,
 			proofs: Proofs::new(
 				msg.proof_init.try_into().map_err(Error::invalid_proof)?,
-				Some(client_proof),
+				client_proof,
 				consensus_proof_obj,
 				None,
 				proof_height,
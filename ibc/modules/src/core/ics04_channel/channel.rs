@@ -16,7 +16,7 @@ use crate::prelude::*;
 
 use core::{fmt, str::FromStr};
 
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use tendermint_proto::Protobuf;
 
 use ibc_proto::ibc::core::channel::v1::{
@@ -31,6 +31,7 @@ use crate::{
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 	},
 	events::WithBlockDataType,
+	serializers::serde_string,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -58,6 +59,7 @@ impl TryFrom<RawIdentifiedChannel> for IdentifiedChannelEnd {
 			counterparty: value.counterparty,
 			connection_hops: value.connection_hops,
 			version: value.version,
+			upgrade_sequence: value.upgrade_sequence,
 		};
 
 		Ok(IdentifiedChannelEnd {
@@ -83,17 +85,22 @@ impl From<IdentifiedChannelEnd> for RawIdentifiedChannel {
 			version: value.channel_end.version.to_string(),
 			port_id: value.port_id.to_string(),
 			channel_id: value.channel_id.to_string(),
+			upgrade_sequence: value.channel_end.upgrade_sequence,
 		}
 	}
 }
 
+/// A channel end. It is encoded as the JSON ibc-go's gRPC gateway renders a channel, with the
+/// counterparty under `counterparty` and the upgrade sequence as a string.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "JsonChannelEnd", into = "JsonChannelEnd")]
 pub struct ChannelEnd {
 	pub state: State,
 	pub ordering: Order,
 	pub remote: Counterparty,
 	pub connection_hops: Vec<ConnectionId>,
 	pub version: Version,
+	pub upgrade_sequence: u64,
 }
 
 impl Default for ChannelEnd {
@@ -104,6 +111,7 @@ impl Default for ChannelEnd {
 			remote: Counterparty::default(),
 			connection_hops: Vec::new(),
 			version: Version::default(),
+			upgrade_sequence: 0,
 		}
 	}
 }
@@ -131,7 +139,8 @@ impl TryFrom<RawChannel> for ChannelEnd {
 
 		let version = value.version.into();
 
-		Ok(ChannelEnd::new(chan_state, chan_ordering, remote, connection_hops, version))
+		Ok(ChannelEnd::new(chan_state, chan_ordering, remote, connection_hops, version)
+			.with_upgrade_sequence(value.upgrade_sequence))
 	}
 }
 
@@ -143,6 +152,48 @@ impl From<ChannelEnd> for RawChannel {
 			counterparty: Some(value.counterparty().clone().into()),
 			connection_hops: value.connection_hops.iter().map(|v| v.as_str().to_string()).collect(),
 			version: value.version.to_string(),
+			upgrade_sequence: value.upgrade_sequence,
+		}
+	}
+}
+
+/// The JSON form of [`ChannelEnd`].
+#[derive(Serialize, Deserialize)]
+struct JsonChannelEnd {
+	state: State,
+	ordering: Order,
+	counterparty: Counterparty,
+	connection_hops: Vec<String>,
+	version: Version,
+	#[serde(with = "serde_string")]
+	upgrade_sequence: u64,
+}
+
+impl TryFrom<JsonChannelEnd> for ChannelEnd {
+	type Error = Error;
+
+	fn try_from(json: JsonChannelEnd) -> Result<Self, Self::Error> {
+		RawChannel {
+			state: json.state as i32,
+			ordering: json.ordering as i32,
+			counterparty: Some(json.counterparty.into()),
+			connection_hops: json.connection_hops,
+			version: json.version.to_string(),
+			upgrade_sequence: json.upgrade_sequence,
+		}
+		.try_into()
+	}
+}
+
+impl From<ChannelEnd> for JsonChannelEnd {
+	fn from(value: ChannelEnd) -> Self {
+		Self {
+			state: value.state,
+			ordering: value.ordering,
+			counterparty: value.remote,
+			connection_hops: value.connection_hops.iter().map(|v| v.as_str().to_string()).collect(),
+			version: value.version,
+			upgrade_sequence: value.upgrade_sequence,
 		}
 	}
 }
@@ -156,7 +207,12 @@ impl ChannelEnd {
 		connection_hops: Vec<ConnectionId>,
 		version: Version,
 	) -> Self {
-		Self { state, ordering, remote, connection_hops, version }
+		Self { state, ordering, remote, connection_hops, version, upgrade_sequence: 0 }
+	}
+
+	/// Sets the sequence of the latest upgrade attempted on this channel.
+	pub fn with_upgrade_sequence(self, upgrade_sequence: u64) -> Self {
+		Self { upgrade_sequence, ..self }
 	}
 
 	/// Updates the ChannelEnd to assume a new State 's'.
@@ -197,6 +253,10 @@ impl ChannelEnd {
 		&self.version
 	}
 
+	pub fn upgrade_sequence(&self) -> u64 {
+		self.upgrade_sequence
+	}
+
 	pub fn validate_basic(&self) -> Result<(), Error> {
 		if self.connection_hops.len() != 1 {
 			return Err(Error::invalid_connection_hops_length(1, self.connection_hops.len()))
@@ -228,7 +288,10 @@ impl ChannelEnd {
 	}
 }
 
+/// The counterparty of a channel end. It is encoded as JSON like ibc-go does, with an empty
+/// channel id while it is unknown.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawCounterparty", into = "RawCounterparty")]
 pub struct Counterparty {
 	pub port_id: PortId,
 	pub channel_id: Option<ChannelId>,
@@ -276,10 +339,15 @@ impl From<Counterparty> for RawCounterparty {
 	}
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+/// The ordering of a channel. It is encoded in JSON as the name of the protobuf enum value, e.g.
+/// `"ORDER_UNORDERED"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Order {
 	Unordered = 1,
 	Ordered = 2,
+	/// Packets are delivered in order, but a timed out packet does not close the channel; the
+	/// receiving end records it as timed out and moves on to the next sequence.
+	OrderedAllowTimeout = 3,
 }
 
 impl Default for Order {
@@ -300,6 +368,7 @@ impl Order {
 		match self {
 			Self::Unordered => "ORDER_UNORDERED",
 			Self::Ordered => "ORDER_ORDERED",
+			Self::OrderedAllowTimeout => "ORDER_ORDERED_ALLOW_TIMEOUT",
 		}
 	}
 
@@ -308,11 +377,25 @@ impl Order {
 		match nr {
 			1 => Ok(Self::Unordered),
 			2 => Ok(Self::Ordered),
+			3 => Ok(Self::OrderedAllowTimeout),
 			_ => Err(Error::unknown_order_type(nr.to_string())),
 		}
 	}
 }
 
+impl Serialize for Order {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_str())
+	}
+}
+
+/// Also accepts the names [`FromStr`] does, such as `"unordered"`.
+impl<'de> Deserialize<'de> for Order {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		String::deserialize(deserializer)?.parse().map_err(de::Error::custom)
+	}
+}
+
 impl FromStr for Order {
 	type Err = Error;
 
@@ -320,17 +403,28 @@ impl FromStr for Order {
 		match s.to_lowercase().trim_start_matches("order_") {
 			"unordered" => Ok(Self::Unordered),
 			"ordered" => Ok(Self::Ordered),
+			"ordered_allow_timeout" => Ok(Self::OrderedAllowTimeout),
 			_ => Err(Error::unknown_order_type(s.to_string())),
 		}
 	}
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+/// The state of a channel end. It is encoded in JSON as the name of the protobuf enum value, e.g.
+/// `"STATE_OPEN"`.
+///
+/// `Flushing` and `FlushComplete` are the upgrade states of ibc-go, which are only decoded from
+/// counterparty channel ends. The upgrade handshake of this crate uses `InitUpgrade` and
+/// `TryUpgrade`, numbered after them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum State {
 	Init = 1,
 	TryOpen = 2,
 	Open = 3,
 	Closed = 4,
+	Flushing = 5,
+	FlushComplete = 6,
+	InitUpgrade = 7,
+	TryUpgrade = 8,
 }
 
 impl State {
@@ -341,6 +435,24 @@ impl State {
 			Self::TryOpen => "TRYOPEN",
 			Self::Open => "OPEN",
 			Self::Closed => "CLOSED",
+			Self::Flushing => "FLUSHING",
+			Self::FlushComplete => "FLUSHCOMPLETE",
+			Self::InitUpgrade => "INITUPGRADE",
+			Self::TryUpgrade => "TRYUPGRADE",
+		}
+	}
+
+	/// Yields the state as the name of its protobuf enum value.
+	pub fn as_proto_str(&self) -> &'static str {
+		match self {
+			Self::Init => "STATE_INIT",
+			Self::TryOpen => "STATE_TRYOPEN",
+			Self::Open => "STATE_OPEN",
+			Self::Closed => "STATE_CLOSED",
+			Self::Flushing => "STATE_FLUSHING",
+			Self::FlushComplete => "STATE_FLUSHCOMPLETE",
+			Self::InitUpgrade => "STATE_INITUPGRADE",
+			Self::TryUpgrade => "STATE_TRYUPGRADE",
 		}
 	}
 
@@ -351,6 +463,10 @@ impl State {
 			2 => Ok(Self::TryOpen),
 			3 => Ok(Self::Open),
 			4 => Ok(Self::Closed),
+			5 => Ok(Self::Flushing),
+			6 => Ok(Self::FlushComplete),
+			7 => Ok(Self::InitUpgrade),
+			8 => Ok(Self::TryUpgrade),
 			_ => Err(Error::unknown_state(s)),
 		}
 	}
@@ -360,6 +476,11 @@ impl State {
 		self == State::Open
 	}
 
+	/// Returns whether or not the channel is in the middle of an upgrade handshake.
+	pub fn is_upgrading(self) -> bool {
+		matches!(self, Self::Flushing | Self::FlushComplete | Self::InitUpgrade | Self::TryUpgrade)
+	}
+
 	/// Returns whether or not the channel with this state
 	/// has progressed less or the same than the argument.
 	///
@@ -381,6 +502,31 @@ impl core::fmt::Display for State {
 	}
 }
 
+impl Serialize for State {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(self.as_proto_str())
+	}
+}
+
+impl<'de> Deserialize<'de> for State {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let name = String::deserialize(deserializer)?;
+		[
+			Self::Init,
+			Self::TryOpen,
+			Self::Open,
+			Self::Closed,
+			Self::Flushing,
+			Self::FlushComplete,
+			Self::InitUpgrade,
+			Self::TryUpgrade,
+		]
+		.into_iter()
+		.find(|state| state.as_proto_str() == name)
+		.ok_or_else(|| de::Error::custom(format!("unknown channel state {}", name)))
+	}
+}
+
 /// Used to query a packet event, identified by `event_id`, for specific channel and sequences.
 /// The query is preformed for the chain context at `height`.
 #[derive(Clone, Debug)]
@@ -421,6 +567,7 @@ pub mod test_util {
 			counterparty: Some(get_dummy_raw_counterparty()),
 			connection_hops: vec![ConnectionId::default().to_string()],
 			version: "ics20".to_string(), // The version is not validated.
+			upgrade_sequence: 0,
 		}
 	}
 }
@@ -434,7 +581,15 @@ mod tests {
 
 	use ibc_proto::ibc::core::channel::v1::Channel as RawChannel;
 
-	use crate::core::ics04_channel::channel::{test_util::get_dummy_raw_channel_end, ChannelEnd};
+	use crate::core::{
+		ics04_channel::{
+			channel::{
+				test_util::get_dummy_raw_channel_end, ChannelEnd, Counterparty, Order, State,
+			},
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId},
+	};
 
 	#[test]
 	fn channel_end_try_from_raw() {
@@ -528,6 +683,11 @@ mod tests {
 		let tests: Vec<Test> = vec![
 			Test { ordering: "UNORDERED", want_res: Order::Unordered, want_err: false },
 			Test { ordering: "ORDERED", want_res: Order::Ordered, want_err: false },
+			Test {
+				ordering: "ORDER_ORDERED_ALLOW_TIMEOUT",
+				want_res: Order::OrderedAllowTimeout,
+				want_err: false,
+			},
 		]
 		.into_iter()
 		.collect();
@@ -542,4 +702,60 @@ mod tests {
 			}
 		}
 	}
+
+	/// Checks that `channel_end` serializes to the channel of an ibc-go
+	/// `/ibc/core/channel/v1/channels/{channel_id}/ports/{port_id}` response, and parses back from
+	/// it.
+	fn assert_matches_ibc_go(response: &str, channel_end: ChannelEnd) {
+		let response: serde_json::Value = serde_json::from_str(response).unwrap();
+		let json = &response["channel"];
+		assert_eq!(&serde_json::to_value(&channel_end).unwrap(), json);
+		assert_eq!(serde_json::from_value::<ChannelEnd>(json.clone()).unwrap(), channel_end);
+	}
+
+	#[test]
+	fn channel_end_json_matches_ibc_go() {
+		let open = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new("transfer".parse().unwrap(), Some(ChannelId::new(3))),
+			vec![ConnectionId::new(0)],
+			Version::ics20(),
+		);
+		assert_matches_ibc_go(include_str!("serialization/query_channel.json"), open);
+
+		let init = ChannelEnd::new(
+			State::Init,
+			Order::Ordered,
+			Counterparty::new("icahost".parse().unwrap(), None),
+			vec![ConnectionId::new(2)],
+			Version::new("ics27-1".to_string()),
+		);
+		assert_matches_ibc_go(include_str!("serialization/query_channel_init.json"), init);
+	}
+
+	#[test]
+	fn state_numbering_matches_proto() {
+		use ibc_proto::ibc::core::channel::v1::State as RawState;
+
+		let states = [
+			(State::Flushing, RawState::Flushing),
+			(State::FlushComplete, RawState::Flushcomplete),
+			(State::InitUpgrade, RawState::Initupgrade),
+			(State::TryUpgrade, RawState::Tryupgrade),
+		];
+		for (state, raw) in states {
+			assert_eq!(State::from_i32(raw as i32).unwrap(), state);
+			assert_eq!(state.as_proto_str(), raw.as_str_name());
+		}
+		// ibc-go reports channels that are flushing packets during an upgrade as 5 and 6.
+		assert_eq!(State::from_i32(5).unwrap(), State::Flushing);
+		assert_eq!(State::from_i32(6).unwrap(), State::FlushComplete);
+	}
+
+	#[test]
+	fn order_json_accepts_legacy_names() {
+		assert_eq!(serde_json::from_str::<Order>("\"Unordered\"").unwrap(), Order::Unordered);
+		assert_eq!(serde_json::to_string(&Order::Ordered).unwrap(), "\"ORDER_ORDERED\"");
+	}
 }
@@ -15,6 +15,11 @@
 use crate::prelude::*;
 
 use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+	core::ics04_channel::msgs::acknowledgement::Acknowledgement, timestamp::Timestamp, Height,
+};
 
 /// Packet commitment
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
@@ -47,3 +52,86 @@ impl From<Vec<u8>> for AcknowledgementCommitment {
 		Self(bytes)
 	}
 }
+
+/// Computes the commitment to a packet exactly as ibc-go does, so that commitments written on one
+/// end of a channel can be verified on the other:
+///
+/// `sha256(timeout_timestamp || revision_number || revision_height || sha256(data))`
+///
+/// where the revision number and height are those of the timeout height, and all integers are
+/// encoded as 8 big-endian bytes.
+pub fn compute_packet_commitment(
+	data: &[u8],
+	timeout_height: &Height,
+	timeout_timestamp: &Timestamp,
+) -> PacketCommitment {
+	let mut input = timeout_timestamp.nanoseconds().to_be_bytes().to_vec();
+	input.extend_from_slice(&timeout_height.revision_number.to_be_bytes());
+	input.extend_from_slice(&timeout_height.revision_height.to_be_bytes());
+	input.extend_from_slice(&Sha256::digest(data));
+	Sha256::digest(&input).to_vec().into()
+}
+
+/// Computes the commitment to an acknowledgement exactly as ibc-go does, `sha256(ack)`.
+pub fn compute_ack_commitment(ack: &Acknowledgement) -> AcknowledgementCommitment {
+	Sha256::digest(ack.as_ref()).to_vec().into()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use test_log::test;
+
+	// Expected values follow ibc-go's `CommitPacket` and `CommitAcknowledgement`.
+	const PACKET_DATA: &[u8] =
+		br#"{"amount":"100","denom":"stake","receiver":"bob","sender":"alice"}"#;
+
+	fn hex(bytes: Vec<u8>) -> String {
+		bytes.iter().map(|b| format!("{:02x}", b)).collect()
+	}
+
+	#[test]
+	fn packet_commitment_matches_ibc_go() {
+		let timestamp = Timestamp::from_nanoseconds(1_700_000_000_000_000_000).unwrap();
+		let cases = [
+			(
+				Height::new(1, 1000),
+				Timestamp::none(),
+				"7d97c541477367ba2f3ee377162531f436baef7964d1c8a395ef0402706e35ba",
+			),
+			(
+				Height::zero(),
+				timestamp,
+				"b7ca7ec1c2cf329cb08e83ecc11ecf2998a600ee3754719c4d34da84adc1c179",
+			),
+			(
+				Height::new(1, 1000),
+				timestamp,
+				"0f024e05940cfb4b2f3a697dfd84336873ee4b03538a4038235e99efe9eb0a5f",
+			),
+		];
+		for (timeout_height, timeout_timestamp, expected) in cases {
+			let commitment =
+				compute_packet_commitment(PACKET_DATA, &timeout_height, &timeout_timestamp);
+			assert_eq!(hex(commitment.into_vec()), expected);
+		}
+	}
+
+	#[test]
+	fn ack_commitment_matches_ibc_go() {
+		let success = Acknowledgement::from(br#"{"result":"AQ=="}"#.to_vec());
+		assert_eq!(
+			hex(compute_ack_commitment(&success).into_vec()),
+			"08f7557ed51826fe18d84512bf24ec75001edbaf2123a477df72a0a9f3640a7c"
+		);
+
+		let error = Acknowledgement::from(
+			br#"{"error":"ABCI code: 1: error handling packet: see events for details"}"#.to_vec(),
+		);
+		assert_eq!(
+			hex(compute_ack_commitment(&error).into_vec()),
+			"439dd0ea54f168850977fce615993ac34f8e2d238c76c0c9328a744e89a2230d"
+		);
+	}
+}
@@ -24,12 +24,13 @@ use crate::{
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			error::Error,
 			handler::{recv_packet::RecvPacketResult, ChannelIdState, ChannelResult},
-			msgs::acknowledgement::Acknowledgement,
 			packet::Receipt,
+			upgrade::UpgradeOrigin,
 		},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
 	prelude::*,
+	signer::Signer,
 	timestamp::Timestamp,
 	Height,
 };
@@ -63,6 +64,17 @@ pub trait ChannelReader {
 		key: &(PortId, ChannelId, Sequence),
 	) -> Result<PacketCommitment, Error>;
 
+	/// Returns the number of packet commitments still outstanding on the given channel, so that
+	/// relayers can skip channels with nothing to relay.
+	fn packet_commitment_count(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<u64, Error> {
+		Err(Error::implementation_specific(
+			"packet_commitment_count is not implemented".to_string(),
+		))
+	}
+
 	fn get_packet_receipt(&self, key: &(PortId, ChannelId, Sequence)) -> Result<Receipt, Error>;
 
 	fn get_packet_acknowledgement(
@@ -70,26 +82,66 @@ pub trait ChannelReader {
 		key: &(PortId, ChannelId, Sequence),
 	) -> Result<AcknowledgementCommitment, Error>;
 
-	fn packet_commitment(
+	/// Returns, in sequence order, up to `limit` acknowledgements written on the given channel
+	/// whose sequence is at least `start_sequence`. Relayers page through the acknowledgements
+	/// that may still have to be relayed back with this.
+	fn packet_acknowledgements(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+		_start_sequence: Sequence,
+		_limit: u64,
+	) -> Result<Vec<(Sequence, AcknowledgementCommitment)>, Error> {
+		Err(Error::implementation_specific(
+			"packet_acknowledgements is not implemented".to_string(),
+		))
+	}
+
+	/// Returns the lowest sequence not yet pruned by `MsgPruneAcknowledgements` for the given
+	/// channel. Channels that were never pruned start at sequence 1.
+	fn get_pruning_sequence_start(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, Error>;
+
+	/// Returns the sequences and host times of the timeouts recorded for the given channel
+	/// through `ChannelKeeper::store_timed_out_packet`, oldest first.
+	fn timed_out_packets(
 		&self,
-		packet_data: Vec<u8>,
-		timeout_height: Height,
-		timeout_timestamp: Timestamp,
-	) -> PacketCommitment {
-		let mut input = timeout_timestamp.nanoseconds().to_be_bytes().to_vec();
-		let revision_number = timeout_height.revision_number.to_be_bytes();
-		input.append(&mut revision_number.to_vec());
-		let revision_height = timeout_height.revision_height.to_be_bytes();
-		input.append(&mut revision_height.to_vec());
-		let data = self.hash(packet_data);
-		input.append(&mut data.to_vec());
-		self.hash(input).into()
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<(Sequence, Timestamp)>, Error> {
+		Ok(Vec::new())
 	}
 
-	fn ack_commitment(&self, ack: Acknowledgement) -> AcknowledgementCommitment {
-		self.hash(ack.into_bytes()).into()
+	/// Returns whether `signer` may initiate the closing handshake of the given channel. Hosts
+	/// should restrict this to the owner of the port; the default allows any signer.
+	fn is_close_authorized(
+		&self,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_signer: &Signer,
+	) -> bool {
+		true
+	}
+
+	/// Returns whether `signer` may propose an upgrade of the given channel, or cancel one before
+	/// it times out. Hosts should restrict this to the owner of the port; the default allows no
+	/// signer, so that channels can't be upgraded unless the host opts in.
+	fn is_upgrade_authorized(
+		&self,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_signer: &Signer,
+	) -> bool {
+		false
 	}
 
+	/// Returns the fields replaced by the upgrade in progress on the given channel, and the host
+	/// height it started at.
+	fn channel_upgrade_origin(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<UpgradeOrigin, Error>;
+
 	/// A Sha2_256 hashing function
 	fn hash(&self, value: Vec<u8>) -> Vec<u8>;
 
@@ -108,12 +160,6 @@ pub trait ChannelReader {
 
 	/// Returns the maximum expected time per block
 	fn max_expected_time_per_block(&self) -> Duration;
-
-	/// Calculates the block delay period using the connection's delay period and the maximum
-	/// expected time per block.
-	fn block_delay(&self, delay_period_time: Duration) -> u64 {
-		calculate_block_delay(delay_period_time, self.max_expected_time_per_block())
-	}
 }
 
 /// A context supplying all the necessary write-only dependencies (i.e., storage writing facility)
@@ -137,7 +183,16 @@ pub trait ChannelKeeper {
 			// Initialize send, recv, and ack sequence numbers.
 			self.store_next_sequence_send((result.port_id.clone(), result.channel_id), 1.into())?;
 			self.store_next_sequence_recv((result.port_id.clone(), result.channel_id), 1.into())?;
-			self.store_next_sequence_ack((result.port_id, result.channel_id), 1.into())?;
+			self.store_next_sequence_ack((result.port_id.clone(), result.channel_id), 1.into())?;
+		}
+
+		if let Some(origin) = result.upgrade_origin {
+			self.store_channel_upgrade_origin((result.port_id, result.channel_id), origin)?;
+		} else if result.channel_end.upgrade_sequence() > 0 &&
+			!result.channel_end.state().is_upgrading()
+		{
+			// The upgrade completed or was cancelled, there is nothing left to restore.
+			self.delete_channel_upgrade_origin((result.port_id, result.channel_id))?;
 		}
 
 		Ok(())
@@ -167,8 +222,20 @@ pub trait ChannelKeeper {
 					self.store_packet_receipt((port_id.clone(), channel_id, sequence), receipt)?;
 					self.store_recv_packet((port_id, channel_id, packet.sequence), packet)?
 				},
-
-				RecvPacketResult::NoOp => unreachable!(),
+				RecvPacketResult::Timeout {
+					port_id,
+					channel_id,
+					next_seq_recv,
+					ack_commitment,
+					packet,
+				} => {
+					self.store_next_sequence_recv((port_id.clone(), channel_id), next_seq_recv)?;
+					self.store_packet_acknowledgement(
+						(port_id.clone(), channel_id, packet.sequence),
+						ack_commitment,
+					)?;
+					self.store_recv_packet((port_id, channel_id, packet.sequence), packet)?
+				},
 			},
 			PacketResult::WriteAck(res) => {
 				self.store_packet_acknowledgement(
@@ -190,8 +257,26 @@ pub trait ChannelKeeper {
 					//Ordered Channel
 					self.store_channel((res.port_id.clone(), res.channel_id), &c)?;
 				}
+				if let Some(s) = res.seq_number {
+					//OrderedAllowTimeout Channel
+					self.store_next_sequence_ack((res.port_id.clone(), res.channel_id), s)?;
+				}
 				self.delete_packet_commitment((res.port_id.clone(), res.channel_id, res.seq))?;
+				if let Some(timestamp) = res.timed_out_at {
+					self.store_timed_out_packet((res.port_id, res.channel_id, res.seq), timestamp)?;
+				}
+			},
+			PacketResult::Prune(res) => {
+				for seq in res.sequences {
+					self.delete_packet_receipt((res.port_id.clone(), res.channel_id, seq))?;
+					self.delete_packet_acknowledgement((res.port_id.clone(), res.channel_id, seq))?;
+				}
+				self.store_pruning_sequence_start(
+					(res.port_id, res.channel_id),
+					res.pruning_sequence_start,
+				)?;
 			},
+			PacketResult::NoOp => {},
 		}
 		Ok(())
 	}
@@ -225,6 +310,8 @@ pub trait ChannelKeeper {
 		receipt: Receipt,
 	) -> Result<(), Error>;
 
+	fn delete_packet_receipt(&mut self, key: (PortId, ChannelId, Sequence)) -> Result<(), Error>;
+
 	fn store_packet_acknowledgement(
 		&mut self,
 		key: (PortId, ChannelId, Sequence),
@@ -249,6 +336,19 @@ pub trait ChannelKeeper {
 		channel_end: &ChannelEnd,
 	) -> Result<(), Error>;
 
+	/// Stores what the upgrade in progress on the given channel replaced and when it started.
+	fn store_channel_upgrade_origin(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		origin: UpgradeOrigin,
+	) -> Result<(), Error>;
+
+	/// Removes the upgrade origin stored for the given channel, if any.
+	fn delete_channel_upgrade_origin(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Error>;
+
 	fn store_next_sequence_send(
 		&mut self,
 		port_channel_id: (PortId, ChannelId),
@@ -267,12 +367,31 @@ pub trait ChannelKeeper {
 		seq: Sequence,
 	) -> Result<(), Error>;
 
+	fn store_pruning_sequence_start(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		seq: Sequence,
+	) -> Result<(), Error>;
+
+	/// Records that the packet was timed out at the given host time. Only called when
+	/// `ReaderContext::persist_timeouts` is enabled; hosts are expected to bound the number of
+	/// records kept per channel.
+	fn store_timed_out_packet(
+		&mut self,
+		_key: (PortId, ChannelId, Sequence),
+		_timestamp: Timestamp,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
 	/// Called upon channel identifier creation (Init or Try message processing).
 	/// Increases the counter which keeps track of how many channels have been created.
 	/// Should never fail.
 	fn increase_channel_counter(&mut self);
 }
 
+/// Number of blocks that must be produced on the host for `delay_period_time` to have elapsed,
+/// rounded up. Hosts without an expected block time impose no block delay.
 pub fn calculate_block_delay(
 	delay_period_time: Duration,
 	max_expected_time_per_block: Duration,
@@ -284,3 +403,23 @@ pub fn calculate_block_delay(
 	FloatCore::ceil(delay_period_time.as_secs_f64() / max_expected_time_per_block.as_secs_f64())
 		as u64
 }
+
+#[cfg(test)]
+mod tests {
+	use super::calculate_block_delay;
+	use core::time::Duration;
+
+	#[test]
+	fn block_delay_rounds_up_to_whole_blocks() {
+		let block_time = Duration::from_secs(6);
+		assert_eq!(calculate_block_delay(Duration::from_secs(0), block_time), 0);
+		assert_eq!(calculate_block_delay(Duration::from_secs(6), block_time), 1);
+		assert_eq!(calculate_block_delay(Duration::from_secs(7), block_time), 2);
+		assert_eq!(calculate_block_delay(Duration::from_secs(60), block_time), 10);
+	}
+
+	#[test]
+	fn block_delay_is_zero_without_expected_block_time() {
+		assert_eq!(calculate_block_delay(Duration::from_secs(60), Duration::ZERO), 0);
+	}
+}
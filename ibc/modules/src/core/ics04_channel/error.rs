@@ -19,6 +19,7 @@ use crate::{
 		ics03_connection::error as connection_error,
 		ics04_channel::channel::State,
 		ics05_port::error as port_error,
+		ics23_commitment::{commitment::CommitmentPrefix, error as commitment_error},
 		ics24_host::{
 			error::ValidationError,
 			identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -112,6 +113,9 @@ define_error! {
 		ZeroPacketTimeout
 			| _ | { "packet timeout height and packet timeout timestamp cannot both be 0" },
 
+		ZeroPruneLimit
+			| _ | { "limit on the number of sequences to prune cannot be 0" },
+
 		InvalidTimeoutHeight
 			| _ | { "invalid timeout height for the packet" },
 
@@ -171,6 +175,14 @@ define_error! {
 					e.connection_id)
 			},
 
+		ProofCloseConnectionMismatch
+			{ connection_id: ConnectionId }
+			| e | {
+				format_args!(
+					"connection {0} used to prove the channel closure does not track the packet's counterparty chain",
+					e.connection_id)
+			},
+
 		PacketVerificationFailed
 			{ sequence: Sequence }
 			[ client_error::Error ]
@@ -216,13 +228,15 @@ define_error! {
 
 		InvalidPacketSequence
 			{
-				given_sequence: Sequence,
-				next_sequence: Sequence
+				port_id: PortId,
+				channel_id: ChannelId,
+				expected: Sequence,
+				received: Sequence,
 			}
 			| e | {
 				format_args!(
-					"Invalid packet sequence {0} ≠ next send sequence {1}",
-					e.given_sequence, e.next_sequence)
+					"invalid packet sequence on port {0} channel {1}: expected {2}, received {3}",
+					e.port_id, e.channel_id, e.expected, e.received)
 			},
 
 		LowPacketHeight
@@ -286,6 +300,29 @@ define_error! {
 					e.client_id)
 			},
 
+		ClientNotFrozen
+			{ client_id: ClientId }
+			| e | {
+				format_args!(
+					"Client with id {0} is not frozen, the channel must be closed with a proof",
+					e.client_id)
+			},
+
+		InvalidCounterpartyPrefix
+			{ prefix: CommitmentPrefix }
+			[ commitment_error::Error ]
+			| e | {
+				format_args!("invalid counterparty commitment prefix {0:?}", e.prefix)
+			},
+
+		ConnectionClientMismatch
+			{ connection_id: ConnectionId, expected: ClientId, actual: ClientId }
+			| e | {
+				format_args!(
+					"connection {0} is tracked by client {1}, not {2}",
+					e.connection_id, e.expected, e.actual)
+			},
+
 		InvalidCounterpartyChannelId
 			[ ValidationError ]
 			| _ | { "Invalid channel id in counterparty" },
@@ -306,6 +343,89 @@ define_error! {
 					e.channel_id)
 			},
 
+		UnauthorizedChannelClose
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"signer is not authorized to close channel {0} on port {1}",
+					e.channel_id, e.port_id)
+			},
+
+		UnauthorizedChannelUpgrade
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"signer is not authorized to upgrade channel {0} on port {1}",
+					e.channel_id, e.port_id)
+			},
+
+		ChannelUpgradeInProgress
+			{ channel_id: ChannelId, state: State }
+			| e | {
+				format_args!(
+					"Channel {0} is {1}, packets can't be sent until its upgrade completes",
+					e.channel_id, e.state)
+			},
+
+		UpgradeNotTimedOut
+			{ channel_id: ChannelId, elapsed: u64, timeout: u64 }
+			| e | {
+				format_args!(
+					"Upgrade of channel {0} started {1} blocks ago and times out after {2} blocks",
+					e.channel_id, e.elapsed, e.timeout)
+			},
+
+		MissingUpgradeOrigin
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"no upgrade in progress on channel {0} of port {1}",
+					e.channel_id, e.port_id)
+			},
+
+		PruneOrderedChannel
+			{ channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"Channel {0} is ordered and does not store packet receipts to prune",
+					e.channel_id)
+			},
+
+		PruneChannelNotClosed
+			{ channel_id: ChannelId, state: State }
+			| e | {
+				format_args!(
+					"Channel {0} is {1}, only the receipts of closed channels can be pruned",
+					e.channel_id, e.state)
+			},
+
+		PacketReceiptPruned
+			{ sequence: Sequence }
+			| e | {
+				format_args!(
+					"Receipt for the packet {0} was pruned, the packet was already received",
+					e.sequence)
+			},
+
+		MissingUpgradeFields
+			| _ | { "missing channel upgrade fields" },
+
+		UpgradeFieldsUnchanged
+			{ channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"Upgrade of channel {0} does not change its ordering or version",
+					e.channel_id)
+			},
+
+		UpgradeSequenceMismatch
+			{ channel_id: ChannelId, expected: u64, actual: u64 }
+			| e | {
+				format_args!(
+					"Upgrade sequence mismatch on channel {0}: expected {1}, counterparty has {2}",
+					e.channel_id, e.expected, e.actual)
+			},
+
 		ChanOpenAckProofVerification
 			| _ | { "Handshake proof verification fails at ChannelOpenAck" },
 
@@ -20,7 +20,7 @@ use tendermint::abci::{Event as AbciEvent, EventAttribute};
 use crate::{
 	core::{
 		ics02_client::height::Height,
-		ics04_channel::{error::Error, packet::Packet},
+		ics04_channel::{channel::Order, error::Error, packet::Packet},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 	},
 	events::{
@@ -37,6 +37,7 @@ const CHANNEL_ID_ATTRIBUTE_KEY: &str = "channel_id";
 const PORT_ID_ATTRIBUTE_KEY: &str = "port_id";
 const COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY: &str = "counterparty_channel_id";
 const COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY: &str = "counterparty_port_id";
+const REASON_ATTRIBUTE_KEY: &str = "reason";
 
 /// Packet event attribute keys
 const PKT_SEQ_ATTRIBUTE_KEY: &str = "packet_sequence";
@@ -48,6 +49,10 @@ const PKT_DST_CHANNEL_ATTRIBUTE_KEY: &str = "packet_dst_channel";
 const PKT_TIMEOUT_HEIGHT_ATTRIBUTE_KEY: &str = "packet_timeout_height";
 const PKT_TIMEOUT_TIMESTAMP_ATTRIBUTE_KEY: &str = "packet_timeout_timestamp";
 const PKT_ACK_ATTRIBUTE_KEY: &str = "packet_ack";
+const PKT_CHANNEL_ORDERING_ATTRIBUTE_KEY: &str = "packet_channel_ordering";
+
+/// Pruning event attribute keys
+const TOTAL_PRUNED_ATTRIBUTE_KEY: &str = "total_pruned_sequences";
 
 pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
 	match event.kind.parse() {
@@ -78,7 +83,39 @@ pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
 			.flatten(),
 		Ok(IbcEventType::CloseConfirmChannel) => extract_attributes_from_tx(event)
 			.map(CloseConfirm::try_from)
-			.map(|res| res.ok().map(IbcEvent::CloseConfirmChannel))
+			.map(|res| {
+				res.ok().map(|close_confirm| {
+					IbcEvent::CloseConfirmChannel(CloseConfirm {
+						reason: extract_reason_from_tx(event),
+						..close_confirm
+					})
+				})
+			})
+			.ok()
+			.flatten(),
+		Ok(IbcEventType::ChannelUpgradeInit) => extract_attributes_from_tx(event)
+			.map(UpgradeInit::try_from)
+			.map(|res| res.ok().map(IbcEvent::ChannelUpgradeInit))
+			.ok()
+			.flatten(),
+		Ok(IbcEventType::ChannelUpgradeTry) => extract_attributes_from_tx(event)
+			.map(UpgradeTry::try_from)
+			.map(|res| res.ok().map(IbcEvent::ChannelUpgradeTry))
+			.ok()
+			.flatten(),
+		Ok(IbcEventType::ChannelUpgradeAck) => extract_attributes_from_tx(event)
+			.map(UpgradeAck::try_from)
+			.map(|res| res.ok().map(IbcEvent::ChannelUpgradeAck))
+			.ok()
+			.flatten(),
+		Ok(IbcEventType::ChannelUpgradeConfirm) => extract_attributes_from_tx(event)
+			.map(UpgradeConfirm::try_from)
+			.map(|res| res.ok().map(IbcEvent::ChannelUpgradeConfirm))
+			.ok()
+			.flatten(),
+		Ok(IbcEventType::ChannelUpgradeCancel) => extract_attributes_from_tx(event)
+			.map(UpgradeCancel::try_from)
+			.map(|res| res.ok().map(IbcEvent::ChannelUpgradeCancel))
 			.ok()
 			.flatten(),
 		Ok(IbcEventType::SendPacket) => {
@@ -86,7 +123,20 @@ pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
 				.map(|(packet, write_ack)| {
 					// This event should not have a write ack.
 					debug_assert_eq!(write_ack.len(), 0);
-					IbcEvent::SendPacket(SendPacket { height: Default::default(), packet })
+					IbcEvent::SendPacket(SendPacket {
+						height: Default::default(),
+						packet,
+						channel_ordering: extract_channel_ordering_from_tx(event),
+					})
+				})
+				.ok()
+		},
+		Ok(IbcEventType::ReceivePacket) => {
+			extract_packet_and_write_ack_from_tx(event)
+				.map(|(packet, write_ack)| {
+					// This event should not have a write ack.
+					debug_assert_eq!(write_ack.len(), 0);
+					IbcEvent::ReceivePacket(ReceivePacket { height: Default::default(), packet })
 				})
 				.ok()
 		},
@@ -199,6 +249,23 @@ fn extract_packet_and_write_ack_from_tx(
 	Ok((packet, write_ack))
 }
 
+/// Events emitted before the channel ordering was recorded come without it.
+fn extract_channel_ordering_from_tx(event: &tendermint::abci::Event) -> Option<Order> {
+	event
+		.attributes
+		.iter()
+		.find(|tag| tag.key.as_str() == PKT_CHANNEL_ORDERING_ATTRIBUTE_KEY)
+		.and_then(|tag| tag.value.as_str().parse().ok())
+}
+
+fn extract_reason_from_tx(event: &tendermint::abci::Event) -> Option<String> {
+	event
+		.attributes
+		.iter()
+		.find(|tag| tag.key.as_str() == REASON_ATTRIBUTE_KEY)
+		.map(|tag| tag.value.to_string())
+}
+
 fn extract_attributes(object: &RawObject<'_>, namespace: &str) -> Result<Attributes, EventError> {
 	Ok(Attributes {
 		height: object.height,
@@ -659,6 +726,10 @@ pub struct CloseConfirm {
 	pub connection_id: ConnectionId,
 	pub counterparty_port_id: PortId,
 	pub counterparty_channel_id: Option<ChannelId>,
+	/// Why the channel was closed without the counterparty's proof, e.g. because its client
+	/// is frozen. `None` for a regular close confirm.
+	#[serde(default)]
+	pub reason: Option<String>,
 }
 
 impl From<CloseConfirm> for Attributes {
@@ -686,18 +757,298 @@ impl CloseConfirm {
 	}
 }
 
+impl TryFrom<Attributes> for CloseConfirm {
+	type Error = EventError;
+	fn try_from(attrs: Attributes) -> Result<Self, Self::Error> {
+		Ok(CloseConfirm {
+			height: attrs.height,
+			port_id: attrs.port_id,
+			channel_id: attrs.channel_id,
+			connection_id: attrs.connection_id,
+			counterparty_port_id: attrs.counterparty_port_id,
+			counterparty_channel_id: attrs.counterparty_channel_id,
+			reason: None,
+		})
+	}
+}
+
 impl From<CloseConfirm> for IbcEvent {
 	fn from(v: CloseConfirm) -> Self {
 		IbcEvent::CloseConfirmChannel(v)
 	}
 }
 
+impl From<CloseConfirm> for AbciEvent {
+	fn from(v: CloseConfirm) -> Self {
+		let reason = v.reason.clone();
+		let mut attributes = Vec::<EventAttribute>::from(Attributes::from(v));
+		if let Some(reason) = reason {
+			attributes.push(EventAttribute {
+				key: REASON_ATTRIBUTE_KEY.to_string(),
+				value: reason,
+				index: false,
+			});
+		}
+		AbciEvent { kind: IbcEventType::CloseConfirmChannel.as_str().to_string(), attributes }
+	}
+}
+
 impl EventType for CloseConfirm {
 	fn event_type() -> IbcEventType {
 		IbcEventType::CloseConfirmChannel
 	}
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct UpgradeInit {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: Option<ChannelId>,
+	pub connection_id: ConnectionId,
+	pub counterparty_port_id: PortId,
+	pub counterparty_channel_id: Option<ChannelId>,
+}
+
+impl From<UpgradeInit> for Attributes {
+	fn from(ev: UpgradeInit) -> Self {
+		Self {
+			height: ev.height,
+			port_id: ev.port_id,
+			channel_id: ev.channel_id,
+			connection_id: ev.connection_id,
+			counterparty_port_id: ev.counterparty_port_id,
+			counterparty_channel_id: ev.counterparty_channel_id,
+		}
+	}
+}
+
+impl UpgradeInit {
+	pub fn channel_id(&self) -> Option<&ChannelId> {
+		self.channel_id.as_ref()
+	}
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+	pub fn height(&self) -> Height {
+		self.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.height = height;
+	}
+}
+
+impl From<UpgradeInit> for IbcEvent {
+	fn from(v: UpgradeInit) -> Self {
+		IbcEvent::ChannelUpgradeInit(v)
+	}
+}
+
+impl EventType for UpgradeInit {
+	fn event_type() -> IbcEventType {
+		IbcEventType::ChannelUpgradeInit
+	}
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct UpgradeTry {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: Option<ChannelId>,
+	pub connection_id: ConnectionId,
+	pub counterparty_port_id: PortId,
+	pub counterparty_channel_id: Option<ChannelId>,
+}
+
+impl From<UpgradeTry> for Attributes {
+	fn from(ev: UpgradeTry) -> Self {
+		Self {
+			height: ev.height,
+			port_id: ev.port_id,
+			channel_id: ev.channel_id,
+			connection_id: ev.connection_id,
+			counterparty_port_id: ev.counterparty_port_id,
+			counterparty_channel_id: ev.counterparty_channel_id,
+		}
+	}
+}
+
+impl UpgradeTry {
+	pub fn channel_id(&self) -> Option<&ChannelId> {
+		self.channel_id.as_ref()
+	}
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+	pub fn height(&self) -> Height {
+		self.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.height = height;
+	}
+}
+
+impl From<UpgradeTry> for IbcEvent {
+	fn from(v: UpgradeTry) -> Self {
+		IbcEvent::ChannelUpgradeTry(v)
+	}
+}
+
+impl EventType for UpgradeTry {
+	fn event_type() -> IbcEventType {
+		IbcEventType::ChannelUpgradeTry
+	}
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct UpgradeAck {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: Option<ChannelId>,
+	pub connection_id: ConnectionId,
+	pub counterparty_port_id: PortId,
+	pub counterparty_channel_id: Option<ChannelId>,
+}
+
+impl From<UpgradeAck> for Attributes {
+	fn from(ev: UpgradeAck) -> Self {
+		Self {
+			height: ev.height,
+			port_id: ev.port_id,
+			channel_id: ev.channel_id,
+			connection_id: ev.connection_id,
+			counterparty_port_id: ev.counterparty_port_id,
+			counterparty_channel_id: ev.counterparty_channel_id,
+		}
+	}
+}
+
+impl UpgradeAck {
+	pub fn channel_id(&self) -> Option<&ChannelId> {
+		self.channel_id.as_ref()
+	}
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+	pub fn height(&self) -> Height {
+		self.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.height = height;
+	}
+}
+
+impl From<UpgradeAck> for IbcEvent {
+	fn from(v: UpgradeAck) -> Self {
+		IbcEvent::ChannelUpgradeAck(v)
+	}
+}
+
+impl EventType for UpgradeAck {
+	fn event_type() -> IbcEventType {
+		IbcEventType::ChannelUpgradeAck
+	}
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct UpgradeConfirm {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: Option<ChannelId>,
+	pub connection_id: ConnectionId,
+	pub counterparty_port_id: PortId,
+	pub counterparty_channel_id: Option<ChannelId>,
+}
+
+impl From<UpgradeConfirm> for Attributes {
+	fn from(ev: UpgradeConfirm) -> Self {
+		Self {
+			height: ev.height,
+			port_id: ev.port_id,
+			channel_id: ev.channel_id,
+			connection_id: ev.connection_id,
+			counterparty_port_id: ev.counterparty_port_id,
+			counterparty_channel_id: ev.counterparty_channel_id,
+		}
+	}
+}
+
+impl UpgradeConfirm {
+	pub fn channel_id(&self) -> Option<&ChannelId> {
+		self.channel_id.as_ref()
+	}
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+	pub fn height(&self) -> Height {
+		self.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.height = height;
+	}
+}
+
+impl From<UpgradeConfirm> for IbcEvent {
+	fn from(v: UpgradeConfirm) -> Self {
+		IbcEvent::ChannelUpgradeConfirm(v)
+	}
+}
+
+impl EventType for UpgradeConfirm {
+	fn event_type() -> IbcEventType {
+		IbcEventType::ChannelUpgradeConfirm
+	}
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct UpgradeCancel {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: Option<ChannelId>,
+	pub connection_id: ConnectionId,
+	pub counterparty_port_id: PortId,
+	pub counterparty_channel_id: Option<ChannelId>,
+}
+
+impl From<UpgradeCancel> for Attributes {
+	fn from(ev: UpgradeCancel) -> Self {
+		Self {
+			height: ev.height,
+			port_id: ev.port_id,
+			channel_id: ev.channel_id,
+			connection_id: ev.connection_id,
+			counterparty_port_id: ev.counterparty_port_id,
+			counterparty_channel_id: ev.counterparty_channel_id,
+		}
+	}
+}
+
+impl UpgradeCancel {
+	pub fn channel_id(&self) -> Option<&ChannelId> {
+		self.channel_id.as_ref()
+	}
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+	pub fn height(&self) -> Height {
+		self.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.height = height;
+	}
+}
+
+impl From<UpgradeCancel> for IbcEvent {
+	fn from(v: UpgradeCancel) -> Self {
+		IbcEvent::ChannelUpgradeCancel(v)
+	}
+}
+
+impl EventType for UpgradeCancel {
+	fn event_type() -> IbcEventType {
+		IbcEventType::ChannelUpgradeCancel
+	}
+}
+
 macro_rules! impl_try_from_attribute_for_event {
     ($($event:ty),+) => {
         $(impl TryFrom<Attributes> for $event {
@@ -717,7 +1068,17 @@ macro_rules! impl_try_from_attribute_for_event {
     };
 }
 
-impl_try_from_attribute_for_event!(OpenInit, OpenTry, OpenAck, OpenConfirm, CloseConfirm);
+impl_try_from_attribute_for_event!(
+	OpenInit,
+	OpenTry,
+	OpenAck,
+	OpenConfirm,
+	UpgradeInit,
+	UpgradeTry,
+	UpgradeAck,
+	UpgradeConfirm,
+	UpgradeCancel
+);
 
 macro_rules! impl_from_ibc_to_abci_event {
     ($($event:ty),+) => {
@@ -734,7 +1095,18 @@ macro_rules! impl_from_ibc_to_abci_event {
     };
 }
 
-impl_from_ibc_to_abci_event!(OpenInit, OpenTry, OpenAck, OpenConfirm, CloseInit, CloseConfirm);
+impl_from_ibc_to_abci_event!(
+	OpenInit,
+	OpenTry,
+	OpenAck,
+	OpenConfirm,
+	CloseInit,
+	UpgradeInit,
+	UpgradeTry,
+	UpgradeAck,
+	UpgradeConfirm,
+	UpgradeCancel
+);
 
 macro_rules! impl_try_from_raw_obj_for_event {
     ($($event:ty),+) => {
@@ -748,12 +1120,28 @@ macro_rules! impl_try_from_raw_obj_for_event {
     };
 }
 
-impl_try_from_raw_obj_for_event!(OpenInit, OpenTry, OpenAck, OpenConfirm, CloseInit, CloseConfirm);
+impl_try_from_raw_obj_for_event!(
+	OpenInit,
+	OpenTry,
+	OpenAck,
+	OpenConfirm,
+	CloseInit,
+	CloseConfirm,
+	UpgradeInit,
+	UpgradeTry,
+	UpgradeAck,
+	UpgradeConfirm,
+	UpgradeCancel
+);
 
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct SendPacket {
 	pub height: Height,
 	pub packet: Packet,
+	/// Ordering of the channel the packet was sent on, `None` when decoded from an event emitted
+	/// before it was recorded.
+	#[serde(default)]
+	pub channel_ordering: Option<Order>,
 }
 
 impl SendPacket {
@@ -787,7 +1175,14 @@ impl TryFrom<SendPacket> for AbciEvent {
 	type Error = Error;
 
 	fn try_from(v: SendPacket) -> Result<Self, Self::Error> {
-		let attributes = Vec::<EventAttribute>::try_from(v.packet)?;
+		let mut attributes = Vec::<EventAttribute>::try_from(v.packet)?;
+		if let Some(ordering) = v.channel_ordering {
+			attributes.push(EventAttribute {
+				key: PKT_CHANNEL_ORDERING_ATTRIBUTE_KEY.to_string(),
+				value: ordering.as_str().to_string(),
+				index: false,
+			});
+		}
 		Ok(AbciEvent { kind: IbcEventType::SendPacket.as_str().to_string(), attributes })
 	}
 }
@@ -1063,6 +1458,73 @@ impl core::fmt::Display for TimeoutOnClosePacket {
 	}
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct AcknowledgementsPruned {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub total_pruned_sequences: u64,
+}
+
+impl AcknowledgementsPruned {
+	pub fn height(&self) -> Height {
+		self.height
+	}
+	pub fn set_height(&mut self, height: Height) {
+		self.height = height;
+	}
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+	pub fn channel_id(&self) -> &ChannelId {
+		&self.channel_id
+	}
+}
+
+impl From<AcknowledgementsPruned> for IbcEvent {
+	fn from(v: AcknowledgementsPruned) -> Self {
+		IbcEvent::AcknowledgementsPruned(v)
+	}
+}
+
+impl From<AcknowledgementsPruned> for AbciEvent {
+	fn from(v: AcknowledgementsPruned) -> Self {
+		let attributes = vec![
+			EventAttribute {
+				key: HEIGHT_ATTRIBUTE_KEY.to_string(),
+				value: v.height.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: PORT_ID_ATTRIBUTE_KEY.to_string(),
+				value: v.port_id.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: CHANNEL_ID_ATTRIBUTE_KEY.to_string(),
+				value: v.channel_id.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: TOTAL_PRUNED_ATTRIBUTE_KEY.to_string(),
+				value: v.total_pruned_sequences.to_string(),
+				index: false,
+			},
+		];
+		AbciEvent { kind: IbcEventType::AcknowledgementsPruned.as_str().to_string(), attributes }
+	}
+}
+
+impl core::fmt::Display for AcknowledgementsPruned {
+	fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+		write!(
+			f,
+			"AcknowledgementsPruned - h:{}, {}/{}, pruned:{}",
+			self.height, self.port_id, self.channel_id, self.total_pruned_sequences
+		)
+	}
+}
+
 macro_rules! impl_try_from_raw_obj_for_packet {
     ($($packet:ty),+) => {
         $(impl TryFrom<RawObject<'_>> for $packet {
@@ -1132,8 +1594,18 @@ mod tests {
 		abci_events.push(AbciEvent::from(open_confirm.clone()));
 		let close_init = CloseInit::try_from(attributes.clone()).unwrap();
 		abci_events.push(AbciEvent::from(close_init.clone()));
-		let close_confirm = CloseConfirm::try_from(attributes).unwrap();
+		let close_confirm = CloseConfirm::try_from(attributes.clone()).unwrap();
 		abci_events.push(AbciEvent::from(close_confirm.clone()));
+		let upgrade_init = UpgradeInit::try_from(attributes.clone()).unwrap();
+		abci_events.push(AbciEvent::from(upgrade_init.clone()));
+		let upgrade_try = UpgradeTry::try_from(attributes.clone()).unwrap();
+		abci_events.push(AbciEvent::from(upgrade_try.clone()));
+		let upgrade_ack = UpgradeAck::try_from(attributes.clone()).unwrap();
+		abci_events.push(AbciEvent::from(upgrade_ack.clone()));
+		let upgrade_confirm = UpgradeConfirm::try_from(attributes.clone()).unwrap();
+		abci_events.push(AbciEvent::from(upgrade_confirm.clone()));
+		let upgrade_cancel = UpgradeCancel::try_from(attributes).unwrap();
+		abci_events.push(AbciEvent::from(upgrade_cancel.clone()));
 
 		for event in abci_events {
 			match try_from_tx(&event) {
@@ -1156,6 +1628,21 @@ mod tests {
 					IbcEvent::CloseConfirmChannel(e) => {
 						assert_eq!(Attributes::from(e), close_confirm.clone().into())
 					},
+					IbcEvent::ChannelUpgradeInit(e) => {
+						assert_eq!(Attributes::from(e), upgrade_init.clone().into())
+					},
+					IbcEvent::ChannelUpgradeTry(e) => {
+						assert_eq!(Attributes::from(e), upgrade_try.clone().into())
+					},
+					IbcEvent::ChannelUpgradeAck(e) => {
+						assert_eq!(Attributes::from(e), upgrade_ack.clone().into())
+					},
+					IbcEvent::ChannelUpgradeConfirm(e) => {
+						assert_eq!(Attributes::from(e), upgrade_confirm.clone().into())
+					},
+					IbcEvent::ChannelUpgradeCancel(e) => {
+						assert_eq!(Attributes::from(e), upgrade_cancel.clone().into())
+					},
 					_ => panic!("unexpected event type"),
 				},
 				None => panic!("converted event was wrong"),
@@ -1163,6 +1650,27 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn close_confirm_event_keeps_reason() {
+		let attributes = Attributes {
+			height: Height::default(),
+			port_id: "test_port".parse().unwrap(),
+			channel_id: Some("channel-0".parse().unwrap()),
+			connection_id: "test_connection".parse().unwrap(),
+			counterparty_port_id: "counterparty_test_port".parse().unwrap(),
+			counterparty_channel_id: Some("channel-1".parse().unwrap()),
+		};
+		let close_confirm = CloseConfirm {
+			reason: Some("client frozen".to_string()),
+			..CloseConfirm::try_from(attributes).unwrap()
+		};
+
+		match try_from_tx(&AbciEvent::from(close_confirm.clone())) {
+			Some(IbcEvent::CloseConfirmChannel(e)) => assert_eq!(e, close_confirm),
+			e => panic!("Expected CloseConfirmChannel event, instead got {:?}", e),
+		}
+	}
+
 	#[test]
 	fn packet_event_to_abci_event() {
 		let packet = Packet {
@@ -1176,7 +1684,11 @@ mod tests {
 			timeout_timestamp: Timestamp::now(),
 		};
 		let mut abci_events = vec![];
-		let send_packet = SendPacket { height: Height::default(), packet: packet.clone() };
+		let send_packet = SendPacket {
+			height: Height::default(),
+			packet: packet.clone(),
+			channel_ordering: Some(Order::OrderedAllowTimeout),
+		};
 		abci_events.push(AbciEvent::try_from(send_packet.clone()).unwrap());
 		let write_ack = WriteAcknowledgement {
 			height: Height::default(),
@@ -1192,7 +1704,7 @@ mod tests {
 		for event in abci_events {
 			match try_from_tx(&event) {
 				Some(e) => match e {
-					IbcEvent::SendPacket(e) => assert_eq!(e.packet, send_packet.packet),
+					IbcEvent::SendPacket(e) => assert_eq!(e, send_packet),
 					IbcEvent::WriteAcknowledgement(e) => {
 						assert_eq!(e.packet, write_ack.packet);
 						assert_eq!(e.ack, write_ack.ack);
@@ -1205,4 +1717,41 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn send_packet_event_without_channel_ordering() {
+		let send_packet = SendPacket {
+			height: Height::default(),
+			packet: Packet {
+				sequence: Sequence::from(3),
+				source_port: "a_test_port".parse().unwrap(),
+				source_channel: "channel-0".parse().unwrap(),
+				destination_port: "b_test_port".parse().unwrap(),
+				destination_channel: "channel-1".parse().unwrap(),
+				data: "test_data".as_bytes().to_vec(),
+				timeout_height: Height::new(1, 10),
+				timeout_timestamp: Timestamp::from_nanoseconds(1_000).unwrap(),
+			},
+			channel_ordering: None,
+		};
+		let abci_event = AbciEvent::try_from(send_packet.clone()).unwrap();
+		assert!(abci_event
+			.attributes
+			.iter()
+			.all(|attr| attr.key != PKT_CHANNEL_ORDERING_ATTRIBUTE_KEY));
+
+		match try_from_tx(&abci_event) {
+			Some(IbcEvent::SendPacket(e)) => {
+				assert_eq!(e, send_packet);
+				assert_eq!(e.packet.timeout_height, Height::new(1, 10));
+				assert_eq!(e.packet.timeout_timestamp.nanoseconds(), 1_000);
+			},
+			e => panic!("Expected SendPacket event, instead got {:?}", e),
+		}
+
+		// Events serialized before the ordering was recorded still deserialize.
+		let mut json = serde_json::to_value(&send_packet).unwrap();
+		json.as_object_mut().unwrap().remove("channel_ordering");
+		assert_eq!(serde_json::from_value::<SendPacket>(json).unwrap(), send_packet);
+	}
 }
@@ -17,10 +17,13 @@
 use crate::{
 	core::{
 		ics04_channel::{
-			channel::ChannelEnd,
+			channel::{ChannelEnd, Order},
 			error::Error,
-			msgs::{ChannelMsg, PacketMsg},
+			msgs::{
+				acknowledgement::Acknowledgement, recv_packet::MsgRecvPacket, ChannelMsg, PacketMsg,
+			},
 			packet::PacketResult,
+			upgrade::UpgradeOrigin,
 		},
 		ics24_host::identifier::{ChannelId, PortId},
 		ics26_routing::context::{
@@ -29,20 +32,23 @@ use crate::{
 	},
 	handler::{HandlerOutput, HandlerOutputBuilder},
 };
-use alloc::string::ToString;
+use alloc::{string::ToString, vec::Vec};
 use core::fmt::Debug;
 
 pub mod acknowledgement;
 pub mod chan_close_confirm;
+pub mod chan_close_frozen;
 pub mod chan_close_init;
 pub mod chan_open_ack;
 pub mod chan_open_confirm;
 pub mod chan_open_init;
 pub mod chan_open_try;
+pub mod prune_acknowledgements;
 pub mod recv_packet;
 pub mod send_packet;
 pub mod timeout;
 pub mod timeout_on_close;
+pub mod upgrade;
 pub mod verify;
 pub mod write_acknowledgement;
 
@@ -63,6 +69,8 @@ pub struct ChannelResult {
 	pub channel_id: ChannelId,
 	pub channel_id_state: ChannelIdState,
 	pub channel_end: ChannelEnd,
+	/// Set when the message starts an upgrade of the channel.
+	pub upgrade_origin: Option<UpgradeOrigin>,
 }
 
 pub fn channel_validate<Ctx>(ctx: &Ctx, msg: &ChannelMsg) -> Result<ModuleId, Error>
@@ -77,8 +85,8 @@ where
 	}
 }
 
-/// General entry point for processing any type of message related to the ICS4 channel open and
-/// channel close handshake protocols.
+/// General entry point for processing any type of message related to the ICS4 channel open,
+/// channel close and channel upgrade handshake protocols.
 pub fn channel_dispatch<Ctx>(
 	ctx: &Ctx,
 	msg: &ChannelMsg,
@@ -93,6 +101,12 @@ where
 		ChannelMsg::ChannelOpenConfirm(msg) => chan_open_confirm::process::<_>(ctx, msg),
 		ChannelMsg::ChannelCloseInit(msg) => chan_close_init::process(ctx, msg),
 		ChannelMsg::ChannelCloseConfirm(msg) => chan_close_confirm::process::<_>(ctx, msg),
+		ChannelMsg::ChannelCloseFrozen(msg) => chan_close_frozen::process(ctx, msg),
+		ChannelMsg::ChannelUpgradeInit(msg) => upgrade::process_init(ctx, msg),
+		ChannelMsg::ChannelUpgradeTry(msg) => upgrade::process_try::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeAck(msg) => upgrade::process_ack::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeConfirm(msg) => upgrade::process_confirm::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeCancel(msg) => upgrade::process_cancel::<_>(ctx, msg),
 	}?;
 	let HandlerOutput { result, log, events } = output;
 	let builder = HandlerOutput::builder().with_log(log).with_events(events);
@@ -169,6 +183,51 @@ where
 			&result.channel_id,
 			&msg.signer,
 		)?,
+		// The channel is closed exactly as if the counterparty had confirmed it.
+		ChannelMsg::ChannelCloseFrozen(msg) => cb.on_chan_close_confirm(
+			&ctx_clone,
+			module_output,
+			&msg.port_id,
+			&result.channel_id,
+			&msg.signer,
+		)?,
+		ChannelMsg::ChannelUpgradeInit(msg) => cb.on_chan_upgrade_init(
+			&ctx_clone,
+			module_output,
+			&msg.port_id,
+			&result.channel_id,
+			&msg.fields,
+			&msg.signer,
+		)?,
+		ChannelMsg::ChannelUpgradeTry(msg) => cb.on_chan_upgrade_try(
+			&ctx_clone,
+			module_output,
+			&msg.port_id,
+			&result.channel_id,
+			&msg.counterparty_upgrade_fields,
+			&msg.signer,
+		)?,
+		ChannelMsg::ChannelUpgradeAck(msg) => cb.on_chan_upgrade_open(
+			&ctx_clone,
+			module_output,
+			&msg.port_id,
+			&result.channel_id,
+			&msg.signer,
+		)?,
+		ChannelMsg::ChannelUpgradeConfirm(msg) => cb.on_chan_upgrade_open(
+			&ctx_clone,
+			module_output,
+			&msg.port_id,
+			&result.channel_id,
+			&msg.signer,
+		)?,
+		ChannelMsg::ChannelUpgradeCancel(msg) => cb.on_chan_upgrade_cancel(
+			&ctx_clone,
+			module_output,
+			&msg.port_id,
+			&result.channel_id,
+			&msg.signer,
+		)?,
 	}
 	Ok(result)
 }
@@ -187,6 +246,8 @@ where
 			ctx.lookup_module_by_port(&msg.packet.source_port).map_err(Error::ics05_port)?,
 		PacketMsg::ToClosePacket(msg) =>
 			ctx.lookup_module_by_port(&msg.packet.source_port).map_err(Error::ics05_port)?,
+		PacketMsg::PruneAcknowledgements(msg) =>
+			ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
 	};
 
 	if ctx.router().has_route(&module_id) {
@@ -209,18 +270,42 @@ where
 		PacketMsg::AckPacket(msg) => acknowledgement::process::<_>(ctx, msg),
 		PacketMsg::ToPacket(msg) => timeout::process::<_>(ctx, msg),
 		PacketMsg::ToClosePacket(msg) => timeout_on_close::process::<_>(ctx, msg),
+		PacketMsg::PruneAcknowledgements(msg) => prune_acknowledgements::process::<_>(ctx, msg),
 	}?;
 	let HandlerOutput { result, log, events } = output;
 	let builder = HandlerOutput::builder().with_log(log).with_events(events);
 	Ok((builder, result))
 }
 
+/// Processes a batch of `MsgRecvPacket`s, fetching the connection end, client state and
+/// consensus state once for all the packets proven through the same connection at the same
+/// height. Each result is the one `packet_dispatch` would return after the results of the
+/// successful messages before it have been stored.
+pub fn recv_packets<Ctx>(
+	ctx: &Ctx,
+	msgs: &[MsgRecvPacket],
+) -> Vec<Result<(HandlerOutputBuilder<()>, PacketResult), Error>>
+where
+	Ctx: ReaderContext,
+{
+	recv_packet::process_batch(ctx, msgs)
+		.into_iter()
+		.map(|output| {
+			let HandlerOutput { result, log, events } = output?;
+			let builder = HandlerOutput::builder().with_log(log).with_events(events);
+			Ok((builder, result))
+		})
+		.collect()
+}
+
+/// Runs the module callback for a packet message, returning the acknowledgement core IBC has to
+/// write for a received packet.
 pub fn packet_callback<Ctx>(
 	ctx: &mut Ctx,
 	module_id: &ModuleId,
 	msg: &PacketMsg,
 	module_output: &mut ModuleOutputBuilder,
-) -> Result<(), Error>
+) -> Result<Option<Acknowledgement>, Error>
 where
 	Ctx: Ics26Context,
 {
@@ -230,9 +315,19 @@ where
 
 	match msg {
 		PacketMsg::RecvPacket(msg) => {
-			cb.on_recv_packet(&ctx_clone, module_output, &msg.packet, &msg.signer)
+			let ack = cb
+				.on_recv_packet(&ctx_clone, module_output, &msg.packet, &msg.signer)
 				.map_err(|e| Error::app_module(e.to_string()))?;
+			return Ok(ack.into_acknowledgement())
 		},
+		// On `OrderedAllowTimeout` channels a packet that reached the counterparty after its
+		// timeout is acknowledged with a sentinel, which the module handles as a timeout.
+		PacketMsg::AckPacket(msg)
+			if msg.acknowledgement.is_timeout() &&
+				ctx_clone
+					.channel_end(&(msg.packet.source_port.clone(), msg.packet.source_channel))
+					.map_or(false, |c| c.order_matches(&Order::OrderedAllowTimeout)) =>
+			cb.on_timeout_packet(&ctx_clone, module_output, &msg.packet, &msg.signer)?,
 		PacketMsg::AckPacket(msg) => cb.on_acknowledgement_packet(
 			&ctx_clone,
 			module_output,
@@ -244,6 +339,8 @@ where
 			cb.on_timeout_packet(&ctx_clone, module_output, &msg.packet, &msg.signer)?,
 		PacketMsg::ToClosePacket(msg) =>
 			cb.on_timeout_packet(&ctx_clone, module_output, &msg.packet, &msg.signer)?,
+		// Pruning only touches core IBC state, the application module is not involved.
+		PacketMsg::PruneAcknowledgements(_) => {},
 	};
-	Ok(())
+	Ok(None)
 }
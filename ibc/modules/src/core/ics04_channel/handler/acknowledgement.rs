@@ -17,6 +17,7 @@ use crate::{
 		ics03_connection::connection::State as ConnectionState,
 		ics04_channel::{
 			channel::{Counterparty, Order, State},
+			commitment::compute_packet_commitment,
 			error::Error,
 			events::AcknowledgePacket,
 			handler::verify::verify_packet_acknowledgement_proofs,
@@ -74,18 +75,24 @@ pub fn process<Ctx: ReaderContext>(
 	}
 
 	// Verify packet commitment
-	let packet_commitment = ctx.get_packet_commitment(&(
+	let packet_commitment = match ctx.get_packet_commitment(&(
 		packet.source_port.clone(),
 		packet.source_channel,
 		packet.sequence,
-	))?;
-
-	if packet_commitment !=
-		ctx.packet_commitment(
-			packet.data.clone(),
-			packet.timeout_height,
-			packet.timeout_timestamp,
-		) {
+	)) {
+		Ok(commitment) => commitment,
+		// The commitment is deleted once the packet is acknowledged, so the packet has already
+		// been acknowledged, e.g. through a competing relayer.
+		Err(e) if e.detail() == Error::packet_commitment_not_found(packet.sequence).detail() => {
+			output.log("success: packet already acknowledged");
+			return Ok(output.with_result(PacketResult::NoOp))
+		},
+		Err(e) => return Err(e),
+	};
+
+	let expected_commitment =
+		compute_packet_commitment(&packet.data, &packet.timeout_height, &packet.timeout_timestamp);
+	if packet_commitment != expected_commitment {
 		return Err(Error::incorrect_packet_commitment(packet.sequence))
 	}
 
@@ -99,12 +106,17 @@ pub fn process<Ctx: ReaderContext>(
 		&msg.proofs,
 	)?;
 
-	let result = if source_channel_end.order_matches(&Order::Ordered) {
+	let result = if !source_channel_end.order_matches(&Order::Unordered) {
 		let next_seq_ack =
 			ctx.get_next_sequence_ack(&(packet.source_port.clone(), packet.source_channel))?;
 
 		if packet.sequence != next_seq_ack {
-			return Err(Error::invalid_packet_sequence(packet.sequence, next_seq_ack))
+			return Err(Error::invalid_packet_sequence(
+				packet.source_port.clone(),
+				packet.source_channel,
+				next_seq_ack,
+				packet.sequence,
+			))
 		}
 
 		PacketResult::Ack(AckPacketResult {
@@ -123,6 +135,11 @@ pub fn process<Ctx: ReaderContext>(
 	};
 
 	output.log("success: packet ack");
+	output.log_kv("packet_src_port", &packet.source_port);
+	output.log_kv("packet_src_channel", &packet.source_channel);
+	output.log_kv("packet_dst_port", &packet.destination_port);
+	output.log_kv("packet_dst_channel", &packet.destination_channel);
+	output.log_kv("packet_sequence", packet.sequence);
 
 	output.emit(IbcEvent::AcknowledgePacket(AcknowledgePacket {
 		height: ctx.host_height(),
@@ -147,11 +164,14 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				context::ChannelReader,
+				commitment::{compute_packet_commitment, PacketCommitment},
+				context::{ChannelKeeper, ChannelReader},
+				error,
 				handler::acknowledgement::process,
 				msgs::acknowledgement::{
 					test_util::get_dummy_raw_msg_acknowledgement, MsgAcknowledgement,
 				},
+				packet::PacketResult,
 				Version,
 			},
 			ics24_host::identifier::{ClientId, ConnectionId},
@@ -181,10 +201,10 @@ mod tests {
 		.unwrap();
 		let packet = msg.packet.clone();
 
-		let data = context.packet_commitment(
-			packet.data.clone(),
-			packet.timeout_height,
-			packet.timeout_timestamp,
+		let data = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
 		);
 
 		let source_channel_end = ChannelEnd::new(
@@ -275,4 +295,161 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn ack_packet_sequence_gap_on_ordered_channel() {
+		let context = MockContext::<MockClientTypes>::default();
+
+		let client_height = Height::new(0, Height::default().revision_height + 2);
+
+		let mut msg = MsgAcknowledgement::try_from(get_dummy_raw_msg_acknowledgement(
+			client_height.revision_height,
+		))
+		.unwrap();
+		msg.packet.sequence = 2.into();
+		let packet = msg.packet.clone();
+
+		let data = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+
+		let source_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let ctx = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(packet.source_port.clone(), packet.source_channel, source_channel_end)
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				data,
+			)
+			.with_ack_sequence(packet.source_port.clone(), packet.source_channel, 1.into());
+
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::InvalidPacketSequence(e) => {
+					assert_eq!(e.port_id, packet.source_port);
+					assert_eq!(e.channel_id, packet.source_channel);
+					assert_eq!(e.expected, 1.into());
+					assert_eq!(e.received, 2.into());
+				},
+				_ => panic!("Expected InvalidPacketSequence error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected InvalidPacketSequence error"),
+		}
+	}
+
+	/// A context with an open unordered channel for the dummy acknowledgement, holding
+	/// `commitment` for its packet if one is given.
+	fn context_with_commitment(
+		msg: &MsgAcknowledgement,
+		commitment: Option<PacketCommitment>,
+	) -> MockContext<MockClientTypes> {
+		let packet = &msg.packet;
+		let client_height = Height::new(0, Height::default().revision_height + 2);
+
+		let source_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(packet.source_port.clone(), packet.source_channel, source_channel_end);
+
+		match commitment {
+			Some(commitment) => ctx.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				commitment,
+			),
+			None => ctx,
+		}
+	}
+
+	#[test]
+	fn ack_packet_twice_is_a_noop() {
+		let msg = MsgAcknowledgement::try_from(get_dummy_raw_msg_acknowledgement(
+			Height::default().revision_height + 2,
+		))
+		.unwrap();
+		let packet = msg.packet.clone();
+		let commitment = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+		let mut ctx = context_with_commitment(&msg, Some(commitment));
+
+		let output = process(&ctx, &msg).unwrap();
+		assert!(matches!(output.result, PacketResult::Ack(_)));
+		ctx.store_packet_result(output.result).unwrap();
+		assert!(ctx
+			.get_packet_commitment(&(packet.source_port, packet.source_channel, packet.sequence))
+			.is_err());
+
+		// The commitment is gone, so a second delivery of the same acknowledgement succeeds
+		// without doing anything.
+		let output = process(&ctx, &msg).unwrap();
+		assert!(matches!(output.result, PacketResult::NoOp));
+		assert!(output.events.is_empty());
+	}
+
+	#[test]
+	fn ack_packet_with_mismatched_commitment() {
+		let msg = MsgAcknowledgement::try_from(get_dummy_raw_msg_acknowledgement(
+			Height::default().revision_height + 2,
+		))
+		.unwrap();
+		let ctx = context_with_commitment(&msg, Some(vec![0u8; 32].into()));
+
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::IncorrectPacketCommitment(e) => {
+					assert_eq!(e.sequence, msg.packet.sequence);
+				},
+				_ => panic!("Expected IncorrectPacketCommitment error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected IncorrectPacketCommitment error"),
+		}
+	}
 }
@@ -0,0 +1,233 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelCloseFrozen`.
+//!
+//! A frozen client can no longer verify the counterparty's state, so the channels built on it
+//! are closed without a proof. Funds escrowed on them can then be refunded through timeouts on
+//! close instead of piling up behind a channel that will never relay again.
+
+use crate::{
+	core::{
+		ics02_client::client_state::ClientState,
+		ics04_channel::{
+			channel::State,
+			error::Error,
+			events::{Attributes, CloseConfirm},
+			handler::{ChannelIdState, ChannelResult},
+			msgs::chan_close_frozen::MsgChannelCloseFrozen,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelCloseFrozen,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let mut channel_end = ctx.channel_end(&(msg.port_id.clone(), msg.channel_id))?;
+
+	if channel_end.state_matches(&State::Closed) {
+		return Err(Error::channel_closed(msg.channel_id))
+	}
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let connection_id = channel_end.connection_hops()[0].clone();
+	let conn = ctx.connection_end(&connection_id).map_err(Error::ics03_connection)?;
+
+	if conn.client_id() != &msg.client_id {
+		return Err(Error::connection_client_mismatch(
+			connection_id,
+			conn.client_id().clone(),
+			msg.client_id.clone(),
+		))
+	}
+
+	let client_state = ctx.client_state(&msg.client_id).map_err(Error::ics02_client)?;
+	if !client_state.is_frozen() {
+		return Err(Error::client_not_frozen(msg.client_id.clone()))
+	}
+
+	output.log("success: channel closed on frozen client");
+
+	channel_end.set_state(State::Closed);
+
+	let event_attributes = Attributes {
+		channel_id: Some(msg.channel_id),
+		height: ctx.host_height(),
+		port_id: msg.port_id.clone(),
+		connection_id,
+		counterparty_port_id: channel_end.counterparty().port_id.clone(),
+		counterparty_channel_id: channel_end.counterparty().channel_id.clone(),
+	};
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade_origin: None,
+	};
+
+	let event = CloseConfirm::try_from(event_attributes).map_err(|_| Error::missing_channel_id())?;
+	output.emit(IbcEvent::CloseConfirmChannel(CloseConfirm {
+		reason: Some(format!("client {} is frozen", msg.client_id)),
+		..event
+	}));
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use core::str::FromStr;
+	use test_log::test;
+
+	use crate::{
+		core::{
+			ics02_client::context::{ClientKeeper, ClientReader},
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				msgs::test_util::get_dummy_raw_counterparty,
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				error,
+				handler::channel_dispatch,
+				msgs::{
+					chan_close_frozen::{
+						test_util::get_dummy_raw_msg_chan_close_frozen, MsgChannelCloseFrozen,
+					},
+					ChannelMsg,
+				},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		},
+		events::IbcEvent,
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+			header::MockHeader,
+		},
+		timestamp::ZERO_DURATION,
+		Height,
+	};
+
+	/// A context holding an open channel on the default port and channel ids, whose connection
+	/// is tracked by `client_id`. The client is frozen when `frozen` is set.
+	fn context_with_channel(client_id: &ClientId, frozen: bool) -> MockContext<MockClientTypes> {
+		let conn_id = ConnectionId::new(2);
+		let conn_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			client_id.clone(),
+			ConnectionCounterparty::try_from(get_dummy_raw_counterparty()).unwrap(),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let chan_end = ChannelEnd::new(
+			State::Open,
+			Order::default(),
+			Counterparty::new(PortId::default(), Some(ChannelId::default())),
+			vec![conn_id.clone()],
+			Version::default(),
+		);
+
+		let default_context = MockContext::<MockClientTypes>::default();
+		let client_height = default_context.host_height();
+		let mut ctx = default_context
+			.with_client(client_id, client_height)
+			.with_connection(conn_id, conn_end)
+			.with_channel(PortId::default(), ChannelId::default(), chan_end);
+		if frozen {
+			let client_state = MockClientState {
+				header: MockHeader::new(client_height),
+				frozen_height: Some(Height::new(0, 1)),
+			};
+			ctx.store_client_state(client_id.clone(), client_state.into()).unwrap();
+		}
+		ctx
+	}
+
+	#[test]
+	fn chan_close_frozen_closes_channel() {
+		let client_id = ClientId::from_str("mockclient1").unwrap();
+		let ctx = context_with_channel(&client_id, true);
+		let msg = MsgChannelCloseFrozen::try_from(get_dummy_raw_msg_chan_close_frozen(&client_id))
+			.unwrap();
+
+		let (output, result) =
+			channel_dispatch(&ctx, &ChannelMsg::ChannelCloseFrozen(msg)).unwrap();
+		assert!(result.channel_end.state_matches(&State::Closed));
+
+		let output = output.with_result(());
+		match &output.events[0] {
+			IbcEvent::CloseConfirmChannel(e) => {
+				assert_eq!(e.height(), ctx.host_height());
+				assert_eq!(e.reason, Some(format!("client {} is frozen", client_id)));
+			},
+			e => panic!("Expected CloseConfirmChannel event, instead got {:?}", e),
+		}
+	}
+
+	#[test]
+	fn chan_close_frozen_rejects_active_client() {
+		let client_id = ClientId::from_str("mockclient1").unwrap();
+		let ctx = context_with_channel(&client_id, false);
+		let msg = MsgChannelCloseFrozen::try_from(get_dummy_raw_msg_chan_close_frozen(&client_id))
+			.unwrap();
+
+		match channel_dispatch(&ctx, &ChannelMsg::ChannelCloseFrozen(msg)) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::ClientNotFrozen(e) => assert_eq!(e.client_id, client_id),
+				_ => panic!("Expected ClientNotFrozen error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ClientNotFrozen error"),
+		}
+	}
+
+	#[test]
+	fn chan_close_frozen_rejects_other_client() {
+		let client_id = ClientId::from_str("mockclient1").unwrap();
+		let other_client_id = ClientId::from_str("mockclient2").unwrap();
+		let ctx = context_with_channel(&client_id, true);
+		let msg =
+			MsgChannelCloseFrozen::try_from(get_dummy_raw_msg_chan_close_frozen(&other_client_id))
+				.unwrap();
+
+		match channel_dispatch(&ctx, &ChannelMsg::ChannelCloseFrozen(msg)) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::ConnectionClientMismatch(e) => {
+					assert_eq!(e.expected, client_id);
+					assert_eq!(e.actual, other_client_id);
+				},
+				_ => panic!("Expected ConnectionClientMismatch error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ConnectionClientMismatch error"),
+		}
+	}
+}
@@ -57,6 +57,10 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		return Err(Error::connection_not_open(channel_end.connection_hops()[0].clone()))
 	}
 
+	if !ctx.is_close_authorized(&msg.port_id, &msg.channel_id, &msg.signer) {
+		return Err(Error::unauthorized_channel_close(msg.port_id.clone(), msg.channel_id))
+	}
+
 	output.log("success: channel close init ");
 
 	// Transition the channel end to the new state & pick a version.
@@ -76,6 +80,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		channel_id: msg.channel_id,
 		channel_id_state: ChannelIdState::Reused,
 		channel_end,
+		upgrade_origin: None,
 	};
 
 	output.emit(IbcEvent::CloseInitChannel(
@@ -106,6 +111,7 @@ mod tests {
 		},
 		ics04_channel::{
 			channel::{ChannelEnd, Counterparty, Order, State as ChannelState},
+			error,
 			handler::channel_dispatch,
 			Version,
 		},
@@ -173,4 +179,57 @@ mod tests {
 			assert_eq!(event.height(), context.host_height());
 		}
 	}
+
+	#[test]
+	fn chan_close_init_respects_close_authority() {
+		let client_id = ClientId::new(&MockClientState::client_type(), 24).unwrap();
+		let conn_id = ConnectionId::new(2);
+
+		let conn_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			client_id.clone(),
+			ConnectionCounterparty::try_from(get_dummy_raw_counterparty()).unwrap(),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let msg = MsgChannelCloseInit::try_from(get_dummy_raw_msg_chan_close_init()).unwrap();
+
+		let chan_end = ChannelEnd::new(
+			ChannelState::Open,
+			Order::default(),
+			Counterparty::new(msg.port_id.clone(), Some(msg.channel_id)),
+			vec![conn_id.clone()],
+			Version::default(),
+		);
+
+		let default_context = MockContext::<MockClientTypes>::default();
+		let client_consensus_state_height = default_context.host_height();
+		let context = default_context
+			.with_client(&client_id, client_consensus_state_height)
+			.with_connection(conn_id, conn_end)
+			.with_channel(msg.port_id.clone(), msg.channel_id, chan_end);
+
+		// The port owner may close the channel.
+		let authorized = context
+			.clone()
+			.with_channel_close_authority(msg.port_id.clone(), msg.signer.clone());
+		let (_, result) =
+			channel_dispatch(&authorized, &ChannelMsg::ChannelCloseInit(msg.clone())).unwrap();
+		assert!(result.channel_end.state_matches(&ChannelState::Closed));
+
+		// Any other signer is rejected.
+		let other_signer = "someoneelse".parse().unwrap();
+		let rejected = context.with_channel_close_authority(msg.port_id.clone(), other_signer);
+		match channel_dispatch(&rejected, &ChannelMsg::ChannelCloseInit(msg.clone())) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::UnauthorizedChannelClose(e) => {
+					assert_eq!(e.port_id, msg.port_id);
+					assert_eq!(e.channel_id, msg.channel_id);
+				},
+				_ => panic!("Expected UnauthorizedChannelClose error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected UnauthorizedChannelClose error"),
+		}
+	}
 }
@@ -85,6 +85,7 @@ pub(crate) fn process<Ctx: ReaderContext>(
 		port_id: msg.port_id.clone(),
 		channel_id: chan_id,
 		channel_end: new_channel_end,
+		upgrade_origin: None,
 		channel_id_state: ChannelIdState::Generated,
 	};
 
@@ -112,7 +113,7 @@ mod tests {
 				version::get_compatible_versions,
 			},
 			ics04_channel::{
-				channel::State,
+				channel::{Order, State},
 				handler::channel_dispatch,
 				msgs::{
 					chan_open_init::{
@@ -154,6 +155,9 @@ mod tests {
 
 		let cid = ConnectionId::default();
 
+		let mut msg_chan_init_allow_timeout = msg_chan_init.clone();
+		msg_chan_init_allow_timeout.channel.ordering = Order::OrderedAllowTimeout;
+
 		let tests: Vec<Test> = vec![
 			Test {
 				name: "Processing fails because no connection exists in the context".to_string(),
@@ -163,10 +167,16 @@ mod tests {
 			},
 			Test {
 				name: "Good parameters".to_string(),
-				ctx: context.with_connection(cid, init_conn_end),
+				ctx: context.clone().with_connection(cid.clone(), init_conn_end.clone()),
 				msg: ChannelMsg::ChannelOpenInit(msg_chan_init),
 				want_pass: true,
 			},
+			Test {
+				name: "Good parameters for an ordered channel that allows timeouts".to_string(),
+				ctx: context.with_connection(cid, init_conn_end),
+				msg: ChannelMsg::ChannelOpenInit(msg_chan_init_allow_timeout),
+				want_pass: true,
+			},
 		]
 		.into_iter()
 		.collect();
@@ -114,8 +114,12 @@ where
 	)?;
 
 	output.log("success: channel open try ");
+	output.log_kv("port_id", &msg.port_id);
+	output.log_kv("channel_id", &channel_id);
+	output.log_kv("connection_id", &msg.channel.connection_hops()[0]);
 
-	// Transition the channel end to the new state & pick a version.
+	// Transition the channel end to the new state. The counterparty's version is only a
+	// placeholder, the channel end gets the version returned by the module's `on_chan_open_try`.
 	new_channel_end.set_state(State::TryOpen);
 
 	let event_attributes = Attributes {
@@ -132,6 +136,7 @@ where
 		channel_id_state: ChannelIdState::Generated,
 		channel_id,
 		channel_end: new_channel_end,
+		upgrade_origin: None,
 	};
 
 	output.emit(IbcEvent::OpenTryChannel(
@@ -159,7 +164,7 @@ mod tests {
 				version::get_compatible_versions,
 			},
 			ics04_channel::{
-				channel::{ChannelEnd, State},
+				channel::{ChannelEnd, Order, State},
 				error,
 				handler::channel_dispatch,
 				msgs::{
@@ -169,12 +174,14 @@ mod tests {
 					ChannelMsg,
 				},
 			},
-			ics24_host::identifier::{ChannelId, ClientId, ConnectionId},
+			ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId},
 		},
 		events::IbcEvent,
 		mock::{
+			builder::MockContextBuilder,
 			client_state::MockClientState,
 			context::{MockClientTypes, MockContext},
+			host::MockHostType,
 		},
 		timestamp::ZERO_DURATION,
 		Height,
@@ -198,7 +205,20 @@ mod tests {
 		// The context. We'll reuse this same one across all tests.
 		let context = MockContext::default();
 
-		// This is the connection underlying the channel we're trying to open.
+		// The same context, holding the connection underlying the channel we're trying to open
+		// over a client that tracks the counterparty at the proof height.
+		let counterparty = MockContext::new(
+			ChainId::new("mockcounterparty".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			Height::new(0, proof_height),
+		);
+		let context_with_connection = MockContextBuilder::new(context.clone())
+			.with_counterparty(counterparty)
+			.with_open_connection(client_id.clone(), conn_id.clone())
+			.build();
+
+		// A connection whose client is missing from the context.
 		let conn_end = ConnectionEnd::new(
 			ConnectionState::Open,
 			client_id.clone(),
@@ -220,6 +240,9 @@ mod tests {
 		// This message does not assume a channel should already be initialized.
 		let msg_vanilla = msg.clone();
 
+		let mut msg_allow_timeout = msg_vanilla.clone();
+		msg_allow_timeout.channel.ordering = Order::OrderedAllowTimeout;
+
 		// A preloaded channel end that resides in the context. This is constructed so as to be
 		// consistent with the incoming ChanOpenTry message `msg`.
 		let correct_chan_end = ChannelEnd::new(
@@ -256,8 +279,7 @@ mod tests {
 			Test {
 				name: "Processing fails b/c the context has no client state".to_string(),
 				ctx: context
-					.clone()
-					.with_connection(conn_id.clone(), conn_end.clone())
+					.with_connection(conn_id, conn_end)
 					.with_channel(msg.port_id.clone(), chan_id, correct_chan_end.clone()),
 				msg: ChannelMsg::ChannelOpenTry(msg.clone()),
 				want_pass: false,
@@ -280,11 +302,11 @@ mod tests {
 			},
 			Test {
 				name: "Processing is successful".to_string(),
-				ctx: context
-					.clone()
-					.with_client(&client_id, Height::new(0, proof_height))
-					.with_connection(conn_id.clone(), conn_end.clone())
-					.with_channel(msg.port_id.clone(), chan_id, correct_chan_end),
+				ctx: context_with_connection.clone().with_channel(
+					msg.port_id.clone(),
+					chan_id,
+					correct_chan_end,
+				),
 				msg: ChannelMsg::ChannelOpenTry(msg),
 				want_pass: true,
 				match_error: Box::new(|_| {}),
@@ -292,13 +314,19 @@ mod tests {
 			Test {
 				name: "Processing is successful against an empty context (no preexisting channel)"
 					.to_string(),
-				ctx: context
-					.with_client(&client_id, Height::new(0, proof_height))
-					.with_connection(conn_id, conn_end),
+				ctx: context_with_connection.clone(),
 				msg: ChannelMsg::ChannelOpenTry(msg_vanilla),
 				want_pass: true,
 				match_error: Box::new(|_| {}),
 			},
+			Test {
+				name: "Processing is successful for an ordered channel that allows timeouts"
+					.to_string(),
+				ctx: context_with_connection,
+				msg: ChannelMsg::ChannelOpenTry(msg_allow_timeout),
+				want_pass: true,
+				match_error: Box::new(|_| {}),
+			},
 		]
 		.into_iter()
 		.collect();
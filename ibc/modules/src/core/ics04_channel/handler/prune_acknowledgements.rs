@@ -0,0 +1,259 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to processing ICS4 messages of type `MsgPruneAcknowledgements`.
+//!
+//! Receipts and acknowledgements are pruned in sequence order, starting from the channel's
+//! pruning sequence start, and stop at the first sequence that has not been received.
+//!
+//! Only `Closed` channels are pruned. This chain can't tell whether the counterparty processed
+//! an acknowledgement, and on an open channel a pruned receipt would let the sender prove the
+//! packet was never received. `recv_packet` rejects sequences below the pruning sequence start.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::{Order, State},
+			error::Error,
+			events::AcknowledgementsPruned,
+			msgs::prune_acknowledgements::MsgPruneAcknowledgements,
+			packet::{PacketResult, Sequence},
+		},
+		ics24_host::identifier::{ChannelId, PortId},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+#[derive(Clone, Debug)]
+pub struct PruneAcknowledgementsResult {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	/// Sequences whose receipt and acknowledgement should be deleted.
+	pub sequences: Vec<Sequence>,
+	/// The first sequence left in place, where the next prune resumes.
+	pub pruning_sequence_start: Sequence,
+}
+
+pub fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgPruneAcknowledgements,
+) -> HandlerResult<PacketResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let channel_end = ctx.channel_end(&port_channel_id)?;
+
+	// Ordered channels track a single receive sequence instead of per-packet receipts.
+	if !channel_end.order_matches(&Order::Unordered) {
+		return Err(Error::prune_ordered_channel(msg.channel_id))
+	}
+
+	if !channel_end.state_matches(&State::Closed) {
+		return Err(Error::prune_channel_not_closed(msg.channel_id, *channel_end.state()))
+	}
+
+	let mut sequences = Vec::new();
+	let mut seq = ctx.get_pruning_sequence_start(&port_channel_id)?;
+	while (sequences.len() as u64) < msg.limit {
+		let key = (msg.port_id.clone(), msg.channel_id, seq);
+		let received = ctx.get_packet_receipt(&key).is_ok();
+		let acked = ctx.get_packet_acknowledgement(&key).is_ok();
+		if !received && !acked {
+			break
+		}
+		sequences.push(seq);
+		seq = seq.increment();
+	}
+
+	output.log(format!("success: pruned {} sequences", sequences.len()));
+
+	let event = AcknowledgementsPruned {
+		height: ctx.host_height(),
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		total_pruned_sequences: sequences.len() as u64,
+	};
+
+	let result = PacketResult::Prune(PruneAcknowledgementsResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		sequences,
+		pruning_sequence_start: seq,
+	});
+
+	output.emit(IbcEvent::AcknowledgementsPruned(event));
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				context::{ChannelKeeper, ChannelReader},
+				error,
+				handler::packet_dispatch,
+				msgs::{
+					prune_acknowledgements::{
+						test_util::get_dummy_raw_msg_prune_acknowledgements,
+						MsgPruneAcknowledgements,
+					},
+					PacketMsg,
+				},
+				packet::{Receipt, Sequence},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+	};
+
+	/// A context holding a channel on the default port and channel ids, with a receipt for each
+	/// of `received` and an acknowledgement for each of `acked`.
+	fn context_with_packets(
+		state: State,
+		ordering: Order,
+		received: &[u64],
+		acked: &[u64],
+	) -> MockContext<MockClientTypes> {
+		let chan_end = ChannelEnd::new(
+			state,
+			ordering,
+			Counterparty::new(PortId::default(), Some(ChannelId::default())),
+			vec![ConnectionId::default()],
+			Version::default(),
+		);
+		let mut ctx = MockContext::<MockClientTypes>::default().with_channel(
+			PortId::default(),
+			ChannelId::default(),
+			chan_end,
+		);
+		for seq in received {
+			ctx.store_packet_receipt(
+				(PortId::default(), ChannelId::default(), Sequence::from(*seq)),
+				Receipt::Ok,
+			)
+			.unwrap();
+		}
+		for seq in acked {
+			ctx.store_packet_acknowledgement(
+				(PortId::default(), ChannelId::default(), Sequence::from(*seq)),
+				vec![1u8].into(),
+			)
+			.unwrap();
+		}
+		ctx
+	}
+
+	fn prune(ctx: &mut MockContext<MockClientTypes>, limit: u64) -> u64 {
+		let msg =
+			MsgPruneAcknowledgements::try_from(get_dummy_raw_msg_prune_acknowledgements(limit))
+				.unwrap();
+		let (output, result) =
+			packet_dispatch(&*ctx, &PacketMsg::PruneAcknowledgements(msg)).unwrap();
+		let output = output.with_result(());
+		ctx.store_packet_result(result).unwrap();
+		match &output.events[0] {
+			IbcEvent::AcknowledgementsPruned(e) => {
+				assert_eq!(e.height(), ctx.host_height());
+				e.total_pruned_sequences
+			},
+			e => panic!("Expected AcknowledgementsPruned event, instead got {:?}", e),
+		}
+	}
+
+	fn has_receipt(ctx: &MockContext<MockClientTypes>, seq: u64) -> bool {
+		ctx.get_packet_receipt(&(PortId::default(), ChannelId::default(), seq.into())).is_ok()
+	}
+
+	#[test]
+	fn prune_rejects_open_channel() {
+		let seqs = [1, 2, 3];
+		let ctx = context_with_packets(State::Open, Order::Unordered, &seqs, &seqs);
+		let msg = MsgPruneAcknowledgements::try_from(get_dummy_raw_msg_prune_acknowledgements(10))
+			.unwrap();
+
+		// Acknowledged here doesn't mean the acknowledgement was relayed back, live receipts stay.
+		match packet_dispatch(&ctx, &PacketMsg::PruneAcknowledgements(msg)) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::PruneChannelNotClosed(e) => {
+					assert_eq!(e.channel_id, ChannelId::default());
+					assert_eq!(e.state, State::Open);
+				},
+				_ => panic!("Expected PruneChannelNotClosed error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected PruneChannelNotClosed error"),
+		}
+		assert!(seqs.iter().all(|seq| has_receipt(&ctx, *seq)));
+	}
+
+	#[test]
+	fn prune_stops_at_unreceived_sequence() {
+		let mut ctx = context_with_packets(State::Closed, Order::Unordered, &[1, 2, 4], &[1, 4]);
+
+		assert_eq!(prune(&mut ctx, 10), 2);
+		assert!(!has_receipt(&ctx, 2));
+		assert!(has_receipt(&ctx, 4));
+		assert_eq!(
+			ctx.get_pruning_sequence_start(&(PortId::default(), ChannelId::default())).unwrap(),
+			Sequence::from(3)
+		);
+	}
+
+	#[test]
+	fn prune_respects_limit() {
+		let seqs = [1, 2, 3, 4, 5];
+		let mut ctx = context_with_packets(State::Closed, Order::Unordered, &seqs, &seqs);
+
+		assert_eq!(prune(&mut ctx, 2), 2);
+		assert!(!has_receipt(&ctx, 2));
+		assert!(has_receipt(&ctx, 3));
+		assert_eq!(
+			ctx.get_pruning_sequence_start(&(PortId::default(), ChannelId::default())).unwrap(),
+			Sequence::from(3)
+		);
+
+		assert_eq!(prune(&mut ctx, 2), 2);
+		assert_eq!(prune(&mut ctx, 2), 1);
+		assert!(!has_receipt(&ctx, 5));
+	}
+
+	#[test]
+	fn prune_rejects_ordered_channel() {
+		let ctx = context_with_packets(State::Open, Order::Ordered, &[], &[]);
+		let msg = MsgPruneAcknowledgements::try_from(get_dummy_raw_msg_prune_acknowledgements(10))
+			.unwrap();
+
+		match packet_dispatch(&ctx, &PacketMsg::PruneAcknowledgements(msg)) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::PruneOrderedChannel(e) => {
+					assert_eq!(e.channel_id, ChannelId::default());
+				},
+				_ => panic!("Expected PruneOrderedChannel error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected PruneOrderedChannel error"),
+		}
+	}
+}
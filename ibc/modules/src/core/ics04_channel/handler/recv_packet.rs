@@ -14,16 +14,17 @@
 
 use crate::{
 	core::{
-		ics03_connection::connection::State as ConnectionState,
+		ics03_connection::connection::{ConnectionEnd, State as ConnectionState},
 		ics04_channel::{
 			channel::{Counterparty, Order, State},
+			commitment::{compute_ack_commitment, AcknowledgementCommitment},
 			error::Error,
-			events::ReceivePacket,
-			handler::verify::verify_packet_recv_proofs,
-			msgs::recv_packet::MsgRecvPacket,
+			events::{ReceivePacket, WriteAcknowledgement},
+			handler::verify::{proof_verification_states, verify_packet_recv_proofs_with_states},
+			msgs::{acknowledgement::Acknowledgement, recv_packet::MsgRecvPacket},
 			packet::{Packet, PacketResult, Receipt, Sequence},
 		},
-		ics24_host::identifier::{ChannelId, PortId},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 		ics26_routing::context::ReaderContext,
 	},
 	events::IbcEvent,
@@ -32,11 +33,11 @@ use crate::{
 	timestamp::Expiry,
 	Height,
 };
+use alloc::collections::{BTreeMap, BTreeSet};
 use core::fmt::Debug;
 
 #[derive(Clone, Debug)]
 pub enum RecvPacketResult {
-	NoOp,
 	Unordered {
 		port_id: PortId,
 		channel_id: ChannelId,
@@ -50,11 +51,131 @@ pub enum RecvPacketResult {
 		next_seq_recv: Sequence,
 		packet: Packet,
 	},
+	/// A packet that arrived after its timeout on an `OrderedAllowTimeout` channel. It is not
+	/// handed to the module, the sentinel timeout acknowledgement is written instead.
+	Timeout {
+		port_id: PortId,
+		channel_id: ChannelId,
+		next_seq_recv: Sequence,
+		ack_commitment: AcknowledgementCommitment,
+		packet: Packet,
+	},
+}
+
+/// Reads shared by the packets of a batch, along with the effect of the packets already
+/// processed, so that each packet sees the state it would after delivering the earlier ones.
+struct RecvPacketBatch<Ctx: ReaderContext> {
+	connections: BTreeMap<ConnectionId, ConnectionEnd>,
+	proof_states: BTreeMap<(ConnectionId, Height), (Ctx::AnyClientState, Ctx::AnyConsensusState)>,
+	next_seq_recv: BTreeMap<(PortId, ChannelId), Sequence>,
+	receipts: BTreeSet<(PortId, ChannelId, Sequence)>,
+}
+
+impl<Ctx: ReaderContext> RecvPacketBatch<Ctx> {
+	fn new() -> Self {
+		Self {
+			connections: BTreeMap::new(),
+			proof_states: BTreeMap::new(),
+			next_seq_recv: BTreeMap::new(),
+			receipts: BTreeSet::new(),
+		}
+	}
+
+	fn connection_end(
+		&mut self,
+		ctx: &Ctx,
+		connection_id: &ConnectionId,
+	) -> Result<ConnectionEnd, Error> {
+		if let Some(connection_end) = self.connections.get(connection_id) {
+			return Ok(connection_end.clone())
+		}
+		let connection_end = ctx.connection_end(connection_id).map_err(Error::ics03_connection)?;
+		self.connections.insert(connection_id.clone(), connection_end.clone());
+		Ok(connection_end)
+	}
+
+	fn verify_proofs(
+		&mut self,
+		ctx: &Ctx,
+		connection_id: &ConnectionId,
+		connection_end: &ConnectionEnd,
+		msg: &MsgRecvPacket,
+	) -> Result<(), Error> {
+		let key = (connection_id.clone(), msg.proofs.height());
+		if !self.proof_states.contains_key(&key) {
+			let states = proof_verification_states(ctx, connection_end, msg.proofs.height())?;
+			self.proof_states.insert(key.clone(), states);
+		}
+		let (client_state, consensus_state) = &self.proof_states[&key];
+		verify_packet_recv_proofs_with_states::<Ctx>(
+			ctx,
+			client_state,
+			consensus_state,
+			msg.proofs.height(),
+			&msg.packet,
+			connection_end,
+			&msg.proofs,
+		)
+	}
+
+	fn next_seq_recv(
+		&self,
+		ctx: &Ctx,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, Error> {
+		match self.next_seq_recv.get(port_channel_id) {
+			Some(seq) => Ok(*seq),
+			None => ctx.get_next_sequence_recv(port_channel_id),
+		}
+	}
+
+	/// Records the effect of a successfully processed packet for the packets after it.
+	fn record(&mut self, result: &PacketResult) {
+		match result {
+			PacketResult::Recv(
+				RecvPacketResult::Ordered { port_id, channel_id, next_seq_recv, .. } |
+				RecvPacketResult::Timeout { port_id, channel_id, next_seq_recv, .. },
+			) => {
+				self.next_seq_recv.insert((port_id.clone(), *channel_id), *next_seq_recv);
+			},
+			PacketResult::Recv(RecvPacketResult::Unordered {
+				port_id, channel_id, sequence, ..
+			}) => {
+				self.receipts.insert((port_id.clone(), *channel_id, *sequence));
+			},
+			_ => {},
+		}
+	}
 }
 
 pub fn process<Ctx: ReaderContext>(
 	ctx: &Ctx,
 	msg: &MsgRecvPacket,
+) -> HandlerResult<PacketResult, Error> {
+	process_in_batch(ctx, msg, &mut RecvPacketBatch::new())
+}
+
+/// Processes the messages in order, with the same results as processing each one after storing
+/// the results of the successful ones before it. Connection ends, client states and consensus
+/// states are fetched once for all the packets sharing a connection and proof height.
+pub fn process_batch<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msgs: &[MsgRecvPacket],
+) -> Vec<HandlerResult<PacketResult, Error>> {
+	let mut batch = RecvPacketBatch::new();
+	msgs.iter()
+		.map(|msg| {
+			let output = process_in_batch(ctx, msg, &mut batch)?;
+			batch.record(&output.result);
+			Ok(output)
+		})
+		.collect()
+}
+
+fn process_in_batch<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgRecvPacket,
+	batch: &mut RecvPacketBatch<Ctx>,
 ) -> HandlerResult<PacketResult, Error> {
 	let mut output = HandlerOutput::builder();
 
@@ -76,60 +197,89 @@ pub fn process<Ctx: ReaderContext>(
 		))
 	}
 
-	let connection_end = ctx
-		.connection_end(&dest_channel_end.connection_hops()[0])
-		.map_err(Error::ics03_connection)?;
+	let connection_id = dest_channel_end.connection_hops()[0].clone();
+	let connection_end = batch.connection_end(ctx, &connection_id)?;
 
 	if !connection_end.state_matches(&ConnectionState::Open) {
-		return Err(Error::connection_not_open(dest_channel_end.connection_hops()[0].clone()))
+		return Err(Error::connection_not_open(connection_id))
 	}
 
 	let latest_height = ctx.host_height();
-	if (!packet.timeout_height.is_zero()) && (packet.timeout_height <= latest_height) {
-		return Err(Error::low_packet_height(latest_height, packet.timeout_height))
-	}
-
+	let height_expired =
+		(!packet.timeout_height.is_zero()) && (packet.timeout_height <= latest_height);
 	let latest_timestamp = ctx.host_timestamp();
-	if let Expiry::Expired = latest_timestamp.check_expiry(&packet.timeout_timestamp) {
-		return Err(Error::low_packet_timestamp())
+	let timestamp_expired =
+		matches!(latest_timestamp.check_expiry(&packet.timeout_timestamp), Expiry::Expired);
+
+	// Only `OrderedAllowTimeout` channels accept a packet past its timeout, to record it as
+	// timed out.
+	let timed_out = height_expired || timestamp_expired;
+	if !dest_channel_end.order_matches(&Order::OrderedAllowTimeout) {
+		if height_expired {
+			return Err(Error::low_packet_height(latest_height, packet.timeout_height))
+		}
+		if timestamp_expired {
+			return Err(Error::low_packet_timestamp())
+		}
 	}
 
-	verify_packet_recv_proofs::<Ctx>(
-		ctx,
-		msg.proofs.height(),
-		packet,
-		&connection_end,
-		&msg.proofs,
-	)?;
+	batch.verify_proofs(ctx, &connection_id, &connection_end, msg)?;
 
-	let result = if dest_channel_end.order_matches(&Order::Ordered) {
-		let next_seq_recv = ctx.get_next_sequence_recv(&(
-			packet.destination_port.clone(),
-			packet.destination_channel,
-		))?;
+	let mut timeout_ack = None;
+	let result = if !dest_channel_end.order_matches(&Order::Unordered) {
+		let next_seq_recv = batch
+			.next_seq_recv(ctx, &(packet.destination_port.clone(), packet.destination_channel))?;
 
 		if packet.sequence < next_seq_recv {
 			output.emit(IbcEvent::ReceivePacket(ReceivePacket {
 				height: Height::zero(),
 				packet: msg.packet.clone(),
 			}));
-			return Ok(output.with_result(PacketResult::Recv(RecvPacketResult::NoOp)))
+			return Ok(output.with_result(PacketResult::NoOp))
 		} else if packet.sequence != next_seq_recv {
-			return Err(Error::invalid_packet_sequence(packet.sequence, next_seq_recv))
+			return Err(Error::invalid_packet_sequence(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				next_seq_recv,
+				packet.sequence,
+			))
 		}
 
-		PacketResult::Recv(RecvPacketResult::Ordered {
-			port_id: packet.destination_port.clone(),
-			channel_id: packet.destination_channel,
-			next_seq_recv: next_seq_recv.increment(),
-			packet: packet.clone(),
-		})
+		if timed_out {
+			let ack = Acknowledgement::timeout();
+			timeout_ack = Some(ack.clone());
+			PacketResult::Recv(RecvPacketResult::Timeout {
+				port_id: packet.destination_port.clone(),
+				channel_id: packet.destination_channel,
+				next_seq_recv: next_seq_recv.increment(),
+				ack_commitment: compute_ack_commitment(&ack),
+				packet: packet.clone(),
+			})
+		} else {
+			PacketResult::Recv(RecvPacketResult::Ordered {
+				port_id: packet.destination_port.clone(),
+				channel_id: packet.destination_channel,
+				next_seq_recv: next_seq_recv.increment(),
+				packet: packet.clone(),
+			})
+		}
 	} else {
-		let packet_rec = ctx.get_packet_receipt(&(
+		// Receipts below the pruning sequence start are gone, but their packets were received.
+		let pruning_sequence_start = ctx.get_pruning_sequence_start(&(
 			packet.destination_port.clone(),
 			packet.destination_channel,
-			packet.sequence,
-		));
+		))?;
+		if packet.sequence < pruning_sequence_start {
+			return Err(Error::packet_receipt_pruned(packet.sequence))
+		}
+
+		let receipt_key =
+			(packet.destination_port.clone(), packet.destination_channel, packet.sequence);
+		let packet_rec = if batch.receipts.contains(&receipt_key) {
+			Ok(Receipt::Ok)
+		} else {
+			ctx.get_packet_receipt(&receipt_key)
+		};
 
 		match packet_rec {
 			Ok(_receipt) => {
@@ -137,7 +287,7 @@ pub fn process<Ctx: ReaderContext>(
 					height: Height::zero(),
 					packet: msg.packet.clone(),
 				}));
-				return Ok(output.with_result(PacketResult::Recv(RecvPacketResult::NoOp)))
+				return Ok(output.with_result(PacketResult::NoOp))
 			},
 			Err(e) if e.detail() == Error::packet_receipt_not_found(packet.sequence).detail() => {
 				// store a receipt that does not contain any data
@@ -154,12 +304,25 @@ pub fn process<Ctx: ReaderContext>(
 	};
 
 	output.log("success: packet receive");
+	output.log_kv("packet_src_port", &packet.source_port);
+	output.log_kv("packet_src_channel", &packet.source_channel);
+	output.log_kv("packet_dst_port", &packet.destination_port);
+	output.log_kv("packet_dst_channel", &packet.destination_channel);
+	output.log_kv("packet_sequence", packet.sequence);
 
 	output.emit(IbcEvent::ReceivePacket(ReceivePacket {
 		height: ctx.host_height(),
 		packet: msg.packet.clone(),
 	}));
 
+	if let Some(ack) = timeout_ack {
+		output.emit(IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
+			height: ctx.host_height(),
+			packet: msg.packet.clone(),
+			ack: ack.into_bytes(),
+		}));
+	}
+
 	Ok(output.with_result(result))
 }
 
@@ -167,30 +330,49 @@ pub fn process<Ctx: ReaderContext>(
 mod tests {
 	use crate::prelude::*;
 
+	use core::time::Duration;
 	use test_log::test;
 
 	use crate::{
 		core::{
-			ics02_client::context::ClientReader,
+			ics02_client::{
+				context::{ClientKeeper, ClientReader},
+				error::ErrorDetail as Ics02ErrorDetail,
+				handler::dispatch as client_dispatch,
+				msgs::{update_client::MsgUpdateAnyClient, ClientMsg},
+			},
 			ics03_connection::{
 				connection::{
 					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
 				},
+				error::ErrorDetail as Ics03ErrorDetail,
 				version::get_compatible_versions,
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				handler::recv_packet::process,
-				msgs::recv_packet::{test_util::get_dummy_raw_msg_recv_packet, MsgRecvPacket},
-				packet::Packet,
+				commitment::compute_ack_commitment,
+				context::{ChannelKeeper, ChannelReader},
+				error,
+				handler::recv_packet::{process, process_batch, RecvPacketResult},
+				msgs::{
+					acknowledgement::{Acknowledgement, TIMEOUT_ACKNOWLEDGEMENT},
+					recv_packet::{test_util::get_dummy_raw_msg_recv_packet, MsgRecvPacket},
+				},
+				packet::{Packet, PacketResult},
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			ics26_routing::context::ReaderContext,
 		},
 		events::IbcEvent,
-		mock::context::{MockClientTypes, MockContext},
+		mock::{
+			client_state::MOCK_TRUSTING_PERIOD,
+			context::{MockClientTypes, MockContext},
+			header::MockHeader,
+		},
 		test_utils::get_dummy_account_id,
 		timestamp::{Timestamp, ZERO_DURATION},
+		Height,
 	};
 
 	#[test]
@@ -222,7 +404,7 @@ mod tests {
 			destination_channel: ChannelId::default(),
 			data: Vec::new(),
 			timeout_height: client_height,
-			timeout_timestamp: Timestamp::from_nanoseconds(1).unwrap(),
+			timeout_timestamp: msg.packet.timeout_timestamp,
 		};
 
 		let msg_packet_old =
@@ -283,12 +465,18 @@ mod tests {
 			},
 			Test {
 				name: "Packet timeout expired".to_string(),
-				ctx: context
-					.with_client(&ClientId::default(), client_height)
-					.with_connection(ConnectionId::default(), connection_end)
-					.with_channel(PortId::default(), ChannelId::default(), dest_channel_end)
-					.with_send_sequence(PortId::default(), ChannelId::default(), 1.into())
-					.with_height(host_height),
+				ctx: {
+					let mut ctx = context
+						.with_client(&ClientId::default(), client_height)
+						.with_connection(ConnectionId::default(), connection_end)
+						.with_channel(PortId::default(), ChannelId::default(), dest_channel_end)
+						.with_send_sequence(PortId::default(), ChannelId::default(), 1.into())
+						.with_height(host_height);
+					// The host clock moves past the timeout timestamp, the height stays below
+					// the timeout height.
+					ctx.advance_time(Duration::from_secs(60));
+					ctx
+				},
 				msg: msg_packet_old,
 				want_pass: false,
 			},
@@ -329,4 +517,570 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn recv_timed_out_packet_on_ordered_allow_timeout_channel() {
+		let context = MockContext::<MockClientTypes>::default();
+		let host_height = context.latest_height().increment();
+		let client_height = host_height.increment();
+
+		let msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.destination_port.clone(), packet.destination_channel);
+
+		let mut msg_timed_out = msg.clone();
+		msg_timed_out.packet.sequence = 1.into();
+		msg_timed_out.packet.timeout_height = Height::zero();
+
+		let mut msg_next = msg;
+		msg_next.packet.sequence = 2.into();
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let context_with = |ordering: Order| {
+			let channel_end = ChannelEnd::new(
+				State::Open,
+				ordering,
+				Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+				vec![ConnectionId::default()],
+				Version::ics20(),
+			);
+			context
+				.clone()
+				.with_client(&ClientId::default(), client_height)
+				.with_connection(ConnectionId::default(), connection_end.clone())
+				.with_channel(
+					packet.destination_port.clone(),
+					packet.destination_channel,
+					channel_end,
+				)
+				.with_height(host_height)
+				.with_recv_sequence(
+					packet.destination_port.clone(),
+					packet.destination_channel,
+					1.into(),
+				)
+		};
+
+		// The first packet is only past its timeout once the host clock has moved forward.
+		let ctx = context_with(Order::OrderedAllowTimeout);
+		let output = process(&ctx, &msg_timed_out).unwrap();
+		assert!(matches!(output.result, PacketResult::Recv(RecvPacketResult::Ordered { .. })));
+		let context_with = |ordering| {
+			let mut ctx = context_with(ordering);
+			ctx.advance_time(Duration::from_secs(60));
+			ctx
+		};
+
+		// An ordered channel refuses the packet, leaving it to be timed out on the sender.
+		let ctx = context_with(Order::Ordered);
+		assert!(process(&ctx, &msg_timed_out).is_err());
+
+		// An ordered allow timeout channel records the packet as timed out.
+		let mut ctx = context_with(Order::OrderedAllowTimeout);
+		let output = process(&ctx, &msg_timed_out).unwrap();
+		assert!(matches!(output.result, PacketResult::Recv(RecvPacketResult::Timeout { .. })));
+		assert!(output.events.iter().any(|e| matches!(
+			e,
+			IbcEvent::WriteAcknowledgement(e) if e.ack == TIMEOUT_ACKNOWLEDGEMENT
+		)));
+		ctx.store_packet_result(output.result).unwrap();
+
+		assert_eq!(ctx.get_next_sequence_recv(&port_channel_id).unwrap(), 2.into());
+		assert_eq!(
+			ctx.get_packet_acknowledgement(&(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				1.into()
+			))
+			.unwrap(),
+			compute_ack_commitment(&Acknowledgement::timeout())
+		);
+
+		// The following sequence is received as usual.
+		let output = process(&ctx, &msg_next).unwrap();
+		match output.result {
+			PacketResult::Recv(RecvPacketResult::Ordered { next_seq_recv, .. }) =>
+				assert_eq!(next_seq_recv, 3.into()),
+			r => panic!("Expected an ordered receive result, instead got {:?}", r),
+		}
+	}
+
+	#[test]
+	fn recv_packet_sequence_gap_on_ordered_channel() {
+		let context = MockContext::<MockClientTypes>::default();
+		let host_height = context.latest_height().increment();
+		let client_height = host_height.increment();
+
+		let mut msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+		msg.packet.sequence = 3.into();
+		let packet = msg.packet.clone();
+
+		let dest_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let ctx = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				dest_channel_end,
+			)
+			.with_height(host_height)
+			.with_recv_sequence(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				1.into(),
+			);
+
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::InvalidPacketSequence(e) => {
+					assert_eq!(e.port_id, packet.destination_port);
+					assert_eq!(e.channel_id, packet.destination_channel);
+					assert_eq!(e.expected, 1.into());
+					assert_eq!(e.received, 3.into());
+				},
+				_ => panic!("Expected InvalidPacketSequence error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected InvalidPacketSequence error"),
+		}
+	}
+
+	#[test]
+	fn recv_packet_twice_is_a_noop() {
+		let context = MockContext::<MockClientTypes>::default();
+		let host_height = context.latest_height().increment();
+		let client_height = host_height.increment();
+
+		let msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.destination_port.clone(), packet.destination_channel);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		for ordering in [Order::Unordered, Order::Ordered] {
+			let dest_channel_end = ChannelEnd::new(
+				State::Open,
+				ordering,
+				Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+				vec![ConnectionId::default()],
+				Version::ics20(),
+			);
+			let mut ctx = context
+				.clone()
+				.with_client(&ClientId::default(), client_height)
+				.with_connection(ConnectionId::default(), connection_end.clone())
+				.with_channel(
+					packet.destination_port.clone(),
+					packet.destination_channel,
+					dest_channel_end,
+				)
+				.with_height(host_height)
+				.with_recv_sequence(
+					packet.destination_port.clone(),
+					packet.destination_channel,
+					packet.sequence,
+				);
+
+			let output = process(&ctx, &msg).unwrap();
+			assert!(!matches!(output.result, PacketResult::NoOp));
+			ctx.store_packet_result(output.result).unwrap();
+			let next_seq_recv = ctx.get_next_sequence_recv(&port_channel_id).unwrap();
+
+			// A competing relayer delivers the same packet again.
+			let output = process(&ctx, &msg).unwrap();
+			assert!(matches!(output.result, PacketResult::NoOp));
+			assert!(!output
+				.events
+				.iter()
+				.any(|e| matches!(e, IbcEvent::WriteAcknowledgement(_))));
+			ctx.store_packet_result(output.result).unwrap();
+			assert_eq!(ctx.get_next_sequence_recv(&port_channel_id).unwrap(), next_seq_recv);
+			assert!(ctx
+				.get_packet_acknowledgement(&(
+					packet.destination_port.clone(),
+					packet.destination_channel,
+					packet.sequence
+				))
+				.is_err());
+		}
+	}
+
+	#[test]
+	fn recv_pruned_packet_is_rejected() {
+		let context = MockContext::<MockClientTypes>::default();
+		let host_height = context.latest_height().increment();
+		let client_height = host_height.increment();
+
+		let msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.destination_port.clone(), packet.destination_channel);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let dest_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let mut ctx = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				dest_channel_end,
+			)
+			.with_height(host_height);
+
+		// The packet was received, then its receipt was pruned.
+		let output = process(&ctx, &msg).unwrap();
+		ctx.store_packet_result(output.result).unwrap();
+		ctx.delete_packet_receipt((
+			packet.destination_port.clone(),
+			packet.destination_channel,
+			packet.sequence,
+		))
+		.unwrap();
+		ctx.store_pruning_sequence_start(port_channel_id, packet.sequence.increment()).unwrap();
+
+		// Delivering it again must not run the module callback a second time.
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::PacketReceiptPruned(e) => {
+					assert_eq!(e.sequence, packet.sequence);
+				},
+				_ => panic!("Expected PacketReceiptPruned error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected PacketReceiptPruned error"),
+		}
+	}
+
+	#[test]
+	fn recv_packet_batch_matches_one_by_one() {
+		let context = MockContext::<MockClientTypes>::default();
+		let host_height = context.latest_height().increment();
+		let client_height = host_height.increment();
+
+		let msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.destination_port.clone(), packet.destination_channel);
+
+		// Consecutive packets, a duplicate within the batch and a gap in the sequence.
+		let msgs: Vec<MsgRecvPacket> = [1u64, 2, 2, 4, 3]
+			.into_iter()
+			.map(|seq| {
+				let mut msg = msg.clone();
+				msg.packet.sequence = seq.into();
+				msg
+			})
+			.collect();
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		for ordering in [Order::Unordered, Order::Ordered] {
+			let dest_channel_end = ChannelEnd::new(
+				State::Open,
+				ordering,
+				Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+				vec![ConnectionId::default()],
+				Version::ics20(),
+			);
+			let ctx = context
+				.clone()
+				.with_client(&ClientId::default(), client_height)
+				.with_connection(ConnectionId::default(), connection_end.clone())
+				.with_channel(
+					packet.destination_port.clone(),
+					packet.destination_channel,
+					dest_channel_end,
+				)
+				.with_height(host_height)
+				.with_recv_sequence(
+					packet.destination_port.clone(),
+					packet.destination_channel,
+					1.into(),
+				);
+
+			let mut one_by_one_ctx = ctx.clone();
+			let one_by_one: Vec<_> = msgs
+				.iter()
+				.map(|msg| {
+					let res = process(&one_by_one_ctx, msg);
+					if let Ok(output) = &res {
+						one_by_one_ctx.store_packet_result(output.result.clone()).unwrap();
+					}
+					res
+				})
+				.collect();
+
+			let mut batch_ctx = ctx;
+			let batch = process_batch(&batch_ctx, &msgs);
+			assert_eq!(batch.len(), one_by_one.len());
+			for (batch_res, res) in batch.into_iter().zip(one_by_one) {
+				match (batch_res, res) {
+					(Ok(batch_output), Ok(output)) => {
+						assert_eq!(
+							format!("{:?}", batch_output.result),
+							format!("{:?}", output.result)
+						);
+						assert_eq!(batch_output.events, output.events);
+						batch_ctx.store_packet_result(batch_output.result).unwrap();
+					},
+					(Err(batch_e), Err(e)) =>
+						assert_eq!(format!("{:?}", batch_e.detail()), format!("{:?}", e.detail())),
+					(batch_res, res) => panic!(
+						"Batch result {:?} differs from one by one result {:?}",
+						batch_res.map(|o| o.result),
+						res.map(|o| o.result)
+					),
+				}
+			}
+
+			assert_eq!(
+				batch_ctx.get_next_sequence_recv(&port_channel_id).unwrap(),
+				one_by_one_ctx.get_next_sequence_recv(&port_channel_id).unwrap()
+			);
+			for seq in 1u64..=4 {
+				let key = (packet.destination_port.clone(), packet.destination_channel, seq.into());
+				assert_eq!(
+					batch_ctx.get_packet_receipt(&key).is_ok(),
+					one_by_one_ctx.get_packet_receipt(&key).is_ok()
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn recv_packet_with_proof_at_pruned_height() {
+		let context = MockContext::<MockClientTypes>::default();
+		let host_height = context.latest_height().increment();
+		let client_height = host_height.increment();
+
+		let msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+		let packet = msg.packet.clone();
+
+		let dest_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::default(),
+			Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let mut ctx = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				dest_channel_end,
+			)
+			.with_height(host_height)
+			.with_recv_sequence(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				packet.sequence,
+			);
+		assert!(process(&ctx, &msg).is_ok());
+
+		// The consensus state at the proof height falls out of the trusting period and is pruned
+		// once it is no longer the latest one.
+		let expired = MOCK_TRUSTING_PERIOD + Duration::from_secs(1);
+		let processed_time = (ctx.host_timestamp() - expired).unwrap();
+		ctx.store_update_time(ClientId::default(), client_height, processed_time).unwrap();
+		for height in [client_height.increment(), client_height.increment().increment()] {
+			let update = MsgUpdateAnyClient {
+				client_id: ClientId::default(),
+				client_message: MockHeader::new(height).into(),
+				signer: get_dummy_account_id(),
+			};
+			let output = client_dispatch(&ctx, ClientMsg::UpdateClient(update)).unwrap();
+			ctx.store_client_result(output.result).unwrap();
+		}
+		assert!(ctx.consensus_state(&ClientId::default(), client_height).is_err());
+
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::Ics02Client(e) => match &e.source {
+					Ics02ErrorDetail::ConsensusStatePruned(e) => {
+						assert_eq!(e.client_id, ClientId::default());
+						assert_eq!(e.height, client_height);
+					},
+					e => panic!("Expected ConsensusStatePruned error, instead got {:?}", e),
+				},
+				_ => panic!("Expected ConsensusStatePruned error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ConsensusStatePruned error"),
+		}
+	}
+
+	#[test]
+	fn recv_packet_waits_for_the_connection_delay() {
+		let delay_period = Duration::from_secs(60);
+		let context = MockContext::<MockClientTypes>::default();
+		let client_height = context.latest_height().increment();
+
+		let mut msg =
+			MsgRecvPacket::try_from(get_dummy_raw_msg_recv_packet(client_height.revision_height))
+				.unwrap();
+		// The packet must not time out while the host chain moves past the delay.
+		msg.packet.timeout_height = Height::zero();
+		msg.packet.timeout_timestamp = Timestamp::none();
+		let packet = msg.packet.clone();
+
+		let dest_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::default(),
+			Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			delay_period,
+		);
+
+		// The consensus state at the proof height is stored right away.
+		let mut ctx = context
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				dest_channel_end,
+			)
+			.with_recv_sequence(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				packet.sequence,
+			);
+		let processed_height =
+			ctx.client_update_height(&ClientId::default(), client_height).unwrap();
+
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::Ics03Connection(e) => match &e.source {
+					Ics03ErrorDetail::NotEnoughTimeElapsed(_) => {},
+					e => panic!("Expected NotEnoughTimeElapsed error, instead got {:?}", e),
+				},
+				_ => panic!("Expected NotEnoughTimeElapsed error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected NotEnoughTimeElapsed error"),
+		}
+
+		// Once the delay has passed on the host clock, the blocks still have to be produced.
+		ctx.advance_time(delay_period);
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::Ics03Connection(e) => match &e.source {
+					Ics03ErrorDetail::NotEnoughBlocksElapsed(e) => {
+						assert_eq!(e.current_host_height, processed_height);
+						assert_eq!(
+							e.earliest_height,
+							processed_height.add(ctx.block_delay(delay_period))
+						);
+					},
+					e => panic!("Expected NotEnoughBlocksElapsed error, instead got {:?}", e),
+				},
+				_ => panic!("Expected NotEnoughBlocksElapsed error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected NotEnoughBlocksElapsed error"),
+		}
+
+		let ctx = ctx.with_height(processed_height.add(ctx.block_delay(delay_period)));
+		let output = process(&ctx, &msg).unwrap();
+		assert!(matches!(output.events[0], IbcEvent::ReceivePacket(_)));
+	}
 }
@@ -17,7 +17,7 @@ use crate::{
 		ics02_client::{client_consensus::ConsensusState, client_state::ClientState},
 		ics04_channel::{
 			channel::{Counterparty, State},
-			commitment::PacketCommitment,
+			commitment::{compute_packet_commitment, PacketCommitment},
 			error::Error,
 			events::SendPacket,
 			packet::{Packet, PacketResult, Sequence},
@@ -54,6 +54,14 @@ pub fn send_packet<Ctx: ReaderContext>(
 		return Err(Error::channel_closed(packet.source_channel))
 	}
 
+	// The channel end holds the proposed ordering and version until the upgrade completes.
+	if source_channel_end.state().is_upgrading() {
+		return Err(Error::channel_upgrade_in_progress(
+			packet.source_channel,
+			*source_channel_end.state(),
+		))
+	}
+
 	let counterparty =
 		Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel));
 
@@ -98,10 +106,20 @@ pub fn send_packet<Ctx: ReaderContext>(
 		ctx.get_next_sequence_send(&(packet.source_port.clone(), packet.source_channel))?;
 
 	if packet.sequence != next_seq_send {
-		return Err(Error::invalid_packet_sequence(packet.sequence, next_seq_send))
+		return Err(Error::invalid_packet_sequence(
+			packet.source_port.clone(),
+			packet.source_channel,
+			next_seq_send,
+			packet.sequence,
+		))
 	}
 
 	output.log("success: packet send ");
+	output.log_kv("packet_src_port", &packet.source_port);
+	output.log_kv("packet_src_channel", &packet.source_channel);
+	output.log_kv("packet_dst_port", &packet.destination_port);
+	output.log_kv("packet_dst_channel", &packet.destination_channel);
+	output.log_kv("packet_sequence", packet.sequence);
 
 	let result = PacketResult::Send(SendPacketResult {
 		port_id: packet.source_port.clone(),
@@ -109,14 +127,18 @@ pub fn send_packet<Ctx: ReaderContext>(
 		seq: packet.sequence,
 		seq_number: next_seq_send.increment(),
 		packet: packet.clone(),
-		commitment: ctx.packet_commitment(
-			packet.data.clone(),
-			packet.timeout_height,
-			packet.timeout_timestamp,
+		commitment: compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
 		),
 	});
 
-	output.emit(IbcEvent::SendPacket(SendPacket { height: ctx.host_height(), packet }));
+	output.emit(IbcEvent::SendPacket(SendPacket {
+		height: ctx.host_height(),
+		packet,
+		channel_ordering: Some(source_channel_end.ordering),
+	}));
 
 	Ok(output.with_result(result))
 }
@@ -129,7 +151,10 @@ mod tests {
 
 	use crate::{
 		core::{
-			ics02_client::{context::ClientReader, height::Height},
+			ics02_client::{
+				context::{ClientKeeper, ClientReader},
+				height::Height,
+			},
 			ics03_connection::{
 				connection::{
 					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
@@ -138,6 +163,7 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
+				error,
 				handler::send_packet::send_packet,
 				packet::{test_utils::get_dummy_raw_packet, Packet},
 				Version,
@@ -145,7 +171,11 @@ mod tests {
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 		},
 		events::IbcEvent,
-		mock::context::{MockClientTypes, MockContext},
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+			header::MockHeader,
+		},
 		prelude::*,
 		timestamp::{Timestamp, ZERO_DURATION},
 	};
@@ -260,4 +290,95 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn send_packet_rejects_frozen_client() {
+		let timestamp = Timestamp::now().add(Duration::from_secs(10)).unwrap();
+		let mut packet: Packet =
+			get_dummy_raw_packet(1, timestamp.nanoseconds()).try_into().unwrap();
+		packet.sequence = 1.into();
+		packet.data = vec![0];
+
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::default(),
+			Counterparty::new(PortId::default(), Some(ChannelId::default())),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), Height::default())
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(PortId::default(), ChannelId::default(), channel_end)
+			.with_send_sequence(PortId::default(), ChannelId::default(), 1.into());
+		let frozen = MockClientState {
+			header: MockHeader::new(Height::default()),
+			frozen_height: Some(Height::new(0, 1)),
+		};
+		ctx.store_client_state(ClientId::default(), frozen.into()).unwrap();
+
+		match send_packet(&ctx, packet) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::FrozenClient(e) => assert_eq!(e.client_id, ClientId::default()),
+				_ => panic!("Expected FrozenClient error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected FrozenClient error"),
+		}
+	}
+
+	#[test]
+	fn send_packet_rejects_upgrading_channel() {
+		let timestamp = Timestamp::now().add(Duration::from_secs(10)).unwrap();
+		let mut packet: Packet =
+			get_dummy_raw_packet(1, timestamp.nanoseconds()).try_into().unwrap();
+		packet.sequence = 1.into();
+		packet.data = vec![0];
+
+		let channel_end = ChannelEnd::new(
+			State::InitUpgrade,
+			Order::default(),
+			Counterparty::new(PortId::default(), Some(ChannelId::default())),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), Height::default())
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(PortId::default(), ChannelId::default(), channel_end)
+			.with_send_sequence(PortId::default(), ChannelId::default(), 1.into());
+
+		match send_packet(&ctx, packet) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::ChannelUpgradeInProgress(e) => {
+					assert_eq!(e.state, State::InitUpgrade)
+				},
+				_ => panic!("Expected ChannelUpgradeInProgress error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ChannelUpgradeInProgress error"),
+		}
+	}
 }
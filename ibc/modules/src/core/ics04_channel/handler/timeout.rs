@@ -17,6 +17,7 @@ use crate::{
 		ics02_client::client_consensus::ConsensusState,
 		ics04_channel::{
 			channel::{ChannelEnd, Counterparty, Order, State},
+			commitment::compute_packet_commitment,
 			error::Error,
 			events::TimeoutPacket,
 			handler::verify::{verify_next_sequence_recv, verify_packet_receipt_absence},
@@ -29,6 +30,7 @@ use crate::{
 	events::IbcEvent,
 	handler::{HandlerOutput, HandlerResult},
 	prelude::*,
+	timestamp::Timestamp,
 };
 use core::fmt::Debug;
 
@@ -38,6 +40,9 @@ pub struct TimeoutPacketResult {
 	pub channel_id: ChannelId,
 	pub seq: Sequence,
 	pub channel: Option<ChannelEnd>,
+	pub seq_number: Option<Sequence>,
+	/// Host time at which the timeout was processed, set when the host persists timeouts.
+	pub timed_out_at: Option<Timestamp>,
 }
 
 pub fn process<Ctx>(ctx: &Ctx, msg: &MsgTimeout) -> HandlerResult<PacketResult, Error>
@@ -97,14 +102,22 @@ where
 	))?;
 
 	let expected_commitment =
-		ctx.packet_commitment(packet.data.clone(), packet.timeout_height, packet.timeout_timestamp);
+		compute_packet_commitment(&packet.data, &packet.timeout_height, &packet.timeout_timestamp);
 	if packet_commitment != expected_commitment {
 		return Err(Error::incorrect_packet_commitment(packet.sequence))
 	}
 
-	let result = if source_channel_end.order_matches(&Order::Ordered) {
+	// Keep a record of the timeout once the commitment is gone, if the host asks for it.
+	let timed_out_at = ctx.persist_timeouts().then(|| ctx.host_timestamp());
+
+	let result = if !source_channel_end.order_matches(&Order::Unordered) {
 		if packet.sequence < msg.next_sequence_recv {
-			return Err(Error::invalid_packet_sequence(packet.sequence, msg.next_sequence_recv))
+			return Err(Error::invalid_packet_sequence(
+				packet.source_port.clone(),
+				packet.source_channel,
+				msg.next_sequence_recv,
+				packet.sequence,
+			))
 		}
 		verify_next_sequence_recv::<Ctx>(
 			ctx,
@@ -115,13 +128,39 @@ where
 			&msg.proofs,
 		)?;
 
-		source_channel_end.state = State::Closed;
-		PacketResult::Timeout(TimeoutPacketResult {
-			port_id: packet.source_port.clone(),
-			channel_id: packet.source_channel,
-			seq: packet.sequence,
-			channel: Some(source_channel_end),
-		})
+		if source_channel_end.order_matches(&Order::OrderedAllowTimeout) {
+			// The channel stays open, the timed out packet only takes its turn in the
+			// acknowledgement sequence.
+			let next_seq_ack =
+				ctx.get_next_sequence_ack(&(packet.source_port.clone(), packet.source_channel))?;
+			if packet.sequence != next_seq_ack {
+				return Err(Error::invalid_packet_sequence(
+					packet.source_port.clone(),
+					packet.source_channel,
+					next_seq_ack,
+					packet.sequence,
+				))
+			}
+
+			PacketResult::Timeout(TimeoutPacketResult {
+				port_id: packet.source_port.clone(),
+				channel_id: packet.source_channel,
+				seq: packet.sequence,
+				channel: None,
+				seq_number: Some(next_seq_ack.increment()),
+				timed_out_at,
+			})
+		} else {
+			source_channel_end.state = State::Closed;
+			PacketResult::Timeout(TimeoutPacketResult {
+				port_id: packet.source_port.clone(),
+				channel_id: packet.source_channel,
+				seq: packet.sequence,
+				channel: Some(source_channel_end),
+				seq_number: None,
+				timed_out_at,
+			})
+		}
 	} else {
 		verify_packet_receipt_absence::<Ctx>(
 			ctx,
@@ -136,10 +175,17 @@ where
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
 			channel: None,
+			seq_number: None,
+			timed_out_at,
 		})
 	};
 
 	output.log("success: packet timeout ");
+	output.log_kv("packet_src_port", &packet.source_port);
+	output.log_kv("packet_src_channel", &packet.source_channel);
+	output.log_kv("packet_dst_port", &packet.destination_port);
+	output.log_kv("packet_dst_channel", &packet.destination_channel);
+	output.log_kv("packet_sequence", packet.sequence);
 
 	output.emit(IbcEvent::TimeoutPacket(TimeoutPacket {
 		height: ctx.host_height(),
@@ -151,11 +197,15 @@ where
 
 #[cfg(test)]
 mod tests {
+	use core::time::Duration;
 	use test_log::test;
 
 	use crate::{
 		core::{
-			ics02_client::{context::ClientReader, height::Height},
+			ics02_client::{
+				context::{ClientKeeper, ClientReader},
+				height::Height,
+			},
 			ics03_connection::{
 				connection::{
 					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
@@ -164,9 +214,12 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				context::ChannelReader,
+				commitment::compute_packet_commitment,
+				context::{ChannelKeeper, ChannelReader},
+				error,
 				handler::timeout::process,
 				msgs::timeout::{test_util::get_dummy_raw_msg_timeout, MsgTimeout},
+				packet::Sequence,
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -200,10 +253,10 @@ mod tests {
 		let mut msg_ok = msg.clone();
 		msg_ok.packet.timeout_timestamp = Default::default();
 
-		let data = context.packet_commitment(
-			msg_ok.packet.data.clone(),
-			msg_ok.packet.timeout_height,
-			msg_ok.packet.timeout_timestamp,
+		let data = compute_packet_commitment(
+			&msg_ok.packet.data,
+			&msg_ok.packet.timeout_height,
+			&msg_ok.packet.timeout_timestamp,
 		);
 
 		let source_channel_end = ChannelEnd::new(
@@ -341,4 +394,293 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn timeout_keeps_ordered_allow_timeout_channel_open() {
+		let height = Height::default().revision_height + 2;
+		let client_height = Height::new(0, height);
+
+		let mut msg = MsgTimeout::try_from(get_dummy_raw_msg_timeout(height, 5)).unwrap();
+		msg.packet.timeout_timestamp = Default::default();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let counterparty =
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel));
+
+		let context_with = |ordering: Order| {
+			let context = MockContext::<MockClientTypes>::default();
+			let commitment = compute_packet_commitment(
+				&packet.data,
+				&packet.timeout_height,
+				&packet.timeout_timestamp,
+			);
+			let channel_end = ChannelEnd::new(
+				State::Open,
+				ordering,
+				counterparty.clone(),
+				vec![ConnectionId::default()],
+				Version::ics20(),
+			);
+			context
+				.with_client(&ClientId::default(), client_height)
+				.with_connection(ConnectionId::default(), connection_end.clone())
+				.with_channel(packet.source_port.clone(), packet.source_channel, channel_end)
+				.with_packet_commitment(
+					packet.source_port.clone(),
+					packet.source_channel,
+					packet.sequence,
+					commitment,
+				)
+				.with_ack_sequence(
+					packet.source_port.clone(),
+					packet.source_channel,
+					packet.sequence,
+				)
+		};
+
+		// A timeout closes an ordered channel.
+		let mut ctx = context_with(Order::Ordered);
+		let output = process(&ctx, &msg).unwrap();
+		ctx.store_packet_result(output.result).unwrap();
+		assert!(ctx.channel_end(&port_channel_id).unwrap().state_matches(&State::Closed));
+
+		// An ordered allow timeout channel stays open and expects the next sequence.
+		let mut ctx = context_with(Order::OrderedAllowTimeout);
+		let output = process(&ctx, &msg).unwrap();
+		ctx.store_packet_result(output.result).unwrap();
+		assert!(ctx.channel_end(&port_channel_id).unwrap().state_matches(&State::Open));
+		assert_eq!(
+			ctx.get_next_sequence_ack(&port_channel_id).unwrap(),
+			packet.sequence.increment()
+		);
+		assert!(ctx
+			.get_packet_commitment(&(packet.source_port, packet.source_channel, packet.sequence))
+			.is_err());
+	}
+
+	#[test]
+	fn timeout_is_recorded_only_when_persisting_timeouts() {
+		let height = Height::default().revision_height + 2;
+		let client_height = Height::new(0, height);
+
+		let mut msg = MsgTimeout::try_from(get_dummy_raw_msg_timeout(height, 5)).unwrap();
+		msg.packet.timeout_timestamp = Default::default();
+		let packet = msg.packet.clone();
+		let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let context_with = |persist_timeouts: bool| {
+			let context = MockContext::<MockClientTypes>::default();
+			let commitment = compute_packet_commitment(
+				&packet.data,
+				&packet.timeout_height,
+				&packet.timeout_timestamp,
+			);
+			context
+				.with_persist_timeouts(persist_timeouts)
+				.with_client(&ClientId::default(), client_height)
+				.with_connection(ConnectionId::default(), connection_end.clone())
+				.with_channel(
+					packet.source_port.clone(),
+					packet.source_channel,
+					channel_end.clone(),
+				)
+				.with_packet_commitment(
+					packet.source_port.clone(),
+					packet.source_channel,
+					packet.sequence,
+					commitment,
+				)
+		};
+
+		let mut ctx = context_with(false);
+		let output = process(&ctx, &msg).unwrap();
+		ctx.store_packet_result(output.result).unwrap();
+		assert!(ctx.timed_out_packets(&port_channel_id).unwrap().is_empty());
+
+		let mut ctx = context_with(true);
+		let output = process(&ctx, &msg).unwrap();
+		ctx.store_packet_result(output.result).unwrap();
+		assert_eq!(
+			ctx.timed_out_packets(&port_channel_id).unwrap(),
+			vec![(packet.sequence, ctx.host_timestamp())]
+		);
+	}
+
+	#[test]
+	fn timeout_timestamp_is_checked_against_the_counterparty_time() {
+		// The chain the packet was sent to, tracked by the client of this chain.
+		let mut counterparty = MockContext::<MockClientTypes>::default();
+		let timeout_timestamp = (counterparty.host_timestamp() + Duration::from_secs(60)).unwrap();
+
+		counterparty.advance_host_chain_height();
+		let early_height = counterparty.latest_height();
+		// The counterparty clock passes the timeout within a single block.
+		counterparty.advance_time(Duration::from_secs(60));
+		counterparty.advance_host_chain_height();
+		let late_height = counterparty.latest_height();
+
+		// The packet only times out on its timestamp.
+		let msg_at = |proof_height: Height| {
+			let mut msg = MsgTimeout::try_from(get_dummy_raw_msg_timeout(
+				proof_height.revision_height,
+				timeout_timestamp.nanoseconds(),
+			))
+			.unwrap();
+			msg.packet.timeout_height = Height::zero();
+			msg
+		};
+		let packet = msg_at(late_height).packet;
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let commitment = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), late_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(packet.source_port.clone(), packet.source_channel, channel_end)
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				commitment,
+			);
+		for height in [early_height, late_height] {
+			let consensus_state = counterparty.host_consensus_state(height, None).unwrap();
+			ctx.store_consensus_state(ClientId::default(), height, consensus_state).unwrap();
+			let (processed_time, processed_height) = (ctx.host_timestamp(), ctx.host_height());
+			ctx.store_update_time(ClientId::default(), height, processed_time).unwrap();
+			ctx.store_update_height(ClientId::default(), height, processed_height).unwrap();
+		}
+
+		match process(&ctx, &msg_at(early_height)) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::PacketTimeoutNotReached(e) => {
+					assert_eq!(e.timeout_timestamp, timeout_timestamp);
+				},
+				_ => panic!("Expected PacketTimeoutNotReached error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected PacketTimeoutNotReached error"),
+		}
+
+		let output = process(&ctx, &msg_at(late_height)).unwrap();
+		assert!(matches!(output.events[0], IbcEvent::TimeoutPacket(_)));
+	}
+
+	#[test]
+	fn timeout_on_ordered_channel_checks_the_next_sequence_recv() {
+		let height = Height::default().revision_height + 2;
+		let client_height = Height::new(0, height);
+
+		let mut msg = MsgTimeout::try_from(get_dummy_raw_msg_timeout(height, 5)).unwrap();
+		msg.packet.timeout_timestamp = Default::default();
+		msg.packet.sequence = Sequence::from(3);
+		let packet = msg.packet.clone();
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let commitment = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(packet.source_port.clone(), packet.source_channel, channel_end)
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				commitment,
+			);
+
+		// The counterparty hasn't received the packet yet, whether or not it's the next one.
+		for next_sequence_recv in [2, 3] {
+			msg.next_sequence_recv = Sequence::from(next_sequence_recv);
+			let output = process(&ctx, &msg).unwrap();
+			assert!(matches!(output.events[0], IbcEvent::TimeoutPacket(_)));
+		}
+
+		// The counterparty already received the packet, it can't time out anymore.
+		msg.next_sequence_recv = Sequence::from(4);
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::InvalidPacketSequence(e) => {
+					assert_eq!(e.expected, Sequence::from(4));
+					assert_eq!(e.received, packet.sequence);
+				},
+				_ => panic!("Expected InvalidPacketSequence error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected InvalidPacketSequence error"),
+		}
+	}
 }
@@ -14,8 +14,11 @@
 
 use crate::{
 	core::{
+		ics02_client::client_state::ClientState,
+		ics03_connection::connection::State as ConnectionState,
 		ics04_channel::{
 			channel::{ChannelEnd, Counterparty, Order, State},
+			commitment::compute_packet_commitment,
 			error::Error,
 			events::TimeoutOnClosePacket,
 			handler::{
@@ -67,7 +70,7 @@ pub fn process<Ctx: ReaderContext>(
 	))?;
 
 	let expected_commitment =
-		ctx.packet_commitment(packet.data.clone(), packet.timeout_height, packet.timeout_timestamp);
+		compute_packet_commitment(&packet.data, &packet.timeout_height, &packet.timeout_timestamp);
 	if packet_commitment != expected_commitment {
 		return Err(Error::incorrect_packet_commitment(packet.sequence))
 	}
@@ -90,11 +93,39 @@ pub fn process<Ctx: ReaderContext>(
 		source_channel_end.version().clone(),
 	);
 
+	// The closed counterparty channel may be proven through another connection to the same
+	// counterparty chain, e.g. when routing through an intermediate hop.
+	let proof_connection_end = match &msg.proof_close_connection_id {
+		Some(proof_connection_id) => {
+			let proof_connection_end = ctx
+				.connection_end(proof_connection_id)
+				.map_err(Error::ics03_connection)?;
+			if !proof_connection_end.state_matches(&ConnectionState::Open) {
+				return Err(Error::connection_not_open(proof_connection_id.clone()))
+			}
+
+			let chain_id = ctx
+				.client_state(connection_end.client_id())
+				.map_err(Error::ics02_client)?
+				.chain_id();
+			let proof_chain_id = ctx
+				.client_state(proof_connection_end.client_id())
+				.map_err(Error::ics02_client)?
+				.chain_id();
+			if chain_id != proof_chain_id {
+				return Err(Error::proof_close_connection_mismatch(proof_connection_id.clone()))
+			}
+
+			proof_connection_end
+		},
+		None => connection_end.clone(),
+	};
+
 	verify_channel_proofs::<Ctx>(
 		ctx,
 		msg.proofs.height(),
 		&source_channel_end,
-		&connection_end,
+		&proof_connection_end,
 		&expected_channel_end,
 		msg.proofs
 			.other_proof()
@@ -102,9 +133,17 @@ pub fn process<Ctx: ReaderContext>(
 			.ok_or_else(|| Error::missing_channel_proof())?,
 	)?;
 
-	let result = if source_channel_end.order_matches(&Order::Ordered) {
+	// Keep a record of the timeout once the commitment is gone, if the host asks for it.
+	let timed_out_at = ctx.persist_timeouts().then(|| ctx.host_timestamp());
+
+	let result = if !source_channel_end.order_matches(&Order::Unordered) {
 		if packet.sequence < msg.next_sequence_recv {
-			return Err(Error::invalid_packet_sequence(packet.sequence, msg.next_sequence_recv))
+			return Err(Error::invalid_packet_sequence(
+				packet.source_port.clone(),
+				packet.source_channel,
+				msg.next_sequence_recv,
+				packet.sequence,
+			))
 		}
 		verify_next_sequence_recv::<Ctx>(
 			ctx,
@@ -115,12 +154,36 @@ pub fn process<Ctx: ReaderContext>(
 			&msg.proofs,
 		)?;
 
-		PacketResult::Timeout(TimeoutPacketResult {
-			port_id: packet.source_port.clone(),
-			channel_id: packet.source_channel,
-			seq: packet.sequence,
-			channel: Some(source_channel_end),
-		})
+		if source_channel_end.order_matches(&Order::OrderedAllowTimeout) {
+			let next_seq_ack =
+				ctx.get_next_sequence_ack(&(packet.source_port.clone(), packet.source_channel))?;
+			if packet.sequence != next_seq_ack {
+				return Err(Error::invalid_packet_sequence(
+					packet.source_port.clone(),
+					packet.source_channel,
+					next_seq_ack,
+					packet.sequence,
+				))
+			}
+
+			PacketResult::Timeout(TimeoutPacketResult {
+				port_id: packet.source_port.clone(),
+				channel_id: packet.source_channel,
+				seq: packet.sequence,
+				channel: None,
+				seq_number: Some(next_seq_ack.increment()),
+				timed_out_at,
+			})
+		} else {
+			PacketResult::Timeout(TimeoutPacketResult {
+				port_id: packet.source_port.clone(),
+				channel_id: packet.source_channel,
+				seq: packet.sequence,
+				channel: Some(source_channel_end),
+				seq_number: None,
+				timed_out_at,
+			})
+		}
 	} else {
 		verify_packet_receipt_absence::<Ctx>(
 			ctx,
@@ -135,10 +198,17 @@ pub fn process<Ctx: ReaderContext>(
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
 			channel: None,
+			seq_number: None,
+			timed_out_at,
 		})
 	};
 
 	output.log("success: packet timeout ");
+	output.log_kv("packet_src_port", &packet.source_port);
+	output.log_kv("packet_src_channel", &packet.source_channel);
+	output.log_kv("packet_dst_port", &packet.destination_port);
+	output.log_kv("packet_dst_channel", &packet.destination_channel);
+	output.log_kv("packet_sequence", packet.sequence);
 
 	output.emit(IbcEvent::TimeoutOnClosePacket(TimeoutOnClosePacket {
 		height: ctx.host_height(),
@@ -163,17 +233,22 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
-				context::ChannelReader,
+				commitment::compute_packet_commitment,
+				error,
 				handler::timeout_on_close::process,
 				msgs::timeout_on_close::{
 					test_util::get_dummy_raw_msg_timeout_on_close, MsgTimeoutOnClose,
 				},
+				packet::Sequence,
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 		},
 		events::IbcEvent,
-		mock::context::{MockClientTypes, MockContext},
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+		},
 		prelude::*,
 		timestamp::ZERO_DURATION,
 	};
@@ -201,10 +276,10 @@ mod tests {
 		.unwrap();
 		let packet = msg.packet.clone();
 
-		let data = context.packet_commitment(
-			msg.packet.data.clone(),
-			msg.packet.timeout_height,
-			msg.packet.timeout_timestamp,
+		let data = compute_packet_commitment(
+			&msg.packet.data,
+			&msg.packet.timeout_height,
+			&msg.packet.timeout_timestamp,
 		);
 
 		let source_channel_end = ChannelEnd::new(
@@ -302,4 +377,156 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn timeout_on_close_with_proof_close_connection() {
+		let height = Height::default().revision_height + 2;
+		let client_height = Height::new(0, height);
+
+		let mut msg =
+			MsgTimeoutOnClose::try_from(get_dummy_raw_msg_timeout_on_close(height, 5)).unwrap();
+		let packet = msg.packet.clone();
+
+		let proof_client_id = ClientId::new(&MockClientState::client_type(), 1).unwrap();
+		let proof_connection_id = ConnectionId::new(1);
+		msg.proof_close_connection_id = Some(proof_connection_id.clone());
+
+		let source_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let connection_end = |state: ConnectionState, client_id: ClientId| {
+			ConnectionEnd::new(
+				state,
+				client_id.clone(),
+				ConnectionCounterparty::new(
+					client_id,
+					Some(ConnectionId::default()),
+					Default::default(),
+				),
+				get_compatible_versions(),
+				ZERO_DURATION,
+			)
+		};
+
+		let context_with = |proof_connection_state: ConnectionState| {
+			let context = MockContext::<MockClientTypes>::default();
+			let data = compute_packet_commitment(
+				&packet.data,
+				&packet.timeout_height,
+				&packet.timeout_timestamp,
+			);
+			context
+				.with_client(&ClientId::default(), client_height)
+				.with_client(&proof_client_id, client_height)
+				.with_connection(
+					ConnectionId::default(),
+					connection_end(ConnectionState::Open, ClientId::default()),
+				)
+				.with_connection(
+					proof_connection_id.clone(),
+					connection_end(proof_connection_state, proof_client_id.clone()),
+				)
+				.with_channel(
+					packet.source_port.clone(),
+					packet.source_channel,
+					source_channel_end.clone(),
+				)
+				.with_packet_commitment(
+					packet.source_port.clone(),
+					packet.source_channel,
+					packet.sequence,
+					data,
+				)
+		};
+
+		// The closed channel is proven through the alternate connection's client.
+		let ctx = context_with(ConnectionState::Open);
+		let output = process(&ctx, &msg).unwrap();
+		assert!(matches!(output.events[0], IbcEvent::TimeoutOnClosePacket(_)));
+
+		// The alternate connection must be usable.
+		let ctx = context_with(ConnectionState::Init);
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::ConnectionNotOpen(e) => {
+					assert_eq!(e.connection_id, proof_connection_id);
+				},
+				_ => panic!("Expected ConnectionNotOpen error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ConnectionNotOpen error"),
+		}
+
+		// Without an alternate connection, the packet's connection is used.
+		msg.proof_close_connection_id = None;
+		let ctx = context_with(ConnectionState::Init);
+		assert!(process(&ctx, &msg).is_ok());
+	}
+
+	#[test]
+	fn timeout_on_close_on_ordered_channel_checks_the_next_sequence_recv() {
+		let height = Height::default().revision_height + 2;
+		let client_height = Height::new(0, height);
+
+		let mut msg =
+			MsgTimeoutOnClose::try_from(get_dummy_raw_msg_timeout_on_close(height, 5)).unwrap();
+		msg.packet.sequence = Sequence::from(3);
+		let packet = msg.packet.clone();
+
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			Counterparty::new(packet.destination_port.clone(), Some(packet.destination_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+		let data = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(packet.source_port.clone(), packet.source_channel, channel_end)
+			.with_packet_commitment(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				data,
+			);
+
+		// The counterparty closed the channel before receiving the packet.
+		msg.next_sequence_recv = Sequence::from(3);
+		let output = process(&ctx, &msg).unwrap();
+		assert!(matches!(output.events[0], IbcEvent::TimeoutOnClosePacket(_)));
+
+		// The counterparty received the packet before closing the channel.
+		msg.next_sequence_recv = Sequence::from(4);
+		match process(&ctx, &msg) {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::InvalidPacketSequence(e) => {
+					assert_eq!(e.expected, Sequence::from(4));
+					assert_eq!(e.received, packet.sequence);
+				},
+				_ => panic!("Expected InvalidPacketSequence error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected InvalidPacketSequence error"),
+		}
+	}
 }
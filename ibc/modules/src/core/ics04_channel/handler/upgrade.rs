@@ -0,0 +1,726 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to the ICS4 channel upgrade handshake messages.
+//!
+//! An upgrade moves an `Open` channel through `InitUpgrade` (on the initiating end) or
+//! `TryUpgrade` (on the responding end) and back to `Open`, replacing its ordering and version
+//! along the way. The proposed channel end is stored in place of the current one, so packets
+//! cannot be sent or received on either end until the handshake completes.
+//!
+//! Only a signer the host authorizes for the port may propose an upgrade. The fields it replaced
+//! are kept until the handshake completes, so that `MsgChannelUpgradeCancel` can restore them:
+//! the initiating end can be cancelled by an authorized signer, or by anyone once the upgrade
+//! has been pending for the connection handshake timeout, and the responding end once the
+//! counterparty is proven to have cancelled.
+
+use crate::{
+	core::{
+		ics03_connection::connection::{ConnectionEnd, State as ConnectionState},
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			events::Attributes,
+			handler::{verify::verify_channel_proofs, ChannelIdState, ChannelResult},
+			msgs::{
+				chan_upgrade_ack::MsgChannelUpgradeAck,
+				chan_upgrade_cancel::MsgChannelUpgradeCancel,
+				chan_upgrade_confirm::MsgChannelUpgradeConfirm,
+				chan_upgrade_init::MsgChannelUpgradeInit, chan_upgrade_try::MsgChannelUpgradeTry,
+			},
+			upgrade::{UpgradeFields, UpgradeOrigin},
+		},
+		ics24_host::identifier::{ChannelId, PortId},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+/// Returns the OPEN connection underlying `channel_end`.
+fn open_connection<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	channel_end: &ChannelEnd,
+) -> Result<ConnectionEnd, Error> {
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	if !conn.state_matches(&ConnectionState::Open) {
+		return Err(Error::connection_not_open(channel_end.connection_hops()[0].clone()))
+	}
+
+	Ok(conn)
+}
+
+/// Builds the channel end we expect to find on the counterparty chain.
+fn expected_counterparty_end(
+	port_id: &PortId,
+	channel_id: ChannelId,
+	channel_end: &ChannelEnd,
+	conn: &ConnectionEnd,
+	state: State,
+	fields: &UpgradeFields,
+	upgrade_sequence: u64,
+) -> Result<ChannelEnd, Error> {
+	let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+		Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+	})?;
+
+	Ok(ChannelEnd::new(
+		state,
+		fields.ordering,
+		Counterparty::new(port_id.clone(), Some(channel_id)),
+		vec![ccid.clone()],
+		fields.version.clone(),
+	)
+	.with_upgrade_sequence(upgrade_sequence))
+}
+
+fn event_attributes<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	port_id: &PortId,
+	channel_id: ChannelId,
+	channel_end: &ChannelEnd,
+) -> Attributes {
+	Attributes {
+		channel_id: Some(channel_id),
+		height: ctx.host_height(),
+		port_id: port_id.clone(),
+		connection_id: channel_end.connection_hops[0].clone(),
+		counterparty_port_id: channel_end.counterparty().port_id.clone(),
+		counterparty_channel_id: channel_end.counterparty().channel_id.clone(),
+	}
+}
+
+pub(crate) fn process_init<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeInit,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let channel_end = ctx.channel_end(&(msg.port_id.clone(), msg.channel_id))?;
+
+	// Only an open channel can be upgraded.
+	if !channel_end.state_matches(&State::Open) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	if !ctx.is_upgrade_authorized(&msg.port_id, &msg.channel_id, &msg.signer) {
+		return Err(Error::unauthorized_channel_upgrade(msg.port_id.clone(), msg.channel_id))
+	}
+
+	if msg.fields.matches(&channel_end) {
+		return Err(Error::upgrade_fields_unchanged(msg.channel_id))
+	}
+
+	open_connection(ctx, &channel_end)?;
+
+	output.log("success: channel upgrade init ");
+
+	let upgrade_origin = UpgradeOrigin {
+		previous: UpgradeFields::new(*channel_end.ordering(), channel_end.version().clone()),
+		height: ctx.host_height(),
+	};
+	let upgrade_sequence = channel_end.upgrade_sequence() + 1;
+	let mut channel_end = msg.fields.apply_to(channel_end).with_upgrade_sequence(upgrade_sequence);
+	channel_end.set_state(State::InitUpgrade);
+
+	let event_attributes = event_attributes(ctx, &msg.port_id, msg.channel_id, &channel_end);
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade_origin: Some(upgrade_origin),
+	};
+
+	output.emit(IbcEvent::ChannelUpgradeInit(
+		event_attributes.try_into().map_err(|_| Error::missing_channel_id())?,
+	));
+
+	Ok(output.with_result(result))
+}
+
+pub(crate) fn process_try<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeTry,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let channel_end = ctx.channel_end(&(msg.port_id.clone(), msg.channel_id))?;
+
+	if !channel_end.state_matches(&State::Open) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	// The counterparty must be proposing an upgrade this end has not seen yet.
+	if msg.counterparty_upgrade_sequence <= channel_end.upgrade_sequence() {
+		return Err(Error::upgrade_sequence_mismatch(
+			msg.channel_id,
+			channel_end.upgrade_sequence() + 1,
+			msg.counterparty_upgrade_sequence,
+		))
+	}
+
+	if msg.counterparty_upgrade_fields.matches(&channel_end) {
+		return Err(Error::upgrade_fields_unchanged(msg.channel_id))
+	}
+
+	let conn = open_connection(ctx, &channel_end)?;
+
+	let expected_channel_end = expected_counterparty_end(
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+		&conn,
+		State::InitUpgrade,
+		&msg.counterparty_upgrade_fields,
+		msg.counterparty_upgrade_sequence,
+	)?;
+
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		&msg.proofs.object_proof(),
+	)?;
+
+	output.log("success: channel upgrade try ");
+
+	let upgrade_origin = UpgradeOrigin {
+		previous: UpgradeFields::new(*channel_end.ordering(), channel_end.version().clone()),
+		height: ctx.host_height(),
+	};
+	let mut channel_end = msg
+		.counterparty_upgrade_fields
+		.apply_to(channel_end)
+		.with_upgrade_sequence(msg.counterparty_upgrade_sequence);
+	channel_end.set_state(State::TryUpgrade);
+
+	let event_attributes = event_attributes(ctx, &msg.port_id, msg.channel_id, &channel_end);
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade_origin: Some(upgrade_origin),
+	};
+
+	output.emit(IbcEvent::ChannelUpgradeTry(
+		event_attributes.try_into().map_err(|_| Error::missing_channel_id())?,
+	));
+
+	Ok(output.with_result(result))
+}
+
+pub(crate) fn process_ack<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeAck,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let mut channel_end = ctx.channel_end(&(msg.port_id.clone(), msg.channel_id))?;
+
+	if !channel_end.state_matches(&State::InitUpgrade) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	if msg.counterparty_upgrade_sequence != channel_end.upgrade_sequence() {
+		return Err(Error::upgrade_sequence_mismatch(
+			msg.channel_id,
+			channel_end.upgrade_sequence(),
+			msg.counterparty_upgrade_sequence,
+		))
+	}
+
+	let conn = open_connection(ctx, &channel_end)?;
+
+	let fields = UpgradeFields::new(*channel_end.ordering(), channel_end.version().clone());
+	let expected_channel_end = expected_counterparty_end(
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+		&conn,
+		State::TryUpgrade,
+		&fields,
+		channel_end.upgrade_sequence(),
+	)?;
+
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		&msg.proofs.object_proof(),
+	)?;
+
+	output.log("success: channel upgrade ack ");
+
+	channel_end.set_state(State::Open);
+
+	let event_attributes = event_attributes(ctx, &msg.port_id, msg.channel_id, &channel_end);
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade_origin: None,
+	};
+
+	output.emit(IbcEvent::ChannelUpgradeAck(
+		event_attributes.try_into().map_err(|_| Error::missing_channel_id())?,
+	));
+
+	Ok(output.with_result(result))
+}
+
+pub(crate) fn process_confirm<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeConfirm,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let mut channel_end = ctx.channel_end(&(msg.port_id.clone(), msg.channel_id))?;
+
+	if !channel_end.state_matches(&State::TryUpgrade) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let conn = open_connection(ctx, &channel_end)?;
+
+	let fields = UpgradeFields::new(*channel_end.ordering(), channel_end.version().clone());
+	let expected_channel_end = expected_counterparty_end(
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+		&conn,
+		State::Open,
+		&fields,
+		channel_end.upgrade_sequence(),
+	)?;
+
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		&msg.proofs.object_proof(),
+	)?;
+
+	output.log("success: channel upgrade confirm ");
+
+	channel_end.set_state(State::Open);
+
+	let event_attributes = event_attributes(ctx, &msg.port_id, msg.channel_id, &channel_end);
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade_origin: None,
+	};
+
+	output.emit(IbcEvent::ChannelUpgradeConfirm(
+		event_attributes.try_into().map_err(|_| Error::missing_channel_id())?,
+	));
+
+	Ok(output.with_result(result))
+}
+
+pub(crate) fn process_cancel<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeCancel,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let channel_end = ctx.channel_end(&(msg.port_id.clone(), msg.channel_id))?;
+	let origin = ctx.channel_upgrade_origin(&(msg.port_id.clone(), msg.channel_id))?;
+
+	match channel_end.state {
+		// Until the upgrade times out, only an authorized signer may abandon it.
+		State::InitUpgrade =>
+			if !ctx.is_upgrade_authorized(&msg.port_id, &msg.channel_id, &msg.signer) {
+				let elapsed =
+					ctx.host_height().revision_height.saturating_sub(origin.height.revision_height);
+				let timeout = ctx.handshake_timeout();
+				if elapsed < timeout {
+					return Err(Error::upgrade_not_timed_out(msg.channel_id, elapsed, timeout))
+				}
+			},
+		// The counterparty proposed this upgrade, so it is only abandoned once the counterparty
+		// is back to `Open` with the previous fields at the same upgrade sequence.
+		State::TryUpgrade => {
+			let proofs = msg.proofs.as_ref().ok_or_else(Error::missing_channel_proof)?;
+			let conn = open_connection(ctx, &channel_end)?;
+
+			let expected_channel_end = expected_counterparty_end(
+				&msg.port_id,
+				msg.channel_id,
+				&channel_end,
+				&conn,
+				State::Open,
+				&origin.previous,
+				channel_end.upgrade_sequence(),
+			)?;
+
+			verify_channel_proofs::<Ctx>(
+				ctx,
+				proofs.height(),
+				&channel_end,
+				&conn,
+				&expected_channel_end,
+				&proofs.object_proof(),
+			)?;
+		},
+		_ => return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state)),
+	}
+
+	output.log("success: channel upgrade cancelled ");
+
+	// The upgrade sequence is kept, so that a new proposal can't be mistaken for this one.
+	let mut channel_end = origin.previous.apply_to(channel_end);
+	channel_end.set_state(State::Open);
+
+	let event_attributes = event_attributes(ctx, &msg.port_id, msg.channel_id, &channel_end);
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade_origin: None,
+	};
+
+	output.emit(IbcEvent::ChannelUpgradeCancel(
+		event_attributes.try_into().map_err(|_| Error::missing_channel_id())?,
+	));
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				msgs::test_util::get_dummy_raw_counterparty,
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				context::{ChannelKeeper, ChannelReader},
+				error,
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_ack::{
+						test_util::get_dummy_raw_msg_chan_upgrade_ack, MsgChannelUpgradeAck,
+					},
+					chan_upgrade_cancel::{
+						test_util::get_dummy_raw_msg_chan_upgrade_cancel, MsgChannelUpgradeCancel,
+					},
+					chan_upgrade_confirm::{
+						test_util::get_dummy_raw_msg_chan_upgrade_confirm, MsgChannelUpgradeConfirm,
+					},
+					chan_upgrade_init::{
+						test_util::get_dummy_raw_msg_chan_upgrade_init, MsgChannelUpgradeInit,
+					},
+					chan_upgrade_try::{
+						test_util::get_dummy_raw_msg_chan_upgrade_try, MsgChannelUpgradeTry,
+					},
+					ChannelMsg,
+				},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		},
+		events::IbcEvent,
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+		},
+		test_utils::get_dummy_bech32_account,
+		timestamp::ZERO_DURATION,
+	};
+
+	/// A context holding an open, ordered `ics20` channel on the default port and channel ids,
+	/// which the dummy signer is authorized to upgrade.
+	fn context_with_open_channel() -> MockContext<MockClientTypes> {
+		let client_id = ClientId::new(&MockClientState::client_type(), 24).unwrap();
+		let conn_id = ConnectionId::new(2);
+
+		let conn_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			client_id.clone(),
+			ConnectionCounterparty::try_from(get_dummy_raw_counterparty()).unwrap(),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+
+		let chan_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			Counterparty::new(PortId::default(), Some(ChannelId::default())),
+			vec![conn_id.clone()],
+			Version::ics20(),
+		);
+
+		let default_context = MockContext::<MockClientTypes>::default();
+		let client_consensus_state_height = default_context.host_height();
+
+		default_context
+			.with_client(&client_id, client_consensus_state_height)
+			.with_connection(conn_id, conn_end)
+			.with_channel(PortId::default(), ChannelId::default(), chan_end)
+			.with_channel_upgrade_authority(
+				PortId::default(),
+				get_dummy_bech32_account().parse().unwrap(),
+			)
+			.with_handshake_timeout(2)
+	}
+
+	fn assert_restored(ctx: &MockContext<MockClientTypes>) {
+		let port_channel_id = (PortId::default(), ChannelId::default());
+		let chan_end = ctx.channel_end(&port_channel_id).unwrap();
+		assert!(chan_end.state_matches(&State::Open));
+		assert!(chan_end.order_matches(&Order::Ordered));
+		assert_eq!(chan_end.version(), &Version::ics20());
+		assert_eq!(chan_end.upgrade_sequence(), 1);
+		assert!(ctx.channel_upgrade_origin(&port_channel_id).is_err());
+	}
+
+	fn init_upgrade(ctx: &mut MockContext<MockClientTypes>) {
+		let msg = MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+		let (_, result) = channel_dispatch(&*ctx, &ChannelMsg::ChannelUpgradeInit(msg)).unwrap();
+		ctx.store_channel_result(result).unwrap();
+	}
+
+	fn assert_upgraded(ctx: &MockContext<MockClientTypes>) {
+		let port_channel_id = (PortId::default(), ChannelId::default());
+		let chan_end = ctx.channel_end(&port_channel_id).unwrap();
+		assert!(chan_end.state_matches(&State::Open));
+		assert!(chan_end.order_matches(&Order::Unordered));
+		assert_eq!(chan_end.version(), &Version::new("ics20-2".to_string()));
+		assert_eq!(chan_end.upgrade_sequence(), 1);
+		assert!(ctx.channel_upgrade_origin(&port_channel_id).is_err());
+	}
+
+	#[test]
+	fn chan_upgrade_init_then_ack() {
+		let mut ctx = context_with_open_channel();
+		let proof_height = ctx.host_height().revision_height;
+
+		let msg = MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+		let (output, result) =
+			channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeInit(msg)).unwrap();
+		let output = output.with_result(());
+		assert!(matches!(output.events[0], IbcEvent::ChannelUpgradeInit(_)));
+		assert!(result.channel_end.state_matches(&State::InitUpgrade));
+		assert_eq!(result.channel_end.upgrade_sequence(), 1);
+		ctx.store_channel_result(result).unwrap();
+
+		let origin =
+			ctx.channel_upgrade_origin(&(PortId::default(), ChannelId::default())).unwrap();
+		assert_eq!(origin.previous.ordering, Order::Ordered);
+		assert_eq!(origin.previous.version, Version::ics20());
+
+		let msg =
+			MsgChannelUpgradeAck::try_from(get_dummy_raw_msg_chan_upgrade_ack(1, proof_height))
+				.unwrap();
+		let (output, result) = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeAck(msg)).unwrap();
+		let output = output.with_result(());
+		assert!(matches!(output.events[0], IbcEvent::ChannelUpgradeAck(_)));
+		ctx.store_channel_result(result).unwrap();
+
+		assert_upgraded(&ctx);
+	}
+
+	#[test]
+	fn chan_upgrade_try_then_confirm() {
+		let mut ctx = context_with_open_channel();
+		let proof_height = ctx.host_height().revision_height;
+
+		let msg =
+			MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(1, proof_height))
+				.unwrap();
+		let (output, result) = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeTry(msg)).unwrap();
+		let output = output.with_result(());
+		assert!(matches!(output.events[0], IbcEvent::ChannelUpgradeTry(_)));
+		assert!(result.channel_end.state_matches(&State::TryUpgrade));
+		ctx.store_channel_result(result).unwrap();
+
+		let msg = MsgChannelUpgradeConfirm::try_from(get_dummy_raw_msg_chan_upgrade_confirm(
+			proof_height,
+		))
+		.unwrap();
+		let (output, result) =
+			channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeConfirm(msg)).unwrap();
+		let output = output.with_result(());
+		assert!(matches!(output.events[0], IbcEvent::ChannelUpgradeConfirm(_)));
+		ctx.store_channel_result(result).unwrap();
+
+		assert_upgraded(&ctx);
+	}
+
+	#[test]
+	fn chan_upgrade_ack_rejects_sequence_mismatch() {
+		let mut ctx = context_with_open_channel();
+		let proof_height = ctx.host_height().revision_height;
+
+		init_upgrade(&mut ctx);
+
+		let msg =
+			MsgChannelUpgradeAck::try_from(get_dummy_raw_msg_chan_upgrade_ack(2, proof_height))
+				.unwrap();
+		let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeAck(msg));
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::UpgradeSequenceMismatch(e) => {
+					assert_eq!(e.expected, 1);
+					assert_eq!(e.actual, 2);
+				},
+				_ => panic!("Expected UpgradeSequenceMismatch error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected UpgradeSequenceMismatch error"),
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_init_rejects_unchanged_fields() {
+		let ctx = context_with_open_channel();
+
+		let mut raw = get_dummy_raw_msg_chan_upgrade_init();
+		if let Some(fields) = raw.fields.as_mut() {
+			fields.ordering = Order::Ordered as i32;
+			fields.version = Version::ics20().to_string();
+		}
+		let msg = MsgChannelUpgradeInit::try_from(raw).unwrap();
+		let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeInit(msg));
+		assert!(res.is_err());
+	}
+
+	#[test]
+	fn chan_upgrade_init_rejects_unauthorized_signer() {
+		let ctx = context_with_open_channel();
+
+		let mut msg =
+			MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+		msg.signer = "someoneelse".parse().unwrap();
+		let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeInit(msg));
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::UnauthorizedChannelUpgrade(_) => {},
+				_ => panic!("Expected UnauthorizedChannelUpgrade error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected UnauthorizedChannelUpgrade error"),
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_by_authority_before_timeout() {
+		let mut ctx = context_with_open_channel();
+		init_upgrade(&mut ctx);
+
+		let msg =
+			MsgChannelUpgradeCancel::try_from(get_dummy_raw_msg_chan_upgrade_cancel(None)).unwrap();
+		let (output, result) =
+			channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeCancel(msg)).unwrap();
+		let output = output.with_result(());
+		assert!(matches!(output.events[0], IbcEvent::ChannelUpgradeCancel(_)));
+		ctx.store_channel_result(result).unwrap();
+
+		assert_restored(&ctx);
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_by_other_account_respects_timeout() {
+		let mut ctx = context_with_open_channel();
+		init_upgrade(&mut ctx);
+
+		let mut msg =
+			MsgChannelUpgradeCancel::try_from(get_dummy_raw_msg_chan_upgrade_cancel(None)).unwrap();
+		msg.signer = "someoneelse".parse().unwrap();
+		let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeCancel(msg.clone()));
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::UpgradeNotTimedOut(e) => {
+					assert_eq!(e.elapsed, 0);
+					assert_eq!(e.timeout, 2);
+				},
+				_ => panic!("Expected UpgradeNotTimedOut error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected UpgradeNotTimedOut error"),
+		}
+
+		ctx.advance_host_chain_height();
+		ctx.advance_host_chain_height();
+		let (_, result) = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeCancel(msg)).unwrap();
+		ctx.store_channel_result(result).unwrap();
+
+		assert_restored(&ctx);
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_from_try_requires_counterparty_proof() {
+		let mut ctx = context_with_open_channel();
+		let proof_height = ctx.host_height().revision_height;
+
+		let msg =
+			MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(1, proof_height))
+				.unwrap();
+		let (_, result) = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeTry(msg)).unwrap();
+		ctx.store_channel_result(result).unwrap();
+
+		let msg =
+			MsgChannelUpgradeCancel::try_from(get_dummy_raw_msg_chan_upgrade_cancel(None)).unwrap();
+		let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeCancel(msg));
+		match res {
+			Err(e) => match e.detail() {
+				error::ErrorDetail::MissingChannelProof(_) => {},
+				_ => panic!("Expected MissingChannelProof error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected MissingChannelProof error"),
+		}
+
+		let msg = MsgChannelUpgradeCancel::try_from(get_dummy_raw_msg_chan_upgrade_cancel(Some(
+			proof_height,
+		)))
+		.unwrap();
+		let (_, result) = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeCancel(msg)).unwrap();
+		ctx.store_channel_result(result).unwrap();
+
+		assert_restored(&ctx);
+	}
+}
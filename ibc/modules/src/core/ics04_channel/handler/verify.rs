@@ -15,16 +15,22 @@
 use crate::{
 	core::{
 		ics02_client::{
-			client_consensus::ConsensusState, client_def::ClientDef, client_state::ClientState,
+			client_consensus::ConsensusState,
+			client_def::ClientDef,
+			client_state::ClientState,
+			error::ErrorDetail as Ics02ErrorDetail,
+			handler::{ensure_client_not_expired, proof_consensus_state},
 		},
-		ics03_connection::connection::ConnectionEnd,
+		ics03_connection::{connection::ConnectionEnd, delay::verify_conn_delay_passed},
 		ics04_channel::{
 			channel::ChannelEnd,
+			commitment::{compute_ack_commitment, compute_packet_commitment},
 			error::Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::{Packet, Sequence},
 		},
-		ics23_commitment::commitment::CommitmentProofBytes,
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes},
+		ics24_host::identifier::ClientId,
 		ics26_routing::context::ReaderContext,
 	},
 	prelude::*,
@@ -49,14 +55,13 @@ where
 
 	let client_state = ctx.client_state(&client_id).map_err(Error::ics02_client)?;
 
-	// The client must not be frozen.
+	// The client must be neither frozen nor expired.
 	if client_state.is_frozen() {
 		return Err(Error::frozen_client(client_id))
 	}
+	ensure_client_not_expired(ctx, &client_id, &client_state).map_err(Error::ics02_client)?;
 
-	let consensus_state = ctx
-		.consensus_state(&client_id, height)
-		.map_err(|_| Error::error_invalid_consensus_state())?;
+	let consensus_state = verification_consensus_state(ctx, &client_id, height)?;
 
 	let client_def = client_state.client_def();
 
@@ -68,7 +73,7 @@ where
 			&client_id,
 			&client_state,
 			height,
-			connection_end.counterparty().prefix(),
+			counterparty_prefix(connection_end)?,
 			&proof,
 			consensus_state.root(),
 			channel_end.counterparty().port_id(),
@@ -81,6 +86,16 @@ where
 		.map_err(Error::verify_channel_failed)
 }
 
+/// Returns the counterparty's commitment prefix of `connection_end`, once it has been checked to
+/// be usable for building the paths that proofs are verified against.
+fn counterparty_prefix(connection_end: &ConnectionEnd) -> Result<&CommitmentPrefix, Error> {
+	let prefix = connection_end.counterparty().prefix();
+	prefix
+		.validate()
+		.map_err(|e| Error::invalid_counterparty_prefix(prefix.clone(), e))?;
+	Ok(prefix)
+}
+
 /// Entry point for verifying all proofs bundled in a ICS4 packet recv. message.
 pub fn verify_packet_recv_proofs<Ctx: ReaderContext>(
 	ctx: &Ctx,
@@ -89,29 +104,66 @@ pub fn verify_packet_recv_proofs<Ctx: ReaderContext>(
 	connection_end: &ConnectionEnd,
 	proofs: &Proofs,
 ) -> Result<(), Error> {
+	let (client_state, consensus_state) =
+		proof_verification_states(ctx, connection_end, proofs.height())?;
+
+	verify_packet_recv_proofs_with_states(
+		ctx,
+		&client_state,
+		&consensus_state,
+		height,
+		packet,
+		connection_end,
+		proofs,
+	)
+}
+
+/// Returns the client state of the connection's client along with its consensus state at
+/// `height`, which together verify proofs made at that height.
+pub fn proof_verification_states<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	connection_end: &ConnectionEnd,
+	height: Height,
+) -> Result<(Ctx::AnyClientState, Ctx::AnyConsensusState), Error> {
 	let client_id = connection_end.client_id();
 	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
 
-	// The client must not be frozen.
+	// The client must be neither frozen nor expired.
 	if client_state.is_frozen() {
 		return Err(Error::frozen_client(client_id.clone()))
 	}
+	ensure_client_not_expired(ctx, client_id, &client_state).map_err(Error::ics02_client)?;
+
+	let consensus_state = verification_consensus_state(ctx, client_id, height)?;
 
-	let consensus_state = ctx
-		.consensus_state(client_id, proofs.height())
-		.map_err(|_| Error::error_invalid_consensus_state())?;
+	Ok((client_state, consensus_state))
+}
 
+/// Verifies the proofs of a packet recv. message against states returned by
+/// [`proof_verification_states`], so that packets proven at the same height can share them.
+pub fn verify_packet_recv_proofs_with_states<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_state: &Ctx::AnyClientState,
+	consensus_state: &Ctx::AnyConsensusState,
+	height: Height,
+	packet: &Packet,
+	connection_end: &ConnectionEnd,
+	proofs: &Proofs,
+) -> Result<(), Error> {
+	let client_id = connection_end.client_id();
 	let client_def = client_state.client_def();
+	counterparty_prefix(connection_end)?;
+	verify_conn_delay_passed(ctx, height, connection_end)?;
 
 	let commitment =
-		ctx.packet_commitment(packet.data.clone(), packet.timeout_height, packet.timeout_timestamp);
+		compute_packet_commitment(&packet.data, &packet.timeout_height, &packet.timeout_timestamp);
 
 	// Verify the proof for the packet against the chain store.
 	client_def
 		.verify_packet_data(
 			ctx,
 			client_id,
-			&client_state,
+			client_state,
 			height,
 			connection_end,
 			proofs.object_proof(),
@@ -137,17 +189,18 @@ pub fn verify_packet_acknowledgement_proofs<Ctx: ReaderContext>(
 ) -> Result<(), Error> {
 	let client_id = connection_end.client_id();
 	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
+	counterparty_prefix(connection_end)?;
 
-	// The client must not be frozen.
+	// The client must be neither frozen nor expired.
 	if client_state.is_frozen() {
 		return Err(Error::frozen_client(client_id.clone()))
 	}
+	ensure_client_not_expired(ctx, client_id, &client_state).map_err(Error::ics02_client)?;
 
-	let consensus_state = ctx
-		.consensus_state(client_id, proofs.height())
-		.map_err(|_| Error::error_invalid_consensus_state())?;
+	let consensus_state = verification_consensus_state(ctx, client_id, proofs.height())?;
+	verify_conn_delay_passed(ctx, height, connection_end)?;
 
-	let ack_commitment = ctx.ack_commitment(acknowledgement);
+	let ack_commitment = compute_ack_commitment(&acknowledgement);
 
 	let client_def = client_state.client_def();
 
@@ -171,13 +224,15 @@ pub fn verify_packet_acknowledgement_proofs<Ctx: ReaderContext>(
 	Ok(())
 }
 
-/// Entry point for verifying all timeout proofs.
+/// Entry point for verifying the timeout proofs of ordered channels: the counterparty stored
+/// `next_sequence_recv` at the ICS-024 `nextSequenceRecv/ports/{port}/channels/{channel}` path
+/// of the packet's destination, under the counterparty's commitment prefix.
 pub fn verify_next_sequence_recv<Ctx>(
 	ctx: &Ctx,
 	height: Height,
 	connection_end: &ConnectionEnd,
 	packet: Packet,
-	seq: Sequence,
+	next_sequence_recv: Sequence,
 	proofs: &Proofs,
 ) -> Result<(), Error>
 where
@@ -185,19 +240,21 @@ where
 {
 	let client_id = connection_end.client_id();
 	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
+	counterparty_prefix(connection_end)?;
 
-	// The client must not be frozen.
+	// The client must be neither frozen nor expired.
 	if client_state.is_frozen() {
 		return Err(Error::frozen_client(client_id.clone()))
 	}
+	ensure_client_not_expired(ctx, client_id, &client_state).map_err(Error::ics02_client)?;
 
-	let consensus_state = ctx
-		.consensus_state(client_id, proofs.height())
-		.map_err(|_| Error::error_invalid_consensus_state())?;
+	let consensus_state = verification_consensus_state(ctx, client_id, proofs.height())?;
+	verify_conn_delay_passed(ctx, height, connection_end)?;
 
 	let client_def = client_state.client_def();
 
-	// Verify the proof for the packet against the chain store.
+	// Verify the proof for the counterparty's next receive sequence against the chain store, it's
+	// the proven value and not the packet's sequence, which is only known not to be below it.
 	client_def
 		.verify_next_sequence_recv(
 			ctx,
@@ -209,9 +266,9 @@ where
 			consensus_state.root(),
 			&packet.destination_port,
 			&packet.destination_channel,
-			packet.sequence,
+			next_sequence_recv,
 		)
-		.map_err(|e| Error::packet_verification_failed(seq, e))?;
+		.map_err(|e| Error::packet_verification_failed(packet.sequence, e))?;
 
 	Ok(())
 }
@@ -228,15 +285,16 @@ where
 {
 	let client_id = connection_end.client_id();
 	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
+	counterparty_prefix(connection_end)?;
 
-	// The client must not be frozen.
+	// The client must be neither frozen nor expired.
 	if client_state.is_frozen() {
 		return Err(Error::frozen_client(client_id.clone()))
 	}
+	ensure_client_not_expired(ctx, client_id, &client_state).map_err(Error::ics02_client)?;
 
-	let consensus_state = ctx
-		.consensus_state(client_id, proofs.height())
-		.map_err(|_| Error::error_invalid_consensus_state())?;
+	let consensus_state = verification_consensus_state(ctx, client_id, proofs.height())?;
+	verify_conn_delay_passed(ctx, height, connection_end)?;
 
 	let client_def = client_state.client_def();
 
@@ -258,3 +316,15 @@ where
 
 	Ok(())
 }
+
+/// The consensus state of `client_id` that proofs at `height` are verified against.
+fn verification_consensus_state<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	client_id: &ClientId,
+	height: Height,
+) -> Result<Ctx::AnyConsensusState, Error> {
+	proof_consensus_state(ctx, client_id, height).map_err(|e| match e.detail() {
+		Ics02ErrorDetail::ConsensusStatePruned(_) => Error::ics02_client(e),
+		_ => Error::error_invalid_consensus_state(),
+	})
+}
@@ -15,10 +15,11 @@
 use crate::{
 	core::{
 		ics04_channel::{
-			channel::State,
-			commitment::AcknowledgementCommitment,
+			channel::{Order, State},
+			commitment::{compute_ack_commitment, AcknowledgementCommitment},
 			error::Error,
 			events::WriteAcknowledgement,
+			msgs::acknowledgement::Acknowledgement,
 			packet::{Packet, PacketResult, Sequence},
 		},
 		ics24_host::identifier::{ChannelId, PortId},
@@ -44,11 +45,27 @@ pub fn process<Ctx: ReaderContext>(
 ) -> HandlerResult<PacketResult, Error> {
 	let mut output = HandlerOutput::builder();
 
-	let dest_channel_end =
-		ctx.channel_end(&(packet.destination_port.clone(), packet.destination_channel))?;
+	let port_channel_id = (packet.destination_port.clone(), packet.destination_channel);
+	let dest_channel_end = ctx.channel_end(&port_channel_id)?;
 
 	if !dest_channel_end.state_matches(&State::Open) {
-		return Err(Error::invalid_channel_state(packet.source_channel, dest_channel_end.state))
+		return Err(Error::invalid_channel_state(packet.destination_channel, dest_channel_end.state))
+	}
+
+	// Only packets this chain has received can be acknowledged. Ordered channels track the next
+	// sequence to receive instead of a receipt per packet.
+	let received = if dest_channel_end.order_matches(&Order::Unordered) {
+		ctx.get_packet_receipt(&(
+			packet.destination_port.clone(),
+			packet.destination_channel,
+			packet.sequence,
+		))
+		.is_ok()
+	} else {
+		ctx.get_next_sequence_recv(&port_channel_id)? > packet.sequence
+	};
+	if !received {
+		return Err(Error::packet_receipt_not_found(packet.sequence))
 	}
 
 	// NOTE: IBC app modules might have written the acknowledgement synchronously on
@@ -70,13 +87,18 @@ pub fn process<Ctx: ReaderContext>(
 	}
 
 	let result = PacketResult::WriteAck(WriteAckPacketResult {
-		port_id: packet.source_port.clone(),
-		channel_id: packet.source_channel,
+		port_id: packet.destination_port.clone(),
+		channel_id: packet.destination_channel,
 		seq: packet.sequence,
-		ack_commitment: ctx.ack_commitment(ack.clone().into()),
+		ack_commitment: compute_ack_commitment(&Acknowledgement::from(ack.clone())),
 	});
 
 	output.log("success: packet write acknowledgement");
+	output.log_kv("packet_src_port", &packet.source_port);
+	output.log_kv("packet_src_channel", &packet.source_channel);
+	output.log_kv("packet_dst_port", &packet.destination_port);
+	output.log_kv("packet_dst_channel", &packet.destination_channel);
+	output.log_kv("packet_sequence", packet.sequence);
 
 	output.emit(IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
 		height: ctx.host_height(),
@@ -104,8 +126,9 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
+				context::ChannelKeeper,
 				handler::write_acknowledgement::process,
-				packet::{test_utils::get_dummy_raw_packet, Packet},
+				packet::{test_utils::get_dummy_raw_packet, Packet, Receipt},
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -156,6 +179,23 @@ mod tests {
 			ZERO_DURATION,
 		);
 
+		let context_with_channel = context
+			.clone()
+			.with_client(&ClientId::default(), client_height)
+			.with_connection(ConnectionId::default(), connection_end.clone())
+			.with_channel(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				dest_channel_end.clone(),
+			);
+		let mut context_with_receipt = context_with_channel.clone();
+		context_with_receipt
+			.store_packet_receipt(
+				(packet.destination_port.clone(), packet.destination_channel, packet.sequence),
+				Receipt::Ok,
+			)
+			.unwrap();
+
 		let tests: Vec<Test> = vec![
 			Test {
 				name: "Processing fails because no channel exists in the context".to_string(),
@@ -164,17 +204,16 @@ mod tests {
 				ack: ack.clone(),
 				want_pass: false,
 			},
+			Test {
+				name: "Processing fails because the packet was not received".to_string(),
+				ctx: context_with_channel.clone(),
+				packet: packet.clone(),
+				ack: ack.clone(),
+				want_pass: false,
+			},
 			Test {
 				name: "Good parameters".to_string(),
-				ctx: context
-					.clone()
-					.with_client(&ClientId::default(), client_height)
-					.with_connection(ConnectionId::default(), connection_end.clone())
-					.with_channel(
-						packet.destination_port.clone(),
-						packet.destination_channel,
-						dest_channel_end.clone(),
-					),
+				ctx: context_with_receipt,
 				packet: packet.clone(),
 				ack,
 				want_pass: true,
@@ -12,18 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Message definitions for all ICS4 domain types: channel open & close handshake datagrams, as well
-//! as packets.
+//! Message definitions for all ICS4 domain types: channel open, close & upgrade handshake
+//! datagrams, as well as packets.
 
 use crate::core::{
 	ics04_channel::{
 		error::Error,
 		msgs::{
 			acknowledgement::MsgAcknowledgement, chan_close_confirm::MsgChannelCloseConfirm,
-			chan_close_init::MsgChannelCloseInit, chan_open_ack::MsgChannelOpenAck,
+			chan_close_frozen::MsgChannelCloseFrozen, chan_close_init::MsgChannelCloseInit,
+			chan_open_ack::MsgChannelOpenAck,
 			chan_open_confirm::MsgChannelOpenConfirm, chan_open_init::MsgChannelOpenInit,
-			chan_open_try::MsgChannelOpenTry, recv_packet::MsgRecvPacket, timeout::MsgTimeout,
-			timeout_on_close::MsgTimeoutOnClose,
+			chan_open_try::MsgChannelOpenTry, chan_upgrade_ack::MsgChannelUpgradeAck,
+			chan_upgrade_cancel::MsgChannelUpgradeCancel,
+			chan_upgrade_confirm::MsgChannelUpgradeConfirm,
+			chan_upgrade_init::MsgChannelUpgradeInit, chan_upgrade_try::MsgChannelUpgradeTry,
+			prune_acknowledgements::MsgPruneAcknowledgements, recv_packet::MsgRecvPacket,
+			timeout::MsgTimeout, timeout_on_close::MsgTimeoutOnClose,
 		},
 	},
 	ics26_routing::context::{Ics26Context, ModuleId},
@@ -37,10 +42,19 @@ pub mod chan_open_try;
 
 // Closing handshake messages.
 pub mod chan_close_confirm;
+pub mod chan_close_frozen;
 pub mod chan_close_init;
 
+// Upgrade handshake messages.
+pub mod chan_upgrade_ack;
+pub mod chan_upgrade_cancel;
+pub mod chan_upgrade_confirm;
+pub mod chan_upgrade_init;
+pub mod chan_upgrade_try;
+
 // Packet specific messages.
 pub mod acknowledgement;
+pub mod prune_acknowledgements;
 pub mod recv_packet;
 pub mod timeout;
 pub mod timeout_on_close;
@@ -54,6 +68,12 @@ pub enum ChannelMsg {
 	ChannelOpenConfirm(MsgChannelOpenConfirm),
 	ChannelCloseInit(MsgChannelCloseInit),
 	ChannelCloseConfirm(MsgChannelCloseConfirm),
+	ChannelCloseFrozen(MsgChannelCloseFrozen),
+	ChannelUpgradeInit(MsgChannelUpgradeInit),
+	ChannelUpgradeTry(MsgChannelUpgradeTry),
+	ChannelUpgradeAck(MsgChannelUpgradeAck),
+	ChannelUpgradeConfirm(MsgChannelUpgradeConfirm),
+	ChannelUpgradeCancel(MsgChannelUpgradeCancel),
 }
 
 impl ChannelMsg {
@@ -71,6 +91,18 @@ impl ChannelMsg {
 				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
 			ChannelMsg::ChannelCloseConfirm(msg) =>
 				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelCloseFrozen(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeInit(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeTry(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeAck(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeConfirm(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
+			ChannelMsg::ChannelUpgradeCancel(msg) =>
+				ctx.lookup_module_by_port(&msg.port_id).map_err(Error::ics05_port)?,
 		};
 		Ok(module_id)
 	}
@@ -82,4 +114,5 @@ pub enum PacketMsg {
 	AckPacket(MsgAcknowledgement),
 	ToPacket(MsgTimeout),
 	ToClosePacket(MsgTimeoutOnClose),
+	PruneAcknowledgements(MsgPruneAcknowledgements),
 }
@@ -27,6 +27,10 @@ use crate::{
 
 pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgAcknowledgement";
 
+/// Acknowledgement written by the receiving end of an `OrderedAllowTimeout` channel, in place of
+/// the module's acknowledgement, for a packet that arrived after its timeout.
+pub const TIMEOUT_ACKNOWLEDGEMENT: &[u8] = b"ibc-timeout";
+
 /// A generic Acknowledgement type that modules may interpret as they like.
 #[derive(Clone, Debug, PartialEq)]
 pub struct Acknowledgement(Vec<u8>);
@@ -39,6 +43,16 @@ impl Acknowledgement {
 	pub fn from_bytes(bytes: Vec<u8>) -> Self {
 		bytes.into()
 	}
+
+	/// The sentinel acknowledgement for a packet that timed out on an `OrderedAllowTimeout`
+	/// channel.
+	pub fn timeout() -> Self {
+		TIMEOUT_ACKNOWLEDGEMENT.to_vec().into()
+	}
+
+	pub fn is_timeout(&self) -> bool {
+		self.0 == TIMEOUT_ACKNOWLEDGEMENT
+	}
 }
 
 impl From<Vec<u8>> for Acknowledgement {
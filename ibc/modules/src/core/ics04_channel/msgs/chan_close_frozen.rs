@@ -0,0 +1,153 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::channel::v1::MsgChannelCloseFrozen as RawMsgChannelCloseFrozen;
+
+use crate::{
+	core::{
+		ics04_channel::error::Error,
+		ics24_host::identifier::{ChannelId, ClientId, PortId},
+	},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgChannelCloseFrozen";
+
+///
+/// Message definition for closing a channel whose underlying client is frozen
+/// (`ChanCloseFrozen` datagram). Unlike `ChanCloseConfirm` it carries no proof, since the frozen
+/// client can no longer verify one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgChannelCloseFrozen {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub client_id: ClientId,
+	pub signer: Signer,
+}
+
+impl MsgChannelCloseFrozen {
+	pub fn new(
+		port_id: PortId,
+		channel_id: ChannelId,
+		client_id: ClientId,
+		signer: Signer,
+	) -> Self {
+		Self { port_id, channel_id, client_id, signer }
+	}
+}
+
+impl Msg for MsgChannelCloseFrozen {
+	type ValidationError = Error;
+	type Raw = RawMsgChannelCloseFrozen;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgChannelCloseFrozen> for MsgChannelCloseFrozen {}
+
+impl TryFrom<RawMsgChannelCloseFrozen> for MsgChannelCloseFrozen {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgChannelCloseFrozen) -> Result<Self, Self::Error> {
+		Ok(MsgChannelCloseFrozen {
+			port_id: raw_msg.port_id.parse().map_err(Error::identifier)?,
+			channel_id: raw_msg.channel_id.parse().map_err(Error::identifier)?,
+			client_id: raw_msg.client_id.parse().map_err(Error::identifier)?,
+			signer: raw_msg.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgChannelCloseFrozen> for RawMsgChannelCloseFrozen {
+	fn from(domain_msg: MsgChannelCloseFrozen) -> Self {
+		RawMsgChannelCloseFrozen {
+			port_id: domain_msg.port_id.to_string(),
+			channel_id: domain_msg.channel_id.to_string(),
+			client_id: domain_msg.client_id.to_string(),
+			signer: domain_msg.signer.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::prelude::*;
+	use ibc_proto::ibc::core::channel::v1::MsgChannelCloseFrozen as RawMsgChannelCloseFrozen;
+
+	use crate::{
+		core::ics24_host::identifier::{ChannelId, ClientId, PortId},
+		test_utils::get_dummy_bech32_account,
+	};
+
+	/// Returns a dummy `RawMsgChannelCloseFrozen` for the given client, for testing only!
+	pub fn get_dummy_raw_msg_chan_close_frozen(client_id: &ClientId) -> RawMsgChannelCloseFrozen {
+		RawMsgChannelCloseFrozen {
+			port_id: PortId::default().to_string(),
+			channel_id: ChannelId::default().to_string(),
+			client_id: client_id.to_string(),
+			signer: get_dummy_bech32_account(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+
+	use ibc_proto::ibc::core::channel::v1::MsgChannelCloseFrozen as RawMsgChannelCloseFrozen;
+
+	use crate::core::{
+		ics04_channel::msgs::chan_close_frozen::{
+			test_util::get_dummy_raw_msg_chan_close_frozen, MsgChannelCloseFrozen,
+		},
+		ics24_host::identifier::ClientId,
+	};
+
+	#[test]
+	fn parse_channel_close_frozen_msg() {
+		let default_raw_msg = get_dummy_raw_msg_chan_close_frozen(&ClientId::default());
+		assert!(MsgChannelCloseFrozen::try_from(default_raw_msg.clone()).is_ok());
+
+		let bad_client =
+			RawMsgChannelCloseFrozen { client_id: "x".to_string(), ..default_raw_msg.clone() };
+		assert!(MsgChannelCloseFrozen::try_from(bad_client).is_err());
+
+		let bad_channel =
+			RawMsgChannelCloseFrozen { channel_id: "chshort".to_string(), ..default_raw_msg };
+		assert!(MsgChannelCloseFrozen::try_from(bad_channel).is_err());
+	}
+
+	#[test]
+	fn to_and_from() {
+		let raw = get_dummy_raw_msg_chan_close_frozen(&ClientId::default());
+		let msg = MsgChannelCloseFrozen::try_from(raw.clone()).unwrap();
+		let raw_back = RawMsgChannelCloseFrozen::from(msg.clone());
+		let msg_back = MsgChannelCloseFrozen::try_from(raw_back.clone()).unwrap();
+		assert_eq!(raw, raw_back);
+		assert_eq!(msg, msg_back);
+	}
+}
@@ -62,7 +62,7 @@ impl TryFrom<RawMsgChannelOpenInit> for MsgChannelOpenInit {
 
 	fn try_from(raw_msg: RawMsgChannelOpenInit) -> Result<Self, Self::Error> {
 		Ok(MsgChannelOpenInit {
-			port_id: raw_msg.port_id.parse().map_err(Error::identifier)?,
+			port_id: PortId::new_strict(&raw_msg.port_id).map_err(Error::identifier)?,
 			channel: raw_msg.channel.ok_or_else(Error::missing_channel)?.try_into()?,
 			signer: raw_msg.signer.parse().map_err(Error::signer)?,
 		})
@@ -138,6 +138,14 @@ mod tests {
 				},
 				want_pass: false,
 			},
+			Test {
+				name: "Incorrect port identifier, non ascii character".to_string(),
+				raw: RawMsgChannelOpenInit {
+					port_id: "trånsfer".to_string(),
+					..default_raw_msg.clone()
+				},
+				want_pass: false,
+			},
 			Test {
 				name: "Missing channel".to_string(),
 				raw: RawMsgChannelOpenInit { channel: None, ..default_raw_msg },
@@ -87,7 +87,7 @@ impl TryFrom<RawMsgChannelOpenTry> for MsgChannelOpenTry {
 		.map_err(ChannelError::invalid_proof)?;
 
 		let msg = MsgChannelOpenTry {
-			port_id: raw_msg.port_id.parse().map_err(ChannelError::identifier)?,
+			port_id: PortId::new_strict(&raw_msg.port_id).map_err(ChannelError::identifier)?,
 			channel: raw_msg.channel.ok_or_else(ChannelError::missing_channel)?.try_into()?,
 			counterparty_version: raw_msg.counterparty_version.into(),
 			proofs,
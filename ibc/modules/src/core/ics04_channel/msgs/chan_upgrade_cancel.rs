@@ -0,0 +1,160 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	core::{
+		ics04_channel::error::Error,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	prelude::*,
+	proofs::Proofs,
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+use ibc_proto::ibc::core::channel::v1::MsgChannelUpgradeCancel as RawMsgChannelUpgradeCancel;
+use tendermint_proto::Protobuf;
+
+pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgChannelUpgradeCancel";
+
+///
+/// Message definition for aborting a channel upgrade handshake (`ChanUpgradeCancel` datagram).
+/// The proof of the counterparty channel end is only needed to cancel from `TryUpgrade`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgChannelUpgradeCancel {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub proofs: Option<Proofs>,
+	pub signer: Signer,
+}
+
+impl MsgChannelUpgradeCancel {
+	pub fn new(
+		port_id: PortId,
+		channel_id: ChannelId,
+		proofs: Option<Proofs>,
+		signer: Signer,
+	) -> Self {
+		Self { port_id, channel_id, proofs, signer }
+	}
+}
+
+impl Msg for MsgChannelUpgradeCancel {
+	type ValidationError = Error;
+	type Raw = RawMsgChannelUpgradeCancel;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgChannelUpgradeCancel> for MsgChannelUpgradeCancel {}
+
+impl TryFrom<RawMsgChannelUpgradeCancel> for MsgChannelUpgradeCancel {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgChannelUpgradeCancel) -> Result<Self, Self::Error> {
+		let proofs = if raw_msg.proof_channel.is_empty() {
+			None
+		} else {
+			Some(
+				Proofs::new(
+					raw_msg.proof_channel.try_into().map_err(Error::invalid_proof)?,
+					None,
+					None,
+					None,
+					raw_msg.proof_height.ok_or_else(Error::missing_height)?.into(),
+				)
+				.map_err(Error::invalid_proof)?,
+			)
+		};
+
+		Ok(MsgChannelUpgradeCancel {
+			port_id: raw_msg.port_id.parse().map_err(Error::identifier)?,
+			channel_id: raw_msg.channel_id.parse().map_err(Error::identifier)?,
+			proofs,
+			signer: raw_msg.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgChannelUpgradeCancel> for RawMsgChannelUpgradeCancel {
+	fn from(domain_msg: MsgChannelUpgradeCancel) -> Self {
+		let (proof_channel, proof_height) = match domain_msg.proofs {
+			Some(proofs) => (proofs.object_proof().clone().into(), Some(proofs.height().into())),
+			None => (vec![], None),
+		};
+		RawMsgChannelUpgradeCancel {
+			port_id: domain_msg.port_id.to_string(),
+			channel_id: domain_msg.channel_id.to_string(),
+			proof_channel,
+			proof_height,
+			signer: domain_msg.signer.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::prelude::*;
+	use ibc_proto::ibc::core::channel::v1::MsgChannelUpgradeCancel as RawMsgChannelUpgradeCancel;
+
+	use crate::{
+		core::ics24_host::identifier::{ChannelId, PortId},
+		test_utils::{get_dummy_bech32_account, get_dummy_proof},
+	};
+	use ibc_proto::ibc::core::client::v1::Height;
+
+	/// Returns a dummy `RawMsgChannelUpgradeCancel`, for testing only! Passing a `proof_height`
+	/// includes a proof of the counterparty channel end.
+	pub fn get_dummy_raw_msg_chan_upgrade_cancel(
+		proof_height: Option<u64>,
+	) -> RawMsgChannelUpgradeCancel {
+		RawMsgChannelUpgradeCancel {
+			port_id: PortId::default().to_string(),
+			channel_id: ChannelId::default().to_string(),
+			proof_channel: proof_height.map(|_| get_dummy_proof()).unwrap_or_default(),
+			proof_height: proof_height
+				.map(|revision_height| Height { revision_number: 0, revision_height }),
+			signer: get_dummy_bech32_account(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+	use ibc_proto::ibc::core::channel::v1::MsgChannelUpgradeCancel as RawMsgChannelUpgradeCancel;
+	use test_log::test;
+
+	use crate::core::ics04_channel::msgs::chan_upgrade_cancel::{
+		test_util::get_dummy_raw_msg_chan_upgrade_cancel, MsgChannelUpgradeCancel,
+	};
+
+	#[test]
+	fn to_and_from() {
+		for proof_height in [None, Some(10)] {
+			let raw = get_dummy_raw_msg_chan_upgrade_cancel(proof_height);
+			let msg = MsgChannelUpgradeCancel::try_from(raw.clone()).unwrap();
+			assert_eq!(msg.proofs.is_some(), proof_height.is_some());
+			let raw_back = RawMsgChannelUpgradeCancel::from(msg.clone());
+			let msg_back = MsgChannelUpgradeCancel::try_from(raw_back.clone()).unwrap();
+			assert_eq!(raw, raw_back);
+			assert_eq!(msg, msg_back);
+		}
+	}
+}
@@ -0,0 +1,149 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::channel::v1::MsgPruneAcknowledgements as RawMsgPruneAcknowledgements;
+
+use crate::{
+	core::{
+		ics04_channel::error::Error,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgPruneAcknowledgements";
+
+///
+/// Message definition for `MsgPruneAcknowledgements`, which removes up to `limit` packet receipts
+/// and acknowledgements that are no longer needed from an unordered channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgPruneAcknowledgements {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub limit: u64,
+	pub signer: Signer,
+}
+
+impl MsgPruneAcknowledgements {
+	pub fn new(port_id: PortId, channel_id: ChannelId, limit: u64, signer: Signer) -> Self {
+		Self { port_id, channel_id, limit, signer }
+	}
+}
+
+impl Msg for MsgPruneAcknowledgements {
+	type ValidationError = Error;
+	type Raw = RawMsgPruneAcknowledgements;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgPruneAcknowledgements> for MsgPruneAcknowledgements {}
+
+impl TryFrom<RawMsgPruneAcknowledgements> for MsgPruneAcknowledgements {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgPruneAcknowledgements) -> Result<Self, Self::Error> {
+		if raw_msg.limit == 0 {
+			return Err(Error::zero_prune_limit())
+		}
+
+		Ok(MsgPruneAcknowledgements {
+			port_id: raw_msg.port_id.parse().map_err(Error::identifier)?,
+			channel_id: raw_msg.channel_id.parse().map_err(Error::identifier)?,
+			limit: raw_msg.limit,
+			signer: raw_msg.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgPruneAcknowledgements> for RawMsgPruneAcknowledgements {
+	fn from(domain_msg: MsgPruneAcknowledgements) -> Self {
+		RawMsgPruneAcknowledgements {
+			port_id: domain_msg.port_id.to_string(),
+			channel_id: domain_msg.channel_id.to_string(),
+			limit: domain_msg.limit,
+			signer: domain_msg.signer.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::prelude::*;
+	use ibc_proto::ibc::core::channel::v1::MsgPruneAcknowledgements as RawMsgPruneAcknowledgements;
+
+	use crate::{
+		core::ics24_host::identifier::{ChannelId, PortId},
+		test_utils::get_dummy_bech32_account,
+	};
+
+	/// Returns a dummy `RawMsgPruneAcknowledgements`, for testing only!
+	pub fn get_dummy_raw_msg_prune_acknowledgements(limit: u64) -> RawMsgPruneAcknowledgements {
+		RawMsgPruneAcknowledgements {
+			port_id: PortId::default().to_string(),
+			channel_id: ChannelId::default().to_string(),
+			limit,
+			signer: get_dummy_bech32_account(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use test_log::test;
+
+	use ibc_proto::ibc::core::channel::v1::MsgPruneAcknowledgements as RawMsgPruneAcknowledgements;
+
+	use crate::core::ics04_channel::msgs::prune_acknowledgements::{
+		test_util::get_dummy_raw_msg_prune_acknowledgements, MsgPruneAcknowledgements,
+	};
+
+	#[test]
+	fn parse_prune_acknowledgements_msg() {
+		let default_raw_msg = get_dummy_raw_msg_prune_acknowledgements(10);
+		assert!(MsgPruneAcknowledgements::try_from(default_raw_msg.clone()).is_ok());
+
+		let zero_limit = get_dummy_raw_msg_prune_acknowledgements(0);
+		assert!(MsgPruneAcknowledgements::try_from(zero_limit).is_err());
+
+		let bad_channel = RawMsgPruneAcknowledgements {
+			channel_id: "chshort".to_string(),
+			..default_raw_msg
+		};
+		assert!(MsgPruneAcknowledgements::try_from(bad_channel).is_err());
+	}
+
+	#[test]
+	fn to_and_from() {
+		let raw = get_dummy_raw_msg_prune_acknowledgements(10);
+		let msg = MsgPruneAcknowledgements::try_from(raw.clone()).unwrap();
+		let raw_back = RawMsgPruneAcknowledgements::from(msg.clone());
+		let msg_back = MsgPruneAcknowledgements::try_from(raw_back.clone()).unwrap();
+		assert_eq!(raw, raw_back);
+		assert_eq!(msg, msg_back);
+	}
+}
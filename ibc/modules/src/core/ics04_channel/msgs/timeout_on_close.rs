@@ -18,9 +18,12 @@ use ibc_proto::ibc::core::channel::v1::MsgTimeoutOnClose as RawMsgTimeoutOnClose
 use tendermint_proto::Protobuf;
 
 use crate::{
-	core::ics04_channel::{
-		error::Error,
-		packet::{Packet, Sequence},
+	core::{
+		ics04_channel::{
+			error::Error,
+			packet::{Packet, Sequence},
+		},
+		ics24_host::identifier::ConnectionId,
 	},
 	proofs::Proofs,
 	signer::Signer,
@@ -37,6 +40,9 @@ pub struct MsgTimeoutOnClose {
 	pub next_sequence_recv: Sequence,
 	pub proofs: Proofs,
 	pub signer: Signer,
+	/// Connection whose client verifies the proof of the closed counterparty channel, when it
+	/// differs from the connection of the packet's channel.
+	pub proof_close_connection_id: Option<ConnectionId>,
 }
 
 impl MsgTimeoutOnClose {
@@ -46,7 +52,7 @@ impl MsgTimeoutOnClose {
 		proofs: Proofs,
 		signer: Signer,
 	) -> MsgTimeoutOnClose {
-		Self { packet, next_sequence_recv, proofs, signer }
+		Self { packet, next_sequence_recv, proofs, signer, proof_close_connection_id: None }
 	}
 }
 
@@ -85,6 +91,11 @@ impl TryFrom<RawMsgTimeoutOnClose> for MsgTimeoutOnClose {
 			next_sequence_recv: Sequence::from(raw_msg.next_sequence_recv),
 			signer: raw_msg.signer.parse().map_err(Error::signer)?,
 			proofs,
+			proof_close_connection_id: Some(raw_msg.proof_close_connection_id)
+				.filter(|id| !id.is_empty())
+				.map(|id| id.parse())
+				.transpose()
+				.map_err(Error::identifier)?,
 		})
 	}
 }
@@ -102,12 +113,16 @@ impl From<MsgTimeoutOnClose> for RawMsgTimeoutOnClose {
 			proof_height: Some(domain_msg.proofs.height().into()),
 			next_sequence_recv: domain_msg.next_sequence_recv.into(),
 			signer: domain_msg.signer.to_string(),
+			proof_close_connection_id: domain_msg
+				.proof_close_connection_id
+				.map_or_else(String::new, |id| id.to_string()),
 		}
 	}
 }
 
 #[cfg(test)]
 pub mod test_util {
+	use crate::prelude::*;
 	use ibc_proto::ibc::core::{
 		channel::v1::MsgTimeoutOnClose as RawMsgTimeoutOnClose, client::v1::Height as RawHeight,
 	};
@@ -130,6 +145,7 @@ pub mod test_util {
 			proof_height: Some(RawHeight { revision_number: 0, revision_height: height }),
 			next_sequence_recv: 1,
 			signer: get_dummy_bech32_account(),
+			proof_close_connection_id: String::new(),
 		}
 	}
 }
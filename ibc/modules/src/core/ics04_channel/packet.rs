@@ -31,8 +31,9 @@ use crate::{
 };
 
 use super::handler::{
-	acknowledgement::AckPacketResult, recv_packet::RecvPacketResult, send_packet::SendPacketResult,
-	timeout::TimeoutPacketResult, write_acknowledgement::WriteAckPacketResult,
+	acknowledgement::AckPacketResult, prune_acknowledgements::PruneAcknowledgementsResult,
+	recv_packet::RecvPacketResult, send_packet::SendPacketResult, timeout::TimeoutPacketResult,
+	write_acknowledgement::WriteAckPacketResult,
 };
 
 /// Enumeration of proof carrying ICS4 message, helper for relayer.
@@ -52,6 +53,10 @@ pub enum PacketResult {
 	WriteAck(WriteAckPacketResult),
 	Ack(AckPacketResult),
 	Timeout(TimeoutPacketResult),
+	Prune(PruneAcknowledgementsResult),
+	/// The message was already processed, e.g. by a competing relayer. Nothing is written and
+	/// module callbacks are skipped.
+	NoOp,
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,78 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Data types for the channel upgrade handshake, which lets an `Open` channel change its ordering
+//! or version without being closed.
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::channel::v1::UpgradeFields as RawUpgradeFields;
+
+use crate::{
+	core::ics04_channel::{
+		channel::{ChannelEnd, Order},
+		error::Error,
+		Version,
+	},
+	prelude::*,
+	Height,
+};
+
+/// The channel end fields that an upgrade may change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeFields {
+	pub ordering: Order,
+	pub version: Version,
+}
+
+impl UpgradeFields {
+	pub fn new(ordering: Order, version: Version) -> Self {
+		Self { ordering, version }
+	}
+
+	/// Returns `true` if applying these fields would leave `channel_end` unchanged.
+	pub fn matches(&self, channel_end: &ChannelEnd) -> bool {
+		channel_end.order_matches(&self.ordering) && channel_end.version_matches(&self.version)
+	}
+
+	/// Returns the given channel end with its ordering and version replaced by these fields.
+	pub fn apply_to(&self, channel_end: ChannelEnd) -> ChannelEnd {
+		ChannelEnd { ordering: self.ordering, version: self.version.clone(), ..channel_end }
+	}
+}
+
+impl Protobuf<RawUpgradeFields> for UpgradeFields {}
+
+impl TryFrom<RawUpgradeFields> for UpgradeFields {
+	type Error = Error;
+
+	fn try_from(value: RawUpgradeFields) -> Result<Self, Self::Error> {
+		Ok(UpgradeFields::new(Order::from_i32(value.ordering)?, value.version.into()))
+	}
+}
+
+impl From<UpgradeFields> for RawUpgradeFields {
+	fn from(value: UpgradeFields) -> Self {
+		RawUpgradeFields { ordering: value.ordering as i32, version: value.version.to_string() }
+	}
+}
+
+/// What an upgrade in progress replaced on the local channel end, together with the host height
+/// at which it started, so that the upgrade can be cancelled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpgradeOrigin {
+	pub previous: UpgradeFields,
+	pub height: Height,
+}
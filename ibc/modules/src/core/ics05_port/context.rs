@@ -25,3 +25,21 @@ pub trait PortReader {
 	/// Return the module_id associated with a given port_id
 	fn lookup_module_by_port(&self, port_id: &PortId) -> Result<ModuleId, Error>;
 }
+
+/// Persists the bindings of ports to modules made at runtime through
+/// [`bind_port`](crate::core::ics26_routing::handler::bind_port).
+pub trait PortKeeper: PortReader {
+	/// Stores the binding of `port_id` to `module_id`, later returned by
+	/// [`PortReader::lookup_module_by_port`].
+	fn store_port_binding(&mut self, port_id: PortId, module_id: ModuleId) -> Result<(), Error>;
+
+	/// Called before `port_id` is bound to `module_id`, hosts can return an error to restrict
+	/// which ports a module may claim.
+	fn authorize_port_binding(
+		&self,
+		_port_id: &PortId,
+		_module_id: &ModuleId,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+}
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{core::ics24_host::identifier::PortId, prelude::*};
+use crate::{
+	core::{ics24_host::identifier::PortId, ics26_routing::context::ModuleId},
+	prelude::*,
+};
 use flex_error::define_error;
 
 define_error! {
@@ -30,6 +33,18 @@ define_error! {
 			{ port_id: PortId }
 			| e | { format_args!("could not retrieve module from port '{0}'", e.port_id) },
 
+		UnknownModule
+			{ module_id: ModuleId }
+			| e | { format_args!("no route is registered for module '{0}'", e.module_id) },
+
+		UnauthorizedBinding
+			{ port_id: PortId, module_id: ModuleId }
+			| e | {
+				format_args!(
+					"module '{0}' is not allowed to bind port '{1}'",
+					e.module_id, e.port_id)
+			},
+
 		ImplementationSpecific
 			{ reason: String }
 			| e | { format_args!("implementation specific error: {}", e.reason) },
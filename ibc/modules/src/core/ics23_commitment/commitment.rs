@@ -130,12 +130,29 @@ impl TryFrom<CommitmentProofBytes> for RawMerkleProof {
 	}
 }
 
+/// Maximum length in bytes of a commitment prefix.
+pub const MAX_COMMITMENT_PREFIX_LENGTH: usize = 128;
+
 #[derive(Clone, PartialEq, Eq, Hash, Deserialize, Default)]
 pub struct CommitmentPrefix {
 	bytes: Vec<u8>,
 }
 
 impl CommitmentPrefix {
+	/// Checks that the prefix can be used as the first key of a merkle path: it must be
+	/// non-empty UTF-8 of at most `MAX_COMMITMENT_PREFIX_LENGTH` bytes. The `Default` and
+	/// deserialized prefixes are not validated on construction.
+	pub fn validate(&self) -> Result<(), Error> {
+		if self.bytes.is_empty() {
+			return Err(Error::empty_prefix())
+		}
+		if self.bytes.len() > MAX_COMMITMENT_PREFIX_LENGTH {
+			return Err(Error::prefix_too_long(self.bytes.len(), MAX_COMMITMENT_PREFIX_LENGTH))
+		}
+		core::str::from_utf8(&self.bytes).map_err(|_| Error::invalid_prefix_encoding())?;
+		Ok(())
+	}
+
 	pub fn as_bytes(&self) -> &[u8] {
 		&self.bytes
 	}
@@ -150,7 +167,7 @@ impl TryFrom<Vec<u8>> for CommitmentPrefix {
 
 	fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
 		if bytes.is_empty() {
-			Err(Self::Error::empty_commitment_prefix())
+			Err(Self::Error::empty_prefix())
 		} else {
 			Ok(Self { bytes })
 		}
@@ -190,3 +207,34 @@ pub mod test_util {
 		RawMerkleProof { proofs: mproofs }
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::ics23_commitment::error::ErrorDetail;
+
+	#[test]
+	fn validate_commitment_prefix() {
+		assert!(CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap().validate().is_ok());
+
+		match CommitmentPrefix::default().validate() {
+			Err(e) => assert!(matches!(e.detail(), ErrorDetail::EmptyPrefix(_))),
+			Ok(_) => panic!("Expected EmptyPrefix error"),
+		}
+
+		let oversize = vec![b'a'; MAX_COMMITMENT_PREFIX_LENGTH + 1];
+		match CommitmentPrefix::try_from(oversize).unwrap().validate() {
+			Err(e) => match e.detail() {
+				ErrorDetail::PrefixTooLong(e) => {
+					assert_eq!(e.length, MAX_COMMITMENT_PREFIX_LENGTH + 1);
+					assert_eq!(e.max, MAX_COMMITMENT_PREFIX_LENGTH);
+				},
+				_ => panic!("Expected PrefixTooLong error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected PrefixTooLong error"),
+		}
+
+		let not_utf8 = CommitmentPrefix::try_from(vec![0xff, 0xfe]).unwrap();
+		assert!(not_utf8.validate().is_err());
+	}
+}
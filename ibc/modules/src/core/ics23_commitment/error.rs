@@ -26,9 +26,22 @@ define_error! {
 			[ TraceError<DecodeError> ]
 			|_| { "failed to decode commitment proof" },
 
-		EmptyCommitmentPrefix
+		EmptyPrefix
 			|_| { "empty commitment prefix" },
 
+		InvalidPrefixEncoding
+			|_| { "commitment prefix is not valid UTF-8" },
+
+		PrefixTooLong
+			{ length: usize, max: usize }
+			|e| {
+				format_args!("commitment prefix of {0} bytes exceeds the maximum of {1}",
+					e.length, e.max)
+			},
+
+		InvalidPath
+			|_| { "merkle path must contain at least one non-empty key" },
+
 		EmptyMerkleProof
 			|_| { "empty merkle proof" },
 
@@ -44,6 +57,12 @@ define_error! {
 		NumberOfKeysMismatch
 			|_| { "mismatch between the number of proofs with that of keys" },
 
+		EmptyBatch
+			|_| { "no items to verify against the batch proof" },
+
+		BatchPathMismatch
+			|_| { "items of a batch proof must share the same path above the lowest subtree" },
+
 		InvalidMerkleProof
 			|_| { "invalid merkle proof" },
 
@@ -20,11 +20,13 @@ use crate::{
 	},
 	prelude::*,
 };
+use alloc::borrow::Cow;
 use core::marker::PhantomData;
 use ibc_proto::ibc::core::commitment::v1::{MerklePath, MerkleProof as RawMerkleProof, MerkleRoot};
 use ics23::{
-	calculate_existence_root, commitment_proof::Proof, verify_membership, verify_non_membership,
-	CommitmentProof, HostFunctionsProvider, NonExistenceProof,
+	batch_entry, calculate_existence_root, commitment_proof::Proof, decompress, is_compressed,
+	verify_membership, verify_non_membership, CommitmentProof, ExistenceProof,
+	HostFunctionsProvider, NonExistenceProof,
 };
 
 pub fn apply_prefix(prefix: &CommitmentPrefix, mut path: Vec<String>) -> MerklePath {
@@ -33,6 +35,32 @@ pub fn apply_prefix(prefix: &CommitmentPrefix, mut path: Vec<String>) -> MerkleP
 	MerklePath { key_path }
 }
 
+/// Typed counterpart of `apply_prefix`, which rejects the prefixes and paths that could only
+/// produce a merkle path no proof can match.
+pub trait ApplyPrefix: Sized {
+	fn try_from_prefix_and_path(
+		prefix: &CommitmentPrefix,
+		path: Vec<String>,
+	) -> Result<Self, Error>;
+}
+
+impl ApplyPrefix for MerklePath {
+	fn try_from_prefix_and_path(
+		prefix: &CommitmentPrefix,
+		mut path: Vec<String>,
+	) -> Result<Self, Error> {
+		prefix.validate()?;
+		if path.is_empty() || path.iter().any(|key| key.is_empty()) {
+			return Err(Error::invalid_path())
+		}
+		let prefix =
+			core::str::from_utf8(prefix.as_bytes()).map_err(|_| Error::invalid_prefix_encoding())?;
+		let mut key_path = vec![prefix.to_string()];
+		key_path.append(&mut path);
+		Ok(MerklePath { key_path })
+	}
+}
+
 impl From<CommitmentRoot> for MerkleRoot {
 	fn from(root: CommitmentRoot) -> Self {
 		Self { hash: root.into_vec() }
@@ -118,17 +146,15 @@ impl<H: HostFunctionsProvider> MerkleProof<H> {
 			.zip(keys.key_path.iter().rev())
 			.skip(start_index)
 		{
-			match &proof.proof {
-				Some(Proof::Exist(existence_proof)) => {
-					subroot = calculate_existence_root::<H>(existence_proof)
-						.map_err(|_| Error::invalid_merkle_proof())?;
-					if !verify_membership::<H>(proof, spec, &subroot, key.as_bytes(), &value) {
-						return Err(Error::verification_failure())
-					}
-					value = subroot.clone();
-				},
-				_ => return Err(Error::invalid_merkle_proof()),
+			let proof = decompressed(proof)?;
+			let existence_proof =
+				existence_proof(&proof, key.as_bytes()).ok_or_else(Error::invalid_merkle_proof)?;
+			subroot = calculate_existence_root::<H>(existence_proof)
+				.map_err(|_| Error::invalid_merkle_proof())?;
+			if !verify_membership::<H>(&proof, spec, &subroot, key.as_bytes(), &value) {
+				return Err(Error::verification_failure())
 			}
+			value = subroot.clone();
 		}
 
 		if root.hash != subroot {
@@ -138,6 +164,65 @@ impl<H: HostFunctionsProvider> MerkleProof<H> {
 		Ok(())
 	}
 
+	/// Verifies that every `(path, value)` item is committed under `root`, where the lowest
+	/// proof is a batch (or compressed batch) proof covering the keys of all the items and the
+	/// proofs above it are shared, so the items must only differ in their last key.
+	pub fn verify_batch_membership(
+		&self,
+		specs: &ProofSpecs,
+		root: MerkleRoot,
+		items: Vec<(MerklePath, Vec<u8>)>,
+	) -> Result<(), Error> {
+		// validate arguments
+		if self.proofs.is_empty() {
+			return Err(Error::empty_merkle_proof())
+		}
+		if root.hash.is_empty() {
+			return Err(Error::empty_merkle_root())
+		}
+		let num = self.proofs.len();
+		let ics23_specs = Vec::<ics23::ProofSpec>::from(specs.clone());
+		if ics23_specs.len() != num {
+			return Err(Error::number_of_specs_mismatch())
+		}
+		let (first_keys, _) = items.first().ok_or_else(Error::empty_batch)?;
+
+		// every item must be proven by the batch in the lowest subtree, with the same subroot
+		let proof = decompressed(&self.proofs[0])?;
+		let spec = &ics23_specs[0];
+		let mut subroot: Option<Vec<u8>> = None;
+		for (keys, value) in &items {
+			if keys.key_path.len() != num {
+				return Err(Error::number_of_keys_mismatch())
+			}
+			if keys.key_path[..num - 1] != first_keys.key_path[..num - 1] {
+				return Err(Error::batch_path_mismatch())
+			}
+			if value.is_empty() {
+				return Err(Error::empty_verified_value())
+			}
+			// keys are represented from root-to-leaf
+			let key = keys.key_path[num - 1].as_bytes();
+			let existence_proof =
+				existence_proof(&proof, key).ok_or_else(Error::invalid_merkle_proof)?;
+			let item_root = calculate_existence_root::<H>(existence_proof)
+				.map_err(|_| Error::invalid_merkle_proof())?;
+			if !verify_membership::<H>(&proof, spec, &item_root, key, value) {
+				return Err(Error::verification_failure())
+			}
+			match &subroot {
+				Some(subroot) if *subroot != item_root =>
+					return Err(Error::verification_failure()),
+				Some(_) => {},
+				None => subroot = Some(item_root),
+			}
+		}
+
+		let subroot = subroot.ok_or_else(Error::empty_batch)?;
+		// verify the shared membership proofs starting from index 1 with value = subroot
+		self.verify_membership(specs, root, first_keys.clone(), subroot, 1)
+	}
+
 	pub fn verify_non_membership(
 		&self,
 		specs: &ProofSpecs,
@@ -165,20 +250,60 @@ impl<H: HostFunctionsProvider> MerkleProof<H> {
 		let spec = ics23_specs.get(0).ok_or_else(Error::invalid_merkle_proof)?;
 		// keys are represented from root-to-leaf
 		let key = keys.key_path.get(num - 1).ok_or_else(Error::invalid_merkle_proof)?;
-		match &proof.proof {
-			Some(Proof::Nonexist(non_existence_proof)) => {
+		let proof = decompressed(proof)?;
+		match non_existence_proof(&proof, key.as_bytes()) {
+			Some(non_existence_proof) => {
 				let subroot = calculate_non_existence_root::<H>(non_existence_proof)?;
-				if !verify_non_membership::<H>(proof, spec, &subroot, key.as_bytes()) {
+				if !verify_non_membership::<H>(&proof, spec, &subroot, key.as_bytes()) {
 					return Err(Error::verification_failure())
 				}
 				// verify membership proofs starting from index 1 with value = subroot
 				self.verify_membership(specs, root, keys, subroot, 1)
 			},
-			_ => Err(Error::invalid_merkle_proof()),
+			None => Err(Error::invalid_merkle_proof()),
 		}
 	}
 }
 
+/// Expands a compressed batch proof, so that its entries can be looked up by key.
+fn decompressed(proof: &CommitmentProof) -> Result<Cow<'_, CommitmentProof>, Error> {
+	if is_compressed(proof) {
+		decompress(proof).map(Cow::Owned).map_err(|_| Error::invalid_merkle_proof())
+	} else {
+		Ok(Cow::Borrowed(proof))
+	}
+}
+
+/// The existence proof for `key` held by a (decompressed) single or batch proof.
+fn existence_proof<'a>(proof: &'a CommitmentProof, key: &[u8]) -> Option<&'a ExistenceProof> {
+	match &proof.proof {
+		Some(Proof::Exist(existence_proof)) => Some(existence_proof),
+		Some(Proof::Batch(batch)) => batch.entries.iter().find_map(|entry| match &entry.proof {
+			Some(batch_entry::Proof::Exist(existence_proof)) if existence_proof.key == key =>
+				Some(existence_proof),
+			_ => None,
+		}),
+		_ => None,
+	}
+}
+
+/// The non-existence proof for `key` held by a (decompressed) single or batch proof.
+fn non_existence_proof<'a>(
+	proof: &'a CommitmentProof,
+	key: &[u8],
+) -> Option<&'a NonExistenceProof> {
+	match &proof.proof {
+		Some(Proof::Nonexist(non_existence_proof)) => Some(non_existence_proof),
+		Some(Proof::Batch(batch)) => batch.entries.iter().find_map(|entry| match &entry.proof {
+			Some(batch_entry::Proof::Nonexist(non_existence_proof))
+				if non_existence_proof.key == key =>
+				Some(non_existence_proof),
+			_ => None,
+		}),
+		_ => None,
+	}
+}
+
 // TODO move to ics23
 fn calculate_non_existence_root<H: HostFunctionsProvider>(
 	proof: &NonExistenceProof,
@@ -236,3 +361,231 @@ fn calculate_non_existence_root<H: HostFunctionsProvider>(
 //         RawMerkleProof { proof: value.proof }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{core::ics23_commitment::error::ErrorDetail, test_utils::Crypto};
+	use ics23::{BatchEntry, BatchProof, HashOp, InnerOp, InnerSpec, LeafOp, LengthOp, ProofSpec};
+
+	/// Leaf hash of `key` and `value`, for leaf specs hashing the value and length prefixing
+	/// both with a varint, as the IAVL and Tendermint specs do.
+	fn leaf_hash(leaf: &LeafOp, key: &[u8], value: &[u8]) -> Vec<u8> {
+		let hashed_value = Crypto::sha2_256(value);
+		let mut preimage = leaf.prefix.clone();
+		preimage.push(key.len() as u8);
+		preimage.extend_from_slice(key);
+		preimage.push(hashed_value.len() as u8);
+		preimage.extend_from_slice(&hashed_value);
+		Crypto::sha2_256(&preimage).to_vec()
+	}
+
+	/// A two leaf tree holding `items`, where an inner node hashes
+	/// `inner_prefix || left || separator || right`. Returns the root and an existence proof for
+	/// each leaf.
+	fn two_leaf_tree(
+		leaf: LeafOp,
+		inner_prefix: &[u8],
+		separator: &[u8],
+		items: [(&str, &str); 2],
+	) -> (Vec<u8>, [ExistenceProof; 2]) {
+		let items = items.map(|(key, value)| (key.as_bytes(), value.as_bytes()));
+		let left = leaf_hash(&leaf, items[0].0, items[0].1);
+		let right = leaf_hash(&leaf, items[1].0, items[1].1);
+		let preimage = [inner_prefix, &left[..], separator, &right[..]].concat();
+		let root = Crypto::sha2_256(&preimage).to_vec();
+
+		let inner = |prefix: Vec<u8>, suffix: Vec<u8>| InnerOp {
+			hash: HashOp::Sha256.into(),
+			prefix,
+			suffix,
+		};
+		let left_proof = ExistenceProof {
+			key: items[0].0.to_vec(),
+			value: items[0].1.to_vec(),
+			leaf: Some(leaf.clone()),
+			path: vec![inner(inner_prefix.to_vec(), [separator, &right[..]].concat())],
+		};
+		let right_proof = ExistenceProof {
+			key: items[1].0.to_vec(),
+			value: items[1].1.to_vec(),
+			leaf: Some(leaf),
+			path: vec![inner([inner_prefix, &left[..], separator].concat(), vec![])],
+		};
+		(root, [left_proof, right_proof])
+	}
+
+	/// A two leaf tendermint tree holding `items`, with its root and a batch proof of both
+	/// leaves, matching what ibc-go returns when querying several keys with a single proof.
+	fn batch_fixture(items: [(&str, &str); 2]) -> (MerkleRoot, CommitmentProof) {
+		let leaf = ics23::tendermint_spec().leaf_spec.unwrap();
+		let (root, proofs) = two_leaf_tree(leaf, &[1u8], &[], items);
+		let entries = proofs
+			.into_iter()
+			.map(|proof| BatchEntry { proof: Some(batch_entry::Proof::Exist(proof)) })
+			.collect();
+		let proof = CommitmentProof { proof: Some(Proof::Batch(BatchProof { entries })) };
+		(MerkleRoot { hash: root }, proof)
+	}
+
+	/// A proof that `key` is absent from a two leaf tree holding `neighbours` on either side.
+	fn non_existence_fixture(
+		leaf: LeafOp,
+		inner_prefix: &[u8],
+		separator: &[u8],
+		key: &str,
+		neighbours: [(&str, &str); 2],
+	) -> (Vec<u8>, CommitmentProof) {
+		let (root, [left, right]) = two_leaf_tree(leaf, inner_prefix, separator, neighbours);
+		let key = key.as_bytes().to_vec();
+		let proof = NonExistenceProof { key, left: Some(left), right: Some(right) };
+		(root, CommitmentProof { proof: Some(Proof::Nonexist(proof)) })
+	}
+
+	/// Proof spec for values stored in a substrate child trie, whose leaf and inner nodes are
+	/// tagged differently from the Cosmos-SDK stores.
+	fn child_trie_spec() -> ProofSpec {
+		ProofSpec {
+			leaf_spec: Some(LeafOp {
+				hash: HashOp::Sha256.into(),
+				prehash_key: HashOp::NoHash.into(),
+				prehash_value: HashOp::Sha256.into(),
+				length: LengthOp::VarProto.into(),
+				prefix: vec![2u8],
+			}),
+			inner_spec: Some(InnerSpec {
+				child_order: vec![0, 1],
+				child_size: 32,
+				min_prefix_length: 1,
+				max_prefix_length: 1,
+				empty_child: vec![],
+				hash: HashOp::Sha256.into(),
+			}),
+			max_depth: 0,
+			min_depth: 0,
+		}
+	}
+
+	fn merkle_proof(proofs: Vec<CommitmentProof>) -> MerkleProof<Crypto> {
+		MerkleProof { proofs, _phantom: PhantomData }
+	}
+
+	fn path(keys: &[&str]) -> MerklePath {
+		MerklePath { key_path: keys.iter().map(|key| key.to_string()).collect() }
+	}
+
+	#[test]
+	fn merkle_path_from_prefix_and_path() {
+		let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+		let merkle_path =
+			MerklePath::try_from_prefix_and_path(&prefix, vec!["connections/connection-0".into()])
+				.unwrap();
+		assert_eq!(merkle_path.key_path, vec!["ibc", "connections/connection-0"]);
+		assert_eq!(merkle_path, apply_prefix(&prefix, vec!["connections/connection-0".into()]));
+
+		let res = MerklePath::try_from_prefix_and_path(&CommitmentPrefix::default(), vec![]);
+		assert!(matches!(res.unwrap_err().detail(), ErrorDetail::EmptyPrefix(_)));
+
+		let res = MerklePath::try_from_prefix_and_path(&prefix, vec![]);
+		assert!(matches!(res.unwrap_err().detail(), ErrorDetail::InvalidPath(_)));
+
+		let res = MerklePath::try_from_prefix_and_path(&prefix, vec![String::new()]);
+		assert!(matches!(res.unwrap_err().detail(), ErrorDetail::InvalidPath(_)));
+	}
+
+	#[test]
+	fn verify_batch_and_compressed_batch_membership() {
+		let specs = ProofSpecs::from(vec![ics23::tendermint_spec()]);
+		let (root, batch) = batch_fixture([("key1", "value1"), ("key2", "value2")]);
+		let items =
+			vec![(path(&["key1"]), b"value1".to_vec()), (path(&["key2"]), b"value2".to_vec())];
+
+		let compressed = ics23::compress(&batch);
+		assert!(is_compressed(&compressed));
+
+		for proof in [batch, compressed] {
+			let proof = merkle_proof(vec![proof]);
+			assert_eq!(proof.verify_batch_membership(&specs, root.clone(), items.clone()), Ok(()));
+			// A single key of the batch can still be verified on its own.
+			assert_eq!(
+				proof.verify_membership(
+					&specs,
+					root.clone(),
+					path(&["key2"]),
+					b"value2".to_vec(),
+					0,
+				),
+				Ok(())
+			);
+		}
+	}
+
+	#[test]
+	fn verify_batch_membership_rejects_unproven_items() {
+		let specs = ProofSpecs::from(vec![ics23::tendermint_spec()]);
+		let (root, batch) = batch_fixture([("key1", "value1"), ("key2", "value2")]);
+		let proof = merkle_proof(vec![batch]);
+
+		let wrong_value =
+			vec![(path(&["key1"]), b"value1".to_vec()), (path(&["key2"]), b"other".to_vec())];
+		assert!(proof.verify_batch_membership(&specs, root.clone(), wrong_value).is_err());
+
+		let missing_key = vec![(path(&["key3"]), b"value3".to_vec())];
+		assert!(proof.verify_batch_membership(&specs, root.clone(), missing_key).is_err());
+
+		assert!(proof.verify_batch_membership(&specs, root, vec![]).is_err());
+	}
+
+	#[test]
+	fn verify_non_membership_with_cosmos_specs() {
+		// IAVL nodes are prefixed with their height, size and version, children with their length.
+		let iavl_leaf = LeafOp { prefix: vec![0, 2, 2], ..ics23::iavl_spec().leaf_spec.unwrap() };
+		let (store_root, store_proof) = non_existence_fixture(
+			iavl_leaf,
+			&[2, 4, 2, 32],
+			&[32],
+			"key2",
+			[("key1", "value1"), ("key3", "value3")],
+		);
+
+		// The multistore commits to the root of the "ibc" store.
+		let tendermint_leaf = ics23::tendermint_spec().leaf_spec.unwrap();
+		let root = leaf_hash(&tendermint_leaf, b"ibc", &store_root);
+		let multistore_proof = CommitmentProof {
+			proof: Some(Proof::Exist(ExistenceProof {
+				key: b"ibc".to_vec(),
+				value: store_root,
+				leaf: Some(tendermint_leaf),
+				path: vec![],
+			})),
+		};
+
+		let proof = merkle_proof(vec![store_proof, multistore_proof]);
+		let root = MerkleRoot { hash: root };
+		let specs = ProofSpecs::cosmos();
+		let absent = path(&["ibc", "key2"]);
+		assert_eq!(proof.verify_non_membership(&specs, root.clone(), absent), Ok(()));
+		assert!(proof.verify_non_membership(&specs, root, path(&["ibc", "key1"])).is_err());
+	}
+
+	#[test]
+	fn verify_non_membership_with_child_trie_spec() {
+		let spec = child_trie_spec();
+		let (root, proof) = non_existence_fixture(
+			spec.leaf_spec.clone().unwrap(),
+			&[3],
+			&[],
+			"key2",
+			[("key1", "value1"), ("key3", "value3")],
+		);
+		let proof = merkle_proof(vec![proof]);
+		let root = MerkleRoot { hash: root };
+
+		let specs = ProofSpecs::from(vec![spec]);
+		assert_eq!(proof.verify_non_membership(&specs, root.clone(), path(&["key2"])), Ok(()));
+
+		// The same proof does not hold under the tendermint spec the Cosmos-SDK uses.
+		let tendermint_specs = ProofSpecs::from(vec![ics23::tendermint_spec()]);
+		assert!(proof.verify_non_membership(&tendermint_specs, root, path(&["key2"])).is_err());
+	}
+}
@@ -35,6 +35,22 @@ define_error! {
 			}
 			| e | { format_args!("identifier {0} has invalid length {1} must be between {2}-{3} characters", e.id, e.length, e.min, e.max) },
 
+		TooShort
+			{
+				id: String,
+				length: usize,
+				min: usize,
+			}
+			| e | { format_args!("identifier {0} has length {1} but must be at least {2} characters", e.id, e.length, e.min) },
+
+		TooLong
+			{
+				id: String,
+				length: usize,
+				max: usize,
+			}
+			| e | { format_args!("identifier {0} has length {1} but must be at most {2} characters", e.id, e.length, e.max) },
+
 		InvalidCharacter
 			{ id: String }
 			| e | { format_args!("identifier {0} must only contain alphanumeric characters or `.`, `_`, `+`, `-`, `#`, - `[`, `]`, `<`, `>`", e.id) },
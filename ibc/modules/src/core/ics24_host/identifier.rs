@@ -160,6 +160,21 @@ impl ClientId {
 		Self::from_str(id.as_str())
 	}
 
+	/// Parses a client identifier that must be accepted by any ICS-024 compliant chain. Unlike
+	/// `from_str`, which stays lenient so that already stored identifiers remain readable, this
+	/// rejects identifiers that a counterparty would refuse.
+	///
+	/// ```
+	/// # use core::str::FromStr;
+	/// # use ibc::core::ics24_host::identifier::ClientId;
+	/// assert!(ClientId::new_strict("07-tendermint-0").is_ok());
+	/// assert!(ClientId::new_strict("07-tendermínt-0").is_err());
+	/// assert!(ClientId::from_str("07-tendermínt-0").is_ok());
+	/// ```
+	pub fn new_strict(id: &str) -> Result<Self, ValidationError> {
+		validate_client_identifier_strict(id).map(|_| Self(id.to_string()))
+	}
+
 	/// Get this identifier as a borrowed `&str`
 	pub fn as_str(&self) -> &str {
 		&self.0
@@ -226,6 +241,18 @@ impl ConnectionId {
 		Self::from_str(id.as_str()).expect("Connection id is valid")
 	}
 
+	/// Parses a connection identifier that must be accepted by any ICS-024 compliant chain, see
+	/// [`ClientId::new_strict`].
+	///
+	/// ```
+	/// # use ibc::core::ics24_host::identifier::ConnectionId;
+	/// assert!(ConnectionId::new_strict("connection-0").is_ok());
+	/// assert!(ConnectionId::new_strict("connection<ö>").is_err());
+	/// ```
+	pub fn new_strict(id: &str) -> Result<Self, ValidationError> {
+		validate_connection_identifier_strict(id).map(|_| Self(id.to_string()))
+	}
+
 	/// Returns the static prefix to be used across all connection identifiers.
 	pub fn prefix() -> &'static str {
 		"connection"
@@ -281,11 +308,33 @@ impl PartialEq<str> for ConnectionId {
 pub struct PortId(String);
 
 impl PortId {
+	/// Parses a port identifier that must be accepted by any ICS-024 compliant chain, see
+	/// [`ClientId::new_strict`].
+	///
+	/// ```
+	/// # use ibc::core::ics24_host::identifier::PortId;
+	/// assert!(PortId::new_strict("transfer").is_ok());
+	/// assert!(PortId::new_strict("trånsfer").is_err());
+	/// ```
+	pub fn new_strict(id: &str) -> Result<Self, ValidationError> {
+		validate_port_identifier_strict(id).map(|_| Self(id.to_string()))
+	}
+
 	/// Infallible creation of the well-known transfer port
 	pub fn transfer() -> Self {
 		Self("transfer".to_string())
 	}
 
+	/// Infallible creation of the well-known nft transfer port
+	pub fn nft_transfer() -> Self {
+		Self("nft-transfer".to_string())
+	}
+
+	/// Infallible creation of the well-known interchain accounts host port
+	pub fn ica_host() -> Self {
+		Self("icahost".to_string())
+	}
+
 	/// Get this identifier as a borrowed `&str`
 	pub fn as_str(&self) -> &str {
 		&self.0
@@ -346,6 +395,26 @@ impl ChannelId {
 		Self(counter)
 	}
 
+	/// Parses a channel identifier that must be accepted by any ICS-024 compliant chain, see
+	/// [`ClientId::new_strict`]. The counter must also be written the way it is displayed, so
+	/// that the parsed identifier refers to the same channel on both ends.
+	///
+	/// ```
+	/// # use core::str::FromStr;
+	/// # use ibc::core::ics24_host::identifier::ChannelId;
+	/// assert!(ChannelId::new_strict("channel-7").is_ok());
+	/// assert!(ChannelId::new_strict("channel-007").is_err());
+	/// assert!(ChannelId::from_str("channel-007").is_ok());
+	/// ```
+	pub fn new_strict(id: &str) -> Result<Self, ValidationError> {
+		validate_channel_identifier_strict(id)?;
+		let channel_id = Self::from_str(id)?;
+		if channel_id.to_string() != id {
+			return Err(ValidationError::channel_id_invalid_format())
+		}
+		Ok(channel_id)
+	}
+
 	pub fn sequence(&self) -> u64 {
 		self.0
 	}
@@ -143,14 +143,6 @@ pub enum ClientUpgradePath {
 	UpgradedClientConsensusState(u64),
 }
 
-/// Sub-paths which are not part of the specification, but are still
-/// useful to represent for parsing purposes.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum SubPath {
-	Channels(ChannelId),
-	Sequences(Sequence),
-}
-
 impl Path {
 	/// Indication if the path is provable.
 	pub fn is_provable(&self) -> bool {
@@ -166,9 +158,24 @@ impl Path {
 define_error! {
 	#[derive(Eq, PartialEq)]
 	PathError {
-		ParseFailure
-			{ path: String }
-			| e | { format!("'{}' could not be parsed into a Path", e.path) },
+		UnknownPrefix
+			{ path: String, segment: String }
+			| e | { format!("'{}' has an unknown path prefix '{}'", e.path, e.segment) },
+
+		InvalidLength
+			{ path: String, length: usize }
+			| e | { format!("'{}' has an unexpected number of segments ({})", e.path, e.length) },
+
+		UnexpectedSegment
+			{ path: String, index: usize, segment: String, expected: String }
+			| e | {
+				format!("segment {} of '{}' is '{}', expected '{}'",
+					e.index, e.path, e.segment, e.expected)
+			},
+
+		InvalidSegment
+			{ path: String, index: usize, segment: String }
+			| e | { format!("segment {} of '{}' is invalid: '{}'", e.index, e.path, e.segment) },
 	}
 }
 
@@ -178,352 +185,214 @@ impl FromStr for Path {
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
 		let components: Vec<&str> = s.split('/').collect();
-
-		parse_client_paths(&components)
-			.or_else(|| parse_connections(&components))
-			.or_else(|| parse_ports(&components))
-			.or_else(|| parse_channel_ends(&components))
-			.or_else(|| parse_seqs(&components))
-			.or_else(|| parse_commitments(&components))
-			.or_else(|| parse_acks(&components))
-			.or_else(|| parse_receipts(&components))
-			.or_else(|| parse_upgrades(&components))
-			.ok_or_else(|| PathError::parse_failure(s.to_string()))
-	}
-}
-
-fn parse_client_paths(components: &[&str]) -> Option<Path> {
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	if first != "clients" {
-		return None
-	}
-
-	let client_id = match ClientId::from_str(components[1]) {
-		Ok(s) => s,
-		Err(_) => return None,
-	};
-
-	if components.len() == 3 {
-		match components[2] {
-			"clientType" => Some(ClientTypePath(client_id).into()),
-			"clientState" => Some(ClientStatePath(client_id).into()),
-			"connections" => Some(ClientConnectionsPath(client_id).into()),
-			_ => None,
-		}
-	} else if components.len() == 4 {
-		if "consensusStates" != components[2] {
-			return None
+		let segments = Segments::new(s, &components);
+
+		match components[0] {
+			"clients" => parse_client_paths(&segments),
+			"connections" => parse_connections(&segments),
+			"ports" => parse_ports(&segments),
+			"channelEnds" => parse_channel_ends(&segments),
+			"nextSequenceSend" | "nextSequenceRecv" | "nextSequenceAck" => parse_seqs(&segments),
+			"commitments" => parse_commitments(&segments),
+			"acks" => parse_acks(&segments),
+			"receipts" => parse_receipts(&segments),
+			UPGRADED_IBC_STATE => parse_upgrades(&segments),
+			first => Err(PathError::unknown_prefix(s.to_string(), first.to_string())),
 		}
-
-		let epoch_height = match components.last() {
-			Some(eh) => *eh,
-			None => return None,
-		};
-
-		let epoch_height: Vec<&str> = epoch_height.split('-').collect();
-
-		if epoch_height.len() != 2 {
-			return None
-		}
-
-		let epoch = epoch_height[0];
-		let height = epoch_height[1];
-
-		let epoch = match epoch.parse::<u64>() {
-			Ok(ep) => ep,
-			Err(_) => return None,
-		};
-
-		let height = match height.parse::<u64>() {
-			Ok(h) => h,
-			Err(_) => return None,
-		};
-
-		Some(ClientConsensusStatePath { client_id, epoch, height }.into())
-	} else {
-		None
 	}
 }
 
-fn parse_connections(components: &[&str]) -> Option<Path> {
-	if components.len() != 2 {
-		return None
-	}
-
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	if first != "connections" {
-		return None
-	}
-
-	let connection_id = match components.last() {
-		Some(c) => *c,
-		None => return None,
-	};
-
-	let connection_id = match ConnectionId::from_str(connection_id) {
-		Ok(c) => c,
-		Err(_) => return None,
-	};
-
-	Some(ConnectionsPath(connection_id).into())
+/// A run of the `/` separated segments of the path being parsed, which remembers where it starts
+/// within the full path so that errors point at the offending segment.
+#[derive(Clone, Copy)]
+struct Segments<'a, 'b> {
+	path: &'a str,
+	components: &'b [&'a str],
+	offset: usize,
 }
 
-fn parse_ports(components: &[&str]) -> Option<Path> {
-	if components.len() != 2 {
-		return None
+impl<'a, 'b> Segments<'a, 'b> {
+	fn new(path: &'a str, components: &'b [&'a str]) -> Self {
+		Self { path, components, offset: 0 }
 	}
 
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	if first != "ports" {
-		return None
+	/// The segments in `start..end`, which must be in bounds.
+	fn sub(&self, start: usize, end: usize) -> Self {
+		let components = &self.components[start..end];
+		Self { path: self.path, components, offset: self.offset + start }
 	}
 
-	let port_id = match components.last() {
-		Some(p) => *p,
-		None => return None,
-	};
-
-	let port_id = match PortId::from_str(port_id) {
-		Ok(p) => p,
-		Err(_) => return None,
-	};
-
-	Some(PortsPath(port_id).into())
-}
-
-fn parse_channels(components: &[&str]) -> Option<SubPath> {
-	if components.len() != 2 {
-		return None
+	fn len(&self) -> usize {
+		self.components.len()
 	}
 
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	if first != "channels" {
-		return None
+	fn expect_len(&self, len: usize) -> Result<(), PathError> {
+		if self.len() != len {
+			return Err(self.invalid_length())
+		}
+		Ok(())
 	}
 
-	let channel_id = match components.last() {
-		Some(c) => *c,
-		None => return None,
-	};
-
-	let channel_id = match ChannelId::from_str(channel_id) {
-		Ok(c) => c,
-		Err(_) => return None,
-	};
-
-	Some(SubPath::Channels(channel_id))
-}
-
-fn parse_sequences(components: &[&str]) -> Option<SubPath> {
-	if components.len() != 2 {
-		return None
+	fn segment(&self, index: usize) -> Result<&'a str, PathError> {
+		self.components.get(index).copied().ok_or_else(|| self.invalid_length())
 	}
 
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	if first != "sequences" {
-		return None
+	/// Checks that the segment at `index` is the literal `expected`.
+	fn expect(&self, index: usize, expected: &str) -> Result<(), PathError> {
+		let segment = self.segment(index)?;
+		if segment != expected {
+			return Err(PathError::unexpected_segment(
+				self.path.to_string(),
+				self.offset + index,
+				segment.to_string(),
+				expected.to_string(),
+			))
+		}
+		Ok(())
 	}
 
-	let sequence_number = match components.last() {
-		Some(s) => *s,
-		None => return None,
-	};
-
-	match Sequence::from_str(sequence_number) {
-		Ok(seq) => Some(SubPath::Sequences(seq)),
-		Err(_) => None,
+	fn parse<T: FromStr>(&self, index: usize) -> Result<T, PathError> {
+		self.segment(index)?.parse::<T>().map_err(|_| self.invalid(index))
 	}
-}
 
-fn parse_channel_ends(components: &[&str]) -> Option<Path> {
-	if components.len() != 5 {
-		return None
+	fn invalid(&self, index: usize) -> PathError {
+		PathError::invalid_segment(
+			self.path.to_string(),
+			self.offset + index,
+			self.components.get(index).copied().unwrap_or_default().to_string(),
+		)
 	}
 
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	if first != "channelEnds" {
-		return None
+	fn invalid_length(&self) -> PathError {
+		PathError::invalid_length(self.path.to_string(), self.path.split('/').count())
 	}
-
-	let port = parse_ports(&components[1..=2]);
-	let channel = parse_channels(&components[3..=4]);
-
-	let port_id =
-		if let Some(Path::Ports(PortsPath(port_id))) = port { port_id } else { return None };
-
-	let channel_id =
-		if let Some(SubPath::Channels(channel_id)) = channel { channel_id } else { return None };
-
-	Some(ChannelEndsPath(port_id, channel_id).into())
 }
 
-fn parse_seqs(components: &[&str]) -> Option<Path> {
-	if components.len() != 5 {
-		return None
-	}
-
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	let port = parse_ports(&components[1..=2]);
-	let channel = parse_channels(&components[3..=4]);
-
-	let port_id =
-		if let Some(Path::Ports(PortsPath(port_id))) = port { port_id } else { return None };
-
-	let channel_id =
-		if let Some(SubPath::Channels(channel_id)) = channel { channel_id } else { return None };
-
-	match first {
-		"nextSequenceSend" => Some(SeqSendsPath(port_id, channel_id).into()),
-		"nextSequenceRecv" => Some(SeqRecvsPath(port_id, channel_id).into()),
-		"nextSequenceAck" => Some(SeqAcksPath(port_id, channel_id).into()),
-		_ => None,
+fn parse_client_paths(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect(0, "clients")?;
+
+	match segments.len() {
+		3 => {
+			let client_id = segments.parse::<ClientId>(1)?;
+			match segments.segment(2)? {
+				"clientType" => Ok(ClientTypePath(client_id).into()),
+				"clientState" => Ok(ClientStatePath(client_id).into()),
+				"connections" => Ok(ClientConnectionsPath(client_id).into()),
+				_ => Err(segments.invalid(2)),
+			}
+		},
+		4 => {
+			let client_id = segments.parse::<ClientId>(1)?;
+			segments.expect(2, "consensusStates")?;
+
+			let (epoch, height) =
+				segments.segment(3)?.split_once('-').ok_or_else(|| segments.invalid(3))?;
+			let epoch = epoch.parse::<u64>().map_err(|_| segments.invalid(3))?;
+			let height = height.parse::<u64>().map_err(|_| segments.invalid(3))?;
+
+			Ok(ClientConsensusStatePath { client_id, epoch, height }.into())
+		},
+		_ => Err(segments.invalid_length()),
 	}
 }
 
-fn parse_commitments(components: &[&str]) -> Option<Path> {
-	if components.len() != 7 {
-		return None
-	}
+fn parse_connections(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect_len(2)?;
+	segments.expect(0, "connections")?;
 
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
-
-	if first != "commitments" {
-		return None
-	}
-
-	let port = parse_ports(&components[1..=2]);
-	let channel = parse_channels(&components[3..=4]);
-	let sequence = parse_sequences(&components[5..]);
-
-	let port_id =
-		if let Some(Path::Ports(PortsPath(port_id))) = port { port_id } else { return None };
-
-	let channel_id =
-		if let Some(SubPath::Channels(channel_id)) = channel { channel_id } else { return None };
-
-	let sequence = if let Some(SubPath::Sequences(seq)) = sequence { seq } else { return None };
-
-	Some(CommitmentsPath { port_id, channel_id, sequence }.into())
+	Ok(ConnectionsPath(segments.parse(1)?).into())
 }
 
-fn parse_acks(components: &[&str]) -> Option<Path> {
-	if components.len() != 7 {
-		return None
-	}
+fn parse_ports(segments: &Segments) -> Result<Path, PathError> {
+	Ok(PortsPath(parse_port_id(segments)?).into())
+}
 
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
+fn parse_port_id(segments: &Segments) -> Result<PortId, PathError> {
+	segments.expect_len(2)?;
+	segments.expect(0, "ports")?;
+	segments.parse(1)
+}
 
-	if first != "acks" {
-		return None
-	}
+fn parse_channel_id(segments: &Segments) -> Result<ChannelId, PathError> {
+	segments.expect_len(2)?;
+	segments.expect(0, "channels")?;
+	segments.parse(1)
+}
 
-	let port = parse_ports(&components[1..=2]);
-	let channel = parse_channels(&components[3..=4]);
-	let sequence = parse_sequences(&components[5..]);
+fn parse_sequence(segments: &Segments) -> Result<Sequence, PathError> {
+	segments.expect_len(2)?;
+	segments.expect(0, "sequences")?;
+	segments.parse(1)
+}
 
-	let port_id =
-		if let Some(Path::Ports(PortsPath(port_id))) = port { port_id } else { return None };
+/// Parses the `ports/{port_id}/channels/{channel_id}` segments that follow the first one.
+fn parse_port_channel(segments: &Segments) -> Result<(PortId, ChannelId), PathError> {
+	Ok((parse_port_id(&segments.sub(1, 3))?, parse_channel_id(&segments.sub(3, 5))?))
+}
 
-	let channel_id =
-		if let Some(SubPath::Channels(channel_id)) = channel { channel_id } else { return None };
+/// Parses the `ports/{port_id}/channels/{channel_id}/sequences/{sequence}` segments that follow
+/// the first one.
+fn parse_port_channel_sequence(
+	segments: &Segments,
+) -> Result<(PortId, ChannelId, Sequence), PathError> {
+	let (port_id, channel_id) = parse_port_channel(segments)?;
+	Ok((port_id, channel_id, parse_sequence(&segments.sub(5, 7))?))
+}
 
-	let sequence = if let Some(SubPath::Sequences(seq)) = sequence { seq } else { return None };
+fn parse_channel_ends(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect_len(5)?;
+	segments.expect(0, "channelEnds")?;
 
-	Some(AcksPath { port_id, channel_id, sequence }.into())
+	let (port_id, channel_id) = parse_port_channel(segments)?;
+	Ok(ChannelEndsPath(port_id, channel_id).into())
 }
 
-fn parse_receipts(components: &[&str]) -> Option<Path> {
-	if components.len() != 7 {
-		return None
-	}
-
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
+fn parse_seqs(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect_len(5)?;
 
-	if first != "receipts" {
-		return None
+	let (port_id, channel_id) = parse_port_channel(segments)?;
+	match segments.segment(0)? {
+		"nextSequenceSend" => Ok(SeqSendsPath(port_id, channel_id).into()),
+		"nextSequenceRecv" => Ok(SeqRecvsPath(port_id, channel_id).into()),
+		"nextSequenceAck" => Ok(SeqAcksPath(port_id, channel_id).into()),
+		_ => Err(segments.invalid(0)),
 	}
+}
 
-	let port = parse_ports(&components[1..=2]);
-	let channel = parse_channels(&components[3..=4]);
-	let sequence = parse_sequences(&components[5..]);
-
-	let port_id =
-		if let Some(Path::Ports(PortsPath(port_id))) = port { port_id } else { return None };
-
-	let channel_id =
-		if let Some(SubPath::Channels(channel_id)) = channel { channel_id } else { return None };
-
-	let sequence = if let Some(SubPath::Sequences(seq)) = sequence { seq } else { return None };
+fn parse_commitments(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect_len(7)?;
+	segments.expect(0, "commitments")?;
 
-	Some(ReceiptsPath { port_id, channel_id, sequence }.into())
+	let (port_id, channel_id, sequence) = parse_port_channel_sequence(segments)?;
+	Ok(CommitmentsPath { port_id, channel_id, sequence }.into())
 }
 
-fn parse_upgrades(components: &[&str]) -> Option<Path> {
-	if components.len() != 3 {
-		return None
-	}
+fn parse_acks(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect_len(7)?;
+	segments.expect(0, "acks")?;
 
-	let first = match components.first() {
-		Some(f) => *f,
-		None => return None,
-	};
+	let (port_id, channel_id, sequence) = parse_port_channel_sequence(segments)?;
+	Ok(AcksPath { port_id, channel_id, sequence }.into())
+}
 
-	if first != UPGRADED_IBC_STATE {
-		return None
-	}
+fn parse_receipts(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect_len(7)?;
+	segments.expect(0, "receipts")?;
 
-	let last = match components.last() {
-		Some(l) => *l,
-		None => return None,
-	};
+	let (port_id, channel_id, sequence) = parse_port_channel_sequence(segments)?;
+	Ok(ReceiptsPath { port_id, channel_id, sequence }.into())
+}
 
-	let height = match components[1].parse::<u64>() {
-		Ok(h) => h,
-		Err(_) => return None,
-	};
+fn parse_upgrades(segments: &Segments) -> Result<Path, PathError> {
+	segments.expect_len(3)?;
+	segments.expect(0, UPGRADED_IBC_STATE)?;
 
-	match last {
-		UPGRADED_CLIENT_STATE => Some(ClientUpgradePath::UpgradedClientState(height).into()),
+	let height = segments.parse::<u64>(1)?;
+	match segments.segment(2)? {
+		UPGRADED_CLIENT_STATE => Ok(ClientUpgradePath::UpgradedClientState(height).into()),
 		UPGRADED_CLIENT_CONSENSUS_STATE =>
-			Some(ClientUpgradePath::UpgradedClientConsensusState(height).into()),
-		_ => None,
+			Ok(ClientUpgradePath::UpgradedClientConsensusState(height).into()),
+		_ => Err(segments.invalid(2)),
 	}
 }
 
@@ -545,24 +414,24 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_client_paths(&components),
-			Some(Path::ClientType(ClientTypePath(ClientId::default())))
+			parse_client_paths(&Segments::new(path, &components)),
+			Ok(Path::ClientType(ClientTypePath(ClientId::default())))
 		);
 
 		let path = "clients/07-tendermint-0/clientState";
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_client_paths(&components),
-			Some(Path::ClientState(ClientStatePath(ClientId::default())))
+			parse_client_paths(&Segments::new(path, &components)),
+			Ok(Path::ClientState(ClientStatePath(ClientId::default())))
 		);
 
 		let path = "clients/07-tendermint-0/consensusStates/15-31";
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_client_paths(&components),
-			Some(Path::ClientConsensusState(ClientConsensusStatePath {
+			parse_client_paths(&Segments::new(path, &components)),
+			Ok(Path::ClientConsensusState(ClientConsensusStatePath {
 				client_id: ClientId::default(),
 				epoch: 15,
 				height: 31,
@@ -622,8 +491,8 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_connections(&components),
-			Some(Path::Connections(ConnectionsPath(ConnectionId::new(0)))),
+			parse_connections(&Segments::new(path, &components)),
+			Ok(Path::Connections(ConnectionsPath(ConnectionId::new(0)))),
 		);
 	}
 
@@ -641,7 +510,10 @@ mod tests {
 		let path = "ports/defaultPort";
 		let components: Vec<&str> = path.split('/').collect();
 
-		assert_eq!(parse_ports(&components), Some(Path::Ports(PortsPath(PortId::default()))),);
+		assert_eq!(
+			parse_ports(&Segments::new(path, &components)),
+			Ok(Path::Ports(PortsPath(PortId::default())))
+		);
 	}
 
 	#[test]
@@ -654,11 +526,11 @@ mod tests {
 	}
 
 	#[test]
-	fn test_parse_channels_fn() {
+	fn test_parse_channel_id_fn() {
 		let path = "channels/channel-0";
 		let components: Vec<&str> = path.split('/').collect();
 
-		assert_eq!(parse_channels(&components), Some(SubPath::Channels(ChannelId::default())),);
+		assert_eq!(parse_channel_id(&Segments::new(path, &components)), Ok(ChannelId::default()));
 	}
 
 	#[test]
@@ -670,11 +542,11 @@ mod tests {
 	}
 
 	#[test]
-	fn test_parse_sequences_fn() {
+	fn test_parse_sequence_fn() {
 		let path = "sequences/0";
 		let components: Vec<&str> = path.split('/').collect();
 
-		assert_eq!(parse_sequences(&components), Some(SubPath::Sequences(Sequence::default())));
+		assert_eq!(parse_sequence(&Segments::new(path, &components)), Ok(Sequence::default()));
 	}
 
 	#[test]
@@ -691,8 +563,8 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_channel_ends(&components),
-			Some(Path::ChannelEnds(ChannelEndsPath(PortId::default(), ChannelId::default()))),
+			parse_channel_ends(&Segments::new(path, &components)),
+			Ok(Path::ChannelEnds(ChannelEndsPath(PortId::default(), ChannelId::default()))),
 		);
 	}
 
@@ -714,24 +586,24 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_seqs(&components),
-			Some(Path::SeqSends(SeqSendsPath(PortId::default(), ChannelId::default()))),
+			parse_seqs(&Segments::new(path, &components)),
+			Ok(Path::SeqSends(SeqSendsPath(PortId::default(), ChannelId::default()))),
 		);
 
 		let path = "nextSequenceRecv/ports/defaultPort/channels/channel-0";
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_seqs(&components),
-			Some(Path::SeqRecvs(SeqRecvsPath(PortId::default(), ChannelId::default()))),
+			parse_seqs(&Segments::new(path, &components)),
+			Ok(Path::SeqRecvs(SeqRecvsPath(PortId::default(), ChannelId::default()))),
 		);
 
 		let path = "nextSequenceAck/ports/defaultPort/channels/channel-0";
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_seqs(&components),
-			Some(Path::SeqAcks(SeqAcksPath(PortId::default(), ChannelId::default()))),
+			parse_seqs(&Segments::new(path, &components)),
+			Ok(Path::SeqAcks(SeqAcksPath(PortId::default(), ChannelId::default()))),
 		);
 	}
 
@@ -777,8 +649,8 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_commitments(&components),
-			Some(Path::Commitments(CommitmentsPath {
+			parse_commitments(&Segments::new(path, &components)),
+			Ok(Path::Commitments(CommitmentsPath {
 				port_id: PortId::default(),
 				channel_id: ChannelId::default(),
 				sequence: Sequence::default(),
@@ -808,8 +680,8 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_acks(&components),
-			Some(Path::Acks(AcksPath {
+			parse_acks(&Segments::new(path, &components)),
+			Ok(Path::Acks(AcksPath {
 				port_id: PortId::default(),
 				channel_id: ChannelId::default(),
 				sequence: Sequence::default(),
@@ -839,8 +711,8 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_receipts(&components),
-			Some(Path::Receipts(ReceiptsPath {
+			parse_receipts(&Segments::new(path, &components)),
+			Ok(Path::Receipts(ReceiptsPath {
 				port_id: PortId::default(),
 				channel_id: ChannelId::default(),
 				sequence: Sequence::default(),
@@ -870,16 +742,16 @@ mod tests {
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_upgrades(&components),
-			Some(Path::Upgrade(ClientUpgradePath::UpgradedClientState(0))),
+			parse_upgrades(&Segments::new(path, &components)),
+			Ok(Path::Upgrade(ClientUpgradePath::UpgradedClientState(0))),
 		);
 
 		let path = "upgradedIBCState/0/upgradedConsState";
 		let components: Vec<&str> = path.split('/').collect();
 
 		assert_eq!(
-			parse_upgrades(&components),
-			Some(Path::Upgrade(ClientUpgradePath::UpgradedClientConsensusState(0))),
+			parse_upgrades(&Segments::new(path, &components)),
+			Ok(Path::Upgrade(ClientUpgradePath::UpgradedClientConsensusState(0))),
 		)
 	}
 
@@ -903,4 +775,111 @@ mod tests {
 			Path::Upgrade(ClientUpgradePath::UpgradedClientConsensusState(0)),
 		);
 	}
+
+	/// Every path variant built over a spread of identifiers and sequence values, from the
+	/// defaults up to the largest values each can hold.
+	fn all_paths() -> Vec<Path> {
+		let client_ids = [
+			ClientId::default(),
+			ClientId::from_str("10-grandpa-3").unwrap(),
+			ClientId::from_str("9999-mock-0").unwrap(),
+		];
+		let connection_ids = [ConnectionId::new(0), ConnectionId::new(u64::MAX)];
+		let port_ids =
+			[PortId::default(), PortId::transfer(), PortId::from_str("a.b_c+d#e").unwrap()];
+		let channel_ids = [ChannelId::default(), ChannelId::new(u64::MAX)];
+		let values = [0, 1, 42, u64::MAX];
+
+		let mut paths: Vec<Path> = Vec::new();
+		for client_id in &client_ids {
+			paths.push(ClientTypePath(client_id.clone()).into());
+			paths.push(ClientStatePath(client_id.clone()).into());
+			paths.push(ClientConnectionsPath(client_id.clone()).into());
+			for epoch in values {
+				for height in values {
+					let client_id = client_id.clone();
+					paths.push(ClientConsensusStatePath { client_id, epoch, height }.into());
+				}
+			}
+		}
+		for connection_id in &connection_ids {
+			paths.push(ConnectionsPath(connection_id.clone()).into());
+		}
+		for port_id in &port_ids {
+			paths.push(PortsPath(port_id.clone()).into());
+			for channel_id in channel_ids {
+				paths.push(ChannelEndsPath(port_id.clone(), channel_id).into());
+				paths.push(SeqSendsPath(port_id.clone(), channel_id).into());
+				paths.push(SeqRecvsPath(port_id.clone(), channel_id).into());
+				paths.push(SeqAcksPath(port_id.clone(), channel_id).into());
+				for sequence in values.map(Sequence::from) {
+					let port_id = port_id.clone();
+					paths.push(
+						CommitmentsPath { port_id: port_id.clone(), channel_id, sequence }.into(),
+					);
+					paths.push(AcksPath { port_id: port_id.clone(), channel_id, sequence }.into());
+					paths.push(ReceiptsPath { port_id, channel_id, sequence }.into());
+				}
+			}
+		}
+		for height in values {
+			paths.push(ClientUpgradePath::UpgradedClientState(height).into());
+			paths.push(ClientUpgradePath::UpgradedClientConsensusState(height).into());
+		}
+		paths
+	}
+
+	#[test]
+	fn displayed_paths_parse_back() {
+		for path in all_paths() {
+			assert_eq!(Path::from_str(&path.to_string()), Ok(path));
+		}
+	}
+
+	#[test]
+	fn truncated_and_extended_paths_dont_parse() {
+		for path in all_paths() {
+			let path = path.to_string();
+			let (truncated, _) = path.rsplit_once('/').unwrap();
+			assert!(Path::from_str(truncated).is_err(), "{} parsed", truncated);
+
+			let extended = format!("{}/extra", path);
+			assert!(Path::from_str(&extended).is_err(), "{} parsed", extended);
+		}
+	}
+
+	#[test]
+	fn parse_errors_point_at_the_offending_segment() {
+		let path = "acks/ports/defaultPort/chans/channel-0/sequences/0";
+		assert_eq!(
+			Path::from_str(path),
+			Err(PathError::unexpected_segment(
+				path.to_string(),
+				3,
+				"chans".to_string(),
+				"channels".to_string()
+			))
+		);
+
+		let path = "receipts/ports/defaultPort/channels/channel-0/sequences/next";
+		assert_eq!(
+			Path::from_str(path),
+			Err(PathError::invalid_segment(path.to_string(), 6, "next".to_string()))
+		);
+
+		let path = "clients/07-tendermint-0/consensusStates/15";
+		assert_eq!(
+			Path::from_str(path),
+			Err(PathError::invalid_segment(path.to_string(), 3, "15".to_string()))
+		);
+
+		let path = "clients";
+		assert_eq!(Path::from_str(path), Err(PathError::invalid_length(path.to_string(), 1)));
+
+		let path = "packets/0";
+		assert_eq!(
+			Path::from_str(path),
+			Err(PathError::unknown_prefix(path.to_string(), "packets".to_string()))
+		);
+	}
 }
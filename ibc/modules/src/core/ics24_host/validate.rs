@@ -20,6 +20,17 @@ use super::error::ValidationError as Error;
 const PATH_SEPARATOR: char = '/';
 const VALID_SPECIAL_CHARS: &str = "._+-#[]<>";
 
+/// Identifier length limits from ICS-024, as enforced by ibc-go.
+pub const CLIENT_ID_MIN_LENGTH: usize = 9;
+pub const CLIENT_ID_MAX_LENGTH: usize = 64;
+pub const CONNECTION_ID_MIN_LENGTH: usize = 10;
+pub const CONNECTION_ID_MAX_LENGTH: usize = 64;
+pub const CHANNEL_ID_MIN_LENGTH: usize = 8;
+pub const CHANNEL_ID_MAX_LENGTH: usize = 64;
+pub const PORT_ID_MIN_LENGTH: usize = 2;
+/// Ports are bound by modules rather than generated, so they may be longer than other identifiers.
+pub const PORT_ID_MAX_LENGTH: usize = 128;
+
 /// Default validator function for identifiers.
 ///
 /// A valid identifier only contain lowercase alphabetic characters, and be of a given min and max
@@ -54,6 +65,56 @@ pub fn validate_identifier(id: &str, min: usize, max: usize) -> Result<(), Error
 	Ok(())
 }
 
+/// Validator function for identifiers that must be accepted by any ICS-024 compliant chain.
+///
+/// Unlike [`validate_identifier`], only ASCII alphanumeric characters are allowed next to the
+/// special characters, and a length error names the bound that was violated.
+pub fn validate_identifier_custom(id: &str, min: usize, max: usize) -> Result<(), Error> {
+	assert!(max >= min);
+
+	if id.is_empty() {
+		return Err(Error::empty())
+	}
+
+	if id.contains(PATH_SEPARATOR) {
+		return Err(Error::contain_separator(id.to_string()))
+	}
+
+	if id.len() < min {
+		return Err(Error::too_short(id.to_string(), id.len(), min))
+	}
+
+	if id.len() > max {
+		return Err(Error::too_long(id.to_string(), id.len(), max))
+	}
+
+	if !id.chars().all(|c| c.is_ascii_alphanumeric() || VALID_SPECIAL_CHARS.contains(c)) {
+		return Err(Error::invalid_character(id.to_string()))
+	}
+
+	Ok(())
+}
+
+/// Strict validator function for Client identifiers, see [`validate_identifier_custom`].
+pub fn validate_client_identifier_strict(id: &str) -> Result<(), Error> {
+	validate_identifier_custom(id, CLIENT_ID_MIN_LENGTH, CLIENT_ID_MAX_LENGTH)
+}
+
+/// Strict validator function for Connection identifiers, see [`validate_identifier_custom`].
+pub fn validate_connection_identifier_strict(id: &str) -> Result<(), Error> {
+	validate_identifier_custom(id, CONNECTION_ID_MIN_LENGTH, CONNECTION_ID_MAX_LENGTH)
+}
+
+/// Strict validator function for Channel identifiers, see [`validate_identifier_custom`].
+pub fn validate_channel_identifier_strict(id: &str) -> Result<(), Error> {
+	validate_identifier_custom(id, CHANNEL_ID_MIN_LENGTH, CHANNEL_ID_MAX_LENGTH)
+}
+
+/// Strict validator function for Port identifiers, see [`validate_identifier_custom`].
+pub fn validate_port_identifier_strict(id: &str) -> Result<(), Error> {
+	validate_identifier_custom(id, PORT_ID_MIN_LENGTH, PORT_ID_MAX_LENGTH)
+}
+
 /// Default validator function for Client identifiers.
 ///
 /// A valid identifier must be between 9-64 characters and only contain lowercase
@@ -80,10 +141,17 @@ pub fn validate_port_identifier(id: &str) -> Result<(), Error> {
 
 #[cfg(test)]
 mod tests {
-	use crate::core::ics24_host::validate::{
-		validate_client_identifier, validate_connection_identifier, validate_identifier,
-		validate_port_identifier,
+	use crate::core::ics24_host::{
+		error::ValidationErrorDetail,
+		validate::{
+			validate_channel_identifier_strict, validate_client_identifier,
+			validate_client_identifier_strict, validate_connection_identifier,
+			validate_connection_identifier_strict, validate_identifier,
+			validate_identifier_custom, validate_port_identifier, validate_port_identifier_strict,
+			CLIENT_ID_MAX_LENGTH, PORT_ID_MAX_LENGTH,
+		},
 	};
+	use crate::prelude::*;
 	use test_log::test;
 
 	#[test]
@@ -154,4 +222,42 @@ mod tests {
 		let id = validate_identifier("id/1", 1, 10);
 		assert!(id.is_err())
 	}
+
+	#[test]
+	fn strict_validation_rejects_non_ascii_chars() {
+		// accepted by the lenient check, which allows any alphanumeric character
+		assert!(validate_client_identifier("07-tendermínt-0").is_ok());
+
+		let id = validate_client_identifier_strict("07-tendermínt-0");
+		assert!(matches!(id.unwrap_err().detail(), ValidationErrorDetail::InvalidCharacter(_)));
+	}
+
+	#[test]
+	fn strict_validation_names_the_violated_limit() {
+		match validate_identifier_custom("short", 6, 10).unwrap_err().detail() {
+			ValidationErrorDetail::TooShort(e) => assert_eq!((e.length, e.min), (5, 6)),
+			e => panic!("Expected TooShort error, instead got {:?}", e),
+		}
+
+		let id = "a".repeat(CLIENT_ID_MAX_LENGTH + 1);
+		match validate_client_identifier_strict(&id).unwrap_err().detail() {
+			ValidationErrorDetail::TooLong(e) =>
+				assert_eq!((e.length, e.max), (CLIENT_ID_MAX_LENGTH + 1, CLIENT_ID_MAX_LENGTH)),
+			e => panic!("Expected TooLong error, instead got {:?}", e),
+		}
+	}
+
+	#[test]
+	fn strict_validation_accepts_ics024_identifiers() {
+		assert!(validate_client_identifier_strict("07-tendermint-0").is_ok());
+		assert!(validate_connection_identifier_strict("connection-0").is_ok());
+		assert!(validate_channel_identifier_strict("channel-0").is_ok());
+		assert!(validate_channel_identifier_strict("chan-0").is_err());
+
+		// port ids bound by modules may be longer than other identifiers
+		let port_id = "p".repeat(PORT_ID_MAX_LENGTH);
+		assert!(validate_port_identifier_strict(&port_id).is_ok());
+		assert!(validate_connection_identifier_strict(&port_id).is_err());
+		assert!(validate_port_identifier_strict(&format!("{}p", port_id)).is_err());
+	}
 }
@@ -20,25 +20,47 @@ use crate::{
 		ics03_connection::context::{ConnectionKeeper, ConnectionReader},
 		ics04_channel::{
 			channel::{Counterparty, Order},
-			context::{ChannelKeeper, ChannelReader},
+			context::{calculate_block_delay, ChannelKeeper, ChannelReader},
 			error::Error,
 			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
 			packet::Packet,
+			upgrade::UpgradeFields,
 			Version,
 		},
 		ics05_port::context::PortReader,
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::error::Error as Ics26Error,
 	},
 	events::ModuleEvent,
 	handler::HandlerOutputBuilder,
 	signer::Signer,
 };
 use alloc::borrow::{Borrow, Cow};
-use core::{any::Any, fmt, fmt::Debug, str::FromStr};
+use core::{any::Any, fmt, fmt::Debug, str::FromStr, time::Duration};
 use serde::{Deserialize, Serialize};
 
 /// This trait captures all the functional dependencies needed in light client implementations
-pub trait ReaderContext: ClientKeeper + ClientReader + ConnectionReader + ChannelReader {}
+pub trait ReaderContext: ClientKeeper + ClientReader + ConnectionReader + ChannelReader {
+	/// Whether the connection handshake must carry client and consensus state proofs. Hosts that
+	/// trust self-client validation alone can return `false`, in which case only the connection
+	/// proof is verified in `ConnOpenTry` and `ConnOpenAck`.
+	fn require_proofs_in_handshake(&self) -> bool {
+		true
+	}
+
+	/// Whether timeouts should be recorded through `ChannelKeeper::store_timed_out_packet`, so
+	/// that timed out packets can still be looked up once their commitment is deleted.
+	fn persist_timeouts(&self) -> bool {
+		false
+	}
+
+	/// Number of host blocks that must be produced for `delay_period` to elapse, derived from the
+	/// maximum expected time per block. Proofs bound to a connection are only accepted once the
+	/// connection delay has passed both in time and in blocks since the client was updated.
+	fn block_delay(&self, delay_period: Duration) -> u64 {
+		calculate_block_delay(delay_period, self.max_expected_time_per_block())
+	}
+}
 
 /// This trait captures all the dependencies needed for module callbacks to read storage
 pub trait ModuleCallbackContext: ConnectionReader + ChannelReader {}
@@ -54,6 +76,15 @@ pub trait Ics26Context:
 	fn router(&self) -> &Self::Router;
 
 	fn router_mut(&mut self) -> &mut Self::Router;
+
+	/// Runs `f`, discarding the writes it made to the host store if it fails, so that a message
+	/// that failed part way leaves no trace. Hosts that can't roll back writes run `f` as is.
+	fn with_transaction<R>(
+		&mut self,
+		f: impl FnOnce(&mut Self) -> Result<R, Ics26Error>,
+	) -> Result<R, Ics26Error> {
+		f(self)
+	}
 }
 
 #[derive(Debug, PartialEq)]
@@ -103,6 +134,31 @@ pub trait Acknowledgement: AsRef<[u8]> {}
 
 pub type ModuleOutputBuilder = HandlerOutputBuilder<(), ModuleEvent>;
 
+/// What a module did with the acknowledgement of a packet received in `on_recv_packet`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OnRecvPacketAck {
+	/// The packet needs no acknowledgement.
+	Nil,
+	/// The packet was processed, core IBC writes the given acknowledgement.
+	Successful(GenericAcknowledgement),
+	/// The packet could not be processed, core IBC writes the given error acknowledgement.
+	Failed(GenericAcknowledgement),
+	/// The module writes the acknowledgement itself through
+	/// [`write_acknowledgement`](crate::core::ics26_routing::handler::write_acknowledgement)
+	/// or the host's equivalent, either in the callback or once it is available at a later block.
+	Async,
+}
+
+impl OnRecvPacketAck {
+	/// The acknowledgement core IBC has to write for the packet, if any.
+	pub fn into_acknowledgement(self) -> Option<GenericAcknowledgement> {
+		match self {
+			OnRecvPacketAck::Successful(ack) | OnRecvPacketAck::Failed(ack) => Some(ack),
+			OnRecvPacketAck::Nil | OnRecvPacketAck::Async => None,
+		}
+	}
+}
+
 pub trait Module: Send + Sync + AsAnyMut {
 	#[allow(clippy::too_many_arguments)]
 	fn on_chan_open_init(
@@ -180,15 +236,68 @@ pub trait Module: Send + Sync + AsAnyMut {
 		Ok(())
 	}
 
-	/// Modules should write acknowledgement to storage in this callback
+	/// Called when this chain proposes an upgrade of the channel. Modules that cannot operate
+	/// with the proposed ordering or version should return an error.
+	fn on_chan_upgrade_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_proposed_upgrade: &UpgradeFields,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	/// Called when this chain accepts an upgrade of the channel proposed by the counterparty.
+	fn on_chan_upgrade_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty_upgrade: &UpgradeFields,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	/// Called once the upgraded channel is `Open` again on this chain.
+	fn on_chan_upgrade_open(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	/// Called when an upgrade is cancelled and the channel is `Open` again with the ordering and
+	/// version it had before the upgrade.
+	fn on_chan_upgrade_cancel(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	/// Modules either return the acknowledgement for core IBC to write, or
+	/// [`OnRecvPacketAck::Async`] when they write it themselves.
 	fn on_recv_packet(
 		&self,
 		_ctx: &dyn ModuleCallbackContext,
 		_output: &mut ModuleOutputBuilder,
 		_packet: &Packet,
 		_relayer: &Signer,
-	) -> Result<(), Error> {
-		Ok(())
+	) -> Result<OnRecvPacketAck, Error> {
+		Ok(OnRecvPacketAck::Nil)
 	}
 
 	fn on_acknowledgement_packet(
@@ -236,13 +345,19 @@ impl<M: Any + Module> AsAnyMut for M {
 	}
 }
 
-/// A router maintains a mapping of `ModuleId`s against `Modules`. Implementations must not publicly
-/// expose APIs to add new routes once constructed. Routes may only be added at the time of
-/// instantiation using the `RouterBuilder`.
+/// A router maintains a mapping of `ModuleId`s against `Modules`. Routes are added when the router
+/// is instantiated using the `RouterBuilder`, or at runtime through `Router::add_route`. Ports
+/// are routed to modules by the bindings kept by the host's `PortKeeper`, not by the router.
 pub trait Router {
 	/// Returns a mutable reference to a `Module` registered against the specified `ModuleId`
 	fn get_route_mut(&mut self, module_id: &ModuleId) -> Option<&mut dyn Module>;
 
 	/// Returns true if the `Router` has a `Module` registered against the specified `ModuleId`
 	fn has_route(&self, module_id: &ModuleId) -> bool;
+
+	/// Registers `Module` against the specified `ModuleId`
+	///
+	/// Returns an error if a `Module` has already been registered against the specified
+	/// `ModuleId`, or if the router's modules are fixed at construction
+	fn add_route(&mut self, module_id: ModuleId, module: Box<dyn Module>) -> Result<(), String>;
 }
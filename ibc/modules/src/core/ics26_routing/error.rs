@@ -17,7 +17,7 @@ use flex_error::{define_error, TraceError};
 
 use crate::{
 	applications::transfer,
-	core::{ics02_client, ics03_connection, ics04_channel},
+	core::{ics02_client, ics03_connection, ics04_channel, ics05_port},
 };
 
 define_error! {
@@ -35,6 +35,10 @@ define_error! {
 			[ ics04_channel::error::Error ]
 			| _ | { "ICS04 channel error" },
 
+		Ics05Port
+			[ ics05_port::error::Error ]
+			| _ | { "ICS05 port error" },
+
 		Ics20FungibleTokenTransfer
 			[ transfer::error::Error ]
 			| _ | { "ICS20 fungible token transfer error" },
@@ -16,7 +16,11 @@ use crate::{
 	core::{
 		ics02_client::{
 			context::{ClientKeeper, ClientTypes},
-			handler::dispatch as ics2_msg_dispatcher,
+			error::Error as Ics02Error,
+			handler::{decode_client_message, decode_client_state, dispatch as ics2_msg_dispatcher},
+			msgs::{
+				create_client, misbehaviour, update_client, update_client_batch, upgrade_client,
+			},
 		},
 		ics03_connection::handler::dispatch as ics3_msg_dispatcher,
 		ics04_channel::{
@@ -25,27 +29,53 @@ use crate::{
 				channel_validate as ics4_validate, get_module_for_packet_msg,
 				packet_callback as ics4_packet_callback,
 				packet_dispatch as ics4_packet_msg_dispatcher, recv_packet::RecvPacketResult,
+				recv_packets as ics4_recv_packets,
+				write_acknowledgement::process as ics4_write_acknowledgement,
 			},
-			packet::PacketResult,
+			msgs::{acknowledgement::Acknowledgement, recv_packet::MsgRecvPacket, PacketMsg},
+			packet::{Packet, PacketResult},
 		},
+		ics05_port::{context::PortKeeper, error::Error as Ics05Error},
+		ics24_host::identifier::PortId,
 		ics26_routing::{
-			context::{Ics26Context, ModuleOutputBuilder, ReaderContext},
+			context::{Ics26Context, ModuleId, ModuleOutputBuilder, ReaderContext, Router},
 			error::Error,
-			msgs::Ics26Envelope::{self, Ics2Msg, Ics3Msg, Ics4ChannelMsg, Ics4PacketMsg},
+			msgs::Ics26Envelope::{
+				self, Ics2Msg, Ics3Msg, Ics4ChannelMsg, Ics4PacketBatch, Ics4PacketMsg,
+			},
 		},
 	},
 	events::IbcEvent,
-	handler::HandlerOutput,
+	handler::{HandlerOutput, HandlerOutputBuilder, Log},
 	prelude::*,
 };
 use core::fmt::Debug;
-use ibc_proto::google::protobuf::Any;
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::client::v1::{
+		MsgCreateClient as RawMsgCreateClient, MsgSubmitMisbehaviour as RawMsgSubmitMisbehaviour,
+		MsgUpdateClient as RawMsgUpdateClient, MsgUpdateClientBatch as RawMsgUpdateClientBatch,
+		MsgUpgradeClient as RawMsgUpgradeClient,
+	},
+};
+use prost::Message;
 
 /// Result of message execution - comprises of events emitted and logs entries created during the
 /// execution of a transaction message.
 pub struct MsgReceipt {
 	pub events: Vec<IbcEvent>,
-	pub log: Vec<String>,
+	pub log: Vec<Log>,
+	pub outcome: DispatchOutcome,
+}
+
+/// Whether a dispatched message changed the host chain store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DispatchOutcome {
+	/// The message was applied and its results stored.
+	Applied,
+	/// The message was valid but redundant, e.g. a packet that another relayer already
+	/// delivered, so nothing was stored.
+	NoOp,
 }
 
 /// Mimics the DeliverTx ABCI interface, but for a single message and at a slightly lower level.
@@ -56,14 +86,58 @@ where
 	Ctx: Ics26Context + ReaderContext,
 	Ics26Envelope<Ctx>: TryFrom<Any>,
 	Error: From<<Ics26Envelope<Ctx> as TryFrom<Any>>::Error>,
+	Ctx::AnyClientState: TryFrom<Any, Error = Ics02Error>,
+	Ctx::AnyClientMessage: TryFrom<Any, Error = Ics02Error>,
 {
 	// Decode the proto message into a domain message, creating an ICS26 envelope.
-	let envelope = decode::<Ctx>(message)?;
+	let envelope = decode::<Ctx>(message.clone())
+		.map_err(|e| client_decode_error(ctx, &message).unwrap_or(e))?;
 
 	// Process the envelope, and accumulate any events that were generated.
-	let HandlerOutput { log, events, .. } = dispatch::<_>(ctx, envelope)?;
+	let HandlerOutput { log, events, result: outcome } = dispatch::<_>(ctx, envelope)?;
+
+	Ok(MsgReceipt { events, log, outcome })
+}
+
+/// Outcome of one of the messages passed to [`deliver_batch`].
+#[derive(Debug)]
+pub enum MessageResult {
+	/// The message was executed, producing these events and log entries.
+	Ok { events: Vec<IbcEvent>, logs: Vec<Log> },
+	/// The message at `index` in the batch failed, none of its writes were applied.
+	Err { index: usize, error: Error },
+}
 
-	Ok(MsgReceipt { events, log })
+/// Delivers a batch of messages in order, each in its own [`Ics26Context::with_transaction`], and
+/// returns the result of every message that was executed. When `abort_on_error` is set, the
+/// messages after the first one that fails are not executed, the writes of the messages before
+/// it are kept and it's up to the caller to discard them.
+pub fn deliver_batch<Ctx>(
+	ctx: &mut Ctx,
+	messages: Vec<Any>,
+	abort_on_error: bool,
+) -> Vec<MessageResult>
+where
+	Ctx: Ics26Context + ReaderContext,
+	Ics26Envelope<Ctx>: TryFrom<Any>,
+	Error: From<<Ics26Envelope<Ctx> as TryFrom<Any>>::Error>,
+	Ctx::AnyClientState: TryFrom<Any, Error = Ics02Error>,
+	Ctx::AnyClientMessage: TryFrom<Any, Error = Ics02Error>,
+{
+	let mut results = Vec::with_capacity(messages.len());
+	for (index, message) in messages.into_iter().enumerate() {
+		match ctx.with_transaction(|ctx| deliver(ctx, message)) {
+			Ok(MsgReceipt { events, log, .. }) =>
+				results.push(MessageResult::Ok { events, logs: log }),
+			Err(error) => {
+				results.push(MessageResult::Err { index, error });
+				if abort_on_error {
+					break
+				}
+			},
+		}
+	}
+	results
 }
 
 /// Attempts to convert a message into a [Ics26Envelope] message
@@ -76,12 +150,47 @@ where
 	message.try_into().map_err(Into::into)
 }
 
+/// Explains why an ICS2 message failed to decode when the client state or client message it
+/// carries is the culprit, which the protobuf decode error alone does not tell.
+fn client_decode_error<Ctx>(ctx: &Ctx, message: &Any) -> Option<Error>
+where
+	Ctx: ReaderContext,
+	Ctx::AnyClientState: TryFrom<Any, Error = Ics02Error>,
+	Ctx::AnyClientMessage: TryFrom<Any, Error = Ics02Error>,
+{
+	let value = message.value.as_slice();
+	let client_state = match message.type_url.as_str() {
+		create_client::TYPE_URL => RawMsgCreateClient::decode(value).ok()?.client_state?,
+		upgrade_client::TYPE_URL => RawMsgUpgradeClient::decode(value).ok()?.client_state?,
+		update_client::TYPE_URL => {
+			let client_message = RawMsgUpdateClient::decode(value).ok()?.client_message?;
+			return decode_client_message(ctx, client_message).err().map(Error::ics02_client)
+		},
+		update_client_batch::TYPE_URL => {
+			let client_messages = RawMsgUpdateClientBatch::decode(value).ok()?.client_messages;
+			return client_messages
+				.into_iter()
+				.find_map(|client_message| decode_client_message(ctx, client_message).err())
+				.map(Error::ics02_client)
+		},
+		misbehaviour::TYPE_URL => {
+			let misbehaviour = RawMsgSubmitMisbehaviour::decode(value).ok()?.misbehaviour?;
+			return decode_client_message(ctx, misbehaviour).err().map(Error::ics02_client)
+		},
+		_ => return None,
+	};
+	decode_client_state(ctx, client_state).err().map(Error::ics02_client)
+}
+
 /// Top-level ICS dispatch function. Routes incoming IBC messages to their corresponding module.
-/// Returns a handler output with empty result of type `HandlerOutput<()>` which contains the log
-/// and events produced after processing the input `msg`.
+/// Returns a handler output which contains the log and events produced after processing the input
+/// `msg`, and whether processing it changed the host chain store.
 /// If this method returns an error, the runtime is expected to rollback all state modifications to
 /// the `Ctx` caused by all messages from the transaction that this `msg` is a part of.
-pub fn dispatch<Ctx>(ctx: &mut Ctx, msg: Ics26Envelope<Ctx>) -> Result<HandlerOutput<()>, Error>
+pub fn dispatch<Ctx>(
+	ctx: &mut Ctx,
+	msg: Ics26Envelope<Ctx>,
+) -> Result<HandlerOutput<DispatchOutcome>, Error>
 where
 	Ctx: Ics26Context + ClientKeeper,
 {
@@ -96,7 +205,7 @@ where
 			HandlerOutput::builder()
 				.with_log(handler_output.log)
 				.with_events(handler_output.events)
-				.with_result(())
+				.with_result(DispatchOutcome::Applied)
 		},
 
 		Ics3Msg(msg) => {
@@ -110,7 +219,7 @@ where
 			HandlerOutput::builder()
 				.with_log(handler_output.log)
 				.with_events(handler_output.events)
-				.with_result(())
+				.with_result(DispatchOutcome::Applied)
 		},
 
 		Ics4ChannelMsg(msg) => {
@@ -127,33 +236,155 @@ where
 			// Apply any results to the host chain store.
 			ctx.store_channel_result(channel_result).map_err(Error::ics04_channel)?;
 
-			handler_builder.with_result(())
+			handler_builder.with_result(()).map(|_| DispatchOutcome::Applied)
 		},
 
 		Ics4PacketMsg(msg) => {
 			let module_id = get_module_for_packet_msg(ctx, &msg).map_err(Error::ics04_channel)?;
-			let (mut handler_builder, packet_result) =
+			let (handler_builder, packet_result) =
 				ics4_packet_msg_dispatcher::<_>(ctx, &msg).map_err(Error::ics04_channel)?;
 
-			if matches!(packet_result, PacketResult::Recv(RecvPacketResult::NoOp)) {
-				return Ok(handler_builder.with_result(()))
-			}
-
-			let mut module_output = ModuleOutputBuilder::new();
-			let cb_result = ics4_packet_callback(ctx, &module_id, &msg, &mut module_output);
-			handler_builder.merge(module_output);
-			cb_result.map_err(Error::ics04_channel)?;
-
-			// Apply any results to the host chain store.
-			ctx.store_packet_result(packet_result).map_err(Error::ics04_channel)?;
+			apply_packet_result(ctx, &module_id, &msg, handler_builder, packet_result)?
+		},
 
-			handler_builder.with_result(())
+		Ics4PacketBatch(msgs) => {
+			let mut outputs =
+				dispatch_recv_packets(ctx, msgs).into_iter().collect::<Result<Vec<_>, _>>()?;
+			// Whatever order the packets were submitted in, their events come out by sequence.
+			outputs.sort_by_key(|output| output.events.iter().find_map(IbcEvent::ordering_key));
+			let outcome = if outputs.iter().all(|output| output.result == DispatchOutcome::NoOp) {
+				DispatchOutcome::NoOp
+			} else {
+				DispatchOutcome::Applied
+			};
+			let mut handler_builder = HandlerOutputBuilder::new();
+			for output in outputs {
+				handler_builder.merge_output(output);
+			}
+			handler_builder.with_result(outcome)
 		},
 	};
 
 	Ok(output)
 }
 
+/// Dispatches a batch of `MsgRecvPacket`s, returning one result per message, each equal to the
+/// one `dispatch` would return for that message after the messages before it. The core handler
+/// checks of the whole batch run upfront, sharing the connection, client state and consensus
+/// state reads between packets proven at the same height. Should the module callback or the
+/// store fail for a packet that passed those checks, its writes are discarded and the messages
+/// after it are dispatched one by one instead.
+pub fn dispatch_recv_packets<Ctx>(
+	ctx: &mut Ctx,
+	msgs: Vec<MsgRecvPacket>,
+) -> Vec<Result<HandlerOutput<DispatchOutcome>, Error>>
+where
+	Ctx: Ics26Context + ClientKeeper,
+{
+	let results = ics4_recv_packets(&*ctx, &msgs);
+	let mut outputs = Vec::with_capacity(msgs.len());
+	let mut msgs = msgs.into_iter().map(PacketMsg::RecvPacket);
+	for (msg, result) in msgs.by_ref().zip(results) {
+		let passed = result.is_ok();
+		let output = get_module_for_packet_msg(ctx, &msg).map_err(Error::ics04_channel).and_then(
+			|module_id| {
+				let (handler_builder, packet_result) = result.map_err(Error::ics04_channel)?;
+				ctx.with_transaction(|ctx| {
+					apply_packet_result(ctx, &module_id, &msg, handler_builder, packet_result)
+				})
+			},
+		);
+		// The results after this one assumed it would be stored.
+		let diverged = passed && output.is_err();
+		outputs.push(output);
+		if diverged {
+			break
+		}
+	}
+	outputs.extend(msgs.map(|msg| ctx.with_transaction(|ctx| dispatch(ctx, Ics4PacketMsg(msg)))));
+	outputs
+}
+
+/// Runs the module callback for a packet message the core handler accepted, then stores the
+/// handler result.
+fn apply_packet_result<Ctx>(
+	ctx: &mut Ctx,
+	module_id: &ModuleId,
+	msg: &PacketMsg,
+	mut handler_builder: HandlerOutputBuilder<()>,
+	packet_result: PacketResult,
+) -> Result<HandlerOutput<DispatchOutcome>, Error>
+where
+	Ctx: Ics26Context,
+{
+	if matches!(packet_result, PacketResult::NoOp) {
+		return Ok(handler_builder.with_result(()).map(|_| DispatchOutcome::NoOp))
+	}
+
+	// A packet received after its timeout is recorded without involving the module.
+	if matches!(packet_result, PacketResult::Recv(RecvPacketResult::Timeout { .. })) {
+		ctx.store_packet_result(packet_result).map_err(Error::ics04_channel)?;
+		return Ok(handler_builder.with_result(()).map(|_| DispatchOutcome::Applied))
+	}
+
+	let mut module_output = ModuleOutputBuilder::new();
+	let ack = ics4_packet_callback(ctx, module_id, msg, &mut module_output)
+		.map_err(Error::ics04_channel)?;
+
+	// Apply any results to the host chain store.
+	ctx.store_packet_result(packet_result).map_err(Error::ics04_channel)?;
+
+	// The receipt is stored by now, so the acknowledgement returned by the module can be written.
+	if let (Some(ack), PacketMsg::RecvPacket(msg)) = (ack, msg) {
+		handler_builder.merge_output(write_acknowledgement(ctx, msg.packet.clone(), ack)?);
+	}
+
+	// The module's events follow all of the core handler's, the acknowledgement included.
+	handler_builder.merge(module_output);
+
+	Ok(handler_builder.with_result(()).map(|_| DispatchOutcome::Applied))
+}
+
+/// Writes the acknowledgement of a packet received on this chain. Modules that returned
+/// [`OnRecvPacketAck::Async`](crate::core::ics26_routing::context::OnRecvPacketAck::Async) from
+/// `on_recv_packet` call this once the acknowledgement is available. Fails unless the packet was
+/// received and has not been acknowledged yet.
+pub fn write_acknowledgement<Ctx>(
+	ctx: &mut Ctx,
+	packet: Packet,
+	ack: Acknowledgement,
+) -> Result<HandlerOutput<()>, Error>
+where
+	Ctx: Ics26Context,
+{
+	let output =
+		ics4_write_acknowledgement(&*ctx, packet, ack.into_bytes()).map_err(Error::ics04_channel)?;
+
+	ctx.store_packet_result(output.result).map_err(Error::ics04_channel)?;
+
+	Ok(HandlerOutput::builder()
+		.with_log(output.log)
+		.with_events(output.events)
+		.with_result(()))
+}
+
+/// Binds `port_id` to `module_id`, so that the channel handshakes and packets of the port are
+/// routed to that module. Fails if the port is already bound, if the router has no route for the
+/// module or if the host refuses the binding in `PortKeeper::authorize_port_binding`.
+pub fn bind_port<Ctx>(ctx: &mut Ctx, port_id: PortId, module_id: ModuleId) -> Result<(), Error>
+where
+	Ctx: Ics26Context + PortKeeper,
+{
+	if ctx.lookup_module_by_port(&port_id).is_ok() {
+		return Err(Error::ics05_port(Ics05Error::port_already_bound(port_id)))
+	}
+	if !ctx.router().has_route(&module_id) {
+		return Err(Error::ics05_port(Ics05Error::unknown_module(module_id)))
+	}
+	ctx.authorize_port_binding(&port_id, &module_id).map_err(Error::ics05_port)?;
+	ctx.store_port_binding(port_id, module_id).map_err(Error::ics05_port)
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::prelude::*;
@@ -168,9 +399,15 @@ mod tests {
 			PrefixedCoin, MODULE_ID_STR,
 		},
 		core::{
-			ics02_client::msgs::{
-				create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient,
-				upgrade_client::MsgUpgradeAnyClient, ClientMsg,
+			ics02_client::{
+				context::ClientReader,
+				error::ErrorDetail as Ics02ErrorDetail,
+				msgs::{
+					create_client::{self, MsgCreateAnyClient},
+					update_client::MsgUpdateAnyClient,
+					upgrade_client::MsgUpgradeAnyClient,
+					ClientMsg,
+				},
 			},
 			ics03_connection::msgs::{
 				conn_open_ack::{test_util::get_dummy_raw_msg_conn_open_ack, MsgConnectionOpenAck},
@@ -203,24 +440,51 @@ mod tests {
 
 	use crate::{
 		core::{
-			ics24_host::identifier::ConnectionId,
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				commitment::compute_ack_commitment,
+				context::{ChannelKeeper, ChannelReader},
+				error::{Error as Ics04Error, ErrorDetail as Ics04ErrorDetail},
+				msgs::acknowledgement::Acknowledgement,
+				packet::{test_utils::get_dummy_raw_packet, Packet, Receipt},
+				Version,
+			},
+			ics03_connection::{
+				connection::{ConnectionEnd, State as ConnectionState},
+				version::get_compatible_versions,
+			},
+			ics05_port::{context::PortReader, error::ErrorDetail as Ics05ErrorDetail},
+			ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
 			ics26_routing::{
-				context::{Ics26Context, ModuleId, Router, RouterBuilder},
-				error::Error,
-				handler::dispatch,
+				context::{
+					Ics26Context, Module, ModuleCallbackContext, ModuleId, ModuleOutputBuilder,
+					Router, RouterBuilder,
+				},
+				error::{Error, ErrorDetail},
+				handler::{
+					bind_port, deliver, deliver_batch, dispatch, write_acknowledgement,
+					MessageResult,
+				},
 				msgs::Ics26Envelope,
 			},
 		},
 		handler::HandlerOutputBuilder,
 		mock::{
+			builder::MockContextBuilder,
 			client_state::{MockClientState, MockConsensusState},
 			context::{MockClientTypes, MockContext, MockRouterBuilder},
 			header::{MockClientMessage, MockHeader},
+			host::MockHostType,
 		},
+		signer::Signer,
 		test_utils::{get_dummy_account_id, DummyTransferModule},
 		timestamp::Timestamp,
 		Height,
 	};
+	use ibc_proto::{
+		google::protobuf::Any, ibc::core::client::v1::MsgCreateClient as RawMsgCreateClient,
+	};
+	use prost::Message;
 
 	#[test]
 	#[ignore]
@@ -349,6 +613,7 @@ mod tests {
 				token: PrefixedCoin { denom, amount: msg_transfer_two.token.amount },
 				sender: msg_transfer_two.sender.clone(),
 				receiver: msg_transfer_two.receiver.clone(),
+				memo: String::new(),
 			};
 			serde_json::to_vec(&data).expect("PacketData's infallible Serialize impl failed")
 		};
@@ -590,4 +855,264 @@ mod tests {
 			);
 		}
 	}
+
+	#[test]
+	fn deferred_acknowledgement_is_written_once() {
+		let packet: Packet = get_dummy_raw_packet(1, 6).try_into().unwrap();
+		let key = (packet.destination_port.clone(), packet.destination_channel, packet.sequence);
+		let chan_end = ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+			vec![ConnectionId::default()],
+			Version::default(),
+		);
+		let mut ctx = MockContext::<MockClientTypes>::default().with_channel(
+			packet.destination_port.clone(),
+			packet.destination_channel,
+			chan_end,
+		);
+		let ack = Acknowledgement::from(vec![1u8]);
+
+		// Nothing can be acknowledged before the packet is received.
+		assert!(write_acknowledgement(&mut ctx, packet.clone(), ack.clone()).is_err());
+
+		// The module returned `OnRecvPacketAck::Async`, so only the receipt is stored.
+		ctx.store_packet_receipt(key.clone(), Receipt::Ok).unwrap();
+		assert!(ctx.get_packet_acknowledgement(&key).is_err());
+
+		let output = write_acknowledgement(&mut ctx, packet.clone(), ack.clone()).unwrap();
+		assert!(matches!(output.events[0], IbcEvent::WriteAcknowledgement(_)));
+		assert_eq!(ctx.get_packet_acknowledgement(&key).unwrap(), compute_ack_commitment(&ack));
+
+		match write_acknowledgement(&mut ctx, packet.clone(), ack) {
+			Err(e) => match e.detail() {
+				ErrorDetail::Ics04Channel(e) => match &e.source {
+					Ics04ErrorDetail::AcknowledgementExists(e) => {
+						assert_eq!(e.sequence, packet.sequence);
+					},
+					e => panic!("Expected AcknowledgementExists error, instead got {:?}", e),
+				},
+				_ => panic!("Expected ICS04 error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected AcknowledgementExists error"),
+		}
+	}
+
+	#[test]
+	fn channel_handshakes_resolve_through_port_bindings() {
+		let msg_conn_init =
+			MsgConnectionOpenInit::try_from(get_dummy_raw_msg_conn_open_init()).unwrap();
+		let conn_end = ConnectionEnd::new(
+			ConnectionState::Init,
+			msg_conn_init.client_id.clone(),
+			msg_conn_init.counterparty.clone(),
+			get_compatible_versions(),
+			msg_conn_init.delay_period,
+		);
+		let mut ctx = MockContext::<MockClientTypes>::default()
+			.with_connection(ConnectionId::default(), conn_end);
+
+		let module_a: ModuleId = "modulea".parse().unwrap();
+		let module_b: ModuleId = "moduleb".parse().unwrap();
+		let port_a: PortId = "porta".parse().unwrap();
+		let port_b: PortId = "portb".parse().unwrap();
+		for module_id in [&module_a, &module_b] {
+			let module = DummyTransferModule::new(ctx.ibc_store_share());
+			ctx.router_mut().add_route(module_id.clone(), Box::new(module)).unwrap();
+		}
+		let module = DummyTransferModule::new(ctx.ibc_store_share());
+		assert!(ctx.router_mut().add_route(module_a.clone(), Box::new(module)).is_err());
+
+		let bind_error = |res: Result<(), Error>| match res {
+			Err(e) => match e.detail() {
+				ErrorDetail::Ics05Port(e) => e.source.clone(),
+				_ => panic!("Expected ICS05 error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ICS05 error"),
+		};
+		let unrouted = bind_port(&mut ctx, port_a.clone(), "modulec".parse().unwrap());
+		assert!(matches!(bind_error(unrouted), Ics05ErrorDetail::UnknownModule(_)));
+
+		bind_port(&mut ctx, port_a.clone(), module_a.clone()).unwrap();
+		bind_port(&mut ctx, port_b.clone(), module_b.clone()).unwrap();
+		assert_eq!(ctx.lookup_module_by_port(&port_a).unwrap(), module_a);
+		assert_eq!(ctx.lookup_module_by_port(&port_b).unwrap(), module_b);
+		let rebound = bind_port(&mut ctx, port_a.clone(), module_b);
+		assert!(matches!(bind_error(rebound), Ics05ErrorDetail::PortAlreadyBound(_)));
+
+		let chan_open_init = |port_id: &PortId| {
+			let mut msg =
+				MsgChannelOpenInit::try_from(get_dummy_raw_msg_chan_open_init()).unwrap();
+			msg.port_id = port_id.clone();
+			Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenInit(msg))
+		};
+		dispatch(&mut ctx, chan_open_init(&port_a)).unwrap();
+		dispatch(&mut ctx, chan_open_init(&port_b)).unwrap();
+
+		let unbound: PortId = "portc".parse().unwrap();
+		match dispatch(&mut ctx, chan_open_init(&unbound)) {
+			Err(e) => match e.detail() {
+				ErrorDetail::Ics04Channel(e) => match &e.source {
+					Ics04ErrorDetail::Ics05Port(e) => match &e.source {
+						Ics05ErrorDetail::ModuleNotFound(e) => assert_eq!(e.port_id, unbound),
+						e => panic!("Expected ModuleNotFound error, instead got {:?}", e),
+					},
+					e => panic!("Expected ICS05 error, instead got {:?}", e),
+				},
+				_ => panic!("Expected ICS04 error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected ModuleNotFound error"),
+		}
+	}
+
+	#[test]
+	fn chan_open_try_stores_the_version_picked_by_the_module() {
+		/// Answers every proposal with a version of its own.
+		#[derive(Debug)]
+		struct CounterProposingModule(Version);
+
+		impl Module for CounterProposingModule {
+			fn on_chan_open_try(
+				&mut self,
+				_ctx: &dyn ModuleCallbackContext,
+				_output: &mut ModuleOutputBuilder,
+				_order: Order,
+				_connection_hops: &[ConnectionId],
+				_port_id: &PortId,
+				_channel_id: &ChannelId,
+				_counterparty: &Counterparty,
+				_version: &Version,
+				_counterparty_version: &Version,
+				_relayer: &Signer,
+			) -> Result<Version, Ics04Error> {
+				Ok(self.0.clone())
+			}
+		}
+
+		let proof_height = 10;
+		let conn_id = ConnectionId::new(2);
+		let client_id = ClientId::new(&MockClientState::client_type(), 45).unwrap();
+		let counterparty = MockContext::new(
+			ChainId::new("mockcounterparty".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			Height::new(0, proof_height),
+		);
+		let mut ctx = MockContextBuilder::new(MockContext::default())
+			.with_counterparty(counterparty)
+			.with_open_connection(client_id, conn_id.clone())
+			.build();
+
+		let module_id: ModuleId = "counterproposing".parse().unwrap();
+		let picked = Version::new("counter-proposal".to_string());
+		let module = CounterProposingModule(picked.clone());
+		ctx.router_mut().add_route(module_id.clone(), Box::new(module)).unwrap();
+
+		let mut msg =
+			MsgChannelOpenTry::try_from(get_dummy_raw_msg_chan_open_try(proof_height)).unwrap();
+		msg.channel.connection_hops = vec![conn_id];
+		msg.channel.version = Version::new("proposal".to_string());
+		msg.counterparty_version = Version::new("proposal".to_string());
+		let port_id = msg.port_id.clone();
+		bind_port(&mut ctx, port_id.clone(), module_id).unwrap();
+		dispatch(&mut ctx, Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelOpenTry(msg))).unwrap();
+
+		// The channel end records the module's version, which the relayer then hands to the
+		// counterparty in its `MsgChannelOpenAck`.
+		let channel_end = ctx.channel_end(&(port_id, ChannelId::new(0))).unwrap();
+		assert_eq!(channel_end.state(), &State::TryOpen);
+		assert_eq!(channel_end.version(), &picked);
+	}
+
+	#[test]
+	fn deliver_batch_reports_the_failed_message() {
+		let create_client = || {
+			let height = Height::new(0, 42);
+			let raw_msg = RawMsgCreateClient {
+				client_state: Some(
+					AnyClientState::from(MockClientState::new(MockClientMessage::Header(
+						MockHeader::new(height),
+					)))
+					.into(),
+				),
+				consensus_state: Some(
+					AnyConsensusState::from(MockConsensusState::new(MockHeader::new(height)))
+						.into(),
+				),
+				signer: get_dummy_account_id().to_string(),
+			};
+			Any { type_url: create_client::TYPE_URL.to_string(), value: raw_msg.encode_to_vec() }
+		};
+		let bogus = Any { type_url: "/bogus.Msg".to_string(), value: vec![] };
+		let batch = vec![create_client(), bogus, create_client()];
+
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		let results = deliver_batch(&mut ctx, batch.clone(), true);
+		assert_eq!(results.len(), 2);
+		assert!(matches!(&results[0], MessageResult::Ok { events, .. } if !events.is_empty()));
+		match &results[1] {
+			MessageResult::Err { index, error } => {
+				assert_eq!(*index, 1);
+				assert!(matches!(error.detail(), ErrorDetail::UnknownMessageTypeUrl(_)));
+			},
+			r => panic!("Expected the second message to fail, instead got {:?}", r),
+		}
+		assert_eq!(ctx.client_counter().unwrap(), 1);
+
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		let results = deliver_batch(&mut ctx, batch, false);
+		assert_eq!(results.len(), 3);
+		assert!(matches!(results[1], MessageResult::Err { index: 1, .. }));
+		assert!(matches!(results[2], MessageResult::Ok { .. }));
+		assert_eq!(ctx.client_counter().unwrap(), 2);
+	}
+
+	#[test]
+	fn failed_transaction_discards_its_writes() {
+		let packet: Packet = get_dummy_raw_packet(1, 6).try_into().unwrap();
+		let key = (packet.destination_port.clone(), packet.destination_channel, packet.sequence);
+		let mut ctx = MockContext::<MockClientTypes>::default();
+
+		let res = ctx.with_transaction(|ctx| {
+			ctx.store_packet_receipt(key.clone(), Receipt::Ok).unwrap();
+			Err::<(), _>(Error::unknown_message_type_url("/bogus.Msg".to_string()))
+		});
+		assert!(res.is_err());
+		assert!(ctx.get_packet_receipt(&key).is_err());
+
+		ctx.with_transaction(|ctx| {
+			ctx.store_packet_receipt(key.clone(), Receipt::Ok).map_err(Error::ics04_channel)
+		})
+		.unwrap();
+		assert!(ctx.get_packet_receipt(&key).is_ok());
+	}
+
+	#[test]
+	fn deliver_reports_unknown_client_type() {
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		let consensus_state =
+			AnyConsensusState::from(MockConsensusState::new(MockHeader::new(Height::new(0, 42))));
+		let raw_msg = RawMsgCreateClient {
+			client_state: Some(Any { type_url: "/bogus.ClientState".to_string(), value: vec![] }),
+			consensus_state: Some(consensus_state.into()),
+			signer: get_dummy_account_id().to_string(),
+		};
+		let message =
+			Any { type_url: create_client::TYPE_URL.to_string(), value: raw_msg.encode_to_vec() };
+
+		match deliver(&mut ctx, message) {
+			Err(e) => match e.detail() {
+				ErrorDetail::Ics02Client(e) => match &e.source {
+					Ics02ErrorDetail::UnknownClientType(e) => {
+						assert_eq!(e.client_type, "/bogus.ClientState");
+						assert_eq!(e.supported, vec![MockClientState::client_type()]);
+					},
+					e => panic!("Expected UnknownClientType error, instead got {:?}", e),
+				},
+				_ => panic!("Expected ICS02 error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected UnknownClientType error"),
+		}
+	}
 }
@@ -19,25 +19,32 @@ use ibc_proto::google::protobuf::Any;
 
 use crate::core::{
 	ics02_client::msgs::{
-		create_client, create_client::MsgCreateAnyClient, update_client,
-		update_client::MsgUpdateAnyClient, upgrade_client, upgrade_client::MsgUpgradeAnyClient,
-		ClientMsg,
+		create_client, create_client::MsgCreateAnyClient, misbehaviour,
+		misbehaviour::MsgSubmitMisbehaviour, recover_client, update_client,
+		update_client::MsgUpdateAnyClient, update_client_batch,
+		update_client_batch::MsgUpdateAnyClientBatch, upgrade_client,
+		upgrade_client::MsgUpgradeAnyClient, ClientMsg,
 	},
 	ics03_connection::msgs::{
-		conn_open_ack, conn_open_ack::MsgConnectionOpenAck, conn_open_confirm, conn_open_init,
-		conn_open_try, conn_open_try::MsgConnectionOpenTry, ConnectionMsg,
+		conn_cancel, conn_open_ack, conn_open_ack::MsgConnectionOpenAck, conn_open_confirm,
+		conn_open_init, conn_open_try, conn_open_try::MsgConnectionOpenTry, ConnectionMsg,
 	},
 	ics04_channel::msgs::{
-		acknowledgement, chan_close_confirm, chan_close_init, chan_open_ack, chan_open_confirm,
-		chan_open_init, chan_open_try, recv_packet, timeout, timeout_on_close, ChannelMsg,
-		PacketMsg,
+		acknowledgement, chan_close_confirm, chan_close_frozen, chan_close_init, chan_open_ack,
+		chan_open_confirm, chan_open_init, chan_open_try, chan_upgrade_ack, chan_upgrade_cancel,
+		chan_upgrade_confirm, chan_upgrade_init, chan_upgrade_try, prune_acknowledgements,
+		recv_packet, timeout, timeout_on_close, ChannelMsg, PacketMsg,
 	},
 	ics26_routing::error::Error,
 };
+use crate::signer::Signer;
 
 use crate::core::ics02_client::context::ClientTypes;
 use ibc_proto::ibc::core::{
-	client::v1::{MsgCreateClient, MsgUpdateClient, MsgUpgradeClient},
+	client::v1::{
+		MsgCreateClient, MsgSubmitMisbehaviour as RawMsgSubmitMisbehaviour, MsgUpdateClient,
+		MsgUpdateClientBatch, MsgUpgradeClient,
+	},
 	connection,
 };
 use tendermint_proto::Protobuf;
@@ -52,6 +59,62 @@ where
 	Ics3Msg(ConnectionMsg<C>),
 	Ics4ChannelMsg(ChannelMsg),
 	Ics4PacketMsg(PacketMsg),
+	/// Consecutive `MsgRecvPacket`s processed together, sharing the connection, client state and
+	/// consensus state reads of packets proven at the same height. Never decoded from an `Any`,
+	/// hosts build it from messages they have already decoded.
+	Ics4PacketBatch(Vec<recv_packet::MsgRecvPacket>),
+}
+
+impl<C> Ics26Envelope<C>
+where
+	C: ClientTypes + Eq + Clone + Debug,
+{
+	/// Accounts the messages in the envelope claim to be signed by. Hosts have to check them
+	/// against the account that actually signed the transaction, handlers use them to authorize
+	/// the messages.
+	pub fn signers(&self) -> Vec<&Signer> {
+		let signer = match self {
+			Ics26Envelope::Ics2Msg(msg) => match msg {
+				ClientMsg::CreateClient(msg) => &msg.signer,
+				ClientMsg::UpdateClient(msg) => &msg.signer,
+				ClientMsg::UpdateClientBatch(msg) => &msg.signer,
+				ClientMsg::UpgradeClient(msg) => &msg.signer,
+				ClientMsg::RecoverClient(msg) => &msg.signer,
+				ClientMsg::Misbehaviour(msg) => &msg.signer,
+			},
+			Ics26Envelope::Ics3Msg(msg) => match msg {
+				ConnectionMsg::ConnectionOpenInit(msg) => &msg.signer,
+				ConnectionMsg::ConnectionOpenTry(msg) => &msg.signer,
+				ConnectionMsg::ConnectionOpenAck(msg) => &msg.signer,
+				ConnectionMsg::ConnectionOpenConfirm(msg) => &msg.signer,
+				ConnectionMsg::ConnectionCancel(msg) => &msg.signer,
+			},
+			Ics26Envelope::Ics4ChannelMsg(msg) => match msg {
+				ChannelMsg::ChannelOpenInit(msg) => &msg.signer,
+				ChannelMsg::ChannelOpenTry(msg) => &msg.signer,
+				ChannelMsg::ChannelOpenAck(msg) => &msg.signer,
+				ChannelMsg::ChannelOpenConfirm(msg) => &msg.signer,
+				ChannelMsg::ChannelCloseInit(msg) => &msg.signer,
+				ChannelMsg::ChannelCloseConfirm(msg) => &msg.signer,
+				ChannelMsg::ChannelCloseFrozen(msg) => &msg.signer,
+				ChannelMsg::ChannelUpgradeInit(msg) => &msg.signer,
+				ChannelMsg::ChannelUpgradeTry(msg) => &msg.signer,
+				ChannelMsg::ChannelUpgradeAck(msg) => &msg.signer,
+				ChannelMsg::ChannelUpgradeConfirm(msg) => &msg.signer,
+				ChannelMsg::ChannelUpgradeCancel(msg) => &msg.signer,
+			},
+			Ics26Envelope::Ics4PacketMsg(msg) => match msg {
+				PacketMsg::RecvPacket(msg) => &msg.signer,
+				PacketMsg::AckPacket(msg) => &msg.signer,
+				PacketMsg::ToPacket(msg) => &msg.signer,
+				PacketMsg::ToClosePacket(msg) => &msg.signer,
+				PacketMsg::PruneAcknowledgements(msg) => &msg.signer,
+			},
+			Ics26Envelope::Ics4PacketBatch(msgs) =>
+				return msgs.iter().map(|msg| &msg.signer).collect(),
+		};
+		vec![signer]
+	}
 }
 
 impl<C> TryFrom<Any> for Ics26Envelope<C>
@@ -66,9 +129,15 @@ where
 	MsgUpdateAnyClient<C>: TryFrom<MsgUpdateClient>,
 	<MsgUpdateAnyClient<C> as TryFrom<MsgUpdateClient>>::Error: Display,
 	MsgUpdateAnyClient<C>: Protobuf<MsgUpdateClient>,
+	MsgUpdateAnyClientBatch<C>: TryFrom<MsgUpdateClientBatch>,
+	<MsgUpdateAnyClientBatch<C> as TryFrom<MsgUpdateClientBatch>>::Error: Display,
+	MsgUpdateAnyClientBatch<C>: Protobuf<MsgUpdateClientBatch>,
 	MsgUpgradeAnyClient<C>: TryFrom<MsgUpgradeClient>,
 	<MsgUpgradeAnyClient<C> as TryFrom<MsgUpgradeClient>>::Error: Display,
 	MsgUpgradeAnyClient<C>: Protobuf<MsgUpgradeClient>,
+	MsgSubmitMisbehaviour<C>: TryFrom<RawMsgSubmitMisbehaviour>,
+	<MsgSubmitMisbehaviour<C> as TryFrom<RawMsgSubmitMisbehaviour>>::Error: Display,
+	MsgSubmitMisbehaviour<C>: Protobuf<RawMsgSubmitMisbehaviour>,
 	MsgConnectionOpenTry<C>: TryFrom<connection::v1::MsgConnectionOpenTry>,
 	<MsgConnectionOpenTry<C> as TryFrom<connection::v1::MsgConnectionOpenTry>>::Error: Display,
 	MsgConnectionOpenTry<C>: Protobuf<connection::v1::MsgConnectionOpenTry>,
@@ -93,12 +162,27 @@ where
 					.map_err(Error::malformed_message_bytes)?;
 				Ok(Ics26Envelope::Ics2Msg(ClientMsg::UpdateClient(domain_msg)))
 			},
+			update_client_batch::TYPE_URL => {
+				let domain_msg = MsgUpdateAnyClientBatch::<C>::decode_vec(&any_msg.value)
+					.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics2Msg(ClientMsg::UpdateClientBatch(domain_msg)))
+			},
 			upgrade_client::TYPE_URL => {
 				let domain_msg =
 					upgrade_client::MsgUpgradeAnyClient::<C>::decode_vec(&any_msg.value)
 						.map_err(Error::malformed_message_bytes)?;
 				Ok(Ics26Envelope::Ics2Msg(ClientMsg::UpgradeClient(domain_msg)))
 			},
+			recover_client::TYPE_URL => {
+				let domain_msg = recover_client::MsgRecoverClient::decode_vec(&any_msg.value)
+					.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics2Msg(ClientMsg::RecoverClient(domain_msg)))
+			},
+			misbehaviour::TYPE_URL => {
+				let domain_msg = MsgSubmitMisbehaviour::<C>::decode_vec(&any_msg.value)
+					.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics2Msg(ClientMsg::Misbehaviour(domain_msg)))
+			},
 
 			// ICS03
 			conn_open_init::TYPE_URL => {
@@ -122,6 +206,11 @@ where
 						.map_err(Error::malformed_message_bytes)?;
 				Ok(Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionOpenConfirm(domain_msg)))
 			},
+			conn_cancel::TYPE_URL => {
+				let domain_msg = conn_cancel::MsgConnectionCancel::decode_vec(&any_msg.value)
+					.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics3Msg(ConnectionMsg::ConnectionCancel(domain_msg)))
+			},
 
 			// ICS04 channel messages
 			chan_open_init::TYPE_URL => {
@@ -156,6 +245,40 @@ where
 						.map_err(Error::malformed_message_bytes)?;
 				Ok(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelCloseConfirm(domain_msg)))
 			},
+			chan_close_frozen::TYPE_URL => {
+				let domain_msg =
+					chan_close_frozen::MsgChannelCloseFrozen::decode_vec(&any_msg.value)
+						.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelCloseFrozen(domain_msg)))
+			},
+			chan_upgrade_init::TYPE_URL => {
+				let domain_msg =
+					chan_upgrade_init::MsgChannelUpgradeInit::decode_vec(&any_msg.value)
+						.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelUpgradeInit(domain_msg)))
+			},
+			chan_upgrade_try::TYPE_URL => {
+				let domain_msg = chan_upgrade_try::MsgChannelUpgradeTry::decode_vec(&any_msg.value)
+					.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelUpgradeTry(domain_msg)))
+			},
+			chan_upgrade_ack::TYPE_URL => {
+				let domain_msg = chan_upgrade_ack::MsgChannelUpgradeAck::decode_vec(&any_msg.value)
+					.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelUpgradeAck(domain_msg)))
+			},
+			chan_upgrade_confirm::TYPE_URL => {
+				let domain_msg =
+					chan_upgrade_confirm::MsgChannelUpgradeConfirm::decode_vec(&any_msg.value)
+						.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelUpgradeConfirm(domain_msg)))
+			},
+			chan_upgrade_cancel::TYPE_URL => {
+				let domain_msg =
+					chan_upgrade_cancel::MsgChannelUpgradeCancel::decode_vec(&any_msg.value)
+						.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics4ChannelMsg(ChannelMsg::ChannelUpgradeCancel(domain_msg)))
+			},
 			// ICS04 packet messages
 			recv_packet::TYPE_URL => {
 				let domain_msg = recv_packet::MsgRecvPacket::decode_vec(&any_msg.value)
@@ -177,6 +300,12 @@ where
 					.map_err(Error::malformed_message_bytes)?;
 				Ok(Ics26Envelope::Ics4PacketMsg(PacketMsg::ToClosePacket(domain_msg)))
 			},
+			prune_acknowledgements::TYPE_URL => {
+				let domain_msg =
+					prune_acknowledgements::MsgPruneAcknowledgements::decode_vec(&any_msg.value)
+						.map_err(Error::malformed_message_bytes)?;
+				Ok(Ics26Envelope::Ics4PacketMsg(PacketMsg::PruneAcknowledgements(domain_msg)))
+			},
 			_ => Err(Error::unknown_message_type_url(any_msg.type_url)),
 		}
 	}
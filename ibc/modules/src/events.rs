@@ -30,7 +30,7 @@ use crate::{
 		},
 		ics04_channel::{
 			error as channel_error, events as ChannelEvents,
-			events::Attributes as ChannelAttributes, packet::Packet,
+			events::Attributes as ChannelAttributes, packet::{Packet, Sequence},
 		},
 		ics24_host::error::ValidationError,
 		ics26_routing::context::ModuleId,
@@ -116,11 +116,13 @@ const CREATE_CLIENT_EVENT: &str = "create_client";
 const UPDATE_CLIENT_EVENT: &str = "update_client";
 const CLIENT_MISBEHAVIOUR_EVENT: &str = "client_misbehaviour";
 const UPGRADE_CLIENT_EVENT: &str = "upgrade_client";
+const CLIENT_RECOVERED_EVENT: &str = "client_recovered";
 /// Connection event types
 const CONNECTION_INIT_EVENT: &str = "connection_open_init";
 const CONNECTION_TRY_EVENT: &str = "connection_open_try";
 const CONNECTION_ACK_EVENT: &str = "connection_open_ack";
 const CONNECTION_CONFIRM_EVENT: &str = "connection_open_confirm";
+const CONNECTION_CANCELLED_EVENT: &str = "connection_cancelled";
 /// Channel event types
 const CHANNEL_OPEN_INIT_EVENT: &str = "channel_open_init";
 const CHANNEL_OPEN_TRY_EVENT: &str = "channel_open_try";
@@ -128,6 +130,11 @@ const CHANNEL_OPEN_ACK_EVENT: &str = "channel_open_ack";
 const CHANNEL_OPEN_CONFIRM_EVENT: &str = "channel_open_confirm";
 const CHANNEL_CLOSE_INIT_EVENT: &str = "channel_close_init";
 const CHANNEL_CLOSE_CONFIRM_EVENT: &str = "channel_close_confirm";
+const CHANNEL_UPGRADE_INIT_EVENT: &str = "channel_upgrade_init";
+const CHANNEL_UPGRADE_TRY_EVENT: &str = "channel_upgrade_try";
+const CHANNEL_UPGRADE_ACK_EVENT: &str = "channel_upgrade_ack";
+const CHANNEL_UPGRADE_CONFIRM_EVENT: &str = "channel_upgrade_confirm";
+const CHANNEL_UPGRADE_CANCEL_EVENT: &str = "channel_upgrade_cancel";
 /// Packet event types
 const SEND_PACKET_EVENT: &str = "send_packet";
 const RECEIVE_PACKET_EVENT: &str = "receive_packet";
@@ -135,6 +142,7 @@ const WRITE_ACK_EVENT: &str = "write_acknowledgement";
 const ACK_PACKET_EVENT: &str = "acknowledge_packet";
 const TIMEOUT_EVENT: &str = "timeout_packet";
 const TIMEOUT_ON_CLOSE_EVENT: &str = "timeout_packet_on_close";
+const ACKNOWLEDGEMENTS_PRUNED_EVENT: &str = "acknowledgements_pruned";
 
 /// Events types
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -144,22 +152,30 @@ pub enum IbcEventType {
 	UpdateClient,
 	UpgradeClient,
 	ClientMisbehaviour,
+	ClientRecovered,
 	OpenInitConnection,
 	OpenTryConnection,
 	OpenAckConnection,
 	OpenConfirmConnection,
+	ConnectionCancelled,
 	OpenInitChannel,
 	OpenTryChannel,
 	OpenAckChannel,
 	OpenConfirmChannel,
 	CloseInitChannel,
 	CloseConfirmChannel,
+	ChannelUpgradeInit,
+	ChannelUpgradeTry,
+	ChannelUpgradeAck,
+	ChannelUpgradeConfirm,
+	ChannelUpgradeCancel,
 	SendPacket,
 	ReceivePacket,
 	WriteAck,
 	AckPacket,
 	Timeout,
 	TimeoutOnClose,
+	AcknowledgementsPruned,
 	AppModule,
 	Empty,
 	ChainError,
@@ -173,22 +189,30 @@ impl IbcEventType {
 			IbcEventType::UpdateClient => UPDATE_CLIENT_EVENT,
 			IbcEventType::UpgradeClient => UPGRADE_CLIENT_EVENT,
 			IbcEventType::ClientMisbehaviour => CLIENT_MISBEHAVIOUR_EVENT,
+			IbcEventType::ClientRecovered => CLIENT_RECOVERED_EVENT,
 			IbcEventType::OpenInitConnection => CONNECTION_INIT_EVENT,
 			IbcEventType::OpenTryConnection => CONNECTION_TRY_EVENT,
 			IbcEventType::OpenAckConnection => CONNECTION_ACK_EVENT,
 			IbcEventType::OpenConfirmConnection => CONNECTION_CONFIRM_EVENT,
+			IbcEventType::ConnectionCancelled => CONNECTION_CANCELLED_EVENT,
 			IbcEventType::OpenInitChannel => CHANNEL_OPEN_INIT_EVENT,
 			IbcEventType::OpenTryChannel => CHANNEL_OPEN_TRY_EVENT,
 			IbcEventType::OpenAckChannel => CHANNEL_OPEN_ACK_EVENT,
 			IbcEventType::OpenConfirmChannel => CHANNEL_OPEN_CONFIRM_EVENT,
 			IbcEventType::CloseInitChannel => CHANNEL_CLOSE_INIT_EVENT,
 			IbcEventType::CloseConfirmChannel => CHANNEL_CLOSE_CONFIRM_EVENT,
+			IbcEventType::ChannelUpgradeInit => CHANNEL_UPGRADE_INIT_EVENT,
+			IbcEventType::ChannelUpgradeTry => CHANNEL_UPGRADE_TRY_EVENT,
+			IbcEventType::ChannelUpgradeAck => CHANNEL_UPGRADE_ACK_EVENT,
+			IbcEventType::ChannelUpgradeConfirm => CHANNEL_UPGRADE_CONFIRM_EVENT,
+			IbcEventType::ChannelUpgradeCancel => CHANNEL_UPGRADE_CANCEL_EVENT,
 			IbcEventType::SendPacket => SEND_PACKET_EVENT,
 			IbcEventType::ReceivePacket => RECEIVE_PACKET_EVENT,
 			IbcEventType::WriteAck => WRITE_ACK_EVENT,
 			IbcEventType::AckPacket => ACK_PACKET_EVENT,
 			IbcEventType::Timeout => TIMEOUT_EVENT,
 			IbcEventType::TimeoutOnClose => TIMEOUT_ON_CLOSE_EVENT,
+			IbcEventType::AcknowledgementsPruned => ACKNOWLEDGEMENTS_PRUNED_EVENT,
 			IbcEventType::AppModule => APP_MODULE_EVENT,
 			IbcEventType::Empty => EMPTY_EVENT,
 			IbcEventType::ChainError => CHAIN_ERROR_EVENT,
@@ -206,22 +230,30 @@ impl FromStr for IbcEventType {
 			UPDATE_CLIENT_EVENT => Ok(IbcEventType::UpdateClient),
 			UPGRADE_CLIENT_EVENT => Ok(IbcEventType::UpgradeClient),
 			CLIENT_MISBEHAVIOUR_EVENT => Ok(IbcEventType::ClientMisbehaviour),
+			CLIENT_RECOVERED_EVENT => Ok(IbcEventType::ClientRecovered),
 			CONNECTION_INIT_EVENT => Ok(IbcEventType::OpenInitConnection),
 			CONNECTION_TRY_EVENT => Ok(IbcEventType::OpenTryConnection),
 			CONNECTION_ACK_EVENT => Ok(IbcEventType::OpenAckConnection),
 			CONNECTION_CONFIRM_EVENT => Ok(IbcEventType::OpenConfirmConnection),
+			CONNECTION_CANCELLED_EVENT => Ok(IbcEventType::ConnectionCancelled),
 			CHANNEL_OPEN_INIT_EVENT => Ok(IbcEventType::OpenInitChannel),
 			CHANNEL_OPEN_TRY_EVENT => Ok(IbcEventType::OpenTryChannel),
 			CHANNEL_OPEN_ACK_EVENT => Ok(IbcEventType::OpenAckChannel),
 			CHANNEL_OPEN_CONFIRM_EVENT => Ok(IbcEventType::OpenConfirmChannel),
 			CHANNEL_CLOSE_INIT_EVENT => Ok(IbcEventType::CloseInitChannel),
 			CHANNEL_CLOSE_CONFIRM_EVENT => Ok(IbcEventType::CloseConfirmChannel),
+			CHANNEL_UPGRADE_INIT_EVENT => Ok(IbcEventType::ChannelUpgradeInit),
+			CHANNEL_UPGRADE_TRY_EVENT => Ok(IbcEventType::ChannelUpgradeTry),
+			CHANNEL_UPGRADE_ACK_EVENT => Ok(IbcEventType::ChannelUpgradeAck),
+			CHANNEL_UPGRADE_CONFIRM_EVENT => Ok(IbcEventType::ChannelUpgradeConfirm),
+			CHANNEL_UPGRADE_CANCEL_EVENT => Ok(IbcEventType::ChannelUpgradeCancel),
 			SEND_PACKET_EVENT => Ok(IbcEventType::SendPacket),
 			RECEIVE_PACKET_EVENT => Ok(IbcEventType::ReceivePacket),
 			WRITE_ACK_EVENT => Ok(IbcEventType::WriteAck),
 			ACK_PACKET_EVENT => Ok(IbcEventType::AckPacket),
 			TIMEOUT_EVENT => Ok(IbcEventType::Timeout),
 			TIMEOUT_ON_CLOSE_EVENT => Ok(IbcEventType::TimeoutOnClose),
+			ACKNOWLEDGEMENTS_PRUNED_EVENT => Ok(IbcEventType::AcknowledgementsPruned),
 			EMPTY_EVENT => Ok(IbcEventType::Empty),
 			CHAIN_ERROR_EVENT => Ok(IbcEventType::ChainError),
 			// from_str() for `APP_MODULE_EVENT` MUST fail because a `ModuleEvent`'s type isn't
@@ -240,11 +272,13 @@ pub enum IbcEvent {
 	UpdateClient(ClientEvents::UpdateClient),
 	UpgradeClient(ClientEvents::UpgradeClient),
 	ClientMisbehaviour(ClientEvents::ClientMisbehaviour),
+	ClientRecovered(ClientEvents::ClientRecovered),
 
 	OpenInitConnection(ConnectionEvents::OpenInit),
 	OpenTryConnection(ConnectionEvents::OpenTry),
 	OpenAckConnection(ConnectionEvents::OpenAck),
 	OpenConfirmConnection(ConnectionEvents::OpenConfirm),
+	ConnectionCancelled(ConnectionEvents::Cancelled),
 
 	OpenInitChannel(ChannelEvents::OpenInit),
 	OpenTryChannel(ChannelEvents::OpenTry),
@@ -252,6 +286,11 @@ pub enum IbcEvent {
 	OpenConfirmChannel(ChannelEvents::OpenConfirm),
 	CloseInitChannel(ChannelEvents::CloseInit),
 	CloseConfirmChannel(ChannelEvents::CloseConfirm),
+	ChannelUpgradeInit(ChannelEvents::UpgradeInit),
+	ChannelUpgradeTry(ChannelEvents::UpgradeTry),
+	ChannelUpgradeAck(ChannelEvents::UpgradeAck),
+	ChannelUpgradeConfirm(ChannelEvents::UpgradeConfirm),
+	ChannelUpgradeCancel(ChannelEvents::UpgradeCancel),
 
 	SendPacket(ChannelEvents::SendPacket),
 	ReceivePacket(ChannelEvents::ReceivePacket),
@@ -259,6 +298,7 @@ pub enum IbcEvent {
 	AcknowledgePacket(ChannelEvents::AcknowledgePacket),
 	TimeoutPacket(ChannelEvents::TimeoutPacket),
 	TimeoutOnClosePacket(ChannelEvents::TimeoutOnClosePacket),
+	AcknowledgementsPruned(ChannelEvents::AcknowledgementsPruned),
 
 	AppModule(ModuleEvent),
 
@@ -293,11 +333,13 @@ impl fmt::Display for IbcEvent {
 			IbcEvent::UpdateClient(ev) => write!(f, "UpdateClientEv({})", ev),
 			IbcEvent::UpgradeClient(ev) => write!(f, "UpgradeClientEv({:?})", ev),
 			IbcEvent::ClientMisbehaviour(ev) => write!(f, "ClientMisbehaviourEv({:?})", ev),
+			IbcEvent::ClientRecovered(ev) => write!(f, "ClientRecoveredEv({})", ev),
 
 			IbcEvent::OpenInitConnection(ev) => write!(f, "OpenInitConnectionEv({:?})", ev),
 			IbcEvent::OpenTryConnection(ev) => write!(f, "OpenTryConnectionEv({:?})", ev),
 			IbcEvent::OpenAckConnection(ev) => write!(f, "OpenAckConnectionEv({:?})", ev),
 			IbcEvent::OpenConfirmConnection(ev) => write!(f, "OpenConfirmConnectionEv({:?})", ev),
+			IbcEvent::ConnectionCancelled(ev) => write!(f, "ConnectionCancelledEv({:?})", ev),
 
 			IbcEvent::OpenInitChannel(ev) => write!(f, "OpenInitChannelEv({:?})", ev),
 			IbcEvent::OpenTryChannel(ev) => write!(f, "OpenTryChannelEv({:?})", ev),
@@ -305,6 +347,11 @@ impl fmt::Display for IbcEvent {
 			IbcEvent::OpenConfirmChannel(ev) => write!(f, "OpenConfirmChannelEv({:?})", ev),
 			IbcEvent::CloseInitChannel(ev) => write!(f, "CloseInitChannelEv({})", ev),
 			IbcEvent::CloseConfirmChannel(ev) => write!(f, "CloseConfirmChannelEv({:?})", ev),
+			IbcEvent::ChannelUpgradeInit(ev) => write!(f, "ChannelUpgradeInitEv({:?})", ev),
+			IbcEvent::ChannelUpgradeTry(ev) => write!(f, "ChannelUpgradeTryEv({:?})", ev),
+			IbcEvent::ChannelUpgradeAck(ev) => write!(f, "ChannelUpgradeAckEv({:?})", ev),
+			IbcEvent::ChannelUpgradeConfirm(ev) => write!(f, "ChannelUpgradeConfirmEv({:?})", ev),
+			IbcEvent::ChannelUpgradeCancel(ev) => write!(f, "ChannelUpgradeCancelEv({:?})", ev),
 
 			IbcEvent::SendPacket(ev) => write!(f, "SendPacketEv({})", ev),
 			IbcEvent::ReceivePacket(ev) => write!(f, "ReceivePacketEv({})", ev),
@@ -312,6 +359,7 @@ impl fmt::Display for IbcEvent {
 			IbcEvent::AcknowledgePacket(ev) => write!(f, "AcknowledgePacketEv({})", ev),
 			IbcEvent::TimeoutPacket(ev) => write!(f, "TimeoutPacketEv({})", ev),
 			IbcEvent::TimeoutOnClosePacket(ev) => write!(f, "TimeoutOnClosePacketEv({})", ev),
+			IbcEvent::AcknowledgementsPruned(ev) => write!(f, "AcknowledgementsPrunedEv({})", ev),
 
 			IbcEvent::AppModule(ev) => write!(f, "AppModuleEv({:?})", ev),
 
@@ -336,22 +384,30 @@ impl IbcEvent {
 			IbcEvent::UpdateClient(ev) => ev.height(),
 			IbcEvent::UpgradeClient(ev) => ev.height(),
 			IbcEvent::ClientMisbehaviour(ev) => ev.height(),
+			IbcEvent::ClientRecovered(ev) => ev.height(),
 			IbcEvent::OpenInitConnection(ev) => ev.height(),
 			IbcEvent::OpenTryConnection(ev) => ev.height(),
 			IbcEvent::OpenAckConnection(ev) => ev.height(),
 			IbcEvent::OpenConfirmConnection(ev) => ev.height(),
+			IbcEvent::ConnectionCancelled(ev) => ev.height(),
 			IbcEvent::OpenInitChannel(ev) => ev.height(),
 			IbcEvent::OpenTryChannel(ev) => ev.height(),
 			IbcEvent::OpenAckChannel(ev) => ev.height(),
 			IbcEvent::OpenConfirmChannel(ev) => ev.height(),
 			IbcEvent::CloseInitChannel(ev) => ev.height(),
 			IbcEvent::CloseConfirmChannel(ev) => ev.height(),
+			IbcEvent::ChannelUpgradeInit(ev) => ev.height(),
+			IbcEvent::ChannelUpgradeTry(ev) => ev.height(),
+			IbcEvent::ChannelUpgradeAck(ev) => ev.height(),
+			IbcEvent::ChannelUpgradeConfirm(ev) => ev.height(),
+			IbcEvent::ChannelUpgradeCancel(ev) => ev.height(),
 			IbcEvent::SendPacket(ev) => ev.height(),
 			IbcEvent::ReceivePacket(ev) => ev.height(),
 			IbcEvent::WriteAcknowledgement(ev) => ev.height(),
 			IbcEvent::AcknowledgePacket(ev) => ev.height(),
 			IbcEvent::TimeoutPacket(ev) => ev.height(),
 			IbcEvent::TimeoutOnClosePacket(ev) => ev.height(),
+			IbcEvent::AcknowledgementsPruned(ev) => ev.height(),
 			_ => unimplemented!(),
 		}
 	}
@@ -363,21 +419,29 @@ impl IbcEvent {
 			IbcEvent::UpdateClient(ev) => ev.set_height(height),
 			IbcEvent::UpgradeClient(ev) => ev.set_height(height),
 			IbcEvent::ClientMisbehaviour(ev) => ev.set_height(height),
+			IbcEvent::ClientRecovered(ev) => ev.set_height(height),
 			IbcEvent::OpenInitConnection(ev) => ev.set_height(height),
 			IbcEvent::OpenTryConnection(ev) => ev.set_height(height),
 			IbcEvent::OpenAckConnection(ev) => ev.set_height(height),
 			IbcEvent::OpenConfirmConnection(ev) => ev.set_height(height),
+			IbcEvent::ConnectionCancelled(ev) => ev.set_height(height),
 			IbcEvent::OpenInitChannel(ev) => ev.set_height(height),
 			IbcEvent::OpenTryChannel(ev) => ev.set_height(height),
 			IbcEvent::OpenAckChannel(ev) => ev.set_height(height),
 			IbcEvent::OpenConfirmChannel(ev) => ev.set_height(height),
 			IbcEvent::CloseInitChannel(ev) => ev.set_height(height),
 			IbcEvent::CloseConfirmChannel(ev) => ev.set_height(height),
+			IbcEvent::ChannelUpgradeInit(ev) => ev.set_height(height),
+			IbcEvent::ChannelUpgradeTry(ev) => ev.set_height(height),
+			IbcEvent::ChannelUpgradeAck(ev) => ev.set_height(height),
+			IbcEvent::ChannelUpgradeConfirm(ev) => ev.set_height(height),
+			IbcEvent::ChannelUpgradeCancel(ev) => ev.set_height(height),
 			IbcEvent::SendPacket(ev) => ev.set_height(height),
 			IbcEvent::ReceivePacket(ev) => ev.set_height(height),
 			IbcEvent::WriteAcknowledgement(ev) => ev.set_height(height),
 			IbcEvent::AcknowledgePacket(ev) => ev.set_height(height),
 			IbcEvent::TimeoutPacket(ev) => ev.set_height(height),
+			IbcEvent::AcknowledgementsPruned(ev) => ev.set_height(height),
 			_ => unimplemented!(),
 		}
 	}
@@ -388,23 +452,31 @@ impl IbcEvent {
 			IbcEvent::CreateClient(_) => IbcEventType::CreateClient,
 			IbcEvent::UpdateClient(_) => IbcEventType::UpdateClient,
 			IbcEvent::ClientMisbehaviour(_) => IbcEventType::ClientMisbehaviour,
+			IbcEvent::ClientRecovered(_) => IbcEventType::ClientRecovered,
 			IbcEvent::UpgradeClient(_) => IbcEventType::UpgradeClient,
 			IbcEvent::OpenInitConnection(_) => IbcEventType::OpenInitConnection,
 			IbcEvent::OpenTryConnection(_) => IbcEventType::OpenTryConnection,
 			IbcEvent::OpenAckConnection(_) => IbcEventType::OpenAckConnection,
 			IbcEvent::OpenConfirmConnection(_) => IbcEventType::OpenConfirmConnection,
+			IbcEvent::ConnectionCancelled(_) => IbcEventType::ConnectionCancelled,
 			IbcEvent::OpenInitChannel(_) => IbcEventType::OpenInitChannel,
 			IbcEvent::OpenTryChannel(_) => IbcEventType::OpenTryChannel,
 			IbcEvent::OpenAckChannel(_) => IbcEventType::OpenAckChannel,
 			IbcEvent::OpenConfirmChannel(_) => IbcEventType::OpenConfirmChannel,
 			IbcEvent::CloseInitChannel(_) => IbcEventType::CloseInitChannel,
 			IbcEvent::CloseConfirmChannel(_) => IbcEventType::CloseConfirmChannel,
+			IbcEvent::ChannelUpgradeInit(_) => IbcEventType::ChannelUpgradeInit,
+			IbcEvent::ChannelUpgradeTry(_) => IbcEventType::ChannelUpgradeTry,
+			IbcEvent::ChannelUpgradeAck(_) => IbcEventType::ChannelUpgradeAck,
+			IbcEvent::ChannelUpgradeConfirm(_) => IbcEventType::ChannelUpgradeConfirm,
+			IbcEvent::ChannelUpgradeCancel(_) => IbcEventType::ChannelUpgradeCancel,
 			IbcEvent::SendPacket(_) => IbcEventType::SendPacket,
 			IbcEvent::ReceivePacket(_) => IbcEventType::ReceivePacket,
 			IbcEvent::WriteAcknowledgement(_) => IbcEventType::WriteAck,
 			IbcEvent::AcknowledgePacket(_) => IbcEventType::AckPacket,
 			IbcEvent::TimeoutPacket(_) => IbcEventType::Timeout,
 			IbcEvent::TimeoutOnClosePacket(_) => IbcEventType::TimeoutOnClose,
+			IbcEvent::AcknowledgementsPruned(_) => IbcEventType::AcknowledgementsPruned,
 			IbcEvent::AppModule(_) => IbcEventType::AppModule,
 			IbcEvent::Empty(_) => IbcEventType::Empty,
 			IbcEvent::ChainError(_) => IbcEventType::ChainError,
@@ -417,6 +489,11 @@ impl IbcEvent {
 			IbcEvent::OpenTryChannel(ev) => Some(ev.into()),
 			IbcEvent::OpenAckChannel(ev) => Some(ev.into()),
 			IbcEvent::OpenConfirmChannel(ev) => Some(ev.into()),
+			IbcEvent::ChannelUpgradeInit(ev) => Some(ev.into()),
+			IbcEvent::ChannelUpgradeTry(ev) => Some(ev.into()),
+			IbcEvent::ChannelUpgradeAck(ev) => Some(ev.into()),
+			IbcEvent::ChannelUpgradeConfirm(ev) => Some(ev.into()),
+			IbcEvent::ChannelUpgradeCancel(ev) => Some(ev.into()),
 			_ => None,
 		}
 	}
@@ -427,6 +504,7 @@ impl IbcEvent {
 			IbcEvent::OpenTryConnection(ev) => Some(ev.attributes()),
 			IbcEvent::OpenAckConnection(ev) => Some(ev.attributes()),
 			IbcEvent::OpenConfirmConnection(ev) => Some(ev.attributes()),
+			IbcEvent::ConnectionCancelled(ev) => Some(ev.attributes()),
 			_ => None,
 		}
 	}
@@ -443,6 +521,12 @@ impl IbcEvent {
 		}
 	}
 
+	/// Key that orders the events of a batch: the sequence of the packet for packet events, none
+	/// for the others.
+	pub fn ordering_key(&self) -> Option<Sequence> {
+		self.packet().map(|packet| packet.sequence)
+	}
+
 	pub fn ack(&self) -> Option<&[u8]> {
 		match self {
 			IbcEvent::WriteAcknowledgement(ev) => Some(&ev.ack),
@@ -542,3 +626,61 @@ pub fn extract_attribute(object: &RawObject<'_>, key: &str) -> Result<String, Er
 pub fn maybe_extract_attribute(object: &RawObject<'_>, key: &str) -> Option<String> {
 	object.events.get(key).map(|tags| tags[object.idx].clone())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::ics04_channel::events::{ReceivePacket, SendPacket, WriteAcknowledgement};
+
+	fn packet(sequence: u64) -> Packet {
+		Packet { sequence: sequence.into(), ..Default::default() }
+	}
+
+	fn module_event(value: &str) -> IbcEvent {
+		IbcEvent::AppModule(ModuleEvent {
+			kind: "transfer".to_string(),
+			module_name: ModuleId::new("transfer".into()).unwrap(),
+			attributes: vec![("amount", value).into()],
+		})
+	}
+
+	#[test]
+	fn packet_events_are_equal_only_when_all_their_fields_are() {
+		let recv = |sequence, height| {
+			IbcEvent::ReceivePacket(ReceivePacket { height, packet: packet(sequence) })
+		};
+		let height = Height::new(0, 10);
+		assert_eq!(recv(1, height), recv(1, height));
+		assert_ne!(recv(1, height), recv(2, height));
+		assert_ne!(recv(1, height), recv(1, Height::new(0, 11)));
+
+		let ack = |ack: &[u8]| {
+			IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
+				height,
+				packet: packet(1),
+				ack: ack.to_vec(),
+			})
+		};
+		assert_eq!(ack(&[1]), ack(&[1]));
+		assert_ne!(ack(&[1]), ack(&[0]));
+
+		// Events of different kinds are never equal, even for the same packet.
+		let send =
+			IbcEvent::SendPacket(SendPacket { height, packet: packet(1), channel_ordering: None });
+		assert_ne!(send, recv(1, height));
+	}
+
+	#[test]
+	fn module_events_are_equal_only_with_the_same_attributes() {
+		assert_eq!(module_event("10"), module_event("10"));
+		assert_ne!(module_event("10"), module_event("11"));
+	}
+
+	#[test]
+	fn only_packet_events_have_an_ordering_key() {
+		let height = Height::new(0, 10);
+		let recv = IbcEvent::ReceivePacket(ReceivePacket { height, packet: packet(7) });
+		assert_eq!(recv.ordering_key(), Some(Sequence::from(7)));
+		assert_eq!(module_event("10").ordering_key(), None);
+	}
+}
@@ -13,14 +13,55 @@
 // limitations under the License.
 
 use crate::{events::IbcEvent, prelude::*};
-use core::marker::PhantomData;
+use core::{fmt, marker::PhantomData};
 
 pub type HandlerResult<T, E> = Result<HandlerOutput<T>, E>;
 
+/// A log entry produced while processing a message, along with key-value attributes (such as
+/// connection, channel or packet identifiers) that hosts can surface as diagnostics.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Log {
+	pub message: String,
+	pub attributes: Vec<(String, String)>,
+}
+
+impl Log {
+	pub fn new(message: impl Into<String>) -> Self {
+		Self { message: message.into(), attributes: Vec::new() }
+	}
+
+	pub fn with_attribute(mut self, key: &str, value: impl ToString) -> Self {
+		self.attributes.push((key.to_string(), value.to_string()));
+		self
+	}
+}
+
+impl From<String> for Log {
+	fn from(message: String) -> Self {
+		Self::new(message)
+	}
+}
+
+impl From<&str> for Log {
+	fn from(message: &str) -> Self {
+		Self::new(message)
+	}
+}
+
+impl fmt::Display for Log {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.message)?;
+		for (key, value) in self.attributes.iter() {
+			write!(f, " {}={}", key, value)?;
+		}
+		Ok(())
+	}
+}
+
 #[derive(Clone, Debug)]
 pub struct HandlerOutput<T, Event = IbcEvent> {
 	pub result: T,
-	pub log: Vec<String>,
+	pub log: Vec<Log>,
 	pub events: Vec<Event>,
 }
 
@@ -28,11 +69,16 @@ impl<T, E> HandlerOutput<T, E> {
 	pub fn builder() -> HandlerOutputBuilder<T, E> {
 		HandlerOutputBuilder::new()
 	}
+
+	/// Replaces the result with `f` applied to it, keeping the logs and events.
+	pub fn map<R>(self, f: impl FnOnce(T) -> R) -> HandlerOutput<R, E> {
+		HandlerOutput { result: f(self.result), log: self.log, events: self.events }
+	}
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct HandlerOutputBuilder<T, E = IbcEvent> {
-	log: Vec<String>,
+	log: Vec<Log>,
 	events: Vec<E>,
 	marker: PhantomData<T>,
 }
@@ -42,13 +88,24 @@ impl<T, E> HandlerOutputBuilder<T, E> {
 		Self { log: Vec::new(), events: Vec::new(), marker: PhantomData }
 	}
 
-	pub fn with_log(mut self, log: impl Into<Vec<String>>) -> Self {
+	pub fn with_log(mut self, log: impl Into<Vec<Log>>) -> Self {
 		self.log.append(&mut log.into());
 		self
 	}
 
 	pub fn log(&mut self, log: impl Into<String>) {
-		self.log.push(log.into());
+		self.log.push(Log::new(log));
+	}
+
+	/// Attaches a key-value attribute to the most recent log entry. If nothing has been logged
+	/// yet, a new entry with an empty message is started.
+	pub fn log_kv(&mut self, key: &str, value: impl ToString) {
+		if self.log.is_empty() {
+			self.log.push(Log::default());
+		}
+		if let Some(entry) = self.log.last_mut() {
+			entry.attributes.push((key.to_string(), value.to_string()));
+		}
 	}
 
 	pub fn with_events(mut self, mut events: Vec<E>) -> Self {
@@ -63,16 +120,75 @@ impl<T, E> HandlerOutputBuilder<T, E> {
 	pub fn with_result(self, result: T) -> HandlerOutput<T, E> {
 		HandlerOutput { result, log: self.log, events: self.events }
 	}
+}
 
+impl<T, E: PartialEq> HandlerOutputBuilder<T, E> {
+	/// Appends the logs and events of `other` after the ones already collected. Events equal to
+	/// one already collected are dropped, so the events emitted first, usually those of the core
+	/// handler, are the ones that are kept.
 	pub fn merge<Event: Into<E>>(&mut self, other: HandlerOutputBuilder<(), Event>) {
 		let HandlerOutputBuilder { mut log, events, .. } = other;
 		self.log.append(&mut log);
-		self.events.append(&mut events.into_iter().map(Into::into).collect());
+		self.append_events(events);
 	}
 
-	pub fn merge_output<Event: Into<E>>(&mut self, other: HandlerOutput<(), Event>) {
+	/// Same as [`Self::merge`], for the output of a handler that already ran to completion. Its
+	/// result is dropped.
+	pub fn merge_output<R, Event: Into<E>>(&mut self, other: HandlerOutput<R, Event>) {
 		let HandlerOutput { mut log, events, .. } = other;
 		self.log.append(&mut log);
-		self.events.append(&mut events.into_iter().map(Into::into).collect());
+		self.append_events(events);
+	}
+
+	fn append_events<Event: Into<E>>(&mut self, events: Vec<Event>) {
+		for event in events.into_iter().map(Into::into) {
+			if !self.events.contains(&event) {
+				self.events.push(event);
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{core::ics02_client::events::NewBlock, Height};
+
+	#[test]
+	fn log_kv_attaches_to_latest_entry() {
+		let mut output = HandlerOutput::<(), IbcEvent>::builder();
+		output.log_kv("connection_id", "connection-0");
+		output.log("success: connection verification passed");
+		output.log_kv("channel_id", "channel-0");
+		output.log_kv("packet_sequence", 1u64);
+
+		let HandlerOutput { log, .. } = output.with_result(());
+		assert_eq!(
+			log,
+			vec![
+				Log::new("").with_attribute("connection_id", "connection-0"),
+				Log::new("success: connection verification passed")
+					.with_attribute("channel_id", "channel-0")
+					.with_attribute("packet_sequence", 1u64),
+			]
+		);
+		assert_eq!(
+			log[1].to_string(),
+			"success: connection verification passed channel_id=channel-0 packet_sequence=1"
+		);
+	}
+
+	#[test]
+	fn merge_keeps_earlier_events_and_drops_duplicates() {
+		let event = |height| IbcEvent::NewBlock(NewBlock::new(Height::new(0, height)));
+
+		let mut output = HandlerOutput::<(), IbcEvent>::builder().with_events(vec![event(1)]);
+		output.merge(HandlerOutputBuilder::<()>::new().with_events(vec![event(2), event(1)]));
+		output.merge_output(
+			HandlerOutputBuilder::<()>::new().with_events(vec![event(2), event(3)]).with_result(()),
+		);
+
+		let HandlerOutput { events, .. } = output.with_result(());
+		assert_eq!(events, vec![event(1), event(2), event(3)]);
 	}
 }
@@ -0,0 +1,431 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fixtures of mock contexts whose clients, connections and channels agree with those of a
+//! counterparty context.
+
+use crate::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState,
+			client_state::ClientState,
+			context::{ClientKeeper, ClientReader},
+		},
+		ics03_connection::{
+			connection::{ConnectionEnd, Counterparty as ConnectionCounterparty, State},
+			context::{ConnectionKeeper, ConnectionReader},
+			error::Error,
+			handler::{dispatch, verify::ConsensusProofwithHostConsensusStateProof},
+			msgs::{
+				conn_open_ack::MsgConnectionOpenAck, conn_open_confirm::MsgConnectionOpenConfirm,
+				conn_open_init::MsgConnectionOpenInit, conn_open_try::MsgConnectionOpenTry,
+				ConnectionMsg,
+			},
+			version::get_compatible_versions,
+		},
+		ics04_channel::{
+			channel::{
+				ChannelEnd, Counterparty as ChannelCounterparty, Order, State as ChannelState,
+			},
+			commitment::compute_packet_commitment,
+			context::{ChannelKeeper, ChannelReader},
+			packet::Packet,
+			Version,
+		},
+		ics23_commitment::commitment::CommitmentProofBytes,
+		ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+	},
+	mock::{
+		client_state::MockClientState,
+		context::{MockClientTypes, MockContext},
+		header::MockHeader,
+		host::MockHostType,
+	},
+	prelude::*,
+	proofs::{ConsensusProof, Proofs},
+	test_utils::{get_dummy_account_id, get_dummy_proof},
+	timestamp::ZERO_DURATION,
+	Height,
+};
+
+/// Builds a [`MockContext`] along with a paired counterparty context. Every connection and channel
+/// opened on the context has its mirror on the counterparty, each end naming the identifiers and
+/// commitment prefix of the other as its counterparty. The counterparty ends get the next
+/// identifiers of the counterparty's counters.
+pub struct MockContextBuilder {
+	ctx: MockContext<MockClientTypes>,
+	counterparty: MockContext<MockClientTypes>,
+	/// The connection opened last, as its identifiers on the context and on the counterparty.
+	connection: Option<(ConnectionId, ConnectionId)>,
+	/// The channel opened last, as its port and its identifiers on the context and on the
+	/// counterparty.
+	channel: Option<(PortId, ChannelId, ChannelId)>,
+}
+
+impl MockContextBuilder {
+	/// Starts from `ctx`, paired with a default counterparty context.
+	pub fn new(ctx: MockContext<MockClientTypes>) -> Self {
+		let counterparty = MockContext::new(
+			ChainId::new("mockcounterparty".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			Height::new(0, 5),
+		);
+		Self { ctx, counterparty, connection: None, channel: None }
+	}
+
+	/// Pairs the context with `counterparty` instead of the default one. Must be called before
+	/// any connection is opened.
+	pub fn with_counterparty(self, counterparty: MockContext<MockClientTypes>) -> Self {
+		assert!(self.connection.is_none(), "The counterparty must be set before any connection");
+		Self { counterparty, ..self }
+	}
+
+	/// Opens the connection `conn_id` over the new client `client_id`, which tracks the
+	/// counterparty at its latest height. The counterparty gets a client tracking the context and
+	/// the mirror connection.
+	pub fn with_open_connection(mut self, client_id: ClientId, conn_id: ConnectionId) -> Self {
+		let counterparty_client_id = next_client_id(&mut self.counterparty);
+		let counterparty_conn_id =
+			ConnectionId::new(self.counterparty.connection_counter().unwrap());
+		self.counterparty.increase_connection_counter();
+
+		track(&mut self.ctx, &client_id, &self.counterparty);
+		track(&mut self.counterparty, &counterparty_client_id, &self.ctx);
+
+		let connection_end = |client_id: &ClientId, counterparty: ConnectionCounterparty| {
+			ConnectionEnd::new(
+				State::Open,
+				client_id.clone(),
+				counterparty,
+				get_compatible_versions(),
+				ZERO_DURATION,
+			)
+		};
+		let local_end = connection_end(
+			&client_id,
+			ConnectionCounterparty::new(
+				counterparty_client_id.clone(),
+				Some(counterparty_conn_id.clone()),
+				self.counterparty.commitment_prefix(),
+			),
+		);
+		let counterparty_end = connection_end(
+			&counterparty_client_id,
+			ConnectionCounterparty::new(
+				client_id.clone(),
+				Some(conn_id.clone()),
+				self.ctx.commitment_prefix(),
+			),
+		);
+		store_connection(&mut self.ctx, &conn_id, &local_end);
+		store_connection(&mut self.counterparty, &counterparty_conn_id, &counterparty_end);
+
+		self.connection = Some((conn_id, counterparty_conn_id));
+		self
+	}
+
+	/// Opens the channel `chan_id` on `port_id` over the connection opened last, and its mirror
+	/// on the same port of the counterparty. Both ends send, receive and acknowledge from
+	/// sequence 1.
+	pub fn with_open_channel(
+		mut self,
+		port_id: PortId,
+		chan_id: ChannelId,
+		order: Order,
+		version: Version,
+	) -> Self {
+		let (conn_id, counterparty_conn_id) = self
+			.connection
+			.clone()
+			.expect("A channel needs a connection, see `with_open_connection`");
+		let counterparty_chan_id = ChannelId::new(self.counterparty.channel_counter().unwrap());
+		self.counterparty.increase_channel_counter();
+
+		let channel_end = |counterparty_chan_id: ChannelId, conn_id: &ConnectionId| {
+			ChannelEnd::new(
+				ChannelState::Open,
+				order,
+				ChannelCounterparty::new(port_id.clone(), Some(counterparty_chan_id)),
+				vec![conn_id.clone()],
+				version.clone(),
+			)
+		};
+		let local_end = channel_end(counterparty_chan_id, &conn_id);
+		let counterparty_end = channel_end(chan_id, &counterparty_conn_id);
+		store_channel(&mut self.ctx, &conn_id, (port_id.clone(), chan_id), &local_end);
+		store_channel(
+			&mut self.counterparty,
+			&counterparty_conn_id,
+			(port_id.clone(), counterparty_chan_id),
+			&counterparty_end,
+		);
+
+		self.channel = Some((port_id, chan_id, counterparty_chan_id));
+		self
+	}
+
+	/// A packet with the given sequence sent from the context to the counterparty on the channel
+	/// opened last, with no data and no timeout.
+	pub fn packet(&self, sequence: u64) -> Packet {
+		let (port_id, chan_id, counterparty_chan_id) = self
+			.channel
+			.clone()
+			.expect("A packet needs a channel, see `with_open_channel`");
+		Packet {
+			sequence: sequence.into(),
+			source_port: port_id.clone(),
+			source_channel: chan_id,
+			destination_port: port_id,
+			destination_channel: counterparty_chan_id,
+			..Default::default()
+		}
+	}
+
+	/// Stores the commitment of `packet` on the context, as if it had been sent from there.
+	pub fn with_packet_commitment(mut self, packet: &Packet) -> Self {
+		let commitment = compute_packet_commitment(
+			&packet.data,
+			&packet.timeout_height,
+			&packet.timeout_timestamp,
+		);
+		self.ctx
+			.store_packet_commitment(
+				(packet.source_port.clone(), packet.source_channel, packet.sequence),
+				commitment,
+			)
+			.unwrap();
+		self
+	}
+
+	/// Returns the context.
+	pub fn build(self) -> MockContext<MockClientTypes> {
+		self.ctx
+	}
+
+	/// Returns the context and its counterparty.
+	pub fn build_pair(self) -> (MockContext<MockClientTypes>, MockContext<MockClientTypes>) {
+		(self.ctx, self.counterparty)
+	}
+}
+
+/// Opens a connection between `a` and `b` by dispatching the messages of the connection handshake
+/// to the handlers of each side in turn, after giving each side a new client tracking the other.
+/// Returns the identifiers of the connection on `a` and on `b`.
+pub fn connect(
+	a: &mut MockContext<MockClientTypes>,
+	b: &mut MockContext<MockClientTypes>,
+) -> Result<(ConnectionId, ConnectionId), Error> {
+	let a_client_id = next_client_id(a);
+	let b_client_id = next_client_id(b);
+	track(a, &a_client_id, b);
+	track(b, &b_client_id, a);
+
+	let msg = MsgConnectionOpenInit {
+		client_id: a_client_id.clone(),
+		counterparty: ConnectionCounterparty::new(b_client_id.clone(), None, b.commitment_prefix()),
+		version: None,
+		delay_period: ZERO_DURATION,
+		signer: get_dummy_account_id(),
+	};
+	let a_conn_id = dispatch_and_store(a, ConnectionMsg::ConnectionOpenInit(msg))?;
+
+	let msg = MsgConnectionOpenTry {
+		client_id: b_client_id.clone(),
+		client_state: Some(a.client_state(&a_client_id).map_err(Error::ics02_client)?),
+		counterparty: ConnectionCounterparty::new(
+			a_client_id.clone(),
+			Some(a_conn_id.clone()),
+			a.commitment_prefix(),
+		),
+		counterparty_versions: a.connection_end(&a_conn_id)?.versions().to_vec(),
+		proofs: handshake_proofs(b, &b_client_id, Some((a, &a_client_id)))?,
+		delay_period: ZERO_DURATION,
+		signer: get_dummy_account_id(),
+	};
+	let b_conn_id = dispatch_and_store(b, ConnectionMsg::ConnectionOpenTry(Box::new(msg)))?;
+
+	let msg = MsgConnectionOpenAck {
+		connection_id: a_conn_id.clone(),
+		counterparty_connection_id: b_conn_id.clone(),
+		client_state: Some(b.client_state(&b_client_id).map_err(Error::ics02_client)?),
+		proofs: handshake_proofs(a, &a_client_id, Some((b, &b_client_id)))?,
+		version: b.connection_end(&b_conn_id)?.versions()[0].clone(),
+		signer: get_dummy_account_id(),
+	};
+	dispatch_and_store(a, ConnectionMsg::ConnectionOpenAck(Box::new(msg)))?;
+
+	let msg = MsgConnectionOpenConfirm {
+		connection_id: b_conn_id.clone(),
+		proofs: handshake_proofs(b, &b_client_id, None)?,
+		signer: get_dummy_account_id(),
+	};
+	dispatch_and_store(b, ConnectionMsg::ConnectionOpenConfirm(msg))?;
+
+	Ok((a_conn_id, b_conn_id))
+}
+
+fn dispatch_and_store(
+	ctx: &mut MockContext<MockClientTypes>,
+	msg: ConnectionMsg<MockContext<MockClientTypes>>,
+) -> Result<ConnectionId, Error> {
+	let output = dispatch(&*ctx, msg)?;
+	let connection_id = output.result.connection_id.clone();
+	ctx.store_connection_result(output.result)?;
+	Ok(connection_id)
+}
+
+/// Proofs for a handshake message received by `receiver`, made at the height its client
+/// `client_id` tracks the sender at. Given the sender and its client tracking `receiver`, the
+/// client and consensus state proofs are included as well.
+fn handshake_proofs(
+	receiver: &MockContext<MockClientTypes>,
+	client_id: &ClientId,
+	sender: Option<(&MockContext<MockClientTypes>, &ClientId)>,
+) -> Result<Proofs, Error> {
+	let height = receiver.client_state(client_id).map_err(Error::ics02_client)?.latest_height();
+	let dummy_proof = || CommitmentProofBytes::try_from(get_dummy_proof()).unwrap();
+	let (client_proof, consensus_proof) = match sender {
+		Some((sender, sender_client_id)) => {
+			let consensus_height = sender
+				.client_state(sender_client_id)
+				.map_err(Error::ics02_client)?
+				.latest_height();
+			// Mock hosts receive the consensus proof bundled with a host consensus state proof.
+			let proof = codec::Encode::encode(&ConsensusProofwithHostConsensusStateProof {
+				host_consensus_state_proof: get_dummy_proof(),
+				consensus_proof: get_dummy_proof(),
+			});
+			let proof = CommitmentProofBytes::try_from(proof).unwrap();
+			let consensus_proof =
+				ConsensusProof::new(proof, consensus_height).map_err(Error::invalid_proof)?;
+			(Some(dummy_proof()), Some(consensus_proof))
+		},
+		None => (None, None),
+	};
+	Proofs::new(dummy_proof(), client_proof, consensus_proof, None, height)
+		.map_err(Error::invalid_proof)
+}
+
+fn next_client_id(ctx: &mut MockContext<MockClientTypes>) -> ClientId {
+	let client_id =
+		ClientId::new(&MockClientState::client_type(), ctx.client_counter().unwrap()).unwrap();
+	ctx.increase_client_counter();
+	client_id
+}
+
+/// Stores on `ctx` the mock client `client_id`, holding the consensus state of the latest block of
+/// `tracked`.
+fn track(
+	ctx: &mut MockContext<MockClientTypes>,
+	client_id: &ClientId,
+	tracked: &MockContext<MockClientTypes>,
+) {
+	let height = tracked.latest_height();
+	let consensus_state = tracked.host_consensus_state(height, None).unwrap();
+	let header = MockHeader { height, timestamp: consensus_state.timestamp() };
+	ctx.store_client_type(client_id.clone(), MockClientState::client_type()).unwrap();
+	ctx.store_client_state(client_id.clone(), MockClientState::new(header.into()).into())
+		.unwrap();
+	ctx.store_consensus_state(client_id.clone(), height, consensus_state).unwrap();
+	let (processed_time, processed_height) = (ctx.host_timestamp(), ctx.host_height());
+	ctx.store_update_time(client_id.clone(), height, processed_time).unwrap();
+	ctx.store_update_height(client_id.clone(), height, processed_height).unwrap();
+}
+
+fn store_connection(
+	ctx: &mut MockContext<MockClientTypes>,
+	conn_id: &ConnectionId,
+	connection_end: &ConnectionEnd,
+) {
+	ctx.store_connection(conn_id.clone(), connection_end).unwrap();
+	ctx.store_connection_to_client(conn_id.clone(), connection_end.client_id()).unwrap();
+}
+
+fn store_channel(
+	ctx: &mut MockContext<MockClientTypes>,
+	conn_id: &ConnectionId,
+	port_channel_id: (PortId, ChannelId),
+	channel_end: &ChannelEnd,
+) {
+	ctx.store_channel(port_channel_id.clone(), channel_end).unwrap();
+	ctx.store_connection_channels(conn_id.clone(), &port_channel_id).unwrap();
+	ctx.store_next_sequence_send(port_channel_id.clone(), 1.into()).unwrap();
+	ctx.store_next_sequence_recv(port_channel_id.clone(), 1.into()).unwrap();
+	ctx.store_next_sequence_ack(port_channel_id, 1.into()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use test_log::test;
+
+	#[test]
+	fn builder_mirrors_ends_on_the_counterparty() {
+		let client_id = ClientId::new(&MockClientState::client_type(), 7).unwrap();
+		let conn_id = ConnectionId::new(3);
+		let port_id = PortId::transfer();
+		let chan_id = ChannelId::new(5);
+		let builder = MockContextBuilder::new(MockContext::default())
+			.with_open_connection(client_id.clone(), conn_id.clone())
+			.with_open_channel(port_id.clone(), chan_id, Order::Unordered, Version::ics20());
+		let packet = builder.packet(1);
+		let (ctx, counterparty) = builder.with_packet_commitment(&packet).build_pair();
+
+		let local_conn = ctx.connection_end(&conn_id).unwrap();
+		let counterparty_conn_id = local_conn.counterparty().connection_id.clone().unwrap();
+		let counterparty_conn = counterparty.connection_end(&counterparty_conn_id).unwrap();
+		assert_eq!(counterparty_conn.counterparty().connection_id, Some(conn_id.clone()));
+		assert_eq!(counterparty_conn.counterparty().client_id(), &client_id);
+		assert_eq!(counterparty_conn.counterparty().prefix(), &ctx.commitment_prefix());
+		assert_eq!(local_conn.counterparty().prefix(), &counterparty.commitment_prefix());
+		assert!(counterparty.client_state(counterparty_conn.client_id()).is_ok());
+		assert_eq!(
+			ctx.client_state(&client_id).unwrap().latest_height(),
+			counterparty.latest_height()
+		);
+
+		let local_chan = ctx.channel_end(&(port_id.clone(), chan_id)).unwrap();
+		let counterparty_chan_id = local_chan.counterparty().channel_id.unwrap();
+		let counterparty_chan =
+			counterparty.channel_end(&(port_id.clone(), counterparty_chan_id)).unwrap();
+		assert_eq!(counterparty_chan.counterparty().channel_id, Some(chan_id));
+		assert_eq!(counterparty_chan.connection_hops(), &vec![counterparty_conn_id]);
+
+		assert_eq!(packet.destination_channel, counterparty_chan_id);
+		assert!(ctx.get_packet_commitment(&(port_id, chan_id, packet.sequence)).is_ok());
+	}
+
+	#[test]
+	fn connect_opens_a_connection_on_both_sides() {
+		let mut a = MockContext::<MockClientTypes>::default();
+		let mut b = MockContext::new(
+			ChainId::new("mockcounterparty".to_string(), 0),
+			MockHostType::Mock,
+			5,
+			Height::new(0, 8),
+		);
+
+		let (a_conn_id, b_conn_id) = connect(&mut a, &mut b).unwrap();
+
+		let a_conn = a.connection_end(&a_conn_id).unwrap();
+		let b_conn = b.connection_end(&b_conn_id).unwrap();
+		assert_eq!(a_conn.state(), &State::Open);
+		assert_eq!(b_conn.state(), &State::Open);
+		assert_eq!(a_conn.counterparty().connection_id, Some(b_conn_id));
+		assert_eq!(b_conn.counterparty().connection_id, Some(a_conn_id));
+		assert_eq!(a_conn.counterparty().client_id(), b_conn.client_id());
+		assert_eq!(b_conn.counterparty().client_id(), a_conn.client_id());
+	}
+}
@@ -241,6 +241,17 @@ impl ClientDef for MockClient {
 		))
 	}
 
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		_old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<Self::ClientState, Error> {
+		Ok(MockClientState { frozen_height: None, ..substitute_client_state })
+	}
+
 	fn verify_client_message<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
@@ -254,9 +265,9 @@ impl ClientDef for MockClient {
 	fn update_state_on_misbehaviour(
 		&self,
 		client_state: Self::ClientState,
-		_client_msg: Self::ClientMessage,
+		client_msg: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
-		Ok(client_state)
+		Ok(MockClientState { frozen_height: Some(client_msg.height()), ..client_state })
 	}
 
 	fn check_for_misbehaviour<Ctx: ReaderContext>(
@@ -264,8 +275,14 @@ impl ClientDef for MockClient {
 		_ctx: &Ctx,
 		_client_id: ClientId,
 		_client_state: Self::ClientState,
-		_client_msg: Self::ClientMessage,
+		client_msg: Self::ClientMessage,
 	) -> Result<bool, Error> {
-		Ok(false)
+		// Two distinct headers at the same height are evidence of misbehaviour.
+		Ok(matches!(
+			client_msg,
+			MockClientMessage::Misbehaviour(misbehaviour)
+				if misbehaviour.header1.height() == misbehaviour.header2.height() &&
+					misbehaviour.header1 != misbehaviour.header2
+		))
 	}
 }
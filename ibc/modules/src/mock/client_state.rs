@@ -48,6 +48,9 @@ use ibc_proto::ibc::mock::{
 
 pub const MOCK_CLIENT_STATE_TYPE_URL: &str = "/ibc.mock.ClientState";
 
+/// Trusting period of every mock client.
+pub const MOCK_TRUSTING_PERIOD: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+
 /// A mock of an IBC client record as it is stored in a mock context.
 /// For testing ICS02 handlers mostly, cf. `MockClientContext`.
 #[derive(Clone, Debug)]
@@ -152,6 +155,10 @@ impl ClientState for MockClientState {
 		self.expired(elapsed)
 	}
 
+	fn trusting_period(&self) -> Duration {
+		MOCK_TRUSTING_PERIOD
+	}
+
 	fn encode_to_vec(&self) -> Vec<u8> {
 		self.encode_vec()
 	}
@@ -183,8 +190,8 @@ impl MockClientState {
 		todo!()
 	}
 
-	pub fn expired(&self, _elapsed: Duration) -> bool {
-		false
+	pub fn expired(&self, elapsed: Duration) -> bool {
+		elapsed > MOCK_TRUSTING_PERIOD
 	}
 }
 
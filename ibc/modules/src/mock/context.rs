@@ -41,6 +41,8 @@ use crate::{
 			connection::ConnectionEnd,
 			context::{ConnectionKeeper, ConnectionReader},
 			error::Error as Ics03Error,
+			handler::HandshakeOrigin,
+			version::{get_compatible_versions, Version},
 		},
 		ics04_channel::{
 			channel::ChannelEnd,
@@ -48,9 +50,10 @@ use crate::{
 			context::{ChannelKeeper, ChannelReader},
 			error::Error as Ics04Error,
 			packet::{Receipt, Sequence},
+			upgrade::UpgradeOrigin,
 		},
 		ics05_port::{
-			context::PortReader,
+			context::{PortKeeper, PortReader},
 			error::{Error as Ics05Error, Error},
 		},
 		ics23_commitment::commitment::CommitmentPrefix,
@@ -74,7 +77,8 @@ use crate::{
 		header::{AnyClientMessage, MockHeader},
 		host::{HostBlock, MockHostBlock},
 	},
-	timestamp::Timestamp,
+	signer::Signer,
+	timestamp::{Timestamp, ZERO_DURATION},
 	Height,
 };
 
@@ -99,12 +103,47 @@ pub struct MockContext<C: HostBlockType = MockClientTypes> {
 	/// Average time duration between blocks
 	pub block_time: Duration,
 
+	/// How far the host clock was moved forward since the latest block, see
+	/// [`MockContext::advance_time`]. Consumed by the next block.
+	pub time_offset: Duration,
+
 	/// An object that stores all IBC related data.
 	pub ibc_store: Arc<Mutex<MockIbcStore<C>>>,
 
 	/// ICS26 router impl
 	pub router: MockRouter,
 
+	/// Connection versions this host is willing to negotiate.
+	pub supported_versions: Vec<Version>,
+
+	/// Whether connection handshakes must carry client and consensus state proofs.
+	pub require_handshake_proofs: bool,
+
+	/// Number of blocks after which anyone may cancel a pending connection handshake.
+	pub handshake_timeout: u64,
+
+	/// Commitment prefixes that counterparties tracked by the given clients must use.
+	pub expected_counterparty_prefixes: BTreeMap<ClientId, CommitmentPrefix>,
+
+	/// Signers allowed to close channels on the given ports. Ports without an entry may be
+	/// closed by anyone.
+	pub channel_close_authorities: BTreeMap<PortId, Signer>,
+
+	/// Signers allowed to propose and cancel upgrades of channels on the given ports. Channels on
+	/// ports without an entry can't be upgraded.
+	pub channel_upgrade_authorities: BTreeMap<PortId, Signer>,
+
+	/// The signer allowed to recover frozen or expired clients, if any.
+	pub client_recovery_authority: Option<Signer>,
+
+	/// Whether processed timeouts are recorded in the store.
+	pub persist_timeouts: bool,
+
+	/// Consensus states that a relayer-supplied host consensus state proof resolves to, in place
+	/// of the matching block in `history`. Lets tests present a counterparty claim that diverges
+	/// from the local history.
+	pub claimed_host_consensus_states: BTreeMap<Height, C::AnyConsensusState>,
+
 	pub _phantom: PhantomData<C>,
 }
 
@@ -150,8 +189,18 @@ impl<C: HostBlockType> Clone for MockContext<C> {
 			max_history_size: self.max_history_size,
 			history: self.history.clone(),
 			block_time: self.block_time,
+			time_offset: self.time_offset,
 			ibc_store,
 			router: self.router.clone(),
+			supported_versions: self.supported_versions.clone(),
+			require_handshake_proofs: self.require_handshake_proofs,
+			handshake_timeout: self.handshake_timeout,
+			expected_counterparty_prefixes: self.expected_counterparty_prefixes.clone(),
+			channel_close_authorities: self.channel_close_authorities.clone(),
+			channel_upgrade_authorities: self.channel_upgrade_authorities.clone(),
+			client_recovery_authority: self.client_recovery_authority.clone(),
+			persist_timeouts: self.persist_timeouts,
+			claimed_host_consensus_states: self.claimed_host_consensus_states.clone(),
 			_phantom: Default::default(),
 		}
 	}
@@ -214,8 +263,18 @@ where
 				})
 				.collect(),
 			block_time,
+			time_offset: ZERO_DURATION,
 			ibc_store: Arc::new(Mutex::new(MockIbcStore::<C>::default())),
 			router: Default::default(),
+			supported_versions: get_compatible_versions(),
+			require_handshake_proofs: true,
+			handshake_timeout: 100,
+			expected_counterparty_prefixes: Default::default(),
+			channel_close_authorities: Default::default(),
+			channel_upgrade_authorities: Default::default(),
+			client_recovery_authority: None,
+			persist_timeouts: false,
+			claimed_host_consensus_states: Default::default(),
 			_phantom: Default::default(),
 		}
 	}
@@ -230,6 +289,66 @@ where
 		self
 	}
 
+	/// Overrides the connection versions this context supports during the handshake.
+	pub fn with_supported_versions(self, supported_versions: Vec<Version>) -> Self {
+		Self { supported_versions, ..self }
+	}
+
+	/// Sets whether connection handshakes handled by this context require client and consensus
+	/// state proofs.
+	pub fn with_handshake_proofs_required(self, require_handshake_proofs: bool) -> Self {
+		Self { require_handshake_proofs, ..self }
+	}
+
+	/// Overrides the number of blocks after which anyone may cancel a pending connection
+	/// handshake.
+	pub fn with_handshake_timeout(self, handshake_timeout: u64) -> Self {
+		Self { handshake_timeout, ..self }
+	}
+
+	/// Pins the commitment prefix that the counterparty tracked by `client_id` must use.
+	pub fn with_expected_counterparty_prefix(
+		mut self,
+		client_id: ClientId,
+		prefix: CommitmentPrefix,
+	) -> Self {
+		self.expected_counterparty_prefixes.insert(client_id, prefix);
+		self
+	}
+
+	/// Restricts closing channels on `port_id` to `signer`.
+	pub fn with_channel_close_authority(mut self, port_id: PortId, signer: Signer) -> Self {
+		self.channel_close_authorities.insert(port_id, signer);
+		self
+	}
+
+	/// Allows `signer` to propose and cancel upgrades of channels on `port_id`.
+	pub fn with_channel_upgrade_authority(mut self, port_id: PortId, signer: Signer) -> Self {
+		self.channel_upgrade_authorities.insert(port_id, signer);
+		self
+	}
+
+	/// Allows `signer` to recover frozen or expired clients.
+	pub fn with_client_recovery_authority(self, signer: Signer) -> Self {
+		Self { client_recovery_authority: Some(signer), ..self }
+	}
+
+	/// Sets whether timeouts handled by this context are recorded in the store.
+	pub fn with_persist_timeouts(self, persist_timeouts: bool) -> Self {
+		Self { persist_timeouts, ..self }
+	}
+
+	/// Makes host consensus state proofs for `height` resolve to `consensus_state` instead of the
+	/// block recorded in the history.
+	pub fn with_claimed_host_consensus_state(
+		mut self,
+		height: Height,
+		consensus_state: C::AnyConsensusState,
+	) -> Self {
+		self.claimed_host_consensus_states.insert(height, consensus_state);
+		self
+	}
+
 	/// Associates a channel (in an arbitrary state) to this context.
 	pub fn with_channel(
 		self,
@@ -317,9 +436,46 @@ where
 		Self { router, ..self }
 	}
 
-	/// Accessor for a block of the local (host) chain from this context.
-	/// Returns `None` if the block at the requested height does not exist.
-	pub fn host_block(&self, target_height: Height) -> Option<&C::HostBlock> {
+	/// Sets the time between two blocks of the host chain. The blocks already in the history
+	/// are spaced out again, the latest one keeping its timestamp.
+	pub fn with_block_time(mut self, block_time: Duration) -> Self {
+		let latest_timestamp = self.history.last().expect("history cannot be empty").timestamp();
+		let blocks = self.history.len() as u32;
+		for i in 0..blocks {
+			let timestamp = latest_timestamp.sub(block_time * (blocks - 1 - i)).unwrap();
+			let height = self.history[i as usize].height();
+			self.history[i as usize] = self.generate_block(height, timestamp);
+		}
+		Self { block_time, ..self }
+	}
+
+	/// Moves the host clock forward by `duration` without producing a block. The host timestamp
+	/// jumps right away and the next block produced carries the advanced timestamp, so that
+	/// timestamp timeouts can be reached without advancing the height.
+	pub fn advance_time(&mut self, duration: Duration) {
+		self.time_offset += duration;
+	}
+
+	/// Overrides the timestamp of the host block at `height`, which its consensus state then
+	/// carries. Overriding the latest block moves the host timestamp as well. Panics if the
+	/// block is not in the history.
+	pub fn set_timestamp_at_height(&mut self, height: Height, timestamp: Timestamp) {
+		let index = self
+			.host_block_index(height)
+			.unwrap_or_else(|| panic!("no host block at height {}", height));
+		self.history[index] = self.generate_block(height, timestamp);
+	}
+
+	fn generate_block(&self, height: Height, timestamp: Timestamp) -> C::HostBlock {
+		<C as HostBlockType>::HostBlock::generate_block(
+			self.host_chain_id.clone(),
+			self.host_chain_type,
+			height.revision_height,
+			timestamp,
+		)
+	}
+
+	fn host_block_index(&self, target_height: Height) -> Option<usize> {
 		let target = target_height.revision_height as usize;
 		let latest = self.latest_height().revision_height as usize;
 
@@ -327,19 +483,24 @@ where
 		if (target > latest) || (target <= latest - self.history.len()) {
 			None // Block for requested height does not exist in history.
 		} else {
-			Some(&self.history[self.history.len() + target - latest - 1])
+			Some(self.history.len() + target - latest - 1)
 		}
 	}
 
+	/// Accessor for a block of the local (host) chain from this context.
+	/// Returns `None` if the block at the requested height does not exist.
+	pub fn host_block(&self, target_height: Height) -> Option<&C::HostBlock> {
+		self.host_block_index(target_height).map(|index| &self.history[index])
+	}
+
 	/// Triggers the advancing of the host chain, by extending the history of blocks (or headers).
 	pub fn advance_host_chain_height(&mut self) {
 		let latest_block = self.history.last().expect("history cannot be empty");
-		let new_block = <C as HostBlockType>::HostBlock::generate_block(
-			self.host_chain_id.clone(),
-			self.host_chain_type,
-			latest_block.height().increment().revision_height,
-			latest_block.timestamp().add(self.block_time).unwrap(),
-		);
+		let timestamp = latest_block
+			.timestamp()
+			.add(self.block_time + core::mem::take(&mut self.time_offset))
+			.unwrap();
+		let new_block = self.generate_block(latest_block.height().increment(), timestamp);
 
 		// Append the new header at the tip of the history.
 		if self.history.len() >= self.max_history_size {
@@ -483,7 +644,15 @@ where
 		debug!("consensus states: {:?}", consensus_states);
 
 		let client_record = MockClientRecord { client_type, client_state, consensus_states };
-		self.ibc_store.lock().unwrap().clients.insert(client_id.clone(), client_record);
+		let (processed_time, processed_height) = (self.host_timestamp(), self.host_height());
+		let mut ibc_store = self.ibc_store.lock().unwrap();
+		ibc_store.clients.insert(client_id.clone(), client_record);
+		// The consensus state counts as stored now, for the connection delay checks.
+		ibc_store.client_processed_times.insert((client_id.clone(), cs_height), processed_time);
+		ibc_store
+			.client_processed_heights
+			.insert((client_id.clone(), cs_height), processed_height);
+		drop(ibc_store);
 		self
 	}
 }
@@ -505,7 +674,7 @@ pub struct MockIbcStore<C: HostBlockType> {
 	pub client_ids_counter: u64,
 
 	/// Association between client ids and connection ids.
-	pub client_connections: BTreeMap<ClientId, ConnectionId>,
+	pub client_connections: BTreeMap<ClientId, Vec<ConnectionId>>,
 
 	/// All the connections in the store.
 	pub connections: BTreeMap<ConnectionId, ConnectionEnd>,
@@ -513,6 +682,9 @@ pub struct MockIbcStore<C: HostBlockType> {
 	/// Counter for connection identifiers (see `increase_connection_counter`).
 	pub connection_ids_counter: u64,
 
+	/// Who started each pending connection handshake and when.
+	pub connection_handshake_origins: BTreeMap<ConnectionId, HandshakeOrigin>,
+
 	/// Association between connection ids and channel ids.
 	pub connection_channels: BTreeMap<ConnectionId, Vec<(PortId, ChannelId)>>,
 
@@ -522,6 +694,9 @@ pub struct MockIbcStore<C: HostBlockType> {
 	/// All the channels in the store. TODO Make new key PortId X ChanneId
 	pub channels: BTreeMap<(PortId, ChannelId), ChannelEnd>,
 
+	/// What the upgrade in progress on each channel replaced and when it started.
+	pub channel_upgrade_origins: BTreeMap<(PortId, ChannelId), UpgradeOrigin>,
+
 	/// Tracks the sequence number for the next packet to be sent.
 	pub next_sequence_send: BTreeMap<(PortId, ChannelId), Sequence>,
 
@@ -541,6 +716,12 @@ pub struct MockIbcStore<C: HostBlockType> {
 
 	// Used by unordered channel
 	pub packet_receipt: BTreeMap<(PortId, ChannelId, Sequence), Receipt>,
+
+	/// Tracks the lowest sequence not yet pruned on each unordered channel.
+	pub pruning_sequence_start: BTreeMap<(PortId, ChannelId), Sequence>,
+
+	/// Sequences and host times of the timeouts recorded on each channel.
+	pub timed_out_packets: BTreeMap<(PortId, ChannelId), Vec<(Sequence, Timestamp)>>,
 }
 
 #[derive(Default)]
@@ -578,6 +759,14 @@ impl Router for MockRouter {
 	fn has_route(&self, module_id: &ModuleId) -> bool {
 		self.0.get(module_id.as_ref()).is_some()
 	}
+
+	fn add_route(&mut self, module_id: ModuleId, module: Box<dyn Module>) -> Result<(), String> {
+		if self.has_route(&module_id) {
+			return Err("Duplicate module_id".to_owned())
+		}
+		self.0.insert(module_id, Arc::from(module));
+		Ok(())
+	}
 }
 
 impl<C: HostBlockType + Default> ReaderContext for MockContext<C>
@@ -587,6 +776,13 @@ where
 	C::AnyConsensusState:
 		Eq + TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock> + 'static,
 {
+	fn require_proofs_in_handshake(&self) -> bool {
+		self.require_handshake_proofs
+	}
+
+	fn persist_timeouts(&self) -> bool {
+		self.persist_timeouts
+	}
 }
 
 impl<C: HostBlockType + Default> ModuleCallbackContext for MockContext<C>
@@ -614,17 +810,36 @@ where
 	fn router_mut(&mut self) -> &mut Self::Router {
 		&mut self.router
 	}
+
+	fn with_transaction<R>(
+		&mut self,
+		f: impl FnOnce(&mut Self) -> Result<R, Ics26Error>,
+	) -> Result<R, Ics26Error> {
+		let snapshot = self.ibc_store.lock().unwrap().clone();
+		let result = f(self);
+		if result.is_err() {
+			*self.ibc_store.lock().unwrap() = snapshot;
+		}
+		result
+	}
 }
 
 impl<C: HostBlockType> PortReader for MockContext<C> {
 	fn lookup_module_by_port(&self, port_id: &PortId) -> Result<ModuleId, Error> {
 		match self.ibc_store.lock().unwrap().port_to_module.get(port_id) {
 			Some(mod_id) => Ok(mod_id.clone()),
-			None => Err(Ics05Error::unknown_port(port_id.clone())),
+			None => Err(Ics05Error::module_not_found(port_id.clone())),
 		}
 	}
 }
 
+impl<C: HostBlockType> PortKeeper for MockContext<C> {
+	fn store_port_binding(&mut self, port_id: PortId, module_id: ModuleId) -> Result<(), Error> {
+		self.ibc_store.lock().unwrap().port_to_module.insert(port_id, module_id);
+		Ok(())
+	}
+}
+
 impl<C: HostBlockType> ChannelReader for MockContext<C> {
 	fn channel_end(&self, pcid: &(PortId, ChannelId)) -> Result<ChannelEnd, Ics04Error> {
 		match self.ibc_store.lock().unwrap().channels.get(pcid) {
@@ -683,6 +898,23 @@ impl<C: HostBlockType> ChannelReader for MockContext<C> {
 		}
 	}
 
+	fn packet_commitment_count(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<u64, Ics04Error> {
+		let count = self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.packet_commitment
+			.keys()
+			.filter(|(port_id, channel_id, _)| {
+				port_id == &port_channel_id.0 && channel_id == &port_channel_id.1
+			})
+			.count();
+		Ok(count as u64)
+	}
+
 	fn get_packet_receipt(
 		&self,
 		key: &(PortId, ChannelId, Sequence),
@@ -703,6 +935,88 @@ impl<C: HostBlockType> ChannelReader for MockContext<C> {
 		}
 	}
 
+	fn packet_acknowledgements(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+		start_sequence: Sequence,
+		limit: u64,
+	) -> Result<Vec<(Sequence, AcknowledgementCommitment)>, Ics04Error> {
+		let (port_id, channel_id) = port_channel_id;
+		let acks = self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.packet_acknowledgement
+			.range((port_id.clone(), *channel_id, start_sequence)..)
+			.take_while(|((port, channel, _), _)| port == port_id && channel == channel_id)
+			.take(limit as usize)
+			.map(|((_, _, seq), ack)| (*seq, ack.clone()))
+			.collect();
+		Ok(acks)
+	}
+
+	fn get_pruning_sequence_start(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, Ics04Error> {
+		Ok(self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.pruning_sequence_start
+			.get(port_channel_id)
+			.cloned()
+			.unwrap_or_else(|| 1.into()))
+	}
+
+	fn timed_out_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<(Sequence, Timestamp)>, Ics04Error> {
+		Ok(self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.timed_out_packets
+			.get(port_channel_id)
+			.cloned()
+			.unwrap_or_default())
+	}
+
+	fn is_close_authorized(
+		&self,
+		port_id: &PortId,
+		_channel_id: &ChannelId,
+		signer: &Signer,
+	) -> bool {
+		match self.channel_close_authorities.get(port_id) {
+			Some(authority) => authority == signer,
+			None => true,
+		}
+	}
+
+	fn is_upgrade_authorized(
+		&self,
+		port_id: &PortId,
+		_channel_id: &ChannelId,
+		signer: &Signer,
+	) -> bool {
+		self.channel_upgrade_authorities.get(port_id) == Some(signer)
+	}
+
+	fn channel_upgrade_origin(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<UpgradeOrigin, Ics04Error> {
+		match self.ibc_store.lock().unwrap().channel_upgrade_origins.get(port_channel_id) {
+			Some(origin) => Ok(origin.clone()),
+			None => Err(Ics04Error::missing_upgrade_origin(
+				port_channel_id.0.clone(),
+				port_channel_id.1,
+			)),
+		}
+	}
+
 	fn hash(&self, value: Vec<u8>) -> Vec<u8> {
 		sha2::Sha256::digest(value).to_vec()
 	}
@@ -809,6 +1123,27 @@ impl<C: HostBlockType> ChannelKeeper for MockContext<C> {
 		Ok(())
 	}
 
+	fn store_channel_upgrade_origin(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		origin: UpgradeOrigin,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.channel_upgrade_origins
+			.insert(port_channel_id, origin);
+		Ok(())
+	}
+
+	fn delete_channel_upgrade_origin(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Ics04Error> {
+		self.ibc_store.lock().unwrap().channel_upgrade_origins.remove(&port_channel_id);
+		Ok(())
+	}
+
 	fn store_next_sequence_send(
 		&mut self,
 		port_channel_id: (PortId, ChannelId),
@@ -836,6 +1171,30 @@ impl<C: HostBlockType> ChannelKeeper for MockContext<C> {
 		Ok(())
 	}
 
+	fn store_pruning_sequence_start(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		seq: Sequence,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store.lock().unwrap().pruning_sequence_start.insert(port_channel_id, seq);
+		Ok(())
+	}
+
+	fn store_timed_out_packet(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		timestamp: Timestamp,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.timed_out_packets
+			.entry((key.0, key.1))
+			.or_default()
+			.push((key.2, timestamp));
+		Ok(())
+	}
+
 	fn increase_channel_counter(&mut self) {
 		self.ibc_store.lock().unwrap().channel_ids_counter += 1;
 	}
@@ -857,6 +1216,14 @@ impl<C: HostBlockType> ChannelKeeper for MockContext<C> {
 		Ok(())
 	}
 
+	fn delete_packet_receipt(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+	) -> Result<(), Ics04Error> {
+		self.ibc_store.lock().unwrap().packet_receipt.remove(&key);
+		Ok(())
+	}
+
 	fn store_send_packet(
 		&mut self,
 		_key: (PortId, ChannelId, Sequence),
@@ -892,6 +1259,17 @@ where
 		}
 	}
 
+	fn client_connections(&self, client_id: &ClientId) -> Result<Vec<ConnectionId>, Ics03Error> {
+		Ok(self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.client_connections
+			.get(client_id)
+			.cloned()
+			.unwrap_or_default())
+	}
+
 	fn host_oldest_height(&self) -> Height {
 		// history must be non-empty, so `self.history[0]` is valid
 		self.history[0].height()
@@ -901,9 +1279,31 @@ where
 		CommitmentPrefix::try_from(b"mock".to_vec()).unwrap()
 	}
 
+	fn expected_counterparty_prefix(&self, client_id: &ClientId) -> Option<CommitmentPrefix> {
+		self.expected_counterparty_prefixes.get(client_id).cloned()
+	}
+
+	fn supported_versions(&self) -> Vec<Version> {
+		self.supported_versions.clone()
+	}
+
 	fn connection_counter(&self) -> Result<u64, Ics03Error> {
 		Ok(self.ibc_store.lock().unwrap().connection_ids_counter)
 	}
+
+	fn handshake_timeout(&self) -> u64 {
+		self.handshake_timeout
+	}
+
+	fn connection_handshake_origin(
+		&self,
+		cid: &ConnectionId,
+	) -> Result<HandshakeOrigin, Ics03Error> {
+		match self.ibc_store.lock().unwrap().connection_handshake_origins.get(cid) {
+			Some(origin) => Ok(origin.clone()),
+			None => Err(Ics03Error::connection_not_found(cid.clone())),
+		}
+	}
 }
 
 impl<C: HostBlockType> ConnectionKeeper for MockContext<C> {
@@ -920,6 +1320,37 @@ impl<C: HostBlockType> ConnectionKeeper for MockContext<C> {
 		Ok(())
 	}
 
+	fn store_connection_handshake_origin(
+		&mut self,
+		connection_id: ConnectionId,
+		origin: HandshakeOrigin,
+	) -> Result<(), Ics03Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.connection_handshake_origins
+			.insert(connection_id, origin);
+		Ok(())
+	}
+
+	fn delete_connection_handshake_origin(
+		&mut self,
+		connection_id: &ConnectionId,
+	) -> Result<(), Ics03Error> {
+		self.ibc_store.lock().unwrap().connection_handshake_origins.remove(connection_id);
+		Ok(())
+	}
+
+	fn delete_connection_end(&mut self, connection_id: &ConnectionId) -> Result<(), Ics03Error> {
+		let mut ibc_store = self.ibc_store.lock().unwrap();
+		ibc_store.connections.remove(connection_id);
+		ibc_store.connection_handshake_origins.remove(connection_id);
+		for conn_ids in ibc_store.client_connections.values_mut() {
+			conn_ids.retain(|conn_id| conn_id != connection_id);
+		}
+		Ok(())
+	}
+
 	fn store_connection_to_client(
 		&mut self,
 		connection_id: ConnectionId,
@@ -929,7 +1360,9 @@ impl<C: HostBlockType> ConnectionKeeper for MockContext<C> {
 			.lock()
 			.unwrap()
 			.client_connections
-			.insert(client_id.clone(), connection_id);
+			.entry(client_id.clone())
+			.or_default()
+			.push(connection_id);
 		Ok(())
 	}
 
@@ -980,6 +1413,10 @@ where
 		MockClientState::client_type().to_owned()
 	}
 
+	fn supported_client_types(&self) -> Vec<ClientType> {
+		C::supported_client_types()
+	}
+
 	/// Search for the lowest consensus state higher than `height`.
 	fn next_consensus_state(
 		&self,
@@ -1032,6 +1469,31 @@ where
 		Ok(None)
 	}
 
+	fn oldest_consensus_state_height(
+		&self,
+		client_id: &ClientId,
+	) -> Result<Option<Height>, Ics02Error> {
+		let ibc_store = self.ibc_store.lock().unwrap();
+		let client_record = ibc_store
+			.clients
+			.get(client_id)
+			.ok_or_else(|| Ics02Error::client_not_found(client_id.clone()))?;
+		Ok(client_record.consensus_states.keys().min().cloned())
+	}
+
+	fn next_consensus_state_height(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<Option<Height>, Ics02Error> {
+		let ibc_store = self.ibc_store.lock().unwrap();
+		let client_record = ibc_store
+			.clients
+			.get(client_id)
+			.ok_or_else(|| Ics02Error::client_not_found(client_id.clone()))?;
+		Ok(client_record.consensus_states.keys().filter(|h| **h > height).min().cloned())
+	}
+
 	fn host_height(&self) -> Height {
 		self.latest_height()
 	}
@@ -1041,15 +1503,20 @@ where
 			.last()
 			.expect("history cannot be empty")
 			.timestamp()
-			.add(self.block_time)
+			.add(self.block_time + self.time_offset)
 			.unwrap()
 	}
 
 	fn host_consensus_state(
 		&self,
 		height: Height,
-		_proof: Option<Vec<u8>>,
+		proof: Option<Vec<u8>>,
 	) -> Result<C::AnyConsensusState, Ics02Error> {
+		if proof.is_some() {
+			if let Some(consensus_state) = self.claimed_host_consensus_states.get(&height) {
+				return Ok(consensus_state.clone())
+			}
+		}
 		match self.host_block(height) {
 			Some(block_ref) => Ok(block_ref.clone().into()),
 			None => Err(Ics02Error::missing_local_consensus_state(height)),
@@ -1059,6 +1526,10 @@ where
 	fn client_counter(&self) -> Result<u64, Ics02Error> {
 		Ok(self.ibc_store.lock().unwrap().client_ids_counter)
 	}
+
+	fn is_client_recovery_authorized(&self, signer: &Signer) -> bool {
+		self.client_recovery_authority.as_ref() == Some(signer)
+	}
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Default)]
@@ -1069,6 +1540,9 @@ where
 	Self: Clone + Debug + Eq,
 {
 	type HostBlock: HostBlock + Debug + Clone;
+
+	/// The client types a `MockContext` over these client types reports as supported.
+	fn supported_client_types() -> Vec<ClientType>;
 }
 
 impl ClientTypes for MockClientTypes {
@@ -1080,6 +1554,10 @@ impl ClientTypes for MockClientTypes {
 
 impl HostBlockType for MockClientTypes {
 	type HostBlock = MockHostBlock;
+
+	fn supported_client_types() -> Vec<ClientType> {
+		vec![MockClientState::client_type()]
+	}
 }
 
 impl<C: HostBlockType> ClientTypes for MockContext<C> {
@@ -1173,6 +1651,36 @@ impl<C: HostBlockType> ClientKeeper for MockContext<C> {
 		Ok(())
 	}
 
+	fn delete_consensus_state(
+		&mut self,
+		client_id: ClientId,
+		height: Height,
+	) -> Result<(), Ics02Error> {
+		if let Some(client_record) = self.ibc_store.lock().unwrap().clients.get_mut(&client_id) {
+			client_record.consensus_states.remove(&height);
+		}
+		Ok(())
+	}
+
+	fn delete_update_time(
+		&mut self,
+		client_id: ClientId,
+		height: Height,
+	) -> Result<(), Ics02Error> {
+		let _ = self.ibc_store.lock().unwrap().client_processed_times.remove(&(client_id, height));
+		Ok(())
+	}
+
+	fn delete_update_height(
+		&mut self,
+		client_id: ClientId,
+		height: Height,
+	) -> Result<(), Ics02Error> {
+		let _ =
+			self.ibc_store.lock().unwrap().client_processed_heights.remove(&(client_id, height));
+		Ok(())
+	}
+
 	fn validate_self_client(&self, _client_state: &C::AnyClientState) -> Result<(), Ics02Error> {
 		Ok(())
 	}
@@ -1183,19 +1691,22 @@ mod tests {
 	use test_log::test;
 
 	use alloc::str::FromStr;
+	use core::time::Duration;
 
 	use crate::{
 		core::{
+			ics02_client::{client_consensus::ConsensusState, context::ClientReader},
 			ics04_channel::{
 				channel::{Counterparty, Order},
+				context::{ChannelKeeper, ChannelReader},
 				error::Error,
-				packet::Packet,
+				packet::{Packet, Sequence},
 				Version,
 			},
 			ics24_host::identifier::{ChainId, ChannelId, ConnectionId, PortId},
 			ics26_routing::context::{
 				Acknowledgement, Module, ModuleCallbackContext, ModuleId, ModuleOutputBuilder,
-				Router, RouterBuilder,
+				OnRecvPacketAck, Router, RouterBuilder,
 			},
 		},
 		mock::{
@@ -1205,6 +1716,7 @@ mod tests {
 		prelude::*,
 		signer::Signer,
 		test_utils::get_dummy_bech32_account,
+		timestamp::Timestamp,
 		Height,
 	};
 
@@ -1342,8 +1854,8 @@ mod tests {
 				_output: &mut ModuleOutputBuilder,
 				_packet: &Packet,
 				_relayer: &Signer,
-			) -> Result<(), Error> {
-				Ok(())
+			) -> Result<OnRecvPacketAck, Error> {
+				Ok(OnRecvPacketAck::Nil)
 			}
 		}
 
@@ -1428,4 +1940,98 @@ impl Default for Attributes {
 			consensus_height: Height::default(),
 		}
 	}
+
+	#[test]
+	fn test_packet_commitment_count() {
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		let channel_0 = (PortId::default(), ChannelId::new(0));
+		let channel_1 = (PortId::default(), ChannelId::new(1));
+
+		for seq in 1..=3u64 {
+			let key = (channel_0.0.clone(), channel_0.1, seq.into());
+			ctx.store_packet_commitment(key, vec![1].into()).unwrap();
+		}
+		ctx.store_packet_commitment((channel_1.0.clone(), channel_1.1, 1.into()), vec![1].into())
+			.unwrap();
+		assert_eq!(ctx.packet_commitment_count(&channel_0).unwrap(), 3);
+		assert_eq!(ctx.packet_commitment_count(&channel_1).unwrap(), 1);
+
+		ctx.delete_packet_commitment((channel_0.0.clone(), channel_0.1, 2.into())).unwrap();
+		assert_eq!(ctx.packet_commitment_count(&channel_0).unwrap(), 2);
+		assert_eq!(ctx.packet_commitment_count(&channel_1).unwrap(), 1);
+	}
+
+	#[test]
+	fn test_packet_acknowledgements_pagination() {
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		let channel_0 = (PortId::default(), ChannelId::new(0));
+		let channel_1 = (PortId::default(), ChannelId::new(1));
+
+		for seq in 1..=7u64 {
+			let key = (channel_0.0.clone(), channel_0.1, seq.into());
+			ctx.store_packet_acknowledgement(key, vec![seq as u8].into()).unwrap();
+		}
+		ctx.store_packet_acknowledgement(
+			(channel_1.0.clone(), channel_1.1, 1.into()),
+			vec![1].into(),
+		)
+		.unwrap();
+
+		let page_size = 3;
+		let mut start = Sequence::from(1);
+		let mut pages = vec![];
+		loop {
+			let page = ctx.packet_acknowledgements(&channel_0, start, page_size).unwrap();
+			if page.is_empty() {
+				break
+			}
+			start = page.last().unwrap().0.increment();
+			pages.push(page.into_iter().map(|(seq, _)| u64::from(seq)).collect::<Vec<_>>());
+		}
+		assert_eq!(pages, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7]]);
+
+		let page = ctx.packet_acknowledgements(&channel_0, 5.into(), 10).unwrap();
+		assert_eq!(page.len(), 3);
+		assert_eq!(page[0].0, Sequence::from(5));
+		assert_eq!(page[0].1.clone().into_vec(), vec![5]);
+
+		let page = ctx.packet_acknowledgements(&channel_1, 1.into(), 10).unwrap();
+		assert_eq!(page.len(), 1);
+	}
+
+	#[test]
+	fn test_host_time_control() {
+		let block_time = Duration::from_secs(10);
+		let mut ctx = MockContext::<MockClientTypes>::default().with_block_time(block_time);
+		let latest_height = ctx.latest_height();
+		let block_timestamp = |ctx: &MockContext<MockClientTypes>, height| {
+			ctx.host_consensus_state(height, None).unwrap().timestamp()
+		};
+
+		// The history is spaced out by the new block time.
+		let latest_timestamp = block_timestamp(&ctx, latest_height);
+		assert_eq!(
+			block_timestamp(&ctx, latest_height.decrement().unwrap()),
+			(latest_timestamp - block_time).unwrap()
+		);
+		assert_eq!(ctx.host_timestamp(), (latest_timestamp + block_time).unwrap());
+
+		// Advancing the time moves the host timestamp without producing a block.
+		let advance = Duration::from_secs(3600);
+		ctx.advance_time(advance);
+		assert_eq!(ctx.latest_height(), latest_height);
+		assert_eq!(ctx.host_timestamp(), (latest_timestamp + (block_time + advance)).unwrap());
+
+		// The next block carries the advanced time, and the ones after it are spaced as usual.
+		ctx.advance_host_chain_height();
+		let next_timestamp = block_timestamp(&ctx, latest_height.increment());
+		assert_eq!(next_timestamp, (latest_timestamp + (block_time + advance)).unwrap());
+		assert_eq!(ctx.host_timestamp(), (next_timestamp + block_time).unwrap());
+
+		// Overriding the timestamp of the latest block moves the host timestamp along.
+		let timestamp = Timestamp::from_nanoseconds(1_000_000_000).unwrap();
+		ctx.set_timestamp_at_height(ctx.latest_height(), timestamp);
+		assert_eq!(block_timestamp(&ctx, ctx.latest_height()), timestamp);
+		assert_eq!(ctx.host_timestamp(), (timestamp + block_time).unwrap());
+	}
 }
@@ -14,6 +14,7 @@
 
 //! Implementation of mocks for context, host chain, and client.
 
+pub mod builder;
 pub mod client_def;
 pub mod client_state;
 pub mod context;
@@ -13,15 +13,39 @@
 // limitations under the License.
 
 use std::{
+	collections::{BTreeMap, BTreeSet},
 	sync::{Arc, Mutex},
 	time::Duration,
 };
 
 use crate::{
-	applications::transfer::{
-		context::{BankKeeper, Ics20Context, Ics20Keeper, Ics20Reader},
-		error::Error as Ics20Error,
-		PrefixedCoin,
+	applications::{
+		fee::{context::FeeKeeper, error::Error as FeeError, PacketFee},
+		interchain_accounts::{
+			context::{
+				IcaControllerContext, IcaControllerKeeper, IcaHostContext, IcaHostExecutor,
+				IcaHostKeeper,
+			},
+			error::Error as IcaError,
+		},
+		nft_transfer::{
+			context::{
+				ClassTraceStore, NftKeeper, NftTransferContext, NftTransferKeeper,
+				NftTransferReader,
+			},
+			error::Error as NftTransferError,
+			PrefixedClassId, TokenId,
+		},
+		transfer::{
+			acknowledgement::Acknowledgement as Ics20Acknowledgement,
+			context::{
+				BankKeeper, DenomTraceStore, EscrowBalanceStore, Ics20Context, Ics20Keeper,
+				Ics20Reader, TransferRateLimiter,
+			},
+			error::Error as Ics20Error,
+			forward::{ForwardKeeper, InFlightForward},
+			Amount, PrefixedCoin, PrefixedDenom,
+		},
 	},
 	core::{
 		ics02_client::{
@@ -34,16 +58,18 @@ use crate::{
 		},
 		ics04_channel::{
 			channel::{ChannelEnd, Counterparty, Order},
-			commitment::{AcknowledgementCommitment, PacketCommitment},
+			commitment::{compute_ack_commitment, AcknowledgementCommitment, PacketCommitment},
 			context::{ChannelKeeper, ChannelReader},
 			error::Error,
-			packet::{Receipt, Sequence},
+			packet::{Packet, Receipt, Sequence},
+			upgrade::UpgradeOrigin,
 			Version,
 		},
 		ics05_port::{context::PortReader, error::Error as PortError},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 		ics26_routing::context::{Module, ModuleId, ModuleOutputBuilder, ReaderContext},
 	},
+	events::IbcEvent,
 	mock::context::{HostBlockType, MockIbcStore},
 	prelude::*,
 	signer::Signer,
@@ -54,6 +80,7 @@ use crate::{
 use crate::core::{
 	ics02_client::context::ClientTypes, ics26_routing::context::ModuleCallbackContext,
 };
+use ibc_proto::google::protobuf::Any;
 use tendermint::{block, consensus, evidence, public_key::Algorithm};
 
 // Needed in mocks.
@@ -82,9 +109,38 @@ pub fn get_dummy_bech32_account() -> String {
 	"cosmos1wxeyh7zgn4tctjzs0vtqpc6p5cxq5t2muzl7ng".to_string()
 }
 
+/// The type url of the only message the interchain accounts of [`DummyTransferModule`] execute.
+pub const DUMMY_ICA_MSG_TYPE_URL: &str = "/ibc.mock.MsgDummy";
+
 #[derive(Debug, Clone)]
 pub struct DummyTransferModule<C: HostBlockType> {
 	ibc_store: Arc<Mutex<MockIbcStore<C>>>,
+	denom_traces: Arc<Mutex<BTreeMap<String, PrefixedDenom>>>,
+	/// (account, denom) => balance
+	balances: Arc<Mutex<BTreeMap<(String, String), Amount>>>,
+	/// (port, channel, denom) => escrowed amount
+	escrow_balances: Arc<Mutex<BTreeMap<(PortId, ChannelId, String), Amount>>>,
+	in_flight_forwards: Arc<Mutex<BTreeMap<(PortId, ChannelId, Sequence), InFlightForward>>>,
+	ibc_events: Arc<Mutex<Vec<IbcEvent>>>,
+	fee_enabled_channels: Arc<Mutex<BTreeSet<(PortId, ChannelId)>>>,
+	packet_fees: Arc<Mutex<BTreeMap<(PortId, ChannelId, Sequence), Vec<PacketFee>>>>,
+	/// (channel, relayer) => counterparty payee
+	counterparty_payees: Arc<Mutex<BTreeMap<(ChannelId, String), Signer>>>,
+	class_traces: Arc<Mutex<BTreeMap<String, PrefixedClassId>>>,
+	/// class => class uri
+	nft_classes: Arc<Mutex<BTreeMap<String, String>>>,
+	/// (class, token) => (owner, token uri)
+	nft_tokens: Arc<Mutex<BTreeMap<(String, String), (Signer, String)>>>,
+	/// (connection, controller port) => channel, on a controller chain
+	ica_controller_channels: Arc<Mutex<BTreeMap<(ConnectionId, PortId), ChannelId>>>,
+	/// (connection, controller port) => interchain account, on a controller chain
+	ica_controller_accounts: Arc<Mutex<BTreeMap<(ConnectionId, PortId), Signer>>>,
+	/// (connection, controller port) => channel, on a host chain
+	ica_host_channels: Arc<Mutex<BTreeMap<(ConnectionId, PortId), ChannelId>>>,
+	/// (connection, controller port) => interchain account, on a host chain
+	ica_host_accounts: Arc<Mutex<BTreeMap<(ConnectionId, PortId), Signer>>>,
+	/// (interchain account, message) executed on a host chain
+	ica_executed: Arc<Mutex<Vec<(Signer, Any)>>>,
 }
 
 impl<C: HostBlockType> PartialEq for DummyTransferModule<C> {
@@ -97,7 +153,73 @@ impl<C: HostBlockType> Eq for DummyTransferModule<C> {}
 
 impl<C: HostBlockType> DummyTransferModule<C> {
 	pub fn new(ibc_store: Arc<Mutex<MockIbcStore<C>>>) -> Self {
-		Self { ibc_store }
+		Self {
+			ibc_store,
+			denom_traces: Default::default(),
+			balances: Default::default(),
+			escrow_balances: Default::default(),
+			in_flight_forwards: Default::default(),
+			ibc_events: Default::default(),
+			fee_enabled_channels: Default::default(),
+			packet_fees: Default::default(),
+			counterparty_payees: Default::default(),
+			class_traces: Default::default(),
+			nft_classes: Default::default(),
+			nft_tokens: Default::default(),
+			ica_controller_channels: Default::default(),
+			ica_controller_accounts: Default::default(),
+			ica_host_channels: Default::default(),
+			ica_host_accounts: Default::default(),
+			ica_executed: Default::default(),
+		}
+	}
+
+	/// The messages executed by the interchain accounts of a host chain, with their signer.
+	pub fn ica_executed(&self) -> Vec<(Signer, Any)> {
+		self.ica_executed.lock().unwrap().clone()
+	}
+
+	/// The owner of a non fungible token, `None` if the token does not exist.
+	pub fn nft_owner(&self, class_id: &PrefixedClassId, token_id: &TokenId) -> Option<Signer> {
+		self.nft_tokens
+			.lock()
+			.unwrap()
+			.get(&(class_id.to_string(), token_id.to_string()))
+			.map(|(owner, _)| owner.clone())
+	}
+
+	pub fn balance(&self, account: &Signer, denom: &PrefixedDenom) -> Amount {
+		self.balances
+			.lock()
+			.unwrap()
+			.get(&(account.to_string(), denom.to_string()))
+			.copied()
+			.unwrap_or_else(|| Amount::from(0u64))
+	}
+
+	/// The IBC events of the packets sent while forwarding transfers.
+	pub fn ibc_events(&self) -> Vec<IbcEvent> {
+		self.ibc_events.lock().unwrap().clone()
+	}
+
+	fn credit(&mut self, account: &Signer, coin: &PrefixedCoin) -> Result<(), Ics20Error> {
+		let mut balances = self.balances.lock().unwrap();
+		let balance = balances
+			.entry((account.to_string(), coin.denom.to_string()))
+			.or_insert_with(|| Amount::from(0u64));
+		*balance = balance.checked_add(coin.amount).ok_or_else(Ics20Error::invalid_token)?;
+		Ok(())
+	}
+
+	fn debit(&mut self, account: &Signer, coin: &PrefixedCoin) -> Result<(), Ics20Error> {
+		let balance = self.balance(account, &coin.denom).checked_sub(coin.amount).ok_or_else(|| {
+			Ics20Error::implementation_specific(format!("insufficient funds in {}", account))
+		})?;
+		self.balances
+			.lock()
+			.unwrap()
+			.insert((account.to_string(), coin.denom.to_string()), balance);
+		Ok(())
 	}
 }
 
@@ -241,8 +363,14 @@ impl HostFunctionsProvider for Crypto {
 
 // implementation for ics23
 impl ics23::HostFunctionsProvider for Crypto {
-	fn sha2_256(_message: &[u8]) -> [u8; 32] {
-		unimplemented!()
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		use sha2::Digest;
+		let mut hasher = sha2::Sha256::new();
+		hasher.update(message);
+		let hash = hasher.finalize();
+		let mut res = [0u8; 32];
+		res.copy_from_slice(&hash);
+		res
 	}
 
 	fn sha2_512(_message: &[u8]) -> [u8; 64] {
@@ -278,9 +406,10 @@ impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
 
 	fn delete_packet_commitment(
 		&mut self,
-		_key: (PortId, ChannelId, Sequence),
+		key: (PortId, ChannelId, Sequence),
 	) -> Result<(), Error> {
-		unimplemented!()
+		self.ibc_store.lock().unwrap().packet_commitment.remove(&key);
+		Ok(())
 	}
 
 	fn store_packet_receipt(
@@ -291,12 +420,17 @@ impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
 		unimplemented!()
 	}
 
+	fn delete_packet_receipt(&mut self, _key: (PortId, ChannelId, Sequence)) -> Result<(), Error> {
+		unimplemented!()
+	}
+
 	fn store_packet_acknowledgement(
 		&mut self,
-		_key: (PortId, ChannelId, Sequence),
-		_ack: AcknowledgementCommitment,
+		key: (PortId, ChannelId, Sequence),
+		ack: AcknowledgementCommitment,
 	) -> Result<(), Error> {
-		unimplemented!()
+		self.ibc_store.lock().unwrap().packet_acknowledgement.insert(key, ack);
+		Ok(())
 	}
 
 	fn delete_packet_acknowledgement(
@@ -322,6 +456,21 @@ impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
 		unimplemented!()
 	}
 
+	fn store_channel_upgrade_origin(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_origin: UpgradeOrigin,
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn delete_channel_upgrade_origin(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
 	fn store_next_sequence_send(
 		&mut self,
 		port_channel_id: (PortId, ChannelId),
@@ -347,6 +496,14 @@ impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
 		unimplemented!()
 	}
 
+	fn store_pruning_sequence_start(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_seq: Sequence,
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
 	fn increase_channel_counter(&mut self) {
 		unimplemented!()
 	}
@@ -379,27 +536,28 @@ impl<C: HostBlockType> BankKeeper for DummyTransferModule<C> {
 
 	fn send_coins(
 		&mut self,
-		_from: &Self::AccountId,
-		_to: &Self::AccountId,
-		_amt: &PrefixedCoin,
+		from: &Self::AccountId,
+		to: &Self::AccountId,
+		amt: &PrefixedCoin,
 	) -> Result<(), Ics20Error> {
-		Ok(())
+		self.debit(from, amt)?;
+		self.credit(to, amt)
 	}
 
 	fn mint_coins(
 		&mut self,
-		_account: &Self::AccountId,
-		_amt: &PrefixedCoin,
+		account: &Self::AccountId,
+		amt: &PrefixedCoin,
 	) -> Result<(), Ics20Error> {
-		Ok(())
+		self.credit(account, amt)
 	}
 
 	fn burn_coins(
 		&mut self,
-		_account: &Self::AccountId,
-		_amt: &PrefixedCoin,
+		account: &Self::AccountId,
+		amt: &PrefixedCoin,
 	) -> Result<(), Ics20Error> {
-		Ok(())
+		self.debit(account, amt)
 	}
 }
 
@@ -419,6 +577,383 @@ impl<C: HostBlockType> Ics20Reader for DummyTransferModule<C> {
 	}
 }
 
+impl<C: HostBlockType> DenomTraceStore for DummyTransferModule<C> {
+	fn store_denom_trace(&mut self, hash: String, denom: PrefixedDenom) -> Result<(), Ics20Error> {
+		self.denom_traces.lock().unwrap().insert(hash, denom);
+		Ok(())
+	}
+
+	fn get_denom_trace(&self, hash: &str) -> Result<Option<PrefixedDenom>, Ics20Error> {
+		Ok(self.denom_traces.lock().unwrap().get(hash).cloned())
+	}
+
+	fn iter_denom_traces(&self) -> Result<Vec<(String, PrefixedDenom)>, Ics20Error> {
+		Ok(self
+			.denom_traces
+			.lock()
+			.unwrap()
+			.iter()
+			.map(|(hash, denom)| (hash.clone(), denom.clone()))
+			.collect())
+	}
+}
+
+impl<C: HostBlockType> TransferRateLimiter for DummyTransferModule<C> {}
+
+impl<C: HostBlockType> EscrowBalanceStore for DummyTransferModule<C> {
+	fn escrow_balance(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		denom: &PrefixedDenom,
+	) -> Amount {
+		self.escrow_balances
+			.lock()
+			.unwrap()
+			.get(&(port_id.clone(), *channel_id, denom.to_string()))
+			.copied()
+			.unwrap_or_else(|| Amount::from(0u64))
+	}
+
+	fn store_escrow_balance(
+		&mut self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		denom: &PrefixedDenom,
+		amount: Amount,
+	) -> Result<(), Ics20Error> {
+		self.escrow_balances
+			.lock()
+			.unwrap()
+			.insert((port_id.clone(), *channel_id, denom.to_string()), amount);
+		Ok(())
+	}
+}
+
+impl<C: HostBlockType> NftTransferKeeper for DummyTransferModule<C> {
+	type AccountId = Signer;
+}
+
+impl<C: HostBlockType> NftTransferReader for DummyTransferModule<C> {
+	type AccountId = Signer;
+
+	fn get_port(&self) -> Result<PortId, NftTransferError> {
+		Ok(PortId::nft_transfer())
+	}
+
+	fn is_send_enabled(&self) -> bool {
+		true
+	}
+
+	fn is_receive_enabled(&self) -> bool {
+		true
+	}
+}
+
+impl<C: HostBlockType> NftKeeper for DummyTransferModule<C> {
+	type AccountId = Signer;
+
+	fn get_class_uri(&self, class_id: &PrefixedClassId) -> Option<String> {
+		self.nft_classes.lock().unwrap().get(&class_id.to_string()).cloned()
+	}
+
+	fn get_token_uri(&self, class_id: &PrefixedClassId, token_id: &TokenId) -> Option<String> {
+		self.nft_tokens
+			.lock()
+			.unwrap()
+			.get(&(class_id.to_string(), token_id.to_string()))
+			.map(|(_, uri)| uri.clone())
+	}
+
+	fn create_class(
+		&mut self,
+		class_id: &PrefixedClassId,
+		class_uri: &str,
+	) -> Result<(), NftTransferError> {
+		self.nft_classes.lock().unwrap().insert(class_id.to_string(), class_uri.to_string());
+		Ok(())
+	}
+
+	fn transfer_token(
+		&mut self,
+		from: &Self::AccountId,
+		to: &Self::AccountId,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+	) -> Result<(), NftTransferError> {
+		let mut tokens = self.nft_tokens.lock().unwrap();
+		match tokens.get_mut(&(class_id.to_string(), token_id.to_string())) {
+			Some((owner, _)) if owner == from => {
+				*owner = to.clone();
+				Ok(())
+			},
+			_ => Err(NftTransferError::token_not_found(class_id.to_string(), token_id.to_string())),
+		}
+	}
+
+	fn mint_token(
+		&mut self,
+		account: &Self::AccountId,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+		token_uri: &str,
+	) -> Result<(), NftTransferError> {
+		if self.get_class_uri(class_id).is_none() {
+			return Err(NftTransferError::class_not_found(class_id.to_string()))
+		}
+		let key = (class_id.to_string(), token_id.to_string());
+		let mut tokens = self.nft_tokens.lock().unwrap();
+		if tokens.contains_key(&key) {
+			return Err(NftTransferError::implementation_specific(format!(
+				"token {} of class {} already exists",
+				token_id, class_id
+			)))
+		}
+		tokens.insert(key, (account.clone(), token_uri.to_string()));
+		Ok(())
+	}
+
+	fn burn_token(
+		&mut self,
+		account: &Self::AccountId,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+	) -> Result<(), NftTransferError> {
+		let key = (class_id.to_string(), token_id.to_string());
+		let mut tokens = self.nft_tokens.lock().unwrap();
+		match tokens.get(&key) {
+			Some((owner, _)) if owner == account => {
+				tokens.remove(&key);
+				Ok(())
+			},
+			_ => Err(NftTransferError::token_not_found(class_id.to_string(), token_id.to_string())),
+		}
+	}
+}
+
+impl<C: HostBlockType> ClassTraceStore for DummyTransferModule<C> {
+	fn store_class_trace(
+		&mut self,
+		hash: String,
+		class_id: PrefixedClassId,
+	) -> Result<(), NftTransferError> {
+		self.class_traces.lock().unwrap().insert(hash, class_id);
+		Ok(())
+	}
+
+	fn get_class_trace(&self, hash: &str) -> Result<Option<PrefixedClassId>, NftTransferError> {
+		Ok(self.class_traces.lock().unwrap().get(hash).cloned())
+	}
+}
+
+impl<C: HostBlockType> IcaControllerKeeper for DummyTransferModule<C> {
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), IcaError> {
+		self.ica_controller_channels.lock().unwrap().insert((connection_id, port_id), channel_id);
+		Ok(())
+	}
+
+	fn get_active_channel(
+		&self,
+		connection_id: &ConnectionId,
+		port_id: &PortId,
+	) -> Option<ChannelId> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ica_controller_channels.lock().unwrap().get(&key).copied()
+	}
+
+	fn store_interchain_account_address(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		address: Signer,
+	) -> Result<(), IcaError> {
+		self.ica_controller_accounts.lock().unwrap().insert((connection_id, port_id), address);
+		Ok(())
+	}
+
+	fn get_interchain_account_address(
+		&self,
+		connection_id: &ConnectionId,
+		port_id: &PortId,
+	) -> Option<Signer> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ica_controller_accounts.lock().unwrap().get(&key).cloned()
+	}
+}
+
+impl<C: HostBlockType> IcaHostKeeper for DummyTransferModule<C> {
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), IcaError> {
+		self.ica_host_channels.lock().unwrap().insert((connection_id, port_id), channel_id);
+		Ok(())
+	}
+
+	fn get_active_channel(
+		&self,
+		connection_id: &ConnectionId,
+		port_id: &PortId,
+	) -> Option<ChannelId> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ica_host_channels.lock().unwrap().get(&key).copied()
+	}
+
+	fn register_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		address: Signer,
+	) -> Result<(), IcaError> {
+		self.ica_host_accounts.lock().unwrap().insert((connection_id, port_id), address);
+		Ok(())
+	}
+
+	fn get_interchain_account(
+		&self,
+		connection_id: &ConnectionId,
+		port_id: &PortId,
+	) -> Option<Signer> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ica_host_accounts.lock().unwrap().get(&key).cloned()
+	}
+}
+
+impl<C: HostBlockType> IcaHostExecutor for DummyTransferModule<C> {
+	/// Executes [`DUMMY_ICA_MSG_TYPE_URL`] messages by recording them, their result being the
+	/// concatenation of their values.
+	fn execute(&mut self, account: &Signer, messages: Vec<Any>) -> Result<Vec<u8>, IcaError> {
+		if let Some(message) = messages.iter().find(|m| m.type_url != DUMMY_ICA_MSG_TYPE_URL) {
+			return Err(IcaError::execution_failed(format!(
+				"unsupported message {}",
+				message.type_url
+			)))
+		}
+		let result = messages.iter().flat_map(|m| m.value.clone()).collect();
+		let mut executed = self.ica_executed.lock().unwrap();
+		executed.extend(messages.into_iter().map(|m| (account.clone(), m)));
+		Ok(result)
+	}
+}
+
+impl<C: HostBlockType> ForwardKeeper for DummyTransferModule<C> {
+	fn store_in_flight_forward(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		forward: InFlightForward,
+	) -> Result<(), Ics20Error> {
+		self.in_flight_forwards.lock().unwrap().insert(key, forward);
+		Ok(())
+	}
+
+	fn get_in_flight_forward(
+		&self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<Option<InFlightForward>, Ics20Error> {
+		Ok(self.in_flight_forwards.lock().unwrap().get(key).cloned())
+	}
+
+	fn delete_in_flight_forward(
+		&mut self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<(), Ics20Error> {
+		self.in_flight_forwards.lock().unwrap().remove(key);
+		Ok(())
+	}
+
+	fn write_acknowledgement(
+		&mut self,
+		packet: &Packet,
+		ack: &Ics20Acknowledgement,
+	) -> Result<(), Ics20Error> {
+		let ack = compute_ack_commitment(&ack.as_ref().to_vec().into());
+		self.store_packet_acknowledgement(
+			(packet.destination_port.clone(), packet.destination_channel, packet.sequence),
+			ack,
+		)
+		.map_err(Ics20Error::ics04_channel)
+	}
+
+	fn deposit_ibc_events(&mut self, events: Vec<IbcEvent>) {
+		self.ibc_events.lock().unwrap().extend(events);
+	}
+}
+
+impl<C: HostBlockType> FeeKeeper for DummyTransferModule<C> {
+	type AccountId = Signer;
+
+	fn fee_escrow_address(&self) -> Result<Signer, FeeError> {
+		"feeibc".parse().map_err(FeeError::signer)
+	}
+
+	fn store_fee_enabled(
+		&mut self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<(), FeeError> {
+		self.fee_enabled_channels.lock().unwrap().insert((port_id.clone(), *channel_id));
+		Ok(())
+	}
+
+	fn is_fee_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+		self.fee_enabled_channels.lock().unwrap().contains(&(port_id.clone(), *channel_id))
+	}
+
+	fn store_packet_fees(
+		&mut self,
+		key: (PortId, ChannelId, Sequence),
+		fees: Vec<PacketFee>,
+	) -> Result<(), FeeError> {
+		self.packet_fees.lock().unwrap().insert(key, fees);
+		Ok(())
+	}
+
+	fn get_packet_fees(
+		&self,
+		key: &(PortId, ChannelId, Sequence),
+	) -> Result<Vec<PacketFee>, FeeError> {
+		Ok(self.packet_fees.lock().unwrap().get(key).cloned().unwrap_or_default())
+	}
+
+	fn delete_packet_fees(&mut self, key: &(PortId, ChannelId, Sequence)) -> Result<(), FeeError> {
+		self.packet_fees.lock().unwrap().remove(key);
+		Ok(())
+	}
+
+	fn store_counterparty_payee(
+		&mut self,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+		counterparty_payee: &Signer,
+	) -> Result<(), FeeError> {
+		self.counterparty_payees
+			.lock()
+			.unwrap()
+			.insert((*channel_id, relayer.to_string()), counterparty_payee.clone());
+		Ok(())
+	}
+
+	fn get_counterparty_payee(
+		&self,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<Option<Signer>, FeeError> {
+		Ok(self
+			.counterparty_payees
+			.lock()
+			.unwrap()
+			.get(&(*channel_id, relayer.to_string()))
+			.cloned())
+	}
+}
+
 impl<C: HostBlockType> ConnectionReader for DummyTransferModule<C> {
 	fn minimum_delay_period(&self) -> Duration {
 		Duration::from_secs(0)
@@ -431,6 +966,10 @@ impl<C: HostBlockType> ConnectionReader for DummyTransferModule<C> {
 		}
 	}
 
+	fn client_connections(&self, _client_id: &ClientId) -> Result<Vec<ConnectionId>, Ics03Error> {
+		todo!()
+	}
+
 	fn host_oldest_height(&self) -> Height {
 		todo!()
 	}
@@ -442,6 +981,17 @@ impl<C: HostBlockType> ConnectionReader for DummyTransferModule<C> {
 	fn connection_counter(&self) -> Result<u64, Ics03Error> {
 		todo!()
 	}
+
+	fn handshake_timeout(&self) -> u64 {
+		todo!()
+	}
+
+	fn connection_handshake_origin(
+		&self,
+		_conn_id: &ConnectionId,
+	) -> Result<crate::core::ics03_connection::handler::HandshakeOrigin, Ics03Error> {
+		todo!()
+	}
 }
 
 impl<C: HostBlockType> ClientReader for DummyTransferModule<C> {
@@ -501,10 +1051,25 @@ impl<C: HostBlockType> ClientReader for DummyTransferModule<C> {
 		todo!()
 	}
 
-	fn host_timestamp(&self) -> Timestamp {
+	fn oldest_consensus_state_height(
+		&self,
+		_client_id: &ClientId,
+	) -> Result<Option<Height>, Ics02Error> {
 		todo!()
 	}
 
+	fn next_consensus_state_height(
+		&self,
+		_client_id: &ClientId,
+		_height: Height,
+	) -> Result<Option<Height>, Ics02Error> {
+		todo!()
+	}
+
+	fn host_timestamp(&self) -> Timestamp {
+		Timestamp::now()
+	}
+
 	fn client_counter(&self) -> Result<u64, Ics02Error> {
 		todo!()
 	}
@@ -512,6 +1077,10 @@ impl<C: HostBlockType> ClientReader for DummyTransferModule<C> {
 	fn host_client_type(&self) -> String {
 		unimplemented!()
 	}
+
+	fn supported_client_types(&self) -> Vec<ClientType> {
+		C::supported_client_types()
+	}
 }
 
 impl<C: HostBlockType> ChannelReader for DummyTransferModule<C> {
@@ -552,9 +1121,12 @@ impl<C: HostBlockType> ChannelReader for DummyTransferModule<C> {
 
 	fn get_packet_commitment(
 		&self,
-		_key: &(PortId, ChannelId, Sequence),
+		key: &(PortId, ChannelId, Sequence),
 	) -> Result<PacketCommitment, Error> {
-		unimplemented!()
+		match self.ibc_store.lock().unwrap().packet_commitment.get(key) {
+			Some(commitment) => Ok(commitment.clone()),
+			None => Err(Error::packet_commitment_not_found(key.2)),
+		}
 	}
 
 	fn get_packet_receipt(&self, _key: &(PortId, ChannelId, Sequence)) -> Result<Receipt, Error> {
@@ -563,8 +1135,25 @@ impl<C: HostBlockType> ChannelReader for DummyTransferModule<C> {
 
 	fn get_packet_acknowledgement(
 		&self,
-		_key: &(PortId, ChannelId, Sequence),
+		key: &(PortId, ChannelId, Sequence),
 	) -> Result<AcknowledgementCommitment, Error> {
+		match self.ibc_store.lock().unwrap().packet_acknowledgement.get(key) {
+			Some(ack) => Ok(ack.clone()),
+			None => Err(Error::packet_acknowledgement_not_found(key.2)),
+		}
+	}
+
+	fn get_pruning_sequence_start(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<Sequence, Error> {
+		unimplemented!()
+	}
+
+	fn channel_upgrade_origin(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<UpgradeOrigin, Error> {
 		unimplemented!()
 	}
 
@@ -608,6 +1197,10 @@ impl<C: HostBlockType> ClientTypes for DummyTransferModule<C> {
 
 impl<C: HostBlockType> HostBlockType for DummyTransferModule<C> {
 	type HostBlock = C::HostBlock;
+
+	fn supported_client_types() -> Vec<ClientType> {
+		C::supported_client_types()
+	}
 }
 
 impl<C: HostBlockType> ClientKeeper for DummyTransferModule<C> {
@@ -658,6 +1251,30 @@ impl<C: HostBlockType> ClientKeeper for DummyTransferModule<C> {
 		Ok(())
 	}
 
+	fn delete_consensus_state(
+		&mut self,
+		_client_id: ClientId,
+		_height: Height,
+	) -> Result<(), Ics02Error> {
+		todo!()
+	}
+
+	fn delete_update_time(
+		&mut self,
+		_client_id: ClientId,
+		_height: Height,
+	) -> Result<(), Ics02Error> {
+		todo!()
+	}
+
+	fn delete_update_height(
+		&mut self,
+		_client_id: ClientId,
+		_height: Height,
+	) -> Result<(), Ics02Error> {
+		todo!()
+	}
+
 	fn validate_self_client(&self, _client_state: &Self::AnyClientState) -> Result<(), Ics02Error> {
 		Ok(())
 	}
@@ -667,4 +1284,12 @@ impl<C: HostBlockType> Ics20Context for DummyTransferModule<C> {
 	type AccountId = Signer;
 }
 
+impl<C: HostBlockType> NftTransferContext for DummyTransferModule<C> {
+	type AccountId = Signer;
+}
+
+impl<C: HostBlockType> IcaControllerContext for DummyTransferModule<C> {}
+
+impl<C: HostBlockType> IcaHostContext for DummyTransferModule<C> {}
+
 impl<C: HostBlockType> ReaderContext for DummyTransferModule<C> {}
@@ -64,14 +64,17 @@ impl CompileCmd {
 	fn compile_ibc_protos(ibc_dir: &Path, out_dir: &Path) {
 		println!("[info ] Compiling IBC .proto files to Rust into '{}'...", out_dir.display());
 
+		let staged = TempDir::new("ibc-proto-staged").unwrap();
+		Self::stage_ibc_protos(ibc_dir, staged.as_ref());
+
 		// Paths
 		let proto_paths = [
-			// ibc-go proto files
-			format!("{}/proto/ibc", ibc_dir.display()),
+			// ibc-go proto files, with the local definitions staged over them
+			format!("{}/ibc", staged.as_ref().display()),
 		];
 
 		let proto_includes_paths = [
-			format!("{}/proto", ibc_dir.display()),
+			format!("{}", staged.as_ref().display()),
 			format!("{}/third_party/proto", ibc_dir.display()),
 		];
 
@@ -147,6 +150,9 @@ impl CompileCmd {
 			.type_attribute(".ibc.core.connection.v1.Version", attrs_jsonschema)
 			.type_attribute(".ibc.core.types.v1", attrs_serde)
 			.type_attribute(".ibc.applications.transfer.v1", attrs_serde)
+			.type_attribute(".ibc.applications.nft_transfer.v1.ClassTrace", attrs_serde)
+			.type_attribute(".ibc.applications.nft_transfer.v1.MsgTransfer", attrs_serde)
+			.type_attribute(".ibc.applications.nft_transfer.v1.MsgTransferResponse", attrs_serde)
 			.type_attribute(".ibc.applications.interchain_accounts.controller.v1", attrs_serde)
 			.type_attribute(".ics23", attrs_serde)
 			.type_attribute(".ics23.LeafOp", attrs_eq)
@@ -182,6 +188,30 @@ impl CompileCmd {
 		}
 	}
 
+	/// Copies the ibc-go proto files into `staged_dir`, then the local definitions over them. A
+	/// local file replaces the ibc-go file at the same path, see `proto/definitions/ibc`.
+	fn stage_ibc_protos(ibc_dir: &Path, staged_dir: &Path) {
+		let root = env!("CARGO_MANIFEST_DIR");
+		let sources =
+			[ibc_dir.join("proto"), PathBuf::from(format!("{}/../proto/definitions", root))];
+
+		for source in &sources {
+			println!("[info ] Staging proto files from {:?}", source);
+			for entry in WalkDir::new(source)
+				.into_iter()
+				.filter_map(|e| e.ok())
+				.filter(|e| e.file_type().is_file())
+			{
+				let target = staged_dir.join(entry.path().strip_prefix(source).unwrap());
+				create_dir_all(target.parent().unwrap()).unwrap();
+				if let Err(e) = copy(entry.path(), &target) {
+					println!("[error] Error while staging {:?}: {}", entry.path(), e);
+					process::exit(1);
+				}
+			}
+		}
+	}
+
 	fn compile_sdk_protos(sdk_dir: &Path, out_dir: &Path, ibc_dep: Option<PathBuf>) {
 		println!(
 			"[info ] Compiling Cosmos-SDK .proto files to Rust into '{}'...",
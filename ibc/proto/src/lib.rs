@@ -144,6 +144,16 @@ pub mod ibc {
 		pub use super::applications::*;
 	}
 	pub mod applications {
+		pub mod fee {
+			pub mod v1 {
+				include_proto!("ibc.applications.fee.v1.rs");
+			}
+		}
+		pub mod nft_transfer {
+			pub mod v1 {
+				include_proto!("ibc.applications.nft_transfer.v1.rs");
+			}
+		}
 		pub mod transfer {
 			pub mod v1 {
 				include_proto!("ibc.applications.transfer.v1.rs");
@@ -0,0 +1,81 @@
+/// ClassTrace contains the base class id for ICS721 non fungible tokens and the
+/// source tracing information path.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClassTrace {
+    /// path defines the chain of port/channel identifiers used for tracing the
+    /// source of the non fungible token.
+    #[prost(string, tag="1")]
+    pub path: ::prost::alloc::string::String,
+    /// base class id of the relayed non fungible token.
+    #[prost(string, tag="2")]
+    pub base_class_id: ::prost::alloc::string::String,
+}
+/// NonFungibleTokenPacketData defines a struct for the packet payload
+/// See NonFungibleTokenPacketData spec:
+/// <https://github.com/cosmos/ibc/tree/master/spec/app/ics-721-nft-transfer#data-structures>
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NonFungibleTokenPacketData {
+    /// the class_id of the tokens to be transferred
+    #[prost(string, tag="1")]
+    pub class_id: ::prost::alloc::string::String,
+    /// the class_uri of the tokens to be transferred
+    #[prost(string, tag="2")]
+    pub class_uri: ::prost::alloc::string::String,
+    /// the non fungible tokens to be transferred
+    #[prost(string, repeated, tag="3")]
+    pub token_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// the non fungible tokens's uri to be transferred
+    #[prost(string, repeated, tag="4")]
+    pub token_uris: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// the sender address
+    #[prost(string, tag="5")]
+    pub sender: ::prost::alloc::string::String,
+    /// the recipient address on the destination chain
+    #[prost(string, tag="6")]
+    pub receiver: ::prost::alloc::string::String,
+    /// optional memo
+    #[prost(string, tag="7")]
+    pub memo: ::prost::alloc::string::String,
+}
+/// MsgTransfer defines a msg to transfer non fungible tokens between
+/// ICS721 enabled chains. See ICS Spec here:
+/// <https://github.com/cosmos/ibc/tree/master/spec/app/ics-721-nft-transfer#data-structures>
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgTransfer {
+    /// the port on which the packet will be sent
+    #[prost(string, tag="1")]
+    pub source_port: ::prost::alloc::string::String,
+    /// the channel by which the packet will be sent
+    #[prost(string, tag="2")]
+    pub source_channel: ::prost::alloc::string::String,
+    /// the class_id of tokens to be transferred
+    #[prost(string, tag="3")]
+    pub class_id: ::prost::alloc::string::String,
+    /// the non fungible tokens to be transferred
+    #[prost(string, repeated, tag="4")]
+    pub token_ids: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// the sender address
+    #[prost(string, tag="5")]
+    pub sender: ::prost::alloc::string::String,
+    /// the recipient address on the destination chain
+    #[prost(string, tag="6")]
+    pub receiver: ::prost::alloc::string::String,
+    /// Timeout height relative to the current block height.
+    /// The timeout is disabled when set to 0.
+    #[prost(message, optional, tag="7")]
+    pub timeout_height: ::core::option::Option<super::super::super::core::client::v1::Height>,
+    /// Timeout timestamp in absolute nanoseconds since unix epoch.
+    /// The timeout is disabled when set to 0.
+    #[prost(uint64, tag="8")]
+    pub timeout_timestamp: u64,
+    /// optional memo
+    #[prost(string, tag="9")]
+    pub memo: ::prost::alloc::string::String,
+}
+/// MsgTransferResponse defines the Msg/Transfer response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgTransferResponse {
+}
@@ -21,6 +21,9 @@ pub struct Channel {
     /// opaque channel version, which is agreed upon during the handshake
     #[prost(string, tag="5")]
     pub version: ::prost::alloc::string::String,
+    /// sequence of the latest upgrade attempted on this channel
+    #[prost(uint64, tag="6")]
+    pub upgrade_sequence: u64,
 }
 /// IdentifiedChannel defines a channel with additional port and channel
 /// identifier fields.
@@ -49,6 +52,9 @@ pub struct IdentifiedChannel {
     /// channel identifier
     #[prost(string, tag="7")]
     pub channel_id: ::prost::alloc::string::String,
+    /// sequence of the latest upgrade attempted on this channel
+    #[prost(uint64, tag="8")]
+    pub upgrade_sequence: u64,
 }
 /// Counterparty defines a channel end counterparty
 #[derive(::serde::Serialize, ::serde::Deserialize)]
@@ -173,6 +179,14 @@ pub enum State {
     /// A channel has been closed and can no longer be used to send or receive
     /// packets.
     Closed = 4,
+    /// A channel has just accepted the upgrade handshake attempt and is flushing in-flight packets.
+    Flushing = 5,
+    /// A channel has just completed flushing any in-flight packets.
+    Flushcomplete = 6,
+    /// A channel has proposed an upgrade of its ordering or version.
+    Initupgrade = 7,
+    /// A channel has accepted an upgrade proposed on the counterparty chain.
+    Tryupgrade = 8,
 }
 impl State {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -186,6 +200,10 @@ impl State {
             State::Tryopen => "STATE_TRYOPEN",
             State::Open => "STATE_OPEN",
             State::Closed => "STATE_CLOSED",
+            State::Flushing => "STATE_FLUSHING",
+            State::Flushcomplete => "STATE_FLUSHCOMPLETE",
+            State::Initupgrade => "STATE_INITUPGRADE",
+            State::Tryupgrade => "STATE_TRYUPGRADE",
         }
     }
 }
@@ -201,6 +219,9 @@ pub enum Order {
     Unordered = 1,
     /// packets are delivered exactly in the order which they were sent
     Ordered = 2,
+    /// packets are delivered in the order which they were sent, but a packet that
+    /// times out does not close the channel
+    OrderedAllowTimeout = 3,
 }
 impl Order {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -212,6 +233,7 @@ impl Order {
             Order::NoneUnspecified => "ORDER_NONE_UNSPECIFIED",
             Order::Unordered => "ORDER_UNORDERED",
             Order::Ordered => "ORDER_ORDERED",
+            Order::OrderedAllowTimeout => "ORDER_ORDERED_ALLOW_TIMEOUT",
         }
     }
 }
@@ -387,6 +409,148 @@ pub struct MsgChannelCloseConfirm {
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MsgChannelCloseConfirmResponse {
 }
+/// MsgChannelCloseFrozen defines a msg sent by a Relayer to close a channel whose
+/// underlying client has been frozen, without a proof from the counterparty.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelCloseFrozen {
+    #[prost(string, tag="1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(string, tag="3")]
+    pub client_id: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgChannelCloseFrozenResponse defines the Msg/ChannelCloseFrozen response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelCloseFrozenResponse {
+}
+/// UpgradeFields are the channel end fields that may be changed by a channel
+/// upgrade.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpgradeFields {
+    #[prost(enumeration="Order", tag="1")]
+    pub ordering: i32,
+    #[prost(string, tag="2")]
+    pub version: ::prost::alloc::string::String,
+}
+/// MsgChannelUpgradeInit defines a msg sent by a Relayer to Chain A to propose
+/// an upgrade of an OPEN channel with Chain B.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeInit {
+    #[prost(string, tag="1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="3")]
+    pub fields: ::core::option::Option<UpgradeFields>,
+    #[prost(string, tag="4")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgChannelUpgradeInitResponse defines the Msg/ChannelUpgradeInit response
+/// type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeInitResponse {
+}
+/// MsgChannelUpgradeTry defines a msg sent by a Relayer to Chain B to accept
+/// the upgrade proposed on Chain A.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeTry {
+    #[prost(string, tag="1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag="3")]
+    pub counterparty_upgrade_fields: ::core::option::Option<UpgradeFields>,
+    #[prost(uint64, tag="4")]
+    pub counterparty_upgrade_sequence: u64,
+    #[prost(bytes="vec", tag="5")]
+    pub proof_channel: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag="6")]
+    pub proof_height: ::core::option::Option<super::super::client::v1::Height>,
+    #[prost(string, tag="7")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgChannelUpgradeTryResponse defines the Msg/ChannelUpgradeTry response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeTryResponse {
+}
+/// MsgChannelUpgradeAck defines a msg sent by a Relayer to Chain A to
+/// acknowledge the change of channel state to TRYUPGRADE on Chain B.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeAck {
+    #[prost(string, tag="1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag="3")]
+    pub counterparty_upgrade_sequence: u64,
+    #[prost(bytes="vec", tag="4")]
+    pub proof_channel: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag="5")]
+    pub proof_height: ::core::option::Option<super::super::client::v1::Height>,
+    #[prost(string, tag="6")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgChannelUpgradeAckResponse defines the Msg/ChannelUpgradeAck response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeAckResponse {
+}
+/// MsgChannelUpgradeConfirm defines a msg sent by a Relayer to Chain B to
+/// acknowledge the completion of the upgrade on Chain A.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeConfirm {
+    #[prost(string, tag="1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="3")]
+    pub proof_channel: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag="4")]
+    pub proof_height: ::core::option::Option<super::super::client::v1::Height>,
+    #[prost(string, tag="5")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgChannelUpgradeConfirmResponse defines the Msg/ChannelUpgradeConfirm
+/// response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeConfirmResponse {
+}
+/// MsgChannelUpgradeCancel defines a msg sent to abort an upgrade in progress
+/// and restore the channel end it replaced. The proof of the counterparty
+/// channel end is only required when cancelling from TRYUPGRADE.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeCancel {
+    #[prost(string, tag="1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(bytes="vec", tag="3")]
+    pub proof_channel: ::prost::alloc::vec::Vec<u8>,
+    #[prost(message, optional, tag="4")]
+    pub proof_height: ::core::option::Option<super::super::client::v1::Height>,
+    #[prost(string, tag="5")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgChannelUpgradeCancelResponse defines the Msg/ChannelUpgradeCancel
+/// response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgChannelUpgradeCancelResponse {
+}
 /// MsgRecvPacket receives incoming IBC packet
 #[derive(::serde::Serialize, ::serde::Deserialize)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -445,6 +609,10 @@ pub struct MsgTimeoutOnClose {
     pub next_sequence_recv: u64,
     #[prost(string, tag="6")]
     pub signer: ::prost::alloc::string::String,
+    /// optional connection whose client proves the closed counterparty channel,
+    /// defaults to the packet's connection when empty
+    #[prost(string, tag="7")]
+    pub proof_close_connection_id: ::prost::alloc::string::String,
 }
 /// MsgTimeoutOnCloseResponse defines the Msg/TimeoutOnClose response type.
 #[derive(::serde::Serialize, ::serde::Deserialize)]
@@ -475,6 +643,28 @@ pub struct MsgAcknowledgementResponse {
     #[prost(enumeration="ResponseResultType", tag="1")]
     pub result: i32,
 }
+/// MsgPruneAcknowledgements defines the request type for the PruneAcknowledgements rpc.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPruneAcknowledgements {
+    #[prost(string, tag="1")]
+    pub port_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub channel_id: ::prost::alloc::string::String,
+    #[prost(uint64, tag="3")]
+    pub limit: u64,
+    #[prost(string, tag="4")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgPruneAcknowledgementsResponse defines the response type for the PruneAcknowledgements rpc.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgPruneAcknowledgementsResponse {
+    /// Number of sequences pruned (includes both packet acknowledgements and packet receipts where
+    /// appropriate).
+    #[prost(uint64, tag="1")]
+    pub total_pruned_sequences: u64,
+}
 /// ResponseResultType defines the possible outcomes of the execution of a message
 #[derive(::serde::Serialize, ::serde::Deserialize)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
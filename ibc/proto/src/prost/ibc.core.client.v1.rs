@@ -191,6 +191,26 @@ pub struct MsgUpdateClient {
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MsgUpdateClientResponse {
 }
+/// MsgUpdateClientBatch defines an sdk.Msg to update a IBC client state using
+/// several headers at once.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpdateClientBatch {
+    /// client unique identifier
+    #[prost(string, tag="1")]
+    pub client_id: ::prost::alloc::string::String,
+    /// headers to update the light client with, in ascending height order
+    #[prost(message, repeated, tag="2")]
+    pub client_messages: ::prost::alloc::vec::Vec<super::super::super::super::google::protobuf::Any>,
+    /// signer address
+    #[prost(string, tag="3")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgUpdateClientBatchResponse defines the Msg/UpdateClientBatch response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgUpdateClientBatchResponse {
+}
 /// MsgUpgradeClient defines an sdk.Msg to upgrade an IBC client to a new client
 /// state
 #[derive(::serde::Serialize, ::serde::Deserialize)]
@@ -242,6 +262,26 @@ pub struct MsgSubmitMisbehaviour {
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MsgSubmitMisbehaviourResponse {
 }
+/// MsgRecoverClient defines the message used to recover a frozen or expired client.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRecoverClient {
+    /// the client identifier for the client to be updated if the proposal passes
+    #[prost(string, tag="1")]
+    pub subject_client_id: ::prost::alloc::string::String,
+    /// the substitute client identifier for the client which will replace the subject
+    /// client
+    #[prost(string, tag="2")]
+    pub substitute_client_id: ::prost::alloc::string::String,
+    /// signer address
+    #[prost(string, tag="3")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgRecoverClientResponse defines the Msg/RecoverClient response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgRecoverClientResponse {
+}
 /// Generated client implementations.
 #[cfg(feature = "client")]
 pub mod msg_client {
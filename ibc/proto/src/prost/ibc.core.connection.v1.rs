@@ -275,6 +275,21 @@ pub struct MsgConnectionOpenConfirm {
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct MsgConnectionOpenConfirmResponse {
 }
+/// MsgConnectionCancel defines a msg sent by a Relayer to abort a connection
+/// handshake that has not progressed past TRYOPEN.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgConnectionCancel {
+    #[prost(string, tag="1")]
+    pub connection_id: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub signer: ::prost::alloc::string::String,
+}
+/// MsgConnectionCancelResponse defines the Msg/ConnectionCancel response type.
+#[derive(::serde::Serialize, ::serde::Deserialize)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MsgConnectionCancelResponse {
+}
 /// Generated client implementations.
 #[cfg(feature = "client")]
 pub mod msg_client {
@@ -29,7 +29,8 @@ use ibc::core::{
 	},
 	ics23_commitment::{
 		commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
-		merkle::{apply_prefix, MerkleProof},
+		error::Error as Ics23Error,
+		merkle::{ApplyPrefix, MerkleProof},
 	},
 	ics24_host::{
 		identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -41,7 +42,10 @@ use ibc::core::{
 	},
 	ics26_routing::context::ReaderContext,
 };
-use ibc_proto::ibc::core::commitment::v1::MerkleProof as RawMerkleProof;
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::commitment::v1::{MerklePath, MerkleProof as RawMerkleProof},
+};
 use prost::Message;
 use tendermint_light_client_verifier::{
 	types::{TrustedBlockState, UntrustedBlockState},
@@ -51,13 +55,18 @@ use tendermint_proto::Protobuf;
 
 use crate::{
 	client_message::{ClientMessage, Header},
-	client_state::ClientState,
-	consensus_state::ConsensusState,
+	client_state::{ClientState, TENDERMINT_CLIENT_STATE_TYPE_URL},
+	consensus_state::{ConsensusState, SENTINEL_ROOT, TENDERMINT_CONSENSUS_STATE_TYPE_URL},
 	error::Error,
 	HostFunctionsProvider,
 };
 use ibc::{prelude::*, timestamp::Timestamp, Height};
 
+/// Key under which the upgraded client state is committed, following the upgrade path.
+pub const UPGRADED_CLIENT_STATE_KEY: &str = "upgradedClient";
+/// Key under which the upgraded consensus state is committed, following the upgrade path.
+pub const UPGRADED_CONSENSUS_STATE_KEY: &str = "upgradedConsState";
+
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct TendermintClient<H>(PhantomData<H>);
 
@@ -205,15 +214,13 @@ where
 		client_state: Self::ClientState,
 		client_message: Self::ClientMessage,
 	) -> Result<Self::ClientState, Ics02Error> {
-		let misbehaviour = match client_message {
-			ClientMessage::Misbehaviour(misbehaviour) => misbehaviour,
-			_ => unreachable!(
-				"02-client will check for misbehaviour before calling update_state_on_misbehaviour; qed"
-			),
+		// A header conflicting with the stored consensus states freezes the client just like an
+		// explicit misbehaviour does.
+		let frozen_height = match client_message {
+			ClientMessage::Header(header) => header.height(),
+			ClientMessage::Misbehaviour(misbehaviour) => misbehaviour.header1.height(),
 		};
-		client_state
-			.with_frozen_height(misbehaviour.header1.height())
-			.map_err(|e| e.into())
+		client_state.with_frozen_height(frozen_height).map_err(|e| e.into())
 	}
 
 	fn check_for_misbehaviour<Ctx: ReaderContext>(
@@ -274,15 +281,11 @@ where
 
 					if let Some(next_cs) = maybe_next_cs {
 						// New (untrusted) header timestamp cannot occur after next
-						// consensus state's height
-						if Timestamp::from(header.signed_header.header().time).nanoseconds() >
+						// consensus state's height, a valid header breaking this is misbehaviour.
+						if Timestamp::from(header.signed_header.header().time).nanoseconds() >=
 							next_cs.timestamp().nanoseconds()
 						{
-							return Err(Error::header_timestamp_too_high(
-								header.signed_header.header().time.to_string(),
-								next_cs.timestamp().to_string(),
-							)
-							.into())
+							return Ok(true)
 						}
 					}
 				}
@@ -301,18 +304,41 @@ where
 
 					if let Some(prev_cs) = maybe_prev_cs {
 						// New (untrusted) header timestamp cannot occur before the
-						// previous consensus state's height
-						if header.signed_header.header().time < prev_cs.timestamp {
-							return Err(Error::header_timestamp_too_low(
-								header.signed_header.header().time.to_string(),
-								prev_cs.timestamp.to_string(),
-							)
-							.into())
+						// previous consensus state's height, a valid header breaking this is
+						// misbehaviour.
+						if header.signed_header.header().time <= prev_cs.timestamp {
+							return Ok(true)
 						}
 					}
 				}
 			},
-			ClientMessage::Misbehaviour(_misbehaviour) => return Ok(true),
+			ClientMessage::Misbehaviour(misbehaviour) => {
+				let (header1, header2) = (&misbehaviour.header1, &misbehaviour.header2);
+				// Both headers have been verified, so two distinct blocks at the same height
+				// fork the chain.
+				if header1.height() == header2.height() {
+					if header1.signed_header.commit.block_id.hash !=
+						header2.signed_header.commit.block_id.hash
+					{
+						return Ok(true)
+					}
+				} else {
+					// BFT time must increase with the height.
+					let (later, earlier) = if header1.height() > header2.height() {
+						(header1, header2)
+					} else {
+						(header2, header1)
+					};
+					if later.signed_header.header().time <= earlier.signed_header.header().time {
+						return Ok(true)
+					}
+				}
+				return Err(Error::misbehaviour_headers_not_conflicting(
+					header1.height(),
+					header2.height(),
+				)
+				.into())
+			},
 		};
 
 		Ok(false)
@@ -320,16 +346,109 @@ where
 
 	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
-		_client_id: ClientId,
-		_old_client_state: &Self::ClientState,
-		_upgrade_client_state: &Self::ClientState,
-		_upgrade_consensus_state: &Self::ConsensusState,
-		_proof_upgrade_client: Vec<u8>,
-		_proof_upgrade_consensus_state: Vec<u8>,
+		ctx: &Ctx,
+		client_id: ClientId,
+		old_client_state: &Self::ClientState,
+		upgrade_client_state: &Self::ClientState,
+		upgrade_consensus_state: &Self::ConsensusState,
+		proof_upgrade_client: Vec<u8>,
+		proof_upgrade_consensus_state: Vec<u8>,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
-		// TODO:
-		Err(Ics02Error::implementation_specific("Not implemented".to_string()))
+		if old_client_state.upgrade_path.is_empty() {
+			return Err(Error::empty_upgrade_path().into())
+		}
+
+		// The upgrade plan is read from the latest consensus state, the last height of the old
+		// chain.
+		let last_height = old_client_state.latest_height;
+		let upgrade_path = &old_client_state.upgrade_path;
+		let consensus_state: Self::ConsensusState = ctx
+			.consensus_state(&client_id, last_height)?
+			.downcast()
+			.ok_or(Ics02Error::client_args_type_mismatch(
+				ClientState::<H>::client_type().to_owned(),
+			))?;
+
+		// The chain commits the upgraded client state without the client chosen parameters.
+		let committed_client_state = Any {
+			type_url: TENDERMINT_CLIENT_STATE_TYPE_URL.to_string(),
+			value: upgrade_client_state.clone().zero_custom_fields().encode_vec(),
+		};
+		verify_upgrade_membership(
+			old_client_state,
+			&consensus_state.root,
+			proof_upgrade_client,
+			upgrade_merkle_path(upgrade_path, last_height, UPGRADED_CLIENT_STATE_KEY),
+			committed_client_state.encode_to_vec(),
+		)
+		.map_err(Ics02Error::invalid_upgrade_client_proof)?;
+
+		let committed_consensus_state = Any {
+			type_url: TENDERMINT_CONSENSUS_STATE_TYPE_URL.to_string(),
+			value: upgrade_consensus_state.encode_vec(),
+		};
+		verify_upgrade_membership(
+			old_client_state,
+			&consensus_state.root,
+			proof_upgrade_consensus_state,
+			upgrade_merkle_path(upgrade_path, last_height, UPGRADED_CONSENSUS_STATE_KEY),
+			committed_consensus_state.encode_to_vec(),
+		)
+		.map_err(Ics02Error::invalid_upgrade_consensus_state_proof)?;
+
+		// Parameters chosen by the chain come from the committed client state, the ones chosen
+		// by the client are kept from the old client state rather than taken from the message.
+		let new_client_state = ClientState {
+			chain_id: upgrade_client_state.chain_id.clone(),
+			unbonding_period: upgrade_client_state.unbonding_period,
+			latest_height: upgrade_client_state.latest_height,
+			proof_specs: upgrade_client_state.proof_specs.clone(),
+			upgrade_path: upgrade_client_state.upgrade_path.clone(),
+			frozen_height: None,
+			..old_client_state.clone()
+		};
+
+		// Headers of the upgraded chain are verified against the last validators of the old
+		// chain, its root is only a stand-in.
+		let new_consensus_state = ConsensusState::new(
+			CommitmentRoot::from_bytes(SENTINEL_ROOT),
+			upgrade_consensus_state.timestamp,
+			upgrade_consensus_state.next_validators_hash,
+		);
+		let cs = Ctx::AnyConsensusState::wrap(&new_consensus_state).ok_or_else(|| {
+			Ics02Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+		})?;
+
+		Ok((new_client_state, ConsensusUpdateResult::Single(cs)))
+	}
+
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<Self::ClientState, Ics02Error> {
+		// The latest height, frozen height, trusting period and chain id are allowed to differ,
+		// every other parameter must match between the two clients.
+		let new_client_state =
+			ClientState { frozen_height: None, ..substitute_client_state.clone() };
+		let expected = ClientState {
+			chain_id: substitute_client_state.chain_id,
+			trusting_period: substitute_client_state.trusting_period,
+			latest_height: substitute_client_state.latest_height,
+			frozen_height: None,
+			..old_client_state
+		};
+
+		if expected != new_client_state {
+			return Err(Ics02Error::substitute_client_state_mismatch(
+				"tendermint client parameters differ".to_string(),
+			))
+		}
+
+		Ok(new_client_state)
 	}
 
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
@@ -541,16 +660,49 @@ where
 	P: Into<Path>,
 	H: ics23::HostFunctionsProvider,
 {
-	let merkle_path = apply_prefix(prefix, vec![path.into().to_string()]);
+	let merkle_path = MerklePath::try_from_prefix_and_path(prefix, vec![path.into().to_string()])
+		.map_err(|e| Ics02Error::from(Error::ics23_error(e)))?;
 	let merkle_proof: MerkleProof<H> = RawMerkleProof::try_from(proof.clone())
 		.map_err(Ics02Error::invalid_commitment_proof)?
 		.into();
 
 	merkle_proof
-		.verify_membership(&client_state.proof_specs, root.clone().into(), merkle_path, value, 0)
+		.verify_membership(&client_state.proof_specs(), root.clone().into(), merkle_path, value, 0)
 		.map_err(|e| Error::ics23_error(e).into())
 }
 
+/// Key path of a value committed for an upgrade at `last_height`, under the last key of
+/// `upgrade_path`.
+fn upgrade_merkle_path(upgrade_path: &[String], last_height: Height, key: &str) -> MerklePath {
+	let (last_key, store_path) =
+		upgrade_path.split_last().expect("the upgrade path was checked to be non empty; qed");
+	let mut key_path = store_path.to_vec();
+	key_path.push(format!("{}/{}/{}", last_key, last_height.revision_height, key));
+	MerklePath { key_path }
+}
+
+fn verify_upgrade_membership<H>(
+	client_state: &ClientState<H>,
+	root: &CommitmentRoot,
+	proof: Vec<u8>,
+	merkle_path: MerklePath,
+	value: Vec<u8>,
+) -> Result<(), Ics23Error>
+where
+	H: ics23::HostFunctionsProvider,
+{
+	let merkle_proof: MerkleProof<H> = RawMerkleProof::decode(proof.as_slice())
+		.map_err(Ics23Error::invalid_raw_merkle_proof)?
+		.into();
+	merkle_proof.verify_membership(
+		&client_state.proof_specs(),
+		root.clone().into(),
+		merkle_path,
+		value,
+		0,
+	)
+}
+
 fn verify_non_membership<H, P>(
 	client_state: &ClientState<H>,
 	prefix: &CommitmentPrefix,
@@ -562,17 +714,18 @@ where
 	P: Into<Path>,
 	H: ics23::HostFunctionsProvider,
 {
-	let merkle_path = apply_prefix(prefix, vec![path.into().to_string()]);
+	let merkle_path = MerklePath::try_from_prefix_and_path(prefix, vec![path.into().to_string()])
+		.map_err(|e| Ics02Error::from(Error::ics23_error(e)))?;
 	let merkle_proof: MerkleProof<H> = RawMerkleProof::try_from(proof.clone())
 		.map_err(Ics02Error::invalid_commitment_proof)?
 		.into();
 
 	merkle_proof
-		.verify_non_membership(&client_state.proof_specs, root.clone().into(), merkle_path)
+		.verify_non_membership(&client_state.proof_specs(), root.clone().into(), merkle_path)
 		.map_err(|e| Error::ics23_error(e).into())
 }
 
-fn verify_delay_passed<Ctx: ReaderContext>(
+pub(crate) fn verify_delay_passed<Ctx: ReaderContext>(
 	ctx: &Ctx,
 	height: Height,
 	connection_end: &ConnectionEnd,
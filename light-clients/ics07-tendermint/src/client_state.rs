@@ -27,6 +27,8 @@ use tendermint_proto::Protobuf;
 
 use ibc_proto::ibc::lightclients::tendermint::v1::ClientState as RawClientState;
 
+pub const TENDERMINT_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.ClientState";
+
 use crate::{
 	client_def::TendermintClient, client_message::Header, error::Error, HostFunctionsProvider,
 };
@@ -147,6 +149,11 @@ impl<H> ClientState<H> {
 		Ok(Self { frozen_height: Some(h), ..self })
 	}
 
+	/// Lift a freeze, for recovering a client that was frozen by governance.
+	pub fn unfreeze(self) -> Self {
+		Self { frozen_height: None, ..self }
+	}
+
 	/// Get the refresh time to ensure the state does not expire
 	pub fn refresh_time(&self) -> Option<Duration> {
 		Some(2 * self.trusting_period / 3)
@@ -181,6 +188,13 @@ impl<H> ClientState<H> {
 		self
 	}
 
+	/// Returns the client state with the fields chosen by the client, rather than by the chain,
+	/// set to zero values. This is the client state committed by a chain for an upgrade.
+	pub fn zero_custom_fields(mut self) -> Self {
+		self.reset();
+		self
+	}
+
 	fn reset(&mut self) {
 		self.trusting_period = ZERO_DURATION;
 		self.trust_level = TrustThreshold::ZERO;
@@ -289,6 +303,14 @@ where
 		self.expired(elapsed)
 	}
 
+	fn trusting_period(&self) -> Duration {
+		self.trusting_period
+	}
+
+	fn proof_specs(&self) -> ProofSpecs {
+		self.proof_specs.clone()
+	}
+
 	fn encode_to_vec(&self) -> Vec<u8> {
 		self.encode_vec()
 	}
@@ -24,6 +24,13 @@ use crate::{client_message::Header, error::Error};
 use ibc::{core::ics23_commitment::commitment::CommitmentRoot, timestamp::Timestamp};
 use ibc_proto::ibc::lightclients::tendermint::v1::ConsensusState as RawConsensusState;
 
+pub const TENDERMINT_CONSENSUS_STATE_TYPE_URL: &str =
+	"/ibc.lightclients.tendermint.v1.ConsensusState";
+
+/// Commitment root of the consensus state installed by a client upgrade. The root of the first
+/// block of the upgraded chain cannot be known in advance, so no proof verifies against it.
+pub const SENTINEL_ROOT: &[u8] = b"sentinel_root";
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ConsensusState {
 	pub timestamp: Time,
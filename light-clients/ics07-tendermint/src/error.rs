@@ -122,6 +122,15 @@ define_error! {
 			{ reason: String }
 			| e | { format_args!("invalid raw misbehaviour: {}", e.reason) },
 
+		MisbehaviourHeadersNotConflicting
+			{
+				height1: Height,
+				height2: Height,
+			}
+			| e | {
+				format_args!("misbehaviour headers at heights {0} and {1} neither fork the chain nor break time monotonicity", e.height1, e.height2)
+			},
+
 		Decode
 			[ TraceError<prost::DecodeError> ]
 			| _ | { "decode error" },
@@ -287,6 +296,9 @@ define_error! {
 			| e | {
 				format_args!("the client is frozen: frozen_height={0} target_height={1}", e.frozen_height, e.target_height)
 			},
+
+		EmptyUpgradePath
+			| _ | { "cannot upgrade the client, no upgrade path is set" },
 	}
 }
 
@@ -59,33 +59,65 @@ mod tests {
 	};
 
 	use crate::{
-		client_message::test_util::{get_dummy_ics07_header, get_dummy_tendermint_header},
-		mock::{AnyClientState, AnyConsensusState, MockClientTypes},
+		client_def::{verify_delay_passed, TendermintClient},
+		client_message::{
+			test_util::{get_dummy_ics07_header, get_dummy_tendermint_header},
+			Header, Misbehaviour,
+		},
+		client_state::TENDERMINT_CLIENT_STATE_TYPE_URL,
+		consensus_state::{
+			ConsensusState as TendermintConsensusState, SENTINEL_ROOT,
+			TENDERMINT_CONSENSUS_STATE_TYPE_URL,
+		},
+		mock::{host::MockHostBlock, AnyClientState, AnyConsensusState, Crypto, MockClientTypes},
 	};
 
 	use crate::{client_message::ClientMessage, mock::AnyClientMessage};
 	use ibc::{
 		core::{
 			ics02_client::{
-				context::ClientReader,
+				client_def::{ClientDef, ConsensusUpdateResult},
+				client_state::ClientState as _,
+				context::{ClientKeeper, ClientReader},
+				error::ErrorDetail,
 				handler::{dispatch, ClientResult},
 				msgs::{
-					create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient, ClientMsg,
+					create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient,
+					update_client_batch::MsgUpdateAnyClientBatch,
+					upgrade_client::MsgUpgradeAnyClient, ClientMsg,
 				},
 				trust_threshold::TrustThreshold,
 			},
-			ics23_commitment::specs::ProofSpecs,
-			ics24_host::identifier::ClientId,
+			ics03_connection::{
+				connection::{ConnectionEnd, Counterparty, State},
+				version::get_compatible_versions,
+			},
+			ics23_commitment::{commitment::CommitmentRoot, specs::ProofSpecs},
+			ics24_host::identifier::{ChainId, ClientId},
+			ics26_routing::context::ReaderContext,
 		},
 		events::IbcEvent,
 		handler::HandlerOutput,
 		mock::context::MockContext,
 		prelude::*,
 		test_utils::get_dummy_account_id,
+		timestamp::Timestamp,
 		Height,
 	};
-	use ibc_proto::ibc::core::client::v1::{MsgCreateClient, MsgUpdateClient};
+	use ibc_proto::{
+		google::protobuf::Any,
+		ibc::core::{
+			client::v1::{MsgCreateClient, MsgUpdateClient},
+			commitment::v1::MerkleProof as RawMerkleProof,
+		},
+		ics23::{
+			commitment_proof::Proof, CommitmentProof, ExistenceProof, HashOp, InnerOp, LeafOp,
+			LengthOp,
+		},
+	};
+	use prost::Message;
 	use std::time::Duration;
+	use tendermint_proto::Protobuf;
 	use test_log::test;
 
 	#[test]
@@ -187,4 +219,455 @@ mod tests {
 		assert_eq!(msg, msg_back);
 		assert_eq!(raw, raw_back);
 	}
+
+	const UPGRADE_PATH: [&str; 2] = ["upgrade", "upgradedIBCState"];
+
+	fn sha256(message: &[u8]) -> Vec<u8> {
+		<Crypto as ics23::HostFunctionsProvider>::sha2_256(message).to_vec()
+	}
+
+	fn leaf_op(prefix: Vec<u8>) -> LeafOp {
+		LeafOp {
+			hash: HashOp::Sha256.into(),
+			prehash_key: HashOp::NoHash.into(),
+			prehash_value: HashOp::Sha256.into(),
+			length: LengthOp::VarProto.into(),
+			prefix,
+		}
+	}
+
+	/// Leaf hash of `key` and `value`, for leaf specs hashing the value and length prefixing
+	/// both with a varint, as the IAVL and Tendermint specs do.
+	fn leaf_hash(leaf: &LeafOp, key: &[u8], value: &[u8]) -> Vec<u8> {
+		let hashed_value = sha256(value);
+		let mut preimage = leaf.prefix.clone();
+		preimage.push(key.len() as u8);
+		preimage.extend_from_slice(key);
+		preimage.push(hashed_value.len() as u8);
+		preimage.extend_from_slice(&hashed_value);
+		sha256(&preimage)
+	}
+
+	/// Commits `client_state` and `consensus_state` the way the Cosmos-SDK upgrade module does,
+	/// in a two leaf IAVL store under the "upgrade" key of the multistore. Returns the multistore
+	/// root and the proofs of both values.
+	fn commit_upgrade(
+		last_height: Height,
+		client_state: Vec<u8>,
+		consensus_state: Vec<u8>,
+	) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+		let key = |suffix: &str| {
+			format!("{}/{}/{}", UPGRADE_PATH[1], last_height.revision_height, suffix).into_bytes()
+		};
+
+		// IAVL nodes are prefixed with their height, size and version, children with their length.
+		let iavl_leaf = leaf_op(vec![0, 2, 2]);
+		let inner_prefix = [2u8, 4, 2, 32];
+		let left = leaf_hash(&iavl_leaf, &key("upgradedClient"), &client_state);
+		let right = leaf_hash(&iavl_leaf, &key("upgradedConsState"), &consensus_state);
+		let store_root = sha256(&[&inner_prefix[..], &left, &[32], &right].concat());
+		let inner = |prefix: Vec<u8>, suffix: Vec<u8>| InnerOp {
+			hash: HashOp::Sha256.into(),
+			prefix,
+			suffix,
+		};
+		let client_proof = ExistenceProof {
+			key: key("upgradedClient"),
+			value: client_state,
+			leaf: Some(iavl_leaf.clone()),
+			path: vec![inner(inner_prefix.to_vec(), [&[32], &right[..]].concat())],
+		};
+		let consensus_proof = ExistenceProof {
+			key: key("upgradedConsState"),
+			value: consensus_state,
+			leaf: Some(iavl_leaf),
+			path: vec![inner([&inner_prefix[..], &left, &[32]].concat(), vec![])],
+		};
+
+		// The multistore commits to the root of the "upgrade" store.
+		let tendermint_leaf = leaf_op(vec![0]);
+		let root = leaf_hash(&tendermint_leaf, UPGRADE_PATH[0].as_bytes(), &store_root);
+		let multistore_proof = ExistenceProof {
+			key: UPGRADE_PATH[0].as_bytes().to_vec(),
+			value: store_root,
+			leaf: Some(tendermint_leaf),
+			path: vec![],
+		};
+
+		let encode = |proof: ExistenceProof| {
+			let proofs = [proof, multistore_proof.clone()]
+				.into_iter()
+				.map(|proof| CommitmentProof { proof: Some(Proof::Exist(proof)) })
+				.collect();
+			RawMerkleProof { proofs }.encode_to_vec()
+		};
+		(root, encode(client_proof), encode(consensus_proof))
+	}
+
+	/// A context holding a tendermint client whose chain committed an upgrade at its latest
+	/// height, along with the message upgrading the client to it.
+	fn upgrade_fixture() -> (
+		MockContext<MockClientTypes>,
+		MsgUpgradeAnyClient<MockContext<MockClientTypes>>,
+		TendermintClientState<Crypto>,
+	) {
+		let tm_header = get_dummy_tendermint_header();
+		let last_height = Height::new(0, u64::from(tm_header.height));
+		let upgrade_path = UPGRADE_PATH.iter().map(|key| key.to_string()).collect::<Vec<_>>();
+		let client_state = TendermintClientState::<Crypto>::new(
+			tm_header.chain_id.clone().into(),
+			TrustThreshold::ONE_THIRD,
+			Duration::from_secs(64000),
+			Duration::from_secs(128000),
+			Duration::from_millis(3000),
+			last_height,
+			ProofSpecs::default(),
+			upgrade_path.clone(),
+		)
+		.unwrap();
+		let upgraded_client_state = TendermintClientState::<Crypto>::new(
+			ChainId::new("test-chain".to_string(), 2),
+			TrustThreshold::ONE_THIRD,
+			Duration::from_secs(64000),
+			Duration::from_secs(256000),
+			Duration::from_millis(3000),
+			Height::new(2, 1),
+			ProofSpecs::default(),
+			upgrade_path,
+		)
+		.unwrap();
+		let upgraded_consensus_state = TendermintConsensusState::from(tm_header.clone());
+
+		let committed_client_state = Any {
+			type_url: TENDERMINT_CLIENT_STATE_TYPE_URL.to_string(),
+			value: upgraded_client_state.clone().zero_custom_fields().encode_vec(),
+		};
+		let committed_consensus_state = Any {
+			type_url: TENDERMINT_CONSENSUS_STATE_TYPE_URL.to_string(),
+			value: upgraded_consensus_state.encode_vec(),
+		};
+		let (root, proof_upgrade_client, proof_upgrade_consensus_state) = commit_upgrade(
+			last_height,
+			committed_client_state.encode_to_vec(),
+			committed_consensus_state.encode_to_vec(),
+		);
+
+		let client_id = ClientId::new(&ClientState::<()>::client_type(), 0).unwrap();
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		ctx.store_client_type(client_id.clone(), ClientState::<()>::client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), AnyClientState::Tendermint(client_state))
+			.unwrap();
+		let consensus_state = TendermintConsensusState {
+			root: CommitmentRoot::from_bytes(&root),
+			..TendermintConsensusState::from(tm_header)
+		};
+		ctx.store_consensus_state(
+			client_id.clone(),
+			last_height,
+			AnyConsensusState::Tendermint(consensus_state),
+		)
+		.unwrap();
+
+		let msg = MsgUpgradeAnyClient::new(
+			client_id,
+			AnyClientState::Tendermint(upgraded_client_state.clone()),
+			AnyConsensusState::Tendermint(upgraded_consensus_state),
+			proof_upgrade_client,
+			proof_upgrade_consensus_state,
+			get_dummy_account_id(),
+		);
+		(ctx, msg, upgraded_client_state)
+	}
+
+	#[test]
+	fn test_tm_upgrade_client_ok() {
+		let (ctx, mut msg, upgraded_client_state) = upgrade_fixture();
+		// Client chosen parameters are not committed, the ones given by the relayer are ignored.
+		msg.client_state = AnyClientState::Tendermint(TendermintClientState {
+			trusting_period: Duration::from_secs(1),
+			trust_level: TrustThreshold::new(2, 3).unwrap(),
+			..upgraded_client_state.clone()
+		});
+
+		let output = dispatch(&ctx, ClientMsg::UpgradeClient(msg)).unwrap();
+		match output.result {
+			ClientResult::Upgrade(res) => {
+				let client_state = match res.client_state {
+					AnyClientState::Tendermint(client_state) => client_state,
+					s => panic!("expected a tendermint client state, instead got {:?}", s),
+				};
+				assert_eq!(client_state.chain_id, upgraded_client_state.chain_id);
+				assert_eq!(client_state.latest_height, Height::new(2, 1));
+				assert_eq!(client_state.unbonding_period, Duration::from_secs(256000));
+				assert_eq!(client_state.trusting_period, Duration::from_secs(64000));
+				assert_eq!(client_state.trust_level, TrustThreshold::ONE_THIRD);
+
+				let consensus_state = match res.consensus_state {
+					Some(ConsensusUpdateResult::Single(AnyConsensusState::Tendermint(cs))) => cs,
+					cs => panic!("expected a tendermint consensus state, instead got {:?}", cs),
+				};
+				assert_eq!(consensus_state.root, CommitmentRoot::from_bytes(SENTINEL_ROOT));
+			},
+			_ => panic!("expected result of type ClientResult::Upgrade"),
+		}
+	}
+
+	#[test]
+	fn test_tm_upgrade_client_forged_state() {
+		let (ctx, mut msg, upgraded_client_state) = upgrade_fixture();
+		msg.client_state = AnyClientState::Tendermint(TendermintClientState {
+			unbonding_period: Duration::from_secs(512000),
+			..upgraded_client_state
+		});
+
+		match dispatch(&ctx, ClientMsg::UpgradeClient(msg)) {
+			Err(e) => assert!(matches!(e.detail(), ErrorDetail::InvalidUpgradeClientProof(_))),
+			Ok(_) => panic!("expected InvalidUpgradeClientProof error"),
+		}
+
+		let (ctx, mut msg, _) = upgrade_fixture();
+		let forged_consensus_state = TendermintConsensusState {
+			next_validators_hash: tendermint::Hash::None,
+			..TendermintConsensusState::from(get_dummy_tendermint_header())
+		};
+		msg.consensus_state = AnyConsensusState::Tendermint(forged_consensus_state);
+
+		match dispatch(&ctx, ClientMsg::UpgradeClient(msg)) {
+			Err(e) =>
+				assert!(matches!(e.detail(), ErrorDetail::InvalidUpgradeConsensusStateProof(_))),
+			Ok(_) => panic!("expected InvalidUpgradeConsensusStateProof error"),
+		}
+	}
+
+	const MISBEHAVIOUR_CHAIN_START_SECS: u64 = 1_650_000_000;
+
+	/// A testgen header for the misbehaviour test chain at `height`, produced `secs` seconds after
+	/// the chain started.
+	fn misbehaviour_header(height: u64, secs: u64) -> Header {
+		let timestamp =
+			Timestamp::from_nanoseconds((MISBEHAVIOUR_CHAIN_START_SECS + secs) * 1_000_000_000)
+				.unwrap();
+		MockHostBlock::generate_tm_block(ChainId::new("mockgaia".to_string(), 1), height, timestamp)
+			.into()
+	}
+
+	/// A context whose tendermint client has consensus states at heights 10 and 20, both produced
+	/// at `height` seconds.
+	fn misbehaviour_fixture() -> (MockContext<MockClientTypes>, ClientId, ClientState<Crypto>) {
+		let client_id = ClientId::new(&ClientState::<()>::client_type(), 0).unwrap();
+		let client_state = TendermintClientState::<Crypto>::new(
+			ChainId::new("mockgaia".to_string(), 1),
+			TrustThreshold::ONE_THIRD,
+			Duration::from_secs(64000),
+			Duration::from_secs(128000),
+			Duration::from_millis(3000),
+			Height::new(1, 20),
+			ProofSpecs::default(),
+			vec![],
+		)
+		.unwrap();
+
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		ctx.store_client_type(client_id.clone(), ClientState::<()>::client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), AnyClientState::Tendermint(client_state.clone()))
+			.unwrap();
+		for height in [10, 20] {
+			let header = misbehaviour_header(height, height);
+			let consensus_state = TendermintConsensusState::from(header.signed_header.header);
+			ctx.store_consensus_state(
+				client_id.clone(),
+				Height::new(1, height),
+				AnyConsensusState::Tendermint(consensus_state),
+			)
+			.unwrap();
+		}
+		(ctx, client_id, client_state)
+	}
+
+	fn check_for_misbehaviour(
+		ctx: &MockContext<MockClientTypes>,
+		client_id: &ClientId,
+		client_state: &ClientState<Crypto>,
+		message: ClientMessage,
+	) -> Result<bool, ibc::core::ics02_client::error::Error> {
+		TendermintClient::<Crypto>::default().check_for_misbehaviour(
+			ctx,
+			client_id.clone(),
+			client_state.clone(),
+			message,
+		)
+	}
+
+	#[test]
+	fn test_tm_conflicting_header_freezes_client() {
+		let (ctx, client_id, client_state) = misbehaviour_fixture();
+
+		let installed = ClientMessage::Header(misbehaviour_header(20, 20));
+		assert!(!check_for_misbehaviour(&ctx, &client_id, &client_state, installed).unwrap());
+
+		// Same height, but a different block than the one the consensus state was taken from.
+		let conflicting = ClientMessage::Header(misbehaviour_header(20, 21));
+		assert!(check_for_misbehaviour(&ctx, &client_id, &client_state, conflicting.clone())
+			.unwrap());
+
+		let frozen = TendermintClient::<Crypto>::default()
+			.update_state_on_misbehaviour(client_state, conflicting)
+			.unwrap();
+		assert_eq!(frozen.frozen_height, Some(Height::new(1, 20)));
+	}
+
+	#[test]
+	fn test_tm_header_breaking_time_monotonicity() {
+		let (ctx, client_id, client_state) = misbehaviour_fixture();
+
+		let in_order = ClientMessage::Header(misbehaviour_header(15, 15));
+		assert!(!check_for_misbehaviour(&ctx, &client_id, &client_state, in_order).unwrap());
+
+		// Timestamped after the consensus state at the later height 20.
+		let after_next = ClientMessage::Header(misbehaviour_header(15, 25));
+		assert!(check_for_misbehaviour(&ctx, &client_id, &client_state, after_next).unwrap());
+
+		// Timestamped before the consensus state at the earlier height 10.
+		let before_prev = ClientMessage::Header(misbehaviour_header(15, 5));
+		assert!(check_for_misbehaviour(&ctx, &client_id, &client_state, before_prev).unwrap());
+	}
+
+	#[test]
+	fn test_tm_misbehaviour_message() {
+		let (ctx, client_id, client_state) = misbehaviour_fixture();
+		let misbehaviour = |header1, header2| {
+			ClientMessage::Misbehaviour(Misbehaviour {
+				client_id: client_id.clone(),
+				header1,
+				header2,
+			})
+		};
+
+		// Two blocks at the same height.
+		let fork = misbehaviour(misbehaviour_header(25, 25), misbehaviour_header(25, 26));
+		assert!(check_for_misbehaviour(&ctx, &client_id, &client_state, fork.clone()).unwrap());
+		let frozen = TendermintClient::<Crypto>::default()
+			.update_state_on_misbehaviour(client_state.clone(), fork)
+			.unwrap();
+		assert_eq!(frozen.frozen_height, Some(Height::new(1, 25)));
+
+		// A later height with an earlier timestamp.
+		let time_violation = misbehaviour(misbehaviour_header(26, 24), misbehaviour_header(25, 25));
+		assert!(check_for_misbehaviour(&ctx, &client_id, &client_state, time_violation).unwrap());
+
+		// Two consistent headers are no evidence of misbehaviour.
+		let consistent = misbehaviour(misbehaviour_header(26, 26), misbehaviour_header(25, 25));
+		match check_for_misbehaviour(&ctx, &client_id, &client_state, consistent) {
+			Err(e) => assert!(matches!(e.detail(), ErrorDetail::ClientError(_))),
+			Ok(found) => panic!("expected an error, instead got {}", found),
+		}
+	}
+
+	#[test]
+	fn test_tm_update_client_batch_of_sequential_headers() {
+		let chain_id = ChainId::new("mockgaia".to_string(), 1);
+		let chain_start = (Timestamp::now() - Duration::from_secs(1000)).unwrap();
+		let blocks = (20..=23)
+			.map(|height| {
+				let timestamp = (chain_start + Duration::from_secs(height)).unwrap();
+				MockHostBlock::generate_tm_block(chain_id.clone(), height, timestamp)
+			})
+			.collect::<Vec<_>>();
+
+		let client_id = ClientId::new(&ClientState::<()>::client_type(), 0).unwrap();
+		let client_state = TendermintClientState::<Crypto>::new(
+			chain_id.clone(),
+			TrustThreshold::ONE_THIRD,
+			Duration::from_secs(64000),
+			Duration::from_secs(128000),
+			Duration::from_millis(3000),
+			Height::new(1, 20),
+			ProofSpecs::default(),
+			vec![],
+		)
+		.unwrap();
+		let consensus_state =
+			TendermintConsensusState::from(blocks[0].signed_header.header.clone());
+
+		let mut ctx = MockContext::<MockClientTypes>::default();
+		ctx.store_client_type(client_id.clone(), ClientState::<()>::client_type()).unwrap();
+		ctx.store_client_state(client_id.clone(), AnyClientState::Tendermint(client_state))
+			.unwrap();
+		ctx.store_consensus_state(
+			client_id.clone(),
+			Height::new(1, 20),
+			AnyConsensusState::Tendermint(consensus_state),
+		)
+		.unwrap();
+		ctx.store_update_time(client_id.clone(), Height::new(1, 20), ctx.host_timestamp())
+			.unwrap();
+
+		// Every header but the first is only trusted through the header before it in the batch.
+		let headers = blocks
+			.windows(2)
+			.map(|pair| {
+				let (trusted, block) = (&pair[0], &pair[1]);
+				AnyClientMessage::Tendermint(ClientMessage::Header(Header {
+					signed_header: block.signed_header.clone(),
+					validator_set: block.validators.clone(),
+					trusted_height: Height::new(1, trusted.signed_header.header.height.value()),
+					trusted_validator_set: trusted.next_validators.clone(),
+				}))
+			})
+			.collect();
+		let msg = MsgUpdateAnyClientBatch::new(client_id.clone(), headers, get_dummy_account_id());
+
+		let output = dispatch(&ctx, ClientMsg::UpdateClientBatch(msg)).unwrap();
+		match &output.events[..] {
+			[IbcEvent::UpdateClient(e)] => assert_eq!(
+				e.header_heights(),
+				&[Height::new(1, 21), Height::new(1, 22), Height::new(1, 23)]
+			),
+			events => panic!("expected a single UpdateClient event, instead got {:?}", events),
+		}
+
+		ctx.store_client_result(output.result).unwrap();
+		assert_eq!(ctx.client_state(&client_id).unwrap().latest_height(), Height::new(1, 23));
+		for (height, block) in (21..=23).zip(&blocks[1..]) {
+			let expected = TendermintConsensusState::from(block.signed_header.header.clone());
+			match ctx.consensus_state(&client_id, Height::new(1, height)).unwrap() {
+				AnyConsensusState::Tendermint(consensus_state) =>
+					assert_eq!(consensus_state, expected),
+				state => panic!("expected a tendermint consensus state, instead got {:?}", state),
+			}
+		}
+	}
+
+	#[test]
+	fn test_tm_packet_proof_waits_for_block_delay() {
+		let delay_period = Duration::from_secs(60);
+		let mut ctx =
+			MockContext::<MockClientTypes>::default().with_block_time(Duration::from_secs(6));
+		let client_id = ClientId::new(&ClientState::<Crypto>::client_type(), 0).unwrap();
+		let height = Height::new(1, 10);
+		let (processed_time, processed_height) = (ctx.host_timestamp(), ctx.host_height());
+		ctx.store_update_time(client_id.clone(), height, processed_time).unwrap();
+		ctx.store_update_height(client_id.clone(), height, processed_height).unwrap();
+		let connection_end = ConnectionEnd::new(
+			State::Open,
+			client_id.clone(),
+			Counterparty::new(client_id, None, Default::default()),
+			get_compatible_versions(),
+			delay_period,
+		);
+
+		// The delay period has passed on the host clock, but not enough blocks were produced.
+		ctx.advance_time(delay_period);
+		match verify_delay_passed(&ctx, height, &connection_end) {
+			Err(e) => match e.detail() {
+				ErrorDetail::ClientError(e) =>
+					assert!(e.inner.contains("not enough blocks elapsed"), "{}", e.inner),
+				e => panic!("expected a client error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("expected the block delay to be enforced"),
+		}
+
+		let ctx = ctx.with_height(processed_height.add(ctx.block_delay(delay_period)));
+		verify_delay_passed(&ctx, height, &connection_end).unwrap();
+	}
 }
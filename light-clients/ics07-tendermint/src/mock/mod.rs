@@ -30,7 +30,9 @@ use ibc::{
 	core::{
 		ics02_client,
 		ics02_client::{
-			client_consensus::ConsensusState, client_state::ClientState, context::ClientTypes,
+			client_consensus::ConsensusState,
+			client_state::{ClientState, ClientType},
+			context::ClientTypes,
 		},
 	},
 	mock::{
@@ -50,11 +52,12 @@ pub const MOCK_CLIENT_STATE_TYPE_URL: &str = "/ibc.mock.ClientState";
 pub const MOCK_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.mock.ClientMessage";
 pub const MOCK_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.mock.ConsensusState";
 
-pub const TENDERMINT_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.tendermint.v1.ClientState";
+pub use crate::{
+	client_state::TENDERMINT_CLIENT_STATE_TYPE_URL,
+	consensus_state::TENDERMINT_CONSENSUS_STATE_TYPE_URL,
+};
 pub const TENDERMINT_CLIENT_MESSAGE_TYPE_URL: &str =
 	"/ibc.lightclients.tendermint.v1.ClientMessage";
-pub const TENDERMINT_CONSENSUS_STATE_TYPE_URL: &str =
-	"/ibc.lightclients.tendermint.v1.ConsensusState";
 
 #[derive(Clone, Debug, PartialEq, Eq, ClientDef)]
 pub enum AnyClient {
@@ -148,14 +151,24 @@ impl ClientTypes for MockClientTypes {
 
 impl HostBlockType for MockClientTypes {
 	type HostBlock = MockHostBlock;
+
+	fn supported_client_types() -> Vec<ClientType> {
+		vec![MockClientState::client_type(), TendermintClientState::<Crypto>::client_type()]
+	}
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct Crypto;
 
 impl ics23::HostFunctionsProvider for Crypto {
-	fn sha2_256(_message: &[u8]) -> [u8; 32] {
-		unimplemented!()
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		use sha2::Digest;
+		let mut hasher = sha2::Sha256::new();
+		hasher.update(message);
+		let hash = hasher.finalize();
+		let mut res = [0u8; 32];
+		res.copy_from_slice(&hash);
+		res
 	}
 
 	fn sha2_512(_message: &[u8]) -> [u8; 64] {
@@ -0,0 +1,66 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use ibc::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc::Height;
+
+use crate::{code::validate_code_hash, error::Error, proto::ClientState as RawClientState};
+
+/// Protobuf type url for Wasm Client State
+pub const WASM_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ClientState";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClientState {
+	/// Client state of the contract, only the contract interprets it.
+	pub data: Vec<u8>,
+	/// Checksum of the contract code that runs this client.
+	pub code_hash: Vec<u8>,
+	pub latest_height: Height,
+}
+
+impl ClientState {
+	pub fn new(data: Vec<u8>, code_hash: Vec<u8>, latest_height: Height) -> Result<Self, Error> {
+		validate_code_hash(&code_hash)?;
+		Ok(Self { data, code_hash, latest_height })
+	}
+}
+
+impl Protobuf<RawClientState> for ClientState {}
+
+impl TryFrom<RawClientState> for ClientState {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		ClientState::new(
+			raw.data,
+			raw.code_hash,
+			Height::new(raw.latest_revision_number, raw.latest_revision_height),
+		)
+	}
+}
+
+impl From<ClientState> for RawClientState {
+	fn from(value: ClientState) -> Self {
+		RawClientState {
+			data: value.data,
+			code_hash: value.code_hash,
+			latest_revision_number: value.latest_height.revision_number,
+			latest_revision_height: value.latest_height.revision_height,
+		}
+	}
+}
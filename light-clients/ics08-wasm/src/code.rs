@@ -0,0 +1,55 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Validation of the wasm code governance stores for wasm clients to run.
+
+use ibc::prelude::*;
+
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+
+/// Maximum size of a wasm contract the host accepts, in bytes.
+pub const MAX_WASM_SIZE: usize = 3 * 1024 * 1024;
+
+/// Every wasm module starts with these bytes.
+pub const WASM_MAGIC: &[u8] = b"\0asm";
+
+/// sha256 checksum of a wasm contract's code, which identifies the contract.
+pub fn code_hash(code: &[u8]) -> Vec<u8> {
+	Sha256::digest(code).to_vec()
+}
+
+/// Checks that `code` is a non empty wasm module within [`MAX_WASM_SIZE`].
+pub fn validate_code(code: &[u8]) -> Result<(), Error> {
+	if code.is_empty() {
+		return Err(Error::empty_code())
+	}
+	if code.len() > MAX_WASM_SIZE {
+		return Err(Error::code_too_large(code.len(), MAX_WASM_SIZE))
+	}
+	if !code.starts_with(WASM_MAGIC) {
+		return Err(Error::invalid_code())
+	}
+	Ok(())
+}
+
+/// Checks that `code_hash` has the length of a sha256 checksum.
+pub fn validate_code_hash(code_hash: &[u8]) -> Result<(), Error> {
+	if code_hash.len() != 32 {
+		return Err(Error::invalid_code_hash(code_hash.len()))
+	}
+	Ok(())
+}
@@ -0,0 +1,52 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use ibc::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc::timestamp::Timestamp;
+
+use crate::{error::Error, proto::ConsensusState as RawConsensusState};
+
+/// Protobuf type url for Wasm Consensus State
+pub const WASM_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.ConsensusState";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusState {
+	/// Consensus state of the contract, only the contract interprets it.
+	pub data: Vec<u8>,
+	pub timestamp: Timestamp,
+}
+
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+	type Error = Error;
+
+	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+		Ok(Self {
+			data: raw.data,
+			timestamp: Timestamp::from_nanoseconds(raw.timestamp)
+				.map_err(Error::invalid_timestamp)?,
+		})
+	}
+}
+
+impl From<ConsensusState> for RawConsensusState {
+	fn from(value: ConsensusState) -> Self {
+		RawConsensusState { data: value.data, timestamp: value.timestamp.nanoseconds() }
+	}
+}
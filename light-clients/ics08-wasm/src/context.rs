@@ -0,0 +1,63 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use ibc::prelude::*;
+
+use ibc::{core::ics24_host::identifier::ClientId, Height};
+
+use crate::{client_state::ClientState, consensus_state::ConsensusState, error::Error};
+
+/// Read access to the wasm codes governance allowed clients to run.
+pub trait WasmCodeReader {
+	/// Returns the code with checksum `code_hash`, if it was stored.
+	fn wasm_code(&self, code_hash: &[u8]) -> Option<Vec<u8>>;
+}
+
+pub trait WasmCodeKeeper {
+	/// Stores `code` under its checksum `code_hash`.
+	fn store_wasm_code(&mut self, code_hash: Vec<u8>, code: Vec<u8>) -> Result<(), Error>;
+}
+
+/// Read access to the state of the host's wasm clients.
+pub trait WasmClientReader: WasmCodeReader {
+	fn wasm_client_state(&self, client_id: &ClientId) -> Result<ClientState, Error>;
+
+	fn wasm_consensus_state(
+		&self,
+		client_id: &ClientId,
+		height: Height,
+	) -> Result<ConsensusState, Error>;
+}
+
+pub trait WasmClientKeeper {
+	fn store_wasm_client_state(
+		&mut self,
+		client_id: ClientId,
+		client_state: ClientState,
+	) -> Result<(), Error>;
+}
+
+/// Executes the entry points of wasm light client contracts.
+pub trait WasmVm {
+	/// Runs the migrate entry point of `code` against the state of the client being migrated to
+	/// it and returns the client state `data` the contract continues with.
+	fn migrate(
+		&self,
+		code: &[u8],
+		client_state: &ClientState,
+		consensus_state: &ConsensusState,
+		msg: &[u8],
+	) -> Result<Vec<u8>, Error>;
+}
@@ -0,0 +1,84 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use ibc::prelude::*;
+
+use flex_error::{define_error, TraceError};
+
+use ibc::{
+	core::ics24_host::{error::ValidationError, identifier::ClientId},
+	signer::SignerError,
+	timestamp::ParseTimestampError,
+	Height,
+};
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		EmptyCode
+			| _ | { "wasm code is empty" },
+
+		CodeTooLarge
+			{ size: usize, max: usize }
+			| e | { format_args!("wasm code of {0} bytes exceeds the maximum size of {1} bytes", e.size, e.max) },
+
+		InvalidCode
+			| _ | { "wasm code does not start with the wasm magic number" },
+
+		InvalidCodeHash
+			{ len: usize }
+			| e | { format_args!("code hash must be a 32 byte sha256 checksum, got {} bytes", e.len) },
+
+		CodeAlreadyStored
+			{ code_hash: Vec<u8> }
+			| e | { format_args!("wasm code with hash {:?} is already stored", e.code_hash) },
+
+		CodeNotFound
+			{ code_hash: Vec<u8> }
+			| e | { format_args!("wasm code with hash {:?} is not stored", e.code_hash) },
+
+		CodeHashUnchanged
+			{ client_id: ClientId }
+			| e | { format_args!("wasm client {} already runs the code it is migrated to", e.client_id) },
+
+		ClientNotFound
+			{ client_id: ClientId }
+			| e | { format_args!("wasm client {} not found", e.client_id) },
+
+		ConsensusStateNotFound
+			{ client_id: ClientId, height: Height }
+			| e | { format_args!("consensus state of wasm client {} not found at height {}", e.client_id, e.height) },
+
+		Vm
+			{ reason: String }
+			| e | { format_args!("wasm contract execution failed: {}", e.reason) },
+
+		Identifier
+			[ ValidationError ]
+			| _ | { "invalid identifier" },
+
+		Signer
+			[ SignerError ]
+			| _ | { "failed to parse signer" },
+
+		Decode
+			[ TraceError<prost::DecodeError> ]
+			| _ | { "decode error" },
+
+		InvalidTimestamp
+			[ ParseTimestampError ]
+			| _ | { "invalid consensus state timestamp" },
+	}
+}
@@ -0,0 +1,299 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+//! Protocol logic for the wasm client messages, `MsgStoreCode` and `MsgMigrateContract`.
+
+use ibc::prelude::*;
+
+use ibc::core::ics24_host::identifier::ClientId;
+
+use crate::{
+	client_state::ClientState,
+	code::{code_hash, validate_code},
+	context::{WasmClientReader, WasmCodeReader, WasmVm},
+	error::Error,
+	msg::{MsgMigrateContract, MsgStoreCode},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoreCodeResult {
+	pub code_hash: Vec<u8>,
+	pub code: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrateContractResult {
+	pub client_id: ClientId,
+	/// The client state running the new contract, to be stored in place of the old one.
+	pub client_state: ClientState,
+}
+
+/// Validates the code of a `MsgStoreCode` and computes the checksum it is stored under, which
+/// wasm clients use to refer to it.
+pub fn store_code<Ctx: WasmCodeReader>(
+	ctx: &Ctx,
+	msg: MsgStoreCode,
+) -> Result<StoreCodeResult, Error> {
+	validate_code(&msg.code)?;
+	let code_hash = code_hash(&msg.code);
+	if ctx.wasm_code(&code_hash).is_some() {
+		return Err(Error::code_already_stored(code_hash))
+	}
+	Ok(StoreCodeResult { code_hash, code: msg.code })
+}
+
+/// Runs the migrate entry point of the contract a `MsgMigrateContract` names against the latest
+/// state of the client and returns the client state pointing at the new contract.
+pub fn migrate_contract<Ctx: WasmClientReader, Vm: WasmVm>(
+	ctx: &Ctx,
+	vm: &Vm,
+	msg: MsgMigrateContract,
+) -> Result<MigrateContractResult, Error> {
+	let client_state = ctx.wasm_client_state(&msg.client_id)?;
+	if client_state.code_hash == msg.code_hash {
+		return Err(Error::code_hash_unchanged(msg.client_id))
+	}
+	// Only codes governance stored may be migrated to.
+	let code =
+		ctx.wasm_code(&msg.code_hash).ok_or_else(|| Error::code_not_found(msg.code_hash.clone()))?;
+	let consensus_state = ctx.wasm_consensus_state(&msg.client_id, client_state.latest_height)?;
+
+	let data = vm.migrate(&code, &client_state, &consensus_state, &msg.msg)?;
+
+	Ok(MigrateContractResult {
+		client_id: msg.client_id,
+		client_state: ClientState { data, code_hash: msg.code_hash, ..client_state },
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use ibc::prelude::*;
+
+	use alloc::collections::BTreeMap;
+	use core::str::FromStr;
+	use test_log::test;
+
+	use ibc::{
+		core::ics24_host::identifier::ClientId, test_utils::get_dummy_account_id,
+		timestamp::Timestamp, Height,
+	};
+
+	use crate::{
+		client_state::ClientState,
+		code::code_hash,
+		consensus_state::ConsensusState,
+		context::{WasmClientKeeper, WasmClientReader, WasmCodeKeeper, WasmCodeReader, WasmVm},
+		error::{Error, ErrorDetail},
+		handler::{migrate_contract, store_code},
+		msg::{MsgMigrateContract, MsgStoreCode},
+		proto::{MsgMigrateContract as RawMsgMigrateContract, MsgStoreCode as RawMsgStoreCode},
+	};
+
+	/// The smallest valid wasm module.
+	const DUMMY_WASM: &[u8] = b"\0asm\x01\0\0\0";
+	/// [`DUMMY_WASM`] with a custom section named "v2", so it has a different checksum.
+	const DUMMY_WASM_V2: &[u8] = b"\0asm\x01\0\0\0\0\x03\x02v2";
+
+	#[derive(Default)]
+	struct MockWasmContext {
+		codes: BTreeMap<Vec<u8>, Vec<u8>>,
+		client_states: BTreeMap<ClientId, ClientState>,
+		consensus_states: BTreeMap<(ClientId, Height), ConsensusState>,
+	}
+
+	impl WasmCodeReader for MockWasmContext {
+		fn wasm_code(&self, code_hash: &[u8]) -> Option<Vec<u8>> {
+			self.codes.get(code_hash).cloned()
+		}
+	}
+
+	impl WasmCodeKeeper for MockWasmContext {
+		fn store_wasm_code(&mut self, code_hash: Vec<u8>, code: Vec<u8>) -> Result<(), Error> {
+			self.codes.insert(code_hash, code);
+			Ok(())
+		}
+	}
+
+	impl WasmClientReader for MockWasmContext {
+		fn wasm_client_state(&self, client_id: &ClientId) -> Result<ClientState, Error> {
+			self.client_states
+				.get(client_id)
+				.cloned()
+				.ok_or_else(|| Error::client_not_found(client_id.clone()))
+		}
+
+		fn wasm_consensus_state(
+			&self,
+			client_id: &ClientId,
+			height: Height,
+		) -> Result<ConsensusState, Error> {
+			self.consensus_states
+				.get(&(client_id.clone(), height))
+				.cloned()
+				.ok_or_else(|| Error::consensus_state_not_found(client_id.clone(), height))
+		}
+	}
+
+	impl WasmClientKeeper for MockWasmContext {
+		fn store_wasm_client_state(
+			&mut self,
+			client_id: ClientId,
+			client_state: ClientState,
+		) -> Result<(), Error> {
+			self.client_states.insert(client_id, client_state);
+			Ok(())
+		}
+	}
+
+	/// Migrates clients to the consensus state they had, followed by the migrate message.
+	struct MockVm;
+
+	impl WasmVm for MockVm {
+		fn migrate(
+			&self,
+			code: &[u8],
+			_client_state: &ClientState,
+			consensus_state: &ConsensusState,
+			msg: &[u8],
+		) -> Result<Vec<u8>, Error> {
+			assert_eq!(code, DUMMY_WASM_V2);
+			Ok([consensus_state.data.as_slice(), msg].concat())
+		}
+	}
+
+	fn raw_store_code(code: &[u8]) -> RawMsgStoreCode {
+		RawMsgStoreCode { signer: get_dummy_account_id().to_string(), code: code.to_vec() }
+	}
+
+	fn raw_migrate_contract(client_id: &ClientId, code_hash: Vec<u8>) -> RawMsgMigrateContract {
+		RawMsgMigrateContract {
+			signer: get_dummy_account_id().to_string(),
+			client_id: client_id.to_string(),
+			code_hash,
+			msg: b"migrate".to_vec(),
+		}
+	}
+
+	/// A context running a wasm client on [`DUMMY_WASM`], with [`DUMMY_WASM_V2`] also stored.
+	fn context_with_client(client_id: &ClientId) -> MockWasmContext {
+		let mut ctx = MockWasmContext::default();
+		for code in [DUMMY_WASM, DUMMY_WASM_V2] {
+			let msg = MsgStoreCode::try_from(raw_store_code(code)).unwrap();
+			let result = store_code(&ctx, msg).unwrap();
+			ctx.store_wasm_code(result.code_hash, result.code).unwrap();
+		}
+
+		let height = Height::new(0, 10);
+		let client_state =
+			ClientState::new(b"client".to_vec(), code_hash(DUMMY_WASM), height).unwrap();
+		ctx.store_wasm_client_state(client_id.clone(), client_state).unwrap();
+		ctx.consensus_states.insert(
+			(client_id.clone(), height),
+			ConsensusState {
+				data: b"consensus".to_vec(),
+				timestamp: Timestamp::from_nanoseconds(1).unwrap(),
+			},
+		);
+		ctx
+	}
+
+	#[test]
+	fn store_code_validates_code() {
+		let ctx = MockWasmContext::default();
+
+		let result =
+			store_code(&ctx, MsgStoreCode::try_from(raw_store_code(DUMMY_WASM)).unwrap()).unwrap();
+		assert_eq!(result.code_hash, code_hash(DUMMY_WASM));
+		assert_eq!(result.code_hash.len(), 32);
+
+		match MsgStoreCode::try_from(raw_store_code(&[])) {
+			Err(e) => assert!(matches!(e.detail(), ErrorDetail::EmptyCode(_))),
+			Ok(_) => panic!("Expected EmptyCode error"),
+		}
+		match MsgStoreCode::try_from(raw_store_code(b"not wasm")) {
+			Err(e) => assert!(matches!(e.detail(), ErrorDetail::InvalidCode(_))),
+			Ok(_) => panic!("Expected InvalidCode error"),
+		}
+	}
+
+	#[test]
+	fn store_code_rejects_stored_code() {
+		let client_id = ClientId::from_str("08-wasm-0").unwrap();
+		let ctx = context_with_client(&client_id);
+
+		let msg = MsgStoreCode::try_from(raw_store_code(DUMMY_WASM)).unwrap();
+		match store_code(&ctx, msg) {
+			Err(e) => match e.detail() {
+				ErrorDetail::CodeAlreadyStored(e) => {
+					assert_eq!(e.code_hash, code_hash(DUMMY_WASM));
+				},
+				_ => panic!("Expected CodeAlreadyStored error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected CodeAlreadyStored error"),
+		}
+	}
+
+	#[test]
+	fn migrate_contract_rewrites_code_hash() {
+		let client_id = ClientId::from_str("08-wasm-0").unwrap();
+		let mut ctx = context_with_client(&client_id);
+
+		let msg =
+			MsgMigrateContract::try_from(raw_migrate_contract(&client_id, code_hash(DUMMY_WASM_V2)))
+				.unwrap();
+		let result = migrate_contract(&ctx, &MockVm, msg).unwrap();
+		assert_eq!(result.client_id, client_id);
+		assert_eq!(result.client_state.code_hash, code_hash(DUMMY_WASM_V2));
+		assert_eq!(result.client_state.data, b"consensusmigrate".to_vec());
+		assert_eq!(result.client_state.latest_height, Height::new(0, 10));
+
+		ctx.store_wasm_client_state(result.client_id, result.client_state.clone()).unwrap();
+		assert_eq!(ctx.wasm_client_state(&client_id).unwrap(), result.client_state);
+	}
+
+	#[test]
+	fn migrate_contract_requires_new_stored_code() {
+		let client_id = ClientId::from_str("08-wasm-0").unwrap();
+		let ctx = context_with_client(&client_id);
+
+		let unknown = code_hash(b"\0asm\x01\0\0\0\0\x03\x02v3");
+		let raw = raw_migrate_contract(&client_id, unknown.clone());
+		let msg = MsgMigrateContract::try_from(raw).unwrap();
+		match migrate_contract(&ctx, &MockVm, msg) {
+			Err(e) => match e.detail() {
+				ErrorDetail::CodeNotFound(e) => assert_eq!(e.code_hash, unknown),
+				_ => panic!("Expected CodeNotFound error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected CodeNotFound error"),
+		}
+
+		let msg =
+			MsgMigrateContract::try_from(raw_migrate_contract(&client_id, code_hash(DUMMY_WASM)))
+				.unwrap();
+		match migrate_contract(&ctx, &MockVm, msg) {
+			Err(e) => assert!(matches!(e.detail(), ErrorDetail::CodeHashUnchanged(_))),
+			Ok(_) => panic!("Expected CodeHashUnchanged error"),
+		}
+
+		match MsgMigrateContract::try_from(raw_migrate_contract(&client_id, vec![0; 31])) {
+			Err(e) => match e.detail() {
+				ErrorDetail::InvalidCodeHash(e) => assert_eq!(e.len, 31),
+				_ => panic!("Expected InvalidCodeHash error, instead got {:?}", e),
+			},
+			Ok(_) => panic!("Expected InvalidCodeHash error"),
+		}
+	}
+}
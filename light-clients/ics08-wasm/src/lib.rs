@@ -0,0 +1,31 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! ICS 08: Wasm Client wraps light clients that are implemented as wasm contracts, identified by
+//! the checksum of their code. The host keeps the codes its governance allowed and executes the
+//! contracts through a [`WasmVm`](context::WasmVm).
+
+extern crate alloc;
+
+pub mod client_state;
+pub mod code;
+pub mod consensus_state;
+pub mod context;
+pub mod error;
+pub mod handler;
+pub mod msg;
+pub mod proto;
@@ -0,0 +1,120 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use ibc::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc::{core::ics24_host::identifier::ClientId, signer::Signer, tx_msg::Msg};
+
+use crate::{
+	code::{validate_code, validate_code_hash},
+	error::Error,
+	proto::{MsgMigrateContract as RawMsgMigrateContract, MsgStoreCode as RawMsgStoreCode},
+};
+
+pub const STORE_CODE_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.MsgStoreCode";
+pub const MIGRATE_CONTRACT_TYPE_URL: &str = "/ibc.lightclients.wasm.v1.MsgMigrateContract";
+
+///
+/// Message definition for `MsgStoreCode`, which allows wasm clients to run `code`. Only the
+/// host's governance may submit it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgStoreCode {
+	pub code: Vec<u8>,
+	pub signer: Signer,
+}
+
+impl Msg for MsgStoreCode {
+	type ValidationError = Error;
+	type Raw = RawMsgStoreCode;
+
+	fn route(&self) -> String {
+		ibc::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		STORE_CODE_TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgStoreCode> for MsgStoreCode {}
+
+impl TryFrom<RawMsgStoreCode> for MsgStoreCode {
+	type Error = Error;
+
+	fn try_from(raw: RawMsgStoreCode) -> Result<Self, Self::Error> {
+		validate_code(&raw.code)?;
+		Ok(Self { code: raw.code, signer: raw.signer.parse().map_err(Error::signer)? })
+	}
+}
+
+impl From<MsgStoreCode> for RawMsgStoreCode {
+	fn from(msg: MsgStoreCode) -> Self {
+		RawMsgStoreCode { signer: msg.signer.to_string(), code: msg.code }
+	}
+}
+
+///
+/// Message definition for `MsgMigrateContract`, which moves a wasm client to the contract with
+/// `code_hash`. The new contract's migrate entry point receives `msg` along with the client's
+/// current state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgMigrateContract {
+	pub client_id: ClientId,
+	pub code_hash: Vec<u8>,
+	pub msg: Vec<u8>,
+	pub signer: Signer,
+}
+
+impl Msg for MsgMigrateContract {
+	type ValidationError = Error;
+	type Raw = RawMsgMigrateContract;
+
+	fn route(&self) -> String {
+		ibc::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		MIGRATE_CONTRACT_TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgMigrateContract> for MsgMigrateContract {}
+
+impl TryFrom<RawMsgMigrateContract> for MsgMigrateContract {
+	type Error = Error;
+
+	fn try_from(raw: RawMsgMigrateContract) -> Result<Self, Self::Error> {
+		validate_code_hash(&raw.code_hash)?;
+		Ok(Self {
+			client_id: raw.client_id.parse().map_err(Error::identifier)?,
+			code_hash: raw.code_hash,
+			msg: raw.msg,
+			signer: raw.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgMigrateContract> for RawMsgMigrateContract {
+	fn from(msg: MsgMigrateContract) -> Self {
+		RawMsgMigrateContract {
+			signer: msg.signer.to_string(),
+			client_id: msg.client_id.to_string(),
+			code_hash: msg.code_hash,
+			msg: msg.msg,
+		}
+	}
+}
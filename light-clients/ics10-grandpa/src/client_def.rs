@@ -19,12 +19,12 @@ use ibc::core::ics02_client::{
 };
 
 use crate::client_message::{ClientMessage, RelayChainHeader};
-use alloc::{format, string::ToString, vec, vec::Vec};
+use alloc::{collections::BTreeMap, format, string::ToString, vec, vec::Vec};
 use codec::Decode;
 use core::marker::PhantomData;
 use finality_grandpa::Chain;
 use grandpa_client_primitives::{
-	justification::{find_scheduled_change, AncestryChain, GrandpaJustification},
+	justification::{AncestryChain, GrandpaJustification},
 	ParachainHeadersWithFinalityProof,
 };
 use ibc::{
@@ -177,12 +177,16 @@ where
 					))?
 				}
 
-				let first_valid = first_justification
-					.verify::<H>(client_state.current_set_id, &client_state.current_authorities)
-					.is_ok();
-				let second_valid = second_justification
-					.verify::<H>(client_state.current_set_id, &client_state.current_authorities)
-					.is_ok();
+				let light_client_state: grandpa_client_primitives::ClientState<H256> =
+					client_state.into();
+				let (first_set_id, first_authorities) =
+					light_client_state.authority_set_at(first_target.number);
+				let first_valid =
+					first_justification.verify::<H>(first_set_id, first_authorities).is_ok();
+				let (second_set_id, second_authorities) =
+					light_client_state.authority_set_at(second_target.number);
+				let second_valid =
+					second_justification.verify::<H>(second_set_id, second_authorities).is_ok();
 
 				if !first_valid || !second_valid {
 					Err(Error::Custom("Invalid justification".to_string()))?
@@ -230,11 +234,12 @@ where
 			let header = ancestry.header(&relay_hash).ok_or_else(|| {
 				Error::Custom(format!("No relay chain header found for hash: {relay_hash:?}"))
 			})?;
-			let (height, consensus_state) = ConsensusState::from_header::<H>(
+			let (parachain_header, consensus_state) = ConsensusState::from_header::<H>(
 				parachain_header_proof,
 				client_state.para_id,
 				header.state_root.clone(),
 			)?;
+			let height = Height::new(client_state.para_id as u64, parachain_header.number as u64);
 			let wrapped = Ctx::AnyConsensusState::wrap(&consensus_state)
 				.expect("AnyConsenusState is type checked; qed");
 			consensus_states.push((height, wrapped));
@@ -255,7 +260,7 @@ where
 		let mut heights = consensus_states
 			.iter()
 			.map(|(h, ..)| {
-				// this cast is safe, see [`ConsensusState::from_header`]
+				// this cast is safe, parachain block numbers are u32
 				h.revision_height as u32
 			})
 			.collect::<Vec<_>>();
@@ -272,14 +277,22 @@ where
 			client_state.latest_para_height = *max_height
 		}
 
+		let mut finalized_headers = finalized
+			.iter()
+			.filter_map(|hash| ancestry.header(hash))
+			.filter(|header| header.number > client_state.latest_relay_height)
+			.collect::<Vec<_>>();
+		finalized_headers.sort_by_key(|header| header.number);
+		let mut light_client_state: grandpa_client_primitives::ClientState<H256> =
+			client_state.clone().into();
+		light_client_state.apply_authority_changes(&finalized_headers, target.number);
+		client_state.current_set_id = light_client_state.current_set_id;
+		client_state.current_authorities = light_client_state.current_authorities;
+		client_state.pending_authority_change = light_client_state.pending_authority_change;
+
 		client_state.latest_relay_hash = header.finality_proof.block;
 		client_state.latest_relay_height = target.number;
 
-		if let Some(scheduled_change) = find_scheduled_change(target) {
-			client_state.current_set_id += 1;
-			client_state.current_authorities = scheduled_change.next_authorities;
-		}
-
 		H::insert_relay_header_hashes(&finalized);
 
 		Ok((client_state, ConsensusUpdateResult::Batch(consensus_states)))
@@ -307,7 +320,8 @@ where
 		}
 
 		// we also check that this update doesn't include competing consensus states for heights we
-		// already processed.
+		// already processed, and that the parachain headers it carries extend the chain of the
+		// consensus states we already stored.
 		let header = match client_message {
 			ClientMessage::Header(header) => header,
 			_ => unreachable!("We've checked for misbehavior in line 180; qed"),
@@ -315,16 +329,31 @@ where
 		let ancestry =
 			AncestryChain::<RelayChainHeader>::new(&header.finality_proof.unknown_headers);
 
+		let stored_block_hash = |number: u32| -> Result<Option<H256>, Ics02Error> {
+			let height = Height::new(client_state.para_id as u64, number as u64);
+			let cs = match ctx.maybe_consensus_state(&client_id, height)? {
+				Some(cs) => cs,
+				None => return Ok(None),
+			};
+			let cs: ConsensusState = cs
+				.downcast()
+				.ok_or(Ics02Error::client_args_type_mismatch(client_state.client_type()))?;
+			// consensus states stored before block hashes were recorded can't be checked.
+			Ok(Some(cs.block_hash).filter(|hash| !hash.is_zero()))
+		};
+
+		let mut parachain_headers = BTreeMap::new();
 		for (relay_hash, parachain_header_proof) in header.parachain_headers {
 			let header = ancestry.header(&relay_hash).ok_or_else(|| {
 				Error::Custom(format!("No relay chain header found for hash: {relay_hash:?}"))
 			})?;
 
-			let (height, consensus_state) = ConsensusState::from_header::<H>(
+			let (parachain_header, consensus_state) = ConsensusState::from_header::<H>(
 				parachain_header_proof,
 				client_state.para_id,
 				header.state_root.clone(),
 			)?;
+			let height = Height::new(client_state.para_id as u64, parachain_header.number as u64);
 
 			match ctx.maybe_consensus_state(&client_id, height)? {
 				Some(cs) => {
@@ -332,13 +361,40 @@ where
 						.downcast()
 						.ok_or(Ics02Error::client_args_type_mismatch(client_state.client_type()))?;
 
-					if cs != consensus_state {
+					let conflicting_hash =
+						!cs.block_hash.is_zero() && cs.block_hash != consensus_state.block_hash;
+					if cs.root != consensus_state.root ||
+						cs.timestamp != consensus_state.timestamp ||
+						conflicting_hash
+					{
 						// Houston we have a problem
 						return Ok(true)
 					}
 				},
 				None => {},
 			};
+
+			match parachain_headers.insert(parachain_header.number, parachain_header.clone()) {
+				// two different parachain blocks at the same height were finalized.
+				Some(other) if other.hash() != parachain_header.hash() => return Ok(true),
+				_ => {},
+			}
+		}
+
+		// every parachain header must be the child of the block we know at the height below it,
+		// either from this update or from an already stored consensus state.
+		for (number, parachain_header) in &parachain_headers {
+			let parent_number = match number.checked_sub(1) {
+				Some(parent_number) => parent_number,
+				None => continue,
+			};
+			let parent_hash = match parachain_headers.get(&parent_number) {
+				Some(parent) => Some(parent.hash()),
+				None => stored_block_hash(parent_number)?,
+			};
+			if matches!(parent_hash, Some(hash) if hash != parachain_header.parent_hash) {
+				return Ok(true)
+			}
 		}
 
 		Ok(false)
@@ -428,6 +484,27 @@ where
 		))
 	}
 
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<Self::ClientState, Ics02Error> {
+		// Only the relay chain and parachain being tracked need to match, the substitute is
+		// expected to have finalized past the subject's latest heights and authority set.
+		if old_client_state.relay_chain != substitute_client_state.relay_chain ||
+			old_client_state.para_id != substitute_client_state.para_id
+		{
+			return Err(Ics02Error::substitute_client_state_mismatch(
+				"grandpa client tracks a different relay chain or parachain".to_string(),
+			))
+		}
+
+		Ok(ClientState { frozen_height: None, ..substitute_client_state })
+	}
+
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
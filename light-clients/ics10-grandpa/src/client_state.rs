@@ -17,11 +17,15 @@ use crate::{
 	client_def::GrandpaClient,
 	client_message::RelayChainHeader,
 	error::Error,
-	proto::{Authority as RawAuthority, ClientState as RawClientState},
+	proto::{
+		Authority as RawAuthority, ClientState as RawClientState,
+		PendingAuthorityChange as RawPendingAuthorityChange,
+	},
 };
 use alloc::{format, string::ToString, vec::Vec};
 use anyhow::anyhow;
 use core::{marker::PhantomData, time::Duration};
+use grandpa_client_primitives::PendingAuthorityChange;
 use ibc::{
 	core::{ics02_client::client_state::ClientType, ics24_host::identifier::ChainId},
 	Height,
@@ -54,6 +58,8 @@ pub struct ClientState<H> {
 	pub current_set_id: u64,
 	/// authorities for the current round
 	pub current_authorities: AuthorityList,
+	/// Authority set change signalled in a finalized relay chain header, but not yet enacted.
+	pub pending_authority_change: Option<PendingAuthorityChange>,
 	/// phantom type.
 	pub _phantom: PhantomData<H>,
 }
@@ -67,6 +73,7 @@ impl<H> From<ClientState<H>> for grandpa_client_primitives::ClientState<H256> {
 			latest_relay_height: client_state.latest_relay_height,
 			latest_para_height: client_state.latest_para_height,
 			para_id: client_state.para_id,
+			pending_authority_change: client_state.pending_authority_change,
 		}
 	}
 }
@@ -140,6 +147,11 @@ impl<H> ClientState<H> {
 		}
 		Ok(Self { frozen_height: Some(h), ..self })
 	}
+
+	/// Lift a freeze, for recovering a client that was frozen by governance.
+	pub fn unfreeze(self) -> Self {
+		Self { frozen_height: None, ..self }
+	}
 }
 
 impl<H> ibc::core::ics02_client::client_state::ClientState for ClientState<H>
@@ -182,6 +194,10 @@ where
 		self.expired(elapsed)
 	}
 
+	fn trusting_period(&self) -> Duration {
+		self.relay_chain.trusting_period()
+	}
+
 	fn encode_to_vec(&self) -> Vec<u8> {
 		self.encode_vec()
 	}
@@ -191,15 +207,17 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 	type Error = Error;
 
 	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
-		let current_authorities = raw
-			.current_authorities
-			.into_iter()
-			.map(|set| {
-				let id = Public::try_from(&*set.public_key)
-					.map_err(|_| anyhow!("Invalid ed25519 public key"))?;
-				Ok((id.into(), set.weight))
+		let current_authorities = authorities_from_raw(raw.current_authorities)?;
+		let pending_authority_change = raw
+			.pending_authority_change
+			.map(|change| -> Result<_, Error> {
+				Ok(PendingAuthorityChange {
+					next_set_id: change.next_set_id,
+					next_authorities: authorities_from_raw(change.next_authorities)?,
+					effective_height: change.effective_height,
+				})
 			})
-			.collect::<Result<_, Error>>()?;
+			.transpose()?;
 
 		let relay_chain = RelayChain::from_i32(raw.relay_chain)?;
 		if raw.latest_relay_hash.len() != 32 {
@@ -216,6 +234,7 @@ impl<H> TryFrom<RawClientState> for ClientState<H> {
 			para_id: raw.para_id,
 			current_set_id: raw.current_set_id,
 			current_authorities,
+			pending_authority_change,
 			latest_relay_hash,
 			latest_relay_height: raw.latest_relay_height,
 			_phantom: Default::default(),
@@ -235,15 +254,34 @@ impl<H> From<ClientState<H>> for RawClientState {
 			relay_chain: client_state.relay_chain as i32,
 			para_id: client_state.para_id,
 			latest_para_height: client_state.latest_para_height,
-			current_authorities: client_state
-				.current_authorities
-				.into_iter()
-				.map(|(id, weight)| RawAuthority {
-					public_key: <sp_finality_grandpa::AuthorityId as AsRef<[u8]>>::as_ref(&id)
-						.to_vec(),
-					weight,
-				})
-				.collect(),
+			current_authorities: authorities_to_raw(client_state.current_authorities),
+			pending_authority_change: client_state.pending_authority_change.map(|change| {
+				RawPendingAuthorityChange {
+					next_set_id: change.next_set_id,
+					next_authorities: authorities_to_raw(change.next_authorities),
+					effective_height: change.effective_height,
+				}
+			}),
 		}
 	}
 }
+
+fn authorities_from_raw(raw: Vec<RawAuthority>) -> Result<AuthorityList, Error> {
+	raw.into_iter()
+		.map(|set| {
+			let id = Public::try_from(&*set.public_key)
+				.map_err(|_| anyhow!("Invalid ed25519 public key"))?;
+			Ok((id.into(), set.weight))
+		})
+		.collect()
+}
+
+fn authorities_to_raw(authorities: AuthorityList) -> Vec<RawAuthority> {
+	authorities
+		.into_iter()
+		.map(|(id, weight)| RawAuthority {
+			public_key: <sp_finality_grandpa::AuthorityId as AsRef<[u8]>>::as_ref(&id).to_vec(),
+			weight,
+		})
+		.collect()
+}
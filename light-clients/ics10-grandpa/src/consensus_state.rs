@@ -25,31 +25,48 @@ use crate::proto::ConsensusState as RawConsensusState;
 
 use crate::error::Error;
 use grandpa_client_primitives::{parachain_header_storage_key, ParachainHeaderProofs};
-use ibc::{core::ics23_commitment::commitment::CommitmentRoot, timestamp::Timestamp, Height};
+use ibc::{core::ics23_commitment::commitment::CommitmentRoot, timestamp::Timestamp};
 use light_client_common::{decode_timestamp_extrinsic, state_machine};
 use primitive_types::H256;
-use sp_runtime::{generic, traits::BlakeTwo256, SaturatedConversion};
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, Header},
+	SaturatedConversion,
+};
 use sp_trie::StorageProof;
 
 /// Protobuf type url for GRANDPA Consensus State
 pub const GRANDPA_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.grandpa.v1.ConsensusState";
 
+/// Header of the parachain tracked by the client.
+pub type ParachainHeader = generic::Header<u32, BlakeTwo256>;
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ConsensusState {
 	pub timestamp: Time,
 	pub root: CommitmentRoot,
+	/// Hash of the parachain block this consensus state was extracted from, zero if it was stored
+	/// before block hashes were recorded.
+	pub block_hash: H256,
 }
 
 impl ConsensusState {
-	pub fn new(root: Vec<u8>, timestamp: Time) -> Self {
-		Self { timestamp, root: root.into() }
+	pub fn new(root: Vec<u8>, timestamp: Time, block_hash: H256) -> Self {
+		Self { timestamp, root: root.into(), block_hash }
+	}
+
+	/// Hash of the parachain block this consensus state commits to.
+	pub fn block_hash(&self) -> H256 {
+		self.block_hash
 	}
 
+	/// Extracts the consensus state of the parachain header proven in `relay_state_root`, returns
+	/// it together with the parachain header.
 	pub fn from_header<H>(
 		parachain_header_proof: ParachainHeaderProofs,
 		para_id: u32,
 		relay_state_root: H256,
-	) -> Result<(Height, Self), Error>
+	) -> Result<(ParachainHeader, Self), Error>
 	where
 		H: grandpa_client_primitives::HostFunctions,
 	{
@@ -65,9 +82,9 @@ impl ConsensusState {
 		.flatten()
 		.ok_or_else(|| anyhow!("Invalid state proof for parachain header"))?;
 
-		let parachain_header =
-			generic::Header::<u32, BlakeTwo256>::decode(&mut &parachain_header_bytes[..])?;
+		let parachain_header = ParachainHeader::decode(&mut &parachain_header_bytes[..])?;
 		let root = parachain_header.state_root.0.to_vec();
+		let block_hash = parachain_header.hash();
 
 		let timestamp = decode_timestamp_extrinsic(&parachain_header_proof.extrinsic)?;
 		let duration = core::time::Duration::from_millis(timestamp);
@@ -75,10 +92,7 @@ impl ConsensusState {
 			.into_tm_time()
 			.ok_or_else(|| anyhow!("Error decoding Timestamp, timestamp cannot be zero"))?;
 
-		Ok((
-			Height::new(para_id as u64, parachain_header.number as u64),
-			Self { root: root.into(), timestamp },
-		))
+		Ok((parachain_header, Self { root: root.into(), timestamp, block_hash }))
 	}
 }
 
@@ -112,7 +126,16 @@ impl TryFrom<RawConsensusState> for ConsensusState {
 			Error::Custom(format!("Invalid consensus state: invalid timestamp {e}"))
 		})?;
 
-		Ok(Self { root: raw.root.into(), timestamp })
+		let block_hash = match raw.block_hash.len() {
+			0 => H256::zero(),
+			32 => H256::from_slice(&raw.block_hash),
+			len =>
+				return Err(Error::Custom(format!(
+					"Invalid consensus state: block hash of {len} bytes"
+				))),
+		};
+
+		Ok(Self { root: raw.root.into(), timestamp, block_hash })
 	}
 }
 
@@ -121,7 +144,11 @@ impl From<ConsensusState> for RawConsensusState {
 		let tpb::Timestamp { seconds, nanos } = value.timestamp.into();
 		let timestamp = prost_types::Timestamp { seconds, nanos };
 
-		RawConsensusState { timestamp: Some(timestamp), root: value.root.into_vec() }
+		RawConsensusState {
+			timestamp: Some(timestamp),
+			root: value.root.into_vec(),
+			block_hash: value.block_hash.as_bytes().to_vec(),
+		}
 	}
 }
 
@@ -134,6 +161,7 @@ pub mod test_util {
 		AnyConsensusState::Grandpa(ConsensusState {
 			timestamp: Time::now(),
 			root: vec![0; 32].into(),
+			block_hash: H256::zero(),
 		})
 	}
 }
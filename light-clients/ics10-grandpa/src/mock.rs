@@ -24,7 +24,8 @@ use ibc::{
 	core::{
 		ics02_client,
 		ics02_client::{
-			client_consensus::ConsensusState as _, client_state::ClientState as _,
+			client_consensus::ConsensusState as _,
+			client_state::{ClientState as _, ClientType},
 			context::ClientTypes,
 		},
 	},
@@ -170,6 +171,10 @@ impl ClientTypes for MockClientTypes {
 
 impl HostBlockType for MockClientTypes {
 	type HostBlock = MockHostBlock;
+
+	fn supported_client_types() -> Vec<ClientType> {
+		vec![MockClientState::client_type(), ClientState::<HostFunctionsManager>::client_type()]
+	}
 }
 
 impl From<MockHostBlock> for AnyClientMessage {
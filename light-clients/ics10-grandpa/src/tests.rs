@@ -14,9 +14,10 @@
 // limitations under the License.
 
 use crate::{
+	client_def::GrandpaClient,
 	client_message::{ClientMessage, Header, RelayChainHeader},
 	client_state::ClientState,
-	consensus_state::ConsensusState,
+	consensus_state::{ConsensusState, ParachainHeader},
 	mock::{
 		AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager, MockClientTypes,
 	},
@@ -24,16 +25,18 @@ use crate::{
 use beefy_prover::helpers::{
 	fetch_timestamp_extrinsic_with_proof, unsafe_arc_cast, TimeStampExtWithProof,
 };
-use codec::Decode;
+use codec::{Compact, Decode, Encode};
 use finality_grandpa_rpc::GrandpaApiClient;
 use futures::stream::StreamExt;
 use grandpa_client_primitives::{
-	justification::GrandpaJustification, parachain_header_storage_key, ParachainHeaderProofs,
+	justification::GrandpaJustification, parachain_header_storage_key, FinalityProof,
+	ParachainHeaderProofs,
 };
 use grandpa_prover::{polkadot, GrandpaProver, JustificationNotification};
 use ibc::{
 	core::{
 		ics02_client::{
+			client_def::ClientDef,
 			client_state::ClientState as _,
 			context::{ClientKeeper, ClientReader},
 			handler::{dispatch, ClientResult::Update},
@@ -47,10 +50,13 @@ use ibc::{
 	handler::HandlerOutput,
 	mock::{context::MockContext, host::MockHostType},
 	test_utils::get_dummy_account_id,
+	timestamp::Timestamp,
 	Height,
 };
 use primitive_types::H256;
-use std::{mem::size_of_val, time::Duration};
+use sp_runtime::traits::{BlakeTwo256, Header as _};
+use sp_trie::{LayoutV0, MemoryDB, TrieDBMut, TrieMut};
+use std::{collections::BTreeMap, mem::size_of_val, time::Duration};
 use subxt::{ext::sp_core::hexdisplay::AsBytesRef, PolkadotConfig};
 
 pub type Justification = GrandpaJustification<RelayChainHeader>;
@@ -140,6 +146,7 @@ async fn test_continuous_update_of_grandpa_client() {
 			para_id: prover.para_id,
 			current_set_id: client_state.current_set_id,
 			current_authorities: client_state.current_authorities,
+			pending_authority_change: client_state.pending_authority_change,
 			_phantom: Default::default(),
 		};
 		let subxt_block_number: subxt::rpc::BlockNumber = decoded_para_head.number.into();
@@ -294,3 +301,128 @@ async fn test_continuous_update_of_grandpa_client() {
 		}
 	}
 }
+
+const PARA_ID: u32 = 2000;
+const TIMESTAMP_MILLIS: u64 = 1_650_894_363_000;
+
+fn parachain_header(number: u32, parent_hash: H256) -> ParachainHeader {
+	ParachainHeader {
+		parent_hash,
+		number,
+		state_root: Default::default(),
+		extrinsics_root: Default::default(),
+		digest: Default::default(),
+	}
+}
+
+/// Builds a grandpa header whose relay chain block proves the given parachain header was
+/// finalized. Only the parts read by [`GrandpaClient::check_for_misbehaviour`] are filled in.
+fn grandpa_header(parachain_header: &ParachainHeader) -> ClientMessage {
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let key = parachain_header_storage_key(PARA_ID);
+	let mut root = Default::default();
+	let state_root = {
+		let mut trie = TrieDBMut::<LayoutV0<BlakeTwo256>>::new(&mut db, &mut root);
+		trie.insert(key.as_ref(), &parachain_header.encode().encode()).unwrap();
+		*trie.root()
+	};
+	let state_proof = db.drain().into_iter().map(|(_, (node, ..))| node).collect();
+
+	let mut extrinsic = (1u8, 0u8, Compact(TIMESTAMP_MILLIS)).encode();
+	extrinsic.insert(0, 0);
+	extrinsic.insert(0, 0);
+
+	let relay_header = RelayChainHeader {
+		parent_hash: Default::default(),
+		number: 1,
+		state_root,
+		extrinsics_root: Default::default(),
+		digest: Default::default(),
+	};
+	let parachain_headers = BTreeMap::from([(
+		relay_header.hash(),
+		ParachainHeaderProofs { state_proof, extrinsic, extrinsic_proof: vec![] },
+	)]);
+	let finality_proof = FinalityProof {
+		block: relay_header.hash(),
+		justification: vec![],
+		unknown_headers: vec![relay_header],
+	};
+
+	ClientMessage::Header(Header { finality_proof, parachain_headers })
+}
+
+/// A context holding a grandpa client whose latest consensus state is for `latest_header`.
+fn context_with_parachain_header(
+	client_id: &ClientId,
+	latest_header: &ParachainHeader,
+) -> (MockContext<MockClientTypes>, ClientState<HostFunctionsManager>) {
+	let mut ctx = MockContext::<MockClientTypes>::default();
+	let client_state = ClientState::<HostFunctionsManager> {
+		latest_para_height: latest_header.number,
+		para_id: PARA_ID,
+		..Default::default()
+	};
+	let consensus_state = ConsensusState::new(
+		latest_header.state_root.as_bytes().to_vec(),
+		Timestamp::from_nanoseconds(TIMESTAMP_MILLIS * 1_000_000)
+			.unwrap()
+			.into_tm_time()
+			.unwrap(),
+		latest_header.hash(),
+	);
+	let height = Height::new(PARA_ID as u64, latest_header.number as u64);
+	ctx.store_client_state(client_id.clone(), AnyClientState::Grandpa(client_state.clone()))
+		.unwrap();
+	ctx.store_consensus_state(
+		client_id.clone(),
+		height,
+		AnyConsensusState::Grandpa(consensus_state),
+	)
+	.unwrap();
+	(ctx, client_state)
+}
+
+#[test]
+fn accepts_parachain_headers_extending_stored_chain() {
+	let client_id = ClientId::new(&ClientState::<HostFunctionsManager>::client_type(), 0).unwrap();
+	let latest_header = parachain_header(1, H256::repeat_byte(1));
+	let (ctx, client_state) = context_with_parachain_header(&client_id, &latest_header);
+
+	let client_message = grandpa_header(&parachain_header(2, latest_header.hash()));
+	let misbehaviour = GrandpaClient::<HostFunctionsManager>::default()
+		.check_for_misbehaviour(&ctx, client_id, client_state, client_message)
+		.unwrap();
+	assert!(!misbehaviour);
+}
+
+#[test]
+fn freezes_client_on_parachain_fork() {
+	let client_id = ClientId::new(&ClientState::<HostFunctionsManager>::client_type(), 0).unwrap();
+	let latest_header = parachain_header(1, H256::repeat_byte(1));
+	let (ctx, client_state) = context_with_parachain_header(&client_id, &latest_header);
+	let client = GrandpaClient::<HostFunctionsManager>::default();
+
+	// a child of some other block at the stored height.
+	let fork_parent = parachain_header(1, H256::repeat_byte(2));
+	let client_message = grandpa_header(&parachain_header(2, fork_parent.hash()));
+	let misbehaviour = client
+		.check_for_misbehaviour(
+			&ctx,
+			client_id.clone(),
+			client_state.clone(),
+			client_message.clone(),
+		)
+		.unwrap();
+	assert!(misbehaviour);
+
+	let client_state = client.update_state_on_misbehaviour(client_state, client_message).unwrap();
+	assert_eq!(client_state.frozen_height, Some(Height::new(PARA_ID as u64, 1)));
+
+	// a competing block at the stored height itself.
+	let (ctx, client_state) = context_with_parachain_header(&client_id, &latest_header);
+	let misbehaviour = client
+		.check_for_misbehaviour(&ctx, client_id, client_state, grandpa_header(&fork_parent))
+		.unwrap();
+	assert!(misbehaviour);
+}
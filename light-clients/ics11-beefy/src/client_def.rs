@@ -262,6 +262,27 @@ where
 		Err(Error::Custom("Beefy Client doesn't need client upgrades".to_string()).into())
 	}
 
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<Self::ClientState, Ics02Error> {
+		if old_client_state.relay_chain != substitute_client_state.relay_chain ||
+			old_client_state.para_id != substitute_client_state.para_id ||
+			old_client_state.beefy_activation_block !=
+				substitute_client_state.beefy_activation_block
+		{
+			return Err(Ics02Error::substitute_client_state_mismatch(
+				"beefy client tracks a different relay chain or parachain".to_string(),
+			))
+		}
+
+		Ok(ClientState { frozen_height: None, ..substitute_client_state })
+	}
+
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
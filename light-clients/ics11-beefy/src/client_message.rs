@@ -126,8 +126,7 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 										))
 									})?;
 								let parent_hash =
-									H256::decode(&mut mmr_partial_leaf.parent_hash.as_slice())
-										.unwrap();
+									H256::decode(&mut mmr_partial_leaf.parent_hash.as_slice())?;
 								let beefy_next_authority_set = if let Some(next_set) =
 									mmr_partial_leaf.beefy_next_authority_set
 								{
@@ -179,15 +178,14 @@ impl TryFrom<RawClientMessage> for ClientMessage {
 									timestamp_extrinsic: raw_para_header.timestamp_extrinsic,
 								})
 							})
-							.collect::<Result<Vec<_>, Error>>()
-							.ok();
-						parachain_headers.map(|parachain_headers| ParachainHeadersWithProof {
+							.collect::<Result<Vec<_>, Error>>()?;
+						Ok::<_, Error>(ParachainHeadersWithProof {
 							headers: parachain_headers,
 							mmr_proofs: consensus_update.mmr_proofs,
 							mmr_size: consensus_update.mmr_size,
 						})
 					})
-					.flatten();
+					.transpose()?;
 
 				let mmr_update_proof = if let Some(mmr_update) = raw_header.client_state {
 					let commitment = mmr_update
@@ -183,6 +183,11 @@ impl<H: Clone> ClientState<H> {
 		Ok(Self { frozen_height: Some(h), ..self })
 	}
 
+	/// Lift a freeze, for recovering a client that was frozen by governance.
+	pub fn unfreeze(self) -> Self {
+		Self { frozen_height: None, ..self }
+	}
+
 	/// Verify that the client is at a sufficient height and unfrozen at the given height
 	pub fn verify_height(&self, height: Height) -> Result<(), Error> {
 		let latest_para_height = Height::new(self.para_id.into(), self.latest_para_height.into());
@@ -280,6 +285,10 @@ where
 		self.expired(elapsed)
 	}
 
+	fn trusting_period(&self) -> Duration {
+		self.relay_chain.trusting_period()
+	}
+
 	fn encode_to_vec(&self) -> Vec<u8> {
 		self.encode_vec()
 	}
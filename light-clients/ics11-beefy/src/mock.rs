@@ -27,7 +27,9 @@ use ibc::{
 	core::{
 		ics02_client,
 		ics02_client::{
-			client_consensus::ConsensusState, client_state::ClientState, context::ClientTypes,
+			client_consensus::ConsensusState,
+			client_state::{ClientState, ClientType},
+			context::ClientTypes,
 		},
 	},
 	mock::{
@@ -155,6 +157,13 @@ impl ClientTypes for MockClientTypes {
 
 impl HostBlockType for MockClientTypes {
 	type HostBlock = MockHostBlock;
+
+	fn supported_client_types() -> Vec<ClientType> {
+		vec![
+			MockClientState::client_type(),
+			BeefyClientState::<HostFunctionsManager>::client_type(),
+		]
+	}
 }
 
 impl From<MockHostBlock> for AnyClientMessage {
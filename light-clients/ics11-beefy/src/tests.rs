@@ -23,20 +23,25 @@ use crate::{
 	mock::{
 		AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager, MockClientTypes,
 	},
+	proto::{client_message::Message as RawMessage, ClientMessage as RawClientMessage},
 };
-use beefy_light_client_primitives::{NodesUtils, PartialMmrLeaf};
+use beefy_light_client_primitives::{
+	BeefyNextAuthoritySet, HostFunctions as _, MerkleHasher, NodesUtils, PartialMmrLeaf,
+};
+use beefy_primitives::mmr::{MmrLeaf, MmrLeafVersion};
 use beefy_prover::{
 	helpers::{fetch_timestamp_extrinsic_with_proof, TimeStampExtWithProof},
 	runtime, Prover,
 };
-use codec::{Decode, Encode};
+use codec::{Compact, Decode, Encode};
 use futures::stream::StreamExt;
 use ibc::{
 	core::{
 		ics02_client::{
+			client_def::ConsensusUpdateResult,
 			client_state::ClientState as _,
 			context::{ClientKeeper, ClientReader},
-			handler::{dispatch, ClientResult::Update},
+			handler::{dispatch, update_client, ClientResult::Update},
 			msgs::{
 				create_client::MsgCreateAnyClient, update_client::MsgUpdateAnyClient, ClientMsg,
 			},
@@ -49,8 +54,14 @@ use ibc::{
 	test_utils::get_dummy_account_id,
 	Height,
 };
+use ibc_proto::google::protobuf::Any;
 use json::Value;
+use mmr_lib::{util::MemStore, MMR};
+use sp_core::H256;
+use sp_runtime::{generic::Header as SubstrateHeader, traits::BlakeTwo256};
+use sp_trie::{generate_trie_proof, LayoutV0, MemoryDB, TrieDBMut, TrieMut};
 use std::time::Duration;
+use tendermint_proto::Protobuf;
 use subxt::{
 	rpc::{rpc_params, Subscription},
 	PolkadotConfig,
@@ -301,3 +312,227 @@ async fn test_continuous_update_of_beefy_client() {
 		}
 	}
 }
+
+const PARA_ID: u32 = 2000;
+
+/// A header of our parachain at `number`, together with its timestamp extrinsic and the proof of
+/// that extrinsic in the header's extrinsics root.
+fn parachain_header_with_timestamp(
+	number: u32,
+) -> (SubstrateHeader<u32, BlakeTwo256>, Vec<u8>, Vec<Vec<u8>>) {
+	let call = (3u8, 0u8, Compact(1_650_894_363_000u64 + number as u64)).encode();
+	// the extrinsic length and version precede the call.
+	let timestamp_extrinsic = [Compact(call.len() as u32 + 1).encode(), vec![4u8], call].concat();
+	let key = Compact(0u32).encode();
+	let mut db = MemoryDB::<BlakeTwo256>::default();
+	let extrinsics_root = {
+		let mut root = Default::default();
+		let mut trie = <TrieDBMut<LayoutV0<BlakeTwo256>>>::new(&mut db, &mut root);
+		trie.insert(&key, &timestamp_extrinsic).unwrap();
+		*trie.root()
+	};
+	let extrinsic_proof =
+		generate_trie_proof::<LayoutV0<BlakeTwo256>, _, _, _>(&db, extrinsics_root, vec![&key])
+			.unwrap();
+	let header = SubstrateHeader {
+		parent_hash: Default::default(),
+		number,
+		state_root: H256::repeat_byte(number as u8),
+		extrinsics_root,
+		digest: Default::default(),
+	};
+	(header, timestamp_extrinsic, extrinsic_proof)
+}
+
+/// Builds an mmr of `leaf_count` relay chain leaves, where the leaves at `proven` carry a header of
+/// our parachain. Returns a client state trusting the mmr root, together with a header proving all
+/// of those parachain headers with a single batch proof.
+fn batch_proof_header(
+	leaf_count: u32,
+	proven: &[u32],
+) -> (BeefyClientState<HostFunctionsManager>, BeefyHeader) {
+	let authority_set = BeefyNextAuthoritySet { id: 1, len: 1, root: H256::zero() };
+	let store = MemStore::default();
+	let mut mmr = MMR::<H256, MerkleHasher<HostFunctionsManager>, _>::new(0, &store);
+	let mut positions = vec![];
+	let mut headers = vec![];
+
+	for leaf_index in 0..leaf_count {
+		// (para_id, encoded header) pairs committed to in the parachain heads root.
+		let mut heads = vec![(PARA_ID + 1, vec![0u8]).encode()];
+		let parachain_header = proven.contains(&leaf_index).then(|| {
+			let parachain_header = parachain_header_with_timestamp(leaf_index);
+			heads.insert(0, (PARA_ID, parachain_header.0.encode()).encode());
+			parachain_header
+		});
+
+		let leaves =
+			heads.iter().map(|head| HostFunctionsManager::keccak_256(head)).collect::<Vec<_>>();
+		let tree =
+			rs_merkle::MerkleTree::<MerkleHasher<HostFunctionsManager>>::from_leaves(&leaves);
+		let partial_mmr_leaf = PartialMmrLeaf {
+			version: MmrLeafVersion::new(0, 0),
+			parent_number_and_hash: (leaf_index, H256::repeat_byte(leaf_index as u8)),
+			beefy_next_authority_set: authority_set.clone(),
+		};
+		let mmr_leaf = MmrLeaf {
+			version: partial_mmr_leaf.version,
+			parent_number_and_hash: partial_mmr_leaf.parent_number_and_hash,
+			beefy_next_authority_set: partial_mmr_leaf.beefy_next_authority_set.clone(),
+			leaf_extra: H256::from(tree.root().unwrap()),
+		};
+		let leaf_hash = HostFunctionsManager::keccak_256(&mmr_leaf.encode());
+		let position = mmr.push(H256::from(leaf_hash)).unwrap();
+
+		if let Some((parachain_header, timestamp_extrinsic, extrinsic_proof)) = parachain_header {
+			positions.push(position);
+			headers.push(BeefyParachainHeader {
+				parachain_header,
+				partial_mmr_leaf,
+				parachain_heads_proof: tree.proof(&[0]).proof_hashes().to_vec(),
+				heads_leaf_index: 0,
+				heads_total_count: heads.len() as u32,
+				extrinsic_proof,
+				timestamp_extrinsic,
+			});
+		}
+	}
+
+	let client_state = BeefyClientState {
+		chain_id: ChainId::new("relay-chain".to_string(), 0),
+		mmr_root_hash: mmr.get_root().unwrap(),
+		latest_beefy_height: leaf_count,
+		beefy_activation_block: 0,
+		para_id: PARA_ID,
+		authority: authority_set.clone(),
+		next_authority_set: authority_set,
+		..Default::default()
+	};
+	let mmr_proof = mmr.gen_proof(positions).unwrap();
+	let header = BeefyHeader {
+		headers_with_proof: Some(ParachainHeadersWithProof {
+			headers,
+			mmr_proofs: mmr_proof.proof_items().iter().map(|item| item.encode()).collect(),
+			mmr_size: NodesUtils::new(leaf_count as u64).size(),
+		}),
+		mmr_update_proof: None,
+	};
+
+	(client_state, header)
+}
+
+/// A context holding `client_state`, whose latest consensus state is that of parachain block 1.
+fn context_with_beefy_client(
+	client_id: &ClientId,
+	mut client_state: BeefyClientState<HostFunctionsManager>,
+) -> MockContext<MockClientTypes> {
+	let mut ctx = MockContext::<MockClientTypes>::default();
+	let (parachain_header, timestamp_extrinsic, extrinsic_proof) =
+		parachain_header_with_timestamp(1);
+	client_state.latest_para_height = parachain_header.number;
+	let consensus_state = ConsensusState::from_header(BeefyParachainHeader {
+		parachain_header,
+		partial_mmr_leaf: PartialMmrLeaf {
+			version: Default::default(),
+			parent_number_and_hash: Default::default(),
+			beefy_next_authority_set: Default::default(),
+		},
+		parachain_heads_proof: vec![],
+		heads_leaf_index: 0,
+		heads_total_count: 0,
+		extrinsic_proof,
+		timestamp_extrinsic,
+	})
+	.unwrap();
+	let height = client_state.latest_height();
+	let (host_timestamp, host_height) = (ctx.host_timestamp(), ctx.host_height());
+	ctx.store_client_state(client_id.clone(), AnyClientState::Beefy(client_state)).unwrap();
+	ctx.store_consensus_state(client_id.clone(), height, AnyConsensusState::Beefy(consensus_state))
+		.unwrap();
+	ctx.store_update_time(client_id.clone(), height, host_timestamp).unwrap();
+	ctx.store_update_height(client_id.clone(), height, host_height).unwrap();
+	ctx
+}
+
+/// Sends `header` through its protobuf encoding, the way a relayer submits it.
+fn update_message(
+	client_id: &ClientId,
+	header: BeefyHeader,
+) -> MsgUpdateAnyClient<MockContext<MockClientTypes>> {
+	let any = Any::from(AnyClientMessage::Beefy(ClientMessage::Header(header)));
+	MsgUpdateAnyClient {
+		client_id: client_id.clone(),
+		client_message: AnyClientMessage::try_from(any).unwrap(),
+		signer: get_dummy_account_id(),
+	}
+}
+
+#[test]
+fn test_batch_proof_header_round_trips_through_protobuf() {
+	let (_, header) = batch_proof_header(6, &[2, 3, 4]);
+
+	let encoded = ClientMessage::Header(header.clone()).encode_vec();
+	let decoded = match ClientMessage::decode_vec(&encoded).unwrap() {
+		ClientMessage::Header(decoded) => decoded,
+		message => panic!("expected a header, got {:?}", message),
+	};
+	assert_eq!(decoded, header);
+	assert_eq!(decoded.headers_with_proof.unwrap().headers.len(), 3);
+}
+
+#[test]
+fn test_batch_proof_header_with_malformed_parachain_header_fails_to_decode() {
+	let (_, header) = batch_proof_header(6, &[2, 3, 4]);
+	let mut raw = RawClientMessage::from(ClientMessage::Header(header));
+	match raw.message {
+		Some(RawMessage::Header(ref mut raw_header)) => raw_header
+			.consensus_state
+			.as_mut()
+			.unwrap()
+			.parachain_headers[1]
+			.parachain_header
+			.truncate(4),
+		_ => unreachable!(),
+	}
+
+	// a single bad header must not silently drop the rest of the batch.
+	assert!(ClientMessage::try_from(raw).is_err());
+}
+
+#[test]
+fn test_update_beefy_client_with_batch_proof() {
+	let client_id = ClientId::new(&ClientState::<HostFunctionsManager>::client_type(), 0).unwrap();
+	let (client_state, header) = batch_proof_header(6, &[2, 3, 4]);
+	let ctx = context_with_beefy_client(&client_id, client_state);
+
+	let output = update_client::process(&ctx, update_message(&client_id, header)).unwrap();
+
+	let result = match output.result {
+		Update(result) => result,
+		_ => unreachable!(),
+	};
+	assert_eq!(result.client_state.latest_height(), Height::new(PARA_ID as u64, 4));
+	match result.consensus_state {
+		Some(ConsensusUpdateResult::Batch(consensus_states)) => {
+			let heights = consensus_states.iter().map(|(height, _)| *height).collect::<Vec<_>>();
+			let expected = (2..=4).map(|h| Height::new(PARA_ID as u64, h)).collect::<Vec<_>>();
+			assert_eq!(heights, expected);
+		},
+		state => panic!("expected a batch of consensus states, got {:?}", state),
+	}
+}
+
+#[test]
+fn test_batch_proof_with_tampered_leaf_fails_update() {
+	let client_id = ClientId::new(&ClientState::<HostFunctionsManager>::client_type(), 0).unwrap();
+	let (client_state, mut header) = batch_proof_header(6, &[2, 3, 4]);
+	let ctx = context_with_beefy_client(&client_id, client_state);
+	// the second leaf no longer matches the one committed to in the mmr.
+	header.headers_with_proof.as_mut().unwrap().headers[1]
+		.partial_mmr_leaf
+		.parent_number_and_hash
+		.1 = H256::repeat_byte(0xff);
+
+	let result = update_client::process(&ctx, update_message(&client_id, header));
+	assert!(result.is_err());
+}
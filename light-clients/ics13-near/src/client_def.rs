@@ -20,13 +20,18 @@ use super::{
 	header::NearHeader,
 	types::{ApprovalInner, CryptoHash, LightClientBlockView},
 };
-use crate::header::NearClientMessage;
+use crate::{
+	header::NearClientMessage,
+	trie::{verify_membership, verify_non_membership},
+};
 use borsh::BorshSerialize;
 use core::fmt::Debug;
 use ibc::{
 	core::{
 		ics02_client::{
+			client_consensus::ConsensusState as _,
 			client_def::{ClientDef, ConsensusUpdateResult},
+			client_state::ClientState as _,
 			error::Error,
 		},
 		ics03_connection::connection::ConnectionEnd,
@@ -36,7 +41,13 @@ use ibc::{
 			packet::Sequence,
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
+				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+			},
+		},
 		ics26_routing::context::ReaderContext,
 	},
 	prelude::*,
@@ -44,6 +55,7 @@ use ibc::{
 };
 use ics23::HostFunctionsProvider;
 use std::marker::PhantomData;
+use tendermint_proto::Protobuf;
 
 pub trait HostFunctionsTrait:
 	HostFunctions + HostFunctionsProvider + Clone + Debug + PartialEq + Eq + Default + Send + Sync
@@ -160,20 +172,17 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		&self,
 		_ctx: &Ctx,
 		_client_id: ClientId,
-		_client_state: Self::ClientState,
-		_client_message: Self::ClientMessage,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		// 1. create new client state from this header, return that.
-		// 2. as well as all the neccessary consensus states.
-		//
-		//
-		// []--[]--[]--[]--[]--[]--[]--[]--[]--[]
-		// 11  12  13  14  15  16  17  18  19  20 <- block merkle root
-		// ^                                    ^
-		// |    <-------consensus states----->  |
-		// current state                       new state
-
-		todo!()
+		let block_view = match client_message {
+			NearClientMessage::Header(header) => header.get_light_client_block_view().clone(),
+		};
+		let consensus_state = ConsensusState::new(&block_view);
+		let wrapped = Ctx::AnyConsensusState::wrap(&consensus_state).ok_or_else(|| {
+			Error::unknown_consensus_state_type("Ctx::AnyConsensusState".to_string())
+		})?;
+		Ok((client_state.with_head(block_view), ConsensusUpdateResult::Single(wrapped)))
 	}
 
 	fn update_state_on_misbehaviour(
@@ -207,19 +216,40 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		todo!()
 	}
 
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		_old_client_state: Self::ClientState,
+		_substitute_client_state: Self::ClientState,
+	) -> Result<Self::ClientState, Error> {
+		Err(Error::implementation_specific(
+			"client recovery is not supported for NEAR clients".to_string(),
+		))
+	}
+
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_client_id: &ClientId,
-		_consensus_height: Height,
-		_expected_consensus_state: &Ctx::AnyConsensusState,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		consensus_height: Height,
+		expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ClientConsensusStatePath {
+			client_id: client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let value = expected_consensus_state.encode_to_vec();
+		verify_membership::<H, _>(prefix, proof, root, path, value)?;
+		Ok(())
 	}
 
 	// Consensus state will be verified in the verification functions  before these are called
@@ -227,112 +257,205 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_connection_id: &ConnectionId,
-		_expected_connection_end: &ConnectionEnd,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec();
+		verify_membership::<H, _>(prefix, proof, root, path, value)?;
+		Ok(())
 	}
 
 	fn verify_channel_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_expected_channel_end: &ChannelEnd,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec();
+		verify_membership::<H, _>(prefix, proof, root, path, value)?;
+		Ok(())
 	}
 
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_client_id: &ClientId,
-		_expected_client_state: &Ctx::AnyClientState,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ClientStatePath(client_id.clone());
+		let value = expected_client_state.encode_to_vec();
+		verify_membership::<H, _>(prefix, proof, root, path, value)?;
+		Ok(())
 	}
 
 	fn verify_packet_data<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
-		_commitment: PacketCommitment,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			commitment_path,
+			commitment.into_vec(),
+		)?;
+		Ok(())
 	}
 
 	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
-		_ack: AcknowledgementCommitment,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: AcknowledgementCommitment,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			ack_path,
+			ack.into_vec(),
+		)?;
+		Ok(())
 	}
 
 	fn verify_next_sequence_recv<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let seq_bytes = u64::from(sequence)
+			.try_to_vec()
+			.map_err(|_| Error::from(NearError::serialization_error()))?;
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		verify_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			seq_path,
+			seq_bytes,
+		)?;
+		Ok(())
 	}
 
 	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed(ctx, height, connection_end)?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_non_membership::<H, _>(
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			receipt_path,
+		)?;
+		Ok(())
+	}
+}
+
+fn verify_delay_passed<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	height: Height,
+	connection_end: &ConnectionEnd,
+) -> Result<(), NearError> {
+	let current_time = ctx.host_timestamp();
+	let current_height = ctx.host_height();
+
+	let client_id = connection_end.client_id();
+	let processed_time = ctx
+		.client_update_time(client_id, height)
+		.map_err(|_| NearError::processed_time_not_found(client_id.clone(), height))?;
+	let processed_height = ctx
+		.client_update_height(client_id, height)
+		.map_err(|_| NearError::processed_height_not_found(client_id.clone(), height))?;
+
+	let delay_period_time = connection_end.delay_period();
+	let delay_period_blocks = ctx.block_delay(delay_period_time);
+
+	let earliest_time = (processed_time + delay_period_time)
+		.map_err(|_| NearError::timestamp_overflow())?;
+	if !(current_time == earliest_time || current_time.after(&earliest_time)) {
+		return Err(NearError::not_enough_time_elapsed(current_time, earliest_time))
 	}
+
+	let earliest_height = processed_height.add(delay_period_blocks);
+	if current_height < earliest_height {
+		return Err(NearError::not_enough_blocks_elapsed(current_height, earliest_height))
+	}
+
+	Ok(())
 }
 
 /// validates a light block that's contained on the `NearHeader` based on the current
@@ -405,7 +528,7 @@ where
 		let validator_public_key = &bp_stake_view.public_key;
 		let data = H::sha256_digest(&approval_message);
 		let signature = maybe_signature.as_ref().unwrap();
-		if H::ed25519_verify(signature.get_inner(), &data, validator_public_key.get_inner()) {
+		if !H::ed25519_verify(signature.get_inner(), &data, validator_public_key.get_inner()) {
 			return Err(NearError::invalid_signature().into())
 		}
 	}
@@ -14,7 +14,10 @@
 // limitations under the License.
 
 use super::types::{CryptoHash, LightClientBlockView, ValidatorStakeView};
-use crate::client_def::{HostFunctionsTrait, NearClient};
+use crate::{
+	client_def::{HostFunctionsTrait, NearClient},
+	error::Error,
+};
 use ibc::{
 	core::{
 		ics02_client::client_state::{ClientState, ClientType},
@@ -42,6 +45,23 @@ pub struct NearClientState<H> {
 pub struct NearUpgradeOptions {}
 
 impl<H: HostFunctionsTrait> NearClientState<H> {
+	pub fn new(
+		chain_id: ChainId,
+		head: LightClientBlockView,
+		current_validators: Vec<ValidatorStakeView>,
+		next_validators: Vec<ValidatorStakeView>,
+	) -> Self {
+		Self {
+			chain_id,
+			current_epoch: head.inner_lite.epoch_id,
+			next_epoch: head.inner_lite.next_epoch_id,
+			head,
+			current_validators,
+			next_validators,
+			_phantom: PhantomData,
+		}
+	}
+
 	pub fn get_validators_by_epoch(
 		&self,
 		epoch_id: &CryptoHash,
@@ -58,6 +78,29 @@ impl<H: HostFunctionsTrait> NearClientState<H> {
 	pub fn get_head(&self) -> &LightClientBlockView {
 		&self.head
 	}
+
+	/// Moves the head of the client to a validated `head`. When `head` is the first block of the
+	/// next epoch, the next epoch's block producers become the current ones.
+	pub fn with_head(mut self, head: LightClientBlockView) -> Self {
+		if head.inner_lite.epoch_id == self.next_epoch {
+			self.current_epoch = self.next_epoch;
+			self.current_validators = core::mem::take(&mut self.next_validators);
+		}
+		self.next_epoch = head.inner_lite.next_epoch_id;
+		if let Some(next_bps) = &head.next_bps {
+			self.next_validators = next_bps.clone();
+		}
+		self.head = head;
+		self
+	}
+
+	/// Check that the client has been updated to `height`, so a consensus state exists there.
+	pub fn verify_height(&self, height: Height) -> Result<(), Error> {
+		if self.latest_height() < height {
+			return Err(Error::insufficient_height(self.latest_height(), height))
+		}
+		Ok(())
+	}
 }
 
 impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
@@ -81,8 +124,8 @@ impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
 	}
 
 	fn frozen_height(&self) -> Option<Height> {
-		// TODO: validate this
-		Some(self.head.get_height())
+		// misbehaviour is not detected for NEAR clients, so they are never frozen.
+		None
 	}
 
 	fn upgrade(
@@ -95,8 +138,14 @@ impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
 		self
 	}
 
+	/// The NEAR client keeps no trusting period, headers are trusted through the block producers
+	/// of the epoch alone. So it never expires.
 	fn expired(&self, _elapsed: Duration) -> bool {
-		todo!()
+		false
+	}
+
+	fn trusting_period(&self) -> Duration {
+		Duration::MAX
 	}
 
 	fn encode_to_vec(&self) -> Vec<u8> {
@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::error::Error;
+use super::{error::Error, types::LightClientBlockView};
 use ibc::{
 	core::{
 		ics02_client::client_consensus::{self},
@@ -27,6 +27,19 @@ use tendermint_proto::Protobuf;
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ConsensusState {
 	commitment_root: CommitmentRoot,
+	timestamp: Timestamp,
+}
+
+impl ConsensusState {
+	/// State proofs are verified against the `prev_state_root` of the block, the state root
+	/// the chunks of the block were applied on.
+	pub fn new(block_view: &LightClientBlockView) -> Self {
+		Self {
+			commitment_root: block_view.inner_lite.prev_state_root.as_ref().to_vec().into(),
+			timestamp: Timestamp::from_nanoseconds(block_view.inner_lite.timestamp_nanosec)
+				.unwrap_or_default(),
+		}
+	}
 }
 
 impl client_consensus::ConsensusState for ConsensusState {
@@ -37,7 +50,7 @@ impl client_consensus::ConsensusState for ConsensusState {
 	}
 
 	fn timestamp(&self) -> Timestamp {
-		todo!()
+		self.timestamp
 	}
 
 	fn encode_to_vec(&self) -> Vec<u8> {
@@ -16,7 +16,11 @@
 use super::types::CryptoHash;
 use crate::client_state::NearClientState;
 use flex_error::define_error;
-use ibc::core::ics02_client::error::Error as Ics02Error;
+use ibc::{
+	core::{ics02_client::error::Error as Ics02Error, ics24_host::identifier::ClientId},
+	timestamp::Timestamp,
+	Height,
+};
 
 define_error! {
 	#[derive(Debug, PartialEq, Eq)]
@@ -44,6 +48,66 @@ define_error! {
 		| _ | { format_args!(
 			"unavailable block producers")
 		},
+		InsufficientHeight
+		{ latest_height: Height, target_height: Height }
+		| e | { format_args!(
+			"the height {} is higher than the latest height {} of the client", e.target_height, e.latest_height)
+		},
+		InvalidCommitmentRoot
+		{ len: usize }
+		| e | { format_args!(
+			"invalid commitment root length: {}", e.len)
+		},
+		InvalidProof
+		| _ | { format_args!(
+			"failed to decode the state proof nodes")
+		},
+		MissingProofNode
+		{ hash: CryptoHash }
+		| e | { format_args!(
+			"state proof is missing the trie node with hash {:?}", e.hash)
+		},
+		InvalidTrieNode
+		{ hash: CryptoHash }
+		| e | { format_args!(
+			"failed to decode the trie node with hash {:?}", e.hash)
+		},
+		KeyNotFound
+		| _ | { format_args!(
+			"state proof shows the key is absent")
+		},
+		ValueMismatch
+		| _ | { format_args!(
+			"value stored under the key does not match the expected value")
+		},
+		UnexpectedValue
+		| _ | { format_args!(
+			"state proof shows a value is stored under the key")
+		},
+		ProcessedTimeNotFound
+		{ client_id: ClientId, height: Height }
+		| e | { format_args!(
+			"processed time for the client {} at height {} not found", e.client_id, e.height)
+		},
+		ProcessedHeightNotFound
+		{ client_id: ClientId, height: Height }
+		| e | { format_args!(
+			"processed height for the client {} at height {} not found", e.client_id, e.height)
+		},
+		NotEnoughTimeElapsed
+		{ current_time: Timestamp, earliest_time: Timestamp }
+		| e | { format_args!(
+			"not enough time elapsed, current timestamp {} is still less than earliest acceptable timestamp {}", e.current_time, e.earliest_time)
+		},
+		TimestampOverflow
+		| _ | { format_args!(
+			"timestamp overflowed")
+		},
+		NotEnoughBlocksElapsed
+		{ current_height: Height, earliest_height: Height }
+		| e | { format_args!(
+			"not enough blocks elapsed, current height {} is still less than earliest acceptable height {}", e.current_height, e.earliest_height)
+		},
 	}
 }
 
@@ -24,6 +24,10 @@ pub struct NearHeader {
 }
 
 impl NearHeader {
+	pub fn new(inner: LightClientBlockView) -> Self {
+		Self { inner }
+	}
+
 	pub fn get_light_client_block_view(&self) -> &LightClientBlockView {
 		&self.inner
 	}
@@ -33,7 +37,7 @@ impl NearHeader {
 	}
 
 	pub fn height(&self) -> Height {
-		todo!()
+		self.inner.get_height()
 	}
 }
 
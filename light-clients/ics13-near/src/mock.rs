@@ -0,0 +1,251 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+#![allow(unreachable_code)]
+
+use crate::{
+	client_def::{HostFunctions, HostFunctionsTrait, NearClient},
+	client_state::{NearClientState, NearUpgradeOptions},
+	consensus_state::ConsensusState as NearConsensusState,
+	header::NearClientMessage,
+};
+use ibc::{
+	core::{
+		ics02_client,
+		ics02_client::{
+			client_consensus::ConsensusState,
+			client_state::{ClientState, ClientType},
+			context::ClientTypes,
+			error::Error,
+		},
+	},
+	mock::{
+		client_def::MockClient,
+		client_state::{MockClientState, MockConsensusState},
+		context::HostBlockType,
+		header::MockClientMessage,
+		host::MockHostBlock,
+	},
+	prelude::*,
+};
+use ibc_derive::{ClientDef, ClientMessage, ClientState, ConsensusState, Protobuf};
+use ibc_proto::google::protobuf::Any;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sp_core::{ed25519, Pair};
+use tendermint_proto::Protobuf;
+
+pub const MOCK_CLIENT_STATE_TYPE_URL: &str = "/ibc.mock.ClientState";
+pub const MOCK_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.mock.ClientMessage";
+pub const MOCK_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.mock.ConsensusState";
+
+pub const NEAR_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ClientState";
+pub const NEAR_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ClientMessage";
+pub const NEAR_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ConsensusState";
+
+#[derive(Clone, Default, PartialEq, Debug, Eq)]
+pub struct HostFunctionsManager;
+
+impl HostFunctions for HostFunctionsManager {
+	fn keccak_256(input: &[u8]) -> [u8; 32] {
+		sp_core::hashing::keccak_256(input)
+	}
+
+	fn secp256k1_ecdsa_recover_compressed(_: &[u8; 65], _: &[u8; 32]) -> Option<Vec<u8>> {
+		unimplemented!()
+	}
+
+	fn ed25519_verify(signature: &[u8; 64], msg: &[u8], pubkey: &[u8]) -> bool {
+		let pubkey = match <[u8; 32]>::try_from(pubkey) {
+			Ok(pubkey) => ed25519::Public::from_raw(pubkey),
+			Err(_) => return false,
+		};
+		ed25519::Pair::verify(&ed25519::Signature::from_raw(*signature), msg, &pubkey)
+	}
+
+	fn verify_membership_trie_proof(
+		_: &[u8; 32],
+		_: &[Vec<u8>],
+		_: &[u8],
+		_: &[u8],
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn verify_non_membership_trie_proof(
+		_: &[u8; 32],
+		_: &[Vec<u8>],
+		_: &[u8],
+	) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn verify_timestamp_extrinsic(_: &[u8; 32], _: &[Vec<u8>], _: &[u8]) -> Result<(), Error> {
+		unimplemented!()
+	}
+
+	fn sha256_digest(data: &[u8]) -> [u8; 32] {
+		Sha256::digest(data).into()
+	}
+
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		Sha256::digest(message).into()
+	}
+
+	fn sha2_512(_: &[u8]) -> [u8; 64] {
+		unimplemented!()
+	}
+
+	fn sha2_512_truncated(_: &[u8]) -> [u8; 32] {
+		unimplemented!()
+	}
+
+	fn sha3_512(_: &[u8]) -> [u8; 64] {
+		unimplemented!()
+	}
+
+	fn ripemd160(_: &[u8]) -> [u8; 20] {
+		unimplemented!()
+	}
+}
+
+impl ics23::HostFunctionsProvider for HostFunctionsManager {
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		Sha256::digest(message).into()
+	}
+
+	fn sha2_512(_: &[u8]) -> [u8; 64] {
+		unimplemented!()
+	}
+
+	fn sha2_512_truncated(_: &[u8]) -> [u8; 32] {
+		unimplemented!()
+	}
+
+	fn sha3_512(_: &[u8]) -> [u8; 64] {
+		unimplemented!()
+	}
+
+	fn ripemd160(_: &[u8]) -> [u8; 20] {
+		unimplemented!()
+	}
+}
+
+impl HostFunctionsTrait for HostFunctionsManager {}
+
+#[derive(Clone, Debug, PartialEq, Eq, ClientDef)]
+pub enum AnyClient {
+	Mock(MockClient),
+	Near(NearClient<HostFunctionsManager>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum AnyUpgradeOptions {
+	Mock(()),
+	Near(NearUpgradeOptions),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, ClientState, Protobuf)]
+#[serde(tag = "type")]
+pub enum AnyClientState {
+	#[ibc(proto_url = "MOCK_CLIENT_STATE_TYPE_URL")]
+	Mock(MockClientState),
+	#[serde(skip)]
+	#[ibc(proto_url = "NEAR_CLIENT_STATE_TYPE_URL")]
+	Near(NearClientState<HostFunctionsManager>),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, ClientMessage)]
+#[allow(clippy::large_enum_variant)]
+pub enum AnyClientMessage {
+	#[ibc(proto_url = "MOCK_CLIENT_MESSAGE_TYPE_URL")]
+	Mock(MockClientMessage),
+	#[serde(skip)]
+	#[ibc(proto_url = "NEAR_CLIENT_MESSAGE_TYPE_URL")]
+	Near(NearClientMessage),
+}
+
+impl Protobuf<Any> for AnyClientMessage {}
+
+impl TryFrom<Any> for AnyClientMessage {
+	type Error = ics02_client::error::Error;
+
+	fn try_from(value: Any) -> Result<Self, Self::Error> {
+		match value.type_url.as_str() {
+			MOCK_CLIENT_MESSAGE_TYPE_URL =>
+				Ok(Self::Mock(panic!("MockClientMessage doesn't implement Protobuf"))),
+			NEAR_CLIENT_MESSAGE_TYPE_URL =>
+				Ok(Self::Near(panic!("NearClientMessage doesn't implement Protobuf"))),
+			_ => Err(ics02_client::error::Error::unknown_consensus_state_type(value.type_url)),
+		}
+	}
+}
+
+impl From<AnyClientMessage> for Any {
+	fn from(client_msg: AnyClientMessage) -> Self {
+		match client_msg {
+			AnyClientMessage::Mock(_mock) => {
+				panic!("MockClientMessage doesn't implement Protobuf");
+			},
+			AnyClientMessage::Near(_near) => {
+				panic!("NearClientMessage doesn't implement Protobuf");
+			},
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, ConsensusState, Protobuf)]
+#[serde(tag = "type")]
+pub enum AnyConsensusState {
+	#[ibc(proto_url = "NEAR_CONSENSUS_STATE_TYPE_URL")]
+	Near(NearConsensusState),
+	#[ibc(proto_url = "MOCK_CONSENSUS_STATE_TYPE_URL")]
+	Mock(MockConsensusState),
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+pub struct MockClientTypes;
+
+impl ClientTypes for MockClientTypes {
+	type AnyClientMessage = AnyClientMessage;
+	type AnyClientState = AnyClientState;
+	type AnyConsensusState = AnyConsensusState;
+	type ClientDef = AnyClient;
+}
+
+impl HostBlockType for MockClientTypes {
+	type HostBlock = MockHostBlock;
+
+	fn supported_client_types() -> Vec<ClientType> {
+		vec![
+			MockClientState::client_type(),
+			NearClientState::<HostFunctionsManager>::client_type(),
+		]
+	}
+}
+
+impl From<MockHostBlock> for AnyClientMessage {
+	fn from(block: MockHostBlock) -> Self {
+		let MockHostBlock::Mock(header) = block;
+		AnyClientMessage::Mock(MockClientMessage::Header(header))
+	}
+}
+
+impl From<MockHostBlock> for AnyConsensusState {
+	fn from(block: MockHostBlock) -> Self {
+		let MockHostBlock::Mock(header) = block;
+		AnyConsensusState::Mock(MockConsensusState::new(header))
+	}
+}
@@ -0,0 +1,168 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_def::{reconstruct_light_client_block_view_fields, HostFunctions},
+	client_state::NearClientState,
+	consensus_state::ConsensusState,
+	header::{NearClientMessage, NearHeader},
+	mock::{
+		AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager, MockClientTypes,
+	},
+	types::{
+		BlockHeaderInnerLiteView, CryptoHash, LightClientBlockView, PublicKey, Signature,
+		ValidatorStakeView, ValidatorStakeViewV1,
+	},
+};
+use ibc::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as _,
+			client_def::ConsensusUpdateResult,
+			client_state::ClientState as _,
+			context::{ClientKeeper, ClientReader},
+			handler::{update_client, ClientResult::Update},
+			msgs::update_client::MsgUpdateAnyClient,
+		},
+		ics24_host::identifier::{ChainId, ClientId},
+	},
+	mock::context::MockContext,
+	prelude::*,
+	test_utils::get_dummy_account_id,
+	Height,
+};
+use sp_core::{ed25519, Pair};
+
+const CURRENT_EPOCH: CryptoHash = CryptoHash([1; 32]);
+const NEXT_EPOCH: CryptoHash = CryptoHash([2; 32]);
+
+fn block_producers(count: u8) -> (Vec<ed25519::Pair>, Vec<ValidatorStakeView>) {
+	let keys = (0..count).map(|i| ed25519::Pair::from_seed(&[i + 1; 32])).collect::<Vec<_>>();
+	let validators = keys
+		.iter()
+		.enumerate()
+		.map(|(i, key)| {
+			ValidatorStakeView::V1(ValidatorStakeViewV1 {
+				account_id: format!("validator{}.near", i),
+				public_key: PublicKey(key.public().0),
+				stake: 10,
+			})
+		})
+		.collect();
+	(keys, validators)
+}
+
+/// A block of the current epoch at `height`, approved by the first `signers` of `keys`.
+fn signed_block(height: u64, keys: &[ed25519::Pair], signers: usize) -> LightClientBlockView {
+	let mut block = LightClientBlockView {
+		prev_block_hash: CryptoHash([height as u8; 32]),
+		next_block_inner_hash: CryptoHash([0; 32]),
+		inner_lite: BlockHeaderInnerLiteView {
+			height,
+			epoch_id: CURRENT_EPOCH,
+			next_epoch_id: NEXT_EPOCH,
+			prev_state_root: CryptoHash([3; 32]),
+			outcome_root: CryptoHash([0; 32]),
+			timestamp: height * 1_000_000_000,
+			timestamp_nanosec: height * 1_000_000_000,
+			next_bp_hash: CryptoHash([0; 32]),
+			block_merkle_root: CryptoHash([0; 32]),
+		},
+		inner_rest_hash: CryptoHash([0; 32]),
+		next_bps: None,
+		approvals_after_next: vec![],
+	};
+	let (_, _, approval_message) =
+		reconstruct_light_client_block_view_fields::<HostFunctionsManager>(&block).unwrap();
+	let data = HostFunctionsManager::sha256_digest(&approval_message);
+	block.approvals_after_next = keys
+		.iter()
+		.enumerate()
+		.map(|(i, key)| (i < signers).then(|| Signature::Ed25519(key.sign(&data))))
+		.collect();
+	block
+}
+
+/// A context holding a NEAR client whose head is the block at `height`.
+fn context_with_near_client(
+	client_id: &ClientId,
+	height: u64,
+	validators: Vec<ValidatorStakeView>,
+) -> MockContext<MockClientTypes> {
+	let mut ctx = MockContext::<MockClientTypes>::default();
+	let head = signed_block(height, &[], 0);
+	let consensus_state = ConsensusState::new(&head);
+	let client_state = NearClientState::<HostFunctionsManager>::new(
+		ChainId::new("near".to_string(), 0),
+		head,
+		validators.clone(),
+		validators,
+	);
+	let height = client_state.latest_height();
+	let (host_timestamp, host_height) = (ctx.host_timestamp(), ctx.host_height());
+	ctx.store_client_state(client_id.clone(), AnyClientState::Near(client_state)).unwrap();
+	ctx.store_consensus_state(client_id.clone(), height, AnyConsensusState::Near(consensus_state))
+		.unwrap();
+	ctx.store_update_time(client_id.clone(), height, host_timestamp).unwrap();
+	ctx.store_update_height(client_id.clone(), height, host_height).unwrap();
+	ctx
+}
+
+fn update_message(
+	client_id: &ClientId,
+	block: LightClientBlockView,
+) -> MsgUpdateAnyClient<MockContext<MockClientTypes>> {
+	MsgUpdateAnyClient {
+		client_id: client_id.clone(),
+		client_message: AnyClientMessage::Near(NearClientMessage::Header(NearHeader::new(block))),
+		signer: get_dummy_account_id(),
+	}
+}
+
+#[test]
+fn updates_near_client_with_approved_block() {
+	let client_id =
+		ClientId::new(&NearClientState::<HostFunctionsManager>::client_type(), 0).unwrap();
+	let (keys, validators) = block_producers(3);
+	let ctx = context_with_near_client(&client_id, 10, validators);
+
+	let block = signed_block(11, &keys, 3);
+	let output = update_client::process(&ctx, update_message(&client_id, block.clone())).unwrap();
+
+	let result = match output.result {
+		Update(result) => result,
+		_ => unreachable!(),
+	};
+	assert_eq!(result.client_state.latest_height(), Height::new(0, 11));
+	match result.consensus_state {
+		Some(ConsensusUpdateResult::Single(AnyConsensusState::Near(consensus_state))) => {
+			assert_eq!(consensus_state, ConsensusState::new(&block));
+			assert_eq!(consensus_state.root().as_bytes(), &[3; 32]);
+		},
+		state => panic!("expected a single NEAR consensus state, got {:?}", state),
+	}
+}
+
+#[test]
+fn rejects_near_block_without_enough_approvals() {
+	let client_id =
+		ClientId::new(&NearClientState::<HostFunctionsManager>::client_type(), 0).unwrap();
+	let (keys, validators) = block_producers(3);
+	let ctx = context_with_near_client(&client_id, 10, validators);
+
+	let block = signed_block(11, &keys, 2);
+	let result = update_client::process(&ctx, update_message(&client_id, block));
+	assert!(result.is_err());
+}
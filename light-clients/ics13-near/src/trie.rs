@@ -0,0 +1,522 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of NEAR state proofs. The NEAR state trie hashes every node with sha256 over its
+//! borsh encoding, and its leaves store the length and sha256 hash of their value rather than the
+//! value itself. A proof is the borsh encoded list of nodes on the path from the state root to
+//! the key.
+
+use crate::{client_def::HostFunctions, error::Error, types::CryptoHash};
+use alloc::collections::BTreeMap;
+use borsh::{
+	maybestd::io::{Result as IoResult, Write},
+	BorshDeserialize, BorshSerialize,
+};
+use ibc::{
+	core::{
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::Path,
+	},
+	prelude::*,
+};
+
+/// Trie key column holding the storage of contracts, keyed by account id and storage key.
+const CONTRACT_DATA: u8 = 9;
+/// Separates the account id from the storage key in contract data trie keys.
+const ACCOUNT_DATA_SEPARATOR: u8 = b',';
+
+/// The length and hash of a value stored in the trie.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ValueRef {
+	pub length: u32,
+	pub hash: CryptoHash,
+}
+
+/// The children of a branch node, encoded as a bitmap of the occupied slots followed by their
+/// hashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Children(pub [Option<CryptoHash>; 16]);
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum RawTrieNode {
+	/// Nibble encoded remainder of the key and the value stored under it.
+	Leaf(Vec<u8>, ValueRef),
+	BranchNoValue(Children),
+	BranchWithValue(ValueRef, Children),
+	/// Nibble encoded key segment shared by everything below the child.
+	Extension(Vec<u8>, CryptoHash),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct RawTrieNodeWithSize {
+	pub node: RawTrieNode,
+	pub memory_usage: u64,
+}
+
+impl BorshSerialize for Children {
+	fn serialize<W: Write>(&self, writer: &mut W) -> IoResult<()> {
+		let mut bitmap: u16 = 0;
+		for (i, child) in self.0.iter().enumerate() {
+			if child.is_some() {
+				bitmap |= 1 << i;
+			}
+		}
+		BorshSerialize::serialize(&bitmap, writer)?;
+		for child in self.0.iter().flatten() {
+			BorshSerialize::serialize(child, writer)?;
+		}
+		Ok(())
+	}
+}
+
+impl BorshDeserialize for Children {
+	fn deserialize(buf: &mut &[u8]) -> IoResult<Self> {
+		let bitmap: u16 = BorshDeserialize::deserialize(buf)?;
+		let mut children = [None; 16];
+		for (i, child) in children.iter_mut().enumerate() {
+			if bitmap & (1 << i) != 0 {
+				*child = Some(BorshDeserialize::deserialize(buf)?);
+			}
+		}
+		Ok(Children(children))
+	}
+}
+
+/// Verify that `value` is stored under `path` in the storage of the ibc contract, whose account
+/// id is the commitment `prefix`, in the state with `root`.
+pub fn verify_membership<H, P>(
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+	value: Vec<u8>,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let value_ref = lookup::<H>(root, proof, &storage_key(prefix, path.into()))?
+		.ok_or_else(Error::key_not_found)?;
+	let value_hash = CryptoHash(H::sha256_digest(&value));
+	if value_ref.length as usize != value.len() || value_ref.hash != value_hash {
+		return Err(Error::value_mismatch())
+	}
+	Ok(())
+}
+
+/// Verify that nothing is stored under `path` in the storage of the ibc contract, whose account
+/// id is the commitment `prefix`, in the state with `root`.
+pub fn verify_non_membership<H, P>(
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	match lookup::<H>(root, proof, &storage_key(prefix, path.into()))? {
+		Some(_) => Err(Error::unexpected_value()),
+		None => Ok(()),
+	}
+}
+
+fn storage_key(prefix: &CommitmentPrefix, path: Path) -> Vec<u8> {
+	let mut key = vec![CONTRACT_DATA];
+	key.extend(prefix.as_bytes());
+	key.push(ACCOUNT_DATA_SEPARATOR);
+	key.extend(path.to_string().as_bytes());
+	key
+}
+
+/// Walks the proof nodes from `root` along `key`, returning the value stored under it, or `None`
+/// if the proof shows the key is absent.
+fn lookup<H: HostFunctions>(
+	root: &CommitmentRoot,
+	proof: &CommitmentProofBytes,
+	key: &[u8],
+) -> Result<Option<ValueRef>, Error> {
+	let root = CryptoHash::try_from(root.as_bytes())
+		.map_err(|_| Error::invalid_commitment_root(root.as_bytes().len()))?;
+	let proof_nodes: Vec<Vec<u8>> =
+		BorshDeserialize::try_from_slice(proof.as_bytes()).map_err(|_| Error::invalid_proof())?;
+	let nodes = proof_nodes
+		.iter()
+		.map(|node| (CryptoHash(H::sha256_digest(node)), node.as_slice()))
+		.collect::<BTreeMap<_, _>>();
+
+	let nibbles = to_nibbles(key);
+	let mut key = nibbles.as_slice();
+	let mut hash = root;
+	loop {
+		let node = nodes.get(&hash).ok_or_else(|| Error::missing_proof_node(hash))?;
+		let node = RawTrieNodeWithSize::try_from_slice(node)
+			.map_err(|_| Error::invalid_trie_node(hash))?
+			.node;
+		let (value, children) = match node {
+			RawTrieNode::Leaf(leaf_key, value) => {
+				let leaf_key =
+					decode_nibbles(&leaf_key).ok_or_else(|| Error::invalid_trie_node(hash))?;
+				return Ok((leaf_key == key).then(|| value))
+			},
+			RawTrieNode::Extension(segment, child) => {
+				let segment =
+					decode_nibbles(&segment).ok_or_else(|| Error::invalid_trie_node(hash))?;
+				if !key.starts_with(&segment) {
+					return Ok(None)
+				}
+				key = &key[segment.len()..];
+				hash = child;
+				continue
+			},
+			RawTrieNode::BranchNoValue(children) => (None, children),
+			RawTrieNode::BranchWithValue(value, children) => (Some(value), children),
+		};
+		match key.split_first() {
+			None => return Ok(value),
+			Some((nibble, rest)) => match children.0[*nibble as usize] {
+				Some(child) => {
+					key = rest;
+					hash = child;
+				},
+				None => return Ok(None),
+			},
+		}
+	}
+}
+
+fn to_nibbles(key: &[u8]) -> Vec<u8> {
+	key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+/// Decodes the key segment of a leaf or extension node. The first byte flags whether the segment
+/// has an odd number of nibbles, in which case its low nibble is the first one.
+fn decode_nibbles(encoded: &[u8]) -> Option<Vec<u8>> {
+	let (first, rest) = encoded.split_first()?;
+	let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+	if first & 0x10 != 0 {
+		nibbles.push(first & 0x0f);
+	}
+	nibbles.extend(to_nibbles(rest));
+	Some(nibbles)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::error::ErrorDetail;
+	use ibc::core::{
+		ics04_channel::packet::Sequence,
+		ics24_host::{
+			identifier::{ChannelId, PortId},
+			path::{CommitmentsPath, ReceiptsPath},
+		},
+	};
+	use sha2::{Digest, Sha256};
+
+	#[derive(Clone, Default)]
+	struct TestHostFunctions;
+
+	impl HostFunctions for TestHostFunctions {
+		fn keccak_256(_: &[u8]) -> [u8; 32] {
+			unimplemented!()
+		}
+
+		fn secp256k1_ecdsa_recover_compressed(_: &[u8; 65], _: &[u8; 32]) -> Option<Vec<u8>> {
+			unimplemented!()
+		}
+
+		fn ed25519_verify(_: &[u8; 64], _: &[u8], _: &[u8]) -> bool {
+			unimplemented!()
+		}
+
+		fn verify_membership_trie_proof(
+			_: &[u8; 32],
+			_: &[Vec<u8>],
+			_: &[u8],
+			_: &[u8],
+		) -> Result<(), ibc::core::ics02_client::error::Error> {
+			unimplemented!()
+		}
+
+		fn verify_non_membership_trie_proof(
+			_: &[u8; 32],
+			_: &[Vec<u8>],
+			_: &[u8],
+		) -> Result<(), ibc::core::ics02_client::error::Error> {
+			unimplemented!()
+		}
+
+		fn verify_timestamp_extrinsic(
+			_: &[u8; 32],
+			_: &[Vec<u8>],
+			_: &[u8],
+		) -> Result<(), ibc::core::ics02_client::error::Error> {
+			unimplemented!()
+		}
+
+		fn sha256_digest(data: &[u8]) -> [u8; 32] {
+			Sha256::digest(data).into()
+		}
+
+		fn sha2_256(message: &[u8]) -> [u8; 32] {
+			Sha256::digest(message).into()
+		}
+
+		fn sha2_512(_: &[u8]) -> [u8; 64] {
+			unimplemented!()
+		}
+
+		fn sha2_512_truncated(_: &[u8]) -> [u8; 32] {
+			unimplemented!()
+		}
+
+		fn sha3_512(_: &[u8]) -> [u8; 64] {
+			unimplemented!()
+		}
+
+		fn ripemd160(_: &[u8]) -> [u8; 20] {
+			unimplemented!()
+		}
+	}
+
+	fn encode_nibbles(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+		let flag = if is_leaf { 0x20 } else { 0 };
+		let (first, rest) = match nibbles.len() % 2 {
+			1 => (flag | 0x10 | nibbles[0], &nibbles[1..]),
+			_ => (flag, nibbles),
+		};
+		let mut encoded = vec![first];
+		encoded.extend(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+		encoded
+	}
+
+	fn value_ref(value: &[u8]) -> ValueRef {
+		ValueRef {
+			length: value.len() as u32,
+			hash: CryptoHash(TestHostFunctions::sha256_digest(value)),
+		}
+	}
+
+	fn insert_node(node: RawTrieNode, nodes: &mut Vec<Vec<u8>>) -> CryptoHash {
+		let encoded = RawTrieNodeWithSize { node, memory_usage: 0 }.try_to_vec().unwrap();
+		let hash = CryptoHash(TestHostFunctions::sha256_digest(&encoded));
+		nodes.push(encoded);
+		hash
+	}
+
+	/// Builds the trie holding `entries`, keyed by nibbles, the way nearcore lays it out and
+	/// returns its root. Children are pushed to `nodes` before their parents.
+	fn build_trie(entries: &[(Vec<u8>, Vec<u8>)], nodes: &mut Vec<Vec<u8>>) -> CryptoHash {
+		if let [(key, value)] = entries {
+			let leaf = RawTrieNode::Leaf(encode_nibbles(key, true), value_ref(value));
+			return insert_node(leaf, nodes)
+		}
+		let first = &entries[0].0;
+		let common = (0..)
+			.take_while(|&i| entries.iter().all(|(key, _)| key.len() > i && key[i] == first[i]))
+			.count();
+		let strip = |entries: &[(Vec<u8>, Vec<u8>)], len: usize| {
+			entries
+				.iter()
+				.map(|(key, value)| (key[len..].to_vec(), value.clone()))
+				.collect::<Vec<_>>()
+		};
+		if common > 0 {
+			let child = build_trie(&strip(entries, common), nodes);
+			let segment = encode_nibbles(&entries[0].0[..common], false);
+			return insert_node(RawTrieNode::Extension(segment, child), nodes)
+		}
+
+		let mut children = [None; 16];
+		for (nibble, child) in children.iter_mut().enumerate() {
+			let group = entries
+				.iter()
+				.filter(|(key, _)| key.first() == Some(&(nibble as u8)))
+				.cloned()
+				.collect::<Vec<_>>();
+			if !group.is_empty() {
+				*child = Some(build_trie(&strip(&group, 1), nodes));
+			}
+		}
+		let node = match entries.iter().find(|(key, _)| key.is_empty()) {
+			Some((_, value)) => RawTrieNode::BranchWithValue(value_ref(value), Children(children)),
+			None => RawTrieNode::BranchNoValue(Children(children)),
+		};
+		insert_node(node, nodes)
+	}
+
+	fn prefix() -> CommitmentPrefix {
+		CommitmentPrefix::try_from(b"ibc.testnet".to_vec()).unwrap()
+	}
+
+	fn commitment_path(sequence: u64) -> CommitmentsPath {
+		CommitmentsPath {
+			port_id: PortId::transfer(),
+			channel_id: ChannelId::new(0),
+			sequence: Sequence::from(sequence),
+		}
+	}
+
+	fn receipt_path(sequence: u64) -> ReceiptsPath {
+		ReceiptsPath {
+			port_id: PortId::transfer(),
+			channel_id: ChannelId::new(0),
+			sequence: Sequence::from(sequence),
+		}
+	}
+
+	/// A contract state with packet commitments for sequences 1, 2 and 10 and a receipt for
+	/// sequence 1, along with the state of another account. Returns the state root and all trie
+	/// nodes.
+	fn contract_state() -> (CommitmentRoot, Vec<Vec<u8>>) {
+		let mut entries = [1, 2, 10]
+			.into_iter()
+			.map(|seq| (storage_key(&prefix(), commitment_path(seq).into()), vec![seq as u8; 32]))
+			.collect::<Vec<_>>();
+		entries.push((storage_key(&prefix(), receipt_path(1).into()), vec![1]));
+		let other = CommitmentPrefix::try_from(b"other.testnet".to_vec()).unwrap();
+		entries.push((storage_key(&other, commitment_path(3).into()), vec![3; 32]));
+
+		let mut entries = entries
+			.into_iter()
+			.map(|(key, value)| (to_nibbles(&key), value))
+			.collect::<Vec<_>>();
+		entries.sort();
+		let mut nodes = vec![];
+		let root = build_trie(&entries, &mut nodes);
+		(root.as_ref().to_vec().into(), nodes)
+	}
+
+	fn proof(nodes: &[Vec<u8>]) -> CommitmentProofBytes {
+		CommitmentProofBytes::try_from(nodes.to_vec().try_to_vec().unwrap()).unwrap()
+	}
+
+	#[test]
+	fn verifies_stored_commitments() {
+		let (root, nodes) = contract_state();
+		let proof = proof(&nodes);
+
+		for seq in [1, 2, 10] {
+			verify_membership::<TestHostFunctions, _>(
+				&prefix(),
+				&proof,
+				&root,
+				commitment_path(seq),
+				vec![seq as u8; 32],
+			)
+			.unwrap();
+		}
+
+		let err = verify_membership::<TestHostFunctions, _>(
+			&prefix(),
+			&proof,
+			&root,
+			commitment_path(1),
+			vec![2; 32],
+		)
+		.unwrap_err();
+		assert!(matches!(err.detail(), ErrorDetail::ValueMismatch(_)));
+
+		// Sequence 3 is only committed by the other account.
+		let err = verify_membership::<TestHostFunctions, _>(
+			&prefix(),
+			&proof,
+			&root,
+			commitment_path(3),
+			vec![3; 32],
+		)
+		.unwrap_err();
+		assert!(matches!(err.detail(), ErrorDetail::KeyNotFound(_)));
+	}
+
+	#[test]
+	fn verifies_absent_receipts() {
+		let (root, nodes) = contract_state();
+		let proof = proof(&nodes);
+
+		verify_non_membership::<TestHostFunctions, _>(&prefix(), &proof, &root, receipt_path(2))
+			.unwrap();
+		verify_non_membership::<TestHostFunctions, _>(&prefix(), &proof, &root, receipt_path(10))
+			.unwrap();
+
+		let err =
+			verify_non_membership::<TestHostFunctions, _>(&prefix(), &proof, &root, receipt_path(1))
+				.unwrap_err();
+		assert!(matches!(err.detail(), ErrorDetail::UnexpectedValue(_)));
+	}
+
+	#[test]
+	fn rejects_truncated_proofs() {
+		let (root, nodes) = contract_state();
+
+		// The leaves are pushed first, so dropping the first node removes one from the path.
+		let truncated = nodes[1..].to_vec();
+		let results = [1, 2, 10].map(|seq| {
+			verify_membership::<TestHostFunctions, _>(
+				&prefix(),
+				&proof(&truncated),
+				&root,
+				commitment_path(seq),
+				vec![seq as u8; 32],
+			)
+		});
+		assert!(results.iter().any(|result| matches!(
+			result.as_ref().map_err(|e| e.detail()),
+			Err(ErrorDetail::MissingProofNode(_))
+		)));
+
+		let encoded = nodes.try_to_vec().unwrap();
+		let cut = CommitmentProofBytes::try_from(encoded[..encoded.len() / 2].to_vec()).unwrap();
+		let err = verify_membership::<TestHostFunctions, _>(
+			&prefix(),
+			&cut,
+			&root,
+			commitment_path(1),
+			vec![1; 32],
+		)
+		.unwrap_err();
+		assert!(matches!(err.detail(), ErrorDetail::InvalidProof(_)));
+	}
+
+	#[test]
+	fn rejects_mismatched_root() {
+		let (_, nodes) = contract_state();
+		let root: CommitmentRoot = vec![7; 32].into();
+
+		let err = verify_membership::<TestHostFunctions, _>(
+			&prefix(),
+			&proof(&nodes),
+			&root,
+			commitment_path(1),
+			vec![1; 32],
+		)
+		.unwrap_err();
+		match err.detail() {
+			ErrorDetail::MissingProofNode(e) => assert_eq!(e.hash, CryptoHash([7; 32])),
+			e => panic!("Expected MissingProofNode error, instead got {:?}", e),
+		}
+
+		let err = verify_non_membership::<TestHostFunctions, _>(
+			&prefix(),
+			&proof(&nodes),
+			&vec![7; 31].into(),
+			receipt_path(2),
+		)
+		.unwrap_err();
+		assert!(matches!(err.detail(), ErrorDetail::InvalidCommitmentRoot(_)));
+	}
+}
@@ -0,0 +1,397 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::marker::PhantomData;
+use tendermint_proto::Protobuf;
+
+use crate::{
+	client_message::{ClientMessage, LightClientUpdate, Misbehaviour},
+	client_state::ClientState,
+	consensus_state::ConsensusState,
+	error::Error,
+	proof::{verify_membership, verify_non_membership},
+	verify::{apply_light_client_update, verify_light_client_update},
+	HostFunctions,
+};
+use ibc::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as _,
+			client_def::{ClientDef, ConsensusUpdateResult},
+			client_state::ClientState as _,
+			error::Error as Ics02Error,
+		},
+		ics03_connection::connection::ConnectionEnd,
+		ics04_channel::{
+			channel::ChannelEnd,
+			commitment::{AcknowledgementCommitment, PacketCommitment},
+			packet::Sequence,
+		},
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
+				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+			},
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	prelude::*,
+	Height,
+};
+use light_client_common::verify_delay_passed;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct EthereumClient<T>(PhantomData<T>);
+
+/// Height of the consensus state `update` finalizes.
+fn finalized_height(update: &LightClientUpdate) -> Height {
+	Height::new(0, update.finalized_header.execution.block_number)
+}
+
+impl<H> ClientDef for EthereumClient<H>
+where
+	H: HostFunctions,
+{
+	type ClientMessage = ClientMessage;
+	type ClientState = ClientState<H>;
+	type ConsensusState = ConsensusState;
+
+	fn verify_client_message<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		message: Self::ClientMessage,
+	) -> Result<(), Ics02Error> {
+		match message {
+			ClientMessage::Header(update) => verify_light_client_update(&client_state, &update)?,
+			ClientMessage::Misbehaviour(Misbehaviour { first, second }) => {
+				if first.finalized_header.beacon.slot != second.finalized_header.beacon.slot ||
+					first.finalized_header == second.finalized_header
+				{
+					return Err(Error::Custom(format!(
+						"Misbehaviour updates must finalize different headers for the same slot"
+					))
+					.into())
+				}
+				verify_light_client_update(&client_state, &first)?;
+				verify_light_client_update(&client_state, &second)?;
+			},
+		}
+		Ok(())
+	}
+
+	fn update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		message: Self::ClientMessage,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		let update = match message {
+			ClientMessage::Header(update) => update,
+			_ => unreachable!(
+				"02-client will check for misbehaviour before calling update_state; qed"
+			),
+		};
+		let finalizes_header =
+			update.finalized_header.beacon.slot > client_state.finalized_header.slot;
+		let client_state = apply_light_client_update(client_state, &update);
+
+		let mut consensus_states = vec![];
+		// Updates that only prove the next sync committee don't finalize a new header
+		if finalizes_header {
+			let consensus_state = ConsensusState::from_header(&update.finalized_header.execution)
+				.map_err(Error::from)?;
+			consensus_states.push((
+				finalized_height(&update),
+				Ctx::AnyConsensusState::wrap(&consensus_state)
+					.ok_or_else(|| Error::Custom("Ctx::AnyConsensusState".to_string()))?,
+			));
+		}
+
+		Ok((client_state, ConsensusUpdateResult::Batch(consensus_states)))
+	}
+
+	fn update_state_on_misbehaviour(
+		&self,
+		mut client_state: Self::ClientState,
+		_header: Self::ClientMessage,
+	) -> Result<Self::ClientState, Ics02Error> {
+		client_state.frozen_height = Some(client_state.latest_height());
+		Ok(client_state)
+	}
+
+	fn check_for_misbehaviour<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: ClientId,
+		client_state: Self::ClientState,
+		message: Self::ClientMessage,
+	) -> Result<bool, Ics02Error> {
+		let update = match message {
+			ClientMessage::Header(update) => update,
+			// both updates were verified, so the sync committee signed conflicting headers
+			ClientMessage::Misbehaviour(_) => return Ok(true),
+		};
+		// we simply check that this update doesn't include a competing consensus state for a
+		// height we already processed.
+		let consensus_state =
+			ConsensusState::from_header(&update.finalized_header.execution).map_err(Error::from)?;
+		match ctx.maybe_consensus_state(&client_id, finalized_height(&update))? {
+			Some(cs) => {
+				let cs: ConsensusState = cs.downcast().ok_or(
+					Ics02Error::client_args_type_mismatch(client_state.client_type().to_owned()),
+				)?;
+				Ok(cs != consensus_state)
+			},
+			None => Ok(false),
+		}
+	}
+
+	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_old_client_state: &Self::ClientState,
+		_upgrade_client_state: &Self::ClientState,
+		_upgrade_consensus_state: &Self::ConsensusState,
+		_proof_upgrade_client: Vec<u8>,
+		_proof_upgrade_consensus_state: Vec<u8>,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		Err(Error::Custom("Ethereum Client doesn't need client upgrades".to_string()).into())
+	}
+
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<Self::ClientState, Ics02Error> {
+		if old_client_state.genesis_validators_root !=
+			substitute_client_state.genesis_validators_root ||
+			old_client_state.ibc_contract_address != substitute_client_state.ibc_contract_address
+		{
+			return Err(Ics02Error::substitute_client_state_mismatch(
+				"ethereum client tracks a different chain or IBC contract".to_string(),
+			))
+		}
+
+		Ok(ClientState { frozen_height: None, ..substitute_client_state })
+	}
+
+	fn verify_client_consensus_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		consensus_height: Height,
+		expected_consensus_state: &Ctx::AnyConsensusState,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ClientConsensusStatePath {
+			client_id: client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let value = expected_consensus_state.encode_to_vec();
+		verify_membership(client_state, prefix, proof, root, path, value)?;
+		Ok(())
+	}
+
+	// Consensus state will be verified in the verification functions  before these are called
+	fn verify_connection_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec();
+		verify_membership(client_state, prefix, proof, root, path, value)?;
+		Ok(())
+	}
+
+	fn verify_channel_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec();
+		verify_membership(client_state, prefix, proof, root, path, value)?;
+		Ok(())
+	}
+
+	fn verify_client_full_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		let path = ClientStatePath(client_id.clone());
+		let value = expected_client_state.encode_to_vec();
+		verify_membership(client_state, prefix, proof, root, path, value)?;
+		Ok(())
+	}
+
+	fn verify_packet_data<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+
+		verify_membership(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			commitment_path,
+			commitment.into_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: AcknowledgementCommitment,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			ack_path,
+			ack.into_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_next_sequence_recv<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let seq_bytes = u64::from(sequence).to_be_bytes().to_vec();
+
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		verify_membership(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			seq_path,
+			seq_bytes,
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end).map_err(Error::Anyhow)?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_non_membership(
+			client_state,
+			connection_end.counterparty().prefix(),
+			proof,
+			root,
+			receipt_path,
+		)?;
+		Ok(())
+	}
+}
@@ -0,0 +1,409 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	error::Error,
+	proto::{
+		client_message, BeaconBlockHeader as RawBeaconBlockHeader,
+		ClientMessage as RawClientMessage, ExecutionPayloadHeader as RawExecutionPayloadHeader,
+		LightClientHeader as RawLightClientHeader, LightClientUpdate as RawLightClientUpdate,
+		Misbehaviour as RawMisbehaviour, SyncAggregate as RawSyncAggregate,
+		SyncCommittee as RawSyncCommittee,
+	},
+	verify::SYNC_COMMITTEE_SIZE,
+};
+use alloc::{format, vec::Vec};
+use anyhow::anyhow;
+use core::convert::TryFrom;
+use ibc::prelude::*;
+use primitive_types::{H160, H256, U256};
+use tendermint_proto::Protobuf;
+
+/// Protobuf type url for Ethereum ClientMessage
+pub const ETHEREUM_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.lightclients.ethereum.v1.ClientMessage";
+
+/// Maximum length of an execution payload's extra data.
+pub const MAX_EXTRA_DATA_BYTES: usize = 32;
+
+/// Length of an execution payload's logs bloom.
+pub const BYTES_PER_LOGS_BLOOM: usize = 256;
+
+/// Compressed BLS12-381 public key.
+pub type BlsPublicKey = [u8; 48];
+
+/// Compressed BLS12-381 signature.
+pub type BlsSignature = [u8; 96];
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct BeaconBlockHeader {
+	pub slot: u64,
+	pub proposer_index: u64,
+	pub parent_root: H256,
+	pub state_root: H256,
+	pub body_root: H256,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncCommittee {
+	/// Public keys of the committee members, in committee order
+	pub pubkeys: Vec<BlsPublicKey>,
+	/// Aggregate of all the members' public keys
+	pub aggregate_pubkey: BlsPublicKey,
+}
+
+/// Capella execution payload header.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct ExecutionPayloadHeader {
+	pub parent_hash: H256,
+	pub fee_recipient: H160,
+	pub state_root: H256,
+	pub receipts_root: H256,
+	pub logs_bloom: Vec<u8>,
+	pub prev_randao: H256,
+	pub block_number: u64,
+	pub gas_limit: u64,
+	pub gas_used: u64,
+	pub timestamp: u64,
+	pub extra_data: Vec<u8>,
+	pub base_fee_per_gas: U256,
+	pub block_hash: H256,
+	pub transactions_root: H256,
+	pub withdrawals_root: H256,
+}
+
+/// Beacon block header, with the execution payload header its body commits to.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct LightClientHeader {
+	pub beacon: BeaconBlockHeader,
+	pub execution: ExecutionPayloadHeader,
+	/// Proof of the execution payload header in the beacon block body
+	pub execution_branch: Vec<H256>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyncAggregate {
+	/// Participation bitvector, one bit per sync committee member
+	pub sync_committee_bits: Vec<u8>,
+	/// Aggregate signature of the participating members
+	pub sync_committee_signature: BlsSignature,
+}
+
+impl SyncAggregate {
+	/// Whether the committee member at `index` took part in the signature.
+	pub fn participated(&self, index: usize) -> bool {
+		self.sync_committee_bits
+			.get(index / 8)
+			.map(|byte| byte >> (index % 8) & 1 == 1)
+			.unwrap_or(false)
+	}
+
+	/// Number of committee members that took part in the signature.
+	pub fn participants(&self) -> usize {
+		self.sync_committee_bits.iter().map(|byte| byte.count_ones() as usize).sum()
+	}
+}
+
+/// Update of the light client signed by the sync committee, following the altair light client
+/// sync protocol. Only finalized updates are accepted, so the finality proof is mandatory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LightClientUpdate {
+	/// Header signed by the sync committee
+	pub attested_header: BeaconBlockHeader,
+	/// Next sync committee, proven in the attested header's state
+	pub next_sync_committee: Option<SyncCommittee>,
+	pub next_sync_committee_branch: Vec<H256>,
+	/// Finalized header, proven in the attested header's state
+	pub finalized_header: LightClientHeader,
+	pub finality_branch: Vec<H256>,
+	/// Sync committee signature over the attested header
+	pub sync_aggregate: SyncAggregate,
+	/// Slot at which the signature was produced
+	pub signature_slot: u64,
+}
+
+/// Two updates that finalize different headers for the same slot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Misbehaviour {
+	pub first: LightClientUpdate,
+	pub second: LightClientUpdate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClientMessage {
+	/// This is the variant for header updates
+	Header(LightClientUpdate),
+	/// This is for submitting misbehaviors.
+	Misbehaviour(Misbehaviour),
+}
+
+impl ibc::core::ics02_client::client_message::ClientMessage for ClientMessage {
+	fn encode_to_vec(&self) -> Vec<u8> {
+		self.encode_vec()
+	}
+}
+
+pub(crate) fn fixed_bytes<const N: usize>(bytes: &[u8], name: &str) -> Result<[u8; N], Error> {
+	bytes.try_into().map_err(|_| {
+		Error::Custom(format!("Invalid {name}: expected {N} bytes, got {}", bytes.len()))
+	})
+}
+
+fn branch(raw: Vec<Vec<u8>>, name: &str) -> Result<Vec<H256>, Error> {
+	raw.iter().map(|node| Ok(H256(fixed_bytes(node, name)?))).collect()
+}
+
+fn into_raw_branch(branch: Vec<H256>) -> Vec<Vec<u8>> {
+	branch.into_iter().map(|node| node.0.to_vec()).collect()
+}
+
+impl TryFrom<RawBeaconBlockHeader> for BeaconBlockHeader {
+	type Error = Error;
+
+	fn try_from(raw: RawBeaconBlockHeader) -> Result<Self, Self::Error> {
+		Ok(Self {
+			slot: raw.slot,
+			proposer_index: raw.proposer_index,
+			parent_root: H256(fixed_bytes(&raw.parent_root, "parent root")?),
+			state_root: H256(fixed_bytes(&raw.state_root, "state root")?),
+			body_root: H256(fixed_bytes(&raw.body_root, "body root")?),
+		})
+	}
+}
+
+impl From<BeaconBlockHeader> for RawBeaconBlockHeader {
+	fn from(header: BeaconBlockHeader) -> Self {
+		RawBeaconBlockHeader {
+			slot: header.slot,
+			proposer_index: header.proposer_index,
+			parent_root: header.parent_root.0.to_vec(),
+			state_root: header.state_root.0.to_vec(),
+			body_root: header.body_root.0.to_vec(),
+		}
+	}
+}
+
+impl TryFrom<RawSyncCommittee> for SyncCommittee {
+	type Error = Error;
+
+	fn try_from(raw: RawSyncCommittee) -> Result<Self, Self::Error> {
+		if raw.pubkeys.len() != SYNC_COMMITTEE_SIZE {
+			return Err(Error::Custom(format!(
+				"Invalid sync committee: expected {SYNC_COMMITTEE_SIZE} members, got {}",
+				raw.pubkeys.len()
+			)))
+		}
+		let pubkeys = raw
+			.pubkeys
+			.iter()
+			.map(|key| fixed_bytes(key, "sync committee public key"))
+			.collect::<Result<_, _>>()?;
+		Ok(Self {
+			pubkeys,
+			aggregate_pubkey: fixed_bytes(&raw.aggregate_pubkey, "aggregate public key")?,
+		})
+	}
+}
+
+impl From<SyncCommittee> for RawSyncCommittee {
+	fn from(committee: SyncCommittee) -> Self {
+		RawSyncCommittee {
+			pubkeys: committee.pubkeys.iter().map(|key| key.to_vec()).collect(),
+			aggregate_pubkey: committee.aggregate_pubkey.to_vec(),
+		}
+	}
+}
+
+impl TryFrom<RawExecutionPayloadHeader> for ExecutionPayloadHeader {
+	type Error = Error;
+
+	fn try_from(raw: RawExecutionPayloadHeader) -> Result<Self, Self::Error> {
+		if raw.logs_bloom.len() != BYTES_PER_LOGS_BLOOM {
+			return Err(Error::Custom(format!(
+				"Invalid logs bloom: expected {BYTES_PER_LOGS_BLOOM} bytes, got {}",
+				raw.logs_bloom.len()
+			)))
+		}
+		if raw.extra_data.len() > MAX_EXTRA_DATA_BYTES {
+			return Err(Error::Custom(format!(
+				"Invalid extra data: expected at most {MAX_EXTRA_DATA_BYTES} bytes, got {}",
+				raw.extra_data.len()
+			)))
+		}
+		Ok(Self {
+			parent_hash: H256(fixed_bytes(&raw.parent_hash, "parent hash")?),
+			fee_recipient: H160(fixed_bytes(&raw.fee_recipient, "fee recipient")?),
+			state_root: H256(fixed_bytes(&raw.state_root, "execution state root")?),
+			receipts_root: H256(fixed_bytes(&raw.receipts_root, "receipts root")?),
+			logs_bloom: raw.logs_bloom,
+			prev_randao: H256(fixed_bytes(&raw.prev_randao, "prev randao")?),
+			block_number: raw.block_number,
+			gas_limit: raw.gas_limit,
+			gas_used: raw.gas_used,
+			timestamp: raw.timestamp,
+			extra_data: raw.extra_data,
+			base_fee_per_gas: U256::from_little_endian(&fixed_bytes::<32>(
+				&raw.base_fee_per_gas,
+				"base fee per gas",
+			)?),
+			block_hash: H256(fixed_bytes(&raw.block_hash, "block hash")?),
+			transactions_root: H256(fixed_bytes(&raw.transactions_root, "transactions root")?),
+			withdrawals_root: H256(fixed_bytes(&raw.withdrawals_root, "withdrawals root")?),
+		})
+	}
+}
+
+impl From<ExecutionPayloadHeader> for RawExecutionPayloadHeader {
+	fn from(header: ExecutionPayloadHeader) -> Self {
+		let mut base_fee_per_gas = [0u8; 32];
+		header.base_fee_per_gas.to_little_endian(&mut base_fee_per_gas);
+		RawExecutionPayloadHeader {
+			parent_hash: header.parent_hash.0.to_vec(),
+			fee_recipient: header.fee_recipient.0.to_vec(),
+			state_root: header.state_root.0.to_vec(),
+			receipts_root: header.receipts_root.0.to_vec(),
+			logs_bloom: header.logs_bloom,
+			prev_randao: header.prev_randao.0.to_vec(),
+			block_number: header.block_number,
+			gas_limit: header.gas_limit,
+			gas_used: header.gas_used,
+			timestamp: header.timestamp,
+			extra_data: header.extra_data,
+			base_fee_per_gas: base_fee_per_gas.to_vec(),
+			block_hash: header.block_hash.0.to_vec(),
+			transactions_root: header.transactions_root.0.to_vec(),
+			withdrawals_root: header.withdrawals_root.0.to_vec(),
+		}
+	}
+}
+
+impl TryFrom<RawLightClientHeader> for LightClientHeader {
+	type Error = Error;
+
+	fn try_from(raw: RawLightClientHeader) -> Result<Self, Self::Error> {
+		Ok(Self {
+			beacon: raw.beacon.ok_or_else(|| anyhow!("Missing beacon block header"))?.try_into()?,
+			execution: raw
+				.execution
+				.ok_or_else(|| anyhow!("Missing execution payload header"))?
+				.try_into()?,
+			execution_branch: branch(raw.execution_branch, "execution branch")?,
+		})
+	}
+}
+
+impl From<LightClientHeader> for RawLightClientHeader {
+	fn from(header: LightClientHeader) -> Self {
+		RawLightClientHeader {
+			beacon: Some(header.beacon.into()),
+			execution: Some(header.execution.into()),
+			execution_branch: into_raw_branch(header.execution_branch),
+		}
+	}
+}
+
+impl TryFrom<RawLightClientUpdate> for LightClientUpdate {
+	type Error = Error;
+
+	fn try_from(raw: RawLightClientUpdate) -> Result<Self, Self::Error> {
+		let sync_aggregate =
+			raw.sync_aggregate.ok_or_else(|| anyhow!("Missing sync aggregate"))?;
+		if sync_aggregate.sync_committee_bits.len() != SYNC_COMMITTEE_SIZE / 8 {
+			return Err(Error::Custom(format!(
+				"Invalid sync committee bits: expected {} bytes, got {}",
+				SYNC_COMMITTEE_SIZE / 8,
+				sync_aggregate.sync_committee_bits.len()
+			)))
+		}
+		Ok(Self {
+			attested_header: raw
+				.attested_header
+				.ok_or_else(|| anyhow!("Missing attested header"))?
+				.try_into()?,
+			next_sync_committee: raw.next_sync_committee.map(TryInto::try_into).transpose()?,
+			next_sync_committee_branch: branch(
+				raw.next_sync_committee_branch,
+				"next sync committee branch",
+			)?,
+			finalized_header: raw
+				.finalized_header
+				.ok_or_else(|| anyhow!("Missing finalized header"))?
+				.try_into()?,
+			finality_branch: branch(raw.finality_branch, "finality branch")?,
+			sync_aggregate: SyncAggregate {
+				sync_committee_bits: sync_aggregate.sync_committee_bits,
+				sync_committee_signature: fixed_bytes(
+					&sync_aggregate.sync_committee_signature,
+					"sync committee signature",
+				)?,
+			},
+			signature_slot: raw.signature_slot,
+		})
+	}
+}
+
+impl From<LightClientUpdate> for RawLightClientUpdate {
+	fn from(update: LightClientUpdate) -> Self {
+		RawLightClientUpdate {
+			attested_header: Some(update.attested_header.into()),
+			next_sync_committee: update.next_sync_committee.map(Into::into),
+			next_sync_committee_branch: into_raw_branch(update.next_sync_committee_branch),
+			finalized_header: Some(update.finalized_header.into()),
+			finality_branch: into_raw_branch(update.finality_branch),
+			sync_aggregate: Some(RawSyncAggregate {
+				sync_committee_bits: update.sync_aggregate.sync_committee_bits,
+				sync_committee_signature: update
+					.sync_aggregate
+					.sync_committee_signature
+					.to_vec(),
+			}),
+			signature_slot: update.signature_slot,
+		}
+	}
+}
+
+impl Protobuf<RawClientMessage> for ClientMessage {}
+
+impl TryFrom<RawClientMessage> for ClientMessage {
+	type Error = Error;
+
+	fn try_from(msg: RawClientMessage) -> Result<Self, Self::Error> {
+		let message = match msg
+			.message
+			.ok_or_else(|| anyhow!("Must supply either Header or Misbehaviour type!"))?
+		{
+			client_message::Message::Header(update) => ClientMessage::Header(update.try_into()?),
+			client_message::Message::Misbehaviour(RawMisbehaviour { first, second }) =>
+				ClientMessage::Misbehaviour(Misbehaviour {
+					first: first.ok_or_else(|| anyhow!("Missing first update"))?.try_into()?,
+					second: second.ok_or_else(|| anyhow!("Missing second update"))?.try_into()?,
+				}),
+		};
+		Ok(message)
+	}
+}
+
+impl From<ClientMessage> for RawClientMessage {
+	fn from(client_message: ClientMessage) -> Self {
+		let message = match client_message {
+			ClientMessage::Header(update) => client_message::Message::Header(update.into()),
+			ClientMessage::Misbehaviour(Misbehaviour { first, second }) =>
+				client_message::Message::Misbehaviour(RawMisbehaviour {
+					first: Some(first.into()),
+					second: Some(second.into()),
+				}),
+		};
+		RawClientMessage { message: Some(message) }
+	}
+}
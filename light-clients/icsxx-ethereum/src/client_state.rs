@@ -0,0 +1,243 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::string::ToString;
+use core::{convert::TryFrom, fmt::Debug, marker::PhantomData, time::Duration};
+use ibc::prelude::*;
+use primitive_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use crate::proto::ClientState as RawClientState;
+
+use crate::{
+	client_def::EthereumClient,
+	client_message::{fixed_bytes, BeaconBlockHeader, SyncCommittee},
+	error::Error,
+	HostFunctions,
+};
+use anyhow::anyhow;
+use ibc::{
+	core::{ics02_client::client_state::ClientType, ics24_host::identifier::ChainId},
+	Height,
+};
+
+/// Protobuf type url for Ethereum ClientState
+pub const ETHEREUM_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.ethereum.v1.ClientState";
+
+#[derive(PartialEq, Clone, Debug, Eq)]
+pub struct ClientState<H> {
+	/// The chain id
+	pub chain_id: ChainId,
+	/// Root of the beacon chain's genesis validators, part of the signing domain
+	pub genesis_validators_root: H256,
+	/// Fork version the sync committee signs with
+	pub fork_version: [u8; 4],
+	/// Latest finalized beacon block header
+	pub finalized_header: BeaconBlockHeader,
+	/// Execution block number of the latest finalized header
+	pub latest_execution_height: u64,
+	/// Sync committee of the finalized header's period
+	pub current_sync_committee: SyncCommittee,
+	/// Sync committee of the following period, once proven
+	pub next_sync_committee: Option<SyncCommittee>,
+	/// Address of the IBC commitments contract
+	pub ibc_contract_address: H160,
+	/// Storage slot of the contract's commitments mapping
+	pub ibc_commitments_slot: H256,
+	/// Duration of the period since the latest consensus state timestamp during which the client
+	/// can be updated
+	pub trusting_period: Duration,
+	/// Block height when the client was frozen due to a misbehaviour
+	pub frozen_height: Option<Height>,
+	/// Phantom type
+	pub _phantom: PhantomData<H>,
+}
+
+impl<H: Clone> Protobuf<RawClientState> for ClientState<H> {}
+
+impl<H: Clone> ClientState<H> {
+	pub fn with_frozen_height(self, h: Height) -> Result<Self, Error> {
+		if h == Height::zero() {
+			return Err(Error::Custom(
+				"ClientState frozen height must be greater than zero".to_string(),
+			))
+		}
+		Ok(Self { frozen_height: Some(h), ..self })
+	}
+
+	/// Lift a freeze, for recovering a client that was frozen by governance.
+	pub fn unfreeze(self) -> Self {
+		Self { frozen_height: None, ..self }
+	}
+
+	/// Verify that the client is at a sufficient height and unfrozen at the given height
+	pub fn verify_height(&self, height: Height) -> Result<(), Error> {
+		let latest_height = self.latest_height();
+		if latest_height < height {
+			return Err(Error::Custom(format!(
+				"Insufficient height, known height: {latest_height}, given height: {height}"
+			)))
+		}
+
+		match self.frozen_height {
+			Some(frozen_height) if frozen_height <= height =>
+				Err(Error::Custom(format!("Client has been frozen at height {frozen_height}"))),
+			_ => Ok(()),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeOptions;
+
+impl<H> ClientState<H> {
+	pub fn latest_height(&self) -> Height {
+		Height::new(0, self.latest_execution_height)
+	}
+
+	pub fn chain_id(&self) -> ChainId {
+		self.chain_id.clone()
+	}
+
+	pub fn client_type() -> ClientType {
+		"xx-ethereum".to_string()
+	}
+
+	pub fn frozen_height(&self) -> Option<Height> {
+		self.frozen_height
+	}
+
+	pub fn upgrade(
+		mut self,
+		_upgrade_height: Height,
+		_upgrade_options: UpgradeOptions,
+		_chain_id: ChainId,
+	) -> Self {
+		self.frozen_height = None;
+		self
+	}
+
+	/// Check if the state is expired when `elapsed` time has passed since the latest consensus
+	/// state timestamp
+	pub fn expired(&self, elapsed: Duration) -> bool {
+		elapsed > self.trusting_period
+	}
+}
+
+impl<H> ibc::core::ics02_client::client_state::ClientState for ClientState<H>
+where
+	H: HostFunctions,
+{
+	type UpgradeOptions = UpgradeOptions;
+	type ClientDef = EthereumClient<H>;
+
+	fn chain_id(&self) -> ChainId {
+		self.chain_id()
+	}
+
+	fn client_def(&self) -> Self::ClientDef {
+		EthereumClient::default()
+	}
+
+	fn client_type(&self) -> ClientType {
+		Self::client_type()
+	}
+
+	fn latest_height(&self) -> Height {
+		self.latest_height()
+	}
+
+	fn frozen_height(&self) -> Option<Height> {
+		self.frozen_height()
+	}
+
+	fn upgrade(
+		self,
+		upgrade_height: Height,
+		upgrade_options: UpgradeOptions,
+		chain_id: ChainId,
+	) -> Self {
+		self.upgrade(upgrade_height, upgrade_options, chain_id)
+	}
+
+	fn expired(&self, elapsed: Duration) -> bool {
+		self.expired(elapsed)
+	}
+
+	fn trusting_period(&self) -> Duration {
+		self.trusting_period
+	}
+
+	fn encode_to_vec(&self) -> Vec<u8> {
+		self.encode_vec()
+	}
+}
+
+impl<H> TryFrom<RawClientState> for ClientState<H> {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		Ok(Self {
+			chain_id: ChainId::from_string(&raw.chain_id),
+			genesis_validators_root: H256(fixed_bytes(
+				&raw.genesis_validators_root,
+				"genesis validators root",
+			)?),
+			fork_version: fixed_bytes(&raw.fork_version, "fork version")?,
+			finalized_header: raw
+				.finalized_header
+				.ok_or_else(|| anyhow!("Missing finalized header"))?
+				.try_into()?,
+			latest_execution_height: raw.latest_execution_height,
+			current_sync_committee: raw
+				.current_sync_committee
+				.ok_or_else(|| anyhow!("Missing current sync committee"))?
+				.try_into()?,
+			next_sync_committee: raw.next_sync_committee.map(TryInto::try_into).transpose()?,
+			ibc_contract_address: H160(fixed_bytes(
+				&raw.ibc_contract_address,
+				"ibc contract address",
+			)?),
+			ibc_commitments_slot: H256(fixed_bytes(
+				&raw.ibc_commitments_slot,
+				"ibc commitments slot",
+			)?),
+			trusting_period: Duration::from_secs(raw.trusting_period),
+			frozen_height: raw.frozen_height.map(|height| Height::new(0, height)),
+			_phantom: Default::default(),
+		})
+	}
+}
+
+impl<H> From<ClientState<H>> for RawClientState {
+	fn from(client_state: ClientState<H>) -> Self {
+		RawClientState {
+			chain_id: client_state.chain_id.to_string(),
+			genesis_validators_root: client_state.genesis_validators_root.0.to_vec(),
+			fork_version: client_state.fork_version.to_vec(),
+			finalized_header: Some(client_state.finalized_header.into()),
+			latest_execution_height: client_state.latest_execution_height,
+			current_sync_committee: Some(client_state.current_sync_committee.into()),
+			next_sync_committee: client_state.next_sync_committee.map(Into::into),
+			ibc_contract_address: client_state.ibc_contract_address.0.to_vec(),
+			ibc_commitments_slot: client_state.ibc_commitments_slot.0.to_vec(),
+			trusting_period: client_state.trusting_period.as_secs(),
+			frozen_height: client_state
+				.frozen_height
+				.map(|frozen_height| frozen_height.revision_height),
+		}
+	}
+}
@@ -0,0 +1,55 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::all)]
+
+//! ICS-XX: Ethereum IBC light client protocol implementation, following the beacon chain's sync
+//! committees and proving the state of an IBC commitments contract on the execution layer.
+
+extern crate alloc;
+extern crate core;
+
+use core::fmt::Debug;
+
+pub mod client_def;
+pub mod client_message;
+pub mod client_state;
+pub mod consensus_state;
+pub mod error;
+pub mod proof;
+mod proto;
+pub mod ssz;
+pub mod verify;
+
+#[cfg(test)]
+mod tests;
+
+/// Host functions that allow the light client to perform cryptographic operations in native.
+pub trait HostFunctions: Clone + Send + Sync + Eq + Debug + Default {
+	/// Sha256 hash function, used to merkleize the beacon chain's objects.
+	fn sha256(input: &[u8]) -> [u8; 32];
+
+	/// Keccak 256 hash function, used by the execution layer's state tries.
+	fn keccak_256(input: &[u8]) -> [u8; 32];
+
+	/// Verify a BLS aggregate signature of all `public_keys` over `message`, as the sync committee
+	/// produces them.
+	fn bls_fast_aggregate_verify(
+		public_keys: &[[u8; 48]],
+		message: &[u8],
+		signature: &[u8; 96],
+	) -> bool;
+}
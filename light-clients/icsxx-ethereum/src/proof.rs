@@ -0,0 +1,226 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of EIP-1186 proofs of the IBC commitments contract's storage.
+//!
+//! The contract keeps a `mapping(bytes32 => bytes32)` at [`ClientState::ibc_commitments_slot`],
+//! from `keccak256(prefix ++ path)` to the `keccak256` of the value stored at the IBC path, with
+//! sequences stored as big endian u64s. A proof is a protobuf encoded [`StorageProof`] of the
+//! contract's account in the execution state trie and of the mapping entry in its storage trie.
+
+use crate::{client_state::ClientState, error::Error, HostFunctions};
+use alloc::{format, string::ToString, vec::Vec};
+use ibc::core::{
+	ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+	ics24_host::path::Path,
+};
+use primitive_types::H256;
+use prost::Message;
+use rlp::Rlp;
+
+pub use crate::proto::StorageProof;
+
+/// Verify that the contract commits to `value` under `path`.
+pub fn verify_membership<H, P>(
+	client_state: &ClientState<H>,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+	value: Vec<u8>,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let path: Path = path.into();
+	let commitment = read_commitment::<H>(client_state, prefix, proof, root, &path)?;
+	if commitment != Some(H::keccak_256(&value)) {
+		return Err(Error::Custom(format!("Proven commitment for path {path} doesn't match")))
+	}
+	Ok(())
+}
+
+/// Verify that the contract doesn't commit to any value under `path`.
+pub fn verify_non_membership<H, P>(
+	client_state: &ClientState<H>,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: P,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let path: Path = path.into();
+	if read_commitment::<H>(client_state, prefix, proof, root, &path)?.is_some() {
+		return Err(Error::Custom(format!("Found a commitment for path {path}")))
+	}
+	Ok(())
+}
+
+/// Storage slot of the contract's mapping entry for `key`.
+pub fn commitment_slot<H: HostFunctions>(key: &[u8], commitments_slot: H256) -> [u8; 32] {
+	let mut preimage = H::keccak_256(key).to_vec();
+	preimage.extend_from_slice(commitments_slot.as_bytes());
+	H::keccak_256(&preimage)
+}
+
+/// Commitment the contract stores under `path`, `None` if the slot is empty.
+fn read_commitment<H: HostFunctions>(
+	client_state: &ClientState<H>,
+	prefix: &CommitmentPrefix,
+	proof: &CommitmentProofBytes,
+	root: &CommitmentRoot,
+	path: &Path,
+) -> Result<Option<[u8; 32]>, Error> {
+	if root.as_bytes().len() != 32 {
+		return Err(Error::Custom(format!(
+			"invalid commitment root length: {}",
+			root.as_bytes().len()
+		)))
+	}
+	let mut key = prefix.as_bytes().to_vec();
+	key.extend(path.to_string().as_bytes());
+	let slot = commitment_slot::<H>(&key, client_state.ibc_commitments_slot);
+	let proof = StorageProof::decode(proof.as_bytes())?;
+
+	let account = verify_trie_proof::<H>(
+		H256::from_slice(root.as_bytes()),
+		&H::keccak_256(client_state.ibc_contract_address.as_bytes()),
+		&proof.account_proof,
+	)?
+	.ok_or_else(|| Error::Custom(format!("IBC contract account doesn't exist")))?;
+	// accounts are encoded as [nonce, balance, storage_root, code_hash]
+	let storage_root = Rlp::new(&account).val_at::<Vec<u8>>(2)?;
+	if storage_root.len() != 32 {
+		return Err(Error::Custom(format!("Invalid IBC contract storage root")))
+	}
+
+	let value = match verify_trie_proof::<H>(
+		H256::from_slice(&storage_root),
+		&H::keccak_256(&slot),
+		&proof.storage_proof,
+	)? {
+		Some(value) => value,
+		None => return Ok(None),
+	};
+	// storage values are RLP encoded big endian integers, without leading zeros
+	let value = Rlp::new(&value).data()?;
+	if value.len() > 32 {
+		return Err(Error::Custom(format!("Invalid storage value length: {}", value.len())))
+	}
+	let mut commitment = [0u8; 32];
+	commitment[32 - value.len()..].copy_from_slice(value);
+	Ok((commitment != [0u8; 32]).then(|| commitment))
+}
+
+enum NodeRef {
+	Hash(H256),
+	Inline(Vec<u8>),
+}
+
+impl NodeRef {
+	fn decode(rlp: Rlp) -> Result<Option<Self>, Error> {
+		if rlp.is_list() {
+			return Ok(Some(NodeRef::Inline(rlp.as_raw().to_vec())))
+		}
+		match rlp.data()? {
+			[] => Ok(None),
+			hash if hash.len() == 32 => Ok(Some(NodeRef::Hash(H256::from_slice(hash)))),
+			_ => Err(Error::Custom(format!("Invalid trie node reference"))),
+		}
+	}
+}
+
+/// Decodes the hex prefix encoded path of a leaf or extension node, returning its nibbles and
+/// whether the node is a leaf.
+fn decode_path(encoded: &[u8]) -> Result<(Vec<u8>, bool), Error> {
+	let first = *encoded.first().ok_or_else(|| Error::Custom(format!("Empty trie node path")))?;
+	let flag = first >> 4;
+	if flag > 3 {
+		return Err(Error::Custom(format!("Invalid trie node path flag: {flag}")))
+	}
+	let mut nibbles = Vec::with_capacity(encoded.len() * 2);
+	if flag & 1 == 1 {
+		nibbles.push(first & 0x0f);
+	}
+	for byte in &encoded[1..] {
+		nibbles.push(byte >> 4);
+		nibbles.push(byte & 0x0f);
+	}
+	Ok((nibbles, flag & 2 == 2))
+}
+
+/// Verify a merkle patricia trie proof of `key` against `root`, returning the value stored
+/// under the key, or `None` if the proof shows it is absent.
+pub fn verify_trie_proof<H: HostFunctions>(
+	root: H256,
+	key: &[u8],
+	proof: &[Vec<u8>],
+) -> Result<Option<Vec<u8>>, Error> {
+	let key = key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect::<Vec<_>>();
+	let mut nibbles = &key[..];
+	let mut proof = proof.iter();
+	let mut next = NodeRef::Hash(root);
+	loop {
+		let node = match next {
+			NodeRef::Hash(hash) => {
+				let node = proof
+					.next()
+					.ok_or_else(|| Error::Custom(format!("Trie proof is incomplete")))?;
+				if H::keccak_256(node) != hash.0 {
+					return Err(Error::Custom(format!("Trie proof node doesn't match its hash")))
+				}
+				node.clone()
+			},
+			NodeRef::Inline(node) => node,
+		};
+		let node = Rlp::new(&node);
+		let child = match node.item_count()? {
+			17 => {
+				let (nibble, rest) = match nibbles.split_first() {
+					Some(split) => split,
+					None => {
+						let value = node.at(16)?.data()?;
+						return Ok((!value.is_empty()).then(|| value.to_vec()))
+					},
+				};
+				nibbles = rest;
+				node.at(*nibble as usize)?
+			},
+			2 => {
+				let (path, is_leaf) = decode_path(node.at(0)?.data()?)?;
+				if is_leaf {
+					if nibbles != &path[..] {
+						return Ok(None)
+					}
+					return Ok(Some(node.at(1)?.data()?.to_vec()))
+				}
+				if !nibbles.starts_with(&path) {
+					return Ok(None)
+				}
+				nibbles = &nibbles[path.len()..];
+				node.at(1)?
+			},
+			count => return Err(Error::Custom(format!("Invalid trie node with {count} items"))),
+		};
+		next = match NodeRef::decode(child)? {
+			Some(next) => next,
+			None => return Ok(None),
+		};
+	}
+}
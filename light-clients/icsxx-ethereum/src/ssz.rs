@@ -0,0 +1,159 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! SSZ merkleization of the beacon chain objects the light client verifies.
+
+use crate::{
+	client_message::{BeaconBlockHeader, BlsPublicKey, ExecutionPayloadHeader, SyncCommittee},
+	HostFunctions,
+};
+use alloc::vec::Vec;
+use primitive_types::H256;
+
+/// Depth of the merkle tree of a sync committee's public keys.
+const SYNC_COMMITTEE_DEPTH: u32 = 9;
+
+fn hash_pair<H: HostFunctions>(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	let mut buf = [0u8; 64];
+	buf[..32].copy_from_slice(left);
+	buf[32..].copy_from_slice(right);
+	H::sha256(&buf)
+}
+
+fn uint64_chunk(value: u64) -> [u8; 32] {
+	let mut chunk = [0u8; 32];
+	chunk[..8].copy_from_slice(&value.to_le_bytes());
+	chunk
+}
+
+/// Right pads `bytes`, which must be at most 32 bytes long, into a chunk.
+fn bytes_chunk(bytes: &[u8]) -> [u8; 32] {
+	let mut chunk = [0u8; 32];
+	chunk[..bytes.len()].copy_from_slice(bytes);
+	chunk
+}
+
+fn byte_chunks(bytes: &[u8]) -> Vec<[u8; 32]> {
+	bytes.chunks(32).map(bytes_chunk).collect()
+}
+
+/// Merkleize `chunks` into a tree of `2^depth` leaves, padded with zero chunks.
+pub fn merkleize<H: HostFunctions>(chunks: &[[u8; 32]], depth: u32) -> [u8; 32] {
+	debug_assert!(chunks.len() <= 1 << depth);
+	let mut layer = chunks.to_vec();
+	layer.resize(1 << depth, [0u8; 32]);
+	for _ in 0..depth {
+		layer = layer.chunks(2).map(|pair| hash_pair::<H>(&pair[0], &pair[1])).collect();
+	}
+	layer[0]
+}
+
+fn mix_in_length<H: HostFunctions>(root: [u8; 32], length: usize) -> [u8; 32] {
+	hash_pair::<H>(&root, &uint64_chunk(length as u64))
+}
+
+pub fn beacon_block_header_root<H: HostFunctions>(header: &BeaconBlockHeader) -> [u8; 32] {
+	let fields = [
+		uint64_chunk(header.slot),
+		uint64_chunk(header.proposer_index),
+		header.parent_root.0,
+		header.state_root.0,
+		header.body_root.0,
+	];
+	merkleize::<H>(&fields, 3)
+}
+
+pub fn bls_public_key_root<H: HostFunctions>(key: &BlsPublicKey) -> [u8; 32] {
+	hash_pair::<H>(&bytes_chunk(&key[..32]), &bytes_chunk(&key[32..]))
+}
+
+pub fn sync_committee_root<H: HostFunctions>(committee: &SyncCommittee) -> [u8; 32] {
+	let keys = committee.pubkeys.iter().map(bls_public_key_root::<H>).collect::<Vec<_>>();
+	hash_pair::<H>(
+		&merkleize::<H>(&keys, SYNC_COMMITTEE_DEPTH),
+		&bls_public_key_root::<H>(&committee.aggregate_pubkey),
+	)
+}
+
+pub fn execution_payload_header_root<H: HostFunctions>(
+	header: &ExecutionPayloadHeader,
+) -> [u8; 32] {
+	let extra_data = merkleize::<H>(&byte_chunks(&header.extra_data), 0);
+	let mut base_fee_per_gas = [0u8; 32];
+	header.base_fee_per_gas.to_little_endian(&mut base_fee_per_gas);
+	let fields = [
+		header.parent_hash.0,
+		bytes_chunk(header.fee_recipient.as_bytes()),
+		header.state_root.0,
+		header.receipts_root.0,
+		merkleize::<H>(&byte_chunks(&header.logs_bloom), 3),
+		header.prev_randao.0,
+		uint64_chunk(header.block_number),
+		uint64_chunk(header.gas_limit),
+		uint64_chunk(header.gas_used),
+		uint64_chunk(header.timestamp),
+		mix_in_length::<H>(extra_data, header.extra_data.len()),
+		base_fee_per_gas,
+		header.block_hash.0,
+		header.transactions_root.0,
+		header.withdrawals_root.0,
+	];
+	merkleize::<H>(&fields, 4)
+}
+
+/// Domain of the signatures of `domain_type` made with the given fork version.
+pub fn compute_domain<H: HostFunctions>(
+	domain_type: [u8; 4],
+	fork_version: [u8; 4],
+	genesis_validators_root: H256,
+) -> [u8; 32] {
+	let fork_data_root = hash_pair::<H>(&bytes_chunk(&fork_version), &genesis_validators_root.0);
+	let mut domain = [0u8; 32];
+	domain[..4].copy_from_slice(&domain_type);
+	domain[4..].copy_from_slice(&fork_data_root[..28]);
+	domain
+}
+
+/// Message signed for an object with the given root, in the given domain.
+pub fn compute_signing_root<H: HostFunctions>(object_root: [u8; 32], domain: [u8; 32]) -> [u8; 32] {
+	hash_pair::<H>(&object_root, &domain)
+}
+
+/// Depth and index, among the nodes at that depth, of the node at generalized index `gindex`.
+pub fn gindex_position(gindex: u64) -> (u32, u64) {
+	let depth = 63 - gindex.leading_zeros();
+	(depth, gindex - (1 << depth))
+}
+
+/// Whether `branch` proves `leaf` at generalized index `gindex` in the tree with the given `root`.
+pub fn is_valid_merkle_branch<H: HostFunctions>(
+	leaf: [u8; 32],
+	branch: &[H256],
+	gindex: u64,
+	root: H256,
+) -> bool {
+	let (depth, index) = gindex_position(gindex);
+	if branch.len() != depth as usize {
+		return false
+	}
+	let value = branch.iter().enumerate().fold(leaf, |value, (i, node)| {
+		if index >> i & 1 == 1 {
+			hash_pair::<H>(&node.0, &value)
+		} else {
+			hash_pair::<H>(&value, &node.0)
+		}
+	});
+	value == root.0
+}
@@ -0,0 +1,470 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The sync committee updates are built from deterministic keys rather than recorded from a
+//! beacon node, so that the tests don't depend on the network and can tamper with every field.
+
+use crate::{
+	client_message::{
+		BeaconBlockHeader, ExecutionPayloadHeader, LightClientHeader, LightClientUpdate,
+		SyncAggregate, SyncCommittee, BYTES_PER_LOGS_BLOOM,
+	},
+	client_state::ClientState,
+	proof::{commitment_slot, verify_membership, verify_non_membership},
+	proto::StorageProof,
+	ssz::{
+		beacon_block_header_root, compute_domain, compute_signing_root,
+		execution_payload_header_root, sync_committee_root,
+	},
+	verify::{
+		apply_light_client_update, verify_light_client_update, DOMAIN_SYNC_COMMITTEE,
+		EPOCHS_PER_SYNC_COMMITTEE_PERIOD, EXECUTION_PAYLOAD_GINDEX, FINALIZED_ROOT_GINDEX,
+		NEXT_SYNC_COMMITTEE_GINDEX, SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE,
+	},
+	HostFunctions,
+};
+use ibc::core::{
+	ics04_channel::packet::Sequence,
+	ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+	ics24_host::{
+		identifier::{ChainId, ChannelId, PortId},
+		path::{CommitmentsPath, Path},
+	},
+};
+use primitive_types::{H160, H256};
+use prost::Message;
+use rlp::RlpStream;
+use sha2::Digest;
+use std::{collections::BTreeMap, time::Duration};
+
+const SLOTS_PER_PERIOD: u64 = SLOTS_PER_EPOCH * EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HostFunctionsManager;
+
+impl HostFunctions for HostFunctionsManager {
+	fn sha256(input: &[u8]) -> [u8; 32] {
+		sha2::Sha256::digest(input).into()
+	}
+
+	fn keccak_256(input: &[u8]) -> [u8; 32] {
+		sha3::Keccak256::digest(input).into()
+	}
+
+	fn bls_fast_aggregate_verify(
+		public_keys: &[[u8; 48]],
+		message: &[u8],
+		signature: &[u8; 96],
+	) -> bool {
+		let public_keys = match public_keys
+			.iter()
+			.map(|key| milagro_bls::PublicKey::from_bytes(key))
+			.collect::<Result<Vec<_>, _>>()
+		{
+			Ok(public_keys) => public_keys,
+			Err(_) => return false,
+		};
+		let signature = match milagro_bls::AggregateSignature::from_bytes(signature) {
+			Ok(signature) => signature,
+			Err(_) => return false,
+		};
+		signature.fast_aggregate_verify(message, &public_keys.iter().collect::<Vec<_>>())
+	}
+}
+
+/// A sync committee whose members cycle through a handful of known secret keys.
+struct Committee {
+	secret_keys: Vec<milagro_bls::SecretKey>,
+	committee: SyncCommittee,
+}
+
+impl Committee {
+	fn new(seed: u8) -> Self {
+		let secret_keys = (1..=4u8)
+			.map(|i| {
+				let mut bytes = [0u8; 32];
+				bytes[30] = seed;
+				bytes[31] = i;
+				milagro_bls::SecretKey::from_bytes(&bytes).unwrap()
+			})
+			.collect::<Vec<_>>();
+		let members = secret_keys
+			.iter()
+			.map(|key| milagro_bls::PublicKey::from_secret_key(key).as_bytes())
+			.collect::<Vec<_>>();
+		let pubkeys = (0..SYNC_COMMITTEE_SIZE)
+			.map(|i| members[i % members.len()])
+			.collect::<Vec<_>>();
+		// the client only commits to the aggregate key, it never verifies against it
+		let committee = SyncCommittee { aggregate_pubkey: pubkeys[0], pubkeys };
+		Self { secret_keys, committee }
+	}
+
+	/// Signature of every member of the committee over `message`.
+	fn sign(&self, message: &[u8]) -> SyncAggregate {
+		let signatures = self
+			.secret_keys
+			.iter()
+			.map(|key| milagro_bls::Signature::new(message, key))
+			.collect::<Vec<_>>();
+		let mut aggregate = milagro_bls::AggregateSignature::new();
+		for i in 0..SYNC_COMMITTEE_SIZE {
+			aggregate.add(&signatures[i % signatures.len()]);
+		}
+		SyncAggregate {
+			sync_committee_bits: vec![0xff; SYNC_COMMITTEE_SIZE / 8],
+			sync_committee_signature: aggregate.as_bytes(),
+		}
+	}
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	HostFunctionsManager::sha256(&[&left[..], &right[..]].concat())
+}
+
+/// Node at `gindex` of a tree of the given depth, holding `leaves` and zero chunks elsewhere.
+fn sparse_node(gindex: u64, depth: u32, leaves: &BTreeMap<u64, [u8; 32]>) -> [u8; 32] {
+	if let Some(leaf) = leaves.get(&gindex) {
+		return *leaf
+	}
+	if gindex >= 1 << depth {
+		return [0u8; 32]
+	}
+	hash_pair(&sparse_node(gindex * 2, depth, leaves), &sparse_node(gindex * 2 + 1, depth, leaves))
+}
+
+fn sparse_branch(gindex: u64, depth: u32, leaves: &BTreeMap<u64, [u8; 32]>) -> Vec<H256> {
+	let mut branch = vec![];
+	let mut gindex = gindex;
+	while gindex > 1 {
+		branch.push(H256(sparse_node(gindex ^ 1, depth, leaves)));
+		gindex /= 2;
+	}
+	branch
+}
+
+fn client_state(committee: &Committee, finalized_slot: u64) -> ClientState<HostFunctionsManager> {
+	ClientState {
+		chain_id: ChainId::new("ethereum".to_string(), 0),
+		genesis_validators_root: H256::repeat_byte(0x4b),
+		fork_version: [3, 0, 0, 0],
+		finalized_header: BeaconBlockHeader { slot: finalized_slot, ..Default::default() },
+		latest_execution_height: 1,
+		current_sync_committee: committee.committee.clone(),
+		next_sync_committee: None,
+		ibc_contract_address: H160::repeat_byte(0x1b),
+		ibc_commitments_slot: H256::zero(),
+		trusting_period: Duration::from_secs(60 * 60 * 24 * 14),
+		frozen_height: None,
+		_phantom: Default::default(),
+	}
+}
+
+/// Update finalizing the header at `finalized_slot`, signed by `signer` one slot after the
+/// attested header at `attested_slot`.
+fn signed_update(
+	client_state: &ClientState<HostFunctionsManager>,
+	signer: &Committee,
+	finalized_slot: u64,
+	attested_slot: u64,
+	next_sync_committee: Option<SyncCommittee>,
+) -> LightClientUpdate {
+	let execution = ExecutionPayloadHeader {
+		state_root: H256::repeat_byte(0xaa),
+		logs_bloom: vec![0; BYTES_PER_LOGS_BLOOM],
+		block_number: finalized_slot,
+		timestamp: 1_681_338_455 + finalized_slot * 12,
+		..Default::default()
+	};
+	let body = BTreeMap::from([(
+		EXECUTION_PAYLOAD_GINDEX,
+		execution_payload_header_root::<HostFunctionsManager>(&execution),
+	)]);
+	let finalized = BeaconBlockHeader {
+		slot: finalized_slot,
+		body_root: H256(sparse_node(1, 4, &body)),
+		..Default::default()
+	};
+
+	let mut state = BTreeMap::from([(
+		FINALIZED_ROOT_GINDEX,
+		beacon_block_header_root::<HostFunctionsManager>(&finalized),
+	)]);
+	if let Some(committee) = &next_sync_committee {
+		state.insert(
+			NEXT_SYNC_COMMITTEE_GINDEX,
+			sync_committee_root::<HostFunctionsManager>(committee),
+		);
+	}
+	let attested_header = BeaconBlockHeader {
+		slot: attested_slot,
+		state_root: H256(sparse_node(1, 6, &state)),
+		..Default::default()
+	};
+
+	let domain = compute_domain::<HostFunctionsManager>(
+		DOMAIN_SYNC_COMMITTEE,
+		client_state.fork_version,
+		client_state.genesis_validators_root,
+	);
+	let signing_root = compute_signing_root::<HostFunctionsManager>(
+		beacon_block_header_root::<HostFunctionsManager>(&attested_header),
+		domain,
+	);
+
+	LightClientUpdate {
+		attested_header,
+		next_sync_committee_branch: next_sync_committee
+			.as_ref()
+			.map(|_| sparse_branch(NEXT_SYNC_COMMITTEE_GINDEX, 6, &state))
+			.unwrap_or_default(),
+		next_sync_committee,
+		finalized_header: LightClientHeader {
+			beacon: finalized,
+			execution,
+			execution_branch: sparse_branch(EXECUTION_PAYLOAD_GINDEX, 4, &body),
+		},
+		finality_branch: sparse_branch(FINALIZED_ROOT_GINDEX, 6, &state),
+		sync_aggregate: signer.sign(&signing_root),
+		signature_slot: attested_slot + 1,
+	}
+}
+
+#[test]
+fn update_finalizes_header_and_learns_next_sync_committee() {
+	let current = Committee::new(1);
+	let next = Committee::new(2);
+	let client_state = client_state(&current, 64);
+
+	let update = signed_update(&client_state, &current, 128, 160, Some(next.committee.clone()));
+	verify_light_client_update(&client_state, &update).unwrap();
+
+	let client_state = apply_light_client_update(client_state, &update);
+	assert_eq!(client_state.finalized_header, update.finalized_header.beacon);
+	assert_eq!(client_state.latest_execution_height, 128);
+	assert_eq!(client_state.current_sync_committee, current.committee);
+	assert_eq!(client_state.next_sync_committee, Some(next.committee));
+}
+
+#[test]
+fn update_in_next_period_rotates_sync_committees() {
+	let current = Committee::new(1);
+	let next = Committee::new(2);
+	let following = Committee::new(3);
+	let mut client_state = client_state(&current, 64);
+	client_state.next_sync_committee = Some(next.committee.clone());
+
+	let finalized_slot = SLOTS_PER_PERIOD + 32;
+	let update = signed_update(
+		&client_state,
+		&next,
+		finalized_slot,
+		finalized_slot + 64,
+		Some(following.committee.clone()),
+	);
+	verify_light_client_update(&client_state, &update).unwrap();
+
+	let client_state = apply_light_client_update(client_state, &update);
+	assert_eq!(client_state.finalized_header.slot, finalized_slot);
+	assert_eq!(client_state.current_sync_committee, next.committee);
+	assert_eq!(client_state.next_sync_committee, Some(following.committee));
+}
+
+#[test]
+fn tampered_finality_branch_is_rejected() {
+	let current = Committee::new(1);
+	let client_state = client_state(&current, 64);
+
+	let mut update = signed_update(&client_state, &current, 128, 160, None);
+	update.finality_branch[0].0[0] ^= 1;
+	assert!(verify_light_client_update(&client_state, &update).is_err());
+}
+
+#[test]
+fn tampered_finalized_execution_header_is_rejected() {
+	let current = Committee::new(1);
+	let client_state = client_state(&current, 64);
+
+	let mut update = signed_update(&client_state, &current, 128, 160, None);
+	update.finalized_header.execution.state_root = H256::repeat_byte(0xbb);
+	assert!(verify_light_client_update(&client_state, &update).is_err());
+}
+
+#[test]
+fn update_signed_by_unknown_sync_committee_is_rejected() {
+	let current = Committee::new(1);
+	let other = Committee::new(2);
+	let client_state = client_state(&current, 64);
+
+	// signed in the current period, by a committee that isn't the current one
+	let update = signed_update(&client_state, &other, 128, 160, None);
+	assert!(verify_light_client_update(&client_state, &update).is_err());
+
+	// signed in the next period, whose committee the client doesn't know yet
+	let slot = SLOTS_PER_PERIOD + 32;
+	let update = signed_update(&client_state, &current, slot, slot + 32, None);
+	assert!(verify_light_client_update(&client_state, &update).is_err());
+}
+
+#[test]
+fn insufficient_participation_is_rejected() {
+	let current = Committee::new(1);
+	let client_state = client_state(&current, 64);
+
+	let mut update = signed_update(&client_state, &current, 128, 160, None);
+	update.sync_aggregate.sync_committee_bits[..SYNC_COMMITTEE_SIZE / 16].fill(0);
+	assert!(verify_light_client_update(&client_state, &update).is_err());
+}
+
+fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+	let flag = if is_leaf { 2 } else { 0 };
+	let mut encoded = if nibbles.len() % 2 == 1 {
+		vec![(flag + 1) << 4 | nibbles[0]]
+	} else {
+		vec![flag << 4]
+	};
+	let even = &nibbles[nibbles.len() % 2..];
+	encoded.extend(even.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+	encoded
+}
+
+fn nibbles(key: &[u8]) -> Vec<u8> {
+	key.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+fn leaf_node(nibbles: &[u8], value: &[u8]) -> Vec<u8> {
+	let mut stream = RlpStream::new_list(2);
+	stream.append(&hex_prefix(nibbles, true)).append(&value.to_vec());
+	stream.out().to_vec()
+}
+
+fn commitment_key(
+	client_state: &ClientState<HostFunctionsManager>,
+	prefix: &CommitmentPrefix,
+	path: &Path,
+) -> [u8; 32] {
+	let key = [prefix.as_bytes(), path.to_string().as_bytes()].concat();
+	HostFunctionsManager::keccak_256(&commitment_slot::<HostFunctionsManager>(
+		&key,
+		client_state.ibc_commitments_slot,
+	))
+}
+
+fn packet_path(sequence: u64) -> Path {
+	CommitmentsPath {
+		port_id: PortId::transfer(),
+		channel_id: ChannelId::new(0),
+		sequence: Sequence::from(sequence),
+	}
+	.into()
+}
+
+#[test]
+fn storage_proofs_of_commitments_contract() {
+	let committee = Committee::new(1);
+	let client_state = client_state(&committee, 64);
+	let prefix = CommitmentPrefix::try_from(b"ibc".to_vec()).unwrap();
+
+	// pick three packets whose storage keys start with different nibbles, so that a branch node
+	// holds the first two and has no child for the third
+	let mut paths = BTreeMap::new();
+	for sequence in 1.. {
+		let path = packet_path(sequence);
+		let key = commitment_key(&client_state, &prefix, &path);
+		paths.entry(key[0] >> 4).or_insert((path, key));
+		if paths.len() == 3 {
+			break
+		}
+	}
+	let mut paths = paths.into_values();
+	let (proven, proven_key) = paths.next().unwrap();
+	let (sibling, sibling_key) = paths.next().unwrap();
+	let (absent, _) = paths.next().unwrap();
+
+	let value = b"packet commitment".to_vec();
+	let stored =
+		|value: &[u8]| rlp::encode(&HostFunctionsManager::keccak_256(value).to_vec()).to_vec();
+	let proven_leaf = leaf_node(&nibbles(&proven_key)[1..], &stored(&value));
+	let sibling_leaf = leaf_node(&nibbles(&sibling_key)[1..], &stored(b"other commitment"));
+	let mut branch = RlpStream::new_list(17);
+	for nibble in 0..16 {
+		if nibble == proven_key[0] >> 4 {
+			branch.append(&HostFunctionsManager::keccak_256(&proven_leaf).to_vec());
+		} else if nibble == sibling_key[0] >> 4 {
+			branch.append(&HostFunctionsManager::keccak_256(&sibling_leaf).to_vec());
+		} else {
+			branch.append_empty_data();
+		}
+	}
+	branch.append_empty_data();
+	let branch = branch.out().to_vec();
+	let storage_root = HostFunctionsManager::keccak_256(&branch);
+
+	let mut account = RlpStream::new_list(4);
+	account
+		.append(&0u64)
+		.append(&0u64)
+		.append(&storage_root.to_vec())
+		.append(&HostFunctionsManager::keccak_256(&[]).to_vec());
+	let account_key =
+		HostFunctionsManager::keccak_256(client_state.ibc_contract_address.as_bytes());
+	let account_leaf = leaf_node(&nibbles(&account_key), &account.out());
+	let state_root: CommitmentRoot =
+		HostFunctionsManager::keccak_256(&account_leaf).to_vec().into();
+
+	let proof = |leaf: Option<&Vec<u8>>| {
+		let storage_proof = core::iter::once(branch.clone()).chain(leaf.cloned()).collect();
+		let proof = StorageProof { account_proof: vec![account_leaf.clone()], storage_proof };
+		CommitmentProofBytes::try_from(proof.encode_to_vec()).unwrap()
+	};
+
+	let proven_proof = proof(Some(&proven_leaf));
+	verify_membership(&client_state, &prefix, &proven_proof, &state_root, proven.clone(), value)
+		.unwrap();
+	assert!(verify_membership(
+		&client_state,
+		&prefix,
+		&proven_proof,
+		&state_root,
+		proven.clone(),
+		b"forged commitment".to_vec()
+	)
+	.is_err());
+	assert!(
+		verify_non_membership(&client_state, &prefix, &proven_proof, &state_root, proven).is_err()
+	);
+	// the sibling packet is proven through the same branch node
+	assert!(verify_membership(
+		&client_state,
+		&prefix,
+		&proof(Some(&sibling_leaf)),
+		&state_root,
+		sibling,
+		b"other commitment".to_vec()
+	)
+	.is_ok());
+
+	verify_non_membership(&client_state, &prefix, &proof(None), &state_root, absent.clone())
+		.unwrap();
+	assert!(verify_membership(
+		&client_state,
+		&prefix,
+		&proof(None),
+		&state_root,
+		absent,
+		b"packet commitment".to_vec()
+	)
+	.is_err());
+}
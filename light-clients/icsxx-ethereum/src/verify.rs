@@ -0,0 +1,186 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of sync committee signed updates, following the altair light client sync
+//! protocol: <https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md>
+
+use crate::{
+	client_message::{LightClientHeader, LightClientUpdate},
+	client_state::ClientState,
+	error::Error,
+	ssz::{
+		beacon_block_header_root, compute_domain, compute_signing_root,
+		execution_payload_header_root, is_valid_merkle_branch, sync_committee_root,
+	},
+	HostFunctions,
+};
+use alloc::{format, vec::Vec};
+use ibc::prelude::*;
+
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+pub const SLOTS_PER_EPOCH: u64 = 32;
+pub const EPOCHS_PER_SYNC_COMMITTEE_PERIOD: u64 = 256;
+pub const DOMAIN_SYNC_COMMITTEE: [u8; 4] = [7, 0, 0, 0];
+
+/// Generalized index of the finalized checkpoint root in the beacon state.
+pub const FINALIZED_ROOT_GINDEX: u64 = 105;
+/// Generalized index of the next sync committee in the beacon state.
+pub const NEXT_SYNC_COMMITTEE_GINDEX: u64 = 55;
+/// Generalized index of the execution payload header in the beacon block body.
+pub const EXECUTION_PAYLOAD_GINDEX: u64 = 25;
+
+pub fn sync_committee_period(slot: u64) -> u64 {
+	slot / SLOTS_PER_EPOCH / EPOCHS_PER_SYNC_COMMITTEE_PERIOD
+}
+
+/// Verify that `header`'s execution payload header is committed to by its beacon block body.
+pub fn verify_execution_branch<H: HostFunctions>(header: &LightClientHeader) -> Result<(), Error> {
+	if !is_valid_merkle_branch::<H>(
+		execution_payload_header_root::<H>(&header.execution),
+		&header.execution_branch,
+		EXECUTION_PAYLOAD_GINDEX,
+		header.beacon.body_root,
+	) {
+		return Err(Error::Custom(format!("Invalid execution payload branch")))
+	}
+	Ok(())
+}
+
+/// Verify that `update` is signed by the sync committee the client trusts for its signature
+/// slot, and that it proves a finalized header, and optionally the next sync committee, in the
+/// attested header's state.
+pub fn verify_light_client_update<H: HostFunctions>(
+	client_state: &ClientState<H>,
+	update: &LightClientUpdate,
+) -> Result<(), Error> {
+	let participants = update.sync_aggregate.participants();
+	if participants * 3 < SYNC_COMMITTEE_SIZE * 2 {
+		return Err(Error::Custom(format!(
+			"Insufficient sync committee participation: {participants}/{SYNC_COMMITTEE_SIZE}"
+		)))
+	}
+
+	let finalized = &update.finalized_header.beacon;
+	if !(update.signature_slot > update.attested_header.slot &&
+		update.attested_header.slot >= finalized.slot)
+	{
+		return Err(Error::Custom(format!(
+			"Invalid update slots, signature: {}, attested: {}, finalized: {}",
+			update.signature_slot, update.attested_header.slot, finalized.slot
+		)))
+	}
+
+	let store_period = sync_committee_period(client_state.finalized_header.slot);
+	let signature_period = sync_committee_period(update.signature_slot);
+	let known_committee = match client_state.next_sync_committee {
+		Some(_) => signature_period == store_period || signature_period == store_period + 1,
+		None => signature_period == store_period,
+	};
+	if !known_committee {
+		return Err(Error::Custom(format!(
+			"Unknown sync committee for period {signature_period}, client period: {store_period}"
+		)))
+	}
+
+	let attested_period = sync_committee_period(update.attested_header.slot);
+	let learns_next_committee = client_state.next_sync_committee.is_none() &&
+		update.next_sync_committee.is_some() &&
+		attested_period == store_period;
+	if !(finalized.slot > client_state.finalized_header.slot || learns_next_committee) {
+		return Err(Error::Custom(format!(
+			"Update for slot {} doesn't advance the client at slot {}",
+			finalized.slot, client_state.finalized_header.slot
+		)))
+	}
+
+	verify_execution_branch::<H>(&update.finalized_header)?;
+	if !is_valid_merkle_branch::<H>(
+		beacon_block_header_root::<H>(finalized),
+		&update.finality_branch,
+		FINALIZED_ROOT_GINDEX,
+		update.attested_header.state_root,
+	) {
+		return Err(Error::Custom(format!("Invalid finality branch")))
+	}
+
+	if let Some(next_sync_committee) = &update.next_sync_committee {
+		match &client_state.next_sync_committee {
+			Some(known) if attested_period == store_period && known != next_sync_committee =>
+				return Err(Error::Custom(format!(
+					"Next sync committee doesn't match the one the client already knows"
+				))),
+			_ => {},
+		}
+		if !is_valid_merkle_branch::<H>(
+			sync_committee_root::<H>(next_sync_committee),
+			&update.next_sync_committee_branch,
+			NEXT_SYNC_COMMITTEE_GINDEX,
+			update.attested_header.state_root,
+		) {
+			return Err(Error::Custom(format!("Invalid next sync committee branch")))
+		}
+	}
+
+	let sync_committee = match &client_state.next_sync_committee {
+		Some(next_sync_committee) if signature_period == store_period + 1 => next_sync_committee,
+		_ => &client_state.current_sync_committee,
+	};
+	let public_keys = sync_committee
+		.pubkeys
+		.iter()
+		.enumerate()
+		.filter(|(index, _)| update.sync_aggregate.participated(*index))
+		.map(|(_, key)| *key)
+		.collect::<Vec<_>>();
+	let domain = compute_domain::<H>(
+		DOMAIN_SYNC_COMMITTEE,
+		client_state.fork_version,
+		client_state.genesis_validators_root,
+	);
+	let signing_root =
+		compute_signing_root::<H>(beacon_block_header_root::<H>(&update.attested_header), domain);
+	if !H::bls_fast_aggregate_verify(
+		&public_keys,
+		&signing_root,
+		&update.sync_aggregate.sync_committee_signature,
+	) {
+		return Err(Error::Custom(format!("Invalid sync committee signature")))
+	}
+
+	Ok(())
+}
+
+/// Advance the client to a verified `update`, moving to the next sync committee period once the
+/// update finalizes a header in it.
+pub fn apply_light_client_update<H>(
+	mut client_state: ClientState<H>,
+	update: &LightClientUpdate,
+) -> ClientState<H> {
+	let store_period = sync_committee_period(client_state.finalized_header.slot);
+	let finalized_period = sync_committee_period(update.finalized_header.beacon.slot);
+	match client_state.next_sync_committee.take() {
+		None => client_state.next_sync_committee = update.next_sync_committee.clone(),
+		Some(next_sync_committee) if finalized_period == store_period + 1 => {
+			client_state.current_sync_committee = next_sync_committee;
+			client_state.next_sync_committee = update.next_sync_committee.clone();
+		},
+		next_sync_committee => client_state.next_sync_committee = next_sync_committee,
+	}
+	if update.finalized_header.beacon.slot > client_state.finalized_header.slot {
+		client_state.finalized_header = update.finalized_header.beacon.clone();
+		client_state.latest_execution_height = update.finalized_header.execution.block_number;
+	}
+	client_state
+}
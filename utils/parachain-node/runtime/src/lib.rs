@@ -56,7 +56,7 @@ use sp_version::RuntimeVersion;
 
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{fungibles::InspectMetadata, AsEnsureOriginWithArg, Everything},
+	traits::{AsEnsureOriginWithArg, Everything},
 	weights::{
 		constants::WEIGHT_PER_SECOND, ConstantMultiplier, DispatchClass, Weight,
 		WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
@@ -488,6 +488,7 @@ impl pallet_collator_selection::Config for Runtime {
 impl pallet_ibc_ping::Config for Runtime {
 	type Event = Event;
 	type IbcHandler = Ibc;
+	type MaxPayloadSize = frame_support::traits::ConstU32<1000>;
 }
 
 impl asset_registry::Config for Runtime {
@@ -532,6 +533,11 @@ parameter_types! {
 	pub const SpamProtectionDeposit: Balance = 1_000_000_000_000;
 	pub const NativeAssetId: AssetId = 1;
 	pub const MinimumConnectionDelay: u64 = 0; // well we don't need the security tbh.
+	pub const ConnectionHandshakeTimeout: u64 = 14400;
+	pub const ConsensusStateRetention: u32 = 64;
+	pub const PersistTimeouts: bool = true;
+	pub const MaxTimedOutPackets: u32 = 1000;
+	pub const TransferRateLimitPeriod: u32 = 7200;
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -647,8 +653,22 @@ impl DenomToAssetId<Runtime> for IbcDenomToAssetIdConversion {
 	}
 
 	fn from_asset_id_to_denom(id: AssetId) -> Option<String> {
-		let name = <pallet_assets::Pallet<Runtime> as InspectMetadata<AccountId>>::name(&id);
-		String::from_utf8(name).ok()
+		IbcAssetIds::<Runtime>::get(id).and_then(|denom| String::from_utf8(denom).ok())
+	}
+
+	fn set_asset_metadata(id: AssetId, symbol: Vec<u8>, decimals: u8) -> Result<(), Self::Error> {
+		use frame_support::traits::fungibles::metadata::Mutate;
+
+		let pallet_id: AccountId = PalletId(*b"pall-ibc").into_account_truncating();
+		let name = IbcAssetIds::<Runtime>::get(id)
+			.ok_or_else(|| DispatchError::Other("asset is not an ibc asset"))?;
+		<pallet_assets::Pallet<Runtime> as Mutate<AccountId>>::set(
+			id,
+			&pallet_id,
+			name,
+			symbol,
+			decimals,
+		)
 	}
 
 	fn ibc_assets(start_key: Option<Either<AssetId, u32>>, limit: u64) -> IbcAssets<AssetId> {
@@ -687,6 +707,11 @@ impl pallet_ibc::Config for Runtime {
 	type ExpectedBlockTime = ExpectedBlockTime;
 	type Router = Router;
 	type MinimumConnectionDelay = MinimumConnectionDelay;
+	type ConnectionHandshakeTimeout = ConnectionHandshakeTimeout;
+	type ConsensusStateRetention = ConsensusStateRetention;
+	type PersistTimeouts = PersistTimeouts;
+	type MaxTimedOutPackets = MaxTimedOutPackets;
+	type TransferRateLimitPeriod = TransferRateLimitPeriod;
 	type ParaId = parachain_info::Pallet<Runtime>;
 	type RelayChain = RelayChainId;
 	type WeightInfo = ();
@@ -858,6 +883,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_ibc_ping_runtime_api::PingRuntimeApi<Block> for Runtime {
+		fn ping_stats(channel_id: u64) -> Option<pallet_ibc_ping::PingStats> {
+			IbcPing::ping_stats(channel_id)
+		}
+	}
+
 	impl ibc_runtime_api::IbcRuntimeApi<Block, AssetId> for Runtime {
 		fn para_id() -> u32 {
 			<Runtime as cumulus_pallet_parachain_system::Config>::SelfParaId::get().into()
@@ -887,6 +918,10 @@ impl_runtime_apis! {
 			Ibc::client(client_id).ok()
 		}
 
+		fn client_status(client_id: Vec<u8>) -> Option<pallet_ibc::ClientStatus> {
+			Ibc::client_status(client_id).ok()
+		}
+
 		fn client_consensus_state(client_id: Vec<u8>, revision_number: u64, revision_height: u64, latest_cs: bool) -> Option<ibc_primitives::QueryConsensusStateResponse> {
 			Ibc::consensus_state(client_id, revision_number, revision_height, latest_cs).ok()
 		}
@@ -931,8 +966,16 @@ impl_runtime_apis! {
 			Ibc::packet_commitments(channel_id, port_id).ok()
 		}
 
-		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::QueryPacketAcknowledgementsResponse>{
-			Ibc::packet_acknowledgements(channel_id, port_id).ok()
+		fn packet_commitment_count(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<u64> {
+			Ibc::packet_commitment_count(channel_id, port_id).ok()
+		}
+
+		fn timed_out_packets(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<Vec<(u64, u64)>> {
+			Ibc::timed_out_packets(channel_id, port_id).ok()
+		}
+
+		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>, seqs: Option<Vec<u64>>) -> Option<ibc_primitives::QueryPacketAcknowledgementsResponse>{
+			Ibc::packet_acknowledgements(channel_id, port_id, seqs).ok()
 		}
 
 		fn unreceived_packets(channel_id: Vec<u8>, port_id: Vec<u8>, seqs: Vec<u64>) -> Option<Vec<u64>> {
@@ -947,6 +990,18 @@ impl_runtime_apis! {
 			Ibc::next_seq_recv(channel_id, port_id).ok()
 		}
 
+		fn next_seq_ack(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::QueryNextSequenceAckResponse> {
+			Ibc::next_seq_ack(channel_id, port_id).ok()
+		}
+
+		fn next_seq_send(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::QueryNextSequenceSendResponse> {
+			Ibc::next_seq_send(channel_id, port_id).ok()
+		}
+
+		fn commitment_keys(paths: Vec<Vec<u8>>) -> Option<Vec<Vec<u8>>> {
+			Ibc::commitment_keys(paths).ok()
+		}
+
 		fn packet_commitment(channel_id: Vec<u8>, port_id: Vec<u8>, seq: u64) -> Option<ibc_primitives::QueryPacketCommitmentResponse> {
 			Ibc::packet_commitment(channel_id, port_id, seq).ok()
 		}
@@ -963,11 +1018,19 @@ impl_runtime_apis! {
 			Ibc::get_denom_trace(asset_id)
 		}
 
+		fn denom_trace_by_hash(hash: Vec<u8>) -> Option<ibc_primitives::QueryDenomTraceResponse> {
+			Ibc::get_denom_trace_by_hash(hash)
+		}
+
 		fn denom_traces(key: Option<AssetId>, offset: Option<u32>, limit: u64, count_total: bool) -> ibc_primitives::QueryDenomTracesResponse {
 			let key = key.map(|k| Either::Left(k)).or_else(|| offset.map(|o| Either::Right(o)));
 			Ibc::get_denom_traces(key, limit, count_total)
 		}
 
+		fn escrow_balances(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<Vec<(Vec<u8>, u128)>> {
+			Ibc::escrow_balances(channel_id, port_id).ok()
+		}
+
 		fn block_events(extrinsic_index: Option<u32>) -> Vec<Result<pallet_ibc::events::IbcEvent, pallet_ibc::errors::IbcError>> {
 			let mut raw_events = frame_system::Pallet::<Self>::read_events_no_consensus().into_iter();
 			if let Some(idx) = extrinsic_index {
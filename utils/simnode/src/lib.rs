@@ -141,6 +141,7 @@ mod tests {
 				para_id: 100,
 				current_set_id: 1,
 				current_authorities: Default::default(),
+				pending_authority_change: None,
 				_phantom: Default::default(),
 			};
 
@@ -151,6 +152,7 @@ mod tests {
 					.into_tm_time()
 					.unwrap(),
 				root: H256::zero().as_bytes().to_vec().into(),
+				block_hash: Default::default(),
 			};
 
 			let justification = GrandpaJustification::<RelayChainHeader> {
@@ -11137,6 +11137,9 @@ pub mod api {
 						revision_number: ::core::primitive::u64,
 						consensus_height: ::core::primitive::u64,
 						consensus_revision_number: ::core::primitive::u64,
+						header_heights:
+							::std::vec::Vec<(::core::primitive::u64, ::core::primitive::u64)>,
+						consensus_root: ::std::vec::Vec<::core::primitive::u8>,
 					},
 					#[codec(index = 3)]
 					UpgradeClient {
@@ -11265,6 +11268,7 @@ pub mod api {
 						counterparty_port_id: ::std::vec::Vec<::core::primitive::u8>,
 						counterparty_channel_id:
 							::core::option::Option<::std::vec::Vec<::core::primitive::u8>>,
+						reason: ::core::option::Option<::std::vec::Vec<::core::primitive::u8>>,
 					},
 					#[codec(index = 15)]
 					ReceivePacket {
@@ -11285,6 +11289,10 @@ pub mod api {
 						dest_port: ::std::vec::Vec<::core::primitive::u8>,
 						dest_channel: ::std::vec::Vec<::core::primitive::u8>,
 						sequence: ::core::primitive::u64,
+						timeout_revision_height: ::core::primitive::u64,
+						timeout_revision_number: ::core::primitive::u64,
+						timeout_timestamp: ::core::primitive::u64,
+						channel_ordering: ::std::vec::Vec<::core::primitive::u8>,
 					},
 					#[codec(index = 17)]
 					AcknowledgePacket {
@@ -11619,6 +11627,9 @@ pub mod api {
 					#[codec(index = 2)]
 					#[doc = "Error registering packet"]
 					PacketSendError,
+					#[codec(index = 3)]
+					#[doc = "Ping payload is larger than `MaxPayloadSize`"]
+					PayloadTooLarge,
 				}
 				#[derive(
 					:: subxt :: ext :: codec :: Decode, :: subxt :: ext :: codec :: Encode, Debug,
@@ -11640,7 +11651,8 @@ pub mod api {
 				:: subxt :: ext :: codec :: Decode, :: subxt :: ext :: codec :: Encode, Debug,
 			)]
 			pub struct SendPingParams {
-				pub data: ::std::vec::Vec<::core::primitive::u8>,
+				pub count: ::core::primitive::u32,
+				pub payload_size: ::core::primitive::u32,
 				pub timeout_height_offset: ::core::primitive::u64,
 				pub timeout_timestamp_offset: ::core::primitive::u64,
 				pub channel_id: ::core::primitive::u64,